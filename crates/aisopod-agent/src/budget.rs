@@ -0,0 +1,198 @@
+//! Token and cost budgets for sessions and peers.
+//!
+//! Budgets are configured in `session.budget` and checked against the
+//! usage recorded by the [`UsageTracker`] before every model call. Once a
+//! session or a peer's daily usage reaches a limit, the pipeline stops
+//! calling the model, replies with the configured `exceeded_message`, and
+//! reports the [`BudgetExceeded`] to the [`BudgetNotifier`] if one is set.
+
+use std::fmt;
+
+use aisopod_config::types::BudgetConfig;
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::types::UsageReport;
+use crate::usage::{session_peer, UsageTracker};
+
+/// The usage a budget limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetScope {
+    /// The usage of a single session.
+    Session,
+    /// The usage of a peer on the current UTC day.
+    DailyPeer,
+}
+
+/// The limit of a budget.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetLimit {
+    /// Maximum number of input and output tokens.
+    Tokens(u64),
+    /// Maximum cost in USD.
+    Cost(f64),
+}
+
+impl fmt::Display for BudgetLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetLimit::Tokens(tokens) => write!(f, "{} tokens", tokens),
+            BudgetLimit::Cost(cost) => write!(f, "${:.2}", cost),
+        }
+    }
+}
+
+/// A budget reached by a session or peer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetExceeded {
+    /// The usage the exceeded budget limits.
+    pub scope: BudgetScope,
+    /// The limit that was reached.
+    pub limit: BudgetLimit,
+    /// The session whose run was refused.
+    pub session_key: String,
+    /// The peer the session belongs to.
+    pub peer: String,
+    /// The agent that would have handled the run.
+    pub agent_id: String,
+    /// The usage counted against the budget.
+    pub usage: UsageReport,
+}
+
+impl BudgetExceeded {
+    /// Returns the notification text sent to operators.
+    pub fn describe(&self) -> String {
+        let (scope, subject) = match self.scope {
+            BudgetScope::Session => ("session", &self.session_key),
+            BudgetScope::DailyPeer => ("daily peer", &self.peer),
+        };
+        format!(
+            "Budget exceeded: {} budget of {} reached by {} (agent '{}', {} tokens, ${:.2} used)",
+            scope, self.limit, subject, self.agent_id, self.usage.total_tokens, self.usage.cost_usd
+        )
+    }
+}
+
+/// Trait for reporting exceeded budgets to operators.
+#[async_trait]
+pub trait BudgetNotifier: Send + Sync {
+    /// Reports a run refused because `exceeded` was reached.
+    ///
+    /// Called for every refused run; implementations decide how often
+    /// operators are actually notified.
+    async fn budget_exceeded(&self, exceeded: &BudgetExceeded) -> Result<()>;
+}
+
+/// Checks the configured budgets of a session against recorded usage.
+///
+/// The session budget is checked before the daily peer budget. Returns the
+/// first budget reached, or `None` if the session may keep running.
+pub fn check_budget(
+    config: &BudgetConfig,
+    tracker: &UsageTracker,
+    session_key: &str,
+    agent_id: &str,
+) -> Option<BudgetExceeded> {
+    let peer = session_peer(session_key);
+    let checks = [
+        (
+            BudgetScope::Session,
+            config.max_session_tokens,
+            config.max_session_cost,
+            tracker.get_session_usage(session_key),
+        ),
+        (
+            BudgetScope::DailyPeer,
+            config.max_daily_peer_tokens,
+            config.max_daily_peer_cost,
+            tracker.get_peer_usage_today(peer),
+        ),
+    ];
+
+    checks
+        .into_iter()
+        .find_map(|(scope, max_tokens, max_cost, usage)| {
+            let usage = usage?;
+            let limit = match (max_tokens, max_cost) {
+                (Some(max), _) if usage.total_tokens >= max => BudgetLimit::Tokens(max),
+                (_, Some(max)) if usage.cost_usd >= max => BudgetLimit::Cost(max),
+                _ => return None,
+            };
+            Some(BudgetExceeded {
+                scope,
+                limit,
+                session_key: session_key.to_string(),
+                peer: peer.to_string(),
+                agent_id: agent_id.to_string(),
+                usage,
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SESSION: &str = "support:telegram:bot:dm:alice";
+
+    #[test]
+    fn test_no_limits_never_exceeded() {
+        let tracker = UsageTracker::new();
+        tracker.record_request(SESSION, "support", 1_000_000, 1_000_000);
+
+        assert!(check_budget(&BudgetConfig::default(), &tracker, SESSION, "support").is_none());
+    }
+
+    #[test]
+    fn test_session_token_budget() {
+        let config = BudgetConfig {
+            max_session_tokens: Some(1000),
+            ..Default::default()
+        };
+        let tracker = UsageTracker::new();
+        tracker.record_request(SESSION, "support", 600, 300);
+        assert!(check_budget(&config, &tracker, SESSION, "support").is_none());
+
+        tracker.record_request(SESSION, "support", 60, 40);
+        let exceeded = check_budget(&config, &tracker, SESSION, "support").unwrap();
+        assert_eq!(exceeded.scope, BudgetScope::Session);
+        assert_eq!(exceeded.limit, BudgetLimit::Tokens(1000));
+        assert_eq!(exceeded.usage.total_tokens, 1000);
+    }
+
+    #[test]
+    fn test_daily_peer_budget_spans_sessions() {
+        let config = BudgetConfig {
+            max_daily_peer_tokens: Some(1000),
+            ..Default::default()
+        };
+        let tracker = UsageTracker::new();
+        tracker.record_request("billing:telegram:bot:dm:alice", "billing", 900, 100);
+
+        let exceeded = check_budget(&config, &tracker, SESSION, "support").unwrap();
+        assert_eq!(exceeded.scope, BudgetScope::DailyPeer);
+        assert_eq!(exceeded.peer, "telegram:bot:dm:alice");
+
+        let other = "support:telegram:bot:dm:bob";
+        assert!(check_budget(&config, &tracker, other, "support").is_none());
+    }
+
+    #[test]
+    fn test_describe_names_limit_and_subject() {
+        let exceeded = BudgetExceeded {
+            scope: BudgetScope::DailyPeer,
+            limit: BudgetLimit::Cost(5.0),
+            session_key: SESSION.to_string(),
+            peer: "telegram:bot:dm:alice".to_string(),
+            agent_id: "support".to_string(),
+            usage: UsageReport::default(),
+        };
+
+        let text = exceeded.describe();
+        assert!(text.contains("daily peer budget of $5.00"));
+        assert!(text.contains("telegram:bot:dm:alice"));
+    }
+}
@@ -3,10 +3,15 @@
 //! This module provides adaptive history compaction strategies to manage
 //! context window size when conversations grow too long. It includes
 //! strategies for adaptive chunking, summary-based compaction, hard clearing,
-//! and oversized tool result truncation.
+//! and oversized tool result truncation, as well as [`LlmSummaryCompactor`],
+//! which has a model summarize the oldest messages instead of dropping them.
+
+use std::sync::Arc;
 
 use crate::context_guard::ContextWindowGuard;
-use aisopod_provider::{Message, MessageContent};
+use aisopod_provider::{ChatCompletionRequest, Message, MessageContent, ModelProvider, Role};
+use anyhow::Result;
+use futures_util::StreamExt;
 
 /// Severity level for compaction decisions.
 ///
@@ -132,7 +137,7 @@ fn chunk_first_content(chunk: &[&Message]) -> String {
     String::new()
 }
 
-/// Extract text content from a message (helper for tests)
+/// Extract text content from a message
 fn message_to_text(msg: &Message) -> String {
     match &msg.content {
         MessageContent::Text(text) => text.clone(),
@@ -290,6 +295,229 @@ pub fn estimate_token_count(messages: &[Message]) -> usize {
         / 4
 }
 
+/// Maximum number of characters of a single tool result shown to the
+/// summarizing model.
+const MAX_TOOL_RESULT_CHARS: usize = 2000;
+
+/// Compacts a transcript by having a model summarize its oldest messages.
+///
+/// Unlike [`CompactionStrategy::Summary`], which replaces old messages with
+/// a placeholder, this compactor sends the oldest messages to a (typically
+/// cheap) model in segments of at most `max_segment_tokens`, folding each
+/// segment into a running summary. The summarized messages are replaced by
+/// a single summary message that also lists the tool results they
+/// contained, so the agent still knows which tools ran and with what
+/// outcome.
+///
+/// Leading system messages are never summarized, and the recent messages
+/// kept verbatim never start with a tool result separated from the
+/// assistant message that requested it.
+pub struct LlmSummaryCompactor {
+    provider: Arc<dyn ModelProvider>,
+    model: String,
+    keep_recent: usize,
+    max_segment_tokens: usize,
+}
+
+impl LlmSummaryCompactor {
+    /// Creates a compactor summarizing with `model` of `provider`, keeping
+    /// the 10 most recent messages and summarizing 4000 tokens at a time.
+    pub fn new(provider: Arc<dyn ModelProvider>, model: impl Into<String>) -> Self {
+        Self {
+            provider,
+            model: model.into(),
+            keep_recent: 10,
+            max_segment_tokens: 4000,
+        }
+    }
+
+    /// Sets the number of most recent messages kept verbatim.
+    pub fn with_keep_recent(mut self, keep_recent: usize) -> Self {
+        self.keep_recent = keep_recent;
+        self
+    }
+
+    /// Sets the approximate number of tokens sent to the model per request.
+    pub fn with_max_segment_tokens(mut self, max_segment_tokens: usize) -> Self {
+        self.max_segment_tokens = max_segment_tokens.max(1);
+        self
+    }
+
+    /// Compacts `messages`, returning them unchanged if there is nothing
+    /// older than the recent messages to summarize.
+    pub async fn compact(&self, messages: &[Message]) -> Result<Vec<Message>> {
+        let system_count = messages
+            .iter()
+            .take_while(|m| m.role == Role::System)
+            .count();
+        let (system, history) = messages.split_at(system_count);
+        if history.len() <= self.keep_recent {
+            return Ok(messages.to_vec());
+        }
+
+        let split = tool_safe_split(history, history.len() - self.keep_recent);
+        if split == 0 {
+            return Ok(messages.to_vec());
+        }
+        let (old_messages, recent_messages) = history.split_at(split);
+
+        let mut summary = String::new();
+        for segment in segments(old_messages, self.max_segment_tokens) {
+            summary = self.summarize(&summary, segment).await?;
+        }
+
+        let mut text = format!(
+            "[Summary of {} earlier messages]\n{}",
+            old_messages.len(),
+            summary
+        );
+        let tool_results = tool_result_metadata(old_messages);
+        if !tool_results.is_empty() {
+            text.push_str("\n\nTool results in the summarized messages:");
+            for line in tool_results {
+                text.push_str("\n- ");
+                text.push_str(&line);
+            }
+        }
+
+        let mut result = system.to_vec();
+        result.push(Message {
+            role: Role::Assistant,
+            content: MessageContent::Text(text),
+            tool_calls: None,
+            tool_call_id: None,
+        });
+        result.extend_from_slice(recent_messages);
+        Ok(result)
+    }
+
+    /// Folds `segment` into the summary written so far.
+    async fn summarize(&self, previous: &str, segment: &[Message]) -> Result<String> {
+        let mut prompt = String::from(
+            "Summarize the following conversation excerpt so it can replace the \
+             excerpt in the conversation history. Keep facts, decisions, open \
+             questions, names, and the outcome of every tool call. Reply with \
+             the summary only.\n\n",
+        );
+        if !previous.is_empty() {
+            prompt.push_str("Summary of the conversation before the excerpt:\n");
+            prompt.push_str(previous);
+            prompt.push_str("\n\n");
+        }
+        prompt.push_str("Excerpt:\n");
+        for msg in segment {
+            prompt.push_str(&transcript_line(msg));
+            prompt.push('\n');
+        }
+
+        let request = ChatCompletionRequest {
+            model: self.model.clone(),
+            messages: vec![Message {
+                role: Role::User,
+                content: MessageContent::Text(prompt),
+                tool_calls: None,
+                tool_call_id: None,
+            }],
+            tools: None,
+            temperature: Some(0.0),
+            max_tokens: None,
+            stop: None,
+            stream: false,
+        };
+
+        let mut stream = self.provider.chat_completion(request).await?;
+        let mut response = String::new();
+        while let Some(chunk) = stream.next().await {
+            if let Some(content) = chunk?.delta.content {
+                response.push_str(&content);
+            }
+        }
+        Ok(response.trim().to_string())
+    }
+}
+
+/// Moves a split point back so that tool results stay with the assistant
+/// message that requested them.
+fn tool_safe_split(messages: &[Message], mut split: usize) -> usize {
+    while split > 0 && messages[split].role == Role::Tool {
+        split -= 1;
+    }
+    split
+}
+
+/// Groups messages into consecutive segments of about `max_tokens` each.
+fn segments(messages: &[Message], max_tokens: usize) -> Vec<&[Message]> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut tokens = 0;
+    for (i, msg) in messages.iter().enumerate() {
+        let msg_tokens = estimate_token_count(std::slice::from_ref(msg));
+        if i > start && tokens + msg_tokens > max_tokens {
+            segments.push(&messages[start..i]);
+            start = i;
+            tokens = 0;
+        }
+        tokens += msg_tokens;
+    }
+    if start < messages.len() {
+        segments.push(&messages[start..]);
+    }
+    segments
+}
+
+/// Renders a message as a line of the transcript sent to the summarizer.
+fn transcript_line(msg: &Message) -> String {
+    let text = message_to_text(msg);
+    match msg.role {
+        Role::System => format!("system: {}", text),
+        Role::User => format!("user: {}", text),
+        Role::Assistant => {
+            let mut line = format!("assistant: {}", text);
+            for call in msg.tool_calls.iter().flatten() {
+                line.push_str(&format!("\n  [called {}({})]", call.name, call.arguments));
+            }
+            line
+        }
+        Role::Tool => {
+            let shown: String = text.chars().take(MAX_TOOL_RESULT_CHARS).collect();
+            let omitted = text.chars().count() - shown.chars().count();
+            let mut line = format!(
+                "tool result ({}): {}",
+                msg.tool_call_id.as_deref().unwrap_or("unknown"),
+                shown
+            );
+            if omitted > 0 {
+                line.push_str(&format!(" [{} more characters]", omitted));
+            }
+            line
+        }
+        _ => text,
+    }
+}
+
+/// Describes each tool result in `messages` by tool name, call ID, and size.
+fn tool_result_metadata(messages: &[Message]) -> Vec<String> {
+    messages
+        .iter()
+        .filter(|m| m.role == Role::Tool)
+        .map(|result| {
+            let call_id = result.tool_call_id.as_deref().unwrap_or("unknown");
+            let name = messages
+                .iter()
+                .flat_map(|m| m.tool_calls.iter().flatten())
+                .find(|call| call.id == call_id)
+                .map(|call| call.name.as_str())
+                .unwrap_or("unknown tool");
+            format!(
+                "{} (call {}): {} characters",
+                name,
+                call_id,
+                message_to_text(result).chars().count()
+            )
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -303,6 +531,180 @@ mod tests {
         }
     }
 
+    /// Provider answering every request with a numbered summary and
+    /// recording the prompts it received.
+    struct SummaryProvider {
+        prompts: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl SummaryProvider {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                prompts: std::sync::Mutex::new(Vec::new()),
+            })
+        }
+
+        fn prompts(&self) -> Vec<String> {
+            self.prompts.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ModelProvider for SummaryProvider {
+        fn id(&self) -> &str {
+            "summary"
+        }
+
+        async fn list_models(&self) -> Result<Vec<aisopod_provider::types::ModelInfo>> {
+            Ok(Vec::new())
+        }
+
+        async fn chat_completion(
+            &self,
+            request: ChatCompletionRequest,
+        ) -> Result<aisopod_provider::ChatCompletionStream> {
+            let mut prompts = self.prompts.lock().unwrap();
+            prompts.push(message_to_text(&request.messages[0]));
+            let chunk = aisopod_provider::types::ChatCompletionChunk {
+                id: "chunk".to_string(),
+                delta: aisopod_provider::types::MessageDelta {
+                    role: Some(Role::Assistant),
+                    content: Some(format!("Summary {}", prompts.len())),
+                    tool_calls: None,
+                    reasoning: None,
+                },
+                finish_reason: Some(aisopod_provider::types::FinishReason::Stop),
+                usage: None,
+            };
+            Ok(Box::pin(futures_util::stream::iter(vec![Ok(chunk)])))
+        }
+
+        async fn health_check(&self) -> Result<aisopod_provider::types::ProviderHealth> {
+            Ok(aisopod_provider::types::ProviderHealth {
+                available: true,
+                latency_ms: None,
+            })
+        }
+    }
+
+    fn tool_call_message(id: &str, name: &str) -> Message {
+        Message {
+            role: Role::Assistant,
+            content: MessageContent::Text(String::new()),
+            tool_calls: Some(vec![aisopod_provider::ToolCall {
+                id: id.to_string(),
+                name: name.to_string(),
+                arguments: "{}".to_string(),
+            }]),
+            tool_call_id: None,
+        }
+    }
+
+    fn tool_result_message(id: &str, content: &str) -> Message {
+        Message {
+            role: Role::Tool,
+            content: MessageContent::Text(content.to_string()),
+            tool_calls: None,
+            tool_call_id: Some(id.to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_llm_summary_replaces_old_messages() {
+        let provider = SummaryProvider::new();
+        let compactor = LlmSummaryCompactor::new(provider.clone(), "cheap").with_keep_recent(2);
+        let mut messages = vec![text_message(Role::System, "You are helpful.")];
+        messages.extend((0..6).map(|i| text_message(Role::User, &format!("Message {}", i))));
+
+        let result = compactor.compact(&messages).await.unwrap();
+
+        assert_eq!(result.len(), 4); // system + summary + 2 recent
+        assert_eq!(message_to_text(&result[0]), "You are helpful.");
+        assert_eq!(
+            message_to_text(&result[1]),
+            "[Summary of 4 earlier messages]\nSummary 1"
+        );
+        assert_eq!(message_to_text(&result[3]), "Message 5");
+
+        let prompts = provider.prompts();
+        assert_eq!(prompts.len(), 1);
+        assert!(prompts[0].contains("user: Message 3"));
+        assert!(!prompts[0].contains("You are helpful."));
+        assert!(!prompts[0].contains("Message 4"));
+    }
+
+    #[tokio::test]
+    async fn test_llm_summary_preserves_tool_result_metadata() {
+        let provider = SummaryProvider::new();
+        let compactor = LlmSummaryCompactor::new(provider, "cheap").with_keep_recent(1);
+        let messages = vec![
+            text_message(Role::User, "What is the weather?"),
+            tool_call_message("call_1", "weather"),
+            tool_result_message("call_1", "Sunny, 21C"),
+            text_message(Role::Assistant, "It is sunny."),
+        ];
+
+        let result = compactor.compact(&messages).await.unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert!(message_to_text(&result[0]).ends_with(
+            "Tool results in the summarized messages:\n- weather (call call_1): 10 characters"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_llm_summary_keeps_tool_results_with_their_call() {
+        let provider = SummaryProvider::new();
+        let compactor = LlmSummaryCompactor::new(provider, "cheap").with_keep_recent(2);
+        let messages = vec![
+            text_message(Role::User, "Look up both."),
+            text_message(Role::Assistant, "Looking."),
+            tool_call_message("call_1", "search"),
+            tool_result_message("call_1", "first"),
+            tool_result_message("call_1", "second"),
+        ];
+
+        let result = compactor.compact(&messages).await.unwrap();
+
+        // The tool call moves into the recent messages along with its results
+        assert_eq!(result.len(), 4);
+        assert!(result[1].tool_calls.is_some());
+        assert_eq!(result[2].role, Role::Tool);
+    }
+
+    #[tokio::test]
+    async fn test_llm_summary_folds_segments() {
+        let provider = SummaryProvider::new();
+        let compactor = LlmSummaryCompactor::new(provider.clone(), "cheap")
+            .with_keep_recent(1)
+            .with_max_segment_tokens(10);
+        let messages = (0..4)
+            .map(|i| text_message(Role::User, &format!("{} {}", "word ".repeat(6), i)))
+            .collect::<Vec<_>>();
+
+        let result = compactor.compact(&messages).await.unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert!(message_to_text(&result[0]).ends_with("Summary 3"));
+        let prompts = provider.prompts();
+        assert_eq!(prompts.len(), 3);
+        assert!(prompts[2].contains("Summary 2"));
+    }
+
+    #[tokio::test]
+    async fn test_llm_summary_no_op_when_under_limit() {
+        let provider = SummaryProvider::new();
+        let compactor = LlmSummaryCompactor::new(provider.clone(), "cheap");
+        let messages = (0..5)
+            .map(|i| text_message(Role::User, &format!("Message {}", i)))
+            .collect::<Vec<_>>();
+
+        let result = compactor.compact(&messages).await.unwrap();
+
+        assert_eq!(result, messages);
+        assert!(provider.prompts().is_empty());
+    }
+
     #[test]
     fn test_compaction_strategy_default() {
         let strategy = CompactionStrategy::default();
@@ -0,0 +1,92 @@
+//! Draft-and-verify mode for agents with two models.
+//!
+//! Agents whose configuration sets `draft_verify` run their loop on the
+//! draft model. Its final response is not streamed; the pipeline first
+//! asks the verify model to review it along with the conversation. In
+//! [`VerifyMode::Edit`] the verify model approves the draft or replies with
+//! a corrected response, which is sent instead. In [`VerifyMode::Critique`]
+//! it approves the draft or critiques it, and the draft model revises its
+//! response, up to `max_revisions` times.
+
+use aisopod_config::types::VerifyMode;
+
+/// The reply of a verify model approving a draft.
+pub const APPROVED: &str = "APPROVED";
+
+/// The review of a draft by the verify model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Review {
+    /// The draft can be sent as is.
+    Approved,
+    /// The corrected response in edit mode, or the critique in critique
+    /// mode.
+    Revised(String),
+}
+
+/// Parses the reply of the verify model.
+///
+/// A reply of [`APPROVED`] alone approves the draft, ignoring case,
+/// surrounding whitespace and trailing punctuation.
+pub fn parse_review(reply: &str) -> Review {
+    let verdict = reply.trim().trim_end_matches(['.', '!']);
+    if verdict.eq_ignore_ascii_case(APPROVED) {
+        Review::Approved
+    } else {
+        Review::Revised(reply.trim().to_string())
+    }
+}
+
+/// Returns the message asking the verify model to review the draft that
+/// precedes it.
+pub(crate) fn review_prompt(mode: VerifyMode) -> String {
+    let otherwise = match mode {
+        VerifyMode::Edit => {
+            "Otherwise reply with only the corrected response, exactly as it should be sent \
+             to the user, without commentary."
+        }
+        VerifyMode::Critique => "Otherwise reply with a concise critique listing its problems.",
+    };
+    format!(
+        "Review your previous response as a strict verifier. If it is correct, complete and \
+         follows the instructions of the conversation, reply with exactly {}. {}",
+        APPROVED, otherwise
+    )
+}
+
+/// Returns the message asking the draft model to revise its response
+/// following `critique`.
+pub(crate) fn revision_prompt(critique: &str) -> String {
+    format!(
+        "A reviewer found problems with your response:\n{}\nRevise your response to address them.",
+        critique
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_approval() {
+        assert_eq!(parse_review("APPROVED"), Review::Approved);
+        assert_eq!(parse_review("  approved.\n"), Review::Approved);
+    }
+
+    #[test]
+    fn test_parse_revision() {
+        assert_eq!(
+            parse_review(" The capital of Australia is Canberra. "),
+            Review::Revised("The capital of Australia is Canberra.".to_string())
+        );
+        assert_eq!(
+            parse_review("APPROVED, but mention the units"),
+            Review::Revised("APPROVED, but mention the units".to_string())
+        );
+    }
+
+    #[test]
+    fn test_review_prompt_depends_on_mode() {
+        assert!(review_prompt(VerifyMode::Edit).contains("corrected response"));
+        assert!(review_prompt(VerifyMode::Critique).contains("critique"));
+    }
+}
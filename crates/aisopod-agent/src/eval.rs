@@ -0,0 +1,511 @@
+//! Evaluation of agents against suites of prompts.
+//!
+//! An [`EvalSuite`] is defined in YAML as a list of cases, each a prompt
+//! sent to an agent in a fresh session along with assertions on the run:
+//!
+//! ```yaml
+//! name: support
+//! agent: support
+//! judge_model: openai/gpt-4o
+//! cases:
+//!   - name: refund-policy
+//!     prompt: How long do I have to return an item?
+//!     assertions:
+//!       - type: contains
+//!         value: 30 days
+//!       - type: regex
+//!         pattern: "(?i)receipt"
+//!       - type: tool_called
+//!         name: search_docs
+//!       - type: llm_judge
+//!         criteria: The answer is polite and does not promise exceptions.
+//! ```
+//!
+//! An [`EvalRunner`] runs the cases through an [`AgentRunner`] and returns
+//! an [`EvalReport`] of the passed and failed cases. LLM-judge assertions
+//! ask the judge model whether the response meets their criteria.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+use aisopod_provider::{ChatCompletionRequest, Message, MessageContent, Role};
+use anyhow::{anyhow, bail, Result};
+use futures_util::StreamExt;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::resolution::resolve_agent_config;
+use crate::runner::AgentRunner;
+use crate::types::{AgentRunParams, AgentRunResult};
+
+/// The reply of a judge model finding that a response meets the criteria.
+pub const JUDGE_PASS: &str = "PASS";
+
+/// A suite of evaluation cases.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalSuite {
+    /// The suite name, defaulting to the file stem when loaded from a file.
+    #[serde(default)]
+    pub name: String,
+    /// The agent running the cases that do not name one.
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// The model judging LLM-judge assertions that do not name one.
+    #[serde(default)]
+    pub judge_model: Option<String>,
+    /// The cases of the suite.
+    pub cases: Vec<EvalCase>,
+}
+
+/// A prompt sent to an agent, with the assertions its run must pass.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalCase {
+    /// The case name, unique within its suite.
+    pub name: String,
+    /// The user message starting the run.
+    pub prompt: String,
+    /// The agent running the case, overriding the suite's agent.
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// The assertions on the run.
+    #[serde(default)]
+    pub assertions: Vec<Assertion>,
+}
+
+/// An assertion on the run of an evaluation case.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Assertion {
+    /// The response contains `value`.
+    Contains {
+        value: String,
+        #[serde(default)]
+        ignore_case: bool,
+    },
+    /// The response matches the regular expression `pattern`.
+    Regex { pattern: String },
+    /// The agent called the tool `name` during the run.
+    ToolCalled { name: String },
+    /// A judge model finds that the response meets `criteria`.
+    LlmJudge {
+        criteria: String,
+        #[serde(default)]
+        model: Option<String>,
+    },
+}
+
+impl fmt::Display for Assertion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Assertion::Contains { value, .. } => write!(f, "contains {:?}", value),
+            Assertion::Regex { pattern } => write!(f, "matches /{}/", pattern),
+            Assertion::ToolCalled { name } => write!(f, "called tool '{}'", name),
+            Assertion::LlmJudge { criteria, .. } => write!(f, "judged: {}", criteria),
+        }
+    }
+}
+
+impl EvalSuite {
+    /// Parses and validates a suite defined in YAML.
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        let suite: EvalSuite =
+            serde_yaml::from_str(yaml).map_err(|e| anyhow!("Invalid eval suite: {}", e))?;
+        suite.validate()?;
+        Ok(suite)
+    }
+
+    /// Loads the suite defined in the YAML file at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let yaml = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read eval suite '{}': {}", path.display(), e))?;
+        let mut suite = Self::from_yaml(&yaml).map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+        if suite.name.is_empty() {
+            suite.name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+        }
+        Ok(suite)
+    }
+
+    /// Checks that the cases are named uniquely and that their assertions
+    /// can be evaluated.
+    fn validate(&self) -> Result<()> {
+        if self.cases.is_empty() {
+            bail!("Eval suite has no cases");
+        }
+        let mut names = HashSet::new();
+        for case in &self.cases {
+            if !names.insert(case.name.as_str()) {
+                bail!("Duplicate eval case '{}'", case.name);
+            }
+            for assertion in &case.assertions {
+                match assertion {
+                    Assertion::Regex { pattern } => {
+                        Regex::new(pattern)
+                            .map_err(|e| anyhow!("Invalid regex in case '{}': {}", case.name, e))?;
+                    }
+                    Assertion::LlmJudge { model: None, .. } if self.judge_model.is_none() => {
+                        bail!(
+                            "Case '{}' has an llm_judge assertion but no judge model is set",
+                            case.name
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of an assertion.
+#[derive(Debug, Clone, Serialize)]
+pub struct AssertionResult {
+    /// The description of the assertion.
+    pub assertion: String,
+    /// Whether the assertion passed.
+    pub passed: bool,
+    /// Why the assertion failed, or the judge's comments.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// The outcome of an evaluation case.
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseReport {
+    /// The case name.
+    pub name: String,
+    /// The agent that ran the case.
+    pub agent: Option<String>,
+    /// Whether the run succeeded and passed all assertions.
+    pub passed: bool,
+    /// The response of the agent.
+    pub response: String,
+    /// The names of the tools called during the run.
+    pub tool_calls: Vec<String>,
+    /// The error failing the run, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The outcomes of the case's assertions.
+    pub assertions: Vec<AssertionResult>,
+    /// How long the case took to run, in milliseconds.
+    pub duration_ms: u64,
+}
+
+/// The outcome of an evaluation suite.
+#[derive(Debug, Clone, Serialize)]
+pub struct EvalReport {
+    /// The suite name.
+    pub suite: String,
+    /// The outcomes of the suite's cases, in order.
+    pub cases: Vec<CaseReport>,
+}
+
+impl EvalReport {
+    /// Returns the number of passed cases.
+    pub fn passed(&self) -> usize {
+        self.cases.iter().filter(|case| case.passed).count()
+    }
+
+    /// Returns the number of failed cases.
+    pub fn failed(&self) -> usize {
+        self.cases.len() - self.passed()
+    }
+
+    /// Returns true if every case passed.
+    pub fn all_passed(&self) -> bool {
+        self.failed() == 0
+    }
+}
+
+/// Runs evaluation suites against agents.
+pub struct EvalRunner {
+    runner: Arc<AgentRunner>,
+    agent: Option<String>,
+}
+
+impl EvalRunner {
+    /// Creates an evaluation runner running agents with `runner`.
+    pub fn new(runner: Arc<AgentRunner>) -> Self {
+        Self {
+            runner,
+            agent: None,
+        }
+    }
+
+    /// Runs every case with `agent_id`, regardless of the suite.
+    pub fn with_agent(mut self, agent_id: impl Into<String>) -> Self {
+        self.agent = Some(agent_id.into());
+        self
+    }
+
+    /// Runs the cases of `suite` one after another.
+    pub async fn run(&self, suite: &EvalSuite) -> EvalReport {
+        let mut cases = Vec::with_capacity(suite.cases.len());
+        for case in &suite.cases {
+            cases.push(self.run_case(suite, case).await);
+        }
+        EvalReport {
+            suite: suite.name.clone(),
+            cases,
+        }
+    }
+
+    /// Runs `case` in a fresh session and evaluates its assertions.
+    async fn run_case(&self, suite: &EvalSuite, case: &EvalCase) -> CaseReport {
+        let agent = self
+            .agent
+            .clone()
+            .or_else(|| case.agent.clone())
+            .or_else(|| suite.agent.clone());
+        debug!("Running eval case '{}' with agent {:?}", case.name, agent);
+        let started = Instant::now();
+
+        let mut report = CaseReport {
+            name: case.name.clone(),
+            agent: agent.clone(),
+            passed: false,
+            response: String::new(),
+            tool_calls: Vec::new(),
+            error: None,
+            assertions: Vec::new(),
+            duration_ms: 0,
+        };
+        match self.run_agent(suite, case, agent).await {
+            Ok(result) => {
+                for assertion in &case.assertions {
+                    let outcome = self.check(suite, case, assertion, &result).await;
+                    report.assertions.push(outcome);
+                }
+                report.passed = report.assertions.iter().all(|outcome| outcome.passed);
+                report.response = result.response;
+                report.tool_calls = result
+                    .tool_calls
+                    .into_iter()
+                    .map(|call| call.name)
+                    .collect();
+            }
+            Err(e) => report.error = Some(e.to_string()),
+        }
+        report.duration_ms = started.elapsed().as_millis() as u64;
+        report
+    }
+
+    async fn run_agent(
+        &self,
+        suite: &EvalSuite,
+        case: &EvalCase,
+        agent: Option<String>,
+    ) -> Result<AgentRunResult> {
+        // The pipeline falls back to the session's agent for unknown IDs
+        if let Some(agent) = &agent {
+            resolve_agent_config(&self.runner.config(), agent)?;
+        }
+        let params = AgentRunParams::new(
+            format!("eval:{}:{}", suite.name, case.name),
+            vec![user_message(&case.prompt)],
+            agent,
+        );
+        self.runner.run_and_get_result(params).await
+    }
+
+    /// Evaluates `assertion` on the run of `case`.
+    async fn check(
+        &self,
+        suite: &EvalSuite,
+        case: &EvalCase,
+        assertion: &Assertion,
+        result: &AgentRunResult,
+    ) -> AssertionResult {
+        let response = &result.response;
+        let (passed, reason) = match assertion {
+            Assertion::Contains { value, ignore_case } => {
+                let passed = if *ignore_case {
+                    response.to_lowercase().contains(&value.to_lowercase())
+                } else {
+                    response.contains(value.as_str())
+                };
+                (passed, None)
+            }
+            Assertion::Regex { pattern } => match Regex::new(pattern) {
+                Ok(regex) => (regex.is_match(response), None),
+                Err(e) => (false, Some(e.to_string())),
+            },
+            Assertion::ToolCalled { name } => {
+                let passed = result.tool_calls.iter().any(|call| call.name == *name);
+                (passed, None)
+            }
+            Assertion::LlmJudge { criteria, model } => {
+                match model.as_ref().or(suite.judge_model.as_ref()) {
+                    Some(model) => {
+                        match self.judge(model, &case.prompt, response, criteria).await {
+                            Ok(verdict) => verdict,
+                            Err(e) => (false, Some(format!("Judge failed: {}", e))),
+                        }
+                    }
+                    None => (false, Some("No judge model is set".to_string())),
+                }
+            }
+        };
+        AssertionResult {
+            assertion: assertion.to_string(),
+            passed,
+            reason,
+        }
+    }
+
+    /// Asks `model` whether `response` to `prompt` meets `criteria`.
+    async fn judge(
+        &self,
+        model: &str,
+        prompt: &str,
+        response: &str,
+        criteria: &str,
+    ) -> Result<(bool, Option<String>)> {
+        let (provider, _) = self
+            .runner
+            .providers()
+            .resolve_model(model)
+            .ok_or_else(|| anyhow!("Judge model not found: {}", model))?;
+        let request = ChatCompletionRequest {
+            model: model.to_string(),
+            messages: vec![user_message(&judge_prompt(prompt, response, criteria))],
+            tools: None,
+            temperature: Some(0.0),
+            max_tokens: None,
+            stop: None,
+            stream: true,
+        };
+
+        let mut reply = String::new();
+        let mut stream = provider.chat_completion(request).await?;
+        while let Some(chunk) = stream.next().await {
+            if let Some(content) = chunk?.delta.content {
+                reply.push_str(&content);
+            }
+        }
+        Ok(parse_verdict(&reply))
+    }
+}
+
+fn user_message(text: &str) -> Message {
+    Message {
+        role: Role::User,
+        content: MessageContent::Text(text.to_string()),
+        tool_calls: None,
+        tool_call_id: None,
+    }
+}
+
+/// Returns the message asking a judge model to grade `response`.
+fn judge_prompt(prompt: &str, response: &str, criteria: &str) -> String {
+    format!(
+        "You are grading the response of an AI assistant.\n\n\
+         User message:\n{}\n\nAssistant response:\n{}\n\nCriteria:\n{}\n\n\
+         If the response meets the criteria, reply with {}. Otherwise reply with FAIL \
+         followed by a one-sentence reason.",
+        prompt, response, criteria, JUDGE_PASS
+    )
+}
+
+/// Parses the reply of a judge model into whether it passed and its
+/// comments, if any.
+///
+/// The reply passes if its first word is [`JUDGE_PASS`], ignoring case.
+pub fn parse_verdict(reply: &str) -> (bool, Option<String>) {
+    let reply = reply.trim();
+    let passed = reply
+        .get(..JUDGE_PASS.len())
+        .is_some_and(|verdict| verdict.eq_ignore_ascii_case(JUDGE_PASS))
+        && !reply[JUDGE_PASS.len()..].starts_with(char::is_alphanumeric);
+    let comments = if passed {
+        &reply[JUDGE_PASS.len()..]
+    } else {
+        reply
+    };
+    let comments = comments.trim_start_matches([':', '.', ' ', '\n']).trim();
+    (passed, (!comments.is_empty()).then(|| comments.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUITE: &str = r#"
+name: smoke
+agent: test-agent
+judge_model: judge/model
+cases:
+  - name: greeting
+    prompt: Say hello
+    assertions:
+      - type: contains
+        value: HELLO
+        ignore_case: true
+      - type: regex
+        pattern: "^H"
+      - type: tool_called
+        name: calculator
+      - type: llm_judge
+        criteria: The greeting is friendly.
+"#;
+
+    #[test]
+    fn test_parse_suite() {
+        let suite = EvalSuite::from_yaml(SUITE).unwrap();
+        assert_eq!(suite.name, "smoke");
+        assert_eq!(suite.agent.as_deref(), Some("test-agent"));
+        let case = &suite.cases[0];
+        assert_eq!(case.assertions.len(), 4);
+        assert_eq!(
+            case.assertions[0],
+            Assertion::Contains {
+                value: "HELLO".to_string(),
+                ignore_case: true
+            }
+        );
+        assert_eq!(case.assertions[2].to_string(), "called tool 'calculator'");
+    }
+
+    #[test]
+    fn test_invalid_suites_rejected() {
+        assert!(EvalSuite::from_yaml("cases: []").is_err());
+
+        let duplicate = "cases:\n  - {name: a, prompt: x}\n  - {name: a, prompt: y}\n";
+        assert!(EvalSuite::from_yaml(duplicate)
+            .unwrap_err()
+            .to_string()
+            .contains("Duplicate"));
+
+        let regex = "cases:\n  - name: a\n    prompt: x\n    assertions:\n      \
+                     - {type: regex, pattern: '('}\n";
+        assert!(EvalSuite::from_yaml(regex).is_err());
+
+        let judge = "cases:\n  - name: a\n    prompt: x\n    assertions:\n      \
+                     - {type: llm_judge, criteria: ok}\n";
+        assert!(EvalSuite::from_yaml(judge)
+            .unwrap_err()
+            .to_string()
+            .contains("no judge model"));
+    }
+
+    #[test]
+    fn test_parse_verdict() {
+        assert_eq!(parse_verdict("PASS"), (true, None));
+        assert_eq!(
+            parse_verdict("pass: concise and polite"),
+            (true, Some("concise and polite".to_string()))
+        );
+        assert_eq!(
+            parse_verdict("FAIL: the tone is rude"),
+            (false, Some("FAIL: the tone is rude".to_string()))
+        );
+        assert!(!parse_verdict("PASSABLE, but terse").0);
+        assert_eq!(parse_verdict(""), (false, None));
+    }
+}
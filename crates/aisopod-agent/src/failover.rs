@@ -2,6 +2,10 @@
 //!
 //! This module provides intelligent failover capabilities for model execution,
 //! including automatic retry, model switching, and error classification.
+//!
+//! Errors are sorted into an [`ErrorClass`], and the [`FailoverPolicy`] of
+//! `models.failover` decides the rule applied to each class: retry the same
+//! model, move to the next model of the chain, compact, or stop.
 
 use std::time::{Duration, Instant};
 
@@ -13,6 +17,7 @@ use tokio::sync::mpsc;
 use crate::resolution::ModelChain;
 use crate::types::AgentEvent;
 
+use aisopod_config::types::{FailoverPolicy, FailoverRule};
 use aisopod_provider::normalize::ProviderError;
 
 /// Action to take when an error occurs during model execution.
@@ -30,6 +35,81 @@ pub enum FailoverAction {
     Abort,
 }
 
+/// Class of an error of a model call, which the failover policy maps to a
+/// [`FailoverRule`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// The credentials were rejected
+    Auth,
+    /// The provider rate-limited the request
+    RateLimit,
+    /// The request exceeded the model's context length
+    ContextOverflow,
+    /// The model does not exist
+    ModelNotFound,
+    /// The provider failed with a server error
+    ServerError,
+    /// The provider could not be reached or timed out
+    NetworkError,
+    /// The provider rejected the request as invalid
+    InvalidRequest,
+    /// The response stream closed before completion
+    StreamClosed,
+    /// The error is of no known class
+    Unknown,
+}
+
+impl ErrorClass {
+    /// Returns the rule `policy` applies to errors of this class.
+    pub fn rule(self, policy: &FailoverPolicy) -> FailoverRule {
+        match self {
+            ErrorClass::Auth => policy.auth,
+            ErrorClass::RateLimit => policy.rate_limit,
+            ErrorClass::ContextOverflow => policy.context_overflow,
+            ErrorClass::ModelNotFound => policy.model_not_found,
+            ErrorClass::ServerError => policy.server_error,
+            ErrorClass::NetworkError => policy.network_error,
+            ErrorClass::InvalidRequest => policy.invalid_request,
+            ErrorClass::StreamClosed => policy.stream_closed,
+            ErrorClass::Unknown => policy.unknown,
+        }
+    }
+}
+
+/// Returns the class of a provider error.
+pub fn error_class(error: &ProviderError) -> ErrorClass {
+    match error {
+        ProviderError::AuthenticationFailed { .. } => ErrorClass::Auth,
+        ProviderError::RateLimited { .. } => ErrorClass::RateLimit,
+        ProviderError::ContextLengthExceeded { .. } => ErrorClass::ContextOverflow,
+        ProviderError::ModelNotFound { .. } => ErrorClass::ModelNotFound,
+        ProviderError::ServerError { .. } => ErrorClass::ServerError,
+        ProviderError::NetworkError { .. } => ErrorClass::NetworkError,
+        ProviderError::InvalidRequest { .. } => ErrorClass::InvalidRequest,
+        ProviderError::StreamClosed => ErrorClass::StreamClosed,
+        ProviderError::Unknown { .. } => ErrorClass::Unknown,
+        // Handle any future variants that may be added to ProviderError
+        _ => ErrorClass::Unknown,
+    }
+}
+
+/// Returns the action for an error of `class` under `policy`.
+fn policy_action(
+    class: ErrorClass,
+    retry_after: Option<Duration>,
+    policy: &FailoverPolicy,
+) -> FailoverAction {
+    match class.rule(policy) {
+        FailoverRule::Retry => FailoverAction::WaitAndRetry(
+            retry_after.unwrap_or(Duration::from_millis(policy.retry_delay_ms)),
+        ),
+        FailoverRule::NextAuth => FailoverAction::RetryWithNextAuth,
+        FailoverRule::Next => FailoverAction::FailoverToNext,
+        FailoverRule::Compact => FailoverAction::CompactAndRetry,
+        FailoverRule::Stop => FailoverAction::Abort,
+    }
+}
+
 /// Represents an attempt to execute a model.
 #[derive(Debug, Clone)]
 pub struct ModelAttempt {
@@ -54,20 +134,33 @@ pub struct FailoverState {
     model_chain: ModelChain,
     /// All models in the chain (primary + fallbacks)
     all_models: Vec<String>,
+    /// The policy deciding how errors are handled
+    policy: FailoverPolicy,
 }
 
 impl FailoverState {
-    /// Creates a new FailoverState from a model chain.
+    /// Creates a new FailoverState from a model chain with the default policy.
     pub fn new(model_chain: &ModelChain) -> Self {
+        Self::with_policy(model_chain, FailoverPolicy::default())
+    }
+
+    /// Creates a new FailoverState from a model chain handling errors by `policy`.
+    pub fn with_policy(model_chain: &ModelChain, policy: FailoverPolicy) -> Self {
         Self {
             attempted_models: Vec::new(),
             current_model_index: 0,
-            max_attempts: 3, // Default max attempts per model
+            max_attempts: policy.max_retries + 1,
             model_chain: model_chain.clone(),
             all_models: model_chain.all_models(),
+            policy,
         }
     }
 
+    /// Gets the policy deciding how errors are handled.
+    pub fn policy(&self) -> &FailoverPolicy {
+        &self.policy
+    }
+
     /// Gets the current model ID being attempted.
     pub fn current_model(&self) -> &str {
         if self.current_model_index < self.all_models.len() {
@@ -125,11 +218,11 @@ impl FailoverState {
 /// Classifies an error and determines the appropriate failover action.
 ///
 /// This function analyzes the type of error and determines what action
-/// should be taken:
-/// - Authentication errors → RetryWithNextAuth or FailoverToNext
-/// - Rate limit errors → WaitAndRetry or FailoverToNext
+/// should be taken under the default [`FailoverPolicy`]:
+/// - Authentication errors → RetryWithNextAuth
+/// - Rate limit errors → WaitAndRetry
 /// - Context length errors → CompactAndRetry
-/// - Timeout errors → RetryWithNextAuth or FailoverToNext
+/// - Network errors → RetryWithNextAuth
 /// - Server errors → FailoverToNext
 /// - Other errors → Abort
 ///
@@ -141,52 +234,22 @@ impl FailoverState {
 ///
 /// The FailoverAction to take for this error.
 pub fn classify_error(error: &ProviderError) -> FailoverAction {
-    match error {
-        ProviderError::AuthenticationFailed { .. } => {
-            // Authentication failures should try different credentials first,
-            // then failover to next model if that doesn't work
-            FailoverAction::RetryWithNextAuth
-        }
-        ProviderError::RateLimited { retry_after, .. } => {
-            // Rate limits should wait and retry with the same model first
-            // If there's a retry_after duration, use it
-            match retry_after {
-                Some(duration) => FailoverAction::WaitAndRetry(*duration),
-                None => FailoverAction::WaitAndRetry(Duration::from_secs(5)), // Default wait
-            }
-        }
-        ProviderError::ContextLengthExceeded { .. } => {
-            // Context length exceeded - compact messages and retry
-            FailoverAction::CompactAndRetry
-        }
-        ProviderError::ModelNotFound { .. } => {
-            // Model not found - failover to next model
-            FailoverAction::FailoverToNext
-        }
-        ProviderError::ServerError { .. } => {
-            // Server errors - try next model
-            FailoverAction::FailoverToNext
-        }
-        ProviderError::NetworkError { .. } => {
-            // Network errors - retry with next auth, then failover
-            FailoverAction::RetryWithNextAuth
-        }
-        ProviderError::InvalidRequest { .. } => {
-            // Invalid request - usually means the request itself is problematic
-            // Try compacting and retrying
-            FailoverAction::CompactAndRetry
-        }
-        ProviderError::StreamClosed => {
-            // Stream closed unexpectedly - try next model
-            FailoverAction::FailoverToNext
-        }
-        ProviderError::Unknown { .. } => {
-            // Unknown errors - abort
-            FailoverAction::Abort
-        }
-        // Handle any future variants that may be added to ProviderError
-        _ => FailoverAction::Abort,
-    }
+    classify_error_with_policy(error, &FailoverPolicy::default())
+}
+
+/// Classifies an error and determines the failover action under `policy`.
+///
+/// Rate limits with a retry-after wait for that long before a retry, other
+/// retries wait for the policy's `retry_delay_ms`.
+pub fn classify_error_with_policy(
+    error: &ProviderError,
+    policy: &FailoverPolicy,
+) -> FailoverAction {
+    let retry_after = match error {
+        ProviderError::RateLimited { retry_after, .. } => *retry_after,
+        _ => None,
+    };
+    policy_action(error_class(error), retry_after, policy)
 }
 
 /// Classifies a generic error and determines the appropriate failover action.
@@ -194,20 +257,29 @@ pub fn classify_error(error: &ProviderError) -> FailoverAction {
 /// This is a generic version that works with any error type by checking
 /// if it's a ProviderError or examining the error message.
 pub fn classify_error_generic<E: StdError + Send + Sync + 'static>(error: &E) -> FailoverAction {
+    classify_error_generic_with_policy(error, &FailoverPolicy::default())
+}
+
+/// Classifies a generic error and determines the failover action under
+/// `policy`.
+pub fn classify_error_generic_with_policy<E: StdError + Send + Sync + 'static>(
+    error: &E,
+    policy: &FailoverPolicy,
+) -> FailoverAction {
     // First, try to downcast to ProviderError
     if let Some(provider_error) = (error as &dyn Any).downcast_ref::<ProviderError>() {
-        return classify_error(provider_error);
+        return classify_error_with_policy(provider_error, policy);
     }
 
     // Fallback to message-based classification
-    classify_error_by_message(&error.to_string())
+    policy_action(error_class_by_message(&error.to_string()), None, policy)
 }
 
 /// Executes a model call with intelligent failover.
 ///
 /// This function implements the failover logic:
 /// 1. Calls the model with the provided function
-/// 2. If an error occurs, classifies it using classify_error_generic
+/// 2. If an error occurs, classifies it under the state's failover policy
 /// 3. Takes appropriate action based on the classification:
 ///    - RetryWithNextAuth: Switches credentials and retries
 ///    - WaitAndRetry: Waits for the specified duration, then retries
@@ -267,7 +339,7 @@ where
                 );
 
                 // Classify the error to determine the appropriate action
-                let action = classify_error_generic(&error);
+                let action = classify_error_generic_with_policy(&error, &state.policy);
 
                 match action {
                     FailoverAction::RetryWithNextAuth => {
@@ -298,8 +370,12 @@ where
                         if state.can_retry_current_model() {
                             current_attempts += 1;
                             continue;
+                        } else if state.advance().is_none() {
+                            return Err(anyhow::anyhow!(
+                                "All models exhausted. Last error: {}",
+                                error
+                            ));
                         } else {
-                            state.advance();
                             let _ = event_tx
                                 .send(AgentEvent::ModelSwitch {
                                     from: model_id.clone(),
@@ -356,6 +432,15 @@ where
 /// This is a fallback version that works with any error type by
 /// examining the error message string for known patterns.
 pub fn classify_error_by_message(error_msg: &str) -> FailoverAction {
+    policy_action(
+        error_class_by_message(error_msg),
+        None,
+        &FailoverPolicy::default(),
+    )
+}
+
+/// Returns the class of an error from the known patterns of its message.
+pub fn error_class_by_message(error_msg: &str) -> ErrorClass {
     // Check for authentication errors
     if error_msg.contains("Authentication")
         || error_msg.contains("invalid API key")
@@ -364,7 +449,7 @@ pub fn classify_error_by_message(error_msg: &str) -> FailoverAction {
         || error_msg.contains("401")
         || error_msg.contains("403")
     {
-        return FailoverAction::RetryWithNextAuth;
+        return ErrorClass::Auth;
     }
 
     // Check for rate limit errors
@@ -373,8 +458,7 @@ pub fn classify_error_by_message(error_msg: &str) -> FailoverAction {
         || error_msg.contains("429")
         || error_msg.contains("too many requests")
     {
-        // Default to 5 second wait if no specific retry_after found
-        return FailoverAction::WaitAndRetry(Duration::from_secs(5));
+        return ErrorClass::RateLimit;
     }
 
     // Check for context length errors
@@ -383,7 +467,7 @@ pub fn classify_error_by_message(error_msg: &str) -> FailoverAction {
         || error_msg.contains("maximum context length")
         || error_msg.contains("413")
     {
-        return FailoverAction::CompactAndRetry;
+        return ErrorClass::ContextOverflow;
     }
 
     // Check for model not found
@@ -391,7 +475,7 @@ pub fn classify_error_by_message(error_msg: &str) -> FailoverAction {
         || error_msg.contains("model not found")
         || error_msg.contains("404")
     {
-        return FailoverAction::FailoverToNext;
+        return ErrorClass::ModelNotFound;
     }
 
     // Check for server errors
@@ -402,7 +486,7 @@ pub fn classify_error_by_message(error_msg: &str) -> FailoverAction {
         || error_msg.contains("503")
         || error_msg.contains("504")
     {
-        return FailoverAction::FailoverToNext;
+        return ErrorClass::ServerError;
     }
 
     // Check for network errors
@@ -411,16 +495,15 @@ pub fn classify_error_by_message(error_msg: &str) -> FailoverAction {
         || error_msg.contains("connection")
         || error_msg.contains("timeout")
     {
-        return FailoverAction::RetryWithNextAuth;
+        return ErrorClass::NetworkError;
     }
 
     // Check for stream closed
     if error_msg.contains("StreamClosed") || error_msg.contains("stream closed") {
-        return FailoverAction::FailoverToNext;
+        return ErrorClass::StreamClosed;
     }
 
-    // Default to Abort for unknown errors
-    FailoverAction::Abort
+    ErrorClass::Unknown
 }
 
 #[cfg(test)]
@@ -0,0 +1,174 @@
+//! Agent-to-agent handoff of sessions.
+//!
+//! A handoff transfers a session from one agent to another, e.g. from a
+//! triage agent to a specialist. The [`HandoffRegistry`] records which
+//! agent each handed-off session is bound to, overriding the configured
+//! bindings for the session's subsequent messages, and keeps the handoff
+//! note and context for the receiving agent's system prompt.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::resolution::resolve_agent_config;
+
+/// A session handed off from one agent to another.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Handoff {
+    /// The agent that handed the session off.
+    pub from_agent: String,
+    /// The agent handling the session after the handoff.
+    pub to_agent: String,
+    /// The note explaining the handoff to the receiving agent.
+    pub note: String,
+    /// Optional context gathered by the handing-off agent.
+    pub context: Option<String>,
+    /// When the handoff happened.
+    pub created_at: DateTime<Utc>,
+}
+
+impl Handoff {
+    /// Returns the system prompt section introducing the handoff to the
+    /// receiving agent.
+    pub fn prompt_section(&self) -> String {
+        let mut section = format!(
+            "## Handoff\nThis conversation was handed off to you by agent '{}'.\nHandoff note: {}",
+            self.from_agent, self.note
+        );
+        if let Some(context) = &self.context {
+            section.push_str("\nContext: ");
+            section.push_str(context);
+        }
+        section
+    }
+}
+
+/// Registry of session handoffs, keyed by session key.
+///
+/// Handoffs are only accepted to agents present in the configuration. A
+/// later handoff of the same session replaces the earlier one.
+pub struct HandoffRegistry {
+    config: Arc<aisopod_config::AisopodConfig>,
+    handoffs: DashMap<String, Handoff>,
+}
+
+impl HandoffRegistry {
+    /// Creates an empty registry validating agents against `config`.
+    pub fn new(config: Arc<aisopod_config::AisopodConfig>) -> Self {
+        Self {
+            config,
+            handoffs: DashMap::new(),
+        }
+    }
+
+    /// Records a handoff of `session_key`, binding it to the handoff's
+    /// receiving agent.
+    pub fn record(&self, session_key: &str, handoff: Handoff) -> Result<()> {
+        resolve_agent_config(&self.config, &handoff.to_agent)?;
+        self.handoffs.insert(session_key.to_string(), handoff);
+        Ok(())
+    }
+
+    /// Returns the latest handoff of `session_key`, if any.
+    pub fn get(&self, session_key: &str) -> Option<Handoff> {
+        self.handoffs.get(session_key).map(|h| h.clone())
+    }
+
+    /// Returns the agent `session_key` was handed off to, if any.
+    pub fn agent_for(&self, session_key: &str) -> Option<String> {
+        self.handoffs.get(session_key).map(|h| h.to_agent.clone())
+    }
+
+    /// Removes the handoff of `session_key`, returning the session to its
+    /// configured binding.
+    pub fn clear(&self, session_key: &str) -> Option<Handoff> {
+        self.handoffs.remove(session_key).map(|(_, h)| h)
+    }
+}
+
+#[async_trait]
+impl aisopod_tools::SessionHandoff for HandoffRegistry {
+    async fn handoff(
+        &self,
+        session_key: &str,
+        from_agent: &str,
+        to_agent: &str,
+        note: &str,
+        context: Option<&str>,
+    ) -> Result<()> {
+        self.record(
+            session_key,
+            Handoff {
+                from_agent: from_agent.to_string(),
+                to_agent: to_agent.to_string(),
+                note: note.to_string(),
+                context: context.map(str::to_string),
+                created_at: Utc::now(),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aisopod_config::types::Agent;
+    use aisopod_tools::SessionHandoff;
+
+    fn registry() -> HandoffRegistry {
+        let mut config = aisopod_config::AisopodConfig::default();
+        for id in ["triage", "billing"] {
+            config.agents.agents.push(Agent {
+                id: id.to_string(),
+                ..Default::default()
+            });
+        }
+        HandoffRegistry::new(Arc::new(config))
+    }
+
+    #[tokio::test]
+    async fn test_handoff_binds_session_to_target() {
+        let registry = registry();
+        registry
+            .handoff("session_1", "triage", "billing", "Refund request", None)
+            .await
+            .unwrap();
+
+        assert_eq!(registry.agent_for("session_1").as_deref(), Some("billing"));
+        assert_eq!(registry.agent_for("session_2"), None);
+
+        registry.clear("session_1");
+        assert_eq!(registry.agent_for("session_1"), None);
+    }
+
+    #[tokio::test]
+    async fn test_handoff_to_unknown_agent_fails() {
+        let registry = registry();
+        let result = registry
+            .handoff("session_1", "triage", "legal", "Contract question", None)
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(registry.agent_for("session_1"), None);
+    }
+
+    #[test]
+    fn test_prompt_section_includes_note_and_context() {
+        let handoff = Handoff {
+            from_agent: "triage".to_string(),
+            to_agent: "billing".to_string(),
+            note: "Refund request".to_string(),
+            context: Some("Order 42".to_string()),
+            created_at: Utc::now(),
+        };
+
+        let section = handoff.prompt_section();
+        assert!(section.contains("agent 'triage'"));
+        assert!(section.contains("Handoff note: Refund request"));
+        assert!(section.contains("Context: Order 42"));
+    }
+}
@@ -4,15 +4,26 @@
 
 pub mod abort;
 pub mod binding;
+pub mod budget;
 pub mod compaction;
 pub mod context_guard;
+pub mod draft_verify;
+pub mod eval;
 pub mod failover;
+pub mod handoff;
+pub mod loop_guard;
 pub mod memory;
 pub mod pipeline;
+pub mod planning;
 pub mod prompt;
+pub mod replay;
 pub mod resolution;
 pub mod runner;
+pub mod scheduled;
 pub mod skills_integration;
+pub mod steering;
+pub mod streaming;
+pub mod structured_output;
 pub mod subagent;
 pub mod transcript;
 pub mod types;
@@ -21,25 +32,38 @@ pub mod usage;
 // Re-export key types from crate root
 pub use abort::{notify_abort, AbortHandle, AbortRegistry};
 pub use binding::{AgentBinding, BindingMatch, PeerMatch};
+pub use budget::{BudgetExceeded, BudgetLimit, BudgetNotifier, BudgetScope};
 pub use compaction::{
     compact_messages, estimate_token_count, select_strategy, CompactionSeverity, CompactionStrategy,
+    LlmSummaryCompactor,
 };
 pub use context_guard::ContextWindowGuard;
+pub use eval::{Assertion, EvalCase, EvalReport, EvalRunner, EvalSuite};
 pub use failover::{
-    classify_error, execute_with_failover, FailoverAction, FailoverState, ModelAttempt,
+    classify_error, classify_error_with_policy, error_class, execute_with_failover, ErrorClass,
+    FailoverAction, FailoverState, ModelAttempt,
 };
+pub use handoff::{Handoff, HandoffRegistry};
+pub use loop_guard::{LoopDetector, ToolLoop};
 pub use memory::{
     create_memory_tool_schema, extract_memories_after_run, inject_memory_context, MemoryConfig,
     MemoryTool,
 };
 pub use pipeline::{AgentPipeline, AgentRunStream};
-pub use prompt::{PromptSection, SystemPromptBuilder};
+pub use planning::{PlanStep, PlanStepStatus, PlanningConfig};
+pub use prompt::{render_template, PromptSection, SystemPromptBuilder};
+pub use replay::{diff_lines, DiffLine, ReplayReport, SessionReplayer, TurnReplay};
 pub use resolution::{
-    list_agent_ids, resolve_agent_config, resolve_agent_model, resolve_session_agent_id,
-    ModelChain, ResolutionConfig,
+    list_agent_ids, resolve_agent_config, resolve_agent_model, resolve_model_chain,
+    resolve_session_agent_id, resolve_tool_context, ConfigToolContexts, ModelChain,
+    ResolutionConfig,
 };
-pub use runner::{AgentRunner, SubagentRunnerExt};
-pub use skills_integration::{collect_skill_tools, merge_skill_prompts, resolve_agent_skills, Skill, SkillContext, SkillMeta, SkillRegistry};
+pub use runner::{AgentRunner, SessionLeases, SubagentRunnerExt};
+pub use scheduled::{register_schedules, schedule_job_id, AgentJobRunner};
+pub use skills_integration::{collect_skill_templates, collect_skill_tools, merge_skill_prompts, resolve_agent_skills, Skill, SkillContext, SkillMeta, SkillRegistry};
+pub use steering::{SteeredRun, SteeringRegistry, Submission};
+pub use streaming::{ReplySink, StreamingReplyConfig};
+pub use structured_output::OutputValidator;
 pub use subagent::{spawn_subagent, ResourceBudget, SubagentSpawnParams};
 pub use transcript::{repair_transcript, ProviderKind};
 pub use types::{AgentEvent, AgentRunParams, AgentRunResult, SessionMetadata, UsageReport};
@@ -0,0 +1,180 @@
+//! Detection of runs stuck repeating the same tool calls.
+//!
+//! The pipeline records every tool call of a run in a [`LoopDetector`]
+//! configured from `tools.loop_guard`. Once the latest calls are one call,
+//! or a cycle of calls, repeated `max_repeats` times in a row, the detector
+//! reports a [`ToolLoop`] and the pipeline intervenes as configured: it
+//! nudges the model, withholds the tools to force a final answer, or fails
+//! the run with the loop's description.
+
+use std::fmt;
+
+use aisopod_config::types::LoopGuardConfig;
+
+/// A loop of tool calls found by a [`LoopDetector`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolLoop {
+    /// The repeated calls, written as `name(arguments)`.
+    pub cycle: Vec<String>,
+    /// How many times in a row the cycle was repeated.
+    pub repeats: usize,
+}
+
+impl fmt::Display for ToolLoop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.cycle.len() == 1 {
+            write!(
+                f,
+                "tool call {} repeated {} times in a row",
+                self.cycle[0], self.repeats
+            )
+        } else {
+            write!(
+                f,
+                "tool calls {} repeated {} times in a row",
+                self.cycle.join(", "),
+                self.repeats
+            )
+        }
+    }
+}
+
+/// Tracks the tool calls of a run and detects loops among them.
+#[derive(Debug)]
+pub struct LoopDetector {
+    max_repeats: usize,
+    max_cycle_len: usize,
+    calls: Vec<String>,
+}
+
+impl LoopDetector {
+    /// Creates a detector with the limits of `config`.
+    pub fn new(config: &LoopGuardConfig) -> Self {
+        Self {
+            max_repeats: config.max_repeats.max(2),
+            max_cycle_len: config.max_cycle_len.max(1),
+            calls: Vec::new(),
+        }
+    }
+
+    /// Records a call of tool `name` with `arguments`, returning the loop
+    /// the call completes, if any.
+    ///
+    /// Arguments are compared as JSON values, so formatting differences do
+    /// not hide a repeated call.
+    pub fn record(&mut self, name: &str, arguments: &str) -> Option<ToolLoop> {
+        let arguments = serde_json::from_str::<serde_json::Value>(arguments)
+            .map(|value| value.to_string())
+            .unwrap_or_else(|_| arguments.to_string());
+        self.calls.push(format!("{}({})", name, arguments));
+        self.detect()
+    }
+
+    /// Forgets the recorded calls, e.g. after intervening in a loop.
+    pub fn reset(&mut self) {
+        self.calls.clear();
+    }
+
+    /// Finds the shortest cycle the latest calls repeat `max_repeats` times.
+    fn detect(&self) -> Option<ToolLoop> {
+        (1..=self.max_cycle_len).find_map(|len| {
+            let span = len * self.max_repeats;
+            let tail = self.calls.get(self.calls.len().checked_sub(span)?..)?;
+            let repeated = tail
+                .iter()
+                .enumerate()
+                .all(|(i, call)| *call == tail[i % len]);
+            repeated.then(|| ToolLoop {
+                cycle: tail[..len].to_vec(),
+                repeats: self.max_repeats,
+            })
+        })
+    }
+}
+
+/// Returns the message telling the model it is stuck in `tool_loop`.
+pub(crate) fn nudge_prompt(tool_loop: &ToolLoop) -> String {
+    format!(
+        "You are repeating yourself: {}, without making progress. Do not repeat these calls. \
+         Try a different approach, or give your final answer if you cannot make progress.",
+        tool_loop
+    )
+}
+
+/// Returns the message asking the model to answer without tools after
+/// `tool_loop`.
+pub(crate) fn force_answer_prompt(tool_loop: &ToolLoop) -> String {
+    format!(
+        "You are stuck in a loop: {}. Tools are no longer available. Give your final answer \
+         now with the information you have.",
+        tool_loop
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector() -> LoopDetector {
+        LoopDetector::new(&LoopGuardConfig::default())
+    }
+
+    #[test]
+    fn test_identical_calls_detected() {
+        let mut detector = detector();
+        assert!(detector.record("read", r#"{"path": "a"}"#).is_none());
+        assert!(detector.record("read", r#"{"path":"a"}"#).is_none());
+
+        let tool_loop = detector.record("read", r#"{ "path": "a" }"#).unwrap();
+        assert_eq!(tool_loop.cycle, vec![r#"read({"path":"a"})"#]);
+        assert_eq!(tool_loop.repeats, 3);
+    }
+
+    #[test]
+    fn test_different_arguments_are_not_a_loop() {
+        let mut detector = detector();
+        for page in 0..10 {
+            let arguments = format!(r#"{{"page": {}}}"#, page);
+            assert!(detector.record("list", &arguments).is_none());
+        }
+    }
+
+    #[test]
+    fn test_oscillation_detected() {
+        let mut detector = detector();
+        let calls = [("open", "{}"), ("close", "{}")];
+        let mut detected = None;
+        for (name, arguments) in calls.iter().cycle().take(6) {
+            detected = detector.record(name, arguments);
+            if detected.is_some() {
+                break;
+            }
+        }
+
+        let tool_loop = detected.unwrap();
+        assert_eq!(tool_loop.cycle, vec!["open({})", "close({})"]);
+        assert_eq!(detector.calls.len(), 6);
+        assert!(tool_loop.to_string().contains("open({}), close({})"));
+    }
+
+    #[test]
+    fn test_cycles_longer_than_limit_ignored() {
+        let config = LoopGuardConfig {
+            max_cycle_len: 2,
+            ..Default::default()
+        };
+        let mut detector = LoopDetector::new(&config);
+        for name in ["a", "b", "c"].iter().cycle().take(9) {
+            assert!(detector.record(name, "{}").is_none());
+        }
+    }
+
+    #[test]
+    fn test_reset_forgets_calls() {
+        let mut detector = detector();
+        detector.record("read", "{}");
+        detector.record("read", "{}");
+        detector.reset();
+        assert!(detector.record("read", "{}").is_none());
+    }
+}
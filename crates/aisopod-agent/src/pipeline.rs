@@ -9,14 +9,24 @@ use std::sync::Arc;
 use anyhow::Result;
 use futures_util::StreamExt;
 use tokio::sync::mpsc;
+use tracing::Instrument;
 
 use crate::abort::AbortHandle;
+use crate::budget::{self, BudgetExceeded, BudgetNotifier};
+use crate::handoff::{Handoff, HandoffRegistry};
+use crate::loop_guard::{self, LoopDetector};
+use crate::draft_verify::{self, Review};
 use crate::resolution::{
-    resolve_agent_config, resolve_agent_model, resolve_session_agent_id, ModelChain,
+    resolve_agent_config, resolve_agent_model, resolve_model_chain, resolve_session_agent_id,
+    resolve_tool_context, ModelChain,
 };
 use crate::skills_integration::SkillRegistry;
+use crate::steering::SteeredRun;
+use crate::streaming::{self, ReplySink, StreamingReplyConfig};
+use crate::structured_output::OutputValidator;
 use crate::types::{AgentEvent, AgentRunParams, AgentRunResult, ToolCallRecord, UsageReport};
 use crate::{failover, prompt, transcript, usage};
+use aisopod_config::types::{DraftVerifyConfig, LoopGuardAction, VerifyMode};
 use aisopod_provider::ToolDefinition;
 
 /// A stream of agent events from an agent run.
@@ -42,6 +52,19 @@ impl AgentRunStream {
     pub fn into_receiver(self) -> mpsc::Receiver<AgentEvent> {
         self.receiver
     }
+
+    /// Consumes the stream, flushing the reply text to `sink` as it arrives.
+    ///
+    /// The reply is edited in place if the sink supports it and sent in
+    /// chunks otherwise; see [`crate::streaming`]. Returns the final result
+    /// of the run, or `None` if the run ended without completing.
+    pub async fn stream_to(
+        self,
+        sink: &dyn ReplySink,
+        config: &StreamingReplyConfig,
+    ) -> Result<Option<AgentRunResult>> {
+        streaming::stream_reply(self.receiver, sink, config).await
+    }
 }
 
 /// The execution pipeline for running an agent.
@@ -70,6 +93,12 @@ pub struct AgentPipeline {
     memory_manager: Option<Arc<aisopod_memory::MemoryManager>>,
     /// Skill registry for resolving and managing skills assigned to agents
     skills: Option<Arc<SkillRegistry>>,
+    /// Optional handoff registry overriding the agent bound to a session
+    handoffs: Option<Arc<HandoffRegistry>>,
+    /// Optional notifier told about runs refused by an exceeded budget
+    budget_notifier: Option<Arc<dyn BudgetNotifier>>,
+    /// Optional steered run receiving the follow-ups of the executed run
+    steered_run: Option<Arc<SteeredRun>>,
 }
 
 impl AgentPipeline {
@@ -90,6 +119,9 @@ impl AgentPipeline {
             memory_pipeline: None,
             memory_manager: None,
             skills: None,
+            handoffs: None,
+            budget_notifier: None,
+            steered_run: None,
         }
     }
 
@@ -111,6 +143,9 @@ impl AgentPipeline {
             memory_pipeline: None,
             memory_manager: None,
             skills: None,
+            handoffs: None,
+            budget_notifier: None,
+            steered_run: None,
         }
     }
 
@@ -133,6 +168,9 @@ impl AgentPipeline {
             memory_pipeline: None,
             memory_manager: None,
             skills: None,
+            handoffs: None,
+            budget_notifier: None,
+            steered_run: None,
         }
     }
 
@@ -155,6 +193,9 @@ impl AgentPipeline {
             memory_pipeline: Some(memory_pipeline),
             memory_manager: Some(memory_manager),
             skills: None,
+            handoffs: None,
+            budget_notifier: None,
+            steered_run: None,
         }
     }
 
@@ -178,6 +219,9 @@ impl AgentPipeline {
             memory_pipeline: Some(memory_pipeline),
             memory_manager: Some(memory_manager),
             skills: None,
+            handoffs: None,
+            budget_notifier: None,
+            steered_run: None,
         }
     }
 
@@ -202,6 +246,9 @@ impl AgentPipeline {
             memory_pipeline: Some(memory_pipeline),
             memory_manager: Some(memory_manager),
             skills: None,
+            handoffs: None,
+            budget_notifier: None,
+            steered_run: None,
         }
     }
 
@@ -223,6 +270,9 @@ impl AgentPipeline {
             memory_pipeline: None,
             memory_manager: None,
             skills: Some(skills),
+            handoffs: None,
+            budget_notifier: None,
+            steered_run: None,
         }
     }
 
@@ -245,6 +295,9 @@ impl AgentPipeline {
             memory_pipeline: None,
             memory_manager: None,
             skills: Some(skills),
+            handoffs: None,
+            budget_notifier: None,
+            steered_run: None,
         }
     }
 
@@ -268,6 +321,9 @@ impl AgentPipeline {
             memory_pipeline: Some(memory_pipeline),
             memory_manager: Some(memory_manager),
             skills: Some(skills),
+            handoffs: None,
+            budget_notifier: None,
+            steered_run: None,
         }
     }
 
@@ -292,9 +348,42 @@ impl AgentPipeline {
             memory_pipeline: Some(memory_pipeline),
             memory_manager: Some(memory_manager),
             skills: Some(skills),
+            handoffs: None,
+            budget_notifier: None,
+            steered_run: None,
         }
     }
 
+    /// Sets the handoff registry whose handoffs decide which agent handles
+    /// a session.
+    ///
+    /// A handed-off session is run by the receiving agent, with the handoff
+    /// note and context added to its system prompt, and an
+    /// `AgentEvent::Handoff` is emitted when a tool hands the session off
+    /// during a run.
+    pub fn with_handoffs(mut self, handoffs: Arc<HandoffRegistry>) -> Self {
+        self.handoffs = Some(handoffs);
+        self
+    }
+
+    /// Sets the notifier reporting exceeded budgets to operators.
+    ///
+    /// Budgets configured in `session.budget` are enforced whenever the
+    /// pipeline has a usage tracker; the notifier only adds the reports.
+    pub fn with_budget_notifier(mut self, notifier: Arc<dyn BudgetNotifier>) -> Self {
+        self.budget_notifier = Some(notifier);
+        self
+    }
+
+    /// Sets the steered run the executed run belongs to.
+    ///
+    /// The run is cancelled through the steered run's abort handle, and
+    /// answers the follow-ups injected into it before completing.
+    pub fn with_steered_run(mut self, run: Arc<SteeredRun>) -> Self {
+        self.steered_run = Some(run);
+        self
+    }
+
     /// Returns true if usage tracking is enabled.
     pub fn has_usage_tracker(&self) -> bool {
         self.usage_tracker.is_some()
@@ -315,6 +404,16 @@ impl AgentPipeline {
         self.skills.as_ref()
     }
 
+    /// Gets the handoff registry if enabled.
+    pub fn handoffs(&self) -> Option<&Arc<HandoffRegistry>> {
+        self.handoffs.as_ref()
+    }
+
+    /// Gets the budget notifier if enabled.
+    pub fn budget_notifier(&self) -> Option<&Arc<dyn BudgetNotifier>> {
+        self.budget_notifier.as_ref()
+    }
+
     /// Returns true if memory integration is enabled.
     pub fn has_memory(&self) -> bool {
         self.memory_pipeline.is_some() && self.memory_manager.is_some()
@@ -376,14 +475,18 @@ impl AgentPipeline {
         params: &AgentRunParams,
         event_tx: &mpsc::Sender<AgentEvent>,
     ) -> Result<AgentRunResult> {
-        // 1. Resolve agent ID
-        let agent_id = resolve_session_agent_id(&self.config, &params.session_key)?;
+        // 1. Resolve agent ID, preferring the agent the session was handed off to
+        let (agent_id, handoff) = self.resolve_agent(params)?;
 
         // 2. Resolve agent config
         let agent_config = resolve_agent_config(&self.config, &agent_id)?;
 
-        // 3. Resolve model chain (primary + fallbacks)
-        let model_chain = resolve_agent_model(&self.config, &agent_id)?;
+        // 3. Resolve model chain (primary + fallbacks), running the loop of
+        // agents in draft-and-verify mode on their draft model
+        let model_chain = match &agent_config.draft_verify {
+            Some(draft_verify) => resolve_model_chain(&self.config, &draft_verify.draft_model),
+            None => resolve_agent_model(&self.config, &agent_id)?,
+        };
 
         // 4. Prepare tool schemas for the agent - convert to ToolDefinition
         let mut tool_definitions: Vec<ToolDefinition> = self
@@ -419,7 +522,21 @@ impl AgentPipeline {
 
         // 6. Merge skill prompts into system prompt
         let system_prompt = {
-            let base_prompt = self.build_system_prompt(&agent_config, &tool_definitions);
+            let mut base_prompt = self.build_system_prompt(
+                &agent_config,
+                &tool_definitions,
+                &skills,
+                model_chain.primary(),
+                params,
+            )?;
+            if let Some(handoff) = &handoff {
+                base_prompt.push_str("\n\n");
+                base_prompt.push_str(&handoff.prompt_section());
+            }
+            if let Some(schema) = &agent_config.output_schema {
+                base_prompt.push_str("\n\n");
+                base_prompt.push_str(&crate::structured_output::schema_instructions(schema));
+            }
             let merged = crate::skills_integration::merge_skill_prompts(&base_prompt, &skills);
             if self.has_memory() {
                 // Use default memory config for now - could be made configurable
@@ -442,7 +559,9 @@ impl AgentPipeline {
         let messages = transcript::repair_transcript(&params.messages, provider_kind);
 
         // 7. Register abort handle if registry is available
-        let abort_handle = if let Some(ref registry) = self.abort_registry {
+        let abort_handle = if let Some(ref run) = self.steered_run {
+            Some(run.abort_handle().clone())
+        } else if let Some(ref registry) = self.abort_registry {
             let handle = AbortHandle::new(params.session_key.clone());
             registry.insert(&params.session_key, handle.clone());
             Some(handle)
@@ -480,24 +599,91 @@ impl AgentPipeline {
         }
 
         // Clean up abort handle if we created one
-        if let (Some(ref registry), Some(ref handle)) =
-            (self.abort_registry.as_ref(), abort_handle.as_ref())
-        {
+        if let (None, Some(registry), Some(handle)) = (
+            self.steered_run.as_ref(),
+            self.abort_registry.as_ref(),
+            abort_handle.as_ref(),
+        ) {
             registry.remove(handle.session_key());
         }
 
         result
     }
 
+    /// Resolves the agent running `params`, along with the handoff that
+    /// bound the session to it, if any.
+    pub(crate) fn resolve_agent(
+        &self,
+        params: &AgentRunParams,
+    ) -> Result<(String, Option<Handoff>)> {
+        let handoff = self
+            .handoffs
+            .as_ref()
+            .and_then(|handoffs| handoffs.get(&params.session_key));
+        let agent_id = match &handoff {
+            Some(handoff) => handoff.to_agent.clone(),
+            None => match params.agent_id.as_ref().filter(|_| params.pin_agent) {
+                Some(agent_id) => agent_id.clone(),
+                None => resolve_session_agent_id(&self.config, &params.session_key)?,
+            },
+        };
+        Ok((agent_id, handoff))
+    }
+
+    /// Executes the pipeline, streaming the reply to `sink` while it is written.
+    ///
+    /// Partial output is flushed to the sink as described in
+    /// [`AgentRunStream::stream_to`]. Fails if the run fails or the reply
+    /// cannot be delivered.
+    pub async fn execute_streaming(
+        &self,
+        params: &AgentRunParams,
+        sink: &dyn ReplySink,
+        config: &StreamingReplyConfig,
+    ) -> Result<AgentRunResult> {
+        let (event_tx, event_rx) = mpsc::channel(64);
+        let run = async move {
+            // The stream ends once the sender is dropped with the run
+            self.execute(params, &event_tx).await
+        };
+        let (result, delivered) =
+            tokio::join!(run, AgentRunStream::new(event_rx).stream_to(sink, config));
+        let result = result?;
+        delivered?;
+        Ok(result)
+    }
+
     /// Builds the system prompt from agent config and tool schemas.
     /// This method does NOT merge skill prompts - use execute() for that integration.
+    ///
+    /// The base prompt is the agent's `prompt_template`, looked up in the
+    /// configured templates and then those of the agent's skills, or its
+    /// `system_prompt`, rendered with the run's template variables. The
+    /// agent's `prompt_sections` override the built sections.
     fn build_system_prompt(
         &self,
         agent_config: &aisopod_config::types::Agent,
         tool_definitions: &[ToolDefinition],
-    ) -> String {
+        skills: &[Arc<dyn crate::skills_integration::Skill>],
+        model: &str,
+        params: &AgentRunParams,
+    ) -> Result<String> {
+        let template = match &agent_config.prompt_template {
+            Some(name) => match self.config.agents.prompt_templates.get(name) {
+                Some(template) => template.clone(),
+                None => crate::skills_integration::collect_skill_templates(skills)
+                    .remove(name)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown prompt template '{}'", name))?,
+            },
+            None => agent_config.system_prompt.clone(),
+        };
+
+        let mut variables = prompt::builtin_variables(agent_config, model, &params.session_key);
+        variables.extend(params.prompt_variables.clone());
+
         let builder = prompt::SystemPromptBuilder::new()
-            .with_base_prompt(&agent_config.system_prompt)
+            .with_variables(variables)
+            .with_template(&template)
             .with_dynamic_context();
 
         // Convert ToolDefinition to ToolSchema type for the builder
@@ -512,7 +698,10 @@ impl AgentPipeline {
             })
             .collect();
 
-        builder.with_tool_descriptions(&schemas).build()
+        Ok(builder
+            .with_tool_descriptions(&schemas)
+            .with_section_overrides(&agent_config.prompt_sections)
+            .build())
     }
 
     /// Determines the provider kind from the model chain.
@@ -544,10 +733,45 @@ impl AgentPipeline {
         abort_handle: Option<&AbortHandle>,
     ) -> Result<AgentRunResult> {
         // Create failover state for tracking model attempts
-        let mut failover_state = failover::FailoverState::new(model_chain);
+        let mut failover_state =
+            failover::FailoverState::with_policy(model_chain, self.config.models.failover.clone());
         let mut total_usage = UsageReport::new(0, 0);
         let mut tool_calls: Vec<ToolCallRecord> = Vec::new();
         let usage_tracker = self.usage_tracker.clone();
+        let agent_config = resolve_agent_config(&self.config, agent_id).ok();
+        let show_reasoning = agent_config
+            .as_ref()
+            .map(|agent_config| agent_config.show_reasoning)
+            .unwrap_or(false);
+        let output_validator = agent_config
+            .as_ref()
+            .and_then(|agent_config| agent_config.output_schema.as_ref())
+            .map(|schema| {
+                OutputValidator::new(schema).map_err(|e| {
+                    anyhow::anyhow!("Invalid output schema for agent '{}': {}", agent_id, e)
+                })
+            })
+            .transpose()?;
+        let max_output_repairs = agent_config
+            .as_ref()
+            .map(|agent_config| agent_config.max_output_repairs)
+            .unwrap_or(0);
+        let mut output_repairs = 0;
+        let draft_verify = agent_config
+            .as_ref()
+            .and_then(|agent_config| agent_config.draft_verify.clone());
+        let mut draft_revisions = 0;
+        // Drafts awaiting review and responses awaiting validation are
+        // only sent once accepted, whole
+        let hold_back_text = draft_verify.is_some() || output_validator.is_some();
+        let loop_guard = &self.config.tools.loop_guard;
+        let mut loop_detector = loop_guard.enabled.then(|| LoopDetector::new(loop_guard));
+        let mut loop_nudged = false;
+        let mut tools_withheld = false;
+        let mut last_handoff = self
+            .handoffs
+            .as_ref()
+            .and_then(|handoffs| handoffs.get(&params.session_key));
 
         loop {
             // Check for cancellation before each iteration
@@ -565,20 +789,34 @@ impl AgentPipeline {
                 }
             }
 
+            // Answer follow-ups the user sent since the last model call
+            if let Some(run) = &self.steered_run {
+                self.push_follow_ups(run.take_follow_ups(), &mut messages, event_tx)
+                    .await;
+            }
+
+            // Stop calling the model once a session or peer budget is spent
+            if let Some(exceeded) = self.exceeded_budget(agent_id, &params.session_key) {
+                return self
+                    .refuse_over_budget(exceeded, tool_calls, total_usage, event_tx)
+                    .await;
+            }
+
             // Get the current model ID for the request
             let current_model = failover_state.current_model().to_string();
 
             // Get the current provider and model
-            let (provider, _) = self
+            let (provider, model_id) = self
                 .providers
                 .resolve_model(&current_model)
                 .ok_or_else(|| anyhow::anyhow!("Model not found: {}", current_model))?;
+            let provider_id = provider.id().to_string();
 
             // Build the request
             let request = aisopod_provider::ChatCompletionRequest {
                 model: current_model.clone(),
                 messages: messages.clone(),
-                tools: if tool_definitions.is_empty() {
+                tools: if tool_definitions.is_empty() || tools_withheld {
                     None
                 } else {
                     Some(tool_definitions.to_vec())
@@ -590,6 +828,15 @@ impl AgentPipeline {
             };
             let request_clone = request.clone();
 
+            // Spans the model call until its response is fully streamed
+            let call_span = tracing::info_span!(
+                "provider.chat_completion",
+                provider = %provider_id,
+                model = %model_id,
+                input_tokens = tracing::field::Empty,
+                output_tokens = tracing::field::Empty,
+            );
+
             // Call model with failover support and cancellation check
             let response_stream = {
                 // Create a future for the model call
@@ -606,6 +853,13 @@ impl AgentPipeline {
                                 ..request.clone()
                             };
                             provider.chat_completion(request).await.map_err(|e| {
+                                // Keep normalized errors so the failover policy sees their class
+                                let e = match e.downcast::<
+                                    aisopod_provider::normalize::ProviderError,
+                                >() {
+                                    Ok(provider_error) => return provider_error,
+                                    Err(e) => e,
+                                };
                                 // Convert anyhow::Error to ProviderError
                                 // Extract provider from current_model (format: "provider/model")
                                 let provider_name = current_model_clone
@@ -620,7 +874,8 @@ impl AgentPipeline {
                             })
                         }
                     },
-                );
+                )
+                .instrument(call_span.clone());
 
                 // Use tokio::select! to check for cancellation
                 if let Some(handle) = abort_handle {
@@ -640,8 +895,8 @@ impl AgentPipeline {
 
             // Process streaming response and collect per-request usage with cancellation check
             let mut response_text = String::new();
-            let mut response_tool_calls: Vec<aisopod_provider::ToolCall> = Vec::new();
-            let mut request_usage: Option<UsageReport> = None;
+            let mut response_tool_calls = aisopod_provider::ToolCallAccumulator::new();
+            let mut token_usage: Option<aisopod_provider::TokenUsage> = None;
 
             let mut stream = response_stream;
             while let Some(chunk) = stream.next().await {
@@ -662,41 +917,61 @@ impl AgentPipeline {
 
                 let chunk = chunk?;
 
-                // Emit text delta events
+                // Emit text delta events, unless the response is held back
                 if let Some(ref content) = chunk.delta.content {
-                    let _ = event_tx
-                        .send(AgentEvent::TextDelta {
-                            text: content.clone(),
-                            index: None, // TODO: track message index
-                        })
-                        .await;
+                    if !hold_back_text {
+                        let _ = event_tx
+                            .send(AgentEvent::TextDelta {
+                                text: content.clone(),
+                                index: None, // TODO: track message index
+                            })
+                            .await;
+                    }
                     response_text.push_str(content);
                 }
 
-                // Collect tool calls
-                if let Some(ref tool_calls_chunk) = chunk.delta.tool_calls {
-                    response_tool_calls.extend(tool_calls_chunk.clone());
+                // Reasoning is never part of the response text
+                if let Some(ref reasoning) = chunk.delta.reasoning {
+                    if show_reasoning {
+                        let _ = event_tx
+                            .send(AgentEvent::ReasoningDelta {
+                                text: reasoning.clone(),
+                            })
+                            .await;
+                    } else {
+                        tracing::debug!("Model reasoning for agent {}: {}", agent_id, reasoning);
+                    }
+                }
+
+                // Collect tool calls, joining calls streamed in fragments
+                for tool_call in chunk.delta.tool_calls.iter().flatten() {
+                    response_tool_calls.push_call(tool_call);
                 }
 
                 // Aggregate usage for this request
                 if let Some(ref u) = chunk.usage {
-                    request_usage = Some(UsageReport::new(
-                        u.prompt_tokens as u64,
-                        u.completion_tokens as u64,
-                    ));
+                    token_usage = Some(u.clone());
                 }
             }
-
-            // Record usage to tracker if available
-            if let Some(ref tracker) = usage_tracker {
+            if let Some(ref u) = token_usage {
+                call_span.record("input_tokens", u.prompt_tokens);
+                call_span.record("output_tokens", u.completion_tokens);
+            }
+            drop(call_span);
+
+            // Record usage to tracker if available, which also prices it
+            let request_usage = token_usage.map(|u| match usage_tracker {
+                Some(ref tracker) => tracker.record_model_request(
+                    &params.session_key,
+                    agent_id,
+                    &provider_id,
+                    &model_id,
+                    &u,
+                ),
+                None => UsageReport::new(u.prompt_tokens as u64, u.completion_tokens as u64),
+            });
+            if usage_tracker.is_some() {
                 if let Some(ref req_usage) = request_usage {
-                    tracker.record_request(
-                        &params.session_key,
-                        agent_id,
-                        req_usage.input_tokens,
-                        req_usage.output_tokens,
-                    );
-
                     // Emit AgentEvent::Usage after each model call
                     let _ = event_tx
                         .send(AgentEvent::Usage {
@@ -709,13 +984,131 @@ impl AgentPipeline {
             // Update total usage
             if let Some(ref req_usage) = request_usage {
                 total_usage.add(req_usage.input_tokens, req_usage.output_tokens);
+                total_usage.add_cost(req_usage.cost_usd);
             }
 
             // Check if there are tool calls
+            let response_tool_calls = response_tool_calls.finish();
             if response_tool_calls.is_empty() {
-                // No tool calls - we're done
-                let result =
-                    AgentRunResult::new(response_text.clone(), tool_calls.clone(), total_usage);
+                // Have the verify model review the draft, unless it was
+                // already revised as often as allowed
+                let review = match &draft_verify {
+                    Some(config)
+                        if config.mode == VerifyMode::Edit
+                            || draft_revisions < config.max_revisions =>
+                    {
+                        self.review_draft(
+                            config,
+                            &messages,
+                            &response_text,
+                            agent_id,
+                            &params.session_key,
+                            &mut total_usage,
+                        )
+                        .await
+                    }
+                    _ => Review::Approved,
+                };
+                if let Review::Revised(revision) = review {
+                    let mode = draft_verify.as_ref().map(|config| config.mode);
+                    if mode == Some(VerifyMode::Critique) {
+                        draft_revisions += 1;
+                        tracing::debug!(
+                            "Draft of agent {} was critiqued, revision {}",
+                            agent_id,
+                            draft_revisions
+                        );
+                        messages.push(aisopod_provider::Message {
+                            role: aisopod_provider::Role::Assistant,
+                            content: aisopod_provider::MessageContent::Text(response_text),
+                            tool_calls: None,
+                            tool_call_id: None,
+                        });
+                        messages.push(aisopod_provider::Message {
+                            role: aisopod_provider::Role::User,
+                            content: aisopod_provider::MessageContent::Text(
+                                draft_verify::revision_prompt(&revision),
+                            ),
+                            tool_calls: None,
+                            tool_call_id: None,
+                        });
+                        continue;
+                    }
+                    response_text = revision;
+                }
+
+                // No tool calls - we're done, once the response matches the output schema
+                let mut result = AgentRunResult::new(
+                    response_text.clone(),
+                    tool_calls.clone(),
+                    total_usage.clone(),
+                );
+                if let Some(validator) = &output_validator {
+                    match validator.validate(&response_text) {
+                        Ok(output) => result = result.with_structured_output(output),
+                        Err(errors) if output_repairs < max_output_repairs => {
+                            output_repairs += 1;
+                            tracing::debug!(
+                                "Response of agent {} does not match its output schema, repair attempt {}",
+                                agent_id,
+                                output_repairs
+                            );
+                            messages.push(aisopod_provider::Message {
+                                role: aisopod_provider::Role::Assistant,
+                                content: aisopod_provider::MessageContent::Text(response_text),
+                                tool_calls: None,
+                                tool_call_id: None,
+                            });
+                            messages.push(aisopod_provider::Message {
+                                role: aisopod_provider::Role::User,
+                                content: aisopod_provider::MessageContent::Text(
+                                    crate::structured_output::repair_prompt(&errors),
+                                ),
+                                tool_calls: None,
+                                tool_call_id: None,
+                            });
+                            continue;
+                        }
+                        Err(errors) => {
+                            let message = format!(
+                                "Response does not match the output schema after {} repair attempts: {}",
+                                output_repairs,
+                                errors.join("; ")
+                            );
+                            let _ = event_tx
+                                .send(AgentEvent::Error {
+                                    message: message.clone(),
+                                })
+                                .await;
+                            return Err(anyhow::anyhow!(message));
+                        }
+                    }
+                }
+                // Follow-ups sent while the response was written are
+                // answered before completing
+                if let Some(run) = &self.steered_run {
+                    let follow_ups = run.complete();
+                    if !follow_ups.is_empty() {
+                        messages.push(aisopod_provider::Message {
+                            role: aisopod_provider::Role::Assistant,
+                            content: aisopod_provider::MessageContent::Text(response_text),
+                            tool_calls: None,
+                            tool_call_id: None,
+                        });
+                        self.push_follow_ups(follow_ups, &mut messages, event_tx)
+                            .await;
+                        continue;
+                    }
+                }
+                // Reviewed drafts and validated responses are sent whole
+                if hold_back_text {
+                    let _ = event_tx
+                        .send(AgentEvent::TextDelta {
+                            text: result.response.clone(),
+                            index: None,
+                        })
+                        .await;
+                }
                 let _ = event_tx
                     .send(AgentEvent::Complete {
                         result: result.clone(),
@@ -724,6 +1117,66 @@ impl AgentPipeline {
                 return Ok(result);
             }
 
+            // Intervene instead of running calls that repeat in a loop
+            let tool_loop = loop_detector.as_mut().and_then(|detector| {
+                response_tool_calls
+                    .iter()
+                    .filter_map(|call| detector.record(&call.name, &call.arguments))
+                    .last()
+            });
+            if let Some(tool_loop) = tool_loop {
+                // Loops persisting after an intervention escalate to the next one
+                let action = match loop_guard.action {
+                    _ if tools_withheld => LoopGuardAction::Abort,
+                    LoopGuardAction::Nudge if loop_nudged => LoopGuardAction::ForceAnswer,
+                    action => action,
+                };
+                tracing::warn!(
+                    "Agent {} is stuck in a loop ({}), action: {:?}",
+                    agent_id,
+                    tool_loop,
+                    action
+                );
+                let prompt = match action {
+                    LoopGuardAction::Nudge => {
+                        // Give the model a fresh chance before escalating
+                        loop_nudged = true;
+                        if let Some(detector) = loop_detector.as_mut() {
+                            detector.reset();
+                        }
+                        loop_guard::nudge_prompt(&tool_loop)
+                    }
+                    LoopGuardAction::ForceAnswer => {
+                        tools_withheld = true;
+                        loop_guard::force_answer_prompt(&tool_loop)
+                    }
+                    LoopGuardAction::Abort => {
+                        let message = format!("Tool call loop detected: {}", tool_loop);
+                        let _ = event_tx
+                            .send(AgentEvent::Error {
+                                message: message.clone(),
+                            })
+                            .await;
+                        return Err(anyhow::anyhow!(message));
+                    }
+                };
+                if !response_text.is_empty() {
+                    messages.push(aisopod_provider::Message {
+                        role: aisopod_provider::Role::Assistant,
+                        content: aisopod_provider::MessageContent::Text(response_text),
+                        tool_calls: None,
+                        tool_call_id: None,
+                    });
+                }
+                messages.push(aisopod_provider::Message {
+                    role: aisopod_provider::Role::User,
+                    content: aisopod_provider::MessageContent::Text(prompt),
+                    tool_calls: None,
+                    tool_call_id: None,
+                });
+                continue;
+            }
+
             // Process tool calls
             for tool_call in response_tool_calls {
                 // Add tool call to the result
@@ -740,9 +1193,9 @@ impl AgentPipeline {
                     })
                     .await;
 
-                // Execute the tool
+                // Execute the tool, forwarding its output as progress updates
                 let tool_result = self
-                    .execute_tool(&tool_call, agent_id, &params.session_key)
+                    .execute_tool(&tool_call, agent_id, &params.session_key, event_tx)
                     .await?;
 
                 let result_content = tool_result.content.clone();
@@ -754,6 +1207,23 @@ impl AgentPipeline {
                     })
                     .await;
 
+                // Tell the user when the tool handed the session to another agent
+                if let Some(handoffs) = &self.handoffs {
+                    let handoff = handoffs.get(&params.session_key);
+                    if handoff != last_handoff {
+                        if let Some(handoff) = &handoff {
+                            let _ = event_tx
+                                .send(AgentEvent::Handoff {
+                                    from_agent: handoff.from_agent.clone(),
+                                    to_agent: handoff.to_agent.clone(),
+                                    note: handoff.note.clone(),
+                                })
+                                .await;
+                        }
+                        last_handoff = handoff;
+                    }
+                }
+
                 // Add tool result to messages
                 messages.push(aisopod_provider::Message {
                     role: aisopod_provider::Role::Assistant,
@@ -778,24 +1248,197 @@ impl AgentPipeline {
         }
     }
 
+    /// Returns the budget the session has exceeded, if any.
+    ///
+    /// Budgets are only enforced with a usage tracker.
+    fn exceeded_budget(&self, agent_id: &str, session_key: &str) -> Option<BudgetExceeded> {
+        let tracker = self.usage_tracker.as_ref()?;
+        budget::check_budget(&self.config.session.budget, tracker, session_key, agent_id)
+    }
+
+    /// Completes a run refused by an exceeded budget with the configured
+    /// reply, and reports the budget to the notifier.
+    async fn refuse_over_budget(
+        &self,
+        exceeded: BudgetExceeded,
+        tool_calls: Vec<ToolCallRecord>,
+        usage: UsageReport,
+        event_tx: &mpsc::Sender<AgentEvent>,
+    ) -> Result<AgentRunResult> {
+        tracing::warn!("{}", exceeded.describe());
+        if let Some(notifier) = &self.budget_notifier {
+            if let Err(e) = notifier.budget_exceeded(&exceeded).await {
+                tracing::warn!("Failed to notify operator of exceeded budget: {}", e);
+            }
+        }
+
+        let reply = self.config.session.budget.exceeded_message.clone();
+        let result = AgentRunResult::new(reply, tool_calls, usage);
+        let _ = event_tx
+            .send(AgentEvent::Complete {
+                result: result.clone(),
+            })
+            .await;
+        Ok(result)
+    }
+
+    /// Asks the verify model to review `draft`, the final response written
+    /// for `messages`, adding the usage of the review to `total_usage`.
+    ///
+    /// A review that fails approves the draft, so an unavailable verify
+    /// model does not fail the run.
+    async fn review_draft(
+        &self,
+        config: &DraftVerifyConfig,
+        messages: &[aisopod_provider::Message],
+        draft: &str,
+        agent_id: &str,
+        session_key: &str,
+        total_usage: &mut UsageReport,
+    ) -> Review {
+        let (provider, model_id) = match self.providers.resolve_model(&config.verify_model) {
+            Some(resolved) => resolved,
+            None => {
+                tracing::warn!("Verify model not found: {}", config.verify_model);
+                return Review::Approved;
+            }
+        };
+
+        let mut messages = messages.to_vec();
+        messages.push(aisopod_provider::Message {
+            role: aisopod_provider::Role::Assistant,
+            content: aisopod_provider::MessageContent::Text(draft.to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+        });
+        messages.push(aisopod_provider::Message {
+            role: aisopod_provider::Role::User,
+            content: aisopod_provider::MessageContent::Text(draft_verify::review_prompt(
+                config.mode,
+            )),
+            tool_calls: None,
+            tool_call_id: None,
+        });
+        let request = aisopod_provider::ChatCompletionRequest {
+            model: config.verify_model.clone(),
+            messages,
+            tools: None,
+            temperature: None,
+            max_tokens: None,
+            stop: None,
+            stream: true,
+        };
+
+        let mut reply = String::new();
+        let mut token_usage = None;
+        let outcome: Result<()> = async {
+            let mut stream = provider.chat_completion(request).await?;
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk?;
+                if let Some(ref content) = chunk.delta.content {
+                    reply.push_str(content);
+                }
+                if let Some(usage) = chunk.usage {
+                    token_usage = Some(usage);
+                }
+            }
+            Ok(())
+        }
+        .await;
+
+        if let Some(usage) = token_usage {
+            let usage = match &self.usage_tracker {
+                Some(tracker) => tracker.record_model_request(
+                    session_key,
+                    agent_id,
+                    provider.id(),
+                    &model_id,
+                    &usage,
+                ),
+                None => {
+                    UsageReport::new(usage.prompt_tokens as u64, usage.completion_tokens as u64)
+                }
+            };
+            total_usage.add(usage.input_tokens, usage.output_tokens);
+            total_usage.add_cost(usage.cost_usd);
+        }
+        if let Err(e) = outcome {
+            tracing::warn!(
+                "Failed to review draft of agent {} with {}: {}",
+                agent_id,
+                config.verify_model,
+                e
+            );
+            return Review::Approved;
+        }
+        draft_verify::parse_review(&reply)
+    }
+
+    /// Appends follow-ups injected into the run to `messages`, announcing
+    /// each as an `AgentEvent::Interruption`.
+    async fn push_follow_ups(
+        &self,
+        follow_ups: Vec<aisopod_provider::Message>,
+        messages: &mut Vec<aisopod_provider::Message>,
+        event_tx: &mpsc::Sender<AgentEvent>,
+    ) {
+        for message in follow_ups {
+            let _ = event_tx
+                .send(AgentEvent::Interruption {
+                    message: message.clone(),
+                })
+                .await;
+            messages.push(message);
+        }
+    }
+
     /// Executes a tool and returns the result.
+    ///
+    /// Output emitted by the tool while it runs is sent as
+    /// `AgentEvent::ToolCallOutput` events.
+    #[tracing::instrument(
+        name = "tool.execute",
+        skip_all,
+        fields(tool = %tool_call.name, call_id = %tool_call.id)
+    )]
     async fn execute_tool(
         &self,
         tool_call: &aisopod_provider::ToolCall,
         agent_id: &str,
         session_key: &str,
+        event_tx: &mpsc::Sender<AgentEvent>,
     ) -> Result<aisopod_tools::ToolResult> {
         let tool_name = &tool_call.name;
         let params: serde_json::Value = serde_json::from_str(&tool_call.arguments)?;
 
-        let tool = self
+        // Tools work in the agent's workspace and sandbox
+        let ctx = resolve_tool_context(&self.config, agent_id, session_key);
+        let (output, mut chunks) = aisopod_tools::ToolOutputSink::channel();
+
+        // The registry applies tool policies and quotas before executing.
+        let execution = self
             .tools
-            .get(tool_name)
-            .ok_or_else(|| anyhow::anyhow!("Tool not found: {}", tool_name))?;
+            .execute_streaming(tool_name, params, &ctx, &output);
+        tokio::pin!(execution);
 
-        let ctx = aisopod_tools::ToolContext::new(agent_id, session_key);
+        let forward = |chunk: aisopod_tools::ToolOutputChunk| AgentEvent::ToolCallOutput {
+            call_id: tool_call.id.clone(),
+            stream: chunk.stream,
+            text: chunk.text,
+        };
+        let result = loop {
+            tokio::select! {
+                result = &mut execution => break result,
+                Some(chunk) = chunks.recv() => {
+                    let _ = event_tx.send(forward(chunk)).await;
+                }
+            }
+        };
+        while let Ok(chunk) = chunks.try_recv() {
+            let _ = event_tx.send(forward(chunk)).await;
+        }
 
-        tool.execute(params, &ctx).await
+        result
     }
 }
 
@@ -0,0 +1,450 @@
+//! Plan-and-execute mode for agent runs.
+//!
+//! Instead of answering in a single tool-calling loop, an agent in planning
+//! mode first asks its model for a numbered step plan, then runs the
+//! pipeline once per step. When a step fails, the model reflects on the
+//! failure and writes a revised plan for the remaining work, until the plan
+//! completes or the iteration budget in [`PlanningConfig`] is spent.
+//!
+//! Progress is reported through `AgentEvent::PlanCreated` and
+//! `AgentEvent::PlanStepProgress`. The events of each step's run are
+//! forwarded as they arrive, except for its `Complete` event; a single
+//! `Complete` event with the combined result ends the run.
+
+use anyhow::{anyhow, Result};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::pipeline::AgentPipeline;
+use crate::resolution::resolve_agent_model;
+use crate::types::{AgentEvent, AgentRunParams, AgentRunResult, UsageReport};
+use aisopod_provider::{ChatCompletionRequest, Message, MessageContent, ModelProvider, Role};
+
+/// Marker a step reply starts with when the step could not be completed.
+const STEP_FAILED_MARKER: &str = "STEP FAILED:";
+
+/// Configuration for plan-and-execute mode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanningConfig {
+    /// Maximum number of plans written for a run, including re-plans after
+    /// failed steps.
+    pub max_iterations: usize,
+    /// Maximum number of steps in a plan; further steps are dropped.
+    pub max_steps: usize,
+}
+
+impl Default for PlanningConfig {
+    fn default() -> Self {
+        Self {
+            max_iterations: 3,
+            max_steps: 8,
+        }
+    }
+}
+
+/// Status of a step in a plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanStepStatus {
+    /// The step has not started yet.
+    Pending,
+    /// The step is being executed.
+    Running,
+    /// The step completed successfully.
+    Completed,
+    /// The step failed.
+    Failed,
+}
+
+/// A step of a plan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanStep {
+    /// Zero-based position of the step in its plan.
+    pub index: usize,
+    /// What the step should accomplish.
+    pub description: String,
+    /// The current status of the step.
+    pub status: PlanStepStatus,
+}
+
+/// A step that finished, kept as context for later steps and re-plans.
+struct StepOutcome {
+    description: String,
+    output: String,
+}
+
+/// Runs `params` in plan-and-execute mode on `pipeline`.
+///
+/// Returns the result of the last step, with the tool calls and usage of
+/// all steps, or an error if no plan completed within the iteration budget.
+pub(crate) async fn execute_with_plan(
+    pipeline: &AgentPipeline,
+    params: &AgentRunParams,
+    config: &PlanningConfig,
+    event_tx: &mpsc::Sender<AgentEvent>,
+) -> Result<AgentRunResult> {
+    let (agent_id, _) = pipeline.resolve_agent(params)?;
+    let model = resolve_agent_model(pipeline.config(), &agent_id)?
+        .primary()
+        .to_string();
+    let (provider, _) = pipeline
+        .providers()
+        .resolve_model(&model)
+        .ok_or_else(|| anyhow!("Model not found: {}", model))?;
+
+    let goal = params
+        .messages
+        .iter()
+        .rev()
+        .find(|m| m.role == Role::User)
+        .map(message_text)
+        .unwrap_or_default();
+
+    let mut completed: Vec<StepOutcome> = Vec::new();
+    let mut tool_calls = Vec::new();
+    let mut usage = UsageReport::default();
+    let mut failure: Option<(String, String)> = None;
+
+    for iteration in 0..config.max_iterations {
+        let prompt = match &failure {
+            None => plan_prompt(&goal),
+            Some((step, error)) => {
+                let reflection = complete(
+                    provider.as_ref(),
+                    &model,
+                    reflect_prompt(&goal, step, error),
+                )
+                .await?;
+                replan_prompt(&goal, &completed, step, error, &reflection)
+            }
+        };
+        let plan_text = complete(provider.as_ref(), &model, prompt).await?;
+        let mut steps = parse_plan(&plan_text, config.max_steps);
+        if steps.is_empty() {
+            // Nothing left to do according to the revised plan
+            if let Some(last) = completed.last() {
+                return Ok(finish(&last.output, tool_calls, usage, event_tx).await);
+            }
+            steps = vec![plan_step(0, goal.clone())];
+        }
+        let _ = event_tx
+            .send(AgentEvent::PlanCreated {
+                iteration,
+                steps: steps.clone(),
+            })
+            .await;
+
+        failure = None;
+        let mut last_output = String::new();
+        for step in &steps {
+            progress(event_tx, step.index, PlanStepStatus::Running, None).await;
+
+            let step_params = AgentRunParams {
+                messages: step_messages(&params.messages, &steps, &completed, step),
+                ..params.clone()
+            };
+            let outcome = execute_step(pipeline, &step_params, event_tx).await;
+            let error = match outcome {
+                Ok(result) => {
+                    tool_calls.extend(result.tool_calls);
+                    usage.add(result.usage.input_tokens, result.usage.output_tokens);
+                    usage.add_cost(result.usage.cost_usd);
+                    match result.response.trim().strip_prefix(STEP_FAILED_MARKER) {
+                        Some(reason) => reason.trim().to_string(),
+                        None => {
+                            progress(
+                                event_tx,
+                                step.index,
+                                PlanStepStatus::Completed,
+                                Some(result.response.clone()),
+                            )
+                            .await;
+                            last_output = result.response.clone();
+                            completed.push(StepOutcome {
+                                description: step.description.clone(),
+                                output: result.response,
+                            });
+                            continue;
+                        }
+                    }
+                }
+                Err(e) => e.to_string(),
+            };
+
+            progress(
+                event_tx,
+                step.index,
+                PlanStepStatus::Failed,
+                Some(error.clone()),
+            )
+            .await;
+            failure = Some((step.description.clone(), error));
+            break;
+        }
+
+        if failure.is_none() {
+            return Ok(finish(&last_output, tool_calls, usage, event_tx).await);
+        }
+    }
+
+    let (step, error) = failure.unwrap_or_default();
+    Err(anyhow!(
+        "Plan did not complete within {} iterations; step '{}' failed: {}",
+        config.max_iterations,
+        step,
+        error
+    ))
+}
+
+/// Runs one step on the pipeline, forwarding all events but `Complete`.
+async fn execute_step(
+    pipeline: &AgentPipeline,
+    params: &AgentRunParams,
+    event_tx: &mpsc::Sender<AgentEvent>,
+) -> Result<AgentRunResult> {
+    let (step_tx, mut step_rx) = mpsc::channel(64);
+    let run = async move {
+        // The forwarding loop ends once the sender is dropped with the run
+        pipeline.execute(params, &step_tx).await
+    };
+    let forward = async {
+        while let Some(event) = step_rx.recv().await {
+            if !matches!(event, AgentEvent::Complete { .. }) {
+                let _ = event_tx.send(event).await;
+            }
+        }
+    };
+    let (result, _) = tokio::join!(run, forward);
+    result
+}
+
+/// Sends the final `Complete` event of a planned run and returns its result.
+async fn finish(
+    response: &str,
+    tool_calls: Vec<crate::types::ToolCallRecord>,
+    usage: UsageReport,
+    event_tx: &mpsc::Sender<AgentEvent>,
+) -> AgentRunResult {
+    let result = AgentRunResult::new(response, tool_calls, usage);
+    let _ = event_tx
+        .send(AgentEvent::Complete {
+            result: result.clone(),
+        })
+        .await;
+    result
+}
+
+async fn progress(
+    event_tx: &mpsc::Sender<AgentEvent>,
+    index: usize,
+    status: PlanStepStatus,
+    output: Option<String>,
+) {
+    let _ = event_tx
+        .send(AgentEvent::PlanStepProgress {
+            index,
+            status,
+            output,
+        })
+        .await;
+}
+
+fn plan_step(index: usize, description: String) -> PlanStep {
+    PlanStep {
+        index,
+        description,
+        status: PlanStepStatus::Pending,
+    }
+}
+
+/// Parses a numbered list such as `1. Do this` into plan steps.
+///
+/// Lines that are not numbered are ignored, and at most `max_steps` steps
+/// are kept.
+pub(crate) fn parse_plan(text: &str, max_steps: usize) -> Vec<PlanStep> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+            if digits == 0 {
+                return None;
+            }
+            let rest = line[digits..].strip_prefix(['.', ')'])?.trim();
+            (!rest.is_empty()).then(|| rest.to_string())
+        })
+        .take(max_steps)
+        .enumerate()
+        .map(|(index, description)| plan_step(index, description))
+        .collect()
+}
+
+fn plan_prompt(goal: &str) -> String {
+    format!(
+        "Break the following task into a short numbered list of concrete steps, \
+         one per line (\"1. ...\"). The last step should produce the answer for \
+         the user. Reply with the list only.\n\nTask: {}",
+        goal
+    )
+}
+
+fn reflect_prompt(goal: &str, step: &str, error: &str) -> String {
+    format!(
+        "While working on the task below, the step \"{}\" failed with: {}\n\n\
+         Briefly explain why it most likely failed and what should be done \
+         differently.\n\nTask: {}",
+        step, error, goal
+    )
+}
+
+fn replan_prompt(
+    goal: &str,
+    completed: &[StepOutcome],
+    step: &str,
+    error: &str,
+    reflection: &str,
+) -> String {
+    let mut prompt = format!("Task: {}\n\n", goal);
+    if !completed.is_empty() {
+        prompt.push_str("Completed steps:\n");
+        for outcome in completed {
+            prompt.push_str(&format!("- {}: {}\n", outcome.description, outcome.output));
+        }
+        prompt.push('\n');
+    }
+    prompt.push_str(&format!(
+        "The step \"{}\" failed with: {}\nReflection: {}\n\n\
+         Write a revised numbered list of the steps still needed to finish the \
+         task, one per line (\"1. ...\"). The last step should produce the \
+         answer for the user. Reply with the list only, or with nothing if the \
+         task is already done.",
+        step, error, reflection
+    ));
+    prompt
+}
+
+/// Returns the conversation for executing `step`, ending with an
+/// instruction describing the plan and the step to carry out.
+fn step_messages(
+    messages: &[Message],
+    steps: &[PlanStep],
+    completed: &[StepOutcome],
+    step: &PlanStep,
+) -> Vec<Message> {
+    let mut instruction = String::from("You are following this plan:\n");
+    for s in steps {
+        instruction.push_str(&format!("{}. {}\n", s.index + 1, s.description));
+    }
+    if !completed.is_empty() {
+        instruction.push_str("\nResults of completed steps:\n");
+        for outcome in completed {
+            instruction.push_str(&format!("- {}: {}\n", outcome.description, outcome.output));
+        }
+    }
+    instruction.push_str(&format!(
+        "\nCarry out step {} only: {}\nIf the step cannot be completed, reply \
+         with \"{} \" followed by the reason.",
+        step.index + 1,
+        step.description,
+        STEP_FAILED_MARKER
+    ));
+
+    let mut messages = messages.to_vec();
+    messages.push(Message {
+        role: Role::User,
+        content: MessageContent::Text(instruction),
+        tool_calls: None,
+        tool_call_id: None,
+    });
+    messages
+}
+
+fn message_text(msg: &Message) -> String {
+    match &msg.content {
+        MessageContent::Text(text) => text.clone(),
+        MessageContent::Parts(parts) => parts
+            .iter()
+            .filter_map(|p| match p {
+                aisopod_provider::ContentPart::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => String::new(),
+    }
+}
+
+/// Sends a single-prompt request to `model` and returns the reply text.
+async fn complete(provider: &dyn ModelProvider, model: &str, prompt: String) -> Result<String> {
+    let request = ChatCompletionRequest {
+        model: model.to_string(),
+        messages: vec![Message {
+            role: Role::User,
+            content: MessageContent::Text(prompt),
+            tool_calls: None,
+            tool_call_id: None,
+        }],
+        tools: None,
+        temperature: Some(0.0),
+        max_tokens: None,
+        stop: None,
+        stream: false,
+    };
+
+    let mut stream = provider.chat_completion(request).await?;
+    let mut response = String::new();
+    while let Some(chunk) = stream.next().await {
+        if let Some(content) = chunk?.delta.content {
+            response.push_str(&content);
+        }
+    }
+    Ok(response.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plan_numbered_lines() {
+        let text =
+            "Here is the plan:\n1. Find the file\n2) Read it\n\n3.   Summarize it\n- not a step";
+        let steps = parse_plan(text, 8);
+
+        let descriptions: Vec<_> = steps.iter().map(|s| s.description.as_str()).collect();
+        assert_eq!(
+            descriptions,
+            vec!["Find the file", "Read it", "Summarize it"]
+        );
+        assert_eq!(steps[2].index, 2);
+        assert!(steps.iter().all(|s| s.status == PlanStepStatus::Pending));
+    }
+
+    #[test]
+    fn test_parse_plan_limits_steps() {
+        let text = "1. a\n2. b\n3. c";
+        assert_eq!(parse_plan(text, 2).len(), 2);
+    }
+
+    #[test]
+    fn test_parse_plan_ignores_unnumbered_text() {
+        assert!(parse_plan("Nothing left to do.", 8).is_empty());
+        assert!(parse_plan("2024 was a year", 8).is_empty());
+    }
+
+    #[test]
+    fn test_step_messages_end_with_instruction() {
+        let steps = parse_plan("1. Find the file\n2. Read it", 8);
+        let completed = vec![StepOutcome {
+            description: "Find the file".to_string(),
+            output: "Found notes.txt".to_string(),
+        }];
+        let messages = step_messages(&[], &steps, &completed, &steps[1]);
+
+        assert_eq!(messages.len(), 1);
+        let text = message_text(&messages[0]);
+        assert!(text.contains("2. Read it"));
+        assert!(text.contains("- Find the file: Found notes.txt"));
+        assert!(text.contains("Carry out step 2 only: Read it"));
+    }
+}
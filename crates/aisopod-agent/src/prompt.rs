@@ -3,9 +3,83 @@
 //! This module provides a builder pattern for constructing system prompts
 //! that include base instructions, dynamic context, tool descriptions,
 //! skill instructions, and memory context.
+//!
+//! The base prompt can be rendered from a template with `{{variable}}`
+//! placeholders, such as `{{agent_name}}`, `{{date}}` or `{{peer_id}}`, and
+//! individual sections can be overridden by label when the prompt is built.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
 
 use crate::types::ToolSchema;
 use chrono::{DateTime, Utc};
+use regex::Regex;
+
+/// Renders `template`, replacing each `{{name}}` placeholder with the value
+/// of variable `name`.
+///
+/// Whitespace inside the braces is ignored. Placeholders of unknown
+/// variables are left as written.
+pub fn render_template(template: &str, variables: &HashMap<String, String>) -> String {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    let placeholder = PLACEHOLDER
+        .get_or_init(|| Regex::new(r"\{\{\s*([A-Za-z0-9_]+)\s*\}\}").expect("valid regex"));
+
+    placeholder
+        .replace_all(template, |caps: &regex::Captures| {
+            variables
+                .get(&caps[1])
+                .cloned()
+                .unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
+
+/// Returns the built-in template variables of a run of `agent` with
+/// `model` in session `session_key`.
+///
+/// The variables are `agent_id`, `agent_name`, `model`, `date`, `datetime`
+/// and `session_key`, plus `channel`, `account_id`, `peer_kind` and
+/// `peer_id` when the session key is a channel session key of the form
+/// `agent:channel:account:peer_kind:peer_id`.
+pub fn builtin_variables(
+    agent: &aisopod_config::types::Agent,
+    model: &str,
+    session_key: &str,
+) -> HashMap<String, String> {
+    let now = Utc::now();
+    let agent_name = if agent.name.is_empty() {
+        &agent.id
+    } else {
+        &agent.name
+    };
+
+    let mut variables = HashMap::from([
+        ("agent_id".to_string(), agent.id.clone()),
+        ("agent_name".to_string(), agent_name.clone()),
+        ("model".to_string(), model.to_string()),
+        ("date".to_string(), now.format("%Y-%m-%d").to_string()),
+        (
+            "datetime".to_string(),
+            now.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        ),
+        ("session_key".to_string(), session_key.to_string()),
+    ]);
+
+    let parts: Vec<&str> = session_key.split(':').collect();
+    if let [_, channel, account_id, peer_kind, peer_id] = parts[..] {
+        for (name, value) in [
+            ("channel", channel),
+            ("account_id", account_id),
+            ("peer_kind", peer_kind),
+            ("peer_id", peer_id),
+        ] {
+            variables.insert(name.to_string(), value.to_string());
+        }
+    }
+
+    variables
+}
 
 /// A section of the system prompt with a label and content.
 #[derive(Debug, Clone, Default)]
@@ -45,6 +119,10 @@ impl PromptSection {
 pub struct SystemPromptBuilder {
     /// The list of sections to include in the prompt.
     sections: Vec<PromptSection>,
+    /// The variables templates are rendered with.
+    variables: HashMap<String, String>,
+    /// Section contents replacing the built ones, by label.
+    overrides: HashMap<String, String>,
 }
 
 impl SystemPromptBuilder {
@@ -52,9 +130,39 @@ impl SystemPromptBuilder {
     pub fn new() -> Self {
         Self {
             sections: Vec::new(),
+            variables: HashMap::new(),
+            overrides: HashMap::new(),
         }
     }
 
+    /// Adds variables for rendering templates, replacing variables of the
+    /// same name.
+    pub fn with_variables(mut self, variables: HashMap<String, String>) -> Self {
+        self.variables.extend(variables);
+        self
+    }
+
+    /// Adds a base prompt rendered from `template` with the builder's
+    /// variables.
+    pub fn with_template(mut self, template: &str) -> Self {
+        let prompt = render_template(template, &self.variables);
+        self.sections
+            .push(PromptSection::new("Base Prompt", prompt));
+        self
+    }
+
+    /// Overrides sections by label when the prompt is built.
+    ///
+    /// An override replaces the content of the section with its label,
+    /// rendered with the builder's variables, and an empty override removes
+    /// the section. Overrides of labels no section has are added as new
+    /// sections at the end, in label order.
+    pub fn with_section_overrides(mut self, overrides: &HashMap<String, String>) -> Self {
+        self.overrides
+            .extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+        self
+    }
+
     /// Adds a base prompt to the system prompt.
     ///
     /// This should contain the primary instructions for the agent.
@@ -156,9 +264,36 @@ impl SystemPromptBuilder {
     ///
     /// Each section is separated by a blank line and includes its header.
     pub fn build(&self) -> String {
+        let mut sections: Vec<PromptSection> = Vec::new();
+        for section in &self.sections {
+            match self.overrides.get(&section.label) {
+                Some(content) if content.is_empty() => {}
+                Some(content) => sections.push(PromptSection::new(
+                    section.label.clone(),
+                    render_template(content, &self.variables),
+                )),
+                None => sections.push(section.clone()),
+            }
+        }
+
+        let mut added: Vec<(&String, &String)> = self
+            .overrides
+            .iter()
+            .filter(|(label, content)| {
+                !content.is_empty() && !self.sections.iter().any(|s| &s.label == *label)
+            })
+            .collect();
+        added.sort();
+        for (label, content) in added {
+            sections.push(PromptSection::new(
+                label.clone(),
+                render_template(content, &self.variables),
+            ));
+        }
+
         let mut result = String::new();
 
-        for (i, section) in self.sections.iter().enumerate() {
+        for (i, section) in sections.iter().enumerate() {
             if i > 0 {
                 result.push('\n');
             }
@@ -372,4 +507,69 @@ mod tests {
         assert!(tools_pos < skills_pos);
         assert!(skills_pos < memory_pos);
     }
+
+    fn variables() -> HashMap<String, String> {
+        HashMap::from([
+            ("agent_name".to_string(), "Ada".to_string()),
+            ("peer_id".to_string(), "alice".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_render_template() {
+        let rendered = render_template(
+            "You are {{agent_name}}, talking to {{ peer_id }} about {{topic}}.",
+            &variables(),
+        );
+        assert_eq!(rendered, "You are Ada, talking to alice about {{topic}}.");
+    }
+
+    #[test]
+    fn test_builtin_variables_from_channel_session_key() {
+        let agent = aisopod_config::types::Agent {
+            id: "support".to_string(),
+            ..Default::default()
+        };
+        let variables = builtin_variables(&agent, "openai/gpt-4o", "support:telegram:bot:dm:alice");
+
+        assert_eq!(variables["agent_name"], "support");
+        assert_eq!(variables["model"], "openai/gpt-4o");
+        assert_eq!(variables["channel"], "telegram");
+        assert_eq!(variables["peer_kind"], "dm");
+        assert_eq!(variables["peer_id"], "alice");
+        assert_eq!(variables["date"].len(), 10);
+
+        let variables = builtin_variables(&agent, "openai/gpt-4o", "cron:daily");
+        assert!(!variables.contains_key("peer_id"));
+    }
+
+    #[test]
+    fn test_with_template_renders_variables() {
+        let prompt = SystemPromptBuilder::new()
+            .with_variables(variables())
+            .with_template("You are {{agent_name}}.")
+            .build();
+
+        assert!(prompt.contains("## Base Prompt\nYou are Ada."));
+    }
+
+    #[test]
+    fn test_section_overrides() {
+        let overrides = HashMap::from([
+            ("Tools".to_string(), String::new()),
+            ("Base Prompt".to_string(), "Hi {{peer_id}}.".to_string()),
+            ("Style".to_string(), "Be brief.".to_string()),
+        ]);
+        let prompt = SystemPromptBuilder::new()
+            .with_variables(variables())
+            .with_base_prompt("Original")
+            .with_tool_descriptions(&[])
+            .with_section_overrides(&overrides)
+            .build();
+
+        assert!(!prompt.contains("## Tools"));
+        assert!(!prompt.contains("Original"));
+        assert!(prompt.contains("## Base Prompt\nHi alice."));
+        assert!(prompt.ends_with("## Style\nBe brief."));
+    }
 }
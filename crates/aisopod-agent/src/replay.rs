@@ -0,0 +1,347 @@
+//! Replay of stored sessions for debugging.
+//!
+//! A [`SessionReplayer`] re-feeds the user messages of a stored session
+//! through an [`AgentRunner`], typically one backed by a mock provider or a
+//! different model, and compares each turn's outcome with the one recorded
+//! in the session. The resulting [`ReplayReport`] lists the turns whose
+//! response or tool calls changed, with a line diff of the responses, to
+//! help track down regressions in prompts and tools.
+//!
+//! Each turn is replayed against the recorded conversation before it, not
+//! the replayed one, so a change in one turn does not cascade into the
+//! following turns. Earlier turns are given to the model as their user and
+//! assistant text; tool messages are left out.
+
+use std::sync::Arc;
+
+use aisopod_provider::{Message, MessageContent, Role};
+use aisopod_session::{SessionKey, StoredMessage};
+use serde::Serialize;
+use serde_json::Value;
+use tracing::debug;
+
+use crate::runner::AgentRunner;
+use crate::types::AgentRunParams;
+
+/// A line of the diff between a recorded and a replayed response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "op", content = "line", rename_all = "snake_case")]
+pub enum DiffLine {
+    /// The line is in both responses.
+    Same(String),
+    /// The line is only in the recorded response.
+    Removed(String),
+    /// The line is only in the replayed response.
+    Added(String),
+}
+
+/// The outcome of replaying one user turn.
+#[derive(Debug, Clone, Serialize)]
+pub struct TurnReplay {
+    /// The position of the turn among the session's user turns, from 0.
+    pub turn: usize,
+    /// The user message starting the turn.
+    pub prompt: String,
+    /// The final assistant response recorded in the session.
+    pub recorded_response: String,
+    /// The response of the replayed run.
+    pub replayed_response: String,
+    /// The names of the tools called in the recorded turn.
+    pub recorded_tools: Vec<String>,
+    /// The names of the tools called in the replayed run.
+    pub replayed_tools: Vec<String>,
+    /// Whether the response or the called tools differ.
+    pub changed: bool,
+    /// The line diff of the responses; empty when they are equal.
+    pub diff: Vec<DiffLine>,
+    /// The error failing the replayed run, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The outcome of replaying a session.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayReport {
+    /// The agent that ran the replay.
+    pub agent: String,
+    /// The replayed turns, in order.
+    pub turns: Vec<TurnReplay>,
+}
+
+impl ReplayReport {
+    /// Returns the number of turns whose outcome changed.
+    pub fn changed(&self) -> usize {
+        self.turns.iter().filter(|turn| turn.changed).count()
+    }
+
+    /// Returns true if every turn replayed with the recorded outcome.
+    pub fn unchanged(&self) -> bool {
+        self.changed() == 0
+    }
+}
+
+/// A recorded user turn and the history before it.
+struct RecordedTurn {
+    prompt: String,
+    history: Vec<Message>,
+    response: String,
+    tools: Vec<String>,
+}
+
+/// Replays stored sessions through an agent runner.
+pub struct SessionReplayer {
+    runner: Arc<AgentRunner>,
+    agent: Option<String>,
+}
+
+impl SessionReplayer {
+    /// Creates a replayer running turns with `runner`.
+    pub fn new(runner: Arc<AgentRunner>) -> Self {
+        Self {
+            runner,
+            agent: None,
+        }
+    }
+
+    /// Replays with `agent_id` instead of the session's agent.
+    pub fn with_agent(mut self, agent_id: impl Into<String>) -> Self {
+        self.agent = Some(agent_id.into());
+        self
+    }
+
+    /// Replays the user turns of the session `key` recorded as `messages`.
+    ///
+    /// The replayed runs use a separate session key, so they do not touch
+    /// the recorded session.
+    pub async fn replay(&self, key: &SessionKey, messages: &[StoredMessage]) -> ReplayReport {
+        let agent = self.agent.clone().unwrap_or_else(|| key.agent_id.clone());
+        let session_key = format!(
+            "replay:{}:{}:{}:{}:{}",
+            key.agent_id, key.channel, key.account_id, key.peer_kind, key.peer_id
+        );
+
+        let mut turns = Vec::new();
+        for (index, recorded) in recorded_turns(messages).into_iter().enumerate() {
+            debug!(
+                "Replaying turn {} of {} with agent {}",
+                index, session_key, agent
+            );
+            let mut run_messages = recorded.history;
+            run_messages.push(text_message(Role::User, &recorded.prompt));
+            let params = AgentRunParams::new(session_key.clone(), run_messages, Some(&agent));
+
+            let (replayed_response, replayed_tools, error) =
+                match self.runner.run_and_get_result(params).await {
+                    Ok(result) => (
+                        result.response,
+                        result
+                            .tool_calls
+                            .into_iter()
+                            .map(|call| call.name)
+                            .collect(),
+                        None,
+                    ),
+                    Err(e) => (String::new(), Vec::new(), Some(e.to_string())),
+                };
+
+            let changed = error.is_some()
+                || replayed_response != recorded.response
+                || replayed_tools != recorded.tools;
+            let diff = if replayed_response == recorded.response {
+                Vec::new()
+            } else {
+                diff_lines(&recorded.response, &replayed_response)
+            };
+            turns.push(TurnReplay {
+                turn: index,
+                prompt: recorded.prompt,
+                recorded_response: recorded.response,
+                replayed_response,
+                recorded_tools: recorded.tools,
+                replayed_tools,
+                changed,
+                diff,
+                error,
+            });
+        }
+
+        ReplayReport { agent, turns }
+    }
+}
+
+/// Splits stored messages into user turns with their recorded outcomes.
+fn recorded_turns(messages: &[StoredMessage]) -> Vec<RecordedTurn> {
+    let mut turns: Vec<RecordedTurn> = Vec::new();
+    let mut history = Vec::new();
+
+    for msg in messages {
+        let text = content_text(&msg.content);
+        match msg.role.as_str() {
+            "user" => {
+                turns.push(RecordedTurn {
+                    prompt: text.clone(),
+                    history: history.clone(),
+                    response: String::new(),
+                    tools: Vec::new(),
+                });
+                history.push(text_message(Role::User, &text));
+            }
+            "assistant" => {
+                if let Some(turn) = turns.last_mut() {
+                    if let Some(tool_calls) = &msg.tool_calls {
+                        turn.tools.extend(tool_call_names(tool_calls));
+                    }
+                    if !text.is_empty() {
+                        turn.response = text.clone();
+                    }
+                }
+                if !text.is_empty() {
+                    history.push(text_message(Role::Assistant, &text));
+                }
+            }
+            "system" => history.push(text_message(Role::System, &text)),
+            _ => {}
+        }
+    }
+
+    turns
+}
+
+/// Returns the text of stored message content.
+fn content_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| match part {
+                Value::String(text) => Some(text.as_str()),
+                other => other.get("text").and_then(Value::as_str),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Returns the tool names of stored tool call data.
+fn tool_call_names(tool_calls: &Value) -> Vec<String> {
+    let calls = match tool_calls {
+        Value::Array(calls) => calls.iter().collect(),
+        other => vec![other],
+    };
+    calls
+        .into_iter()
+        .filter_map(|call| {
+            call.get("name")
+                .or_else(|| call.pointer("/function/name"))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+fn text_message(role: Role, text: &str) -> Message {
+    Message {
+        role,
+        content: MessageContent::Text(text.to_string()),
+        tool_calls: None,
+        tool_call_id: None,
+    }
+}
+
+/// Returns the line diff turning `recorded` into `replayed`.
+pub fn diff_lines(recorded: &str, replayed: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = recorded.lines().collect();
+    let new: Vec<&str> = replayed.lines().collect();
+
+    // Length of the longest common subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    diff.extend(
+        old[i..]
+            .iter()
+            .map(|line| DiffLine::Removed(line.to_string())),
+    );
+    diff.extend(
+        new[j..]
+            .iter()
+            .map(|line| DiffLine::Added(line.to_string())),
+    );
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines("a\nb\nc", "a\nx\nc\nd");
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Same("a".to_string()),
+                DiffLine::Removed("b".to_string()),
+                DiffLine::Added("x".to_string()),
+                DiffLine::Same("c".to_string()),
+                DiffLine::Added("d".to_string()),
+            ]
+        );
+        assert!(diff_lines("", "").is_empty());
+    }
+
+    #[test]
+    fn test_recorded_turns() {
+        let messages = vec![
+            StoredMessage::user("What is 10 * 10?"),
+            StoredMessage::with_tool_calls(
+                "assistant",
+                "",
+                json!([{"id": "call_1", "name": "calculator", "arguments": "{}"}]),
+            ),
+            StoredMessage::tool("100"),
+            StoredMessage::assistant("It is 100."),
+            StoredMessage::user("Thanks"),
+            StoredMessage::assistant("You're welcome."),
+        ];
+
+        let turns = recorded_turns(&messages);
+
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].prompt, "What is 10 * 10?");
+        assert_eq!(turns[0].response, "It is 100.");
+        assert_eq!(turns[0].tools, vec!["calculator"]);
+        assert!(turns[0].history.is_empty());
+        assert_eq!(turns[1].response, "You're welcome.");
+        assert!(turns[1].tools.is_empty());
+        // The tool plumbing of the first turn is left out of the history
+        assert_eq!(turns[1].history.len(), 2);
+        assert_eq!(turns[1].history[1].role, Role::Assistant);
+    }
+}
@@ -4,8 +4,12 @@
 //! - Resolve the agent ID for a given session
 //! - Resolve agent configuration by agent ID
 //! - Resolve model configuration for an agent
+//! - Resolve the workspace and sandbox of an agent's tools
 //! - List all configured agent IDs
 
+use std::sync::Arc;
+
+use aisopod_config::types::{FailoverPolicy, SandboxConfig};
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 
@@ -25,6 +29,9 @@ pub struct ResolutionConfig {
     /// Whether to use priority-based binding selection
     #[serde(default = "default_true")]
     pub use_priority: bool,
+    /// How errors move execution along the resolved model chain
+    #[serde(default)]
+    pub failover: FailoverPolicy,
 }
 
 fn default_true() -> bool {
@@ -37,6 +44,7 @@ impl Default for ResolutionConfig {
             bindings: Vec::new(),
             default_agent: None,
             use_priority: true,
+            failover: FailoverPolicy::default(),
         }
     }
 }
@@ -110,6 +118,72 @@ pub fn resolve_agent_config(
         .ok_or_else(|| anyhow!("Agent not found: {}", agent_id))
 }
 
+/// Resolves the context the tools called by an agent run in.
+///
+/// Tools work in the agent's workspace, or the default one. They run in
+/// the sandbox of the agent's binding, or in a default sandbox when the
+/// agent or the agent defaults enable `sandbox`.
+///
+/// # Arguments
+///
+/// * `config` - The aisopod configuration to search
+/// * `agent_id` - The agent calling the tools
+/// * `session_key` - The session the tools are called in
+pub fn resolve_tool_context(
+    config: &aisopod_config::AisopodConfig,
+    agent_id: &str,
+    session_key: &str,
+) -> aisopod_tools::ToolContext {
+    let agent = config.agents.agents.iter().find(|a| a.id == agent_id);
+    let defaults = &config.agents.default;
+    let mut ctx = aisopod_tools::ToolContext::new(agent_id, session_key);
+
+    let workspace = agent
+        .map(|a| a.workspace.as_str())
+        .filter(|workspace| !workspace.is_empty())
+        .unwrap_or(&defaults.workspace);
+    if !workspace.is_empty() {
+        ctx = ctx.with_workspace_path(workspace);
+    }
+
+    let binding_sandbox = config
+        .bindings
+        .iter()
+        .filter(|binding| binding.agent_id == agent_id)
+        .find_map(|binding| binding.sandbox.clone());
+    let sandboxed = defaults.sandbox || agent.is_some_and(|a| a.sandbox);
+    let sandbox = binding_sandbox.or_else(|| {
+        sandboxed.then(|| SandboxConfig {
+            enabled: true,
+            ..SandboxConfig::default()
+        })
+    });
+    if let Some(sandbox) = sandbox {
+        ctx = ctx.with_sandbox_config(sandbox);
+    }
+    ctx
+}
+
+/// Resolves tool contexts with [`resolve_tool_context`] from a fixed
+/// configuration, for tools called outside of agent runs.
+#[derive(Debug, Clone)]
+pub struct ConfigToolContexts {
+    config: Arc<aisopod_config::AisopodConfig>,
+}
+
+impl ConfigToolContexts {
+    /// Creates a resolver for the agents of `config`.
+    pub fn new(config: Arc<aisopod_config::AisopodConfig>) -> Self {
+        Self { config }
+    }
+}
+
+impl aisopod_tools::ToolContextResolver for ConfigToolContexts {
+    fn tool_context(&self, agent_id: &str, session_key: &str) -> aisopod_tools::ToolContext {
+        resolve_tool_context(&self.config, agent_id, session_key)
+    }
+}
+
 /// Resolves the model chain for a given agent.
 ///
 /// A model chain represents the sequence of models to try for an agent,
@@ -142,25 +216,37 @@ pub fn resolve_agent_model(
         ));
     };
 
+    Ok(resolve_model_chain(config, &primary_model))
+}
+
+/// Resolves the model chain starting with `primary`.
+///
+/// The fallbacks are those configured for `primary` in the models config.
+/// Model aliases of the models config are resolved to model IDs, for the
+/// primary model as well as the fallbacks.
+pub fn resolve_model_chain(config: &aisopod_config::AisopodConfig, primary: &str) -> ModelChain {
+    let models = &config.models;
+    let primary = models.resolve_alias(primary);
+
     // Get fallback models from the models config
-    let fallbacks: Vec<String> = config
-        .models
+    let fallbacks: Vec<String> = models
         .fallbacks
         .iter()
         .filter_map(|f| {
-            if f.primary == primary_model {
-                Some(f.fallbacks.clone())
+            if models.resolve_alias(&f.primary) == primary {
+                Some(&f.fallbacks)
             } else {
                 None
             }
         })
         .flatten()
+        .map(|model| models.resolve_alias(model).to_string())
         .collect();
 
-    Ok(ModelChain {
-        primary: primary_model,
+    ModelChain {
+        primary: primary.to_string(),
         fallbacks,
-    })
+    }
 }
 
 /// A chain of models for an agent.
@@ -243,6 +329,7 @@ mod tests {
             bindings: Vec::new(),
             default_agent: Some("default_agent".to_string()),
             use_priority: false,
+            failover: FailoverPolicy::default(),
         };
 
         assert_eq!(config.default_agent, Some("default_agent".to_string()));
@@ -303,4 +390,76 @@ mod tests {
         let result = resolve_agent_model(&config, "nonexistent_agent");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_resolve_tool_context() {
+        let mut config = aisopod_config::AisopodConfig::default();
+        config.agents.default.workspace = "/srv/default".to_string();
+        config.agents.agents = vec![
+            aisopod_config::types::Agent {
+                id: "coder".to_string(),
+                workspace: "/srv/coder".to_string(),
+                sandbox: true,
+                ..Default::default()
+            },
+            aisopod_config::types::Agent {
+                id: "bound".to_string(),
+                ..Default::default()
+            },
+        ];
+        config.bindings.push(aisopod_config::types::AgentBinding {
+            agent_id: "bound".to_string(),
+            sandbox: Some(SandboxConfig {
+                enabled: true,
+                network_access: false,
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        let ctx = resolve_tool_context(&config, "coder", "s1");
+        assert_eq!(ctx.session_key, "s1");
+        assert_eq!(ctx.workspace_path.unwrap().to_str(), Some("/srv/coder"));
+        let sandbox = ctx.sandbox_config.unwrap();
+        assert!(sandbox.enabled && sandbox.network_access);
+
+        let ctx = resolve_tool_context(&config, "bound", "s1");
+        assert_eq!(ctx.workspace_path.unwrap().to_str(), Some("/srv/default"));
+        assert!(!ctx.sandbox_config.unwrap().network_access);
+
+        // Tools called outside of agent runs resolve the same context
+        let contexts = ConfigToolContexts::new(Arc::new(config.clone()));
+        let ctx = aisopod_tools::ToolContextResolver::tool_context(&contexts, "coder", "s2");
+        assert_eq!(ctx.workspace_path.unwrap().to_str(), Some("/srv/coder"));
+        assert!(ctx.sandbox_config.unwrap().enabled);
+
+        // Agents without a workspace or sandbox get neither
+        let ctx = resolve_tool_context(&aisopod_config::AisopodConfig::default(), "x", "s1");
+        assert!(ctx.workspace_path.is_none() && ctx.sandbox_config.is_none());
+    }
+
+    #[test]
+    fn test_resolve_model_chain_resolves_aliases() {
+        let mut config = aisopod_config::AisopodConfig::default();
+        config
+            .models
+            .aliases
+            .insert("smart".to_string(), "anthropic/claude-sonnet-4".to_string());
+        config
+            .models
+            .aliases
+            .insert("fast".to_string(), "openai/gpt-4o-mini".to_string());
+        config.models.fallbacks.push(aisopod_config::ModelFallback {
+            primary: "smart".to_string(),
+            fallbacks: vec!["fast".to_string(), "openai/gpt-4o".to_string()],
+        });
+
+        let chain = resolve_model_chain(&config, "smart");
+        assert_eq!(chain.primary(), "anthropic/claude-sonnet-4");
+        assert_eq!(chain.fallbacks(), ["openai/gpt-4o-mini", "openai/gpt-4o"]);
+
+        // Fallbacks configured for an alias apply to its model too
+        let chain = resolve_model_chain(&config, "anthropic/claude-sonnet-4");
+        assert_eq!(chain.fallbacks().len(), 2);
+    }
 }
@@ -4,17 +4,85 @@
 //! agent execution using configuration, provider registry, tool registry,
 //! and session store.
 
-use std::sync::Arc;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use tokio::sync::broadcast;
+use tracing::Instrument;
 
 use crate::abort::{AbortHandle, AbortRegistry};
+use crate::budget::BudgetNotifier;
+use crate::handoff::HandoffRegistry;
 use crate::memory::{inject_memory_context, MemoryConfig};
+use crate::planning::PlanningConfig;
 use crate::resolution;
 use crate::skills_integration::SkillRegistry;
+use crate::steering::{SteeringRegistry, Submission};
 use crate::types::{AgentEvent, AgentRunParams, AgentRunResult};
+use aisopod_config::ConfigApplier;
 use aisopod_memory::{MemoryManager, MemoryQueryPipeline};
+use aisopod_session::{acquire_lease_for, SessionBackend};
+
+/// Session leases taken by a runner sharing its sessions with other
+/// instances.
+///
+/// A runner with leases runs an agent on a session only while it holds the
+/// session's lease in `backend`, so that two instances behind a load
+/// balancer never process the same session at once. A run whose session is
+/// leased by another instance fails, and a run whose lease is taken over
+/// is stopped.
+#[derive(Clone)]
+pub struct SessionLeases {
+    /// The store shared by the instances, which keeps the leases.
+    pub backend: Arc<dyn SessionBackend>,
+    /// The identifier of this instance, unique among the instances.
+    pub owner: String,
+    /// How long a lease lasts unless renewed while the run continues.
+    pub ttl: Duration,
+}
+
+impl std::fmt::Debug for SessionLeases {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionLeases")
+            .field("owner", &self.owner)
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+/// Runs `run` while holding the lease on `session_key`, if leases are
+/// enabled.
+async fn with_session_lease<T>(
+    leases: Option<&SessionLeases>,
+    session_key: &str,
+    run: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let Some(leases) = leases else {
+        return run.await;
+    };
+    let guard = acquire_lease_for(
+        leases.backend.clone(),
+        session_key,
+        &leases.owner,
+        leases.ttl,
+    )
+    .await?
+    .ok_or_else(|| {
+        anyhow!(
+            "Session {} is being processed by another instance",
+            session_key
+        )
+    })?;
+    tokio::select! {
+        outcome = run => outcome,
+        _ = guard.lost() => Err(anyhow!(
+            "Session {} was taken over by another instance",
+            session_key
+        )),
+    }
+}
 
 /// Extension trait for AgentRunner to support subagent spawning.
 pub trait SubagentRunnerExt {
@@ -69,8 +137,8 @@ pub trait SubagentRunnerExt {
 /// }
 /// ```
 pub struct AgentRunner {
-    /// The agent configuration.
-    config: Arc<aisopod_config::AisopodConfig>,
+    /// The agent configuration, replaced when the configuration is reloaded.
+    config: RwLock<Arc<aisopod_config::AisopodConfig>>,
     /// The provider registry for model access.
     providers: Arc<aisopod_provider::ProviderRegistry>,
     /// The tool registry for tool execution.
@@ -89,6 +157,16 @@ pub struct AgentRunner {
     memory_config: MemoryConfig,
     /// Optional skill registry for resolving and managing skills assigned to agents
     skills: Option<Arc<SkillRegistry>>,
+    /// Optional handoff registry overriding the agent bound to a session
+    handoffs: Option<Arc<HandoffRegistry>>,
+    /// Optional notifier told about runs refused by an exceeded budget
+    budget_notifier: Option<Arc<dyn BudgetNotifier>>,
+    /// Plan-and-execute configuration; runs plan first when set
+    planning: Option<PlanningConfig>,
+    /// Registry of the streamed runs in progress for each session
+    steering: Arc<SteeringRegistry>,
+    /// Session leases shared with other instances, if any
+    leases: Option<SessionLeases>,
 }
 
 impl AgentRunner {
@@ -107,7 +185,7 @@ impl AgentRunner {
         sessions: Arc<aisopod_session::SessionStore>,
     ) -> Self {
         Self {
-            config,
+            config: RwLock::new(config),
             providers,
             tools,
             sessions,
@@ -117,6 +195,11 @@ impl AgentRunner {
             memory_manager: None,
             memory_config: MemoryConfig::default(),
             skills: None,
+            handoffs: None,
+            budget_notifier: None,
+            planning: None,
+            steering: Arc::new(SteeringRegistry::new()),
+            leases: None,
         }
     }
 
@@ -137,7 +220,7 @@ impl AgentRunner {
         usage_tracker: Arc<crate::usage::UsageTracker>,
     ) -> Self {
         Self {
-            config,
+            config: RwLock::new(config),
             providers,
             tools,
             sessions,
@@ -147,6 +230,11 @@ impl AgentRunner {
             memory_manager: None,
             memory_config: MemoryConfig::default(),
             skills: None,
+            handoffs: None,
+            budget_notifier: None,
+            planning: None,
+            steering: Arc::new(SteeringRegistry::new()),
+            leases: None,
         }
     }
 
@@ -178,7 +266,7 @@ impl AgentRunner {
         let tools = Arc::new(tools_ref);
 
         Self {
-            config,
+            config: RwLock::new(config),
             providers,
             tools,
             sessions,
@@ -188,6 +276,11 @@ impl AgentRunner {
             memory_manager: Some(memory_manager),
             memory_config: MemoryConfig::default(),
             skills: None,
+            handoffs: None,
+            budget_notifier: None,
+            planning: None,
+            steering: Arc::new(SteeringRegistry::new()),
+            leases: None,
         }
     }
 
@@ -221,7 +314,7 @@ impl AgentRunner {
         let tools = Arc::new(tools_ref);
 
         Self {
-            config,
+            config: RwLock::new(config),
             providers,
             tools,
             sessions,
@@ -231,6 +324,11 @@ impl AgentRunner {
             memory_manager: Some(memory_manager),
             memory_config: MemoryConfig::default(),
             skills: None,
+            handoffs: None,
+            budget_notifier: None,
+            planning: None,
+            steering: Arc::new(SteeringRegistry::new()),
+            leases: None,
         }
     }
 
@@ -251,7 +349,7 @@ impl AgentRunner {
         skills: Arc<SkillRegistry>,
     ) -> Self {
         Self {
-            config,
+            config: RwLock::new(config),
             providers,
             tools,
             sessions,
@@ -261,6 +359,11 @@ impl AgentRunner {
             memory_manager: None,
             memory_config: MemoryConfig::default(),
             skills: Some(skills),
+            handoffs: None,
+            budget_notifier: None,
+            planning: None,
+            steering: Arc::new(SteeringRegistry::new()),
+            leases: None,
         }
     }
 
@@ -283,7 +386,7 @@ impl AgentRunner {
         usage_tracker: Arc<crate::usage::UsageTracker>,
     ) -> Self {
         Self {
-            config,
+            config: RwLock::new(config),
             providers,
             tools,
             sessions,
@@ -293,6 +396,11 @@ impl AgentRunner {
             memory_manager: None,
             memory_config: MemoryConfig::default(),
             skills: Some(skills),
+            handoffs: None,
+            budget_notifier: None,
+            planning: None,
+            steering: Arc::new(SteeringRegistry::new()),
+            leases: None,
         }
     }
 
@@ -326,7 +434,7 @@ impl AgentRunner {
         let tools = Arc::new(tools_ref);
 
         Self {
-            config,
+            config: RwLock::new(config),
             providers,
             tools,
             sessions,
@@ -336,6 +444,11 @@ impl AgentRunner {
             memory_manager: Some(memory_manager),
             memory_config: MemoryConfig::default(),
             skills: Some(skills),
+            handoffs: None,
+            budget_notifier: None,
+            planning: None,
+            steering: Arc::new(SteeringRegistry::new()),
+            leases: None,
         }
     }
 
@@ -371,7 +484,7 @@ impl AgentRunner {
         let tools = Arc::new(tools_ref);
 
         Self {
-            config,
+            config: RwLock::new(config),
             providers,
             tools,
             sessions,
@@ -381,6 +494,11 @@ impl AgentRunner {
             memory_manager: Some(memory_manager),
             memory_config: MemoryConfig::default(),
             skills: Some(skills),
+            handoffs: None,
+            budget_notifier: None,
+            planning: None,
+            steering: Arc::new(SteeringRegistry::new()),
+            leases: None,
         }
     }
 
@@ -430,6 +548,68 @@ impl AgentRunner {
         self.skills.as_ref()
     }
 
+    /// Sets the handoff registry deciding which agent handles handed-off
+    /// sessions.
+    ///
+    /// Agents hand sessions off through a `HandoffTool` backed by the same
+    /// registry, which must be registered in the tool registry separately.
+    pub fn with_handoffs(mut self, handoffs: Arc<HandoffRegistry>) -> Self {
+        self.handoffs = Some(handoffs);
+        self
+    }
+
+    /// Gets the handoff registry if enabled.
+    pub fn handoffs(&self) -> Option<&Arc<HandoffRegistry>> {
+        self.handoffs.as_ref()
+    }
+
+    /// Sets the notifier reporting runs refused by an exceeded budget.
+    ///
+    /// Budgets are only enforced when the runner has a usage tracker.
+    pub fn with_budget_notifier(mut self, notifier: Arc<dyn BudgetNotifier>) -> Self {
+        self.budget_notifier = Some(notifier);
+        self
+    }
+
+    /// Gets the budget notifier if enabled.
+    pub fn budget_notifier(&self) -> Option<&Arc<dyn BudgetNotifier>> {
+        self.budget_notifier.as_ref()
+    }
+
+    /// Enables plan-and-execute mode.
+    ///
+    /// Runs first ask the model for a step plan, then execute it one step
+    /// at a time, re-planning after failed steps within the iteration
+    /// budget of `config`. Progress is emitted as `AgentEvent::PlanCreated`
+    /// and `AgentEvent::PlanStepProgress` events.
+    pub fn with_planning(mut self, config: PlanningConfig) -> Self {
+        self.planning = Some(config);
+        self
+    }
+
+    /// Gets the plan-and-execute configuration if enabled.
+    pub fn planning(&self) -> Option<&PlanningConfig> {
+        self.planning.as_ref()
+    }
+
+    /// Enables session leases, for instances sharing their sessions.
+    ///
+    /// See [`SessionLeases`].
+    pub fn with_session_leases(mut self, leases: SessionLeases) -> Self {
+        self.leases = Some(leases);
+        self
+    }
+
+    /// Gets the session leases if enabled.
+    pub fn session_leases(&self) -> Option<&SessionLeases> {
+        self.leases.as_ref()
+    }
+
+    /// Gets the registry of the streamed runs in progress.
+    pub fn steering(&self) -> &Arc<SteeringRegistry> {
+        &self.steering
+    }
+
     /// Registers an active session with its abort handle.
     ///
     /// # Arguments
@@ -464,8 +644,19 @@ impl AgentRunner {
 
 impl AgentRunner {
     /// Gets the agent configuration.
-    pub fn config(&self) -> &Arc<aisopod_config::AisopodConfig> {
-        &self.config
+    ///
+    /// Runs keep the configuration they started with, so this is a snapshot
+    /// that may be outdated by a concurrent [`update_config`](Self::update_config).
+    pub fn config(&self) -> Arc<aisopod_config::AisopodConfig> {
+        self.config.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replaces the agent configuration for the runs started from now on.
+    ///
+    /// Agents, model aliases and session policies of the new configuration
+    /// apply to the next runs; runs in progress finish with the old one.
+    pub fn update_config(&self, config: Arc<aisopod_config::AisopodConfig>) {
+        *self.config.write().unwrap_or_else(|e| e.into_inner()) = config;
     }
 
     /// Gets the provider registry.
@@ -498,13 +689,14 @@ impl AgentRunner {
     /// Returns the final result of the agent run, or an error if
     /// the run failed.
     pub async fn run_and_get_result(&self, params: AgentRunParams) -> Result<AgentRunResult> {
+        let config = self.config();
         let pipeline = if let (Some(memory_pipeline), Some(memory_manager)) = 
             (self.memory_pipeline.clone(), self.memory_manager.clone())
         {
             // Use memory-enabled pipeline with skills if available
             if let Some(ref tracker) = self.usage_tracker {
                 crate::pipeline::AgentPipeline::new_with_skills_memory_and_usage_tracker(
-                    self.config.clone(),
+                    config.clone(),
                     self.providers.clone(),
                     self.tools.clone(),
                     self.sessions.clone(),
@@ -515,7 +707,7 @@ impl AgentRunner {
                 )
             } else {
                 crate::pipeline::AgentPipeline::new_with_skills_and_memory(
-                    self.config.clone(),
+                    config.clone(),
                     self.providers.clone(),
                     self.tools.clone(),
                     self.sessions.clone(),
@@ -528,7 +720,7 @@ impl AgentRunner {
             // Use pipeline with usage tracker only
             if let Some(ref skills) = self.skills {
                 crate::pipeline::AgentPipeline::new_with_skills_and_usage_tracker(
-                    self.config.clone(),
+                    config.clone(),
                     self.providers.clone(),
                     self.tools.clone(),
                     self.sessions.clone(),
@@ -537,7 +729,7 @@ impl AgentRunner {
                 )
             } else {
                 crate::pipeline::AgentPipeline::new_with_usage_tracker(
-                    self.config.clone(),
+                    config.clone(),
                     self.providers.clone(),
                     self.tools.clone(),
                     self.sessions.clone(),
@@ -548,7 +740,7 @@ impl AgentRunner {
             // Use basic pipeline with skills if available
             if let Some(ref skills) = self.skills {
                 crate::pipeline::AgentPipeline::new_with_skills(
-                    self.config.clone(),
+                    config.clone(),
                     self.providers.clone(),
                     self.tools.clone(),
                     self.sessions.clone(),
@@ -556,20 +748,45 @@ impl AgentRunner {
                 )
             } else {
                 crate::pipeline::AgentPipeline::new(
-                    self.config.clone(),
+                    config.clone(),
                     self.providers.clone(),
                     self.tools.clone(),
                     self.sessions.clone(),
                 )
             }
         };
+        let pipeline = match self.handoffs.clone() {
+            Some(handoffs) => pipeline.with_handoffs(handoffs),
+            None => pipeline,
+        };
+        let pipeline = match self.budget_notifier.clone() {
+            Some(notifier) => pipeline.with_budget_notifier(notifier),
+            None => pipeline,
+        };
         // Create a dummy event channel that we ignore
         let (event_tx, _) = tokio::sync::mpsc::channel(100);
-        pipeline.execute(&params, &event_tx).await
+        let run = async {
+            match &self.planning {
+                Some(planning) => {
+                    crate::planning::execute_with_plan(&pipeline, &params, planning, &event_tx)
+                        .await
+                }
+                None => pipeline.execute(&params, &event_tx).await,
+            }
+        };
+        with_session_lease(self.leases.as_ref(), &params.session_key, run).await
     }
 
     /// Runs an agent with the given parameters.
     ///
+    /// Runs of the same session execute one at a time. A run submitted
+    /// while another run of its session is in progress is handled according
+    /// to the `session.messages.follow_up` policy: it waits for its turn,
+    /// restarts the running run with its messages appended, or is injected
+    /// into the running run. Injected messages are answered on the stream
+    /// of the running run, and the stream returned for them ends without
+    /// events.
+    ///
     /// # Arguments
     ///
     /// * `params` - The parameters for the agent run.
@@ -582,8 +799,18 @@ impl AgentRunner {
         // Create a channel for streaming events
         let (event_tx, event_rx) = tokio::sync::mpsc::channel(100);
 
+        let policy = self.config().session.messages.follow_up;
+        let (steered_run, params) = match self.steering.submit(policy, params) {
+            Submission::Start(run, params) => (run, params),
+            Submission::Injected => {
+                tracing::debug!("Injected follow-up into the running run of its session");
+                return Ok(crate::pipeline::AgentRunStream::new(event_rx));
+            }
+        };
+        let steering = self.steering.clone();
+
         // Clone the pipeline dependencies
-        let config = self.config.clone();
+        let config = self.config();
         let providers = self.providers.clone();
         let tools = self.tools.clone();
         let sessions = self.sessions.clone();
@@ -593,9 +820,32 @@ impl AgentRunner {
         let memory_manager = self.memory_manager.clone();
         let usage_tracker = self.usage_tracker.clone();
         let skills = self.skills.clone();
+        let handoffs = self.handoffs.clone();
+        let budget_notifier = self.budget_notifier.clone();
+        let planning = self.planning.clone();
+        let leases = self.leases.clone();
+
+        // The run continues the trace of the request that started it
+        let span = tracing::info_span!(
+            "agent.run",
+            session = %params.session_key,
+            agent = ?params.agent_id,
+        );
 
         // Spawn the pipeline execution
         tokio::spawn(async move {
+            steered_run.wait_turn().await;
+            if steered_run.abort_handle().is_aborted() {
+                // Restarted by a follow-up before it started
+                steering.finish(&steered_run);
+                let _ = event_tx
+                    .send(crate::types::AgentEvent::Error {
+                        message: "Agent run superseded by a follow-up message".to_string(),
+                    })
+                    .await;
+                return;
+            }
+
             let pipeline = if let (Some(memory_pipeline), Some(memory_manager)) =
                 (memory_pipeline, memory_manager)
             {
@@ -662,7 +912,36 @@ impl AgentRunner {
                     crate::pipeline::AgentPipeline::new(config, providers, tools, sessions)
                 }
             };
-            if let Err(e) = pipeline.execute(&params, &event_tx).await {
+            let pipeline = match handoffs {
+                Some(handoffs) => pipeline.with_handoffs(handoffs),
+                None => pipeline,
+            };
+            let pipeline = match budget_notifier {
+                Some(notifier) => pipeline.with_budget_notifier(notifier),
+                None => pipeline,
+            };
+            let pipeline = pipeline.with_steered_run(steered_run.clone());
+            let run = async {
+                match planning {
+                    Some(planning) => {
+                        crate::planning::execute_with_plan(
+                            &pipeline, &params, &planning, &event_tx,
+                        )
+                        .await
+                    }
+                    None => pipeline.execute(&params, &event_tx).await,
+                }
+            };
+            let outcome = with_session_lease(leases.as_ref(), &params.session_key, run).await;
+            let unanswered = steering.finish(&steered_run);
+            if !unanswered.is_empty() {
+                tracing::warn!(
+                    "Dropping {} follow-up(s) injected into the failed run of session {}",
+                    unanswered.len(),
+                    params.session_key
+                );
+            }
+            if let Err(e) = outcome {
                 let error_message: String = e.to_string();
                 let _ = event_tx
                     .send(crate::types::AgentEvent::Error {
@@ -670,7 +949,8 @@ impl AgentRunner {
                     })
                     .await;
             }
-        });
+        }
+        .instrument(span));
 
         // Return the stream
         Ok(crate::pipeline::AgentRunStream::new(event_rx))
@@ -693,6 +973,26 @@ impl AgentRunner {
     }
 }
 
+#[async_trait::async_trait]
+impl ConfigApplier for AgentRunner {
+    fn name(&self) -> &str {
+        "agent runner"
+    }
+
+    fn sections(&self) -> &[&str] {
+        &["agents", "models", "session"]
+    }
+
+    async fn apply(
+        &self,
+        _old: &aisopod_config::AisopodConfig,
+        new: &aisopod_config::AisopodConfig,
+    ) -> Result<()> {
+        self.update_config(Arc::new(new.clone()));
+        Ok(())
+    }
+}
+
 impl SubagentRunnerExt for AgentRunner {
     fn get_max_subagent_depth(&self) -> usize {
         // Use the global config default - need to check each agent
@@ -702,7 +1002,7 @@ impl SubagentRunnerExt for AgentRunner {
 
     fn validate_model_allowlist(&self, agent_id: &str, model: &str) -> Result<()> {
         // Get the agent config to check its allowlist
-        let agent_config = resolution::resolve_agent_config(self.config(), agent_id)
+        let agent_config = resolution::resolve_agent_config(&self.config(), agent_id)
             .map_err(|e| anyhow::anyhow!("Failed to resolve agent config: {}", e))?;
 
         if let Some(ref allowlist) = agent_config.subagent_allowed_models {
@@ -745,7 +1045,29 @@ mod tests {
         let runner = AgentRunner::new(config, providers, tools, sessions);
 
         // Just verify it compiles - full tests will be added in subsequent issues
-        assert_eq!(runner.config.meta.version, "1.0");
+        assert_eq!(runner.config().meta.version, "1.0");
+    }
+
+    #[tokio::test]
+    async fn test_agent_runner_applies_reloaded_config() {
+        let config = Arc::new(aisopod_config::AisopodConfig::default());
+        let providers = Arc::new(aisopod_provider::ProviderRegistry::new());
+        let tools = Arc::new(aisopod_tools::ToolRegistry::new());
+        let sessions = Arc::new(
+            SessionStore::new_in_memory().expect("Failed to create in-memory session store"),
+        );
+        let runner = Arc::new(AgentRunner::new(config.clone(), providers, tools, sessions));
+
+        let mut new = aisopod_config::AisopodConfig::default();
+        new.models
+            .aliases
+            .insert("fast".to_string(), "openai/gpt-4o-mini".to_string());
+        let report = aisopod_config::ConfigReloader::new()
+            .with_applier(runner.clone())
+            .apply(&config, &new)
+            .await;
+        assert_eq!(report.applied, ["agent runner"]);
+        assert_eq!(runner.config().models.resolve_alias("fast"), "openai/gpt-4o-mini");
     }
 
     #[test]
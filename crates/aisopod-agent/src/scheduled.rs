@@ -0,0 +1,154 @@
+//! Cron-triggered autonomous agent runs.
+//!
+//! Schedules configured in `agents.schedules` are registered with a
+//! [`JobScheduler`], typically the persistent `SqliteJobScheduler`, as
+//! recurring jobs whose command is the schedule's prompt. When a job comes
+//! due the scheduler hands it to an [`AgentJobRunner`], which runs the
+//! agent headlessly through the [`AgentRunner`] and sends the reply to the
+//! schedule's delivery target with a [`MessageSender`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aisopod_config::types::AgentSchedule;
+use aisopod_provider::{Message, MessageContent, Role};
+use aisopod_tools::{
+    JobRunner, JobScheduler, MessageSender, ScheduledJob, ToolContext, ToolContextResolver,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use tracing::{debug, info};
+
+use crate::resolution::resolve_tool_context;
+use crate::runner::AgentRunner;
+use crate::types::AgentRunParams;
+
+/// Prefix of the IDs of jobs registered for configured schedules.
+pub const SCHEDULE_JOB_PREFIX: &str = "agent-schedule:";
+
+/// Returns the ID of the job registered for the schedule `schedule_id`.
+pub fn schedule_job_id(schedule_id: &str) -> String {
+    format!("{}{}", SCHEDULE_JOB_PREFIX, schedule_id)
+}
+
+/// Registers `schedules` as recurring jobs of `scheduler`.
+///
+/// Jobs of schedules that are no longer configured are removed, so the
+/// scheduler mirrors the configuration after a reload.
+pub async fn register_schedules(
+    scheduler: &dyn JobScheduler,
+    schedules: &[AgentSchedule],
+) -> Result<Vec<ScheduledJob>> {
+    let job_ids: Vec<String> = schedules.iter().map(|s| schedule_job_id(&s.id)).collect();
+    for job in scheduler.list().await? {
+        if job.id.starts_with(SCHEDULE_JOB_PREFIX) && !job_ids.contains(&job.id) {
+            debug!("Removing job '{}' of a removed schedule", job.id);
+            scheduler.remove(&job.id).await?;
+        }
+    }
+
+    let mut jobs = Vec::with_capacity(schedules.len());
+    for (schedule, job_id) in schedules.iter().zip(&job_ids) {
+        let job = scheduler
+            .schedule(job_id, &schedule.cron, &schedule.prompt)
+            .await
+            .map_err(|e| anyhow!("Failed to register schedule '{}': {}", schedule.id, e))?;
+        jobs.push(job);
+    }
+    info!("Registered {} scheduled agent run(s)", jobs.len());
+    Ok(jobs)
+}
+
+/// A [`JobRunner`] running agents headlessly with the job command as prompt.
+///
+/// Jobs of configured schedules run the schedule's agent and deliver the
+/// reply to its target. Jobs created with the cron tool run the agent that
+/// scheduled them, others the agent bound to the job's session, and return
+/// the reply only.
+/// Every run starts a fresh conversation in the session `cron:<job id>`.
+pub struct AgentJobRunner {
+    runner: Arc<AgentRunner>,
+    schedules: HashMap<String, AgentSchedule>,
+    sender: Option<Arc<dyn MessageSender>>,
+}
+
+impl AgentJobRunner {
+    /// Creates a job runner running agents with `runner`.
+    pub fn new(runner: Arc<AgentRunner>) -> Self {
+        Self {
+            runner,
+            schedules: HashMap::new(),
+            sender: None,
+        }
+    }
+
+    /// Sets the configured schedules, whose jobs run the schedule's agent.
+    pub fn with_schedules(mut self, schedules: Vec<AgentSchedule>) -> Self {
+        self.schedules = schedules
+            .into_iter()
+            .map(|schedule| (schedule_job_id(&schedule.id), schedule))
+            .collect();
+        self
+    }
+
+    /// Sets the sender delivering replies to the schedules' targets.
+    pub fn with_sender(mut self, sender: Arc<dyn MessageSender>) -> Self {
+        self.sender = Some(sender);
+        self
+    }
+}
+
+#[async_trait]
+impl JobRunner for AgentJobRunner {
+    async fn run(&self, job: &ScheduledJob) -> Result<String> {
+        let schedule = self.schedules.get(&job.id);
+        let agent_id = schedule
+            .map(|schedule| schedule.agent_id.clone())
+            .or_else(|| job.owner.as_ref().map(|owner| owner.agent_id.clone()));
+        debug!(
+            "Running scheduled job '{}' with agent {:?}",
+            job.id, agent_id
+        );
+
+        let prompt = Message {
+            role: Role::User,
+            content: MessageContent::Text(job.command.clone()),
+            tool_calls: None,
+            tool_call_id: None,
+        };
+        let pin_agent = agent_id.is_some();
+        let mut params = AgentRunParams::new(format!("cron:{}", job.id), vec![prompt], agent_id);
+        // Configured schedules and jobs scheduled by an agent run their agent
+        // whatever the session's binding
+        params.pin_agent = pin_agent;
+        let result = self.runner.run_and_get_result(params).await?;
+
+        if let Some(target) = schedule.and_then(|schedule| schedule.deliver.as_ref()) {
+            let sender = self.sender.as_ref().ok_or_else(|| {
+                anyhow!(
+                    "Job '{}' has a delivery target but no message sender is configured",
+                    job.id
+                )
+            })?;
+            sender
+                .send_message(
+                    &target.channel,
+                    &result.response,
+                    target.account.as_deref(),
+                    Some(&target.peer),
+                )
+                .await
+                .map_err(|e| anyhow!("Failed to deliver job '{}' reply: {}", job.id, e))?;
+        }
+
+        Ok(result.response)
+    }
+}
+
+/// Scheduled tool invocations run in the workspace and sandbox of the agent
+/// that scheduled them, as resolved from the current configuration.
+impl ToolContextResolver for AgentRunner {
+    fn tool_context(&self, agent_id: &str, session_key: &str) -> ToolContext {
+        resolve_tool_context(&self.config(), agent_id, session_key)
+    }
+}
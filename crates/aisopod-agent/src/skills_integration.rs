@@ -5,6 +5,7 @@
 //! - Merging skill system prompt fragments into agent prompts
 //! - Collecting tools from assigned skills
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// Trait for skills that can be integrated with agents.
@@ -24,6 +25,12 @@ pub trait Skill: Send + Sync + std::fmt::Debug {
 
     /// Returns the set of tools this skill provides.
     fn tools(&self) -> Vec<Arc<dyn Tool>>;
+
+    /// Returns the named prompt templates this skill provides, which agents
+    /// can select with their `prompt_template`.
+    fn prompt_templates(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
 }
 
 /// Metadata describing a skill's identity and requirements.
@@ -146,6 +153,17 @@ pub fn collect_skill_tools(
         .collect()
 }
 
+/// Collects the prompt templates of the assigned skills.
+///
+/// When several skills provide a template of the same name, the template
+/// of the skill assigned last wins.
+pub fn collect_skill_templates(skills: &[Arc<dyn Skill>]) -> HashMap<String, String> {
+    skills
+        .iter()
+        .flat_map(|skill| skill.prompt_templates())
+        .collect()
+}
+
 /// A skill registry implementation for agent skill management.
 ///
 /// This registry stores skills and allows them to be assigned to agents.
@@ -0,0 +1,330 @@
+//! Steering of running agents by follow-up user messages.
+//!
+//! Runs started through [`AgentRunner::run`](crate::AgentRunner::run) are
+//! tracked per session in a [`SteeringRegistry`], so a message arriving
+//! while a run of its session is in progress is handled according to the
+//! configured `session.messages.follow_up` policy instead of racing it:
+//!
+//! - [`FollowUpPolicy::Queue`] runs the message once the runs before it
+//!   have finished.
+//! - [`FollowUpPolicy::Restart`] aborts the running run and starts a new
+//!   one with the follow-up appended to the aborted run's messages.
+//! - [`FollowUpPolicy::Interrupt`] injects the message into the running
+//!   loop, which announces it as an `AgentEvent::Interruption` and answers
+//!   it before completing.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use aisopod_config::types::FollowUpPolicy;
+use aisopod_provider::Message;
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use tokio_util::sync::CancellationToken;
+
+use crate::abort::AbortHandle;
+use crate::types::AgentRunParams;
+
+/// A run tracked by the [`SteeringRegistry`].
+#[derive(Debug)]
+pub struct SteeredRun {
+    id: u64,
+    session_key: String,
+    messages: Vec<Message>,
+    abort_handle: AbortHandle,
+    /// The run submitted before this one, until it has finished.
+    predecessor: Mutex<Option<Arc<SteeredRun>>>,
+    /// Cancelled once the run has finished executing.
+    finished: CancellationToken,
+    /// Follow-ups injected into the run, or `None` once it stopped
+    /// accepting them.
+    follow_ups: Mutex<Option<Vec<Message>>>,
+}
+
+impl SteeredRun {
+    fn new(
+        id: u64,
+        session_key: &str,
+        messages: Vec<Message>,
+        predecessor: Option<Arc<SteeredRun>>,
+    ) -> Self {
+        Self {
+            id,
+            session_key: session_key.to_string(),
+            messages,
+            abort_handle: AbortHandle::new(session_key.to_string()),
+            predecessor: Mutex::new(predecessor),
+            finished: CancellationToken::new(),
+            follow_ups: Mutex::new(Some(Vec::new())),
+        }
+    }
+
+    /// Returns the session key of the run.
+    pub fn session_key(&self) -> &str {
+        &self.session_key
+    }
+
+    /// Returns the abort handle cancelling the run.
+    pub fn abort_handle(&self) -> &AbortHandle {
+        &self.abort_handle
+    }
+
+    /// Waits until the runs of the session submitted before this one have
+    /// finished.
+    pub async fn wait_turn(&self) {
+        let predecessor = self.predecessor.lock().unwrap().take();
+        if let Some(predecessor) = predecessor {
+            predecessor.finished.cancelled().await;
+        }
+    }
+
+    /// Takes the follow-ups injected since the last call.
+    pub fn take_follow_ups(&self) -> Vec<Message> {
+        let mut follow_ups = self.follow_ups.lock().unwrap();
+        follow_ups.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Takes the follow-ups injected since the last call, and stops
+    /// accepting new ones if there are none.
+    ///
+    /// Called by a run about to complete, which answers the returned
+    /// follow-ups before completing, if any.
+    pub fn complete(&self) -> Vec<Message> {
+        let mut follow_ups = self.follow_ups.lock().unwrap();
+        match follow_ups.as_mut().map(std::mem::take) {
+            Some(taken) if !taken.is_empty() => taken,
+            _ => {
+                *follow_ups = None;
+                Vec::new()
+            }
+        }
+    }
+
+    /// Returns true if the run has not completed yet.
+    fn is_open(&self) -> bool {
+        self.follow_ups.lock().unwrap().is_some()
+    }
+
+    /// Injects `messages` into the run, returning false if it already
+    /// stopped accepting follow-ups.
+    fn inject(&self, messages: &[Message]) -> bool {
+        match self.follow_ups.lock().unwrap().as_mut() {
+            Some(follow_ups) => {
+                follow_ups.extend_from_slice(messages);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// The outcome of submitting a run to the [`SteeringRegistry`].
+#[derive(Debug)]
+pub enum Submission {
+    /// A new run should be started with the given parameters, once
+    /// [`SteeredRun::wait_turn`] returns.
+    Start(Arc<SteeredRun>, AgentRunParams),
+    /// The messages were injected into the session's running run.
+    Injected,
+}
+
+/// A registry of the runs in progress for each session.
+///
+/// Runs of a session execute one at a time, in the order they were
+/// submitted.
+#[derive(Debug, Default)]
+pub struct SteeringRegistry {
+    /// The latest run submitted for each session.
+    runs: DashMap<String, Arc<SteeredRun>>,
+    next_id: AtomicU64,
+}
+
+impl SteeringRegistry {
+    /// Creates a new empty `SteeringRegistry`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Submits a run of `params`, applying `policy` if a run of the same
+    /// session is in progress.
+    pub fn submit(&self, policy: FollowUpPolicy, mut params: AgentRunParams) -> Submission {
+        let entry = self.runs.entry(params.session_key.clone());
+        let predecessor = match &entry {
+            Entry::Occupied(active) => Some(active.get().clone()),
+            Entry::Vacant(_) => None,
+        };
+        if let Some(active) = &predecessor {
+            match policy {
+                FollowUpPolicy::Queue => {}
+                FollowUpPolicy::Restart => {
+                    if active.is_open() {
+                        active.abort_handle.abort();
+                        let mut messages = active.messages.clone();
+                        messages.append(&mut params.messages);
+                        params.messages = messages;
+                    }
+                }
+                FollowUpPolicy::Interrupt => {
+                    if active.inject(&params.messages) {
+                        return Submission::Injected;
+                    }
+                }
+            }
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let run = Arc::new(SteeredRun::new(
+            id,
+            &params.session_key,
+            params.messages.clone(),
+            predecessor,
+        ));
+        entry.insert(run.clone());
+        Submission::Start(run, params)
+    }
+
+    /// Stops tracking `run` once it has executed, letting the next run of
+    /// its session start.
+    ///
+    /// Returns the follow-ups injected into the run that it did not answer,
+    /// which happens when it failed or was aborted.
+    pub fn finish(&self, run: &SteeredRun) -> Vec<Message> {
+        self.runs
+            .remove_if(&run.session_key, |_, active| active.id == run.id);
+        let unanswered = run.follow_ups.lock().unwrap().take().unwrap_or_default();
+        run.finished.cancel();
+        unanswered
+    }
+
+    /// Returns true if a run of the session is in progress or waiting.
+    pub fn is_active(&self, session_key: &str) -> bool {
+        self.runs.contains_key(session_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aisopod_provider::{MessageContent, Role};
+
+    const SESSION: &str = "test-agent:telegram:bot:dm:alice";
+
+    fn params(text: &str) -> AgentRunParams {
+        let message = Message {
+            role: Role::User,
+            content: MessageContent::Text(text.to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+        };
+        AgentRunParams::new(SESSION, vec![message], None::<String>)
+    }
+
+    fn texts(messages: &[Message]) -> Vec<String> {
+        messages
+            .iter()
+            .filter_map(|message| match &message.content {
+                MessageContent::Text(text) => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn start(submission: Submission) -> (Arc<SteeredRun>, AgentRunParams) {
+        match submission {
+            Submission::Start(run, params) => (run, params),
+            Submission::Injected => panic!("expected a new run"),
+        }
+    }
+
+    #[test]
+    fn test_queue_starts_separate_run() {
+        let registry = SteeringRegistry::new();
+        let (first, _) = start(registry.submit(FollowUpPolicy::Queue, params("one")));
+        let (_, second) = start(registry.submit(FollowUpPolicy::Queue, params("two")));
+
+        assert!(!first.abort_handle().is_aborted());
+        assert_eq!(texts(&second.messages), vec!["two"]);
+    }
+
+    #[test]
+    fn test_restart_aborts_and_combines_messages() {
+        let registry = SteeringRegistry::new();
+        let (first, _) = start(registry.submit(FollowUpPolicy::Restart, params("one")));
+        let (_, second) = start(registry.submit(FollowUpPolicy::Restart, params("two")));
+        let (_, third) = start(registry.submit(FollowUpPolicy::Restart, params("three")));
+
+        assert!(first.abort_handle().is_aborted());
+        assert_eq!(texts(&second.messages), vec!["one", "two"]);
+        assert_eq!(texts(&third.messages), vec!["one", "two", "three"]);
+    }
+
+    #[test]
+    fn test_restart_after_completion_starts_fresh() {
+        let registry = SteeringRegistry::new();
+        let (first, _) = start(registry.submit(FollowUpPolicy::Restart, params("one")));
+        assert!(first.complete().is_empty());
+
+        let (_, second) = start(registry.submit(FollowUpPolicy::Restart, params("two")));
+
+        assert!(!first.abort_handle().is_aborted());
+        assert_eq!(texts(&second.messages), vec!["two"]);
+    }
+
+    #[test]
+    fn test_interrupt_injects_into_running_run() {
+        let registry = SteeringRegistry::new();
+        let (first, _) = start(registry.submit(FollowUpPolicy::Interrupt, params("one")));
+
+        assert!(matches!(
+            registry.submit(FollowUpPolicy::Interrupt, params("two")),
+            Submission::Injected
+        ));
+        assert_eq!(texts(&first.take_follow_ups()), vec!["two"]);
+        assert!(first.take_follow_ups().is_empty());
+    }
+
+    #[test]
+    fn test_interrupt_after_finish_starts_new_run() {
+        let registry = SteeringRegistry::new();
+        let (first, _) = start(registry.submit(FollowUpPolicy::Interrupt, params("one")));
+        assert!(matches!(
+            registry.submit(FollowUpPolicy::Interrupt, params("two")),
+            Submission::Injected
+        ));
+
+        // Pending follow-ups keep the run open until answered
+        assert_eq!(texts(&first.complete()), vec!["two"]);
+        assert!(first.complete().is_empty());
+
+        let (_, third) = start(registry.submit(FollowUpPolicy::Interrupt, params("three")));
+        assert_eq!(texts(&third.messages), vec!["three"]);
+    }
+
+    #[test]
+    fn test_finish_untracks_run_and_returns_unanswered_follow_ups() {
+        let registry = SteeringRegistry::new();
+        let (first, _) = start(registry.submit(FollowUpPolicy::Interrupt, params("one")));
+        registry.submit(FollowUpPolicy::Interrupt, params("two"));
+
+        assert_eq!(texts(&registry.finish(&first)), vec!["two"]);
+        assert!(!registry.is_active(SESSION));
+    }
+
+    #[tokio::test]
+    async fn test_wait_turn_serializes_runs_of_session() {
+        let registry = SteeringRegistry::new();
+        let (first, _) = start(registry.submit(FollowUpPolicy::Queue, params("one")));
+        let (second, _) = start(registry.submit(FollowUpPolicy::Queue, params("two")));
+
+        first.wait_turn().await;
+        let waiting =
+            tokio::time::timeout(std::time::Duration::from_millis(20), second.wait_turn()).await;
+        assert!(waiting.is_err());
+
+        registry.finish(&first);
+        second.wait_turn().await;
+        assert!(registry.is_active(SESSION));
+        registry.finish(&second);
+        assert!(!registry.is_active(SESSION));
+    }
+}
@@ -0,0 +1,451 @@
+//! Streaming of partial agent replies to chat channels.
+//!
+//! Chat platforms show a reply only once it is sent, so waiting for the
+//! whole run leaves the user staring at nothing. This module flushes the
+//! text deltas of a run to a [`ReplySink`] while the model is still writing:
+//!
+//! - Where the sink can edit messages, the reply is sent as soon as text
+//!   arrives and then edited in place, at most once per
+//!   [`StreamingReplyConfig::edit_interval`].
+//! - Elsewhere, the reply is sent in chunks of about
+//!   [`StreamingReplyConfig::chunk_size`] characters, split at paragraph,
+//!   line, or word boundaries.
+//!
+//! If the run hands the session off to another agent, the user is told who
+//! handles the conversation next in a message after the reply.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+use crate::types::{AgentEvent, AgentRunResult};
+
+/// Destination of a streamed reply, such as the chat a message came from.
+#[async_trait]
+pub trait ReplySink: Send + Sync {
+    /// Sends a new message.
+    ///
+    /// Returns the ID of the message if it can be edited with
+    /// [`ReplySink::edit`], or `None` if the destination cannot edit messages.
+    async fn send(&self, text: &str) -> Result<Option<String>>;
+
+    /// Replaces the text of a message previously sent with [`ReplySink::send`].
+    async fn edit(&self, message_id: &str, text: &str) -> Result<()>;
+}
+
+/// Configuration for streaming replies to a [`ReplySink`].
+#[derive(Debug, Clone)]
+pub struct StreamingReplyConfig {
+    /// Minimum time between two edits of the same message.
+    pub edit_interval: Duration,
+    /// Number of characters collected before a chunk is sent, when the
+    /// sink cannot edit messages.
+    pub chunk_size: usize,
+    /// Maximum number of characters in one message; longer replies
+    /// continue in a new message.
+    pub max_message_length: Option<usize>,
+}
+
+impl Default for StreamingReplyConfig {
+    fn default() -> Self {
+        Self {
+            edit_interval: Duration::from_secs(1),
+            chunk_size: 1000,
+            max_message_length: None,
+        }
+    }
+}
+
+/// Writes a growing reply to a sink, by editing or in chunks.
+struct ReplyWriter<'a> {
+    sink: &'a dyn ReplySink,
+    config: &'a StreamingReplyConfig,
+    /// Whether the sink has so far returned editable message IDs.
+    edits: bool,
+    /// The whole reply received so far.
+    text: String,
+    /// End of the text that is final in messages already sent.
+    committed: usize,
+    /// End of the text shown in the message being edited.
+    shown: usize,
+    /// ID of the message being edited.
+    message_id: Option<String>,
+    /// When the message being edited was last updated.
+    last_flush: Option<Instant>,
+}
+
+impl<'a> ReplyWriter<'a> {
+    fn new(sink: &'a dyn ReplySink, config: &'a StreamingReplyConfig) -> Self {
+        Self {
+            sink,
+            config,
+            edits: true,
+            text: String::new(),
+            committed: 0,
+            shown: 0,
+            message_id: None,
+            last_flush: None,
+        }
+    }
+
+    fn push(&mut self, delta: &str) {
+        self.text.push_str(delta);
+    }
+
+    fn pending(&self) -> &str {
+        &self.text[self.committed..]
+    }
+
+    fn message_limit(&self) -> usize {
+        self.config.max_message_length.unwrap_or(usize::MAX)
+    }
+
+    /// Returns when the message being edited should next be updated.
+    fn next_edit(&self) -> Option<Instant> {
+        if !self.edits || self.text.len() <= self.shown {
+            return None;
+        }
+        Some(match self.last_flush {
+            Some(last) => last + self.config.edit_interval,
+            None => Instant::now(),
+        })
+    }
+
+    /// Returns whether enough has changed to update the channel now.
+    fn is_due(&self) -> bool {
+        if self.edits {
+            self.next_edit().is_some_and(|at| at <= Instant::now())
+        } else {
+            self.pending().chars().count() >= self.config.chunk_size
+        }
+    }
+
+    /// Shows the text received so far, completely if `last` is set.
+    async fn flush(&mut self, last: bool) -> Result<()> {
+        if self.edits {
+            self.flush_edits().await?;
+        }
+        // The sink may have turned out unable to edit during the flush
+        if !self.edits {
+            self.flush_chunks(last).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush_edits(&mut self) -> Result<()> {
+        // Freeze full messages and continue the reply in a new one
+        let limit = self.message_limit();
+        while self.pending().chars().count() > limit {
+            let end = split_point(self.pending(), limit);
+            let part = self.pending()[..end].trim().to_string();
+            self.deliver(&part).await?;
+            self.committed += end;
+            self.shown = self.committed;
+            self.message_id = None;
+        }
+
+        if self.text.len() > self.shown {
+            let part = self.pending().trim().to_string();
+            if !part.is_empty() {
+                self.deliver(&part).await?;
+                self.last_flush = Some(Instant::now());
+            }
+            self.shown = self.text.len();
+            if !self.edits {
+                self.committed = self.shown;
+            }
+        }
+        Ok(())
+    }
+
+    async fn flush_chunks(&mut self, last: bool) -> Result<()> {
+        let limit = self.config.chunk_size.min(self.message_limit()).max(1);
+        loop {
+            let pending = self.pending();
+            let count = pending.chars().count();
+            if count == 0 || (!last && count < self.config.chunk_size) {
+                return Ok(());
+            }
+            let end = if last && count <= limit {
+                pending.len()
+            } else {
+                split_point(pending, limit)
+            };
+            let part = pending[..end].trim().to_string();
+            if !part.is_empty() {
+                self.sink.send(&part).await?;
+            }
+            self.committed += end;
+        }
+    }
+
+    /// Updates the message being edited, or starts a new one.
+    async fn deliver(&mut self, text: &str) -> Result<()> {
+        match &self.message_id {
+            Some(id) => self.sink.edit(id, text).await,
+            None => {
+                self.message_id = self.sink.send(text).await?;
+                if self.message_id.is_none() {
+                    self.edits = false;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Returns the byte offset at which to end a message of at most `limit`
+/// characters from the start of `text`.
+///
+/// Prefers a paragraph break in the second half of the window, then ending
+/// right before whitespace, then a line break in the second half of the
+/// window, then the last space.
+fn split_point(text: &str, limit: usize) -> usize {
+    let (window_end, at_whitespace) = match text.char_indices().nth(limit) {
+        Some((i, c)) => (i, c.is_whitespace()),
+        None => return text.len(),
+    };
+    let window = &text[..window_end];
+    let late_break = |separator: &str| {
+        window
+            .rfind(separator)
+            .filter(|&i| i >= window.len() / 2)
+            .map(|i| i + separator.len())
+    };
+
+    if let Some(end) = late_break("\n\n") {
+        return end;
+    }
+    if at_whitespace {
+        return window_end;
+    }
+    if let Some(end) = late_break("\n") {
+        return end;
+    }
+    match window.rfind(' ') {
+        Some(i) if i > 0 => i + 1,
+        _ => window_end,
+    }
+}
+
+/// Streams the reply of an agent run to `sink` as its events arrive.
+///
+/// Returns the final result of the run once the event stream closes, or
+/// `None` if the run ended without completing. If the run produced no text
+/// deltas, its final response is sent instead. A handoff during the run is
+/// announced in a separate message after the reply.
+pub(crate) async fn stream_reply(
+    mut events: mpsc::Receiver<AgentEvent>,
+    sink: &dyn ReplySink,
+    config: &StreamingReplyConfig,
+) -> Result<Option<AgentRunResult>> {
+    let mut writer = ReplyWriter::new(sink, config);
+    let mut completed = None;
+    let mut handed_off_to = None;
+
+    loop {
+        let next_edit = writer.next_edit();
+        tokio::select! {
+            event = events.recv() => match event {
+                Some(AgentEvent::TextDelta { text, .. }) => {
+                    writer.push(&text);
+                    if writer.is_due() {
+                        writer.flush(false).await?;
+                    }
+                }
+                Some(AgentEvent::Complete { result }) => completed = Some(result),
+                Some(AgentEvent::Handoff { to_agent, .. }) => handed_off_to = Some(to_agent),
+                Some(_) => {}
+                None => break,
+            },
+            _ = tokio::time::sleep_until(next_edit.unwrap_or_else(Instant::now)), if next_edit.is_some() => {
+                writer.flush(false).await?;
+            }
+        }
+    }
+
+    if writer.text.is_empty() {
+        if let Some(result) = &completed {
+            writer.push(&result.response);
+        }
+    }
+    writer.flush(true).await?;
+    if let Some(agent) = handed_off_to {
+        sink.send(&handoff_notice(&agent)).await?;
+    }
+    Ok(completed)
+}
+
+/// Returns the message telling the user that `agent` took over the
+/// conversation.
+fn handoff_notice(agent: &str) -> String {
+    format!("You have been handed off to agent '{}'.", agent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Sink recording the messages it holds, optionally without edits.
+    struct RecordingSink {
+        editable: bool,
+        messages: Mutex<Vec<String>>,
+        edits: Mutex<usize>,
+    }
+
+    impl RecordingSink {
+        fn new(editable: bool) -> Self {
+            Self {
+                editable,
+                messages: Mutex::new(Vec::new()),
+                edits: Mutex::new(0),
+            }
+        }
+
+        fn messages(&self) -> Vec<String> {
+            self.messages.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl ReplySink for RecordingSink {
+        async fn send(&self, text: &str) -> Result<Option<String>> {
+            let mut messages = self.messages.lock().unwrap();
+            messages.push(text.to_string());
+            Ok(self.editable.then(|| (messages.len() - 1).to_string()))
+        }
+
+        async fn edit(&self, message_id: &str, text: &str) -> Result<()> {
+            let index: usize = message_id.parse()?;
+            self.messages.lock().unwrap()[index] = text.to_string();
+            *self.edits.lock().unwrap() += 1;
+            Ok(())
+        }
+    }
+
+    async fn stream_deltas(
+        deltas: &[&str],
+        sink: &RecordingSink,
+        config: &StreamingReplyConfig,
+    ) -> Option<AgentRunResult> {
+        let (tx, rx) = mpsc::channel(deltas.len() + 1);
+        for delta in deltas {
+            tx.send(AgentEvent::TextDelta {
+                text: delta.to_string(),
+                index: None,
+            })
+            .await
+            .unwrap();
+        }
+        drop(tx);
+        stream_reply(rx, sink, config).await.unwrap()
+    }
+
+    #[test]
+    fn test_split_point_prefers_boundaries() {
+        assert_eq!(split_point("one two three", 9), 8);
+        assert_eq!(split_point("first line\nsecond line", 15), 11);
+        assert_eq!(split_point("para one.\n\npara two", 15), 11);
+        assert_eq!(split_point("three four five", 10), 10);
+        assert_eq!(split_point("abcdefghij", 4), 4);
+    }
+
+    #[tokio::test]
+    async fn test_edits_message_in_place() {
+        let sink = RecordingSink::new(true);
+        let config = StreamingReplyConfig {
+            edit_interval: Duration::ZERO,
+            ..Default::default()
+        };
+        stream_deltas(&["Hello", ", ", "world!"], &sink, &config).await;
+
+        assert_eq!(sink.messages(), vec!["Hello, world!"]);
+        assert!(*sink.edits.lock().unwrap() >= 1);
+    }
+
+    #[tokio::test]
+    async fn test_throttles_edits() {
+        let sink = RecordingSink::new(true);
+        let config = StreamingReplyConfig {
+            edit_interval: Duration::from_secs(3600),
+            ..Default::default()
+        };
+        stream_deltas(&["a ", "b ", "c ", "d"], &sink, &config).await;
+
+        // The first delta is sent at once, the rest only in the final edit
+        assert_eq!(sink.messages(), vec!["a b c d"]);
+        assert_eq!(*sink.edits.lock().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_continues_in_new_message_past_length_limit() {
+        let sink = RecordingSink::new(true);
+        let config = StreamingReplyConfig {
+            edit_interval: Duration::ZERO,
+            max_message_length: Some(12),
+            ..Default::default()
+        };
+        stream_deltas(&["one two ", "three four ", "five"], &sink, &config).await;
+
+        assert_eq!(sink.messages(), vec!["one two", "three four", "five"]);
+    }
+
+    #[tokio::test]
+    async fn test_sends_chunks_without_edits() {
+        let sink = RecordingSink::new(false);
+        let config = StreamingReplyConfig {
+            chunk_size: 10,
+            ..Default::default()
+        };
+        stream_deltas(&["one two ", "three four ", "five"], &sink, &config).await;
+
+        assert_eq!(sink.messages(), vec!["one two", "three four", "five"]);
+        assert_eq!(*sink.edits.lock().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_announces_handoff_after_reply() {
+        let sink = RecordingSink::new(true);
+        let (tx, rx) = mpsc::channel(2);
+        tx.send(AgentEvent::Handoff {
+            from_agent: "triage".to_string(),
+            to_agent: "billing".to_string(),
+            note: "Refund request".to_string(),
+        })
+        .await
+        .unwrap();
+        let result = AgentRunResult::new("Billing will help you.", Vec::new(), Default::default());
+        tx.send(AgentEvent::Complete { result }).await.unwrap();
+        drop(tx);
+
+        stream_reply(rx, &sink, &StreamingReplyConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            sink.messages(),
+            vec![
+                "Billing will help you.",
+                "You have been handed off to agent 'billing'."
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sends_final_response_without_deltas() {
+        let sink = RecordingSink::new(true);
+        let (tx, rx) = mpsc::channel(1);
+        let result = AgentRunResult::new("Done.", Vec::new(), Default::default());
+        tx.send(AgentEvent::Complete { result }).await.unwrap();
+        drop(tx);
+
+        let completed = stream_reply(rx, &sink, &StreamingReplyConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(completed.unwrap().response, "Done.");
+        assert_eq!(sink.messages(), vec!["Done."]);
+    }
+}
@@ -0,0 +1,150 @@
+//! Structured output enforcement for agents with an output schema.
+//!
+//! Agents whose configuration sets `output_schema` must answer with JSON
+//! matching that schema. The pipeline adds the schema to the system prompt,
+//! validates the final response with an [`OutputValidator`], and re-prompts
+//! the model with the validation errors up to `max_output_repairs` times
+//! before failing the run. A valid response is returned parsed in
+//! `AgentRunResult::structured_output`.
+
+use anyhow::Result;
+use serde_json::Value;
+
+use aisopod_tools::ParamValidator;
+
+/// A compiled output schema.
+#[derive(Debug)]
+pub struct OutputValidator {
+    validator: ParamValidator,
+}
+
+impl OutputValidator {
+    /// Compiles an agent's output schema.
+    ///
+    /// Fails if the schema itself is not a valid JSON Schema.
+    pub fn new(schema: &Value) -> Result<Self> {
+        Ok(Self {
+            validator: ParamValidator::new(schema)?,
+        })
+    }
+
+    /// Parses `response` as JSON and checks it against the schema.
+    ///
+    /// The JSON may be wrapped in a Markdown code fence. Returns the parsed
+    /// value, or a description of every problem found.
+    pub fn validate(&self, response: &str) -> Result<Value, Vec<String>> {
+        let value: Value = serde_json::from_str(extract_json(response))
+            .map_err(|e| vec![format!("Response is not valid JSON: {}", e)])?;
+        self.validator
+            .validate("output", &value)
+            .map_err(|invalid| {
+                invalid
+                    .errors
+                    .into_iter()
+                    .map(|error| {
+                        if error.path.is_empty() {
+                            error.message
+                        } else {
+                            format!("{}: {}", error.path, error.message)
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })?;
+        Ok(value)
+    }
+}
+
+/// Returns the system prompt section asking for output matching `schema`.
+pub(crate) fn schema_instructions(schema: &Value) -> String {
+    format!(
+        "## Output format\nYour final response must be a single JSON value matching this \
+         JSON schema, with no other text:\n{}",
+        serde_json::to_string_pretty(schema).unwrap_or_else(|_| schema.to_string())
+    )
+}
+
+/// Returns the message asking the model to fix a response that failed
+/// validation with `errors`.
+pub(crate) fn repair_prompt(errors: &[String]) -> String {
+    let mut prompt = String::from("Your response does not match the required JSON schema:\n");
+    for error in errors {
+        prompt.push_str("- ");
+        prompt.push_str(error);
+        prompt.push('\n');
+    }
+    prompt.push_str("Reply again with only the corrected JSON.");
+    prompt
+}
+
+/// Strips a Markdown code fence around JSON, if present.
+fn extract_json(response: &str) -> &str {
+    let trimmed = response.trim();
+    let Some(rest) = trimmed.strip_prefix("```") else {
+        return trimmed;
+    };
+    // Skip the language tag on the opening fence line
+    let body = rest.split_once('\n').map_or("", |(_, body)| body);
+    body.trim_end().strip_suffix("```").unwrap_or(body).trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn validator() -> OutputValidator {
+        OutputValidator::new(&json!({
+            "type": "object",
+            "properties": {
+                "sentiment": {"type": "string", "enum": ["positive", "negative"]},
+                "score": {"type": "number"}
+            },
+            "required": ["sentiment", "score"]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate_accepts_matching_json() {
+        let value = validator()
+            .validate(r#"{"sentiment": "positive", "score": 0.9}"#)
+            .unwrap();
+        assert_eq!(value["score"], 0.9);
+    }
+
+    #[test]
+    fn test_validate_accepts_fenced_json() {
+        let response = "```json\n{\"sentiment\": \"negative\", \"score\": 0.1}\n```";
+        assert!(validator().validate(response).is_ok());
+    }
+
+    #[test]
+    fn test_validate_reports_schema_errors() {
+        let errors = validator()
+            .validate(r#"{"sentiment": "mixed"}"#)
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().any(|e| e.contains("score")));
+        assert!(errors.iter().any(|e| e.starts_with("/sentiment:")));
+    }
+
+    #[test]
+    fn test_validate_reports_invalid_json() {
+        let errors = validator()
+            .validate("The sentiment is positive.")
+            .unwrap_err();
+        assert!(errors[0].starts_with("Response is not valid JSON"));
+    }
+
+    #[test]
+    fn test_invalid_schema_is_rejected() {
+        assert!(OutputValidator::new(&json!({"type": "no-such-type"})).is_err());
+    }
+
+    #[test]
+    fn test_repair_prompt_lists_errors() {
+        let prompt = repair_prompt(&["/score: \"high\" is not of type \"number\"".to_string()]);
+        assert!(prompt.contains("- /score: \"high\" is not of type \"number\"\n"));
+    }
+}
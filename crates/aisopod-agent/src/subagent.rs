@@ -98,7 +98,7 @@ pub async fn spawn_subagent(
     }
 
     // Step 2: Validate model against allowlist
-    let model = resolution::resolve_agent_model(runner.config(), &params.agent_id)
+    let model = resolution::resolve_agent_model(&runner.config(), &params.agent_id)
         .map_err(|e| anyhow::anyhow!("Failed to resolve agent model: {}", e))?
         .primary;
 
@@ -5,6 +5,7 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 
 /// Session metadata for matching agents to sessions.
 ///
@@ -117,6 +118,16 @@ pub struct AgentRunParams {
     /// Optional thread ID from parent for context sharing.
     #[serde(default)]
     pub thread_id: Option<String>,
+    /// Extra variables for rendering the agent's prompt template, such as
+    /// `channel_capabilities`, overriding the built-in variables.
+    #[serde(default)]
+    pub prompt_variables: HashMap<String, String>,
+    /// Whether `agent_id` overrides the agent bound to the session.
+    ///
+    /// Only set by scheduled runs, which run the schedule's agent; other
+    /// callers cannot route a session away from its binding.
+    #[serde(skip)]
+    pub(crate) pin_agent: bool,
 }
 
 impl AgentRunParams {
@@ -132,6 +143,8 @@ impl AgentRunParams {
             agent_id: agent_id.map(|id| id.into()),
             depth: 0,
             thread_id: None,
+            prompt_variables: HashMap::new(),
+            pin_agent: false,
         }
     }
 
@@ -148,6 +161,8 @@ impl AgentRunParams {
             agent_id: agent_id.map(|id| id.into()),
             depth,
             thread_id: None,
+            prompt_variables: HashMap::new(),
+            pin_agent: false,
         }
     }
 
@@ -165,6 +180,8 @@ impl AgentRunParams {
             agent_id: agent_id.map(|id| id.into()),
             depth,
             thread_id: thread_id.map(|id| id.into()),
+            prompt_variables: HashMap::new(),
+            pin_agent: false,
         }
     }
 
@@ -182,8 +199,20 @@ impl AgentRunParams {
             agent_id: agent_id.map(|id| id.into()),
             depth,
             thread_id: thread_id.map(|id| id.to_string()),
+            prompt_variables: HashMap::new(),
+            pin_agent: false,
         }
     }
+
+    /// Sets a variable for rendering the agent's prompt template.
+    pub fn with_prompt_variable(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.prompt_variables.insert(name.into(), value.into());
+        self
+    }
 }
 
 /// The result of an agent run.
@@ -197,6 +226,11 @@ pub struct AgentRunResult {
     pub tool_calls: Vec<ToolCallRecord>,
     /// Usage statistics for the run.
     pub usage: UsageReport,
+    /// The response parsed as JSON, for agents with an output schema.
+    ///
+    /// Only set once the response has been validated against the schema.
+    #[serde(default)]
+    pub structured_output: Option<Value>,
 }
 
 impl AgentRunResult {
@@ -210,13 +244,33 @@ impl AgentRunResult {
             response: response.into(),
             tool_calls,
             usage,
+            structured_output: None,
         }
     }
+
+    /// Sets the validated structured output of the run.
+    pub fn with_structured_output(mut self, output: Value) -> Self {
+        self.structured_output = Some(output);
+        self
+    }
+
+    /// Deserializes the structured output into `T`.
+    ///
+    /// Fails if the agent has no output schema or the output does not
+    /// deserialize into `T`.
+    pub fn output<T: serde::de::DeserializeOwned>(&self) -> anyhow::Result<T> {
+        let output = self
+            .structured_output
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Agent run has no structured output"))?;
+        Ok(serde_json::from_value(output)?)
+    }
 }
 
 /// Usage statistics for an agent run.
 ///
-/// Contains the number of input and output tokens consumed.
+/// Contains the number of input and output tokens consumed, and their cost
+/// for models with known pricing.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UsageReport {
     /// The number of input tokens (prompt tokens).
@@ -229,6 +283,9 @@ pub struct UsageReport {
     /// The number of requests made.
     #[serde(default)]
     pub request_count: u64,
+    /// The cost of the requests in USD.
+    #[serde(default)]
+    pub cost_usd: f64,
 }
 
 impl UsageReport {
@@ -240,6 +297,7 @@ impl UsageReport {
             output_tokens,
             total_tokens,
             request_count: 0,
+            cost_usd: 0.0,
         }
     }
 
@@ -250,6 +308,11 @@ impl UsageReport {
         self.total_tokens = self.input_tokens + self.output_tokens;
         self.request_count += 1;
     }
+
+    /// Adds the given cost in USD to this report.
+    pub fn add_cost(&mut self, cost_usd: f64) {
+        self.cost_usd += cost_usd;
+    }
 }
 
 impl Default for UsageReport {
@@ -259,6 +322,7 @@ impl Default for UsageReport {
             output_tokens: 0,
             total_tokens: 0,
             request_count: 0,
+            cost_usd: 0.0,
         }
     }
 }
@@ -278,6 +342,13 @@ pub enum AgentEvent {
         #[serde(default)]
         index: Option<usize>,
     },
+    /// A delta of the model's reasoning (chain-of-thought).
+    ///
+    /// Only emitted for agents with `show_reasoning` enabled.
+    ReasoningDelta {
+        /// The reasoning text delta.
+        text: String,
+    },
     /// A tool call has started.
     ToolCallStart {
         /// The tool name being called.
@@ -285,6 +356,15 @@ pub enum AgentEvent {
         /// The unique identifier for this tool call.
         call_id: String,
     },
+    /// A running tool call produced incremental output.
+    ToolCallOutput {
+        /// The unique identifier of the tool call.
+        call_id: String,
+        /// The stream the output was written to.
+        stream: aisopod_tools::OutputStream,
+        /// The output text.
+        text: String,
+    },
     /// A tool call has completed with a result.
     ToolCallResult {
         /// The unique identifier of the tool call.
@@ -303,6 +383,40 @@ pub enum AgentEvent {
         /// The reason for the model switch.
         reason: String,
     },
+    /// The session was handed off to another agent, which handles its
+    /// subsequent messages.
+    Handoff {
+        /// The agent that handed the session off.
+        from_agent: String,
+        /// The agent now handling the session.
+        to_agent: String,
+        /// The handoff note for the receiving agent.
+        note: String,
+    },
+    /// A plan was written in plan-and-execute mode, either initially or
+    /// after a failed step.
+    PlanCreated {
+        /// Zero-based number of the plan within the run.
+        iteration: usize,
+        /// The steps of the plan.
+        steps: Vec<crate::planning::PlanStep>,
+    },
+    /// A step of the current plan changed status.
+    PlanStepProgress {
+        /// Zero-based position of the step in the current plan.
+        index: usize,
+        /// The new status of the step.
+        status: crate::planning::PlanStepStatus,
+        /// The step's reply once completed, or the error once failed.
+        #[serde(default)]
+        output: Option<String>,
+    },
+    /// A follow-up message sent by the user while the run was in progress
+    /// was injected into it, and is answered before the run completes.
+    Interruption {
+        /// The injected message.
+        message: aisopod_provider::Message,
+    },
     /// An error occurred during agent execution.
     Error {
         /// The error message.
@@ -1,17 +1,59 @@
 //! Usage tracking for agent execution.
 //!
 //! This module provides the `UsageTracker` struct which tracks token usage
-//! at per-request, per-session, and per-agent levels.
+//! and its cost at per-request, per-session, per-agent, and daily per-peer
+//! levels.
 
+use aisopod_config::types::ModelsConfig;
+use aisopod_provider::{ModelPricing, PricingTable, TokenUsage};
+use chrono::{NaiveDate, Utc};
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 
 use crate::types::UsageReport;
 
+/// Builds the pricing table for a configuration.
+///
+/// Starts from the built-in list prices and applies the `pricing` of each
+/// configured model, scoped to the model's provider if it names one.
+pub fn pricing_from_config(config: &ModelsConfig) -> PricingTable {
+    let mut table = PricingTable::builtin();
+    for model in &config.models {
+        if let Some(price) = model.pricing {
+            let provider = (!model.provider.is_empty()).then_some(model.provider.as_str());
+            table.set(
+                provider,
+                &model.id,
+                ModelPricing::per_million(
+                    price.input,
+                    price.output,
+                    price.cache_read,
+                    price.cache_write,
+                ),
+            );
+        }
+    }
+    table
+}
+
+/// Returns the peer a session belongs to.
+///
+/// Canonical session keys (`agent:channel:account:peer_kind:peer_id`) are
+/// stripped of their agent, so that a peer's sessions with different
+/// agents share one peer. Other keys are their own peer.
+pub fn session_peer(session_key: &str) -> &str {
+    match session_key.split_once(':') {
+        Some((_, peer)) if peer.matches(':').count() >= 3 => peer,
+        _ => session_key,
+    }
+}
+
 /// A tracker for token usage across sessions and agents.
 ///
 /// `UsageTracker` maintains separate usage reports for each session and agent,
-/// allowing for aggregation and querying of usage statistics.
+/// allowing for aggregation and querying of usage statistics. Requests
+/// recorded with [`record_model_request`](Self::record_model_request) are
+/// priced with the tracker's [`PricingTable`].
 ///
 /// # Example
 ///
@@ -33,27 +75,46 @@ use crate::types::UsageReport;
 /// assert!(agent_usage.is_some());
 /// assert_eq!(agent_usage.unwrap().input_tokens, 100);
 /// ```
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct UsageTracker {
     /// Per-session usage, keyed by session_key
     session_usage: DashMap<String, UsageReport>,
     /// Per-agent usage, keyed by agent_id
     agent_usage: DashMap<String, UsageReport>,
+    /// Usage of the current UTC day per peer, keyed by peer
+    peer_usage: DashMap<String, (NaiveDate, UsageReport)>,
+    /// Prices used to compute the cost of requests
+    pricing: PricingTable,
 }
 
 impl UsageTracker {
-    /// Creates a new `UsageTracker` with empty usage maps.
+    /// Creates a new `UsageTracker` with empty usage maps and the built-in
+    /// pricing table.
     pub fn new() -> Self {
         Self {
             session_usage: DashMap::new(),
             agent_usage: DashMap::new(),
+            peer_usage: DashMap::new(),
+            pricing: PricingTable::builtin(),
         }
     }
 
+    /// Replaces the pricing table used to compute request costs.
+    pub fn with_pricing(mut self, pricing: PricingTable) -> Self {
+        self.pricing = pricing;
+        self
+    }
+
+    /// Returns the pricing table used to compute request costs.
+    pub fn pricing(&self) -> &PricingTable {
+        &self.pricing
+    }
+
     /// Records a request with the given token counts.
     ///
-    /// This method adds the token counts to both the session and agent
-    /// usage reports, incrementing the request count for both.
+    /// This method adds the token counts to the session, agent, and daily
+    /// peer usage reports, incrementing the request count for each. The
+    /// peer is derived from the session key with [`session_peer`].
     ///
     /// # Arguments
     ///
@@ -85,6 +146,69 @@ impl UsageTracker {
                 .or_insert(UsageReport::default());
             agent_entry.add(input_tokens, output_tokens);
         }
+
+        // Update today's peer usage, starting over on a new day
+        {
+            let today = Utc::now().date_naive();
+            let mut peer_entry = self
+                .peer_usage
+                .entry(session_peer(session_key).to_string())
+                .or_insert((today, UsageReport::default()));
+            if peer_entry.0 != today {
+                *peer_entry = (today, UsageReport::default());
+            }
+            peer_entry.1.add(input_tokens, output_tokens);
+        }
+    }
+
+    /// Records a model request with the token usage reported by the provider.
+    ///
+    /// Like [`record_request`](Self::record_request), but also prices the
+    /// request and adds its cost to the session and agent reports. Models
+    /// without known pricing cost nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_key` - The session key identifying the conversation session.
+    /// * `agent_id` - The agent ID for the request.
+    /// * `provider` - The ID of the provider that served the request.
+    /// * `model` - The model that served the request.
+    /// * `usage` - The token usage reported for the request.
+    ///
+    /// # Returns
+    ///
+    /// Returns the usage report of this request alone.
+    pub fn record_model_request(
+        &self,
+        session_key: &str,
+        agent_id: &str,
+        provider: &str,
+        model: &str,
+        usage: &TokenUsage,
+    ) -> UsageReport {
+        let mut report = UsageReport::new(
+            u64::from(usage.prompt_tokens),
+            u64::from(usage.completion_tokens),
+        );
+        report.request_count = 1;
+        report.cost_usd = self.pricing.cost(provider, model, usage).unwrap_or(0.0);
+
+        self.record_request(
+            session_key,
+            agent_id,
+            report.input_tokens,
+            report.output_tokens,
+        );
+        if let Some(mut session_entry) = self.session_usage.get_mut(session_key) {
+            session_entry.add_cost(report.cost_usd);
+        }
+        if let Some(mut agent_entry) = self.agent_usage.get_mut(agent_id) {
+            agent_entry.add_cost(report.cost_usd);
+        }
+        if let Some(mut peer_entry) = self.peer_usage.get_mut(session_peer(session_key)) {
+            peer_entry.1.add_cost(report.cost_usd);
+        }
+        report
     }
 
     /// Gets the cumulative usage report for a session.
@@ -117,6 +241,24 @@ impl UsageTracker {
         self.agent_usage.get(agent_id).map(|report| report.clone())
     }
 
+    /// Gets the usage of a peer on the current UTC day.
+    ///
+    /// # Arguments
+    ///
+    /// * `peer` - The peer to look up, as returned by [`session_peer`].
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(UsageReport)` if the peer has usage recorded today,
+    /// or `None` otherwise.
+    pub fn get_peer_usage_today(&self, peer: &str) -> Option<UsageReport> {
+        let today = Utc::now().date_naive();
+        self.peer_usage
+            .get(peer)
+            .filter(|entry| entry.0 == today)
+            .map(|entry| entry.1.clone())
+    }
+
     /// Resets usage for a session.
     ///
     /// This clears the session usage but does not affect agent usage.
@@ -155,6 +297,12 @@ impl UsageTracker {
     }
 }
 
+impl Default for UsageTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -272,6 +420,37 @@ mod tests {
         assert_eq!(agent_usage.request_count, 2);
     }
 
+    #[test]
+    fn test_peer_usage_spans_agents_of_a_peer() {
+        let tracker = UsageTracker::new();
+        tracker.record_request("support:telegram:bot:dm:alice", "support", 100, 50);
+        tracker.record_request("billing:telegram:bot:dm:alice", "billing", 10, 5);
+        tracker.record_request("support:telegram:bot:dm:bob", "support", 1, 1);
+
+        let usage = tracker
+            .get_peer_usage_today("telegram:bot:dm:alice")
+            .unwrap();
+        assert_eq!(usage.total_tokens, 165);
+        assert_eq!(usage.request_count, 2);
+        assert!(tracker
+            .get_peer_usage_today("telegram:bot:dm:carol")
+            .is_none());
+    }
+
+    #[test]
+    fn test_session_peer() {
+        assert_eq!(
+            session_peer("support:telegram:bot:dm:alice"),
+            "telegram:bot:dm:alice"
+        );
+        assert_eq!(
+            session_peer(":telegram:bot:dm:alice"),
+            "telegram:bot:dm:alice"
+        );
+        assert_eq!(session_peer("session_1"), "session_1");
+        assert_eq!(session_peer("cli:main"), "cli:main");
+    }
+
     #[test]
     fn test_nonexistent_session() {
         let tracker = UsageTracker::new();
@@ -0,0 +1,158 @@
+//! Budget enforcement tests for agent engine.
+//!
+//! This module tests that the pipeline refuses runs of sessions and peers
+//! over their token or cost budget with the configured reply, and reports
+//! exceeded budgets to the budget notifier.
+
+#[path = "helpers.rs"]
+mod helpers;
+
+use std::sync::{Arc, Mutex};
+
+use aisopod_agent::pipeline::AgentPipeline;
+use aisopod_agent::types::AgentEvent;
+use aisopod_agent::usage::UsageTracker;
+use aisopod_agent::{BudgetExceeded, BudgetNotifier, BudgetScope};
+use aisopod_config::types::BudgetConfig;
+use aisopod_provider::{ModelPricing, PricingTable, TokenUsage};
+use anyhow::Result;
+
+use helpers::{
+    test_agent_run_params, test_config, test_session_store, test_tool_registry, user_message,
+    MockProvider,
+};
+
+const SESSION: &str = "test-agent:telegram:bot:dm:alice";
+
+/// Notifier recording the budgets it is told about.
+#[derive(Default)]
+struct RecordingNotifier {
+    exceeded: Mutex<Vec<BudgetExceeded>>,
+}
+
+#[async_trait::async_trait]
+impl BudgetNotifier for RecordingNotifier {
+    async fn budget_exceeded(&self, exceeded: &BudgetExceeded) -> Result<()> {
+        self.exceeded.lock().unwrap().push(exceeded.clone());
+        Ok(())
+    }
+}
+
+fn budget_pipeline(budget: BudgetConfig, tracker: Arc<UsageTracker>) -> AgentPipeline {
+    let mut config = test_config();
+    config.session.budget = budget;
+
+    let mut providers = aisopod_provider::ProviderRegistry::new();
+    providers.register(Arc::new(
+        MockProvider::new("mock").with_response_text("Model reply"),
+    ));
+    providers.register_alias("mock/test-model", "mock", "mock/test-model");
+
+    AgentPipeline::new_with_usage_tracker(
+        Arc::new(config),
+        Arc::new(providers),
+        test_tool_registry(),
+        test_session_store(),
+        tracker,
+    )
+}
+
+async fn run(pipeline: &AgentPipeline, session_key: &str) -> (String, Vec<AgentEvent>) {
+    let params = test_agent_run_params(session_key, vec![user_message("Hello")], Some("default"));
+    let (event_tx, mut event_rx) = tokio::sync::mpsc::channel(50);
+    let result = pipeline.execute(&params, &event_tx).await.unwrap();
+    drop(event_tx);
+
+    let mut events = Vec::new();
+    while let Some(event) = event_rx.recv().await {
+        events.push(event);
+    }
+    (result.response, events)
+}
+
+#[tokio::test]
+async fn test_run_within_budget_calls_model() {
+    let tracker = Arc::new(UsageTracker::new());
+    tracker.record_request(SESSION, "test-agent", 100, 100);
+    let pipeline = budget_pipeline(
+        BudgetConfig {
+            max_session_tokens: Some(1000),
+            ..Default::default()
+        },
+        tracker,
+    );
+
+    let (response, _) = run(&pipeline, SESSION).await;
+
+    assert_eq!(response, "Model reply");
+}
+
+#[tokio::test]
+async fn test_session_over_budget_is_refused_and_reported() {
+    let tracker = Arc::new(UsageTracker::new());
+    tracker.record_request(SESSION, "test-agent", 800, 200);
+    let notifier = Arc::new(RecordingNotifier::default());
+    let pipeline = budget_pipeline(
+        BudgetConfig {
+            max_session_tokens: Some(1000),
+            exceeded_message: "Budget spent.".to_string(),
+            ..Default::default()
+        },
+        tracker,
+    )
+    .with_budget_notifier(notifier.clone());
+
+    let (response, events) = run(&pipeline, SESSION).await;
+
+    assert_eq!(response, "Budget spent.");
+    assert!(!events
+        .iter()
+        .any(|e| matches!(e, AgentEvent::TextDelta { .. })));
+    assert!(events.iter().any(|e| matches!(
+        e,
+        AgentEvent::Complete { result } if result.response == "Budget spent."
+    )));
+
+    let exceeded = notifier.exceeded.lock().unwrap();
+    assert_eq!(exceeded.len(), 1);
+    assert_eq!(exceeded[0].scope, BudgetScope::Session);
+    assert_eq!(exceeded[0].session_key, SESSION);
+}
+
+#[tokio::test]
+async fn test_daily_peer_budget_covers_all_sessions_of_peer() {
+    // Another agent spent $2 of alice's daily budget
+    let mut pricing = PricingTable::new();
+    pricing.set(
+        None,
+        "priced-model",
+        ModelPricing::per_million(2.0, 0.0, None, None),
+    );
+    let tracker = Arc::new(UsageTracker::new().with_pricing(pricing));
+    tracker.record_model_request(
+        "other-agent:telegram:bot:dm:alice",
+        "other-agent",
+        "mock",
+        "priced-model",
+        &TokenUsage {
+            prompt_tokens: 1_000_000,
+            completion_tokens: 0,
+            total_tokens: 1_000_000,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+        },
+    );
+    let pipeline = budget_pipeline(
+        BudgetConfig {
+            max_daily_peer_cost: Some(1.0),
+            ..Default::default()
+        },
+        tracker,
+    );
+
+    let (alice, _) = run(&pipeline, SESSION).await;
+    let (bob, _) = run(&pipeline, "test-agent:telegram:bot:dm:bob").await;
+
+    assert_eq!(alice, BudgetConfig::default().exceeded_message);
+    assert_eq!(bob, "Model reply");
+}
@@ -0,0 +1,226 @@
+//! Draft-and-verify tests for agent engine.
+//!
+//! This module tests agents whose responses are drafted by one model and
+//! reviewed by another, in both edit and critique mode.
+
+#[path = "helpers.rs"]
+mod helpers;
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use aisopod_agent::types::AgentEvent;
+use aisopod_agent::AgentRunner;
+use aisopod_config::types::{DraftVerifyConfig, VerifyMode};
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{
+    ChatCompletionChunk, FinishReason, MessageDelta, ModelInfo, ProviderHealth,
+};
+use aisopod_provider::{ChatCompletionRequest, ChatCompletionStream, MessageContent, Role};
+use anyhow::Result;
+
+use helpers::{
+    test_agent_run_params, test_config, test_session_store, test_tool_registry, user_message,
+};
+
+/// Provider replying with a fixed sequence of texts and recording the
+/// requests it receives.
+struct ScriptedProvider {
+    id: &'static str,
+    replies: Mutex<VecDeque<&'static str>>,
+    requests: Mutex<Vec<ChatCompletionRequest>>,
+}
+
+impl ScriptedProvider {
+    fn new(id: &'static str, replies: &[&'static str]) -> Self {
+        Self {
+            id,
+            replies: Mutex::new(replies.iter().copied().collect()),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn request_count(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+
+    /// Returns the text of the last message of the `index`th request.
+    fn last_message(&self, index: usize) -> String {
+        match &self.requests.lock().unwrap()[index]
+            .messages
+            .last()
+            .unwrap()
+            .content
+        {
+            MessageContent::Text(text) => text.clone(),
+            _ => String::new(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ModelProvider for ScriptedProvider {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionStream> {
+        self.requests.lock().unwrap().push(request);
+        let reply = self
+            .replies
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or_else(|| anyhow::anyhow!("No scripted reply left"))?;
+        let chunk = ChatCompletionChunk {
+            id: "chunk_1".to_string(),
+            delta: MessageDelta {
+                role: Some(Role::Assistant),
+                content: Some(reply.to_string()),
+                tool_calls: None,
+                reasoning: None,
+            },
+            finish_reason: Some(FinishReason::Stop),
+            usage: None,
+        };
+        Ok(Box::pin(futures_util::stream::iter(vec![Ok(chunk)])))
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth> {
+        Ok(ProviderHealth {
+            available: true,
+            latency_ms: None,
+        })
+    }
+}
+
+/// Creates a runner whose "test-agent" drafts with `draft` and verifies
+/// with `verify`.
+fn draft_verify_runner(
+    draft: Arc<ScriptedProvider>,
+    verify: Arc<ScriptedProvider>,
+    mode: VerifyMode,
+) -> AgentRunner {
+    let mut config = test_config();
+    let agent = config
+        .agents
+        .agents
+        .iter_mut()
+        .find(|agent| agent.id == "test-agent")
+        .unwrap();
+    agent.draft_verify = Some(DraftVerifyConfig {
+        draft_model: "draft/model".to_string(),
+        verify_model: "verify/model".to_string(),
+        mode,
+        max_revisions: 1,
+    });
+
+    let mut providers = aisopod_provider::ProviderRegistry::new();
+    providers.register(draft);
+    providers.register(verify);
+    providers.register_alias("draft/model", "draft", "draft/model");
+    providers.register_alias("verify/model", "verify", "verify/model");
+
+    AgentRunner::new(
+        Arc::new(config),
+        Arc::new(providers),
+        test_tool_registry(),
+        test_session_store(),
+    )
+}
+
+async fn run(runner: &AgentRunner) -> (String, Vec<AgentEvent>) {
+    let params = test_agent_run_params(
+        "session",
+        vec![user_message("What is the capital of Australia?")],
+        Some("test-agent"),
+    );
+    let mut receiver = runner.run(params).await.unwrap().into_receiver();
+    let mut events = Vec::new();
+    while let Some(event) = receiver.recv().await {
+        events.push(event);
+    }
+    let response = events
+        .iter()
+        .find_map(|event| match event {
+            AgentEvent::Complete { result } => Some(result.response.clone()),
+            _ => None,
+        })
+        .expect("run did not complete");
+    (response, events)
+}
+
+fn streamed_text(events: &[AgentEvent]) -> Vec<String> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            AgentEvent::TextDelta { text, .. } => Some(text.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_approved_draft_is_sent() {
+    let draft = Arc::new(ScriptedProvider::new("draft", &["Canberra."]));
+    let verify = Arc::new(ScriptedProvider::new("verify", &["APPROVED"]));
+    let runner = draft_verify_runner(draft.clone(), verify.clone(), VerifyMode::Edit);
+
+    let (response, events) = run(&runner).await;
+
+    assert_eq!(response, "Canberra.");
+    assert_eq!(streamed_text(&events), vec!["Canberra."]);
+    assert!(verify.last_message(0).contains("APPROVED"));
+}
+
+#[tokio::test]
+async fn test_edited_draft_replaces_response() {
+    let draft = Arc::new(ScriptedProvider::new("draft", &["Sydney."]));
+    let verify = Arc::new(ScriptedProvider::new("verify", &["Canberra."]));
+    let runner = draft_verify_runner(draft.clone(), verify.clone(), VerifyMode::Edit);
+
+    let (response, events) = run(&runner).await;
+
+    // The rejected draft is never streamed
+    assert_eq!(response, "Canberra.");
+    assert_eq!(streamed_text(&events), vec!["Canberra."]);
+    assert_eq!(draft.request_count(), 1);
+}
+
+#[tokio::test]
+async fn test_critiqued_draft_is_revised_by_draft_model() {
+    let draft = Arc::new(ScriptedProvider::new(
+        "draft",
+        &["Sydney.", "Canberra, not Sydney."],
+    ));
+    let verify = Arc::new(ScriptedProvider::new(
+        "verify",
+        &["Sydney is not the capital."],
+    ));
+    let runner = draft_verify_runner(draft.clone(), verify.clone(), VerifyMode::Critique);
+
+    let (response, _) = run(&runner).await;
+
+    assert_eq!(response, "Canberra, not Sydney.");
+    assert!(draft.last_message(1).contains("Sydney is not the capital."));
+    // The revision is not reviewed again once max_revisions is reached
+    assert_eq!(verify.request_count(), 1);
+}
+
+#[tokio::test]
+async fn test_failed_review_keeps_draft() {
+    let draft = Arc::new(ScriptedProvider::new("draft", &["Canberra."]));
+    let verify = Arc::new(ScriptedProvider::new("verify", &[]));
+    let runner = draft_verify_runner(draft, verify, VerifyMode::Edit);
+
+    let (response, _) = run(&runner).await;
+
+    assert_eq!(response, "Canberra.");
+}
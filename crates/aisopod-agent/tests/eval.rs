@@ -0,0 +1,170 @@
+//! Evaluation harness tests for agent engine.
+//!
+//! This module tests running YAML-defined eval suites against agents and
+//! the reports of their assertions.
+
+#[path = "helpers.rs"]
+mod helpers;
+
+use std::sync::Arc;
+
+use aisopod_agent::{AgentRunner, EvalRunner, EvalSuite};
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{
+    ChatCompletionChunk, FinishReason, MessageDelta, ModelInfo, ProviderHealth,
+};
+use aisopod_provider::{ChatCompletionRequest, ChatCompletionStream, Role};
+use anyhow::Result;
+
+use helpers::{test_config, test_session_store, test_tool_registry};
+
+/// Provider always replying with the same text.
+struct FixedProvider {
+    id: &'static str,
+    reply: &'static str,
+}
+
+#[async_trait::async_trait]
+impl ModelProvider for FixedProvider {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn chat_completion(
+        &self,
+        _request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionStream> {
+        let chunk = ChatCompletionChunk {
+            id: "chunk_1".to_string(),
+            delta: MessageDelta {
+                role: Some(Role::Assistant),
+                content: Some(self.reply.to_string()),
+                tool_calls: None,
+                reasoning: None,
+            },
+            finish_reason: Some(FinishReason::Stop),
+            usage: None,
+        };
+        Ok(Box::pin(futures_util::stream::iter(vec![Ok(chunk)])))
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth> {
+        Ok(ProviderHealth {
+            available: true,
+            latency_ms: None,
+        })
+    }
+}
+
+fn eval_runner(judge_reply: &'static str) -> EvalRunner {
+    let mut config = test_config();
+    for agent in &mut config.agents.agents {
+        agent.model = "agent/model".to_string();
+    }
+
+    let mut providers = aisopod_provider::ProviderRegistry::new();
+    providers.register(Arc::new(FixedProvider {
+        id: "agent",
+        reply: "Hello, world!",
+    }));
+    providers.register(Arc::new(FixedProvider {
+        id: "judge",
+        reply: judge_reply,
+    }));
+    providers.register_alias("agent/model", "agent", "agent/model");
+    providers.register_alias("judge/model", "judge", "judge/model");
+
+    let runner = AgentRunner::new(
+        Arc::new(config),
+        Arc::new(providers),
+        test_tool_registry(),
+        test_session_store(),
+    );
+    EvalRunner::new(Arc::new(runner))
+}
+
+const SUITE: &str = r#"
+name: smoke
+agent: test-agent
+judge_model: judge/model
+cases:
+  - name: greeting
+    prompt: Say hello
+    assertions:
+      - type: contains
+        value: hello
+        ignore_case: true
+      - type: regex
+        pattern: "world!$"
+  - name: calculation
+    prompt: What is 10 * 10?
+    assertions:
+      - type: tool_called
+        name: calculator
+  - name: judged
+    prompt: Greet me warmly
+    assertions:
+      - type: llm_judge
+        criteria: The greeting is warm and personal.
+  - name: unknown-agent
+    prompt: Hi
+    agent: nobody
+"#;
+
+#[tokio::test]
+async fn test_suite_reports_passed_and_failed_cases() {
+    let suite = EvalSuite::from_yaml(SUITE).unwrap();
+
+    let report = eval_runner("FAIL: the greeting is generic").run(&suite).await;
+
+    assert_eq!(report.suite, "smoke");
+    assert_eq!(report.passed(), 1);
+    assert_eq!(report.failed(), 3);
+
+    let greeting = &report.cases[0];
+    assert!(greeting.passed);
+    assert_eq!(greeting.response, "Hello, world!");
+    assert_eq!(greeting.agent.as_deref(), Some("test-agent"));
+
+    let calculation = &report.cases[1];
+    assert!(!calculation.passed);
+    assert_eq!(calculation.assertions[0].assertion, "called tool 'calculator'");
+
+    let judged = &report.cases[2];
+    assert!(!judged.passed);
+    assert_eq!(
+        judged.assertions[0].reason.as_deref(),
+        Some("FAIL: the greeting is generic")
+    );
+
+    let unknown = &report.cases[3];
+    assert!(!unknown.passed);
+    assert!(unknown.error.as_deref().unwrap().contains("nobody"));
+}
+
+#[tokio::test]
+async fn test_judge_approval_passes() {
+    let suite = EvalSuite::from_yaml(SUITE).unwrap();
+
+    let report = eval_runner("PASS").run(&suite).await;
+
+    assert!(report.cases[2].passed);
+}
+
+#[tokio::test]
+async fn test_agent_override_applies_to_all_cases() {
+    let suite = EvalSuite::from_yaml(SUITE).unwrap();
+
+    let report = eval_runner("PASS").with_agent("default").run(&suite).await;
+
+    assert!(report
+        .cases
+        .iter()
+        .all(|case| case.agent.as_deref() == Some("default")));
+    assert!(report.cases[3].passed);
+    assert!(!report.all_passed());
+}
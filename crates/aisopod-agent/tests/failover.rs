@@ -3,8 +3,12 @@
 //! This module tests the model failover system including error classification
 //! and failover state management.
 
-use aisopod_agent::failover::{classify_error, FailoverAction, FailoverState, ModelAttempt};
+use aisopod_agent::failover::{
+    classify_error, classify_error_with_policy, execute_with_failover, FailoverAction,
+    FailoverState, ModelAttempt,
+};
 use aisopod_agent::resolution::ModelChain;
+use aisopod_config::types::{FailoverPolicy, FailoverRule};
 use aisopod_provider::normalize::ProviderError;
 use std::time::Duration;
 
@@ -285,3 +289,122 @@ fn test_failover_state_max_attempts_default() {
     state.record_attempt(None, Duration::from_millis(100));
     assert!(!state.can_retry_current_model());
 }
+
+#[test]
+fn test_classify_error_with_custom_policy() {
+    let policy = FailoverPolicy {
+        auth: FailoverRule::Stop,
+        context_overflow: FailoverRule::Next,
+        server_error: FailoverRule::Retry,
+        retry_delay_ms: 250,
+        ..Default::default()
+    };
+
+    let auth = ProviderError::AuthenticationFailed {
+        provider: "openai".to_string(),
+        message: "Invalid API key".to_string(),
+    };
+    assert_eq!(
+        classify_error_with_policy(&auth, &policy),
+        FailoverAction::Abort
+    );
+
+    let overflow = ProviderError::ContextLengthExceeded {
+        provider: "openai".to_string(),
+        max_tokens: 4096,
+    };
+    assert_eq!(
+        classify_error_with_policy(&overflow, &policy),
+        FailoverAction::FailoverToNext
+    );
+
+    let server = ProviderError::ServerError {
+        provider: "openai".to_string(),
+        status: 503,
+        message: "Service unavailable".to_string(),
+    };
+    assert_eq!(
+        classify_error_with_policy(&server, &policy),
+        FailoverAction::WaitAndRetry(Duration::from_millis(250))
+    );
+}
+
+#[test]
+fn test_failover_state_max_attempts_from_policy() {
+    let chain = ModelChain::new("gpt-4");
+    let policy = FailoverPolicy {
+        max_retries: 4,
+        ..Default::default()
+    };
+    let state = FailoverState::with_policy(&chain, policy);
+
+    assert_eq!(state.max_attempts, 5);
+}
+
+#[tokio::test]
+async fn test_transient_errors_retry_same_model_then_next() {
+    let chain = ModelChain::with_fallbacks("primary", vec!["fallback".to_string()]);
+    let policy = FailoverPolicy {
+        max_retries: 1,
+        server_error: FailoverRule::Retry,
+        retry_delay_ms: 0,
+        ..Default::default()
+    };
+    let mut state = FailoverState::with_policy(&chain, policy);
+    let (tx, _rx) = tokio::sync::mpsc::channel(10);
+    let calls = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    let calls_clone = calls.clone();
+    let result = execute_with_failover(&mut state, tx, move |model_id| {
+        let calls = calls_clone.clone();
+        async move {
+            calls.lock().unwrap().push(model_id.clone());
+            if model_id == "primary" {
+                Err(ProviderError::ServerError {
+                    provider: "test".to_string(),
+                    status: 503,
+                    message: "Service unavailable".to_string(),
+                })
+            } else {
+                Ok(model_id)
+            }
+        }
+    })
+    .await;
+
+    assert_eq!(result.unwrap(), "fallback");
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![
+            "primary".to_string(),
+            "primary".to_string(),
+            "fallback".to_string()
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_stop_rule_aborts_without_trying_fallbacks() {
+    let chain = ModelChain::with_fallbacks("primary", vec!["fallback".to_string()]);
+    let policy = FailoverPolicy {
+        auth: FailoverRule::Stop,
+        ..Default::default()
+    };
+    let mut state = FailoverState::with_policy(&chain, policy);
+    let (tx, _rx) = tokio::sync::mpsc::channel(10);
+
+    let result = execute_with_failover(&mut state, tx, |model_id| async move {
+        if model_id == "primary" {
+            Err(ProviderError::AuthenticationFailed {
+                provider: "test".to_string(),
+                message: "Invalid API key".to_string(),
+            })
+        } else {
+            Ok(model_id)
+        }
+    })
+    .await;
+
+    assert!(result.unwrap_err().to_string().contains("Aborting"));
+    assert_eq!(state.attempted_models.len(), 1);
+}
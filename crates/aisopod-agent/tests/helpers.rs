@@ -173,6 +173,7 @@ impl MockProvider {
                     role: Some(Role::Assistant),
                     content: Some(response_text.to_string()),
                     tool_calls: None,
+                    reasoning: None,
                 },
                 finish_reason: if tool_calls.is_empty() {
                     Some(aisopod_provider::types::FinishReason::Stop)
@@ -184,6 +185,8 @@ impl MockProvider {
                         prompt_tokens: 10,
                         completion_tokens: (response_text.len() / 4) as u32,
                         total_tokens: (10 + response_text.len() / 4) as u32,
+                        cache_read_tokens: 0,
+                        cache_write_tokens: 0,
                     })
                 } else {
                     None
@@ -199,6 +202,7 @@ impl MockProvider {
                     role: None,
                     content: None,
                     tool_calls: Some(vec![tool_call.clone()]),
+                    reasoning: None,
                 },
                 finish_reason: None,
                 usage: None,
@@ -213,12 +217,15 @@ impl MockProvider {
                     role: None,
                     content: None,
                     tool_calls: None,
+                    reasoning: None,
                 },
                 finish_reason: Some(aisopod_provider::types::FinishReason::Stop),
                 usage: Some(aisopod_provider::types::TokenUsage {
                     prompt_tokens: 10,
                     completion_tokens: tool_calls.len() as u32 * 5,
                     total_tokens: (10 + tool_calls.len() * 5) as u32,
+                    cache_read_tokens: 0,
+                    cache_write_tokens: 0,
                 }),
             });
         }
@@ -315,6 +322,12 @@ pub fn test_config() -> AisopodConfig {
                     max_subagent_depth: 3,
                     subagent_allowed_models: None,
                     skills: Vec::new(),
+                    show_reasoning: false,
+                    output_schema: None,
+                    max_output_repairs: 2,
+                    draft_verify: None,
+                    prompt_template: None,
+                    prompt_sections: Default::default(),
                 },
                 aisopod_config::types::Agent {
                     id: "test-agent".to_string(),
@@ -327,6 +340,12 @@ pub fn test_config() -> AisopodConfig {
                     max_subagent_depth: 3,
                     subagent_allowed_models: None,
                     skills: Vec::new(),
+                    show_reasoning: false,
+                    output_schema: None,
+                    max_output_repairs: 2,
+                    draft_verify: None,
+                    prompt_template: None,
+                    prompt_sections: Default::default(),
                 },
                 aisopod_config::types::Agent {
                     id: "fallback-agent".to_string(),
@@ -339,8 +358,17 @@ pub fn test_config() -> AisopodConfig {
                     max_subagent_depth: 3,
                     subagent_allowed_models: None,
                     skills: Vec::new(),
+                    show_reasoning: false,
+                    output_schema: None,
+                    max_output_repairs: 2,
+                    draft_verify: None,
+                    prompt_template: None,
+                    prompt_sections: Default::default(),
                 },
             ],
+            schedules: Vec::new(),
+            scheduler_path: String::new(),
+            prompt_templates: Default::default(),
         },
         models: aisopod_config::types::ModelsConfig {
             models: vec![],
@@ -350,6 +378,7 @@ pub fn test_config() -> AisopodConfig {
                 fallbacks: vec!["mock/fallback-model".to_string()],
             }],
             default_provider: String::new(),
+            ..Default::default()
         },
         bindings: vec![aisopod_config::types::AgentBinding {
             agent_id: "test-agent".to_string(),
@@ -384,6 +413,12 @@ pub fn test_config_with_fallbacks() -> AisopodConfig {
         max_subagent_depth: 3,
         subagent_allowed_models: None,
         skills: Vec::new(),
+        show_reasoning: false,
+        output_schema: None,
+        max_output_repairs: 2,
+        draft_verify: None,
+        prompt_template: None,
+        prompt_sections: Default::default(),
     });
 
     config
@@ -441,6 +476,7 @@ pub fn test_agent_run_result(
         response: response.into(),
         tool_calls,
         usage: UsageReport::new(input_tokens, output_tokens),
+        structured_output: None,
     }
 }
 
@@ -0,0 +1,153 @@
+//! Session lease tests for agent engine.
+//!
+//! This module tests that a runner sharing its sessions with other
+//! instances runs an agent on a session only while holding its lease.
+
+#[path = "helpers.rs"]
+mod helpers;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use aisopod_agent::types::AgentEvent;
+use aisopod_agent::{AgentRunner, SessionLeases};
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{ModelInfo, ProviderHealth};
+use aisopod_provider::{ChatCompletionRequest, ChatCompletionStream};
+use aisopod_session::SessionStore;
+use anyhow::Result;
+
+use helpers::{
+    test_agent_run_params, test_config, test_session_store, test_tool_registry, user_message,
+    MockProvider,
+};
+
+const SESSION: &str = "test-agent:telegram:bot:dm:alice";
+
+/// Provider that never answers, keeping its runs in progress.
+struct StalledProvider;
+
+#[async_trait::async_trait]
+impl ModelProvider for StalledProvider {
+    fn id(&self) -> &str {
+        "mock"
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn chat_completion(
+        &self,
+        _request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionStream> {
+        std::future::pending().await
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth> {
+        Ok(ProviderHealth {
+            available: true,
+            latency_ms: None,
+        })
+    }
+}
+
+fn leased_runner(
+    provider: Arc<dyn ModelProvider>,
+    leases: Arc<SessionStore>,
+    ttl: Duration,
+) -> AgentRunner {
+    let mut providers = aisopod_provider::ProviderRegistry::new();
+    providers.register(provider);
+    providers.register_alias("mock/test-model", "mock", "mock/test-model");
+
+    AgentRunner::new(
+        Arc::new(test_config()),
+        Arc::new(providers),
+        test_tool_registry(),
+        test_session_store(),
+    )
+    .with_session_leases(SessionLeases {
+        backend: leases,
+        owner: "node-a".to_string(),
+        ttl,
+    })
+}
+
+fn params(text: &str) -> aisopod_agent::AgentRunParams {
+    test_agent_run_params(SESSION, vec![user_message(text)], Some("test-agent"))
+}
+
+/// Receives the events of a run until its stream ends.
+async fn collect_events(mut rx: tokio::sync::mpsc::Receiver<AgentEvent>) -> Vec<AgentEvent> {
+    let mut events = Vec::new();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+    })
+    .await
+    .expect("run did not finish");
+    events
+}
+
+#[tokio::test]
+async fn test_run_requires_the_session_lease() {
+    let leases = test_session_store();
+    let runner = leased_runner(
+        Arc::new(MockProvider::new("mock")),
+        leases.clone(),
+        Duration::from_secs(60),
+    );
+
+    // Another instance is processing the session
+    leases
+        .try_acquire_lease(SESSION, "node-b", Duration::from_secs(60))
+        .unwrap()
+        .unwrap();
+    let error = runner.run_and_get_result(params("one")).await.unwrap_err();
+    assert!(error.to_string().contains("another instance"));
+    let events = collect_events(runner.run(params("two")).await.unwrap().into_receiver()).await;
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, AgentEvent::Error { .. })));
+    assert!(!events
+        .iter()
+        .any(|event| matches!(event, AgentEvent::Complete { .. })));
+
+    // Once it is done, this instance takes the session and releases it
+    // after the run
+    leases.release_lease(SESSION, "node-b").unwrap();
+    let result = runner.run_and_get_result(params("three")).await.unwrap();
+    assert_eq!(result.response, "Test response");
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(leases.get_lease(SESSION).unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_run_stops_when_the_lease_is_taken_over() {
+    let leases = test_session_store();
+    let runner = leased_runner(
+        Arc::new(StalledProvider),
+        leases.clone(),
+        Duration::from_millis(60),
+    );
+
+    let run = runner.run(params("one")).await.unwrap().into_receiver();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(leases.get_lease(SESSION).unwrap().unwrap().owner, "node-a");
+
+    // Another instance takes the session over, as after a long stall
+    leases.release_lease(SESSION, "node-a").unwrap();
+    leases
+        .try_acquire_lease(SESSION, "node-b", Duration::from_secs(60))
+        .unwrap()
+        .unwrap();
+
+    let events = collect_events(run).await;
+    assert!(events.iter().any(|event| matches!(
+        event,
+        AgentEvent::Error { message } if message.contains("taken over")
+    )));
+    assert_eq!(leases.get_lease(SESSION).unwrap().unwrap().owner, "node-b");
+}
@@ -0,0 +1,216 @@
+//! Tool-call loop guard tests for agent engine.
+//!
+//! This module tests that runs repeating the same tool calls are nudged,
+//! forced to answer without tools, or aborted.
+
+#[path = "helpers.rs"]
+mod helpers;
+
+use std::sync::{Arc, Mutex};
+
+use aisopod_agent::types::AgentEvent;
+use aisopod_agent::AgentRunner;
+use aisopod_config::types::LoopGuardAction;
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{
+    ChatCompletionChunk, FinishReason, MessageDelta, ModelInfo, ProviderHealth,
+};
+use aisopod_provider::{ChatCompletionRequest, ChatCompletionStream, Role, ToolCall};
+use anyhow::Result;
+
+use helpers::{
+    test_agent_run_params, test_config, test_session_store, test_tool_registry, user_message,
+};
+
+/// Provider calling the calculator with the same arguments whenever tools
+/// are offered, and answering once they are withheld unless `stubborn`.
+struct LoopingProvider {
+    stubborn: bool,
+    requests: Mutex<Vec<ChatCompletionRequest>>,
+}
+
+impl LoopingProvider {
+    fn new(stubborn: bool) -> Self {
+        Self {
+            stubborn,
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn requests(&self) -> Vec<ChatCompletionRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl ModelProvider for LoopingProvider {
+    fn id(&self) -> &str {
+        "looping"
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionStream> {
+        let call_tool = request.tools.is_some() || self.stubborn;
+        let call = self.requests.lock().unwrap().len();
+        self.requests.lock().unwrap().push(request);
+
+        let delta = if call_tool {
+            MessageDelta {
+                role: Some(Role::Assistant),
+                content: None,
+                tool_calls: Some(vec![ToolCall {
+                    id: format!("call_{}", call),
+                    name: "calculator".to_string(),
+                    arguments: r#"{"expression": "10 * 10"}"#.to_string(),
+                }]),
+                reasoning: None,
+            }
+        } else {
+            MessageDelta {
+                role: Some(Role::Assistant),
+                content: Some("The answer is 100.".to_string()),
+                tool_calls: None,
+                reasoning: None,
+            }
+        };
+        let chunk = ChatCompletionChunk {
+            id: format!("chunk_{}", call),
+            delta,
+            finish_reason: Some(FinishReason::Stop),
+            usage: None,
+        };
+        Ok(Box::pin(futures_util::stream::iter(vec![Ok(chunk)])))
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth> {
+        Ok(ProviderHealth {
+            available: true,
+            latency_ms: None,
+        })
+    }
+}
+
+fn loop_guard_runner(provider: Arc<LoopingProvider>, action: LoopGuardAction) -> AgentRunner {
+    let mut config = test_config();
+    config.tools.loop_guard.action = action;
+    for agent in &mut config.agents.agents {
+        agent.model = "looping/model".to_string();
+    }
+
+    let mut providers = aisopod_provider::ProviderRegistry::new();
+    providers.register(provider);
+    providers.register_alias("looping/model", "looping", "looping/model");
+
+    AgentRunner::new(
+        Arc::new(config),
+        Arc::new(providers),
+        test_tool_registry(),
+        test_session_store(),
+    )
+}
+
+async fn run(runner: &AgentRunner) -> Vec<AgentEvent> {
+    let params = test_agent_run_params(
+        "session",
+        vec![user_message("What is 10 * 10?")],
+        Some("test-agent"),
+    );
+    let mut receiver = runner.run(params).await.unwrap().into_receiver();
+    let mut events = Vec::new();
+    while let Some(event) = receiver.recv().await {
+        events.push(event);
+    }
+    events
+}
+
+fn tool_call_count(events: &[AgentEvent]) -> usize {
+    events
+        .iter()
+        .filter(|event| matches!(event, AgentEvent::ToolCallStart { .. }))
+        .count()
+}
+
+fn last_user_text(request: &ChatCompletionRequest) -> String {
+    request
+        .messages
+        .iter()
+        .rev()
+        .find(|message| message.role == Role::User)
+        .and_then(|message| match &message.content {
+            aisopod_provider::MessageContent::Text(text) => Some(text.clone()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn test_nudge_then_force_answer() {
+    let provider = Arc::new(LoopingProvider::new(false));
+    let runner = loop_guard_runner(provider.clone(), LoopGuardAction::Nudge);
+
+    let events = run(&runner).await;
+
+    // Two calls run before each detected third repeat is withheld
+    assert_eq!(tool_call_count(&events), 4);
+    let requests = provider.requests();
+    assert_eq!(requests.len(), 7);
+    assert!(last_user_text(&requests[3]).contains("repeating yourself"));
+    assert!(requests[5].tools.is_some());
+    assert!(requests[6].tools.is_none());
+    assert!(last_user_text(&requests[6]).contains("final answer now"));
+    assert!(events.iter().any(|event| matches!(
+        event,
+        AgentEvent::Complete { result } if result.response == "The answer is 100."
+    )));
+}
+
+#[tokio::test]
+async fn test_force_answer_withholds_tools() {
+    let provider = Arc::new(LoopingProvider::new(false));
+    let runner = loop_guard_runner(provider.clone(), LoopGuardAction::ForceAnswer);
+
+    let events = run(&runner).await;
+
+    assert_eq!(tool_call_count(&events), 2);
+    assert_eq!(provider.requests().len(), 4);
+    assert!(matches!(events.last(), Some(AgentEvent::Complete { .. })));
+}
+
+#[tokio::test]
+async fn test_abort_reports_loop() {
+    let provider = Arc::new(LoopingProvider::new(false));
+    let runner = loop_guard_runner(provider.clone(), LoopGuardAction::Abort);
+
+    let events = run(&runner).await;
+
+    assert_eq!(tool_call_count(&events), 2);
+    assert!(events.iter().any(|event| matches!(
+        event,
+        AgentEvent::Error { message } if message.contains("Tool call loop detected")
+    )));
+    assert!(!events
+        .iter()
+        .any(|event| matches!(event, AgentEvent::Complete { .. })));
+}
+
+#[tokio::test]
+async fn test_loop_persisting_without_tools_aborts() {
+    let provider = Arc::new(LoopingProvider::new(true));
+    let runner = loop_guard_runner(provider.clone(), LoopGuardAction::ForceAnswer);
+
+    let events = run(&runner).await;
+
+    // The repeated call is not run once tools were withheld
+    assert_eq!(tool_call_count(&events), 2);
+    assert_eq!(provider.requests().len(), 4);
+    assert!(events.iter().any(|event| matches!(
+        event,
+        AgentEvent::Error { message } if message.contains("Tool call loop detected")
+    )));
+}
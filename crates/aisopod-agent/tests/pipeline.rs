@@ -410,3 +410,201 @@ async fn test_pipeline_error_handling() {
     assert!(result.is_err(), "Pipeline should fail with error");
     assert!(result.unwrap_err().to_string().contains("Test error"));
 }
+
+#[tokio::test]
+async fn test_pipeline_handoff_rebinds_session() {
+    use aisopod_agent::HandoffRegistry;
+    use aisopod_provider::ToolCall;
+
+    // The triage agent hands the session off, then tells the user
+    let mock_provider = Arc::new(
+        MockProvider::new("mock")
+            .with_response_text("A specialist will take over.")
+            .with_tool_calls(vec![ToolCall {
+                id: "call_1".to_string(),
+                name: "handoff".to_string(),
+                arguments: r#"{"agent_id":"fallback-agent","note":"Needs a specialist"}"#
+                    .to_string(),
+            }]),
+    );
+
+    let config = Arc::new(test_config());
+    let mut providers = aisopod_provider::ProviderRegistry::new();
+    providers.register(mock_provider);
+    providers.register_alias("mock/test-model", "mock", "mock/test-model");
+
+    let handoffs = Arc::new(HandoffRegistry::new(config.clone()));
+    let mut tools = aisopod_tools::ToolRegistry::new();
+    tools.register(Arc::new(aisopod_tools::HandoffTool::new(handoffs.clone())));
+
+    let pipeline = AgentPipeline::new(
+        config,
+        Arc::new(providers),
+        Arc::new(tools),
+        test_session_store(),
+    )
+    .with_handoffs(handoffs.clone());
+
+    let (event_tx, event_rx) = tokio::sync::mpsc::channel(50);
+    let params = test_agent_run_params("test_session", vec![user_message("Help!")], None::<String>);
+    let result = pipeline.execute(&params, &event_tx).await;
+    drop(event_tx);
+
+    assert!(result.is_ok(), "Pipeline execution should succeed");
+    let handoff = handoffs
+        .get("test_session")
+        .expect("session should be handed off");
+    assert_eq!(handoff.from_agent, "test-agent");
+    assert_eq!(handoff.to_agent, "fallback-agent");
+    assert_eq!(handoff.note, "Needs a specialist");
+
+    let events = collect_events(event_rx).await;
+    assert!(events.iter().any(|e| matches!(
+        e,
+        AgentEvent::Handoff { to_agent, .. } if to_agent == "fallback-agent"
+    )));
+}
+
+/// Reply sink that records the messages sent, without edit support
+struct ChunkSink {
+    messages: std::sync::Mutex<Vec<String>>,
+}
+
+#[async_trait::async_trait]
+impl aisopod_agent::ReplySink for ChunkSink {
+    async fn send(&self, text: &str) -> anyhow::Result<Option<String>> {
+        self.messages.lock().unwrap().push(text.to_string());
+        Ok(None)
+    }
+
+    async fn edit(&self, _message_id: &str, _text: &str) -> anyhow::Result<()> {
+        anyhow::bail!("edits are not supported")
+    }
+}
+
+#[tokio::test]
+async fn test_pipeline_execute_streaming() {
+    // Test that the reply is delivered to the sink while the run returns its result
+
+    let mock_provider = Arc::new(MockProvider::new("mock").with_response_text("Hello, world!"));
+    let config = test_config();
+
+    let mut providers = aisopod_provider::ProviderRegistry::new();
+    providers.register(mock_provider);
+    providers.register_alias("mock/test-model", "mock", "mock/test-model");
+    let providers = Arc::new(providers);
+
+    let pipeline = AgentPipeline::new(
+        Arc::new(config),
+        providers,
+        test_tool_registry(),
+        test_session_store(),
+    );
+
+    let messages = vec![user_message("Hello")];
+    let params = test_agent_run_params("test_session", messages, Some("default"));
+    let sink = ChunkSink {
+        messages: std::sync::Mutex::new(Vec::new()),
+    };
+
+    let result = pipeline
+        .execute_streaming(
+            &params,
+            &sink,
+            &aisopod_agent::StreamingReplyConfig::default(),
+        )
+        .await
+        .expect("Pipeline execution should succeed");
+
+    assert_eq!(result.response, "Hello, world!");
+    assert_eq!(sink.messages.lock().unwrap().join(""), "Hello, world!");
+}
+
+/// Skill providing a prompt template.
+#[derive(Debug)]
+struct TemplateSkill;
+
+impl aisopod_agent::Skill for TemplateSkill {
+    fn id(&self) -> &str {
+        "templates"
+    }
+
+    fn meta(&self) -> aisopod_agent::SkillMeta {
+        aisopod_agent::SkillMeta::new("templates", "1.0.0", "Prompt templates")
+    }
+
+    fn system_prompt_fragment(&self) -> Option<String> {
+        None
+    }
+
+    fn tools(&self) -> Vec<Arc<dyn aisopod_tools::Tool>> {
+        Vec::new()
+    }
+
+    fn prompt_templates(&self) -> std::collections::HashMap<String, String> {
+        std::collections::HashMap::from([(
+            "concise".to_string(),
+            "You are {{agent_name}}. Answer in one sentence.".to_string(),
+        )])
+    }
+}
+
+fn templated_pipeline(template: &str) -> AgentPipeline {
+    let mut config = test_config();
+    for agent in &mut config.agents.agents {
+        agent.prompt_template = Some(template.to_string());
+        agent.skills = vec!["templates".to_string()];
+    }
+    config.agents.prompt_templates.insert(
+        "support".to_string(),
+        "You support {{peer_id}} on {{channel}}.".to_string(),
+    );
+
+    let mut providers = aisopod_provider::ProviderRegistry::new();
+    providers.register(Arc::new(
+        MockProvider::new("mock").with_response_text("Hello, world!"),
+    ));
+    providers.register_alias("mock/test-model", "mock", "mock/test-model");
+
+    let mut skills = aisopod_agent::SkillRegistry::new();
+    skills.register(Arc::new(TemplateSkill));
+
+    AgentPipeline::new_with_skills(
+        Arc::new(config),
+        Arc::new(providers),
+        test_tool_registry(),
+        test_session_store(),
+        Arc::new(skills),
+    )
+}
+
+#[tokio::test]
+async fn test_pipeline_with_prompt_templates() {
+    let (event_tx, _event_rx) = tokio::sync::mpsc::channel(50);
+    let params = test_agent_run_params(
+        "default:telegram:bot:dm:alice",
+        vec![user_message("Hello")],
+        Some("default"),
+    );
+
+    for template in ["support", "concise"] {
+        let result = templated_pipeline(template)
+            .execute(&params, &event_tx)
+            .await;
+        assert!(result.is_ok(), "Template '{}' should resolve", template);
+    }
+}
+
+#[tokio::test]
+async fn test_pipeline_with_unknown_prompt_template_fails() {
+    let (event_tx, _event_rx) = tokio::sync::mpsc::channel(50);
+    let params =
+        test_agent_run_params("test_session", vec![user_message("Hello")], Some("default"));
+
+    let result = templated_pipeline("missing")
+        .execute(&params, &event_tx)
+        .await;
+
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("Unknown prompt template 'missing'"));
+}
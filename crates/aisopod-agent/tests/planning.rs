@@ -0,0 +1,230 @@
+//! Plan-and-execute tests for agent engine.
+//!
+//! This module tests the planning loop of `AgentRunner`: plan generation,
+//! step execution, reflection and re-planning after failed steps, and the
+//! plan-progress events emitted along the way.
+
+#[path = "helpers.rs"]
+mod helpers;
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use aisopod_agent::types::AgentEvent;
+use aisopod_agent::{AgentRunner, PlanStepStatus, PlanningConfig};
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{
+    ChatCompletionChunk, FinishReason, MessageDelta, ModelInfo, ProviderHealth,
+};
+use aisopod_provider::{ChatCompletionRequest, ChatCompletionStream, Role};
+use anyhow::Result;
+
+use helpers::{
+    test_agent_run_params, test_config, test_session_store, test_tool_registry, user_message,
+};
+
+/// Provider replying with a fixed sequence of texts, one per request.
+struct ScriptedProvider {
+    replies: Mutex<VecDeque<&'static str>>,
+}
+
+impl ScriptedProvider {
+    fn new(replies: &[&'static str]) -> Self {
+        Self {
+            replies: Mutex::new(replies.iter().copied().collect()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ModelProvider for ScriptedProvider {
+    fn id(&self) -> &str {
+        "mock"
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn chat_completion(
+        &self,
+        _request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionStream> {
+        let reply = self
+            .replies
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or_else(|| anyhow::anyhow!("No scripted reply left"))?;
+        let chunk = ChatCompletionChunk {
+            id: "chunk_1".to_string(),
+            delta: MessageDelta {
+                role: Some(Role::Assistant),
+                content: Some(reply.to_string()),
+                tool_calls: None,
+                reasoning: None,
+            },
+            finish_reason: Some(FinishReason::Stop),
+            usage: None,
+        };
+        Ok(Box::pin(futures_util::stream::iter(vec![Ok(chunk)])))
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth> {
+        Ok(ProviderHealth {
+            available: true,
+            latency_ms: None,
+        })
+    }
+}
+
+fn planning_runner(replies: &[&'static str], config: PlanningConfig) -> AgentRunner {
+    let mut providers = aisopod_provider::ProviderRegistry::new();
+    providers.register(Arc::new(ScriptedProvider::new(replies)));
+    providers.register_alias("mock/test-model", "mock", "mock/test-model");
+
+    AgentRunner::new(
+        Arc::new(test_config()),
+        Arc::new(providers),
+        test_tool_registry(),
+        test_session_store(),
+    )
+    .with_planning(config)
+}
+
+async fn run_events(runner: &AgentRunner) -> Vec<AgentEvent> {
+    let params = test_agent_run_params(
+        "test_session",
+        vec![user_message("Where is my order?")],
+        Some("default"),
+    );
+    let mut receiver = runner.run(params).await.unwrap().into_receiver();
+
+    // The run is spawned, so wait for the stream to close
+    let mut events = Vec::new();
+    while let Some(event) = receiver.recv().await {
+        events.push(event);
+    }
+    events
+}
+
+fn step_statuses(events: &[AgentEvent]) -> Vec<(usize, PlanStepStatus)> {
+    events
+        .iter()
+        .filter_map(|e| match e {
+            AgentEvent::PlanStepProgress { index, status, .. } => Some((*index, *status)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_plan_executes_steps_in_order() {
+    let runner = planning_runner(
+        &[
+            "1. Look up the order\n2. Answer the user",
+            "Order 42 shipped yesterday",
+            "Your order shipped yesterday.",
+        ],
+        PlanningConfig::default(),
+    );
+
+    let events = run_events(&runner).await;
+
+    let plans: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            AgentEvent::PlanCreated { steps, .. } => Some(steps.len()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(plans, vec![2]);
+    assert_eq!(
+        step_statuses(&events),
+        vec![
+            (0, PlanStepStatus::Running),
+            (0, PlanStepStatus::Completed),
+            (1, PlanStepStatus::Running),
+            (1, PlanStepStatus::Completed),
+        ]
+    );
+
+    let completions: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            AgentEvent::Complete { result } => Some(result.response.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(completions, vec!["Your order shipped yesterday."]);
+}
+
+#[tokio::test]
+async fn test_plan_replans_after_failed_step() {
+    let runner = planning_runner(
+        &[
+            "1. Query the order service\n2. Answer the user",
+            "STEP FAILED: the order service is unavailable",
+            "The service was down; the order cache can be used instead.",
+            "1. Check the order cache\n2. Answer the user",
+            "Order 42 shipped yesterday",
+            "Your order shipped yesterday.",
+        ],
+        PlanningConfig::default(),
+    );
+
+    let events = run_events(&runner).await;
+
+    let iterations: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            AgentEvent::PlanCreated { iteration, steps } => {
+                Some((*iteration, steps[0].description.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        iterations,
+        vec![
+            (0, "Query the order service".to_string()),
+            (1, "Check the order cache".to_string()),
+        ]
+    );
+    assert!(events.iter().any(|e| matches!(
+        e,
+        AgentEvent::PlanStepProgress {
+            status: PlanStepStatus::Failed,
+            output: Some(reason),
+            ..
+        } if reason == "the order service is unavailable"
+    )));
+    assert!(events.iter().any(|e| matches!(
+        e,
+        AgentEvent::Complete { result } if result.response == "Your order shipped yesterday."
+    )));
+}
+
+#[tokio::test]
+async fn test_plan_stops_when_iteration_budget_is_spent() {
+    let runner = planning_runner(
+        &[
+            "1. Query the order service",
+            "STEP FAILED: the order service is unavailable",
+        ],
+        PlanningConfig {
+            max_iterations: 1,
+            ..Default::default()
+        },
+    );
+
+    let events = run_events(&runner).await;
+
+    assert!(!events
+        .iter()
+        .any(|e| matches!(e, AgentEvent::Complete { .. })));
+    assert!(events.iter().any(|e| matches!(
+        e,
+        AgentEvent::Error { message } if message.contains("within 1 iterations")
+    )));
+}
@@ -0,0 +1,92 @@
+//! Session replay tests for agent engine.
+//!
+//! This module tests replaying the user turns of a stored session through
+//! an agent backed by a mock provider and diffing the outcomes against the
+//! recorded ones.
+
+#[path = "helpers.rs"]
+mod helpers;
+
+use std::sync::Arc;
+
+use aisopod_agent::{AgentRunner, DiffLine, SessionReplayer};
+use aisopod_session::{SessionKey, StoredMessage};
+
+use helpers::{test_config, test_session_store, test_tool_registry, MockProvider};
+
+fn replayer(reply: &str) -> SessionReplayer {
+    let mut providers = aisopod_provider::ProviderRegistry::new();
+    providers.register(Arc::new(
+        MockProvider::new("mock").with_response_text(reply),
+    ));
+    providers.register_alias("mock/test-model", "mock", "mock/test-model");
+
+    let runner = AgentRunner::new(
+        Arc::new(test_config()),
+        Arc::new(providers),
+        test_tool_registry(),
+        test_session_store(),
+    );
+    SessionReplayer::new(Arc::new(runner))
+}
+
+fn session_key() -> SessionKey {
+    SessionKey {
+        agent_id: "test-agent".to_string(),
+        channel: "telegram".to_string(),
+        account_id: "bot".to_string(),
+        peer_kind: "dm".to_string(),
+        peer_id: "alice".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_replay_reports_changed_turns() {
+    let messages = vec![
+        StoredMessage::user("Hello"),
+        StoredMessage::assistant("Model reply"),
+        StoredMessage::user("Tell me more"),
+        StoredMessage::assistant("More details\nModel reply"),
+    ];
+
+    let report = replayer("Model reply")
+        .replay(&session_key(), &messages)
+        .await;
+
+    assert_eq!(report.agent, "test-agent");
+    assert_eq!(report.turns.len(), 2);
+    assert_eq!(report.changed(), 1);
+    assert!(!report.unchanged());
+
+    let unchanged = &report.turns[0];
+    assert!(!unchanged.changed);
+    assert!(unchanged.diff.is_empty());
+
+    let changed = &report.turns[1];
+    assert!(changed.changed);
+    assert_eq!(changed.prompt, "Tell me more");
+    assert_eq!(changed.replayed_response, "Model reply");
+    assert_eq!(
+        changed.diff,
+        vec![
+            DiffLine::Removed("More details".to_string()),
+            DiffLine::Same("Model reply".to_string()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_replay_with_other_agent() {
+    let messages = vec![
+        StoredMessage::user("Hello"),
+        StoredMessage::assistant("Model reply"),
+    ];
+
+    let report = replayer("Model reply")
+        .with_agent("default")
+        .replay(&session_key(), &messages)
+        .await;
+
+    assert_eq!(report.agent, "default");
+    assert!(report.unchanged());
+}
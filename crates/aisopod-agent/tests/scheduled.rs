@@ -0,0 +1,298 @@
+//! Scheduled agent run tests for agent engine.
+//!
+//! This module tests that configured schedules are registered with the
+//! persistent job scheduler, that due jobs run their agent headlessly
+//! and deliver the reply to the schedule's target, and that jobs scheduled
+//! by an agent run in that agent's tool context.
+
+#[path = "helpers.rs"]
+mod helpers;
+
+use std::sync::{Arc, Mutex};
+
+use aisopod_agent::scheduled::SCHEDULE_JOB_PREFIX;
+use aisopod_agent::{register_schedules, schedule_job_id, AgentJobRunner, AgentRunner};
+use aisopod_config::types::{AgentSchedule, ScheduleDelivery};
+use aisopod_tools::{
+    BashTool, JobOwner, JobRunner, JobScheduler, MessageSender, SqliteJobScheduler, ToolJobRunner,
+    ToolRegistry,
+};
+use anyhow::Result;
+use chrono::{Duration, Utc};
+
+use helpers::{test_config, test_session_store, test_tool_registry, user_message, MockProvider};
+use serde_json::json;
+
+/// A delivered message: channel, content, account and peer.
+type Delivery = (String, String, Option<String>, Option<String>);
+
+/// Sender recording the messages it was asked to deliver.
+#[derive(Default)]
+struct RecordingSender {
+    sent: Mutex<Vec<Delivery>>,
+}
+
+#[async_trait::async_trait]
+impl MessageSender for RecordingSender {
+    async fn send_message(
+        &self,
+        channel: &str,
+        content: &str,
+        account: Option<&str>,
+        peer: Option<&str>,
+    ) -> Result<()> {
+        self.sent.lock().unwrap().push((
+            channel.to_string(),
+            content.to_string(),
+            account.map(str::to_string),
+            peer.map(str::to_string),
+        ));
+        Ok(())
+    }
+}
+
+/// Creates a runner whose "fallback-agent" replies differently from the
+/// agent bound to sessions by default.
+fn test_runner() -> Arc<AgentRunner> {
+    test_runner_with(test_config(), test_tool_registry())
+}
+
+/// Creates a runner as [`test_runner`] does, with the given configuration
+/// and tools.
+fn test_runner_with(
+    config: aisopod_config::AisopodConfig,
+    tools: Arc<ToolRegistry>,
+) -> Arc<AgentRunner> {
+    let mut providers = aisopod_provider::ProviderRegistry::new();
+    providers.register(Arc::new(
+        MockProvider::new("mock").with_response_text("Bound agent reply"),
+    ));
+    providers.register(Arc::new(
+        MockProvider::new("nightly").with_response_text("Nightly report"),
+    ));
+    providers.register_alias("mock/test-model", "mock", "mock/test-model");
+    providers.register_alias("mock/fallback-model", "nightly", "mock/fallback-model");
+
+    Arc::new(AgentRunner::new(
+        Arc::new(config),
+        Arc::new(providers),
+        tools,
+        test_session_store(),
+    ))
+}
+
+fn nightly_schedule(deliver: Option<ScheduleDelivery>) -> AgentSchedule {
+    AgentSchedule {
+        id: "nightly".to_string(),
+        agent_id: "fallback-agent".to_string(),
+        cron: "0 0 3 * * *".to_string(),
+        prompt: "Summarize the day".to_string(),
+        deliver,
+    }
+}
+
+fn ops_delivery() -> ScheduleDelivery {
+    ScheduleDelivery {
+        channel: "telegram".to_string(),
+        account: Some("ops-bot".to_string()),
+        peer: "user:admin".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_register_schedules_creates_recurring_jobs() {
+    let schedules = vec![nightly_schedule(None)];
+    let runner = AgentJobRunner::new(test_runner()).with_schedules(schedules.clone());
+    let scheduler = SqliteJobScheduler::in_memory(Arc::new(runner)).unwrap();
+
+    let jobs = register_schedules(&scheduler, &schedules).await.unwrap();
+
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].id, schedule_job_id("nightly"));
+    assert_eq!(jobs[0].command, "Summarize the day");
+    assert_eq!(jobs[0].cron_expression, "0 0 3 * * *");
+}
+
+#[tokio::test]
+async fn test_register_schedules_removes_unconfigured_jobs() {
+    let runner = AgentJobRunner::new(test_runner());
+    let scheduler = SqliteJobScheduler::in_memory(Arc::new(runner)).unwrap();
+    let stale = format!("{}weekly", SCHEDULE_JOB_PREFIX);
+    scheduler
+        .schedule(&stale, "0 0 9 * * Mon", "Weekly digest")
+        .await
+        .unwrap();
+    scheduler
+        .schedule("reminder", "0 0 8 * * *", "Drink water")
+        .await
+        .unwrap();
+
+    register_schedules(&scheduler, &[nightly_schedule(None)])
+        .await
+        .unwrap();
+
+    let mut ids: Vec<String> = scheduler
+        .list()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|job| job.id)
+        .collect();
+    ids.sort();
+    assert_eq!(
+        ids,
+        vec![schedule_job_id("nightly"), "reminder".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn test_due_schedule_runs_agent_and_delivers_reply() {
+    let schedules = vec![nightly_schedule(Some(ops_delivery()))];
+    let sender = Arc::new(RecordingSender::default());
+    let runner = AgentJobRunner::new(test_runner())
+        .with_schedules(schedules.clone())
+        .with_sender(sender.clone());
+    let scheduler = SqliteJobScheduler::in_memory(Arc::new(runner)).unwrap();
+    register_schedules(&scheduler, &schedules).await.unwrap();
+
+    let runs = scheduler
+        .run_due_at(Utc::now() + Duration::days(2))
+        .await
+        .unwrap();
+
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].result.as_ref().unwrap(), "Nightly report");
+    let sent = sender.sent.lock().unwrap();
+    assert_eq!(
+        *sent,
+        vec![(
+            "telegram".to_string(),
+            "Nightly report".to_string(),
+            Some("ops-bot".to_string()),
+            Some("user:admin".to_string()),
+        )]
+    );
+}
+
+#[tokio::test]
+async fn test_unconfigured_job_runs_bound_agent_without_delivery() {
+    let sender = Arc::new(RecordingSender::default());
+    let runner = AgentJobRunner::new(test_runner()).with_sender(sender.clone());
+    let scheduler = SqliteJobScheduler::in_memory(Arc::new(runner)).unwrap();
+    scheduler
+        .schedule("reminder", "0 0 8 * * *", "Drink water")
+        .await
+        .unwrap();
+
+    let output = scheduler.run_now("reminder").await.unwrap();
+
+    assert_eq!(output, "Bound agent reply");
+    assert!(sender.sent.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_job_scheduled_by_agent_runs_that_agent() {
+    let runner = AgentJobRunner::new(test_runner());
+    let scheduler = SqliteJobScheduler::in_memory(Arc::new(runner)).unwrap();
+    scheduler
+        .schedule_for(
+            &JobOwner::new("fallback-agent", "chat"),
+            "report",
+            "0 0 8 * * *",
+            "Summarize the day",
+        )
+        .await
+        .unwrap();
+
+    let output = scheduler.run_now("report").await.unwrap();
+
+    assert_eq!(output, "Nightly report");
+}
+
+#[tokio::test]
+async fn test_scheduled_bash_stays_in_agent_sandbox() {
+    let mut config = test_config();
+    config
+        .agents
+        .agents
+        .iter_mut()
+        .find(|agent| agent.id == "test-agent")
+        .unwrap()
+        .sandbox = true;
+    let mut tools = ToolRegistry::new();
+    tools.register(Arc::new(BashTool::default()));
+    let agents = test_runner_with(config, Arc::new(tools));
+    let runner = ToolJobRunner::new(agents.tools().clone(), agents.clone());
+    let scheduler = SqliteJobScheduler::in_memory(Arc::new(runner)).unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let marker = dir.path().join("ran-on-host");
+    let command = json!({
+        "tool": "bash",
+        "params": {"command": format!("touch {}", marker.display())},
+    })
+    .to_string();
+    for (job_id, agent_id) in [("sandboxed", "test-agent"), ("unsandboxed", "default")] {
+        scheduler
+            .schedule_for(
+                &JobOwner::new(agent_id, "chat"),
+                job_id,
+                "0 0 8 * * *",
+                &command,
+            )
+            .await
+            .unwrap();
+    }
+
+    // The sandboxed agent has no workspace to mount, so its command is
+    // refused instead of running on the host
+    let error = scheduler.run_now("sandboxed").await.unwrap_err();
+    assert!(error.to_string().contains("workspace"), "{}", error);
+    assert!(!marker.exists());
+
+    scheduler.run_now("unsandboxed").await.unwrap();
+    assert!(marker.exists());
+}
+
+#[tokio::test]
+async fn test_tool_job_without_owner_is_refused() {
+    let agents = test_runner();
+    let runner = ToolJobRunner::new(agents.tools().clone(), agents.clone());
+    let job = aisopod_tools::ScheduledJob {
+        id: "job-1".to_string(),
+        cron_expression: "0 * * * * *".to_string(),
+        command: json!({"tool": "calculator"}).to_string(),
+        next_run: None,
+        last_run: None,
+        owner: None,
+    };
+
+    let error = runner.run(&job).await.unwrap_err();
+
+    assert!(error.to_string().contains("not scheduled by an agent"));
+}
+
+#[tokio::test]
+async fn test_delivery_without_sender_fails_job() {
+    let schedules = vec![nightly_schedule(Some(ops_delivery()))];
+    let runner = AgentJobRunner::new(test_runner()).with_schedules(schedules.clone());
+    let scheduler = SqliteJobScheduler::in_memory(Arc::new(runner)).unwrap();
+    register_schedules(&scheduler, &schedules).await.unwrap();
+
+    let result = scheduler.run_now(&schedule_job_id("nightly")).await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_requested_agent_does_not_override_session_binding() {
+    let params = aisopod_agent::AgentRunParams::new(
+        "chat",
+        vec![user_message("Hello")],
+        Some("fallback-agent"),
+    );
+
+    let result = test_runner().run_and_get_result(params).await.unwrap();
+
+    assert_eq!(result.response, "Bound agent reply");
+}
@@ -0,0 +1,239 @@
+//! Mid-run steering tests for agent engine.
+//!
+//! This module tests the handling of follow-up messages sent while a run
+//! of their session is in progress, under each `session.messages.follow_up`
+//! policy.
+
+#[path = "helpers.rs"]
+mod helpers;
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use aisopod_agent::types::AgentEvent;
+use aisopod_agent::AgentRunner;
+use aisopod_config::types::FollowUpPolicy;
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{
+    ChatCompletionChunk, FinishReason, MessageDelta, ModelInfo, ProviderHealth,
+};
+use aisopod_provider::{ChatCompletionRequest, ChatCompletionStream, MessageContent, Role};
+use anyhow::Result;
+use tokio::sync::Semaphore;
+
+use helpers::{
+    test_agent_run_params, test_config, test_session_store, test_tool_registry, user_message,
+};
+
+const SESSION: &str = "test-agent:telegram:bot:dm:alice";
+
+/// Provider replying with a fixed sequence of texts, each once a permit
+/// is released, and recording the requests it receives.
+struct GatedProvider {
+    replies: Mutex<VecDeque<&'static str>>,
+    requests: Mutex<Vec<ChatCompletionRequest>>,
+    gate: Semaphore,
+}
+
+impl GatedProvider {
+    fn new(replies: &[&'static str]) -> Self {
+        Self {
+            replies: Mutex::new(replies.iter().copied().collect()),
+            requests: Mutex::new(Vec::new()),
+            gate: Semaphore::new(0),
+        }
+    }
+
+    /// Lets the oldest waiting request reply.
+    fn release(&self) {
+        self.gate.add_permits(1);
+    }
+
+    /// Waits until the provider received `count` requests.
+    async fn wait_for_requests(&self, count: usize) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while self.requests.lock().unwrap().len() < count {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("provider request not received");
+    }
+
+    /// Returns the texts of the messages of the `index`th request.
+    fn request_texts(&self, index: usize) -> Vec<String> {
+        self.requests.lock().unwrap()[index]
+            .messages
+            .iter()
+            .filter_map(|message| match &message.content {
+                MessageContent::Text(text) => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl ModelProvider for GatedProvider {
+    fn id(&self) -> &str {
+        "mock"
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionStream> {
+        self.requests.lock().unwrap().push(request);
+        self.gate.acquire().await?.forget();
+        let reply = self
+            .replies
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or_else(|| anyhow::anyhow!("No scripted reply left"))?;
+        let chunk = ChatCompletionChunk {
+            id: "chunk_1".to_string(),
+            delta: MessageDelta {
+                role: Some(Role::Assistant),
+                content: Some(reply.to_string()),
+                tool_calls: None,
+                reasoning: None,
+            },
+            finish_reason: Some(FinishReason::Stop),
+            usage: None,
+        };
+        Ok(Box::pin(futures_util::stream::iter(vec![Ok(chunk)])))
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth> {
+        Ok(ProviderHealth {
+            available: true,
+            latency_ms: None,
+        })
+    }
+}
+
+fn steered_runner(provider: Arc<GatedProvider>, policy: FollowUpPolicy) -> AgentRunner {
+    let mut config = test_config();
+    config.session.messages.follow_up = policy;
+
+    let mut providers = aisopod_provider::ProviderRegistry::new();
+    providers.register(provider);
+    providers.register_alias("mock/test-model", "mock", "mock/test-model");
+
+    AgentRunner::new(
+        Arc::new(config),
+        Arc::new(providers),
+        test_tool_registry(),
+        test_session_store(),
+    )
+}
+
+async fn start(runner: &AgentRunner, text: &str) -> tokio::sync::mpsc::Receiver<AgentEvent> {
+    let params = test_agent_run_params(SESSION, vec![user_message(text)], Some("test-agent"));
+    runner.run(params).await.unwrap().into_receiver()
+}
+
+/// Receives the events of a run until its stream ends.
+async fn collect_events(mut rx: tokio::sync::mpsc::Receiver<AgentEvent>) -> Vec<AgentEvent> {
+    let mut events = Vec::new();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+    })
+    .await
+    .expect("run did not finish");
+    events
+}
+
+fn completed_response(events: &[AgentEvent]) -> Option<String> {
+    events.iter().find_map(|event| match event {
+        AgentEvent::Complete { result } => Some(result.response.clone()),
+        _ => None,
+    })
+}
+
+#[tokio::test]
+async fn test_queue_runs_follow_up_after_running_run() {
+    let provider = Arc::new(GatedProvider::new(&["First reply", "Second reply"]));
+    let runner = steered_runner(provider.clone(), FollowUpPolicy::Queue);
+
+    let first = start(&runner, "one").await;
+    provider.wait_for_requests(1).await;
+    let second = start(&runner, "two").await;
+
+    // The follow-up waits for its turn instead of racing the running run
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(provider.requests.lock().unwrap().len(), 1);
+
+    provider.release();
+    let first = collect_events(first).await;
+    provider.release();
+    let second = collect_events(second).await;
+
+    assert_eq!(completed_response(&first).as_deref(), Some("First reply"));
+    assert_eq!(completed_response(&second).as_deref(), Some("Second reply"));
+    assert_eq!(provider.request_texts(1), vec!["two"]);
+    assert!(!runner.steering().is_active(SESSION));
+}
+
+#[tokio::test]
+async fn test_restart_aborts_running_run_and_combines_messages() {
+    let provider = Arc::new(GatedProvider::new(&["Combined reply"]));
+    let runner = steered_runner(provider.clone(), FollowUpPolicy::Restart);
+
+    let first = start(&runner, "one").await;
+    provider.wait_for_requests(1).await;
+    let second = start(&runner, "two").await;
+
+    let first = collect_events(first).await;
+    assert!(completed_response(&first).is_none());
+    assert!(first
+        .iter()
+        .any(|event| matches!(event, AgentEvent::Error { .. })));
+
+    provider.wait_for_requests(2).await;
+    provider.release();
+    let second = collect_events(second).await;
+
+    assert_eq!(
+        completed_response(&second).as_deref(),
+        Some("Combined reply")
+    );
+    assert_eq!(provider.request_texts(1), vec!["one", "two"]);
+}
+
+#[tokio::test]
+async fn test_interrupt_injects_follow_up_into_running_run() {
+    let provider = Arc::new(GatedProvider::new(&["First reply", "Follow-up reply"]));
+    let runner = steered_runner(provider.clone(), FollowUpPolicy::Interrupt);
+
+    let first = start(&runner, "one").await;
+    provider.wait_for_requests(1).await;
+    let second = start(&runner, "two").await;
+
+    // The follow-up is answered on the running run's stream
+    assert!(collect_events(second).await.is_empty());
+
+    provider.release();
+    provider.wait_for_requests(2).await;
+    provider.release();
+    let first = collect_events(first).await;
+
+    assert!(first.iter().any(|event| matches!(
+        event,
+        AgentEvent::Interruption { message }
+            if matches!(&message.content, MessageContent::Text(text) if text == "two")
+    )));
+    assert_eq!(
+        completed_response(&first).as_deref(),
+        Some("Follow-up reply")
+    );
+    assert_eq!(provider.request_texts(1), vec!["one", "First reply", "two"]);
+}
@@ -0,0 +1,252 @@
+//! Structured output tests for agent engine.
+//!
+//! This module tests agents with an output schema: validation of the final
+//! response, re-prompting with the validation errors, and the typed result
+//! returned in `AgentRunResult`.
+
+#[path = "helpers.rs"]
+mod helpers;
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use aisopod_agent::AgentRunner;
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{
+    ChatCompletionChunk, FinishReason, MessageDelta, ModelInfo, ProviderHealth,
+};
+use aisopod_provider::{ChatCompletionRequest, ChatCompletionStream, MessageContent, Role};
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::json;
+
+use helpers::{
+    test_agent_run_params, test_config, test_session_store, test_tool_registry, user_message,
+};
+
+/// Provider replying with a fixed sequence of texts and recording the
+/// requests it receives.
+struct ScriptedProvider {
+    replies: Mutex<VecDeque<&'static str>>,
+    requests: Mutex<Vec<ChatCompletionRequest>>,
+}
+
+impl ScriptedProvider {
+    fn new(replies: &[&'static str]) -> Self {
+        Self {
+            replies: Mutex::new(replies.iter().copied().collect()),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ModelProvider for ScriptedProvider {
+    fn id(&self) -> &str {
+        "mock"
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionStream> {
+        self.requests.lock().unwrap().push(request);
+        let reply = self
+            .replies
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or_else(|| anyhow::anyhow!("No scripted reply left"))?;
+        let chunk = ChatCompletionChunk {
+            id: "chunk_1".to_string(),
+            delta: MessageDelta {
+                role: Some(Role::Assistant),
+                content: Some(reply.to_string()),
+                tool_calls: None,
+                reasoning: None,
+            },
+            finish_reason: Some(FinishReason::Stop),
+            usage: None,
+        };
+        Ok(Box::pin(futures_util::stream::iter(vec![Ok(chunk)])))
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth> {
+        Ok(ProviderHealth {
+            available: true,
+            latency_ms: None,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Sentiment {
+    sentiment: String,
+    score: f64,
+}
+
+fn structured_runner(provider: Arc<ScriptedProvider>, max_output_repairs: usize) -> AgentRunner {
+    let mut config = test_config();
+    // Sessions are bound to "test-agent" in the test configuration
+    let agent = config
+        .agents
+        .agents
+        .iter_mut()
+        .find(|agent| agent.id == "test-agent")
+        .unwrap();
+    agent.output_schema = Some(json!({
+        "type": "object",
+        "properties": {
+            "sentiment": {"type": "string", "enum": ["positive", "negative"]},
+            "score": {"type": "number"}
+        },
+        "required": ["sentiment", "score"]
+    }));
+    agent.max_output_repairs = max_output_repairs;
+
+    let mut providers = aisopod_provider::ProviderRegistry::new();
+    providers.register(provider);
+    providers.register_alias("mock/test-model", "mock", "mock/test-model");
+
+    AgentRunner::new(
+        Arc::new(config),
+        Arc::new(providers),
+        test_tool_registry(),
+        test_session_store(),
+    )
+}
+
+fn params() -> aisopod_agent::AgentRunParams {
+    test_agent_run_params(
+        "test_session",
+        vec![user_message("I love this product!")],
+        Some("default"),
+    )
+}
+
+fn text(content: &MessageContent) -> &str {
+    match content {
+        MessageContent::Text(text) => text,
+        _ => "",
+    }
+}
+
+#[tokio::test]
+async fn test_valid_output_is_returned_typed() {
+    let provider = Arc::new(ScriptedProvider::new(&[
+        r#"{"sentiment": "positive", "score": 0.95}"#,
+    ]));
+    let runner = structured_runner(provider.clone(), 2);
+
+    let result = runner.run_and_get_result(params()).await.unwrap();
+
+    assert_eq!(
+        result.output::<Sentiment>().unwrap(),
+        Sentiment {
+            sentiment: "positive".to_string(),
+            score: 0.95,
+        }
+    );
+    assert_eq!(provider.requests.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_invalid_output_is_repaired() {
+    let provider = Arc::new(ScriptedProvider::new(&[
+        "The sentiment is positive.",
+        r#"{"sentiment": "very positive", "score": 0.95}"#,
+        r#"{"sentiment": "positive", "score": 0.95}"#,
+    ]));
+    let runner = structured_runner(provider.clone(), 2);
+
+    let result = runner.run_and_get_result(params()).await.unwrap();
+
+    assert_eq!(result.output::<Sentiment>().unwrap().sentiment, "positive");
+    let requests = provider.requests.lock().unwrap();
+    assert_eq!(requests.len(), 3);
+    let repair = requests[2].messages.last().unwrap();
+    assert_eq!(repair.role, Role::User);
+    assert!(text(&repair.content).contains("/sentiment:"));
+}
+
+#[tokio::test]
+async fn test_run_fails_when_repairs_are_exhausted() {
+    let provider = Arc::new(ScriptedProvider::new(&[
+        "The sentiment is positive.",
+        "Positive, definitely.",
+    ]));
+    let runner = structured_runner(provider.clone(), 1);
+
+    let err = runner.run_and_get_result(params()).await.unwrap_err();
+
+    assert!(err.to_string().contains("after 1 repair attempts"));
+    assert_eq!(provider.requests.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_agent_without_schema_has_no_structured_output() {
+    let mut providers = aisopod_provider::ProviderRegistry::new();
+    providers.register(Arc::new(ScriptedProvider::new(&["Hello!"])));
+    providers.register_alias("mock/test-model", "mock", "mock/test-model");
+    let runner = AgentRunner::new(
+        Arc::new(test_config()),
+        Arc::new(providers),
+        test_tool_registry(),
+        test_session_store(),
+    );
+
+    let result = runner.run_and_get_result(params()).await.unwrap();
+
+    assert_eq!(result.structured_output, None);
+    assert!(result.output::<Sentiment>().is_err());
+}
+
+/// Reply sink that records the messages sent, without edit support
+struct RecordingSink {
+    messages: Mutex<Vec<String>>,
+}
+
+#[async_trait::async_trait]
+impl aisopod_agent::ReplySink for RecordingSink {
+    async fn send(&self, text: &str) -> Result<Option<String>> {
+        self.messages.lock().unwrap().push(text.to_string());
+        Ok(None)
+    }
+
+    async fn edit(&self, _message_id: &str, _text: &str) -> Result<()> {
+        anyhow::bail!("edits are not supported")
+    }
+}
+
+#[tokio::test]
+async fn test_only_validated_output_is_streamed() {
+    let provider = Arc::new(ScriptedProvider::new(&[
+        "The sentiment is positive.",
+        r#"{"sentiment": "positive", "score": 0.95}"#,
+    ]));
+    let runner = structured_runner(provider.clone(), 1);
+    let sink = RecordingSink {
+        messages: Mutex::new(Vec::new()),
+    };
+
+    let result = runner
+        .run(params())
+        .await
+        .unwrap()
+        .stream_to(&sink, &aisopod_agent::StreamingReplyConfig::default())
+        .await
+        .unwrap()
+        .expect("run completed");
+
+    // The attempt that failed validation never reaches the channel
+    assert_eq!(result.output::<Sentiment>().unwrap().sentiment, "positive");
+    assert_eq!(
+        sink.messages.lock().unwrap().join(""),
+        r#"{"sentiment": "positive", "score": 0.95}"#
+    );
+    assert_eq!(provider.requests.lock().unwrap().len(), 2);
+}
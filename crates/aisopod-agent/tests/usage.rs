@@ -3,9 +3,21 @@
 //! This module tests the UsageTracker which tracks token usage
 //! at per-request, per-session, and per-agent levels.
 
-use aisopod_agent::usage::UsageTracker;
+use aisopod_agent::usage::{pricing_from_config, UsageTracker};
+use aisopod_config::types::{Model, ModelPrice, ModelsConfig};
+use aisopod_provider::TokenUsage;
 use std::sync::Arc;
 
+fn token_usage(prompt_tokens: u32, completion_tokens: u32) -> TokenUsage {
+    TokenUsage {
+        prompt_tokens,
+        completion_tokens,
+        total_tokens: prompt_tokens + completion_tokens,
+        cache_read_tokens: 0,
+        cache_write_tokens: 0,
+    }
+}
+
 #[test]
 fn test_new_tracker_has_empty_maps() {
     let tracker = UsageTracker::new();
@@ -216,3 +228,92 @@ fn test_concurrent_access() {
     assert_eq!(agent_usage.total_tokens, 600);
     assert_eq!(agent_usage.request_count, 40);
 }
+
+#[test]
+fn test_record_model_request_aggregates_cost() {
+    let tracker = UsageTracker::new();
+
+    let report = tracker.record_model_request(
+        "session_1",
+        "agent_1",
+        "openai",
+        "gpt-4o-mini",
+        &token_usage(1_000_000, 1_000_000),
+    );
+    assert_eq!(report.request_count, 1);
+    assert!((report.cost_usd - 0.75).abs() < 1e-9);
+
+    tracker.record_model_request(
+        "session_1",
+        "agent_1",
+        "anthropic",
+        "claude-3-5-haiku-20241022",
+        &token_usage(1_000_000, 0),
+    );
+    // Local models have no pricing
+    tracker.record_model_request(
+        "session_2",
+        "agent_1",
+        "ollama",
+        "llama3.2",
+        &token_usage(1_000_000, 0),
+    );
+
+    let session_usage = tracker.get_session_usage("session_1").unwrap();
+    assert_eq!(session_usage.request_count, 2);
+    assert!((session_usage.cost_usd - 1.55).abs() < 1e-9);
+    assert_eq!(
+        tracker.get_session_usage("session_2").unwrap().cost_usd,
+        0.0
+    );
+
+    let agent_usage = tracker.get_agent_usage("agent_1").unwrap();
+    assert_eq!(agent_usage.request_count, 3);
+    assert!((agent_usage.cost_usd - 1.55).abs() < 1e-9);
+}
+
+#[test]
+fn test_pricing_from_config_overrides() {
+    let config = ModelsConfig {
+        models: vec![
+            Model {
+                id: "gpt-4o".to_string(),
+                provider: "azure-openai".to_string(),
+                pricing: Some(ModelPrice {
+                    input: 2.0,
+                    output: 8.0,
+                    cache_read: None,
+                    cache_write: None,
+                }),
+                ..Default::default()
+            },
+            Model {
+                id: "llama3.2".to_string(),
+                pricing: Some(ModelPrice {
+                    input: 0.1,
+                    output: 0.1,
+                    cache_read: None,
+                    cache_write: None,
+                }),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    let tracker = UsageTracker::new().with_pricing(pricing_from_config(&config));
+    let usage = token_usage(1_000_000, 0);
+
+    let cost = |provider: &str, model: &str| tracker.pricing().cost(provider, model, &usage);
+    assert_eq!(
+        cost("azure-openai", "gpt-4o").map(|c| (c * 1e6).round()),
+        Some(2e6)
+    );
+    assert_eq!(
+        cost("openai", "gpt-4o").map(|c| (c * 1e6).round()),
+        Some(2.5e6)
+    );
+    assert_eq!(
+        cost("ollama", "llama3.2").map(|c| (c * 1e6).round()),
+        Some(1e5)
+    );
+}
@@ -477,6 +477,39 @@ impl OutboundAdapter for SlackChannel {
 
         Ok(())
     }
+
+    /// Send a text message and return its timestamp, which identifies it for edits.
+    async fn send_editable_text(&self, target: &MessageTarget, text: &str) -> Result<Option<String>> {
+        let account_with_conn = self.get_account_with_connection(&target.account_id)
+            .ok_or_else(|| anyhow::anyhow!("Account connection not found: {}", target.account_id))?;
+
+        let response = send::send_text_message(
+            account_with_conn.connection().client(),
+            &target.peer.id,
+            text,
+            None,
+        ).await?;
+
+        if !response.is_ok() {
+            let error = response.error.as_deref().unwrap_or("Unknown error");
+            return Err(anyhow::anyhow!("chat.postMessage failed: {}", error));
+        }
+        Ok(response.ts)
+    }
+
+    /// Edit a message identified by its timestamp.
+    async fn edit_text(&self, target: &MessageTarget, message_id: &str, text: &str) -> Result<()> {
+        let account_with_conn = self.get_account_with_connection(&target.account_id)
+            .ok_or_else(|| anyhow::anyhow!("Account connection not found: {}", target.account_id))?;
+
+        send::edit_message(
+            account_with_conn.connection().client(),
+            &target.peer.id,
+            message_id,
+            text,
+            None,
+        ).await
+    }
 }
 
 // ============================================================================
@@ -588,6 +621,10 @@ impl aisopod_channel::plugin::ChannelPlugin for SlackChannel {
         // Return None for now - can be implemented later with proper security adapter
         None
     }
+
+    fn outbound(&self) -> Option<&dyn OutboundAdapter> {
+        Some(self)
+    }
 }
 
 /// Register a Slack channel with the given configuration.
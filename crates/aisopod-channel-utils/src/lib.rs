@@ -9,8 +9,12 @@
 //! - [`rate_limit`] - Rate limiting utilities for API calls
 //! - [`markdown`] - Markdown format conversion between platforms
 //! - [`media`] - Media transcoding and validation utilities
-//! - [`retry`] - Retry logic for failed operations
+//! - [`retry`] - Retry logic and per-endpoint circuit breakers for failed operations
 //! - [`error_mapping`] - Common error types and conversion utilities
+//! - [`link_preview`] - OpenGraph/oEmbed link previews rendered per platform
+//! - [`mentions`] - Mapping between `@name` mentions and platform mention syntax
+//! - [`templates`] - Operator-customizable templates for system notifications
+//! - [`voice`] - Voice note detection, metadata normalization, and conversion
 //!
 //! ## Usage Examples
 //!
@@ -82,6 +86,24 @@
 //! }
 //! ```
 //!
+//! ### Circuit Breaking per Endpoint
+//!
+//! ```rust,ignore
+//! use aisopod_channel_utils::retry::{CircuitBreakerConfig, CircuitBreakerRegistry};
+//!
+//! let mut breakers = CircuitBreakerRegistry::new(CircuitBreakerConfig::default());
+//!
+//! if breakers.allow_request("sendMessage") {
+//!     match send_message().await {
+//!         Ok(_) => breakers.record_success("sendMessage"),
+//!         Err(_) => breakers.record_failure("sendMessage"),
+//!     }
+//! }
+//!
+//! // Inside StatusAdapter::health_check
+//! let health = breakers.health();
+//! ```
+//!
 //! ### Error Mapping
 //!
 //! ```rust,ignore
@@ -111,6 +133,58 @@
 //! }
 //! ```
 //!
+//! ### Link Previews
+//!
+//! ```rust,ignore
+//! use aisopod_channel_utils::link_preview::{render_for_platform, LinkPreviewFetcher, PreviewCache};
+//!
+//! let fetcher = LinkPreviewFetcher::new()?
+//!     .with_cache(PreviewCache::new("/var/cache/aisopod/previews", Duration::from_secs(86400), 500));
+//!
+//! for preview in fetcher.unfurl(&reply_text, 3).await {
+//!     let rendered = render_for_platform(&preview, "discord");
+//!     // Attach `rendered` to the outgoing message
+//! }
+//! ```
+//!
+//! ### Mentions
+//!
+//! ```rust,ignore
+//! use aisopod_channel_utils::mentions::{MentionMapper, MentionTarget};
+//!
+//! let mut mentions = MentionMapper::for_platform("slack");
+//! mentions.register(MentionTarget::new("U123").with_username("alice"));
+//!
+//! // Outbound: "@alice" becomes "<@U123>"
+//! let text = mentions.to_platform("Thanks @alice!");
+//!
+//! // Inbound: "<@U123>" becomes "@alice"
+//! let text = mentions.from_platform(&incoming_text);
+//! ```
+//!
+//! ### Notification Templates
+//!
+//! ```rust,ignore
+//! use aisopod_channel_utils::templates::{NotificationTemplates, TemplateContext, APPROVAL_PROMPT};
+//!
+//! // Usually deserialized from operator configuration
+//! let templates = NotificationTemplates::default();
+//!
+//! let ctx = TemplateContext::new().with("tool", "bash").with("summary", "ls -la");
+//! let text = templates.render(APPROVAL_PROMPT, "slack", Some("C123"), &ctx)?;
+//! ```
+//!
+//! ### Voice Notes
+//!
+//! ```rust,ignore
+//! use aisopod_channel_utils::voice::{convert_for_platform, encode_telegram_waveform};
+//!
+//! // Signal sends AAC, Telegram wants OGG/Opus
+//! let meta = convert_for_platform(&input, &output, "telegram").await?;
+//! let duration = meta.duration_secs();
+//! let waveform = meta.waveform.as_deref().map(encode_telegram_waveform);
+//! ```
+//!
 //! ## Supported Platforms
 //!
 //! | Platform | Rate Limits | Media Formats | Markdown |
@@ -140,11 +214,23 @@ pub mod markdown;
 pub mod media;
 pub mod retry;
 pub mod error_mapping;
+pub mod link_preview;
+pub mod mentions;
+pub mod templates;
+pub mod voice;
 
 // Re-export common types for convenience
 pub use rate_limit::{RateLimiter, RateLimitResult};
 pub use markdown::{parse_markdown, MarkdownFormat, MarkdownNode};
 pub use media::{validate_media, MediaConstraints, MediaType, MediaInfo, MediaError, ConversionOptions, detect_media_type_from_extension, get_mime_type, convert_media};
+pub use retry::{
+    CircuitBreaker, CircuitBreakerConfig, CircuitBreakerRegistry, CircuitMetrics, CircuitState,
+    CircuitStateListener,
+};
+pub use link_preview::{LinkPreview, LinkPreviewFetcher, PreviewCache, RenderedPreview};
+pub use mentions::{BroadcastMention, MentionMapper, MentionStyle, MentionTarget};
+pub use templates::{NotificationTemplates, Template, TemplateContext};
+pub use voice::{VoiceFormat, VoiceNoteMetadata};
 pub use error_mapping::{
     ChannelError,
     ChannelResult,
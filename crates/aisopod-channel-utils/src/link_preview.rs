@@ -0,0 +1,1038 @@
+//! Link preview (unfurl) generation for URLs in agent replies.
+//!
+//! This module fetches OpenGraph and oEmbed metadata for links and renders
+//! platform-appropriate previews: Discord embeds, Slack Block Kit sections,
+//! and plain one-line summaries for text-only platforms such as IRC.
+//! Fetched metadata can be kept in a small on-disk cache so repeated links
+//! do not trigger repeated requests.
+//!
+//! Links come from untrusted messages, so the fetcher only contacts public
+//! addresses, on the first request and on every redirect, and follows an
+//! oEmbed discovery link only to the page's own origin or to a configured
+//! oEmbed provider.
+
+use std::fs;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use aisopod_shared::net::{is_public_address, PublicAddressResolver};
+use regex::Regex;
+use reqwest::redirect::{Attempt, Policy};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{debug, warn};
+use url::{Host, Url};
+
+use crate::error_mapping::{error_from_http_status, ChannelError, ChannelResult};
+
+/// Platform name used in errors raised by this module.
+const PLATFORM: &str = "link_preview";
+
+/// Default maximum number of bytes read from a fetched page.
+pub const DEFAULT_MAX_BODY_BYTES: usize = 512 * 1024;
+
+/// Default request timeout for metadata fetches.
+pub const DEFAULT_FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Maximum number of redirects followed for a single fetch.
+const MAX_REDIRECTS: usize = 5;
+
+/// Maximum description length used when rendering previews.
+const MAX_DESCRIPTION_CHARS: usize = 300;
+
+/// Where the preview metadata came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewSource {
+    /// OpenGraph `<meta property="og:*">` tags
+    OpenGraph,
+    /// An oEmbed JSON endpoint
+    OEmbed,
+    /// Fallback HTML `<title>` / `<meta name="description">` tags
+    Html,
+    /// The URL points directly at an image
+    Image,
+}
+
+/// Metadata describing a link, ready to be rendered for a platform.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkPreview {
+    /// The URL being previewed
+    pub url: String,
+    /// Page title
+    pub title: Option<String>,
+    /// Short description of the page
+    pub description: Option<String>,
+    /// Name of the site (e.g. "GitHub")
+    pub site_name: Option<String>,
+    /// URL of a representative image
+    pub image_url: Option<String>,
+    /// Source of the metadata
+    pub source: PreviewSource,
+}
+
+impl LinkPreview {
+    /// Create an empty preview for a URL.
+    pub fn new(url: impl Into<String>, source: PreviewSource) -> Self {
+        Self {
+            url: url.into(),
+            title: None,
+            description: None,
+            site_name: None,
+            image_url: None,
+            source,
+        }
+    }
+
+    /// Whether the preview carries anything worth displaying.
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.description.is_none() && self.image_url.is_none()
+    }
+
+    /// Fill missing fields from another preview of the same URL.
+    fn merge_missing(&mut self, other: LinkPreview) {
+        if self.title.is_none() {
+            self.title = other.title;
+        }
+        if self.description.is_none() {
+            self.description = other.description;
+        }
+        if self.site_name.is_none() {
+            self.site_name = other.site_name;
+        }
+        if self.image_url.is_none() {
+            self.image_url = other.image_url;
+        }
+    }
+}
+
+/// A preview rendered for a specific platform.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenderedPreview {
+    /// A Discord embed object
+    DiscordEmbed(Value),
+    /// A list of Slack Block Kit blocks
+    SlackBlocks(Value),
+    /// A plain-text summary
+    Text(String),
+}
+
+fn url_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"https?://[^\s<>"'`]+"#).unwrap())
+}
+
+fn meta_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?is)<meta\s[^>]*>").unwrap())
+}
+
+fn link_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?is)<link\s[^>]*>").unwrap())
+}
+
+fn attr_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"(?is)([a-z:_-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap())
+}
+
+fn title_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap())
+}
+
+/// Extract HTTP(S) URLs from a block of text, in order of appearance and
+/// without duplicates.
+///
+/// Trailing punctuation that commonly follows a link in prose (such as
+/// `.`, `,` or a closing parenthesis) is stripped.
+pub fn extract_urls(text: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for m in url_regex().find_iter(text) {
+        let url = m
+            .as_str()
+            .trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '}', '*', '_']);
+        if !urls.iter().any(|u| u == url) {
+            urls.push(url.to_string());
+        }
+    }
+    urls
+}
+
+/// Decode the handful of HTML entities commonly found in meta tags.
+fn decode_entities(input: &str) -> String {
+    input
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// Collect the attributes of a single HTML tag into (lowercase name, value) pairs.
+fn tag_attributes(tag: &str) -> Vec<(String, String)> {
+    attr_regex()
+        .captures_iter(tag)
+        .map(|c| {
+            let value = c.get(2).or_else(|| c.get(3)).map_or("", |m| m.as_str());
+            (c[1].to_ascii_lowercase(), decode_entities(value.trim()))
+        })
+        .collect()
+}
+
+fn non_empty(value: String) -> Option<String> {
+    let trimmed = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed)
+    }
+}
+
+/// Parse OpenGraph metadata (with HTML fallbacks) from a page.
+///
+/// # Arguments
+///
+/// * `html` - The page body
+/// * `url` - The URL the page was fetched from
+///
+/// # Returns
+///
+/// Returns `None` if the page has neither OpenGraph tags nor a title or
+/// description.
+pub fn parse_open_graph(html: &str, url: &str) -> Option<LinkPreview> {
+    let mut og = LinkPreview::new(url, PreviewSource::OpenGraph);
+    let mut fallback = LinkPreview::new(url, PreviewSource::Html);
+
+    for tag in meta_regex().find_iter(html) {
+        let attrs = tag_attributes(tag.as_str());
+        let key = attrs
+            .iter()
+            .find(|(name, _)| name == "property" || name == "name")
+            .map(|(_, value)| value.to_ascii_lowercase());
+        let content = attrs
+            .iter()
+            .find(|(name, _)| name == "content")
+            .and_then(|(_, value)| non_empty(value.clone()));
+        let (Some(key), Some(content)) = (key, content) else {
+            continue;
+        };
+
+        match key.as_str() {
+            "og:title" => og.title = Some(content),
+            "og:description" => og.description = Some(content),
+            "og:site_name" => og.site_name = Some(content),
+            "og:image" | "og:image:url" | "og:image:secure_url" if og.image_url.is_none() => {
+                og.image_url = Some(content)
+            }
+            "og:url" => og.url = content,
+            "twitter:title" => fallback.title = fallback.title.or(Some(content)),
+            "twitter:description" | "description" => {
+                fallback.description = fallback.description.or(Some(content))
+            }
+            "twitter:image" => fallback.image_url = fallback.image_url.or(Some(content)),
+            _ => {}
+        }
+    }
+
+    if fallback.title.is_none() {
+        fallback.title = title_regex()
+            .captures(html)
+            .and_then(|c| non_empty(decode_entities(&c[1])));
+    }
+
+    if og.is_empty() {
+        return if fallback.is_empty() {
+            None
+        } else {
+            Some(fallback)
+        };
+    }
+
+    og.merge_missing(fallback);
+    Some(og)
+}
+
+/// Find the oEmbed JSON discovery link in a page, if any.
+pub fn find_oembed_url(html: &str) -> Option<String> {
+    link_regex().find_iter(html).find_map(|tag| {
+        let attrs = tag_attributes(tag.as_str());
+        let is_oembed = attrs.iter().any(|(name, value)| {
+            name == "type" && value.eq_ignore_ascii_case("application/json+oembed")
+        });
+        if !is_oembed {
+            return None;
+        }
+        attrs
+            .into_iter()
+            .find(|(name, _)| name == "href")
+            .map(|(_, href)| href)
+    })
+}
+
+/// Parse an oEmbed JSON response.
+///
+/// # Arguments
+///
+/// * `body` - The oEmbed response body
+/// * `url` - The URL being previewed
+pub fn parse_oembed(body: &str, url: &str) -> ChannelResult<LinkPreview> {
+    let value: Value = serde_json::from_str(body).map_err(|e| {
+        ChannelError::invalid_request(
+            PLATFORM,
+            format!("Invalid oEmbed response: {}", e),
+            None::<String>,
+        )
+    })?;
+
+    let field = |name: &str| {
+        value
+            .get(name)
+            .and_then(Value::as_str)
+            .and_then(|s| non_empty(s.to_string()))
+    };
+
+    let mut preview = LinkPreview::new(url, PreviewSource::OEmbed);
+    preview.title = field("title");
+    preview.site_name = field("provider_name");
+    preview.description = field("author_name").map(|author| format!("by {}", author));
+    preview.image_url = field("thumbnail_url").or_else(|| {
+        // Photo embeds carry the image itself in `url`
+        (field("type").as_deref() == Some("photo"))
+            .then(|| field("url"))
+            .flatten()
+    });
+    Ok(preview)
+}
+
+/// Truncate text to a number of characters, appending an ellipsis if cut.
+fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut out: String = text.chars().take(max.saturating_sub(1)).collect();
+    out.push('…');
+    out
+}
+
+/// Render a preview as a Discord embed object.
+pub fn render_discord_embed(preview: &LinkPreview) -> Value {
+    let mut embed = json!({ "url": preview.url });
+    if let Some(title) = &preview.title {
+        embed["title"] = json!(truncate_chars(title, 256));
+    }
+    if let Some(description) = &preview.description {
+        embed["description"] = json!(truncate_chars(description, MAX_DESCRIPTION_CHARS));
+    }
+    if let Some(site_name) = &preview.site_name {
+        embed["provider"] = json!({ "name": site_name });
+    }
+    if let Some(image_url) = &preview.image_url {
+        embed["thumbnail"] = json!({ "url": image_url });
+    }
+    embed
+}
+
+/// Render a preview as Slack Block Kit blocks.
+pub fn render_slack_blocks(preview: &LinkPreview) -> Value {
+    let title = preview.title.as_deref().unwrap_or(&preview.url);
+    let mut text = format!("*<{}|{}>*", preview.url, title.replace(['<', '>', '|'], ""));
+    if let Some(description) = &preview.description {
+        text.push('\n');
+        text.push_str(&truncate_chars(description, MAX_DESCRIPTION_CHARS));
+    }
+
+    let mut section = json!({
+        "type": "section",
+        "text": { "type": "mrkdwn", "text": text },
+    });
+    if let Some(image_url) = &preview.image_url {
+        section["accessory"] = json!({
+            "type": "image",
+            "image_url": image_url,
+            "alt_text": title,
+        });
+    }
+
+    let mut blocks = vec![section];
+    if let Some(site_name) = &preview.site_name {
+        blocks.push(json!({
+            "type": "context",
+            "elements": [{ "type": "mrkdwn", "text": site_name }],
+        }));
+    }
+    Value::Array(blocks)
+}
+
+/// Render a preview as a single line of plain text.
+///
+/// The format is `[site] title - description <url>`, suitable for IRC and
+/// other platforms without rich embeds.
+pub fn render_plain_summary(preview: &LinkPreview, max_chars: usize) -> String {
+    let mut summary = String::new();
+    if let Some(site_name) = &preview.site_name {
+        summary.push_str(&format!("[{}] ", site_name));
+    }
+    summary.push_str(preview.title.as_deref().unwrap_or(&preview.url));
+    if let Some(description) = &preview.description {
+        summary.push_str(" - ");
+        summary.push_str(description);
+    }
+    truncate_chars(&summary, max_chars)
+}
+
+/// Render a preview in the most appropriate form for a platform.
+///
+/// # Arguments
+///
+/// * `preview` - The preview to render
+/// * `platform` - Platform name (e.g., "discord", "slack", "irc")
+pub fn render_for_platform(preview: &LinkPreview, platform: &str) -> RenderedPreview {
+    match platform {
+        "discord" => RenderedPreview::DiscordEmbed(render_discord_embed(preview)),
+        "slack" => RenderedPreview::SlackBlocks(render_slack_blocks(preview)),
+        // IRC lines are limited to 512 bytes including the protocol prefix
+        "irc" | "twitch" => RenderedPreview::Text(render_plain_summary(preview, 350)),
+        _ => RenderedPreview::Text(render_plain_summary(preview, 500)),
+    }
+}
+
+/// A cached preview together with the time it was fetched.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedPreview {
+    /// Seconds since the Unix epoch when the preview was fetched
+    fetched_at: u64,
+    /// The preview itself
+    preview: LinkPreview,
+}
+
+/// Small on-disk cache of link previews.
+///
+/// Each entry is stored as a JSON file named after a hash of the URL.
+/// Entries older than the TTL are ignored, and the oldest entries are
+/// evicted once `max_entries` is exceeded.
+#[derive(Debug, Clone)]
+pub struct PreviewCache {
+    /// Directory holding the cache files
+    dir: PathBuf,
+    /// How long an entry stays valid
+    ttl: Duration,
+    /// Maximum number of entries kept on disk
+    max_entries: usize,
+}
+
+impl PreviewCache {
+    /// Create a cache in the given directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory for cache files (created on first write)
+    /// * `ttl` - How long an entry stays valid
+    /// * `max_entries` - Maximum number of entries kept on disk
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl,
+            max_entries,
+        }
+    }
+
+    /// Look up a cached preview for a URL.
+    pub fn get(&self, url: &str) -> Option<LinkPreview> {
+        let data = fs::read_to_string(self.entry_path(url)).ok()?;
+        let cached: CachedPreview = serde_json::from_str(&data).ok()?;
+        if now_secs().saturating_sub(cached.fetched_at) > self.ttl.as_secs()
+            || cached.preview.url != url
+        {
+            return None;
+        }
+        Some(cached.preview)
+    }
+
+    /// Store a preview under the given URL.
+    pub fn put(&self, url: &str, preview: &LinkPreview) -> std::io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let cached = CachedPreview {
+            fetched_at: now_secs(),
+            preview: LinkPreview {
+                url: url.to_string(),
+                ..preview.clone()
+            },
+        };
+        let data = serde_json::to_string(&cached)?;
+        fs::write(self.entry_path(url), data)?;
+        self.evict()
+    }
+
+    /// Remove every cached entry.
+    pub fn clear(&self) -> std::io::Result<()> {
+        if self.dir.exists() {
+            for entry in fs::read_dir(&self.dir)? {
+                let path = entry?.path();
+                if path.extension().is_some_and(|ext| ext == "json") {
+                    fs::remove_file(path)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}.json", fnv1a(url.as_bytes())))
+    }
+
+    /// Remove the oldest entries beyond `max_entries`.
+    fn evict(&self) -> std::io::Result<()> {
+        let mut entries: Vec<(SystemTime, PathBuf)> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .map(|path| {
+                let modified = fs::metadata(&path)
+                    .and_then(|m| m.modified())
+                    .unwrap_or(UNIX_EPOCH);
+                (modified, path)
+            })
+            .collect();
+
+        if entries.len() <= self.max_entries {
+            return Ok(());
+        }
+
+        entries.sort();
+        let excess = entries.len() - self.max_entries;
+        for (_, path) in entries.into_iter().take(excess) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+/// 64-bit FNV-1a hash, stable across builds so cache file names persist.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Fetches link metadata over HTTP and turns it into [`LinkPreview`]s.
+#[derive(Debug, Clone)]
+pub struct LinkPreviewFetcher {
+    /// HTTP client used for metadata requests
+    client: reqwest::Client,
+    /// Optional on-disk cache
+    cache: Option<PreviewCache>,
+    /// Maximum number of bytes read from a page
+    max_body_bytes: usize,
+    /// Hosts whose oEmbed endpoints may be used for any page
+    oembed_providers: Vec<String>,
+}
+
+impl LinkPreviewFetcher {
+    /// Create a fetcher with default timeout and no cache.
+    ///
+    /// Host names are resolved to public addresses only, and redirects to
+    /// private IP literals are refused. Proxies from the environment are
+    /// ignored, since a proxy would resolve host names itself.
+    ///
+    /// Fails if the HTTP client cannot be built.
+    pub fn new() -> ChannelResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(DEFAULT_FETCH_TIMEOUT)
+            .user_agent(concat!("aisopod-link-preview/", env!("CARGO_PKG_VERSION")))
+            .redirect(Policy::custom(check_redirect))
+            .dns_resolver(Arc::new(PublicAddressResolver))
+            .no_proxy()
+            .build()
+            .map_err(|e| ChannelError::generic(PLATFORM, e.to_string()))?;
+        Ok(Self::with_client(client))
+    }
+
+    /// Create a fetcher using a pre-configured HTTP client.
+    ///
+    /// Only the URLs requested directly are checked for private addresses;
+    /// redirects and DNS answers are left to the client.
+    pub fn with_client(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            cache: None,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            oembed_providers: Vec::new(),
+        }
+    }
+
+    /// Allow oEmbed endpoints on these hosts for pages on any origin.
+    ///
+    /// Entries match a host exactly, or any subdomain when written as
+    /// `*.example.com`.
+    pub fn with_oembed_providers(mut self, hosts: Vec<String>) -> Self {
+        self.oembed_providers = hosts;
+        self
+    }
+
+    /// Attach an on-disk cache.
+    pub fn with_cache(mut self, cache: PreviewCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Limit how many bytes of each page are inspected.
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    /// Fetch a preview for a single URL, consulting the cache first.
+    pub async fn fetch(&self, url: &str) -> ChannelResult<LinkPreview> {
+        if let Some(cached) = self.cache.as_ref().and_then(|cache| cache.get(url)) {
+            debug!("Link preview cache hit for {}", url);
+            return Ok(cached);
+        }
+
+        let preview = self.fetch_uncached(url).await?;
+
+        if let Some(cache) = &self.cache {
+            if let Err(e) = cache.put(url, &preview) {
+                warn!("Failed to cache link preview for {}: {}", url, e);
+            }
+        }
+        Ok(preview)
+    }
+
+    /// Fetch previews for every URL found in a message.
+    ///
+    /// Links that fail to resolve or have no usable metadata are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The message text to scan for links
+    /// * `max_links` - Maximum number of links to preview
+    pub async fn unfurl(&self, text: &str, max_links: usize) -> Vec<LinkPreview> {
+        let mut previews = Vec::new();
+        for url in extract_urls(text).into_iter().take(max_links) {
+            match self.fetch(&url).await {
+                Ok(preview) if !preview.is_empty() => previews.push(preview),
+                Ok(_) => debug!("No preview metadata for {}", url),
+                Err(e) => debug!("Failed to unfurl {}: {}", url, e),
+            }
+        }
+        previews
+    }
+
+    async fn fetch_uncached(&self, url: &str) -> ChannelResult<LinkPreview> {
+        let response = self.get(url).await?;
+        let page_url = response.url().clone();
+
+        let is_image = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.starts_with("image/"));
+        if is_image {
+            let mut preview = LinkPreview::new(url, PreviewSource::Image);
+            preview.image_url = Some(url.to_string());
+            return Ok(preview);
+        }
+
+        let body = self.read_body(response).await?;
+        let mut preview = parse_open_graph(&body, url)
+            .unwrap_or_else(|| LinkPreview::new(url, PreviewSource::Html));
+
+        // Fall back to oEmbed when the page itself lacks a title or image
+        if preview.title.is_none() || preview.image_url.is_none() {
+            let oembed_url = find_oembed_url(&body)
+                .and_then(|href| page_url.join(&href).ok())
+                .filter(|oembed_url| self.is_oembed_allowed(oembed_url, &page_url));
+            if let Some(oembed_url) = oembed_url {
+                match self.fetch_oembed(oembed_url.as_str(), url).await {
+                    Ok(oembed) if preview.is_empty() => preview = oembed,
+                    Ok(oembed) => preview.merge_missing(oembed),
+                    Err(e) => debug!("oEmbed lookup failed for {}: {}", url, e),
+                }
+            }
+        }
+
+        Ok(preview)
+    }
+
+    async fn fetch_oembed(&self, oembed_url: &str, url: &str) -> ChannelResult<LinkPreview> {
+        let response = self.get(oembed_url).await?;
+        let body = self.read_body(response).await?;
+        parse_oembed(&body, url)
+    }
+
+    /// Whether an oEmbed endpoint may be used for a page: it must be on the
+    /// page's own origin or on a configured provider host.
+    fn is_oembed_allowed(&self, oembed_url: &Url, page_url: &Url) -> bool {
+        if oembed_url.origin() == page_url.origin() {
+            return true;
+        }
+        let Some(host) = oembed_url.host_str() else {
+            return false;
+        };
+        let host = host.to_ascii_lowercase();
+        let allowed = self.oembed_providers.iter().any(|pattern| {
+            let pattern = pattern.to_ascii_lowercase();
+            match pattern.strip_prefix("*.") {
+                Some(suffix) => host.ends_with(&format!(".{}", suffix)),
+                None => pattern == host,
+            }
+        });
+        if !allowed {
+            debug!("Ignoring oEmbed endpoint {} for {}", oembed_url, page_url);
+        }
+        allowed
+    }
+
+    async fn get(&self, url: &str) -> ChannelResult<reqwest::Response> {
+        let parsed = Url::parse(url).map_err(|e| {
+            ChannelError::invalid_request(
+                PLATFORM,
+                format!("Invalid URL {}: {}", url, e),
+                None::<String>,
+            )
+        })?;
+        if let Err(reason) = check_url(&parsed) {
+            return Err(ChannelError::invalid_request(
+                PLATFORM,
+                reason,
+                None::<String>,
+            ));
+        }
+
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| ChannelError::network(PLATFORM, e.to_string(), None))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(error_from_http_status(
+                status.as_u16(),
+                format!("Failed to fetch {}", url),
+                PLATFORM,
+            ));
+        }
+        Ok(response)
+    }
+
+    async fn read_body(&self, mut response: reqwest::Response) -> ChannelResult<String> {
+        let mut body = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| ChannelError::network(PLATFORM, e.to_string(), None))?
+        {
+            body.extend_from_slice(&chunk);
+            if body.len() >= self.max_body_bytes {
+                body.truncate(self.max_body_bytes);
+                break;
+            }
+        }
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+}
+
+/// Checks that a URL uses http(s) and does not name a private IP address.
+///
+/// Host names are checked once resolved, by [`PublicAddressResolver`].
+fn check_url(url: &Url) -> Result<(), String> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("Unsupported URL scheme '{}'", url.scheme()));
+    }
+    let ip = match url.host() {
+        Some(Host::Ipv4(ip)) => IpAddr::V4(ip),
+        Some(Host::Ipv6(ip)) => IpAddr::V6(ip),
+        Some(Host::Domain(_)) => return Ok(()),
+        None => return Err(format!("URL {} has no host", url)),
+    };
+    if is_public_address(ip) {
+        Ok(())
+    } else {
+        Err(format!("Address {} is not a public address", ip))
+    }
+}
+
+/// Redirect policy re-checking every hop with [`check_url`].
+fn check_redirect(attempt: Attempt) -> reqwest::redirect::Action {
+    if attempt.previous().len() > MAX_REDIRECTS {
+        return attempt.error(format!("more than {} redirects", MAX_REDIRECTS));
+    }
+    match check_url(attempt.url()) {
+        Ok(()) => attempt.follow(),
+        Err(reason) => attempt.error(reason),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><head>
+        <title>Fallback title</title>
+        <meta property="og:title" content="Rust &amp; You" />
+        <meta content="A language empowering everyone" property="og:description">
+        <meta property="og:site_name" content="rust-lang.org">
+        <meta property="og:image" content="https://www.rust-lang.org/logo.png">
+        <link rel="alternate" type="application/json+oembed" href="https://example.com/oembed?url=x">
+        </head></html>"#;
+
+    fn sample_preview() -> LinkPreview {
+        parse_open_graph(PAGE, "https://www.rust-lang.org/").unwrap()
+    }
+
+    #[test]
+    fn test_extract_urls() {
+        let urls = extract_urls(
+            "See https://example.com/a, and (https://example.com/b). Again: https://example.com/a",
+        );
+        assert_eq!(urls, vec!["https://example.com/a", "https://example.com/b"]);
+    }
+
+    #[test]
+    fn test_parse_open_graph() {
+        let preview = sample_preview();
+        assert_eq!(preview.source, PreviewSource::OpenGraph);
+        assert_eq!(preview.title.as_deref(), Some("Rust & You"));
+        assert_eq!(
+            preview.description.as_deref(),
+            Some("A language empowering everyone")
+        );
+        assert_eq!(preview.site_name.as_deref(), Some("rust-lang.org"));
+        assert_eq!(
+            preview.image_url.as_deref(),
+            Some("https://www.rust-lang.org/logo.png")
+        );
+    }
+
+    #[test]
+    fn test_parse_html_fallback() {
+        let html = r#"<title> Plain   page </title><meta name="description" content="Just HTML">"#;
+        let preview = parse_open_graph(html, "https://example.com").unwrap();
+        assert_eq!(preview.source, PreviewSource::Html);
+        assert_eq!(preview.title.as_deref(), Some("Plain page"));
+        assert_eq!(preview.description.as_deref(), Some("Just HTML"));
+
+        assert!(parse_open_graph("<p>nothing</p>", "https://example.com").is_none());
+    }
+
+    #[test]
+    fn test_find_and_parse_oembed() {
+        assert_eq!(
+            find_oembed_url(PAGE).as_deref(),
+            Some("https://example.com/oembed?url=x")
+        );
+
+        let body = r#"{"type":"video","title":"A video","author_name":"Someone",
+            "provider_name":"VideoSite","thumbnail_url":"https://example.com/t.jpg"}"#;
+        let preview = parse_oembed(body, "https://example.com/v/1").unwrap();
+        assert_eq!(preview.source, PreviewSource::OEmbed);
+        assert_eq!(preview.title.as_deref(), Some("A video"));
+        assert_eq!(preview.description.as_deref(), Some("by Someone"));
+        assert_eq!(preview.site_name.as_deref(), Some("VideoSite"));
+        assert_eq!(
+            preview.image_url.as_deref(),
+            Some("https://example.com/t.jpg")
+        );
+
+        assert!(parse_oembed("not json", "https://example.com").is_err());
+    }
+
+    #[test]
+    fn test_render_discord_embed() {
+        let embed = render_discord_embed(&sample_preview());
+        assert_eq!(embed["title"], "Rust & You");
+        assert_eq!(embed["url"], "https://www.rust-lang.org/");
+        assert_eq!(embed["provider"]["name"], "rust-lang.org");
+        assert_eq!(
+            embed["thumbnail"]["url"],
+            "https://www.rust-lang.org/logo.png"
+        );
+    }
+
+    #[test]
+    fn test_render_slack_blocks() {
+        let blocks = render_slack_blocks(&sample_preview());
+        let blocks = blocks.as_array().unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0]["type"], "section");
+        assert!(blocks[0]["text"]["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("*<https://www.rust-lang.org/|Rust & You>*"));
+        assert_eq!(blocks[0]["accessory"]["type"], "image");
+        assert_eq!(blocks[1]["type"], "context");
+    }
+
+    #[test]
+    fn test_render_plain_summary() {
+        let summary = render_plain_summary(&sample_preview(), 500);
+        assert_eq!(
+            summary,
+            "[rust-lang.org] Rust & You - A language empowering everyone"
+        );
+
+        let truncated = render_plain_summary(&sample_preview(), 20);
+        assert_eq!(truncated.chars().count(), 20);
+        assert!(truncated.ends_with('…'));
+    }
+
+    #[test]
+    fn test_render_for_platform() {
+        let preview = sample_preview();
+        assert!(matches!(
+            render_for_platform(&preview, "discord"),
+            RenderedPreview::DiscordEmbed(_)
+        ));
+        assert!(matches!(
+            render_for_platform(&preview, "slack"),
+            RenderedPreview::SlackBlocks(_)
+        ));
+        assert!(matches!(
+            render_for_platform(&preview, "irc"),
+            RenderedPreview::Text(_)
+        ));
+    }
+
+    #[test]
+    fn test_preview_cache_roundtrip_and_eviction() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = PreviewCache::new(dir.path(), Duration::from_secs(3600), 2);
+        let preview = sample_preview();
+
+        assert!(cache.get("https://a.example").is_none());
+        cache.put("https://a.example", &preview).unwrap();
+        assert_eq!(
+            cache.get("https://a.example").unwrap().title.as_deref(),
+            Some("Rust & You")
+        );
+
+        cache.put("https://b.example", &preview).unwrap();
+        cache.put("https://c.example", &preview).unwrap();
+        let files = fs::read_dir(dir.path()).unwrap().count();
+        assert_eq!(files, 2);
+
+        cache.clear().unwrap();
+        assert!(cache.get("https://c.example").is_none());
+    }
+
+    #[test]
+    fn test_preview_cache_expired() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = PreviewCache::new(dir.path(), Duration::from_secs(60), 10);
+        let path = cache.entry_path("https://a.example");
+        let write_entry = |age: u64| {
+            let cached = CachedPreview {
+                fetched_at: now_secs() - age,
+                preview: LinkPreview {
+                    url: "https://a.example".to_string(),
+                    ..sample_preview()
+                },
+            };
+            fs::write(&path, serde_json::to_string(&cached).unwrap()).unwrap();
+        };
+
+        write_entry(30);
+        assert_eq!(
+            cache.get("https://a.example").unwrap().title.as_deref(),
+            Some("Rust & You")
+        );
+
+        write_entry(120);
+        assert!(cache.get("https://a.example").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_refuses_private_addresses() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let fetcher = LinkPreviewFetcher::new().unwrap();
+
+        for url in [
+            format!("http://127.0.0.1:{}/", port),
+            format!("http://localhost:{}/", port),
+            "http://169.254.169.254/latest/meta-data/".to_string(),
+            "http://[fd00::1]/".to_string(),
+            "file:///etc/passwd".to_string(),
+        ] {
+            assert!(fetcher.fetch(&url).await.is_err(), "{}", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_ignores_environment_proxy() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let proxy = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_url = format!("http://{}", proxy.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = proxy.accept().await {
+                let mut buf = vec![0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\n\r\n{}",
+                    PAGE.len(),
+                    PAGE
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        std::env::set_var("HTTP_PROXY", &proxy_url);
+        std::env::set_var("http_proxy", &proxy_url);
+
+        // A proxy would resolve the host itself, past the address check
+        let fetcher = LinkPreviewFetcher::new().unwrap();
+        let result = fetcher.fetch("http://localhost/").await;
+        std::env::remove_var("HTTP_PROXY");
+        std::env::remove_var("http_proxy");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_check_url() {
+        for url in ["https://example.com/", "http://93.184.216.34/"] {
+            assert!(check_url(&Url::parse(url).unwrap()).is_ok(), "{}", url);
+        }
+        for url in [
+            "http://10.0.0.1/",
+            "http://[::1]/",
+            "http://[::ffff:192.168.0.1]/",
+            "ftp://example.com/",
+        ] {
+            assert!(check_url(&Url::parse(url).unwrap()).is_err(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_oembed_endpoint_must_match_origin_or_provider() {
+        let fetcher = LinkPreviewFetcher::new()
+            .unwrap()
+            .with_oembed_providers(vec!["*.youtube.com".to_string()]);
+        let page = Url::parse("https://blog.example.com/post").unwrap();
+        let allowed =
+            |endpoint: &str| fetcher.is_oembed_allowed(&Url::parse(endpoint).unwrap(), &page);
+
+        assert!(allowed("https://blog.example.com/oembed?url=x"));
+        assert!(allowed("https://www.youtube.com/oembed?url=x"));
+        assert!(!allowed("http://blog.example.com/oembed?url=x"));
+        assert!(!allowed("https://internal.example.com/oembed"));
+        assert!(!allowed("http://169.254.169.254/latest/meta-data/"));
+    }
+}
@@ -0,0 +1,568 @@
+//! Mention resolution between display names and platform mention syntax.
+//!
+//! Agents write mentions the way people do, as `@alice`. Each platform has
+//! its own wire syntax for a mention that actually notifies the user:
+//!
+//! | Platform | User mention | Broadcast |
+//! |----------|--------------|-----------|
+//! | discord | `<@123>` | `@everyone`, `@here` |
+//! | slack | `<@U123>` | `<!channel>`, `<!here>`, `<!everyone>` |
+//! | telegram | `[Alice](tg://user?id=123)` | - |
+//! | matrix | `[Alice](https://matrix.to/#/@alice:example.org)` | `@room` |
+//! | msteams | `<at>Alice</at>` | - |
+//! | others | `@alice` | - |
+//!
+//! [`MentionMapper`] converts agent output into the platform syntax and
+//! converts inbound platform mentions back into `@name` form. Broadcast
+//! mentions in agent output are only converted on channels that opt in
+//! with [`MentionMapper::with_broadcasts`]; elsewhere they are defused so
+//! that model output cannot ping a whole server.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use regex::{Captures, Regex};
+
+/// Platform mention syntax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MentionStyle {
+    /// Discord: `<@id>`
+    Discord,
+    /// Slack: `<@id>`
+    Slack,
+    /// Telegram (Markdown): `[name](tg://user?id=id)`
+    Telegram,
+    /// Matrix: `[name](https://matrix.to/#/@user:server)`
+    Matrix,
+    /// Microsoft Teams: `<at>name</at>`
+    MsTeams,
+    /// Plain `@username` (IRC, Mattermost, Twitch, ...)
+    Plain,
+}
+
+impl MentionStyle {
+    /// Get the mention style for a platform name.
+    pub fn for_platform(platform: &str) -> Self {
+        match platform {
+            "discord" => Self::Discord,
+            "slack" => Self::Slack,
+            "telegram" => Self::Telegram,
+            "matrix" => Self::Matrix,
+            "msteams" => Self::MsTeams,
+            _ => Self::Plain,
+        }
+    }
+}
+
+/// Mentions that address everyone in a conversation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BroadcastMention {
+    /// Everyone in the conversation (`@everyone`, `@channel`, `@room`)
+    Everyone,
+    /// Only members currently online (`@here`)
+    Here,
+}
+
+/// A user that can be mentioned on a platform.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MentionTarget {
+    /// Platform user ID (e.g. `U123`, `123456789`, `@alice:example.org`)
+    pub user_id: String,
+    /// Username or handle, without a leading `@`
+    pub username: Option<String>,
+    /// Human-readable display name
+    pub display_name: Option<String>,
+}
+
+impl MentionTarget {
+    /// Create a target from a user ID.
+    pub fn new(user_id: impl Into<String>) -> Self {
+        Self {
+            user_id: user_id.into(),
+            username: None,
+            display_name: None,
+        }
+    }
+
+    /// Set the username.
+    pub fn with_username(mut self, username: impl Into<String>) -> Self {
+        self.username = Some(username.into().trim_start_matches('@').to_string());
+        self
+    }
+
+    /// Set the display name.
+    pub fn with_display_name(mut self, display_name: impl Into<String>) -> Self {
+        self.display_name = Some(display_name.into());
+        self
+    }
+
+    /// The name shown to readers: display name, then username, then ID.
+    pub fn label(&self) -> &str {
+        self.display_name
+            .as_deref()
+            .or(self.username.as_deref())
+            .unwrap_or(&self.user_id)
+    }
+
+    /// The handle used in `@name` form: username, then display name, then ID.
+    pub fn handle(&self) -> &str {
+        self.username
+            .as_deref()
+            .or(self.display_name.as_deref())
+            .unwrap_or(&self.user_id)
+    }
+}
+
+fn agent_mention_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // The leading group keeps e-mail addresses (`bob@example.com`) from matching
+    RE.get_or_init(|| Regex::new(r"(^|[^\w@/])@([\w][\w.\-]*)").unwrap())
+}
+
+fn platform_mention_regex(style: MentionStyle) -> &'static Regex {
+    static DISCORD: OnceLock<Regex> = OnceLock::new();
+    static SLACK: OnceLock<Regex> = OnceLock::new();
+    static TELEGRAM: OnceLock<Regex> = OnceLock::new();
+    static MATRIX: OnceLock<Regex> = OnceLock::new();
+    static MSTEAMS: OnceLock<Regex> = OnceLock::new();
+    static PLAIN: OnceLock<Regex> = OnceLock::new();
+
+    match style {
+        MentionStyle::Discord => DISCORD.get_or_init(|| Regex::new(r"<@!?(\d+)>").unwrap()),
+        MentionStyle::Slack => SLACK.get_or_init(|| {
+            Regex::new(r"<@([A-Z0-9]+)(?:\|[^>]*)?>|<!(channel|here|everyone)(?:\|[^>]*)?>")
+                .unwrap()
+        }),
+        MentionStyle::Telegram => {
+            TELEGRAM.get_or_init(|| Regex::new(r"\[([^\]]*)\]\(tg://user\?id=(\d+)\)").unwrap())
+        }
+        MentionStyle::Matrix => MATRIX.get_or_init(|| {
+            Regex::new(r"\[([^\]]*)\]\(https://matrix\.to/#/(@[^)\s]+)\)").unwrap()
+        }),
+        MentionStyle::MsTeams => MSTEAMS.get_or_init(|| Regex::new(r"<at>([^<]*)</at>").unwrap()),
+        MentionStyle::Plain => PLAIN.get_or_init(|| Regex::new(r"$^").unwrap()),
+    }
+}
+
+/// Zero-width space, inserted after `@` to keep a broadcast word from
+/// notifying anyone
+const ZERO_WIDTH_SPACE: char = '\u{200B}';
+
+/// Escapes text for use inside a Telegram MarkdownV2 link label.
+fn escape_telegram(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "_*[]()~`>#+-=|{}.!\\".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Escapes text for use inside a Markdown link label (Matrix).
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\`*_[]()<>!".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Converts mentions between agent-friendly `@name` text and a platform's
+/// mention syntax.
+///
+/// Names are matched case-insensitively against usernames and display
+/// names. Unknown names are left untouched.
+#[derive(Debug, Clone)]
+pub struct MentionMapper {
+    /// Mention syntax of the target platform
+    style: MentionStyle,
+    /// Whether broadcast mentions in agent output notify the conversation
+    broadcasts: bool,
+    /// Known users
+    targets: Vec<MentionTarget>,
+    /// Lowercased username/display name -> index into `targets`
+    by_name: HashMap<String, usize>,
+    /// User ID -> index into `targets`
+    by_id: HashMap<String, usize>,
+}
+
+impl MentionMapper {
+    /// Create a mapper for a mention style.
+    pub fn new(style: MentionStyle) -> Self {
+        Self {
+            style,
+            broadcasts: false,
+            targets: Vec::new(),
+            by_name: HashMap::new(),
+            by_id: HashMap::new(),
+        }
+    }
+
+    /// Create a mapper for a platform name (e.g., "discord", "slack").
+    pub fn for_platform(platform: &str) -> Self {
+        Self::new(MentionStyle::for_platform(platform))
+    }
+
+    /// Allow agent output to use broadcast mentions on this channel.
+    ///
+    /// Off by default: `@everyone` and the like are then defused instead of
+    /// converted.
+    pub fn with_broadcasts(mut self, allowed: bool) -> Self {
+        self.broadcasts = allowed;
+        self
+    }
+
+    /// Get the mention style used by this mapper.
+    pub fn style(&self) -> MentionStyle {
+        self.style
+    }
+
+    /// Register a user that can be mentioned.
+    ///
+    /// Registering the same user ID again replaces the earlier entry.
+    pub fn register(&mut self, target: MentionTarget) {
+        let index = match self.by_id.get(&target.user_id) {
+            Some(&index) => {
+                self.by_name.retain(|_, i| *i != index);
+                self.targets[index] = target;
+                index
+            }
+            None => {
+                self.targets.push(target);
+                self.targets.len() - 1
+            }
+        };
+
+        let target = &self.targets[index];
+        self.by_id.insert(target.user_id.clone(), index);
+        for name in [&target.username, &target.display_name]
+            .into_iter()
+            .flatten()
+        {
+            // The first user registered under a name keeps it
+            self.by_name.entry(name.to_lowercase()).or_insert(index);
+        }
+    }
+
+    /// Look up a user by username or display name (case-insensitive).
+    pub fn resolve(&self, name: &str) -> Option<&MentionTarget> {
+        let name = name.trim_start_matches('@').to_lowercase();
+        self.by_name.get(&name).map(|&i| &self.targets[i])
+    }
+
+    /// Look up a user by platform user ID.
+    pub fn resolve_id(&self, user_id: &str) -> Option<&MentionTarget> {
+        self.by_id.get(user_id).map(|&i| &self.targets[i])
+    }
+
+    /// Render a mention of a single user in platform syntax.
+    pub fn render(&self, target: &MentionTarget) -> String {
+        match self.style {
+            MentionStyle::Discord | MentionStyle::Slack => format!("<@{}>", target.user_id),
+            MentionStyle::Telegram => {
+                format!(
+                    "[{}](tg://user?id={})",
+                    escape_telegram(target.label()),
+                    target.user_id
+                )
+            }
+            MentionStyle::Matrix => {
+                format!(
+                    "[{}](https://matrix.to/#/{})",
+                    escape_markdown(target.label()),
+                    target.user_id
+                )
+            }
+            MentionStyle::MsTeams => format!("<at>{}</at>", target.label()),
+            MentionStyle::Plain => format!("@{}", target.handle()),
+        }
+    }
+
+    /// Render a broadcast mention in platform syntax.
+    ///
+    /// Returns `None` if the platform has no equivalent.
+    pub fn render_broadcast(&self, broadcast: BroadcastMention) -> Option<&'static str> {
+        match (self.style, broadcast) {
+            (MentionStyle::Discord, BroadcastMention::Everyone) => Some("@everyone"),
+            (MentionStyle::Discord, BroadcastMention::Here) => Some("@here"),
+            (MentionStyle::Slack, BroadcastMention::Everyone) => Some("<!channel>"),
+            (MentionStyle::Slack, BroadcastMention::Here) => Some("<!here>"),
+            (MentionStyle::Matrix, BroadcastMention::Everyone) => Some("@room"),
+            _ => None,
+        }
+    }
+
+    /// Convert `@name` mentions in agent output to platform mention syntax.
+    ///
+    /// `@everyone`, `@channel`, `@room`, `@all` and `@here` are converted to
+    /// the platform's broadcast mention where one exists, if the channel
+    /// allows broadcasts. Otherwise a zero-width space is inserted after the
+    /// `@`, since some platforms (Discord, Matrix, Mattermost) notify on the
+    /// plain word. Names that do not match a registered user are left as
+    /// typed.
+    pub fn to_platform(&self, text: &str) -> String {
+        agent_mention_regex()
+            .replace_all(text, |caps: &Captures| {
+                let prefix = &caps[1];
+                let raw = &caps[2];
+                // Sentence punctuation directly after a name is not part of it
+                let name = raw.trim_end_matches(['.', '-']);
+                let suffix = &raw[name.len()..];
+
+                let broadcast = match name.to_lowercase().as_str() {
+                    "everyone" | "channel" | "room" | "all" => Some(BroadcastMention::Everyone),
+                    "here" => Some(BroadcastMention::Here),
+                    _ => None,
+                };
+
+                let rendered = match broadcast {
+                    Some(b) if self.resolve(name).is_none() => {
+                        if self.broadcasts {
+                            self.render_broadcast(b).map(str::to_string)
+                        } else {
+                            Some(format!("@{}{}", ZERO_WIDTH_SPACE, name))
+                        }
+                    }
+                    _ => self.resolve(name).map(|target| self.render(target)),
+                };
+
+                match rendered {
+                    Some(mention) => format!("{}{}{}", prefix, mention, suffix),
+                    None => caps[0].to_string(),
+                }
+            })
+            .into_owned()
+    }
+
+    /// Convert platform mention syntax in inbound text to `@name` form.
+    ///
+    /// Mentions of unregistered users are rendered using the name carried in
+    /// the mention itself, or the raw user ID if there is none.
+    pub fn from_platform(&self, text: &str) -> String {
+        let handle_for = |user_id: &str, fallback: &str| {
+            self.resolve_id(user_id)
+                .map(|t| t.handle().to_string())
+                .unwrap_or_else(|| {
+                    if fallback.is_empty() {
+                        user_id.to_string()
+                    } else {
+                        fallback.to_string()
+                    }
+                })
+        };
+
+        let regex = platform_mention_regex(self.style);
+        regex
+            .replace_all(text, |caps: &Captures| match self.style {
+                MentionStyle::Discord => format!("@{}", handle_for(&caps[1], "")),
+                MentionStyle::Slack => match caps.get(2) {
+                    Some(broadcast) => format!("@{}", broadcast.as_str()),
+                    None => format!("@{}", handle_for(&caps[1], "")),
+                },
+                MentionStyle::Telegram => format!("@{}", handle_for(&caps[2], &caps[1])),
+                MentionStyle::Matrix => format!("@{}", handle_for(&caps[2], &caps[1])),
+                MentionStyle::MsTeams => {
+                    let name = &caps[1];
+                    format!(
+                        "@{}",
+                        self.resolve(name).map(|t| t.handle()).unwrap_or(name)
+                    )
+                }
+                MentionStyle::Plain => caps[0].to_string(),
+            })
+            .into_owned()
+    }
+
+    /// Extract the user IDs mentioned in inbound platform text.
+    ///
+    /// For styles that carry names rather than IDs (Teams, plain text) only
+    /// registered users are returned.
+    pub fn mentioned_user_ids(&self, text: &str) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        let mut push = |id: String| {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        };
+
+        match self.style {
+            MentionStyle::Plain => {
+                for caps in agent_mention_regex().captures_iter(text) {
+                    if let Some(target) = self.resolve(caps[2].trim_end_matches(['.', '-'])) {
+                        push(target.user_id.clone());
+                    }
+                }
+            }
+            style => {
+                for caps in platform_mention_regex(style).captures_iter(text) {
+                    let id = match style {
+                        MentionStyle::Discord => Some(caps[1].to_string()),
+                        MentionStyle::Slack => caps.get(1).map(|m| m.as_str().to_string()),
+                        MentionStyle::Telegram | MentionStyle::Matrix => Some(caps[2].to_string()),
+                        MentionStyle::MsTeams => self.resolve(&caps[1]).map(|t| t.user_id.clone()),
+                        MentionStyle::Plain => None,
+                    };
+                    if let Some(id) = id {
+                        push(id);
+                    }
+                }
+            }
+        }
+        ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapper(platform: &str, alice_id: &str) -> MentionMapper {
+        let mut mapper = MentionMapper::for_platform(platform);
+        mapper.register(
+            MentionTarget::new(alice_id)
+                .with_username("alice")
+                .with_display_name("Alice Liddell"),
+        );
+        mapper
+    }
+
+    #[test]
+    fn test_to_platform_per_style() {
+        let text = "Thanks @alice, see you.";
+        assert_eq!(
+            mapper("discord", "123").to_platform(text),
+            "Thanks <@123>, see you."
+        );
+        assert_eq!(
+            mapper("slack", "U123").to_platform(text),
+            "Thanks <@U123>, see you."
+        );
+        assert_eq!(
+            mapper("telegram", "123").to_platform(text),
+            "Thanks [Alice Liddell](tg://user?id=123), see you."
+        );
+        assert_eq!(
+            mapper("matrix", "@alice:example.org").to_platform(text),
+            "Thanks [Alice Liddell](https://matrix.to/#/@alice:example.org), see you."
+        );
+        assert_eq!(
+            mapper("msteams", "29:abc").to_platform(text),
+            "Thanks <at>Alice Liddell</at>, see you."
+        );
+        assert_eq!(mapper("irc", "alice").to_platform(text), text);
+    }
+
+    #[test]
+    fn test_to_platform_leaves_unknown_and_emails() {
+        let mapper = mapper("discord", "123");
+        assert_eq!(
+            mapper.to_platform("Mail alice@example.com or ask @bob."),
+            "Mail alice@example.com or ask @bob."
+        );
+        assert_eq!(mapper.to_platform("@ALICE."), "<@123>.");
+    }
+
+    #[test]
+    fn test_broadcast_mentions() {
+        let allowing = |platform: &str| mapper(platform, "1").with_broadcasts(true);
+        assert_eq!(
+            allowing("slack").to_platform("@channel heads up"),
+            "<!channel> heads up"
+        );
+        assert_eq!(allowing("matrix").to_platform("@everyone hi"), "@room hi");
+        assert_eq!(allowing("discord").to_platform("@here hi"), "@here hi");
+        assert_eq!(allowing("telegram").to_platform("@room hi"), "@room hi");
+    }
+
+    #[test]
+    fn test_broadcast_mentions_are_defused_by_default() {
+        for platform in ["discord", "slack", "matrix", "mattermost"] {
+            for word in ["everyone", "here", "channel", "all", "room"] {
+                let text = format!("@{} hi", word);
+                assert_eq!(
+                    mapper(platform, "1").to_platform(&text),
+                    format!("@\u{200B}{} hi", word),
+                    "{} on {}",
+                    word,
+                    platform
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_labels_are_escaped() {
+        let mut telegram = MentionMapper::for_platform("telegram");
+        telegram.register(
+            MentionTarget::new("1")
+                .with_username("eve")
+                .with_display_name("Eve](tg://user?id=2) *x*"),
+        );
+        assert_eq!(
+            telegram.to_platform("@eve"),
+            "[Eve\\]\\(tg://user?id\\=2\\) \\*x\\*](tg://user?id=1)"
+        );
+
+        let mut matrix = MentionMapper::for_platform("matrix");
+        matrix.register(
+            MentionTarget::new("@eve:example.org")
+                .with_username("eve")
+                .with_display_name("Eve](https://evil.example)"),
+        );
+        assert_eq!(
+            matrix.to_platform("@eve"),
+            "[Eve\\]\\(https://evil.example\\)](https://matrix.to/#/@eve:example.org)"
+        );
+    }
+
+    #[test]
+    fn test_from_platform() {
+        assert_eq!(
+            mapper("discord", "123").from_platform("hey <@!123> and <@999>"),
+            "hey @alice and @999"
+        );
+        assert_eq!(
+            mapper("slack", "U123").from_platform("<@U123|alice> <!here>"),
+            "@alice @here"
+        );
+        assert_eq!(
+            mapper("telegram", "123").from_platform("[Bob](tg://user?id=5) [A](tg://user?id=123)"),
+            "@Bob @alice"
+        );
+        assert_eq!(
+            mapper("msteams", "29:abc").from_platform("<at>Alice Liddell</at> hi"),
+            "@alice hi"
+        );
+    }
+
+    #[test]
+    fn test_mentioned_user_ids() {
+        assert_eq!(
+            mapper("discord", "123").mentioned_user_ids("<@123> <@456> <@123>"),
+            vec!["123", "456"]
+        );
+        assert_eq!(
+            mapper("matrix", "@alice:example.org")
+                .mentioned_user_ids("[Alice](https://matrix.to/#/@alice:example.org)"),
+            vec!["@alice:example.org"]
+        );
+        assert_eq!(
+            mapper("irc", "alice").mentioned_user_ids("@alice and @carol"),
+            vec!["alice"]
+        );
+    }
+
+    #[test]
+    fn test_register_replaces_existing_user() {
+        let mut mapper = mapper("discord", "123");
+        mapper.register(MentionTarget::new("123").with_username("alicia"));
+        assert!(mapper.resolve("alice").is_none());
+        assert_eq!(mapper.resolve("@Alicia").unwrap().user_id, "123");
+        assert_eq!(mapper.resolve_id("123").unwrap().label(), "alicia");
+    }
+}
@@ -4,8 +4,12 @@
 //! exponential backoff, jitter, and circuit breaker patterns to handle
 //! transient failures in channel operations.
 
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
+use aisopod_channel::ChannelHealth;
+use tracing::{debug, warn};
+
 /// Maximum number of retry attempts allowed.
 pub const MAX_RETRIES: u32 = 5;
 
@@ -100,14 +104,450 @@ impl std::fmt::Display for TotalRetryError {
 
 impl std::error::Error for TotalRetryError {}
 
+/// Circuit breaker configuration.
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures required to open the circuit
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before allowing probe requests
+    pub open_timeout: Duration,
+    /// Maximum number of probe requests allowed while half-open
+    pub half_open_max_probes: u32,
+    /// Successful probes required to close the circuit again
+    pub success_threshold: u32,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_timeout: Duration::from_secs(30),
+            half_open_max_probes: 1,
+            success_threshold: 1,
+        }
+    }
+}
+
+impl From<&RetryConfig> for CircuitBreakerConfig {
+    fn from(config: &RetryConfig) -> Self {
+        Self {
+            failure_threshold: config.circuit_breaker_threshold,
+            open_timeout: config.circuit_breaker_timeout,
+            ..Default::default()
+        }
+    }
+}
+
+/// Counters describing the behaviour of a circuit breaker over its lifetime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitMetrics {
+    /// Current circuit state
+    pub state: CircuitState,
+    /// Current run of consecutive failures
+    pub consecutive_failures: u32,
+    /// Total successful operations recorded
+    pub total_successes: u64,
+    /// Total failed operations recorded
+    pub total_failures: u64,
+    /// Requests rejected because the circuit was open
+    pub rejected_requests: u64,
+    /// Number of times the circuit has transitioned to open
+    pub times_opened: u64,
+}
+
+/// Circuit breaker state machine.
+///
+/// The breaker moves through three states:
+///
+/// - `Closed`: requests flow normally; consecutive failures are counted and
+///   the circuit opens once `failure_threshold` is reached.
+/// - `Open`: requests are rejected until `open_timeout` has elapsed.
+/// - `HalfOpen`: up to `half_open_max_probes` probe requests are let through.
+///   `success_threshold` successful probes close the circuit, while any
+///   failed probe re-opens it.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: CircuitState,
+    consecutive_failures: u32,
+    half_open_successes: u32,
+    half_open_in_flight: u32,
+    opened_at: Option<Instant>,
+    total_successes: u64,
+    total_failures: u64,
+    rejected_requests: u64,
+    times_opened: u64,
+}
+
+impl CircuitBreaker {
+    /// Create a new circuit breaker with default configuration.
+    pub fn new() -> Self {
+        Self::with_config(CircuitBreakerConfig::default())
+    }
+
+    /// Create a new circuit breaker with custom configuration.
+    pub fn with_config(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            half_open_successes: 0,
+            half_open_in_flight: 0,
+            opened_at: None,
+            total_successes: 0,
+            total_failures: 0,
+            rejected_requests: 0,
+            times_opened: 0,
+        }
+    }
+
+    /// Check whether a request would currently be allowed, without
+    /// changing any state.
+    pub fn would_allow(&self) -> bool {
+        match self.state {
+            CircuitState::Closed => true,
+            CircuitState::Open => self.open_timeout_elapsed(),
+            CircuitState::HalfOpen => self.half_open_in_flight < self.config.half_open_max_probes,
+        }
+    }
+
+    /// Ask the breaker for permission to perform a request.
+    ///
+    /// An open circuit whose timeout has elapsed transitions to half-open and
+    /// the request is admitted as a probe. Rejected requests are counted in
+    /// the metrics.
+    ///
+    /// # Returns
+    ///
+    /// Returns `(allowed, transition)` where `transition` is `Some((from, to))`
+    /// if this call changed the circuit state.
+    pub fn allow_request(&mut self) -> (bool, Option<(CircuitState, CircuitState)>) {
+        let mut transition = None;
+
+        if self.state == CircuitState::Open && self.open_timeout_elapsed() {
+            transition = self.transition_to(CircuitState::HalfOpen);
+        }
+
+        let allowed = match self.state {
+            CircuitState::Closed => true,
+            CircuitState::Open => false,
+            CircuitState::HalfOpen => {
+                if self.half_open_in_flight < self.config.half_open_max_probes {
+                    self.half_open_in_flight += 1;
+                    true
+                } else {
+                    false
+                }
+            }
+        };
+
+        if !allowed {
+            self.rejected_requests += 1;
+        }
+
+        (allowed, transition)
+    }
+
+    /// Record a successful operation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some((from, to))` if the circuit state changed.
+    pub fn record_success(&mut self) -> Option<(CircuitState, CircuitState)> {
+        self.total_successes += 1;
+        self.consecutive_failures = 0;
+
+        match self.state {
+            CircuitState::HalfOpen => {
+                self.half_open_in_flight = self.half_open_in_flight.saturating_sub(1);
+                self.half_open_successes += 1;
+                if self.half_open_successes >= self.config.success_threshold {
+                    self.transition_to(CircuitState::Closed)
+                } else {
+                    None
+                }
+            }
+            // A success reported while open (e.g. a request started before the
+            // circuit tripped) is evidence the endpoint recovered.
+            CircuitState::Open => self.transition_to(CircuitState::Closed),
+            CircuitState::Closed => None,
+        }
+    }
+
+    /// Record a failed operation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some((from, to))` if the circuit state changed.
+    pub fn record_failure(&mut self) -> Option<(CircuitState, CircuitState)> {
+        self.total_failures += 1;
+        self.consecutive_failures += 1;
+
+        match self.state {
+            CircuitState::Closed if self.consecutive_failures >= self.config.failure_threshold => {
+                self.transition_to(CircuitState::Open)
+            }
+            CircuitState::HalfOpen => self.transition_to(CircuitState::Open),
+            CircuitState::Open => {
+                // Restart the open timeout so a still-failing endpoint stays isolated
+                self.opened_at = Some(Instant::now());
+                None
+            }
+            CircuitState::Closed => None,
+        }
+    }
+
+    /// Force the circuit into the half-open state if it is currently open.
+    pub fn force_half_open(&mut self) -> Option<(CircuitState, CircuitState)> {
+        if self.state == CircuitState::Open {
+            self.transition_to(CircuitState::HalfOpen)
+        } else {
+            None
+        }
+    }
+
+    /// Reset the circuit to closed, clearing failure counters but keeping
+    /// lifetime metrics.
+    pub fn reset(&mut self) -> Option<(CircuitState, CircuitState)> {
+        self.consecutive_failures = 0;
+        self.transition_to(CircuitState::Closed)
+    }
+
+    /// Get current circuit state.
+    pub fn state(&self) -> CircuitState {
+        self.state
+    }
+
+    /// Get the configuration of this breaker.
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+
+    /// Time remaining until an open circuit admits probe requests.
+    ///
+    /// Returns `None` if the circuit is not open.
+    pub fn retry_after(&self) -> Option<Duration> {
+        if self.state != CircuitState::Open {
+            return None;
+        }
+        let elapsed = self.opened_at.map(|t| t.elapsed()).unwrap_or_default();
+        Some(self.config.open_timeout.saturating_sub(elapsed))
+    }
+
+    /// Get a snapshot of the breaker's metrics.
+    pub fn metrics(&self) -> CircuitMetrics {
+        CircuitMetrics {
+            state: self.state,
+            consecutive_failures: self.consecutive_failures,
+            total_successes: self.total_successes,
+            total_failures: self.total_failures,
+            rejected_requests: self.rejected_requests,
+            times_opened: self.times_opened,
+        }
+    }
+
+    fn open_timeout_elapsed(&self) -> bool {
+        self.opened_at
+            .map(|opened_at| opened_at.elapsed() >= self.config.open_timeout)
+            .unwrap_or(true)
+    }
+
+    fn transition_to(&mut self, next: CircuitState) -> Option<(CircuitState, CircuitState)> {
+        let previous = self.state;
+        if previous == next {
+            return None;
+        }
+
+        self.state = next;
+        self.half_open_successes = 0;
+        self.half_open_in_flight = 0;
+        match next {
+            CircuitState::Open => {
+                self.opened_at = Some(Instant::now());
+                self.times_opened += 1;
+            }
+            CircuitState::Closed => {
+                self.opened_at = None;
+                self.consecutive_failures = 0;
+            }
+            CircuitState::HalfOpen => {}
+        }
+
+        debug!(
+            "Circuit breaker transitioned from {:?} to {:?}",
+            previous, next
+        );
+        Some((previous, next))
+    }
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hook notified whenever an endpoint's circuit changes state.
+///
+/// Channels can implement this to log transitions or to update the status
+/// they report through `StatusAdapter`.
+pub trait CircuitStateListener: Send + Sync {
+    /// Called after the circuit for `endpoint` moved from `from` to `to`.
+    fn on_state_change(&self, endpoint: &str, from: CircuitState, to: CircuitState);
+}
+
+/// Per-endpoint circuit breaker tracking.
+///
+/// Each endpoint (e.g. an API route or a remote host) gets its own
+/// [`CircuitBreaker`], so a failing endpoint does not block unrelated
+/// requests to the same platform.
+pub struct CircuitBreakerRegistry {
+    /// Configuration applied to newly tracked endpoints
+    config: CircuitBreakerConfig,
+    /// Breakers keyed by endpoint
+    breakers: HashMap<String, CircuitBreaker>,
+    /// Listeners notified on state transitions
+    listeners: Vec<Box<dyn CircuitStateListener>>,
+}
+
+impl std::fmt::Debug for CircuitBreakerRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CircuitBreakerRegistry")
+            .field("config", &self.config)
+            .field("breakers", &self.breakers)
+            .field("listeners", &self.listeners.len())
+            .finish()
+    }
+}
+
+impl CircuitBreakerRegistry {
+    /// Create a new registry that applies `config` to every endpoint.
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            breakers: HashMap::new(),
+            listeners: Vec::new(),
+        }
+    }
+
+    /// Register a listener for circuit state transitions.
+    pub fn add_listener(&mut self, listener: Box<dyn CircuitStateListener>) {
+        self.listeners.push(listener);
+    }
+
+    /// Ask whether a request to `endpoint` may proceed.
+    pub fn allow_request(&mut self, endpoint: &str) -> bool {
+        let (allowed, transition) = self.breaker_mut(endpoint).allow_request();
+        self.notify(endpoint, transition);
+        if !allowed {
+            warn!(
+                "Circuit open for endpoint '{}', rejecting request",
+                endpoint
+            );
+        }
+        allowed
+    }
+
+    /// Record a successful request to `endpoint`.
+    pub fn record_success(&mut self, endpoint: &str) {
+        let transition = self.breaker_mut(endpoint).record_success();
+        self.notify(endpoint, transition);
+    }
+
+    /// Record a failed request to `endpoint`.
+    pub fn record_failure(&mut self, endpoint: &str) {
+        let transition = self.breaker_mut(endpoint).record_failure();
+        self.notify(endpoint, transition);
+    }
+
+    /// Reset the circuit for `endpoint` to closed.
+    pub fn reset(&mut self, endpoint: &str) {
+        if let Some(breaker) = self.breakers.get_mut(endpoint) {
+            let transition = breaker.reset();
+            self.notify(endpoint, transition);
+        }
+    }
+
+    /// Get the circuit state of `endpoint`.
+    ///
+    /// Endpoints that have never been seen are reported as closed.
+    pub fn state(&self, endpoint: &str) -> CircuitState {
+        self.breakers
+            .get(endpoint)
+            .map(CircuitBreaker::state)
+            .unwrap_or(CircuitState::Closed)
+    }
+
+    /// Get the breaker tracking `endpoint`, if any.
+    pub fn get(&self, endpoint: &str) -> Option<&CircuitBreaker> {
+        self.breakers.get(endpoint)
+    }
+
+    /// Get metrics for every tracked endpoint.
+    pub fn metrics(&self) -> HashMap<String, CircuitMetrics> {
+        self.breakers
+            .iter()
+            .map(|(endpoint, breaker)| (endpoint.clone(), breaker.metrics()))
+            .collect()
+    }
+
+    /// Endpoints whose circuit is currently not closed, sorted by name.
+    pub fn degraded_endpoints(&self) -> Vec<String> {
+        let mut endpoints: Vec<String> = self
+            .breakers
+            .iter()
+            .filter(|(_, breaker)| breaker.state() != CircuitState::Closed)
+            .map(|(endpoint, _)| endpoint.clone())
+            .collect();
+        endpoints.sort();
+        endpoints
+    }
+
+    /// Summarize the registry as a [`ChannelHealth`] value.
+    ///
+    /// Channels can return this from `StatusAdapter::health_check` (possibly
+    /// combined with their own connectivity checks) so that open circuits are
+    /// surfaced as a degraded status.
+    pub fn health(&self) -> ChannelHealth {
+        let degraded = self.degraded_endpoints();
+        if degraded.is_empty() {
+            ChannelHealth::Healthy
+        } else {
+            ChannelHealth::Degraded(format!(
+                "circuit breaker not closed for: {}",
+                degraded.join(", ")
+            ))
+        }
+    }
+
+    fn breaker_mut(&mut self, endpoint: &str) -> &mut CircuitBreaker {
+        let config = &self.config;
+        self.breakers
+            .entry(endpoint.to_string())
+            .or_insert_with(|| CircuitBreaker::with_config(config.clone()))
+    }
+
+    fn notify(&self, endpoint: &str, transition: Option<(CircuitState, CircuitState)>) {
+        if let Some((from, to)) = transition {
+            for listener in &self.listeners {
+                listener.on_state_change(endpoint, from, to);
+            }
+        }
+    }
+}
+
+impl Default for CircuitBreakerRegistry {
+    fn default() -> Self {
+        Self::new(CircuitBreakerConfig::default())
+    }
+}
+
 /// Retry state manager.
 #[derive(Debug)]
 pub struct RetryState {
     config: RetryConfig,
     attempts: u32,
-    circuit_state: CircuitState,
-    circuit_failures: u32,
-    circuit_opened_at: Option<Instant>,
+    circuit: CircuitBreaker,
 }
 
 impl RetryState {
@@ -118,28 +558,17 @@ impl RetryState {
 
     /// Create a new retry state with custom configuration.
     pub fn with_config(config: RetryConfig) -> Self {
+        let circuit = CircuitBreaker::with_config(CircuitBreakerConfig::from(&config));
         Self {
             config,
             attempts: 0,
-            circuit_state: CircuitState::Closed,
-            circuit_failures: 0,
-            circuit_opened_at: None,
+            circuit,
         }
     }
 
     /// Check if retry is allowed based on circuit breaker state.
     pub fn can_retry(&self) -> bool {
-        match self.circuit_state {
-            CircuitState::Open => {
-                // Check if timeout has passed
-                if let Some(opened_at) = self.circuit_opened_at {
-                    Instant::now().duration_since(opened_at) >= self.config.circuit_breaker_timeout
-                } else {
-                    false
-                }
-            }
-            CircuitState::Closed | CircuitState::HalfOpen => true,
-        }
+        self.circuit.would_allow()
     }
 
     /// Check if more retries are available.
@@ -174,34 +603,28 @@ impl RetryState {
 
     /// Record a successful operation.
     pub fn record_success(&mut self) {
-        self.circuit_failures = 0;
-        self.circuit_state = CircuitState::Closed;
+        self.circuit.record_success();
         self.attempts = 0;
     }
 
     /// Record a failed operation.
     pub fn record_failure(&mut self) {
-        self.circuit_failures += 1;
-
-        if self.circuit_failures >= self.config.circuit_breaker_threshold {
-            self.circuit_state = CircuitState::Open;
-            self.circuit_opened_at = Some(Instant::now());
-        } else if self.circuit_state == CircuitState::HalfOpen {
-            self.circuit_state = CircuitState::Open;
-            self.circuit_opened_at = Some(Instant::now());
-        }
+        self.circuit.record_failure();
     }
 
     /// Transition circuit to half-open state for testing.
     pub fn test_half_open(&mut self) {
-        if self.circuit_state == CircuitState::Open {
-            self.circuit_state = CircuitState::HalfOpen;
-        }
+        self.circuit.force_half_open();
     }
 
     /// Get current circuit state.
     pub fn circuit_state(&self) -> CircuitState {
-        self.circuit_state
+        self.circuit.state()
+    }
+
+    /// Get the underlying circuit breaker.
+    pub fn circuit(&self) -> &CircuitBreaker {
+        &self.circuit
     }
 }
 
@@ -234,8 +657,9 @@ where
 
     /// Execute the operation with retry logic.
     pub fn execute(&mut self) -> RetryResult<T> {
-        // Check circuit breaker
-        if !self.state.can_retry() {
+        // Check circuit breaker, admitting a probe if the open timeout elapsed
+        let (allowed, _) = self.state.circuit.allow_request();
+        if !allowed {
             return RetryResult::CircuitOpen;
         }
 
@@ -306,7 +730,7 @@ mod tests {
         assert_eq!(state.config.max_retries, MAX_RETRIES);
         assert_eq!(state.config.base_delay, BASE_DELAY);
         assert_eq!(state.attempts, 0);
-        assert_eq!(state.circuit_state, CircuitState::Closed);
+        assert_eq!(state.circuit_state(), CircuitState::Closed);
     }
 
     #[test]
@@ -344,7 +768,7 @@ mod tests {
         });
 
         // Should start closed
-        assert_eq!(state.circuit_state, CircuitState::Closed);
+        assert_eq!(state.circuit_state(), CircuitState::Closed);
 
         // Record failures
         state.record_failure();
@@ -352,7 +776,7 @@ mod tests {
         state.record_failure();
 
         // Should be open after threshold
-        assert_eq!(state.circuit_state, CircuitState::Open);
+        assert_eq!(state.circuit_state(), CircuitState::Open);
         assert!(!state.can_retry());
     }
 
@@ -393,15 +817,15 @@ mod tests {
         let mut state = RetryState::new();
 
         // Open the circuit
-        state.circuit_state = CircuitState::Open;
-        state.circuit_opened_at = Some(Instant::now() - Duration::from_secs(60));
+        state.circuit.state = CircuitState::Open;
+        state.circuit.opened_at = Some(Instant::now() - Duration::from_secs(60));
 
         // Should be able to retry (timeout passed)
         assert!(state.can_retry());
 
         // Transition to half-open
         state.test_half_open();
-        assert_eq!(state.circuit_state, CircuitState::HalfOpen);
+        assert_eq!(state.circuit_state(), CircuitState::HalfOpen);
     }
 
     #[test]
@@ -431,4 +855,142 @@ mod tests {
         assert!(display.contains("Connection failed"));
         assert!(display.contains("5s"));
     }
+
+    fn fast_breaker() -> CircuitBreaker {
+        CircuitBreaker::with_config(CircuitBreakerConfig {
+            failure_threshold: 2,
+            open_timeout: Duration::from_millis(0),
+            half_open_max_probes: 1,
+            success_threshold: 2,
+        })
+    }
+
+    #[test]
+    fn test_circuit_breaker_opens_after_threshold() {
+        let mut breaker = CircuitBreaker::with_config(CircuitBreakerConfig {
+            failure_threshold: 2,
+            open_timeout: Duration::from_secs(60),
+            ..Default::default()
+        });
+
+        assert_eq!(breaker.record_failure(), None);
+        assert_eq!(
+            breaker.record_failure(),
+            Some((CircuitState::Closed, CircuitState::Open))
+        );
+
+        let (allowed, transition) = breaker.allow_request();
+        assert!(!allowed);
+        assert_eq!(transition, None);
+        assert!(breaker.retry_after().is_some());
+
+        let metrics = breaker.metrics();
+        assert_eq!(metrics.total_failures, 2);
+        assert_eq!(metrics.rejected_requests, 1);
+        assert_eq!(metrics.times_opened, 1);
+    }
+
+    #[test]
+    fn test_circuit_breaker_half_open_probe_limit() {
+        let mut breaker = fast_breaker();
+        breaker.record_failure();
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        // Timeout elapsed: the first request becomes a probe
+        let (allowed, transition) = breaker.allow_request();
+        assert!(allowed);
+        assert_eq!(
+            transition,
+            Some((CircuitState::Open, CircuitState::HalfOpen))
+        );
+
+        // Only one probe may be in flight
+        let (allowed, _) = breaker.allow_request();
+        assert!(!allowed);
+    }
+
+    #[test]
+    fn test_circuit_breaker_closes_after_successful_probes() {
+        let mut breaker = fast_breaker();
+        breaker.record_failure();
+        breaker.record_failure();
+
+        assert!(breaker.allow_request().0);
+        assert_eq!(breaker.record_success(), None);
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+
+        assert!(breaker.allow_request().0);
+        assert_eq!(
+            breaker.record_success(),
+            Some((CircuitState::HalfOpen, CircuitState::Closed))
+        );
+        assert_eq!(breaker.metrics().consecutive_failures, 0);
+    }
+
+    #[test]
+    fn test_circuit_breaker_failed_probe_reopens() {
+        let mut breaker = fast_breaker();
+        breaker.record_failure();
+        breaker.record_failure();
+
+        assert!(breaker.allow_request().0);
+        assert_eq!(
+            breaker.record_failure(),
+            Some((CircuitState::HalfOpen, CircuitState::Open))
+        );
+        assert_eq!(breaker.metrics().times_opened, 2);
+    }
+
+    struct RecordingListener(std::sync::Arc<std::sync::Mutex<Vec<(String, CircuitState)>>>);
+
+    impl CircuitStateListener for RecordingListener {
+        fn on_state_change(&self, endpoint: &str, _from: CircuitState, to: CircuitState) {
+            self.0.lock().unwrap().push((endpoint.to_string(), to));
+        }
+    }
+
+    #[test]
+    fn test_registry_tracks_endpoints_independently() {
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut registry = CircuitBreakerRegistry::new(CircuitBreakerConfig {
+            failure_threshold: 1,
+            open_timeout: Duration::from_secs(60),
+            ..Default::default()
+        });
+        registry.add_listener(Box::new(RecordingListener(events.clone())));
+
+        registry.record_failure("send_message");
+        registry.record_success("upload_file");
+
+        assert_eq!(registry.state("send_message"), CircuitState::Open);
+        assert_eq!(registry.state("upload_file"), CircuitState::Closed);
+        assert_eq!(registry.state("unknown"), CircuitState::Closed);
+        assert!(!registry.allow_request("send_message"));
+        assert!(registry.allow_request("upload_file"));
+
+        assert_eq!(
+            events.lock().unwrap().as_slice(),
+            &[("send_message".to_string(), CircuitState::Open)]
+        );
+        assert_eq!(registry.metrics()["send_message"].rejected_requests, 1);
+    }
+
+    #[test]
+    fn test_registry_health() {
+        let mut registry = CircuitBreakerRegistry::new(CircuitBreakerConfig {
+            failure_threshold: 1,
+            ..Default::default()
+        });
+        assert!(matches!(registry.health(), ChannelHealth::Healthy));
+
+        registry.record_failure("api.example.com");
+        match registry.health() {
+            ChannelHealth::Degraded(reason) => assert!(reason.contains("api.example.com")),
+            other => panic!("Expected degraded health, got {:?}", other),
+        }
+
+        registry.reset("api.example.com");
+        assert!(matches!(registry.health(), ChannelHealth::Healthy));
+    }
 }
@@ -0,0 +1,504 @@
+//! Lightweight templating for outbound system notifications.
+//!
+//! Operators can customize the text of system notifications (approval
+//! prompts, error notices, usage warnings, ...) per platform and per channel
+//! without code changes. Templates support a small syntax:
+//!
+//! - `{{name}}` - substitute a variable (empty if unset)
+//! - `{{name|fallback}}` - substitute a variable, or `fallback` if unset/empty
+//! - `{{#if name}}...{{else}}...{{/if}}` - conditional on a variable being set
+//! - `{{#unless name}}...{{/unless}}` - inverse conditional
+//!
+//! A variable is considered set if it is present, non-empty, and not
+//! `"false"` or `"0"`.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error_mapping::{ChannelError, ChannelResult};
+
+/// Platform name used in errors raised by this module.
+const PLATFORM: &str = "templates";
+
+/// Notification sent when a tool call needs human approval.
+pub const APPROVAL_PROMPT: &str = "approval_prompt";
+/// Notification sent when the agent fails to produce a reply.
+pub const ERROR_NOTICE: &str = "error_notice";
+/// Notification sent when a usage limit is close to or over its budget.
+pub const USAGE_WARNING: &str = "usage_warning";
+
+/// A parsed template node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    /// Literal text
+    Text(String),
+    /// Variable substitution with an optional fallback
+    Var {
+        name: String,
+        fallback: Option<String>,
+    },
+    /// Conditional block
+    If {
+        name: String,
+        negate: bool,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+}
+
+/// A parsed template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+/// Variables available while rendering a template.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    vars: HashMap<String, String>,
+}
+
+impl TemplateContext {
+    /// Create an empty context.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a variable, returning the context for chaining.
+    pub fn with(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Set a variable.
+    pub fn set(&mut self, name: impl Into<String>, value: impl ToString) {
+        self.vars.insert(name.into(), value.to_string());
+    }
+
+    /// Get a variable.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.vars.get(name).map(String::as_str)
+    }
+
+    fn is_truthy(&self, name: &str) -> bool {
+        matches!(self.get(name), Some(v) if !v.is_empty() && v != "false" && v != "0")
+    }
+}
+
+/// How a run of nodes ended while parsing.
+#[derive(Debug, PartialEq, Eq)]
+enum Terminator {
+    /// End of input
+    Eof,
+    /// `{{else}}`
+    Else,
+    /// `{{/if}}`
+    EndIf,
+    /// `{{/unless}}`
+    EndUnless,
+}
+
+impl Template {
+    /// Parse a template string.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for unterminated tags, unbalanced `{{#if}}` /
+    /// `{{#unless}}` blocks, or unknown block tags.
+    pub fn parse(source: &str) -> ChannelResult<Self> {
+        let mut rest = source;
+        match Self::parse_nodes(&mut rest)? {
+            (nodes, Terminator::Eof) => Ok(Self { nodes }),
+            (_, terminator) => Err(template_error(format!(
+                "Unexpected {:?} outside of a conditional block",
+                terminator
+            ))),
+        }
+    }
+
+    /// Parse nodes until the end of input or a block-closing tag.
+    fn parse_nodes(rest: &mut &str) -> ChannelResult<(Vec<Node>, Terminator)> {
+        let mut nodes = Vec::new();
+
+        loop {
+            let Some(start) = rest.find("{{") else {
+                if !rest.is_empty() {
+                    nodes.push(Node::Text(rest.to_string()));
+                }
+                *rest = "";
+                return Ok((nodes, Terminator::Eof));
+            };
+
+            if start > 0 {
+                nodes.push(Node::Text(rest[..start].to_string()));
+            }
+            let after_open = &rest[start + 2..];
+            let end = after_open
+                .find("}}")
+                .ok_or_else(|| template_error("Unterminated '{{' tag"))?;
+            let tag = after_open[..end].trim();
+            *rest = &after_open[end + 2..];
+
+            let block = tag
+                .strip_prefix("#if ")
+                .map(|name| (name, false))
+                .or_else(|| tag.strip_prefix("#unless ").map(|name| (name, true)));
+            if let Some((name, negate)) = block {
+                nodes.push(Self::parse_block(rest, name.trim(), negate)?);
+                continue;
+            }
+
+            match tag {
+                "else" => return Ok((nodes, Terminator::Else)),
+                "/if" => return Ok((nodes, Terminator::EndIf)),
+                "/unless" => return Ok((nodes, Terminator::EndUnless)),
+                _ if tag.starts_with('#') || tag.starts_with('/') => {
+                    return Err(template_error(format!("Unknown tag '{{{{{}}}}}'", tag)));
+                }
+                _ => {
+                    let (name, fallback) = match tag.split_once('|') {
+                        Some((name, fallback)) => (name.trim(), Some(fallback.trim().to_string())),
+                        None => (tag, None),
+                    };
+                    if name.is_empty() {
+                        return Err(template_error("Empty variable name"));
+                    }
+                    nodes.push(Node::Var {
+                        name: name.to_string(),
+                        fallback,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Parse the body of an `{{#if}}` / `{{#unless}}` block.
+    fn parse_block(rest: &mut &str, name: &str, negate: bool) -> ChannelResult<Node> {
+        if name.is_empty() {
+            return Err(template_error("Conditional block without a variable name"));
+        }
+        let closing = if negate {
+            Terminator::EndUnless
+        } else {
+            Terminator::EndIf
+        };
+
+        let (then, terminator) = Self::parse_nodes(rest)?;
+        let otherwise = match terminator {
+            Terminator::Else => match Self::parse_nodes(rest)? {
+                (otherwise, t) if t == closing => otherwise,
+                _ => return Err(template_error(format!("Unterminated block '{}'", name))),
+            },
+            t if t == closing => Vec::new(),
+            _ => return Err(template_error(format!("Unterminated block '{}'", name))),
+        };
+
+        Ok(Node::If {
+            name: name.to_string(),
+            negate,
+            then,
+            otherwise,
+        })
+    }
+
+    /// Render the template with the given variables.
+    pub fn render(&self, ctx: &TemplateContext) -> String {
+        let mut out = String::new();
+        render_nodes(&self.nodes, ctx, &mut out);
+        out
+    }
+
+    /// Names of all variables referenced by the template.
+    pub fn variables(&self) -> Vec<String> {
+        fn collect(nodes: &[Node], out: &mut Vec<String>) {
+            for node in nodes {
+                match node {
+                    Node::Text(_) => {}
+                    Node::Var { name, .. } => push_unique(out, name),
+                    Node::If {
+                        name,
+                        then,
+                        otherwise,
+                        ..
+                    } => {
+                        push_unique(out, name);
+                        collect(then, out);
+                        collect(otherwise, out);
+                    }
+                }
+            }
+        }
+        fn push_unique(out: &mut Vec<String>, name: &str) {
+            if !out.iter().any(|n| n == name) {
+                out.push(name.to_string());
+            }
+        }
+
+        let mut out = Vec::new();
+        collect(&self.nodes, &mut out);
+        out
+    }
+}
+
+fn render_nodes(nodes: &[Node], ctx: &TemplateContext, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var { name, fallback } => match ctx.get(name) {
+                Some(value) if !value.is_empty() => out.push_str(value),
+                _ => out.push_str(fallback.as_deref().unwrap_or("")),
+            },
+            Node::If {
+                name,
+                negate,
+                then,
+                otherwise,
+            } => {
+                if ctx.is_truthy(name) != *negate {
+                    render_nodes(then, ctx, out);
+                } else {
+                    render_nodes(otherwise, ctx, out);
+                }
+            }
+        }
+    }
+}
+
+fn template_error(message: impl Into<String>) -> ChannelError {
+    ChannelError::invalid_request(PLATFORM, message, Some("invalid_template"))
+}
+
+/// Operator-provided notification templates with per-platform and
+/// per-channel overrides.
+///
+/// Lookup order for a notification is: channel override, platform
+/// override, operator default, then the built-in default.
+///
+/// This struct deserializes from configuration such as:
+///
+/// ```json5
+/// {
+///   defaults: { error_notice: "Sorry, something went wrong." },
+///   platforms: { slack: { approval_prompt: ":lock: Approve `{{tool}}`?" } },
+///   channels: { "ops-room": { usage_warning: "{{used}}/{{limit}} tokens" } },
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationTemplates {
+    /// Templates applying to every platform, keyed by notification name
+    #[serde(default)]
+    pub defaults: HashMap<String, String>,
+    /// Per-platform overrides, keyed by platform then notification name
+    #[serde(default)]
+    pub platforms: HashMap<String, HashMap<String, String>>,
+    /// Per-channel overrides, keyed by channel ID then notification name
+    #[serde(default)]
+    pub channels: HashMap<String, HashMap<String, String>>,
+}
+
+impl NotificationTemplates {
+    /// Create an empty set of templates (built-in defaults only).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Built-in template for a notification, if there is one.
+    pub fn builtin(notification: &str) -> Option<&'static str> {
+        match notification {
+            APPROVAL_PROMPT => Some(
+                "Approval required: {{agent|The agent}} wants to run `{{tool}}`.\
+                 {{#if summary}}\n{{summary}}{{/if}}\nReply \"approve\" or \"deny\".",
+            ),
+            ERROR_NOTICE => Some(
+                "Sorry, something went wrong while handling your message.\
+                 {{#if error}} ({{error}}){{/if}}",
+            ),
+            USAGE_WARNING => Some(
+                "Usage warning: {{used}} of {{limit}} {{unit|tokens}} used\
+                 {{#if period}} this {{period}}{{/if}}.",
+            ),
+            _ => None,
+        }
+    }
+
+    /// Select the template source for a notification.
+    ///
+    /// # Arguments
+    ///
+    /// * `notification` - Notification name (e.g. [`APPROVAL_PROMPT`])
+    /// * `platform` - Platform name (e.g. "slack")
+    /// * `channel_id` - Channel ID, if known
+    pub fn source(
+        &self,
+        notification: &str,
+        platform: &str,
+        channel_id: Option<&str>,
+    ) -> Option<&str> {
+        channel_id
+            .and_then(|id| self.channels.get(id))
+            .and_then(|templates| templates.get(notification))
+            .or_else(|| {
+                self.platforms
+                    .get(platform)
+                    .and_then(|templates| templates.get(notification))
+            })
+            .or_else(|| self.defaults.get(notification))
+            .map(String::as_str)
+            .or_else(|| Self::builtin(notification))
+    }
+
+    /// Render a notification for a platform and channel.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no template exists for `notification` or if the
+    /// selected template fails to parse.
+    pub fn render(
+        &self,
+        notification: &str,
+        platform: &str,
+        channel_id: Option<&str>,
+        ctx: &TemplateContext,
+    ) -> ChannelResult<String> {
+        let source = self
+            .source(notification, platform, channel_id)
+            .ok_or_else(|| {
+                ChannelError::not_found(PLATFORM, notification, "notification template")
+            })?;
+        Ok(Template::parse(source)?.render(ctx))
+    }
+
+    /// Parse every configured template, returning the first error found.
+    ///
+    /// Useful for validating operator configuration at startup.
+    pub fn validate(&self) -> ChannelResult<()> {
+        let all = self
+            .defaults
+            .iter()
+            .chain(self.platforms.values().flatten())
+            .chain(self.channels.values().flatten());
+        for (name, source) in all {
+            Template::parse(source).map_err(|e| {
+                template_error(format!("Template '{}' is invalid: {}", name, e.message()))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(source: &str, ctx: &TemplateContext) -> String {
+        Template::parse(source).unwrap().render(ctx)
+    }
+
+    #[test]
+    fn test_variables_and_fallbacks() {
+        let ctx = TemplateContext::new()
+            .with("name", "Alice")
+            .with("empty", "");
+        assert_eq!(render("Hi {{ name }}!", &ctx), "Hi Alice!");
+        assert_eq!(render("Hi {{missing}}!", &ctx), "Hi !");
+        assert_eq!(render("Hi {{empty|there}}!", &ctx), "Hi there!");
+        assert_eq!(render("no tags", &ctx), "no tags");
+    }
+
+    #[test]
+    fn test_conditionals() {
+        let ctx = TemplateContext::new().with("yes", "1").with("no", "false");
+        assert_eq!(render("{{#if yes}}A{{else}}B{{/if}}", &ctx), "A");
+        assert_eq!(render("{{#if no}}A{{else}}B{{/if}}", &ctx), "B");
+        assert_eq!(render("{{#if missing}}A{{/if}}!", &ctx), "!");
+        assert_eq!(render("{{#unless no}}shown{{/unless}}", &ctx), "shown");
+        assert_eq!(
+            render("{{#if yes}}[{{#if no}}x{{else}}y{{/if}}]{{/if}}", &ctx),
+            "[y]"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Template::parse("{{name").is_err());
+        assert!(Template::parse("{{#if a}}open").is_err());
+        assert!(Template::parse("{{/if}}").is_err());
+        assert!(Template::parse("{{#if a}}x{{/unless}}").is_err());
+        assert!(Template::parse("{{#each items}}{{/each}}").is_err());
+        assert!(Template::parse("{{}}").is_err());
+    }
+
+    #[test]
+    fn test_template_variables() {
+        let template = Template::parse("{{a}} {{#if b}}{{c}}{{else}}{{a}}{{/if}}").unwrap();
+        assert_eq!(template.variables(), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_notification_override_order() {
+        let mut templates = NotificationTemplates::new();
+        templates
+            .defaults
+            .insert(ERROR_NOTICE.to_string(), "default".to_string());
+        templates.platforms.insert(
+            "slack".to_string(),
+            HashMap::from([(ERROR_NOTICE.to_string(), "slack".to_string())]),
+        );
+        templates.channels.insert(
+            "ops".to_string(),
+            HashMap::from([(ERROR_NOTICE.to_string(), "ops".to_string())]),
+        );
+
+        let ctx = TemplateContext::new();
+        let render = |platform, channel| {
+            templates
+                .render(ERROR_NOTICE, platform, channel, &ctx)
+                .unwrap()
+        };
+        assert_eq!(render("slack", Some("ops")), "ops");
+        assert_eq!(render("slack", Some("general")), "slack");
+        assert_eq!(render("discord", None), "default");
+    }
+
+    #[test]
+    fn test_builtin_notifications() {
+        let templates = NotificationTemplates::new();
+        let ctx = TemplateContext::new()
+            .with("tool", "bash")
+            .with("summary", "rm -rf build/");
+        let text = templates
+            .render(APPROVAL_PROMPT, "telegram", None, &ctx)
+            .unwrap();
+        assert_eq!(
+            text,
+            "Approval required: The agent wants to run `bash`.\nrm -rf build/\nReply \"approve\" or \"deny\"."
+        );
+
+        let ctx = TemplateContext::new()
+            .with("used", 900)
+            .with("limit", 1000)
+            .with("period", "day");
+        assert_eq!(
+            templates.render(USAGE_WARNING, "irc", None, &ctx).unwrap(),
+            "Usage warning: 900 of 1000 tokens used this day."
+        );
+
+        assert!(templates
+            .render("unknown", "irc", None, &TemplateContext::new())
+            .is_err());
+    }
+
+    #[test]
+    fn test_deserialize_and_validate() {
+        let templates: NotificationTemplates = serde_json::from_str(
+            r#"{"platforms": {"discord": {"error_notice": "{{#if error}}oops"}}}"#,
+        )
+        .unwrap();
+        assert!(templates.validate().is_err());
+
+        let templates: NotificationTemplates =
+            serde_json::from_str(r#"{"defaults": {"error_notice": "ok {{error}}"}}"#).unwrap();
+        assert!(templates.validate().is_ok());
+    }
+}
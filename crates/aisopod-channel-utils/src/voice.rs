@@ -0,0 +1,637 @@
+//! Voice note detection, metadata normalization, and conversion helpers.
+//!
+//! Voice notes arrive in different containers depending on the platform
+//! (OGG/Opus from Telegram and WhatsApp, AAC/M4A from Signal, ...), and each
+//! platform expects its own container and metadata when sending one. This
+//! module provides helpers shared by the Telegram, WhatsApp, Signal, and
+//! Matrix channels:
+//!
+//! - Format detection from file headers or MIME types
+//! - Duration extraction for OGG and WAV files
+//! - Waveform resampling and platform-specific encoding
+//! - Conversion to the preferred container per platform using `ffmpeg`
+
+use std::path::Path;
+use std::time::Duration;
+
+use crate::media::MediaError;
+
+/// Sample rate Opus granule positions are expressed in.
+const OPUS_GRANULE_RATE: u64 = 48_000;
+
+/// Number of waveform samples Telegram accepts.
+pub const TELEGRAM_WAVEFORM_SAMPLES: usize = 100;
+
+/// Number of waveform samples commonly used for Matrix voice messages.
+pub const MATRIX_WAVEFORM_SAMPLES: usize = 64;
+
+/// Voice note container/codec combinations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoiceFormat {
+    /// Opus audio in an OGG container
+    OggOpus,
+    /// Vorbis audio in an OGG container
+    OggVorbis,
+    /// AAC audio in an MP4/M4A container
+    M4aAac,
+    /// MPEG-1 Layer III audio
+    Mp3,
+    /// Adaptive Multi-Rate narrowband audio
+    Amr,
+    /// Opus audio in a WebM container
+    WebmOpus,
+    /// Uncompressed PCM in a WAV container
+    Wav,
+    /// Unrecognized format
+    Unknown,
+}
+
+impl VoiceFormat {
+    /// Detect the format from the first bytes of a file.
+    pub fn detect(header: &[u8]) -> Self {
+        if header.starts_with(b"OggS") {
+            if contains(header, b"OpusHead") {
+                Self::OggOpus
+            } else if contains(header, b"\x01vorbis") {
+                Self::OggVorbis
+            } else {
+                Self::Unknown
+            }
+        } else if header.len() >= 12 && &header[4..8] == b"ftyp" {
+            Self::M4aAac
+        } else if header.starts_with(b"ID3")
+            || (header.len() >= 2 && header[0] == 0xFF && header[1] & 0xE0 == 0xE0)
+        {
+            Self::Mp3
+        } else if header.starts_with(b"#!AMR") {
+            Self::Amr
+        } else if header.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+            Self::WebmOpus
+        } else if header.len() >= 12 && header.starts_with(b"RIFF") && &header[8..12] == b"WAVE" {
+            Self::Wav
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// Detect the format from a MIME type such as `audio/ogg; codecs=opus`.
+    pub fn from_mime(mime: &str) -> Self {
+        let mime = mime.to_ascii_lowercase();
+        let essence = mime.split(';').next().unwrap_or("").trim();
+        match essence {
+            "audio/ogg" | "audio/opus" if !mime.contains("vorbis") => Self::OggOpus,
+            "audio/ogg" => Self::OggVorbis,
+            "audio/mp4" | "audio/m4a" | "audio/x-m4a" | "audio/aac" => Self::M4aAac,
+            "audio/mpeg" | "audio/mp3" => Self::Mp3,
+            "audio/amr" => Self::Amr,
+            "audio/webm" => Self::WebmOpus,
+            "audio/wav" | "audio/x-wav" | "audio/wave" => Self::Wav,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// MIME type to send with this format.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::OggOpus => "audio/ogg; codecs=opus",
+            Self::OggVorbis => "audio/ogg",
+            Self::M4aAac => "audio/mp4",
+            Self::Mp3 => "audio/mpeg",
+            Self::Amr => "audio/amr",
+            Self::WebmOpus => "audio/webm",
+            Self::Wav => "audio/wav",
+            Self::Unknown => "application/octet-stream",
+        }
+    }
+
+    /// File extension for this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::OggOpus | Self::OggVorbis => "ogg",
+            Self::M4aAac => "m4a",
+            Self::Mp3 => "mp3",
+            Self::Amr => "amr",
+            Self::WebmOpus => "webm",
+            Self::Wav => "wav",
+            Self::Unknown => "bin",
+        }
+    }
+
+    /// The container a platform expects for voice notes.
+    ///
+    /// | Platform | Format |
+    /// |----------|--------|
+    /// | telegram | OGG/Opus |
+    /// | whatsapp | OGG/Opus |
+    /// | matrix | OGG/Opus |
+    /// | signal | M4A/AAC |
+    /// | others | OGG/Opus |
+    pub fn preferred_for_platform(platform: &str) -> Self {
+        match platform {
+            "signal" | "imessage" => Self::M4aAac,
+            _ => Self::OggOpus,
+        }
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+/// Normalized metadata for a voice note.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoiceNoteMetadata {
+    /// Detected format
+    pub format: VoiceFormat,
+    /// Duration, if it could be determined
+    pub duration: Option<Duration>,
+    /// Waveform amplitudes normalized to `0.0..=1.0`
+    pub waveform: Option<Vec<f32>>,
+}
+
+impl VoiceNoteMetadata {
+    /// Inspect an in-memory voice note.
+    pub fn from_bytes(data: &[u8]) -> Self {
+        let format = VoiceFormat::detect(data);
+        let duration = match format {
+            VoiceFormat::OggOpus | VoiceFormat::OggVorbis => ogg_duration(data),
+            VoiceFormat::Wav => wav_duration(data),
+            _ => None,
+        };
+        let waveform = match format {
+            VoiceFormat::Wav => wav_pcm16_samples(data).map(|pcm| waveform_from_pcm(&pcm, 100)),
+            _ => None,
+        };
+        Self {
+            format,
+            duration,
+            waveform,
+        }
+    }
+
+    /// Inspect a voice note on disk.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, MediaError> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)
+            .map_err(|_| MediaError::FileNotFound(path.display().to_string()))?;
+        Ok(Self::from_bytes(&data))
+    }
+
+    /// Duration rounded to whole seconds, as required by Telegram and WhatsApp.
+    ///
+    /// Non-empty voice notes shorter than a second are reported as one second.
+    pub fn duration_secs(&self) -> Option<u64> {
+        self.duration.map(|d| {
+            let secs = (d.as_millis() + 500) / 1000;
+            if secs == 0 && !d.is_zero() {
+                1
+            } else {
+                secs as u64
+            }
+        })
+    }
+
+    /// Duration in milliseconds, as used by Matrix `info.duration`.
+    pub fn duration_millis(&self) -> Option<u64> {
+        self.duration.map(|d| d.as_millis() as u64)
+    }
+}
+
+/// Compute the duration of an OGG stream from its last granule position.
+///
+/// For Opus streams the pre-skip from the `OpusHead` packet is subtracted.
+pub fn ogg_duration(data: &[u8]) -> Option<Duration> {
+    let last_page = (0..data.len().saturating_sub(14))
+        .rev()
+        .find(|&i| &data[i..i + 4] == b"OggS")?;
+    let granule = i64::from_le_bytes(data[last_page + 6..last_page + 14].try_into().ok()?);
+    if granule < 0 {
+        return None;
+    }
+    let granule = granule as u64;
+
+    if let Some(pos) = data.windows(8).position(|w| w == b"OpusHead") {
+        let pre_skip = u16::from_le_bytes(data.get(pos + 10..pos + 12)?.try_into().ok()?);
+        let samples = granule.saturating_sub(u64::from(pre_skip));
+        return Some(Duration::from_micros(
+            samples * 1_000_000 / OPUS_GRANULE_RATE,
+        ));
+    }
+
+    // Vorbis: sample rate lives in the identification header
+    let pos = data.windows(7).position(|w| w == b"\x01vorbis")?;
+    let rate = u32::from_le_bytes(data.get(pos + 12..pos + 16)?.try_into().ok()?);
+    if rate == 0 {
+        return None;
+    }
+    Some(Duration::from_micros(granule * 1_000_000 / u64::from(rate)))
+}
+
+/// Locate a RIFF chunk, returning its body.
+fn wav_chunk<'a>(data: &'a [u8], id: &[u8; 4]) -> Option<&'a [u8]> {
+    let mut pos = 12;
+    while pos + 8 <= data.len() {
+        let size = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().ok()?) as usize;
+        let body_start = pos + 8;
+        let body_end = body_start.checked_add(size)?.min(data.len());
+        if &data[pos..pos + 4] == id {
+            return Some(&data[body_start..body_end]);
+        }
+        // Chunks are padded to an even size
+        pos = body_start + size + (size & 1);
+    }
+    None
+}
+
+/// Compute the duration of a WAV file from its byte rate and data size.
+pub fn wav_duration(data: &[u8]) -> Option<Duration> {
+    let fmt = wav_chunk(data, b"fmt ")?;
+    let byte_rate = u32::from_le_bytes(fmt.get(8..12)?.try_into().ok()?);
+    if byte_rate == 0 {
+        return None;
+    }
+    let data_len = wav_chunk(data, b"data")?.len() as u64;
+    Some(Duration::from_micros(
+        data_len * 1_000_000 / u64::from(byte_rate),
+    ))
+}
+
+/// Extract 16-bit PCM samples (first channel) from a WAV file.
+fn wav_pcm16_samples(data: &[u8]) -> Option<Vec<i16>> {
+    let fmt = wav_chunk(data, b"fmt ")?;
+    let audio_format = u16::from_le_bytes(fmt.get(0..2)?.try_into().ok()?);
+    let channels = u16::from_le_bytes(fmt.get(2..4)?.try_into().ok()?).max(1) as usize;
+    let bits = u16::from_le_bytes(fmt.get(14..16)?.try_into().ok()?);
+    if audio_format != 1 || bits != 16 {
+        return None;
+    }
+    let body = wav_chunk(data, b"data")?;
+    Some(
+        body.chunks_exact(2 * channels)
+            .map(|frame| i16::from_le_bytes([frame[0], frame[1]]))
+            .collect(),
+    )
+}
+
+/// Build a waveform of `buckets` peak amplitudes in `0.0..=1.0` from PCM samples.
+pub fn waveform_from_pcm(samples: &[i16], buckets: usize) -> Vec<f32> {
+    if samples.is_empty() || buckets == 0 {
+        return Vec::new();
+    }
+    let peaks: Vec<f32> = (0..buckets)
+        .map(|b| {
+            let start = b * samples.len() / buckets;
+            let end = ((b + 1) * samples.len() / buckets)
+                .max(start + 1)
+                .min(samples.len());
+            samples[start..end]
+                .iter()
+                .map(|s| s.unsigned_abs() as f32 / 32768.0)
+                .fold(0.0, f32::max)
+        })
+        .collect();
+    normalize_waveform(&peaks, buckets)
+}
+
+/// Resample a waveform to `len` points and scale it so the loudest point is 1.0.
+///
+/// Resampling keeps the peak of each bucket when shrinking and repeats
+/// samples when stretching, so short transients stay visible.
+pub fn normalize_waveform(values: &[f32], len: usize) -> Vec<f32> {
+    if values.is_empty() || len == 0 {
+        return Vec::new();
+    }
+
+    let resampled: Vec<f32> = (0..len)
+        .map(|i| {
+            let start = i * values.len() / len;
+            let end = ((i + 1) * values.len() / len)
+                .max(start + 1)
+                .min(values.len());
+            values[start..end]
+                .iter()
+                .map(|v| if v.is_finite() { v.abs() } else { 0.0 })
+                .fold(0.0, f32::max)
+        })
+        .collect();
+
+    let peak = resampled.iter().copied().fold(0.0, f32::max);
+    if peak <= f32::EPSILON {
+        return resampled;
+    }
+    resampled.into_iter().map(|v| v / peak).collect()
+}
+
+/// Quantize a normalized waveform to integers in `0..=max`.
+pub fn quantize_waveform(waveform: &[f32], max: u16) -> Vec<u16> {
+    waveform
+        .iter()
+        .map(|v| (v.clamp(0.0, 1.0) * f32::from(max)).round() as u16)
+        .collect()
+}
+
+/// Encode a waveform the way Telegram expects: 100 samples of 5 bits each,
+/// packed little-endian into a byte string.
+pub fn encode_telegram_waveform(waveform: &[f32]) -> Vec<u8> {
+    let values = quantize_waveform(&normalize_waveform(waveform, TELEGRAM_WAVEFORM_SAMPLES), 31);
+    let mut out = vec![0u8; (values.len() * 5).div_ceil(8)];
+    for (i, value) in values.iter().enumerate() {
+        let bit = i * 5;
+        let chunk = u16::from(*value as u8 & 0x1F) << (bit % 8);
+        out[bit / 8] |= chunk as u8;
+        if bit / 8 + 1 < out.len() {
+            out[bit / 8 + 1] |= (chunk >> 8) as u8;
+        }
+    }
+    out
+}
+
+/// Decode a Telegram 5-bit packed waveform into values in `0.0..=1.0`.
+pub fn decode_telegram_waveform(data: &[u8]) -> Vec<f32> {
+    let count = data.len() * 8 / 5;
+    (0..count)
+        .map(|i| {
+            let bit = i * 5;
+            let lo = u16::from(data[bit / 8]);
+            let hi = data.get(bit / 8 + 1).copied().map(u16::from).unwrap_or(0);
+            let value = ((lo | (hi << 8)) >> (bit % 8)) & 0x1F;
+            f32::from(value) / 31.0
+        })
+        .collect()
+}
+
+/// Encode a waveform for Matrix voice messages (MSC3245): integers in `0..=1024`.
+pub fn encode_matrix_waveform(waveform: &[f32]) -> Vec<u16> {
+    quantize_waveform(&normalize_waveform(waveform, MATRIX_WAVEFORM_SAMPLES), 1024)
+}
+
+/// Whether a voice note must be converted before sending to a platform.
+pub fn needs_conversion(format: VoiceFormat, platform: &str) -> bool {
+    format != VoiceFormat::preferred_for_platform(platform)
+}
+
+/// Build the `ffmpeg` arguments converting `input` into `target`.
+///
+/// Voice notes are downmixed to mono at speech-friendly bitrates.
+pub fn ffmpeg_args(
+    input: &Path,
+    output: &Path,
+    target: VoiceFormat,
+) -> Result<Vec<String>, MediaError> {
+    let codec: &[&str] = match target {
+        VoiceFormat::OggOpus => &[
+            "-c:a", "libopus", "-b:a", "32k", "-ar", "48000", "-f", "ogg",
+        ],
+        VoiceFormat::M4aAac => &["-c:a", "aac", "-b:a", "64k", "-ar", "44100", "-f", "ipod"],
+        VoiceFormat::Mp3 => &["-c:a", "libmp3lame", "-b:a", "64k", "-f", "mp3"],
+        VoiceFormat::WebmOpus => &["-c:a", "libopus", "-b:a", "32k", "-f", "webm"],
+        VoiceFormat::Wav => &["-c:a", "pcm_s16le", "-f", "wav"],
+        VoiceFormat::OggVorbis | VoiceFormat::Amr | VoiceFormat::Unknown => {
+            return Err(MediaError::UnsupportedFormat(format!("{:?}", target)));
+        }
+    };
+
+    let mut args = vec![
+        "-y".to_string(),
+        "-hide_banner".to_string(),
+        "-loglevel".to_string(),
+        "error".to_string(),
+        "-i".to_string(),
+        input.display().to_string(),
+        "-vn".to_string(),
+        "-ac".to_string(),
+        "1".to_string(),
+    ];
+    args.extend(codec.iter().map(|s| s.to_string()));
+    args.push(output.display().to_string());
+    Ok(args)
+}
+
+/// Convert a voice note to the container preferred by a platform.
+///
+/// If the input is already in the preferred format it is copied unchanged.
+/// Otherwise `ffmpeg` (which must be on `PATH`) performs the conversion.
+///
+/// # Returns
+///
+/// Returns the metadata of the converted file. The waveform of the source is
+/// kept when the converted file does not carry one itself.
+pub async fn convert_for_platform(
+    input: &Path,
+    output: &Path,
+    platform: &str,
+) -> Result<VoiceNoteMetadata, MediaError> {
+    let source = VoiceNoteMetadata::from_file(input)?;
+    let target = VoiceFormat::preferred_for_platform(platform);
+
+    if source.format == target {
+        tokio::fs::copy(input, output).await?;
+        return Ok(source);
+    }
+
+    let args = ffmpeg_args(input, output, target)?;
+    let result = tokio::process::Command::new("ffmpeg")
+        .args(&args)
+        .output()
+        .await
+        .map_err(|e| MediaError::Generic(format!("Failed to run ffmpeg: {}", e)))?;
+    if !result.status.success() {
+        return Err(MediaError::Generic(format!(
+            "ffmpeg conversion to {:?} failed: {}",
+            target,
+            String::from_utf8_lossy(&result.stderr).trim()
+        )));
+    }
+
+    let mut converted = VoiceNoteMetadata::from_file(output)?;
+    if converted.waveform.is_none() {
+        converted.waveform = source.waveform;
+    }
+    if converted.duration.is_none() {
+        converted.duration = source.duration;
+    }
+    Ok(converted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ogg_page(granule: i64, payload: &[u8]) -> Vec<u8> {
+        let mut page = b"OggS".to_vec();
+        page.push(0); // version
+        page.push(0); // header type
+        page.extend_from_slice(&granule.to_le_bytes());
+        page.extend_from_slice(&[0; 12]); // serial, sequence, crc
+        page.push(1);
+        page.push(payload.len() as u8);
+        page.extend_from_slice(payload);
+        page
+    }
+
+    fn opus_file(pre_skip: u16, granule: i64) -> Vec<u8> {
+        let mut head = b"OpusHead".to_vec();
+        head.push(1); // version
+        head.push(1); // channels
+        head.extend_from_slice(&pre_skip.to_le_bytes());
+        head.extend_from_slice(&48_000u32.to_le_bytes());
+        let mut data = ogg_page(0, &head);
+        data.extend(ogg_page(granule, b"audio"));
+        data
+    }
+
+    fn wav_file(samples: &[i16], rate: u32) -> Vec<u8> {
+        let data_len = (samples.len() * 2) as u32;
+        let mut wav = b"RIFF".to_vec();
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+        wav.extend_from_slice(&rate.to_le_bytes());
+        wav.extend_from_slice(&(rate * 2).to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        for s in samples {
+            wav.extend_from_slice(&s.to_le_bytes());
+        }
+        wav
+    }
+
+    #[test]
+    fn test_detect_formats() {
+        assert_eq!(
+            VoiceFormat::detect(&opus_file(312, 0)),
+            VoiceFormat::OggOpus
+        );
+        assert_eq!(
+            VoiceFormat::detect(b"\0\0\0\x20ftypM4A \0\0"),
+            VoiceFormat::M4aAac
+        );
+        assert_eq!(VoiceFormat::detect(b"ID3\x04\0\0"), VoiceFormat::Mp3);
+        assert_eq!(VoiceFormat::detect(b"#!AMR\n"), VoiceFormat::Amr);
+        assert_eq!(VoiceFormat::detect(&wav_file(&[0], 8000)), VoiceFormat::Wav);
+        assert_eq!(VoiceFormat::detect(b"hello"), VoiceFormat::Unknown);
+    }
+
+    #[test]
+    fn test_from_mime() {
+        assert_eq!(
+            VoiceFormat::from_mime("audio/ogg; codecs=opus"),
+            VoiceFormat::OggOpus
+        );
+        assert_eq!(
+            VoiceFormat::from_mime("audio/ogg; codecs=vorbis"),
+            VoiceFormat::OggVorbis
+        );
+        assert_eq!(VoiceFormat::from_mime("audio/aac"), VoiceFormat::M4aAac);
+        assert_eq!(VoiceFormat::from_mime("text/plain"), VoiceFormat::Unknown);
+    }
+
+    #[test]
+    fn test_ogg_opus_duration() {
+        // 3 seconds of audio plus the 312 sample pre-skip
+        let data = opus_file(312, 3 * 48_000 + 312);
+        let meta = VoiceNoteMetadata::from_bytes(&data);
+        assert_eq!(meta.duration, Some(Duration::from_secs(3)));
+        assert_eq!(meta.duration_secs(), Some(3));
+        assert_eq!(meta.duration_millis(), Some(3000));
+    }
+
+    #[test]
+    fn test_wav_duration_and_waveform() {
+        let mut samples = vec![0i16; 8000];
+        samples[4000] = 16384;
+        let meta = VoiceNoteMetadata::from_bytes(&wav_file(&samples, 8000));
+        assert_eq!(meta.format, VoiceFormat::Wav);
+        assert_eq!(meta.duration, Some(Duration::from_secs(1)));
+
+        let waveform = meta.waveform.unwrap();
+        assert_eq!(waveform.len(), 100);
+        assert_eq!(waveform[50], 1.0);
+        assert_eq!(waveform[0], 0.0);
+    }
+
+    #[test]
+    fn test_short_duration_rounds_up() {
+        let meta = VoiceNoteMetadata {
+            format: VoiceFormat::OggOpus,
+            duration: Some(Duration::from_millis(200)),
+            waveform: None,
+        };
+        assert_eq!(meta.duration_secs(), Some(1));
+    }
+
+    #[test]
+    fn test_normalize_waveform() {
+        assert_eq!(
+            normalize_waveform(&[0.1, 0.5, 0.2, 0.25], 2),
+            vec![1.0, 0.5]
+        );
+        assert_eq!(normalize_waveform(&[0.5], 3), vec![1.0, 1.0, 1.0]);
+        assert_eq!(normalize_waveform(&[0.0, 0.0], 2), vec![0.0, 0.0]);
+        assert!(normalize_waveform(&[], 10).is_empty());
+    }
+
+    #[test]
+    fn test_telegram_waveform_roundtrip() {
+        let waveform: Vec<f32> = (0..100).map(|i| (i % 32) as f32 / 31.0).collect();
+        let encoded = encode_telegram_waveform(&waveform);
+        assert_eq!(encoded.len(), 63);
+
+        let decoded = decode_telegram_waveform(&encoded);
+        for (original, decoded) in waveform.iter().zip(&decoded) {
+            assert!((original - decoded).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn test_matrix_waveform() {
+        let encoded = encode_matrix_waveform(&[0.0, 0.5, 1.0, 0.25]);
+        assert_eq!(encoded.len(), MATRIX_WAVEFORM_SAMPLES);
+        assert_eq!(encoded.iter().copied().max(), Some(1024));
+    }
+
+    #[test]
+    fn test_preferred_format_and_args() {
+        assert_eq!(
+            VoiceFormat::preferred_for_platform("telegram"),
+            VoiceFormat::OggOpus
+        );
+        assert_eq!(
+            VoiceFormat::preferred_for_platform("signal"),
+            VoiceFormat::M4aAac
+        );
+        assert!(!needs_conversion(VoiceFormat::OggOpus, "whatsapp"));
+        assert!(needs_conversion(VoiceFormat::OggOpus, "signal"));
+
+        let args = ffmpeg_args(
+            Path::new("in.m4a"),
+            Path::new("out.ogg"),
+            VoiceFormat::OggOpus,
+        )
+        .unwrap();
+        assert!(args.windows(2).any(|w| w == ["-c:a", "libopus"]));
+        assert_eq!(args.last().map(String::as_str), Some("out.ogg"));
+        assert!(ffmpeg_args(Path::new("a"), Path::new("b"), VoiceFormat::Amr).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_convert_same_format_copies() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("in.ogg");
+        let output = dir.path().join("out.ogg");
+        std::fs::write(&input, opus_file(0, 48_000)).unwrap();
+
+        let meta = convert_for_platform(&input, &output, "telegram")
+            .await
+            .unwrap();
+        assert_eq!(meta.format, VoiceFormat::OggOpus);
+        assert_eq!(
+            std::fs::read(&output).unwrap(),
+            std::fs::read(&input).unwrap()
+        );
+    }
+}
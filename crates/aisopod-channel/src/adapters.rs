@@ -6,7 +6,7 @@
 //! Each adapter trait represents a specific capability domain, from onboarding
 //! to device pairing. Channel plugins implement only the adapters they support.
 
-use crate::message::{Media, MessageTarget, SenderInfo};
+use crate::message::{Media, MessageButton, MessageTarget, SenderInfo};
 use crate::types::{ChannelMeta, ChannelCapabilities};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -152,6 +152,71 @@ pub trait OutboundAdapter: Send + Sync {
     /// * `Ok(())` - Media was sent successfully.
     /// * `Err(anyhow::Error)` - An error if sending fails.
     async fn send_media(&self, target: &MessageTarget, media: &Media) -> Result<(), anyhow::Error>;
+
+    /// Send a text message with quick-reply buttons to the specified target.
+    ///
+    /// A pressed button must be delivered as an incoming text message
+    /// carrying the button's value. The default implementation sends the
+    /// text alone, so the text should also explain how to reply by hand.
+    ///
+    /// # Arguments
+    /// * `target` - The message target specifying where to send.
+    /// * `text` - The plain text content to send.
+    /// * `buttons` - The buttons to attach below the text.
+    ///
+    /// # Returns
+    /// * `Ok(())` - Message was sent successfully.
+    /// * `Err(anyhow::Error)` - An error if sending fails.
+    async fn send_buttons(
+        &self,
+        target: &MessageTarget,
+        text: &str,
+        _buttons: &[MessageButton],
+    ) -> Result<(), anyhow::Error> {
+        self.send_text(target, text).await
+    }
+
+    /// Send a text message that can later be edited with [`OutboundAdapter::edit_text`].
+    ///
+    /// Channels whose platform can edit sent messages should override this
+    /// together with `edit_text`. The default implementation sends the text
+    /// with [`OutboundAdapter::send_text`] and returns `None`.
+    ///
+    /// # Arguments
+    /// * `target` - The message target specifying where to send.
+    /// * `text` - The plain text content to send.
+    ///
+    /// # Returns
+    /// * `Ok(Some(id))` - Message was sent and can be edited by this ID.
+    /// * `Ok(None)` - Message was sent but cannot be edited.
+    /// * `Err(anyhow::Error)` - An error if sending fails.
+    async fn send_editable_text(
+        &self,
+        target: &MessageTarget,
+        text: &str,
+    ) -> Result<Option<String>, anyhow::Error> {
+        self.send_text(target, text).await?;
+        Ok(None)
+    }
+
+    /// Replace the text of a message sent with [`OutboundAdapter::send_editable_text`].
+    ///
+    /// # Arguments
+    /// * `target` - The target the message was sent to.
+    /// * `message_id` - The ID returned when the message was sent.
+    /// * `text` - The new plain text content.
+    ///
+    /// # Returns
+    /// * `Ok(())` - Message was edited successfully.
+    /// * `Err(anyhow::Error)` - An error if editing fails or is not supported.
+    async fn edit_text(
+        &self,
+        _target: &MessageTarget,
+        _message_id: &str,
+        _text: &str,
+    ) -> Result<(), anyhow::Error> {
+        Err(anyhow::anyhow!("Editing messages is not supported by this channel"))
+    }
 }
 
 /// Adapter for gateway connection lifecycle management.
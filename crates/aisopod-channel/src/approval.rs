@@ -0,0 +1,344 @@
+//! Channel-backed approval handler for dangerous tool operations.
+//!
+//! [`ChannelApprovalHandler`] sends each approval request to an operator
+//! conversation on a configured channel and waits for a reply. Operators
+//! answer with `approve <code>` or `deny <code> [reason]`, either typed or
+//! through the quick-reply buttons of channels that support them. Replies
+//! reach the handler through [`ChannelApprovalHandler::handle_incoming`],
+//! which the [`MessageRouter`](crate::MessageRouter) calls before routing a
+//! message to an agent.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use aisopod_config::types::ApprovalConfig;
+use aisopod_tools::{
+    ApprovalError, ApprovalHandler, ApprovalRequest, ApprovalResponse, ApprovalStateTracker,
+};
+use async_trait::async_trait;
+use tokio::sync::oneshot;
+use tracing::{debug, warn};
+
+use crate::channel::ChannelRegistry;
+use crate::message::{IncomingMessage, MessageButton, MessageContent, OutgoingMessage};
+use crate::sender::{parse_peer, ChannelMessageSender};
+use crate::Result;
+
+/// A request waiting for the operator's decision.
+struct PendingApproval {
+    request_id: String,
+    decision: oneshot::Sender<ApprovalResponse>,
+}
+
+/// Asks an operator on a channel to approve tool operations.
+///
+/// Every decision, including timeouts, is recorded in the handler's
+/// [`ApprovalStateTracker`]. When only one request is pending, operators
+/// may omit its code and reply with a bare `approve` or `deny`.
+pub struct ChannelApprovalHandler {
+    registry: Arc<RwLock<ChannelRegistry>>,
+    sender: ChannelMessageSender,
+    channel: String,
+    account: Option<String>,
+    peer: String,
+    operators: Vec<String>,
+    timeout: Option<Duration>,
+    tracker: Arc<ApprovalStateTracker>,
+    pending: Mutex<HashMap<String, PendingApproval>>,
+    next_code: AtomicU64,
+}
+
+impl ChannelApprovalHandler {
+    /// Creates a handler sending requests to `peer` on `channel`.
+    ///
+    /// The peer is written as `[kind:]id`, as for the `message` tool.
+    pub fn new(
+        registry: Arc<RwLock<ChannelRegistry>>,
+        channel: impl Into<String>,
+        peer: impl Into<String>,
+    ) -> Self {
+        Self {
+            sender: ChannelMessageSender::new(registry.clone()),
+            registry,
+            channel: channel.into(),
+            account: None,
+            peer: peer.into(),
+            operators: Vec::new(),
+            timeout: None,
+            tracker: Arc::new(ApprovalStateTracker::new()),
+            pending: Mutex::new(HashMap::new()),
+            next_code: AtomicU64::new(1),
+        }
+    }
+
+    /// Creates a handler from the `tools.approval` configuration.
+    ///
+    /// Returns `None` when no operator channel and peer are configured.
+    pub fn from_config(
+        registry: Arc<RwLock<ChannelRegistry>>,
+        config: &ApprovalConfig,
+    ) -> Option<Self> {
+        let (Some(channel), Some(peer)) = (&config.channel, &config.peer) else {
+            return None;
+        };
+        let mut handler =
+            Self::new(registry, channel, peer).with_operators(config.operators.clone());
+        if let Some(account) = &config.account {
+            handler = handler.with_account(account);
+        }
+        if let Some(timeout) = config.timeout {
+            handler = handler.with_timeout(Duration::from_secs(timeout));
+        }
+        Some(handler)
+    }
+
+    /// Sends requests from the given channel account instead of the first one.
+    pub fn with_account(mut self, account: impl Into<String>) -> Self {
+        self.account = Some(account.into());
+        self
+    }
+
+    /// Restricts decisions to the given sender IDs.
+    pub fn with_operators(mut self, operators: Vec<String>) -> Self {
+        self.operators = operators;
+        self
+    }
+
+    /// Overrides the timeout carried by each request.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Records decisions in a shared tracker.
+    pub fn with_tracker(mut self, tracker: Arc<ApprovalStateTracker>) -> Self {
+        self.tracker = tracker;
+        self
+    }
+
+    /// Returns the tracker recording approval decisions.
+    pub fn tracker(&self) -> &Arc<ApprovalStateTracker> {
+        &self.tracker
+    }
+
+    /// Returns the number of requests waiting for a decision.
+    pub fn pending_count(&self) -> usize {
+        self.pending.lock().map(|p| p.len()).unwrap_or(0)
+    }
+
+    /// Applies an operator's reply to a pending request.
+    ///
+    /// Returns `true` if the message was a decision for a pending request
+    /// and has been consumed, `false` if it should be routed normally.
+    pub fn handle_incoming(&self, message: &IncomingMessage) -> bool {
+        if message.sender.is_bot || !self.is_operator_message(message) {
+            return false;
+        }
+        let text = match &message.content {
+            MessageContent::Text(text) => text.clone(),
+            _ => return false,
+        };
+        let Some((approve, code, reason)) = parse_reply(&text) else {
+            return false;
+        };
+
+        let Ok(mut pending) = self.pending.lock() else {
+            return false;
+        };
+        let code = match code {
+            Some(code) => code,
+            None if pending.len() == 1 => pending.keys().next().cloned().unwrap_or_default(),
+            None => return false,
+        };
+        let Some(entry) = pending.remove(&code) else {
+            return false;
+        };
+        drop(pending);
+
+        debug!(
+            "Approval request '{}' {} by '{}'",
+            entry.request_id,
+            if approve { "approved" } else { "denied" },
+            message.sender.id
+        );
+        let response = if approve {
+            ApprovalResponse::Approved
+        } else {
+            ApprovalResponse::Denied {
+                reason: reason
+                    .unwrap_or_else(|| format!("Denied by operator {}", message.sender.id)),
+            }
+        };
+        // The requester may already have given up waiting
+        let _ = entry.decision.send(response);
+        true
+    }
+
+    /// Returns whether the message comes from the operator conversation.
+    fn is_operator_message(&self, message: &IncomingMessage) -> bool {
+        let channel = self
+            .registry
+            .read()
+            .ok()
+            .and_then(|registry| registry.normalize_id(&self.channel));
+        let Ok((peer, _)) = parse_peer(&self.peer) else {
+            return false;
+        };
+        let same_channel =
+            channel.as_deref() == Some(message.channel.as_str()) || message.channel == self.channel;
+        let same_account = self
+            .account
+            .as_ref()
+            .is_none_or(|account| *account == message.account_id);
+        let allowed_sender =
+            self.operators.is_empty() || self.operators.contains(&message.sender.id);
+        same_channel && same_account && message.peer.id == peer.id && allowed_sender
+    }
+
+    /// Sends a message to the operator, attaching buttons where supported.
+    async fn notify(&self, text: &str, buttons: &[MessageButton]) -> Result<()> {
+        let (plugin, target) =
+            self.sender
+                .resolve_target(&self.channel, self.account.as_deref(), Some(&self.peer))?;
+        match plugin.outbound() {
+            Some(outbound) if buttons.is_empty() => outbound.send_text(&target, text).await,
+            Some(outbound) => outbound.send_buttons(&target, text, buttons).await,
+            None => {
+                plugin
+                    .send(OutgoingMessage {
+                        target,
+                        content: MessageContent::Text(text.to_string()),
+                        reply_to: None,
+                    })
+                    .await
+            }
+        }
+    }
+
+    fn remove_pending(&self, code: &str) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(code);
+        }
+    }
+}
+
+/// Parses `approve|deny [code] [reason]` replies.
+///
+/// Returns whether the request is approved, the code if given, and the
+/// denial reason if given.
+fn parse_reply(text: &str) -> Option<(bool, Option<String>, Option<String>)> {
+    let mut words = text.split_whitespace();
+    let approve = match words.next()?.to_lowercase().as_str() {
+        "approve" | "approved" | "allow" | "yes" | "y" => true,
+        "deny" | "denied" | "reject" | "no" | "n" => false,
+        _ => return None,
+    };
+    let mut rest: Vec<&str> = words.collect();
+    let code = rest
+        .first()
+        .map(|word| word.trim_start_matches('#'))
+        .filter(|code| !code.is_empty() && code.chars().all(|c| c.is_ascii_digit()))
+        .map(String::from);
+    if code.is_some() {
+        rest.remove(0);
+    }
+    let reason = (!rest.is_empty()).then(|| rest.join(" "));
+    Some((approve, code, reason))
+}
+
+#[async_trait]
+impl ApprovalHandler for ChannelApprovalHandler {
+    async fn request_approval(
+        &self,
+        request: ApprovalRequest,
+    ) -> std::result::Result<ApprovalResponse, ApprovalError> {
+        let timeout = self.timeout.unwrap_or(request.timeout);
+        let code = self.next_code.fetch_add(1, Ordering::SeqCst).to_string();
+        let (tx, rx) = oneshot::channel();
+
+        self.pending
+            .lock()
+            .map_err(|_| ApprovalError::HandlerError("Approval state lock poisoned".into()))?
+            .insert(
+                code.clone(),
+                PendingApproval {
+                    request_id: request.id.clone(),
+                    decision: tx,
+                },
+            );
+        self.tracker
+            .record_pending(&request.id)
+            .map_err(|e| ApprovalError::HandlerError(e.to_string()))?;
+
+        let text = format!(
+            "Approval required (#{code})\nAgent: {}\nRisk: {:?}\nOperation: {}\n\n\
+             Reply \"approve {code}\" or \"deny {code} [reason]\" within {} seconds.",
+            request.agent_id,
+            request.risk_level,
+            request.operation,
+            timeout.as_secs(),
+        );
+        let buttons = [
+            MessageButton::new("Approve", format!("approve {}", code)),
+            MessageButton::new("Deny", format!("deny {}", code)),
+        ];
+        if let Err(e) = self.notify(&text, &buttons).await {
+            self.remove_pending(&code);
+            let _ = self
+                .tracker
+                .record_denied(&request.id, "operator unreachable");
+            return Err(ApprovalError::HandlerError(format!(
+                "Failed to send approval request: {}",
+                e
+            )));
+        }
+
+        let response = match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(_)) | Err(_) => {
+                self.remove_pending(&code);
+                if let Err(e) = self
+                    .notify(&format!("Approval request #{} timed out.", code), &[])
+                    .await
+                {
+                    warn!("Failed to notify operator of approval timeout: {}", e);
+                }
+                ApprovalResponse::TimedOut
+            }
+        };
+
+        let recorded = match &response {
+            ApprovalResponse::Approved => self.tracker.record_approved(&request.id),
+            ApprovalResponse::Denied { reason } => self.tracker.record_denied(&request.id, reason),
+            ApprovalResponse::TimedOut => self.tracker.record_timed_out(&request.id),
+        };
+        recorded.map_err(|e| ApprovalError::HandlerError(e.to_string()))?;
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reply() {
+        assert_eq!(
+            parse_reply("approve 3"),
+            Some((true, Some("3".into()), None))
+        );
+        assert_eq!(parse_reply("Yes"), Some((true, None, None)));
+        assert_eq!(
+            parse_reply("deny #12 too risky"),
+            Some((false, Some("12".into()), Some("too risky".into())))
+        );
+        assert_eq!(
+            parse_reply("no not today"),
+            Some((false, None, Some("not today".into())))
+        );
+        assert_eq!(parse_reply("what is this?"), None);
+        assert_eq!(parse_reply(""), None);
+    }
+}
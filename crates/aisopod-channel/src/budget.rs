@@ -0,0 +1,103 @@
+//! Channel-backed operator notifications for exceeded budgets.
+//!
+//! [`ChannelBudgetNotifier`] reports sessions and peers that reached their
+//! `session.budget` limits to an operator conversation on a configured
+//! channel. Each session or peer is reported once per UTC day, however many
+//! of its runs are refused.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, RwLock};
+
+use aisopod_agent::{BudgetExceeded, BudgetNotifier, BudgetScope};
+use aisopod_config::types::BudgetNotifyConfig;
+use aisopod_tools::MessageSender;
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+
+use crate::channel::ChannelRegistry;
+use crate::sender::ChannelMessageSender;
+use crate::Result;
+
+/// Reports exceeded budgets to an operator on a channel.
+pub struct ChannelBudgetNotifier {
+    sender: ChannelMessageSender,
+    channel: String,
+    account: Option<String>,
+    peer: String,
+    notified: Mutex<HashSet<(NaiveDate, BudgetScope, String)>>,
+}
+
+impl ChannelBudgetNotifier {
+    /// Creates a notifier sending reports to `peer` on `channel`.
+    ///
+    /// The peer is written as `[kind:]id`, as for the `message` tool.
+    pub fn new(
+        registry: Arc<RwLock<ChannelRegistry>>,
+        channel: impl Into<String>,
+        peer: impl Into<String>,
+    ) -> Self {
+        Self {
+            sender: ChannelMessageSender::new(registry),
+            channel: channel.into(),
+            account: None,
+            peer: peer.into(),
+            notified: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Creates a notifier from the `session.budget.notify` configuration.
+    ///
+    /// Returns `None` when no operator channel and peer are configured.
+    pub fn from_config(
+        registry: Arc<RwLock<ChannelRegistry>>,
+        config: &BudgetNotifyConfig,
+    ) -> Option<Self> {
+        let (Some(channel), Some(peer)) = (&config.channel, &config.peer) else {
+            return None;
+        };
+        let notifier = Self::new(registry, channel, peer);
+        Some(match &config.account {
+            Some(account) => notifier.with_account(account),
+            None => notifier,
+        })
+    }
+
+    /// Sends reports from the given channel account instead of the first one.
+    pub fn with_account(mut self, account: impl Into<String>) -> Self {
+        self.account = Some(account.into());
+        self
+    }
+
+    /// Records that `exceeded` is being reported, returning false if it
+    /// already was today.
+    fn first_report_today(&self, exceeded: &BudgetExceeded) -> bool {
+        let subject = match exceeded.scope {
+            BudgetScope::Session => &exceeded.session_key,
+            BudgetScope::DailyPeer => &exceeded.peer,
+        };
+        let today = Utc::now().date_naive();
+        let Ok(mut notified) = self.notified.lock() else {
+            return true;
+        };
+        // Forget reports of earlier days
+        notified.retain(|(day, _, _)| *day == today);
+        notified.insert((today, exceeded.scope, subject.clone()))
+    }
+}
+
+#[async_trait]
+impl BudgetNotifier for ChannelBudgetNotifier {
+    async fn budget_exceeded(&self, exceeded: &BudgetExceeded) -> Result<()> {
+        if !self.first_report_today(exceeded) {
+            return Ok(());
+        }
+        self.sender
+            .send_message(
+                &self.channel,
+                &exceeded.describe(),
+                self.account.as_deref(),
+                Some(&self.peer),
+            )
+            .await
+    }
+}
@@ -18,6 +18,10 @@
 //!
 //! - [`ChannelRegistry`] - Central registry for channel plugins
 //! - [`ChannelAlias`] - Alias mapping for channel IDs
+//! - [`ChannelMessageSender`] - Sends `message` tool calls through registered channels
+//! - [`ChannelApprovalHandler`] - Asks an operator on a channel to approve tool operations
+//! - [`ChannelBudgetNotifier`] - Tells an operator on a channel about exceeded budgets
+//! - [`ChannelReplySink`] - Streams agent replies to a chat, editing them in place where supported
 //!
 //! ## Adapter Traits
 //!
@@ -77,23 +81,29 @@
 //! ```
 
 pub mod adapters;
+pub mod approval;
+pub mod budget;
 pub mod channel;
 pub mod media;
 pub mod message;
 pub mod plugin;
+pub mod reload;
 pub mod router;
 pub mod security;
+pub mod sender;
+pub mod session_commands;
+pub mod streaming;
 pub mod types;
 pub mod util;
 
 // Re-export message types
 pub use message::{
-    IncomingMessage, OutgoingMessage, MessageContent, MessagePart, MessageTarget,
+    IncomingMessage, OutgoingMessage, MessageButton, MessageContent, MessagePart, MessageTarget,
     PeerInfo, PeerKind, Media,
 };
 
 // Re-export router
-pub use router::{MessageRouter, AgentResolver, ConfigAgentResolver};
+pub use router::{MessageRouter, AgentResolver, ConfigAgentResolver, HandoffAgentResolver};
 
 // Re-export security types
 pub use security::{SecurityEnforcer, MentionCheckResult};
@@ -120,6 +130,23 @@ pub use adapters::{
 // Re-export channel registry
 pub use channel::{ChannelAlias, ChannelRegistry};
 
+// Re-export the message tool sender
+pub use sender::ChannelMessageSender;
+
+// Re-export the streaming reply sink
+pub use streaming::ChannelReplySink;
+
+// Re-export the operator approval handler
+pub use approval::ChannelApprovalHandler;
+
+pub use session_commands::SessionCommandHandler;
+
+// Re-export the operator budget notifier
+pub use budget::ChannelBudgetNotifier;
+
+// Re-export the channel hot reload applier
+pub use reload::{ChannelApplier, ChannelFactory};
+
 // Re-export shared utilities
 pub use util::{
     connection::{ConnectionManager, ConnectionState},
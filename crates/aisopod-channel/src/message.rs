@@ -4,9 +4,13 @@
 //! abstraction layer. These types represent all messages flowing between channels
 //! and the agent engine.
 
+use aisopod_provider::ContentPart;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::media::detect_mime_type;
 use crate::types::MediaType;
 
 /// The kind of peer in a conversation.
@@ -73,6 +77,45 @@ pub struct Media {
     pub size_bytes: Option<u64>,
 }
 
+impl Media {
+    /// Returns a text placeholder describing the media, e.g. `[Image: <url>]`.
+    pub fn placeholder(&self) -> String {
+        match &self.media_type {
+            MediaType::Image => format!("[Image: {}]", self.url.as_deref().unwrap_or("unknown")),
+            MediaType::Audio => format!("[Audio: {}]", self.url.as_deref().unwrap_or("unknown")),
+            MediaType::Video => format!("[Video: {}]", self.url.as_deref().unwrap_or("unknown")),
+            MediaType::Document => format!("[Document: {}]", self.filename.as_deref().unwrap_or("unknown")),
+            MediaType::Other(other) => format!("[{}: {}]", other, self.url.as_deref().unwrap_or("unknown")),
+        }
+    }
+
+    /// Converts the media to a content part of a model request.
+    ///
+    /// Images with data become base64 image parts, and images with only a
+    /// URL become image URL parts; providers downscale them to their limits.
+    /// Other media become a text placeholder.
+    pub fn to_content_part(&self) -> ContentPart {
+        if self.media_type == MediaType::Image {
+            if let Some(data) = &self.data {
+                let media_type = self
+                    .mime_type
+                    .clone()
+                    .unwrap_or_else(|| detect_mime_type(data, self.filename.as_deref()));
+                return ContentPart::Image {
+                    media_type,
+                    data: STANDARD.encode(data),
+                };
+            }
+            if let Some(url) = &self.url {
+                return ContentPart::ImageUrl { url: url.clone() };
+            }
+        }
+        ContentPart::Text {
+            text: self.placeholder(),
+        }
+    }
+}
+
 /// A single part of a mixed message.
 ///
 /// This enum represents one component of a potentially rich message
@@ -99,6 +142,52 @@ pub enum MessageContent {
     Mixed(Vec<MessagePart>),
 }
 
+impl MessageContent {
+    /// Converts the content to the content of a model request message.
+    ///
+    /// Text stays plain text; media is converted with
+    /// [`Media::to_content_part`] so images reach multi-modal models.
+    pub fn to_provider_content(&self) -> aisopod_provider::MessageContent {
+        match self {
+            MessageContent::Text(text) => aisopod_provider::MessageContent::Text(text.clone()),
+            MessageContent::Media(media) => {
+                aisopod_provider::MessageContent::Parts(vec![media.to_content_part()])
+            }
+            MessageContent::Mixed(parts) => aisopod_provider::MessageContent::Parts(
+                parts
+                    .iter()
+                    .map(|part| match part {
+                        MessagePart::Text(text) => ContentPart::Text { text: text.clone() },
+                        MessagePart::Media(media) => media.to_content_part(),
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// A quick-reply button attached to an outgoing message.
+///
+/// Channels that render buttons deliver a press as an incoming text
+/// message whose content is the button's `value`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageButton {
+    /// Text shown on the button.
+    pub label: String,
+    /// Text sent back when the button is pressed.
+    pub value: String,
+}
+
+impl MessageButton {
+    /// Creates a button with the given label and reply value.
+    pub fn new(label: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            value: value.into(),
+        }
+    }
+}
+
 /// Target information for an outgoing message.
 ///
 /// This struct specifies where an outgoing message should be delivered.
@@ -152,30 +241,13 @@ impl IncomingMessage {
     pub fn content_to_string(&self) -> String {
         match &self.content {
             MessageContent::Text(text) => text.clone(),
-            MessageContent::Media(media) => {
-                // Return a placeholder for media content
-                match &media.media_type {
-                    crate::types::MediaType::Image => format!("[Image: {}]", media.url.as_deref().unwrap_or("unknown")),
-                    crate::types::MediaType::Audio => format!("[Audio: {}]", media.url.as_deref().unwrap_or("unknown")),
-                    crate::types::MediaType::Video => format!("[Video: {}]", media.url.as_deref().unwrap_or("unknown")),
-                    crate::types::MediaType::Document => format!("[Document: {}]", media.filename.as_deref().unwrap_or("unknown")),
-                    crate::types::MediaType::Other(other) => format!("[{}: {}]", other, media.url.as_deref().unwrap_or("unknown")),
-                }
-            }
+            MessageContent::Media(media) => media.placeholder(),
             MessageContent::Mixed(parts) => {
                 parts
                     .iter()
                     .map(|part| match part {
                         MessagePart::Text(text) => text.clone(),
-                        MessagePart::Media(media) => {
-                            match &media.media_type {
-                                crate::types::MediaType::Image => format!("[Image: {}]", media.url.as_deref().unwrap_or("unknown")),
-                                crate::types::MediaType::Audio => format!("[Audio: {}]", media.url.as_deref().unwrap_or("unknown")),
-                                crate::types::MediaType::Video => format!("[Video: {}]", media.url.as_deref().unwrap_or("unknown")),
-                                crate::types::MediaType::Document => format!("[Document: {}]", media.filename.as_deref().unwrap_or("unknown")),
-                                crate::types::MediaType::Other(other) => format!("[{}: {}]", other, media.url.as_deref().unwrap_or("unknown")),
-                            }
-                        }
+                        MessagePart::Media(media) => media.placeholder(),
                     })
                     .collect::<Vec<_>>()
                     .join(" ")
@@ -5,7 +5,7 @@
 //! capabilities, and configuration.
 
 use crate::types::{ChannelCapabilities, ChannelMeta};
-use crate::adapters::{ChannelConfigAdapter, SecurityAdapter};
+use crate::adapters::{ChannelConfigAdapter, OutboundAdapter, SecurityAdapter};
 use crate::message::{IncomingMessage, OutgoingMessage};
 use crate::Result;
 use async_trait::async_trait;
@@ -53,6 +53,15 @@ pub trait ChannelPlugin: Send + Sync {
     /// Returns `None` if the channel doesn't implement security checks.
     fn security(&self) -> Option<&dyn SecurityAdapter>;
 
+    /// Returns the outbound adapter for this channel if available.
+    ///
+    /// The outbound adapter delivers text and media to a `MessageTarget`.
+    /// The default implementation returns `None`, in which case callers
+    /// fall back to [`ChannelPlugin::send`].
+    fn outbound(&self) -> Option<&dyn OutboundAdapter> {
+        None
+    }
+
     /// Connect to the channel service.
     ///
     /// This method establishes the connection to the channel's backend service.
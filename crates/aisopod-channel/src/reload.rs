@@ -0,0 +1,169 @@
+//! Hot reload of the configured channels.
+//!
+//! [`ChannelApplier`] keeps a [`ChannelRegistry`] in line with the
+//! `channels` section of a reloaded configuration: channels that were
+//! removed or disabled are stopped and unregistered, new and re-enabled
+//! channels are started and registered, and channels whose settings changed
+//! are restarted. Channels whose entries are unchanged keep running.
+//!
+//! Creating a running plugin from a config entry is up to the embedding
+//! application, which knows the available channel crates, through a
+//! [`ChannelFactory`].
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+use aisopod_config::types::Channel;
+use aisopod_config::{AisopodConfig, ConfigApplier};
+use anyhow::{anyhow, bail, Context};
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::channel::ChannelRegistry;
+use crate::plugin::ChannelPlugin;
+use crate::Result;
+
+/// Creates and stops the channel plugins of configured channels.
+#[async_trait]
+pub trait ChannelFactory: Send + Sync {
+    /// Creates and connects the plugin of a configured channel.
+    async fn start(&self, channel: &Channel) -> Result<Arc<dyn ChannelPlugin>>;
+
+    /// Stops the plugin of a channel that was removed from the
+    /// configuration or disabled. The plugin is unregistered afterwards.
+    async fn stop(&self, _plugin: Arc<dyn ChannelPlugin>) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Starts and stops channels as the `channels` configuration changes.
+pub struct ChannelApplier {
+    registry: Arc<RwLock<ChannelRegistry>>,
+    factory: Arc<dyn ChannelFactory>,
+    /// Plugin IDs of the running channels, keyed by config channel ID.
+    running: Mutex<HashMap<String, String>>,
+}
+
+impl ChannelApplier {
+    /// Creates an applier registering the started channels in `registry`.
+    pub fn new(registry: Arc<RwLock<ChannelRegistry>>, factory: Arc<dyn ChannelFactory>) -> Self {
+        Self {
+            registry,
+            factory,
+            running: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Starts the enabled channels of the initial configuration.
+    pub async fn start_all(&self, config: &AisopodConfig) -> Result<()> {
+        self.apply(&AisopodConfig::default(), config).await
+    }
+
+    /// IDs of the configured channels that are running.
+    pub fn running(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.running_ids().keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    fn running_ids(&self) -> std::sync::MutexGuard<'_, HashMap<String, String>> {
+        self.running.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn start(&self, channel: &Channel) -> Result<()> {
+        let plugin = self
+            .factory
+            .start(channel)
+            .await
+            .with_context(|| format!("Failed to start channel '{}'", channel.id))?;
+        let plugin_id = plugin.id().to_string();
+        self.registry
+            .write()
+            .map_err(|_| anyhow!("Channel registry lock poisoned"))?
+            .register(plugin);
+        self.running_ids().insert(channel.id.clone(), plugin_id);
+        info!("Started channel '{}'", channel.id);
+        Ok(())
+    }
+
+    async fn stop(&self, id: &str) -> Result<()> {
+        let Some(plugin_id) = self.running_ids().remove(id) else {
+            return Ok(());
+        };
+        let plugin = {
+            let mut registry = self
+                .registry
+                .write()
+                .map_err(|_| anyhow!("Channel registry lock poisoned"))?;
+            let plugin = registry.get(&plugin_id);
+            registry.unregister(&plugin_id);
+            plugin
+        };
+        if let Some(plugin) = plugin {
+            self.factory
+                .stop(plugin)
+                .await
+                .with_context(|| format!("Failed to stop channel '{}'", id))?;
+        }
+        info!("Stopped channel '{}'", id);
+        Ok(())
+    }
+}
+
+/// The enabled channels of a configuration with their serialized entries,
+/// used to detect changed settings.
+fn enabled_channels(config: &AisopodConfig) -> HashMap<&str, (&Channel, serde_json::Value)> {
+    config
+        .channels
+        .enabled_channels()
+        .map(|channel| {
+            let entry = serde_json::to_value(channel).unwrap_or_default();
+            (channel.id.as_str(), (channel, entry))
+        })
+        .collect()
+}
+
+#[async_trait]
+impl ConfigApplier for ChannelApplier {
+    fn name(&self) -> &str {
+        "channels"
+    }
+
+    fn sections(&self) -> &[&str] {
+        &["channels"]
+    }
+
+    async fn apply(&self, old: &AisopodConfig, new: &AisopodConfig) -> Result<()> {
+        let old_channels = enabled_channels(old);
+        let new_channels = enabled_channels(new);
+        let mut errors = Vec::new();
+
+        for (id, (_, old_entry)) in &old_channels {
+            let unchanged = new_channels
+                .get(id)
+                .is_some_and(|(_, new_entry)| new_entry == old_entry);
+            if !unchanged {
+                if let Err(e) = self.stop(id).await {
+                    errors.push(format!("{:#}", e));
+                }
+            }
+        }
+
+        for (id, (channel, new_entry)) in &new_channels {
+            let unchanged = old_channels
+                .get(id)
+                .is_some_and(|(_, old_entry)| old_entry == new_entry);
+            // Channels that failed to start before are retried on any change
+            if !unchanged || !self.running_ids().contains_key(*id) {
+                if let Err(e) = self.start(channel).await {
+                    errors.push(format!("{:#}", e));
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            bail!("{}", errors.join("; "));
+        }
+        Ok(())
+    }
+}
@@ -3,18 +3,23 @@
 //! This module implements the core message routing functionality that takes
 //! incoming messages from channels and delivers them to the appropriate agents.
 
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use anyhow::Result;
+use async_trait::async_trait;
 use tracing::{instrument, trace};
 
-use crate::message::IncomingMessage;
+use crate::approval::ChannelApprovalHandler;
+use crate::message::{IncomingMessage, MessageContent, MessageTarget, OutgoingMessage};
 use crate::channel::ChannelRegistry;
+use crate::plugin::ChannelPlugin;
 use crate::adapters::{ChannelConfigAdapter, SecurityAdapter};
 use crate::security::SecurityEnforcer;
+use crate::session_commands::SessionCommandHandler;
 use aisopod_session::{SessionKey, routing::resolve_session_key, PeerKind};
 use aisopod_agent::resolution::resolve_session_agent_id;
-use aisopod_config::AisopodConfig;
+use aisopod_agent::HandoffRegistry;
+use aisopod_config::{AisopodConfig, ConfigApplier};
 use aisopod_tools::SessionManager;
 
 /// A message router that routes incoming messages to the appropriate agent.
@@ -39,6 +44,10 @@ pub struct MessageRouter {
     agent_resolver: Arc<dyn AgentResolver>,
     /// The session manager for session key generation and session lifecycle.
     session_manager: Arc<dyn SessionManager>,
+    /// Optional handler consuming operator replies to approval requests.
+    approvals: Option<Arc<ChannelApprovalHandler>>,
+    /// Optional handler of the peers' session commands.
+    session_commands: Option<Arc<SessionCommandHandler>>,
 }
 
 /// Trait for agent resolution.
@@ -54,22 +63,75 @@ pub trait AgentResolver: Send + Sync {
 /// Default agent resolver implementation.
 ///
 /// This resolver uses the aisopod configuration to determine which
-/// agent should handle a given session based on the session key.
+/// agent should handle a given session based on the session key. As a
+/// [`ConfigApplier`], it rebinds agents when the `agents` or `bindings`
+/// sections of a reloaded configuration change.
 pub struct ConfigAgentResolver {
-    config: Arc<AisopodConfig>,
+    config: RwLock<Arc<AisopodConfig>>,
 }
 
 impl ConfigAgentResolver {
     /// Creates a new `ConfigAgentResolver` with the given configuration.
     pub fn new(config: Arc<AisopodConfig>) -> Self {
-        Self { config }
+        Self {
+            config: RwLock::new(config),
+        }
+    }
+
+    /// Replaces the configuration used for later resolutions.
+    pub fn update_config(&self, config: Arc<AisopodConfig>) {
+        *self.config.write().unwrap_or_else(|e| e.into_inner()) = config;
     }
 }
 
 impl AgentResolver for ConfigAgentResolver {
     fn resolve(&self, session_key: &SessionKey) -> Result<String> {
         let session_key_str = session_key.canonical_string();
-        resolve_session_agent_id(&self.config, &session_key_str)
+        let config = self.config.read().unwrap_or_else(|e| e.into_inner()).clone();
+        resolve_session_agent_id(&config, &session_key_str)
+    }
+}
+
+#[async_trait]
+impl ConfigApplier for ConfigAgentResolver {
+    fn name(&self) -> &str {
+        "agent resolver"
+    }
+
+    fn sections(&self) -> &[&str] {
+        &["agents", "bindings"]
+    }
+
+    async fn apply(&self, _old: &AisopodConfig, new: &AisopodConfig) -> Result<()> {
+        self.update_config(Arc::new(new.clone()));
+        Ok(())
+    }
+}
+
+/// Agent resolver honouring agent-to-agent handoffs.
+///
+/// Sessions handed off to another agent resolve to the receiving agent;
+/// all other sessions are resolved by the wrapped resolver. Session keys are
+/// built before handoffs are applied, so a handed-off conversation keeps
+/// its session.
+pub struct HandoffAgentResolver {
+    inner: Arc<dyn AgentResolver>,
+    handoffs: Arc<HandoffRegistry>,
+}
+
+impl HandoffAgentResolver {
+    /// Creates a new `HandoffAgentResolver` wrapping `inner`.
+    pub fn new(inner: Arc<dyn AgentResolver>, handoffs: Arc<HandoffRegistry>) -> Self {
+        Self { inner, handoffs }
+    }
+}
+
+impl AgentResolver for HandoffAgentResolver {
+    fn resolve(&self, session_key: &SessionKey) -> Result<String> {
+        match self.handoffs.agent_for(&session_key.canonical_string()) {
+            Some(agent_id) => Ok(agent_id),
+            None => self.inner.resolve(session_key),
+        }
     }
 }
 
@@ -90,9 +152,30 @@ impl MessageRouter {
             registry,
             agent_resolver,
             session_manager,
+            approvals: None,
+            session_commands: None,
         }
     }
 
+    /// Sets the approval handler whose operator replies are intercepted.
+    ///
+    /// Messages that decide a pending approval request are consumed after
+    /// the security check and never reach an agent.
+    pub fn with_approval_handler(mut self, approvals: Arc<ChannelApprovalHandler>) -> Self {
+        self.approvals = Some(approvals);
+        self
+    }
+
+    /// Sets the handler of the session commands peers use to manage their
+    /// conversations.
+    ///
+    /// Session commands are answered directly and never reach an agent;
+    /// all other messages are routed to the sender's active session.
+    pub fn with_session_commands(mut self, commands: Arc<SessionCommandHandler>) -> Self {
+        self.session_commands = Some(commands);
+        self
+    }
+
     /// Routes an incoming message to the appropriate agent.
     ///
     /// This method implements the full message routing pipeline:
@@ -157,6 +240,14 @@ impl MessageRouter {
 
         trace!("Security check passed");
 
+        // Operator replies to approval requests are not meant for an agent
+        if let Some(approvals) = &self.approvals {
+            if approvals.handle_incoming(&message) {
+                trace!("Consumed approval decision");
+                return Ok(());
+            }
+        }
+
         // Step 5: Check mention requirement for group messages
         // Build bot identifiers from the bot's sender info
         let bot_identifiers = vec![message.account_id.clone()];
@@ -174,10 +265,29 @@ impl MessageRouter {
             }
         }
 
+        // Session commands switch between the peer's conversations
+        if let Some(commands) = &self.session_commands {
+            if let Some(reply) =
+                commands.handle_command(&session_key, &message.content_to_string())
+            {
+                trace!("Handled session command");
+                self.reply(plugin.as_ref(), &normalized_channel_id, &message, &reply)
+                    .await?;
+                return Ok(());
+            }
+        }
+
         // Step 6: Resolve agent
         let agent_id = self.agent_resolver.resolve(&session_key)?;
         trace!("Resolved agent: {}", agent_id);
 
+        // Agents are resolved for the peer, but the conversation continues
+        // in the session the peer switched to
+        let session_key = match &self.session_commands {
+            Some(commands) => commands.active_session_key(&session_key)?,
+            None => session_key,
+        };
+
         // Step 7: Route to agent runner
         self.route_to_runner(&session_key, &message, agent_id).await?;
 
@@ -223,6 +333,34 @@ impl MessageRouter {
         resolve_session_key(&agent_id, &ctx)
     }
 
+    /// Sends a text reply to the conversation a message came from.
+    async fn reply(
+        &self,
+        plugin: &dyn ChannelPlugin,
+        channel_id: &str,
+        message: &IncomingMessage,
+        text: &str,
+    ) -> Result<()> {
+        let target = MessageTarget {
+            channel: channel_id.to_string(),
+            account_id: message.account_id.clone(),
+            peer: message.peer.clone(),
+            thread_id: None,
+        };
+        match plugin.outbound() {
+            Some(outbound) => outbound.send_text(&target, text).await,
+            None => {
+                plugin
+                    .send(OutgoingMessage {
+                        target,
+                        content: MessageContent::Text(text.to_string()),
+                        reply_to: Some(message.id.clone()),
+                    })
+                    .await
+            }
+        }
+    }
+
     /// Routes the message to the agent runner.
     async fn route_to_runner(
         &self,
@@ -251,6 +389,8 @@ impl std::fmt::Debug for MessageRouter {
             .field("registry", &"ChannelRegistry {...}")
             .field("agent_resolver", &"AgentResolver {...}")
             .field("session_manager", &"SessionManager {...}")
+            .field("approvals", &self.approvals.is_some())
+            .field("session_commands", &self.session_commands.is_some())
             .finish()
     }
 }
@@ -274,6 +414,43 @@ mod tests {
         assert!(true);
     }
 
+    /// Resolver always returning the same agent.
+    struct FixedResolver(&'static str);
+
+    impl AgentResolver for FixedResolver {
+        fn resolve(&self, _session_key: &SessionKey) -> Result<String> {
+            Ok(self.0.to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handoff_resolver_prefers_handoff() {
+        use aisopod_tools::SessionHandoff;
+
+        let mut config = AisopodConfig::default();
+        config.agents.agents.push(aisopod_config::types::Agent {
+            id: "billing".to_string(),
+            ..Default::default()
+        });
+        let handoffs = Arc::new(HandoffRegistry::new(Arc::new(config)));
+        let resolver =
+            HandoffAgentResolver::new(Arc::new(FixedResolver("triage")), handoffs.clone());
+        let key = SessionKey {
+            agent_id: "triage".to_string(),
+            channel: "slack".to_string(),
+            account_id: "bot".to_string(),
+            peer_kind: "dm".to_string(),
+            peer_id: "alice".to_string(),
+        };
+        assert_eq!(resolver.resolve(&key).unwrap(), "triage");
+
+        handoffs
+            .handoff(&key.canonical_string(), "triage", "billing", "Refund", None)
+            .await
+            .unwrap();
+        assert_eq!(resolver.resolve(&key).unwrap(), "billing");
+    }
+
     #[test]
     fn test_session_key_creation() {
         let key = SessionKey {
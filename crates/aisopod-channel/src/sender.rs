@@ -0,0 +1,129 @@
+//! Channel-backed implementation of the `message` tool's sender.
+//!
+//! [`ChannelMessageSender`] lets agents proactively message users and groups
+//! from tool calls. It resolves the tool's `channel`/`account`/`peer`
+//! arguments into a [`MessageTarget`] through the [`ChannelRegistry`] and
+//! delivers the text with the channel's [`OutboundAdapter`], falling back to
+//! [`ChannelPlugin::send`] for channels without one.
+
+use std::sync::{Arc, RwLock};
+
+use aisopod_tools::MessageSender;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use tracing::debug;
+
+use crate::channel::ChannelRegistry;
+use crate::message::{MessageContent, MessageTarget, OutgoingMessage, PeerInfo, PeerKind};
+use crate::plugin::ChannelPlugin;
+use crate::Result;
+
+/// Sends `message` tool output through registered channels.
+///
+/// Peers are written as `[kind:]id`, where `kind` is one of `user`,
+/// `group`, `channel` or `thread` and defaults to `user`. When no account
+/// is given, the channel's first configured account is used.
+#[derive(Clone)]
+pub struct ChannelMessageSender {
+    registry: Arc<RwLock<ChannelRegistry>>,
+}
+
+impl ChannelMessageSender {
+    /// Creates a sender resolving channels through the given registry.
+    pub fn new(registry: Arc<RwLock<ChannelRegistry>>) -> Self {
+        Self { registry }
+    }
+
+    /// Resolves tool arguments into the channel plugin and message target.
+    pub fn resolve_target(
+        &self,
+        channel: &str,
+        account: Option<&str>,
+        peer: Option<&str>,
+    ) -> Result<(Arc<dyn ChannelPlugin>, MessageTarget)> {
+        let plugin = self
+            .registry
+            .read()
+            .map_err(|_| anyhow!("Channel registry lock poisoned"))?
+            .get(channel)
+            .ok_or_else(|| anyhow!("Unknown channel '{}'", channel))?;
+
+        let account_id = match account {
+            Some(account) => account.to_string(),
+            None => plugin
+                .config()
+                .list_accounts()?
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow!("Channel '{}' has no configured accounts", channel))?,
+        };
+
+        let peer = peer.ok_or_else(|| {
+            anyhow!(
+                "A peer is required to send a message on channel '{}'",
+                channel
+            )
+        })?;
+        let (peer, thread_id) = parse_peer(peer)?;
+
+        let target = MessageTarget {
+            channel: plugin.id().to_string(),
+            account_id,
+            peer,
+            thread_id,
+        };
+        Ok((plugin, target))
+    }
+}
+
+/// Parses a `[kind:]id` peer reference.
+pub(crate) fn parse_peer(peer: &str) -> Result<(PeerInfo, Option<String>)> {
+    let (kind, id) = match peer.split_once(':') {
+        Some(("user", id)) => (PeerKind::User, id),
+        Some(("group", id)) => (PeerKind::Group, id),
+        Some(("channel", id)) => (PeerKind::Channel, id),
+        Some(("thread", id)) => (PeerKind::Thread, id),
+        _ => (PeerKind::User, peer),
+    };
+    if id.is_empty() {
+        return Err(anyhow!("Invalid peer '{}'", peer));
+    }
+
+    let thread_id = matches!(kind, PeerKind::Thread).then(|| id.to_string());
+    let info = PeerInfo {
+        id: id.to_string(),
+        kind,
+        title: None,
+    };
+    Ok((info, thread_id))
+}
+
+#[async_trait]
+impl MessageSender for ChannelMessageSender {
+    async fn send_message(
+        &self,
+        channel: &str,
+        content: &str,
+        account: Option<&str>,
+        peer: Option<&str>,
+    ) -> Result<()> {
+        let (plugin, target) = self.resolve_target(channel, account, peer)?;
+        debug!(
+            "Sending message via channel '{}' account '{}' to '{}'",
+            target.channel, target.account_id, target.peer.id
+        );
+
+        match plugin.outbound() {
+            Some(outbound) => outbound.send_text(&target, content).await,
+            None => {
+                plugin
+                    .send(OutgoingMessage {
+                        target,
+                        content: MessageContent::Text(content.to_string()),
+                        reply_to: None,
+                    })
+                    .await
+            }
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Chat commands for managing a peer's named sessions.
+//!
+//! [`SessionCommandHandler`] lets a peer keep several conversations with an
+//! agent and switch between them from the chat itself:
+//!
+//! - `/new [name]` starts a new conversation and switches to it
+//! - `/resume <name>` switches to an existing conversation
+//! - `/sessions` lists the conversations
+//! - `/rename [name] <new-name>` renames a conversation, the active one by
+//!   default
+//! - `/archive [name]` archives a conversation, the active one by default
+//!
+//! The [`MessageRouter`](crate::MessageRouter) answers these commands
+//! directly instead of routing them to an agent, and routes all other
+//! messages to the peer's active conversation.
+
+use std::sync::Arc;
+
+use aisopod_session::{NamedSession, SessionKey, SessionStatus, SessionStore};
+
+use crate::Result;
+
+/// A parsed session command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SessionCommand {
+    New(Option<String>),
+    Resume(String),
+    List,
+    Rename(Option<String>, String),
+    Archive(Option<String>),
+}
+
+/// Handles the session commands of peers.
+pub struct SessionCommandHandler {
+    store: Arc<SessionStore>,
+}
+
+impl SessionCommandHandler {
+    /// Creates a handler managing sessions in `store`.
+    pub fn new(store: Arc<SessionStore>) -> Self {
+        Self { store }
+    }
+
+    /// Returns the key of the conversation the peer with the `base` key
+    /// is in.
+    pub fn active_session_key(&self, base: &SessionKey) -> Result<SessionKey> {
+        self.store.active_session_key(base)
+    }
+
+    /// Applies a session command sent by the peer with the `base` key.
+    ///
+    /// Returns the reply to send back to the peer if `text` is a session
+    /// command, or `None` if the message should be routed normally. Failed
+    /// commands are answered with the reason they failed.
+    pub fn handle_command(&self, base: &SessionKey, text: &str) -> Option<String> {
+        let command = parse_command(text)?;
+        Some(match self.apply(base, command) {
+            Ok(reply) => reply,
+            Err(e) => e.to_string(),
+        })
+    }
+
+    fn apply(&self, base: &SessionKey, command: SessionCommand) -> Result<String> {
+        match command {
+            SessionCommand::New(name) => {
+                let session = self.store.create_named_session(base, name.as_deref())?;
+                Ok(format!("Started new session '{}'.", session.name))
+            }
+            SessionCommand::Resume(name) => {
+                let session = self.store.switch_named_session(base, &name)?;
+                Ok(format!(
+                    "Resumed session '{}' ({} messages).",
+                    session.name, session.message_count
+                ))
+            }
+            SessionCommand::List => {
+                let sessions = self.store.list_named_sessions(base)?;
+                if sessions.is_empty() {
+                    return Ok("No sessions yet.".to_string());
+                }
+                let lines: Vec<String> = sessions.iter().map(session_line).collect();
+                Ok(format!("Sessions:\n{}", lines.join("\n")))
+            }
+            SessionCommand::Rename(name, new_name) => {
+                let name = match name {
+                    Some(name) => name,
+                    None => self.active_name(base)?,
+                };
+                let session = self.store.rename_named_session(base, &name, &new_name)?;
+                Ok(format!("Renamed session '{}' to '{}'.", name, session.name))
+            }
+            SessionCommand::Archive(name) => {
+                let name = match name {
+                    Some(name) => name,
+                    None => self.active_name(base)?,
+                };
+                let session = self.store.archive_named_session(base, &name)?;
+                Ok(format!(
+                    "Archived session '{}'. Use /resume {} to continue it.",
+                    session.name, session.name
+                ))
+            }
+        }
+    }
+
+    /// Returns the name of the peer's active session.
+    fn active_name(&self, base: &SessionKey) -> Result<String> {
+        self.store
+            .list_named_sessions(base)?
+            .into_iter()
+            .find(|session| session.active)
+            .map(|session| session.name)
+            .ok_or_else(|| anyhow::anyhow!("No active session"))
+    }
+}
+
+/// Formats a session for the `/sessions` listing.
+fn session_line(session: &NamedSession) -> String {
+    let marker = if session.active { "*" } else { "-" };
+    let archived = if session.status == SessionStatus::Archived {
+        ", archived"
+    } else {
+        ""
+    };
+    format!(
+        "{} {} ({} messages{})",
+        marker, session.name, session.message_count, archived
+    )
+}
+
+/// Parses a session command, ignoring a trailing `@botname` on the command
+/// as sent by some channels in groups.
+fn parse_command(text: &str) -> Option<SessionCommand> {
+    let mut words = text.split_whitespace();
+    let command = words.next()?.strip_prefix('/')?;
+    let command = command.split('@').next().unwrap_or(command).to_lowercase();
+    let args: Vec<String> = words.map(String::from).collect();
+
+    match (command.as_str(), args.as_slice()) {
+        ("new", []) => Some(SessionCommand::New(None)),
+        ("new", [name]) => Some(SessionCommand::New(Some(name.clone()))),
+        ("resume", [name]) => Some(SessionCommand::Resume(name.clone())),
+        ("sessions", []) => Some(SessionCommand::List),
+        ("rename", [new_name]) => Some(SessionCommand::Rename(None, new_name.clone())),
+        ("rename", [name, new_name]) => {
+            Some(SessionCommand::Rename(Some(name.clone()), new_name.clone()))
+        }
+        ("archive", []) => Some(SessionCommand::Archive(None)),
+        ("archive", [name]) => Some(SessionCommand::Archive(Some(name.clone()))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> SessionKey {
+        SessionKey {
+            agent_id: "default".to_string(),
+            channel: "telegram".to_string(),
+            account_id: "bot".to_string(),
+            peer_kind: "dm".to_string(),
+            peer_id: "alice".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("/new"), Some(SessionCommand::New(None)));
+        assert_eq!(
+            parse_command("/new@my_bot travel"),
+            Some(SessionCommand::New(Some("travel".into())))
+        );
+        assert_eq!(
+            parse_command("/resume work"),
+            Some(SessionCommand::Resume("work".into()))
+        );
+        assert_eq!(
+            parse_command("/rename old new"),
+            Some(SessionCommand::Rename(Some("old".into()), "new".into()))
+        );
+        assert_eq!(parse_command("/sessions"), Some(SessionCommand::List));
+        assert_eq!(parse_command("/resume"), None);
+        assert_eq!(parse_command("/new a b"), None);
+        assert_eq!(parse_command("new travel"), None);
+        assert_eq!(parse_command("/help"), None);
+    }
+
+    #[test]
+    fn test_handle_commands() {
+        let store = Arc::new(SessionStore::new_in_memory().unwrap());
+        let handler = SessionCommandHandler::new(store);
+        let base = base();
+
+        assert_eq!(handler.handle_command(&base, "Hello"), None);
+        assert_eq!(
+            handler.handle_command(&base, "/new travel").unwrap(),
+            "Started new session 'travel'."
+        );
+        assert_eq!(
+            handler.active_session_key(&base).unwrap().peer_id,
+            "alice#1"
+        );
+
+        assert_eq!(
+            handler.handle_command(&base, "/rename trip").unwrap(),
+            "Renamed session 'travel' to 'trip'."
+        );
+        assert!(handler
+            .handle_command(&base, "/archive")
+            .unwrap()
+            .starts_with("Archived session 'trip'."));
+        assert_eq!(handler.active_session_key(&base).unwrap(), base);
+
+        assert_eq!(
+            handler.handle_command(&base, "/sessions").unwrap(),
+            "Sessions:\n* default (0 messages)\n- trip (0 messages, archived)"
+        );
+        assert_eq!(
+            handler.handle_command(&base, "/resume nowhere").unwrap(),
+            "No session named 'nowhere'"
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! Streaming agent replies back to the chat a message came from.
+//!
+//! [`ChannelReplySink`] delivers the partial output of an agent run to a
+//! channel. On channels whose outbound adapter can edit messages the reply
+//! is sent once and then edited in place; elsewhere it arrives in chunks.
+//! See [`aisopod_agent::streaming`] for the throttling and chunking rules.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use aisopod_agent::{ReplySink, StreamingReplyConfig};
+
+use crate::message::{IncomingMessage, MessageContent, MessageTarget, OutgoingMessage};
+use crate::plugin::ChannelPlugin;
+
+/// Reply sink delivering to one target of a channel.
+pub struct ChannelReplySink {
+    plugin: Arc<dyn ChannelPlugin>,
+    target: MessageTarget,
+}
+
+impl ChannelReplySink {
+    /// Creates a sink delivering to `target` through `plugin`.
+    pub fn new(plugin: Arc<dyn ChannelPlugin>, target: MessageTarget) -> Self {
+        Self { plugin, target }
+    }
+
+    /// Creates a sink replying to the conversation `message` came from.
+    pub fn for_message(plugin: Arc<dyn ChannelPlugin>, message: &IncomingMessage) -> Self {
+        let target = MessageTarget {
+            channel: plugin.id().to_string(),
+            account_id: message.account_id.clone(),
+            peer: message.peer.clone(),
+            thread_id: None,
+        };
+        Self::new(plugin, target)
+    }
+
+    /// Returns the target replies are delivered to.
+    pub fn target(&self) -> &MessageTarget {
+        &self.target
+    }
+
+    /// Returns the default streaming configuration, limited to the
+    /// channel's maximum message length.
+    pub fn reply_config(&self) -> StreamingReplyConfig {
+        StreamingReplyConfig {
+            max_message_length: self.plugin.capabilities().max_message_length,
+            ..Default::default()
+        }
+    }
+}
+
+#[async_trait]
+impl ReplySink for ChannelReplySink {
+    async fn send(&self, text: &str) -> Result<Option<String>> {
+        match self.plugin.outbound() {
+            Some(outbound) => outbound.send_editable_text(&self.target, text).await,
+            None => {
+                self.plugin
+                    .send(OutgoingMessage {
+                        target: self.target.clone(),
+                        content: MessageContent::Text(text.to_string()),
+                        reply_to: None,
+                    })
+                    .await?;
+                Ok(None)
+            }
+        }
+    }
+
+    async fn edit(&self, message_id: &str, text: &str) -> Result<()> {
+        match self.plugin.outbound() {
+            Some(outbound) => outbound.edit_text(&self.target, message_id, text).await,
+            None => Err(anyhow::anyhow!(
+                "Channel '{}' cannot edit messages",
+                self.plugin.id()
+            )),
+        }
+    }
+}
@@ -0,0 +1,314 @@
+//! Tests for the ChannelApprovalHandler.
+
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use aisopod_channel::adapters::{
+    AccountSnapshot, ChannelConfigAdapter, OutboundAdapter, SecurityAdapter,
+};
+use aisopod_channel::message::{
+    IncomingMessage, Media, MessageButton, MessageContent, MessageTarget, PeerInfo, PeerKind,
+    SenderInfo,
+};
+use aisopod_channel::types::ChatType;
+use aisopod_channel::{
+    ChannelApprovalHandler, ChannelCapabilities, ChannelMeta, ChannelPlugin, ChannelRegistry,
+};
+use aisopod_config::types::ApprovalConfig;
+use aisopod_tools::{ApprovalHandler, ApprovalRequest, ApprovalState, RiskLevel};
+use async_trait::async_trait;
+
+// ============================================================================
+// Helper types
+// ============================================================================
+
+struct StaticAccounts(Vec<String>);
+
+impl ChannelConfigAdapter for StaticAccounts {
+    fn list_accounts(&self) -> Result<Vec<String>, anyhow::Error> {
+        Ok(self.0.clone())
+    }
+
+    fn resolve_account(&self, id: &str) -> Result<AccountSnapshot, anyhow::Error> {
+        Ok(AccountSnapshot {
+            id: id.to_string(),
+            channel: "test".to_string(),
+            enabled: true,
+            connected: true,
+        })
+    }
+
+    fn enable_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    fn disable_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    fn delete_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+}
+
+/// A channel recording the texts and buttons it was asked to deliver.
+struct ButtonChannel {
+    meta: ChannelMeta,
+    capabilities: ChannelCapabilities,
+    accounts: StaticAccounts,
+    sent: Mutex<Vec<(String, Vec<MessageButton>)>>,
+}
+
+impl ButtonChannel {
+    fn new() -> Self {
+        Self {
+            meta: ChannelMeta {
+                label: "Slack".to_string(),
+                docs_url: None,
+                ui_hints: serde_json::Value::Object(serde_json::Map::new()),
+            },
+            capabilities: ChannelCapabilities {
+                chat_types: vec![ChatType::Dm],
+                supports_media: false,
+                supports_reactions: false,
+                supports_threads: false,
+                supports_typing: false,
+                supports_voice: false,
+                max_message_length: None,
+                supported_media_types: vec![],
+            },
+            accounts: StaticAccounts(vec!["workspace-1".to_string()]),
+            sent: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn sent(&self) -> Vec<(String, Vec<MessageButton>)> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl OutboundAdapter for ButtonChannel {
+    async fn send_text(&self, _target: &MessageTarget, text: &str) -> Result<(), anyhow::Error> {
+        self.sent.lock().unwrap().push((text.to_string(), vec![]));
+        Ok(())
+    }
+
+    async fn send_media(
+        &self,
+        _target: &MessageTarget,
+        _media: &Media,
+    ) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    async fn send_buttons(
+        &self,
+        _target: &MessageTarget,
+        text: &str,
+        buttons: &[MessageButton],
+    ) -> Result<(), anyhow::Error> {
+        self.sent
+            .lock()
+            .unwrap()
+            .push((text.to_string(), buttons.to_vec()));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChannelPlugin for ButtonChannel {
+    fn id(&self) -> &str {
+        "slack"
+    }
+
+    fn meta(&self) -> &ChannelMeta {
+        &self.meta
+    }
+
+    fn capabilities(&self) -> &ChannelCapabilities {
+        &self.capabilities
+    }
+
+    fn config(&self) -> &dyn ChannelConfigAdapter {
+        &self.accounts
+    }
+
+    fn security(&self) -> Option<&dyn SecurityAdapter> {
+        None
+    }
+
+    fn outbound(&self) -> Option<&dyn OutboundAdapter> {
+        Some(self)
+    }
+}
+
+fn registry_with(channel: Arc<ButtonChannel>) -> Arc<RwLock<ChannelRegistry>> {
+    let mut registry = ChannelRegistry::new();
+    registry.register(channel);
+    Arc::new(RwLock::new(registry))
+}
+
+fn reply(sender: &str, peer: &str, text: &str) -> IncomingMessage {
+    IncomingMessage {
+        id: "msg-1".to_string(),
+        channel: "slack".to_string(),
+        account_id: "workspace-1".to_string(),
+        sender: SenderInfo {
+            id: sender.to_string(),
+            ..Default::default()
+        },
+        peer: PeerInfo {
+            id: peer.to_string(),
+            kind: PeerKind::User,
+            title: None,
+        },
+        content: MessageContent::Text(text.to_string()),
+        reply_to: None,
+        timestamp: chrono::Utc::now(),
+        metadata: serde_json::Value::Null,
+    }
+}
+
+async fn wait_for_pending(handler: &ChannelApprovalHandler, count: usize) {
+    for _ in 0..100 {
+        if handler.pending_count() == count {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("expected {} pending approval requests", count);
+}
+
+fn request(operation: &str) -> ApprovalRequest {
+    ApprovalRequest::new("agent-1", operation, RiskLevel::High).with_timeout(Duration::from_secs(5))
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_operator_approves_with_button_value() {
+    let slack = Arc::new(ButtonChannel::new());
+    let handler = Arc::new(
+        ChannelApprovalHandler::new(registry_with(slack.clone()), "slack", "U-OPS")
+            .with_operators(vec!["alice".to_string()]),
+    );
+
+    let request = request("Execute bash command: rm -r build");
+    let request_id = request.id.clone();
+    let waiting = tokio::spawn({
+        let handler = handler.clone();
+        async move { handler.request_approval(request).await }
+    });
+    wait_for_pending(&handler, 1).await;
+
+    let sent = slack.sent();
+    assert_eq!(sent.len(), 1);
+    let (text, buttons) = &sent[0];
+    assert!(text.contains("Approval required (#1)"));
+    assert!(text.contains("rm -r build"));
+    assert!(text.contains("Risk: High"));
+    assert_eq!(
+        buttons,
+        &vec![
+            MessageButton::new("Approve", "approve 1"),
+            MessageButton::new("Deny", "deny 1"),
+        ]
+    );
+    assert_eq!(
+        handler.tracker().get_state(&request_id).unwrap(),
+        Some(ApprovalState::Pending)
+    );
+
+    // Only configured operators in the operator conversation may decide
+    assert!(!handler.handle_incoming(&reply("mallory", "U-OPS", "approve 1")));
+    assert!(!handler.handle_incoming(&reply("alice", "U-OTHER", "approve 1")));
+    assert!(!handler.handle_incoming(&reply("alice", "U-OPS", "what is this?")));
+    assert!(!handler.handle_incoming(&reply("alice", "U-OPS", "approve 7")));
+    assert!(handler.handle_incoming(&reply("alice", "U-OPS", "approve 1")));
+
+    let response = waiting.await.unwrap().unwrap();
+    assert!(response.is_approved());
+    assert_eq!(handler.pending_count(), 0);
+    assert_eq!(
+        handler.tracker().get_state(&request_id).unwrap(),
+        Some(ApprovalState::Approved)
+    );
+}
+
+#[tokio::test]
+async fn test_operator_denies_with_reason() {
+    let slack = Arc::new(ButtonChannel::new());
+    let handler = Arc::new(ChannelApprovalHandler::new(
+        registry_with(slack.clone()),
+        "slack",
+        "user:U-OPS",
+    ));
+
+    let request = request("Commit in repository '.': WIP");
+    let request_id = request.id.clone();
+    let waiting = tokio::spawn({
+        let handler = handler.clone();
+        async move { handler.request_approval(request).await }
+    });
+    wait_for_pending(&handler, 1).await;
+
+    // With a single pending request the code may be omitted
+    assert!(handler.handle_incoming(&reply("bob", "U-OPS", "no please squash first")));
+
+    let response = waiting.await.unwrap().unwrap();
+    assert_eq!(response.denial_reason(), Some("please squash first"));
+    assert_eq!(
+        handler.tracker().get_state(&request_id).unwrap(),
+        Some(ApprovalState::Denied {
+            reason: "please squash first".to_string()
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_unanswered_request_times_out() {
+    let slack = Arc::new(ButtonChannel::new());
+    let config = ApprovalConfig {
+        channel: Some("slack".to_string()),
+        peer: Some("U-OPS".to_string()),
+        ..Default::default()
+    };
+    let handler = ChannelApprovalHandler::from_config(registry_with(slack.clone()), &config)
+        .unwrap()
+        .with_timeout(Duration::from_millis(50));
+
+    let request = request("Execute bash command: make deploy");
+    let request_id = request.id.clone();
+    let response = handler.request_approval(request).await.unwrap();
+    assert!(response.is_timed_out());
+    assert_eq!(handler.pending_count(), 0);
+    assert_eq!(
+        handler.tracker().get_state(&request_id).unwrap(),
+        Some(ApprovalState::TimedOut)
+    );
+
+    let sent = slack.sent();
+    assert_eq!(sent.len(), 2);
+    assert!(sent[1].0.contains("#1 timed out"));
+
+    // Late replies are routed normally
+    assert!(!handler.handle_incoming(&reply("alice", "U-OPS", "approve 1")));
+}
+
+#[tokio::test]
+async fn test_unreachable_operator_is_an_error() {
+    let slack = Arc::new(ButtonChannel::new());
+    let handler = ChannelApprovalHandler::new(registry_with(slack), "discord", "U-OPS");
+
+    assert!(handler.request_approval(request("anything")).await.is_err());
+    assert_eq!(handler.pending_count(), 0);
+
+    assert!(ChannelApprovalHandler::from_config(
+        Arc::new(RwLock::new(ChannelRegistry::new())),
+        &ApprovalConfig::default()
+    )
+    .is_none());
+}
@@ -0,0 +1,201 @@
+//! Tests for the ChannelBudgetNotifier.
+
+use std::sync::{Arc, Mutex, RwLock};
+
+use aisopod_agent::{BudgetExceeded, BudgetLimit, BudgetNotifier, BudgetScope, UsageReport};
+use aisopod_channel::adapters::{AccountSnapshot, ChannelConfigAdapter, SecurityAdapter};
+use aisopod_channel::message::{MessageContent, OutgoingMessage};
+use aisopod_channel::types::ChatType;
+use aisopod_channel::{
+    ChannelBudgetNotifier, ChannelCapabilities, ChannelMeta, ChannelPlugin, ChannelRegistry,
+};
+use aisopod_config::types::BudgetNotifyConfig;
+use async_trait::async_trait;
+
+// ============================================================================
+// Helper types
+// ============================================================================
+
+struct StaticAccounts(Vec<String>);
+
+impl ChannelConfigAdapter for StaticAccounts {
+    fn list_accounts(&self) -> Result<Vec<String>, anyhow::Error> {
+        Ok(self.0.clone())
+    }
+
+    fn resolve_account(&self, id: &str) -> Result<AccountSnapshot, anyhow::Error> {
+        Ok(AccountSnapshot {
+            id: id.to_string(),
+            channel: "test".to_string(),
+            enabled: true,
+            connected: true,
+        })
+    }
+
+    fn enable_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    fn disable_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    fn delete_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+}
+
+/// A channel recording the messages it was asked to send.
+struct RecordingChannel {
+    meta: ChannelMeta,
+    capabilities: ChannelCapabilities,
+    accounts: StaticAccounts,
+    sent: Mutex<Vec<OutgoingMessage>>,
+}
+
+impl RecordingChannel {
+    fn new() -> Self {
+        Self {
+            meta: ChannelMeta {
+                label: "Ops".to_string(),
+                docs_url: None,
+                ui_hints: serde_json::Value::Object(serde_json::Map::new()),
+            },
+            capabilities: ChannelCapabilities {
+                chat_types: vec![ChatType::Dm],
+                supports_media: false,
+                supports_reactions: false,
+                supports_threads: false,
+                supports_typing: false,
+                supports_voice: false,
+                max_message_length: None,
+                supported_media_types: vec![],
+            },
+            accounts: StaticAccounts(vec!["ops-bot".to_string()]),
+            sent: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn texts(&self) -> Vec<String> {
+        self.sent
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|msg| match &msg.content {
+                MessageContent::Text(text) => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl ChannelPlugin for RecordingChannel {
+    fn id(&self) -> &str {
+        "ops"
+    }
+
+    fn meta(&self) -> &ChannelMeta {
+        &self.meta
+    }
+
+    fn capabilities(&self) -> &ChannelCapabilities {
+        &self.capabilities
+    }
+
+    fn config(&self) -> &dyn ChannelConfigAdapter {
+        &self.accounts
+    }
+
+    fn security(&self) -> Option<&dyn SecurityAdapter> {
+        None
+    }
+
+    async fn send(&self, msg: OutgoingMessage) -> aisopod_channel::Result<()> {
+        self.sent.lock().unwrap().push(msg);
+        Ok(())
+    }
+}
+
+fn notifier_with(channel: Arc<RecordingChannel>) -> ChannelBudgetNotifier {
+    let mut registry = ChannelRegistry::new();
+    registry.register(channel);
+    let config = BudgetNotifyConfig {
+        channel: Some("ops".to_string()),
+        account: None,
+        peer: Some("user:admin".to_string()),
+    };
+    ChannelBudgetNotifier::from_config(Arc::new(RwLock::new(registry)), &config).unwrap()
+}
+
+fn exceeded(scope: BudgetScope, session_key: &str) -> BudgetExceeded {
+    BudgetExceeded {
+        scope,
+        limit: BudgetLimit::Tokens(1000),
+        session_key: session_key.to_string(),
+        peer: "telegram:bot:dm:alice".to_string(),
+        agent_id: "support".to_string(),
+        usage: UsageReport::new(800, 200),
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[test]
+fn test_from_config_requires_channel_and_peer() {
+    let registry = Arc::new(RwLock::new(ChannelRegistry::new()));
+    let config = BudgetNotifyConfig {
+        channel: Some("ops".to_string()),
+        ..Default::default()
+    };
+    assert!(ChannelBudgetNotifier::from_config(registry, &config).is_none());
+}
+
+#[tokio::test]
+async fn test_reports_exceeded_budget_to_operator() {
+    let channel = Arc::new(RecordingChannel::new());
+    let notifier = notifier_with(channel.clone());
+
+    notifier
+        .budget_exceeded(&exceeded(
+            BudgetScope::Session,
+            "support:telegram:bot:dm:alice",
+        ))
+        .await
+        .unwrap();
+
+    let sent = channel.sent.lock().unwrap();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].target.account_id, "ops-bot");
+    assert_eq!(sent[0].target.peer.id, "admin");
+    drop(sent);
+    assert!(channel.texts()[0].contains("session budget of 1000 tokens"));
+}
+
+#[tokio::test]
+async fn test_reports_each_subject_once_a_day() {
+    let channel = Arc::new(RecordingChannel::new());
+    let notifier = notifier_with(channel.clone());
+    let session = exceeded(BudgetScope::Session, "support:telegram:bot:dm:alice");
+
+    notifier.budget_exceeded(&session).await.unwrap();
+    notifier.budget_exceeded(&session).await.unwrap();
+    notifier
+        .budget_exceeded(&exceeded(
+            BudgetScope::DailyPeer,
+            "support:telegram:bot:dm:alice",
+        ))
+        .await
+        .unwrap();
+    notifier
+        .budget_exceeded(&exceeded(
+            BudgetScope::Session,
+            "billing:telegram:bot:dm:alice",
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(channel.texts().len(), 3);
+}
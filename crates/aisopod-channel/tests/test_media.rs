@@ -4,6 +4,8 @@ use std::io::Cursor;
 
 use aisopod_channel::media::{detect_media_type, detect_mime_type, resize_image, validate_media};
 use aisopod_channel::types::MediaType;
+use aisopod_channel::{Media, MessageContent, MessagePart};
+use aisopod_provider::ContentPart;
 use image::{DynamicImage, GenericImageView, ImageBuffer, ImageFormat, Rgba};
 
 // ============================================================================
@@ -441,3 +443,73 @@ fn test_detect_media_type_case_insensitive_extension() {
     let media_type_mixed = detect_media_type(&data, Some("file.PnG"));
     assert_eq!(media_type_mixed, MediaType::Image);
 }
+
+// ============================================================================
+// Model Request Conversion Tests
+// ============================================================================
+
+fn media(media_type: MediaType, url: Option<&str>, data: Option<Vec<u8>>) -> Media {
+    Media {
+        media_type,
+        url: url.map(str::to_string),
+        data,
+        filename: None,
+        mime_type: None,
+        size_bytes: None,
+    }
+}
+
+#[test]
+fn test_image_data_to_content_part() {
+    let data = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A];
+    let part = media(
+        MediaType::Image,
+        Some("https://example.com/a.png"),
+        Some(data),
+    )
+    .to_content_part();
+    assert_eq!(
+        part,
+        ContentPart::Image {
+            media_type: "image/png".to_string(),
+            data: "iVBORw0K".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_image_url_to_content_part() {
+    let part = media(MediaType::Image, Some("https://example.com/a.jpg"), None).to_content_part();
+    assert_eq!(
+        part,
+        ContentPart::ImageUrl {
+            url: "https://example.com/a.jpg".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_mixed_content_to_provider_content() {
+    let content = MessageContent::Mixed(vec![
+        MessagePart::Text("What is this?".to_string()),
+        MessagePart::Media(media(
+            MediaType::Audio,
+            Some("https://example.com/a.ogg"),
+            None,
+        )),
+    ]);
+    match content.to_provider_content() {
+        aisopod_provider::MessageContent::Parts(parts) => assert_eq!(
+            parts,
+            vec![
+                ContentPart::Text {
+                    text: "What is this?".to_string(),
+                },
+                ContentPart::Text {
+                    text: "[Audio: https://example.com/a.ogg]".to_string(),
+                },
+            ]
+        ),
+        other => panic!("expected parts, got {:?}", other),
+    }
+}
@@ -0,0 +1,215 @@
+//! Tests for the hot reload of channels and agent bindings.
+
+use std::sync::{Arc, Mutex, RwLock};
+
+use aisopod_channel::adapters::{ChannelConfigAdapter, SecurityAdapter};
+use aisopod_channel::types::ChatType;
+use aisopod_channel::{
+    AgentResolver, ChannelApplier, ChannelCapabilities, ChannelFactory, ChannelMeta, ChannelPlugin,
+    ChannelRegistry, ConfigAgentResolver,
+};
+use aisopod_config::types::{Agent, AgentBinding, Channel, ChannelConnection};
+use aisopod_config::{AisopodConfig, ConfigApplier, ConfigReloader};
+use aisopod_session::SessionKey;
+use anyhow::bail;
+use async_trait::async_trait;
+
+// ============================================================================
+// Helper types
+// ============================================================================
+
+struct NamedChannel {
+    id: String,
+    meta: ChannelMeta,
+    capabilities: ChannelCapabilities,
+}
+
+#[async_trait]
+impl ChannelPlugin for NamedChannel {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn meta(&self) -> &ChannelMeta {
+        &self.meta
+    }
+
+    fn capabilities(&self) -> &ChannelCapabilities {
+        &self.capabilities
+    }
+
+    fn config(&self) -> &dyn ChannelConfigAdapter {
+        unimplemented!("NamedChannel does not implement config")
+    }
+
+    fn security(&self) -> Option<&dyn SecurityAdapter> {
+        None
+    }
+}
+
+/// A factory recording the channels it started and stopped.
+#[derive(Default)]
+struct RecordingFactory {
+    events: Mutex<Vec<String>>,
+}
+
+impl RecordingFactory {
+    fn take_events(&self) -> Vec<String> {
+        std::mem::take(&mut *self.events.lock().unwrap())
+    }
+}
+
+#[async_trait]
+impl ChannelFactory for RecordingFactory {
+    async fn start(&self, channel: &Channel) -> anyhow::Result<Arc<dyn ChannelPlugin>> {
+        if channel.connection.token == "invalid" {
+            bail!("invalid token");
+        }
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("start {}", channel.id));
+        Ok(Arc::new(NamedChannel {
+            id: channel.id.clone(),
+            meta: ChannelMeta {
+                label: channel.name.clone(),
+                docs_url: None,
+                ui_hints: serde_json::Value::Null,
+            },
+            capabilities: ChannelCapabilities {
+                chat_types: vec![ChatType::Dm],
+                supports_media: false,
+                supports_reactions: false,
+                supports_threads: false,
+                supports_typing: false,
+                supports_voice: false,
+                max_message_length: None,
+                supported_media_types: vec![],
+            },
+        }))
+    }
+
+    async fn stop(&self, plugin: Arc<dyn ChannelPlugin>) -> anyhow::Result<()> {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("stop {}", plugin.id()));
+        Ok(())
+    }
+}
+
+fn channel(id: &str, token: &str) -> Channel {
+    Channel {
+        id: id.to_string(),
+        name: id.to_string(),
+        channel_type: "test".to_string(),
+        enabled: true,
+        connection: ChannelConnection {
+            endpoint: "polling".to_string(),
+            token: token.to_string(),
+        },
+    }
+}
+
+fn config_with(channels: Vec<Channel>) -> AisopodConfig {
+    let mut config = AisopodConfig::default();
+    config.channels.channels = channels;
+    config
+}
+
+fn agent(id: &str) -> Agent {
+    Agent {
+        id: id.to_string(),
+        name: id.to_string(),
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_channel_applier_starts_and_stops_changed_channels() {
+    let registry = Arc::new(RwLock::new(ChannelRegistry::new()));
+    let factory = Arc::new(RecordingFactory::default());
+    let applier = ChannelApplier::new(registry.clone(), factory.clone());
+
+    let initial = config_with(vec![
+        channel("tg", "a"),
+        channel("dc", "b"),
+        channel("sl", "c"),
+    ]);
+    applier.start_all(&initial).await.unwrap();
+    let mut started = factory.take_events();
+    started.sort();
+    assert_eq!(started, ["start dc", "start sl", "start tg"]);
+
+    // tg unchanged, dc removed, sl disabled, new mm added, and tg's token kept
+    let mut disabled = channel("sl", "c");
+    disabled.enabled = false;
+    let mut changed = config_with(vec![channel("tg", "a"), disabled, channel("mm", "d")]);
+    applier.apply(&initial, &changed).await.unwrap();
+    let mut events = factory.take_events();
+    events.sort();
+    assert_eq!(events, ["start mm", "stop dc", "stop sl"]);
+    assert_eq!(applier.running(), ["mm", "tg"]);
+    assert!(registry.read().unwrap().get("dc").is_none());
+    assert!(registry.read().unwrap().get("mm").is_some());
+
+    // A changed token restarts the channel
+    let previous = changed.clone();
+    changed.channels.channels[0].connection.token = "rotated".to_string();
+    applier.apply(&previous, &changed).await.unwrap();
+    assert_eq!(factory.take_events(), ["stop tg", "start tg"]);
+}
+
+#[tokio::test]
+async fn test_channel_applier_reports_failures_and_retries() {
+    let registry = Arc::new(RwLock::new(ChannelRegistry::new()));
+    let factory = Arc::new(RecordingFactory::default());
+    let applier = ChannelApplier::new(registry, factory.clone());
+
+    let initial = config_with(vec![channel("tg", "a"), channel("dc", "invalid")]);
+    let err = applier.start_all(&initial).await.unwrap_err();
+    assert!(format!("{:#}", err).contains("Failed to start channel 'dc'"));
+    assert_eq!(applier.running(), ["tg"]);
+
+    // The failed channel starts once its entry is fixed
+    let mut changed = initial.clone();
+    changed.channels.channels.push(channel("sl", "c"));
+    changed.channels.channels[1].connection.token = "fixed".to_string();
+    applier.apply(&initial, &changed).await.unwrap();
+    assert_eq!(applier.running(), ["dc", "sl", "tg"]);
+}
+
+#[tokio::test]
+async fn test_agent_resolver_rebinds_on_reload() {
+    let mut old = AisopodConfig::default();
+    old.agents.agents = vec![agent("support"), agent("sales")];
+    let old = Arc::new(old);
+
+    let resolver = Arc::new(ConfigAgentResolver::new(old.clone()));
+    let session_key = SessionKey {
+        agent_id: "support".to_string(),
+        channel: "telegram".to_string(),
+        account_id: "bot".to_string(),
+        peer_kind: "dm".to_string(),
+        peer_id: "user1".to_string(),
+    };
+    assert_eq!(resolver.resolve(&session_key).unwrap(), "support");
+
+    let mut new = (*old).clone();
+    new.bindings.push(AgentBinding {
+        agent_id: "sales".to_string(),
+        channels: vec!["telegram".to_string()],
+        priority: 10,
+        sandbox: None,
+    });
+    let report = ConfigReloader::new()
+        .with_applier(resolver.clone())
+        .apply(&old, &new)
+        .await;
+    assert_eq!(report.applied, [resolver.name()]);
+    assert_eq!(resolver.resolve(&session_key).unwrap(), "sales");
+}
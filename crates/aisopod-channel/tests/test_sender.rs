@@ -0,0 +1,276 @@
+//! Tests for the ChannelMessageSender.
+
+use std::sync::{Arc, Mutex, RwLock};
+
+use aisopod_channel::adapters::{
+    AccountSnapshot, ChannelConfigAdapter, OutboundAdapter, SecurityAdapter,
+};
+use aisopod_channel::message::{Media, MessageContent, MessageTarget, OutgoingMessage, PeerKind};
+use aisopod_channel::types::ChatType;
+use aisopod_channel::{
+    ChannelCapabilities, ChannelMessageSender, ChannelMeta, ChannelPlugin, ChannelRegistry,
+};
+use aisopod_tools::{MessageSender, MessageTool, Tool, ToolContext};
+use async_trait::async_trait;
+use serde_json::json;
+
+// ============================================================================
+// Helper types
+// ============================================================================
+
+struct StaticAccounts(Vec<String>);
+
+impl ChannelConfigAdapter for StaticAccounts {
+    fn list_accounts(&self) -> Result<Vec<String>, anyhow::Error> {
+        Ok(self.0.clone())
+    }
+
+    fn resolve_account(&self, id: &str) -> Result<AccountSnapshot, anyhow::Error> {
+        Ok(AccountSnapshot {
+            id: id.to_string(),
+            channel: "test".to_string(),
+            enabled: true,
+            connected: true,
+        })
+    }
+
+    fn enable_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    fn disable_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    fn delete_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+}
+
+/// A channel recording what it was asked to deliver.
+struct RecordingChannel {
+    id: String,
+    meta: ChannelMeta,
+    capabilities: ChannelCapabilities,
+    accounts: StaticAccounts,
+    with_outbound: bool,
+    outbound_sent: Mutex<Vec<(MessageTarget, String)>>,
+    plugin_sent: Mutex<Vec<OutgoingMessage>>,
+}
+
+impl RecordingChannel {
+    fn new(id: &str, accounts: &[&str], with_outbound: bool) -> Self {
+        Self {
+            id: id.to_string(),
+            meta: ChannelMeta {
+                label: id.to_string(),
+                docs_url: None,
+                ui_hints: serde_json::Value::Object(serde_json::Map::new()),
+            },
+            capabilities: ChannelCapabilities {
+                chat_types: vec![ChatType::Dm, ChatType::Group],
+                supports_media: false,
+                supports_reactions: false,
+                supports_threads: true,
+                supports_typing: false,
+                supports_voice: false,
+                max_message_length: None,
+                supported_media_types: vec![],
+            },
+            accounts: StaticAccounts(accounts.iter().map(|a| a.to_string()).collect()),
+            with_outbound,
+            outbound_sent: Mutex::new(Vec::new()),
+            plugin_sent: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl OutboundAdapter for RecordingChannel {
+    async fn send_text(&self, target: &MessageTarget, text: &str) -> Result<(), anyhow::Error> {
+        self.outbound_sent
+            .lock()
+            .unwrap()
+            .push((target.clone(), text.to_string()));
+        Ok(())
+    }
+
+    async fn send_media(
+        &self,
+        _target: &MessageTarget,
+        _media: &Media,
+    ) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChannelPlugin for RecordingChannel {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn meta(&self) -> &ChannelMeta {
+        &self.meta
+    }
+
+    fn capabilities(&self) -> &ChannelCapabilities {
+        &self.capabilities
+    }
+
+    fn config(&self) -> &dyn ChannelConfigAdapter {
+        &self.accounts
+    }
+
+    fn security(&self) -> Option<&dyn SecurityAdapter> {
+        None
+    }
+
+    fn outbound(&self) -> Option<&dyn OutboundAdapter> {
+        if self.with_outbound {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    async fn send(&self, msg: OutgoingMessage) -> aisopod_channel::Result<()> {
+        self.plugin_sent.lock().unwrap().push(msg);
+        Ok(())
+    }
+}
+
+fn registry_with(channels: Vec<Arc<RecordingChannel>>) -> Arc<RwLock<ChannelRegistry>> {
+    let mut registry = ChannelRegistry::new();
+    for channel in channels {
+        registry.register(channel);
+    }
+    Arc::new(RwLock::new(registry))
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_sends_through_outbound_adapter() {
+    let slack = Arc::new(RecordingChannel::new("slack", &["workspace-1"], true));
+    let sender = ChannelMessageSender::new(registry_with(vec![slack.clone()]));
+
+    sender
+        .send_message(
+            "slack",
+            "Build finished",
+            Some("workspace-1"),
+            Some("channel:C123"),
+        )
+        .await
+        .unwrap();
+
+    let sent = slack.outbound_sent.lock().unwrap();
+    assert_eq!(sent.len(), 1);
+    let (target, text) = &sent[0];
+    assert_eq!(text, "Build finished");
+    assert_eq!(target.channel, "slack");
+    assert_eq!(target.account_id, "workspace-1");
+    assert_eq!(target.peer.id, "C123");
+    assert!(matches!(target.peer.kind, PeerKind::Channel));
+    assert!(target.thread_id.is_none());
+    assert!(slack.plugin_sent.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_falls_back_to_plugin_send_and_default_account() {
+    let irc = Arc::new(RecordingChannel::new("irc", &["libera"], false));
+    let sender = ChannelMessageSender::new(registry_with(vec![irc.clone()]));
+
+    sender
+        .send_message("irc", "hello", None, Some("alice"))
+        .await
+        .unwrap();
+
+    let sent = irc.plugin_sent.lock().unwrap();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].target.account_id, "libera");
+    assert_eq!(sent[0].target.peer.id, "alice");
+    assert!(matches!(sent[0].target.peer.kind, PeerKind::User));
+    assert!(matches!(&sent[0].content, MessageContent::Text(t) if t == "hello"));
+}
+
+#[tokio::test]
+async fn test_resolves_aliases_and_threads() {
+    let channel = Arc::new(RecordingChannel::new("telegram", &["bot"], true));
+    let registry = registry_with(vec![channel]);
+    registry.write().unwrap().add_alias("tg", "telegram");
+    let sender = ChannelMessageSender::new(registry);
+
+    let (plugin, target) = sender
+        .resolve_target("tg", None, Some("thread:42"))
+        .unwrap();
+    assert_eq!(plugin.id(), "telegram");
+    assert_eq!(target.channel, "telegram");
+    assert!(matches!(target.peer.kind, PeerKind::Thread));
+    assert_eq!(target.thread_id.as_deref(), Some("42"));
+}
+
+#[tokio::test]
+async fn test_resolution_errors() {
+    let empty = Arc::new(RecordingChannel::new("matrix", &[], true));
+    let sender = ChannelMessageSender::new(registry_with(vec![empty]));
+
+    let err = sender
+        .resolve_target("discord", None, Some("u1"))
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("Unknown channel"));
+
+    let err = sender
+        .resolve_target("matrix", None, Some("u1"))
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("no configured accounts"));
+
+    let err = sender
+        .resolve_target("matrix", Some("acct"), None)
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("peer is required"));
+
+    let err = sender
+        .resolve_target("matrix", Some("acct"), Some("group:"))
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("Invalid peer"));
+}
+
+#[tokio::test]
+async fn test_message_tool_with_channel_sender() {
+    let slack = Arc::new(RecordingChannel::new("slack", &["workspace-1"], true));
+    let sender = Arc::new(ChannelMessageSender::new(registry_with(
+        vec![slack.clone()],
+    )));
+    let tool = MessageTool::new(sender);
+    let ctx = ToolContext::new("agent-1", "session-1");
+
+    let result = tool
+        .execute(
+            json!({"channel": "slack", "content": "Reminder: standup", "peer": "group:G1"}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+    assert!(!result.is_error);
+    assert_eq!(slack.outbound_sent.lock().unwrap().len(), 1);
+
+    let result = tool
+        .execute(
+            json!({"channel": "nowhere", "content": "hi", "peer": "u"}),
+            &ctx,
+        )
+        .await;
+    let failed = match result {
+        Ok(r) => r.is_error,
+        Err(_) => true,
+    };
+    assert!(failed);
+}
@@ -0,0 +1,257 @@
+//! Tests for streaming agent replies through ChannelReplySink.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use aisopod_agent::types::AgentEvent;
+use aisopod_agent::{AgentRunStream, StreamingReplyConfig};
+use aisopod_channel::adapters::{
+    AccountSnapshot, ChannelConfigAdapter, OutboundAdapter, SecurityAdapter,
+};
+use aisopod_channel::message::{
+    Media, MessageContent, MessageTarget, OutgoingMessage, PeerInfo, PeerKind,
+};
+use aisopod_channel::types::ChatType;
+use aisopod_channel::{ChannelCapabilities, ChannelMeta, ChannelPlugin, ChannelReplySink};
+use async_trait::async_trait;
+
+// ============================================================================
+// Helper types
+// ============================================================================
+
+struct NoAccounts;
+
+impl ChannelConfigAdapter for NoAccounts {
+    fn list_accounts(&self) -> Result<Vec<String>, anyhow::Error> {
+        Ok(vec!["bot".to_string()])
+    }
+
+    fn resolve_account(&self, id: &str) -> Result<AccountSnapshot, anyhow::Error> {
+        Ok(AccountSnapshot {
+            id: id.to_string(),
+            channel: "test".to_string(),
+            enabled: true,
+            connected: true,
+        })
+    }
+
+    fn enable_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    fn disable_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    fn delete_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+}
+
+/// A channel holding the messages it shows, optionally able to edit them.
+struct ChatChannel {
+    meta: ChannelMeta,
+    capabilities: ChannelCapabilities,
+    accounts: NoAccounts,
+    with_outbound: bool,
+    editable: bool,
+    messages: Mutex<Vec<String>>,
+    edits: Mutex<usize>,
+}
+
+impl ChatChannel {
+    fn new(with_outbound: bool, editable: bool, max_message_length: Option<usize>) -> Self {
+        Self {
+            meta: ChannelMeta {
+                label: "chat".to_string(),
+                docs_url: None,
+                ui_hints: serde_json::Value::Object(serde_json::Map::new()),
+            },
+            capabilities: ChannelCapabilities {
+                chat_types: vec![ChatType::Dm],
+                supports_media: false,
+                supports_reactions: false,
+                supports_threads: false,
+                supports_typing: false,
+                supports_voice: false,
+                max_message_length,
+                supported_media_types: vec![],
+            },
+            accounts: NoAccounts,
+            with_outbound,
+            editable,
+            messages: Mutex::new(Vec::new()),
+            edits: Mutex::new(0),
+        }
+    }
+
+    fn messages(&self) -> Vec<String> {
+        self.messages.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl OutboundAdapter for ChatChannel {
+    async fn send_text(&self, _target: &MessageTarget, text: &str) -> Result<(), anyhow::Error> {
+        self.messages.lock().unwrap().push(text.to_string());
+        Ok(())
+    }
+
+    async fn send_media(
+        &self,
+        _target: &MessageTarget,
+        _media: &Media,
+    ) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    async fn send_editable_text(
+        &self,
+        target: &MessageTarget,
+        text: &str,
+    ) -> Result<Option<String>, anyhow::Error> {
+        self.send_text(target, text).await?;
+        let id = self.messages.lock().unwrap().len() - 1;
+        Ok(self.editable.then(|| id.to_string()))
+    }
+
+    async fn edit_text(
+        &self,
+        _target: &MessageTarget,
+        message_id: &str,
+        text: &str,
+    ) -> Result<(), anyhow::Error> {
+        let index: usize = message_id.parse()?;
+        self.messages.lock().unwrap()[index] = text.to_string();
+        *self.edits.lock().unwrap() += 1;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChannelPlugin for ChatChannel {
+    fn id(&self) -> &str {
+        "chat"
+    }
+
+    fn meta(&self) -> &ChannelMeta {
+        &self.meta
+    }
+
+    fn capabilities(&self) -> &ChannelCapabilities {
+        &self.capabilities
+    }
+
+    fn config(&self) -> &dyn ChannelConfigAdapter {
+        &self.accounts
+    }
+
+    fn security(&self) -> Option<&dyn SecurityAdapter> {
+        None
+    }
+
+    fn outbound(&self) -> Option<&dyn OutboundAdapter> {
+        if self.with_outbound {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    async fn send(&self, msg: OutgoingMessage) -> aisopod_channel::Result<()> {
+        if let MessageContent::Text(text) = msg.content {
+            self.messages.lock().unwrap().push(text);
+        }
+        Ok(())
+    }
+}
+
+fn sink_for(channel: &Arc<ChatChannel>) -> ChannelReplySink {
+    let target = MessageTarget {
+        channel: "chat".to_string(),
+        account_id: "bot".to_string(),
+        peer: PeerInfo {
+            id: "alice".to_string(),
+            kind: PeerKind::User,
+            title: None,
+        },
+        thread_id: None,
+    };
+    ChannelReplySink::new(channel.clone(), target)
+}
+
+/// Streams text deltas through the sink as an agent run would.
+async fn stream_reply(deltas: &[&str], sink: &ChannelReplySink, config: StreamingReplyConfig) {
+    let (tx, rx) = tokio::sync::mpsc::channel(deltas.len());
+    for delta in deltas {
+        tx.send(AgentEvent::TextDelta {
+            text: delta.to_string(),
+            index: None,
+        })
+        .await
+        .unwrap();
+    }
+    drop(tx);
+    AgentRunStream::new(rx)
+        .stream_to(sink, &config)
+        .await
+        .unwrap();
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_reply_is_edited_in_place() {
+    let channel = Arc::new(ChatChannel::new(true, true, None));
+    let sink = sink_for(&channel);
+    let config = StreamingReplyConfig {
+        edit_interval: Duration::ZERO,
+        ..sink.reply_config()
+    };
+
+    stream_reply(&["The build ", "passed ", "on all targets."], &sink, config).await;
+
+    assert_eq!(channel.messages(), vec!["The build passed on all targets."]);
+    assert!(*channel.edits.lock().unwrap() >= 1);
+}
+
+#[tokio::test]
+async fn test_reply_respects_channel_message_length() {
+    let channel = Arc::new(ChatChannel::new(true, true, Some(16)));
+    let sink = sink_for(&channel);
+    assert_eq!(sink.reply_config().max_message_length, Some(16));
+
+    stream_reply(
+        &["The build passed ", "on all targets."],
+        &sink,
+        sink.reply_config(),
+    )
+    .await;
+
+    assert_eq!(
+        channel.messages(),
+        vec!["The build passed", "on all targets."]
+    );
+}
+
+#[tokio::test]
+async fn test_reply_is_chunked_without_edit_support() {
+    for with_outbound in [true, false] {
+        let channel = Arc::new(ChatChannel::new(with_outbound, false, None));
+        let sink = sink_for(&channel);
+        let config = StreamingReplyConfig {
+            chunk_size: 16,
+            ..sink.reply_config()
+        };
+
+        stream_reply(&["The build ", "passed ", "on all targets."], &sink, config).await;
+
+        assert_eq!(
+            channel.messages(),
+            vec!["The build", "passed on all", "targets."]
+        );
+        assert_eq!(*channel.edits.lock().unwrap(), 0);
+    }
+}
@@ -0,0 +1,488 @@
+//! Configuration diagnostics module
+//!
+//! Deserialization ignores keys the configuration does not know, so a
+//! misspelled key silently falls back to its default. This module checks a
+//! configuration file more thoroughly than loading it does, for
+//! `aisopod config validate`:
+//!
+//! - unknown keys, found by walking the file against the
+//!   [configuration schema](crate::config_schema)
+//! - the semantic rules of [`AisopodConfig::validate`], including references
+//!   between sections such as bindings of unknown agents
+//! - agent models that name neither a configured model nor an alias
+//!
+//! Each [`Diagnostic`] carries the line of the file it refers to, when it
+//! can be found, and a suggested fix where one is known.
+//!
+//! Unknown keys are only looked for in the file itself, not in the files it
+//! includes with `@include`.
+
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::{Map, Value};
+
+use crate::types::AisopodConfig;
+use crate::validation::{closest_match, ValidationError};
+
+/// How serious a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The configuration does not load
+    Error,
+    /// The configuration loads but likely does not do what was intended
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem found in a configuration file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// How serious the problem is
+    pub severity: Severity,
+    /// The path to the offending field (e.g., "bindings[0].agent_id")
+    pub path: String,
+    /// A human-readable description of the problem
+    pub message: String,
+    /// A suggested fix
+    pub suggestion: Option<String>,
+    /// The 1-based line of the file the problem was found at
+    pub line: Option<usize>,
+}
+
+impl Diagnostic {
+    fn new(severity: Severity, error: ValidationError) -> Self {
+        Self {
+            severity,
+            path: error.path,
+            message: error.message,
+            suggestion: error.suggestion,
+            line: None,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.severity, self.path, self.message)
+    }
+}
+
+/// Check a configuration file for unknown keys, validation errors and
+/// unresolved references.
+///
+/// Returns the diagnostics ordered by line, or an empty list if the file
+/// has no problems.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, parsed or deserialized, as
+/// nothing more can be checked then.
+pub fn diagnose_config_file(path: &Path) -> Result<Vec<Diagnostic>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let raw: Value = match ext {
+        "json" | "json5" => json5::from_str(&contents)
+            .with_context(|| format!("Failed to parse JSON5 config: {}", path.display()))?,
+        "toml" => toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse TOML config: {}", path.display()))?,
+        _ => {
+            return Err(anyhow!(
+                "Unsupported config file extension: '{}'. Use .json5, .json, or .toml",
+                ext
+            ))
+        }
+    };
+
+    let value = crate::loader::read_config_value(path)?;
+    let config: AisopodConfig = serde_json::from_value(value)
+        .with_context(|| format!("Failed to deserialize config: {}", path.display()))?;
+
+    let mut diagnostics: Vec<Diagnostic> = unknown_keys(&raw)
+        .into_iter()
+        .map(|error| Diagnostic::new(Severity::Warning, error))
+        .collect();
+    if let Err(errors) = config.validate() {
+        diagnostics.extend(
+            errors
+                .into_iter()
+                .map(|error| Diagnostic::new(Severity::Error, error)),
+        );
+    }
+    diagnostics.extend(
+        unresolved_models(&config)
+            .into_iter()
+            .map(|error| Diagnostic::new(Severity::Warning, error)),
+    );
+
+    for diagnostic in &mut diagnostics {
+        diagnostic.line = locate(&contents, &diagnostic.path);
+    }
+    diagnostics.sort_by_key(|d| d.line.unwrap_or(usize::MAX));
+    Ok(diagnostics)
+}
+
+/// Find the keys of a configuration value that the configuration schema
+/// does not know.
+pub fn unknown_keys(value: &Value) -> Vec<ValidationError> {
+    let schema = serde_json::to_value(crate::config_schema()).unwrap_or_default();
+    let mut errors = Vec::new();
+    check_keys(&schema, &schema, value, "", &mut errors);
+    errors
+}
+
+fn check_keys(
+    root: &Value,
+    schema: &Value,
+    value: &Value,
+    path: &str,
+    errors: &mut Vec<ValidationError>,
+) {
+    let schema = resolve_ref(root, schema);
+
+    // Options and enums: check against the variant fitting the value best
+    if let Some(variants) = schema
+        .get("anyOf")
+        .or_else(|| schema.get("oneOf"))
+        .and_then(Value::as_array)
+    {
+        let best = variants
+            .iter()
+            .filter(|variant| accepts_type(resolve_ref(root, variant), value))
+            .map(|variant| {
+                let mut variant_errors = Vec::new();
+                check_keys(root, variant, value, path, &mut variant_errors);
+                variant_errors
+            })
+            .min_by_key(Vec::len);
+        errors.extend(best.unwrap_or_default());
+        return;
+    }
+
+    match value {
+        Value::Object(map) => check_object(root, schema, map, path, errors),
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    let item_path = format!("{}[{}]", path, index);
+                    check_keys(root, item_schema, item, &item_path, errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_object(
+    root: &Value,
+    schema: &Value,
+    map: &Map<String, Value>,
+    path: &str,
+    errors: &mut Vec<ValidationError>,
+) {
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let additional = schema
+        .get("additionalProperties")
+        .filter(|additional| **additional != Value::Bool(false));
+
+    for (key, item) in map {
+        if key == "@include" {
+            continue;
+        }
+        let item_path = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", path, key)
+        };
+
+        if let Some(property) = properties.and_then(|properties| properties.get(key)) {
+            check_keys(root, property, item, &item_path, errors);
+        } else if let Some(additional) = additional {
+            check_keys(root, additional, item, &item_path, errors);
+        } else if let Some(properties) = properties {
+            let known = properties.keys().map(String::as_str);
+            errors.push(ValidationError {
+                path: item_path,
+                message: format!("Unknown key '{}' is ignored", key),
+                suggestion: Some(match closest_match(key, known) {
+                    Some(known) => format!("did you mean '{}'?", known),
+                    None => "remove it".to_string(),
+                }),
+            });
+        }
+    }
+}
+
+/// Returns false if the schema's `type` rules the value out.
+fn accepts_type(schema: &Value, value: &Value) -> bool {
+    let value_type = match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    };
+    let matches = |t: &Value| t == value_type || (t == "number" && value_type == "integer");
+    match schema.get("type") {
+        Some(Value::Array(types)) => types.iter().any(matches),
+        Some(t) => matches(t),
+        None => true,
+    }
+}
+
+fn resolve_ref<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+    schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| reference.strip_prefix('#'))
+        .and_then(|pointer| root.pointer(pointer))
+        .unwrap_or(schema)
+}
+
+/// Find agent models that name neither a configured model nor an alias.
+///
+/// Models in the `provider/model` form are left to the providers, as are
+/// bare model names when the configuration lists no models or aliases.
+fn unresolved_models(config: &AisopodConfig) -> Vec<ValidationError> {
+    let models = &config.models;
+    if models.models.is_empty() && models.aliases.is_empty() {
+        return Vec::new();
+    }
+    let known = || {
+        models
+            .aliases
+            .keys()
+            .map(String::as_str)
+            .chain(models.models.iter().map(|m| m.id.as_str()))
+    };
+
+    let mut errors = Vec::new();
+    for agent in &config.agents.agents {
+        let mut fields = vec![("model", &agent.model)];
+        if let Some(draft_verify) = &agent.draft_verify {
+            fields.push(("draft_verify.draft_model", &draft_verify.draft_model));
+            fields.push(("draft_verify.verify_model", &draft_verify.verify_model));
+        }
+
+        for (field, model) in fields {
+            if model.is_empty() || model.contains('/') || known().any(|k| k == model) {
+                continue;
+            }
+            errors.push(ValidationError {
+                path: format!("agents[\"{}\"].{}", agent.id, field),
+                message: format!("Model '{}' is not a configured model or alias", model),
+                suggestion: Some(match closest_match(model, known()) {
+                    Some(known) => format!("did you mean '{}'?", known),
+                    None => format!(
+                        "add '{}' to models.aliases, or use the provider/model form",
+                        model
+                    ),
+                }),
+            });
+        }
+    }
+    errors
+}
+
+/// Find the line of the field at `path`, e.g. `agents["support"].model`.
+///
+/// The keys and quoted IDs of the path are looked up one after another in
+/// the file, each after the previous one, which finds the field in both
+/// JSON5 and TOML files without parsing them again. Array indices are
+/// skipped, so the line is a best guess for fields of later array items.
+/// Returns the line of the deepest part of the path that was found.
+fn locate(contents: &str, path: &str) -> Option<usize> {
+    let mut position = 0;
+    let mut found = None;
+
+    for token in path_tokens(path) {
+        match find_token(contents, &token, position) {
+            Some(offset) => {
+                position = offset + token.len();
+                found = Some(offset);
+            }
+            None => break,
+        }
+    }
+
+    found.map(|offset| contents[..offset].matches('\n').count() + 1)
+}
+
+/// Split a path into its keys and quoted IDs, dropping array indices.
+fn path_tokens(path: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for part in path.split('.') {
+        let mut pieces = part.split('[');
+        if let Some(key) = pieces.next().filter(|key| !key.is_empty()) {
+            tokens.push(key.to_string());
+        }
+        for index in pieces {
+            let index = index.trim_end_matches(']').trim_matches('"');
+            if !index.is_empty() && index.parse::<usize>().is_err() {
+                tokens.push(index.to_string());
+            }
+        }
+    }
+    tokens
+}
+
+/// Find `token` as a whole word at or after `from`.
+fn find_token(contents: &str, token: &str, from: usize) -> Option<usize> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    let mut start = from;
+    while let Some(offset) = contents.get(start..)?.find(token) {
+        let offset = start + offset;
+        let end = offset + token.len();
+        let before = contents[..offset].chars().next_back();
+        let after = contents[end..].chars().next();
+        if !before.is_some_and(is_word) && !after.is_some_and(is_word) {
+            return Some(offset);
+        }
+        start = end;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    fn write_config(dir: &TempDir, name: &str, contents: &str) -> std::path::PathBuf {
+        let path = dir.path().join(name);
+        fs::write(&path, contents).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_unknown_keys_detected_with_suggestions() {
+        let value = serde_json::json!({
+            "gateway": { "server": { "prot": 8080 } },
+            "agents": { "agents": [{ "id": "a", "modle": "gpt-4o" }] },
+            "models": { "aliases": { "fast": "openai/gpt-4o-mini" } },
+            "bindings": [{ "agent_id": "a", "channels": [], "sandbox": { "enabled": true } }],
+            "whatever": true,
+        });
+
+        let errors = unknown_keys(&value);
+        let paths: Vec<&str> = errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            ["agents.agents[0].modle", "gateway.server.prot", "whatever"]
+        );
+        assert_eq!(
+            errors[0].suggestion.as_deref(),
+            Some("did you mean 'model'?")
+        );
+        assert_eq!(errors[2].suggestion.as_deref(), Some("remove it"));
+    }
+
+    #[test]
+    fn test_diagnose_json5_file() {
+        let dir = TempDir::new().unwrap();
+        let path = write_config(
+            &dir,
+            "aisopod.json5",
+            r#"{
+  agents: {
+    agents: [
+      { id: "support", name: "Support", model: "fsat" },
+    ],
+  },
+  models: {
+    aliases: { fast: "openai/gpt-4o-mini" },
+  },
+  bindings: [
+    { agent_id: "suport", channels: ["telegram"] },
+  ],
+  channels: {
+    channels: [
+      { id: "telegram-bot", channel_type: "telegram" },
+      { id: "telegram-bot", channel_type: "telegram", enabeld: false },
+    ],
+  },
+}
+"#,
+        );
+
+        let diagnostics = diagnose_config_file(&path).unwrap();
+        let summary: Vec<(Severity, &str, Option<usize>)> = diagnostics
+            .iter()
+            .map(|d| (d.severity, d.path.as_str(), d.line))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (Severity::Warning, "agents[\"support\"].model", Some(4)),
+                (Severity::Error, "bindings[0].agent_id", Some(11)),
+                (Severity::Warning, "channels.channels[1].enabeld", Some(16)),
+                (
+                    Severity::Error,
+                    "channels.channels[\"telegram-bot\"].id",
+                    Some(16)
+                ),
+            ]
+        );
+        assert_eq!(
+            diagnostics[0].suggestion.as_deref(),
+            Some("did you mean 'fast'?")
+        );
+        assert_eq!(
+            diagnostics[1].suggestion.as_deref(),
+            Some("did you mean 'support'?")
+        );
+    }
+
+    #[test]
+    fn test_diagnose_toml_file() {
+        let dir = TempDir::new().unwrap();
+        let path = write_config(
+            &dir,
+            "aisopod.toml",
+            r#"[gateway.server]
+port = 8080
+
+[[agents.agents]]
+id = "support"
+name = "Support"
+model = "openai/gpt-4o"
+sytem_prompt = "Be helpful"
+"#,
+        );
+
+        let diagnostics = diagnose_config_file(&path).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].path, "agents.agents[0].sytem_prompt");
+        assert_eq!(diagnostics[0].line, Some(8));
+        assert_eq!(
+            diagnostics[0].suggestion.as_deref(),
+            Some("did you mean 'system_prompt'?")
+        );
+    }
+
+    #[test]
+    fn test_valid_file_has_no_diagnostics() {
+        let dir = TempDir::new().unwrap();
+        let path = write_config(
+            &dir,
+            "aisopod.json5",
+            r#"{ agents: { agents: [{ id: "a", name: "A", model: "openai/gpt-4o" }] } }"#,
+        );
+        assert!(diagnose_config_file(&path).unwrap().is_empty());
+    }
+}
@@ -8,29 +8,54 @@
 //! - `loader`: Configuration file loading functionality
 //! - `env`: Environment variable substitution functionality
 //! - `includes`: @include directive processing functionality
+//! - `profiles`: Profile overlays for environment-specific settings
+//! - `remote`: Configuration fetched from HTTP(S) and S3 URLs
 //! - `validation`: Configuration semantic validation
+//! - `diagnostics`: Unknown keys and other problems of config files, with their lines
+//! - `migration`: Upgrades of config files written for older releases
 //! - `sensitive`: Sensitive field handling with redaction
+//! - `secrets`: Secret references resolved from external backends
 //! - `generate`: Default configuration generation functionality
+//! - `schema`: JSON Schema generation for the configuration
 //! - `watcher`: Configuration file watcher for hot reload
+//! - `reload`: Application of reloaded configuration to running components
 
+pub mod diagnostics;
 pub mod env;
 pub mod generate;
 pub mod includes;
 pub mod loader;
+pub mod migration;
+pub mod profiles;
+pub mod reload;
+pub mod remote;
+pub mod schema;
+pub mod secrets;
 pub mod sensitive;
 pub mod types;
 pub mod validation;
 pub mod watcher;
 
+pub use diagnostics::{diagnose_config_file, Diagnostic, Severity};
 pub use env::expand_env_vars;
 pub use generate::{generate_config_with_format, generate_default_config, ConfigFormat};
 pub use loader::default_config_path;
 pub use loader::load_config;
 pub use loader::load_config_json5;
 pub use loader::load_config_json5_str;
+pub use loader::load_config_json5_str_unexpanded;
 pub use loader::load_config_toml;
 pub use loader::load_config_toml_str;
-pub use sensitive::Sensitive;
+pub use loader::load_config_with_profile;
+pub use migration::{
+    migrate_config, migrate_config_file, MigrationReport, CURRENT_CONFIG_VERSION,
+};
+pub use profiles::{active_profile, ensure_no_active_profile, PROFILE_ENV_VAR};
+pub use reload::{ConfigApplier, ConfigReloader, ReloadReport};
+pub use remote::{is_remote_url, RemoteConfigSource};
+pub use schema::{config_schema, config_schema_json};
+pub use secrets::{register_secret_backend, SecretBackend};
+pub use sensitive::{with_secrets_unresolved, Sensitive};
 pub use types::AgentDefaults;
 pub use types::AisopodConfig;
 pub use types::ModelFallback;
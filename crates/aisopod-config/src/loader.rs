@@ -99,6 +99,11 @@ fn check_file_permissions(path: &Path) -> Result<()> {
 /// - The file content cannot be parsed
 /// - The parsed content doesn't match the expected configuration structure
 pub fn load_config(path: &Path) -> Result<AisopodConfig> {
+    // Overlay the file with the selected profile, if any
+    if let Some(profile) = crate::profiles::active_profile() {
+        return load_config_with_profile(path, &profile);
+    }
+
     // Security: Check file permissions before reading
     check_file_permissions(path)?;
     
@@ -113,6 +118,96 @@ pub fn load_config(path: &Path) -> Result<AisopodConfig> {
     }
 }
 
+/// Load a configuration file overlaid with the file of a profile.
+///
+/// The overlay of profile `prod` for `aisopod.toml` is `aisopod.prod.toml`,
+/// see [`crate::profiles`]. Both files are parsed, have environment variables
+/// expanded, `@include` directives processed and are migrated before the
+/// overlay is merged into the base.
+///
+/// # Errors
+///
+/// Returns an error if the profile name is invalid, either file cannot be
+/// loaded, or the merged configuration is invalid.
+pub fn load_config_with_profile(path: &Path, profile: &str) -> Result<AisopodConfig> {
+    let overlay_path = crate::profiles::profile_overlay_path(path, profile)?;
+    if !overlay_path.exists() {
+        return Err(anyhow!(
+            "Config profile '{}' not found: {} does not exist",
+            profile,
+            overlay_path.display()
+        ));
+    }
+
+    let mut value = read_config_value(path)?;
+    let overlay = read_config_value(&overlay_path)?;
+    crate::profiles::merge_overlay(&mut value, overlay);
+
+    let config: AisopodConfig = serde_json::from_value(value).with_context(|| {
+        format!(
+            "Failed to deserialize config {} with profile '{}'",
+            path.display(),
+            profile
+        )
+    })?;
+
+    config.validate().map_err(|errs| {
+        let messages: Vec<String> = errs.iter().map(|e| e.to_string()).collect();
+        anyhow!(
+            "Config validation failed with profile '{}':\n  {}",
+            profile,
+            messages.join("\n  ")
+        )
+    })?;
+
+    Ok(config)
+}
+
+/// Read a configuration file into a migrated value, with environment
+/// variables expanded and `@include` directives processed.
+pub(crate) fn read_config_value(path: &Path) -> Result<serde_json::Value> {
+    // Security: Check file permissions before reading
+    check_file_permissions(path)?;
+
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let mut value: serde_json::Value = match ext {
+        "json" | "json5" => json5::from_str(&contents)
+            .with_context(|| format!("Failed to parse JSON5 config: {}", path.display()))?,
+        "toml" => toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse TOML config: {}", path.display()))?,
+        _ => {
+            return Err(anyhow!(
+                "Unsupported config file extension: '{}'. Use .json5, .json, or .toml",
+                ext
+            ))
+        }
+    };
+    crate::env::expand_env_vars(&mut value).with_context(|| {
+        format!(
+            "Failed to expand environment variables in config: {}",
+            path.display()
+        )
+    })?;
+
+    let canonical = path.canonicalize()?;
+    let base_dir = canonical
+        .parent()
+        .ok_or_else(|| anyhow!("Canonicalized config path has no parent directory"))?;
+    let mut seen = HashSet::new();
+    seen.insert(canonical.clone());
+    crate::includes::process_includes(&mut value, base_dir, &mut seen).with_context(|| {
+        format!(
+            "Failed to process @include directives in config: {}",
+            path.display()
+        )
+    })?;
+
+    migrate(&mut value, &path.display().to_string())?;
+    Ok(value)
+}
+
 /// Load and parse a TOML configuration file.
 ///
 /// # Arguments
@@ -158,6 +253,8 @@ pub fn load_config_toml(path: &Path) -> Result<AisopodConfig> {
         )
     })?;
 
+    migrate(&mut value, &path.display().to_string())?;
+
     let config: AisopodConfig = serde_json::from_value(value)
         .with_context(|| format!("Failed to deserialize TOML config: {}", path.display()))?;
 
@@ -173,6 +270,24 @@ pub fn load_config_toml(path: &Path) -> Result<AisopodConfig> {
     Ok(config)
 }
 
+/// Migrate a parsed config to the current config version.
+///
+/// Files are only migrated in memory; `aisopod config migrate` upgrades them
+/// on disk.
+fn migrate(value: &mut serde_json::Value, source: &str) -> Result<()> {
+    let report = crate::migration::migrate_config(value)
+        .with_context(|| format!("Failed to migrate config: {}", source))?;
+    if !report.is_up_to_date() {
+        tracing::info!(
+            "Config {} migrated in memory from version {} to {}, run `aisopod config migrate` to upgrade the file",
+            source,
+            report.from_version,
+            report.to_version
+        );
+    }
+    Ok(())
+}
+
 /// Load a JSON5 configuration string directly (for testing).
 ///
 /// This is a helper function for testing generated configs.
@@ -185,10 +300,34 @@ pub fn load_config_toml(path: &Path) -> Result<AisopodConfig> {
 ///
 /// * `Result<AisopodConfig>` - The parsed configuration or an error
 pub fn load_config_json5_str(content: &str) -> Result<AisopodConfig> {
+    parse_config_json5_str(content, true)
+}
+
+/// Load a JSON5 configuration string without expanding `${VAR}` references.
+///
+/// Use this for content supplied by someone other than the gateway's
+/// operator, so that the process environment never ends up in the result
+/// or in an error message. References are kept as written.
+///
+/// # Arguments
+///
+/// * `content` - JSON5 configuration string
+///
+/// # Returns
+///
+/// * `Result<AisopodConfig>` - The parsed configuration or an error
+pub fn load_config_json5_str_unexpanded(content: &str) -> Result<AisopodConfig> {
+    parse_config_json5_str(content, false)
+}
+
+fn parse_config_json5_str(content: &str, expand_env: bool) -> Result<AisopodConfig> {
     let mut value: serde_json::Value =
         json5::from_str(content).with_context(|| "Failed to parse JSON5 content")?;
-    crate::env::expand_env_vars(&mut value)
-        .with_context(|| "Failed to expand environment variables in JSON5 content")?;
+    if expand_env {
+        crate::env::expand_env_vars(&mut value)
+            .with_context(|| "Failed to expand environment variables in JSON5 content")?;
+    }
+    migrate(&mut value, "content")?;
 
     let config: AisopodConfig =
         serde_json::from_value(value).with_context(|| "Failed to deserialize JSON5 config")?;
@@ -216,8 +355,9 @@ pub fn load_config_json5_str(content: &str) -> Result<AisopodConfig> {
 ///
 /// * `Result<AisopodConfig>` - The parsed configuration or an error
 pub fn load_config_toml_str(content: &str) -> Result<AisopodConfig> {
-    let value: serde_json::Value =
+    let mut value: serde_json::Value =
         toml::from_str(content).with_context(|| "Failed to parse TOML content")?;
+    migrate(&mut value, "content")?;
 
     let config: AisopodConfig =
         serde_json::from_value(value).with_context(|| "Failed to deserialize TOML config")?;
@@ -262,6 +402,8 @@ pub fn load_config_json5(path: &Path) -> Result<AisopodConfig> {
         )
     })?;
 
+    migrate(&mut value, &path.display().to_string())?;
+
     let config: AisopodConfig = serde_json::from_value(value)
         .with_context(|| format!("Failed to deserialize config: {}", path.display()))?;
 
@@ -491,4 +633,64 @@ mod tests {
             .to_string()
             .contains(&config_path.display().to_string()));
     }
+
+    #[test]
+    fn test_load_config_with_profile() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let config_path = temp_dir.path().join("aisopod.toml");
+        let write = |path: &Path, content: &str| {
+            fs::write(path, content).expect("Failed to write test config");
+            fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+                .expect("Failed to set permissions");
+        };
+        write(
+            &config_path,
+            r#"
+[gateway.server]
+port = 8080
+
+[models.aliases]
+smart = "openai/gpt-4o"
+
+[[channels.channels]]
+id = "telegram"
+channel_type = "telegram"
+
+[[channels.channels]]
+id = "discord"
+channel_type = "discord"
+"#,
+        );
+        write(
+            &temp_dir.path().join("aisopod.prod.toml"),
+            r#"
+[gateway.server]
+port = 443
+
+[models.aliases]
+smart = "anthropic/claude-sonnet-4"
+
+[[channels.channels]]
+id = "discord"
+enabled = false
+"#,
+        );
+
+        let config = load_config_with_profile(&config_path, "prod").unwrap();
+        assert_eq!(config.gateway.server.port, 443);
+        assert_eq!(
+            config.models.resolve_alias("smart"),
+            "anthropic/claude-sonnet-4"
+        );
+        let enabled: Vec<&str> = config
+            .channels
+            .enabled_channels()
+            .map(|c| c.id.as_str())
+            .collect();
+        assert_eq!(enabled, ["telegram"]);
+        assert_eq!(config.channels.channels[1].channel_type, "discord");
+
+        let err = load_config_with_profile(&config_path, "staging").unwrap_err();
+        assert!(err.to_string().contains("aisopod.staging.toml"));
+    }
 }
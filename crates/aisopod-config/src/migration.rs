@@ -0,0 +1,478 @@
+//! Configuration migration module
+//!
+//! Config files record the layout they were written for in their top-level
+//! `config_version` field; files without it predate versioning and are at
+//! version 0. When a release renames keys or restructures sections, it bumps
+//! [`CURRENT_CONFIG_VERSION`] and adds a [`Migration`] from the previous
+//! version, so that older files are upgraded instead of having their
+//! settings silently ignored as unknown keys.
+//!
+//! Configs are migrated in memory every time they are loaded.
+//! [`migrate_config_file`] upgrades a file on disk, or with `dry_run` only
+//! reports the changes it would make.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The config version written by this release.
+pub const CURRENT_CONFIG_VERSION: u32 = 1;
+
+/// The key holding the config version in config files.
+pub const CONFIG_VERSION_KEY: &str = "config_version";
+
+/// An upgrade of config files from one version to the next.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// The version upgraded from; the migration produces `from_version + 1`.
+    pub from_version: u32,
+    /// A short description of the changes, shown to users.
+    pub description: &'static str,
+    /// Rewrites the config root in place, e.g. with [`rename_key`].
+    pub apply: fn(&mut Map<String, Value>) -> Result<()>,
+}
+
+/// The migrations of the config layout, in version order.
+const MIGRATIONS: &[Migration] = &[Migration {
+    from_version: 0,
+    description: "Record the config version in config_version",
+    apply: add_config_version,
+}];
+
+/// Unversioned configs have the version 1 layout; only the version itself is
+/// added, by the migration engine.
+fn add_config_version(_root: &mut Map<String, Value>) -> Result<()> {
+    Ok(())
+}
+
+/// The built-in config migrations, in version order.
+pub fn migrations() -> &'static [Migration] {
+    MIGRATIONS
+}
+
+/// A single change made to a config by a migration.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigChange {
+    /// A setting was added.
+    Added { path: String, value: Value },
+    /// A setting was removed.
+    Removed { path: String, value: Value },
+    /// A setting's value changed.
+    Changed {
+        path: String,
+        old: Value,
+        new: Value,
+    },
+}
+
+impl fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigChange::Added { path, value } => write!(f, "+ {} = {}", path, value),
+            ConfigChange::Removed { path, value } => write!(f, "- {} = {}", path, value),
+            ConfigChange::Changed { path, old, new } => {
+                write!(f, "~ {} = {} -> {}", path, old, new)
+            }
+        }
+    }
+}
+
+/// The outcome of migrating a config.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationReport {
+    /// The version of the config before migrating.
+    pub from_version: u32,
+    /// The version of the config after migrating.
+    pub to_version: u32,
+    /// The descriptions of the applied migrations, in order.
+    pub applied: Vec<&'static str>,
+    /// The settings changed by the migrations, by path.
+    pub changes: Vec<ConfigChange>,
+}
+
+impl MigrationReport {
+    /// Returns `true` if the config was already at the target version.
+    pub fn is_up_to_date(&self) -> bool {
+        self.applied.is_empty()
+    }
+
+    /// Format the changes as a diff, one setting per line.
+    pub fn diff(&self) -> String {
+        self.changes
+            .iter()
+            .map(|change| change.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Read the config version of a config value, 0 if it has none.
+///
+/// # Errors
+///
+/// Returns an error if `config_version` is not a non-negative integer.
+pub fn config_version(value: &Value) -> Result<u32> {
+    match value.get(CONFIG_VERSION_KEY) {
+        None => Ok(0),
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| {
+                anyhow!(
+                    "Invalid {} {}, expected a non-negative integer",
+                    CONFIG_VERSION_KEY,
+                    version
+                )
+            }),
+    }
+}
+
+/// Migrate a config value to [`CURRENT_CONFIG_VERSION`] with the built-in
+/// migrations.
+///
+/// # Errors
+///
+/// Returns an error if the config is newer than this release supports or a
+/// migration fails.
+pub fn migrate_config(value: &mut Value) -> Result<MigrationReport> {
+    migrate_config_with(value, MIGRATIONS, CURRENT_CONFIG_VERSION)
+}
+
+/// Migrate a config value to `target` with the given migrations.
+///
+/// The value is left unchanged if a migration fails.
+///
+/// # Errors
+///
+/// Returns an error if the config root is not an object, the config is newer
+/// than `target`, a migration between its version and `target` is missing,
+/// or a migration fails.
+pub fn migrate_config_with(
+    value: &mut Value,
+    migrations: &[Migration],
+    target: u32,
+) -> Result<MigrationReport> {
+    let from_version = config_version(value)?;
+    if from_version > target {
+        bail!(
+            "Config version {} is newer than the supported version {}, upgrade aisopod to load it",
+            from_version,
+            target
+        );
+    }
+
+    let before = value.clone();
+    let mut migrated = value.clone();
+    let root = migrated
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("Config root must be an object"))?;
+    let mut applied = Vec::new();
+    for version in from_version..target {
+        let migration = migrations
+            .iter()
+            .find(|m| m.from_version == version)
+            .ok_or_else(|| anyhow!("No migration from config version {}", version))?;
+        (migration.apply)(root).with_context(|| {
+            format!(
+                "Failed to migrate config from version {}: {}",
+                version, migration.description
+            )
+        })?;
+        applied.push(migration.description);
+    }
+    if from_version < target {
+        root.insert(CONFIG_VERSION_KEY.to_string(), target.into());
+    }
+
+    *value = migrated;
+    Ok(MigrationReport {
+        from_version,
+        to_version: target,
+        applied,
+        changes: diff_values(&before, value),
+    })
+}
+
+/// Move the setting at the dotted path `from` to the dotted path `to`,
+/// creating parent sections as needed and removing sections left empty.
+///
+/// Does nothing if `from` is not set.
+///
+/// # Errors
+///
+/// Returns an error if both paths are set, or a parent of `to` is not a
+/// section.
+pub fn rename_key(root: &mut Map<String, Value>, from: &str, to: &str) -> Result<()> {
+    let to_segments = split(to);
+    if get_path(root, &to_segments).is_some() && get_path(root, &split(from)).is_some() {
+        bail!("Both '{}' and its new name '{}' are set", from, to);
+    }
+    let Some(value) = take_path(root, &split(from)) else {
+        return Ok(());
+    };
+
+    let (last, parents) = to_segments
+        .split_last()
+        .ok_or_else(|| anyhow!("Empty config path"))?;
+    let mut section = root;
+    for segment in parents {
+        section = section
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or_else(|| {
+                anyhow!(
+                    "Cannot move '{}' to '{}': '{}' is not a section",
+                    from,
+                    to,
+                    segment
+                )
+            })?;
+    }
+    section.insert(last.to_string(), value);
+    Ok(())
+}
+
+fn split(path: &str) -> Vec<&str> {
+    path.split('.').collect()
+}
+
+fn get_path<'a>(map: &'a Map<String, Value>, segments: &[&str]) -> Option<&'a Value> {
+    let (first, rest) = segments.split_first()?;
+    let value = map.get(*first)?;
+    if rest.is_empty() {
+        Some(value)
+    } else {
+        get_path(value.as_object()?, rest)
+    }
+}
+
+fn take_path(map: &mut Map<String, Value>, segments: &[&str]) -> Option<Value> {
+    let (first, rest) = segments.split_first()?;
+    if rest.is_empty() {
+        return map.remove(*first);
+    }
+    let child = map.get_mut(*first)?.as_object_mut()?;
+    let value = take_path(child, rest);
+    if child.is_empty() {
+        map.remove(*first);
+    }
+    value
+}
+
+/// List the settings that differ between two config values.
+fn diff_values(before: &Value, after: &Value) -> Vec<ConfigChange> {
+    let mut old = BTreeMap::new();
+    let mut new = BTreeMap::new();
+    flatten("", before, &mut old);
+    flatten("", after, &mut new);
+
+    let mut changes = Vec::new();
+    for (path, value) in &old {
+        match new.get(path) {
+            None => changes.push(ConfigChange::Removed {
+                path: path.clone(),
+                value: (*value).clone(),
+            }),
+            Some(new_value) if new_value != value => changes.push(ConfigChange::Changed {
+                path: path.clone(),
+                old: (*value).clone(),
+                new: (*new_value).clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (path, value) in &new {
+        if !old.contains_key(path) {
+            changes.push(ConfigChange::Added {
+                path: path.clone(),
+                value: (*value).clone(),
+            });
+        }
+    }
+    changes
+}
+
+/// Flatten a value into its leaf settings by dotted path, with array items
+/// as `path[index]`.
+fn flatten<'a>(prefix: &str, value: &'a Value, result: &mut BTreeMap<String, &'a Value>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&path, value, result);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (i, item) in items.iter().enumerate() {
+                flatten(&format!("{}[{}]", prefix, i), item, result);
+            }
+        }
+        _ => {
+            result.insert(prefix.to_string(), value);
+        }
+    }
+}
+
+/// Migrate a config file on disk to [`CURRENT_CONFIG_VERSION`].
+///
+/// Unless `dry_run` is set and if the file needs migrating, the original is
+/// kept next to it as `<file>.v<version>.bak` and the file is rewritten in
+/// its format. Comments of JSON5 files are not preserved in the rewritten
+/// file. Environment variable references and `@include` directives are kept
+/// as they are.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, parsed, migrated or written.
+pub fn migrate_config_file(path: &Path, dry_run: bool) -> Result<MigrationReport> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+    let is_toml = path.extension().and_then(|e| e.to_str()) == Some("toml");
+    let mut value: Value = if is_toml {
+        toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse TOML config: {}", path.display()))?
+    } else {
+        json5::from_str(&contents)
+            .with_context(|| format!("Failed to parse JSON5 config: {}", path.display()))?
+    };
+
+    let report = migrate_config(&mut value)
+        .with_context(|| format!("Failed to migrate config: {}", path.display()))?;
+    if dry_run || report.is_up_to_date() {
+        return Ok(report);
+    }
+
+    let migrated = if is_toml {
+        toml::to_string_pretty(&value)?
+    } else {
+        serde_json::to_string_pretty(&value)?
+    };
+    let mut backup = PathBuf::from(path).into_os_string();
+    backup.push(format!(".v{}.bak", report.from_version));
+    std::fs::copy(path, &backup)
+        .with_context(|| format!("Failed to back up config file: {}", path.display()))?;
+    std::fs::write(path, migrated)
+        .with_context(|| format!("Failed to write config file: {}", path.display()))?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn move_port(root: &mut Map<String, Value>) -> Result<()> {
+        rename_key(root, "server.port", "gateway.server.port")
+    }
+
+    fn rename_model(root: &mut Map<String, Value>) -> Result<()> {
+        rename_key(root, "agents.default.llm", "agents.default.model")
+    }
+
+    const TEST_MIGRATIONS: &[Migration] = &[
+        Migration {
+            from_version: 0,
+            description: "Move server into gateway",
+            apply: move_port,
+        },
+        Migration {
+            from_version: 1,
+            description: "Rename agents.default.llm to model",
+            apply: rename_model,
+        },
+    ];
+
+    #[test]
+    fn test_unversioned_config_is_migrated() {
+        let mut value = json!({"gateway": {"server": {"port": 8080}}});
+        let report = migrate_config(&mut value).unwrap();
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.to_version, CURRENT_CONFIG_VERSION);
+        assert_eq!(value[CONFIG_VERSION_KEY], CURRENT_CONFIG_VERSION);
+        assert_eq!(report.diff(), "+ config_version = 1");
+
+        let report = migrate_config(&mut value).unwrap();
+        assert!(report.is_up_to_date());
+        assert!(report.changes.is_empty());
+    }
+
+    #[test]
+    fn test_migrations_are_chained() {
+        let mut value = json!({
+            "server": {"port": 9000},
+            "agents": {"default": {"llm": "gpt-4"}},
+        });
+        let report = migrate_config_with(&mut value, TEST_MIGRATIONS, 2).unwrap();
+        assert_eq!(report.applied.len(), 2);
+        assert_eq!(
+            value,
+            json!({
+                "config_version": 2,
+                "gateway": {"server": {"port": 9000}},
+                "agents": {"default": {"model": "gpt-4"}},
+            })
+        );
+        assert_eq!(
+            report.diff(),
+            "- agents.default.llm = \"gpt-4\"\n\
+             - server.port = 9000\n\
+             + agents.default.model = \"gpt-4\"\n\
+             + config_version = 2\n\
+             + gateway.server.port = 9000"
+        );
+
+        // Starting from version 1 only applies the later migration
+        let mut value = json!({"config_version": 1, "server": {"port": 9000}});
+        let report = migrate_config_with(&mut value, TEST_MIGRATIONS, 2).unwrap();
+        assert_eq!(report.applied, ["Rename agents.default.llm to model"]);
+        assert_eq!(value["server"]["port"], 9000);
+    }
+
+    #[test]
+    fn test_conflicting_rename_fails_without_changes() {
+        let mut value = json!({
+            "server": {"port": 9000},
+            "gateway": {"server": {"port": 8080}},
+        });
+        let original = value.clone();
+        let err = migrate_config_with(&mut value, TEST_MIGRATIONS, 2).unwrap_err();
+        assert!(format!("{:#}", err).contains("Both 'server.port'"));
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    fn test_newer_and_invalid_versions_are_rejected() {
+        let mut value = json!({"config_version": CURRENT_CONFIG_VERSION + 1});
+        assert!(migrate_config(&mut value).is_err());
+        let mut value = json!({"config_version": "two"});
+        assert!(migrate_config(&mut value).is_err());
+    }
+
+    #[test]
+    fn test_migrate_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("aisopod.toml");
+        std::fs::write(&path, "[gateway.server]\nport = \"${PORT}\"\n").unwrap();
+
+        let report = migrate_config_file(&path, true).unwrap();
+        assert!(!report.is_up_to_date());
+        assert!(!std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("config_version"));
+
+        migrate_config_file(&path, false).unwrap();
+        let migrated = std::fs::read_to_string(&path).unwrap();
+        assert!(migrated.contains("config_version = 1"));
+        assert!(migrated.contains("${PORT}"));
+        assert!(temp_dir.path().join("aisopod.toml.v0.bak").exists());
+        assert!(migrate_config_file(&path, false).unwrap().is_up_to_date());
+    }
+}
@@ -0,0 +1,176 @@
+//! Configuration profile module
+//!
+//! A profile adapts a base config file to an environment with an overlay
+//! file next to it: with the `prod` profile, `aisopod.toml` is overlaid with
+//! `aisopod.prod.toml`. The profile is selected with the `AISOPOD_PROFILE`
+//! environment variable, which the CLI's `--profile` flag sets.
+//!
+//! Overlays are merged deterministically with [`merge_overlay`]: sections
+//! are merged key by key, lists of entries with an `id` (agents, models,
+//! channels) are merged entry by entry, and all other values in the overlay
+//! replace those of the base. A profile can thus enable or disable single
+//! channels or point model aliases at other models without repeating the
+//! rest of the config.
+
+use anyhow::{bail, Result};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// The environment variable selecting the config profile.
+pub const PROFILE_ENV_VAR: &str = "AISOPOD_PROFILE";
+
+/// Returns the profile selected by [`PROFILE_ENV_VAR`], if any.
+pub fn active_profile() -> Option<String> {
+    std::env::var(PROFILE_ENV_VAR)
+        .ok()
+        .map(|profile| profile.trim().to_string())
+        .filter(|profile| !profile.is_empty())
+}
+
+/// Fails if a profile is active, for commands writing the config file.
+///
+/// A config loaded with a profile holds the settings of the overlay, which
+/// saving it would write into the base file.
+pub fn ensure_no_active_profile() -> Result<()> {
+    match active_profile() {
+        Some(profile) => bail!(
+            "Cannot write the config file with profile '{}' active, edit its overlay file instead",
+            profile
+        ),
+        None => Ok(()),
+    }
+}
+
+/// Returns the path of the overlay of `profile` for the config file at
+/// `path`, e.g. `aisopod.prod.toml` for `aisopod.toml`.
+///
+/// # Errors
+///
+/// Returns an error if the profile name contains characters other than
+/// ASCII letters, digits, `-` and `_`.
+pub fn profile_overlay_path(path: &Path, profile: &str) -> Result<PathBuf> {
+    if profile.is_empty()
+        || !profile
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!(
+            "Invalid profile name '{}', use letters, digits, '-' and '_'",
+            profile
+        );
+    }
+
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let file_name = match path.extension() {
+        Some(ext) => format!("{}.{}.{}", stem, profile, ext.to_string_lossy()),
+        None => format!("{}.{}", stem, profile),
+    };
+    Ok(path.with_file_name(file_name))
+}
+
+/// Merge a profile overlay into a base config value.
+///
+/// Objects are merged recursively. Arrays whose items are all objects with
+/// a string `id` are merged by `id`: items of the overlay are merged into
+/// the base item with the same `id`, or appended in overlay order. Any
+/// other overlay value, including an empty array, replaces the base value.
+pub fn merge_overlay(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_overlay(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(base), Value::Array(overlay))
+            if !overlay.is_empty() && has_ids(base) && has_ids(&overlay) =>
+        {
+            for item in overlay {
+                let position = base
+                    .iter()
+                    .position(|existing| id_of(existing) == id_of(&item));
+                match position {
+                    Some(i) => merge_overlay(&mut base[i], item),
+                    None => base.push(item),
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+fn id_of(item: &Value) -> Option<&str> {
+    item.get("id").and_then(Value::as_str)
+}
+
+fn has_ids(items: &[Value]) -> bool {
+    items.iter().all(|item| id_of(item).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_profile_overlay_path() {
+        assert_eq!(
+            profile_overlay_path(Path::new("/etc/aisopod/aisopod.toml"), "prod").unwrap(),
+            PathBuf::from("/etc/aisopod/aisopod.prod.toml")
+        );
+        assert_eq!(
+            profile_overlay_path(Path::new("aisopod-config.json5"), "staging-eu").unwrap(),
+            PathBuf::from("aisopod-config.staging-eu.json5")
+        );
+        assert!(profile_overlay_path(Path::new("aisopod.toml"), "../prod").is_err());
+        assert!(profile_overlay_path(Path::new("aisopod.toml"), "").is_err());
+    }
+
+    #[test]
+    fn test_merge_overlay() {
+        let mut base = json!({
+            "gateway": {"server": {"port": 8080, "name": "aisopod"}},
+            "models": {
+                "aliases": {"fast": "openai/gpt-4o-mini", "smart": "openai/gpt-4o"},
+            },
+            "channels": {"channels": [
+                {"id": "tg", "channel_type": "telegram"},
+                {"id": "dc", "channel_type": "discord"},
+            ]},
+            "tools": {"bash": {"allowed_commands": ["ls", "cat"]}},
+        });
+        let overlay = json!({
+            "gateway": {"server": {"port": 443}},
+            "models": {"aliases": {"smart": "anthropic/claude-sonnet-4"}},
+            "channels": {"channels": [
+                {"id": "dc", "enabled": false},
+                {"id": "slack", "channel_type": "slack"},
+            ]},
+            "tools": {"bash": {"allowed_commands": ["ls"]}},
+        });
+
+        merge_overlay(&mut base, overlay);
+        assert_eq!(
+            base,
+            json!({
+                "gateway": {"server": {"port": 443, "name": "aisopod"}},
+                "models": {
+                    "aliases": {"fast": "openai/gpt-4o-mini", "smart": "anthropic/claude-sonnet-4"},
+                },
+                "channels": {"channels": [
+                    {"id": "tg", "channel_type": "telegram"},
+                    {"id": "dc", "channel_type": "discord", "enabled": false},
+                    {"id": "slack", "channel_type": "slack"},
+                ]},
+                "tools": {"bash": {"allowed_commands": ["ls"]}},
+            })
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Configuration hot-reload module
+//!
+//! The [`ConfigWatcher`](crate::ConfigWatcher) publishes every changed
+//! configuration; this module pushes the changes into the running
+//! components. A component implements [`ConfigApplier`] for the sections it
+//! depends on, e.g. the channel registry for `channels` or the agent
+//! resolver for `agents` and `bindings`, and is registered with a
+//! [`ConfigReloader`]. When a new configuration arrives, the reloader
+//! computes the changed sections with [`diff_sections`] and calls the
+//! appliers depending on any of them, so that unrelated components are left
+//! alone.
+//!
+//! Appliers are independent: a failing applier is reported and does not
+//! keep the others from applying their changes.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+use crate::types::AisopodConfig;
+use crate::watcher::diff_sections;
+
+/// A running component that applies configuration changes without a restart.
+#[async_trait]
+pub trait ConfigApplier: Send + Sync {
+    /// A name for the applier, used in logs and reports.
+    fn name(&self) -> &str;
+
+    /// The top-level config sections the applier depends on, e.g.
+    /// `channels`.
+    fn sections(&self) -> &[&str];
+
+    /// Apply the change from `old` to `new`.
+    ///
+    /// Only called when at least one of [`sections`](Self::sections)
+    /// changed.
+    async fn apply(&self, old: &AisopodConfig, new: &AisopodConfig) -> Result<()>;
+}
+
+/// The outcome of applying a configuration change.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReloadReport {
+    /// Top-level sections that changed
+    pub changed_sections: Vec<String>,
+    /// Appliers that applied the change
+    pub applied: Vec<String>,
+    /// Appliers that failed, with their errors
+    pub failed: Vec<(String, String)>,
+}
+
+impl ReloadReport {
+    /// Returns true if every concerned applier applied the change.
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Dispatches configuration changes to the registered appliers.
+#[derive(Clone, Default)]
+pub struct ConfigReloader {
+    appliers: Vec<Arc<dyn ConfigApplier>>,
+}
+
+impl ConfigReloader {
+    /// Create a reloader without appliers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an applier. Appliers are called in registration order.
+    pub fn with_applier(mut self, applier: Arc<dyn ConfigApplier>) -> Self {
+        self.appliers.push(applier);
+        self
+    }
+
+    /// Apply the change from `old` to `new` with the concerned appliers.
+    pub async fn apply(&self, old: &AisopodConfig, new: &AisopodConfig) -> ReloadReport {
+        let changed_sections = diff_sections(old, new);
+        let mut report = ReloadReport::default();
+
+        for applier in &self.appliers {
+            let concerned = applier
+                .sections()
+                .iter()
+                .any(|section| changed_sections.iter().any(|changed| changed == section));
+            if !concerned {
+                continue;
+            }
+
+            match applier.apply(old, new).await {
+                Ok(()) => report.applied.push(applier.name().to_string()),
+                Err(e) => {
+                    error!(
+                        "Failed to apply config change to {}: {:#}",
+                        applier.name(),
+                        e
+                    );
+                    report
+                        .failed
+                        .push((applier.name().to_string(), format!("{:#}", e)));
+                }
+            }
+        }
+
+        if !report.applied.is_empty() {
+            info!(
+                "Applied config change of {} to {}",
+                changed_sections.join(", "),
+                report.applied.join(", ")
+            );
+        }
+        report.changed_sections = changed_sections;
+        report
+    }
+
+    /// Apply every configuration published on `receiver` until its sender
+    /// is dropped, e.g. with the receiver of a
+    /// [`ConfigWatcher`](crate::ConfigWatcher).
+    pub fn spawn(self, mut receiver: watch::Receiver<AisopodConfig>) -> JoinHandle<()> {
+        let mut current = receiver.borrow_and_update().clone();
+        tokio::spawn(async move {
+            while receiver.changed().await.is_ok() {
+                let new = receiver.borrow_and_update().clone();
+                self.apply(&current, &new).await;
+                current = new;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::bail;
+    use std::sync::Mutex;
+
+    struct RecordingApplier {
+        name: &'static str,
+        sections: &'static [&'static str],
+        fail: bool,
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl RecordingApplier {
+        fn new(name: &'static str, sections: &'static [&'static str]) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                sections,
+                fail: false,
+                calls: Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl ConfigApplier for RecordingApplier {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn sections(&self) -> &[&str] {
+            self.sections
+        }
+
+        async fn apply(&self, _old: &AisopodConfig, new: &AisopodConfig) -> Result<()> {
+            if self.fail {
+                bail!("boom");
+            }
+            self.calls.lock().unwrap().push(new.meta.version.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_concerned_appliers_are_called() {
+        let channels = RecordingApplier::new("channels", &["channels"]);
+        let meta = RecordingApplier::new("meta", &["meta"]);
+        let failing = Arc::new(RecordingApplier {
+            name: "failing",
+            sections: &["meta"],
+            fail: true,
+            calls: Mutex::new(Vec::new()),
+        });
+        let reloader = ConfigReloader::new()
+            .with_applier(channels.clone())
+            .with_applier(failing)
+            .with_applier(meta.clone());
+
+        let old = AisopodConfig::default();
+        let mut new = AisopodConfig::default();
+        new.meta.version = "2.0".to_string();
+
+        let report = reloader.apply(&old, &new).await;
+        assert_eq!(report.changed_sections, ["meta"]);
+        assert_eq!(report.applied, ["meta"]);
+        assert_eq!(report.failed, [("failing".to_string(), "boom".to_string())]);
+        assert!(!report.is_success());
+        assert!(channels.calls.lock().unwrap().is_empty());
+        assert_eq!(*meta.calls.lock().unwrap(), ["2.0"]);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_applies_published_configs() {
+        let meta = RecordingApplier::new("meta", &["meta"]);
+        let (tx, rx) = watch::channel(AisopodConfig::default());
+        let handle = ConfigReloader::new().with_applier(meta.clone()).spawn(rx);
+
+        let mut new = AisopodConfig::default();
+        new.meta.version = "2.0".to_string();
+        tx.send(new).unwrap();
+        drop(tx);
+
+        handle.await.unwrap();
+        assert_eq!(*meta.calls.lock().unwrap(), ["2.0"]);
+    }
+}
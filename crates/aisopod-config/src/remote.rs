@@ -0,0 +1,431 @@
+//! Remote configuration source module
+//!
+//! Fleet-managed deployments can keep their configuration at a central
+//! location instead of on each host. A [`RemoteConfigSource`] fetches the
+//! configuration from one of:
+//!
+//! - `http://` or `https://` URLs, polled with `If-None-Match` so that an
+//!   unchanged config costs a `304 Not Modified` response.
+//! - `s3://<bucket>/<key>` URLs, read with the `aws` CLI using its usual
+//!   credentials. The object's ETag is checked before downloading it.
+//!
+//! The format follows the extension of the URL path: `.toml` is parsed as
+//! TOML and anything else as JSON5. `@include` directives are not supported
+//! in remote configs.
+//!
+//! # Signatures
+//!
+//! With trusted keys set, a config is only accepted with a valid detached
+//! signature, fetched from the config URL with `.sig` appended. The signature
+//! file holds the base64 Ed25519 signature of the config bytes; keys are 32
+//! bytes in standard base64, as for plugin signatures.
+//!
+//! Polling is done by [`ConfigWatcher::watch_remote`](crate::ConfigWatcher::watch_remote),
+//! which publishes changed configs like the file watcher does.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use reqwest::header::{ETAG, IF_NONE_MATCH};
+use reqwest::StatusCode;
+use tokio::process::Command;
+
+use crate::generate::ConfigFormat;
+use crate::loader::{load_config_json5_str, load_config_toml_str};
+use crate::types::AisopodConfig;
+
+/// Default interval between two polls of a remote config.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Suffix appended to the config URL to get its detached signature.
+pub const SIGNATURE_SUFFIX: &str = ".sig";
+
+/// Timeout of a single remote request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Returns true if `location` is a URL of a remote config rather than a path.
+pub fn is_remote_url(location: &str) -> bool {
+    ["http://", "https://", "s3://"]
+        .iter()
+        .any(|scheme| location.starts_with(scheme))
+}
+
+/// Where a remote config is stored.
+#[derive(Debug, Clone, PartialEq)]
+enum Location {
+    Http(String),
+    S3 { bucket: String, key: String },
+}
+
+impl Location {
+    fn parse(url: &str) -> Result<Self> {
+        if let Some(rest) = url.strip_prefix("s3://") {
+            return match rest.split_once('/') {
+                Some((bucket, key)) if !bucket.is_empty() && !key.is_empty() => Ok(Self::S3 {
+                    bucket: bucket.to_string(),
+                    key: key.to_string(),
+                }),
+                _ => bail!("Invalid S3 URL '{}', expected 's3://<bucket>/<key>'", url),
+            };
+        }
+        if url.starts_with("http://") || url.starts_with("https://") {
+            return Ok(Self::Http(url.to_string()));
+        }
+        bail!("Unsupported remote config URL '{}'", url)
+    }
+
+    /// The location of the detached signature of this config.
+    fn signature(&self) -> Self {
+        match self {
+            Self::Http(url) => Self::Http(format!("{}{}", url, SIGNATURE_SUFFIX)),
+            Self::S3 { bucket, key } => Self::S3 {
+                bucket: bucket.clone(),
+                key: format!("{}{}", key, SIGNATURE_SUFFIX),
+            },
+        }
+    }
+}
+
+/// A fetched remote config document.
+#[derive(Debug, Clone)]
+pub struct RemoteDocument {
+    /// The raw config bytes
+    pub body: Vec<u8>,
+    /// The entity tag of the document, used for the next poll
+    pub etag: Option<String>,
+}
+
+/// A configuration fetched from an HTTP(S) or S3 URL.
+#[derive(Debug, Clone)]
+pub struct RemoteConfigSource {
+    url: String,
+    location: Location,
+    format: ConfigFormat,
+    trusted_keys: Vec<VerifyingKey>,
+    poll_interval: Duration,
+    client: reqwest::Client,
+}
+
+impl RemoteConfigSource {
+    /// Create a source for the config at `url`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL is not an `http`, `https` or `s3` URL.
+    pub fn new(url: &str) -> Result<Self> {
+        let location = Location::parse(url)?;
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let format = if path.ends_with(".toml") {
+            ConfigFormat::Toml
+        } else {
+            ConfigFormat::Json5
+        };
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self {
+            url: url.to_string(),
+            location,
+            format,
+            trusted_keys: Vec::new(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            client,
+        })
+    }
+
+    /// Override the format given by the URL extension.
+    pub fn with_format(mut self, format: ConfigFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Trust a base64 Ed25519 public key, which makes signatures required.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is not a valid base64 Ed25519 public key.
+    pub fn with_trusted_key(mut self, key: &str) -> Result<Self> {
+        let bytes: [u8; 32] = BASE64
+            .decode(key.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| anyhow!("Invalid public key '{}', expected 32 bytes of base64", key))?;
+        let key = VerifyingKey::from_bytes(&bytes)
+            .map_err(|e| anyhow!("Invalid public key '{}': {}", key, e))?;
+        self.trusted_keys.push(key);
+        Ok(self)
+    }
+
+    /// Set the interval between two polls.
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// The URL of the config.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The interval between two polls.
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    /// Fetch the config document, unless its entity tag is still `etag`.
+    ///
+    /// The signature is verified if trusted keys are set.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(document))` - The document changed, or no `etag` was given
+    /// * `Ok(None)` - The document still has the entity tag `etag`
+    pub async fn fetch(&self, etag: Option<&str>) -> Result<Option<RemoteDocument>> {
+        let document = match &self.location {
+            Location::Http(url) => self.fetch_http(url, etag).await?,
+            Location::S3 { bucket, key } => fetch_s3(bucket, key, etag).await?,
+        };
+        let Some(document) = document else {
+            return Ok(None);
+        };
+
+        if !self.trusted_keys.is_empty() {
+            let signature = match &self.location.signature() {
+                Location::Http(url) => self.fetch_http(url, None).await?,
+                Location::S3 { bucket, key } => fetch_s3(bucket, key, None).await?,
+            }
+            .ok_or_else(|| anyhow!("Missing signature for '{}'", self.url))?;
+            self.verify(&document.body, &signature.body)
+                .with_context(|| format!("Rejected remote config '{}'", self.url))?;
+        }
+        Ok(Some(document))
+    }
+
+    /// Fetch and parse the config, returning it with its entity tag.
+    pub async fn load(&self) -> Result<(AisopodConfig, Option<String>)> {
+        let document = self
+            .fetch(None)
+            .await?
+            .ok_or_else(|| anyhow!("No config returned by '{}'", self.url))?;
+        let config = self.parse(&document.body)?;
+        Ok((config, document.etag))
+    }
+
+    /// Parse and validate fetched config bytes.
+    pub fn parse(&self, body: &[u8]) -> Result<AisopodConfig> {
+        let content = std::str::from_utf8(body)
+            .with_context(|| format!("Remote config '{}' is not UTF-8", self.url))?;
+        match self.format {
+            ConfigFormat::Json5 => load_config_json5_str(content),
+            ConfigFormat::Toml => load_config_toml_str(content),
+        }
+        .with_context(|| format!("Failed to load remote config '{}'", self.url))
+    }
+
+    /// Verify the detached signature of config bytes against the trusted keys.
+    fn verify(&self, body: &[u8], signature: &[u8]) -> Result<()> {
+        let signature = std::str::from_utf8(signature)
+            .ok()
+            .and_then(|s| BASE64.decode(s.trim()).ok())
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+            .ok_or_else(|| anyhow!("Malformed signature"))?;
+        if self
+            .trusted_keys
+            .iter()
+            .any(|key| key.verify(body, &signature).is_ok())
+        {
+            Ok(())
+        } else {
+            bail!("Signature does not match any trusted key")
+        }
+    }
+
+    async fn fetch_http(&self, url: &str, etag: Option<&str>) -> Result<Option<RemoteDocument>> {
+        let mut request = self.client.get(url);
+        if let Some(etag) = etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch '{}'", url))?;
+
+        match response.status() {
+            StatusCode::NOT_MODIFIED => Ok(None),
+            status if status.is_success() => {
+                let etag = response
+                    .headers()
+                    .get(ETAG)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string);
+                let body = response.bytes().await?.to_vec();
+                Ok(Some(RemoteDocument { body, etag }))
+            }
+            status => bail!("Failed to fetch '{}': HTTP {}", url, status),
+        }
+    }
+}
+
+/// Fetch an S3 object with the `aws` CLI, unless its ETag is still `etag`.
+async fn fetch_s3(bucket: &str, key: &str, etag: Option<&str>) -> Result<Option<RemoteDocument>> {
+    let current_etag = run_aws(&[
+        "s3api",
+        "head-object",
+        "--bucket",
+        bucket,
+        "--key",
+        key,
+        "--query",
+        "ETag",
+        "--output",
+        "text",
+    ])
+    .await?;
+    let current_etag = String::from_utf8_lossy(&current_etag).trim().to_string();
+    if etag == Some(current_etag.as_str()) {
+        return Ok(None);
+    }
+
+    let body = run_aws(&["s3", "cp", &format!("s3://{}/{}", bucket, key), "-"]).await?;
+    Ok(Some(RemoteDocument {
+        body,
+        etag: Some(current_etag),
+    }))
+}
+
+async fn run_aws(args: &[&str]) -> Result<Vec<u8>> {
+    let output = Command::new("aws")
+        .args(args)
+        .output()
+        .await
+        .context("Failed to run 'aws'")?;
+    if !output.status.success() {
+        bail!(
+            "'aws {}' failed with {}: {}",
+            args[..2].join(" "),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const CONFIG: &str = r#"{ meta: { version: "1.0" } }"#;
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7; 32])
+    }
+
+    fn public_key(key: &SigningKey) -> String {
+        BASE64.encode(key.verifying_key().to_bytes())
+    }
+
+    #[test]
+    fn test_location_parsing() {
+        assert!(is_remote_url("https://config.example.com/aisopod.toml"));
+        assert!(is_remote_url("s3://fleet/aisopod.json5"));
+        assert!(!is_remote_url("/etc/aisopod/aisopod.toml"));
+
+        assert_eq!(
+            Location::parse("s3://fleet/prod/aisopod.toml").unwrap(),
+            Location::S3 {
+                bucket: "fleet".to_string(),
+                key: "prod/aisopod.toml".to_string()
+            }
+        );
+        assert!(Location::parse("s3://fleet").is_err());
+        assert!(Location::parse("ftp://fleet/aisopod.toml").is_err());
+
+        let source = RemoteConfigSource::new("https://example.com/aisopod.toml?v=2").unwrap();
+        assert_eq!(source.format, ConfigFormat::Toml);
+        assert!(source.with_trusted_key("not-a-key").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_uses_etag() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/aisopod.json5"))
+            .and(header("If-None-Match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/aisopod.json5"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("ETag", "\"v1\"")
+                    .set_body_string(CONFIG),
+            )
+            .mount(&server)
+            .await;
+
+        let source = RemoteConfigSource::new(&format!("{}/aisopod.json5", server.uri())).unwrap();
+        let (config, etag) = source.load().await.unwrap();
+        assert_eq!(config.meta.version, "1.0");
+        assert_eq!(etag.as_deref(), Some("\"v1\""));
+        assert!(source.fetch(etag.as_deref()).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_verifies_signature() {
+        let key = signing_key();
+        let signature = BASE64.encode(key.sign(CONFIG.as_bytes()).to_bytes());
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/aisopod.json5"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(CONFIG))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/aisopod.json5.sig"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(signature))
+            .mount(&server)
+            .await;
+        let url = format!("{}/aisopod.json5", server.uri());
+
+        let trusted = RemoteConfigSource::new(&url)
+            .unwrap()
+            .with_trusted_key(&public_key(&key))
+            .unwrap();
+        assert!(trusted.load().await.is_ok());
+
+        let other = SigningKey::from_bytes(&[9; 32]);
+        let untrusted = RemoteConfigSource::new(&url)
+            .unwrap()
+            .with_trusted_key(&public_key(&other))
+            .unwrap();
+        let err = untrusted.load().await.unwrap_err();
+        assert!(format!("{:#}", err).contains("trusted key"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_requires_signature_with_trusted_keys() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/aisopod.json5"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(CONFIG))
+            .mount(&server)
+            .await;
+
+        let source = RemoteConfigSource::new(&format!("{}/aisopod.json5", server.uri()))
+            .unwrap()
+            .with_trusted_key(&public_key(&signing_key()))
+            .unwrap();
+        assert!(source.load().await.is_err());
+    }
+}
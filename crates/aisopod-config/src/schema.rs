@@ -0,0 +1,88 @@
+//! Configuration JSON Schema module
+//!
+//! This module derives a JSON Schema describing the full configuration from
+//! the configuration types. Editors use it for autocompletion and inline
+//! documentation of JSON5 and TOML configs, and CI pipelines can validate
+//! configs against it before deploying them.
+//!
+//! The schema describes configs after `${VAR}` substitution and `@include`
+//! processing, so configs relying on either should be validated in their
+//! expanded form.
+
+use crate::types::AisopodConfig;
+use anyhow::Result;
+use schemars::Schema;
+
+/// Generate the JSON Schema of the full configuration.
+///
+/// Sensitive fields are marked `writeOnly`.
+///
+/// # Examples
+///
+/// ```
+/// use aisopod_config::config_schema;
+///
+/// let schema = config_schema();
+/// assert_eq!(schema.get("title").unwrap(), "AisopodConfig");
+/// ```
+pub fn config_schema() -> Schema {
+    schemars::schema_for!(AisopodConfig)
+}
+
+/// Generate the JSON Schema of the full configuration as a pretty-printed
+/// JSON string.
+///
+/// # Errors
+///
+/// Returns an error if serialization of the schema fails.
+pub fn config_schema_json() -> Result<String> {
+    Ok(serde_json::to_string_pretty(&config_schema())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn definition<'a>(schema: &'a Value, name: &str) -> &'a Value {
+        &schema["$defs"][name]
+    }
+
+    #[test]
+    fn test_schema_covers_all_sections() {
+        let schema = config_schema().to_value();
+        let properties = schema["properties"].as_object().unwrap();
+        for section in [
+            "meta", "auth", "gateway", "agents", "models", "channels", "tools",
+        ] {
+            assert!(properties.contains_key(section), "missing {}", section);
+        }
+        // Sections with defaults are optional
+        assert!(schema.get("required").is_none());
+    }
+
+    #[test]
+    fn test_sensitive_fields_are_write_only() {
+        let schema = config_schema().to_value();
+        let password = &definition(&schema, "PasswordCredential")["properties"]["password"];
+        assert_eq!(password["type"], "string");
+        assert_eq!(password["writeOnly"], true);
+    }
+
+    #[test]
+    fn test_durations_are_strings() {
+        let schema = config_schema().to_value();
+        let timeout = &definition(&schema, "SandboxConfig")["properties"]["timeout"];
+        assert_eq!(timeout["type"], "string");
+    }
+
+    #[test]
+    fn test_schema_json_is_valid_json() {
+        let json = config_schema_json().unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value["$schema"],
+            "https://json-schema.org/draft/2020-12/schema"
+        );
+    }
+}
@@ -0,0 +1,375 @@
+//! Secrets backend module
+//!
+//! This module resolves references to secrets kept outside configuration
+//! files, so that configs don't have to hold plaintext tokens. A secret-valued
+//! field (see [`Sensitive`](crate::Sensitive)) may hold a reference of the
+//! form `<scheme>:<reference>`, which is resolved by the backend registered
+//! for the scheme when the config is loaded:
+//!
+//! - `keyring:<account>` or `keyring:<service>/<account>` - a password from
+//!   the OS keyring (`secret-tool` on Linux, `security` on macOS). The
+//!   service defaults to `aisopod`.
+//! - `vault:<path>#<key>` - a field of a HashiCorp Vault KV secret, read with
+//!   the `vault` CLI using its usual `VAULT_ADDR`/`VAULT_TOKEN` settings.
+//! - `aws-sm:<name>` or `aws-sm:<name>#<key>` - an AWS Secrets Manager
+//!   secret, or a field of a JSON secret, read with the `aws` CLI using its
+//!   usual credentials.
+//!
+//! Further backends can be added with [`register_secret_backend`]. Values
+//! whose prefix is not a registered scheme are used as they are.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// A source of secrets that config values can reference.
+pub trait SecretBackend: Send + Sync {
+    /// The scheme prefix of references resolved by this backend,
+    /// e.g. `vault`.
+    fn scheme(&self) -> &str;
+
+    /// Resolve a reference, given without the scheme prefix, to the secret
+    /// value.
+    fn resolve(&self, reference: &str) -> Result<String>;
+}
+
+/// Resolves `keyring:` references from the OS keyring.
+#[derive(Debug, Clone, Default)]
+pub struct KeyringBackend;
+
+impl KeyringBackend {
+    fn command(reference: &str) -> Result<(&'static str, Vec<String>)> {
+        let (service, account) = match reference.split_once('/') {
+            Some((service, account)) => (service, account),
+            None => ("aisopod", reference),
+        };
+        if service.is_empty() || account.is_empty() {
+            bail!("Invalid keyring reference '{}'", reference);
+        }
+        check_argument("keyring", service)?;
+        check_argument("keyring", account)?;
+
+        if cfg!(target_os = "macos") {
+            Ok((
+                "security",
+                vec![
+                    "find-generic-password".into(),
+                    "-s".into(),
+                    service.into(),
+                    "-a".into(),
+                    account.into(),
+                    "-w".into(),
+                ],
+            ))
+        } else {
+            Ok((
+                "secret-tool",
+                vec![
+                    "lookup".into(),
+                    "service".into(),
+                    service.into(),
+                    "account".into(),
+                    account.into(),
+                ],
+            ))
+        }
+    }
+}
+
+impl SecretBackend for KeyringBackend {
+    fn scheme(&self) -> &str {
+        "keyring"
+    }
+
+    fn resolve(&self, reference: &str) -> Result<String> {
+        let (program, args) = Self::command(reference)?;
+        run_command(program, &args)
+    }
+}
+
+/// Resolves `vault:` references from HashiCorp Vault.
+#[derive(Debug, Clone, Default)]
+pub struct VaultBackend;
+
+impl VaultBackend {
+    fn command(reference: &str) -> Result<(&'static str, Vec<String>)> {
+        let (path, key) = reference
+            .split_once('#')
+            .filter(|(path, key)| !path.is_empty() && !key.is_empty())
+            .ok_or_else(|| {
+                anyhow!(
+                    "Invalid vault reference '{}', expected 'vault:<path>#<key>'",
+                    reference
+                )
+            })?;
+        check_argument("vault", path)?;
+        Ok((
+            "vault",
+            vec![
+                "kv".into(),
+                "get".into(),
+                format!("-field={}", key),
+                "--".into(),
+                path.into(),
+            ],
+        ))
+    }
+}
+
+impl SecretBackend for VaultBackend {
+    fn scheme(&self) -> &str {
+        "vault"
+    }
+
+    fn resolve(&self, reference: &str) -> Result<String> {
+        let (program, args) = Self::command(reference)?;
+        run_command(program, &args)
+    }
+}
+
+/// Resolves `aws-sm:` references from AWS Secrets Manager.
+#[derive(Debug, Clone, Default)]
+pub struct AwsSecretsManagerBackend;
+
+impl AwsSecretsManagerBackend {
+    fn command(name: &str) -> Result<(&'static str, Vec<String>)> {
+        if name.is_empty() {
+            bail!("Invalid aws-sm reference: missing secret name");
+        }
+        check_argument("aws-sm", name)?;
+        Ok((
+            "aws",
+            vec![
+                "secretsmanager".into(),
+                "get-secret-value".into(),
+                format!("--secret-id={}", name),
+                "--query".into(),
+                "SecretString".into(),
+                "--output".into(),
+                "text".into(),
+            ],
+        ))
+    }
+}
+
+impl SecretBackend for AwsSecretsManagerBackend {
+    fn scheme(&self) -> &str {
+        "aws-sm"
+    }
+
+    fn resolve(&self, reference: &str) -> Result<String> {
+        let (name, key) = match reference.split_once('#') {
+            Some((name, key)) => (name, Some(key)),
+            None => (reference, None),
+        };
+        let (program, args) = Self::command(name)?;
+        let secret = run_command(program, &args)?;
+        match key {
+            Some(key) => json_field(&secret, key)
+                .with_context(|| format!("Failed to read key '{}' of secret '{}'", key, name)),
+            None => Ok(secret),
+        }
+    }
+}
+
+/// Refuse reference parts that a backend CLI would take for an option.
+fn check_argument(scheme: &str, value: &str) -> Result<()> {
+    if value.starts_with('-') {
+        bail!(
+            "Invalid {} reference: '{}' must not start with '-'",
+            scheme,
+            value
+        );
+    }
+    Ok(())
+}
+
+/// Extract a string field from a JSON object secret.
+fn json_field(secret: &str, key: &str) -> Result<String> {
+    let value: Value = serde_json::from_str(secret).context("Secret is not a JSON object")?;
+    match value.get(key) {
+        Some(Value::String(s)) => Ok(s.clone()),
+        Some(other) => Ok(other.to_string()),
+        None => bail!("Secret has no key '{}'", key),
+    }
+}
+
+/// Run a backend CLI and return its output without the trailing newline.
+fn run_command(program: &str, args: &[String]) -> Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run '{}'", program))?;
+    if !output.status.success() {
+        bail!(
+            "'{}' failed with {}: {}",
+            program,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let stdout = String::from_utf8(output.stdout)
+        .with_context(|| format!("'{}' returned a non-UTF-8 secret", program))?;
+    Ok(stdout.trim_end_matches(['\r', '\n']).to_string())
+}
+
+type Registry = RwLock<HashMap<String, Arc<dyn SecretBackend>>>;
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let backends: [Arc<dyn SecretBackend>; 3] = [
+            Arc::new(KeyringBackend),
+            Arc::new(VaultBackend),
+            Arc::new(AwsSecretsManagerBackend),
+        ];
+        RwLock::new(
+            backends
+                .into_iter()
+                .map(|backend| (backend.scheme().to_string(), backend))
+                .collect(),
+        )
+    })
+}
+
+/// Register a secrets backend, replacing any backend with the same scheme.
+///
+/// Backends must be registered before the configs referencing them are
+/// loaded.
+pub fn register_secret_backend(backend: Arc<dyn SecretBackend>) {
+    let mut backends = registry().write().unwrap_or_else(|e| e.into_inner());
+    backends.insert(backend.scheme().to_string(), backend);
+}
+
+/// Whether a value references a secret of a registered backend.
+///
+/// Unlike [`resolve_secret`], this does not contact the backend.
+pub fn is_secret_reference(value: &str) -> bool {
+    value.split_once(':').is_some_and(|(scheme, _)| {
+        let backends = registry().read().unwrap_or_else(|e| e.into_inner());
+        backends.contains_key(scheme)
+    })
+}
+
+/// Resolve a value if it references a secret of a registered backend.
+///
+/// # Returns
+///
+/// * `Ok(Some(secret))` - The value was a reference and was resolved
+/// * `Ok(None)` - The value is not a secret reference
+///
+/// # Errors
+///
+/// Returns an error if the value is a reference and the backend fails to
+/// resolve it.
+///
+/// # Examples
+///
+/// ```
+/// use aisopod_config::secrets::resolve_secret;
+///
+/// assert_eq!(resolve_secret("sk-plaintext").unwrap(), None);
+/// ```
+pub fn resolve_secret(value: &str) -> Result<Option<String>> {
+    let Some((scheme, reference)) = value.split_once(':') else {
+        return Ok(None);
+    };
+    let backend = {
+        let backends = registry().read().unwrap_or_else(|e| e.into_inner());
+        match backends.get(scheme) {
+            Some(backend) => backend.clone(),
+            None => return Ok(None),
+        }
+    };
+    backend
+        .resolve(reference)
+        .map(Some)
+        .with_context(|| format!("Failed to resolve secret reference '{}'", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StaticBackend;
+
+    impl SecretBackend for StaticBackend {
+        fn scheme(&self) -> &str {
+            "test-static"
+        }
+
+        fn resolve(&self, reference: &str) -> Result<String> {
+            match reference {
+                "token" => Ok("s3cr3t".to_string()),
+                _ => bail!("no such secret"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_resolve_registered_backend() {
+        register_secret_backend(Arc::new(StaticBackend));
+        assert_eq!(
+            resolve_secret("test-static:token").unwrap(),
+            Some("s3cr3t".to_string())
+        );
+        let err = resolve_secret("test-static:missing").unwrap_err();
+        assert!(err.to_string().contains("test-static:missing"));
+    }
+
+    #[test]
+    fn test_is_secret_reference() {
+        register_secret_backend(Arc::new(StaticBackend));
+        assert!(is_secret_reference("test-static:missing"));
+        assert!(!is_secret_reference("plain-token"));
+        assert!(!is_secret_reference("unknown:value"));
+    }
+
+    #[test]
+    fn test_non_references_are_kept() {
+        assert_eq!(resolve_secret("plain-token").unwrap(), None);
+        assert_eq!(resolve_secret("https://example.com").unwrap(), None);
+        assert_eq!(resolve_secret("unknown:value").unwrap(), None);
+    }
+
+    #[test]
+    fn test_vault_command() {
+        let (program, args) = VaultBackend::command("secret/aisopod#api_key").unwrap();
+        assert_eq!(program, "vault");
+        assert_eq!(
+            args,
+            ["kv", "get", "-field=api_key", "--", "secret/aisopod"]
+        );
+        assert!(VaultBackend::command("secret/aisopod").is_err());
+        assert!(VaultBackend::command("#api_key").is_err());
+        assert!(VaultBackend::command("-address=https://attacker.example#x").is_err());
+    }
+
+    #[test]
+    fn test_keyring_command() {
+        let (_, args) = KeyringBackend::command("telegram").unwrap();
+        assert!(args.contains(&"aisopod".to_string()));
+        assert!(args.contains(&"telegram".to_string()));
+        let (_, args) = KeyringBackend::command("work/slack").unwrap();
+        assert!(args.contains(&"work".to_string()));
+        assert!(args.contains(&"slack".to_string()));
+        assert!(KeyringBackend::command("work/").is_err());
+        assert!(KeyringBackend::command("-x/slack").is_err());
+        assert!(KeyringBackend::command("work/-x").is_err());
+    }
+
+    #[test]
+    fn test_aws_json_field() {
+        let secret = r#"{"token": "abc", "port": 8080}"#;
+        assert_eq!(json_field(secret, "token").unwrap(), "abc");
+        assert_eq!(json_field(secret, "port").unwrap(), "8080");
+        assert!(json_field(secret, "missing").is_err());
+        assert!(json_field("plain", "token").is_err());
+        assert!(AwsSecretsManagerBackend::command("").is_err());
+        assert!(AwsSecretsManagerBackend::command("--endpoint-url=https://x").is_err());
+        let (_, args) = AwsSecretsManagerBackend::command("prod/aisopod").unwrap();
+        assert!(args.contains(&"--secret-id=prod/aisopod".to_string()));
+    }
+}
@@ -1,24 +1,73 @@
+use crate::secrets;
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde::de::{DeserializeOwned, Error as _};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::cell::Cell;
 use std::fmt;
 
+thread_local! {
+    static UNRESOLVED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `f` with secrets left unresolved and unrevealed on this thread.
+///
+/// While `f` runs, deserializing a [`Sensitive`] keeps a secret reference as
+/// it is, without contacting its backend, and serializing one writes its
+/// reference, or the redaction marker for a plaintext secret. Configs
+/// compared this way differ in their secrets only by reference, which makes
+/// it safe to parse and compare configs from untrusted callers.
+pub fn with_secrets_unresolved<R>(f: impl FnOnce() -> R) -> R {
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            UNRESOLVED.with(|flag| flag.set(self.0));
+        }
+    }
+
+    let _restore = Restore(UNRESOLVED.with(|flag| flag.replace(true)));
+    f()
+}
+
+fn secrets_unresolved() -> bool {
+    UNRESOLVED.with(Cell::get)
+}
+
 /// A wrapper that redacts its contents in Display and Debug output.
 ///
 /// This type is used to wrap sensitive values like API keys, tokens, and passwords
 /// to prevent accidental exposure in logs, error messages, and debug output.
+///
+/// When deserialized from a secret reference such as `vault:secret/aisopod#token`
+/// (see [`crate::secrets`]), the referenced secret is resolved and the reference is
+/// kept, so that serializing the config writes back the reference, not the secret.
 #[derive(Clone, Default)]
-pub struct Sensitive<T>(T);
+pub struct Sensitive<T> {
+    value: T,
+    reference: Option<String>,
+}
 
 impl<T> Sensitive<T> {
     /// Create a new Sensitive wrapper around a value.
     pub fn new(value: T) -> Self {
-        Self(value)
+        Self {
+            value,
+            reference: None,
+        }
     }
 
     /// Access the inner value.
     ///
     /// Use sparingly and only when the actual value is needed (e.g., for making API calls).
     pub fn expose(&self) -> &T {
-        &self.0
+        &self.value
+    }
+
+    /// The secret reference the value was resolved from, if any.
+    pub fn reference(&self) -> Option<&str> {
+        self.reference.as_deref()
     }
 
     /// Produce a redacted display string, suitable for UI.
@@ -41,13 +90,66 @@ impl<T> fmt::Debug for Sensitive<T> {
 
 impl<T: Serialize> Serialize for Sensitive<T> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        self.0.serialize(serializer)
+        match &self.reference {
+            Some(reference) => reference.serialize(serializer),
+            None if secrets_unresolved() => Self::redacted_display().serialize(serializer),
+            None => self.value.serialize(serializer),
+        }
     }
 }
 
-impl<'de, T: Deserialize<'de>> Deserialize<'de> for Sensitive<T> {
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Sensitive<T> {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        T::deserialize(deserializer).map(Sensitive)
+        let value = Value::deserialize(deserializer)?;
+        if let Value::String(reference) = &value {
+            if secrets_unresolved() {
+                if !secrets::is_secret_reference(reference) {
+                    return T::deserialize(value)
+                        .map(Sensitive::new)
+                        .map_err(D::Error::custom);
+                }
+                let value = T::deserialize(value.clone()).map_err(D::Error::custom)?;
+                return Ok(Self {
+                    value,
+                    reference: Some(reference.clone()),
+                });
+            }
+            if let Some(secret) = secrets::resolve_secret(reference).map_err(|e| {
+                // Include the causes, which hold the backend's error
+                D::Error::custom(format!("{:#}", e))
+            })? {
+                let value = T::deserialize(Value::String(secret)).map_err(D::Error::custom)?;
+                return Ok(Self {
+                    value,
+                    reference: Some(reference.clone()),
+                });
+            }
+        }
+        T::deserialize(value)
+            .map(Sensitive::new)
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Sensitive values have the schema of their contents, marked `writeOnly`
+/// so that editors and documentation generators treat them as secrets.
+impl<T: JsonSchema> JsonSchema for Sensitive<T> {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        T::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        format!("Sensitive<{}>", T::schema_id()).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let mut schema = generator.subschema_for::<T>();
+        schema.insert("writeOnly".to_string(), true.into());
+        schema
     }
 }
 
@@ -95,4 +197,68 @@ mod tests {
         assert_eq!(format!("{:?}", config), "Sensitive(***REDACTED***)");
         assert_eq!(config.expose(), "secret-value");
     }
+
+    #[test]
+    fn test_secret_reference_is_resolved_and_kept() {
+        struct TestBackend;
+
+        impl secrets::SecretBackend for TestBackend {
+            fn scheme(&self) -> &str {
+                "test-sensitive"
+            }
+
+            fn resolve(&self, reference: &str) -> anyhow::Result<String> {
+                Ok(format!("resolved-{}", reference))
+            }
+        }
+
+        secrets::register_secret_backend(std::sync::Arc::new(TestBackend));
+        let secret: Sensitive<String> =
+            serde_json::from_str("\"test-sensitive:bot-token\"").unwrap();
+        assert_eq!(secret.expose(), "resolved-bot-token");
+        assert_eq!(secret.reference(), Some("test-sensitive:bot-token"));
+        // Saving the config writes the reference, not the secret
+        assert_eq!(
+            serde_json::to_string(&secret).unwrap(),
+            "\"test-sensitive:bot-token\""
+        );
+        assert_eq!(Sensitive::new("x".to_string()).reference(), None);
+    }
+
+    #[test]
+    fn test_unresolved_secrets_are_not_resolved_or_revealed() {
+        struct FailingBackend;
+
+        impl secrets::SecretBackend for FailingBackend {
+            fn scheme(&self) -> &str {
+                "test-unresolved"
+            }
+
+            fn resolve(&self, _reference: &str) -> anyhow::Result<String> {
+                anyhow::bail!("backend must not be contacted")
+            }
+        }
+
+        secrets::register_secret_backend(std::sync::Arc::new(FailingBackend));
+        assert!(serde_json::from_str::<Sensitive<String>>("\"test-unresolved:token\"").is_err());
+
+        with_secrets_unresolved(|| {
+            let secret: Sensitive<String> =
+                serde_json::from_str("\"test-unresolved:token\"").unwrap();
+            assert_eq!(secret.reference(), Some("test-unresolved:token"));
+            assert_eq!(
+                serde_json::to_string(&secret).unwrap(),
+                "\"test-unresolved:token\""
+            );
+            let plaintext = Sensitive::new("my-api-key".to_string());
+            assert_eq!(
+                serde_json::to_string(&plaintext).unwrap(),
+                "\"***REDACTED***\""
+            );
+        });
+        assert_eq!(
+            serde_json::to_string(&Sensitive::new("my-api-key".to_string())).unwrap(),
+            "\"my-api-key\""
+        );
+    }
 }
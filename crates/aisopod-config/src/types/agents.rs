@@ -1,7 +1,9 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Agents configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct AgentsConfig {
     /// List of agents
     #[serde(default)]
@@ -9,10 +11,21 @@ pub struct AgentsConfig {
     /// Default agent configuration
     #[serde(default)]
     pub default: AgentDefaults,
+    /// Scheduled agent runs
+    #[serde(default)]
+    pub schedules: Vec<AgentSchedule>,
+    /// Path of the SQLite database keeping scheduled jobs; in memory when
+    /// empty
+    #[serde(default)]
+    pub scheduler_path: String,
+    /// Named system prompt templates, referenced by an agent's
+    /// `prompt_template`. Templates may use `{{variable}}` placeholders
+    #[serde(default)]
+    pub prompt_templates: HashMap<String, String>,
 }
 
 /// Agent definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Agent {
     /// Agent ID
     pub id: String,
@@ -43,6 +56,32 @@ pub struct Agent {
     /// List of skill IDs to assign to this agent
     #[serde(default)]
     pub skills: Vec<String>,
+    /// Stream the model's reasoning (chain-of-thought) tokens as events.
+    /// When disabled, reasoning is only logged at debug level.
+    #[serde(default)]
+    pub show_reasoning: bool,
+    /// JSON schema the agent's final response must conform to.
+    /// When set, the response is parsed as JSON and validated against it.
+    #[serde(default)]
+    pub output_schema: Option<serde_json::Value>,
+    /// Maximum number of times the model is re-prompted to repair a
+    /// response that does not match `output_schema` (default: 2)
+    #[serde(default = "default_max_output_repairs")]
+    pub max_output_repairs: usize,
+    /// Draft-and-verify mode, where one model drafts the responses and
+    /// another reviews them before they are sent
+    #[serde(default)]
+    pub draft_verify: Option<DraftVerifyConfig>,
+    /// Name of the prompt template used as the base of the system prompt,
+    /// from `agents.prompt_templates` or the agent's skills. The template
+    /// replaces `system_prompt` when set
+    #[serde(default)]
+    pub prompt_template: Option<String>,
+    /// Overrides of system prompt sections by label, e.g. `Tools`. The
+    /// content replaces the section's, an empty content removes the section,
+    /// and labels of no built-in section are added as new sections
+    #[serde(default)]
+    pub prompt_sections: HashMap<String, String>,
 }
 
 /// Default maximum depth for subagent spawning
@@ -50,8 +89,82 @@ fn default_max_subagent_depth() -> usize {
     3
 }
 
+/// Default maximum number of structured output repairs
+fn default_max_output_repairs() -> usize {
+    2
+}
+
+/// Draft-and-verify configuration
+///
+/// The draft model runs the agent loop, tool calls included, and its final
+/// response is reviewed by the verify model before it is sent. A cheap
+/// draft model with a stronger verify model saves cost on simple turns; a
+/// strong draft model with a cheap verify model adds a critique pass
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DraftVerifyConfig {
+    /// Model writing the drafts
+    pub draft_model: String,
+    /// Model reviewing the drafts
+    pub verify_model: String,
+    /// How the verify model reviews drafts
+    #[serde(default)]
+    pub mode: VerifyMode,
+    /// Maximum number of revisions asked for in critique mode (default: 1)
+    #[serde(default = "default_max_revisions")]
+    pub max_revisions: usize,
+}
+
+/// Default maximum number of draft revisions
+fn default_max_revisions() -> usize {
+    1
+}
+
+/// How drafts are reviewed in draft-and-verify mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VerifyMode {
+    /// The verify model approves the draft or replies with a corrected
+    /// response, which is sent instead
+    #[default]
+    Edit,
+    /// The verify model approves the draft or critiques it, and the draft
+    /// model revises its response
+    Critique,
+}
+
+/// Scheduled agent run
+///
+/// Runs the agent headlessly with `prompt` whenever `cron` fires, and
+/// sends the reply to `deliver` when set
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AgentSchedule {
+    /// Schedule ID
+    pub id: String,
+    /// Agent to run
+    pub agent_id: String,
+    /// Cron expression with seconds, e.g. `0 0 9 * * Mon-Fri`
+    pub cron: String,
+    /// Prompt the agent is run with
+    pub prompt: String,
+    /// Where the agent's reply is sent
+    #[serde(default)]
+    pub deliver: Option<ScheduleDelivery>,
+}
+
+/// Delivery target of a scheduled run's reply
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScheduleDelivery {
+    /// Channel the reply is sent through
+    pub channel: String,
+    /// Channel account to send from; the channel's first account when unset
+    #[serde(default)]
+    pub account: Option<String>,
+    /// Peer, written as `[user|group|channel|thread:]id`
+    pub peer: String,
+}
+
 /// Default agent configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct AgentDefaults {
     /// Default model
     #[serde(default)]
@@ -77,6 +190,12 @@ impl Default for Agent {
             max_subagent_depth: default_max_subagent_depth(),
             subagent_allowed_models: None,
             skills: Vec::new(),
+            show_reasoning: false,
+            output_schema: None,
+            max_output_repairs: default_max_output_repairs(),
+            draft_verify: None,
+            prompt_template: None,
+            prompt_sections: HashMap::new(),
         }
     }
 }
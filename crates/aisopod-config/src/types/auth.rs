@@ -1,21 +1,24 @@
 use crate::sensitive::Sensitive;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Authentication mode for gateway
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum AuthMode {
     /// Token-based authentication (Bearer token)
     Token,
     /// Password-based authentication (HTTP Basic)
     Password,
+    /// OpenID Connect ID or access tokens (Bearer JWT)
+    Oidc,
     /// No authentication required
     #[default]
     None,
 }
 
 /// Authentication configuration for the gateway
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AuthConfig {
     /// API keys for external services
     #[serde(default)]
@@ -32,10 +35,13 @@ pub struct AuthConfig {
     /// Password-based credentials (username -> role + scopes)
     #[serde(default)]
     pub passwords: Vec<PasswordCredential>,
+    /// OpenID Connect provider for the `oidc` mode
+    #[serde(default)]
+    pub oidc: OidcConfig,
 }
 
 /// Token credential
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct TokenCredential {
     /// The token value
     pub token: String,
@@ -47,7 +53,7 @@ pub struct TokenCredential {
 }
 
 /// Password credential
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct PasswordCredential {
     /// Username
     pub username: String,
@@ -60,8 +66,80 @@ pub struct PasswordCredential {
     pub scopes: Vec<String>,
 }
 
+/// OpenID Connect token validation
+///
+/// Bearer tokens must be JWTs signed by one of the issuer's keys, which are
+/// found through its discovery document and cached. The principal's role
+/// and scopes are read from claims; claim names may be dotted paths into
+/// nested claims, e.g. `realm_access.roles`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OidcConfig {
+    /// Issuer URL, matched against the `iss` claim
+    #[serde(default)]
+    pub issuer: String,
+    /// Accepted audiences; a token's `aud` claim must contain one of them.
+    /// Required, so that tokens the issuer signed for other clients are
+    /// rejected
+    #[serde(default)]
+    pub audiences: Vec<String>,
+    /// JWKS URL, instead of the one from the issuer's discovery document
+    #[serde(default)]
+    pub jwks_uri: Option<String>,
+    /// How long fetched signing keys are cached, in seconds (default: 3600)
+    #[serde(default = "default_jwks_cache_ttl")]
+    pub jwks_cache_ttl: u64,
+    /// Claim holding the principal's role; the first entry if it is a list
+    #[serde(default = "default_role_claim")]
+    pub role_claim: String,
+    /// Role of principals whose token has no role claim
+    #[serde(default = "default_oidc_role")]
+    pub default_role: String,
+    /// Claim holding the principal's scopes, as a space-separated string
+    /// or a list
+    #[serde(default = "default_scopes_claim")]
+    pub scopes_claim: String,
+    /// Tolerated clock skew when checking expiry, in seconds (default: 60)
+    #[serde(default = "default_leeway")]
+    pub leeway: u64,
+}
+
+impl Default for OidcConfig {
+    fn default() -> Self {
+        Self {
+            issuer: String::new(),
+            audiences: Vec::new(),
+            jwks_uri: None,
+            jwks_cache_ttl: default_jwks_cache_ttl(),
+            role_claim: default_role_claim(),
+            default_role: default_oidc_role(),
+            scopes_claim: default_scopes_claim(),
+            leeway: default_leeway(),
+        }
+    }
+}
+
+fn default_jwks_cache_ttl() -> u64 {
+    3600
+}
+
+fn default_role_claim() -> String {
+    "role".to_string()
+}
+
+fn default_oidc_role() -> String {
+    "operator".to_string()
+}
+
+fn default_scopes_claim() -> String {
+    "scope".to_string()
+}
+
+fn default_leeway() -> u64 {
+    60
+}
+
 /// Authentication profile
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct AuthProfile {
     /// Profile name
     pub name: String,
@@ -84,6 +162,7 @@ impl Default for AuthConfig {
             gateway_mode: AuthMode::None,
             tokens: Vec::new(),
             passwords: Vec::new(),
+            oidc: OidcConfig::default(),
         }
     }
 }
@@ -1,8 +1,9 @@
 use crate::sensitive::Sensitive;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Channels configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ChannelsConfig {
     /// Channel definitions
     #[serde(default)]
@@ -42,22 +43,29 @@ pub struct ChannelsConfig {
     pub msteams: MsTeamsConfig,
 }
 
+impl ChannelsConfig {
+    /// Returns the channel definitions that are enabled.
+    pub fn enabled_channels(&self) -> impl Iterator<Item = &Channel> {
+        self.channels.iter().filter(|channel| channel.enabled)
+    }
+}
+
 /// Generic channel configuration for platforms with simple token auth
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct GenericChannelConfig {
     /// API token
     pub token: Option<Sensitive<String>>,
 }
 
 /// Telegram bot configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct TelegramConfig {
     /// Telegram bot token
     pub token: Option<Sensitive<String>>,
 }
 
 /// Discord bot configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct DiscordConfig {
     /// Discord bot token
     pub token: Option<Sensitive<String>>,
@@ -67,7 +75,7 @@ pub struct DiscordConfig {
 }
 
 /// WhatsApp Business API configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct WhatsappConfig {
     /// Access token
     pub access_token: Option<Sensitive<String>>,
@@ -77,7 +85,7 @@ pub struct WhatsappConfig {
 }
 
 /// Slack workspace configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct SlackConfig {
     /// Bot token (starts with xoxb-)
     pub token: Option<Sensitive<String>>,
@@ -93,7 +101,7 @@ pub struct SlackConfig {
 }
 
 /// Matrix room configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct MatrixConfig {
     /// Access token
     pub access_token: Option<Sensitive<String>>,
@@ -106,7 +114,7 @@ pub struct MatrixConfig {
 }
 
 /// Microsoft Teams-specific configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct MsTeamsConfig {
     /// Azure AD tenant ID
     pub tenant_id: Option<Sensitive<String>>,
@@ -123,7 +131,7 @@ pub struct MsTeamsConfig {
 }
 
 /// Channel definition
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Channel {
     /// Channel ID
     pub id: String,
@@ -133,13 +141,33 @@ pub struct Channel {
     /// Channel type
     #[serde(default)]
     pub channel_type: String,
+    /// Whether the channel is enabled, so that profiles can turn single
+    /// channels off
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
     /// Connection settings
     #[serde(default)]
     pub connection: ChannelConnection,
 }
 
+impl Default for Channel {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: String::new(),
+            channel_type: String::new(),
+            enabled: default_enabled(),
+            connection: ChannelConnection::default(),
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
 /// Channel connection settings
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ChannelConnection {
     /// Connection string or endpoint
     #[serde(default)]
@@ -150,7 +178,7 @@ pub struct ChannelConnection {
 }
 
 /// Default channel settings
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ChannelDefaults {
     /// Default channel type
     #[serde(default)]
@@ -1,7 +1,8 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Environment configuration for variable mappings
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct EnvConfig {
     /// Environment variable mappings
     #[serde(default)]
@@ -9,7 +10,7 @@ pub struct EnvConfig {
 }
 
 /// Environment variable mapping
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct EnvMapping {
     /// Environment variable name
     pub name: String,
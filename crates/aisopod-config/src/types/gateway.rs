@@ -1,7 +1,9 @@
+use crate::sensitive::Sensitive;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Gateway configuration for HTTP server
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GatewayConfig {
     /// HTTP server settings
     #[serde(default)]
@@ -27,6 +29,12 @@ pub struct GatewayConfig {
     /// Pairing cleanup interval in seconds
     #[serde(default = "default_pairing_cleanup_interval")]
     pub pairing_cleanup_interval: u64,
+    /// OpenTelemetry trace export
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    /// Multi-node clustering
+    #[serde(default)]
+    pub cluster: ClusterConfig,
 }
 
 impl Default for GatewayConfig {
@@ -40,12 +48,14 @@ impl Default for GatewayConfig {
             rate_limit: RateLimitConfig::default(),
             request_size_limits: RequestSizeLimitsConfig::default(),
             pairing_cleanup_interval: default_pairing_cleanup_interval(),
+            telemetry: TelemetryConfig::default(),
+            cluster: ClusterConfig::default(),
         }
     }
 }
 
 /// HTTP server configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ServerConfig {
     /// Server name
     #[serde(default)]
@@ -69,7 +79,7 @@ impl Default for ServerConfig {
 }
 
 /// Bind address configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BindConfig {
     /// IP address to bind to
     #[serde(default = "default_bind_address")]
@@ -93,7 +103,7 @@ fn default_bind_address() -> String {
 }
 
 /// TLS configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct TlsConfig {
     /// Enable TLS
     #[serde(default)]
@@ -104,6 +114,37 @@ pub struct TlsConfig {
     /// Private key file path
     #[serde(default)]
     pub key_path: String,
+    /// Client certificate (mutual TLS) authentication
+    #[serde(default)]
+    pub client_auth: ClientAuthConfig,
+}
+
+/// Client certificate (mutual TLS) authentication configuration
+///
+/// When enabled, clients must present a certificate chaining to one of the
+/// CAs in `ca_path` during the TLS handshake, before any HTTP request is
+/// processed.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct ClientAuthConfig {
+    /// Verify client certificates
+    #[serde(default)]
+    pub enabled: bool,
+    /// PEM bundle of the CAs client certificates must chain to
+    #[serde(default)]
+    pub ca_path: String,
+    /// Also accept clients presenting no certificate; certificates that are
+    /// presented are still verified
+    #[serde(default)]
+    pub optional: bool,
+    /// Common names or DNS subject alternative names allowed to connect,
+    /// e.g. `device-42.fleet.example.com` or `*.fleet.example.com`; any
+    /// certificate issued by the CAs when empty
+    #[serde(default)]
+    pub allowed_names: Vec<String>,
+    /// PEM files of certificate revocation lists to check client
+    /// certificates against
+    #[serde(default)]
+    pub crl_paths: Vec<String>,
 }
 
 fn default_port() -> u16 {
@@ -115,7 +156,7 @@ fn default_handshake_timeout() -> u64 {
 }
 
 /// Web UI configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WebUiConfig {
     /// Enable static file serving for the web UI
     #[serde(default = "default_enabled")]
@@ -126,6 +167,9 @@ pub struct WebUiConfig {
     /// Allowed origins for CORS headers
     #[serde(default = "default_cors_origins")]
     pub cors_origins: Vec<String>,
+    /// Serve the embedded admin UI at `/admin`
+    #[serde(default = "default_enabled")]
+    pub admin: bool,
 }
 
 impl Default for WebUiConfig {
@@ -134,6 +178,7 @@ impl Default for WebUiConfig {
             enabled: default_enabled(),
             dist_path: default_dist_path(),
             cors_origins: default_cors_origins(),
+            admin: default_enabled(),
         }
     }
 }
@@ -154,14 +199,23 @@ fn default_cors_origins() -> Vec<String> {
 }
 
 /// Rate limiting configuration for the gateway
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RateLimitConfig {
     /// Maximum number of requests allowed in the window
     #[serde(default = "default_max_requests")]
     pub max_requests: u64,
-    /// Sliding window duration in seconds
+    /// Window duration in seconds; clients regain `max_requests` requests
+    /// per window
     #[serde(default = "default_window")]
     pub window: u64,
+    /// Requests a client may make at once before being held to the steady
+    /// rate; `max_requests` when unset
+    #[serde(default)]
+    pub burst: Option<u64>,
+    /// Limits per bearer token, applied in addition to the per-IP limits so
+    /// that a token shared across many addresses is still held in check
+    #[serde(default)]
+    pub per_token: Option<TokenRateLimitConfig>,
 }
 
 impl Default for RateLimitConfig {
@@ -169,6 +223,32 @@ impl Default for RateLimitConfig {
         Self {
             max_requests: default_max_requests(),
             window: default_window(),
+            burst: None,
+            per_token: None,
+        }
+    }
+}
+
+/// Rate limits applied per bearer token
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TokenRateLimitConfig {
+    /// Maximum number of requests allowed in the window
+    #[serde(default = "default_max_requests")]
+    pub max_requests: u64,
+    /// Window duration in seconds
+    #[serde(default = "default_window")]
+    pub window: u64,
+    /// Requests a token may make at once; `max_requests` when unset
+    #[serde(default)]
+    pub burst: Option<u64>,
+}
+
+impl Default for TokenRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_requests: default_max_requests(),
+            window: default_window(),
+            burst: None,
         }
     }
 }
@@ -182,7 +262,7 @@ fn default_window() -> u64 {
 }
 
 /// Request size limits configuration for security
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RequestSizeLimitsConfig {
     /// Maximum size of request body in bytes (default: 10MB)
     #[serde(default = "default_max_body_size")]
@@ -193,6 +273,11 @@ pub struct RequestSizeLimitsConfig {
     /// Maximum number of headers (default: 100)
     #[serde(default = "default_max_headers_count")]
     pub max_headers_count: usize,
+    /// Seconds a client may take to send a request body before the request
+    /// is rejected, so slow clients cannot hold connections open (default:
+    /// 30, 0 disables)
+    #[serde(default = "default_body_timeout")]
+    pub body_timeout: u64,
 }
 
 impl Default for RequestSizeLimitsConfig {
@@ -201,6 +286,7 @@ impl Default for RequestSizeLimitsConfig {
             max_body_size: default_max_body_size(),
             max_headers_size: default_max_headers_size(),
             max_headers_count: default_max_headers_count(),
+            body_timeout: default_body_timeout(),
         }
     }
 }
@@ -217,6 +303,91 @@ fn default_max_headers_count() -> usize {
     100
 }
 
+fn default_body_timeout() -> u64 {
+    30
+}
+
 fn default_pairing_cleanup_interval() -> u64 {
     300  // 5 minutes
 }
+
+/// OpenTelemetry trace export configuration
+///
+/// When enabled, spans of gateway requests, RPC dispatch, agent runs,
+/// provider calls and tool executions are exported over OTLP/HTTP.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TelemetryConfig {
+    /// Enable trace export
+    #[serde(default)]
+    pub enabled: bool,
+    /// OTLP/HTTP traces endpoint of the collector
+    #[serde(default = "default_otlp_endpoint")]
+    pub endpoint: String,
+    /// Service name reported on every span
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+    /// Fraction of traces to sample, from 0.0 to 1.0 (default: 1.0)
+    #[serde(default = "default_sample_ratio")]
+    pub sample_ratio: f64,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: default_otlp_endpoint(),
+            service_name: default_service_name(),
+            sample_ratio: default_sample_ratio(),
+        }
+    }
+}
+
+fn default_otlp_endpoint() -> String {
+    "http://localhost:4318/v1/traces".to_string()
+}
+
+fn default_service_name() -> String {
+    "aisopod".to_string()
+}
+
+fn default_sample_ratio() -> f64 {
+    1.0
+}
+
+/// Multi-node clustering configuration
+///
+/// Gateway instances with the same Redis URL and channel form a cluster:
+/// events broadcast on one node reach the WebSocket clients of every node,
+/// and pending approvals are kept in Redis so any node can list and decide
+/// them. Requires the gateway's `cluster` feature.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ClusterConfig {
+    /// Join a cluster
+    #[serde(default)]
+    pub enabled: bool,
+    /// Name of this node, unique in the cluster; generated when empty
+    #[serde(default)]
+    pub node_id: String,
+    /// Redis connection URL, e.g. `redis://127.0.0.1:6379/0`, or
+    /// `memory://<name>` for nodes running in the same process
+    #[serde(default)]
+    pub redis_url: Sensitive<String>,
+    /// Redis pub/sub channel, also the prefix of the keys the cluster keeps
+    #[serde(default = "default_cluster_channel")]
+    pub channel: String,
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            node_id: String::new(),
+            redis_url: Sensitive::default(),
+            channel: default_cluster_channel(),
+        }
+    }
+}
+
+fn default_cluster_channel() -> String {
+    "aisopod:cluster".to_string()
+}
@@ -1,7 +1,9 @@
+use crate::sensitive::Sensitive;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Memory configuration for QMD memory system
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct MemoryConfig {
     /// Memory backend configuration
     #[serde(default)]
@@ -12,21 +14,25 @@ pub struct MemoryConfig {
 }
 
 /// Memory backend configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct MemoryBackend {
-    /// Backend type
+    /// Backend type: `sqlite`, `lancedb`, `postgres` or `qdrant`
     #[serde(default)]
     pub r#type: String,
-    /// Connection string
+    /// Connection string: a file path for `sqlite` and `lancedb`, a URL for
+    /// `postgres` and `qdrant`
     #[serde(default)]
     pub connection: String,
-    /// Database name
+    /// Database name; the collection name for `qdrant`
     #[serde(default)]
     pub database: String,
+    /// API key for backends that require one
+    #[serde(default)]
+    pub api_key: Option<Sensitive<String>>,
 }
 
 /// Memory settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MemorySettings {
     /// Default memory limit in MB
     #[serde(default)]
@@ -3,6 +3,7 @@
 //! This module defines all configuration types for the aisopod application.
 //! Each sub-module defines a specific configuration area.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 mod agents;
@@ -22,9 +23,14 @@ mod sandbox;
 
 pub use agents::Agent;
 pub use agents::AgentDefaults;
+pub use agents::AgentSchedule;
 pub use agents::AgentsConfig;
+pub use agents::DraftVerifyConfig;
+pub use agents::ScheduleDelivery;
+pub use agents::VerifyMode;
 pub use auth::AuthConfig;
 pub use auth::AuthMode;
+pub use auth::OidcConfig;
 pub use auth::AuthProfile;
 pub use auth::PasswordCredential;
 pub use auth::TokenCredential;
@@ -34,31 +40,56 @@ pub use channels::ChannelConnection;
 pub use channels::ChannelsConfig;
 pub use env::EnvConfig;
 pub use gateway::BindConfig;
+pub use gateway::ClusterConfig;
 pub use gateway::GatewayConfig;
 pub use gateway::RateLimitConfig;
 pub use gateway::RequestSizeLimitsConfig;
+pub use gateway::TokenRateLimitConfig;
 pub use gateway::ServerConfig;
+pub use gateway::TelemetryConfig;
 pub use gateway::TlsConfig;
+pub use gateway::ClientAuthConfig;
 pub use gateway::WebUiConfig;
 pub use memory::MemoryConfig;
 pub use meta::MetaConfig;
+pub use models::FailoverPolicy;
+pub use models::FailoverRule;
 pub use models::Model;
 pub use models::ModelFallback;
+pub use models::ModelPrice;
 pub use models::ModelProvider;
 pub use models::ModelsConfig;
+pub use models::ProviderRouting;
+pub use models::WireLogConfig;
+pub use plugins::PluginEntry;
 pub use plugins::PluginsConfig;
+pub use session::BudgetConfig;
+pub use session::BudgetNotifyConfig;
 pub use session::CompactionConfig;
+pub use session::EncryptionConfig;
+pub use session::FollowUpPolicy;
 pub use session::MessageConfig;
+pub use session::RetentionConfig;
 pub use session::SessionConfig;
+pub use session::SessionBackendKind;
+pub use session::SessionStorageConfig;
 pub use skills::SkillsConfig;
-pub use tools::ToolsConfig;
+pub use tools::{
+    ApprovalConfig, DocsToolConfig, HttpToolConfig, LoopGuardAction, LoopGuardConfig, McpConfig,
+    McpExportConfig, McpServerConfig, McpTransportKind, SqlDatabaseConfig, SqlDriver,
+    SqlToolConfig, ToolQuotaConfig, ToolQuotasConfig, ToolsConfig, WebSearchBackend,
+    WebSearchToolConfig,
+};
 pub use sandbox::SandboxConfig;
 pub use sandbox::SandboxRuntime;
 pub use sandbox::WorkspaceAccess;
 
 /// Root configuration struct that composes all configuration types
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AisopodConfig {
+    /// Version of the config file layout, see [`crate::migration`]
+    #[serde(default = "default_config_version")]
+    pub config_version: u32,
     /// Metadata configuration
     #[serde(default)]
     pub meta: MetaConfig,
@@ -99,3 +130,28 @@ pub struct AisopodConfig {
     #[serde(default)]
     pub gateway: GatewayConfig,
 }
+
+impl Default for AisopodConfig {
+    fn default() -> Self {
+        Self {
+            config_version: default_config_version(),
+            meta: MetaConfig::default(),
+            auth: AuthConfig::default(),
+            env: EnvConfig::default(),
+            agents: AgentsConfig::default(),
+            models: ModelsConfig::default(),
+            channels: ChannelsConfig::default(),
+            tools: ToolsConfig::default(),
+            skills: SkillsConfig::default(),
+            plugins: PluginsConfig::default(),
+            session: SessionConfig::default(),
+            bindings: Vec::new(),
+            memory: MemoryConfig::default(),
+            gateway: GatewayConfig::default(),
+        }
+    }
+}
+
+fn default_config_version() -> u32 {
+    crate::migration::CURRENT_CONFIG_VERSION
+}
@@ -1,7 +1,9 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Models configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ModelsConfig {
     /// Model definitions
     #[serde(default)]
@@ -15,10 +17,29 @@ pub struct ModelsConfig {
     /// Default model provider name
     #[serde(default)]
     pub default_provider: String,
+    /// Wire-level logging of provider requests and responses
+    #[serde(default)]
+    pub wire_log: WireLogConfig,
+    /// How failed model calls are retried or failed over, by error class
+    #[serde(default)]
+    pub failover: FailoverPolicy,
+    /// Short names for models, mapping an alias to a model ID. Profiles
+    /// can point an alias at another model without changing the agents
+    /// using it.
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+}
+
+impl ModelsConfig {
+    /// Resolves a model alias to its model ID, returning other model IDs
+    /// unchanged.
+    pub fn resolve_alias<'a>(&'a self, model: &'a str) -> &'a str {
+        self.aliases.get(model).map(String::as_str).unwrap_or(model)
+    }
 }
 
 /// Model definition
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct Model {
     /// Model ID
     pub id: String,
@@ -31,10 +52,32 @@ pub struct Model {
     /// Model capabilities
     #[serde(default)]
     pub capabilities: Vec<String>,
+    /// Mark the system prompt and tool definitions as cacheable, for
+    /// providers that support prompt caching
+    #[serde(default)]
+    pub prompt_caching: bool,
+    /// Prices overriding the built-in pricing table for this model
+    #[serde(default)]
+    pub pricing: Option<ModelPrice>,
+}
+
+/// Model prices in USD per million tokens
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, Default, PartialEq)]
+pub struct ModelPrice {
+    /// Price of input (prompt) tokens
+    pub input: f64,
+    /// Price of output (completion) tokens
+    pub output: f64,
+    /// Price of input tokens read from the prompt cache, if different
+    #[serde(default)]
+    pub cache_read: Option<f64>,
+    /// Price of input tokens written to the prompt cache, if different
+    #[serde(default)]
+    pub cache_write: Option<f64>,
 }
 
 /// Model provider configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ModelProvider {
     /// Provider name
     pub name: String,
@@ -44,10 +87,72 @@ pub struct ModelProvider {
     /// API key reference
     #[serde(default)]
     pub api_key: String,
+    /// Upstream routing preferences, for aggregators such as OpenRouter
+    #[serde(default)]
+    pub routing: ProviderRouting,
+}
+
+/// Routing preferences passed through to an aggregating provider
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default, PartialEq)]
+pub struct ProviderRouting {
+    /// Upstream providers to try first, in order
+    #[serde(default)]
+    pub order: Vec<String>,
+    /// Whether upstream providers not listed in `order` may serve requests
+    #[serde(default)]
+    pub allow_fallbacks: Option<bool>,
+    /// Models to try, in order, when the requested model is unavailable
+    #[serde(default)]
+    pub fallbacks: Vec<String>,
+}
+
+/// Wire-level logging of provider traffic, for debugging provider
+/// incompatibilities. Provider API keys and credential fields are redacted.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct WireLogConfig {
+    /// Enable wire logging
+    #[serde(default)]
+    pub enabled: bool,
+    /// Directory the log files are written to
+    #[serde(default = "default_wire_log_dir")]
+    pub dir: String,
+    /// Size in megabytes at which the log file is rotated
+    #[serde(default = "default_wire_log_max_file_mb")]
+    pub max_file_mb: u64,
+    /// Number of rotated log files kept
+    #[serde(default = "default_wire_log_max_files")]
+    pub max_files: usize,
+    /// Additional headers, query parameters and body fields to redact
+    #[serde(default)]
+    pub redact: Vec<String>,
+}
+
+impl Default for WireLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: default_wire_log_dir(),
+            max_file_mb: default_wire_log_max_file_mb(),
+            max_files: default_wire_log_max_files(),
+            redact: Vec::new(),
+        }
+    }
+}
+
+fn default_wire_log_dir() -> String {
+    "logs".to_string()
+}
+
+fn default_wire_log_max_file_mb() -> u64 {
+    10
+}
+
+fn default_wire_log_max_files() -> usize {
+    5
 }
 
 /// Model fallback configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ModelFallback {
     /// Primary model
     pub primary: String,
@@ -55,3 +160,111 @@ pub struct ModelFallback {
     #[serde(default)]
     pub fallbacks: Vec<String>,
 }
+
+/// What to do when a model call fails with an error of some class
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FailoverRule {
+    /// Wait and retry the same model, up to `max_retries` times, then move
+    /// to the next model in the chain
+    Retry,
+    /// Retry with the next credential; moves to the next model until
+    /// multiple credentials per provider are supported
+    NextAuth,
+    /// Move to the next model in the chain
+    Next,
+    /// Compact the conversation and retry
+    Compact,
+    /// Fail the run
+    Stop,
+}
+
+/// Failover policy of the model chain
+///
+/// Each class of provider error is mapped to a rule. The defaults wait and
+/// retry on rate limits, compact on context overflow and invalid requests,
+/// move on to the next model on other recoverable errors, and fail the run
+/// on unknown errors
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct FailoverPolicy {
+    /// Times the same model is retried under the `retry` rule (default: 2)
+    #[serde(default = "default_max_retries")]
+    pub max_retries: usize,
+    /// Milliseconds waited before a retry when the provider gives no
+    /// retry-after (default: 5000)
+    #[serde(default = "default_retry_delay_ms")]
+    pub retry_delay_ms: u64,
+    /// Rule for authentication failures
+    #[serde(default = "default_auth_rule")]
+    pub auth: FailoverRule,
+    /// Rule for rate limits
+    #[serde(default = "default_rate_limit_rule")]
+    pub rate_limit: FailoverRule,
+    /// Rule for requests exceeding the model's context length
+    #[serde(default = "default_context_overflow_rule")]
+    pub context_overflow: FailoverRule,
+    /// Rule for unknown models
+    #[serde(default = "default_next_rule")]
+    pub model_not_found: FailoverRule,
+    /// Rule for provider server errors
+    #[serde(default = "default_next_rule")]
+    pub server_error: FailoverRule,
+    /// Rule for network errors and timeouts
+    #[serde(default = "default_auth_rule")]
+    pub network_error: FailoverRule,
+    /// Rule for requests rejected as invalid
+    #[serde(default = "default_context_overflow_rule")]
+    pub invalid_request: FailoverRule,
+    /// Rule for streams closed before completion
+    #[serde(default = "default_next_rule")]
+    pub stream_closed: FailoverRule,
+    /// Rule for errors of no known class
+    #[serde(default = "default_unknown_rule")]
+    pub unknown: FailoverRule,
+}
+
+impl Default for FailoverPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: default_max_retries(),
+            retry_delay_ms: default_retry_delay_ms(),
+            auth: default_auth_rule(),
+            rate_limit: default_rate_limit_rule(),
+            context_overflow: default_context_overflow_rule(),
+            model_not_found: default_next_rule(),
+            server_error: default_next_rule(),
+            network_error: default_auth_rule(),
+            invalid_request: default_context_overflow_rule(),
+            stream_closed: default_next_rule(),
+            unknown: default_unknown_rule(),
+        }
+    }
+}
+
+fn default_max_retries() -> usize {
+    2
+}
+
+fn default_retry_delay_ms() -> u64 {
+    5000
+}
+
+fn default_auth_rule() -> FailoverRule {
+    FailoverRule::NextAuth
+}
+
+fn default_rate_limit_rule() -> FailoverRule {
+    FailoverRule::Retry
+}
+
+fn default_context_overflow_rule() -> FailoverRule {
+    FailoverRule::Compact
+}
+
+fn default_next_rule() -> FailoverRule {
+    FailoverRule::Next
+}
+
+fn default_unknown_rule() -> FailoverRule {
+    FailoverRule::Stop
+}
@@ -1,7 +1,8 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Plugins configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct PluginsConfig {
     /// Plugin registry
     #[serde(default)]
@@ -12,7 +13,7 @@ pub struct PluginsConfig {
 }
 
 /// Plugin registry entry
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct PluginEntry {
     /// Plugin ID
     pub id: String,
@@ -25,10 +26,14 @@ pub struct PluginEntry {
     /// Enabled flag
     #[serde(default)]
     pub enabled: bool,
+    /// Capabilities granted to the plugin (channels, tools, providers,
+    /// commands, hooks, network, filesystem)
+    #[serde(default)]
+    pub grants: Vec<String>,
 }
 
 /// Plugin settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PluginSettings {
     /// Auto-load plugins
     #[serde(default)]
@@ -39,6 +44,12 @@ pub struct PluginSettings {
     /// Load timeout in seconds
     #[serde(default = "default_timeout")]
     pub load_timeout: u64,
+    /// Refuse plugins not signed by one of the trusted keys
+    #[serde(default)]
+    pub require_signatures: bool,
+    /// Base64 Ed25519 public keys trusted to sign plugins
+    #[serde(default)]
+    pub trusted_keys: Vec<String>,
 }
 
 impl Default for PluginSettings {
@@ -47,6 +58,8 @@ impl Default for PluginSettings {
             auto_load: false,
             plugin_dir: String::new(),
             load_timeout: default_timeout(),
+            require_signatures: false,
+            trusted_keys: Vec::new(),
         }
     }
 }
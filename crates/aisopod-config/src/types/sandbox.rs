@@ -1,10 +1,11 @@
 use humantime_serde::deserialize as humantime_deserialize;
 use humantime_serde::serialize as humantime_serialize;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 /// Sandbox runtime configuration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum SandboxRuntime {
     /// Docker container runtime
@@ -20,7 +21,7 @@ impl Default for SandboxRuntime {
 }
 
 /// Workspace access configuration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum WorkspaceAccess {
     /// No workspace access
@@ -38,7 +39,7 @@ impl Default for WorkspaceAccess {
 }
 
 /// Sandbox configuration for agent tool execution
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SandboxConfig {
     /// Whether sandbox execution is enabled
     #[serde(default)]
@@ -76,6 +77,7 @@ pub struct SandboxConfig {
         serialize_with = "humantime_serialize",
         deserialize_with = "humantime_deserialize"
     )]
+    #[schemars(with = "String")]
     pub timeout: Duration,
 }
 
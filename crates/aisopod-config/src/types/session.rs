@@ -1,7 +1,9 @@
+use crate::sensitive::Sensitive;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Session configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct SessionConfig {
     /// Message handling settings
     #[serde(default)]
@@ -9,10 +11,22 @@ pub struct SessionConfig {
     /// Session compaction settings
     #[serde(default)]
     pub compaction: CompactionConfig,
+    /// Token and cost budgets
+    #[serde(default)]
+    pub budget: BudgetConfig,
+    /// Retention limits and automatic pruning
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    /// Encryption of stored messages
+    #[serde(default)]
+    pub encryption: EncryptionConfig,
+    /// Where sessions are stored
+    #[serde(default)]
+    pub storage: SessionStorageConfig,
 }
 
 /// Message handling configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct MessageConfig {
     /// Maximum messages in session
     #[serde(default)]
@@ -23,10 +37,26 @@ pub struct MessageConfig {
     /// Message formatting
     #[serde(default)]
     pub format: String,
+    /// Handling of messages sent while a run of the session is in progress
+    #[serde(default)]
+    pub follow_up: FollowUpPolicy,
+}
+
+/// Handling of a user message arriving while a run of its session is in progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FollowUpPolicy {
+    /// Run the message once the runs before it have finished
+    #[default]
+    Queue,
+    /// Abort the running run and restart it with the message appended
+    Restart,
+    /// Inject the message into the running loop before its next model call
+    Interrupt,
 }
 
 /// Session compaction configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CompactionConfig {
     /// Enabled flag
     #[serde(default)]
@@ -52,3 +82,139 @@ impl Default for CompactionConfig {
 fn default_interval() -> u64 {
     3600
 }
+
+/// Session budget configuration
+///
+/// Limits left unset are not enforced. Costs are in USD and daily budgets
+/// reset at midnight UTC
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BudgetConfig {
+    /// Maximum tokens per session
+    #[serde(default)]
+    pub max_session_tokens: Option<u64>,
+    /// Maximum cost per session
+    #[serde(default)]
+    pub max_session_cost: Option<f64>,
+    /// Maximum tokens per peer per day
+    #[serde(default)]
+    pub max_daily_peer_tokens: Option<u64>,
+    /// Maximum cost per peer per day
+    #[serde(default)]
+    pub max_daily_peer_cost: Option<f64>,
+    /// Reply sent instead of running the agent once a budget is exceeded
+    #[serde(default = "default_exceeded_message")]
+    pub exceeded_message: String,
+    /// Operator notified when a budget is exceeded
+    #[serde(default)]
+    pub notify: BudgetNotifyConfig,
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        Self {
+            max_session_tokens: None,
+            max_session_cost: None,
+            max_daily_peer_tokens: None,
+            max_daily_peer_cost: None,
+            exceeded_message: default_exceeded_message(),
+            notify: BudgetNotifyConfig::default(),
+        }
+    }
+}
+
+fn default_exceeded_message() -> String {
+    "Sorry, this conversation has reached its usage budget. Please try again later.".to_string()
+}
+
+/// Budget notification configuration
+///
+/// Exceeded budgets are reported to the configured channel peer when
+/// `channel` and `peer` are set
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct BudgetNotifyConfig {
+    /// Channel the notifications are sent through
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Channel account to send from; the channel's first account when unset
+    #[serde(default)]
+    pub account: Option<String>,
+    /// Operator peer, written as `[user|group|channel|thread:]id`
+    #[serde(default)]
+    pub peer: Option<String>,
+}
+
+/// Session retention configuration
+///
+/// Limits left unset are not enforced. When any limit is set, sessions are
+/// pruned in the background every `prune_interval` seconds
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RetentionConfig {
+    /// Days after its last update a session is deleted
+    #[serde(default)]
+    pub max_age_days: Option<u64>,
+    /// Maximum messages kept per session; older messages are pruned
+    #[serde(default)]
+    pub max_messages: Option<u32>,
+    /// Maximum sessions kept per peer; the least recently updated are deleted
+    #[serde(default)]
+    pub max_sessions_per_peer: Option<u32>,
+    /// Pruning interval in seconds
+    #[serde(default = "default_interval")]
+    pub prune_interval: u64,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            max_age_days: None,
+            max_messages: None,
+            max_sessions_per_peer: None,
+            prune_interval: default_interval(),
+        }
+    }
+}
+
+/// Session storage configuration
+///
+/// Clustered gateways must share the store, either a SQLite file all nodes
+/// can reach or a Postgres database
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct SessionStorageConfig {
+    /// Storage backend
+    #[serde(default)]
+    pub backend: SessionBackendKind,
+    /// SQLite database file; sessions are kept in memory when empty
+    #[serde(default)]
+    pub path: String,
+    /// Postgres connection URL (postgres backend)
+    #[serde(default)]
+    pub url: Sensitive<String>,
+    /// Redis URL of a cache of active sessions; no cache when empty
+    #[serde(default)]
+    pub cache_url: Sensitive<String>,
+}
+
+/// Session storage backend selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionBackendKind {
+    /// SQLite database, in a file or in memory
+    #[default]
+    Sqlite,
+    /// Postgres database shared by several gateways
+    Postgres,
+}
+
+/// Session encryption configuration
+///
+/// Message bodies are encrypted at rest with AES-256-GCM when a key is
+/// configured, either directly or through the OS keyring
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct EncryptionConfig {
+    /// Base64 encoded 32-byte key, usually given as `${VAR}`
+    #[serde(default)]
+    pub key: Option<Sensitive<String>>,
+    /// OS keyring service holding the base64 encoded key
+    #[serde(default)]
+    pub keyring: Option<String>,
+}
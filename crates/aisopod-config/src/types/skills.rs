@@ -1,7 +1,8 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Skills configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct SkillsConfig {
     /// Skill modules
     #[serde(default)]
@@ -12,7 +13,7 @@ pub struct SkillsConfig {
 }
 
 /// Skill module definition
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct SkillModule {
     /// Module ID
     pub id: String,
@@ -28,7 +29,7 @@ pub struct SkillModule {
 }
 
 /// Skill settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SkillSettings {
     /// Default timeout in seconds
     #[serde(default = "default_timeout")]
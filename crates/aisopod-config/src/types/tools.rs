@@ -1,7 +1,10 @@
+use crate::sensitive::Sensitive;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Tools configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ToolsConfig {
     /// Bash tool settings
     #[serde(default)]
@@ -12,10 +15,34 @@ pub struct ToolsConfig {
     /// File system tool settings
     #[serde(default)]
     pub filesystem: FileSystemToolConfig,
+    /// Web search tool settings
+    #[serde(default)]
+    pub web_search: WebSearchToolConfig,
+    /// Documentation search tool settings
+    #[serde(default)]
+    pub docs: DocsToolConfig,
+    /// HTTP request tool settings
+    #[serde(default)]
+    pub http: HttpToolConfig,
+    /// SQL query tool settings
+    #[serde(default)]
+    pub sql: SqlToolConfig,
+    /// Operator approval settings
+    #[serde(default)]
+    pub approval: ApprovalConfig,
+    /// MCP client settings
+    #[serde(default)]
+    pub mcp: McpConfig,
+    /// Tool-call loop detection settings
+    #[serde(default)]
+    pub loop_guard: LoopGuardConfig,
+    /// Tool call quotas
+    #[serde(default)]
+    pub quotas: ToolQuotasConfig,
 }
 
 /// Bash tool configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BashToolConfig {
     /// Enabled flag
     #[serde(default)]
@@ -39,7 +66,7 @@ impl Default for BashToolConfig {
 }
 
 /// Exec tool configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ExecToolConfig {
     /// Enabled flag
     #[serde(default)]
@@ -50,7 +77,7 @@ pub struct ExecToolConfig {
 }
 
 /// File system tool configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct FileSystemToolConfig {
     /// Enabled flag
     #[serde(default)]
@@ -63,6 +90,444 @@ pub struct FileSystemToolConfig {
     pub operations: Vec<String>,
 }
 
+/// Web search backend selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WebSearchBackend {
+    /// Self-hosted SearXNG instance (requires `endpoint`)
+    Searxng,
+    /// Brave Search API (requires `api_key`)
+    Brave,
+    /// Bing Web Search API (requires `api_key`)
+    Bing,
+    /// DuckDuckGo HTML results (no key required)
+    #[default]
+    Duckduckgo,
+}
+
+/// Web search tool configuration
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebSearchToolConfig {
+    /// Enabled flag
+    #[serde(default)]
+    pub enabled: bool,
+    /// Search backend
+    #[serde(default)]
+    pub backend: WebSearchBackend,
+    /// Backend base URL (required for SearXNG, optional override for others)
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// API key for backends that require one
+    #[serde(default)]
+    pub api_key: Option<Sensitive<String>>,
+    /// Maximum number of results returned
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+    /// Timeout in seconds
+    #[serde(default = "default_search_timeout")]
+    pub timeout: u64,
+}
+
+impl Default for WebSearchToolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: WebSearchBackend::default(),
+            endpoint: None,
+            api_key: None,
+            max_results: default_max_results(),
+            timeout: default_search_timeout(),
+        }
+    }
+}
+
+/// Documentation search tool configuration
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DocsToolConfig {
+    /// Enabled flag
+    #[serde(default)]
+    pub enabled: bool,
+    /// Directories, files or http(s) URLs to index
+    #[serde(default)]
+    pub sources: Vec<String>,
+    /// File extensions indexed when walking directories
+    #[serde(default = "default_docs_extensions")]
+    pub extensions: Vec<String>,
+    /// Target chunk size in characters
+    #[serde(default = "default_chunk_size")]
+    pub chunk_size: usize,
+    /// Characters of overlap carried into the next chunk
+    #[serde(default = "default_chunk_overlap")]
+    pub chunk_overlap: usize,
+    /// Maximum number of results returned
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+}
+
+impl Default for DocsToolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sources: Vec::new(),
+            extensions: default_docs_extensions(),
+            chunk_size: default_chunk_size(),
+            chunk_overlap: default_chunk_overlap(),
+            max_results: default_max_results(),
+        }
+    }
+}
+
+/// HTTP request tool configuration
+///
+/// Hosts are matched exactly, or with all subdomains when written as
+/// `*.example.com`. Loopback, private and link-local addresses stay
+/// unreachable, including through DNS names and redirects, unless
+/// `allow_private_networks` is set
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HttpToolConfig {
+    /// Hosts the tool may contact; any host not denied when empty
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+    /// Hosts the tool may never contact
+    #[serde(default)]
+    pub denied_hosts: Vec<String>,
+    /// Allow requests to loopback, private and link-local addresses
+    #[serde(default)]
+    pub allow_private_networks: bool,
+    /// Timeout in seconds, also the longest timeout a request may ask for
+    #[serde(default = "default_http_timeout")]
+    pub timeout: u64,
+}
+
+impl Default for HttpToolConfig {
+    fn default() -> Self {
+        Self {
+            allowed_hosts: Vec::new(),
+            denied_hosts: Vec::new(),
+            allow_private_networks: false,
+            timeout: default_http_timeout(),
+        }
+    }
+}
+
+/// SQL query tool configuration
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SqlToolConfig {
+    /// Enabled flag
+    #[serde(default)]
+    pub enabled: bool,
+    /// Databases the tool may query
+    #[serde(default)]
+    pub databases: Vec<SqlDatabaseConfig>,
+    /// Default maximum number of rows returned per query
+    #[serde(default = "default_sql_max_rows")]
+    pub max_rows: usize,
+    /// Default query timeout in seconds
+    #[serde(default = "default_sql_timeout")]
+    pub timeout: u64,
+}
+
+impl Default for SqlToolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            databases: Vec::new(),
+            max_rows: default_sql_max_rows(),
+            timeout: default_sql_timeout(),
+        }
+    }
+}
+
+/// SQL database driver
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SqlDriver {
+    /// SQLite database file
+    #[default]
+    Sqlite,
+    /// PostgreSQL server
+    Postgres,
+    /// MySQL or MariaDB server
+    Mysql,
+}
+
+/// A database connection available to the SQL tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SqlDatabaseConfig {
+    /// Name the agent uses to select the database
+    pub name: String,
+    /// Database driver
+    #[serde(default)]
+    pub driver: SqlDriver,
+    /// Connection URL, or the database file path for SQLite
+    pub url: Sensitive<String>,
+    /// Reject statements that modify data
+    #[serde(default = "default_enabled")]
+    pub read_only: bool,
+    /// Maximum number of rows returned, overriding the tool default
+    #[serde(default)]
+    pub max_rows: Option<usize>,
+    /// Query timeout in seconds, overriding the tool default
+    #[serde(default)]
+    pub timeout: Option<u64>,
+}
+
+/// Operator approval configuration
+///
+/// Approval requests from tools are sent to the configured channel peer
+/// when `channel` and `peer` are set
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct ApprovalConfig {
+    /// Channel the approval requests are sent through
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Channel account to send from; the channel's first account when unset
+    #[serde(default)]
+    pub account: Option<String>,
+    /// Operator peer, written as `[user|group|channel|thread:]id`
+    #[serde(default)]
+    pub peer: Option<String>,
+    /// Sender IDs allowed to decide; anyone in the peer conversation when empty
+    #[serde(default)]
+    pub operators: Vec<String>,
+    /// Timeout in seconds, overriding the timeout of each request
+    #[serde(default)]
+    pub timeout: Option<u64>,
+}
+
+/// Tool-call loop guard configuration
+///
+/// A run calling the same tool calls over and over, either one identical
+/// call or a cycle of up to `max_cycle_len` calls, is stopped once the
+/// calls were repeated `max_repeats` times in a row
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LoopGuardConfig {
+    /// Enabled flag
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Consecutive repetitions of a call or cycle that count as a loop
+    #[serde(default = "default_loop_max_repeats")]
+    pub max_repeats: usize,
+    /// Longest cycle of calls detected, e.g. 2 for A, B, A, B
+    #[serde(default = "default_loop_max_cycle_len")]
+    pub max_cycle_len: usize,
+    /// Intervention when a loop is detected
+    #[serde(default)]
+    pub action: LoopGuardAction,
+}
+
+impl Default for LoopGuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_repeats: default_loop_max_repeats(),
+            max_cycle_len: default_loop_max_cycle_len(),
+            action: LoopGuardAction::default(),
+        }
+    }
+}
+
+/// Tool quota configuration
+///
+/// Quotas are keyed by tool name, or `*` for every tool; an agent's quota
+/// for a tool replaces the global one
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ToolQuotasConfig {
+    /// Quotas applied to all agents
+    #[serde(default)]
+    pub global: HashMap<String, ToolQuotaConfig>,
+    /// Per-agent quotas, keyed by agent ID
+    #[serde(default)]
+    pub agents: HashMap<String, HashMap<String, ToolQuotaConfig>>,
+    /// Seconds after which the usage recorded for an idle session is
+    /// forgotten
+    #[serde(default = "default_quota_session_idle_timeout")]
+    pub session_idle_timeout: u64,
+}
+
+impl Default for ToolQuotasConfig {
+    fn default() -> Self {
+        Self {
+            global: HashMap::new(),
+            agents: HashMap::new(),
+            session_idle_timeout: default_quota_session_idle_timeout(),
+        }
+    }
+}
+
+impl ToolQuotasConfig {
+    /// Returns `true` if no quota is configured
+    pub fn is_empty(&self) -> bool {
+        self.global.is_empty() && self.agents.values().all(HashMap::is_empty)
+    }
+}
+
+/// Limits on the calls to one tool; unset limits are not enforced
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct ToolQuotaConfig {
+    /// Maximum number of calls per session
+    #[serde(default)]
+    pub max_calls_per_session: Option<u32>,
+    /// Maximum number of executions running at the same time per agent
+    #[serde(default)]
+    pub max_concurrent: Option<u32>,
+    /// Minimum number of seconds between two calls in a session
+    #[serde(default)]
+    pub cooldown: Option<u64>,
+}
+
+/// Intervention of the loop guard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LoopGuardAction {
+    /// Tell the model it is looping; a loop detected again after the nudge
+    /// forces a final answer
+    #[default]
+    Nudge,
+    /// Withhold the tools so the model has to give its final answer
+    ForceAnswer,
+    /// Fail the run with a description of the loop
+    Abort,
+}
+
+/// MCP (Model Context Protocol) client configuration
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct McpConfig {
+    /// MCP servers to connect to at startup
+    #[serde(default)]
+    pub servers: Vec<McpServerConfig>,
+    /// Settings for exposing aisopod tools as an MCP server
+    #[serde(default)]
+    pub export: McpExportConfig,
+}
+
+/// Configuration for serving local tools over MCP
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct McpExportConfig {
+    /// Tool names exposed to MCP clients; `*` matches any tool and a trailing
+    /// `*` matches a prefix. Nothing is exported when empty
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// Agent ID that exported tool calls run as
+    #[serde(default = "default_export_agent_id")]
+    pub agent_id: String,
+    /// Browser origins allowed to call the HTTP/SSE transport; requests
+    /// from any other origin are rejected
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+}
+
+impl Default for McpExportConfig {
+    fn default() -> Self {
+        Self {
+            tools: Vec::new(),
+            agent_id: default_export_agent_id(),
+            allowed_origins: Vec::new(),
+        }
+    }
+}
+
+/// Transport used to reach an MCP server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum McpTransportKind {
+    /// Spawn the server as a child process and talk JSON-RPC over stdin/stdout
+    #[default]
+    Stdio,
+    /// Connect to a remote server over HTTP with Server-Sent Events
+    Sse,
+}
+
+/// A single MCP server entry
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct McpServerConfig {
+    /// Server name, used to prefix the names of its tools
+    pub name: String,
+    /// Enabled flag
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Transport type
+    #[serde(default)]
+    pub transport: McpTransportKind,
+    /// Command to launch (stdio transport)
+    #[serde(default)]
+    pub command: String,
+    /// Command arguments (stdio transport)
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Extra environment variables for the server process (stdio transport)
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// SSE endpoint URL (sse transport)
+    #[serde(default)]
+    pub url: String,
+    /// Extra HTTP headers, e.g. authorization (sse transport)
+    #[serde(default)]
+    pub headers: HashMap<String, Sensitive<String>>,
+    /// Request timeout in seconds
+    #[serde(default = "default_mcp_timeout")]
+    pub timeout: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_export_agent_id() -> String {
+    "mcp".to_string()
+}
+
+fn default_mcp_timeout() -> u64 {
+    30
+}
+
+fn default_loop_max_repeats() -> usize {
+    3
+}
+
+fn default_loop_max_cycle_len() -> usize {
+    3
+}
+
+fn default_quota_session_idle_timeout() -> u64 {
+    24 * 60 * 60
+}
+
+fn default_max_results() -> usize {
+    5
+}
+
+fn default_docs_extensions() -> Vec<String> {
+    ["md", "markdown", "txt", "rst", "adoc"]
+        .iter()
+        .map(|ext| ext.to_string())
+        .collect()
+}
+
+fn default_chunk_size() -> usize {
+    1200
+}
+
+fn default_chunk_overlap() -> usize {
+    200
+}
+
+fn default_sql_max_rows() -> usize {
+    100
+}
+
+fn default_sql_timeout() -> u64 {
+    30
+}
+
+fn default_http_timeout() -> u64 {
+    30
+}
+
+fn default_search_timeout() -> u64 {
+    15
+}
+
 fn default_timeout() -> u64 {
     300
 }
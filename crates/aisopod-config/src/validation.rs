@@ -2,7 +2,7 @@
 //!
 //! Provides semantic validation of configuration beyond what serde deserialization provides.
 
-use crate::types::AisopodConfig;
+use crate::types::{AisopodConfig, AuthMode, SessionBackendKind};
 use std::fmt;
 
 /// Represents a validation error with the field path and a human-readable message.
@@ -12,14 +12,35 @@ pub struct ValidationError {
     pub path: String,
     /// A human-readable error message
     pub message: String,
+    /// A suggested fix, e.g. the closest known name for a misspelled one
+    pub suggestion: Option<String>,
 }
 
 impl fmt::Display for ValidationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}: {}", self.path, self.message)
+        write!(f, "{}: {}", self.path, self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " ({})", suggestion)?;
+        }
+        Ok(())
     }
 }
 
+/// Returns the candidate closest to a misspelled `name`, if any is close
+/// enough to be a likely typo.
+pub(crate) fn closest_match<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(2);
+    candidates
+        .into_iter()
+        .map(|candidate| (strsim::levenshtein(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
 impl AisopodConfig {
     /// Validate semantic rules across the entire configuration.
     ///
@@ -31,8 +52,15 @@ impl AisopodConfig {
 
         self.validate_meta(&mut errors);
         self.validate_gateway(&mut errors);
+        self.validate_auth(&mut errors);
         self.validate_agents(&mut errors);
+        self.validate_schedules(&mut errors);
+        self.validate_bindings(&mut errors);
+        self.validate_channels(&mut errors);
         self.validate_models(&mut errors);
+        self.validate_loop_guard(&mut errors);
+        self.validate_tool_quotas(&mut errors);
+        self.validate_session_storage(&mut errors);
 
         if errors.is_empty() {
             Ok(())
@@ -46,6 +74,7 @@ impl AisopodConfig {
             errors.push(ValidationError {
                 path: "meta.version".to_string(),
                 message: "Version must not be empty".to_string(),
+                suggestion: None,
             });
         }
     }
@@ -58,6 +87,7 @@ impl AisopodConfig {
             errors.push(ValidationError {
                 path: "gateway.server.port".to_string(),
                 message: "Port must be between 1 and 65535, got 0".to_string(),
+                suggestion: None,
             });
         }
 
@@ -65,6 +95,114 @@ impl AisopodConfig {
             errors.push(ValidationError {
                 path: "gateway.bind.address".to_string(),
                 message: "Address must not be empty".to_string(),
+                suggestion: None,
+            });
+        }
+
+        let tls = &self.gateway.tls;
+        if tls.client_auth.enabled {
+            if tls.client_auth.ca_path.is_empty() {
+                errors.push(ValidationError {
+                    path: "gateway.tls.client_auth.ca_path".to_string(),
+                    message: "CA bundle must be set when client authentication is enabled"
+                        .to_string(),
+                    suggestion: None,
+                });
+            }
+            if tls.cert_path.is_empty() || tls.key_path.is_empty() {
+                errors.push(ValidationError {
+                    path: "gateway.tls.client_auth.enabled".to_string(),
+                    message: "Client authentication requires TLS".to_string(),
+                    suggestion: Some(
+                        "set gateway.tls.cert_path and gateway.tls.key_path".to_string(),
+                    ),
+                });
+            }
+        }
+
+        let rate_limit = &self.gateway.rate_limit;
+        let limits = std::iter::once(("gateway.rate_limit", rate_limit.window, rate_limit.burst))
+            .chain(
+                rate_limit
+                    .per_token
+                    .as_ref()
+                    .map(|t| ("gateway.rate_limit.per_token", t.window, t.burst)),
+            );
+        for (path, window, burst) in limits {
+            if window == 0 {
+                errors.push(ValidationError {
+                    path: format!("{}.window", path),
+                    message: "Window must be at least 1 second".to_string(),
+                    suggestion: None,
+                });
+            }
+            if burst == Some(0) {
+                errors.push(ValidationError {
+                    path: format!("{}.burst", path),
+                    message: "Burst must allow at least 1 request".to_string(),
+                    suggestion: Some("remove it to allow max_requests at once".to_string()),
+                });
+            }
+        }
+
+        let telemetry = &self.gateway.telemetry;
+        if !(0.0..=1.0).contains(&telemetry.sample_ratio) {
+            errors.push(ValidationError {
+                path: "gateway.telemetry.sample_ratio".to_string(),
+                message: format!(
+                    "Sample ratio must be between 0.0 and 1.0, got {}",
+                    telemetry.sample_ratio
+                ),
+                suggestion: None,
+            });
+        }
+        if telemetry.enabled && telemetry.endpoint.is_empty() {
+            errors.push(ValidationError {
+                path: "gateway.telemetry.endpoint".to_string(),
+                message: "Endpoint must not be empty when telemetry is enabled".to_string(),
+                suggestion: None,
+            });
+        }
+
+        let cluster = &self.gateway.cluster;
+        if cluster.enabled && cluster.redis_url.expose().is_empty() {
+            errors.push(ValidationError {
+                path: "gateway.cluster.redis_url".to_string(),
+                message: "Redis URL must be set when clustering is enabled".to_string(),
+                suggestion: Some("e.g. 'redis://127.0.0.1:6379/0'".to_string()),
+            });
+        }
+        if cluster.enabled && cluster.channel.is_empty() {
+            errors.push(ValidationError {
+                path: "gateway.cluster.channel".to_string(),
+                message: "Channel must not be empty when clustering is enabled".to_string(),
+                suggestion: None,
+            });
+        }
+    }
+
+    fn validate_auth(&self, errors: &mut Vec<ValidationError>) {
+        if self.auth.gateway_mode != AuthMode::Oidc {
+            return;
+        }
+        if self.auth.oidc.issuer.is_empty() {
+            errors.push(ValidationError {
+                path: "auth.oidc.issuer".to_string(),
+                message: "Issuer must be set for the oidc gateway mode".to_string(),
+                suggestion: Some(
+                    "set it to your identity provider's issuer URL, e.g. 'https://login.example.com/realms/aisopod'"
+                        .to_string(),
+                ),
+            });
+        }
+        if self.auth.oidc.audiences.is_empty() {
+            errors.push(ValidationError {
+                path: "auth.oidc.audiences".to_string(),
+                message: "At least one audience must be set for the oidc gateway mode".to_string(),
+                suggestion: Some(
+                    "set it to the client ID the identity provider issues aisopod's tokens for"
+                        .to_string(),
+                ),
             });
         }
     }
@@ -77,11 +215,94 @@ impl AisopodConfig {
                 errors.push(ValidationError {
                     path: "agents[].name".to_string(),
                     message: "Agent name must not be empty".to_string(),
+                    suggestion: None,
                 });
             } else if !seen_names.insert(&agent.name) {
                 errors.push(ValidationError {
-                    path: format!("agents[\"{}\").name", agent.name),
+                    path: format!("agents[\"{}\"].name", agent.name),
                     message: format!("Duplicate agent name: {}", agent.name),
+                    suggestion: None,
+                });
+            }
+
+            if let Some(draft_verify) = &agent.draft_verify {
+                if draft_verify.draft_model.is_empty() || draft_verify.verify_model.is_empty() {
+                    errors.push(ValidationError {
+                        path: format!("agents[\"{}\"].draft_verify", agent.id),
+                        message: "Draft and verify models must not be empty".to_string(),
+                        suggestion: None,
+                    });
+                }
+            }
+        }
+    }
+
+    fn validate_schedules(&self, errors: &mut Vec<ValidationError>) {
+        let mut seen_ids = std::collections::HashSet::new();
+
+        for schedule in &self.agents.schedules {
+            if schedule.id.is_empty() {
+                errors.push(ValidationError {
+                    path: "agents.schedules[].id".to_string(),
+                    message: "Schedule ID must not be empty".to_string(),
+                    suggestion: None,
+                });
+            } else if !seen_ids.insert(&schedule.id) {
+                errors.push(ValidationError {
+                    path: format!("agents.schedules[\"{}\"].id", schedule.id),
+                    message: format!("Duplicate schedule ID: {}", schedule.id),
+                    suggestion: None,
+                });
+            }
+
+            if !self.agents.agents.iter().any(|a| a.id == schedule.agent_id) {
+                errors.push(ValidationError {
+                    path: format!("agents.schedules[\"{}\"].agent_id", schedule.id),
+                    message: format!("Unknown agent: {}", schedule.agent_id),
+                    suggestion: self.unknown_agent_suggestion(&schedule.agent_id),
+                });
+            }
+        }
+    }
+
+    fn validate_bindings(&self, errors: &mut Vec<ValidationError>) {
+        for (index, binding) in self.bindings.iter().enumerate() {
+            if !self.agents.agents.iter().any(|a| a.id == binding.agent_id) {
+                errors.push(ValidationError {
+                    path: format!("bindings[{}].agent_id", index),
+                    message: format!("Unknown agent: {}", binding.agent_id),
+                    suggestion: self.unknown_agent_suggestion(&binding.agent_id),
+                });
+            }
+        }
+    }
+
+    fn unknown_agent_suggestion(&self, agent_id: &str) -> Option<String> {
+        let ids = self.agents.agents.iter().map(|a| a.id.as_str());
+        Some(match closest_match(agent_id, ids) {
+            Some(id) => format!("did you mean '{}'?", id),
+            None => format!("add an agent with id '{}' to agents.agents", agent_id),
+        })
+    }
+
+    fn validate_channels(&self, errors: &mut Vec<ValidationError>) {
+        let mut seen_ids = std::collections::HashSet::new();
+
+        for channel in &self.channels.channels {
+            if channel.id.is_empty() {
+                errors.push(ValidationError {
+                    path: "channels.channels[].id".to_string(),
+                    message: "Channel ID must not be empty".to_string(),
+                    suggestion: None,
+                });
+            } else if !seen_ids.insert(&channel.id) {
+                errors.push(ValidationError {
+                    path: format!("channels.channels[\"{}\"].id", channel.id),
+                    message: format!("Duplicate channel ID: {}", channel.id),
+                    suggestion: Some(format!(
+                        "give each account its own ID, e.g. '{}-2'",
+                        channel.id
+                    )),
                 });
             }
         }
@@ -95,21 +316,100 @@ impl AisopodConfig {
                 errors.push(ValidationError {
                     path: "models[].id".to_string(),
                     message: "Model ID must not be empty".to_string(),
+                    suggestion: None,
                 });
             } else if !seen_ids.insert(&model.id) {
                 errors.push(ValidationError {
-                    path: format!("models[\"{}\").id", model.id),
+                    path: format!("models[\"{}\"].id", model.id),
                     message: format!("Duplicate model ID: {}", model.id),
+                    suggestion: None,
+                });
+            }
+        }
+
+        let mut aliases: Vec<_> = self.models.aliases.iter().collect();
+        aliases.sort();
+        for (alias, target) in aliases {
+            if target.is_empty() {
+                errors.push(ValidationError {
+                    path: format!("models.aliases.{}", alias),
+                    message: "Alias target must not be empty".to_string(),
+                    suggestion: None,
+                });
+            } else if self.models.aliases.contains_key(target) {
+                errors.push(ValidationError {
+                    path: format!("models.aliases.{}", alias),
+                    message: format!("Alias must name a model, not the alias '{}'", target),
+                    suggestion: None,
                 });
             }
         }
     }
+
+    fn validate_loop_guard(&self, errors: &mut Vec<ValidationError>) {
+        let guard = &self.tools.loop_guard;
+        if !guard.enabled {
+            return;
+        }
+        if guard.max_repeats < 2 {
+            errors.push(ValidationError {
+                path: "tools.loop_guard.max_repeats".to_string(),
+                message: "Loop guard max_repeats must be at least 2".to_string(),
+                suggestion: None,
+            });
+        }
+        if guard.max_cycle_len == 0 {
+            errors.push(ValidationError {
+                path: "tools.loop_guard.max_cycle_len".to_string(),
+                message: "Loop guard max_cycle_len must be at least 1".to_string(),
+                suggestion: None,
+            });
+        }
+    }
+
+    fn validate_tool_quotas(&self, errors: &mut Vec<ValidationError>) {
+        if self.tools.quotas.session_idle_timeout == 0 {
+            errors.push(ValidationError {
+                path: "tools.quotas.session_idle_timeout".to_string(),
+                message: "Tool quota session_idle_timeout must be at least 1 second".to_string(),
+                suggestion: None,
+            });
+        }
+    }
+
+    fn validate_session_storage(&self, errors: &mut Vec<ValidationError>) {
+        let storage = &self.session.storage;
+        if storage.backend == SessionBackendKind::Postgres && storage.url.expose().is_empty() {
+            errors.push(ValidationError {
+                path: "session.storage.url".to_string(),
+                message: "URL must be set for the postgres session backend".to_string(),
+                suggestion: Some("e.g. 'postgres://aisopod@db.example.com/aisopod'".to_string()),
+            });
+        }
+        // Nodes coordinate through session leases in the shared store
+        if self.gateway.cluster.enabled
+            && storage.backend == SessionBackendKind::Sqlite
+            && storage.path.is_empty()
+        {
+            errors.push(ValidationError {
+                path: "session.storage".to_string(),
+                message: "Sessions must be kept in a store shared by the cluster's nodes"
+                    .to_string(),
+                suggestion: Some(
+                    "set session.storage.path to a SQLite file all nodes can reach, or use the postgres backend"
+                        .to_string(),
+                ),
+            });
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::{Agent, AgentsConfig, GatewayConfig, MetaConfig, ModelsConfig};
+    use crate::types::{
+        Agent, AgentBinding, AgentSchedule, Channel, DraftVerifyConfig, VerifyMode,
+    };
 
     #[test]
     fn test_default_config_is_valid() {
@@ -147,6 +447,103 @@ mod tests {
         assert!(errors.iter().any(|e| e.path == "gateway.bind.address"));
     }
 
+    #[test]
+    fn test_client_auth_requires_ca_and_tls() {
+        let mut config = AisopodConfig::default();
+        config.gateway.tls.client_auth.enabled = true;
+        let errors = config.validate().unwrap_err();
+        assert!(errors
+            .iter()
+            .any(|e| e.path == "gateway.tls.client_auth.ca_path"));
+        assert!(errors
+            .iter()
+            .any(|e| e.path == "gateway.tls.client_auth.enabled"));
+
+        config.gateway.tls.client_auth.ca_path = "clients-ca.pem".to_string();
+        config.gateway.tls.cert_path = "server.pem".to_string();
+        config.gateway.tls.key_path = "server-key.pem".to_string();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_rate_limit_window_and_burst() {
+        let mut config = AisopodConfig::default();
+        config.gateway.rate_limit.burst = Some(0);
+        config.gateway.rate_limit.per_token = Some(crate::types::TokenRateLimitConfig {
+            window: 0,
+            ..Default::default()
+        });
+        let errors = config.validate().unwrap_err();
+        let paths: Vec<_> = errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "gateway.rate_limit.burst",
+                "gateway.rate_limit.per_token.window"
+            ]
+        );
+
+        config.gateway.rate_limit.burst = Some(20);
+        config.gateway.rate_limit.per_token = Some(Default::default());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_oidc_mode_requires_issuer_and_audience() {
+        let mut config = AisopodConfig::default();
+        config.auth.gateway_mode = AuthMode::Oidc;
+        let errors = config.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.path == "auth.oidc.issuer"));
+
+        config.auth.oidc.issuer = "https://login.example.com".to_string();
+        let errors = config.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.path == "auth.oidc.audiences"));
+
+        config.auth.oidc.audiences = vec!["aisopod".to_string()];
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_telemetry_sample_ratio_out_of_range() {
+        let mut config = AisopodConfig::default();
+        config.gateway.telemetry.sample_ratio = 1.5;
+        let errors = config.validate().unwrap_err();
+        assert!(errors
+            .iter()
+            .any(|e| e.path == "gateway.telemetry.sample_ratio"));
+    }
+
+    #[test]
+    fn test_cluster_requires_redis_url() {
+        let mut config = AisopodConfig::default();
+        config.gateway.cluster.enabled = true;
+        let errors = config.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.path == "gateway.cluster.redis_url"));
+
+        config.gateway.cluster.redis_url =
+            crate::sensitive::Sensitive::new("redis://127.0.0.1:6379/0".to_string());
+        config.session.storage.path = "/srv/aisopod/sessions.db".to_string();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_cluster_requires_shared_session_storage() {
+        let mut config = AisopodConfig::default();
+        config.gateway.cluster.enabled = true;
+        config.gateway.cluster.redis_url =
+            crate::sensitive::Sensitive::new("redis://127.0.0.1:6379/0".to_string());
+        let errors = config.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.path == "session.storage"));
+
+        config.session.storage.backend = SessionBackendKind::Postgres;
+        let errors = config.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.path == "session.storage.url"));
+
+        config.session.storage.url =
+            crate::sensitive::Sensitive::new("postgres://localhost/aisopod".to_string());
+        assert!(config.validate().is_ok());
+    }
+
     #[test]
     fn test_duplicate_agent_names_detected() {
         let mut config = AisopodConfig::default();
@@ -162,6 +559,12 @@ mod tests {
                 subagent_allowed_models: None,
                 system_prompt: "Default system prompt".to_string(),
                 skills: Vec::new(),
+                show_reasoning: false,
+                output_schema: None,
+                max_output_repairs: 2,
+                draft_verify: None,
+                prompt_template: None,
+                prompt_sections: Default::default(),
             },
             Agent {
                 id: "agent2".to_string(),
@@ -174,6 +577,12 @@ mod tests {
                 subagent_allowed_models: None,
                 system_prompt: "Default system prompt".to_string(),
                 skills: Vec::new(),
+                show_reasoning: false,
+                output_schema: None,
+                max_output_repairs: 2,
+                draft_verify: None,
+                prompt_template: None,
+                prompt_sections: Default::default(),
             },
         ];
         let errors = config.validate().unwrap_err();
@@ -182,6 +591,150 @@ mod tests {
             .any(|e| e.message.contains("Duplicate agent name: agent1")));
     }
 
+    #[test]
+    fn test_draft_verify_without_models_detected() {
+        let mut config = AisopodConfig::default();
+        config.agents.agents.push(Agent {
+            id: "support".to_string(),
+            name: "Support".to_string(),
+            draft_verify: Some(DraftVerifyConfig {
+                draft_model: "openai/gpt-4o-mini".to_string(),
+                verify_model: String::new(),
+                mode: VerifyMode::Edit,
+                max_revisions: 1,
+            }),
+            ..Default::default()
+        });
+
+        let errors = config.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "agents[\"support\"].draft_verify");
+    }
+
+    #[test]
+    fn test_loop_guard_limits_validated() {
+        let mut config = AisopodConfig::default();
+        config.tools.loop_guard.max_repeats = 1;
+        config.tools.loop_guard.max_cycle_len = 0;
+        let errors = config.validate().unwrap_err();
+        assert_eq!(errors.len(), 2);
+
+        config.tools.loop_guard.enabled = false;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_schedule_of_unknown_agent_detected() {
+        let mut config = AisopodConfig::default();
+        config.agents.agents.push(Agent {
+            id: "reporter".to_string(),
+            name: "Reporter".to_string(),
+            ..Default::default()
+        });
+        let schedule = |id: &str, agent_id: &str| AgentSchedule {
+            id: id.to_string(),
+            agent_id: agent_id.to_string(),
+            cron: "0 0 9 * * *".to_string(),
+            prompt: "Summarize yesterday's tickets".to_string(),
+            deliver: None,
+        };
+        config.agents.schedules = vec![
+            schedule("daily", "reporter"),
+            schedule("weekly", "missing"),
+            schedule("daily", "reporter"),
+        ];
+
+        let errors = config.validate().unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors
+            .iter()
+            .any(|e| e.path == "agents.schedules[\"weekly\"].agent_id"));
+        assert!(errors
+            .iter()
+            .any(|e| e.message.contains("Duplicate schedule ID: daily")));
+    }
+
+    #[test]
+    fn test_binding_to_unknown_agent_detected() {
+        let mut config = AisopodConfig::default();
+        config.agents.agents.push(Agent {
+            id: "support".to_string(),
+            name: "Support".to_string(),
+            ..Default::default()
+        });
+        let binding = |agent_id: &str| AgentBinding {
+            agent_id: agent_id.to_string(),
+            channels: vec!["telegram".to_string()],
+            priority: 0,
+            sandbox: None,
+        };
+        config.bindings = vec![binding("support"), binding("suport"), binding("billing")];
+
+        let errors = config.validate().unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].path, "bindings[1].agent_id");
+        assert_eq!(
+            errors[0].to_string(),
+            "bindings[1].agent_id: Unknown agent: suport (did you mean 'support'?)"
+        );
+        assert_eq!(errors[1].path, "bindings[2].agent_id");
+        assert_eq!(
+            errors[1].suggestion.as_deref(),
+            Some("add an agent with id 'billing' to agents.agents")
+        );
+    }
+
+    #[test]
+    fn test_channel_id_collisions_detected() {
+        let mut config = AisopodConfig::default();
+        let channel = |id: &str| Channel {
+            id: id.to_string(),
+            channel_type: "telegram".to_string(),
+            ..Default::default()
+        };
+        config.channels.channels = vec![
+            channel("telegram-support"),
+            channel("telegram-sales"),
+            channel("telegram-support"),
+        ];
+
+        let errors = config.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "channels.channels[\"telegram-support\"].id");
+        assert!(errors[0].suggestion.is_some());
+    }
+
+    #[test]
+    fn test_model_aliases_validated() {
+        let mut config = AisopodConfig::default();
+        config
+            .models
+            .aliases
+            .insert("smart".to_string(), "anthropic/claude-sonnet-4".to_string());
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.models.resolve_alias("smart"),
+            "anthropic/claude-sonnet-4"
+        );
+        assert_eq!(
+            config.models.resolve_alias("openai/gpt-4o"),
+            "openai/gpt-4o"
+        );
+
+        config
+            .models
+            .aliases
+            .insert("default".to_string(), "smart".to_string());
+        config
+            .models
+            .aliases
+            .insert("empty".to_string(), String::new());
+        let errors = config.validate().unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].path, "models.aliases.default");
+        assert_eq!(errors[1].path, "models.aliases.empty");
+    }
+
     #[test]
     fn test_multiple_errors_collected() {
         let mut config = AisopodConfig::default();
@@ -2,7 +2,8 @@
 //!
 //! This module provides the `ConfigWatcher` struct that monitors a configuration
 //! file for changes and automatically reloads and validates the configuration
-//! when modifications are detected.
+//! when modifications are detected. Configurations fetched from a
+//! [`RemoteConfigSource`] are polled for changes instead.
 
 #![deny(unused_must_use)]
 
@@ -16,6 +17,7 @@ use tokio::sync::watch;
 use tracing::{debug, error, info, warn};
 
 use crate::loader::load_config;
+use crate::remote::RemoteConfigSource;
 use crate::types::AisopodConfig;
 
 /// Time to wait before reloading after a file change (debounce)
@@ -169,6 +171,63 @@ impl ConfigWatcher {
         })
     }
 
+    /// Start polling a remote configuration for changes.
+    ///
+    /// Loads the configuration from `source`, then fetches it again every
+    /// poll interval of the source. Unchanged configs are detected with their
+    /// entity tag and skipped without parsing. Changed configs are verified,
+    /// parsed and validated like the initial one; if valid, the new config is
+    /// sent via the watch channel; if not, the previous config is kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the initial configuration cannot be fetched,
+    /// verified or loaded.
+    pub async fn watch_remote(source: RemoteConfigSource) -> Result<Self> {
+        let (initial_config, mut etag) = source.load().await?;
+        let (tx, rx) = watch::channel(initial_config);
+        let tx = tokio::sync::Mutex::new(tx);
+        let (stop_sender, mut stop_receiver) = tokio::sync::oneshot::channel::<()>();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(source.poll_interval());
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately, right after the initial load
+            interval.tick().await;
+
+            loop {
+                tokio::select! {
+                    _ = &mut stop_receiver => {
+                        debug!("ConfigWatcher stop signal received");
+                        break;
+                    }
+                    _ = interval.tick() => {
+                        match source.fetch(etag.as_deref()).await {
+                            Ok(None) => debug!("Remote config '{}' not modified", source.url()),
+                            Ok(Some(document)) => match source.parse(&document.body) {
+                                Ok(new_config) => {
+                                    etag = document.etag;
+                                    publish(&tx, new_config, source.url()).await;
+                                }
+                                Err(e) => error!("{:#}. Keeping previous config.", e),
+                            },
+                            Err(e) => error!(
+                                "Failed to poll remote config: {:#}. Keeping previous config.",
+                                e
+                            ),
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            _watcher: None,
+            _stop_sender: Some(stop_sender),
+            receiver: rx,
+        })
+    }
+
     /// Get a receiver for the watch channel to receive config updates.
     pub fn receiver(&self) -> watch::Receiver<AisopodConfig> {
         self.receiver.clone()
@@ -202,16 +261,7 @@ async fn reload_and_send(
 
     match load_config(config_path) {
         Ok(new_config) => {
-            // Use the mutex to safely send the new config
-            let tx_guard = tx.lock().await;
-            if tx_guard.send(new_config).is_err() {
-                warn!("Config receiver dropped, stopping watcher");
-            } else {
-                info!(
-                    "Successfully reloaded and sent config from: {}",
-                    config_path.display()
-                );
-            }
+            publish(tx, new_config, &config_path.display().to_string()).await;
         }
         Err(e) => {
             error!(
@@ -223,6 +273,31 @@ async fn reload_and_send(
     }
 }
 
+/// Send a reloaded config to the receivers if any of its sections changed.
+async fn publish(
+    tx: &tokio::sync::Mutex<watch::Sender<AisopodConfig>>,
+    new_config: AisopodConfig,
+    origin: &str,
+) {
+    // Use the mutex to safely send the new config
+    let tx_guard = tx.lock().await;
+    let changed = diff_sections(&tx_guard.borrow(), &new_config);
+    if changed.is_empty() {
+        debug!("Config from '{}' is unchanged", origin);
+        return;
+    }
+
+    if tx_guard.send(new_config).is_err() {
+        warn!("Config receiver dropped, stopping watcher");
+    } else {
+        info!(
+            "Successfully reloaded and sent config from: {} (changed: {})",
+            origin,
+            changed.join(", ")
+        );
+    }
+}
+
 /// Identify which top-level sections changed between two configurations.
 ///
 /// Compares two configurations and returns a list of section names that differ.
@@ -373,6 +448,12 @@ mod tests {
             subagent_allowed_models: None,
             system_prompt: "Default system prompt".to_string(),
             skills: Vec::new(),
+            show_reasoning: false,
+            output_schema: None,
+            max_output_repairs: 2,
+            draft_verify: None,
+            prompt_template: None,
+            prompt_sections: Default::default(),
         });
 
         let changed = diff_sections(&old, &new);
@@ -396,6 +477,43 @@ mod tests {
         tokio::time::sleep(Duration::from_millis(50)).await;
     }
 
+    #[tokio::test]
+    async fn test_watcher_polls_remote_config() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/aisopod.json5"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(r#"{ meta: { version: "1.0" } }"#),
+            )
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/aisopod.json5"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(r#"{ meta: { version: "2.0" } }"#),
+            )
+            .mount(&server)
+            .await;
+
+        let source = RemoteConfigSource::new(&format!("{}/aisopod.json5", server.uri()))
+            .unwrap()
+            .with_poll_interval(Duration::from_millis(50));
+        let watcher = ConfigWatcher::watch_remote(source).await.unwrap();
+        let mut rx = watcher.receiver();
+        assert_eq!(rx.borrow().meta.version, "1.0");
+
+        tokio::time::timeout(Duration::from_secs(5), rx.changed())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(rx.borrow().meta.version, "2.0");
+    }
+
     #[tokio::test]
     async fn test_watcher_file_not_found() {
         let config_path = PathBuf::from("/nonexistent/path/config.json5");
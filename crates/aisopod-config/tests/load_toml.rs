@@ -104,6 +104,102 @@ port = 7777  # another inline comment
     assert_eq!(config.gateway.server.port, 7777);
 }
 
+#[test]
+fn test_load_config_toml_provider_routing() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let config_path = temp_dir.path().join("config.toml");
+
+    let config_content = r#"
+[[models.providers]]
+name = "openrouter"
+api_key = "sk-or-test"
+
+[models.providers.routing]
+order = ["anthropic", "google-vertex"]
+allow_fallbacks = false
+fallbacks = ["openai/gpt-4o"]
+
+[[models.providers]]
+name = "openai"
+"#;
+
+    std::fs::write(&config_path, config_content).expect("Failed to write test config");
+    fs::set_permissions(&config_path, fs::Permissions::from_mode(0o600))
+        .expect("Failed to set secure permissions");
+
+    let config = load_config(&config_path).expect("Failed to load config");
+    let routing = &config.models.providers[0].routing;
+    assert_eq!(routing.order, ["anthropic", "google-vertex"]);
+    assert_eq!(routing.allow_fallbacks, Some(false));
+    assert_eq!(routing.fallbacks, ["openai/gpt-4o"]);
+    assert_eq!(config.models.providers[1].routing, Default::default());
+}
+
+#[test]
+fn test_load_config_toml_model_prompt_caching() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let config_path = temp_dir.path().join("config.toml");
+
+    let config_content = r#"
+[[models.models]]
+id = "claude-sonnet-4-5"
+provider = "anthropic"
+prompt_caching = true
+
+[[models.models]]
+id = "claude-haiku-4-5"
+provider = "anthropic"
+"#;
+
+    std::fs::write(&config_path, config_content).expect("Failed to write test config");
+    fs::set_permissions(&config_path, fs::Permissions::from_mode(0o600))
+        .expect("Failed to set secure permissions");
+
+    let config = load_config(&config_path).expect("Failed to load config");
+    assert!(config.models.models[0].prompt_caching);
+    assert!(!config.models.models[1].prompt_caching);
+}
+
+#[test]
+fn test_load_config_toml_model_pricing() {
+    use aisopod_config::types::ModelPrice;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let config_path = temp_dir.path().join("config.toml");
+
+    let config_content = r#"
+[[models.models]]
+id = "gpt-4o"
+provider = "azure-openai"
+pricing = { input = 2.0, output = 8.0, cache_read = 1.0 }
+
+[[models.models]]
+id = "llama3.2"
+provider = "ollama"
+"#;
+
+    std::fs::write(&config_path, config_content).expect("Failed to write test config");
+    fs::set_permissions(&config_path, fs::Permissions::from_mode(0o600))
+        .expect("Failed to set secure permissions");
+
+    let config = load_config(&config_path).expect("Failed to load config");
+    assert_eq!(
+        config.models.models[0].pricing,
+        Some(ModelPrice {
+            input: 2.0,
+            output: 8.0,
+            cache_read: Some(1.0),
+            cache_write: None,
+        })
+    );
+    assert_eq!(config.models.models[1].pricing, None);
+}
+
 #[test]
 fn test_load_config_toml_file_not_found() {
     let config_path = PathBuf::from("/nonexistent/path/config.toml");
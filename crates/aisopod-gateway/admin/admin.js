@@ -0,0 +1,276 @@
+// aisopod admin UI
+//
+// Talks to the gateway over the REST API and the WebSocket RPC API. The
+// API token is kept in sessionStorage and sent as the Authorization header
+// on REST calls and as the `aisopod.bearer.<token>` subprotocol on the
+// WebSocket, since browsers cannot set headers on the handshake.
+
+"use strict";
+
+const TOKEN_KEY = "aisopod.admin.token";
+const MAX_EVENTS = 200;
+const REFRESH_MS = 15000;
+
+const $ = (selector, root = document) => root.querySelector(selector);
+
+let socket = null;
+let nextId = 1;
+// Calls awaiting a response; a connection answers its requests in order
+const pending = [];
+
+function token() {
+  return sessionStorage.getItem(TOKEN_KEY) || "";
+}
+
+function setConnection(text) {
+  $("#connection").textContent = text;
+}
+
+function showError(section, error) {
+  $(".error", section).textContent = error ? String(error.message || error) : "";
+}
+
+function cell(row, text) {
+  const td = row.insertCell();
+  td.textContent = text == null ? "" : String(text);
+  return td;
+}
+
+function fillTable(section, items, columns) {
+  const body = $("tbody", section);
+  body.replaceChildren();
+  for (const item of items) {
+    const row = body.insertRow();
+    for (const column of columns) {
+      cell(row, column(item, row));
+    }
+  }
+}
+
+// ---- WebSocket RPC ----
+
+function connect() {
+  if (socket) {
+    socket.onclose = null;
+    socket.close();
+  }
+  const protocols = ["aisopod"];
+  if (token()) {
+    protocols.push("aisopod.bearer." + token());
+  }
+  const scheme = location.protocol === "https:" ? "wss:" : "ws:";
+  socket = new WebSocket(`${scheme}//${location.host}/ws`, protocols);
+  setConnection("connecting");
+
+  socket.onopen = () => {
+    setConnection("connected");
+    refreshApprovals();
+  };
+  socket.onmessage = (message) => onMessage(JSON.parse(message.data));
+  socket.onclose = () => {
+    setConnection("disconnected");
+    for (const { reject } of pending.splice(0)) {
+      reject(new Error("connection closed"));
+    }
+    // The gateway closes idle connections; reconnect to keep events flowing
+    setTimeout(connect, 1000);
+  };
+}
+
+function onMessage(message) {
+  if (message.method === "gateway.event") {
+    logEvent(message.params);
+    if (message.params && message.params.type === "approval") {
+      refreshApprovals();
+    }
+    return;
+  }
+  const call = pending.shift();
+  if (!call) {
+    return;
+  }
+  if (message.error) {
+    call.reject(new Error(message.error.message));
+  } else {
+    call.resolve(message.result);
+  }
+}
+
+function rpc(method, params) {
+  return new Promise((resolve, reject) => {
+    if (!socket || socket.readyState !== WebSocket.OPEN) {
+      reject(new Error("not connected"));
+      return;
+    }
+    pending.push({ resolve, reject });
+    socket.send(JSON.stringify({ jsonrpc: "2.0", method, params, id: nextId++ }));
+  });
+}
+
+// ---- REST ----
+
+async function api(path) {
+  const headers = token() ? { Authorization: "Bearer " + token() } : {};
+  const response = await fetch(path, { headers });
+  if (!response.ok) {
+    throw new Error(`${response.status} ${response.statusText}`);
+  }
+  return response.json();
+}
+
+// ---- Sections ----
+
+async function refreshChannels() {
+  const section = $("#channels");
+  try {
+    const list = await api("/api/v1/channels");
+    fillTable(section, list.channels, [
+      (c) => c.id,
+      (c) => c.channel_type,
+      (c, row) => {
+        row.className = c.state;
+        return c.state;
+      },
+      (c) => c.last_error,
+    ]);
+    showError(section, null);
+  } catch (error) {
+    showError(section, error);
+  }
+}
+
+async function refreshSessions() {
+  const section = $("#sessions");
+  const peer = Object.fromEntries(new FormData($("#session-peer")));
+  if (!peer.agent_id) {
+    return;
+  }
+  try {
+    const result = await rpc("session.list", peer);
+    fillTable(section, result.sessions, [
+      (s) => s.name,
+      (s) => s.status,
+      (s) => s.message_count,
+      (s) => new Date(s.updated_at).toLocaleString(),
+      (s) => (s.active ? "yes" : ""),
+    ]);
+    showError(section, null);
+  } catch (error) {
+    showError(section, error);
+  }
+}
+
+async function refreshApprovals() {
+  const section = $("#approvals");
+  try {
+    const result = await rpc("approval.list", {});
+    const body = $("tbody", section);
+    body.replaceChildren();
+    for (const approval of result.approvals) {
+      const row = body.insertRow();
+      cell(row, approval.agent_id);
+      cell(row, approval.operation);
+      cell(row, approval.risk_level);
+      cell(row, new Date(approval.requested_at * 1000).toLocaleString());
+      const actions = row.insertCell();
+      actions.append(
+        decisionButton("Approve", "approval.approve", approval.id),
+        decisionButton("Deny", "approval.deny", approval.id),
+      );
+    }
+    showError(section, null);
+  } catch (error) {
+    showError(section, error);
+  }
+}
+
+function decisionButton(label, method, id) {
+  const button = document.createElement("button");
+  button.type = "button";
+  button.textContent = label;
+  button.onclick = async () => {
+    try {
+      await rpc(method, { id });
+    } catch (error) {
+      showError($("#approvals"), error);
+    }
+    refreshApprovals();
+  };
+  return button;
+}
+
+async function previewConfigDiff(event) {
+  event.preventDefault();
+  const section = $("#config");
+  const list = $(".sections", section);
+  list.replaceChildren();
+  try {
+    const params = Object.fromEntries(new FormData(event.target));
+    const result = await rpc("config.diff", params);
+    if (result.changed_sections.length === 0) {
+      list.append(Object.assign(document.createElement("li"), { textContent: "No changes" }));
+    }
+    for (const name of result.changed_sections) {
+      list.append(Object.assign(document.createElement("li"), { textContent: name }));
+    }
+    showError(section, null);
+  } catch (error) {
+    showError(section, error);
+  }
+}
+
+function logEvent(event) {
+  const log = $("#events .log");
+  const entry = document.createElement("li");
+  const time = document.createElement("time");
+  time.textContent = new Date().toLocaleTimeString();
+  const body = document.createElement("code");
+  body.textContent = JSON.stringify(event);
+  entry.append(time, " ", body);
+  log.prepend(entry);
+  while (log.children.length > MAX_EVENTS) {
+    log.lastChild.remove();
+  }
+}
+
+// ---- Wiring ----
+
+function refreshAll() {
+  refreshChannels();
+  refreshSessions();
+  refreshApprovals();
+}
+
+$("#login").addEventListener("submit", (event) => {
+  event.preventDefault();
+  const value = $("#token").value.trim();
+  if (value) {
+    sessionStorage.setItem(TOKEN_KEY, value);
+  }
+  $("#token").value = "";
+  connect();
+  refreshChannels();
+});
+
+$("#logout").addEventListener("click", () => {
+  sessionStorage.removeItem(TOKEN_KEY);
+  connect();
+});
+
+$("#session-peer").addEventListener("submit", (event) => {
+  event.preventDefault();
+  refreshSessions();
+});
+
+$("#config-diff").addEventListener("submit", previewConfigDiff);
+
+$("#clear-events").addEventListener("click", () => $("#events .log").replaceChildren());
+
+for (const button of document.querySelectorAll("[data-refresh]")) {
+  const refresh = { channels: refreshChannels, approvals: refreshApprovals }[button.dataset.refresh];
+  button.addEventListener("click", refresh);
+}
+
+connect();
+refreshChannels();
+setInterval(refreshAll, REFRESH_MS);
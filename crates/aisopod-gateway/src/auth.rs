@@ -4,7 +4,9 @@
 //! This module provides authentication validation functions and the AuthInfo struct
 //! that carries user role and scopes through the request pipeline.
 
+mod api_tokens;
 mod device_tokens;
+mod oidc;
 mod password;
 mod tokens;
 
@@ -12,7 +14,9 @@ use aisopod_config::sensitive::Sensitive;
 use aisopod_config::types::{AuthConfig, AuthMode, PasswordCredential, TokenCredential};
 use std::collections::HashMap;
 
+pub use api_tokens::{ApiToken, ApiTokenId, ApiTokenInfo, ApiTokenStore, TokenGrant, TokenPreset};
 pub use device_tokens::{DeviceToken, DeviceTokenInfo, DeviceTokenManager};
+pub use oidc::OidcValidator;
 pub use password::{hash_password, verify_password};
 pub use tokens::{generate_token, TokenStore};
 
@@ -46,6 +50,25 @@ impl AuthInfo {
     pub fn has_role(&self, role: &str) -> bool {
         self.role == role
     }
+
+    /// Check if the authenticated user may access the channel type `channel`
+    ///
+    /// Users holding no `channel:<type>` scope may access every channel.
+    pub fn allows_channel(&self, channel: &str) -> bool {
+        let mut restricted = self
+            .scopes
+            .iter()
+            .filter_map(|s| s.strip_prefix(scopes::CHANNEL_SCOPE_PREFIX))
+            .peekable();
+        restricted.peek().is_none() || restricted.any(|c| c == channel)
+    }
+
+    /// Check if the authenticated user is restricted to some channel types
+    pub fn is_channel_restricted(&self) -> bool {
+        self.scopes
+            .iter()
+            .any(|s| s.starts_with(scopes::CHANNEL_SCOPE_PREFIX))
+    }
 }
 
 /// Validate a Bearer token against the configured token credentials
@@ -371,4 +394,20 @@ mod tests {
         // This test is to ensure the function signature compiles
         // Actual token store tests are in the tokens module tests
     }
+
+    #[test]
+    fn test_allows_channel() {
+        let mut auth_info = AuthInfo {
+            role: "operator".to_string(),
+            scopes: vec!["operator.read".to_string()],
+        };
+        assert!(auth_info.allows_channel("telegram"));
+
+        auth_info.scopes.push("channel:telegram".to_string());
+        auth_info.scopes.push("channel:discord".to_string());
+        assert!(auth_info.allows_channel("telegram"));
+        assert!(auth_info.allows_channel("discord"));
+        assert!(!auth_info.allows_channel("slack"));
+        assert!(auth_info.is_channel_restricted());
+    }
 }
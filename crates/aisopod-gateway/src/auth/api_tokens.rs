@@ -0,0 +1,364 @@
+//! Scoped API tokens minted at runtime
+//!
+//! Besides the static tokens in the configuration, administrators can mint
+//! API tokens through the `token.*` RPC methods. Each token carries its own
+//! scopes, may be restricted to some channel types and may expire, so an
+//! integration can be handed exactly the access it needs and cut off again
+//! without editing the configuration.
+//!
+//! Tokens have the form `<id>.<secret>`. The store only keeps a SHA-256
+//! digest of the secret, so its file does not contain usable tokens.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::auth::scopes::{Scope, CHANNEL_SCOPE_PREFIX};
+use crate::auth::tokens::{constant_time_eq, generate_token};
+use crate::auth::AuthInfo;
+
+/// Role of the principals authenticated by API tokens
+const API_TOKEN_ROLE: &str = "operator";
+
+/// Common sets of scopes for new tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenPreset {
+    /// Full access, including minting further tokens
+    Admin,
+    /// Read-only access
+    ReadOnly,
+    /// Sending messages and reading chat history only
+    Chat,
+}
+
+impl TokenPreset {
+    /// The scopes granted by this preset
+    pub fn scopes(&self) -> Vec<Scope> {
+        match self {
+            Self::Admin => vec![Scope::OperatorAdmin],
+            Self::ReadOnly => vec![Scope::OperatorRead],
+            Self::Chat => vec![Scope::OperatorChat],
+        }
+    }
+}
+
+/// A minted API token as kept in the store
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    /// Base64 of the SHA-256 digest of the token's secret
+    pub secret_hash: String,
+    pub scopes: Vec<String>,
+    /// Channel types the token is restricted to; all when empty
+    #[serde(default)]
+    pub channels: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub revoked: bool,
+}
+
+impl ApiToken {
+    /// Whether the token is neither revoked nor expired at `now`
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        !self.revoked && self.expires_at.is_none_or(|expires_at| now < expires_at)
+    }
+
+    /// The principal the token authenticates
+    fn auth_info(&self) -> AuthInfo {
+        let channels = self
+            .channels
+            .iter()
+            .map(|channel| format!("{}{}", CHANNEL_SCOPE_PREFIX, channel));
+        AuthInfo {
+            role: API_TOKEN_ROLE.to_string(),
+            scopes: self.scopes.iter().cloned().chain(channels).collect(),
+        }
+    }
+}
+
+/// ID of the minted API token a request was authenticated with
+///
+/// The auth middleware stores it in the request extensions, so that
+/// long-lived connections can stop serving a token once it is revoked or
+/// expires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiTokenId(pub String);
+
+/// Information about an API token, without its secret
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiTokenInfo {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub channels: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked: bool,
+}
+
+impl From<&ApiToken> for ApiTokenInfo {
+    fn from(token: &ApiToken) -> Self {
+        Self {
+            id: token.id.clone(),
+            name: token.name.clone(),
+            scopes: token.scopes.clone(),
+            channels: token.channels.clone(),
+            created_at: token.created_at,
+            expires_at: token.expires_at,
+            revoked: token.revoked,
+        }
+    }
+}
+
+/// What a new token grants
+#[derive(Debug, Clone, Default)]
+pub struct TokenGrant {
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    /// Channel types to restrict the token to; all when empty
+    pub channels: Vec<String>,
+    /// Lifetime of the token; unlimited when `None`
+    pub expires_in: Option<Duration>,
+}
+
+/// Store of minted API tokens, optionally persisted to a file
+#[derive(Debug)]
+pub struct ApiTokenStore {
+    /// Map from token ID to token
+    tokens: Mutex<HashMap<String, ApiToken>>,
+    store_path: Option<PathBuf>,
+}
+
+impl ApiTokenStore {
+    /// Create a store that is not persisted
+    pub fn in_memory() -> Self {
+        Self {
+            tokens: Mutex::new(HashMap::new()),
+            store_path: None,
+        }
+    }
+
+    /// Open the store persisted at `store_path`, loading its tokens if the
+    /// file exists
+    pub fn open(store_path: PathBuf) -> Result<Self> {
+        let tokens = if store_path.exists() {
+            let data = std::fs::read_to_string(&store_path)
+                .with_context(|| format!("Failed to read {}", store_path.display()))?;
+            toml::from_str(&data)
+                .with_context(|| format!("Failed to parse {}", store_path.display()))?
+        } else {
+            HashMap::new()
+        };
+        Ok(Self {
+            tokens: Mutex::new(tokens),
+            store_path: Some(store_path),
+        })
+    }
+
+    /// Persist the tokens, if the store has a file
+    fn save(&self, tokens: &HashMap<String, ApiToken>) -> Result<()> {
+        let Some(path) = &self.store_path else {
+            return Ok(());
+        };
+        let data = toml::to_string_pretty(tokens)?;
+        std::fs::write(path, data).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Mint a token for `grant`. Returns the token's information and the
+    /// token itself, which is not retrievable later.
+    pub fn mint(&self, grant: TokenGrant) -> Result<(ApiTokenInfo, String)> {
+        if grant.scopes.is_empty() {
+            bail!("A token needs at least one scope");
+        }
+        let created_at = Utc::now();
+        let expires_at = match grant.expires_in {
+            Some(expires_in) => Some(
+                created_at
+                    + chrono::Duration::from_std(expires_in).context("Token lifetime too long")?,
+            ),
+            None => None,
+        };
+
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let secret = generate_token();
+        let token = ApiToken {
+            id: id.clone(),
+            name: grant.name,
+            secret_hash: digest(&secret),
+            scopes: grant
+                .scopes
+                .iter()
+                .map(|s| s.as_str().to_string())
+                .collect(),
+            channels: grant.channels,
+            created_at,
+            expires_at,
+            revoked: false,
+        };
+        let info = ApiTokenInfo::from(&token);
+
+        let mut tokens = self.tokens.lock().unwrap();
+        tokens.insert(id.clone(), token);
+        self.save(&tokens)?;
+        Ok((info, format!("{}.{}", id, secret)))
+    }
+
+    /// Validate a token. Returns the principal it authenticates if it is
+    /// known and active.
+    pub fn validate(&self, candidate: &str) -> Option<AuthInfo> {
+        let (id, secret) = candidate.split_once('.')?;
+        let tokens = self.tokens.lock().unwrap();
+        let token = tokens.get(id)?;
+        if !constant_time_eq(digest(secret).as_bytes(), token.secret_hash.as_bytes()) {
+            return None;
+        }
+        token.is_active(Utc::now()).then(|| token.auth_info())
+    }
+
+    /// The ID of `candidate` if it is a known and active token
+    pub fn active_id(&self, candidate: &str) -> Option<ApiTokenId> {
+        self.validate(candidate)?;
+        let (id, _) = candidate.split_once('.')?;
+        Some(ApiTokenId(id.to_string()))
+    }
+
+    /// Whether the token with ID `id` is known and active
+    pub fn is_active(&self, id: &ApiTokenId) -> bool {
+        let tokens = self.tokens.lock().unwrap();
+        tokens
+            .get(&id.0)
+            .is_some_and(|token| token.is_active(Utc::now()))
+    }
+
+    /// Revoke the token with ID `id`. Returns `false` if there is none.
+    pub fn revoke(&self, id: &str) -> Result<bool> {
+        let mut tokens = self.tokens.lock().unwrap();
+        let Some(token) = tokens.get_mut(id) else {
+            return Ok(false);
+        };
+        token.revoked = true;
+        self.save(&tokens)?;
+        Ok(true)
+    }
+
+    /// List all tokens, oldest first
+    pub fn list(&self) -> Vec<ApiTokenInfo> {
+        let tokens = self.tokens.lock().unwrap();
+        let mut infos: Vec<_> = tokens.values().map(ApiTokenInfo::from).collect();
+        infos.sort_by_key(|info| info.created_at);
+        infos
+    }
+}
+
+/// Digest of a token secret as kept in the store
+fn digest(secret: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(secret.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grant(scopes: Vec<Scope>) -> TokenGrant {
+        TokenGrant {
+            name: "ci".to_string(),
+            scopes,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_mint_and_validate() {
+        let store = ApiTokenStore::in_memory();
+        let (info, token) = store.mint(grant(TokenPreset::ReadOnly.scopes())).unwrap();
+        assert!(token.starts_with(&format!("{}.", info.id)));
+
+        let auth_info = store.validate(&token).unwrap();
+        assert_eq!(auth_info.role, "operator");
+        assert_eq!(auth_info.scopes, ["operator.read"]);
+
+        let (id, _) = token.split_once('.').unwrap();
+        assert!(store.validate(&format!("{}.wrong", id)).is_none());
+        assert!(store.validate("no-separator").is_none());
+    }
+
+    #[test]
+    fn test_channels_become_scopes() {
+        let store = ApiTokenStore::in_memory();
+        let (_, token) = store
+            .mint(TokenGrant {
+                channels: vec!["telegram".to_string()],
+                ..grant(TokenPreset::Chat.scopes())
+            })
+            .unwrap();
+
+        let auth_info = store.validate(&token).unwrap();
+        assert_eq!(auth_info.scopes, ["operator.chat", "channel:telegram"]);
+        assert!(auth_info.allows_channel("telegram"));
+        assert!(!auth_info.allows_channel("slack"));
+    }
+
+    #[test]
+    fn test_revoked_and_expired_tokens_are_rejected() {
+        let store = ApiTokenStore::in_memory();
+        let (info, token) = store.mint(grant(TokenPreset::Admin.scopes())).unwrap();
+        assert!(store.revoke(&info.id).unwrap());
+        assert!(store.validate(&token).is_none());
+        assert!(!store.revoke("missing").unwrap());
+
+        let (_, token) = store
+            .mint(TokenGrant {
+                expires_in: Some(Duration::ZERO),
+                ..grant(TokenPreset::Admin.scopes())
+            })
+            .unwrap();
+        assert!(store.validate(&token).is_none());
+    }
+
+    #[test]
+    fn test_active_id_follows_revocation() {
+        let store = ApiTokenStore::in_memory();
+        let (info, token) = store.mint(grant(TokenPreset::Chat.scopes())).unwrap();
+        let id = store.active_id(&token).unwrap();
+        assert_eq!(id, ApiTokenId(info.id.clone()));
+        assert!(store.is_active(&id));
+
+        store.revoke(&info.id).unwrap();
+        assert!(!store.is_active(&id));
+        assert!(store.active_id(&token).is_none());
+        assert!(!store.is_active(&ApiTokenId("missing".to_string())));
+    }
+
+    #[test]
+    fn test_mint_requires_scopes() {
+        let store = ApiTokenStore::in_memory();
+        assert!(store.mint(grant(vec![])).is_err());
+        assert!(store.list().is_empty());
+    }
+
+    #[test]
+    fn test_tokens_persist_without_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("api-tokens.toml");
+
+        let store = ApiTokenStore::open(path.clone()).unwrap();
+        let (info, token) = store.mint(grant(TokenPreset::ReadOnly.scopes())).unwrap();
+        let (_, secret) = token.split_once('.').unwrap();
+        assert!(!std::fs::read_to_string(&path).unwrap().contains(secret));
+
+        let reopened = ApiTokenStore::open(path).unwrap();
+        assert!(reopened.validate(&token).is_some());
+        assert_eq!(reopened.list()[0].id, info.id);
+    }
+}
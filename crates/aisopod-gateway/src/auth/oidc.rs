@@ -0,0 +1,497 @@
+//! OpenID Connect bearer-token validation
+//!
+//! In the `oidc` gateway mode, clients authenticate with JWTs issued by an
+//! identity provider. The provider's signing keys are located through its
+//! discovery document (or the configured `jwks_uri`) and cached for
+//! `jwks_cache_ttl` seconds; a token signed with an unknown key triggers an
+//! early refresh so key rotation is picked up without a restart. While the
+//! provider is unreachable, the previously fetched keys keep being used.
+
+use std::time::{Duration, Instant};
+
+use aisopod_config::types::OidcConfig;
+use anyhow::{anyhow, bail, Context, Result};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use tokio::sync::{Mutex, RwLock};
+
+use super::AuthInfo;
+
+/// Minimum time between two fetches of the signing keys, so tokens with
+/// unknown key IDs cannot make the gateway hammer the provider
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Algorithms accepted for token signatures; symmetric algorithms are
+/// rejected since the gateway holds no shared secret with the provider
+const ALLOWED_ALGORITHMS: &[Algorithm] = &[
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
+
+/// The part of an OpenID provider's discovery document we need
+#[derive(Debug, Deserialize)]
+struct DiscoveryDocument {
+    jwks_uri: String,
+}
+
+/// Signing keys fetched from the provider
+#[derive(Debug, Default)]
+struct CachedKeys {
+    /// Keys of the last successful fetch
+    keys: Option<JwkSet>,
+    fetched_at: Option<Instant>,
+    /// Time of the last fetch, whether or not it succeeded
+    attempted_at: Option<Instant>,
+}
+
+/// Validates OIDC bearer tokens and maps their claims to [`AuthInfo`]
+#[derive(Debug)]
+pub struct OidcValidator {
+    config: OidcConfig,
+    client: reqwest::Client,
+    keys: RwLock<CachedKeys>,
+    /// Held while fetching the keys, so concurrent requests share one fetch
+    refresh: Mutex<()>,
+    refresh_interval: Duration,
+}
+
+impl OidcValidator {
+    /// Create a validator for the provider in `config`; keys are fetched on
+    /// first use
+    pub fn new(config: OidcConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+            keys: RwLock::new(CachedKeys::default()),
+            refresh: Mutex::new(()),
+            refresh_interval: MIN_REFRESH_INTERVAL,
+        }
+    }
+
+    /// Validate `token` and return the principal it authenticates
+    pub async fn validate(&self, token: &str) -> Result<AuthInfo> {
+        let header = decode_header(token).context("Malformed token")?;
+        if !ALLOWED_ALGORITHMS.contains(&header.alg) {
+            bail!("Unsupported token algorithm {:?}", header.alg);
+        }
+        let key = self.decoding_key(header.kid.as_deref()).await?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.config.issuer]);
+        // Config validation requires audiences; without any, no token is
+        // accepted. Tokens without an audience are refused too.
+        validation.set_audience(&self.config.audiences);
+        validation.set_required_spec_claims(&["exp", "aud"]);
+        validation.leeway = self.config.leeway;
+
+        let claims = decode::<Map<String, Value>>(token, &key, &validation)
+            .context("Invalid token")?
+            .claims;
+        Ok(self.principal(&claims))
+    }
+
+    /// Map token claims to the principal's role and scopes
+    fn principal(&self, claims: &Map<String, Value>) -> AuthInfo {
+        let role = match claim(claims, &self.config.role_claim) {
+            Some(Value::String(role)) => Some(role.clone()),
+            Some(Value::Array(roles)) => roles.iter().find_map(|r| r.as_str()).map(String::from),
+            _ => None,
+        };
+        let scopes = match claim(claims, &self.config.scopes_claim) {
+            Some(Value::String(scopes)) => scopes.split_whitespace().map(String::from).collect(),
+            Some(Value::Array(scopes)) => scopes
+                .iter()
+                .filter_map(|s| s.as_str())
+                .map(String::from)
+                .collect(),
+            _ => Vec::new(),
+        };
+        AuthInfo {
+            role: role.unwrap_or_else(|| self.config.default_role.clone()),
+            scopes,
+        }
+    }
+
+    /// The key with ID `kid`, refreshing the cached keys when they expired
+    /// or do not contain it
+    async fn decoding_key(&self, kid: Option<&str>) -> Result<DecodingKey> {
+        if let Some(key) = self.cached_key(kid).await {
+            return key;
+        }
+
+        // Requests arriving during a refresh wait for it and use its keys
+        let _refresh = self.refresh.lock().await;
+        if let Some(key) = self.cached_key(kid).await {
+            return key;
+        }
+
+        let fetched = self.fetch_keys().await;
+        let mut cached = self.keys.write().await;
+        let now = Instant::now();
+        cached.attempted_at = Some(now);
+        match fetched {
+            Ok(keys) => {
+                let key = find_key(&keys, kid);
+                cached.keys = Some(keys);
+                cached.fetched_at = Some(now);
+                key.unwrap_or_else(|| missing_key(kid))
+            }
+            Err(e) => match cached.keys.as_ref().and_then(|keys| find_key(keys, kid)) {
+                Some(key) => {
+                    tracing::warn!(error = %e, "Failed to refresh OIDC signing keys, using cached keys");
+                    key
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    /// The key with ID `kid` from the cache, or `None` when the keys should
+    /// be fetched; keys are fetched at most once per refresh interval, and
+    /// expired keys are used until a fetch succeeds
+    async fn cached_key(&self, kid: Option<&str>) -> Option<Result<DecodingKey>> {
+        let cached = self.keys.read().await;
+        let throttled = cached.attempted_at?.elapsed() < self.refresh_interval;
+        let ttl = Duration::from_secs(self.config.jwks_cache_ttl);
+        let expired = cached.fetched_at.map_or(true, |at| at.elapsed() >= ttl);
+        match cached.keys.as_ref().and_then(|keys| find_key(keys, kid)) {
+            Some(key) if !expired || throttled => Some(key),
+            None if throttled => Some(match cached.keys {
+                Some(_) => missing_key(kid),
+                None => Err(anyhow!("The provider's signing keys are unavailable")),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Fetch the provider's signing keys
+    async fn fetch_keys(&self) -> Result<JwkSet> {
+        let jwks_uri = match &self.config.jwks_uri {
+            Some(uri) => uri.clone(),
+            None => {
+                let url = format!(
+                    "{}/.well-known/openid-configuration",
+                    self.config.issuer.trim_end_matches('/')
+                );
+                self.get_json::<DiscoveryDocument>(&url).await?.jwks_uri
+            }
+        };
+        tracing::debug!(jwks_uri = %jwks_uri, "Fetching OIDC signing keys");
+        self.get_json(&jwks_uri).await
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T> {
+        self.client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Failed to fetch {}", url))?
+            .json()
+            .await
+            .with_context(|| format!("Invalid response from {}", url))
+    }
+}
+
+/// The error for a key the provider does not have
+fn missing_key(kid: Option<&str>) -> Result<DecodingKey> {
+    bail!("No signing key {} at the provider", kid.unwrap_or("<none>"))
+}
+
+/// The key with ID `kid` in `keys`, or the only key when the token names
+/// none
+fn find_key(keys: &JwkSet, kid: Option<&str>) -> Option<Result<DecodingKey>> {
+    let jwk = match kid {
+        Some(kid) => keys.find(kid)?,
+        None if keys.keys.len() == 1 => &keys.keys[0],
+        None => return None,
+    };
+    Some(DecodingKey::from_jwk(jwk).map_err(|e| anyhow!("Unusable signing key: {}", e)))
+}
+
+/// The claim at the dotted `path`, e.g. `realm_access.roles`
+fn claim<'a>(claims: &'a Map<String, Value>, path: &str) -> Option<&'a Value> {
+    let mut parts = path.split('.');
+    let mut value = claims.get(parts.next()?)?;
+    for part in parts {
+        value = value.get(part)?;
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// An ES256 signing key and its JWK
+    fn signing_key(kid: &str) -> (EncodingKey, Value) {
+        let key_pair = rcgen::KeyPair::generate().unwrap();
+        let encoding_key = EncodingKey::from_ec_pem(key_pair.serialize_pem().as_bytes()).unwrap();
+        // Uncompressed point: 0x04 || x || y
+        let point = key_pair.public_key_raw();
+        let b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        let jwk = json!({
+            "kty": "EC",
+            "crv": "P-256",
+            "kid": kid,
+            "alg": "ES256",
+            "use": "sig",
+            "x": b64.encode(&point[1..33]),
+            "y": b64.encode(&point[33..65]),
+        });
+        (encoding_key, jwk)
+    }
+
+    fn sign(key: &EncodingKey, kid: &str, claims: Value) -> String {
+        let mut header = Header::new(Algorithm::ES256);
+        header.kid = Some(kid.to_string());
+        encode(&header, &claims, key).unwrap()
+    }
+
+    fn now() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    /// A provider serving discovery and the given keys, expecting the keys
+    /// to be fetched `fetches` times
+    async fn provider(jwks: Vec<Value>, fetches: u64) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/.well-known/openid-configuration"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "issuer": server.uri(),
+                "jwks_uri": format!("{}/jwks", server.uri()),
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/jwks"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "keys": jwks })))
+            .expect(fetches)
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn validator(server: &MockServer) -> OidcValidator {
+        OidcValidator::new(OidcConfig {
+            issuer: server.uri(),
+            audiences: vec!["aisopod".to_string()],
+            role_claim: "realm_access.roles".to_string(),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_validates_token_and_maps_claims() {
+        let (key, jwk) = signing_key("k1");
+        let server = provider(vec![jwk], 1).await;
+        let validator = validator(&server);
+
+        let token = sign(
+            &key,
+            "k1",
+            json!({
+                "iss": server.uri(),
+                "aud": "aisopod",
+                "sub": "alice",
+                "exp": now() + 300,
+                "scope": "operator.read operator.write",
+                "realm_access": { "roles": ["admin", "user"] },
+            }),
+        );
+        let auth_info = validator.validate(&token).await.unwrap();
+        assert_eq!(auth_info.role, "admin");
+        assert_eq!(auth_info.scopes, ["operator.read", "operator.write"]);
+
+        // Served from the cached keys; the role falls back to the default
+        let token = sign(
+            &key,
+            "k1",
+            json!({ "iss": server.uri(), "aud": "aisopod", "exp": now() + 300 }),
+        );
+        let auth_info = validator.validate(&token).await.unwrap();
+        assert_eq!(auth_info.role, "operator");
+        assert!(auth_info.scopes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_invalid_tokens() {
+        let (key, jwk) = signing_key("k1");
+        // Fetched once by each of the two validators
+        let server = provider(vec![jwk], 2).await;
+        let validator = validator(&server);
+        let claims = |extra: Value| {
+            let mut claims = json!({ "iss": server.uri(), "aud": "aisopod", "exp": now() + 300 });
+            claims
+                .as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            claims
+        };
+
+        for token in [
+            sign(&key, "k1", claims(json!({ "aud": "someone-else" }))),
+            sign(
+                &key,
+                "k1",
+                claims(json!({ "iss": "https://evil.example.com" })),
+            ),
+            sign(&key, "k1", claims(json!({ "exp": now() - 3600 }))),
+            sign(
+                &key,
+                "k1",
+                json!({ "iss": server.uri(), "exp": now() + 300 }),
+            ),
+        ] {
+            assert!(validator.validate(&token).await.is_err());
+        }
+
+        // Without configured audiences, no token is accepted
+        let open = OidcValidator::new(OidcConfig {
+            audiences: Vec::new(),
+            ..validator.config.clone()
+        });
+        let token = sign(&key, "k1", claims(json!({})));
+        assert!(open.validate(&token).await.is_err());
+
+        // A token signed by another key under the same ID
+        let (other_key, _) = signing_key("k1");
+        let token = sign(&other_key, "k1", claims(json!({})));
+        assert!(validator.validate(&token).await.is_err());
+
+        // Symmetric algorithms are refused before any key lookup
+        let token = encode(
+            &Header::new(Algorithm::HS256),
+            &claims(json!({})),
+            &EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap();
+        let error = validator.validate(&token).await.unwrap_err();
+        assert!(error.to_string().contains("Unsupported token algorithm"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_key_refreshes_at_most_once_per_interval() {
+        let (_, jwk) = signing_key("k1");
+        let server = provider(vec![jwk], 1).await;
+        let validator = validator(&server);
+
+        let (rotated, _) = signing_key("k2");
+        let token = sign(
+            &rotated,
+            "k2",
+            json!({ "iss": server.uri(), "aud": "aisopod", "exp": now() + 300 }),
+        );
+        // The first attempt fetches the keys; the second is refused from
+        // the cache rather than fetching them again
+        for _ in 0..2 {
+            let error = validator.validate(&token).await.unwrap_err();
+            assert!(error.to_string().contains("No signing key k2"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_share_one_fetch() {
+        let (key, jwk) = signing_key("k1");
+        let server = provider(vec![jwk], 1).await;
+        let validator = validator(&server);
+
+        let token = sign(
+            &key,
+            "k1",
+            json!({ "iss": server.uri(), "aud": "aisopod", "exp": now() + 300 }),
+        );
+        let (a, b, c) = tokio::join!(
+            validator.validate(&token),
+            validator.validate(&token),
+            validator.validate(&token),
+        );
+        assert!(a.is_ok() && b.is_ok() && c.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_failed_fetches_are_throttled() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let validator = validator(&server);
+
+        let (key, _) = signing_key("k1");
+        let token = sign(
+            &key,
+            "k1",
+            json!({ "iss": server.uri(), "aud": "aisopod", "exp": now() + 300 }),
+        );
+        // The second attempt does not reach the provider again
+        assert!(validator.validate(&token).await.is_err());
+        let error = validator.validate(&token).await.unwrap_err();
+        assert!(error.to_string().contains("unavailable"));
+    }
+
+    #[tokio::test]
+    async fn test_serves_cached_keys_when_refresh_fails() {
+        let (key, jwk) = signing_key("k1");
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/jwks"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "keys": [jwk] })))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/jwks"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(2)
+            .mount(&server)
+            .await;
+        // Keys expire at once and may be refreshed at any time
+        let mut validator = OidcValidator::new(OidcConfig {
+            issuer: server.uri(),
+            jwks_uri: Some(format!("{}/jwks", server.uri())),
+            audiences: vec!["aisopod".to_string()],
+            jwks_cache_ttl: 0,
+            ..Default::default()
+        });
+        validator.refresh_interval = Duration::ZERO;
+
+        let token = sign(
+            &key,
+            "k1",
+            json!({ "iss": server.uri(), "aud": "aisopod", "exp": now() + 300 }),
+        );
+        for _ in 0..3 {
+            validator.validate(&token).await.unwrap();
+        }
+    }
+
+    #[test]
+    fn test_claim_paths() {
+        let claims = json!({ "a": { "b": { "c": 1 } }, "role": "x" });
+        let claims = claims.as_object().unwrap();
+        assert_eq!(claim(claims, "a.b.c"), Some(&json!(1)));
+        assert_eq!(claim(claims, "role"), Some(&json!("x")));
+        assert_eq!(claim(claims, "a.missing"), None);
+    }
+}
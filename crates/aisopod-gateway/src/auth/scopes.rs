@@ -19,6 +19,8 @@ pub enum Scope {
     OperatorApprovals,
     /// Pairing access - allows device pairing operations
     OperatorPairing,
+    /// Chat access - allows sending messages and reading chat history only
+    OperatorChat,
 }
 
 impl Scope {
@@ -30,6 +32,20 @@ impl Scope {
             Self::OperatorWrite => "operator.write",
             Self::OperatorApprovals => "operator.approvals",
             Self::OperatorPairing => "operator.pairing",
+            Self::OperatorChat => "operator.chat",
+        }
+    }
+
+    /// Parse a scope from its string representation.
+    pub fn parse(scope: &str) -> Option<Self> {
+        match scope {
+            "operator.admin" => Some(Self::OperatorAdmin),
+            "operator.read" => Some(Self::OperatorRead),
+            "operator.write" => Some(Self::OperatorWrite),
+            "operator.approvals" => Some(Self::OperatorApprovals),
+            "operator.pairing" => Some(Self::OperatorPairing),
+            "operator.chat" => Some(Self::OperatorChat),
+            _ => None,
         }
     }
 
@@ -39,6 +55,7 @@ impl Scope {
     /// Write scope grants access to read and write scopes.
     /// Approvals scope grants access to read and approvals scopes.
     /// Pairing scope grants access to read and pairing scopes.
+    /// Chat scope grants access to no other scope; see [`Scope::allows_method`].
     pub fn allows(&self, target_scope: &Scope) -> bool {
         match self {
             Self::OperatorAdmin => true, // Admin can do everything
@@ -46,6 +63,21 @@ impl Scope {
             Self::OperatorWrite => matches!(target_scope, Scope::OperatorRead | Scope::OperatorWrite),
             Self::OperatorApprovals => matches!(target_scope, Scope::OperatorRead | Scope::OperatorApprovals),
             Self::OperatorPairing => matches!(target_scope, Scope::OperatorRead | Scope::OperatorPairing),
+            Self::OperatorChat => matches!(target_scope, Scope::OperatorChat),
+        }
+    }
+
+    /// Check if this scope grants access to the RPC method `method`.
+    ///
+    /// Besides the methods whose required scope it allows, the chat scope
+    /// grants access to the chat methods, whatever scope they require.
+    pub fn allows_method(&self, method: &str) -> bool {
+        match required_scope(method) {
+            None => true,
+            Some(required) => {
+                self.allows(required)
+                    || (*self == Self::OperatorChat && CHAT_METHODS.contains(&method))
+            }
         }
     }
 }
@@ -56,6 +88,24 @@ impl std::fmt::Display for Scope {
     }
 }
 
+/// Methods a token with only the chat scope may call
+const CHAT_METHODS: &[&str] = &["chat.send", "chat.history"];
+
+/// Prefix of the scopes restricting a principal to one channel type, e.g.
+/// `channel:telegram`
+pub const CHANNEL_SCOPE_PREFIX: &str = "channel:";
+
+/// Methods acting on one channel, named by their `channel` parameter
+pub const CHANNEL_METHODS: &[&str] = &[
+    "chat.send",
+    "session.export",
+    "session.list",
+    "session.create",
+    "session.switch",
+    "session.rename",
+    "session.archive",
+];
+
 /// Get the required scope for a method, if any.
 ///
 /// Returns `Some(&Scope)` if the method requires a scope,
@@ -78,13 +128,16 @@ static METHOD_SCOPES: std::sync::LazyLock<HashMap<&'static str, Scope>> = std::s
     m.insert("agent.get", Scope::OperatorRead);
     m.insert("session.list", Scope::OperatorRead);
     m.insert("session.get", Scope::OperatorRead);
+    m.insert("session.export", Scope::OperatorRead);
     m.insert("chat.history", Scope::OperatorRead);
     m.insert("tools.list", Scope::OperatorRead);
     m.insert("models.list", Scope::OperatorRead);
     m.insert("channels.list", Scope::OperatorRead);
     m.insert("config.get", Scope::OperatorRead);
+    m.insert("config.diff", Scope::OperatorRead);
     m.insert("health.check", Scope::OperatorRead);
     m.insert("memory.query", Scope::OperatorRead);
+    m.insert("memory.search", Scope::OperatorRead);
     m.insert("approval.list", Scope::OperatorRead);
 
     // Write methods (create/update endpoints)
@@ -93,7 +146,12 @@ static METHOD_SCOPES: std::sync::LazyLock<HashMap<&'static str, Scope>> = std::s
     m.insert("chat.send", Scope::OperatorWrite);
     m.insert("session.create", Scope::OperatorWrite);
     m.insert("session.close", Scope::OperatorWrite);
+    m.insert("session.switch", Scope::OperatorWrite);
+    m.insert("session.rename", Scope::OperatorWrite);
+    m.insert("session.archive", Scope::OperatorWrite);
     m.insert("config.update", Scope::OperatorWrite);
+    m.insert("memory.delete", Scope::OperatorWrite);
+    m.insert("memory.update", Scope::OperatorWrite);
 
     // Approval methods (approve/reject endpoints)
     m.insert("approval.request", Scope::OperatorApprovals);
@@ -106,6 +164,9 @@ static METHOD_SCOPES: std::sync::LazyLock<HashMap<&'static str, Scope>> = std::s
 
     // Admin methods (destructive/administrative endpoints)
     m.insert("admin.shutdown", Scope::OperatorAdmin);
+    m.insert("token.create", Scope::OperatorAdmin);
+    m.insert("token.revoke", Scope::OperatorAdmin);
+    m.insert("token.list", Scope::OperatorAdmin);
 
     m
 });
@@ -128,6 +189,36 @@ mod tests {
         assert_eq!(Scope::OperatorWrite.as_str(), "operator.write");
         assert_eq!(Scope::OperatorApprovals.as_str(), "operator.approvals");
         assert_eq!(Scope::OperatorPairing.as_str(), "operator.pairing");
+        assert_eq!(Scope::OperatorChat.as_str(), "operator.chat");
+    }
+
+    #[test]
+    fn test_scope_parse_round_trips() {
+        for scope in [
+            Scope::OperatorAdmin,
+            Scope::OperatorRead,
+            Scope::OperatorWrite,
+            Scope::OperatorApprovals,
+            Scope::OperatorPairing,
+            Scope::OperatorChat,
+        ] {
+            assert_eq!(Scope::parse(scope.as_str()), Some(scope));
+        }
+        assert_eq!(Scope::parse("operator.root"), None);
+    }
+
+    #[test]
+    fn test_chat_scope_allows_only_chat_methods() {
+        assert!(Scope::OperatorChat.allows_method("chat.send"));
+        assert!(Scope::OperatorChat.allows_method("chat.history"));
+        assert!(!Scope::OperatorChat.allows_method("agent.list"));
+        assert!(!Scope::OperatorChat.allows_method("session.export"));
+        assert!(!Scope::OperatorChat.allows_method("token.create"));
+
+        assert!(Scope::OperatorWrite.allows_method("chat.send"));
+        assert!(!Scope::OperatorRead.allows_method("chat.send"));
+        assert!(!Scope::OperatorWrite.allows_method("token.create"));
+        assert!(Scope::OperatorAdmin.allows_method("token.revoke"));
     }
 
     #[test]
@@ -183,11 +274,14 @@ mod tests {
         assert_eq!(required_scope("system.ping"), Some(&Scope::OperatorRead));
         assert_eq!(required_scope("agent.list"), Some(&Scope::OperatorRead));
         assert_eq!(required_scope("session.get"), Some(&Scope::OperatorRead));
+        assert_eq!(required_scope("memory.search"), Some(&Scope::OperatorRead));
 
         // Write methods
         assert_eq!(required_scope("agent.start"), Some(&Scope::OperatorWrite));
         assert_eq!(required_scope("chat.send"), Some(&Scope::OperatorWrite));
         assert_eq!(required_scope("config.update"), Some(&Scope::OperatorWrite));
+        assert_eq!(required_scope("memory.delete"), Some(&Scope::OperatorWrite));
+        assert_eq!(required_scope("memory.update"), Some(&Scope::OperatorWrite));
 
         // Approval methods
         assert_eq!(required_scope("approval.request"), Some(&Scope::OperatorApprovals));
@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashSet;
 use std::sync::Arc;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
 
 /// Subscription filter for a client's event preferences
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -129,19 +129,40 @@ impl GatewayEvent {
 pub struct Broadcaster {
     /// Underlying broadcast sender
     sender: broadcast::Sender<GatewayEvent>,
+    /// Relay of published events to the other nodes of a cluster
+    relay: Option<mpsc::UnboundedSender<GatewayEvent>>,
 }
 
 impl Broadcaster {
     /// Create a new broadcaster with the specified channel capacity
     pub fn new(capacity: usize) -> Self {
         let (sender, _) = broadcast::channel(capacity);
-        Self { sender }
+        Self {
+            sender,
+            relay: None,
+        }
+    }
+
+    /// Also send every published event to `relay`
+    pub fn with_relay(mut self, relay: mpsc::UnboundedSender<GatewayEvent>) -> Self {
+        self.relay = Some(relay);
+        self
     }
 
     /// Publish an event to all subscribers
     ///
     /// Returns the number of subscribers that received the event
     pub fn publish(&self, event: GatewayEvent) -> usize {
+        if let Some(relay) = &self.relay {
+            let _ = relay.send(event.clone());
+        }
+        self.publish_local(event)
+    }
+
+    /// Publish an event to this node's subscribers only
+    ///
+    /// Used for events relayed from other nodes of a cluster.
+    pub fn publish_local(&self, event: GatewayEvent) -> usize {
         self.sender.send(event).map_or(0, |count| count)
     }
 
@@ -308,6 +329,26 @@ mod tests {
         assert!(rx3.try_recv().is_ok());
     }
 
+    #[test]
+    fn test_broadcaster_relay() {
+        let (relay, mut relayed) = mpsc::unbounded_channel();
+        let broadcaster = Broadcaster::new(16).with_relay(relay);
+        let mut rx = broadcaster.subscribe();
+
+        let event = GatewayEvent::Agent {
+            agent_id: "agent-1".to_string(),
+            event: "created".to_string(),
+        };
+        broadcaster.publish(event.clone());
+        assert_eq!(relayed.try_recv().unwrap(), event);
+        assert_eq!(rx.try_recv().unwrap(), event);
+
+        // Events from other nodes reach local subscribers without being relayed back
+        broadcaster.publish_local(event.clone());
+        assert_eq!(rx.try_recv().unwrap(), event);
+        assert!(relayed.try_recv().is_err());
+    }
+
     #[test]
     fn test_broadcaster_event_serialization() {
         let event = GatewayEvent::Presence {
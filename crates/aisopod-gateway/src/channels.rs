@@ -0,0 +1,69 @@
+//! Channels of the gateway
+//!
+//! The process embedding the gateway registers its channel plugins in a
+//! [`ChannelRegistry`] and hands it over as [`GatewayChannels`]. Agents
+//! reach users through those channels: the replies of scheduled runs are
+//! delivered to their targets with the channels' message sender.
+//!
+//! When `tools.approval` names an operator conversation, the agents' tool
+//! operations needing approval are asked for there. The process routing
+//! the incoming messages passes [`GatewayChannels::approval_handler`] to
+//! its [`MessageRouter`](aisopod_channel::MessageRouter), so that the
+//! operators' replies reach the waiting requests.
+
+use std::sync::{Arc, RwLock};
+
+use aisopod_channel::{ChannelApprovalHandler, ChannelMessageSender, ChannelRegistry};
+use aisopod_config::types::ApprovalConfig;
+use aisopod_tools::MessageSender;
+
+/// The channels the gateway's agents send messages through
+#[derive(Clone)]
+pub struct GatewayChannels {
+    registry: Arc<RwLock<ChannelRegistry>>,
+    sender: Arc<ChannelMessageSender>,
+    approvals: Option<Arc<ChannelApprovalHandler>>,
+}
+
+impl GatewayChannels {
+    /// Use the channels of `registry`
+    pub fn new(registry: Arc<RwLock<ChannelRegistry>>) -> Self {
+        Self {
+            sender: Arc::new(ChannelMessageSender::new(registry.clone())),
+            registry,
+            approvals: None,
+        }
+    }
+
+    /// Ask for approvals in the operator conversation of `config`
+    ///
+    /// Nothing changes when no operator channel and peer are configured.
+    pub fn with_approvals(mut self, config: &ApprovalConfig) -> Self {
+        if let Some(handler) = ChannelApprovalHandler::from_config(self.registry.clone(), config) {
+            self.approvals = Some(Arc::new(handler));
+        }
+        self
+    }
+
+    /// The registry of the channels
+    pub fn registry(&self) -> &Arc<RwLock<ChannelRegistry>> {
+        &self.registry
+    }
+
+    /// The sender delivering messages through the channels
+    pub fn sender(&self) -> Arc<dyn MessageSender> {
+        self.sender.clone()
+    }
+
+    /// The handler asking the operators for approvals, if configured
+    pub fn approval_handler(&self) -> Option<&Arc<ChannelApprovalHandler>> {
+        self.approvals.as_ref()
+    }
+}
+
+impl Default for GatewayChannels {
+    /// No channels; sending a message fails with an unknown channel
+    fn default() -> Self {
+        Self::new(Arc::new(RwLock::new(ChannelRegistry::new())))
+    }
+}
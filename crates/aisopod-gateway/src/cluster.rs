@@ -0,0 +1,545 @@
+//! Multi-node clustering
+//!
+//! Gateway instances behind a load balancer join a cluster so that a
+//! WebSocket client sees the same events whichever node it is connected
+//! to. Each node relays the events published on its [`Broadcaster`] and the
+//! changes made to its [`ApprovalStore`] over a [`ClusterBus`]; the other
+//! nodes re-publish the events to their own clients and apply the changes
+//! to their copy of the approval store. Pending approvals are also kept in
+//! the bus's shared state, which a node loads when it joins.
+//!
+//! Approvals are replicated, not locked: two operators deciding the same
+//! approval on different nodes at the same moment may both succeed.
+//!
+//! Session state is shared through the session crate's Postgres store and
+//! Redis cache, so it needs no relaying here. Agent runs take the lease of
+//! their session in that store, owned by the node's ID, so that two nodes
+//! never process the same session at once. The Redis bus is behind the
+//! `cluster` feature; [`MemoryClusterBus`] connects nodes in one process,
+//! and is used for a `redis_url` of the form `memory://<name>`.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, warn};
+
+use crate::broadcast::{Broadcaster, GatewayEvent};
+use crate::rpc::approval::{ApprovalChange, ApprovalStore, PendingApproval};
+use aisopod_config::types::ClusterConfig;
+
+/// Capacity of the channel delivering messages from the bus
+const INCOMING_CAPACITY: usize = 256;
+
+/// Prefix of the bus URLs naming a [`MemoryClusterBus`] of this process
+const MEMORY_BUS_PREFIX: &str = "memory://";
+
+/// A message exchanged between the nodes of a cluster
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClusterMessage {
+    /// ID of the node the message comes from
+    pub origin: String,
+    /// What happened on that node
+    #[serde(flatten)]
+    pub payload: ClusterPayload,
+}
+
+/// The content of a [`ClusterMessage`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ClusterPayload {
+    /// An event was broadcast to the node's clients
+    Event { event: GatewayEvent },
+    /// The node's approval store changed
+    Approval { change: ApprovalChange },
+}
+
+/// Transport and shared state of a cluster
+#[async_trait]
+pub trait ClusterBus: Send + Sync {
+    /// Publishes a message to every node, including this one
+    async fn publish(&self, message: &ClusterMessage) -> Result<()>;
+
+    /// Subscribes to the messages published by all nodes
+    async fn subscribe(&self) -> Result<mpsc::Receiver<ClusterMessage>>;
+
+    /// Adds a pending approval to the shared state
+    async fn save_approval(&self, approval: &PendingApproval) -> Result<()>;
+
+    /// Removes a decided approval from the shared state
+    async fn remove_approval(&self, id: &str) -> Result<()>;
+
+    /// Lists the pending approvals in the shared state
+    async fn load_approvals(&self) -> Result<Vec<PendingApproval>>;
+}
+
+/// Cluster bus connecting nodes in the same process
+#[derive(Clone)]
+pub struct MemoryClusterBus {
+    messages: broadcast::Sender<ClusterMessage>,
+    approvals: Arc<Mutex<HashMap<String, PendingApproval>>>,
+}
+
+impl MemoryClusterBus {
+    /// Create a bus with no nodes
+    pub fn new() -> Self {
+        let (messages, _) = broadcast::channel(INCOMING_CAPACITY);
+        Self {
+            messages,
+            approvals: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl Default for MemoryClusterBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ClusterBus for MemoryClusterBus {
+    async fn publish(&self, message: &ClusterMessage) -> Result<()> {
+        let _ = self.messages.send(message.clone());
+        Ok(())
+    }
+
+    async fn subscribe(&self) -> Result<mpsc::Receiver<ClusterMessage>> {
+        let mut messages = self.messages.subscribe();
+        let (tx, rx) = mpsc::channel(INCOMING_CAPACITY);
+        tokio::spawn(async move {
+            loop {
+                match messages.recv().await {
+                    Ok(message) => {
+                        if tx.send(message).await.is_err() {
+                            return;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Cluster subscriber lagged, skipped {} messages", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                }
+            }
+        });
+        Ok(rx)
+    }
+
+    async fn save_approval(&self, approval: &PendingApproval) -> Result<()> {
+        let mut approvals = self.approvals.lock().unwrap();
+        approvals.insert(approval.id.clone(), approval.clone());
+        Ok(())
+    }
+
+    async fn remove_approval(&self, id: &str) -> Result<()> {
+        self.approvals.lock().unwrap().remove(id);
+        Ok(())
+    }
+
+    async fn load_approvals(&self) -> Result<Vec<PendingApproval>> {
+        Ok(self.approvals.lock().unwrap().values().cloned().collect())
+    }
+}
+
+/// This node's membership of a cluster
+pub struct Cluster {
+    node_id: String,
+    bus: Arc<dyn ClusterBus>,
+}
+
+impl Cluster {
+    /// Create a cluster member named `node_id` communicating over `bus`
+    pub fn new(node_id: impl Into<String>, bus: Arc<dyn ClusterBus>) -> Self {
+        Self {
+            node_id: node_id.into(),
+            bus,
+        }
+    }
+
+    /// The ID of this node
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// Join the cluster, relaying the node's events and approvals
+    ///
+    /// Returns the broadcaster and approval store to use in place of the
+    /// given ones. The approval store starts with the cluster's pending
+    /// approvals. Relaying runs in background tasks until the bus's
+    /// subscription ends.
+    pub async fn join(
+        &self,
+        broadcaster: Broadcaster,
+        approvals: ApprovalStore,
+    ) -> Result<(Broadcaster, ApprovalStore)> {
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        let (change_tx, change_rx) = mpsc::unbounded_channel();
+        let broadcaster = broadcaster.with_relay(event_tx);
+        let approvals = approvals.with_replication(change_tx);
+
+        // Subscribe before loading the snapshot so no change falls in between
+        let incoming = self.bus.subscribe().await?;
+        for approval in self.bus.load_approvals().await? {
+            approvals.apply(ApprovalChange::Stored { approval });
+        }
+
+        tokio::spawn(relay_outgoing(
+            self.node_id.clone(),
+            self.bus.clone(),
+            event_rx,
+            change_rx,
+        ));
+        tokio::spawn(apply_incoming(
+            self.node_id.clone(),
+            incoming,
+            broadcaster.clone(),
+            approvals.clone(),
+        ));
+
+        Ok((broadcaster, approvals))
+    }
+}
+
+/// Join the cluster described by `config`, if enabled
+///
+/// Returns the given broadcaster and approval store unchanged when
+/// clustering is disabled.
+pub async fn join_configured(
+    config: &ClusterConfig,
+    broadcaster: Broadcaster,
+    approvals: ApprovalStore,
+) -> Result<(Broadcaster, ApprovalStore)> {
+    if !config.enabled {
+        return Ok((broadcaster, approvals));
+    }
+    let bus = connect_bus(config).await?;
+    let cluster = Cluster::new(node_id(config), bus);
+    tracing::info!(node_id = %cluster.node_id(), channel = %config.channel, "Joining gateway cluster");
+    cluster.join(broadcaster, approvals).await
+}
+
+/// The name of this node in the cluster described by `config`, generated
+/// when not configured
+pub fn node_id(config: &ClusterConfig) -> String {
+    if config.node_id.is_empty() {
+        format!("node-{}", uuid::Uuid::new_v4().simple())
+    } else {
+        config.node_id.clone()
+    }
+}
+
+async fn connect_bus(config: &ClusterConfig) -> Result<Arc<dyn ClusterBus>> {
+    match config.redis_url.expose().strip_prefix(MEMORY_BUS_PREFIX) {
+        Some(name) => Ok(Arc::new(memory_bus(name))),
+        None => connect_redis_bus(config).await,
+    }
+}
+
+/// The in-process bus called `name`, shared by every node joining it
+fn memory_bus(name: &str) -> MemoryClusterBus {
+    static BUSES: OnceLock<Mutex<HashMap<String, MemoryClusterBus>>> = OnceLock::new();
+    let mut buses = BUSES.get_or_init(Default::default).lock().unwrap();
+    buses.entry(name.to_string()).or_default().clone()
+}
+
+#[cfg(feature = "cluster")]
+async fn connect_redis_bus(config: &ClusterConfig) -> Result<Arc<dyn ClusterBus>> {
+    let bus =
+        redis_bus::RedisClusterBus::connect(config.redis_url.expose(), &config.channel).await?;
+    Ok(Arc::new(bus))
+}
+
+#[cfg(not(feature = "cluster"))]
+async fn connect_redis_bus(_config: &ClusterConfig) -> Result<Arc<dyn ClusterBus>> {
+    anyhow::bail!("gateway.cluster is enabled, but aisopod was built without the 'cluster' feature")
+}
+
+/// Publish this node's events and approval changes on the bus
+async fn relay_outgoing(
+    node_id: String,
+    bus: Arc<dyn ClusterBus>,
+    mut events: mpsc::UnboundedReceiver<GatewayEvent>,
+    mut changes: mpsc::UnboundedReceiver<ApprovalChange>,
+) {
+    loop {
+        let payload = tokio::select! {
+            Some(event) = events.recv() => ClusterPayload::Event { event },
+            Some(change) = changes.recv() => {
+                let stored = match &change {
+                    ApprovalChange::Stored { approval } => bus.save_approval(approval).await,
+                    ApprovalChange::Removed { id } => bus.remove_approval(id).await,
+                };
+                if let Err(e) = stored {
+                    warn!("Failed to update the cluster's approvals: {:#}", e);
+                }
+                ClusterPayload::Approval { change }
+            }
+            else => return,
+        };
+        let message = ClusterMessage {
+            origin: node_id.clone(),
+            payload,
+        };
+        if let Err(e) = bus.publish(&message).await {
+            warn!("Failed to relay a message to the cluster: {:#}", e);
+        }
+    }
+}
+
+/// Apply the messages of the other nodes to this node
+async fn apply_incoming(
+    node_id: String,
+    mut incoming: mpsc::Receiver<ClusterMessage>,
+    broadcaster: Broadcaster,
+    approvals: ApprovalStore,
+) {
+    while let Some(message) = incoming.recv().await {
+        if message.origin == node_id {
+            continue;
+        }
+        debug!(origin = %message.origin, "Applying cluster message");
+        match message.payload {
+            ClusterPayload::Event { event } => {
+                broadcaster.publish_local(event);
+            }
+            ClusterPayload::Approval { change } => approvals.apply(change),
+        }
+    }
+}
+
+#[cfg(feature = "cluster")]
+mod redis_bus {
+    use super::*;
+    use anyhow::{anyhow, Context};
+    use futures_util::StreamExt;
+    use redis::aio::ConnectionManager;
+    use redis::AsyncCommands;
+    use std::time::Duration;
+
+    /// Delay before subscribing again after losing the subscription
+    const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+
+    /// Cluster bus over Redis pub/sub, keeping approvals in a Redis hash
+    ///
+    /// Messages are published on the configured channel; pending approvals
+    /// are kept in the hash `<channel>:approvals`.
+    pub struct RedisClusterBus {
+        client: redis::Client,
+        connection: ConnectionManager,
+        channel: String,
+        approvals_key: String,
+    }
+
+    impl RedisClusterBus {
+        /// Connect to Redis at `url`, using `channel` for messages
+        pub async fn connect(url: &str, channel: &str) -> Result<Self> {
+            let client =
+                redis::Client::open(url).map_err(|e| anyhow!("Invalid Redis URL: {}", e))?;
+            let connection = ConnectionManager::new(client.clone())
+                .await
+                .map_err(|e| anyhow!("Failed to connect to Redis: {}", e))?;
+            Ok(Self {
+                client,
+                connection,
+                channel: channel.to_string(),
+                approvals_key: format!("{}:approvals", channel),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl ClusterBus for RedisClusterBus {
+        async fn publish(&self, message: &ClusterMessage) -> Result<()> {
+            let payload = serde_json::to_string(message)?;
+            let mut connection = self.connection.clone();
+            let _: usize = connection.publish(&self.channel, payload).await?;
+            Ok(())
+        }
+
+        async fn subscribe(&self) -> Result<mpsc::Receiver<ClusterMessage>> {
+            let mut pubsub = self.client.get_async_pubsub().await?;
+            pubsub.subscribe(&self.channel).await?;
+            let (tx, rx) = mpsc::channel(INCOMING_CAPACITY);
+            let client = self.client.clone();
+            let channel = self.channel.clone();
+            tokio::spawn(async move {
+                loop {
+                    let mut messages = pubsub.into_on_message();
+                    while let Some(message) = messages.next().await {
+                        let decoded = message
+                            .get_payload::<String>()
+                            .context("Non-text cluster message")
+                            .and_then(|payload| Ok(serde_json::from_str(&payload)?));
+                        match decoded {
+                            Ok(message) => {
+                                if tx.send(message).await.is_err() {
+                                    return;
+                                }
+                            }
+                            Err(e) => warn!("Ignoring invalid cluster message: {:#}", e),
+                        }
+                    }
+                    warn!("Lost the cluster subscription, resubscribing");
+                    pubsub = loop {
+                        tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+                        let resubscribed = async {
+                            let mut pubsub = client.get_async_pubsub().await?;
+                            pubsub.subscribe(&channel).await?;
+                            Ok::<_, redis::RedisError>(pubsub)
+                        };
+                        match resubscribed.await {
+                            Ok(pubsub) => break pubsub,
+                            Err(e) => warn!("Failed to resubscribe to the cluster: {}", e),
+                        }
+                    };
+                }
+            });
+            Ok(rx)
+        }
+
+        async fn save_approval(&self, approval: &PendingApproval) -> Result<()> {
+            let value = serde_json::to_string(approval)?;
+            let mut connection = self.connection.clone();
+            let _: usize = connection
+                .hset(&self.approvals_key, &approval.id, value)
+                .await?;
+            Ok(())
+        }
+
+        async fn remove_approval(&self, id: &str) -> Result<()> {
+            let mut connection = self.connection.clone();
+            let _: usize = connection.hdel(&self.approvals_key, id).await?;
+            Ok(())
+        }
+
+        async fn load_approvals(&self) -> Result<Vec<PendingApproval>> {
+            let mut connection = self.connection.clone();
+            let values: HashMap<String, String> = connection.hgetall(&self.approvals_key).await?;
+            values
+                .values()
+                .map(|value| Ok(serde_json::from_str(value)?))
+                .collect()
+        }
+    }
+}
+
+#[cfg(feature = "cluster")]
+pub use redis_bus::RedisClusterBus;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn node(node_id: &str, bus: &MemoryClusterBus) -> (Broadcaster, ApprovalStore) {
+        Cluster::new(node_id, Arc::new(bus.clone()))
+            .join(Broadcaster::new(16), ApprovalStore::new())
+            .await
+            .unwrap()
+    }
+
+    /// Wait for the relay tasks to deliver pending messages
+    async fn settle() {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    #[tokio::test]
+    async fn test_events_reach_other_nodes_once() {
+        let bus = MemoryClusterBus::new();
+        let (broadcaster_a, _) = node("a", &bus).await;
+        let (broadcaster_b, _) = node("b", &bus).await;
+        let mut events_a = broadcaster_a.subscribe();
+        let mut events_b = broadcaster_b.subscribe();
+
+        let event = GatewayEvent::Agent {
+            agent_id: "agent-1".to_string(),
+            event: "created".to_string(),
+        };
+        broadcaster_a.publish(event.clone());
+        settle().await;
+
+        assert_eq!(events_a.try_recv().unwrap(), event);
+        assert!(events_a.try_recv().is_err());
+        assert_eq!(events_b.try_recv().unwrap(), event);
+        assert!(events_b.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_approvals_are_shared() {
+        let bus = MemoryClusterBus::new();
+        let (_, approvals_a) = node("a", &bus).await;
+        let (_, approvals_b) = node("b", &bus).await;
+
+        let approval = PendingApproval::new(
+            "agent-1".to_string(),
+            "rm -rf /tmp/x".to_string(),
+            "High".to_string(),
+        );
+        let id = approval.id.clone();
+        approvals_a.store(approval);
+        settle().await;
+        assert!(approvals_b.get(&id).is_some());
+
+        // A node joining later starts with the pending approvals
+        let (_, approvals_c) = node("c", &bus).await;
+        assert!(approvals_c.get(&id).is_some());
+
+        approvals_b.remove(&id);
+        settle().await;
+        assert!(approvals_a.get(&id).is_none());
+        assert!(approvals_c.get(&id).is_none());
+        assert!(bus.load_approvals().await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_message_serialization() {
+        let message = ClusterMessage {
+            origin: "a".to_string(),
+            payload: ClusterPayload::Approval {
+                change: ApprovalChange::Removed {
+                    id: "req-1".to_string(),
+                },
+            },
+        };
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "origin": "a",
+                "kind": "approval",
+                "change": { "change": "removed", "id": "req-1" }
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<ClusterMessage>(json).unwrap(),
+            message
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disabled_config_leaves_node_alone() {
+        let config = ClusterConfig::default();
+        let (broadcaster, _) = join_configured(&config, Broadcaster::new(16), ApprovalStore::new())
+            .await
+            .unwrap();
+        let mut events = broadcaster.subscribe();
+        broadcaster.publish(GatewayEvent::Agent {
+            agent_id: "agent-1".to_string(),
+            event: "created".to_string(),
+        });
+        assert!(events.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_node_id_is_generated_when_not_configured() {
+        let mut config = ClusterConfig::default();
+        let generated = node_id(&config);
+        assert!(generated.starts_with("node-"));
+        assert_ne!(node_id(&config), generated);
+
+        config.node_id = "gateway-1".to_string();
+        assert_eq!(node_id(&config), "gateway-1");
+    }
+}
@@ -5,16 +5,29 @@
 pub mod audit;
 pub mod auth;
 pub mod broadcast;
+pub mod channels;
 pub mod client;
+pub mod cluster;
 pub mod middleware;
+pub mod openapi;
+pub mod rest;
 pub mod routes;
 pub mod rpc;
 pub mod server;
+pub mod session_store;
+pub mod sse;
 pub mod static_files;
+pub mod telemetry;
 pub mod tls;
 pub mod ws;
 
 pub use server::run;
 pub use server::run_with_config;
+pub use server::run_with_channels;
+pub use server::run_with_status;
+pub use server::run_with_memory;
+pub use server::run_with_updates;
+pub use server::run_with_stores;
 pub use server::build_app;
-pub use routes::{GatewayStatus, GatewayStatusState};
+pub use channels::GatewayChannels;
+pub use routes::{ChannelStatus, GatewayStatus, GatewayStatusState, PluginStatus};
@@ -13,11 +13,12 @@ use axum::{
 };
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tracing::{debug, warn};
 
 use crate::audit::{log_auth_failure, log_auth_success};
 use crate::auth::{build_password_map, build_token_map, validate_basic, validate_token, AuthInfo};
-use crate::auth::{hash_password, verify_password, TokenStore};
+use crate::auth::{verify_password, ApiTokenId, ApiTokenStore, OidcValidator, TokenStore};
 use aisopod_config::sensitive::Sensitive;
 
 /// Request extension key for AuthInfo
@@ -47,6 +48,10 @@ pub struct AuthConfigData {
     password_map: HashMap<String, HashMap<String, AuthInfo>>,
     /// Flag indicating if passwords are hashed
     passwords_hashed: bool,
+    /// OIDC token validator, shared so the cached signing keys survive clones
+    oidc: Option<Arc<OidcValidator>>,
+    /// API tokens minted at runtime, accepted in token mode
+    api_tokens: Option<Arc<ApiTokenStore>>,
 }
 
 impl AuthConfigData {
@@ -67,15 +72,29 @@ impl AuthConfigData {
             None
         };
 
+        let oidc = if config.gateway_mode == aisopod_config::types::AuthMode::Oidc {
+            Some(Arc::new(OidcValidator::new(config.oidc.clone())))
+        } else {
+            None
+        };
+
         Self {
             config,
             token_map,
             token_store,
             password_map,
             passwords_hashed,
+            oidc,
+            api_tokens: None,
         }
     }
 
+    /// Also accept the API tokens in `store` in token mode
+    pub fn with_api_tokens(mut self, store: Arc<ApiTokenStore>) -> Self {
+        self.api_tokens = Some(store);
+        self
+    }
+
     /// Get the auth mode
     pub fn mode(&self) -> &aisopod_config::types::AuthMode {
         &self.config.gateway_mode
@@ -104,8 +123,29 @@ impl AuthConfigData {
             }
         }
         
-        // Fallback to token map for direct lookup
-        self.token_map.get(token).cloned()
+        // Fallback to token map for direct lookup, then to the minted API tokens
+        self.token_map.get(token).cloned().or_else(|| {
+            self.api_tokens
+                .as_ref()
+                .and_then(|store| store.validate(token))
+        })
+    }
+
+    /// The ID of `token` if it is an active minted API token rather than
+    /// a configured one
+    pub fn api_token_id(&self, token: &str) -> Option<ApiTokenId> {
+        if self.token_map.contains_key(token) {
+            return None;
+        }
+        self.api_tokens.as_ref()?.active_id(token)
+    }
+
+    /// Validate an OIDC bearer token and return AuthInfo if valid
+    pub async fn validate_oidc(&self, token: &str) -> anyhow::Result<AuthInfo> {
+        match &self.oidc {
+            Some(validator) => validator.validate(token).await,
+            None => anyhow::bail!("OIDC authentication is not enabled"),
+        }
     }
 
     /// Validate basic auth credentials and return AuthInfo if valid
@@ -140,16 +180,42 @@ impl AuthConfigData {
     }
 }
 
+/// WebSocket subprotocol prefix carrying a bearer token
+///
+/// Browsers cannot set headers on a WebSocket handshake, so the admin UI
+/// offers `aisopod.bearer.<token>` as a subprotocol instead.
+pub const BEARER_SUBPROTOCOL_PREFIX: &str = "aisopod.bearer.";
+
 /// Extract Authorization header value
-fn extract_authorization(header_map: &HeaderMap) -> Option<String> {
+///
+/// Falls back to a bearer token offered as a WebSocket subprotocol.
+pub(crate) fn extract_authorization(header_map: &HeaderMap) -> Option<String> {
     header_map
         .get(axum::http::header::AUTHORIZATION)
         .and_then(|h| h.to_str().ok())
         .map(|s| s.to_string())
+        .or_else(|| {
+            header_map
+                .get_all(axum::http::header::SEC_WEBSOCKET_PROTOCOL)
+                .iter()
+                .filter_map(|h| h.to_str().ok())
+                .flat_map(|h| h.split(','))
+                .find_map(|p| p.trim().strip_prefix(BEARER_SUBPROTOCOL_PREFIX))
+                .map(|token| format!("Bearer {}", token))
+        })
+}
+
+/// Check if the request fetches an asset of the embedded admin UI
+///
+/// The assets hold no data; the UI authenticates its own API calls.
+fn is_admin_asset_request(request: &axum::extract::Request) -> bool {
+    let path = request.uri().path();
+    request.method() == axum::http::Method::GET
+        && (path == "/admin" || path.starts_with("/admin/"))
 }
 
 /// Parse Bearer token from Authorization header
-fn parse_bearer_token(auth_value: &str) -> Option<String> {
+pub(crate) fn parse_bearer_token(auth_value: &str) -> Option<String> {
     let parts: Vec<&str> = auth_value.splitn(2, ' ').collect();
     if parts.len() == 2 && parts[0].eq_ignore_ascii_case("bearer") {
         Some(parts[1].to_string())
@@ -204,7 +270,7 @@ fn unauthorized_rpc_response(message: &str) -> Response {
 }
 
 /// Check if the request is for an RPC endpoint
-fn is_rpc_request(request: &axum::extract::Request) -> bool {
+pub(crate) fn is_rpc_request(request: &axum::extract::Request) -> bool {
     request.uri().path() == "/rpc"
 }
 
@@ -212,11 +278,13 @@ fn is_rpc_request(request: &axum::extract::Request) -> bool {
 ///
 /// This middleware validates incoming requests based on the configured auth mode:
 /// - **token**: Validates `Authorization: Bearer <token>`
+/// - **oidc**: Validates `Authorization: Bearer <jwt>` against the configured OpenID provider
 /// - **password**: Validates `Authorization: Basic <base64(username:password)>`
 /// - **none**: Allows all requests through without validation
 ///
 /// On successful authentication, the AuthInfo is stored in request extensions.
-/// The /health endpoint is always accessible without authentication.
+/// The /health endpoint and the admin UI assets are always accessible
+/// without authentication.
 pub async fn auth_middleware(
     request: axum::extract::Request,
     next: axum::middleware::Next,
@@ -231,6 +299,9 @@ pub async fn auth_middleware(
         eprintln!("Auth: /health endpoint, allowing through");
         return next.run(request).await;
     }
+    if is_admin_asset_request(&request) {
+        return next.run(request).await;
+    }
 
     let config_data = request.extensions().get::<AuthConfigData>().cloned();
 
@@ -297,6 +368,9 @@ pub async fn auth_middleware(
                     let client_ip = get_client_ip(&request);
                     log_auth_success(&client_ip, "token", &auth_info.role);
                     eprintln!("Token validation successful for role: {}", auth_info.role);
+                    if let Some(token_id) = config_data.api_token_id(&token) {
+                        request.extensions_mut().insert(token_id);
+                    }
                     request.extensions_mut().insert(auth_info);
                     next.run(request).await
                 }
@@ -313,6 +387,44 @@ pub async fn auth_middleware(
             }
         }
 
+        aisopod_config::types::AuthMode::Oidc => {
+            // Extract the Bearer token and validate it against the provider's keys
+            let header_map = request.headers();
+            let token = match extract_authorization(header_map)
+                .as_deref()
+                .and_then(parse_bearer_token)
+            {
+                Some(t) => t,
+                None => {
+                    let client_ip = get_client_ip(&request);
+                    log_auth_failure(&client_ip, "oidc", "missing or malformed bearer token");
+                    return if is_rpc_request(&request) {
+                        unauthorized_rpc_response("Missing bearer token")
+                    } else {
+                        unauthorized_response("Missing bearer token")
+                    };
+                }
+            };
+
+            match config_data.validate_oidc(&token).await {
+                Ok(auth_info) => {
+                    let client_ip = get_client_ip(&request);
+                    log_auth_success(&client_ip, "oidc", &auth_info.role);
+                    request.extensions_mut().insert(auth_info);
+                    next.run(request).await
+                }
+                Err(e) => {
+                    let client_ip = get_client_ip(&request);
+                    log_auth_failure(&client_ip, "oidc", &format!("{:#}", e));
+                    if is_rpc_request(&request) {
+                        unauthorized_rpc_response("Invalid token")
+                    } else {
+                        unauthorized_response("Invalid token")
+                    }
+                }
+            }
+        }
+
         aisopod_config::types::AuthMode::Password => {
             // Extract and validate Basic auth credentials
             let header_map = request.headers();
@@ -405,6 +517,7 @@ mod tests {
 
         Router::new()
             .route("/test", get(echo_auth_info))
+            .route("/admin/*path", get(echo_auth_info).post(echo_auth_info))
             .layer(axum::middleware::from_fn(auth_middleware))
             .layer(axum::middleware::from_fn(
                 move |mut req: AxumRequest<Body>, next: axum::middleware::Next| {
@@ -500,6 +613,63 @@ mod tests {
         assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
     }
 
+    #[tokio::test]
+    async fn test_auth_middleware_token_subprotocol() {
+        let config = AuthConfig {
+            gateway_mode: aisopod_config::types::AuthMode::Token,
+            tokens: vec![aisopod_config::types::TokenCredential {
+                token: "test-token".to_string(),
+                role: "operator".to_string(),
+                scopes: vec!["chat:write".to_string()],
+            }],
+            ..Default::default()
+        };
+
+        let router = create_test_router_with_middleware(config);
+
+        let request = AxumRequest::builder()
+            .uri("/test")
+            .header(
+                axum::http::header::SEC_WEBSOCKET_PROTOCOL,
+                "aisopod, aisopod.bearer.test-token",
+            )
+            .body(Body::empty())
+            .expect("test should pass");
+
+        let response = router.oneshot(request).await.expect("test should pass");
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_admin_assets_allowed_without_credentials() {
+        let config = AuthConfig {
+            gateway_mode: aisopod_config::types::AuthMode::Token,
+            tokens: vec![aisopod_config::types::TokenCredential {
+                token: "test-token".to_string(),
+                role: "operator".to_string(),
+                scopes: vec!["chat:write".to_string()],
+            }],
+            ..Default::default()
+        };
+
+        let router = create_test_router_with_middleware(config);
+
+        let request = AxumRequest::builder()
+            .uri("/admin/admin.js")
+            .body(Body::empty())
+            .expect("test should pass");
+        let response = router.clone().oneshot(request).await.expect("test should pass");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let request = AxumRequest::builder()
+            .method("POST")
+            .uri("/admin/admin.js")
+            .body(Body::empty())
+            .expect("test should pass");
+        let response = router.oneshot(request).await.expect("test should pass");
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
     #[tokio::test]
     async fn test_auth_middleware_password_success() {
         let config = AuthConfig {
@@ -583,6 +753,59 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
+    #[test]
+    fn test_validate_token_accepts_minted_api_tokens() {
+        let config = AuthConfig {
+            gateway_mode: aisopod_config::types::AuthMode::Token,
+            tokens: vec![aisopod_config::types::TokenCredential {
+                token: "test-token".to_string(),
+                role: "operator".to_string(),
+                scopes: vec!["operator.admin".to_string()],
+            }],
+            ..Default::default()
+        };
+        let store = Arc::new(ApiTokenStore::in_memory());
+        let (_, token) = store
+            .mint(crate::auth::TokenGrant {
+                name: "dashboard".to_string(),
+                scopes: crate::auth::TokenPreset::ReadOnly.scopes(),
+                ..Default::default()
+            })
+            .expect("test should pass");
+
+        let config_data = AuthConfigData::new(config.clone());
+        assert!(config_data.validate_token(&token).is_none());
+
+        let config_data = AuthConfigData::new(config).with_api_tokens(store);
+        assert!(config_data.validate_token("test-token").is_some());
+        let auth_info = config_data.validate_token(&token).expect("test should pass");
+        assert_eq!(auth_info.scopes, ["operator.read"]);
+    }
+
+    #[tokio::test]
+    async fn test_auth_middleware_oidc_rejects_bad_tokens() {
+        let config = AuthConfig {
+            gateway_mode: aisopod_config::types::AuthMode::Oidc,
+            oidc: aisopod_config::types::OidcConfig {
+                issuer: "https://id.example.com".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        for header in [None, Some("Basic abc"), Some("Bearer not-a-jwt")] {
+            let router = create_test_router_with_middleware(config.clone());
+            let mut request = AxumRequest::builder().uri("/test");
+            if let Some(header) = header {
+                request = request.header(axum::http::header::AUTHORIZATION, header);
+            }
+            let request = request.body(Body::empty()).expect("test should pass");
+
+            let response = router.oneshot(request).await.expect("test should pass");
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+
     #[tokio::test]
     async fn test_health_endpoint_always_allowed() {
         let config = AuthConfig {
@@ -9,8 +9,12 @@ pub use auth::AuthConfigData;
 pub use auth::ExtractAuthInfo;
 pub use auth::AUTH_INFO_KEY;
 pub use rate_limit::rate_limit_middleware;
+pub use rate_limit::ClientRateLimit;
+pub use rate_limit::RateLimitBypass;
 pub use rate_limit::RateLimitConfig;
+pub use rate_limit::RateLimitKey;
 pub use rate_limit::RateLimiter;
+pub use security::request_limits_middleware;
 pub use security::sanitize_input;
 pub use security::SecretString;
 pub use security::validate_no_injection;
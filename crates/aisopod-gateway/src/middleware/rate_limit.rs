@@ -1,8 +1,11 @@
 #![allow(clippy::all)]
 //! Rate limiting middleware for the gateway
 //!
-//! This module provides per-IP sliding-window rate limiting to prevent
-//! abusive or misconfigured clients from overwhelming the server.
+//! This module provides per-IP and, optionally, per-token rate limiting to
+//! prevent abusive or misconfigured clients from overwhelming the server.
+//! Each client has a token bucket holding up to `burst` requests, which
+//! refills at `max_requests` per `window`, so clients may make short bursts
+//! of requests while being held to a steady rate.
 
 use axum::{
     body::Body,
@@ -13,46 +16,96 @@ use axum::{
     Json,
 };
 use dashmap::DashMap;
+use sha2::{Digest, Sha256};
 use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::{debug, warn};
 
+use super::auth::{extract_authorization, is_rpc_request, parse_bearer_token};
+use crate::rpc::types::RpcResponse;
+
 /// Rate limiter configuration
 #[derive(Debug, Clone)]
 pub struct RateLimitConfig {
     /// Maximum number of requests allowed in the window
     pub max_requests: u64,
-    /// Sliding window duration
+    /// Window in which clients regain `max_requests` requests
     pub window: Duration,
+    /// Maximum number of requests a client may make at once
+    pub burst: u64,
 }
 
 impl RateLimitConfig {
-    /// Create a new rate limit configuration
+    /// Create a new rate limit configuration allowing bursts of
+    /// `max_requests`
     pub fn new(max_requests: u64, window: Duration) -> Self {
         Self {
             max_requests,
             window,
+            burst: max_requests,
         }
     }
 
+    /// Allow bursts of `burst` requests
+    pub fn with_burst(mut self, burst: u64) -> Self {
+        self.burst = burst;
+        self
+    }
+
     /// Default configuration: 100 requests per minute
     pub fn default() -> Self {
-        Self {
-            max_requests: 100,
-            window: Duration::from_secs(60),
-        }
+        Self::new(100, Duration::from_secs(60))
+    }
+
+    /// Requests regained per second
+    fn rate(&self) -> f64 {
+        self.max_requests as f64 / self.window.as_secs_f64()
+    }
+}
+
+/// What a rate limit is applied to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RateLimitKey {
+    /// A client IP address
+    Ip(IpAddr),
+    /// A bearer token, identified by its SHA-256 digest so that the limiter
+    /// does not keep tokens in memory
+    Token([u8; 32]),
+}
+
+impl RateLimitKey {
+    /// The key of bearer token `token`
+    pub fn token(token: &str) -> Self {
+        Self::Token(Sha256::digest(token.as_bytes()).into())
+    }
+}
+
+/// Requests a client may currently make
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    /// Add the requests regained since the last update
+    fn refill(&mut self, config: &RateLimitConfig, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * config.rate()).min(config.burst as f64);
+        self.updated = now;
     }
 }
 
-/// Rate limiter that tracks requests per IP address using a sliding window
+/// Rate limiter that tracks requests per IP address and per bearer token
+/// using token buckets
 pub struct RateLimiter {
-    /// Map from IP address to list of request timestamps
-    state: DashMap<IpAddr, Vec<Instant>>,
-    /// Maximum requests allowed in the window
-    max_requests: u64,
-    /// Sliding window duration
-    window: Duration,
+    /// Map from IP address or token to its bucket
+    state: DashMap<RateLimitKey, Bucket>,
+    /// Limits per IP address
+    config: RateLimitConfig,
+    /// Limits per bearer token, if tokens are limited
+    token_config: Option<RateLimitConfig>,
 }
 
 impl RateLimiter {
@@ -60,8 +113,27 @@ impl RateLimiter {
     pub fn new(config: RateLimitConfig) -> Self {
         Self {
             state: DashMap::new(),
-            max_requests: config.max_requests,
-            window: config.window,
+            config,
+            token_config: None,
+        }
+    }
+
+    /// Also limit the requests made with each bearer token
+    pub fn with_token_limits(mut self, config: RateLimitConfig) -> Self {
+        self.token_config = Some(config);
+        self
+    }
+
+    /// Whether requests are limited per bearer token
+    pub fn limits_tokens(&self) -> bool {
+        self.token_config.is_some()
+    }
+
+    /// The limits applying to `key`, if any
+    fn config_for(&self, key: &RateLimitKey) -> Option<&RateLimitConfig> {
+        match key {
+            RateLimitKey::Ip(_) => Some(&self.config),
+            RateLimitKey::Token(_) => self.token_config.as_ref(),
         }
     }
 
@@ -69,78 +141,59 @@ impl RateLimiter {
     ///
     /// Returns `Ok(())` if the request is allowed.
     /// Returns `Err(retry_after)` if the rate limit is exceeded, where `retry_after`
-    /// is the duration until the oldest request expires and a new request can be made.
+    /// is the duration until a new request can be made.
     pub fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        self.check_key(RateLimitKey::Ip(ip))
+    }
+
+    /// Check if a request counted against `key` is allowed, like
+    /// [`check`](Self::check)
+    pub fn check_key(&self, key: RateLimitKey) -> Result<(), Duration> {
+        let Some(config) = self.config_for(&key) else {
+            return Ok(());
+        };
         let now = Instant::now();
-        let window_start = now - self.window;
-
-        match self.state.entry(ip) {
-            dashmap::mapref::entry::Entry::Vacant(entry) => {
-                // New IP, allow the request
-                eprintln!("Rate limiter: New IP entry created, timestamps.len() = 1");
-                let timestamps = vec![now];
-                entry.insert(timestamps);
-                Ok(())
-            }
-            dashmap::mapref::entry::Entry::Occupied(mut entry) => {
-                let timestamps = entry.get_mut();
-                eprintln!(
-                    "Rate limiter: Existing IP entry, timestamps.len() before cleanup = {}",
-                    timestamps.len()
-                );
-
-                // Remove expired timestamps (older than window_start)
-                let expired_count = timestamps.iter().take_while(|&&t| t < window_start).count();
-
-                if expired_count > 0 {
-                    timestamps.drain(0..expired_count);
-                }
-
-                // Check if we've exceeded the limit
-                eprintln!("Rate limiter check: timestamps.len() = {}, max_requests = {}, limit check: {} >= {} = {}", timestamps.len(), self.max_requests, timestamps.len(), self.max_requests, timestamps.len() >= self.max_requests as usize);
-                if timestamps.len() >= self.max_requests as usize {
-                    // Calculate how long until the oldest request expires
-                    let oldest = timestamps[0];
-                    let retry_after = (oldest + self.window) - now;
-                    Err(retry_after.max(Duration::from_secs(1)))
-                } else {
-                    // Add current timestamp and allow
-                    timestamps.push(now);
-                    Ok(())
-                }
-            }
+        let mut bucket = self.state.entry(key).or_insert_with(|| Bucket {
+            tokens: config.burst as f64,
+            updated: now,
+        });
+        bucket.refill(config, now);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
         }
+        let rate = config.rate();
+        let wait = if rate > 0.0 {
+            (1.0 - bucket.tokens) / rate
+        } else {
+            config.window.as_secs_f64()
+        };
+        // Retry-After is given in whole seconds
+        Err(Duration::from_secs(wait.ceil().max(1.0) as u64))
     }
 
-    /// Cleanup loop that periodically evicts expired entries
+    /// Cleanup loop that periodically evicts the buckets of clients that
+    /// have regained all their requests
     ///
     /// This should be run as a background task to prevent unbounded memory growth.
     pub async fn cleanup_loop(&self) {
-        let window = self.window;
-        let state = self.state.clone();
-
-        tokio::spawn(async move {
-            loop {
-                tokio::time::sleep(window).await;
-
-                // Clean up empty entries
-                state.retain(|_, timestamps| {
-                    let now = Instant::now();
-                    let window_start = now - window;
-
-                    // Remove expired timestamps
-                    let expired_count =
-                        timestamps.iter().take_while(|&&t| t < window_start).count();
-
-                    if expired_count > 0 {
-                        timestamps.drain(0..expired_count);
-                    }
-
-                    !timestamps.is_empty()
-                });
+        loop {
+            tokio::time::sleep(self.config.window).await;
+            self.evict_full_buckets(Instant::now());
+            debug!("Rate limiter cleanup completed");
+        }
+    }
 
-                debug!("Rate limiter cleanup completed");
-            }
+    /// Evict the buckets that are full at `now`, which behave the same as
+    /// fresh ones
+    fn evict_full_buckets(&self, now: Instant) {
+        self.state.retain(|key, bucket| {
+            let Some(config) = self.config_for(key) else {
+                return false;
+            };
+            bucket.refill(config, now);
+            bucket.tokens < config.burst as f64
         });
     }
 
@@ -155,79 +208,118 @@ impl RateLimiter {
 /// Request extension key for RateLimiter
 pub const RATE_LIMITER_KEY: &str = "aisopod.rate_limiter";
 
-/// Header to bypass rate limiting (for test setup)
-pub const RATE_LIMIT_BYPASS_HEADER: &str = "X-Aisopod-Bypass-Rate-Limit";
+/// Request extension exempting a request from rate limiting
+///
+/// Extensions are attached by the server, not the client, so tests add it
+/// to the requests of their setup without opening a bypass to clients.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitBypass;
 
-/// Axum middleware for rate limiting
+/// The rate limits of the client making a request
 ///
-/// This middleware checks the client's IP address against the rate limiter.
-/// If the limit is exceeded, it returns HTTP 429 Too Many Requests with a
-/// `Retry-After` header.
-pub async fn rate_limit_middleware(
-    request: Request,
-    next: axum::middleware::Next,
-) -> Response<Body> {
-    eprintln!(">>> RATE LIMIT MIDDLEWARE CALLED <<<");
+/// WebSocket connections keep it to also limit the RPC calls made over the
+/// connection.
+#[derive(Clone)]
+pub struct ClientRateLimit {
+    limiter: Arc<RateLimiter>,
+    ip: IpAddr,
+    token: Option<RateLimitKey>,
+}
 
-    // Check for bypass header - allows tests to skip rate limiting during setup
-    let bypass = request.headers().get(RATE_LIMIT_BYPASS_HEADER).is_some();
+impl ClientRateLimit {
+    /// The limits of the client making `request`, unless no rate limiter is
+    /// configured or the request bypasses rate limiting
+    pub fn of(request: &Request) -> Option<Self> {
+        // Allows tests to skip rate limiting during setup
+        if request.extensions().get::<RateLimitBypass>().is_some() {
+            return None;
+        }
+        let limiter = request.extensions().get::<Arc<RateLimiter>>().cloned()?;
+
+        // For tests where ConnectInfo isn't available, use 127.0.0.1 as a
+        // default (all test requests come from localhost anyway)
+        let ip = request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|addr| addr.ip())
+            .unwrap_or_else(|| "127.0.0.1".parse().expect("default IP address is valid"));
+        let token = if limiter.limits_tokens() {
+            extract_authorization(request.headers())
+                .and_then(|value| parse_bearer_token(&value))
+                .map(|token| RateLimitKey::token(&token))
+        } else {
+            None
+        };
+        Some(Self { limiter, ip, token })
+    }
 
-    if bypass {
-        eprintln!("Rate limiting bypassed via header");
-        return next.run(request).await;
+    /// The client's IP address
+    pub fn ip(&self) -> IpAddr {
+        self.ip
     }
 
-    // Get the rate limiter from request extensions
-    let limiter = match request.extensions().get::<Arc<RateLimiter>>().cloned() {
-        Some(l) => l,
-        None => {
-            // No rate limiter configured, allow all requests
-            eprintln!("No rate limiter in extensions, allowing all requests");
-            return next.run(request).await;
+    /// Check if the client may make another request, counting it against
+    /// both its IP address and its token
+    pub fn check(&self) -> Result<(), Duration> {
+        self.limiter.check(self.ip)?;
+        match &self.token {
+            Some(token) => self.limiter.check_key(token.clone()),
+            None => Ok(()),
         }
-    };
+    }
+}
 
-    eprintln!("Got rate limiter from extensions");
+/// Build the response rejecting a rate limited request, in JSON-RPC format
+/// for RPC requests
+fn rate_limited_response(rpc: bool, retry_after: Duration) -> Response<Body> {
+    let mut response = if rpc {
+        Json(RpcResponse::rate_limited(None, retry_after)).into_response()
+    } else {
+        Json(serde_json::json!({
+            "error": "rate_limit_exceeded",
+            "message": "Too many requests",
+            "retry_after": retry_after.as_secs()
+        }))
+        .into_response()
+    };
+    *response.status_mut() = StatusCode::TOO_MANY_REQUESTS;
+    response.headers_mut().insert(
+        header::RETRY_AFTER,
+        retry_after
+            .as_secs()
+            .to_string()
+            .parse()
+            .expect("duration seconds should be parseable"),
+    );
+    response
+}
 
-    // Get IP from ConnectInfo - for tests where ConnectInfo isn't available,
-    // use 127.0.0.1 as a default (all test requests come from localhost anyway)
-    let ip = request
-        .extensions()
-        .get::<ConnectInfo<SocketAddr>>()
-        .map(|addr| addr.ip())
-        .unwrap_or_else(|| "127.0.0.1".parse().expect("default IP address is valid"));
+/// Axum middleware for rate limiting
+///
+/// This middleware checks the client's IP address, and its bearer token if
+/// tokens are limited, against the rate limiter. If a limit is exceeded, it
+/// returns HTTP 429 Too Many Requests with a `Retry-After` header; requests
+/// to `/rpc` get a JSON-RPC error whose data holds `retry_after`.
+pub async fn rate_limit_middleware(
+    request: Request,
+    next: axum::middleware::Next,
+) -> Response<Body> {
+    let Some(client) = ClientRateLimit::of(&request) else {
+        return next.run(request).await;
+    };
 
-    eprintln!("Rate limiting IP: {}", ip);
-    match limiter.check(ip) {
+    match client.check() {
         Ok(()) => {
             // Request allowed, continue to next middleware/handler
             next.run(request).await
         }
         Err(retry_after) => {
-            // Rate limit exceeded, return 429
             warn!(
                 "Rate limit exceeded for IP {}: retry after {} seconds",
-                ip,
+                client.ip(),
                 retry_after.as_secs()
             );
-
-            let json = Json(serde_json::json!({
-                "error": "rate_limit_exceeded",
-                "message": "Too many requests",
-                "retry_after": retry_after.as_secs()
-            }));
-
-            let mut response = json.into_response();
-            *response.status_mut() = StatusCode::TOO_MANY_REQUESTS;
-            response.headers_mut().insert(
-                header::RETRY_AFTER,
-                retry_after
-                    .as_secs()
-                    .to_string()
-                    .parse()
-                    .expect("duration seconds should be parseable"),
-            );
-            response
+            rate_limited_response(is_rpc_request(&request), retry_after)
         }
     }
 }
@@ -342,4 +434,157 @@ mod tests {
         let result = limiter.check(ip2);
         assert!(result.is_ok(), "IP2 should be allowed");
     }
+
+    #[test]
+    fn test_burst_then_steady_rate() {
+        // 60 requests per minute regain one request per second
+        let config = RateLimitConfig::new(60, Duration::from_secs(60)).with_burst(3);
+        let limiter = RateLimiter::new(config);
+        let ip = "192.168.1.5".parse().unwrap();
+
+        for _ in 0..3 {
+            limiter.check(ip).expect("burst should be allowed");
+        }
+        assert_eq!(limiter.check(ip), Err(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_bucket_refills() {
+        let config = RateLimitConfig::new(1, Duration::from_millis(50));
+        let limiter = RateLimiter::new(config);
+        let ip = "192.168.1.6".parse().unwrap();
+
+        limiter.check(ip).expect("request should be allowed");
+        assert!(limiter.check(ip).is_err());
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(limiter.check(ip).is_ok());
+    }
+
+    #[test]
+    fn test_token_limits() {
+        let limiter = RateLimiter::new(RateLimitConfig::new(10, Duration::from_secs(60)));
+        assert!(!limiter.limits_tokens());
+        for _ in 0..20 {
+            assert!(limiter.check_key(RateLimitKey::token("a")).is_ok());
+        }
+
+        let limiter = limiter.with_token_limits(RateLimitConfig::new(1, Duration::from_secs(60)));
+        assert!(limiter.check_key(RateLimitKey::token("a")).is_ok());
+        assert!(limiter.check_key(RateLimitKey::token("a")).is_err());
+        assert!(limiter.check_key(RateLimitKey::token("b")).is_ok());
+        assert!(limiter.check("192.168.1.7".parse().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_evict_full_buckets() {
+        let config = RateLimitConfig::new(2, Duration::from_secs(10));
+        let limiter = RateLimiter::new(config);
+        let now = Instant::now();
+        limiter.check("192.168.1.8".parse().unwrap()).unwrap();
+
+        limiter.evict_full_buckets(now);
+        assert_eq!(limiter.state.len(), 1);
+        limiter.evict_full_buckets(now + Duration::from_secs(10));
+        assert!(limiter.state.is_empty());
+    }
+
+    fn limited_router(limiter: RateLimiter) -> axum::Router {
+        let limiter = Arc::new(limiter);
+        axum::Router::new()
+            .route("/health", axum::routing::get(|| async { "ok" }))
+            .route("/rpc", axum::routing::post(|| async { "ok" }))
+            .layer(axum::middleware::from_fn(rate_limit_middleware))
+            .layer(axum::Extension(limiter))
+    }
+
+    async fn send(router: &axum::Router, method: &str, uri: &str, token: &str) -> Response<Body> {
+        use tower::ServiceExt;
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        router.clone().oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_middleware_rejects_rpc_with_retry_after() {
+        let router = limited_router(RateLimiter::new(RateLimitConfig::new(
+            1,
+            Duration::from_secs(30),
+        )));
+        assert_eq!(
+            send(&router, "POST", "/rpc", "t").await.status(),
+            StatusCode::OK
+        );
+
+        let response = send(&router, "POST", "/rpc", "t").await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "30");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["jsonrpc"], "2.0");
+        assert_eq!(json["error"]["code"], -32007);
+        assert_eq!(json["error"]["data"]["retry_after"], 30);
+
+        let response = send(&router, "GET", "/health", "t").await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"], "rate_limit_exceeded");
+    }
+
+    #[tokio::test]
+    async fn test_only_the_bypass_extension_skips_limits() {
+        use tower::ServiceExt;
+        let router = limited_router(RateLimiter::new(RateLimitConfig::new(
+            1,
+            Duration::from_secs(30),
+        )));
+        let request = |bypass: bool| {
+            let mut request = Request::builder()
+                .uri("/health")
+                .header("X-Aisopod-Bypass-Rate-Limit", "1")
+                .body(Body::empty())
+                .unwrap();
+            if bypass {
+                request.extensions_mut().insert(RateLimitBypass);
+            }
+            request
+        };
+
+        // A client header does not skip the limits
+        for expected in [StatusCode::OK, StatusCode::TOO_MANY_REQUESTS] {
+            let response = router.clone().oneshot(request(false)).await.unwrap();
+            assert_eq!(response.status(), expected);
+        }
+        let response = router.clone().oneshot(request(true)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_middleware_limits_tokens() {
+        let router = limited_router(
+            RateLimiter::new(RateLimitConfig::new(100, Duration::from_secs(60)))
+                .with_token_limits(RateLimitConfig::new(2, Duration::from_secs(60))),
+        );
+        for _ in 0..2 {
+            assert_eq!(
+                send(&router, "GET", "/health", "a").await.status(),
+                StatusCode::OK
+            );
+        }
+        assert_eq!(
+            send(&router, "GET", "/health", "a").await.status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        assert_eq!(
+            send(&router, "GET", "/health", "b").await.status(),
+            StatusCode::OK
+        );
+    }
 }
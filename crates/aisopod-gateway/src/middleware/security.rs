@@ -4,10 +4,21 @@
 //! - SecretString type for masking sensitive values in logs
 //! - Input sanitization functions to prevent injection attacks
 //! - Request size validation
+//! - Slow client protection, by bounding the time taken to send a body
 
 use std::ops::Deref;
 
 use std::fmt;
+use std::time::Duration;
+
+use axum::body::{Body, HttpBody};
+use axum::extract::Request;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use tracing::warn;
+
+use super::auth::is_rpc_request;
+use crate::rpc::types::{error_codes, RpcResponse};
 
 /// A wrapper type for sensitive string values that masks their output in logs
 ///
@@ -131,6 +142,8 @@ pub struct RequestSizeLimits {
     pub max_headers_size: usize,
     /// Maximum number of headers
     pub max_headers_count: usize,
+    /// Maximum time a client may take to send the request body, if limited
+    pub body_timeout: Option<Duration>,
 }
 
 impl Default for RequestSizeLimits {
@@ -139,6 +152,7 @@ impl Default for RequestSizeLimits {
             max_body_size: 10 * 1024 * 1024,      // 10MB default
             max_headers_size: 8192,                // 8KB default
             max_headers_count: 100,                // 100 headers default
+            body_timeout: Some(Duration::from_secs(30)),
         }
     }
 }
@@ -150,8 +164,16 @@ impl RequestSizeLimits {
             max_body_size,
             max_headers_size,
             max_headers_count,
+            body_timeout: Some(Duration::from_secs(30)),
         }
     }
+
+    /// Limit the time a client may take to send the request body, or not
+    /// at all if `body_timeout` is `None`
+    pub fn with_body_timeout(mut self, body_timeout: Option<Duration>) -> Self {
+        self.body_timeout = body_timeout;
+        self
+    }
     
     /// Check if a request body size is within limits
     pub fn check_body_size(&self, size: usize) -> Result<(), String> {
@@ -190,6 +212,113 @@ impl RequestSizeLimits {
     }
 }
 
+/// Build the response rejecting a request that exceeds the limits, in
+/// JSON-RPC format for RPC requests
+fn limit_exceeded_response(
+    rpc: bool,
+    status: StatusCode,
+    error: &str,
+    code: i32,
+    message: String,
+) -> Response {
+    warn!("Request rejected: {}", message);
+    let body = if rpc {
+        serde_json::json!(RpcResponse::error(None, code, message))
+    } else {
+        serde_json::json!({ "error": error, "message": message })
+    };
+    (status, axum::Json(body)).into_response()
+}
+
+/// Axum middleware enforcing the [`RequestSizeLimits`] in the request
+/// extensions
+///
+/// Requests with too many or too large headers are rejected with 431 Request
+/// Header Fields Too Large. The body is read before the request is handled,
+/// so that bodies over the limit are rejected with 413 Payload Too Large
+/// whatever length they declare, and clients sending their body too slowly
+/// get 408 Request Timeout instead of tying up the connection.
+pub async fn request_limits_middleware(
+    request: Request,
+    next: axum::middleware::Next,
+) -> Response {
+    let Some(limits) = request.extensions().get::<RequestSizeLimits>().cloned() else {
+        return next.run(request).await;
+    };
+    let rpc = is_rpc_request(&request);
+
+    let headers = request.headers();
+    let headers_size = headers
+        .iter()
+        .map(|(name, value)| name.as_str().len() + value.len())
+        .sum();
+    if let Err(message) = limits
+        .check_headers_count(headers.len())
+        .and_then(|()| limits.check_headers_size(headers_size))
+    {
+        return limit_exceeded_response(
+            rpc,
+            StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+            "headers_too_large",
+            error_codes::PAYLOAD_TOO_LARGE,
+            message,
+        );
+    }
+
+    // Declared lengths are checked before reading anything
+    let content_length = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if let Some(Err(message)) = content_length.map(|length| limits.check_body_size(length)) {
+        return limit_exceeded_response(
+            rpc,
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "payload_too_large",
+            error_codes::PAYLOAD_TOO_LARGE,
+            message,
+        );
+    }
+    if request.body().size_hint().exact() == Some(0) {
+        return next.run(request).await;
+    }
+
+    let (parts, body) = request.into_parts();
+    let read = axum::body::to_bytes(body, limits.max_body_size);
+    let read = match limits.body_timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, read).await {
+            Ok(read) => read,
+            Err(_) => {
+                return limit_exceeded_response(
+                    rpc,
+                    StatusCode::REQUEST_TIMEOUT,
+                    "request_timeout",
+                    error_codes::REQUEST_TIMEOUT,
+                    format!("Request body not received within {} seconds", timeout.as_secs()),
+                )
+            }
+        },
+        None => read.await,
+    };
+    let bytes = match read.map_err(axum::Error::into_inner) {
+        Ok(bytes) => bytes,
+        Err(e) if e.is::<http_body_util::LengthLimitError>() => {
+            return limit_exceeded_response(
+                rpc,
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "payload_too_large",
+                error_codes::PAYLOAD_TOO_LARGE,
+                format!(
+                    "Request body exceeds maximum of {} bytes",
+                    limits.max_body_size
+                ),
+            )
+        }
+        Err(_) => return StatusCode::BAD_REQUEST.into_response(),
+    };
+    next.run(Request::from_parts(parts, Body::from(bytes))).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -374,4 +503,92 @@ mod tests {
         assert!(limits.check_headers_count(5).is_ok());
         assert!(limits.check_headers_count(6).is_err());
     }
+
+    fn limited_router(limits: RequestSizeLimits) -> axum::Router {
+        axum::Router::new()
+            .route("/echo", axum::routing::post(|body: String| async move { body }))
+            .route("/rpc", axum::routing::post(|body: String| async move { body }))
+            .layer(axum::middleware::from_fn(request_limits_middleware))
+            .layer(axum::Extension(limits))
+    }
+
+    async fn send(router: &axum::Router, request: Request) -> (StatusCode, String) {
+        use tower::ServiceExt;
+        let response = router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    /// A body of `chunks` streamed without a declared length
+    fn streamed_body(chunks: Vec<&'static str>) -> Body {
+        Body::from_stream(futures_util::stream::iter(
+            chunks
+                .into_iter()
+                .map(Ok::<_, std::convert::Infallible>),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_request_limits_pass_body_through() {
+        let router = limited_router(RequestSizeLimits::new(16, 8192, 100));
+        let request = Request::post("/echo")
+            .body(streamed_body(vec!["hello ", "world"]))
+            .unwrap();
+        assert_eq!(
+            send(&router, request).await,
+            (StatusCode::OK, "hello world".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_request_limits_reject_large_bodies() {
+        let router = limited_router(RequestSizeLimits::new(8, 8192, 100));
+
+        let request = Request::post("/echo")
+            .header(header::CONTENT_LENGTH, "100")
+            .body(Body::from("x".repeat(100)))
+            .unwrap();
+        assert_eq!(send(&router, request).await.0, StatusCode::PAYLOAD_TOO_LARGE);
+
+        // Streamed bodies have no declared length to check up front
+        let request = Request::post("/rpc")
+            .body(streamed_body(vec!["12345", "67890"]))
+            .unwrap();
+        let (status, body) = send(&router, request).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["error"]["code"], error_codes::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_request_limits_reject_large_headers() {
+        let router = limited_router(RequestSizeLimits::new(1024, 8192, 2));
+        let mut request = Request::post("/echo");
+        for i in 0..3 {
+            request = request.header(format!("x-header-{}", i), "value");
+        }
+        let request = request.body(Body::empty()).unwrap();
+        assert_eq!(
+            send(&router, request).await.0,
+            StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_request_limits_time_out_slow_bodies() {
+        let router = limited_router(
+            RequestSizeLimits::new(1024, 8192, 100)
+                .with_body_timeout(Some(Duration::from_millis(50))),
+        );
+        let body = Body::from_stream(futures_util::stream::pending::<
+            Result<&'static str, std::convert::Infallible>,
+        >());
+        let request = Request::post("/echo").body(body).unwrap();
+        let (status, body) = send(&router, request).await;
+        assert_eq!(status, StatusCode::REQUEST_TIMEOUT);
+        assert!(body.contains("request_timeout"));
+    }
 }
@@ -0,0 +1,161 @@
+//! OpenAPI document of the REST API
+//!
+//! The document is derived from the annotations on the handlers in
+//! [`crate::rest`] and [`crate::sse`] and their request and response types, so it changes
+//! together with them. It is served at `/openapi.json`; with the
+//! `swagger-ui` feature, Swagger UI is served at `/docs` as well.
+
+use axum::Router;
+use utoipa::OpenApi;
+
+use crate::rest;
+use crate::routes::ChannelStatus;
+use crate::sse;
+
+/// Path the OpenAPI document is served at
+pub const OPENAPI_PATH: &str = "/openapi.json";
+
+/// The OpenAPI document of the REST API
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "aisopod gateway",
+        description = "HTTP endpoints for integrations that cannot hold a WebSocket. \
+            Each endpoint requires the scope of the RPC method it maps onto."
+    ),
+    paths(
+        rest::send_message,
+        rest::trigger_run,
+        rest::list_sessions,
+        rest::session_transcript,
+        rest::channel_status,
+        sse::chat_stream,
+    ),
+    components(schemas(
+        rest::ErrorBody,
+        rest::SendMessageRequest,
+        sse::ChatStreamRequest,
+        rest::MessageResponse,
+        rest::TriggerRunRequest,
+        rest::RunAccepted,
+        rest::SessionList,
+        rest::SessionTranscript,
+        rest::ChannelList,
+        ChannelStatus,
+    )),
+    tags(
+        (name = "messages", description = "Send messages to agents"),
+        (name = "agents", description = "Trigger agent runs"),
+        (name = "sessions", description = "List and export sessions"),
+        (name = "channels", description = "Channel status"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Build the routes serving the OpenAPI document
+#[cfg(not(feature = "swagger-ui"))]
+pub fn openapi_routes() -> Router {
+    use axum::routing::get;
+    use axum::Json;
+
+    Router::new().route(OPENAPI_PATH, get(|| async { Json(ApiDoc::openapi()) }))
+}
+
+/// Build the routes serving the OpenAPI document and Swagger UI
+#[cfg(feature = "swagger-ui")]
+pub fn openapi_routes() -> Router {
+    utoipa_swagger_ui::SwaggerUi::new("/docs")
+        .url(OPENAPI_PATH, ApiDoc::openapi())
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::GatewayStatusState;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    /// Every documented operation is routed, so the document cannot list
+    /// endpoints that were removed or renamed
+    #[tokio::test]
+    async fn test_documented_paths_are_routed() {
+        let doc = ApiDoc::openapi();
+        assert_eq!(doc.paths.paths.len(), 6);
+
+        for (path, item) in &doc.paths.paths {
+            let uri = path.replace("{agent_id}", "support");
+            let method = if item.post.is_some() {
+                Method::POST
+            } else {
+                Method::GET
+            };
+            let app =
+                rest::rest_routes(Arc::new(GatewayStatusState::default())).merge(sse::sse_routes());
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .method(method)
+                        .uri(&uri)
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_ne!(response.status(), StatusCode::NOT_FOUND, "{}", path);
+            assert_ne!(
+                response.status(),
+                StatusCode::METHOD_NOT_ALLOWED,
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serves_document() {
+        let response = openapi_routes()
+            .oneshot(Request::get(OPENAPI_PATH).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let doc: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        let send = &doc["paths"]["/api/v1/messages"]["post"];
+        assert_eq!(
+            send["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/SendMessageRequest"
+        );
+        let params = doc["paths"]["/api/v1/sessions/transcript"]["get"]["parameters"]
+            .as_array()
+            .unwrap();
+        let names: Vec<_> = params.iter().map(|p| p["name"].as_str().unwrap()).collect();
+        assert_eq!(
+            names,
+            [
+                "agent_id",
+                "channel",
+                "account_id",
+                "peer_kind",
+                "peer_id",
+                "format"
+            ]
+        );
+        assert!(doc["components"]["schemas"]["ChannelStatus"].is_object());
+    }
+
+    #[cfg(feature = "swagger-ui")]
+    #[tokio::test]
+    async fn test_serves_swagger_ui() {
+        let response = openapi_routes()
+            .oneshot(Request::get("/docs/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
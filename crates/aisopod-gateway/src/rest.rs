@@ -0,0 +1,600 @@
+//! REST API module
+//!
+//! Integrations that cannot hold a WebSocket, such as webhooks, cron jobs or
+//! serverless functions, drive the gateway through plain HTTP endpoints:
+//!
+//! - `POST /api/v1/messages` - Sends a message to an agent and waits for
+//!   its response
+//! - `POST /api/v1/agents/:agent_id/runs` - Triggers an agent run in the
+//!   background
+//! - `GET /api/v1/sessions` - Lists a peer's named sessions
+//! - `GET /api/v1/sessions/transcript` - Exports a session's transcript
+//! - `GET /api/v1/channels` - Reports the state of the configured channels
+//!
+//! Each endpoint maps onto an RPC method (`chat.send`, `session.list`,
+//! `session.export` and `channels.list`), so it requires the same scope as
+//! the method does over the WebSocket, and RPC errors are returned with the
+//! matching HTTP status. Sessions are identified by the fields of their
+//! session key, given as query parameters. Callers restricted to some
+//! channel types only see those channels and their sessions.
+//!
+//! The handlers and their request and response types are annotated for the
+//! OpenAPI document built in [`crate::openapi`].
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::rejection::QueryRejection;
+use axum::extract::{ConnectInfo, Extension, Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::Instrument;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::auth::AuthInfo;
+use crate::routes::{ChannelStatus, GatewayStatusState};
+use crate::rpc::chat::run_agent_to_completion;
+use crate::rpc::middleware::auth::{check_channel, check_scope, UNAUTHORIZED_CODE};
+use crate::rpc::session::SessionExportParams;
+use crate::rpc::types::{error_codes, RpcRequest, RpcResponse};
+use crate::rpc::{register_session_methods, MethodRouter, RequestContext, SessionRpcDeps};
+
+/// JSON-RPC code of invalid parameters
+const INVALID_PARAMS: i32 = -32602;
+
+/// Build the REST API routes, reporting channel states from `status_state`
+pub fn rest_routes(status_state: Arc<GatewayStatusState>) -> Router {
+    Router::new()
+        .route("/api/v1/messages", post(send_message))
+        .route("/api/v1/agents/:agent_id/runs", post(trigger_run))
+        .route("/api/v1/sessions", get(list_sessions))
+        .route("/api/v1/sessions/transcript", get(session_transcript))
+        .route("/api/v1/channels", get(channel_status))
+        .with_state(status_state)
+}
+
+/// The authenticated caller of an endpoint
+pub(crate) struct Caller {
+    auth_info: Option<AuthInfo>,
+    remote_addr: SocketAddr,
+}
+
+impl Caller {
+    pub(crate) fn new(
+        auth_info: Option<Extension<AuthInfo>>,
+        connect_info: Option<ConnectInfo<SocketAddr>>,
+    ) -> Self {
+        Self {
+            auth_info: auth_info.map(|Extension(info)| info),
+            remote_addr: connect_info
+                .map(|ConnectInfo(addr)| addr)
+                .unwrap_or_else(|| {
+                    SocketAddr::new(std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST), 0)
+                }),
+        }
+    }
+
+    /// The error response if the caller may not call the RPC `method`
+    /// without a `channel` parameter
+    pub(crate) fn denied(&self, method: &str) -> Option<Response> {
+        let auth_info = self.auth_info.as_ref()?;
+        let client_ip = self.remote_addr.to_string();
+        check_scope(auth_info, method, &client_ip)
+            .and_then(|()| check_channel(auth_info, method, None, &client_ip))
+            .err()
+            .map(into_http_response)
+    }
+
+    /// Whether the caller may see the channel type `channel`
+    fn allows_channel(&self, channel: &str) -> bool {
+        self.auth_info
+            .as_ref()
+            .is_none_or(|auth_info| auth_info.allows_channel(channel))
+    }
+
+    /// Dispatch the RPC `method` on `router` on behalf of the caller
+    fn dispatch(self, router: &MethodRouter, method: &str, params: Value) -> Response {
+        let conn_id = format!("http-{}", uuid::Uuid::new_v4().simple());
+        let ctx = match self.auth_info {
+            Some(auth_info) => RequestContext::with_auth(conn_id, self.remote_addr, auth_info),
+            None => RequestContext::new(conn_id, self.remote_addr),
+        };
+        let request = RpcRequest {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params: Some(params),
+            id: None,
+        };
+        into_http_response(router.dispatch(ctx, request))
+    }
+}
+
+/// Map an RPC response onto an HTTP response with the matching status
+fn into_http_response(response: RpcResponse) -> Response {
+    let Some(error) = response.error else {
+        return Json(response.result.unwrap_or(Value::Null)).into_response();
+    };
+    let (status, kind) = match error.code {
+        INVALID_PARAMS | error_codes::INVALID_REQUEST => {
+            (StatusCode::BAD_REQUEST, "invalid_params")
+        }
+        code if code == UNAUTHORIZED_CODE as i32 => (StatusCode::FORBIDDEN, "forbidden"),
+        error_codes::AUTH_ERROR => (StatusCode::UNAUTHORIZED, "unauthorized"),
+        error_codes::NOT_FOUND => (StatusCode::NOT_FOUND, "not_found"),
+        error_codes::METHOD_NOT_FOUND => (StatusCode::NOT_IMPLEMENTED, "not_implemented"),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error"),
+    };
+    error_response(status, kind, error.message)
+}
+
+pub(crate) fn error_response(
+    status: StatusCode,
+    kind: &str,
+    message: impl Into<String>,
+) -> Response {
+    let body = ErrorBody {
+        error: kind.to_string(),
+        message: message.into(),
+    };
+    (status, Json(body)).into_response()
+}
+
+/// Map a rejected query string onto an invalid parameters response
+fn invalid_query(rejection: QueryRejection) -> Response {
+    error_response(
+        StatusCode::BAD_REQUEST,
+        "invalid_params",
+        rejection.body_text(),
+    )
+}
+
+/// The agent runner of the gateway, or a fresh one taking the gateway's
+/// session leases as on WebSocket connections when none was provided
+pub(crate) fn agent_runner(
+    runner: Option<Extension<Arc<aisopod_agent::AgentRunner>>>,
+    leases: Option<Extension<aisopod_agent::SessionLeases>>,
+) -> Arc<aisopod_agent::AgentRunner> {
+    match runner {
+        Some(Extension(runner)) => runner,
+        None => crate::ws::create_agent_runner_with_leases(leases.map(|Extension(l)| l)),
+    }
+}
+
+/// Body of every error response
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ErrorBody {
+    /// Machine-readable error kind, e.g. `forbidden` or `not_found`
+    pub error: String,
+    /// Human-readable description of the error
+    pub message: String,
+}
+
+/// Request body for sending a message to an agent
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SendMessageRequest {
+    /// The message text
+    pub text: String,
+    /// Session to continue; a new session is started when omitted
+    #[serde(default)]
+    pub session: Option<String>,
+    /// Agent to run; resolved from the bindings when omitted
+    #[serde(default)]
+    pub agent: Option<String>,
+}
+
+/// Request body for triggering an agent run
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TriggerRunRequest {
+    /// The prompt of the run
+    pub text: String,
+    /// Session to run in; a new session is started when omitted
+    #[serde(default)]
+    pub session: Option<String>,
+}
+
+/// The agent's response to a message
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MessageResponse {
+    /// Session the message was sent in
+    pub session: String,
+    /// The agent's response text
+    pub text: String,
+    /// Tool calls the agent made while responding
+    #[schema(value_type = Vec<Object>)]
+    pub tool_calls: Vec<aisopod_agent::types::ToolCallRecord>,
+    /// Token usage of the run
+    #[schema(value_type = Object)]
+    pub usage: aisopod_agent::UsageReport,
+    /// The response parsed as JSON, for agents with an output schema
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structured_output: Option<Value>,
+}
+
+/// Acknowledgement of a triggered agent run
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RunAccepted {
+    /// Always `accepted`
+    pub status: String,
+    /// Agent the run was triggered for
+    pub agent: String,
+    /// Session the run takes place in
+    pub session: String,
+}
+
+/// The fields of a peer's session key
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SessionPeerQuery {
+    /// The agent that owns the session
+    pub agent_id: String,
+    /// The channel type, e.g. `telegram`
+    pub channel: String,
+    /// The bot account on the channel
+    pub account_id: String,
+    /// `dm` or `group`
+    pub peer_kind: String,
+    /// The remote user or group
+    pub peer_id: String,
+}
+
+/// A peer's named sessions, as returned by `session.list`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionList {
+    /// The base session first, then the named sessions in creation order
+    #[schema(value_type = Vec<Object>)]
+    pub sessions: Vec<aisopod_session::NamedSession>,
+}
+
+/// An exported transcript, as returned by `session.export`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionTranscript {
+    /// File extension of the format, `md` or `jsonl`
+    pub extension: String,
+    /// The rendered transcript
+    pub content: String,
+}
+
+/// The state of the configured channels
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChannelList {
+    /// Number of channels
+    pub count: usize,
+    /// One entry per channel
+    pub channels: Vec<ChannelStatus>,
+}
+
+pub(crate) fn new_session_key() -> String {
+    format!("rest-{}", uuid::Uuid::new_v4().simple())
+}
+
+/// Handler for sending a message and waiting for the agent's response
+#[utoipa::path(
+    post,
+    path = "/api/v1/messages",
+    tag = "messages",
+    request_body = SendMessageRequest,
+    responses(
+        (status = 200, description = "The agent's response", body = MessageResponse),
+        (status = 403, description = "Missing the operator.write scope", body = ErrorBody),
+        (status = 500, description = "The agent run failed", body = ErrorBody),
+    )
+)]
+pub(crate) async fn send_message(
+    auth_info: Option<Extension<AuthInfo>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    runner: Option<Extension<Arc<aisopod_agent::AgentRunner>>>,
+    leases: Option<Extension<aisopod_agent::SessionLeases>>,
+    Json(request): Json<SendMessageRequest>,
+) -> Response {
+    let caller = Caller::new(auth_info, connect_info);
+    if let Some(response) = caller.denied("chat.send") {
+        return response;
+    }
+
+    let session = request.session.unwrap_or_else(new_session_key);
+    match run_agent_to_completion(
+        agent_runner(runner, leases),
+        session.clone(),
+        request.text,
+        request.agent,
+    )
+    .await
+    {
+        Ok(result) => Json(MessageResponse {
+            session,
+            text: result.response,
+            tool_calls: result.tool_calls,
+            usage: result.usage,
+            structured_output: result.structured_output,
+        })
+        .into_response(),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "agent_error",
+            format!("{:#}", e),
+        ),
+    }
+}
+
+/// Handler for triggering an agent run without waiting for it
+#[utoipa::path(
+    post,
+    path = "/api/v1/agents/{agent_id}/runs",
+    tag = "agents",
+    params(("agent_id" = String, Path, description = "Agent to run")),
+    request_body = TriggerRunRequest,
+    responses(
+        (status = 202, description = "The run was started", body = RunAccepted),
+        (status = 403, description = "Missing the operator.write scope", body = ErrorBody),
+    )
+)]
+pub(crate) async fn trigger_run(
+    auth_info: Option<Extension<AuthInfo>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    runner: Option<Extension<Arc<aisopod_agent::AgentRunner>>>,
+    leases: Option<Extension<aisopod_agent::SessionLeases>>,
+    Path(agent_id): Path<String>,
+    Json(request): Json<TriggerRunRequest>,
+) -> Response {
+    let caller = Caller::new(auth_info, connect_info);
+    if let Some(response) = caller.denied("chat.send") {
+        return response;
+    }
+
+    let session = request.session.unwrap_or_else(new_session_key);
+    let runner = agent_runner(runner, leases);
+    let run_session = session.clone();
+    let run_agent = agent_id.clone();
+    tokio::spawn(
+        async move {
+            if let Err(e) =
+                run_agent_to_completion(runner, run_session.clone(), request.text, Some(run_agent))
+                    .await
+            {
+                tracing::warn!(session = %run_session, "Triggered agent run failed: {:#}", e);
+            }
+        }
+        .in_current_span(),
+    );
+
+    let accepted = RunAccepted {
+        status: "accepted".to_string(),
+        agent: agent_id,
+        session,
+    };
+    (StatusCode::ACCEPTED, Json(accepted)).into_response()
+}
+
+/// The session RPC methods, if the gateway has a session store
+fn session_router(deps: Option<Extension<Arc<SessionRpcDeps>>>) -> Option<MethodRouter> {
+    let Extension(deps) = deps?;
+    let router = MethodRouter::new();
+    register_session_methods(&router, deps.as_ref().clone());
+    Some(router)
+}
+
+fn no_session_store() -> Response {
+    error_response(
+        StatusCode::SERVICE_UNAVAILABLE,
+        "unavailable",
+        "Session store not configured",
+    )
+}
+
+/// Handler for listing a peer's named sessions
+#[utoipa::path(
+    get,
+    path = "/api/v1/sessions",
+    tag = "sessions",
+    params(SessionPeerQuery),
+    responses(
+        (status = 200, description = "The peer's sessions", body = SessionList),
+        (status = 400, description = "Missing session key fields", body = ErrorBody),
+        (status = 403, description = "Missing the operator.read scope", body = ErrorBody),
+        (status = 503, description = "No session store is configured", body = ErrorBody),
+    )
+)]
+pub(crate) async fn list_sessions(
+    auth_info: Option<Extension<AuthInfo>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    deps: Option<Extension<Arc<SessionRpcDeps>>>,
+    query: Result<Query<SessionPeerQuery>, QueryRejection>,
+) -> Response {
+    let Some(router) = session_router(deps) else {
+        return no_session_store();
+    };
+    match query {
+        Ok(Query(query)) => {
+            Caller::new(auth_info, connect_info).dispatch(&router, "session.list", json!(query))
+        }
+        Err(rejection) => invalid_query(rejection),
+    }
+}
+
+/// Handler for exporting a session's transcript
+#[utoipa::path(
+    get,
+    path = "/api/v1/sessions/transcript",
+    tag = "sessions",
+    params(SessionExportParams),
+    responses(
+        (status = 200, description = "The rendered transcript", body = SessionTranscript),
+        (status = 400, description = "Missing session key fields or unknown format", body = ErrorBody),
+        (status = 403, description = "Missing the operator.read scope", body = ErrorBody),
+        (status = 404, description = "No such session", body = ErrorBody),
+        (status = 503, description = "No session store is configured", body = ErrorBody),
+    )
+)]
+pub(crate) async fn session_transcript(
+    auth_info: Option<Extension<AuthInfo>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    deps: Option<Extension<Arc<SessionRpcDeps>>>,
+    query: Result<Query<SessionExportParams>, QueryRejection>,
+) -> Response {
+    let Some(router) = session_router(deps) else {
+        return no_session_store();
+    };
+    match query {
+        Ok(Query(query)) => {
+            Caller::new(auth_info, connect_info).dispatch(&router, "session.export", json!(query))
+        }
+        Err(rejection) => invalid_query(rejection),
+    }
+}
+
+/// Handler for the state of the configured channels
+#[utoipa::path(
+    get,
+    path = "/api/v1/channels",
+    tag = "channels",
+    responses(
+        (status = 200, description = "The state of each channel", body = ChannelList),
+        (status = 403, description = "Missing the operator.read scope", body = ErrorBody),
+    )
+)]
+pub(crate) async fn channel_status(
+    State(state): State<Arc<GatewayStatusState>>,
+    auth_info: Option<Extension<AuthInfo>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+) -> Response {
+    let caller = Caller::new(auth_info, connect_info);
+    if let Some(response) = caller.denied("channels.list") {
+        return response;
+    }
+    let channels: Vec<_> = state
+        .channels()
+        .into_iter()
+        .filter(|channel| caller.allows_channel(&channel.channel_type))
+        .collect();
+    Json(ChannelList {
+        count: channels.len(),
+        channels,
+    })
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aisopod_session::{SessionKey, SessionStore};
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn app(state: Arc<GatewayStatusState>, deps: Option<SessionRpcDeps>, auth: AuthInfo) -> Router {
+        let mut app = rest_routes(state).layer(Extension(auth));
+        if let Some(deps) = deps {
+            app = app.layer(Extension(Arc::new(deps)));
+        }
+        app
+    }
+
+    fn operator(scopes: &[&str]) -> AuthInfo {
+        AuthInfo {
+            role: "operator".to_string(),
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    async fn get_json(app: Router, uri: &str) -> (StatusCode, Value) {
+        let response = app
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_channel_status() {
+        let state = Arc::new(GatewayStatusState::default());
+        state.set_channels(vec![ChannelStatus {
+            id: "telegram-support".to_string(),
+            channel_type: "telegram".to_string(),
+            state: "connected".to_string(),
+            last_error: None,
+        }]);
+
+        let app = app(state.clone(), None, operator(&["operator.read"]));
+        let (status, body) = get_json(app, "/api/v1/channels").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["count"], 1);
+        assert_eq!(body["channels"][0]["state"], "connected");
+
+        let app = self::app(state.clone(), None, operator(&[]));
+        let (status, body) = get_json(app, "/api/v1/channels").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["error"], "forbidden");
+
+        let app = self::app(state, None, operator(&["operator.read", "channel:slack"]));
+        let (status, body) = get_json(app, "/api/v1/channels").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["count"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_session_transcript_maps_onto_export() {
+        let store = Arc::new(SessionStore::new_in_memory().unwrap());
+        let key = SessionKey {
+            agent_id: "support".to_string(),
+            channel: "telegram".to_string(),
+            account_id: "bot".to_string(),
+            peer_kind: "dm".to_string(),
+            peer_id: "alice".to_string(),
+        };
+        store.get_or_create("support", &key).unwrap();
+        let deps = SessionRpcDeps { store };
+        let state = Arc::new(GatewayStatusState::default());
+        let auth = operator(&["operator.read"]);
+
+        let query = "agent_id=support&channel=telegram&account_id=bot&peer_kind=dm";
+        let app = app(state.clone(), Some(deps.clone()), auth.clone());
+        let uri = format!("/api/v1/sessions/transcript?{}&peer_id=alice", query);
+        let (status, body) = get_json(app, &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["extension"], "md");
+
+        let app = self::app(state.clone(), Some(deps.clone()), auth.clone());
+        let uri = format!("/api/v1/sessions/transcript?{}&peer_id=bob", query);
+        let (status, _) = get_json(app, &uri).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let slack_only = operator(&["operator.read", "channel:slack"]);
+        let app = self::app(state.clone(), Some(deps.clone()), slack_only);
+        let uri = format!("/api/v1/sessions/transcript?{}&peer_id=alice", query);
+        let (status, _) = get_json(app, &uri).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let app = self::app(state.clone(), Some(deps), auth.clone());
+        let (status, body) = get_json(app, "/api/v1/sessions/transcript").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "invalid_params");
+
+        let app = self::app(state, None, auth);
+        let (status, _) = get_json(app, "/api/v1/sessions?peer_id=alice").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_send_message_requires_write_scope() {
+        let state = Arc::new(GatewayStatusState::default());
+        let app = app(state, None, operator(&["operator.read"]));
+        let response = app
+            .oneshot(
+                Request::post("/api/v1/messages")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"text": "hello"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+}
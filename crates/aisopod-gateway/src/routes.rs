@@ -59,6 +59,42 @@ pub struct GatewayStatus {
     pub active_sessions: usize,
     /// Gateway uptime in seconds
     pub uptime: u64,
+    /// Health of loaded plugins
+    #[serde(default)]
+    pub plugins: Vec<PluginStatus>,
+}
+
+/// Health of a loaded plugin, as reported by the status endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginStatus {
+    /// Plugin ID
+    pub id: String,
+    /// Health state (e.g., "healthy", "unhealthy", "disabled")
+    pub state: String,
+    /// Number of calls into the plugin
+    pub calls: u64,
+    /// Number of calls that returned an error
+    pub errors: u64,
+    /// Number of calls that panicked
+    pub panics: u64,
+    /// Average call duration in milliseconds
+    pub average_latency_ms: f64,
+    /// Number of times the plugin was restarted
+    pub restarts: u32,
+}
+
+/// State of a configured channel, as reported by the channels endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ChannelStatus {
+    /// Channel ID
+    pub id: String,
+    /// Channel type (e.g., "telegram", "slack")
+    pub channel_type: String,
+    /// Connection state (e.g., "connected", "disconnected", "error")
+    pub state: String,
+    /// Last connection error, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
 }
 
 /// Status endpoint handler
@@ -70,6 +106,7 @@ pub async fn status(
         active_channels: state.active_channels.load(std::sync::atomic::Ordering::Relaxed),
         active_sessions: state.active_sessions.load(std::sync::atomic::Ordering::Relaxed),
         uptime: state.start_time.elapsed().as_secs(),
+        plugins: state.plugins(),
     };
     Json(json!(status))
 }
@@ -85,6 +122,10 @@ pub struct GatewayStatusState {
     pub active_channels: std::sync::atomic::AtomicUsize,
     /// Number of active sessions (atomic)
     pub active_sessions: std::sync::atomic::AtomicUsize,
+    /// Health of loaded plugins
+    pub plugins: Mutex<Vec<PluginStatus>>,
+    /// State of the configured channels
+    pub channels: Mutex<Vec<ChannelStatus>>,
 }
 
 impl GatewayStatusState {
@@ -95,6 +136,8 @@ impl GatewayStatusState {
             agent_count: std::sync::atomic::AtomicUsize::new(agent_count),
             active_channels: std::sync::atomic::AtomicUsize::new(active_channels),
             active_sessions: std::sync::atomic::AtomicUsize::new(active_sessions),
+            plugins: Mutex::new(Vec::new()),
+            channels: Mutex::new(Vec::new()),
         }
     }
 
@@ -112,6 +155,26 @@ impl GatewayStatusState {
     pub fn set_active_sessions(&self, count: usize) {
         self.active_sessions.store(count, std::sync::atomic::Ordering::Relaxed);
     }
+
+    /// Update the health of loaded plugins
+    pub fn set_plugins(&self, plugins: Vec<PluginStatus>) {
+        *self.plugins.lock().unwrap() = plugins;
+    }
+
+    /// Get the health of loaded plugins
+    pub fn plugins(&self) -> Vec<PluginStatus> {
+        self.plugins.lock().unwrap().clone()
+    }
+
+    /// Update the state of the configured channels
+    pub fn set_channels(&self, channels: Vec<ChannelStatus>) {
+        *self.channels.lock().unwrap() = channels;
+    }
+
+    /// Get the state of the configured channels
+    pub fn channels(&self) -> Vec<ChannelStatus> {
+        self.channels.lock().unwrap().clone()
+    }
 }
 
 impl Default for GatewayStatusState {
@@ -3,15 +3,28 @@
 //! This module provides the approval request data structures
 //! used by the approval handlers.
 
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
 /// Storage for pending approval requests
 #[derive(Debug, Clone)]
 pub struct ApprovalStore {
     inner: Arc<Mutex<HashMap<String, PendingApproval>>>,
+    replication: Option<mpsc::UnboundedSender<ApprovalChange>>,
+}
+
+/// A change to an approval store, replicated to the other nodes of a cluster
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum ApprovalChange {
+    /// An approval was requested
+    Stored { approval: PendingApproval },
+    /// An approval was decided
+    Removed { id: String },
 }
 
 impl ApprovalStore {
@@ -19,13 +32,21 @@ impl ApprovalStore {
     pub fn new() -> Self {
         Self {
             inner: Arc::new(Mutex::new(HashMap::new())),
+            replication: None,
         }
     }
 
+    /// Send every change made through this store to `sender`
+    pub fn with_replication(mut self, sender: mpsc::UnboundedSender<ApprovalChange>) -> Self {
+        self.replication = Some(sender);
+        self
+    }
+
     /// Store a pending approval request
     pub fn store(&self, approval: PendingApproval) {
         let mut store = self.inner.lock().unwrap();
-        store.insert(approval.id.clone(), approval);
+        store.insert(approval.id.clone(), approval.clone());
+        self.replicate(ApprovalChange::Stored { approval });
     }
 
     /// Get a pending approval by ID
@@ -37,7 +58,31 @@ impl ApprovalStore {
     /// Remove and return a pending approval by ID
     pub fn remove(&self, id: &str) -> Option<PendingApproval> {
         let mut store = self.inner.lock().unwrap();
-        store.remove(id)
+        let removed = store.remove(id);
+        if removed.is_some() {
+            self.replicate(ApprovalChange::Removed { id: id.to_string() });
+        }
+        removed
+    }
+
+    /// Apply a change replicated from another node, without replicating it
+    pub fn apply(&self, change: ApprovalChange) {
+        let mut store = self.inner.lock().unwrap();
+        match change {
+            ApprovalChange::Stored { approval } => {
+                store.insert(approval.id.clone(), approval);
+            }
+            ApprovalChange::Removed { id } => {
+                store.remove(&id);
+            }
+        }
+    }
+
+    fn replicate(&self, change: ApprovalChange) {
+        if let Some(replication) = &self.replication {
+            // The receiver only goes away when the cluster relay has stopped
+            let _ = replication.send(change);
+        }
     }
 
     /// List all pending approvals
@@ -54,7 +99,7 @@ impl Default for ApprovalStore {
 }
 
 /// Represents a pending approval request
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PendingApproval {
     /// Unique ID of the approval request
     pub id: String,
@@ -71,7 +116,8 @@ pub struct PendingApproval {
 }
 
 /// Status of an approval request
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ApprovalStatus {
     Pending,
     Approved,
@@ -173,6 +219,40 @@ mod tests {
         assert_eq!(approvals.len(), 2);
     }
 
+    #[test]
+    fn test_approval_store_replication() {
+        let (sender, mut changes) = mpsc::unbounded_channel();
+        let store = ApprovalStore::new().with_replication(sender);
+        let approval = PendingApproval::new(
+            "agent-1".to_string(),
+            "echo hello".to_string(),
+            "Low".to_string(),
+        );
+        let id = approval.id.clone();
+
+        store.store(approval.clone());
+        store.remove(&id);
+        store.remove(&id);
+        assert_eq!(
+            changes.try_recv().unwrap(),
+            ApprovalChange::Stored {
+                approval: approval.clone()
+            }
+        );
+        assert_eq!(
+            changes.try_recv().unwrap(),
+            ApprovalChange::Removed { id: id.clone() }
+        );
+        assert!(changes.try_recv().is_err());
+
+        // Changes from other nodes are applied but not replicated again
+        store.apply(ApprovalChange::Stored { approval });
+        assert!(store.get(&id).is_some());
+        store.apply(ApprovalChange::Removed { id: id.clone() });
+        assert!(store.get(&id).is_none());
+        assert!(changes.try_recv().is_err());
+    }
+
     #[test]
     fn test_approval_request_params_deserialization() {
         let json = r#"{"agent_id":"agent-123","operation":"rm -rf /","risk_level":"Critical"}"#;
@@ -6,6 +6,7 @@
 use anyhow::Result;
 use serde_json::json;
 use std::sync::Arc;
+use tracing::Instrument;
 
 /// Handler for the chat.send RPC method.
 ///
@@ -81,20 +82,24 @@ impl ChatSendHandler {
         let conn_id_for_response = conn_id_clone.clone();
 
         // Spawn a task to run the agent and stream results
-        tokio::spawn(async move {
-            if let Err(e) = run_agent_and_stream(
-                agent_runner,
-                ws_sender,
-                conn_id_clone,
-                params.text,
-                channel,
-                agent_id,
-            )
-            .await
-            {
-                eprintln!("Error running agent: {}", e);
+        let span = tracing::info_span!("rpc.dispatch", method = "chat.send", conn_id = %conn_id);
+        tokio::spawn(
+            async move {
+                if let Err(e) = run_agent_and_stream(
+                    agent_runner,
+                    ws_sender,
+                    conn_id_clone,
+                    params.text,
+                    channel,
+                    agent_id,
+                )
+                .await
+                {
+                    eprintln!("Error running agent: {}", e);
+                }
             }
-        });
+            .instrument(span),
+        );
 
         // Return immediate acknowledgment (use conn_id_for_response since conn_id_clone is moved)
         serde_json::json!({
@@ -108,6 +113,41 @@ impl ChatSendHandler {
     }
 }
 
+/// Parameters of a run answering the user message `text`
+pub(crate) fn user_message_params(
+    session_key: String,
+    text: String,
+    agent_id: Option<String>,
+) -> aisopod_agent::AgentRunParams {
+    let message = aisopod_provider::Message {
+        role: aisopod_provider::Role::User,
+        content: aisopod_provider::MessageContent::Text(text),
+        tool_calls: None,
+        tool_call_id: None,
+    };
+    aisopod_agent::AgentRunParams::new(session_key, vec![message], agent_id)
+}
+
+/// Run an agent to completion and return its result, for callers that
+/// cannot consume the streamed events
+pub async fn run_agent_to_completion(
+    agent_runner: Arc<aisopod_agent::AgentRunner>,
+    session_key: String,
+    text: String,
+    agent_id: Option<String>,
+) -> Result<aisopod_agent::AgentRunResult> {
+    let params = user_message_params(session_key, text, agent_id);
+    let mut receiver = agent_runner.run(params).await?.into_receiver();
+    while let Some(event) = receiver.recv().await {
+        match event {
+            aisopod_agent::AgentEvent::Complete { result } => return Ok(result),
+            aisopod_agent::AgentEvent::Error { message } => anyhow::bail!(message),
+            _ => {}
+        }
+    }
+    anyhow::bail!("Agent run ended without a result")
+}
+
 /// Run an agent and stream results via WebSocket
 async fn run_agent_and_stream(
     agent_runner: Arc<aisopod_agent::AgentRunner>,
@@ -183,6 +223,28 @@ async fn run_agent_and_stream(
                     break;
                 }
             }
+            aisopod_agent::AgentEvent::ToolCallOutput { call_id, stream, text } => {
+                // Stream incremental tool output as a progress update
+                let response = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "method": "chat.response",
+                    "params": {
+                        "tool_call_output": {
+                            "call_id": call_id,
+                            "stream": stream,
+                            "text": text
+                        },
+                        "done": false
+                    }
+                });
+
+                if let Err(e) = ws_sender.send(axum::extract::ws::Message::Text(
+                    serde_json::to_string(&response)?
+                )).await {
+                    eprintln!("Failed to send tool call output: {}", e);
+                    break;
+                }
+            }
             aisopod_agent::AgentEvent::ToolCallResult { call_id, result, is_error } => {
                 // Stream tool call result
                 let response = serde_json::json!({
@@ -205,6 +267,67 @@ async fn run_agent_and_stream(
                     break;
                 }
             }
+            aisopod_agent::AgentEvent::PlanCreated { iteration, steps } => {
+                // Stream a new or revised plan
+                let response = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "method": "chat.response",
+                    "params": {
+                        "plan": {
+                            "iteration": iteration,
+                            "steps": steps
+                        },
+                        "done": false
+                    }
+                });
+
+                if let Err(e) = ws_sender.send(axum::extract::ws::Message::Text(
+                    serde_json::to_string(&response)?
+                )).await {
+                    eprintln!("Failed to send plan: {}", e);
+                    break;
+                }
+            }
+            aisopod_agent::AgentEvent::PlanStepProgress { index, status, output } => {
+                // Stream plan step progress
+                let response = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "method": "chat.response",
+                    "params": {
+                        "plan_step": {
+                            "index": index,
+                            "status": status,
+                            "output": output
+                        },
+                        "done": false
+                    }
+                });
+
+                if let Err(e) = ws_sender.send(axum::extract::ws::Message::Text(
+                    serde_json::to_string(&response)?
+                )).await {
+                    eprintln!("Failed to send plan step progress: {}", e);
+                    break;
+                }
+            }
+            aisopod_agent::AgentEvent::Interruption { message } => {
+                // Stream a follow-up injected into the running run
+                let response = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "method": "chat.response",
+                    "params": {
+                        "interruption": message,
+                        "done": false
+                    }
+                });
+
+                if let Err(e) = ws_sender.send(axum::extract::ws::Message::Text(
+                    serde_json::to_string(&response)?
+                )).await {
+                    eprintln!("Failed to send interruption: {}", e);
+                    break;
+                }
+            }
             aisopod_agent::AgentEvent::Error { message } => {
                 // Stream error
                 let response = serde_json::json!({
@@ -0,0 +1,272 @@
+//! Config RPC methods
+//!
+//! This module implements `config.diff`, which previews a configuration
+//! change without applying it: the proposed file is parsed and validated
+//! like one loaded from disk, then compared section by section with the
+//! running configuration. Like `config.get`, it requires the
+//! `operator.read` scope. Environment variable and secret references in
+//! the proposal are not resolved, secrets are compared by reference only,
+//! and an invalid proposal is rejected without details.
+//!
+//! The method is registered on a connection's router when a
+//! [`ConfigRpcDeps`] is present in the request extensions.
+
+use crate::rpc::handler::{MethodRouter, RpcMethod};
+use crate::rpc::types;
+use crate::rpc::RequestContext;
+use aisopod_config::{diff_sections, with_secrets_unresolved, AisopodConfig};
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Dependencies of the config RPC methods
+#[derive(Clone)]
+pub struct ConfigRpcDeps {
+    /// The configuration the gateway is running with
+    pub config: Arc<AisopodConfig>,
+}
+
+/// Format of a proposed configuration file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigDiffFormat {
+    #[default]
+    Json5,
+    Toml,
+}
+
+/// Config diff parameters
+#[derive(Debug, Deserialize)]
+pub struct ConfigDiffParams {
+    /// The complete proposed configuration file
+    pub content: String,
+    /// Format of `content`; `json5` when omitted
+    #[serde(default)]
+    pub format: ConfigDiffFormat,
+}
+
+/// Register the `config.diff` handler on `router`
+pub fn register_config_methods(router: &MethodRouter, deps: ConfigRpcDeps) {
+    router.register("config.diff", ConfigDiffHandler::with_deps(deps));
+}
+
+/// Handler for config.diff RPC method
+pub struct ConfigDiffHandler {
+    deps: ConfigRpcDeps,
+}
+
+impl ConfigDiffHandler {
+    /// Create a new config diff handler with dependencies
+    pub fn with_deps(deps: ConfigRpcDeps) -> Self {
+        Self { deps }
+    }
+}
+
+impl RpcMethod for ConfigDiffHandler {
+    fn handle(
+        &self,
+        ctx: &RequestContext,
+        params: Option<serde_json::Value>,
+    ) -> types::RpcResponse {
+        let invalid_params = |message: String| {
+            types::RpcResponse::error(
+                Some(serde_json::json!(ctx.conn_id.clone())),
+                -32602,
+                message,
+            )
+        };
+        let params: ConfigDiffParams = match params.map(serde_json::from_value) {
+            Some(Ok(params)) => params,
+            Some(Err(e)) => return invalid_params(format!("Invalid parameters: {}", e)),
+            None => return invalid_params("Missing parameters".to_string()),
+        };
+        // The proposal comes from the caller: leave its `${VAR}` and secret
+        // references unexpanded so that neither the gateway's environment
+        // nor a secret backend is consulted on its behalf, and compare
+        // secrets by reference so that a guess can't be checked against
+        // the running secret
+        let changed = with_secrets_unresolved(|| {
+            let proposed = match params.format {
+                ConfigDiffFormat::Json5 => {
+                    aisopod_config::load_config_json5_str_unexpanded(&params.content)
+                }
+                ConfigDiffFormat::Toml => aisopod_config::load_config_toml_str(&params.content),
+            };
+            proposed.map(|proposed| diff_sections(&self.deps.config, &proposed))
+        });
+        match changed {
+            Ok(changed) => types::RpcResponse::success(
+                Some(serde_json::json!(ctx.conn_id.clone())),
+                serde_json::json!({ "changed_sections": changed }),
+            ),
+            // No details: validation messages quote field values
+            Err(_) => invalid_params("Invalid configuration".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::AuthInfo;
+    use crate::rpc::types::RpcRequest;
+    use aisopod_config::types::AuthProfile;
+    use aisopod_config::Sensitive;
+    use serde_json::json;
+
+    fn router() -> MethodRouter {
+        let router = MethodRouter::new();
+        register_config_methods(
+            &router,
+            ConfigRpcDeps {
+                config: Arc::new(AisopodConfig::default()),
+            },
+        );
+        router
+    }
+
+    fn call(
+        router: &MethodRouter,
+        scopes: &[&str],
+        params: serde_json::Value,
+    ) -> types::RpcResponse {
+        let auth_info = AuthInfo {
+            role: "operator".to_string(),
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+        };
+        let ctx = RequestContext::with_auth(
+            "conn-1".to_string(),
+            "127.0.0.1:0".parse().unwrap(),
+            auth_info,
+        );
+        let request = RpcRequest {
+            jsonrpc: "2.0".to_string(),
+            method: "config.diff".to_string(),
+            params: Some(params),
+            id: Some(json!(1)),
+        };
+        router.dispatch(ctx, request)
+    }
+
+    #[test]
+    fn test_diff_lists_changed_sections() {
+        let router = router();
+        let response = call(
+            &router,
+            &["operator.read"],
+            json!({ "content": "{ meta: { version: \"2.0\" } }" }),
+        );
+        let result = response.result.expect("diff computed");
+        assert_eq!(result["changed_sections"], json!(["meta"]));
+
+        let response = call(
+            &router,
+            &["operator.read"],
+            json!({ "content": "[meta]\nversion = \"2.0\"\n", "format": "toml" }),
+        );
+        assert_eq!(
+            response.result.unwrap()["changed_sections"],
+            json!(["meta"])
+        );
+    }
+
+    #[test]
+    fn test_unchanged_config_has_no_sections() {
+        let response = call(&router(), &["operator.read"], json!({ "content": "{}" }));
+        assert_eq!(response.result.unwrap()["changed_sections"], json!([]));
+    }
+
+    #[test]
+    fn test_invalid_config_is_rejected() {
+        let response = call(
+            &router(),
+            &["operator.read"],
+            json!({ "content": "{ meta: " }),
+        );
+        let error = response.error.expect("parse error");
+        assert_eq!(error.code, -32602);
+        assert!(error.message.starts_with("Invalid configuration"));
+    }
+
+    #[test]
+    fn test_secrets_are_compared_by_reference() {
+        let mut running = AisopodConfig::default();
+        running.auth.profiles.push(AuthProfile {
+            name: "openai".to_string(),
+            api_key: Sensitive::new("running-secret".to_string()),
+            ..Default::default()
+        });
+        let router = MethodRouter::new();
+        register_config_methods(
+            &router,
+            ConfigRpcDeps {
+                config: Arc::new(running),
+            },
+        );
+        let propose = |api_key: &str| {
+            let content = format!(
+                "{{ auth: {{ profiles: [{{ name: \"openai\", api_key: \"{}\" }}] }} }}",
+                api_key
+            );
+            call(&router, &["operator.read"], json!({ "content": content }))
+        };
+
+        // Guessing the running plaintext secret reveals nothing
+        assert_eq!(
+            propose("running-secret").result,
+            propose("wrong-guess").result
+        );
+        // References are compared as written, without running the backend
+        let response = propose("vault:secret/aisopod#api_key");
+        assert_eq!(
+            response.result.unwrap()["changed_sections"],
+            json!(["auth"])
+        );
+    }
+
+    #[test]
+    fn test_environment_is_not_expanded() {
+        std::env::set_var("AISOPOD_CONFIG_DIFF_TEST_SECRET", "env-secret-value");
+        let router = router();
+        let response = call(
+            &router,
+            &["operator.read"],
+            json!({
+                "content": "{ bindings: [{ agent_id: \"${AISOPOD_CONFIG_DIFF_TEST_SECRET}\", channels: [] }] }"
+            }),
+        );
+        let response = serde_json::to_string(&response).unwrap();
+        assert!(!response.contains("env-secret-value"));
+
+        // A reference is compared as written, so the diff can't be used
+        // to check a guess against the environment
+        let mut running = AisopodConfig::default();
+        running
+            .agents
+            .prompt_templates
+            .insert("t".to_string(), "env-secret-value".to_string());
+        let router = MethodRouter::new();
+        register_config_methods(
+            &router,
+            ConfigRpcDeps {
+                config: Arc::new(running),
+            },
+        );
+        let response = call(
+            &router,
+            &["operator.read"],
+            json!({
+                "content": "{ agents: { prompt_templates: { t: \"${AISOPOD_CONFIG_DIFF_TEST_SECRET}\" } } }"
+            }),
+        );
+        assert_eq!(
+            response.result.unwrap()["changed_sections"],
+            json!(["agents"])
+        );
+    }
+
+    #[test]
+    fn test_requires_read_scope() {
+        let response = call(&router(), &[], json!({ "content": "{}" }));
+        assert!(response.error.is_some());
+    }
+}
@@ -8,7 +8,7 @@ use crate::broadcast::{Broadcaster, GatewayEvent, Subscription};
 use crate::rpc::approval::{PendingApproval, ApprovalStatus, ApprovalRequestParams, ApprovalStore};
 use crate::rpc::canvas::{CanvasInteractParams, CanvasInteractResult};
 use crate::rpc::chat::ChatSendHandler;
-use crate::rpc::middleware::auth::check_scope;
+use crate::rpc::middleware::auth::{check_channel, check_scope};
 use crate::rpc::node_pair::{PairingStore, PairRequestHandler, PairConfirmHandler, PairRevokeHandler};
 use crate::rpc::types;
 
@@ -129,6 +129,9 @@ impl MethodRouter {
     /// Returns a response with -32601 if the method is not found
     pub fn dispatch(&self, ctx: RequestContext, req: types::RpcRequest) -> types::RpcResponse {
         let method_name = &req.method;
+        let _span =
+            tracing::info_span!("rpc.dispatch", method = %method_name, conn_id = %ctx.conn_id)
+                .entered();
 
         // Check scope authorization before dispatching
         if let Some(auth_info) = ctx.auth_info() {
@@ -136,6 +139,11 @@ impl MethodRouter {
             if let Err(error_response) = check_scope(auth_info, method_name, &client_ip) {
                 return error_response;
             }
+            if let Err(error_response) =
+                check_channel(auth_info, method_name, req.params.as_ref(), &client_ip)
+            {
+                return error_response;
+            }
         }
 
         let methods = self.methods.lock().unwrap();
@@ -0,0 +1,436 @@
+//! Memory browse and edit RPC methods
+//!
+//! This module implements the `memory.*` RPC methods that let operators
+//! inspect and correct what agents have remembered:
+//! 1. `memory.search` - Searches memories, or lists them when no query is given
+//! 2. `memory.delete` - Deletes a memory
+//! 3. `memory.update` - Edits the content, tags or importance of a memory
+//!
+//! The methods are registered on a connection's router when a
+//! [`MemoryRpcDeps`] is present in the request extensions.
+
+use crate::rpc::handler::{MethodRouter, RpcMethod};
+use crate::rpc::types;
+use crate::rpc::RequestContext;
+use aisopod_memory::{
+    EmbeddingProvider, MemoryEntry, MemoryFilter, MemoryQueryOptions, MemorySource, MemoryStore,
+    MemoryUpdate,
+};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::Arc;
+
+/// Number of memories returned by `memory.search` when no limit is given
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// Dependencies of the memory RPC methods
+#[derive(Clone)]
+pub struct MemoryRpcDeps {
+    /// Store the methods operate on
+    pub store: Arc<dyn MemoryStore>,
+    /// Embedder used to re-embed edited content; without one edited
+    /// memories keep their previous embedding
+    pub embedder: Option<Arc<dyn EmbeddingProvider>>,
+}
+
+/// Memory search parameters
+#[derive(Debug, Deserialize)]
+pub struct MemorySearchParams {
+    /// Text to search for; memories are listed newest first when omitted
+    #[serde(default)]
+    pub query: Option<String>,
+    /// Match the query's terms instead of its meaning
+    #[serde(default)]
+    pub keyword: bool,
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// A memory as returned to operators, without its embedding
+#[derive(Debug, Serialize)]
+pub struct MemoryView {
+    pub id: String,
+    pub agent_id: String,
+    pub namespace: String,
+    pub content: String,
+    pub source: MemorySource,
+    pub tags: Vec<String>,
+    pub importance: f32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Relevance to the search query, when there is one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
+}
+
+impl From<MemoryEntry> for MemoryView {
+    fn from(entry: MemoryEntry) -> Self {
+        Self {
+            id: entry.id,
+            agent_id: entry.agent_id,
+            namespace: entry.namespace,
+            content: entry.content,
+            source: entry.metadata.source,
+            tags: entry.metadata.tags,
+            importance: entry.metadata.importance,
+            created_at: entry.created_at,
+            updated_at: entry.updated_at,
+            score: None,
+        }
+    }
+}
+
+/// Memory delete parameters
+#[derive(Debug, Deserialize)]
+pub struct MemoryDeleteParams {
+    pub id: String,
+}
+
+/// Memory update parameters; omitted fields are left unchanged
+#[derive(Debug, Deserialize)]
+pub struct MemoryUpdateParams {
+    pub id: String,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub importance: Option<f32>,
+}
+
+/// Register the `memory.*` handlers on `router`
+pub fn register_memory_methods(router: &MethodRouter, deps: MemoryRpcDeps) {
+    router.register(
+        "memory.search",
+        MemorySearchHandler::with_deps(deps.clone()),
+    );
+    router.register(
+        "memory.delete",
+        MemoryDeleteHandler::with_deps(deps.clone()),
+    );
+    router.register("memory.update", MemoryUpdateHandler::with_deps(deps));
+}
+
+/// Run a store operation to completion from a synchronous handler
+///
+/// Requires the multi-threaded runtime the gateway runs on.
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(future))
+}
+
+/// Parse required handler parameters, describing why they are invalid
+fn parse_params<T: DeserializeOwned>(params: Option<serde_json::Value>) -> Result<T, String> {
+    match params {
+        Some(p) => serde_json::from_value(p).map_err(|e| format!("Invalid parameters: {}", e)),
+        None => Err("Missing parameters".to_string()),
+    }
+}
+
+/// Build the error response for invalid parameters
+fn invalid_params(ctx: &RequestContext, message: String) -> types::RpcResponse {
+    types::RpcResponse::error(
+        Some(serde_json::json!(ctx.conn_id.clone())),
+        -32602,
+        message,
+    )
+}
+
+/// Build the error response for a failed store operation
+fn store_error(ctx: &RequestContext, error: anyhow::Error) -> types::RpcResponse {
+    types::RpcResponse::error(
+        Some(serde_json::json!(ctx.conn_id.clone())),
+        types::error_codes::INTERNAL_ERROR,
+        format!("Memory store error: {}", error),
+    )
+}
+
+/// Build the error response for an unknown memory ID
+fn not_found(ctx: &RequestContext, id: &str) -> types::RpcResponse {
+    types::RpcResponse::error(
+        Some(serde_json::json!(ctx.conn_id.clone())),
+        types::error_codes::NOT_FOUND,
+        format!("Memory {} not found", id),
+    )
+}
+
+/// Handler for memory.search RPC method
+pub struct MemorySearchHandler {
+    deps: MemoryRpcDeps,
+}
+
+impl MemorySearchHandler {
+    /// Create a new memory search handler with dependencies
+    pub fn with_deps(deps: MemoryRpcDeps) -> Self {
+        Self { deps }
+    }
+}
+
+impl RpcMethod for MemorySearchHandler {
+    fn handle(
+        &self,
+        ctx: &RequestContext,
+        params: Option<serde_json::Value>,
+    ) -> types::RpcResponse {
+        // Searching without parameters lists all memories
+        let params: MemorySearchParams =
+            match parse_params(Some(params.unwrap_or_else(|| serde_json::json!({})))) {
+                Ok(p) => p,
+                Err(message) => return invalid_params(ctx, message),
+            };
+
+        let filter = MemoryFilter {
+            agent_id: params.agent_id,
+            namespace: params.namespace,
+            tags: params.tags,
+            ..Default::default()
+        };
+        let limit = params.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+        let store = &self.deps.store;
+
+        let result = block_on(async {
+            match params.query.filter(|q| !q.trim().is_empty()) {
+                Some(query) => {
+                    let opts = MemoryQueryOptions {
+                        top_k: limit,
+                        filter,
+                        min_score: None,
+                    };
+                    let matches = if params.keyword {
+                        store.keyword_query(&query, opts).await?
+                    } else {
+                        store.query(&query, opts).await?
+                    };
+                    Ok(matches
+                        .into_iter()
+                        .map(|m| MemoryView {
+                            score: Some(m.score),
+                            ..MemoryView::from(m.entry)
+                        })
+                        .collect::<Vec<_>>())
+                }
+                None => {
+                    let mut entries = store.list(filter).await?;
+                    entries.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+                    entries.truncate(limit);
+                    Ok(entries.into_iter().map(MemoryView::from).collect())
+                }
+            }
+        });
+
+        match result {
+            Ok(memories) => types::RpcResponse::success(
+                Some(serde_json::json!(ctx.conn_id.clone())),
+                serde_json::json!({ "memories": memories }),
+            ),
+            Err(e) => store_error(ctx, e),
+        }
+    }
+}
+
+/// Handler for memory.delete RPC method
+pub struct MemoryDeleteHandler {
+    deps: MemoryRpcDeps,
+}
+
+impl MemoryDeleteHandler {
+    /// Create a new memory delete handler with dependencies
+    pub fn with_deps(deps: MemoryRpcDeps) -> Self {
+        Self { deps }
+    }
+}
+
+impl RpcMethod for MemoryDeleteHandler {
+    fn handle(
+        &self,
+        ctx: &RequestContext,
+        params: Option<serde_json::Value>,
+    ) -> types::RpcResponse {
+        let params: MemoryDeleteParams = match parse_params(params) {
+            Ok(p) => p,
+            Err(message) => return invalid_params(ctx, message),
+        };
+        let store = &self.deps.store;
+
+        let result = block_on(async {
+            if store.get(&params.id).await?.is_none() {
+                return Ok(false);
+            }
+            store.delete(&params.id).await?;
+            Ok(true)
+        });
+
+        match result {
+            Ok(true) => types::RpcResponse::success(
+                Some(serde_json::json!(ctx.conn_id.clone())),
+                serde_json::json!({ "deleted": params.id }),
+            ),
+            Ok(false) => not_found(ctx, &params.id),
+            Err(e) => store_error(ctx, e),
+        }
+    }
+}
+
+/// Handler for memory.update RPC method
+pub struct MemoryUpdateHandler {
+    deps: MemoryRpcDeps,
+}
+
+impl MemoryUpdateHandler {
+    /// Create a new memory update handler with dependencies
+    pub fn with_deps(deps: MemoryRpcDeps) -> Self {
+        Self { deps }
+    }
+}
+
+impl RpcMethod for MemoryUpdateHandler {
+    fn handle(
+        &self,
+        ctx: &RequestContext,
+        params: Option<serde_json::Value>,
+    ) -> types::RpcResponse {
+        let params: MemoryUpdateParams = match parse_params(params) {
+            Ok(p) => p,
+            Err(message) => return invalid_params(ctx, message),
+        };
+        let store = &self.deps.store;
+        let update = MemoryUpdate {
+            content: params.content,
+            tags: params.tags,
+            importance: params.importance,
+            embedder: self.deps.embedder.clone(),
+        };
+
+        let result = block_on(async {
+            if store.get(&params.id).await?.is_none() {
+                return Ok(None);
+            }
+            store.update(&params.id, update).await.map(Some)
+        });
+
+        match result {
+            Ok(Some(entry)) => types::RpcResponse::success(
+                Some(serde_json::json!(ctx.conn_id.clone())),
+                serde_json::json!({ "memory": MemoryView::from(entry) }),
+            ),
+            Ok(None) => not_found(ctx, &params.id),
+            Err(e) => store_error(ctx, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aisopod_memory::sqlite::SqliteMemoryStore;
+    use aisopod_memory::MockEmbeddingProvider;
+    use std::net::SocketAddr;
+
+    /// Test helper to create a router with memory methods over in-memory SQLite
+    async fn create_router() -> MethodRouter {
+        let store = Arc::new(SqliteMemoryStore::new(":memory:", 4).unwrap());
+        let embedder = Arc::new(MockEmbeddingProvider::new(4));
+        for (id, agent, content) in [
+            ("m-1", "agent-1", "The user prefers dark mode"),
+            ("m-2", "agent-1", "The deploy key is rotated by rotate_keys"),
+            ("m-3", "agent-2", "The user's cat is called Miso"),
+        ] {
+            let embedding = embedder.embed(content).await.unwrap();
+            let entry = MemoryEntry::new(
+                id.to_string(),
+                agent.to_string(),
+                content.to_string(),
+                embedding,
+            );
+            store.store(entry).await.unwrap();
+        }
+
+        let router = MethodRouter::new();
+        register_memory_methods(
+            &router,
+            MemoryRpcDeps {
+                store,
+                embedder: Some(embedder),
+            },
+        );
+        router
+    }
+
+    fn call(router: &MethodRouter, method: &str, params: serde_json::Value) -> types::RpcResponse {
+        let addr: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+        let ctx = RequestContext::new("conn-1".to_string(), addr);
+        let request = types::RpcRequest {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params: Some(params),
+            id: Some(serde_json::json!(1)),
+        };
+        router.dispatch(ctx, request)
+    }
+
+    fn memory_ids(response: &types::RpcResponse) -> Vec<String> {
+        response.result.as_ref().unwrap()["memories"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["id"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_memory_search_lists_and_searches() {
+        let router = create_router().await;
+
+        let response = call(
+            &router,
+            "memory.search",
+            serde_json::json!({ "agent_id": "agent-1" }),
+        );
+        let mut ids = memory_ids(&response);
+        ids.sort();
+        assert_eq!(ids, vec!["m-1", "m-2"]);
+
+        let response = call(
+            &router,
+            "memory.search",
+            serde_json::json!({ "query": "who calls rotate_keys", "keyword": true }),
+        );
+        assert_eq!(memory_ids(&response), vec!["m-2"]);
+        let memory = &response.result.as_ref().unwrap()["memories"][0];
+        assert!(memory["score"].as_f64().unwrap() > 0.0);
+        assert!(memory.get("embedding").is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_memory_update_and_delete() {
+        let router = create_router().await;
+
+        let response = call(
+            &router,
+            "memory.update",
+            serde_json::json!({ "id": "m-3", "content": "The user's cat is called Mochi", "tags": ["pets"] }),
+        );
+        let memory = &response.result.as_ref().unwrap()["memory"];
+        assert_eq!(memory["content"], "The user's cat is called Mochi");
+        assert_eq!(memory["tags"], serde_json::json!(["pets"]));
+
+        let response = call(&router, "memory.delete", serde_json::json!({ "id": "m-3" }));
+        assert_eq!(response.result.unwrap()["deleted"], "m-3");
+
+        for method in ["memory.delete", "memory.update"] {
+            let response = call(&router, method, serde_json::json!({ "id": "m-3" }));
+            let error = response.error.unwrap();
+            assert_eq!(error.code, types::error_codes::NOT_FOUND);
+        }
+
+        let response = call(&router, "memory.delete", serde_json::json!({}));
+        assert_eq!(response.error.unwrap().code, -32602);
+    }
+}
@@ -6,9 +6,9 @@
 use crate::audit::log_authz_decision;
 use crate::auth::AuthInfo;
 use crate::rpc::jsonrpc::{RpcError, RpcResponse};
-use crate::auth::scopes::{required_scope, Scope};
+use crate::auth::scopes::{required_scope, Scope, CHANNEL_METHODS, CHANNEL_SCOPE_PREFIX};
 
-const UNAUTHORIZED_CODE: i64 = -32603;
+pub(crate) const UNAUTHORIZED_CODE: i64 = -32603;
 
 /// Check if the authenticated user has the required scope for a method.
 ///
@@ -32,7 +32,7 @@ pub fn check_scope(auth_info: &AuthInfo, method: &str, client_ip: &str) -> Resul
         return Ok(());
     };
 
-    let granted = has_scope(auth_info, required);
+    let granted = has_scope(auth_info, method, required);
     
     // Log the authorization decision
     log_authz_decision(method, required.as_str(), granted, client_ip);
@@ -51,11 +51,57 @@ pub fn check_scope(auth_info: &AuthInfo, method: &str, client_ip: &str) -> Resul
     }
 }
 
+/// Check if the caller may call a method restricted to one channel.
+///
+/// Principals holding `channel:<type>` scopes, such as per-channel API
+/// tokens, may only call methods whose `channel` parameter names one of
+/// those channel types. They may not call the methods acting on a channel,
+/// such as `chat.send`, without naming it. Other methods and principals
+/// without channel scopes are not restricted.
+#[allow(clippy::result_large_err)]
+pub fn check_channel(
+    auth_info: &AuthInfo,
+    method: &str,
+    params: Option<&serde_json::Value>,
+    client_ip: &str,
+) -> Result<(), RpcResponse> {
+    let Some(channel) = params.and_then(|p| p.get("channel")).and_then(|c| c.as_str()) else {
+        if auth_info.is_channel_restricted() && CHANNEL_METHODS.contains(&method) {
+            log_authz_decision(method, CHANNEL_SCOPE_PREFIX, false, client_ip);
+            return Err(RpcResponse::error(
+                None,
+                UNAUTHORIZED_CODE as i32,
+                format!(
+                    "Insufficient permissions: method '{}' requires a channel the caller may access",
+                    method
+                ),
+            ));
+        }
+        return Ok(());
+    };
+    let granted = auth_info.allows_channel(channel);
+    let required = format!("{}{}", CHANNEL_SCOPE_PREFIX, channel);
+    log_authz_decision(method, &required, granted, client_ip);
+
+    if granted {
+        Ok(())
+    } else {
+        Err(RpcResponse::error(
+            None,
+            UNAUTHORIZED_CODE as i32,
+            format!(
+                "Insufficient permissions: not allowed to access channel '{}'",
+                channel
+            ),
+        ))
+    }
+}
+
 /// Check if the auth info has the required scope.
 ///
 /// This function checks if the auth info contains the exact scope,
-/// or if it has a broader scope that implicitly grants access.
-fn has_scope(auth_info: &AuthInfo, required: &Scope) -> bool {
+/// or if it has a broader scope that implicitly grants access to the method.
+fn has_scope(auth_info: &AuthInfo, method: &str, required: &Scope) -> bool {
     // Check for exact scope match
     if auth_info.scopes.iter().any(|s| s == required.as_str()) {
         return true;
@@ -64,8 +110,8 @@ fn has_scope(auth_info: &AuthInfo, required: &Scope) -> bool {
     // Check if any scope grants broader permissions that include the required scope
     for scope_str in &auth_info.scopes {
         // Parse the scope string to check for broader access
-        if let Some(parsed) = parse_scope_string(scope_str) {
-            if parsed.allows(required) {
+        if let Some(parsed) = Scope::parse(scope_str) {
+            if parsed.allows_method(method) {
                 return true;
             }
         }
@@ -74,18 +120,6 @@ fn has_scope(auth_info: &AuthInfo, required: &Scope) -> bool {
     false
 }
 
-/// Parse a scope string into a Scope enum.
-fn parse_scope_string(scope_str: &str) -> Option<Scope> {
-    match scope_str {
-        "operator.admin" => Some(Scope::OperatorAdmin),
-        "operator.read" => Some(Scope::OperatorRead),
-        "operator.write" => Some(Scope::OperatorWrite),
-        "operator.approvals" => Some(Scope::OperatorApprovals),
-        "operator.pairing" => Some(Scope::OperatorPairing),
-        _ => None,
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -179,4 +213,40 @@ mod tests {
         let result = check_scope(&auth_info, "agent.list", "127.0.0.1");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_check_scope_chat_only() {
+        let auth_info = auth_info_with_scopes(vec!["operator.chat"]);
+        assert!(check_scope(&auth_info, "chat.send", "127.0.0.1").is_ok());
+        assert!(check_scope(&auth_info, "chat.history", "127.0.0.1").is_ok());
+        assert!(check_scope(&auth_info, "agent.list", "127.0.0.1").is_err());
+        assert!(check_scope(&auth_info, "token.create", "127.0.0.1").is_err());
+    }
+
+    #[test]
+    fn test_check_channel() {
+        let params = serde_json::json!({ "channel": "telegram", "peer_id": "42" });
+
+        // No channel scopes - every channel is allowed
+        let auth_info = auth_info_with_scopes(vec!["operator.read"]);
+        assert!(check_channel(&auth_info, "session.list", Some(&params), "127.0.0.1").is_ok());
+
+        let auth_info = auth_info_with_scopes(vec!["operator.read", "channel:slack"]);
+        let error = check_channel(&auth_info, "session.list", Some(&params), "127.0.0.1")
+            .unwrap_err();
+        assert_eq!(error.error.as_ref().unwrap().code, UNAUTHORIZED_CODE as i32);
+
+        let auth_info = auth_info_with_scopes(vec!["operator.read", "channel:telegram"]);
+        assert!(check_channel(&auth_info, "session.list", Some(&params), "127.0.0.1").is_ok());
+
+        // Methods without a channel parameter are not restricted
+        assert!(check_channel(&auth_info, "agent.list", None, "127.0.0.1").is_ok());
+
+        // Methods acting on a channel must name one
+        let params = serde_json::json!({ "text": "hi" });
+        assert!(check_channel(&auth_info, "chat.send", Some(&params), "127.0.0.1").is_err());
+        assert!(check_channel(&auth_info, "chat.send", None, "127.0.0.1").is_err());
+        let auth_info = auth_info_with_scopes(vec!["operator.chat"]);
+        assert!(check_channel(&auth_info, "chat.send", Some(&params), "127.0.0.1").is_ok());
+    }
 }
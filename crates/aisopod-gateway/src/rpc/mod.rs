@@ -3,17 +3,25 @@
 pub mod approval;
 pub mod canvas;
 pub mod chat;
+pub mod config;
 pub mod handler;
+pub mod memory;
 pub mod middleware;
 pub mod node_capabilities;
 pub mod node_pair;
+pub mod session;
+pub mod tokens;
 pub mod types;
 
 pub use handler::{default_router, MethodRouter, PlaceholderHandler, RequestContext, RpcMethod, ApprovalRequestHandler, ApprovalApproveHandler, ApprovalDenyHandler, ApprovalListHandler, CanvasInteractHandler};
 pub use approval::{PendingApproval, ApprovalStatus, ApprovalRequestParams, ApprovalStore};
+pub use config::{ConfigRpcDeps, ConfigDiffHandler, ConfigDiffParams, ConfigDiffFormat, register_config_methods};
 pub use canvas::{CanvasState, CanvasUpdateParams, CanvasAction, CanvasContent, CanvasInteractParams, CanvasInteractResult};
+pub use memory::{MemoryRpcDeps, MemorySearchHandler, MemoryDeleteHandler, MemoryUpdateHandler, MemorySearchParams, MemoryDeleteParams, MemoryUpdateParams, MemoryView, register_memory_methods};
 pub use node_capabilities::{NodeDescribeHandler, NodeInvokeHandler, NodeDescribeParams, NodeDescribeResult, NodeInvokeRequest, NodeInvokeResult, CapabilityStore};
 pub use node_pair::{PairingStore, PairRequestHandler, PairConfirmHandler, PairRevokeHandler, PairRequestParams, PairRequestResult, PairConfirmParams, PairConfirmResult, PairRevokeParams, PairRevokeResult, PendingPairing, generate_pairing_code, run_pairing_cleanup_task};
+pub use session::{SessionRpcDeps, SessionExportHandler, SessionExportParams, SessionListHandler, SessionListParams, SessionCreateHandler, SessionCreateParams, SessionSwitchHandler, SessionNameParams, SessionRenameHandler, SessionRenameParams, SessionArchiveHandler, register_session_methods};
+pub use tokens::{TokenRpcDeps, TokenCreateHandler, TokenCreateParams, TokenRevokeHandler, TokenRevokeParams, TokenListHandler, register_token_methods};
 pub use types::{error_codes, parse, RpcError, RpcRequest, RpcResponse};
 
 // Re-export DeviceCapability from client module
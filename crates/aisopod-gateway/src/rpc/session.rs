@@ -0,0 +1,548 @@
+//! Session RPC methods
+//!
+//! This module implements the `session.*` RPC methods:
+//! 1. `session.export` - Renders a session's transcript as Markdown for
+//!    sharing or as JSONL for offline analysis, including tool calls and
+//!    token usage
+//! 2. `session.list` - Lists a peer's named sessions
+//! 3. `session.create` - Starts a new named session for a peer and makes it
+//!    the active one
+//! 4. `session.switch` - Makes one of a peer's sessions the active one,
+//!    reactivating it if it was archived
+//! 5. `session.rename` - Renames one of a peer's sessions
+//! 6. `session.archive` - Archives one of a peer's sessions
+//!
+//! The named session methods identify the peer by its own session key, the
+//! one its default conversation is stored under, and its sessions by name.
+//!
+//! The methods are registered on a connection's router when a
+//! [`SessionRpcDeps`] is present in the request extensions.
+
+use crate::rpc::handler::{MethodRouter, RpcMethod};
+use crate::rpc::types;
+use crate::rpc::RequestContext;
+use aisopod_session::{export_from_store, ExportFormat, NamedSession, SessionKey, SessionStore};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::IntoParams;
+
+/// Dependencies of the session RPC methods
+#[derive(Clone)]
+pub struct SessionRpcDeps {
+    /// Store the methods read sessions from
+    pub store: Arc<SessionStore>,
+}
+
+/// Session export parameters
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SessionExportParams {
+    /// The agent that owns the session
+    pub agent_id: String,
+    /// The channel type, e.g. `telegram`
+    pub channel: String,
+    /// The bot account on the channel
+    pub account_id: String,
+    /// `dm` or `group`
+    pub peer_kind: String,
+    /// The remote user or group
+    pub peer_id: String,
+    /// `markdown` (the default) or `jsonl`
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// Session list parameters
+#[derive(Debug, Deserialize)]
+pub struct SessionListParams {
+    /// The peer's own session key
+    #[serde(flatten)]
+    pub peer: SessionKey,
+}
+
+/// Session create parameters
+#[derive(Debug, Deserialize)]
+pub struct SessionCreateParams {
+    /// The peer's own session key
+    #[serde(flatten)]
+    pub peer: SessionKey,
+    /// Name of the new session; `session-N` when omitted
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Parameters of the methods acting on one named session
+#[derive(Debug, Deserialize)]
+pub struct SessionNameParams {
+    /// The peer's own session key
+    #[serde(flatten)]
+    pub peer: SessionKey,
+    pub name: String,
+}
+
+/// Session rename parameters
+#[derive(Debug, Deserialize)]
+pub struct SessionRenameParams {
+    /// The peer's own session key
+    #[serde(flatten)]
+    pub peer: SessionKey,
+    pub name: String,
+    pub new_name: String,
+}
+
+/// Register the `session.*` handlers on `router`
+pub fn register_session_methods(router: &MethodRouter, deps: SessionRpcDeps) {
+    router.register(
+        "session.export",
+        SessionExportHandler::with_deps(deps.clone()),
+    );
+    router.register("session.list", SessionListHandler::with_deps(deps.clone()));
+    router.register(
+        "session.create",
+        SessionCreateHandler::with_deps(deps.clone()),
+    );
+    router.register(
+        "session.switch",
+        SessionSwitchHandler::with_deps(deps.clone()),
+    );
+    router.register(
+        "session.rename",
+        SessionRenameHandler::with_deps(deps.clone()),
+    );
+    router.register("session.archive", SessionArchiveHandler::with_deps(deps));
+}
+
+/// Parse required handler parameters, describing why they are invalid
+fn parse_params<T: DeserializeOwned>(params: Option<serde_json::Value>) -> Result<T, String> {
+    match params {
+        Some(p) => serde_json::from_value(p).map_err(|e| format!("Invalid parameters: {}", e)),
+        None => Err("Missing parameters".to_string()),
+    }
+}
+
+/// Build the error response for invalid parameters
+fn invalid_params(ctx: &RequestContext, message: String) -> types::RpcResponse {
+    types::RpcResponse::error(
+        Some(serde_json::json!(ctx.conn_id.clone())),
+        -32602,
+        message,
+    )
+}
+
+/// Build the error response for a failed store operation
+fn store_error(ctx: &RequestContext, error: anyhow::Error) -> types::RpcResponse {
+    types::RpcResponse::error(
+        Some(serde_json::json!(ctx.conn_id.clone())),
+        types::error_codes::INTERNAL_ERROR,
+        format!("Session store error: {}", error),
+    )
+}
+
+/// Build the response returning one named session
+fn session_response(ctx: &RequestContext, session: NamedSession) -> types::RpcResponse {
+    types::RpcResponse::success(
+        Some(serde_json::json!(ctx.conn_id.clone())),
+        serde_json::json!({ "session": session }),
+    )
+}
+
+/// Build the error response if the peer has no session called `name`
+fn missing_session(
+    ctx: &RequestContext,
+    store: &SessionStore,
+    peer: &SessionKey,
+    name: &str,
+) -> Option<types::RpcResponse> {
+    let sessions = match store.list_named_sessions(peer) {
+        Ok(sessions) => sessions,
+        Err(e) => return Some(store_error(ctx, e)),
+    };
+    let name = aisopod_session::routing::normalize(name);
+    if sessions.iter().any(|session| session.name == name) {
+        return None;
+    }
+    Some(types::RpcResponse::error(
+        Some(serde_json::json!(ctx.conn_id.clone())),
+        types::error_codes::NOT_FOUND,
+        format!("Session '{}' not found", name),
+    ))
+}
+
+/// Handler for session.export RPC method
+pub struct SessionExportHandler {
+    deps: SessionRpcDeps,
+}
+
+impl SessionExportHandler {
+    /// Create a new session export handler with dependencies
+    pub fn with_deps(deps: SessionRpcDeps) -> Self {
+        Self { deps }
+    }
+}
+
+impl RpcMethod for SessionExportHandler {
+    fn handle(
+        &self,
+        ctx: &RequestContext,
+        params: Option<serde_json::Value>,
+    ) -> types::RpcResponse {
+        let id = Some(serde_json::json!(ctx.conn_id.clone()));
+        let params: SessionExportParams = match params.map(serde_json::from_value) {
+            Some(Ok(p)) => p,
+            Some(Err(e)) => {
+                return types::RpcResponse::error(id, -32602, format!("Invalid parameters: {}", e))
+            }
+            None => return types::RpcResponse::error(id, -32602, "Missing parameters".to_string()),
+        };
+        let format = match params
+            .format
+            .as_deref()
+            .unwrap_or("markdown")
+            .parse::<ExportFormat>()
+        {
+            Ok(format) => format,
+            Err(e) => return types::RpcResponse::error(id, -32602, e.to_string()),
+        };
+
+        let key = SessionKey {
+            agent_id: params.agent_id,
+            channel: params.channel,
+            account_id: params.account_id,
+            peer_kind: params.peer_kind,
+            peer_id: params.peer_id,
+        };
+
+        match self.deps.store.get(&key) {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return types::RpcResponse::error(
+                    id,
+                    types::error_codes::NOT_FOUND,
+                    format!("Session {} not found", key.peer_id),
+                )
+            }
+            Err(e) => {
+                return types::RpcResponse::error(
+                    id,
+                    types::error_codes::INTERNAL_ERROR,
+                    format!("Session store error: {}", e),
+                )
+            }
+        }
+
+        match export_from_store(&self.deps.store, &key, format) {
+            Ok(content) => types::RpcResponse::success(
+                id,
+                serde_json::json!({
+                    "extension": format.extension(),
+                    "content": content,
+                }),
+            ),
+            Err(e) => types::RpcResponse::error(
+                id,
+                types::error_codes::INTERNAL_ERROR,
+                format!("Session store error: {}", e),
+            ),
+        }
+    }
+}
+
+/// Handler for session.list RPC method
+pub struct SessionListHandler {
+    deps: SessionRpcDeps,
+}
+
+impl SessionListHandler {
+    /// Create a new session list handler with dependencies
+    pub fn with_deps(deps: SessionRpcDeps) -> Self {
+        Self { deps }
+    }
+}
+
+impl RpcMethod for SessionListHandler {
+    fn handle(
+        &self,
+        ctx: &RequestContext,
+        params: Option<serde_json::Value>,
+    ) -> types::RpcResponse {
+        let params: SessionListParams = match parse_params(params) {
+            Ok(p) => p,
+            Err(message) => return invalid_params(ctx, message),
+        };
+
+        match self.deps.store.list_named_sessions(&params.peer) {
+            Ok(sessions) => types::RpcResponse::success(
+                Some(serde_json::json!(ctx.conn_id.clone())),
+                serde_json::json!({ "sessions": sessions }),
+            ),
+            Err(e) => store_error(ctx, e),
+        }
+    }
+}
+
+/// Handler for session.create RPC method
+pub struct SessionCreateHandler {
+    deps: SessionRpcDeps,
+}
+
+impl SessionCreateHandler {
+    /// Create a new session create handler with dependencies
+    pub fn with_deps(deps: SessionRpcDeps) -> Self {
+        Self { deps }
+    }
+}
+
+impl RpcMethod for SessionCreateHandler {
+    fn handle(
+        &self,
+        ctx: &RequestContext,
+        params: Option<serde_json::Value>,
+    ) -> types::RpcResponse {
+        let params: SessionCreateParams = match parse_params(params) {
+            Ok(p) => p,
+            Err(message) => return invalid_params(ctx, message),
+        };
+
+        // Failures other than store errors are invalid or taken names
+        match self
+            .deps
+            .store
+            .create_named_session(&params.peer, params.name.as_deref())
+        {
+            Ok(session) => session_response(ctx, session),
+            Err(e) => invalid_params(ctx, e.to_string()),
+        }
+    }
+}
+
+/// Handler for session.switch RPC method
+pub struct SessionSwitchHandler {
+    deps: SessionRpcDeps,
+}
+
+impl SessionSwitchHandler {
+    /// Create a new session switch handler with dependencies
+    pub fn with_deps(deps: SessionRpcDeps) -> Self {
+        Self { deps }
+    }
+}
+
+impl RpcMethod for SessionSwitchHandler {
+    fn handle(
+        &self,
+        ctx: &RequestContext,
+        params: Option<serde_json::Value>,
+    ) -> types::RpcResponse {
+        let params: SessionNameParams = match parse_params(params) {
+            Ok(p) => p,
+            Err(message) => return invalid_params(ctx, message),
+        };
+        let store = &self.deps.store;
+        if let Some(response) = missing_session(ctx, store, &params.peer, &params.name) {
+            return response;
+        }
+
+        match store.switch_named_session(&params.peer, &params.name) {
+            Ok(session) => session_response(ctx, session),
+            Err(e) => store_error(ctx, e),
+        }
+    }
+}
+
+/// Handler for session.rename RPC method
+pub struct SessionRenameHandler {
+    deps: SessionRpcDeps,
+}
+
+impl SessionRenameHandler {
+    /// Create a new session rename handler with dependencies
+    pub fn with_deps(deps: SessionRpcDeps) -> Self {
+        Self { deps }
+    }
+}
+
+impl RpcMethod for SessionRenameHandler {
+    fn handle(
+        &self,
+        ctx: &RequestContext,
+        params: Option<serde_json::Value>,
+    ) -> types::RpcResponse {
+        let params: SessionRenameParams = match parse_params(params) {
+            Ok(p) => p,
+            Err(message) => return invalid_params(ctx, message),
+        };
+        let store = &self.deps.store;
+        if let Some(response) = missing_session(ctx, store, &params.peer, &params.name) {
+            return response;
+        }
+
+        // The session exists, so failures are invalid or taken names
+        match store.rename_named_session(&params.peer, &params.name, &params.new_name) {
+            Ok(session) => session_response(ctx, session),
+            Err(e) => invalid_params(ctx, e.to_string()),
+        }
+    }
+}
+
+/// Handler for session.archive RPC method
+pub struct SessionArchiveHandler {
+    deps: SessionRpcDeps,
+}
+
+impl SessionArchiveHandler {
+    /// Create a new session archive handler with dependencies
+    pub fn with_deps(deps: SessionRpcDeps) -> Self {
+        Self { deps }
+    }
+}
+
+impl RpcMethod for SessionArchiveHandler {
+    fn handle(
+        &self,
+        ctx: &RequestContext,
+        params: Option<serde_json::Value>,
+    ) -> types::RpcResponse {
+        let params: SessionNameParams = match parse_params(params) {
+            Ok(p) => p,
+            Err(message) => return invalid_params(ctx, message),
+        };
+        let store = &self.deps.store;
+        if let Some(response) = missing_session(ctx, store, &params.peer, &params.name) {
+            return response;
+        }
+
+        match store.archive_named_session(&params.peer, &params.name) {
+            Ok(session) => session_response(ctx, session),
+            Err(e) => store_error(ctx, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aisopod_session::StoredMessage;
+    use std::net::SocketAddr;
+
+    fn key() -> SessionKey {
+        SessionKey {
+            agent_id: "agent-1".to_string(),
+            channel: "discord".to_string(),
+            account_id: "bot-1".to_string(),
+            peer_kind: "dm".to_string(),
+            peer_id: "user-1".to_string(),
+        }
+    }
+
+    fn create_router() -> MethodRouter {
+        let store = Arc::new(SessionStore::new_in_memory().unwrap());
+        store.get_or_create("agent-1", &key()).unwrap();
+        store
+            .append_messages(
+                "agent-1",
+                &key(),
+                &[
+                    StoredMessage::user("Hello"),
+                    StoredMessage::assistant("Hi there"),
+                ],
+            )
+            .unwrap();
+
+        let router = MethodRouter::new();
+        register_session_methods(&router, SessionRpcDeps { store });
+        router
+    }
+
+    fn call(router: &MethodRouter, params: serde_json::Value) -> types::RpcResponse {
+        call_method(router, "session.export", params)
+    }
+
+    fn call_method(
+        router: &MethodRouter,
+        method: &str,
+        params: serde_json::Value,
+    ) -> types::RpcResponse {
+        let addr: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+        let ctx = RequestContext::new("conn-1".to_string(), addr);
+        let request = types::RpcRequest {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params: Some(params),
+            id: Some(serde_json::json!(1)),
+        };
+        router.dispatch(ctx, request)
+    }
+
+    #[test]
+    fn test_session_export() {
+        let router = create_router();
+        let mut params = serde_json::to_value(key()).unwrap();
+
+        let response = call(&router, params.clone());
+        let result = response.result.unwrap();
+        assert_eq!(result["extension"], "md");
+        assert!(result["content"].as_str().unwrap().contains("Hi there"));
+
+        params["format"] = serde_json::json!("jsonl");
+        let response = call(&router, params.clone());
+        let content = response.result.unwrap()["content"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(content.lines().count(), 3);
+
+        params["format"] = serde_json::json!("pdf");
+        let response = call(&router, params.clone());
+        assert_eq!(response.error.unwrap().code, -32602);
+
+        params["format"] = serde_json::json!("markdown");
+        params["peer_id"] = serde_json::json!("user-2");
+        let response = call(&router, params);
+        assert_eq!(response.error.unwrap().code, types::error_codes::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_named_session_lifecycle() {
+        let router = create_router();
+        let mut params = serde_json::to_value(key()).unwrap();
+
+        params["name"] = serde_json::json!("Work");
+        let response = call_method(&router, "session.create", params.clone());
+        let session = &response.result.unwrap()["session"];
+        assert_eq!(session["name"], "work");
+        assert_eq!(session["key"]["peer_id"], "user-1#1");
+        assert_eq!(session["active"], true);
+
+        let response = call_method(&router, "session.create", params.clone());
+        assert_eq!(response.error.unwrap().code, -32602);
+
+        params["new_name"] = serde_json::json!("office");
+        let response = call_method(&router, "session.rename", params.clone());
+        assert_eq!(response.result.unwrap()["session"]["name"], "office");
+
+        params["name"] = serde_json::json!("office");
+        let response = call_method(&router, "session.archive", params.clone());
+        let session = &response.result.unwrap()["session"];
+        assert_eq!(session["status"], "Archived");
+        assert_eq!(session["active"], false);
+
+        params["name"] = serde_json::json!("default");
+        let response = call_method(&router, "session.switch", params.clone());
+        assert_eq!(response.result.unwrap()["session"]["message_count"], 2);
+
+        params["name"] = serde_json::json!("nowhere");
+        let response = call_method(&router, "session.switch", params.clone());
+        assert_eq!(response.error.unwrap().code, types::error_codes::NOT_FOUND);
+
+        let response = call_method(
+            &router,
+            "session.list",
+            serde_json::to_value(key()).unwrap(),
+        );
+        let sessions = response.result.unwrap()["sessions"].clone();
+        assert_eq!(sessions.as_array().unwrap().len(), 2);
+        assert_eq!(sessions[0]["active"], true);
+        assert_eq!(sessions[1]["name"], "office");
+    }
+}
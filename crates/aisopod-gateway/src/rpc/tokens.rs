@@ -0,0 +1,352 @@
+//! API token RPC methods
+//!
+//! This module implements the `token.*` RPC methods, which require the
+//! admin scope:
+//! 1. `token.create` - Mints an API token from a preset (`admin`,
+//!    `read_only` or `chat`) and/or explicit scopes, optionally restricted
+//!    to some channel types and expiring after `expires_in` seconds. The
+//!    token is only returned by this call.
+//! 2. `token.revoke` - Revokes a token by ID
+//! 3. `token.list` - Lists the tokens, without their secrets
+//!
+//! The methods are registered on a connection's router when a
+//! [`TokenRpcDeps`] is present in the request extensions.
+
+use crate::auth::{ApiTokenStore, Scope, TokenGrant, TokenPreset};
+use crate::rpc::handler::{MethodRouter, RpcMethod};
+use crate::rpc::types;
+use crate::rpc::RequestContext;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Dependencies of the token RPC methods
+#[derive(Clone)]
+pub struct TokenRpcDeps {
+    /// Store the tokens are minted in
+    pub store: Arc<ApiTokenStore>,
+}
+
+/// Token create parameters
+#[derive(Debug, Deserialize)]
+pub struct TokenCreateParams {
+    /// Name describing what the token is for
+    pub name: String,
+    /// Preset granting a common set of scopes
+    #[serde(default)]
+    pub preset: Option<TokenPreset>,
+    /// Scopes granted in addition to the preset's, e.g. `operator.write`
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// Channel types to restrict the token to; all when empty
+    #[serde(default)]
+    pub channels: Vec<String>,
+    /// Lifetime of the token in seconds; unlimited when omitted
+    #[serde(default)]
+    pub expires_in: Option<u64>,
+}
+
+/// Token revoke parameters
+#[derive(Debug, Deserialize)]
+pub struct TokenRevokeParams {
+    pub id: String,
+}
+
+/// Register the `token.*` handlers on `router`
+pub fn register_token_methods(router: &MethodRouter, deps: TokenRpcDeps) {
+    router.register("token.create", TokenCreateHandler::with_deps(deps.clone()));
+    router.register("token.revoke", TokenRevokeHandler::with_deps(deps.clone()));
+    router.register("token.list", TokenListHandler::with_deps(deps));
+}
+
+/// Parse required handler parameters, describing why they are invalid
+fn parse_params<T: DeserializeOwned>(params: Option<serde_json::Value>) -> Result<T, String> {
+    match params {
+        Some(p) => serde_json::from_value(p).map_err(|e| format!("Invalid parameters: {}", e)),
+        None => Err("Missing parameters".to_string()),
+    }
+}
+
+/// Build the error response for invalid parameters
+fn invalid_params(ctx: &RequestContext, message: String) -> types::RpcResponse {
+    types::RpcResponse::error(
+        Some(serde_json::json!(ctx.conn_id.clone())),
+        -32602,
+        message,
+    )
+}
+
+/// Build the error response for a failed store operation
+fn store_error(ctx: &RequestContext, error: anyhow::Error) -> types::RpcResponse {
+    types::RpcResponse::error(
+        Some(serde_json::json!(ctx.conn_id.clone())),
+        types::error_codes::INTERNAL_ERROR,
+        format!("Token store error: {:#}", error),
+    )
+}
+
+/// The grant requested by `params`
+fn token_grant(params: TokenCreateParams) -> Result<TokenGrant, String> {
+    let mut scopes = params.preset.map(|p| p.scopes()).unwrap_or_default();
+    for scope in &params.scopes {
+        match Scope::parse(scope) {
+            Some(scope) if !scopes.contains(&scope) => scopes.push(scope),
+            Some(_) => {}
+            None => return Err(format!("Unknown scope '{}'", scope)),
+        }
+    }
+    if scopes.is_empty() {
+        return Err("Either a preset or scopes are required".to_string());
+    }
+    Ok(TokenGrant {
+        name: params.name,
+        scopes,
+        channels: params.channels,
+        expires_in: params.expires_in.map(Duration::from_secs),
+    })
+}
+
+/// Handler for token.create RPC method
+pub struct TokenCreateHandler {
+    deps: TokenRpcDeps,
+}
+
+impl TokenCreateHandler {
+    /// Create a new token create handler with dependencies
+    pub fn with_deps(deps: TokenRpcDeps) -> Self {
+        Self { deps }
+    }
+}
+
+impl RpcMethod for TokenCreateHandler {
+    fn handle(
+        &self,
+        ctx: &RequestContext,
+        params: Option<serde_json::Value>,
+    ) -> types::RpcResponse {
+        let grant = match parse_params(params).and_then(token_grant) {
+            Ok(grant) => grant,
+            Err(message) => return invalid_params(ctx, message),
+        };
+        match self.deps.store.mint(grant) {
+            Ok((info, token)) => {
+                tracing::info!(
+                    target: "audit",
+                    event = "api_token_created",
+                    token_id = %info.id,
+                    name = %info.name,
+                    scopes = ?info.scopes,
+                    "API token created"
+                );
+                types::RpcResponse::success(
+                    Some(serde_json::json!(ctx.conn_id.clone())),
+                    serde_json::json!({ "token": token, "info": info }),
+                )
+            }
+            Err(e) => store_error(ctx, e),
+        }
+    }
+}
+
+/// Handler for token.revoke RPC method
+pub struct TokenRevokeHandler {
+    deps: TokenRpcDeps,
+}
+
+impl TokenRevokeHandler {
+    /// Create a new token revoke handler with dependencies
+    pub fn with_deps(deps: TokenRpcDeps) -> Self {
+        Self { deps }
+    }
+}
+
+impl RpcMethod for TokenRevokeHandler {
+    fn handle(
+        &self,
+        ctx: &RequestContext,
+        params: Option<serde_json::Value>,
+    ) -> types::RpcResponse {
+        let params: TokenRevokeParams = match parse_params(params) {
+            Ok(params) => params,
+            Err(message) => return invalid_params(ctx, message),
+        };
+        match self.deps.store.revoke(&params.id) {
+            Ok(true) => {
+                tracing::info!(
+                    target: "audit",
+                    event = "api_token_revoked",
+                    token_id = %params.id,
+                    "API token revoked"
+                );
+                types::RpcResponse::success(
+                    Some(serde_json::json!(ctx.conn_id.clone())),
+                    serde_json::json!({ "revoked": params.id }),
+                )
+            }
+            Ok(false) => types::RpcResponse::error(
+                Some(serde_json::json!(ctx.conn_id.clone())),
+                types::error_codes::NOT_FOUND,
+                format!("Token '{}' not found", params.id),
+            ),
+            Err(e) => store_error(ctx, e),
+        }
+    }
+}
+
+/// Handler for token.list RPC method
+pub struct TokenListHandler {
+    deps: TokenRpcDeps,
+}
+
+impl TokenListHandler {
+    /// Create a new token list handler with dependencies
+    pub fn with_deps(deps: TokenRpcDeps) -> Self {
+        Self { deps }
+    }
+}
+
+impl RpcMethod for TokenListHandler {
+    fn handle(
+        &self,
+        ctx: &RequestContext,
+        _params: Option<serde_json::Value>,
+    ) -> types::RpcResponse {
+        types::RpcResponse::success(
+            Some(serde_json::json!(ctx.conn_id.clone())),
+            serde_json::json!({ "tokens": self.deps.store.list() }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::AuthInfo;
+    use crate::rpc::types::RpcRequest;
+    use serde_json::json;
+
+    fn router() -> (MethodRouter, Arc<ApiTokenStore>) {
+        let store = Arc::new(ApiTokenStore::in_memory());
+        let router = MethodRouter::new();
+        register_token_methods(
+            &router,
+            TokenRpcDeps {
+                store: store.clone(),
+            },
+        );
+        (router, store)
+    }
+
+    fn call(
+        router: &MethodRouter,
+        scopes: &[&str],
+        method: &str,
+        params: serde_json::Value,
+    ) -> types::RpcResponse {
+        let auth_info = AuthInfo {
+            role: "operator".to_string(),
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+        };
+        let ctx = RequestContext::with_auth(
+            "conn-1".to_string(),
+            "127.0.0.1:0".parse().unwrap(),
+            auth_info,
+        );
+        let request = RpcRequest {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params: Some(params),
+            id: Some(json!(1)),
+        };
+        router.dispatch(ctx, request)
+    }
+
+    #[test]
+    fn test_create_list_and_revoke() {
+        let (router, store) = router();
+        let response = call(
+            &router,
+            &["operator.admin"],
+            "token.create",
+            json!({
+                "name": "support bot",
+                "preset": "chat",
+                "channels": ["telegram"],
+                "expires_in": 3600
+            }),
+        );
+        let result = response.result.expect("token created");
+        let token = result["token"].as_str().unwrap();
+        let id = result["info"]["id"].as_str().unwrap().to_string();
+        assert_eq!(result["info"]["scopes"], json!(["operator.chat"]));
+        assert!(result["info"]["expires_at"].is_string());
+
+        let auth_info = store.validate(token).unwrap();
+        assert_eq!(auth_info.scopes, ["operator.chat", "channel:telegram"]);
+
+        let response = call(&router, &["operator.admin"], "token.list", json!({}));
+        let tokens = &response.result.unwrap()["tokens"];
+        assert_eq!(tokens.as_array().unwrap().len(), 1);
+        assert!(tokens[0].get("secret_hash").is_none());
+
+        let response = call(
+            &router,
+            &["operator.admin"],
+            "token.revoke",
+            json!({ "id": id }),
+        );
+        assert!(response.error.is_none());
+        assert!(store.validate(token).is_none());
+
+        let response = call(
+            &router,
+            &["operator.admin"],
+            "token.revoke",
+            json!({ "id": "missing" }),
+        );
+        assert_eq!(response.error.unwrap().code, types::error_codes::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_create_validates_scopes() {
+        let (router, _) = router();
+        for params in [
+            json!({ "name": "empty" }),
+            json!({ "name": "bogus", "scopes": ["operator.root"] }),
+        ] {
+            let response = call(&router, &["operator.admin"], "token.create", params);
+            assert_eq!(response.error.unwrap().code, -32602);
+        }
+
+        let response = call(
+            &router,
+            &["operator.admin"],
+            "token.create",
+            json!({ "name": "ci", "preset": "read_only", "scopes": ["operator.write", "operator.read"] }),
+        );
+        assert_eq!(
+            response.result.unwrap()["info"]["scopes"],
+            json!(["operator.read", "operator.write"])
+        );
+    }
+
+    #[test]
+    fn test_token_methods_require_admin() {
+        let (router, store) = router();
+        for scopes in [
+            &["operator.write"][..],
+            &["operator.chat"],
+            &["operator.read"],
+        ] {
+            let response = call(
+                &router,
+                scopes,
+                "token.create",
+                json!({ "name": "escalate", "preset": "admin" }),
+            );
+            assert!(response.error.is_some());
+        }
+        assert!(store.list().is_empty());
+    }
+}
@@ -76,6 +76,17 @@ impl RpcResponse {
             id,
         }
     }
+
+    /// Create an error response for a rate limited request, whose data tells
+    /// the client after how many seconds to retry
+    pub fn rate_limited(id: Option<serde_json::Value>, retry_after: std::time::Duration) -> Self {
+        Self::error_with_data(
+            id,
+            error_codes::RATE_LIMITED,
+            "Too many requests",
+            Some(serde_json::json!({ "retry_after": retry_after.as_secs() })),
+        )
+    }
 }
 
 /// Standard JSON-RPC 2.0 error codes
@@ -94,6 +105,13 @@ pub mod error_codes {
     pub const METHOD_NOT_ALLOWED: i32 = -32005;
     /// Internal error - internal gateway error
     pub const INTERNAL_ERROR: i32 = -32006;
+    /// Rate limited - too many requests, `data.retry_after` holds the seconds
+    /// to wait
+    pub const RATE_LIMITED: i32 = -32007;
+    /// Payload too large - request exceeds the size limits
+    pub const PAYLOAD_TOO_LARGE: i32 = -32008;
+    /// Request timeout - the client was too slow sending the request
+    pub const REQUEST_TIMEOUT: i32 = -32009;
 }
 
 /// Parse a raw JSON-RPC request string into an RpcRequest
@@ -14,6 +14,7 @@ use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::net::TcpListener;
 use tokio::signal;
+use tokio::sync::watch;
 use tower::layer::util::Identity;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer};
@@ -21,21 +22,43 @@ use tracing::Level;
 use tracing::{info, warn};
 
 use crate::broadcast::Broadcaster;
+use crate::channels::GatewayChannels;
 use crate::client::ClientRegistry;
+use crate::rpc::memory::MemoryRpcDeps;
+use crate::rpc::session::SessionRpcDeps;
+use crate::session_store::open_session_stores;
+use crate::rpc::tokens::TokenRpcDeps;
+use crate::rpc::approval::ApprovalStore;
+use crate::cluster::{self, join_configured};
+use crate::rpc::config::ConfigRpcDeps;
 use crate::rpc::node_pair::{PairingStore, run_pairing_cleanup_task};
 use crate::middleware::{
-    auth_middleware, rate_limit_middleware, AuthConfigData, RateLimitConfig, RateLimiter,
+    auth_middleware, rate_limit_middleware, request_limits_middleware, AuthConfigData,
+    RateLimitConfig, RateLimiter,
 };
 use crate::routes::{api_routes, device_token_routes, GatewayStatusState, rpc_routes};
-use crate::static_files::{get_cache_control, get_content_type, StaticFileState};
-use crate::tls::{is_tls_enabled, load_tls_config};
+use crate::openapi::openapi_routes;
+use crate::rest::rest_routes;
+use crate::sse::sse_routes;
+use crate::static_files::{admin_routes, get_cache_control, get_content_type, StaticFileState};
+use crate::tls::{is_tls_enabled, load_mtls_config, load_tls_config};
 use crate::ws::ws_routes;
-use aisopod_config::types::{AisopodConfig, AuthConfig, GatewayConfig};
+use aisopod_config::types::{
+    AgentsConfig, AisopodConfig, AuthConfig, ClusterConfig, GatewayConfig, RetentionConfig,
+};
+use aisopod_config::ConfigReloader;
+use aisopod_session::{run_pruning_task, RetentionPolicy, SessionBackend};
 use rust_embed::RustEmbed;
 
-use crate::auth::DeviceTokenManager;
+use crate::auth::{ApiTokenStore, DeviceTokenManager};
 use crate::middleware::RequestSizeLimits;
 
+/// How long a clustered node's lease on a session lasts unless renewed
+const SESSION_LEASE_TTL: Duration = Duration::from_secs(30);
+
+/// How often the job scheduler runs the jobs that came due
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Embedded static assets from the web UI dist directory
 #[derive(RustEmbed)]
 #[folder = "../../web-ui/dist"]
@@ -113,6 +136,107 @@ async fn static_file_handler(State(state): State<StaticFileState>, uri: Uri) ->
 
 /// Run the Axum HTTP server with the given configuration
 pub async fn run_with_config(config: &AisopodConfig) -> Result<()> {
+    run_with_status(config, Arc::new(GatewayStatusState::default())).await
+}
+
+/// Run the Axum HTTP server, sending messages through the given channels
+///
+/// The replies of scheduled agent runs are delivered to their targets
+/// through these channels, and approvals are asked for through them as
+/// configured under `tools.approval`. To route the operators' replies,
+/// set up the approvals with [`GatewayChannels::with_approvals`] and pass
+/// their handler to the message router.
+pub async fn run_with_channels(config: &AisopodConfig, channels: GatewayChannels) -> Result<()> {
+    let status_state = Arc::new(GatewayStatusState::default());
+    run_with_configured_stores(config, status_state, None, None, channels).await
+}
+
+/// Run the Axum HTTP server, serving `/status` from the given state
+///
+/// The caller keeps a handle to the state to update the agent, channel,
+/// session, and plugin health figures while the server runs.
+pub async fn run_with_status(
+    config: &AisopodConfig,
+    status_state: Arc<GatewayStatusState>,
+) -> Result<()> {
+    run_with_memory(config, status_state, None).await
+}
+
+/// Run the Axum HTTP server, also serving the `memory.*` RPC methods
+///
+/// The memory methods are only registered on WebSocket connections when
+/// `memory` is given. Sessions are kept in the store configured under
+/// `session.storage`.
+pub async fn run_with_memory(
+    config: &AisopodConfig,
+    status_state: Arc<GatewayStatusState>,
+    memory: Option<MemoryRpcDeps>,
+) -> Result<()> {
+    run_with_configured_stores(config, status_state, memory, None, GatewayChannels::default())
+        .await
+}
+
+/// Run the Axum HTTP server, applying the configurations published on
+/// `updates` to the running agents
+///
+/// Typically fed by a [`ConfigWatcher`](aisopod_config::ConfigWatcher);
+/// changes to the `agents`, `models` and `session` sections apply to the
+/// next agent runs.
+pub async fn run_with_updates(
+    config: &AisopodConfig,
+    updates: watch::Receiver<AisopodConfig>,
+) -> Result<()> {
+    let status_state = Arc::new(GatewayStatusState::default());
+    run_with_configured_stores(
+        config,
+        status_state,
+        None,
+        Some(updates),
+        GatewayChannels::default(),
+    )
+    .await
+}
+
+/// Open the session store configured under `session.storage` and run the
+/// server with it
+async fn run_with_configured_stores(
+    config: &AisopodConfig,
+    status_state: Arc<GatewayStatusState>,
+    memory: Option<MemoryRpcDeps>,
+    updates: Option<watch::Receiver<AisopodConfig>>,
+    channels: GatewayChannels,
+) -> Result<()> {
+    let stores = open_session_stores(&config.session).await?;
+    let sessions = stores.sqlite.map(|store| SessionRpcDeps { store });
+    run_with_stores(
+        config,
+        status_state,
+        memory,
+        sessions,
+        Some(stores.backend),
+        updates,
+        channels,
+    )
+    .await
+}
+
+/// Run the Axum HTTP server, also serving the `memory.*` and
+/// `session.export` RPC methods
+///
+/// Each group of methods is only registered on WebSocket connections when
+/// its dependencies are given. Clustered nodes take session leases in
+/// `shared_sessions`, or in the store of `sessions` when it is not given.
+/// Configurations published on `updates` apply to the running agents.
+/// Messages are sent through `channels`.
+pub async fn run_with_stores(
+    config: &AisopodConfig,
+    status_state: Arc<GatewayStatusState>,
+    memory: Option<MemoryRpcDeps>,
+    sessions: Option<SessionRpcDeps>,
+    shared_sessions: Option<Arc<dyn SessionBackend>>,
+    updates: Option<watch::Receiver<AisopodConfig>>,
+    channels: GatewayChannels,
+) -> Result<()> {
     let gateway_config = &config.gateway;
     let auth_config = &config.auth;
 
@@ -168,11 +292,19 @@ pub async fn run_with_config(config: &AisopodConfig) -> Result<()> {
     let rate_limit_config = RateLimitConfig::new(
         config_rate_limit.max_requests,
         Duration::from_secs(config_rate_limit.window),
-    );
-    let rate_limiter = Arc::new(RateLimiter::new(rate_limit_config.clone()));
+    )
+    .with_burst(config_rate_limit.burst.unwrap_or(config_rate_limit.max_requests));
+    let mut rate_limiter = RateLimiter::new(rate_limit_config.clone());
+    if let Some(per_token) = &config_rate_limit.per_token {
+        rate_limiter = rate_limiter.with_token_limits(
+            RateLimitConfig::new(per_token.max_requests, Duration::from_secs(per_token.window))
+                .with_burst(per_token.burst.unwrap_or(per_token.max_requests)),
+        );
+    }
+    let rate_limiter = Arc::new(rate_limiter);
     eprintln!(
-        "Created rate limiter with max_requests = {}, window = {:?}",
-        rate_limit_config.max_requests, rate_limit_config.window
+        "Created rate limiter with max_requests = {}, window = {:?}, burst = {}",
+        rate_limit_config.max_requests, rate_limit_config.window, rate_limit_config.burst
     );
 
     // Security: Setup request size limits
@@ -183,6 +315,9 @@ pub async fn run_with_config(config: &AisopodConfig) -> Result<()> {
         size_limits.max_body_size,
         size_limits.max_headers_size,
         size_limits.max_headers_count,
+    )
+    .with_body_timeout(
+        (size_limits.body_timeout > 0).then(|| Duration::from_secs(size_limits.body_timeout)),
     );
     eprintln!(
         "Created request size limits: max_body={} bytes, max_headers={} bytes, max_count={}",
@@ -197,20 +332,122 @@ pub async fn run_with_config(config: &AisopodConfig) -> Result<()> {
         cleanup_limiter.cleanup_loop().await;
     });
 
+    // Token stores are kept per bind address
+    let config_dir = gateway_config
+        .bind
+        .address
+        .to_string()
+        .replace([':', '/'], "-");
+    let store_suffix = config_dir.trim_start_matches('[').trim_end_matches(']');
+
+    // Open the store of API tokens minted over RPC
+    let api_tokens = Arc::new(ApiTokenStore::open(std::path::PathBuf::from(format!(
+        "api-tokens-{}.toml",
+        store_suffix
+    )))?);
+    let token_deps = Arc::new(TokenRpcDeps {
+        store: api_tokens.clone(),
+    });
+
     // Build the auth config data
-    let auth_config_data = Arc::new(AuthConfigData::new(auth_config.clone()));
+    let auth_config_data =
+        Arc::new(AuthConfigData::new(auth_config.clone()).with_api_tokens(api_tokens));
     // Clone for the secrets masking middleware
     let auth_config_data_for_secrets = auth_config_data.clone();
 
     // Create the client registry
     let client_registry = Arc::new(ClientRegistry::new());
 
-    // Create the broadcast channel for gateway events
-    let broadcaster = Arc::new(Broadcaster::new(128));
+    // Create the broadcast channel for gateway events and the store of
+    // pending approvals, relayed to the other nodes when clustering
+    let cluster_config = ClusterConfig {
+        node_id: cluster::node_id(&gateway_config.cluster),
+        ..gateway_config.cluster.clone()
+    };
+    let (broadcaster, approval_store) = join_configured(
+        &cluster_config,
+        Broadcaster::new(128),
+        ApprovalStore::new(),
+    )
+    .await?;
+    let broadcaster = Arc::new(broadcaster);
+    let approval_store = Arc::new(approval_store);
 
     // Create the pairing store for managing pending pairing requests
     let pairing_store = Arc::new(PairingStore::new());
 
+    // Let connections preview changes against the running configuration
+    let config_deps = Arc::new(ConfigRpcDeps {
+        config: Arc::new(config.clone()),
+    });
+
+    // Share the memory store dependencies between connections
+    let memory = memory.map(Arc::new);
+
+    // Clustered nodes take the lease of a session in the shared session
+    // store around each agent run
+    let session_leases = shared_sessions
+        .or_else(|| {
+            sessions
+                .as_ref()
+                .map(|sessions| sessions.store.clone() as Arc<dyn SessionBackend>)
+        })
+        .filter(|_| cluster_config.enabled)
+        .map(|backend| aisopod_agent::SessionLeases {
+            backend,
+            owner: cluster_config.node_id.clone(),
+            ttl: SESSION_LEASE_TTL,
+        });
+
+    // Jobs are kept in the database at `agents.scheduler_path`, or in
+    // memory when it is not set
+    let jobs = Arc::new(if config.agents.scheduler_path.is_empty() {
+        aisopod_tools::SqliteJobScheduler::in_memory(Arc::new(aisopod_tools::NoOpJobRunner))?
+    } else {
+        aisopod_tools::SqliteJobScheduler::open(
+            &config.agents.scheduler_path,
+            Arc::new(aisopod_tools::NoOpJobRunner),
+        )?
+    });
+
+    // Tool operations needing approval are asked for in the operator
+    // conversation of `tools.approval`, unless the channels already ask
+    // for them
+    let channels = match channels.approval_handler() {
+        Some(_) => channels,
+        None => channels.with_approvals(&config.tools.approval),
+    };
+
+    // The tools of the configured MCP servers are registered next to the
+    // built-in ones; the clients keep their connections open until the
+    // server stops. The message tool sends through the channels.
+    let (tools, _mcp_clients) =
+        crate::ws::create_tool_registry(&config, jobs.clone(), &channels).await;
+
+    // One agent runner serves every connection and request, keeping its
+    // sessions in the configured store and scheduling the jobs of the cron
+    // tool in the job database
+    let agent_runner = crate::ws::create_agent_runner_with_tools(
+        Arc::new(config.clone()),
+        Arc::new(tools),
+        sessions.as_ref().map(|sessions| sessions.store.clone()),
+        session_leases.clone(),
+    );
+
+    // The configured schedules run their agents headlessly with that runner,
+    // delivering the replies through the channels
+    start_job_scheduler(&jobs, &config.agents, agent_runner.clone(), channels.sender()).await?;
+
+    // Configuration changes apply to the runs started after them
+    if let Some(updates) = updates {
+        ConfigReloader::new()
+            .with_applier(agent_runner.clone())
+            .spawn(updates);
+    }
+
+    // Share the session store dependencies between connections
+    let sessions = sessions.map(Arc::new);
+
     // Spawn the pairing cleanup task
     let pairing_cleanup_interval = Duration::from_secs(gateway_config.pairing_cleanup_interval);
     let pairing_store_for_cleanup = pairing_store.clone();
@@ -218,21 +455,27 @@ pub async fn run_with_config(config: &AisopodConfig) -> Result<()> {
         run_pairing_cleanup_task(pairing_store_for_cleanup, pairing_cleanup_interval).await;
     });
 
+    // Spawn the session pruning task when retention limits are configured
+    if let Some(sessions) = &sessions {
+        let retention = &config.session.retention;
+        let policy = retention_policy(retention);
+        if !policy.is_unbounded() {
+            let store = sessions.store.clone();
+            let interval = Duration::from_secs(retention.prune_interval);
+            tokio::spawn(async move {
+                run_pruning_task(store, policy, interval).await;
+            });
+        }
+    }
+
     // Setup device token manager with storage in the config directory
-    let config_dir = gateway_config
-        .bind
-        .address
-        .to_string()
-        .replace([':', '/'], "-");
-    let token_store_path = std::path::PathBuf::from(format!(
-        "device-tokens-{}.toml",
-        config_dir.trim_start_matches('[').trim_end_matches(']')
-    ));
+    let token_store_path = std::path::PathBuf::from(format!("device-tokens-{}.toml", store_suffix));
     let device_token_manager = Arc::new(Mutex::new(DeviceTokenManager::new(token_store_path)));
 
     // Setup static file serving state
     let web_ui_config = gateway_config.web_ui.clone();
     let static_state = StaticFileState::new(web_ui_config.clone());
+    let admin_state = static_state.clone();
 
     // Build the router with the /health endpoint, API routes, and WebSocket route
     // Use the configured handshake timeout or default to 5 seconds
@@ -312,34 +555,8 @@ pub async fn run_with_config(config: &AisopodConfig) -> Result<()> {
             },
         ))
         .layer(axum::middleware::from_fn(rate_limit_middleware))
-        // Request body size limit middleware
-        .layer(axum::middleware::from_fn(
-            |mut req: axum::extract::Request, next: axum::middleware::Next| {
-                async move {
-                    // Get size limits from extensions
-                    if let Some(size_limits) = req.extensions().get::<RequestSizeLimits>().cloned() {
-                        // Get content length from headers
-                        if let Some(content_length) = req.headers().get("content-length") {
-                            if let Ok(content_length_str) = content_length.to_str() {
-                                if let Ok(content_length_bytes) = content_length_str.parse::<usize>() {
-                                    if let Err(e) = size_limits.check_body_size(content_length_bytes) {
-                                        warn!("Request body size limit exceeded: {}", e);
-                                        return (
-                                            StatusCode::PAYLOAD_TOO_LARGE,
-                                            axum::Json(serde_json::json!({
-                                                "error": "payload_too_large",
-                                                "message": e.to_string()
-                                            }))
-                                        ).into_response();
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    next.run(req).await
-                }
-            },
-        ))
+        // Request size limits and slow client protection
+        .layer(axum::middleware::from_fn(request_limits_middleware))
         // HTTPS enforcement middleware
         .layer(axum::middleware::from_fn(
             move |mut req: axum::extract::Request, next: axum::middleware::Next| {
@@ -381,6 +598,58 @@ pub async fn run_with_config(config: &AisopodConfig) -> Result<()> {
                 }
             },
         ))
+        // Memory store middleware
+        .layer(axum::middleware::from_fn(
+            move |mut req: axum::extract::Request, next: axum::middleware::Next| {
+                let memory = memory.clone();
+                async move {
+                    if let Some(memory) = memory {
+                        req.extensions_mut().insert(memory);
+                    }
+                    next.run(req).await
+                }
+            },
+        ))
+        // Session store, session leases and agent runner middleware
+        .layer(axum::middleware::from_fn(
+            move |mut req: axum::extract::Request, next: axum::middleware::Next| {
+                let sessions = sessions.clone();
+                let session_leases = session_leases.clone();
+                let agent_runner = agent_runner.clone();
+                async move {
+                    if let Some(sessions) = sessions {
+                        req.extensions_mut().insert(sessions);
+                    }
+                    if let Some(session_leases) = session_leases {
+                        req.extensions_mut().insert(session_leases);
+                    }
+                    req.extensions_mut().insert(agent_runner);
+                    next.run(req).await
+                }
+            },
+        ))
+        // API token store middleware
+        .layer(axum::middleware::from_fn(
+            move |mut req: axum::extract::Request, next: axum::middleware::Next| {
+                let token_deps = token_deps.clone();
+                async move {
+                    req.extensions_mut().insert(token_deps);
+                    next.run(req).await
+                }
+            },
+        ))
+        // Approval store and running config middleware
+        .layer(axum::middleware::from_fn(
+            move |mut req: axum::extract::Request, next: axum::middleware::Next| {
+                let approval_store = approval_store.clone();
+                let config_deps = config_deps.clone();
+                async move {
+                    req.extensions_mut().insert(approval_store);
+                    req.extensions_mut().insert(config_deps);
+                    next.run(req).await
+                }
+            },
+        ))
         // Auth config data MUST be injected BEFORE auth_middleware runs
         // By adding this layer BEFORE auth_middleware in the ServiceBuilder,
         // it runs BEFORE auth_middleware in the request flow (outer layers run first)
@@ -431,17 +700,18 @@ pub async fn run_with_config(config: &AisopodConfig) -> Result<()> {
         ));
 
     eprintln!("=== MIDDLEWARE STACK BUILT === layers: 7");
-    
-    // Create status state with initial counts (will be updated by agents/channels)
-    let status_state = Arc::new(GatewayStatusState::new(0, 0, 0));
-    
+
     // Build the main app - order matters: static_router first (with 404 for API paths),
     // then API routes, then WebSocket routes, then device token routes, then RPC routes
     let app = Router::new()
         .route("/health", get(health))
         .nest_service("/", static_router)
+        .merge(admin_routes(admin_state))
         .merge(device_token_routes())
         .merge(api_routes(Some(status_state.clone())))
+        .merge(rest_routes(status_state.clone()))
+        .merge(sse_routes())
+        .merge(openapi_routes())
         .merge(ws_routes(handshake_timeout))
         .merge(rpc_routes())
         .layer(middleware_stack);
@@ -493,11 +763,13 @@ pub async fn run_with_config(config: &AisopodConfig) -> Result<()> {
 
     if tls_enabled {
         // Start the server with TLS using axum-server
-        let tls_config = load_tls_config(
-            std::path::Path::new(&gateway_config.tls.cert_path),
-            std::path::Path::new(&gateway_config.tls.key_path),
-        )
-        .await?;
+        let cert_path = std::path::Path::new(&gateway_config.tls.cert_path);
+        let key_path = std::path::Path::new(&gateway_config.tls.key_path);
+        let tls_config = if gateway_config.tls.client_auth.enabled {
+            load_mtls_config(cert_path, key_path, &gateway_config.tls.client_auth).await?
+        } else {
+            load_tls_config(cert_path, key_path).await?
+        };
 
         // Note: axum-server bind_rustls doesn't support with_graceful_shutdown
         // The server will shut down when the signal is received via Ctrl+C
@@ -515,6 +787,43 @@ pub async fn run_with_config(config: &AisopodConfig) -> Result<()> {
     Ok(())
 }
 
+/// Build the session retention policy from its configuration
+fn retention_policy(config: &RetentionConfig) -> RetentionPolicy {
+    RetentionPolicy {
+        // Ages too large to represent are treated as unlimited
+        max_age: config
+            .max_age_days
+            .and_then(|days| chrono::Duration::try_days(i64::try_from(days).ok()?)),
+        max_messages: config.max_messages,
+        max_sessions_per_peer: config.max_sessions_per_peer,
+    }
+}
+
+/// Start running the jobs of `jobs`, registering the configured schedules
+///
+/// Tool invocations go through the tool registry of `runner`, in the tool
+/// context of the agent that scheduled them; other jobs run agents
+/// headlessly with `runner`. Schedules with a delivery target send their
+/// replies with `sender`.
+pub async fn start_job_scheduler(
+    jobs: &aisopod_tools::SqliteJobScheduler,
+    config: &AgentsConfig,
+    runner: Arc<aisopod_agent::AgentRunner>,
+    sender: Arc<dyn aisopod_tools::MessageSender>,
+) -> Result<Arc<aisopod_tools::SqliteJobScheduler>> {
+    let agent_jobs = Arc::new(
+        aisopod_agent::AgentJobRunner::new(runner.clone())
+            .with_schedules(config.schedules.clone())
+            .with_sender(sender),
+    );
+    let job_runner = aisopod_tools::ToolJobRunner::new(runner.tools().clone(), runner.clone())
+        .with_fallback(agent_jobs);
+    let scheduler = Arc::new(jobs.with_runner(Arc::new(job_runner)));
+    aisopod_agent::register_schedules(scheduler.as_ref(), &config.schedules).await?;
+    scheduler.clone().start(SCHEDULER_INTERVAL);
+    Ok(scheduler)
+}
+
 /// Run the Axum HTTP server with the given configuration (backward compatible)
 pub async fn run(config: &GatewayConfig) -> Result<()> {
     let aisopod_config = AisopodConfig {
@@ -548,8 +857,15 @@ pub async fn build_app(auth_config: AuthConfig) -> Router {
         cleanup_limiter.cleanup_loop().await;
     });
     
+    // Create an in-memory API token store
+    let api_tokens = Arc::new(ApiTokenStore::in_memory());
+    let token_deps = Arc::new(TokenRpcDeps {
+        store: api_tokens.clone(),
+    });
+
     // Create auth config data
-    let auth_config_data = Arc::new(AuthConfigData::new(config.auth.clone()));
+    let auth_config_data =
+        Arc::new(AuthConfigData::new(config.auth.clone()).with_api_tokens(api_tokens));
     // Clone for the secrets masking middleware
     let auth_config_data_for_secrets = auth_config_data.clone();
     
@@ -558,6 +874,14 @@ pub async fn build_app(auth_config: AuthConfig) -> Router {
     
     // Create broadcaster
     let broadcaster = Arc::new(Broadcaster::new(128));
+
+    // Share pending approvals between connections
+    let approval_store = Arc::new(ApprovalStore::new());
+
+    // Serve config.diff against the test config
+    let config_deps = Arc::new(ConfigRpcDeps {
+        config: Arc::new(config.clone()),
+    });
     
     // Setup device token manager with default storage path
     let token_store_path = std::path::PathBuf::from("device-tokens.toml");
@@ -566,6 +890,7 @@ pub async fn build_app(auth_config: AuthConfig) -> Router {
     // Setup static file serving state
     let web_ui_config = gateway_config.web_ui.clone();
     let static_state = StaticFileState::new(web_ui_config.clone());
+    let admin_state = static_state.clone();
     
     // Create status state
     let status_state = Arc::new(GatewayStatusState::new(0, 0, 0));
@@ -621,6 +946,27 @@ pub async fn build_app(auth_config: AuthConfig) -> Router {
                 }
             },
         ))
+        .layer(axum::middleware::from_fn(
+            move |mut req: axum::extract::Request, next: axum::middleware::Next| {
+                let token_deps = token_deps.clone();
+                async move {
+                    req.extensions_mut().insert(token_deps);
+                    next.run(req).await
+                }
+            },
+        ))
+        // Approval store and running config middleware
+        .layer(axum::middleware::from_fn(
+            move |mut req: axum::extract::Request, next: axum::middleware::Next| {
+                let approval_store = approval_store.clone();
+                let config_deps = config_deps.clone();
+                async move {
+                    req.extensions_mut().insert(approval_store);
+                    req.extensions_mut().insert(config_deps);
+                    next.run(req).await
+                }
+            },
+        ))
         // Auth config data MUST be injected BEFORE auth_middleware runs
         // By adding this layer BEFORE auth_middleware in the ServiceBuilder,
         // it runs BEFORE auth_middleware in the request flow (outer layers run first)
@@ -671,8 +1017,12 @@ pub async fn build_app(auth_config: AuthConfig) -> Router {
     let app = Router::new()
         .route("/health", get(health))
         .nest_service("/", static_router)
+        .merge(admin_routes(admin_state))
         .merge(device_token_routes())
-        .merge(api_routes(Some(status_state)))
+        .merge(api_routes(Some(status_state.clone())))
+        .merge(rest_routes(status_state))
+        .merge(sse_routes())
+        .merge(openapi_routes())
         .merge(ws_routes(None))
         .merge(rpc_routes())
         .layer(middleware_stack);
@@ -0,0 +1,171 @@
+//! Session storage of the gateway
+//!
+//! The gateway keeps sessions in the store configured under
+//! `session.storage`: a SQLite database, in memory unless a file is given,
+//! or, behind the `postgres` feature, a Postgres database. Either can be
+//! fronted by a Redis cache of active sessions, behind the `cluster`
+//! feature. Messages are encrypted with the configured key in the store
+//! and in the cache alike. Clustered nodes take their session leases in this store, so
+//! all nodes of a cluster must be configured with the same one.
+
+use anyhow::{anyhow, Result};
+use std::path::Path;
+use std::sync::Arc;
+
+use aisopod_config::types::{EncryptionConfig, SessionBackendKind, SessionConfig};
+use aisopod_session::{MessageCipher, SessionBackend, SessionStore};
+
+/// The session stores opened from the configuration
+#[derive(Clone)]
+pub struct SessionStores {
+    /// The configured store, including its cache
+    pub backend: Arc<dyn SessionBackend>,
+    /// The SQLite store when that is the backend, which also serves the
+    /// session RPC methods
+    pub sqlite: Option<Arc<SessionStore>>,
+}
+
+/// Build the message cipher from the encryption config, if a key is configured
+pub fn session_cipher(config: &EncryptionConfig) -> Result<Option<MessageCipher>> {
+    if let Some(key) = &config.key {
+        return MessageCipher::from_base64(key.expose()).map(Some);
+    }
+    match &config.keyring {
+        #[cfg(feature = "keyring")]
+        Some(service) => MessageCipher::from_keyring(service).map(Some),
+        #[cfg(not(feature = "keyring"))]
+        Some(_) => Err(anyhow!(
+            "session.encryption.keyring requires aisopod to be built with the 'keyring' feature"
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Open the session store described by `config`
+pub async fn open_session_stores(config: &SessionConfig) -> Result<SessionStores> {
+    let storage = &config.storage;
+    let cipher = session_cipher(&config.encryption)?;
+
+    let (backend, sqlite): (Arc<dyn SessionBackend>, _) = match storage.backend {
+        SessionBackendKind::Sqlite => {
+            let store = if storage.path.is_empty() {
+                SessionStore::new_in_memory()?
+            } else {
+                SessionStore::new(Path::new(&storage.path))?
+            };
+            let store = Arc::new(match cipher.clone() {
+                Some(cipher) => store.with_cipher(cipher),
+                None => store,
+            });
+            (store.clone(), Some(store))
+        }
+        SessionBackendKind::Postgres => (
+            open_postgres(storage.url.expose(), cipher.clone()).await?,
+            None,
+        ),
+    };
+
+    let backend = if storage.cache_url.expose().is_empty() {
+        backend
+    } else {
+        with_cache(backend, storage.cache_url.expose(), cipher).await?
+    };
+    Ok(SessionStores { backend, sqlite })
+}
+
+#[cfg(feature = "postgres")]
+async fn open_postgres(
+    url: &str,
+    cipher: Option<MessageCipher>,
+) -> Result<Arc<dyn SessionBackend>> {
+    use aisopod_session::{PostgresSessionConfig, PostgresSessionStore};
+
+    let store = PostgresSessionStore::connect(PostgresSessionConfig::new(url)).await?;
+    Ok(Arc::new(match cipher {
+        Some(cipher) => store.with_cipher(cipher),
+        None => store,
+    }))
+}
+
+#[cfg(not(feature = "postgres"))]
+async fn open_postgres(
+    _url: &str,
+    _cipher: Option<MessageCipher>,
+) -> Result<Arc<dyn SessionBackend>> {
+    Err(anyhow!(
+        "session.storage.backend is postgres, but aisopod was built without the 'postgres' feature"
+    ))
+}
+
+#[cfg(feature = "cluster")]
+async fn with_cache(
+    backend: Arc<dyn SessionBackend>,
+    url: &str,
+    cipher: Option<MessageCipher>,
+) -> Result<Arc<dyn SessionBackend>> {
+    use aisopod_session::{CachedSessionBackend, RedisSessionCache};
+
+    let cache = RedisSessionCache::connect(url).await?;
+    let backend = CachedSessionBackend::new(backend, Arc::new(cache));
+    Ok(Arc::new(match cipher {
+        Some(cipher) => backend.with_cipher(cipher),
+        None => backend,
+    }))
+}
+
+#[cfg(not(feature = "cluster"))]
+async fn with_cache(
+    _backend: Arc<dyn SessionBackend>,
+    _url: &str,
+    _cipher: Option<MessageCipher>,
+) -> Result<Arc<dyn SessionBackend>> {
+    Err(anyhow!(
+        "session.storage.cache_url is set, but aisopod was built without the 'cluster' feature"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aisopod_config::types::SessionStorageConfig;
+
+    #[tokio::test]
+    async fn test_sqlite_store_is_shared_through_its_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = SessionConfig {
+            storage: SessionStorageConfig {
+                path: dir.path().join("sessions.db").display().to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let first = open_session_stores(&config).await.unwrap();
+        let second = open_session_stores(&config).await.unwrap();
+        assert!(first.sqlite.is_some());
+
+        let ttl = std::time::Duration::from_secs(60);
+        assert!(first
+            .backend
+            .try_acquire_lease("s", "node-a", ttl)
+            .await
+            .unwrap()
+            .is_some());
+        assert!(second
+            .backend
+            .try_acquire_lease("s", "node-b", ttl)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_missing_features_are_reported() {
+        let mut config = SessionConfig::default();
+        config.storage.cache_url =
+            aisopod_config::sensitive::Sensitive::new("redis://127.0.0.1:1".to_string());
+        if !cfg!(feature = "cluster") {
+            let error = open_session_stores(&config).await.err().unwrap();
+            assert!(error.to_string().contains("'cluster' feature"));
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Server-sent events chat endpoint
+//!
+//! `POST /v1/chat` starts an agent run for a prompt and streams its
+//! [`AgentEvent`]s back as server-sent events, so a web frontend can show a
+//! response as it is generated with nothing more than `fetch`. The stream
+//! opens with a `start` event naming the session, followed by one event per
+//! agent event, named after its variant in snake case (`text_delta`,
+//! `tool_call_start`, `usage`, ...) with the variant's fields as JSON data.
+//! It ends after the `complete` or `error` event.
+//!
+//! Like `POST /api/v1/messages`, the endpoint requires the scope of the
+//! `chat.send` RPC method.
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use aisopod_agent::AgentEvent;
+use axum::extract::{ConnectInfo, Extension};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use futures_util::stream::{self, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use utoipa::ToSchema;
+
+use crate::auth::AuthInfo;
+use crate::rest::{agent_runner, error_response, new_session_key, Caller, ErrorBody};
+use crate::rpc::chat::user_message_params;
+
+/// Build the server-sent events chat routes
+pub fn sse_routes() -> Router {
+    Router::new().route("/v1/chat", post(chat_stream))
+}
+
+/// Request body for a streamed chat
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ChatStreamRequest {
+    /// The user's prompt
+    pub prompt: String,
+    /// Session key to continue; a new session is started when omitted
+    #[serde(default)]
+    pub session: Option<String>,
+    /// Agent to run; resolved from the bindings when omitted
+    #[serde(default)]
+    pub agent: Option<String>,
+}
+
+/// Handler streaming an agent's response to a prompt
+#[utoipa::path(
+    post,
+    path = "/v1/chat",
+    tag = "messages",
+    request_body = ChatStreamRequest,
+    responses(
+        (status = 200, description = "Server-sent events of the run, from `start` to \
+            `complete` or `error`", content_type = "text/event-stream", body = String),
+        (status = 403, description = "Missing the operator.write scope", body = ErrorBody),
+        (status = 500, description = "The agent run could not be started", body = ErrorBody),
+    )
+)]
+pub(crate) async fn chat_stream(
+    auth_info: Option<Extension<AuthInfo>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    runner: Option<Extension<Arc<aisopod_agent::AgentRunner>>>,
+    leases: Option<Extension<aisopod_agent::SessionLeases>>,
+    Json(request): Json<ChatStreamRequest>,
+) -> Response {
+    let caller = Caller::new(auth_info, connect_info);
+    if let Some(response) = caller.denied("chat.send") {
+        return response;
+    }
+
+    let session = request.session.unwrap_or_else(new_session_key);
+    let params = user_message_params(session.clone(), request.prompt, request.agent);
+    let receiver = match agent_runner(runner, leases).run(params).await {
+        Ok(stream) => stream.into_receiver(),
+        Err(e) => {
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "agent_error",
+                format!("{:#}", e),
+            )
+        }
+    };
+
+    let start = Event::default()
+        .event("start")
+        .json_data(json!({ "session": session }))
+        .expect("session event serializes");
+    let events = stream::unfold(Some(receiver), |receiver| async move {
+        let mut receiver = receiver?;
+        let event = receiver.recv().await?;
+        let finished = matches!(
+            event,
+            AgentEvent::Complete { .. } | AgentEvent::Error { .. }
+        );
+        Some((event, (!finished).then_some(receiver)))
+    });
+    let stream = stream::once(async { start })
+        .chain(events.map(|event| sse_event(&event)))
+        .map(Ok::<_, Infallible>);
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// Convert an agent event into a server-sent event named after its variant
+fn sse_event(event: &AgentEvent) -> Event {
+    let (name, data) = match serde_json::to_value(event) {
+        Ok(Value::Object(variant)) => match variant.into_iter().next() {
+            Some((name, data)) => (snake_case(&name), data),
+            None => (
+                "error".to_string(),
+                json!({ "message": "Empty agent event" }),
+            ),
+        },
+        Ok(other) => ("error".to_string(), json!({ "message": other })),
+        Err(e) => ("error".to_string(), json!({ "message": e.to_string() })),
+    };
+    Event::default().event(name).data(data.to_string())
+}
+
+/// Convert a `PascalCase` variant name into `snake_case`
+fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn operator(scopes: &[&str]) -> AuthInfo {
+        AuthInfo {
+            role: "operator".to_string(),
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn chat_request(body: &str) -> Request<Body> {
+        Request::post("/v1/chat")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_snake_case() {
+        assert_eq!(snake_case("TextDelta"), "text_delta");
+        assert_eq!(snake_case("ToolCallStart"), "tool_call_start");
+        assert_eq!(snake_case("Usage"), "usage");
+    }
+
+    #[tokio::test]
+    async fn test_chat_stream_requires_write_scope() {
+        let app = sse_routes().layer(Extension(operator(&["operator.read"])));
+        let response = app
+            .oneshot(chat_request(r#"{"prompt": "hello"}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_chat_stream_events() {
+        let app = sse_routes().layer(Extension(operator(&["operator.write"])));
+        let response = app
+            .oneshot(chat_request(
+                r#"{"prompt": "hello", "session": "web-1", "agent": "missing"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+        // The stream ends on its own once the run failed
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(
+            body.starts_with("event: start\ndata: {\"session\":\"web-1\"}\n\n"),
+            "{}",
+            body
+        );
+        assert!(
+            body.contains("event: error\ndata: {\"message\":"),
+            "{}",
+            body
+        );
+    }
+}
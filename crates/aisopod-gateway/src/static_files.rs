@@ -7,7 +7,9 @@
 use axum::{
     extract::State,
     http::{header, StatusCode, Uri},
-    response::IntoResponse,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
 };
 use rust_embed::{Embed, RustEmbed};
 use std::sync::Arc;
@@ -23,6 +25,14 @@ use aisopod_config::types::WebUiConfig;
 #[folder = "/home/ayourtch/rust/aisopod/web-ui/dist"]
 struct Assets;
 
+/// Embedded assets of the admin UI
+///
+/// Unlike the web UI, the admin UI is plain HTML, CSS and JavaScript kept
+/// in the crate's `admin` directory, so it is always available.
+#[derive(RustEmbed)]
+#[folder = "admin/"]
+struct AdminAssets;
+
 /// Static file serving state
 #[derive(Clone)]
 pub struct StaticFileState {
@@ -195,6 +205,50 @@ pub async fn static_handler(State(state): State<StaticFileState>, uri: Uri) -> i
     static_handler_internal(state, path).await
 }
 
+/// Admin UI handler
+///
+/// Serves the embedded admin UI below `/admin`, with `index.html` for the
+/// directory itself. The page asks for an API token and uses it to call
+/// the REST and WebSocket RPC APIs, so the assets are served without
+/// authentication.
+pub async fn admin_handler(State(state): State<StaticFileState>, uri: Uri) -> Response {
+    let config = state.get_config().await;
+    if !config.admin {
+        return (StatusCode::NOT_FOUND, "Not Found").into_response();
+    }
+
+    let path = uri.path().strip_prefix("/admin").unwrap_or_default();
+    let path = path.trim_start_matches('/');
+    let path = if path.is_empty() { "index.html" } else { path };
+    match AdminAssets::get(path) {
+        Some(file) => {
+            let mut headers = axum::http::HeaderMap::new();
+            headers.insert(
+                header::CONTENT_TYPE,
+                get_content_type(path)
+                    .parse()
+                    .expect("content_type is a valid header value"),
+            );
+            headers.insert(
+                header::CACHE_CONTROL,
+                get_cache_control(path)
+                    .parse()
+                    .expect("cache_control is a valid header value"),
+            );
+            (headers, file.data).into_response()
+        }
+        None => (StatusCode::NOT_FOUND, "Not Found").into_response(),
+    }
+}
+
+/// Build the router serving the admin UI at `/admin`
+pub fn admin_routes(state: StaticFileState) -> Router {
+    Router::new()
+        .route("/admin", get(admin_handler))
+        .route("/admin/*path", get(admin_handler))
+        .with_state(state)
+}
+
 /// Build CORS headers based on configured origins
 pub fn build_cors_headers(origins: &[String]) -> String {
     origins.join(", ")
@@ -0,0 +1,146 @@
+//! OpenTelemetry trace export
+//!
+//! [`init_tracing`] installs the process-wide tracing subscriber: log
+//! output to stdout filtered by `RUST_LOG`, plus, when
+//! `gateway.telemetry.enabled` is set, an OTLP/HTTP exporter for the spans
+//! of aisopod and of the HTTP layer. A traced request nests as
+//! `request` (HTTP) → `rpc.dispatch` → `agent.run` → `provider.chat_completion`
+//! and `tool.execute`, so the latency of each stage shows up in the
+//! collector.
+
+use anyhow::{Context, Result};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use tracing::Level;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+use aisopod_config::types::TelemetryConfig;
+
+/// Keeps trace export running; flushes pending spans when dropped
+#[must_use = "spans are only exported while the guard is alive"]
+pub struct TelemetryGuard {
+    provider: Option<SdkTracerProvider>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.provider.take() {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("Failed to flush traces: {}", e);
+            }
+        }
+    }
+}
+
+/// Install the tracing subscriber, logging with `default_filter` unless
+/// `RUST_LOG` is set and exporting spans as configured in `config`
+pub fn init_tracing(config: &TelemetryConfig, default_filter: &str) -> Result<TelemetryGuard> {
+    let env_filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter));
+    let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_target(true)
+        .with_filter(env_filter);
+
+    let provider = if config.enabled {
+        Some(tracer_provider(config)?)
+    } else {
+        None
+    };
+    let otel_layer = provider.as_ref().map(|provider| {
+        tracing_opentelemetry::layer()
+            .with_tracer(provider.tracer("aisopod"))
+            .with_filter(span_filter())
+    });
+
+    tracing_subscriber::registry()
+        .with(fmt_layer)
+        .with(otel_layer)
+        .try_init()
+        .context("Failed to install the tracing subscriber")?;
+
+    if provider.is_some() {
+        tracing::info!(
+            endpoint = %config.endpoint,
+            sample_ratio = config.sample_ratio,
+            "Exporting traces over OTLP"
+        );
+    }
+    Ok(TelemetryGuard { provider })
+}
+
+/// Build the tracer provider exporting to the configured collector
+fn tracer_provider(config: &TelemetryConfig) -> Result<SdkTracerProvider> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(&config.endpoint)
+        .build()
+        .with_context(|| format!("Failed to create OTLP exporter for {}", config.endpoint))?;
+
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_sampler(sampler(config.sample_ratio))
+        .with_resource(
+            Resource::builder()
+                .with_service_name(config.service_name.clone())
+                .build(),
+        )
+        .build())
+}
+
+/// Sample a `ratio` of new traces, and follow the caller's decision for
+/// traces that were started upstream
+fn sampler(ratio: f64) -> Sampler {
+    let root = if ratio >= 1.0 {
+        Sampler::AlwaysOn
+    } else if ratio <= 0.0 {
+        Sampler::AlwaysOff
+    } else {
+        Sampler::TraceIdRatioBased(ratio)
+    };
+    Sampler::ParentBased(Box::new(root))
+}
+
+/// The spans worth exporting: aisopod's own and the HTTP request spans,
+/// independent of the log filter
+fn span_filter() -> Targets {
+    Targets::new()
+        .with_target("aisopod", Level::INFO)
+        .with_target("tower_http", Level::INFO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampler_bounds() {
+        let sampled = |ratio| format!("{:?}", sampler(ratio));
+        assert_eq!(sampled(1.0), "ParentBased(AlwaysOn)");
+        assert_eq!(sampled(-1.0), "ParentBased(AlwaysOff)");
+        assert_eq!(sampled(0.25), "ParentBased(TraceIdRatioBased(0.25))");
+    }
+
+    #[test]
+    fn test_span_filter() {
+        let filter = span_filter();
+        assert!(filter.would_enable("aisopod_agent::runner", &Level::INFO));
+        assert!(filter.would_enable("tower_http::trace::make_span", &Level::INFO));
+        assert!(!filter.would_enable("aisopod_agent::runner", &Level::DEBUG));
+        assert!(!filter.would_enable("hyper::proto", &Level::INFO));
+    }
+
+    #[tokio::test]
+    async fn test_tracer_provider_builds() {
+        let config = TelemetryConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let provider = tracer_provider(&config).unwrap();
+        provider.shutdown().ok();
+    }
+}
@@ -1,6 +1,16 @@
-use anyhow::{anyhow, Result};
+use aisopod_config::types::ClientAuthConfig;
+use anyhow::{anyhow, bail, Context, Result};
 use axum_server::tls_rustls::RustlsConfig;
+use rustls::client::danger::HandshakeSignatureValid;
+use rustls::pki_types::{CertificateDer, UnixTime};
+use rustls::server::danger::{ClientCertVerified, ClientCertVerifier};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{CertificateError, DigitallySignedStruct, DistinguishedName, RootCertStore};
 use std::path::Path;
+use std::sync::Arc;
+
+/// OID of the common name attribute (2.5.4.3), without tag and length
+const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
 
 // Initialize Rustls CryptoProvider to avoid runtime errors
 // This must be called before any TLS operations
@@ -14,7 +24,7 @@ fn init_rustls_provider() {
 pub async fn load_tls_config(cert_path: &Path, key_path: &Path) -> Result<RustlsConfig> {
     // Ensure the provider is initialized
     init_rustls_provider();
-    
+
     RustlsConfig::from_pem_file(cert_path, key_path)
         .await
         .map_err(|e| anyhow!("Failed to load TLS configuration from files: {}", e))
@@ -24,3 +34,251 @@ pub async fn load_tls_config(cert_path: &Path, key_path: &Path) -> Result<Rustls
 pub fn is_tls_enabled(cert_path: &str, key_path: &str) -> bool {
     !cert_path.is_empty() && !key_path.is_empty()
 }
+
+/// Read the PEM file at `path` with `parse`, naming the file in errors
+fn read_pem<T>(
+    path: &Path,
+    parse: impl FnOnce(&mut dyn std::io::BufRead) -> std::io::Result<T>,
+) -> Result<T> {
+    let data = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    parse(&mut data.as_slice()).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Build the verifier of client certificates described by `config`
+pub fn client_cert_verifier(config: &ClientAuthConfig) -> Result<Arc<dyn ClientCertVerifier>> {
+    init_rustls_provider();
+
+    let ca_path = Path::new(&config.ca_path);
+    let mut roots = RootCertStore::empty();
+    for cert in read_pem(ca_path, |rd| {
+        rustls_pemfile::certs(rd).collect::<std::io::Result<Vec<_>>>()
+    })? {
+        roots
+            .add(cert)
+            .with_context(|| format!("Invalid CA certificate in {}", ca_path.display()))?;
+    }
+    if roots.is_empty() {
+        bail!("No CA certificates in {}", ca_path.display());
+    }
+
+    let mut crls = Vec::new();
+    for crl_path in &config.crl_paths {
+        crls.extend(read_pem(Path::new(crl_path), |rd| {
+            rustls_pemfile::crls(rd).collect::<std::io::Result<Vec<_>>>()
+        })?);
+    }
+
+    // Fleets commonly lack CRLs for their intermediates, so only the client's
+    // own certificate is checked for revocation
+    let mut builder = WebPkiClientVerifier::builder(Arc::new(roots))
+        .with_crls(crls)
+        .only_check_end_entity_revocation();
+    if config.optional {
+        builder = builder.allow_unauthenticated();
+    }
+    let verifier = builder
+        .build()
+        .map_err(|e| anyhow!("Failed to build client certificate verifier: {}", e))?;
+
+    if config.allowed_names.is_empty() {
+        return Ok(verifier);
+    }
+    Ok(Arc::new(AllowedNamesVerifier {
+        inner: verifier,
+        allowed_names: config.allowed_names.clone(),
+    }))
+}
+
+/// Load TLS configuration from certificate and key files, verifying client
+/// certificates as configured in `client_auth`
+pub async fn load_mtls_config(
+    cert_path: &Path,
+    key_path: &Path,
+    client_auth: &ClientAuthConfig,
+) -> Result<RustlsConfig> {
+    let verifier = client_cert_verifier(client_auth)?;
+    let certs = read_pem(cert_path, |rd| rustls_pemfile::certs(rd).collect())?;
+    let key = read_pem(key_path, rustls_pemfile::private_key)?
+        .ok_or_else(|| anyhow!("No private key in {}", key_path.display()))?;
+
+    let mut server_config = rustls::ServerConfig::builder()
+        .with_client_cert_verifier(verifier)
+        .with_single_cert(certs, key)
+        .map_err(|e| anyhow!("Failed to load TLS configuration from files: {}", e))?;
+    server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(RustlsConfig::from_config(Arc::new(server_config)))
+}
+
+/// Client certificate verifier additionally requiring the certificate's
+/// common name or one of its DNS names to be in an allowlist
+#[derive(Debug)]
+struct AllowedNamesVerifier {
+    inner: Arc<dyn ClientCertVerifier>,
+    allowed_names: Vec<String>,
+}
+
+impl ClientCertVerifier for AllowedNamesVerifier {
+    fn offer_client_auth(&self) -> bool {
+        self.inner.offer_client_auth()
+    }
+
+    fn client_auth_mandatory(&self) -> bool {
+        self.inner.client_auth_mandatory()
+    }
+
+    fn root_hint_subjects(&self) -> &[DistinguishedName] {
+        self.inner.root_hint_subjects()
+    }
+
+    fn verify_client_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        now: UnixTime,
+    ) -> Result<ClientCertVerified, rustls::Error> {
+        let verified = self
+            .inner
+            .verify_client_cert(end_entity, intermediates, now)?;
+        let names = certificate_names(end_entity)?;
+        let allowed = names.iter().any(|name| {
+            self.allowed_names
+                .iter()
+                .any(|pattern| name_matches(pattern, name))
+        });
+        if !allowed {
+            tracing::warn!(
+                target: "audit",
+                event = "client_certificate_rejected",
+                names = ?names,
+                "Client certificate names are not allowed"
+            );
+            return Err(rustls::Error::InvalidCertificate(
+                CertificateError::ApplicationVerificationFailure,
+            ));
+        }
+        Ok(verified)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// The common names and DNS subject alternative names of `cert`
+fn certificate_names(cert: &CertificateDer<'_>) -> Result<Vec<String>, rustls::Error> {
+    let cert = webpki::EndEntityCert::try_from(cert)
+        .map_err(|_| rustls::Error::InvalidCertificate(CertificateError::BadEncoding))?;
+    let mut names: Vec<String> = common_names(cert.subject())
+        .into_iter()
+        .map(String::from)
+        .collect();
+    names.extend(cert.valid_dns_names().map(String::from));
+    Ok(names)
+}
+
+/// Whether `name` matches `pattern`, either exactly or, for `*.suffix`
+/// patterns, as a single label followed by `suffix`, ignoring ASCII case
+fn name_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => name
+            .split_once('.')
+            .is_some_and(|(label, rest)| !label.is_empty() && rest.eq_ignore_ascii_case(suffix)),
+        None => pattern.eq_ignore_ascii_case(name),
+    }
+}
+
+/// Split the DER element at the start of `input` into its tag, its contents
+/// and the rest of the input
+fn der_element(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (len, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 || rest.len() < count {
+            return None;
+        }
+        let (bytes, rest) = rest.split_at(count);
+        let len = bytes.iter().fold(0usize, |len, b| len << 8 | *b as usize);
+        (len, rest)
+    };
+    if rest.len() < len {
+        return None;
+    }
+    let (contents, rest) = rest.split_at(len);
+    Some((tag, contents, rest))
+}
+
+/// The common names in a DER encoded name, given without its outer
+/// `SEQUENCE` as returned by [`webpki::Cert::subject`]
+fn common_names(subject: &[u8]) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rdns = subject;
+    while let Some((_, rdn, rest)) = der_element(rdns) {
+        rdns = rest;
+        let mut attributes = rdn;
+        while let Some((_, attribute, rest)) = der_element(attributes) {
+            attributes = rest;
+            let Some((_, oid, value)) = der_element(attribute) else {
+                continue;
+            };
+            if oid != OID_COMMON_NAME {
+                continue;
+            }
+            if let Some(name) = der_element(value).and_then(|(_, v, _)| std::str::from_utf8(v).ok())
+            {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_matches() {
+        assert!(name_matches("device-1", "DEVICE-1"));
+        assert!(name_matches("*.fleet.example.com", "d1.fleet.example.com"));
+        assert!(!name_matches("*.fleet.example.com", "fleet.example.com"));
+        assert!(!name_matches(
+            "*.fleet.example.com",
+            "a.b.fleet.example.com"
+        ));
+        assert!(!name_matches("*.fleet.example.com", ".fleet.example.com"));
+        assert!(!name_matches("device-1", "device-10"));
+    }
+
+    #[test]
+    fn test_common_names() {
+        // SET { SEQUENCE { OID 2.5.4.10, "Acme" } }, SET { SEQUENCE { OID 2.5.4.3, "dev" } }
+        let subject = [
+            0x31, 0x0d, 0x30, 0x0b, 0x06, 0x03, 0x55, 0x04, 0x0a, 0x0c, 0x04, b'A', b'c', b'm',
+            b'e', 0x31, 0x0c, 0x30, 0x0a, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0c, 0x03, b'd', b'e',
+            b'v',
+        ];
+        assert_eq!(common_names(&subject), ["dev"]);
+        assert!(common_names(&subject[..20]).is_empty());
+    }
+}
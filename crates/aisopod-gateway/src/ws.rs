@@ -14,11 +14,13 @@ use std::time::Duration;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use crate::auth::AuthInfo;
+use crate::auth::{ApiTokenId, AuthInfo};
 use crate::broadcast::Broadcaster;
 use crate::client::{ClientRegistry, GatewayClient};
-use crate::rpc::{self, chat::ChatSendHandler, MethodRouter, RequestContext, ApprovalStore, ApprovalRequestHandler, ApprovalApproveHandler, ApprovalDenyHandler, ApprovalListHandler, PairingStore, PairRequestHandler, PairConfirmHandler, PairRevokeHandler, CapabilityStore, NodeDescribeHandler, NodeInvokeHandler};
+use crate::rpc::{self, chat::ChatSendHandler, MethodRouter, RequestContext, ApprovalStore, ApprovalRequestHandler, ApprovalApproveHandler, ApprovalDenyHandler, ApprovalListHandler, PairingStore, PairRequestHandler, PairConfirmHandler, PairRevokeHandler, CapabilityStore, NodeDescribeHandler, NodeInvokeHandler, MemoryRpcDeps, register_memory_methods, SessionRpcDeps, register_session_methods, TokenRpcDeps, register_token_methods, ConfigRpcDeps, register_config_methods};
 use crate::auth::DeviceTokenManager;
+use crate::middleware::ClientRateLimit;
+use crate::rpc::middleware::auth::{check_channel, check_scope};
 
 /// Default handshake timeout in seconds
 pub const DEFAULT_HANDSHAKE_TIMEOUT_SECS: u64 = 5;
@@ -47,30 +49,102 @@ pub const AGENT_RUNNER_KEY: &str = "aisopod.gateway.agent_runner";
 /// - SessionStore: For conversation state management
 /// - AgentRunner: The main agent execution orchestrator
 pub fn create_agent_runner() -> Arc<aisopod_agent::AgentRunner> {
-    // Create configuration (will be replaced with actual config loading in production)
-    let config = Arc::new(aisopod_config::AisopodConfig::default());
-    
-    // Create provider registry
-    let providers = Arc::new(aisopod_provider::ProviderRegistry::new());
-    
-    // Create tool registry with built-in tools
+    create_agent_runner_with_leases(None)
+}
+
+/// Build the agent dependencies stack, taking session leases when given
+///
+/// Clustered nodes take the lease of a session around each agent run, so
+/// that a session is processed by one node at a time.
+pub fn create_agent_runner_with_leases(
+    leases: Option<aisopod_agent::SessionLeases>,
+) -> Arc<aisopod_agent::AgentRunner> {
+    create_agent_runner_with_sessions(
+        Arc::new(aisopod_config::AisopodConfig::default()),
+        None,
+        leases,
+        Arc::new(aisopod_tools::NoOpJobScheduler::new()),
+    )
+}
+
+/// Build the agent dependencies stack for `config`, keeping sessions in
+/// `sessions` and scheduling the jobs of the cron tool with `jobs`
+///
+/// The sessions are kept in memory when no store is given. Nodes sharing
+/// a store take the leases given around each agent run. Token usage is
+/// tracked by the runner, so sharing it shares the usage budgets.
+pub fn create_agent_runner_with_sessions(
+    config: Arc<aisopod_config::AisopodConfig>,
+    sessions: Option<Arc<aisopod_session::SessionStore>>,
+    leases: Option<aisopod_agent::SessionLeases>,
+    jobs: Arc<dyn aisopod_tools::JobScheduler>,
+) -> Arc<aisopod_agent::AgentRunner> {
+    // Create tool registry with built-in tools; without channels, the
+    // messages of the message tool go to unknown channels
     let mut tools = aisopod_tools::ToolRegistry::new();
-    aisopod_tools::register_all_tools(&mut tools);
-    let tools = Arc::new(tools);
-    
-    // Create session store (in-memory for now)
-    let sessions = Arc::new(
-        aisopod_session::SessionStore::new_in_memory()
-            .expect("Failed to create in-memory session store"),
+    aisopod_tools::register_configured_tools_with_services(
+        &mut tools,
+        &config.tools,
+        jobs,
+        crate::GatewayChannels::default().sender(),
+    );
+    create_agent_runner_with_tools(config, Arc::new(tools), sessions, leases)
+}
+
+/// Build the tool registry for `config`, with the built-in tools and the
+/// tools of the configured MCP servers
+///
+/// The message tool sends its messages through `channels`, and the tool
+/// operations needing approval are asked for with their approval handler.
+/// The returned MCP clients keep the server connections open, so they must
+/// be held for as long as the registry is in use.
+pub async fn create_tool_registry(
+    config: &aisopod_config::AisopodConfig,
+    jobs: Arc<dyn aisopod_tools::JobScheduler>,
+    channels: &crate::GatewayChannels,
+) -> (aisopod_tools::ToolRegistry, Vec<Arc<aisopod_tools::McpClient>>) {
+    let mut tools = aisopod_tools::ToolRegistry::new();
+    aisopod_tools::register_configured_tools_with_services(
+        &mut tools,
+        &config.tools,
+        jobs,
+        channels.sender(),
     );
+    if let Some(approvals) = channels.approval_handler() {
+        tools.set_approval_handler(approvals.clone());
+    }
+    let mcp_clients = aisopod_tools::mcp::connect_mcp_servers(&mut tools, &config.tools.mcp).await;
+    (tools, mcp_clients)
+}
+
+/// Build the agent dependencies stack for `config` around the given tool
+/// registry
+pub fn create_agent_runner_with_tools(
+    config: Arc<aisopod_config::AisopodConfig>,
+    tools: Arc<aisopod_tools::ToolRegistry>,
+    sessions: Option<Arc<aisopod_session::SessionStore>>,
+    leases: Option<aisopod_agent::SessionLeases>,
+) -> Arc<aisopod_agent::AgentRunner> {
+    // Create provider registry
+    let providers = Arc::new(aisopod_provider::ProviderRegistry::new());
 
-    // Create the agent runner with all dependencies
-    Arc::new(aisopod_agent::AgentRunner::new(
-        config,
-        providers,
-        tools,
-        sessions,
-    ))
+    // Use the given session store, or an in-memory one
+    let sessions = sessions.unwrap_or_else(|| {
+        Arc::new(
+            aisopod_session::SessionStore::new_in_memory()
+                .expect("Failed to create in-memory session store"),
+        )
+    });
+
+    // Create the agent runner with all dependencies; its usage tracker
+    // enforces the `session.budget` limits over all the runs it starts
+    let usage = Arc::new(aisopod_agent::usage::UsageTracker::new());
+    let runner =
+        aisopod_agent::AgentRunner::new_with_usage_tracker(config, providers, tools, sessions, usage);
+    Arc::new(match leases {
+        Some(leases) => runner.with_session_leases(leases),
+        None => runner,
+    })
 }
 
 /// Build the WebSocket routes with configurable timeout
@@ -91,7 +165,9 @@ pub async fn ws_handler(
     let handshake_timeout_duration =
         Duration::from_secs(handshake_timeout.unwrap_or(DEFAULT_HANDSHAKE_TIMEOUT_SECS));
 
-    ws.on_upgrade(move |socket| async move {
+    // Browsers that authenticate with a bearer subprotocol also offer
+    // `aisopod`, and fail the handshake unless one of theirs is selected
+    ws.protocols(["aisopod"]).on_upgrade(move |socket| async move {
         // Extract protocol version from headers
         let version_header = request
             .headers()
@@ -170,8 +246,65 @@ async fn handle_connection(ws: WebSocket, request: axum::extract::Request) {
         .get::<std::sync::Arc<PairingStore>>()
         .cloned();
 
-    // Create agent runner for this connection
-    let agent_runner = create_agent_runner();
+    // Get memory store dependencies from extensions
+    let memory_deps = request
+        .extensions()
+        .get::<std::sync::Arc<MemoryRpcDeps>>()
+        .cloned();
+
+    // Get session store dependencies from extensions
+    let session_deps = request
+        .extensions()
+        .get::<std::sync::Arc<SessionRpcDeps>>()
+        .cloned();
+
+    // Get API token store dependencies from extensions
+    let token_deps = request
+        .extensions()
+        .get::<std::sync::Arc<TokenRpcDeps>>()
+        .cloned();
+
+    // Get running configuration dependencies from extensions
+    let config_deps = request
+        .extensions()
+        .get::<std::sync::Arc<ConfigRpcDeps>>()
+        .cloned();
+
+    // Get the gateway-wide approval store from extensions
+    let shared_approval_store = request
+        .extensions()
+        .get::<std::sync::Arc<ApprovalStore>>()
+        .cloned();
+
+    // The minted API token the connection authenticated with, which must stay
+    // active for the connection to be served
+    let api_token = request
+        .extensions()
+        .get::<ApiTokenId>()
+        .cloned()
+        .zip(token_deps.as_ref().map(|deps| deps.store.clone()));
+    let token_active = || {
+        api_token
+            .as_ref()
+            .is_none_or(|(id, store)| store.is_active(id))
+    };
+
+    // RPC calls made over the connection count against the client's rate limits
+    let rate_limit = ClientRateLimit::of(&request);
+
+    // Use the gateway's agent runner, or create one for this connection
+    let agent_runner = match request
+        .extensions()
+        .get::<Arc<aisopod_agent::AgentRunner>>()
+    {
+        Some(runner) => runner.clone(),
+        None => create_agent_runner_with_leases(
+            request
+                .extensions()
+                .get::<aisopod_agent::SessionLeases>()
+                .cloned(),
+        ),
+    };
     
     // Clone the agent runner for use in the loop
     let agent_runner_for_loop = Arc::clone(&agent_runner);
@@ -194,8 +327,11 @@ async fn handle_connection(ws: WebSocket, request: axum::extract::Request) {
     // Clone tx for use in chat.send handler (since tx will be moved into GatewayClient)
     let tx_for_chat = tx.clone();
 
-    // Create approval store for managing pending approval requests
-    let approval_store = std::sync::Arc::new(ApprovalStore::new());
+    // Use the gateway's approval store so approvals requested on one
+    // connection can be listed and decided on another, falling back to one
+    // scoped to this connection
+    let approval_store =
+        shared_approval_store.unwrap_or_else(|| std::sync::Arc::new(ApprovalStore::new()));
 
     // Register approval handlers with the method router if broadcaster is available
     if let Some(broadcaster) = &broadcaster {
@@ -249,6 +385,26 @@ async fn handle_connection(ws: WebSocket, request: axum::extract::Request) {
         method_router.register("node.invoke", node_invoke_handler);
     }
 
+    // Register memory handlers if a memory store is available
+    if let Some(memory_deps) = &memory_deps {
+        register_memory_methods(&method_router, memory_deps.as_ref().clone());
+    }
+
+    // Register session handlers if a session store is available
+    if let Some(session_deps) = &session_deps {
+        register_session_methods(&method_router, session_deps.as_ref().clone());
+    }
+
+    // Register token handlers if an API token store is available
+    if let Some(token_deps) = &token_deps {
+        register_token_methods(&method_router, token_deps.as_ref().clone());
+    }
+
+    // Register config handlers if the running configuration is available
+    if let Some(config_deps) = &config_deps {
+        register_config_methods(&method_router, config_deps.as_ref().clone());
+    }
+
     // Register client if we have auth info and registry
     // The sender is moved into the client and also used in the main loop
     // Clone auth_info before moving it into GatewayClient
@@ -285,6 +441,12 @@ async fn handle_connection(ws: WebSocket, request: axum::extract::Request) {
                     break;
                 }
 
+                if !token_active() {
+                    info!(conn_id = %conn_id, "WebSocket connection closed because its API token was revoked or expired");
+                    let _ = ws_tx.send(Message::Close(None)).await;
+                    break;
+                }
+
                 debug!(conn_id = %conn_id, "Sending ping frame");
                 if let Err(e) = ws_tx.send(Message::Ping(vec![])).await {
                     warn!(conn_id = %conn_id, "Failed to send ping: {}", e);
@@ -309,11 +471,36 @@ async fn handle_connection(ws: WebSocket, request: axum::extract::Request) {
                     }
                     Some(Ok(Message::Text(text))) => {
                         eprintln!("=== WS RECEIVED TEXT: {} ===", text);
+                        if !token_active() {
+                            info!(conn_id = %conn_id, "WebSocket connection closed because its API token was revoked or expired");
+                            let _ = ws_tx.send(Message::Close(None)).await;
+                            break;
+                        }
                         // Try to parse as JSON-RPC request
                         match rpc::parse(&text) {
                             Ok(request) => {
-                                // Check if this is a chat.send request
-                                if request.method == "chat.send" {
+                                if let Some(Err(retry_after)) = rate_limit.as_ref().map(|r| r.check()) {
+                                    warn!(conn_id = %conn_id, "Rate limit exceeded: retry after {} seconds", retry_after.as_secs());
+                                    let response = rpc::RpcResponse::rate_limited(request.id, retry_after);
+                                    let response_text = serde_json::to_string(&response)
+                                        .expect("RPC responses should serialize");
+                                    if let Err(e) = ws_tx.send(Message::Text(response_text)).await {
+                                        error!(conn_id = %conn_id, "Failed to send RPC response: {}", e);
+                                        break;
+                                    }
+                                    continue;
+                                }
+
+                                // Check if this is a chat.send request the caller may make;
+                                // denied calls fall through to the method router, which
+                                // reports the missing scope or channel
+                                if request.method == "chat.send"
+                                    && auth_info.as_ref().is_none_or(|auth_info| {
+                                        let client_ip = remote_addr.to_string();
+                                        check_scope(auth_info, "chat.send", &client_ip).is_ok()
+                                            && check_channel(auth_info, "chat.send", request.params.as_ref(), &client_ip).is_ok()
+                                    })
+                                {
                                     // Handle chat.send specially with full dependencies
                                     let chat_handler = ChatSendHandler;
                                     let ws_sender = std::sync::Arc::new(tx_for_chat.clone());
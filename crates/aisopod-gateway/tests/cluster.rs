@@ -0,0 +1,108 @@
+//! Integration tests for gateway nodes running in a cluster
+//!
+//! Two nodes are started with `run_with_config`, joined through an
+//! in-process bus and sharing one SQLite session store, as two instances
+//! behind a load balancer would share a database.
+
+#![deny(unused_must_use)]
+
+use aisopod_config::sensitive::Sensitive;
+use aisopod_config::types::{
+    AisopodConfig, ClusterConfig, GatewayConfig, SessionConfig, SessionStorageConfig,
+};
+use aisopod_gateway::server::run_with_config;
+use aisopod_session::SessionStore;
+use serde_json::{json, Value};
+use std::net::TcpListener;
+use std::path::Path;
+use std::time::Duration;
+
+/// Reserve a free local port for a node
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+/// Start a clustered node sharing the session store at `sessions`
+async fn start_node(node_id: &str, sessions: &Path) -> String {
+    let port = free_port();
+    let mut gateway = GatewayConfig::default();
+    gateway.bind.address = "127.0.0.1".to_string();
+    gateway.server.port = port;
+    gateway.cluster = ClusterConfig {
+        enabled: true,
+        node_id: node_id.to_string(),
+        redis_url: Sensitive::new("memory://cluster-test".to_string()),
+        ..Default::default()
+    };
+    let config = AisopodConfig {
+        gateway,
+        session: SessionConfig {
+            storage: SessionStorageConfig {
+                path: sessions.display().to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    tokio::spawn(async move {
+        if let Err(e) = run_with_config(&config).await {
+            panic!("node failed: {:#}", e);
+        }
+    });
+
+    let addr = format!("127.0.0.1:{}", port);
+    for _ in 0..50 {
+        if tokio::net::TcpStream::connect(&addr).await.is_ok() {
+            return format!("http://{}", addr);
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("node {} did not start", node_id);
+}
+
+/// Send a message in `session` and return the error message, if any
+async fn send_message(base_url: &str, session: &str) -> Option<String> {
+    let response = reqwest::Client::new()
+        .post(format!("{}/api/v1/messages", base_url))
+        .json(&json!({ "text": "hello", "session": session }))
+        .send()
+        .await
+        .unwrap();
+    if response.status().is_success() {
+        return None;
+    }
+    let body: Value = response.json().await.unwrap();
+    Some(body["message"].as_str().unwrap_or_default().to_string())
+}
+
+#[tokio::test]
+async fn test_nodes_take_session_leases_in_the_shared_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let sessions = dir.path().join("sessions.db");
+    let node_a = start_node("node-a", &sessions).await;
+    let node_b = start_node("node-b", &sessions).await;
+
+    // Node B is processing the session
+    let store = SessionStore::new(&sessions).unwrap();
+    store
+        .try_acquire_lease("s1", "node-b", Duration::from_secs(60))
+        .unwrap()
+        .unwrap();
+
+    // Node A refuses to run the agent on it, while node B goes on to run
+    // it, failing later for lack of a configured model
+    let error = send_message(&node_a, "s1").await.unwrap();
+    assert!(error.contains("another instance"), "{}", error);
+    let error = send_message(&node_b, "s1").await.unwrap_or_default();
+    assert!(!error.contains("another instance"), "{}", error);
+
+    // Once node B is done, node A takes the session
+    store.release_lease("s1", "node-b").unwrap();
+    let error = send_message(&node_a, "s1").await.unwrap_or_default();
+    assert!(!error.contains("another instance"), "{}", error);
+}
@@ -175,6 +175,7 @@ impl GatewayTestConfig {
                 enabled: false,
                 cert_path: String::new(),
                 key_path: String::new(),
+                client_auth: Default::default(),
             },
             web_ui: WebUiConfig {
                 enabled: false,
@@ -184,13 +185,17 @@ impl GatewayTestConfig {
             rate_limit: aisopod_config::types::RateLimitConfig {
                 max_requests: self.rate_limit_max_requests,
                 window: self.rate_limit_window,
+                ..Default::default()
             },
             request_size_limits: aisopod_config::types::RequestSizeLimitsConfig {
                 max_body_size: 10 * 1024 * 1024,  // 10MB default
                 max_headers_size: 8192,            // 8KB default
                 max_headers_count: 100,            // 100 headers default
+                ..Default::default()
             },
             pairing_cleanup_interval: 300,  // 5 minutes default
+            telemetry: Default::default(),
+            cluster: Default::default(),
         }
     }
 }
@@ -502,6 +507,44 @@ async fn test_over_limit_returns_429() {
     assert!(retry_after.is_some(), "Should have Retry-After header");
 }
 
+#[tokio::test]
+async fn test_ws_rpc_calls_are_rate_limited() {
+    let config = GatewayTestConfig {
+        rate_limit_max_requests: 3,
+        rate_limit_window: 60,
+        ..Default::default()
+    }
+    .into_gateway_config();
+    let addr = start_test_server(config).await;
+
+    // The upgrade request counts as the first of the 3 requests
+    let (mut ws, _response) = connect_async(format!("ws://{}/ws", addr))
+        .await
+        .expect("Failed to connect to WebSocket");
+
+    let mut codes = Vec::new();
+    for id in 1..=3 {
+        let request = format!(r#"{{"jsonrpc":"2.0","method":"test.method","id":{}}}"#, id);
+        ws.send(Message::Text(request))
+            .await
+            .expect("Failed to send RPC request");
+        let text = loop {
+            match ws.next().await.expect("Connection closed unexpectedly") {
+                Ok(Message::Text(text)) => break text,
+                Ok(_) => continue,
+                Err(e) => panic!("Failed to receive message: {}", e),
+            }
+        };
+        let json: serde_json::Value = serde_json::from_str(&text).expect("Invalid JSON response");
+        assert_eq!(json["id"], id);
+        codes.push(json["error"]["code"].clone());
+        if id == 3 {
+            assert!(json["error"]["data"]["retry_after"].as_u64().unwrap() >= 1);
+        }
+    }
+    assert_eq!(codes, [-32601, -32601, -32007]);
+}
+
 // ============================================================================
 // WebSocket Tests
 // ============================================================================
@@ -0,0 +1,238 @@
+//! Integration tests for the scheduled agent runs of the gateway
+
+#![deny(unused_must_use)]
+
+use aisopod_channel::adapters::{
+    AccountSnapshot, ChannelConfigAdapter, OutboundAdapter, SecurityAdapter,
+};
+use aisopod_channel::message::{Media, MessageTarget};
+use aisopod_channel::types::ChatType;
+use aisopod_channel::{ChannelCapabilities, ChannelMeta, ChannelPlugin, ChannelRegistry};
+use aisopod_config::types::{
+    Agent, AgentSchedule, AgentsConfig, AisopodConfig, GatewayConfig, ScheduleDelivery,
+};
+use aisopod_gateway::server::{run_with_config, start_job_scheduler};
+use aisopod_gateway::GatewayChannels;
+use aisopod_tools::{NoOpJobRunner, SqliteJobScheduler};
+use async_trait::async_trait;
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+/// A channel recording the texts it was asked to deliver, with their peer
+struct RecordingChannel {
+    meta: ChannelMeta,
+    capabilities: ChannelCapabilities,
+    sent: Mutex<Vec<(String, String)>>,
+}
+
+impl RecordingChannel {
+    fn new() -> Self {
+        Self {
+            meta: ChannelMeta {
+                label: "Recording".to_string(),
+                docs_url: None,
+                ui_hints: serde_json::Value::Null,
+            },
+            capabilities: ChannelCapabilities {
+                chat_types: vec![ChatType::Dm],
+                supports_media: false,
+                supports_reactions: false,
+                supports_threads: false,
+                supports_typing: false,
+                supports_voice: false,
+                max_message_length: None,
+                supported_media_types: vec![],
+            },
+            sent: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn sent(&self) -> Vec<(String, String)> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+impl ChannelConfigAdapter for RecordingChannel {
+    fn list_accounts(&self) -> Result<Vec<String>, anyhow::Error> {
+        Ok(vec!["bot".to_string()])
+    }
+
+    fn resolve_account(&self, id: &str) -> Result<AccountSnapshot, anyhow::Error> {
+        Ok(AccountSnapshot {
+            id: id.to_string(),
+            channel: "recording".to_string(),
+            enabled: true,
+            connected: true,
+        })
+    }
+
+    fn enable_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    fn disable_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    fn delete_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OutboundAdapter for RecordingChannel {
+    async fn send_text(&self, target: &MessageTarget, text: &str) -> Result<(), anyhow::Error> {
+        self.sent
+            .lock()
+            .unwrap()
+            .push((target.peer.id.clone(), text.to_string()));
+        Ok(())
+    }
+
+    async fn send_media(
+        &self,
+        _target: &MessageTarget,
+        _media: &Media,
+    ) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChannelPlugin for RecordingChannel {
+    fn id(&self) -> &str {
+        "recording"
+    }
+
+    fn meta(&self) -> &ChannelMeta {
+        &self.meta
+    }
+
+    fn capabilities(&self) -> &ChannelCapabilities {
+        &self.capabilities
+    }
+
+    fn config(&self) -> &dyn ChannelConfigAdapter {
+        self
+    }
+
+    fn security(&self) -> Option<&dyn SecurityAdapter> {
+        None
+    }
+
+    fn outbound(&self) -> Option<&dyn OutboundAdapter> {
+        Some(self)
+    }
+}
+
+#[tokio::test]
+async fn test_gateway_registers_configured_schedules() {
+    let dir = tempfile::tempdir().unwrap();
+    let jobs = dir.path().join("jobs.db");
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let mut gateway = GatewayConfig::default();
+    gateway.bind.address = "127.0.0.1".to_string();
+    gateway.server.port = port;
+    let config = AisopodConfig {
+        gateway,
+        agents: AgentsConfig {
+            schedules: vec![AgentSchedule {
+                id: "daily".to_string(),
+                agent_id: "default".to_string(),
+                cron: "0 0 9 * * *".to_string(),
+                prompt: "Summarize the news".to_string(),
+                deliver: None,
+            }],
+            scheduler_path: jobs.display().to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    tokio::spawn(async move {
+        if let Err(e) = run_with_config(&config).await {
+            panic!("gateway failed: {:#}", e);
+        }
+    });
+
+    let addr = format!("127.0.0.1:{}", port);
+    let mut started = false;
+    for _ in 0..50 {
+        if tokio::net::TcpStream::connect(&addr).await.is_ok() {
+            started = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(started, "gateway did not start");
+
+    // The schedule was registered as a job of the gateway's scheduler
+    let scheduler = SqliteJobScheduler::open(&jobs, Arc::new(NoOpJobRunner)).unwrap();
+    let job = scheduler.get("agent-schedule:daily").unwrap().unwrap();
+    assert_eq!(job.cron_expression, "0 0 9 * * *");
+    assert_eq!(job.command, "Summarize the news");
+    assert!(job.next_run.is_some());
+}
+
+#[tokio::test]
+async fn test_scheduled_reply_is_delivered_through_gateway_channels() {
+    let recording = Arc::new(RecordingChannel::new());
+    let mut registry = ChannelRegistry::new();
+    registry.register(recording.clone());
+    let channels = GatewayChannels::new(Arc::new(RwLock::new(registry)));
+
+    let agents = AgentsConfig {
+        agents: vec![Agent {
+            id: "reporter".to_string(),
+            model: "mock/mock-model".to_string(),
+            ..Default::default()
+        }],
+        schedules: vec![AgentSchedule {
+            id: "every-second".to_string(),
+            agent_id: "reporter".to_string(),
+            cron: "* * * * * *".to_string(),
+            prompt: "Report".to_string(),
+            deliver: Some(ScheduleDelivery {
+                channel: "recording".to_string(),
+                account: None,
+                peer: "user:alice".to_string(),
+            }),
+        }],
+        ..Default::default()
+    };
+    let config = AisopodConfig {
+        agents: agents.clone(),
+        ..Default::default()
+    };
+    let mut providers = aisopod_provider::ProviderRegistry::new();
+    providers.register(Arc::new(aisopod_provider::MockProvider::new("mock")));
+    providers.register_alias("mock/mock-model", "mock", "mock-model");
+    let runner = Arc::new(aisopod_agent::AgentRunner::new(
+        Arc::new(config),
+        Arc::new(providers),
+        Arc::new(aisopod_tools::ToolRegistry::new()),
+        Arc::new(aisopod_session::SessionStore::new_in_memory().unwrap()),
+    ));
+
+    let jobs = SqliteJobScheduler::in_memory(Arc::new(NoOpJobRunner)).unwrap();
+    start_job_scheduler(&jobs, &agents, runner, channels.sender())
+        .await
+        .unwrap();
+
+    let mut sent = Vec::new();
+    for _ in 0..50 {
+        sent = recording.sent();
+        if !sent.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(!sent.is_empty(), "the scheduled reply was not delivered");
+    assert_eq!(sent[0].0, "alice");
+    assert!(!sent[0].1.is_empty());
+}
@@ -487,3 +487,184 @@ async fn test_insufficient_scope_for_write_method() {
         .unwrap()
         .contains("Insufficient permissions"));
 }
+
+// ============================================================================
+// Admin UI Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_admin_ui_assets_served_without_auth() {
+    let server = build_test_server_with_token("test-token", "operator", vec!["operator.read"]).await;
+    let addr = server.addr();
+
+    let response = reqwest::get(format!("http://{}/admin", addr))
+        .await
+        .expect("Admin request failed");
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "text/html; charset=utf-8");
+    assert!(response.text().await.unwrap().contains("/admin/admin.js"));
+
+    let response = reqwest::get(format!("http://{}/admin/admin.js", addr))
+        .await
+        .expect("Admin request failed");
+    assert_eq!(response.status(), 200);
+
+    let response = reqwest::get(format!("http://{}/admin/missing.js", addr))
+        .await
+        .expect("Admin request failed");
+    assert_eq!(response.status(), 404);
+
+    // The data the UI shows still requires a token
+    let response = reqwest::get(format!("http://{}/api/v1/channels", addr))
+        .await
+        .expect("Channels request failed");
+    assert_eq!(response.status(), 401);
+}
+
+#[tokio::test]
+async fn test_websocket_bearer_subprotocol() {
+    use futures::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let server = build_test_server_with_token("test-token", "operator", vec!["operator.read"]).await;
+    let addr = server.addr();
+
+    let mut request = format!("ws://{}/ws", addr).into_client_request().unwrap();
+    request.headers_mut().insert(
+        "Sec-WebSocket-Protocol",
+        "aisopod, aisopod.bearer.test-token".parse().unwrap(),
+    );
+    let (mut ws, response) = tokio_tungstenite::connect_async(request)
+        .await
+        .expect("WebSocket handshake failed");
+    assert_eq!(response.headers()["sec-websocket-protocol"], "aisopod");
+
+    ws.send(Message::Text(
+        json!({
+            "jsonrpc": "2.0",
+            "method": "config.diff",
+            "params": { "content": "{ meta: { version: \"2.0\" } }" },
+            "id": 1
+        })
+        .to_string(),
+    ))
+    .await
+    .unwrap();
+    let reply = tokio::time::timeout(Duration::from_secs(2), async {
+        loop {
+            match ws.next().await {
+                Some(Ok(Message::Text(text))) => break text,
+                Some(Ok(_)) => continue,
+                other => panic!("unexpected WebSocket message: {:?}", other),
+            }
+        }
+    })
+    .await
+    .expect("no reply");
+    let body: serde_json::Value = serde_json::from_str(&reply).unwrap();
+    let changed = body["result"]["changed_sections"].as_array().unwrap();
+    assert!(changed.contains(&json!("meta")));
+
+    // Without the token the handshake is rejected
+    let mut request = format!("ws://{}/ws", addr).into_client_request().unwrap();
+    request
+        .headers_mut()
+        .insert("Sec-WebSocket-Protocol", "aisopod".parse().unwrap());
+    assert!(tokio_tungstenite::connect_async(request).await.is_err());
+}
+
+/// Connect to the gateway's WebSocket with a bearer token
+async fn connect_ws_with_token(
+    addr: SocketAddr,
+    token: &str,
+) -> tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<TcpStream>> {
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+    let mut request = format!("ws://{}/ws", addr).into_client_request().unwrap();
+    request.headers_mut().insert(
+        "Authorization",
+        format!("Bearer {}", token).parse().unwrap(),
+    );
+    let (ws, _) = tokio_tungstenite::connect_async(request)
+        .await
+        .expect("WebSocket handshake failed");
+    ws
+}
+
+/// Send an RPC call over `ws` and return the next message, skipping pings
+async fn ws_call(
+    ws: &mut tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<TcpStream>>,
+    method: &str,
+    params: serde_json::Value,
+) -> Option<tokio_tungstenite::tungstenite::Message> {
+    use futures::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    let request = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 });
+    ws.send(Message::Text(request.to_string())).await.ok()?;
+    tokio::time::timeout(Duration::from_secs(2), async {
+        loop {
+            match ws.next().await {
+                Some(Ok(Message::Ping(_) | Message::Pong(_))) => continue,
+                Some(Ok(message)) => break Some(message),
+                _ => break None,
+            }
+        }
+    })
+    .await
+    .expect("no reply")
+}
+
+#[tokio::test]
+async fn test_api_token_channel_restriction_and_revocation() {
+    use tokio_tungstenite::tungstenite::Message;
+
+    let server =
+        build_test_server_with_token("admin-token", "operator", vec!["operator.admin"]).await;
+    let addr = server.addr();
+    let mut admin = connect_ws_with_token(addr, "admin-token").await;
+
+    let Some(Message::Text(reply)) = ws_call(
+        &mut admin,
+        "token.create",
+        json!({ "name": "bot", "preset": "chat", "channels": ["telegram"] }),
+    )
+    .await
+    else {
+        panic!("token.create failed");
+    };
+    let created: serde_json::Value = serde_json::from_str(&reply).unwrap();
+    let token = created["result"]["token"].as_str().unwrap().to_string();
+    let id = created["result"]["info"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    // A channel-restricted token may not leave the channel out
+    let mut client = connect_ws_with_token(addr, &token).await;
+    let Some(Message::Text(reply)) =
+        ws_call(&mut client, "chat.send", json!({ "text": "hi" })).await
+    else {
+        panic!("chat.send got no reply");
+    };
+    let body: serde_json::Value = serde_json::from_str(&reply).unwrap();
+    assert!(body["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("requires a channel"));
+
+    // Once revoked, the open connection is closed
+    ws_call(&mut admin, "token.revoke", json!({ "id": id })).await;
+    let reply = ws_call(
+        &mut client,
+        "chat.send",
+        json!({ "text": "hi", "channel": "telegram" }),
+    )
+    .await;
+    assert!(
+        matches!(reply, None | Some(Message::Close(_))),
+        "got {:?}",
+        reply
+    );
+}
@@ -5,7 +5,15 @@ use std::path::Path;
 use tracing_test::traced_test;
 
 // Import the gateway modules
-use aisopod_gateway::tls::{is_tls_enabled, load_tls_config};
+use aisopod_config::types::ClientAuthConfig;
+use aisopod_gateway::tls::{
+    client_cert_verifier, is_tls_enabled, load_mtls_config, load_tls_config,
+};
+use rcgen::{
+    date_time_ymd, BasicConstraints, CertificateParams, CertificateRevocationListParams, DnType,
+    ExtendedKeyUsagePurpose, IsCa, KeyIdMethod, KeyPair, RevokedCertParams, SerialNumber,
+};
+use rustls::pki_types::{CertificateDer, UnixTime};
 
 fn generate_self_signed_cert(
     key_path: &Path,
@@ -81,3 +89,195 @@ async fn test_tls_config_loading() {
     let _ = fs::remove_file(&key_path);
     let _ = fs::remove_file(&cert_path);
 }
+
+/// A CA issuing client certificates
+struct TestCa {
+    cert: rcgen::Certificate,
+    key: KeyPair,
+}
+
+impl TestCa {
+    fn new(name: &str) -> Self {
+        let key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(vec![]).unwrap();
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.distinguished_name.push(DnType::CommonName, name);
+        let cert = params.self_signed(&key).unwrap();
+        Self { cert, key }
+    }
+
+    /// Issue a client certificate with common name `cn` and DNS names `sans`
+    fn issue(&self, serial: u64, cn: &str, sans: &[&str]) -> CertificateDer<'static> {
+        let key = KeyPair::generate().unwrap();
+        let mut params =
+            CertificateParams::new(sans.iter().map(|s| s.to_string()).collect::<Vec<_>>()).unwrap();
+        params.distinguished_name.push(DnType::CommonName, cn);
+        params.serial_number = Some(SerialNumber::from(serial));
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ClientAuth];
+        params
+            .signed_by(&key, &self.cert, &self.key)
+            .unwrap()
+            .der()
+            .clone()
+    }
+
+    /// PEM of a CRL revoking the certificates with serial numbers `serials`
+    fn crl(&self, serials: &[u64]) -> String {
+        CertificateRevocationListParams {
+            this_update: date_time_ymd(2024, 1, 1),
+            next_update: date_time_ymd(2040, 1, 1),
+            crl_number: SerialNumber::from(1u64),
+            issuing_distribution_point: None,
+            revoked_certs: serials
+                .iter()
+                .map(|serial| RevokedCertParams {
+                    serial_number: SerialNumber::from(*serial),
+                    revocation_time: date_time_ymd(2024, 1, 1),
+                    reason_code: None,
+                    invalidity_date: None,
+                })
+                .collect(),
+            key_identifier_method: KeyIdMethod::Sha256,
+        }
+        .signed_by(&self.cert, &self.key)
+        .unwrap()
+        .pem()
+        .unwrap()
+    }
+}
+
+fn client_auth(dir: &Path, ca: &TestCa) -> ClientAuthConfig {
+    let ca_path = dir.join("ca.pem");
+    fs::write(&ca_path, ca.cert.pem()).unwrap();
+    ClientAuthConfig {
+        enabled: true,
+        ca_path: ca_path.to_string_lossy().into_owned(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_client_cert_must_chain_to_ca() {
+    let dir = tempfile::tempdir().unwrap();
+    let ca = TestCa::new("Fleet CA");
+    let verifier = client_cert_verifier(&client_auth(dir.path(), &ca)).unwrap();
+    assert!(verifier.client_auth_mandatory());
+
+    let cert = ca.issue(1, "device-1", &[]);
+    assert!(verifier
+        .verify_client_cert(&cert, &[], UnixTime::now())
+        .is_ok());
+
+    let other = TestCa::new("Other CA").issue(1, "device-1", &[]);
+    assert!(verifier
+        .verify_client_cert(&other, &[], UnixTime::now())
+        .is_err());
+}
+
+#[test]
+fn test_client_cert_allowed_names() {
+    let dir = tempfile::tempdir().unwrap();
+    let ca = TestCa::new("Fleet CA");
+    let config = ClientAuthConfig {
+        allowed_names: vec!["ops-console".to_string(), "*.fleet.example.com".to_string()],
+        ..client_auth(dir.path(), &ca)
+    };
+    let verifier = client_cert_verifier(&config).unwrap();
+    let verify = |cert: CertificateDer<'_>| {
+        verifier
+            .verify_client_cert(&cert, &[], UnixTime::now())
+            .is_ok()
+    };
+
+    assert!(verify(ca.issue(1, "OPS-CONSOLE", &[])));
+    assert!(verify(ca.issue(
+        2,
+        "device-2",
+        &["device-2.fleet.example.com"]
+    )));
+    assert!(!verify(ca.issue(3, "device-3", &["a.b.fleet.example.com"])));
+    assert!(!verify(ca.issue(4, "laptop", &["laptop.example.com"])));
+}
+
+#[test]
+fn test_revoked_client_cert_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let ca = TestCa::new("Fleet CA");
+    let crl_path = dir.path().join("fleet.crl.pem");
+    fs::write(&crl_path, ca.crl(&[7])).unwrap();
+    let config = ClientAuthConfig {
+        crl_paths: vec![crl_path.to_string_lossy().into_owned()],
+        ..client_auth(dir.path(), &ca)
+    };
+    let verifier = client_cert_verifier(&config).unwrap();
+
+    let revoked = ca.issue(7, "device-7", &[]);
+    assert!(verifier
+        .verify_client_cert(&revoked, &[], UnixTime::now())
+        .is_err());
+    let valid = ca.issue(8, "device-8", &[]);
+    assert!(verifier
+        .verify_client_cert(&valid, &[], UnixTime::now())
+        .is_ok());
+}
+
+#[test]
+fn test_optional_client_auth() {
+    let dir = tempfile::tempdir().unwrap();
+    let ca = TestCa::new("Fleet CA");
+    let config = ClientAuthConfig {
+        optional: true,
+        ..client_auth(dir.path(), &ca)
+    };
+    let verifier = client_cert_verifier(&config).unwrap();
+    assert!(verifier.offer_client_auth());
+    assert!(!verifier.client_auth_mandatory());
+}
+
+#[test]
+fn test_client_auth_requires_ca_certificates() {
+    let dir = tempfile::tempdir().unwrap();
+    let ca_path = dir.path().join("empty.pem");
+    fs::write(&ca_path, "").unwrap();
+    let config = ClientAuthConfig {
+        enabled: true,
+        ca_path: ca_path.to_string_lossy().into_owned(),
+        ..Default::default()
+    };
+    assert!(client_cert_verifier(&config).is_err());
+
+    let config = ClientAuthConfig {
+        ca_path: dir
+            .path()
+            .join("missing.pem")
+            .to_string_lossy()
+            .into_owned(),
+        ..config
+    };
+    assert!(client_cert_verifier(&config).is_err());
+}
+
+#[tokio::test]
+async fn test_mtls_config_loading() {
+    let dir = tempfile::tempdir().unwrap();
+    let ca = TestCa::new("Fleet CA");
+    let server_key = KeyPair::generate().unwrap();
+    let server_cert = CertificateParams::new(vec!["localhost".to_string()])
+        .unwrap()
+        .self_signed(&server_key)
+        .unwrap();
+    let cert_path = dir.path().join("cert.pem");
+    let key_path = dir.path().join("key.pem");
+    fs::write(&cert_path, server_cert.pem()).unwrap();
+    fs::write(&key_path, server_key.serialize_pem()).unwrap();
+
+    let config = client_auth(dir.path(), &ca);
+    load_mtls_config(&cert_path, &key_path, &config)
+        .await
+        .expect("Failed to load mutual TLS config");
+
+    fs::write(&key_path, "").unwrap();
+    assert!(load_mtls_config(&cert_path, &key_path, &config)
+        .await
+        .is_err());
+}
@@ -0,0 +1,299 @@
+//! Integration tests for the tools of the gateway's agent runner
+
+use aisopod_channel::adapters::{
+    AccountSnapshot, ChannelConfigAdapter, OutboundAdapter, SecurityAdapter,
+};
+use aisopod_channel::message::{
+    IncomingMessage, Media, MessageContent, MessageTarget, PeerInfo, PeerKind, SenderInfo,
+};
+use aisopod_channel::types::ChatType;
+use aisopod_channel::{ChannelCapabilities, ChannelMeta, ChannelPlugin, ChannelRegistry};
+use aisopod_config::types::{AisopodConfig, McpServerConfig};
+use aisopod_gateway::ws::{create_agent_runner_with_tools, create_tool_registry};
+use aisopod_gateway::GatewayChannels;
+use aisopod_tools::{McpServer, NoOpJobScheduler, Tool, ToolContext, ToolRegistry, ToolResult};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+/// A channel recording the texts it was asked to deliver, with their peer
+struct RecordingChannel {
+    meta: ChannelMeta,
+    capabilities: ChannelCapabilities,
+    sent: Mutex<Vec<(String, String)>>,
+}
+
+impl RecordingChannel {
+    fn new() -> Self {
+        Self {
+            meta: ChannelMeta {
+                label: "Recording".to_string(),
+                docs_url: None,
+                ui_hints: serde_json::Value::Null,
+            },
+            capabilities: ChannelCapabilities {
+                chat_types: vec![ChatType::Dm],
+                supports_media: false,
+                supports_reactions: false,
+                supports_threads: false,
+                supports_typing: false,
+                supports_voice: false,
+                max_message_length: None,
+                supported_media_types: vec![],
+            },
+            sent: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn sent(&self) -> Vec<(String, String)> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+impl ChannelConfigAdapter for RecordingChannel {
+    fn list_accounts(&self) -> Result<Vec<String>, anyhow::Error> {
+        Ok(vec!["bot".to_string()])
+    }
+
+    fn resolve_account(&self, id: &str) -> Result<AccountSnapshot, anyhow::Error> {
+        Ok(AccountSnapshot {
+            id: id.to_string(),
+            channel: "recording".to_string(),
+            enabled: true,
+            connected: true,
+        })
+    }
+
+    fn enable_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    fn disable_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    fn delete_account(&self, _id: &str) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OutboundAdapter for RecordingChannel {
+    async fn send_text(&self, target: &MessageTarget, text: &str) -> Result<(), anyhow::Error> {
+        self.sent
+            .lock()
+            .unwrap()
+            .push((target.peer.id.clone(), text.to_string()));
+        Ok(())
+    }
+
+    async fn send_media(
+        &self,
+        _target: &MessageTarget,
+        _media: &Media,
+    ) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChannelPlugin for RecordingChannel {
+    fn id(&self) -> &str {
+        "recording"
+    }
+
+    fn meta(&self) -> &ChannelMeta {
+        &self.meta
+    }
+
+    fn capabilities(&self) -> &ChannelCapabilities {
+        &self.capabilities
+    }
+
+    fn config(&self) -> &dyn ChannelConfigAdapter {
+        self
+    }
+
+    fn security(&self) -> Option<&dyn SecurityAdapter> {
+        None
+    }
+
+    fn outbound(&self) -> Option<&dyn OutboundAdapter> {
+        Some(self)
+    }
+}
+
+/// Tool echoing its `text` parameter
+struct EchoTool;
+
+#[async_trait]
+impl Tool for EchoTool {
+    fn name(&self) -> &str {
+        "echo"
+    }
+
+    fn description(&self) -> &str {
+        "Echoes its input"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({"type": "object", "properties": {"text": {"type": "string"}}})
+    }
+
+    async fn execute(&self, params: Value, _ctx: &ToolContext) -> Result<ToolResult> {
+        Ok(ToolResult::success(
+            params["text"].as_str().unwrap_or_default(),
+        ))
+    }
+}
+
+#[tokio::test]
+async fn test_configured_mcp_server_tools_reach_the_runner() {
+    let mut exported = ToolRegistry::new();
+    exported.register(Arc::new(EchoTool));
+    let server =
+        Arc::new(McpServer::new(Arc::new(exported)).with_exported_tools(vec!["echo".to_string()]));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/sse", listener.local_addr().unwrap());
+    tokio::spawn(server.serve_sse(listener));
+
+    let mut config = AisopodConfig::default();
+    let server: McpServerConfig = serde_json::from_value(json!({
+        "name": "local",
+        "transport": "sse",
+        "url": url,
+        "timeout": 5
+    }))
+    .unwrap();
+    config.tools.mcp.servers.push(server);
+
+    let (tools, mcp_clients) = create_tool_registry(
+        &config,
+        Arc::new(NoOpJobScheduler::new()),
+        &GatewayChannels::default(),
+    )
+    .await;
+    assert_eq!(mcp_clients.len(), 1);
+
+    let runner = create_agent_runner_with_tools(Arc::new(config), Arc::new(tools), None, None);
+    assert!(runner.tools().get("mcp__local__echo").is_some());
+
+    let result = runner
+        .tools()
+        .execute(
+            "mcp__local__echo",
+            json!({"text": "through the gateway"}),
+            &ToolContext::new("default", "test"),
+        )
+        .await
+        .unwrap();
+    assert!(!result.is_error);
+    assert_eq!(result.content, "through the gateway");
+}
+
+#[tokio::test]
+async fn test_message_tool_sends_through_gateway_channels() {
+    let recording = Arc::new(RecordingChannel::new());
+    let mut registry = ChannelRegistry::new();
+    registry.register(recording.clone());
+    let channels = GatewayChannels::new(Arc::new(RwLock::new(registry)));
+
+    let config = AisopodConfig::default();
+    let (tools, _mcp_clients) =
+        create_tool_registry(&config, Arc::new(NoOpJobScheduler::new()), &channels).await;
+
+    let result = tools
+        .execute(
+            "message",
+            json!({
+                "channel": "recording",
+                "content": "hello from the agent",
+                "account": "bot",
+                "peer": "user:alice"
+            }),
+            &ToolContext::new("default", "test"),
+        )
+        .await
+        .unwrap();
+    assert!(!result.is_error, "{}", result.content);
+    assert_eq!(
+        recording.sent(),
+        vec![("alice".to_string(), "hello from the agent".to_string())]
+    );
+}
+
+#[tokio::test]
+async fn test_agent_tool_operations_are_approved_through_gateway_channels() {
+    let recording = Arc::new(RecordingChannel::new());
+    let mut registry = ChannelRegistry::new();
+    registry.register(recording.clone());
+
+    let mut config = AisopodConfig::default();
+    config.tools.approval.channel = Some("recording".to_string());
+    config.tools.approval.account = Some("bot".to_string());
+    config.tools.approval.peer = Some("user:ops".to_string());
+    config.tools.approval.operators = vec!["alice".to_string()];
+    let channels = GatewayChannels::new(Arc::new(RwLock::new(registry)))
+        .with_approvals(&config.tools.approval);
+    let approvals = channels.approval_handler().unwrap().clone();
+
+    let (tools, _mcp_clients) =
+        create_tool_registry(&config, Arc::new(NoOpJobScheduler::new()), &channels).await;
+    let runner = create_agent_runner_with_tools(Arc::new(config), Arc::new(tools), None, None);
+
+    let dir = tempfile::tempdir().unwrap();
+    let marker = dir.path().join("marker");
+    let command = format!("touch {}", marker.display());
+    let running = tokio::spawn({
+        let runner = runner.clone();
+        async move {
+            runner
+                .tools()
+                .execute(
+                    "bash",
+                    json!({ "command": command }),
+                    &ToolContext::new("default", "test"),
+                )
+                .await
+        }
+    });
+
+    for _ in 0..100 {
+        if approvals.pending_count() == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(approvals.pending_count(), 1);
+    let sent = recording.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].0, "ops");
+    assert!(sent[0].1.contains("touch"));
+    assert!(!marker.exists());
+
+    let reply = IncomingMessage {
+        id: "msg-1".to_string(),
+        channel: "recording".to_string(),
+        account_id: "bot".to_string(),
+        sender: SenderInfo {
+            id: "alice".to_string(),
+            ..Default::default()
+        },
+        peer: PeerInfo {
+            id: "ops".to_string(),
+            kind: PeerKind::User,
+            title: None,
+        },
+        content: MessageContent::Text("approve 1".to_string()),
+        reply_to: None,
+        timestamp: chrono::Utc::now(),
+        metadata: Value::Null,
+    };
+    assert!(approvals.handle_incoming(&reply));
+
+    let result = running.await.unwrap().unwrap();
+    assert!(!result.is_error, "{}", result.content);
+    assert!(marker.exists());
+}
@@ -0,0 +1,106 @@
+//! Memory store selection from configuration.
+//!
+//! [`store_from_config`] opens the [`MemoryStore`] named by the `backend`
+//! section of the memory configuration. Backends other than SQLite are
+//! behind cargo features and report an error when the feature is disabled.
+
+use std::sync::Arc;
+
+use aisopod_config::types::MemoryConfig;
+use anyhow::{anyhow, Result};
+
+use crate::embedding::EmbeddingProvider;
+use crate::sqlite::SqliteMemoryStore;
+use crate::store::MemoryStore;
+
+/// Path of the SQLite database when no connection is configured.
+const DEFAULT_SQLITE_PATH: &str = "memory.db";
+
+/// Opens the memory store selected by `config.backend`.
+///
+/// The backend type is one of `sqlite` (the default when empty), `lancedb`,
+/// `postgres` (alias `pgvector`) or `qdrant`. Embeddings have the dimension
+/// of `embedder`.
+///
+/// # Errors
+/// Returns an error if the backend type is unknown, its cargo feature is
+/// disabled, or the store cannot be opened.
+pub async fn store_from_config(
+    config: &MemoryConfig,
+    embedder: Arc<dyn EmbeddingProvider>,
+) -> Result<Arc<dyn MemoryStore>> {
+    let backend = &config.backend;
+    let dim = embedder.dimensions();
+
+    match backend.r#type.as_str() {
+        "" | "sqlite" => {
+            let path = if backend.connection.is_empty() {
+                DEFAULT_SQLITE_PATH
+            } else {
+                &backend.connection
+            };
+            Ok(Arc::new(SqliteMemoryStore::new_with_embedder(
+                path, dim, embedder,
+            )?))
+        }
+        #[cfg(feature = "lancedb")]
+        "lancedb" => Ok(Arc::new(
+            crate::lancedb::LanceDbMemoryStore::new(&backend.connection, dim).await?,
+        )),
+        #[cfg(feature = "postgres")]
+        "postgres" | "pgvector" => {
+            let pg_config = crate::pgvector::PgVectorConfig::new(&backend.connection);
+            Ok(Arc::new(
+                crate::pgvector::PgVectorMemoryStore::connect(pg_config, dim, embedder).await?,
+            ))
+        }
+        #[cfg(feature = "qdrant")]
+        "qdrant" => {
+            let mut qdrant_config = crate::qdrant::QdrantConfig::new(&backend.connection);
+            if !backend.database.is_empty() {
+                qdrant_config.collection = backend.database.clone();
+            }
+            qdrant_config.api_key = backend.api_key.as_ref().map(|key| key.expose().clone());
+            Ok(Arc::new(
+                crate::qdrant::QdrantMemoryStore::new(qdrant_config, dim, embedder).await?,
+            ))
+        }
+        #[cfg(not(feature = "lancedb"))]
+        "lancedb" => Err(feature_disabled("lancedb", "lancedb")),
+        #[cfg(not(feature = "postgres"))]
+        name @ ("postgres" | "pgvector") => Err(feature_disabled(name, "postgres")),
+        #[cfg(not(feature = "qdrant"))]
+        "qdrant" => Err(feature_disabled("qdrant", "qdrant")),
+        other => Err(anyhow!("Unknown memory backend '{}'", other)),
+    }
+}
+
+#[cfg(not(all(feature = "lancedb", feature = "postgres", feature = "qdrant")))]
+fn feature_disabled(backend: &str, feature: &str) -> anyhow::Error {
+    anyhow!(
+        "Memory backend '{}' requires aisopod-memory to be built with the '{}' feature",
+        backend,
+        feature
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockEmbeddingProvider;
+
+    #[tokio::test]
+    async fn test_store_from_config_selects_backend() {
+        let dir = tempfile::tempdir().unwrap();
+        let embedder: Arc<dyn EmbeddingProvider> = Arc::new(MockEmbeddingProvider::new(4));
+
+        let mut config = MemoryConfig::default();
+        config.backend.connection = dir.path().join("memory.db").display().to_string();
+        let store = store_from_config(&config, embedder.clone()).await.unwrap();
+        assert!(store.as_any().is::<SqliteMemoryStore>());
+
+        config.backend.r#type = "mongodb".to_string();
+        let err = store_from_config(&config, embedder).await.err().unwrap();
+        assert_eq!(err.to_string(), "Unknown memory backend 'mongodb'");
+    }
+}
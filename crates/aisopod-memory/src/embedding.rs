@@ -1,7 +1,66 @@
 //! Embedding provider trait and implementations.
+//!
+//! The [`EmbeddingProvider`] trait and its backends live in `aisopod-provider`,
+//! where embedding providers are registered in the [`ProviderRegistry`]
+//! alongside chat providers. This module re-exports them, resolves the
+//! embedder used for memory from the registry, and provides a mock for tests.
+
+use std::sync::Arc;
+
+use aisopod_provider::ProviderRegistry;
+use anyhow::{anyhow, Result};
 
 pub mod mock;
-pub mod openai;
 
+pub use aisopod_provider::embedding::{
+    EmbeddingProvider, GeminiEmbeddingProvider, OllamaEmbeddingProvider, OpenAIEmbeddingProvider,
+};
+#[cfg(feature = "local-embeddings")]
+pub use aisopod_provider::embedding::{
+    LocalCrossEncoder, LocalEmbeddingModel, LocalEmbeddingProvider,
+};
 pub use mock::MockEmbeddingProvider;
-pub use openai::{EmbeddingProvider, OpenAiEmbeddingProvider};
+
+/// The OpenAI embedding provider under its former name.
+#[deprecated(note = "use `OpenAIEmbeddingProvider` from `aisopod-provider` instead")]
+pub type OpenAiEmbeddingProvider = OpenAIEmbeddingProvider;
+
+/// Looks up the embedding provider registered under `name`.
+///
+/// # Errors
+/// Returns an error if no embedding provider is registered under `name`.
+pub fn embedder_from_registry(
+    registry: &ProviderRegistry,
+    name: &str,
+) -> Result<Arc<dyn EmbeddingProvider>> {
+    registry
+        .get_embedder(name)
+        .ok_or_else(|| anyhow!("No embedding provider registered as '{}'", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_former_openai_name() {
+        let provider = OpenAiEmbeddingProvider::new("key".to_string(), None, None);
+        assert_eq!(provider.dimensions(), 1536);
+    }
+
+    #[test]
+    fn test_embedder_from_registry() {
+        let mut registry = ProviderRegistry::new();
+        registry.register_embedder("mock", Arc::new(MockEmbeddingProvider::new(4)));
+
+        let embedder = embedder_from_registry(&registry, "mock").unwrap();
+        assert_eq!(embedder.dimensions(), 4);
+
+        let err = embedder_from_registry(&registry, "openai").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "No embedding provider registered as 'openai'"
+        );
+    }
+}
@@ -0,0 +1,731 @@
+//! Entity and relationship graph memory.
+//!
+//! Vector search finds memories that sound like the question, but it cannot
+//! chain facts: "who is Carol relative to Alice" may need one memory saying
+//! Carol reports to Bob and another saying Bob is Alice's brother. This
+//! module keeps a small graph beside the vector store. An
+//! [`EntityExtractor`] pulls entities, attributes and relations out of each
+//! memory as it is stored, a [`GraphStore`] persists them per namespace,
+//! and [`GraphMemory`] answers queries with both the similar memories and
+//! the relations connecting the entities the query names.
+
+use std::any::Any;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{ChatCompletionRequest, Message, MessageContent, Role};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::keyword::tokenize;
+use crate::store::MemoryStore;
+use crate::types::{MemoryEntry, MemoryMatch, MemoryQueryOptions};
+
+/// A person, place, organization or thing memories talk about.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entity {
+    /// Name of the entity; names are matched case-insensitively.
+    pub name: String,
+    /// Kind of entity, such as "person" or "project".
+    #[serde(default, rename = "type")]
+    pub kind: Option<String>,
+    /// Attributes of the entity, such as a role or an email address.
+    #[serde(default)]
+    pub attributes: HashMap<String, String>,
+}
+
+/// A directed relation between two entities, such as "Carol reports to Bob".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Relation {
+    /// Name of the entity the relation starts from.
+    pub subject: String,
+    /// The relation, phrased so that "subject predicate object" reads as a
+    /// sentence.
+    pub predicate: String,
+    /// Name of the entity the relation points to.
+    pub object: String,
+    /// ID of the memory the relation was extracted from.
+    #[serde(default)]
+    pub memory_id: String,
+}
+
+impl std::fmt::Display for Relation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -[{}]-> {}",
+            self.subject, self.predicate, self.object
+        )
+    }
+}
+
+/// Entities and relations extracted from one memory.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GraphFacts {
+    /// Entities mentioned, with any attributes stated about them.
+    #[serde(default)]
+    pub entities: Vec<Entity>,
+    /// Relations stated between entities.
+    #[serde(default)]
+    pub relations: Vec<Relation>,
+}
+
+/// Extracts entities and relations from memory content.
+#[async_trait]
+pub trait EntityExtractor: Send + Sync {
+    /// Extracts the entities and relations stated in `content`.
+    ///
+    /// # Errors
+    /// Returns an error if extraction fails; the memory is then stored
+    /// without graph facts.
+    async fn extract(&self, content: &str) -> Result<GraphFacts>;
+}
+
+/// Entity extractor asking a chat model for the facts as JSON.
+pub struct LlmEntityExtractor {
+    provider: Arc<dyn ModelProvider>,
+    model: String,
+}
+
+impl LlmEntityExtractor {
+    /// Creates an extractor using `model` on `provider`.
+    pub fn new(provider: Arc<dyn ModelProvider>, model: impl Into<String>) -> Self {
+        Self {
+            provider,
+            model: model.into(),
+        }
+    }
+
+    /// Builds the extraction prompt for `content`.
+    fn prompt(content: &str) -> String {
+        format!(
+            "Extract the entities and relations stated in the text below.\n\n\
+             Text: {}\n\n\
+             Reply with only a JSON object of the form \
+             {{\"entities\": [{{\"name\": \"Alice\", \"type\": \"person\", \
+             \"attributes\": {{\"role\": \"engineer\"}}}}], \
+             \"relations\": [{{\"subject\": \"Alice\", \"predicate\": \"is the sister of\", \
+             \"object\": \"Bob\"}}]}}. Use empty lists if there are none.",
+            content.replace('\n', " ")
+        )
+    }
+
+    /// Parses the model's facts. The object may be wrapped in prose or a
+    /// code fence.
+    fn parse_facts(response: &str) -> Result<GraphFacts> {
+        let (start, end) = response
+            .find('{')
+            .zip(response.rfind('}'))
+            .filter(|(start, end)| start < end)
+            .ok_or_else(|| anyhow!("Entity extractor response contains no JSON object"))?;
+        Ok(serde_json::from_str(&response[start..=end])?)
+    }
+}
+
+#[async_trait]
+impl EntityExtractor for LlmEntityExtractor {
+    async fn extract(&self, content: &str) -> Result<GraphFacts> {
+        let request = ChatCompletionRequest {
+            model: self.model.clone(),
+            messages: vec![Message {
+                role: Role::User,
+                content: MessageContent::Text(Self::prompt(content)),
+                tool_calls: None,
+                tool_call_id: None,
+            }],
+            tools: None,
+            temperature: Some(0.0),
+            max_tokens: None,
+            stop: None,
+            stream: false,
+        };
+
+        let mut stream = self.provider.chat_completion(request).await?;
+        let mut response = String::new();
+        while let Some(chunk) = stream.next().await {
+            if let Some(content) = chunk?.delta.content {
+                response.push_str(&content);
+            }
+        }
+        Self::parse_facts(&response)
+    }
+}
+
+/// Trait for persisting the entity graph.
+///
+/// Entities and relations are scoped by namespace like memories are, so a
+/// graph shared between agents or tenants never connects their facts.
+#[async_trait]
+pub trait GraphStore: Send + Sync {
+    /// Records the facts extracted from memory `memory_id`.
+    ///
+    /// Entities already known are updated: new attributes are added and
+    /// restated ones overwritten.
+    async fn add_facts(&self, namespace: &str, memory_id: &str, facts: &GraphFacts) -> Result<()>;
+
+    /// Removes the relations extracted from memory `memory_id`.
+    ///
+    /// Entities are kept, since other memories may mention them.
+    async fn remove_memory(&self, memory_id: &str) -> Result<()>;
+
+    /// Lists the entities of a namespace.
+    async fn entities(&self, namespace: &str) -> Result<Vec<Entity>>;
+
+    /// Lists the relations of a namespace starting from or pointing to
+    /// `entity`.
+    async fn relations(&self, namespace: &str, entity: &str) -> Result<Vec<Relation>>;
+
+    /// Finds the shortest chain of relations connecting `from` and `to`.
+    ///
+    /// Relations are followed in either direction, so the chain explains
+    /// how the two entities are related whichever way the facts were
+    /// phrased. The default implementation runs a breadth-first search over
+    /// [`relations`](GraphStore::relations).
+    ///
+    /// # Returns
+    /// Returns the relations along the path in order from `from`, or `None`
+    /// if the entities are not connected within `max_hops` relations.
+    async fn find_path(
+        &self,
+        namespace: &str,
+        from: &str,
+        to: &str,
+        max_hops: usize,
+    ) -> Result<Option<Vec<Relation>>> {
+        let target = to.to_lowercase();
+        let mut visited = HashSet::from([from.to_lowercase()]);
+        let mut queue = VecDeque::from([(from.to_string(), Vec::<Relation>::new())]);
+
+        while let Some((entity, path)) = queue.pop_front() {
+            if path.len() >= max_hops {
+                continue;
+            }
+            let key = entity.to_lowercase();
+            for relation in self.relations(namespace, &entity).await? {
+                let next = if relation.subject.to_lowercase() == key {
+                    relation.object.clone()
+                } else {
+                    relation.subject.clone()
+                };
+                let key = next.to_lowercase();
+                if !visited.insert(key.clone()) {
+                    continue;
+                }
+                let mut path = path.clone();
+                path.push(relation);
+                if key == target {
+                    return Ok(Some(path));
+                }
+                queue.push_back((next, path));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns a reference to self as `Any` for downcasting.
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// Graph store backed by SQLite.
+///
+/// This struct manages two tables:
+/// - `graph_entities`: Entities with their kind and attributes
+/// - `graph_relations`: Relations with the memory they were extracted from
+pub struct SqliteGraphStore {
+    db: Arc<Mutex<Connection>>,
+}
+
+impl SqliteGraphStore {
+    /// Opens or creates a graph store at the given path.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the SQLite database file (use `:memory:` for in-memory DB);
+    ///   may be the database of a `SqliteMemoryStore`
+    ///
+    /// # Returns
+    /// Returns a new `SqliteGraphStore` or an error if initialization fails.
+    pub fn new(path: &str) -> Result<Self> {
+        let db = Connection::open(path)?;
+        db.execute_batch(
+            "CREATE TABLE IF NOT EXISTS graph_entities (
+                namespace TEXT NOT NULL,
+                key TEXT NOT NULL,
+                name TEXT NOT NULL,
+                kind TEXT,
+                attributes TEXT NOT NULL DEFAULT '{}',
+                PRIMARY KEY (namespace, key)
+            );
+            CREATE TABLE IF NOT EXISTS graph_relations (
+                namespace TEXT NOT NULL,
+                subject TEXT NOT NULL,
+                subject_key TEXT NOT NULL,
+                predicate TEXT NOT NULL,
+                object TEXT NOT NULL,
+                object_key TEXT NOT NULL,
+                memory_id TEXT NOT NULL,
+                UNIQUE (namespace, subject_key, predicate, object_key, memory_id)
+            );
+            CREATE INDEX IF NOT EXISTS idx_graph_relations_subject ON graph_relations(namespace, subject_key);
+            CREATE INDEX IF NOT EXISTS idx_graph_relations_object ON graph_relations(namespace, object_key);
+            CREATE INDEX IF NOT EXISTS idx_graph_relations_memory ON graph_relations(memory_id);",
+        )?;
+        Ok(Self {
+            db: Arc::new(Mutex::new(db)),
+        })
+    }
+
+    fn upsert_entity(db: &Connection, namespace: &str, entity: &Entity) -> Result<()> {
+        let key = entity.name.to_lowercase();
+        let existing: Option<String> = db
+            .query_row(
+                "SELECT attributes FROM graph_entities WHERE namespace = ? AND key = ?",
+                rusqlite::params![namespace, key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let mut attributes: HashMap<String, String> = existing
+            .map(|json| serde_json::from_str(&json))
+            .transpose()?
+            .unwrap_or_default();
+        attributes.extend(entity.attributes.clone());
+
+        db.execute(
+            "INSERT INTO graph_entities (namespace, key, name, kind, attributes)
+             VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT(namespace, key) DO UPDATE SET
+                kind = COALESCE(excluded.kind, kind),
+                attributes = excluded.attributes",
+            rusqlite::params![
+                namespace,
+                key,
+                entity.name,
+                entity.kind,
+                serde_json::to_string(&attributes)?
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl GraphStore for SqliteGraphStore {
+    async fn add_facts(&self, namespace: &str, memory_id: &str, facts: &GraphFacts) -> Result<()> {
+        let mut db = self.db.lock().map_err(|e| anyhow!(e.to_string()))?;
+        let tx = db.transaction()?;
+
+        for entity in &facts.entities {
+            Self::upsert_entity(&tx, namespace, entity)?;
+        }
+        for relation in &facts.relations {
+            // Relations may name entities the extractor did not list
+            for name in [&relation.subject, &relation.object] {
+                tx.execute(
+                    "INSERT OR IGNORE INTO graph_entities (namespace, key, name) VALUES (?, ?, ?)",
+                    rusqlite::params![namespace, name.to_lowercase(), name],
+                )?;
+            }
+            tx.execute(
+                "INSERT OR IGNORE INTO graph_relations
+                    (namespace, subject, subject_key, predicate, object, object_key, memory_id)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                rusqlite::params![
+                    namespace,
+                    relation.subject,
+                    relation.subject.to_lowercase(),
+                    relation.predicate,
+                    relation.object,
+                    relation.object.to_lowercase(),
+                    memory_id
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn remove_memory(&self, memory_id: &str) -> Result<()> {
+        let db = self.db.lock().map_err(|e| anyhow!(e.to_string()))?;
+        db.execute(
+            "DELETE FROM graph_relations WHERE memory_id = ?",
+            rusqlite::params![memory_id],
+        )?;
+        Ok(())
+    }
+
+    async fn entities(&self, namespace: &str) -> Result<Vec<Entity>> {
+        let db = self.db.lock().map_err(|e| anyhow!(e.to_string()))?;
+        let mut stmt = db.prepare(
+            "SELECT name, kind, attributes FROM graph_entities WHERE namespace = ? ORDER BY key",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![namespace], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+
+        let mut entities = Vec::new();
+        for row in rows {
+            let (name, kind, attributes) = row?;
+            entities.push(Entity {
+                name,
+                kind,
+                attributes: serde_json::from_str(&attributes)?,
+            });
+        }
+        Ok(entities)
+    }
+
+    async fn relations(&self, namespace: &str, entity: &str) -> Result<Vec<Relation>> {
+        let db = self.db.lock().map_err(|e| anyhow!(e.to_string()))?;
+        let mut stmt = db.prepare(
+            "SELECT subject, predicate, object, memory_id FROM graph_relations
+             WHERE namespace = ?1 AND (subject_key = ?2 OR object_key = ?2)
+             ORDER BY rowid",
+        )?;
+        let relations = stmt
+            .query_map(rusqlite::params![namespace, entity.to_lowercase()], |row| {
+                Ok(Relation {
+                    subject: row.get(0)?,
+                    predicate: row.get(1)?,
+                    object: row.get(2)?,
+                    memory_id: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(relations)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Result of a combined vector and graph query.
+#[derive(Debug, Clone, Default)]
+pub struct GraphQueryResult {
+    /// Memories similar to the query.
+    pub matches: Vec<MemoryMatch>,
+    /// Known entities named in the query.
+    pub entities: Vec<Entity>,
+    /// Relations of the named entities.
+    pub relations: Vec<Relation>,
+    /// Shortest chains of relations between each pair of named entities
+    /// that are connected.
+    pub paths: Vec<Vec<Relation>>,
+}
+
+impl GraphQueryResult {
+    /// Formats the result as text for the agent's context.
+    pub fn to_context(&self) -> String {
+        let mut sections = Vec::new();
+
+        if !self.paths.is_empty() {
+            let paths: Vec<String> = self
+                .paths
+                .iter()
+                .map(|path| {
+                    let steps: Vec<String> = path.iter().map(Relation::to_string).collect();
+                    format!("- {}", steps.join("; "))
+                })
+                .collect();
+            sections.push(format!("## How they are related\n{}", paths.join("\n")));
+        }
+        if !self.relations.is_empty() {
+            let relations: Vec<String> =
+                self.relations.iter().map(|r| format!("- {}", r)).collect();
+            sections.push(format!("## Known relations\n{}", relations.join("\n")));
+        }
+        let described: Vec<String> = self
+            .entities
+            .iter()
+            .filter(|e| e.kind.is_some() || !e.attributes.is_empty())
+            .map(|e| {
+                let mut attributes: Vec<String> = e
+                    .attributes
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", key, value))
+                    .collect();
+                attributes.sort();
+                if let Some(kind) = &e.kind {
+                    attributes.insert(0, kind.clone());
+                }
+                format!("- {} ({})", e.name, attributes.join(", "))
+            })
+            .collect();
+        if !described.is_empty() {
+            sections.push(format!("## Entities\n{}", described.join("\n")));
+        }
+        if !self.matches.is_empty() {
+            let memories: Vec<String> = self
+                .matches
+                .iter()
+                .map(|m| format!("- {}", m.entry.content))
+                .collect();
+            sections.push(format!("## Related memories\n{}", memories.join("\n")));
+        }
+
+        sections.join("\n\n")
+    }
+}
+
+/// Memory store with an entity graph kept alongside the vector index.
+///
+/// Memories are stored in the wrapped [`MemoryStore`] as usual, and the
+/// facts the extractor finds in them are recorded in the [`GraphStore`]
+/// under the memory's namespace.
+pub struct GraphMemory {
+    store: Arc<dyn MemoryStore>,
+    graph: Arc<dyn GraphStore>,
+    extractor: Arc<dyn EntityExtractor>,
+    max_hops: usize,
+}
+
+impl GraphMemory {
+    /// Creates a graph memory over `store` and `graph`, extracting facts
+    /// with `extractor`.
+    pub fn new(
+        store: Arc<dyn MemoryStore>,
+        graph: Arc<dyn GraphStore>,
+        extractor: Arc<dyn EntityExtractor>,
+    ) -> Self {
+        Self {
+            store,
+            graph,
+            extractor,
+            max_hops: 3,
+        }
+    }
+
+    /// Sets the longest chain of relations followed to connect two
+    /// entities (3 by default).
+    pub fn with_max_hops(mut self, max_hops: usize) -> Self {
+        self.max_hops = max_hops;
+        self
+    }
+
+    /// Gets a reference to the memory store.
+    pub fn store(&self) -> &Arc<dyn MemoryStore> {
+        &self.store
+    }
+
+    /// Gets a reference to the graph store.
+    pub fn graph(&self) -> &Arc<dyn GraphStore> {
+        &self.graph
+    }
+
+    /// Stores a memory and records the entities and relations it states.
+    ///
+    /// Facts previously extracted from the same memory ID are replaced. If
+    /// extraction fails the memory is still stored, without graph facts.
+    ///
+    /// # Returns
+    /// Returns the ID of the stored entry.
+    ///
+    /// # Errors
+    /// Returns an error if storing the memory or its facts fails.
+    pub async fn store_memory(&self, entry: MemoryEntry) -> Result<String> {
+        let namespace = entry.namespace.clone();
+        let content = entry.content.clone();
+        let id = self.store.store(entry).await?;
+
+        self.graph.remove_memory(&id).await?;
+        match self.extractor.extract(&content).await {
+            Ok(facts) => self.graph.add_facts(&namespace, &id, &facts).await?,
+            Err(e) => warn!("Failed to extract entities from memory {}: {}", id, e),
+        }
+        Ok(id)
+    }
+
+    /// Deletes a memory and the relations extracted from it.
+    ///
+    /// # Errors
+    /// Returns an error if deletion fails.
+    pub async fn delete_memory(&self, id: &str) -> Result<()> {
+        self.store.delete(id).await?;
+        self.graph.remove_memory(id).await
+    }
+
+    /// Explains how entity `x` is related to entity `y`.
+    ///
+    /// # Returns
+    /// Returns the shortest chain of relations from `x` to `y`, or `None` if
+    /// they are not connected within the hop limit.
+    ///
+    /// # Errors
+    /// Returns an error if the graph lookup fails.
+    pub async fn relate(&self, namespace: &str, x: &str, y: &str) -> Result<Option<Vec<Relation>>> {
+        self.graph.find_path(namespace, x, y, self.max_hops).await
+    }
+
+    /// Searches memories and the entity graph together.
+    ///
+    /// The vector search runs as with [`MemoryStore::query`]. Entities named
+    /// in the query are looked up in the namespace selected by the filter
+    /// (the default namespace if unset), with their relations and the
+    /// shortest paths connecting each pair of them.
+    ///
+    /// # Errors
+    /// Returns an error if the vector search or a graph lookup fails.
+    pub async fn query(&self, query: &str, opts: MemoryQueryOptions) -> Result<GraphQueryResult> {
+        let namespace = opts.filter.namespace.clone().unwrap_or_default();
+        let matches = self.store.query(query, opts).await?;
+
+        let query_terms = tokenize(query);
+        let entities: Vec<Entity> = self
+            .graph
+            .entities(&namespace)
+            .await?
+            .into_iter()
+            .filter(|e| {
+                let name_terms = tokenize(&e.name);
+                !name_terms.is_empty()
+                    && query_terms
+                        .windows(name_terms.len())
+                        .any(|window| window == name_terms.as_slice())
+            })
+            .collect();
+
+        let mut relations: Vec<Relation> = Vec::new();
+        for entity in &entities {
+            for relation in self.graph.relations(&namespace, &entity.name).await? {
+                if !relations.contains(&relation) {
+                    relations.push(relation);
+                }
+            }
+        }
+
+        let mut paths = Vec::new();
+        for (i, from) in entities.iter().enumerate() {
+            for to in &entities[i + 1..] {
+                if let Some(path) = self
+                    .graph
+                    .find_path(&namespace, &from.name, &to.name, self.max_hops)
+                    .await?
+                {
+                    paths.push(path);
+                }
+            }
+        }
+
+        Ok(GraphQueryResult {
+            matches,
+            entities,
+            relations,
+            paths,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relation(subject: &str, predicate: &str, object: &str) -> Relation {
+        Relation {
+            subject: subject.to_string(),
+            predicate: predicate.to_string(),
+            object: object.to_string(),
+            memory_id: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_facts() {
+        let facts = LlmEntityExtractor::parse_facts(
+            "```json\n{\"entities\": [{\"name\": \"Bob\", \"type\": \"person\", \
+             \"attributes\": {\"team\": \"infra\"}}], \
+             \"relations\": [{\"subject\": \"Carol\", \"predicate\": \"reports to\", \
+             \"object\": \"Bob\"}]}\n```",
+        )
+        .unwrap();
+        assert_eq!(facts.entities[0].kind.as_deref(), Some("person"));
+        assert_eq!(facts.entities[0].attributes["team"], "infra");
+        assert_eq!(
+            facts.relations,
+            vec![relation("Carol", "reports to", "Bob")]
+        );
+
+        assert!(LlmEntityExtractor::parse_facts("nothing here").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_find_path_follows_relations_both_ways() {
+        let graph = SqliteGraphStore::new(":memory:").unwrap();
+        let facts = GraphFacts {
+            entities: Vec::new(),
+            relations: vec![
+                relation("Carol", "reports to", "Bob"),
+                relation("Bob", "is the brother of", "Alice"),
+                relation("Alice", "lives in", "Lisbon"),
+            ],
+        };
+        graph.add_facts("", "m1", &facts).await.unwrap();
+
+        let path = graph
+            .find_path("", "alice", "CAROL", 3)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            path.iter().map(|r| r.to_string()).collect::<Vec<_>>(),
+            vec![
+                "Bob -[is the brother of]-> Alice",
+                "Carol -[reports to]-> Bob"
+            ]
+        );
+        assert!(graph
+            .find_path("", "Carol", "Lisbon", 2)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(graph
+            .find_path("other", "Carol", "Bob", 3)
+            .await
+            .unwrap()
+            .is_none());
+
+        graph.remove_memory("m1").await.unwrap();
+        assert!(graph.relations("", "Bob").await.unwrap().is_empty());
+        assert_eq!(graph.entities("").await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_entity_attributes_merge() {
+        let graph = SqliteGraphStore::new(":memory:").unwrap();
+        let entity = |attributes: &[(&str, &str)], kind: Option<&str>| Entity {
+            name: "Bob".to_string(),
+            kind: kind.map(str::to_string),
+            attributes: attributes
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        };
+        for (memory_id, e) in [
+            ("m1", entity(&[("team", "infra")], Some("person"))),
+            (
+                "m2",
+                entity(&[("team", "platform"), ("city", "Oslo")], None),
+            ),
+        ] {
+            let facts = GraphFacts {
+                entities: vec![e],
+                relations: Vec::new(),
+            };
+            graph.add_facts("", memory_id, &facts).await.unwrap();
+        }
+
+        let entities = graph.entities("").await.unwrap();
+        assert_eq!(entities.len(), 1);
+        assert_eq!(entities[0].kind.as_deref(), Some("person"));
+        assert_eq!(entities[0].attributes["team"], "platform");
+        assert_eq!(entities[0].attributes["city"], "Oslo");
+    }
+}
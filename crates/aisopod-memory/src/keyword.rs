@@ -0,0 +1,194 @@
+//! Keyword retrieval and rank fusion for hybrid memory search.
+//!
+//! Embeddings are good at paraphrases but often miss exact names, IDs and
+//! code identifiers. This module provides the pieces of a keyword stage that
+//! catches those: a tokenizer that keeps identifiers such as `retry_after`
+//! whole, Okapi BM25 ranking, and reciprocal rank fusion to merge the keyword
+//! ranking with the vector ranking.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::types::{MemoryEntry, MemoryMatch};
+
+/// BM25 term frequency saturation.
+const BM25_K1: f32 = 1.2;
+
+/// BM25 document length normalization.
+const BM25_B: f32 = 0.75;
+
+/// Splits text into lowercase terms.
+///
+/// Terms are runs of alphanumeric characters and underscores, so snake_case
+/// identifiers stay whole while punctuation, dots and dashes separate terms.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Ranks `entries` against `query` with Okapi BM25.
+///
+/// Entries sharing no term with the query are dropped. The score of each
+/// match is its BM25 score, so it is only comparable within one ranking.
+///
+/// # Returns
+/// Returns the matching entries sorted by score descending, at most `top_k`.
+pub fn bm25_rank(query: &str, entries: Vec<MemoryEntry>, top_k: usize) -> Vec<MemoryMatch> {
+    let query_terms: HashSet<String> = tokenize(query).into_iter().collect();
+    if query_terms.is_empty() || entries.is_empty() {
+        return Vec::new();
+    }
+
+    let documents: Vec<Vec<String>> = entries.iter().map(|e| tokenize(&e.content)).collect();
+    let count = documents.len() as f32;
+    let avg_len = documents.iter().map(Vec::len).sum::<usize>() as f32 / count;
+
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for document in &documents {
+        let unique: HashSet<&str> = document.iter().map(String::as_str).collect();
+        for term in unique.into_iter().filter(|t| query_terms.contains(*t)) {
+            *document_frequency.entry(term).or_default() += 1;
+        }
+    }
+
+    let mut matches: Vec<MemoryMatch> = entries
+        .into_iter()
+        .zip(&documents)
+        .filter_map(|(entry, document)| {
+            let len = document.len() as f32;
+            let score: f32 = query_terms
+                .iter()
+                .filter_map(|term| {
+                    let df = *document_frequency.get(term.as_str())? as f32;
+                    let tf = document.iter().filter(|t| *t == term).count() as f32;
+                    if tf == 0.0 {
+                        return None;
+                    }
+                    let idf = ((count - df + 0.5) / (df + 0.5) + 1.0).ln();
+                    Some(
+                        idf * tf * (BM25_K1 + 1.0)
+                            / (tf + BM25_K1 * (1.0 - BM25_B + BM25_B * len / avg_len.max(1.0))),
+                    )
+                })
+                .sum();
+            (score > 0.0).then_some(MemoryMatch { entry, score })
+        })
+        .collect();
+
+    sort_by_score(&mut matches);
+    matches.truncate(top_k);
+    matches
+}
+
+/// Merges rankings with weighted reciprocal rank fusion.
+///
+/// Each entry scores `weight / (k + rank)` summed over the rankings it
+/// appears in, with ranks starting at 1. Scores are normalized by the best
+/// possible score, so an entry ranked first everywhere scores 1.0.
+///
+/// # Arguments
+/// * `rankings` - Each ranking with its weight, best match first
+/// * `k` - Rank offset damping the influence of the top ranks (commonly 60)
+///
+/// # Returns
+/// Returns the fused matches sorted by score descending.
+pub fn reciprocal_rank_fusion(rankings: Vec<(Vec<MemoryMatch>, f32)>, k: f32) -> Vec<MemoryMatch> {
+    let best = rankings.iter().map(|(_, weight)| weight).sum::<f32>() / (k + 1.0);
+    let mut fused: Vec<MemoryMatch> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for (ranking, weight) in rankings {
+        for (rank, m) in ranking.into_iter().enumerate() {
+            let contribution = weight / (k + rank as f32 + 1.0);
+            match positions.get(&m.entry.id) {
+                Some(&position) => fused[position].score += contribution,
+                None => {
+                    positions.insert(m.entry.id.clone(), fused.len());
+                    fused.push(MemoryMatch {
+                        entry: m.entry,
+                        score: contribution,
+                    });
+                }
+            }
+        }
+    }
+
+    if best > 0.0 {
+        for m in &mut fused {
+            m.score /= best;
+        }
+    }
+    sort_by_score(&mut fused);
+    fused
+}
+
+fn sort_by_score(matches: &mut [MemoryMatch]) {
+    matches.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, content: &str) -> MemoryEntry {
+        MemoryEntry::new(
+            id.to_string(),
+            "agent-1".to_string(),
+            content.to_string(),
+            vec![0.0; 4],
+        )
+    }
+
+    fn ids(matches: &[MemoryMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.entry.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_tokenize_keeps_identifiers() {
+        assert_eq!(
+            tokenize("Call parse_retry_after() for TICKET-4521."),
+            vec!["call", "parse_retry_after", "for", "ticket", "4521"]
+        );
+    }
+
+    #[test]
+    fn test_bm25_prefers_rare_terms() {
+        let entries = vec![
+            entry("a", "the user likes the blue theme"),
+            entry("b", "the deploy key is rotated by rotate_keys"),
+            entry("c", "the user prefers short answers"),
+        ];
+
+        let matches = bm25_rank("who calls rotate_keys", entries.clone(), 10);
+        assert_eq!(ids(&matches), vec!["b"]);
+
+        let matches = bm25_rank("the user", entries, 2);
+        assert_eq!(matches.len(), 2);
+        assert!(ids(&matches).iter().all(|id| *id != "b"));
+    }
+
+    #[test]
+    fn test_reciprocal_rank_fusion() {
+        let vector = vec![
+            MemoryMatch { entry: entry("a", ""), score: 0.9 },
+            MemoryMatch { entry: entry("b", ""), score: 0.8 },
+        ];
+        let keyword = vec![
+            MemoryMatch { entry: entry("b", ""), score: 7.0 },
+            MemoryMatch { entry: entry("c", ""), score: 3.0 },
+        ];
+
+        let fused = reciprocal_rank_fusion(vec![(vector, 1.0), (keyword, 1.0)], 60.0);
+        assert_eq!(ids(&fused), vec!["b", "a", "c"]);
+        assert!(fused.iter().all(|m| m.score > 0.0 && m.score <= 1.0));
+
+        let only = vec![MemoryMatch { entry: entry("a", ""), score: 0.5 }];
+        let fused = reciprocal_rank_fusion(vec![(only, 1.0), (Vec::new(), 1.0)], 60.0);
+        assert!((fused[0].score - 0.5).abs() < 1e-6);
+    }
+}
@@ -15,7 +15,7 @@ use futures_util::stream::TryStreamExt;
 use lancedb::arrow::RecordBatchStream;
 use lancedb::connection::Connection as LanceDbConnection;
 use lancedb::query::{ExecutableQuery, QueryBase};
-use lancedb::table::Table as LanceDbTable;
+use lancedb::table::{NewColumnTransform, Table as LanceDbTable};
 use lancedb::Result as LanceDbResult;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
@@ -55,6 +55,7 @@ struct DbMemory {
     created_at: i64,
     updated_at: i64,
     embedding: Vec<f32>,
+    namespace: String,
 }
 
 impl LanceDbMemoryStore {
@@ -76,7 +77,25 @@ impl LanceDbMemoryStore {
         let table_name = "memories";
 
         let table = match db.open_table(table_name).execute().await {
-            Ok(tbl) => tbl,
+            Ok(tbl) => {
+                // Add the namespace column to tables created before namespaces
+                let schema = tbl
+                    .schema()
+                    .await
+                    .map_err(|e| anyhow!("Failed to read table schema: {}", e))?;
+                if schema.field_with_name("namespace").is_err() {
+                    tbl.add_columns(
+                        NewColumnTransform::SqlExpressions(vec![(
+                            "namespace".to_string(),
+                            "''".to_string(),
+                        )]),
+                        None,
+                    )
+                    .await
+                    .map_err(|e| anyhow!("Failed to add namespace column: {}", e))?;
+                }
+                tbl
+            }
             Err(_) => {
                 // Create schema with lancedb's arrow module
                 let schema = Arc::new(lancedb::arrow::arrow_schema::Schema::new(vec![
@@ -142,6 +161,11 @@ impl LanceDbMemoryStore {
                         ),
                         true,
                     ),
+                    lancedb::arrow::arrow_schema::Field::new(
+                        "namespace",
+                        lancedb::arrow::arrow_schema::DataType::Utf8,
+                        false,
+                    ),
                 ]));
 
                 // Create empty table with schema
@@ -220,6 +244,7 @@ impl MemoryStore for LanceDbMemoryStore {
             created_at,
             updated_at,
             embedding: entry.embedding.clone(),
+            namespace: entry.namespace.clone(),
         };
 
         // Convert to record batch for insertion
@@ -248,6 +273,10 @@ impl MemoryStore for LanceDbMemoryStore {
             filters.push(format!("agent_id = '{}'", agent_id.replace("'", "''")));
         }
 
+        if let Some(namespace) = &opts.filter.namespace {
+            filters.push(format!("namespace = '{}'", namespace.replace("'", "''")));
+        }
+
         if let Some(source) = &opts.filter.source {
             let source_str = Self::source_to_string(source);
             filters.push(format!("source = '{}'", source_str.replace("'", "''")));
@@ -389,9 +418,16 @@ impl MemoryStore for LanceDbMemoryStore {
                 .downcast_ref::<arrow_array::Int64Array>()
                 .unwrap()
                 .value(0);
+            let namespace = record
+                .column(11)
+                .as_any()
+                .downcast_ref::<arrow_array::StringArray>()
+                .unwrap()
+                .value(0)
+                .to_string();
             // The distance column should be available
             let distance = record
-                .column(11)
+                .column(12)
                 .as_any()
                 .downcast_ref::<arrow_array::Float32Array>()
                 .unwrap()
@@ -413,6 +449,7 @@ impl MemoryStore for LanceDbMemoryStore {
             let entry = MemoryEntry {
                 id,
                 agent_id,
+                namespace,
                 content,
                 embedding: vec![0.0; self.embedding_dim], // We'll need to extract from the embedding column
                 metadata: crate::types::MemoryMetadata {
@@ -464,6 +501,10 @@ impl MemoryStore for LanceDbMemoryStore {
             filters.push(format!("agent_id = '{}'", agent_id.replace("'", "''")));
         }
 
+        if let Some(namespace) = &filter.namespace {
+            filters.push(format!("namespace = '{}'", namespace.replace("'", "''")));
+        }
+
         if let Some(source) = &filter.source {
             let source_str = Self::source_to_string(source);
             filters.push(format!("source = '{}'", source_str.replace("'", "''")));
@@ -605,6 +646,13 @@ impl MemoryStore for LanceDbMemoryStore {
                 .downcast_ref::<arrow_array::Float32Array>()
                 .unwrap();
             let embedding: Vec<f32> = embedding_f32.values().to_vec();
+            let namespace = record
+                .column(11)
+                .as_any()
+                .downcast_ref::<arrow_array::StringArray>()
+                .unwrap()
+                .value(0)
+                .to_string();
 
             let tags: Vec<String> =
                 serde_json::from_str(tags_json.as_str()).unwrap_or_else(|_| Vec::new());
@@ -614,6 +662,7 @@ impl MemoryStore for LanceDbMemoryStore {
             let entry = MemoryEntry {
                 id,
                 agent_id,
+                namespace,
                 content,
                 embedding,
                 metadata: crate::types::MemoryMetadata {
@@ -679,6 +728,7 @@ impl LanceDbMemoryStore {
                 ),
                 true,
             ),
+            Field::new("namespace", DataType::Utf8, false),
         ]);
 
         let tags_json = serde_json::to_string(&memory.tags).unwrap_or_else(|_| "[]".to_string());
@@ -703,6 +753,7 @@ impl LanceDbMemoryStore {
                 Arc::new(Int64Array::from(vec![memory.created_at])),
                 Arc::new(Int64Array::from(vec![memory.updated_at])),
                 Arc::new(embedding_array),
+                Arc::new(StringArray::from(vec![memory.namespace.as_str()])),
             ],
         )
         .map_err(|e| anyhow!("Failed to create record batch: {}", e))
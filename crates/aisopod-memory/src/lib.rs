@@ -6,9 +6,22 @@
 //!
 //! - Core types: [`MemoryEntry`], [`MemoryMetadata`], [`MemorySource`], [`MemoryMatch`],
 //!   [`MemoryFilter`], [`MemoryQueryOptions`]
-//! - Trait: [`MemoryStore`] - async trait for memory persistence and retrieval
-//! - Pipeline: [`MemoryQueryPipeline`] - end-to-end memory query orchestration
+//! - Trait: [`MemoryStore`] - async trait for memory persistence and retrieval,
+//!   with JSON Lines export and import ([`ImportOptions`]) and in-place
+//!   edits ([`MemoryUpdate`])
+//! - Isolation: [`ScopedMemoryStore`] confines a shared store to one namespace,
+//!   keeping agents and tenants from reading each other's memories
+//! - Backends: SQLite-Vec (default), and LanceDB, Postgres/pgvector and Qdrant
+//!   behind the `lancedb`, `postgres` and `qdrant` features, opened from the
+//!   memory configuration with [`store_from_config`]
+//! - Pipeline: [`MemoryQueryPipeline`] - end-to-end memory query orchestration,
+//!   optionally fusing keyword and vector retrieval ([`HybridSearchConfig`])
+//!   and reranking the top hits with a [`Reranker`] ([`RerankConfig`])
+//! - Graph: [`GraphMemory`] - entities and relations extracted at store time,
+//!   queried together with vector search to relate the entities a query names
 //! - Management: [`MemoryManager`] - automatic memory lifecycle management
+//! - Embeddings: [`EmbeddingProvider`] from `aisopod-provider`, resolved by name
+//!   from the provider registry with [`embedder_from_registry`]
 //!
 //! ## Example
 //!
@@ -33,10 +46,15 @@
 //! }
 //! ```
 
+pub mod backend;
 pub mod embedding;
+pub mod graph;
 pub mod integration;
+pub mod keyword;
 pub mod management;
+pub mod namespace;
 pub mod pipeline;
+pub mod rerank;
 pub mod sqlite;
 pub mod store;
 pub mod types;
@@ -44,13 +62,39 @@ pub mod types;
 #[cfg(feature = "lancedb")]
 pub mod lancedb;
 
+#[cfg(feature = "postgres")]
+pub mod pgvector;
+
+#[cfg(feature = "qdrant")]
+pub mod qdrant;
+
+pub use backend::store_from_config;
 pub use embedding::MockEmbeddingProvider;
-pub use embedding::{EmbeddingProvider, OpenAiEmbeddingProvider};
+pub use embedding::{
+    embedder_from_registry, EmbeddingProvider, GeminiEmbeddingProvider, OllamaEmbeddingProvider,
+    OpenAIEmbeddingProvider,
+};
+#[allow(deprecated)]
+pub use embedding::OpenAiEmbeddingProvider;
+#[cfg(feature = "local-embeddings")]
+pub use embedding::{LocalCrossEncoder, LocalEmbeddingModel, LocalEmbeddingProvider};
+pub use graph::{
+    Entity, EntityExtractor, GraphFacts, GraphMemory, GraphQueryResult, GraphStore,
+    LlmEntityExtractor, Relation, SqliteGraphStore,
+};
 pub use integration::build_memory_context;
-pub use management::{MemoryManager, MemoryManagerConfig};
-pub use pipeline::MemoryQueryPipeline;
+pub use management::{ExpiryAction, MemoryManager, MemoryManagerConfig};
+pub use namespace::{agent_namespace, ScopedMemoryStore};
+pub use pipeline::{HybridSearchConfig, MemoryQueryPipeline};
+pub use rerank::{LlmReranker, RerankConfig, Reranker};
 pub use store::MemoryStore;
 pub use types::*;
 
 #[cfg(feature = "lancedb")]
 pub use lancedb::LanceDbMemoryStore;
+
+#[cfg(feature = "postgres")]
+pub use pgvector::{PgVectorConfig, PgVectorIndex, PgVectorMemoryStore};
+
+#[cfg(feature = "qdrant")]
+pub use qdrant::{QdrantConfig, QdrantMemoryStore};
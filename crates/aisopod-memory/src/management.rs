@@ -2,16 +2,34 @@
 //!
 //! This module provides the `MemoryManager` struct that handles automatic
 //! memory lifecycle management including extraction, scoring, consolidation,
-//! expiration, and quota enforcement.
+//! summarization of related memories, expiration, and quota enforcement.
 
 use crate::embedding::EmbeddingProvider;
 use crate::store::MemoryStore;
-use crate::types::{MemoryEntry, MemoryFilter, MemoryMetadata, MemorySource};
-use aisopod_provider::types::{Message, MessageContent, Role};
+use crate::types::{
+    MemoryEntry, MemoryFilter, MemoryMatch, MemoryMetadata, MemoryQueryOptions, MemorySource,
+    ARCHIVED_AT_KEY, SUMMARIZED_FROM_KEY, SUMMARIZED_INTO_KEY,
+};
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{ChatCompletionRequest, Message, MessageContent, Role};
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Duration, Utc};
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// What happens to a memory once it is stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExpiryAction {
+    /// Delete the memory from the store.
+    #[default]
+    Delete,
+    /// Keep the memory but mark it archived, excluding it from retrieval.
+    Archive,
+}
 
 /// Configuration for memory management behavior.
 ///
@@ -26,6 +44,27 @@ pub struct MemoryManagerConfig {
     pub min_importance_threshold: f32,
     /// Cosine similarity threshold for memory consolidation.
     pub consolidation_similarity_threshold: f32,
+    /// Cosine similarity at or above which a memory being stored is a
+    /// duplicate of an existing one and is merged into it instead of
+    /// inserted. `None` disables deduplication.
+    pub dedup_similarity_threshold: Option<f32>,
+    /// Days after which a memory's relevance halves, measured from its last
+    /// update. `None` disables recency decay.
+    pub decay_half_life_days: Option<f32>,
+    /// TTL given to extracted memories. `None` keeps them until they expire
+    /// by age and importance.
+    pub default_ttl: Option<Duration>,
+    /// Whether stale memories are deleted or archived.
+    pub expiry_action: ExpiryAction,
+    /// Interval between background expiry sweeps.
+    pub sweep_interval: std::time::Duration,
+    /// Cosine similarity to a cluster's first memory at or above which a
+    /// memory joins the cluster during summarization.
+    pub summary_similarity_threshold: f32,
+    /// Smallest cluster that is summarized into a single memory.
+    pub summary_min_cluster_size: usize,
+    /// Interval between background summarization runs.
+    pub summary_interval: std::time::Duration,
 }
 
 impl Default for MemoryManagerConfig {
@@ -35,6 +74,14 @@ impl Default for MemoryManagerConfig {
             expiration_days: 90,
             min_importance_threshold: 0.1,
             consolidation_similarity_threshold: 0.92,
+            dedup_similarity_threshold: Some(0.95),
+            decay_half_life_days: Some(30.0),
+            default_ttl: None,
+            expiry_action: ExpiryAction::Delete,
+            sweep_interval: std::time::Duration::from_secs(3600),
+            summary_similarity_threshold: 0.8,
+            summary_min_cluster_size: 3,
+            summary_interval: std::time::Duration::from_secs(24 * 3600),
         }
     }
 }
@@ -42,15 +89,20 @@ impl Default for MemoryManagerConfig {
 /// Manages automatic memory lifecycle operations.
 ///
 /// This struct provides methods for:
-/// - Extracting facts from conversations and storing them as memories
+/// - Extracting facts from conversations and storing them as memories,
+///   merging facts that repeat an existing memory
 /// - Scoring memory importance based on frequency, recency, and base importance
 /// - Consolidating similar memories to reduce redundancy
-/// - Expiring old or low-importance memories
+/// - Summarizing clusters of related memories into one canonical memory
+///   through a chat model, archiving the originals
+/// - Searching memories with relevance decayed by age
+/// - Expiring memories past their TTL, or old and low-importance ones
 /// - Enforcing per-agent storage quotas
 pub struct MemoryManager {
     store: Arc<dyn MemoryStore>,
     embedder: Arc<dyn EmbeddingProvider>,
     config: MemoryManagerConfig,
+    summarizer: Option<(Arc<dyn ModelProvider>, String)>,
 }
 
 impl MemoryManager {
@@ -69,9 +121,22 @@ impl MemoryManager {
             store,
             embedder,
             config,
+            summarizer: None,
         }
     }
 
+    /// Sets the chat model used to summarize clusters of related memories.
+    ///
+    /// Without one, [`summarize_clusters`](Self::summarize_clusters) fails.
+    pub fn with_summarizer(
+        mut self,
+        provider: Arc<dyn ModelProvider>,
+        model: impl Into<String>,
+    ) -> Self {
+        self.summarizer = Some((provider, model.into()));
+        self
+    }
+
     /// Gets a reference to the store.
     pub fn store(&self) -> &Arc<dyn MemoryStore> {
         &self.store
@@ -81,14 +146,17 @@ impl MemoryManager {
     ///
     /// Iterates through conversation messages, identifies key facts, decisions,
     /// preferences, and instructions using simple heuristics, generates embeddings,
-    /// and stores each as a memory entry.
+    /// and stores each as a memory entry. A fact that duplicates an existing
+    /// memory refreshes that memory instead (see [`store_deduplicated`]).
     ///
     /// # Arguments
     /// * `agent_id` - The agent ID these memories belong to
     /// * `conversation` - List of messages to extract facts from
     ///
     /// # Returns
-    /// Returns a list of newly created memory entries.
+    /// Returns the stored memory entries, new or refreshed.
+    ///
+    /// [`store_deduplicated`]: Self::store_deduplicated
     ///
     /// # Errors
     /// Returns an error if storage or embedding generation fails.
@@ -98,6 +166,7 @@ impl MemoryManager {
         conversation: &[Message],
     ) -> Result<Vec<MemoryEntry>> {
         let mut entries = Vec::new();
+        let mut existing = self.dedup_candidates(agent_id).await?;
 
         for message in conversation {
             // Skip empty messages
@@ -135,9 +204,10 @@ impl MemoryManager {
                 // Calculate importance based on heuristics
                 let base_importance = if is_explicit_memory { 0.9 } else { 0.5 };
 
-                let entry = MemoryEntry {
+                let mut entry = MemoryEntry {
                     id: uuid::Uuid::new_v4().to_string(),
                     agent_id: agent_id.to_string(),
+                    namespace: String::new(),
                     content: fact,
                     embedding,
                     metadata: MemoryMetadata {
@@ -157,9 +227,12 @@ impl MemoryManager {
                     created_at: Utc::now(),
                     updated_at: Utc::now(),
                 };
+                if let Some(ttl) = self.config.default_ttl {
+                    entry.set_expires_at(Some(entry.created_at + ttl));
+                }
 
-                // Store the entry
-                self.store.store(entry.clone()).await?;
+                // Store the entry, merging it into a duplicate if there is one
+                let entry = self.store_merged(entry, &mut existing).await?;
                 entries.push(entry);
             }
         }
@@ -167,6 +240,113 @@ impl MemoryManager {
         Ok(entries)
     }
 
+    /// Stores `entry`, or merges it into an existing near-duplicate memory.
+    ///
+    /// The agent's memory whose embedding has the highest cosine similarity
+    /// to `entry`, if at or above `dedup_similarity_threshold`, is refreshed
+    /// instead of inserting `entry`: it keeps its ID, content and creation
+    /// time, takes the higher importance and the union of tags, and its
+    /// update time is reset so it does not decay or expire by age.
+    ///
+    /// # Arguments
+    /// * `entry` - The memory entry to store
+    ///
+    /// # Returns
+    /// Returns the entry as stored: `entry` itself or the refreshed memory.
+    ///
+    /// # Errors
+    /// Returns an error if listing or storage fails.
+    pub async fn store_deduplicated(&self, entry: MemoryEntry) -> Result<MemoryEntry> {
+        let mut existing = self.dedup_candidates(&entry.agent_id).await?;
+        self.store_merged(entry, &mut existing).await
+    }
+
+    /// Lists the memories of `agent_id` that new entries are deduplicated
+    /// against, or none when deduplication is disabled.
+    async fn dedup_candidates(&self, agent_id: &str) -> Result<Vec<MemoryEntry>> {
+        if self.config.dedup_similarity_threshold.is_none() {
+            return Ok(Vec::new());
+        }
+        let filter = MemoryFilter {
+            agent_id: Some(agent_id.to_string()),
+            ..Default::default()
+        };
+        let now = Utc::now();
+        Ok(self
+            .store
+            .list(filter)
+            .await?
+            .into_iter()
+            .filter(|mem| !mem.is_archived() && !mem.is_expired(now))
+            .collect())
+    }
+
+    /// Stores `entry` or merges it into its duplicate among `existing`,
+    /// keeping `existing` up to date for the next entry.
+    async fn store_merged(
+        &self,
+        entry: MemoryEntry,
+        existing: &mut Vec<MemoryEntry>,
+    ) -> Result<MemoryEntry> {
+        let duplicate = self
+            .config
+            .dedup_similarity_threshold
+            .and_then(|threshold| {
+                existing
+                    .iter()
+                    .enumerate()
+                    .map(|(i, mem)| (i, Self::cosine_similarity(&mem.embedding, &entry.embedding)))
+                    .filter(|(_, similarity)| *similarity >= threshold)
+                    .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+                    .map(|(i, _)| i)
+            });
+
+        let stored = match duplicate {
+            Some(i) => Self::merge_duplicate(existing[i].clone(), entry),
+            None => entry,
+        };
+        self.store.store(stored.clone()).await?;
+
+        match duplicate {
+            Some(i) => existing[i] = stored.clone(),
+            None if self.config.dedup_similarity_threshold.is_some() => {
+                existing.push(stored.clone())
+            }
+            None => {}
+        }
+        Ok(stored)
+    }
+
+    /// Refreshes `existing` with a duplicate `incoming` memory.
+    fn merge_duplicate(mut existing: MemoryEntry, incoming: MemoryEntry) -> MemoryEntry {
+        // A TTL only applies if both memories had one; keep the later expiry
+        let expires_at = existing
+            .expires_at()
+            .zip(incoming.expires_at())
+            .map(|(a, b)| a.max(b));
+
+        existing.metadata.importance = existing
+            .metadata
+            .importance
+            .max(incoming.metadata.importance);
+        for tag in incoming.metadata.tags {
+            if !existing.metadata.tags.contains(&tag) {
+                existing.metadata.tags.push(tag);
+            }
+        }
+        if existing.metadata.session_key.is_none() {
+            existing.metadata.session_key = incoming.metadata.session_key;
+        }
+
+        for (key, value) in incoming.metadata.custom {
+            existing.metadata.custom.entry(key).or_insert(value);
+        }
+        existing.set_expires_at(expires_at);
+
+        existing.updated_at = Utc::now();
+        existing
+    }
+
     /// Extracts facts from conversation content using simple heuristics.
     ///
     /// Looks for sentences indicating:
@@ -321,6 +501,7 @@ impl MemoryManager {
     pub async fn consolidate(&self, agent_id: &str) -> Result<u32> {
         let filter = MemoryFilter {
             agent_id: Some(agent_id.to_string()),
+            namespace: None,
             tags: None,
             source: None,
             importance_min: None,
@@ -391,6 +572,226 @@ impl MemoryManager {
         Ok(consolidated_count)
     }
 
+    /// Summarizes clusters of related memories of an agent.
+    ///
+    /// Live memories are grouped by namespace and clustered by embedding
+    /// similarity. Each cluster of at least `summary_min_cluster_size`
+    /// memories is condensed by the summarizer model into one canonical
+    /// memory, and the originals are archived with a pointer to it.
+    ///
+    /// # Arguments
+    /// * `agent_id` - The agent ID to summarize memories for
+    ///
+    /// # Returns
+    /// Returns the number of summaries created.
+    ///
+    /// # Errors
+    /// Returns an error if no summarizer is configured, or if listing,
+    /// summarization, embedding or storage fails.
+    pub async fn summarize_clusters(&self, agent_id: &str) -> Result<u32> {
+        let filter = MemoryFilter {
+            agent_id: Some(agent_id.to_string()),
+            ..Default::default()
+        };
+        self.summarize_matching(filter).await
+    }
+
+    /// Summarizes clusters of related memories of all agents.
+    ///
+    /// # Returns
+    /// Returns the number of summaries created.
+    ///
+    /// # Errors
+    /// Returns an error if no summarizer is configured, or if listing,
+    /// summarization, embedding or storage fails.
+    pub async fn summarize_all_clusters(&self) -> Result<u32> {
+        self.summarize_matching(MemoryFilter::default()).await
+    }
+
+    /// Summarizes the clusters among the memories matching `filter`.
+    async fn summarize_matching(&self, filter: MemoryFilter) -> Result<u32> {
+        let (provider, model) = self
+            .summarizer
+            .as_ref()
+            .ok_or_else(|| anyhow!("No summarizer model is configured"))?;
+        let now = Utc::now();
+
+        // Never cluster across agents or namespaces
+        let mut groups: BTreeMap<(String, String), Vec<MemoryEntry>> = BTreeMap::new();
+        for mem in self.store.list(filter).await? {
+            if !mem.is_archived() && !mem.is_expired(now) {
+                groups
+                    .entry((mem.namespace.clone(), mem.agent_id.clone()))
+                    .or_default()
+                    .push(mem);
+            }
+        }
+
+        let mut summarized_count = 0u32;
+        for memories in groups.into_values() {
+            for cluster in self.cluster(memories) {
+                let prompt = Self::summary_prompt(&cluster);
+                let summary = Self::complete(provider.as_ref(), model, prompt).await?;
+                if summary.is_empty() {
+                    warn!("Summarizer returned an empty summary, keeping the memories");
+                    continue;
+                }
+                self.replace_with_summary(cluster, summary, now).await?;
+                summarized_count += 1;
+            }
+        }
+
+        Ok(summarized_count)
+    }
+
+    /// Groups memories whose embedding is similar to a cluster's first
+    /// memory, oldest first, and keeps the clusters large enough to
+    /// summarize.
+    fn cluster(&self, mut memories: Vec<MemoryEntry>) -> Vec<Vec<MemoryEntry>> {
+        memories.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+
+        let mut clusters: Vec<Vec<MemoryEntry>> = Vec::new();
+        for mem in memories {
+            let cluster = clusters.iter_mut().find(|cluster| {
+                Self::cosine_similarity(&cluster[0].embedding, &mem.embedding)
+                    >= self.config.summary_similarity_threshold
+            });
+            match cluster {
+                Some(cluster) => cluster.push(mem),
+                None => clusters.push(vec![mem]),
+            }
+        }
+
+        clusters.retain(|cluster| cluster.len() >= self.config.summary_min_cluster_size.max(2));
+        clusters
+    }
+
+    /// Builds the prompt asking for one memory that replaces `cluster`.
+    fn summary_prompt(cluster: &[MemoryEntry]) -> String {
+        let memories: Vec<String> = cluster
+            .iter()
+            .map(|m| format!("- {}", m.content.replace('\n', " ")))
+            .collect();
+        format!(
+            "The following memories of an assistant are about the same subject. \
+             Rewrite them as one concise memory that keeps every distinct fact, \
+             preferring the later memories where they conflict.\n\n{}\n\n\
+             Reply with only the rewritten memory.",
+            memories.join("\n")
+        )
+    }
+
+    /// Sends `prompt` to `model` and collects the streamed reply.
+    async fn complete(provider: &dyn ModelProvider, model: &str, prompt: String) -> Result<String> {
+        let request = ChatCompletionRequest {
+            model: model.to_string(),
+            messages: vec![Message {
+                role: Role::User,
+                content: MessageContent::Text(prompt),
+                tool_calls: None,
+                tool_call_id: None,
+            }],
+            tools: None,
+            temperature: Some(0.0),
+            max_tokens: None,
+            stop: None,
+            stream: false,
+        };
+
+        let mut stream = provider.chat_completion(request).await?;
+        let mut response = String::new();
+        while let Some(chunk) = stream.next().await {
+            if let Some(content) = chunk?.delta.content {
+                response.push_str(&content);
+            }
+        }
+        Ok(response.trim().to_string())
+    }
+
+    /// Stores `summary` as the canonical memory of `cluster` and archives
+    /// the cluster's memories.
+    async fn replace_with_summary(
+        &self,
+        cluster: Vec<MemoryEntry>,
+        summary: String,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let first = &cluster[0];
+        let embedding = self.embedder.embed(&summary).await?;
+
+        let mut tags: Vec<String> = Vec::new();
+        for tag in cluster.iter().flat_map(|m| &m.metadata.tags) {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        let ids: Vec<String> = cluster.iter().map(|m| m.id.clone()).collect();
+
+        let mut entry = MemoryEntry::new(
+            uuid::Uuid::new_v4().to_string(),
+            first.agent_id.clone(),
+            summary,
+            embedding,
+        )
+        .with_namespace(first.namespace.clone());
+        entry.created_at = first.created_at;
+        entry.metadata = MemoryMetadata {
+            source: MemorySource::System,
+            tags,
+            importance: cluster
+                .iter()
+                .map(|m| m.metadata.importance)
+                .fold(0.0, f32::max),
+            ..Default::default()
+        };
+        entry
+            .metadata
+            .custom
+            .insert(SUMMARIZED_FROM_KEY.to_string(), ids.into());
+        let summary_id = self.store.store(entry).await?;
+
+        for mut mem in cluster {
+            mem.set_expires_at(None);
+            mem.metadata
+                .custom
+                .insert(ARCHIVED_AT_KEY.to_string(), now.to_rfc3339().into());
+            mem.metadata
+                .custom
+                .insert(SUMMARIZED_INTO_KEY.to_string(), summary_id.clone().into());
+            mem.updated_at = now;
+            self.store.store(mem).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Spawns a task that runs
+    /// [`summarize_all_clusters`](Self::summarize_all_clusters) every
+    /// `summary_interval`, starting after the first interval.
+    ///
+    /// The task holds only a weak reference to the manager and ends once the
+    /// manager is dropped, or when the returned handle is aborted.
+    pub fn spawn_summarization(self: &Arc<Self>) -> JoinHandle<()> {
+        let manager = Arc::downgrade(self);
+        let interval = self.config.summary_interval;
+        tokio::spawn(async move {
+            let start = tokio::time::Instant::now() + interval;
+            let mut ticker = tokio::time::interval_at(start, interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                match manager.summarize_all_clusters().await {
+                    Ok(0) => {}
+                    Ok(count) => debug!(count, "Summarized related memories"),
+                    Err(e) => warn!("Memory summarization failed: {}", e),
+                }
+            }
+        })
+    }
+
     /// Computes cosine similarity between two vectors.
     fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
         if a.is_empty() || b.is_empty() || a.len() != b.len() {
@@ -417,10 +818,84 @@ impl MemoryManager {
         dot_product / (a_magnitude * b_magnitude)
     }
 
-    /// Expires old or low-importance memories for an agent.
+    /// Returns the recency decay of `entry` at `now`, between 0.0 and 1.0.
+    ///
+    /// The factor halves every `decay_half_life_days` since the entry was
+    /// last updated, and is 1.0 when decay is disabled.
+    pub fn decay_factor(&self, entry: &MemoryEntry, now: DateTime<Utc>) -> f32 {
+        let Some(half_life) = self.config.decay_half_life_days.filter(|days| *days > 0.0) else {
+            return 1.0;
+        };
+        let days_old = now.signed_duration_since(entry.updated_at).num_seconds() as f32 / 86400.0;
+        2.0_f32.powf(-days_old.max(0.0) / half_life)
+    }
+
+    /// Searches memories, weighting each match's score by its recency decay.
+    ///
+    /// Memories past their TTL or archived are never returned, even before
+    /// the next expiry sweep removes them.
+    ///
+    /// # Arguments
+    /// * `query` - The natural language query string
+    /// * `opts` - Query options including filter criteria and result limits
+    ///
+    /// # Returns
+    /// Returns matches sorted by decayed score descending, at most `top_k`.
+    ///
+    /// # Errors
+    /// Returns an error if the store query fails.
+    pub async fn search(&self, query: &str, opts: MemoryQueryOptions) -> Result<Vec<MemoryMatch>> {
+        let now = Utc::now();
+        let top_k = opts.top_k;
+        let min_score = opts.min_score;
+
+        // Decay can reorder matches, so rank a wider candidate set
+        let candidates = MemoryQueryOptions {
+            top_k: top_k * 2,
+            min_score: None,
+            ..opts
+        };
+        let mut matches: Vec<MemoryMatch> = self
+            .store
+            .query(query, candidates)
+            .await?
+            .into_iter()
+            .filter(|m| !m.entry.is_expired(now) && !m.entry.is_archived())
+            .map(|mut m| {
+                m.score *= self.decay_factor(&m.entry, now);
+                m
+            })
+            .filter(|m| min_score.is_none_or(|min| m.score >= min))
+            .collect();
+
+        matches.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        matches.truncate(top_k);
+        Ok(matches)
+    }
+
+    /// Returns true if `mem` should be expired at `now`: its TTL has passed,
+    /// or it is older than `expiration_days` and below
+    /// `min_importance_threshold`. Archived memories are never stale.
+    fn is_stale(&self, mem: &MemoryEntry, now: DateTime<Utc>) -> bool {
+        if mem.is_archived() {
+            return false;
+        }
+        let cutoff = now - Duration::days(self.config.expiration_days as i64);
+        mem.is_expired(now)
+            || (mem.created_at < cutoff
+                && mem.metadata.importance < self.config.min_importance_threshold)
+    }
+
+    /// Expires stale memories for an agent.
     ///
-    /// Deletes memories where `created_at` is older than `expiration_days`
-    /// AND `importance` is below `min_importance_threshold`.
+    /// A memory is stale when its TTL has passed, or when `created_at` is
+    /// older than `expiration_days` AND `importance` is below
+    /// `min_importance_threshold`. Stale memories are deleted or archived
+    /// according to `expiry_action`.
     ///
     /// # Arguments
     /// * `agent_id` - The agent ID to expire memories for
@@ -429,36 +904,81 @@ impl MemoryManager {
     /// Returns the number of entries expired.
     ///
     /// # Errors
-    /// Returns an error if listing or deletion fails.
+    /// Returns an error if listing, deletion or archiving fails.
     pub async fn expire(&self, agent_id: &str) -> Result<u32> {
         let filter = MemoryFilter {
             agent_id: Some(agent_id.to_string()),
-            tags: None,
-            source: None,
-            importance_min: None,
-            session_key: None,
-            created_after: None,
-            created_before: None,
+            ..Default::default()
         };
+        self.expire_matching(filter).await
+    }
 
-        let memories = self.store.list(filter).await?;
+    /// Expires stale memories of all agents.
+    ///
+    /// # Returns
+    /// Returns the number of entries expired.
+    ///
+    /// # Errors
+    /// Returns an error if listing, deletion or archiving fails.
+    pub async fn sweep(&self) -> Result<u32> {
+        self.expire_matching(MemoryFilter::default()).await
+    }
 
-        let cutoff = Utc::now() - Duration::days(self.config.expiration_days as i64);
+    /// Expires the stale memories matching `filter`.
+    async fn expire_matching(&self, filter: MemoryFilter) -> Result<u32> {
+        let memories = self.store.list(filter).await?;
+        let now = Utc::now();
 
         let mut expired_count = 0u32;
 
-        for mem in memories {
-            if mem.created_at < cutoff
-                && mem.metadata.importance < self.config.min_importance_threshold
-            {
-                self.store.delete(&mem.id).await?;
-                expired_count += 1;
+        for mut mem in memories {
+            if !self.is_stale(&mem, now) {
+                continue;
+            }
+            match self.config.expiry_action {
+                ExpiryAction::Delete => {
+                    self.store.delete(&mem.id).await?;
+                }
+                ExpiryAction::Archive => {
+                    mem.set_expires_at(None);
+                    mem.metadata
+                        .custom
+                        .insert(ARCHIVED_AT_KEY.to_string(), now.to_rfc3339().into());
+                    mem.updated_at = now;
+                    self.store.store(mem).await?;
+                }
             }
+            expired_count += 1;
         }
 
         Ok(expired_count)
     }
 
+    /// Spawns a task that runs [`sweep`](Self::sweep) every `sweep_interval`,
+    /// starting immediately.
+    ///
+    /// The task holds only a weak reference to the manager and ends once the
+    /// manager is dropped, or when the returned handle is aborted.
+    pub fn spawn_expiry_sweep(self: &Arc<Self>) -> JoinHandle<()> {
+        let manager = Arc::downgrade(self);
+        let interval = self.config.sweep_interval;
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                match manager.sweep().await {
+                    Ok(0) => {}
+                    Ok(count) => debug!(count, "Expired stale memories"),
+                    Err(e) => warn!("Memory expiry sweep failed: {}", e),
+                }
+            }
+        })
+    }
+
     /// Enforces per-agent storage quotas by evicting lowest-importance memories.
     ///
     /// If an agent's memory count exceeds `max_memories_per_agent`, deletes
@@ -475,6 +995,7 @@ impl MemoryManager {
     pub async fn enforce_quota(&self, agent_id: &str) -> Result<u32> {
         let filter = MemoryFilter {
             agent_id: Some(agent_id.to_string()),
+            namespace: None,
             tags: None,
             source: None,
             importance_min: None,
@@ -541,6 +1062,33 @@ mod tests {
         assert_eq!(config.expiration_days, 90);
         assert_eq!(config.min_importance_threshold, 0.1);
         assert_eq!(config.consolidation_similarity_threshold, 0.92);
+        assert_eq!(config.dedup_similarity_threshold, Some(0.95));
+        assert_eq!(config.decay_half_life_days, Some(30.0));
+        assert_eq!(config.expiry_action, ExpiryAction::Delete);
+    }
+
+    #[test]
+    fn test_decay_factor() {
+        let store = Arc::new(SqliteMemoryStore::new(":memory:", 4).unwrap());
+        let embedder = Arc::new(MockEmbeddingProvider::new(4));
+        let mut manager = MemoryManager::new(store, embedder, MemoryManagerConfig::default());
+
+        let now = Utc::now();
+        let mut entry = MemoryEntry::new(
+            "decay".to_string(),
+            "agent-1".to_string(),
+            "content".to_string(),
+            vec![0.0; 4],
+        );
+        entry.updated_at = now - Duration::days(30);
+        assert!((manager.decay_factor(&entry, now) - 0.5).abs() < 0.001);
+
+        entry.updated_at = now;
+        assert!((manager.decay_factor(&entry, now) - 1.0).abs() < 0.001);
+
+        manager.config.decay_half_life_days = None;
+        entry.updated_at = now - Duration::days(365);
+        assert_eq!(manager.decay_factor(&entry, now), 1.0);
     }
 
     #[tokio::test]
@@ -603,6 +1151,7 @@ mod tests {
             expiration_days: 90,
             min_importance_threshold: 0.1,
             consolidation_similarity_threshold: 0.92,
+            ..Default::default()
         };
         let manager = MemoryManager::new(store.clone(), embedder, config);
         (manager, store)
@@ -0,0 +1,124 @@
+//! Namespace scoping of memory stores.
+//!
+//! A namespace partitions one backend between agents, and between the end
+//! users of a multi-tenant deployment. [`ScopedMemoryStore`] wraps a shared
+//! store and confines every read and write to a single namespace, so code
+//! holding it cannot see or modify memories of another namespace whatever
+//! filter it passes.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+
+use crate::store::MemoryStore;
+use crate::types::{MemoryEntry, MemoryFilter, MemoryMatch, MemoryQueryOptions};
+
+/// Returns the namespace of an agent, qualified by the tenant if any.
+///
+/// # Example
+/// ```
+/// use aisopod_memory::agent_namespace;
+///
+/// assert_eq!(agent_namespace(None, "support"), "support");
+/// assert_eq!(agent_namespace(Some("acme"), "support"), "acme/support");
+/// ```
+pub fn agent_namespace(tenant: Option<&str>, agent_id: &str) -> String {
+    match tenant {
+        Some(tenant) => format!("{}/{}", tenant, agent_id),
+        None => agent_id.to_string(),
+    }
+}
+
+/// Memory store confined to one namespace of a shared store.
+///
+/// Stored entries are stamped with the namespace, and queries, listings and
+/// exports only ever see entries of the namespace. Storing or deleting an ID
+/// that belongs to another namespace fails instead of touching it.
+pub struct ScopedMemoryStore {
+    inner: Arc<dyn MemoryStore>,
+    namespace: String,
+}
+
+impl ScopedMemoryStore {
+    /// Confines `inner` to `namespace`.
+    pub fn new(inner: Arc<dyn MemoryStore>, namespace: impl Into<String>) -> Self {
+        Self {
+            inner,
+            namespace: namespace.into(),
+        }
+    }
+
+    /// Returns the namespace this store is confined to.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Returns the shared store this store wraps.
+    pub fn inner(&self) -> &Arc<dyn MemoryStore> {
+        &self.inner
+    }
+
+    fn scope(&self, mut filter: MemoryFilter) -> MemoryFilter {
+        filter.namespace = Some(self.namespace.clone());
+        filter
+    }
+
+    /// Fails if `id` exists in a different namespace.
+    async fn check_owned(&self, id: &str) -> Result<()> {
+        match self.inner.get(id).await? {
+            Some(existing) if existing.namespace != self.namespace => Err(anyhow!(
+                "Memory {} does not belong to namespace '{}'",
+                id,
+                self.namespace
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl MemoryStore for ScopedMemoryStore {
+    async fn store(&self, mut entry: MemoryEntry) -> Result<String> {
+        if !entry.id.is_empty() {
+            self.check_owned(&entry.id).await?;
+        }
+        entry.namespace = self.namespace.clone();
+        self.inner.store(entry).await
+    }
+
+    async fn query(&self, query: &str, mut opts: MemoryQueryOptions) -> Result<Vec<MemoryMatch>> {
+        opts.filter = self.scope(opts.filter);
+        self.inner.query(query, opts).await
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.check_owned(id).await?;
+        self.inner.delete(id).await
+    }
+
+    async fn list(&self, filter: MemoryFilter) -> Result<Vec<MemoryEntry>> {
+        self.inner.list(self.scope(filter)).await
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<MemoryEntry>> {
+        Ok(self
+            .inner
+            .get(id)
+            .await?
+            .filter(|entry| entry.namespace == self.namespace))
+    }
+
+    async fn keyword_query(
+        &self,
+        query: &str,
+        mut opts: MemoryQueryOptions,
+    ) -> Result<Vec<MemoryMatch>> {
+        opts.filter = self.scope(opts.filter);
+        self.inner.keyword_query(query, opts).await
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -1,25 +1,60 @@
 //! Memory query pipeline for end-to-end memory retrieval and context injection.
 //!
 //! This module provides the `MemoryQueryPipeline` struct that orchestrates
-//! the full memory query flow: embedding generation, vector search, filtering,
-//! re-ranking, and context formatting.
+//! the full memory query flow: embedding generation, vector search, optional
+//! keyword search fused with the vector results, filtering, optional
+//! reranking by a [`Reranker`], re-ranking, and context formatting.
 
 use crate::embedding::{EmbeddingProvider, MockEmbeddingProvider};
+use crate::keyword::reciprocal_rank_fusion;
+use crate::rerank::{RerankConfig, Reranker};
 use crate::store::MemoryStore;
 use crate::types::{MemoryFilter, MemoryMatch, MemoryQueryOptions};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use std::any::Any;
 use std::sync::Arc;
+use tracing::warn;
+
+/// Settings for hybrid keyword + vector retrieval.
+///
+/// The vector and keyword rankings are merged with reciprocal rank fusion, so
+/// a memory ranked highly by either stage is retrieved even when the other
+/// stage misses it.
+#[derive(Debug, Clone)]
+pub struct HybridSearchConfig {
+    /// Weight of the vector similarity ranking.
+    pub vector_weight: f32,
+    /// Weight of the keyword (BM25/full-text) ranking.
+    pub keyword_weight: f32,
+    /// Reciprocal rank fusion constant; higher values flatten the
+    /// advantage of the top ranks.
+    pub rrf_k: f32,
+    /// Each stage retrieves `top_k` times this many candidates for fusion.
+    pub candidate_multiplier: usize,
+}
+
+impl Default for HybridSearchConfig {
+    fn default() -> Self {
+        Self {
+            vector_weight: 1.0,
+            keyword_weight: 1.0,
+            rrf_k: 60.0,
+            candidate_multiplier: 3,
+        }
+    }
+}
 
 /// Pipeline for querying and retrieving relevant memories.
 ///
 /// This struct orchestrates the full memory query flow:
 /// 1. Generate a query embedding via the `EmbeddingProvider`.
-/// 2. Perform vector similarity search via the `MemoryStore`.
+/// 2. Perform vector similarity search via the `MemoryStore`, fused with a
+///    keyword search when hybrid search is enabled.
 /// 3. Apply post-retrieval filtering.
-/// 4. Re-rank results using combined score (similarity, importance, recency).
-/// 5. Sort by final_score descending and truncate to top_k.
+/// 4. Score the top hits with the reranker, when one is configured.
+/// 5. Re-rank results using combined score (similarity, importance, recency).
+/// 6. Sort by final_score descending and truncate to top_k.
 ///
 /// # Example
 /// ```ignore
@@ -38,6 +73,8 @@ use std::sync::Arc;
 pub struct MemoryQueryPipeline {
     store: Arc<dyn MemoryStore>,
     embedder: Arc<dyn EmbeddingProvider>,
+    hybrid: Option<HybridSearchConfig>,
+    reranker: Option<(Arc<dyn Reranker>, RerankConfig)>,
 }
 
 impl MemoryQueryPipeline {
@@ -47,17 +84,41 @@ impl MemoryQueryPipeline {
     /// * `store` - The underlying memory store for vector search
     /// * `embedder` - The embedding provider for generating query embeddings
     pub fn new(store: Arc<dyn MemoryStore>, embedder: Arc<dyn EmbeddingProvider>) -> Self {
-        Self { store, embedder }
+        Self {
+            store,
+            embedder,
+            hybrid: None,
+            reranker: None,
+        }
+    }
+
+    /// Enables hybrid retrieval, fusing a keyword search with the vector
+    /// search to improve recall for exact names, IDs and code identifiers.
+    pub fn with_hybrid_search(mut self, config: HybridSearchConfig) -> Self {
+        self.hybrid = Some(config);
+        self
+    }
+
+    /// Enables reranking, scoring the top retrieval hits with `reranker`
+    /// before they are injected into the context.
+    pub fn with_reranker(mut self, reranker: Arc<dyn Reranker>, config: RerankConfig) -> Self {
+        self.reranker = Some((reranker, config));
+        self
     }
 
     /// Query memories and return re-ranked results.
     ///
     /// This method performs the full memory query pipeline:
     /// 1. Generates a query embedding via the `EmbeddingProvider`.
-    /// 2. Executes vector similarity search via the `MemoryStore`.
+    /// 2. Executes vector similarity search via the `MemoryStore`. With
+    ///    hybrid search enabled, also executes a keyword search and fuses
+    ///    both rankings; the fused score replaces the similarity score.
     /// 3. Applies post-retrieval filtering.
-    /// 4. Re-ranks results using combined score (similarity, importance, recency).
-    /// 5. Sorts by final_score descending and truncates to top_k.
+    /// 4. With a reranker configured, blends its score into the similarity
+    ///    score of the top candidates. A reranker that fails or exceeds its
+    ///    latency budget is skipped.
+    /// 5. Re-ranks results using combined score (similarity, importance, recency).
+    /// 6. Sorts by final_score descending and truncates to top_k.
     ///
     /// # Arguments
     /// * `query` - The natural language query string
@@ -76,10 +137,44 @@ impl MemoryQueryPipeline {
         // Step 2: Perform vector similarity search
         // Note: We need to pass the embedding to the store somehow
         // For now, we'll call the store's query method and re-rank results
-        let mut matches = self.store.query(query, opts.clone()).await?;
+        let retrieve = match &self.reranker {
+            Some((_, rerank)) => opts.top_k.max(rerank.candidates),
+            None => opts.top_k,
+        };
+        let mut matches = match &self.hybrid {
+            None => {
+                let candidates = MemoryQueryOptions {
+                    top_k: retrieve,
+                    ..opts.clone()
+                };
+                self.store.query(query, candidates).await?
+            }
+            Some(hybrid) => {
+                let candidates = MemoryQueryOptions {
+                    top_k: retrieve * hybrid.candidate_multiplier.max(1),
+                    ..opts.clone()
+                };
+                let vector = self.store.query(query, candidates.clone()).await?;
+                let keyword = self.store.keyword_query(query, candidates).await?;
+                reciprocal_rank_fusion(
+                    vec![
+                        (vector, hybrid.vector_weight),
+                        (keyword, hybrid.keyword_weight),
+                    ],
+                    hybrid.rrf_k,
+                )
+            }
+        };
+        matches.truncate(retrieve);
 
-        // Step 3 & 4: Apply post-retrieval filtering and re-rank
-        let filtered_matches = self.apply_post_filtering(matches)?;
+        // Step 3: Apply post-retrieval filtering
+        let mut filtered_matches = self.apply_post_filtering(matches)?;
+
+        // Step 4: Rerank the remaining candidates
+        if let Some((reranker, config)) = &self.reranker {
+            self.apply_reranking(query, &mut filtered_matches, reranker.as_ref(), config)
+                .await;
+        }
 
         // Re-rank using combined score
         let re_ranked: Vec<MemoryMatch> = filtered_matches
@@ -95,7 +190,7 @@ impl MemoryQueryPipeline {
             })
             .collect();
 
-        // Step 5: Sort by final_score descending
+        // Step 6: Sort by final_score descending
         let mut sorted = re_ranked;
         sorted.sort_by(|a, b| {
             b.score
@@ -109,18 +204,67 @@ impl MemoryQueryPipeline {
 
     /// Apply post-retrieval filtering to memory matches.
     ///
-    /// Filters results based on the filter criteria in the options.
+    /// The store already applies the filter from `MemoryQueryOptions`; this
+    /// drops memories past their TTL or archived, which stay in the store
+    /// until the next expiry sweep.
     ///
     /// # Arguments
     /// * `matches` - The raw query results to filter
     ///
     /// # Returns
-    /// Returns filtered matches that satisfy all filter criteria.
+    /// Returns the matches that are still live.
     fn apply_post_filtering(&self, matches: Vec<MemoryMatch>) -> Result<Vec<MemoryMatch>> {
-        // The store already handles most filters, but we do additional filtering here
-        // if needed. For now, we just return the matches as-is since the store
-        // already applies the filters from MemoryQueryOptions.
-        Ok(matches)
+        let now = Utc::now();
+        Ok(matches
+            .into_iter()
+            .filter(|m| !m.entry.is_expired(now) && !m.entry.is_archived())
+            .collect())
+    }
+
+    /// Blends reranker scores into the match scores.
+    ///
+    /// The reranker runs under `config.latency_budget`; when it times out or
+    /// fails the matches keep their retrieval scores.
+    async fn apply_reranking(
+        &self,
+        query: &str,
+        matches: &mut [MemoryMatch],
+        reranker: &dyn Reranker,
+        config: &RerankConfig,
+    ) {
+        if matches.is_empty() {
+            return;
+        }
+
+        let scores =
+            match tokio::time::timeout(config.latency_budget, reranker.score(query, matches)).await
+            {
+                Ok(Ok(scores)) if scores.len() == matches.len() => scores,
+                Ok(Ok(scores)) => {
+                    warn!(
+                        expected = matches.len(),
+                        got = scores.len(),
+                        "Reranker returned the wrong number of scores, keeping retrieval order"
+                    );
+                    return;
+                }
+                Ok(Err(e)) => {
+                    warn!(error = %e, "Reranking failed, keeping retrieval order");
+                    return;
+                }
+                Err(_) => {
+                    warn!(
+                        budget_ms = config.latency_budget.as_millis() as u64,
+                        "Reranking exceeded its latency budget, keeping retrieval order"
+                    );
+                    return;
+                }
+            };
+
+        let weight = config.weight.clamp(0.0, 1.0);
+        for (m, rerank_score) in matches.iter_mut().zip(scores) {
+            m.score = weight * rerank_score + (1.0 - weight) * m.score;
+        }
     }
 
     /// Calculate a recency factor based on when a memory was created.
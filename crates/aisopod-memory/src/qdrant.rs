@@ -0,0 +1,497 @@
+//! Qdrant memory storage backend.
+//!
+//! This module provides a `QdrantMemoryStore` implementation using the Qdrant
+//! REST API. The collection is created on first use with a cosine vector
+//! space and keyword/range payload indexes on the fields [`MemoryFilter`]
+//! filters on, so filters are evaluated by Qdrant during the vector search.
+//!
+//! Qdrant point IDs must be UUIDs or integers, so each memory is stored under
+//! a UUID derived from its ID, with the original ID kept in the payload.
+
+use crate::embedding::EmbeddingProvider;
+use crate::store::MemoryStore;
+use crate::types::{
+    MemoryEntry, MemoryFilter, MemoryMatch, MemoryMetadata, MemoryQueryOptions, MemorySource,
+};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use reqwest::{Client, Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Number of points sent per upsert request.
+const DEFAULT_BATCH_SIZE: usize = 128;
+
+/// Number of points fetched per scroll request when listing.
+const SCROLL_PAGE_SIZE: usize = 256;
+
+/// Configuration for a [`QdrantMemoryStore`].
+#[derive(Debug, Clone)]
+pub struct QdrantConfig {
+    /// Base URL of the Qdrant REST API, e.g. `http://localhost:6333`.
+    pub url: String,
+    /// Name of the collection holding the memories.
+    pub collection: String,
+    /// API key sent in the `api-key` header, if the server requires one.
+    pub api_key: Option<String>,
+    /// Number of points sent per upsert request.
+    pub batch_size: usize,
+}
+
+impl QdrantConfig {
+    /// Creates a configuration for the `memories` collection at `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            collection: "memories".to_string(),
+            api_key: None,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+}
+
+/// Memory storage backend using Qdrant.
+pub struct QdrantMemoryStore {
+    client: Client,
+    config: QdrantConfig,
+    embedding_dim: usize,
+    embedder: Arc<dyn EmbeddingProvider>,
+}
+
+/// Payload stored with each point.
+#[derive(Debug, Serialize, Deserialize)]
+struct Payload {
+    memory_id: String,
+    agent_id: String,
+    /// Missing on points stored before namespaces
+    #[serde(default)]
+    namespace: String,
+    content: String,
+    source: String,
+    session_key: Option<String>,
+    tags: Vec<String>,
+    importance: f32,
+    #[serde(default)]
+    metadata: HashMap<String, Value>,
+    /// Milliseconds since the Unix epoch, so it can be range-filtered.
+    created_at: i64,
+    updated_at: i64,
+}
+
+/// A point returned by search or scroll.
+#[derive(Debug, Deserialize)]
+struct Point {
+    #[serde(default)]
+    score: Option<f32>,
+    payload: Payload,
+    #[serde(default)]
+    vector: Option<Vec<f32>>,
+}
+
+/// The result page of a scroll request.
+#[derive(Debug, Deserialize)]
+struct ScrollPage {
+    points: Vec<Point>,
+    next_page_offset: Option<Value>,
+}
+
+/// The envelope of every Qdrant response.
+#[derive(Debug, Deserialize)]
+struct Response<T> {
+    result: T,
+}
+
+impl QdrantMemoryStore {
+    /// Connects to Qdrant and creates the collection and its payload indexes
+    /// if they do not exist.
+    ///
+    /// # Arguments
+    /// * `config` - Server, collection and batching settings
+    /// * `embedding_dim` - Dimension of the embeddings to store
+    /// * `embedder` - The embedding provider to use for converting text queries to embeddings
+    ///
+    /// # Returns
+    /// Returns a new `QdrantMemoryStore`, or an error if the server is
+    /// unreachable or the collection has a different vector size.
+    pub async fn new(
+        config: QdrantConfig,
+        embedding_dim: usize,
+        embedder: Arc<dyn EmbeddingProvider>,
+    ) -> Result<Self> {
+        let store = Self {
+            client: Client::new(),
+            config,
+            embedding_dim,
+            embedder,
+        };
+        store.ensure_collection().await?;
+        Ok(store)
+    }
+
+    /// Creates the collection with payload indexes unless it already exists.
+    async fn ensure_collection(&self) -> Result<()> {
+        let response = self
+            .request(Method::GET, "")
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to connect to Qdrant: {}", e))?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            self.call(
+                Method::PUT,
+                "",
+                json!({ "vectors": { "size": self.embedding_dim, "distance": "Cosine" } }),
+            )
+            .await?;
+
+            for (field, schema) in [
+                ("memory_id", "keyword"),
+                ("agent_id", "keyword"),
+                ("namespace", "keyword"),
+                ("source", "keyword"),
+                ("session_key", "keyword"),
+                ("tags", "keyword"),
+                ("importance", "float"),
+                ("created_at", "integer"),
+            ] {
+                self.call(
+                    Method::PUT,
+                    "/index?wait=true",
+                    json!({ "field_name": field, "field_schema": schema }),
+                )
+                .await?;
+            }
+            return Ok(());
+        }
+
+        let info: Value = Self::parse(response).await?;
+        let size = info
+            .pointer("/result/config/params/vectors/size")
+            .and_then(Value::as_u64);
+        match size {
+            Some(size) if size as usize != self.embedding_dim => Err(anyhow!(
+                "Qdrant collection '{}' holds {}-dimensional vectors, but {} were configured",
+                self.config.collection,
+                size,
+                self.embedding_dim
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Stores several entries, sending them in batches of the configured size.
+    ///
+    /// # Returns
+    /// Returns the IDs of the stored entries, in order.
+    pub async fn store_batch(&self, entries: Vec<MemoryEntry>) -> Result<Vec<String>> {
+        let mut ids = Vec::with_capacity(entries.len());
+        let mut points = Vec::with_capacity(entries.len());
+        for mut entry in entries {
+            if entry.id.is_empty() {
+                entry.id = Uuid::new_v4().to_string();
+            }
+            if entry.embedding.len() != self.embedding_dim {
+                return Err(anyhow!(
+                    "Embedding has {} dimensions, expected {}",
+                    entry.embedding.len(),
+                    self.embedding_dim
+                ));
+            }
+            ids.push(entry.id.clone());
+            points.push(Self::entry_to_point(entry));
+        }
+
+        for batch in points.chunks(self.config.batch_size.max(1)) {
+            self.call(Method::PUT, "/points?wait=true", json!({ "points": batch }))
+                .await?;
+        }
+        Ok(ids)
+    }
+
+    /// Builds a request against a path below the collection.
+    fn request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        let url = format!(
+            "{}/collections/{}{}",
+            self.config.url.trim_end_matches('/'),
+            self.config.collection,
+            path
+        );
+        let request = self.client.request(method, url);
+        match &self.config.api_key {
+            Some(key) => request.header("api-key", key),
+            None => request,
+        }
+    }
+
+    /// Sends a JSON request and returns the response body.
+    async fn call(&self, method: Method, path: &str, body: Value) -> Result<Value> {
+        let response = self.request(method, path).json(&body).send().await?;
+        Self::parse(response).await
+    }
+
+    async fn parse<T: serde::de::DeserializeOwned>(response: reqwest::Response) -> Result<T> {
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Qdrant request failed ({}): {}", status, body));
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Maps a filter to Qdrant `must` conditions.
+    fn filter_to_qdrant(filter: &MemoryFilter) -> Value {
+        let mut must = Vec::new();
+        let mut keyword = |key: &str, value: &str| {
+            must.push(json!({ "key": key, "match": { "value": value } }));
+        };
+
+        if let Some(agent_id) = &filter.agent_id {
+            keyword("agent_id", agent_id);
+        }
+        if let Some(source) = &filter.source {
+            keyword("source", source_to_string(source));
+        }
+        if let Some(session_key) = &filter.session_key {
+            keyword("session_key", session_key);
+        }
+        // Matching an array field matches any element, so one condition per
+        // tag requires all of them
+        for tag in filter.tags.iter().flatten() {
+            keyword("tags", tag);
+        }
+        match filter.namespace.as_deref() {
+            // Points stored before namespaces have no namespace field
+            Some("") => must.push(json!({ "should": [
+                { "key": "namespace", "match": { "value": "" } },
+                { "is_empty": { "key": "namespace" } }
+            ] })),
+            Some(namespace) => {
+                must.push(json!({ "key": "namespace", "match": { "value": namespace } }))
+            }
+            None => {}
+        }
+        if let Some(importance_min) = filter.importance_min {
+            must.push(json!({ "key": "importance", "range": { "gte": importance_min } }));
+        }
+        if filter.created_after.is_some() || filter.created_before.is_some() {
+            let mut range = serde_json::Map::new();
+            if let Some(after) = filter.created_after {
+                range.insert("gte".to_string(), json!(after.timestamp_millis()));
+            }
+            if let Some(before) = filter.created_before {
+                range.insert("lte".to_string(), json!(before.timestamp_millis()));
+            }
+            must.push(json!({ "key": "created_at", "range": range }));
+        }
+
+        json!({ "must": must })
+    }
+
+    fn entry_to_point(entry: MemoryEntry) -> Value {
+        let payload = Payload {
+            memory_id: entry.id.clone(),
+            agent_id: entry.agent_id,
+            namespace: entry.namespace,
+            content: entry.content,
+            source: source_to_string(&entry.metadata.source).to_string(),
+            session_key: entry.metadata.session_key,
+            tags: entry.metadata.tags,
+            importance: entry.metadata.importance,
+            metadata: entry.metadata.custom,
+            created_at: entry.created_at.timestamp_millis(),
+            updated_at: entry.updated_at.timestamp_millis(),
+        };
+        json!({
+            "id": point_id(&entry.id),
+            "vector": entry.embedding,
+            "payload": payload,
+        })
+    }
+
+    fn point_to_entry(&self, point: Point) -> Result<MemoryEntry> {
+        let payload = point.payload;
+        Ok(MemoryEntry {
+            id: payload.memory_id,
+            agent_id: payload.agent_id,
+            namespace: payload.namespace,
+            content: payload.content,
+            embedding: point
+                .vector
+                .unwrap_or_else(|| vec![0.0; self.embedding_dim]),
+            metadata: MemoryMetadata {
+                source: string_to_source(&payload.source),
+                session_key: payload.session_key,
+                tags: payload.tags,
+                importance: payload.importance,
+                custom: payload.metadata,
+            },
+            created_at: from_millis(payload.created_at)?,
+            updated_at: from_millis(payload.updated_at)?,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl MemoryStore for QdrantMemoryStore {
+    async fn store(&self, entry: MemoryEntry) -> Result<String> {
+        let mut ids = self.store_batch(vec![entry]).await?;
+        ids.pop()
+            .ok_or_else(|| anyhow!("Qdrant upsert returned no ID"))
+    }
+
+    async fn query(&self, query: &str, opts: MemoryQueryOptions) -> Result<Vec<MemoryMatch>> {
+        let query_embedding = self.embedder.embed(query).await?;
+
+        let response: Response<Vec<Point>> = serde_json::from_value(
+            self.call(
+                Method::POST,
+                "/points/search",
+                json!({
+                    "vector": query_embedding,
+                    "filter": Self::filter_to_qdrant(&opts.filter),
+                    "limit": opts.top_k,
+                    "with_payload": true,
+                }),
+            )
+            .await?,
+        )?;
+
+        let min_score = opts.min_score.unwrap_or(0.0);
+        let mut matches = Vec::new();
+        for point in response.result {
+            // Qdrant returns the cosine similarity; rescale it to the
+            // 1 - distance / 2 score the other backends use
+            let score = (1.0 + point.score.unwrap_or(-1.0)) / 2.0;
+            if score < min_score {
+                continue;
+            }
+            matches.push(MemoryMatch {
+                entry: self.point_to_entry(point)?,
+                score,
+            });
+        }
+
+        Ok(matches)
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.call(
+            Method::POST,
+            "/points/delete?wait=true",
+            json!({ "points": [point_id(id)] }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn list(&self, filter: MemoryFilter) -> Result<Vec<MemoryEntry>> {
+        let qdrant_filter = Self::filter_to_qdrant(&filter);
+        let mut entries = Vec::new();
+        let mut offset = Value::Null;
+
+        loop {
+            let mut body = json!({
+                "filter": qdrant_filter,
+                "limit": SCROLL_PAGE_SIZE,
+                "with_payload": true,
+                "with_vector": true,
+            });
+            if !offset.is_null() {
+                body["offset"] = offset;
+            }
+
+            let page: Response<ScrollPage> =
+                serde_json::from_value(self.call(Method::POST, "/points/scroll", body).await?)?;
+            for point in page.result.points {
+                entries.push(self.point_to_entry(point)?);
+            }
+            match page.result.next_page_offset {
+                Some(next) if !next.is_null() => offset = next,
+                _ => break,
+            }
+        }
+
+        Ok(entries)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Derives the Qdrant point ID of a memory ID. Memory IDs that are UUIDs
+/// are used as-is.
+fn point_id(id: &str) -> String {
+    Uuid::parse_str(id)
+        .unwrap_or_else(|_| Uuid::new_v5(&Uuid::NAMESPACE_OID, id.as_bytes()))
+        .to_string()
+}
+
+fn from_millis(millis: i64) -> Result<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(millis).ok_or_else(|| anyhow!("Invalid timestamp"))
+}
+
+/// Helper to convert MemorySource to string.
+fn source_to_string(source: &MemorySource) -> &'static str {
+    match source {
+        MemorySource::Agent => "Agent",
+        MemorySource::User => "User",
+        MemorySource::System => "System",
+    }
+}
+
+/// Helper to convert string to MemorySource.
+fn string_to_source(s: &str) -> MemorySource {
+    match s {
+        "Agent" => MemorySource::Agent,
+        "User" => MemorySource::User,
+        _ => MemorySource::System,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_id_is_stable() {
+        let uuid = Uuid::new_v4().to_string();
+        assert_eq!(point_id(&uuid), uuid);
+        assert_eq!(point_id("fact-1"), point_id("fact-1"));
+        assert_ne!(point_id("fact-1"), point_id("fact-2"));
+        assert!(Uuid::parse_str(&point_id("fact-1")).is_ok());
+    }
+
+    #[test]
+    fn test_filter_to_qdrant() {
+        let after = DateTime::from_timestamp_millis(1_000).unwrap();
+        let filter = MemoryFilter {
+            agent_id: Some("agent-1".to_string()),
+            tags: Some(vec!["a".to_string(), "b".to_string()]),
+            source: Some(MemorySource::User),
+            importance_min: Some(0.5),
+            created_after: Some(after),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            QdrantMemoryStore::filter_to_qdrant(&filter),
+            json!({ "must": [
+                { "key": "agent_id", "match": { "value": "agent-1" } },
+                { "key": "source", "match": { "value": "User" } },
+                { "key": "tags", "match": { "value": "a" } },
+                { "key": "tags", "match": { "value": "b" } },
+                { "key": "importance", "range": { "gte": 0.5 } },
+                { "key": "created_at", "range": { "gte": 1000 } },
+            ]})
+        );
+        assert_eq!(
+            QdrantMemoryStore::filter_to_qdrant(&MemoryFilter::default()),
+            json!({ "must": [] })
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Reranking of retrieved memories before context injection.
+//!
+//! Vector similarity is a cheap first pass: it finds memories about the
+//! right topic but is a poor judge of which one actually answers the query.
+//! A [`Reranker`] reads the query together with each candidate and scores
+//! its relevance, either by asking a chat model ([`LlmReranker`]) or with a
+//! local cross-encoder (`LocalCrossEncoder`, behind the `local-embeddings`
+//! feature).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{ChatCompletionRequest, Message, MessageContent, Role};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+
+use crate::types::MemoryMatch;
+
+/// Scores how relevant retrieved memories are to a query.
+#[async_trait]
+pub trait Reranker: Send + Sync {
+    /// Scores each candidate's relevance to `query`.
+    ///
+    /// # Returns
+    /// Returns one score per candidate, in order, between 0.0 and 1.0.
+    ///
+    /// # Errors
+    /// Returns an error if scoring fails; the pipeline then keeps the
+    /// retrieval order.
+    async fn score(&self, query: &str, candidates: &[MemoryMatch]) -> Result<Vec<f32>>;
+}
+
+/// Settings for the reranking stage of the query pipeline.
+#[derive(Debug, Clone)]
+pub struct RerankConfig {
+    /// Number of top retrieval hits passed to the reranker; at least
+    /// `top_k` hits are always retrieved.
+    pub candidates: usize,
+    /// Maximum time to wait for the reranker. When it is exceeded the
+    /// retrieval scores are used unchanged.
+    pub latency_budget: Duration,
+    /// Weight of the rerank score against the retrieval score, from 0.0
+    /// (retrieval only) to 1.0 (rerank only).
+    pub weight: f32,
+}
+
+impl Default for RerankConfig {
+    fn default() -> Self {
+        Self {
+            candidates: 20,
+            latency_budget: Duration::from_secs(2),
+            weight: 0.8,
+        }
+    }
+}
+
+/// Reranker asking a chat model to grade each memory.
+///
+/// All candidates are graded in a single request, so the cost is one
+/// completion per query regardless of the number of candidates.
+pub struct LlmReranker {
+    provider: Arc<dyn ModelProvider>,
+    model: String,
+}
+
+impl LlmReranker {
+    /// Creates a reranker using `model` on `provider`.
+    pub fn new(provider: Arc<dyn ModelProvider>, model: impl Into<String>) -> Self {
+        Self {
+            provider,
+            model: model.into(),
+        }
+    }
+
+    /// Builds the grading prompt listing the numbered candidates.
+    fn prompt(query: &str, candidates: &[MemoryMatch]) -> String {
+        let memories: Vec<String> = candidates
+            .iter()
+            .enumerate()
+            .map(|(i, m)| format!("[{}] {}", i + 1, m.entry.content.replace('\n', " ")))
+            .collect();
+        format!(
+            "Rate how relevant each memory is to the query, from 0 (unrelated) to 10 \
+             (directly answers it).\n\nQuery: {}\n\nMemories:\n{}\n\n\
+             Reply with only a JSON array of {} numbers, one per memory in order.",
+            query,
+            memories.join("\n"),
+            candidates.len()
+        )
+    }
+
+    /// Parses the model's grades, scaled to 0.0..=1.0.
+    ///
+    /// The array may be wrapped in prose or a code fence.
+    fn parse_scores(response: &str, expected: usize) -> Result<Vec<f32>> {
+        let (start, end) = response
+            .find('[')
+            .zip(response.rfind(']'))
+            .filter(|(start, end)| start < end)
+            .ok_or_else(|| anyhow!("Reranker response contains no JSON array"))?;
+        let grades: Vec<f32> = serde_json::from_str(&response[start..=end])?;
+        if grades.len() != expected {
+            return Err(anyhow!(
+                "Reranker returned {} scores for {} memories",
+                grades.len(),
+                expected
+            ));
+        }
+        Ok(grades
+            .into_iter()
+            .map(|grade| (grade / 10.0).clamp(0.0, 1.0))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Reranker for LlmReranker {
+    async fn score(&self, query: &str, candidates: &[MemoryMatch]) -> Result<Vec<f32>> {
+        if candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let request = ChatCompletionRequest {
+            model: self.model.clone(),
+            messages: vec![Message {
+                role: Role::User,
+                content: MessageContent::Text(Self::prompt(query, candidates)),
+                tool_calls: None,
+                tool_call_id: None,
+            }],
+            tools: None,
+            temperature: Some(0.0),
+            max_tokens: Some(16 + 8 * candidates.len() as u32),
+            stop: None,
+            stream: false,
+        };
+
+        let mut stream = self.provider.chat_completion(request).await?;
+        let mut response = String::new();
+        while let Some(chunk) = stream.next().await {
+            if let Some(content) = chunk?.delta.content {
+                response.push_str(&content);
+            }
+        }
+        Self::parse_scores(&response, candidates.len())
+    }
+}
+
+#[cfg(feature = "local-embeddings")]
+#[async_trait]
+impl Reranker for crate::embedding::LocalCrossEncoder {
+    async fn score(&self, query: &str, candidates: &[MemoryMatch]) -> Result<Vec<f32>> {
+        let passages: Vec<&str> = candidates
+            .iter()
+            .map(|m| m.entry.content.as_str())
+            .collect();
+        crate::embedding::LocalCrossEncoder::score(self, query, &passages).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MemoryEntry;
+    use aisopod_provider::helpers::MockProvider;
+    use aisopod_provider::types::{ChatCompletionChunk, MessageDelta};
+
+    fn candidate(id: &str, content: &str) -> MemoryMatch {
+        MemoryMatch {
+            entry: MemoryEntry::new(
+                id.to_string(),
+                "agent-1".to_string(),
+                content.to_string(),
+                vec![0.0; 4],
+            ),
+            score: 0.5,
+        }
+    }
+
+    fn chunk(content: &str) -> std::result::Result<ChatCompletionChunk, String> {
+        Ok(ChatCompletionChunk {
+            id: "chunk".to_string(),
+            delta: MessageDelta {
+                role: None,
+                content: Some(content.to_string()),
+                tool_calls: None,
+                reasoning: None,
+            },
+            finish_reason: None,
+            usage: None,
+        })
+    }
+
+    #[test]
+    fn test_parse_scores() {
+        let scores = LlmReranker::parse_scores("```json\n[10, 2.5, 0, 12]\n```", 4).unwrap();
+        assert_eq!(scores, vec![1.0, 0.25, 0.0, 1.0]);
+
+        assert!(LlmReranker::parse_scores("[1, 2]", 3).is_err());
+        assert!(LlmReranker::parse_scores("no idea", 1).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_llm_reranker_collects_streamed_response() {
+        let provider = MockProvider::new("mock").with_chunks(vec![chunk("[3, "), chunk("9]")]);
+        let reranker = LlmReranker::new(Arc::new(provider), "mock-model");
+
+        let candidates = vec![
+            candidate("a", "The user owns a cat"),
+            candidate("b", "The user's cat is called Miso"),
+        ];
+        let scores = reranker
+            .score("what is the cat's name", &candidates)
+            .await
+            .unwrap();
+        assert_eq!(scores, vec![0.3, 0.9]);
+    }
+}
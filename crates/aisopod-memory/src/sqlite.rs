@@ -4,6 +4,7 @@
 //! the sqlite-vec extension for vector storage and cosine similarity search.
 
 use crate::embedding::EmbeddingProvider;
+use crate::keyword::tokenize;
 use crate::store::MemoryStore;
 use crate::types::{MemoryEntry, MemoryFilter, MemoryMatch, MemoryQueryOptions, MemorySource};
 use anyhow::{anyhow, Result};
@@ -18,9 +19,10 @@ use sqlite_vec::sqlite3_vec_init;
 
 /// Memory storage backend using SQLite with sqlite-vec extension.
 ///
-/// This struct manages a SQLite database with two tables:
+/// This struct manages a SQLite database with three tables:
 /// - `memories`: Stores memory metadata and content
 /// - `memory_embeddings`: Virtual table for vector operations using sqlite-vec
+/// - `memories_fts`: FTS5 full-text index of the content for keyword search
 pub struct SqliteMemoryStore {
     db: Arc<Mutex<Connection>>,
     embedding_dim: usize,
@@ -32,6 +34,7 @@ pub struct SqliteMemoryStore {
 struct DbMemory {
     id: String,
     agent_id: String,
+    namespace: String,
     content: String,
     source: String,
     session_key: Option<String>,
@@ -47,6 +50,7 @@ struct DbMemory {
 struct DbMemoryWithEmbedding {
     id: String,
     agent_id: String,
+    namespace: String,
     content: String,
     source: String,
     session_key: Option<String>,
@@ -63,6 +67,7 @@ struct DbMemoryWithEmbedding {
 struct DbMemoryInput<'a> {
     id: &'a str,
     agent_id: &'a str,
+    namespace: &'a str,
     content: &'a str,
     source: &'a str,
     session_key: &'a Option<String>,
@@ -133,6 +138,7 @@ impl SqliteMemoryStore {
             "CREATE TABLE IF NOT EXISTS memories (
                 id TEXT PRIMARY KEY,
                 agent_id TEXT NOT NULL,
+                namespace TEXT NOT NULL DEFAULT '',
                 content TEXT NOT NULL,
                 source TEXT NOT NULL,
                 session_key TEXT,
@@ -144,9 +150,22 @@ impl SqliteMemoryStore {
             );"
         ))?;
 
-        // Create index on agent_id for fast scoped queries
+        // Add the namespace column to databases created before namespaces
+        let has_namespace: bool = db.query_row(
+            "SELECT EXISTS (SELECT 1 FROM pragma_table_info('memories') WHERE name = 'namespace')",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_namespace {
+            db.execute_batch(
+                "ALTER TABLE memories ADD COLUMN namespace TEXT NOT NULL DEFAULT '';",
+            )?;
+        }
+
+        // Create indexes on agent_id and namespace for fast scoped queries
         db.execute_batch(
-            "CREATE INDEX IF NOT EXISTS idx_memories_agent_id ON memories(agent_id);",
+            "CREATE INDEX IF NOT EXISTS idx_memories_agent_id ON memories(agent_id);
+             CREATE INDEX IF NOT EXISTS idx_memories_namespace ON memories(namespace, agent_id);",
         )?;
 
         // Create the vector table with dynamic dimension
@@ -157,6 +176,25 @@ impl SqliteMemoryStore {
             );"
         ))?;
 
+        // Create the full-text index for keyword search, keeping underscores
+        // inside tokens so code identifiers stay whole. Backfill it when it
+        // is added to an existing database.
+        let has_fts: bool = db.query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE name = 'memories_fts')",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_fts {
+            db.execute_batch(
+                "CREATE VIRTUAL TABLE memories_fts USING fts5(
+                    id UNINDEXED,
+                    content,
+                    tokenize = \"unicode61 tokenchars '_'\"
+                );
+                INSERT INTO memories_fts (id, content) SELECT id, content FROM memories;",
+            )?;
+        }
+
         Ok(())
     }
 
@@ -188,6 +226,59 @@ impl SqliteMemoryStore {
             _ => MemorySource::System,
         }
     }
+
+    /// Builds the SQL conditions and parameters for a filter, against the
+    /// `memories` table aliased as `m`.
+    fn filter_conditions(filter: &MemoryFilter) -> (Vec<String>, Vec<Box<dyn ToSql>>) {
+        let mut conditions = Vec::new();
+        let mut params: Vec<Box<dyn ToSql>> = Vec::new();
+
+        if let Some(agent_id) = &filter.agent_id {
+            conditions.push("m.agent_id = ?".to_string());
+            params.push(Box::new(agent_id.clone()));
+        }
+
+        if let Some(namespace) = &filter.namespace {
+            conditions.push("m.namespace = ?".to_string());
+            params.push(Box::new(namespace.clone()));
+        }
+
+        if let Some(source) = &filter.source {
+            let source_str = Self::source_to_string(source);
+            conditions.push("m.source = ?".to_string());
+            params.push(Box::new(source_str));
+        }
+
+        if let Some(session_key) = &filter.session_key {
+            conditions.push("m.session_key = ?".to_string());
+            params.push(Box::new(session_key.clone()));
+        }
+
+        if let Some(importance_min) = filter.importance_min {
+            conditions.push("m.importance >= ?".to_string());
+            params.push(Box::new(importance_min));
+        }
+
+        if let Some(created_after) = &filter.created_after {
+            conditions.push("m.created_at >= ?".to_string());
+            params.push(Box::new(created_after.to_rfc3339()));
+        }
+
+        if let Some(created_before) = &filter.created_before {
+            conditions.push("m.created_at <= ?".to_string());
+            params.push(Box::new(created_before.to_rfc3339()));
+        }
+
+        // Handle tags filter with json_each
+        for tag in filter.tags.iter().flatten() {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM json_each(m.tags) WHERE json_each.value = ?)".to_string(),
+            );
+            params.push(Box::new(tag.clone()));
+        }
+
+        (conditions, params)
+    }
 }
 
 #[async_trait::async_trait]
@@ -209,6 +300,7 @@ impl MemoryStore for SqliteMemoryStore {
         let db_input = DbMemoryInput {
             id: &entry.id,
             agent_id: &entry.agent_id,
+            namespace: &entry.namespace,
             content: &entry.content,
             source: source_str,
             session_key: &entry.metadata.session_key,
@@ -222,10 +314,11 @@ impl MemoryStore for SqliteMemoryStore {
         // Insert into memories table
         db.execute(
             r#"
-            INSERT INTO memories (id, agent_id, content, source, session_key, tags, importance, metadata, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO memories (id, agent_id, content, source, session_key, tags, importance, metadata, created_at, updated_at, namespace)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             ON CONFLICT(id) DO UPDATE SET
                 agent_id = excluded.agent_id,
+                namespace = excluded.namespace,
                 content = excluded.content,
                 source = excluded.source,
                 session_key = excluded.session_key,
@@ -245,9 +338,20 @@ impl MemoryStore for SqliteMemoryStore {
                 metadata_json.to_string(),
                 db_input.created_at,
                 db_input.updated_at,
+                db_input.namespace,
             ],
         )?;
 
+        // Replace the entry in the full-text index
+        db.execute(
+            "DELETE FROM memories_fts WHERE id = ?",
+            rusqlite::params![&entry.id],
+        )?;
+        db.execute(
+            "INSERT INTO memories_fts (id, content) VALUES (?, ?)",
+            rusqlite::params![&entry.id, &entry.content],
+        )?;
+
         // Insert into memory_embeddings table
         let embedding: Vec<f32> = entry.embedding.iter().map(|&x| x as f32).collect();
         // Serialize embedding as bytes for storage
@@ -285,52 +389,7 @@ impl MemoryStore for SqliteMemoryStore {
         let db = self.db.lock().map_err(|e| anyhow!(e.to_string()))?;
 
         // Build the filter conditions
-        let mut conditions = Vec::new();
-        let mut params: Vec<Box<dyn ToSql>> = Vec::new();
-
-        if let Some(agent_id) = &opts.filter.agent_id {
-            conditions.push("m.agent_id = ?".to_string());
-            params.push(Box::new(agent_id));
-        }
-
-        if let Some(source) = &opts.filter.source {
-            let source_str = Self::source_to_string(source);
-            conditions.push("m.source = ?".to_string());
-            params.push(Box::new(source_str));
-        }
-
-        if let Some(session_key) = &opts.filter.session_key {
-            conditions.push("m.session_key = ?".to_string());
-            params.push(Box::new(session_key));
-        }
-
-        if let Some(importance_min) = &opts.filter.importance_min {
-            conditions.push("m.importance >= ?".to_string());
-            params.push(Box::new(importance_min));
-        }
-
-        if let Some(created_after) = &opts.filter.created_after {
-            conditions.push("m.created_at >= ?".to_string());
-            params.push(Box::new(created_after.to_rfc3339()));
-        }
-
-        if let Some(created_before) = &opts.filter.created_before {
-            conditions.push("m.created_at <= ?".to_string());
-            params.push(Box::new(created_before.to_rfc3339()));
-        }
-
-        // Handle tags filter with json_each
-        if let Some(tags) = &opts.filter.tags {
-            if !tags.is_empty() {
-                // For each tag, create a condition and add it to params
-                for tag in tags {
-                    conditions.push(format!(
-                        "EXISTS (SELECT 1 FROM json_each(m.tags) WHERE json_each.value = ?)"
-                    ));
-                    params.push(Box::new(tag));
-                }
-            }
-        }
+        let (conditions, params) = Self::filter_conditions(&opts.filter);
 
         let where_clause = if conditions.is_empty() {
             String::new()
@@ -361,7 +420,7 @@ impl MemoryStore for SqliteMemoryStore {
         // Execute the query with vector search
         let sql = format!(
             r#"
-            SELECT m.id, m.agent_id, m.content, m.source, m.session_key, m.tags, m.importance, m.metadata, m.created_at, m.updated_at, e.distance
+            SELECT m.id, m.agent_id, m.content, m.source, m.session_key, m.tags, m.importance, m.metadata, m.created_at, m.updated_at, e.distance, m.namespace
             FROM memory_embeddings e
             JOIN memories m ON e.id = m.id
             WHERE {}
@@ -385,6 +444,7 @@ impl MemoryStore for SqliteMemoryStore {
                     created_at: row.get(8)?,
                     updated_at: row.get(9)?,
                     distance: row.get::<_, f64>(10)?,
+                    namespace: row.get(11)?,
                 })
             })?
             .filter_map(|r| r.ok())
@@ -407,6 +467,7 @@ impl MemoryStore for SqliteMemoryStore {
                     entry: MemoryEntry {
                         id: m.id,
                         agent_id: m.agent_id,
+                        namespace: m.namespace,
                         content: m.content,
                         embedding: vec![0.0; self.embedding_dim], // Placeholder - embeddings not retrieved
                         metadata: crate::types::MemoryMetadata {
@@ -447,6 +508,9 @@ impl MemoryStore for SqliteMemoryStore {
             rusqlite::params![id],
         )?;
 
+        // Delete from the full-text index
+        db.execute("DELETE FROM memories_fts WHERE id = ?", rusqlite::params![id])?;
+
         // Delete from memories table
         db.execute("DELETE FROM memories WHERE id = ?", rusqlite::params![id])?;
 
@@ -457,51 +521,7 @@ impl MemoryStore for SqliteMemoryStore {
         let db = self.db.lock().map_err(|e| anyhow!(e.to_string()))?;
 
         // Build the filter conditions
-        let mut conditions = Vec::new();
-        let mut params: Vec<Box<dyn ToSql>> = Vec::new();
-
-        if let Some(agent_id) = filter.agent_id {
-            conditions.push("agent_id = ?".to_string());
-            params.push(Box::new(agent_id));
-        }
-
-        if let Some(source) = filter.source {
-            let source_str = Self::source_to_string(&source);
-            conditions.push("source = ?".to_string());
-            params.push(Box::new(source_str));
-        }
-
-        if let Some(session_key) = filter.session_key {
-            conditions.push("session_key = ?".to_string());
-            params.push(Box::new(session_key));
-        }
-
-        if let Some(importance_min) = filter.importance_min {
-            conditions.push("importance >= ?".to_string());
-            params.push(Box::new(importance_min));
-        }
-
-        if let Some(created_after) = filter.created_after {
-            conditions.push("created_at >= ?".to_string());
-            params.push(Box::new(created_after.to_rfc3339()));
-        }
-
-        if let Some(created_before) = filter.created_before {
-            conditions.push("created_at <= ?".to_string());
-            params.push(Box::new(created_before.to_rfc3339()));
-        }
-
-        // Handle tags filter with json_each
-        if let Some(tags) = filter.tags {
-            if !tags.is_empty() {
-                for tag in tags {
-                    conditions.push(format!(
-                        "EXISTS (SELECT 1 FROM json_each(m.tags) WHERE json_each.value = ?)"
-                    ));
-                    params.push(Box::new(tag));
-                }
-            }
-        }
+        let (conditions, params) = Self::filter_conditions(&filter);
 
         let where_clause = if conditions.is_empty() {
             String::new()
@@ -511,7 +531,7 @@ impl MemoryStore for SqliteMemoryStore {
 
         let sql = format!(
             r#"
-            SELECT m.id, m.agent_id, m.content, m.source, m.session_key, m.tags, m.importance, m.metadata, m.created_at, m.updated_at, e.embedding
+            SELECT m.id, m.agent_id, m.content, m.source, m.session_key, m.tags, m.importance, m.metadata, m.created_at, m.updated_at, e.embedding, m.namespace
             FROM memories m
             JOIN memory_embeddings e ON m.id = e.id
             {}
@@ -534,6 +554,7 @@ impl MemoryStore for SqliteMemoryStore {
                     created_at: row.get(8)?,
                     updated_at: row.get(9)?,
                     embedding_bytes: row.get(10)?,
+                    namespace: row.get(11)?,
                 })
             })?
             .filter_map(|r| r.ok())
@@ -555,6 +576,7 @@ impl MemoryStore for SqliteMemoryStore {
                 Some(MemoryEntry {
                     id: m.id,
                     agent_id: m.agent_id,
+                    namespace: m.namespace,
                     content: m.content,
                     embedding,
                     metadata: crate::types::MemoryMetadata {
@@ -573,6 +595,92 @@ impl MemoryStore for SqliteMemoryStore {
         Ok(entries)
     }
 
+    async fn keyword_query(&self, query: &str, opts: MemoryQueryOptions) -> Result<Vec<MemoryMatch>> {
+        // Quote each term so FTS5 operators in the query are taken literally
+        let terms = tokenize(query);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let match_expr = terms
+            .iter()
+            .map(|term| format!("\"{}\"", term))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+
+        let db = self.db.lock().map_err(|e| anyhow!(e.to_string()))?;
+
+        let (conditions, params) = Self::filter_conditions(&opts.filter);
+        let mut all_params: Vec<Box<dyn ToSql>> = vec![Box::new(match_expr)];
+        all_params.extend(params);
+        all_params.push(Box::new(opts.top_k as i64));
+
+        let filter_clause: String = conditions
+            .iter()
+            .map(|condition| format!(" AND {}", condition))
+            .collect();
+
+        // bm25() is lower for better matches, so negate it into a score
+        let sql = format!(
+            r#"
+            SELECT m.id, m.agent_id, m.content, m.source, m.session_key, m.tags, m.importance, m.metadata, m.created_at, m.updated_at, -bm25(memories_fts) AS score, m.namespace
+            FROM memories_fts f
+            JOIN memories m ON f.id = m.id
+            WHERE memories_fts MATCH ?{}
+            ORDER BY score DESC
+            LIMIT ?
+            "#,
+            filter_clause
+        );
+
+        let mut stmt = db.prepare(&sql)?;
+        let matches = stmt
+            .query_map(rusqlite::params_from_iter(all_params.iter()), |row| {
+                Ok(DbMemoryMatch {
+                    id: row.get(0)?,
+                    agent_id: row.get(1)?,
+                    content: row.get(2)?,
+                    source: row.get(3)?,
+                    session_key: row.get(4)?,
+                    tags: row.get(5)?,
+                    importance: row.get(6)?,
+                    metadata: row.get(7)?,
+                    created_at: row.get(8)?,
+                    updated_at: row.get(9)?,
+                    distance: row.get(10)?,
+                    namespace: row.get(11)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .filter_map(|m| {
+                Some(MemoryMatch {
+                    score: m.distance as f32,
+                    entry: MemoryEntry {
+                        id: m.id,
+                        agent_id: m.agent_id,
+                        namespace: m.namespace,
+                        content: m.content,
+                        embedding: vec![0.0; self.embedding_dim], // Placeholder - embeddings not retrieved
+                        metadata: crate::types::MemoryMetadata {
+                            source: Self::string_to_source(&m.source),
+                            session_key: m.session_key,
+                            tags: Self::deserialize_tags(&m.tags),
+                            importance: m.importance as f32,
+                            custom: serde_json::from_str(&m.metadata).unwrap_or_default(),
+                        },
+                        created_at: chrono::DateTime::parse_from_rfc3339(&m.created_at)
+                            .ok()?
+                            .with_timezone(&chrono::Utc),
+                        updated_at: chrono::DateTime::parse_from_rfc3339(&m.updated_at)
+                            .ok()?
+                            .with_timezone(&chrono::Utc),
+                    },
+                })
+            })
+            .collect();
+
+        Ok(matches)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -583,6 +691,7 @@ impl MemoryStore for SqliteMemoryStore {
 struct DbMemoryMatch {
     id: String,
     agent_id: String,
+    namespace: String,
     content: String,
     source: String,
     session_key: Option<String>,
@@ -615,6 +724,48 @@ mod tests {
         assert!(store.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_namespace_column_added_to_existing_database() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("old.db");
+        Connection::open(&db_path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE memories (
+                    id TEXT PRIMARY KEY,
+                    agent_id TEXT NOT NULL,
+                    content TEXT NOT NULL,
+                    source TEXT NOT NULL,
+                    session_key TEXT,
+                    tags TEXT DEFAULT '[]',
+                    importance REAL DEFAULT 0.5,
+                    metadata TEXT DEFAULT '{}',
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                );",
+            )
+            .unwrap();
+
+        let store = SqliteMemoryStore::new(db_path.to_str().unwrap(), 4).unwrap();
+        let entry = MemoryEntry::new(
+            "scoped-1".to_string(),
+            "agent-1".to_string(),
+            "scoped content".to_string(),
+            vec![0.1, 0.2, 0.3, 0.4],
+        )
+        .with_namespace("tenant-a/agent-1");
+        store.store(entry).await.unwrap();
+
+        let filter = |namespace: &str| MemoryFilter {
+            namespace: Some(namespace.to_string()),
+            ..Default::default()
+        };
+        let entries = store.list(filter("tenant-a/agent-1")).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].namespace, "tenant-a/agent-1");
+        assert!(store.list(filter("")).await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_sqlite_store_store_and_query() {
         let dir = tempdir().unwrap();
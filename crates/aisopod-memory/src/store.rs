@@ -3,9 +3,17 @@
 //! This module defines the core trait that all memory backends must implement,
 //! providing a standardized interface for storing, querying, and managing memories.
 
-use crate::types::{MemoryEntry, MemoryFilter, MemoryMatch, MemoryQueryOptions};
-use anyhow::Result;
+use crate::keyword::bm25_rank;
+use crate::types::{
+    ImportOptions, MemoryEntry, MemoryFilter, MemoryMatch, MemoryQueryOptions, MemoryUpdate,
+};
+use anyhow::{anyhow, Context, Result};
 use std::any::Any;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+use uuid::Uuid;
+
+/// Number of texts embedded per request when re-embedding an import.
+const IMPORT_EMBED_BATCH: usize = 64;
 
 /// Trait for memory storage and retrieval operations.
 ///
@@ -73,6 +81,170 @@ pub trait MemoryStore: Send + Sync {
     /// Returns an error if listing fails (e.g., database error).
     async fn list(&self, filter: MemoryFilter) -> Result<Vec<MemoryEntry>>;
 
+    /// Fetches a memory entry by its ID.
+    ///
+    /// The default implementation scans [`list`](MemoryStore::list) with an
+    /// empty filter; backends with a primary key index may override it.
+    ///
+    /// # Returns
+    /// Returns the entry, or `None` if no memory has this ID.
+    ///
+    /// # Errors
+    /// Returns an error if the lookup fails (e.g., database error).
+    async fn get(&self, id: &str) -> Result<Option<MemoryEntry>> {
+        let entries = self.list(MemoryFilter::default()).await?;
+        Ok(entries.into_iter().find(|entry| entry.id == id))
+    }
+
+    /// Applies `update` to the memory with ID `id`.
+    ///
+    /// Fields left unset in the update keep their value, and `updated_at`
+    /// is set to now.
+    ///
+    /// # Returns
+    /// Returns the updated entry.
+    ///
+    /// # Errors
+    /// Returns an error if no memory has this ID, or if re-embedding or
+    /// storing fails.
+    async fn update(&self, id: &str, update: MemoryUpdate) -> Result<MemoryEntry> {
+        let mut entry = self
+            .get(id)
+            .await?
+            .ok_or_else(|| anyhow!("Memory {} not found", id))?;
+
+        if let Some(content) = update.content.filter(|content| *content != entry.content) {
+            if let Some(embedder) = &update.embedder {
+                entry.embedding = embedder.embed(&content).await?;
+            }
+            entry.content = content;
+        }
+        if let Some(tags) = update.tags {
+            entry.metadata.tags = tags;
+        }
+        if let Some(importance) = update.importance {
+            entry.metadata.importance = importance.clamp(0.0, 1.0);
+        }
+        entry.updated_at = chrono::Utc::now();
+
+        self.store(entry.clone()).await?;
+        Ok(entry)
+    }
+
+    /// Performs a keyword search for matching memories.
+    ///
+    /// Ranks memories by the terms they share with the query, catching exact
+    /// names, IDs and code identifiers that semantic search can miss. The
+    /// default implementation lists the memories matching the filter and
+    /// ranks them with BM25; backends with a full-text index override it.
+    ///
+    /// # Arguments
+    /// * `query` - The query string to search for
+    /// * `opts` - Query options; `min_score` is ignored since keyword scores
+    ///   are not comparable to similarity scores
+    ///
+    /// # Returns
+    /// Returns a list of memory matches sorted by keyword score (descending),
+    /// limited to `top_k` results.
+    ///
+    /// # Errors
+    /// Returns an error if the search fails (e.g., database error).
+    async fn keyword_query(&self, query: &str, opts: MemoryQueryOptions) -> Result<Vec<MemoryMatch>> {
+        let entries = self.list(opts.filter).await?;
+        Ok(bm25_rank(query, entries, opts.top_k))
+    }
+
+    /// Writes the memories matching `filter` as JSON Lines.
+    ///
+    /// Each line is one [`MemoryEntry`] with its embedding and metadata, in
+    /// creation order, so the output can be restored with
+    /// [`import_jsonl`](MemoryStore::import_jsonl) into any backend.
+    ///
+    /// # Returns
+    /// Returns the number of memories written.
+    ///
+    /// # Errors
+    /// Returns an error if listing or writing fails.
+    async fn export_jsonl(
+        &self,
+        filter: MemoryFilter,
+        writer: &mut (dyn AsyncWrite + Send + Unpin),
+    ) -> Result<usize> {
+        let mut entries = self.list(filter).await?;
+        entries.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+
+        for entry in &entries {
+            let mut line = serde_json::to_vec(entry)?;
+            line.push(b'\n');
+            writer.write_all(&line).await?;
+        }
+        writer.flush().await?;
+        Ok(entries.len())
+    }
+
+    /// Stores the memories read as JSON Lines from `reader`.
+    ///
+    /// The whole input is parsed before anything is stored, so a malformed
+    /// line leaves the store untouched. Memories keep their IDs, so
+    /// importing a file twice updates rather than duplicates them.
+    ///
+    /// # Arguments
+    /// * `reader` - JSON Lines as written by [`export_jsonl`](MemoryStore::export_jsonl);
+    ///   blank lines are skipped
+    /// * `opts` - Agent reassignment and re-embedding options
+    ///
+    /// # Returns
+    /// Returns the number of memories stored.
+    ///
+    /// # Errors
+    /// Returns an error if a line is not a valid memory, a memory has no
+    /// embedding and no embedder is given, or storing fails.
+    async fn import_jsonl(
+        &self,
+        reader: &mut (dyn AsyncBufRead + Send + Unpin),
+        opts: ImportOptions,
+    ) -> Result<usize> {
+        let mut entries = Vec::new();
+        let mut lines = reader.lines();
+        let mut number = 0;
+        while let Some(line) = lines.next_line().await? {
+            number += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut entry: MemoryEntry = serde_json::from_str(&line)
+                .with_context(|| format!("Invalid memory on line {}", number))?;
+            if let Some(agent_id) = &opts.agent_id {
+                let key = format!("{}/{}", agent_id, entry.id);
+                entry.id = Uuid::new_v5(&Uuid::NAMESPACE_OID, key.as_bytes()).to_string();
+                entry.agent_id = agent_id.clone();
+            }
+            if entry.embedding.is_empty() && opts.embedder.is_none() {
+                return Err(anyhow!(
+                    "Memory on line {} has no embedding and no embedder was given",
+                    number
+                ));
+            }
+            entries.push(entry);
+        }
+
+        if let Some(embedder) = &opts.embedder {
+            for chunk in entries.chunks_mut(IMPORT_EMBED_BATCH) {
+                let texts: Vec<&str> = chunk.iter().map(|e| e.content.as_str()).collect();
+                let embeddings = embedder.embed_batch(&texts).await?;
+                for (entry, embedding) in chunk.iter_mut().zip(embeddings) {
+                    entry.embedding = embedding;
+                }
+            }
+        }
+
+        let count = entries.len();
+        for entry in entries {
+            self.store(entry).await?;
+        }
+        Ok(count)
+    }
+
     /// Returns a reference to self as `Any` for downcasting.
     fn as_any(&self) -> &dyn Any;
 }
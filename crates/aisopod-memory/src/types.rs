@@ -3,8 +3,28 @@
 //! This module defines the fundamental data structures used throughout
 //! the memory system, including entries, metadata, filters, and query options.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::embedding::EmbeddingProvider;
+
+/// Custom metadata key holding the time an entry expires, as RFC 3339.
+///
+/// Kept in [`MemoryMetadata::custom`] so every store persists it without a
+/// schema change.
+pub const EXPIRES_AT_KEY: &str = "expires_at";
+
+/// Custom metadata key holding the time an expired entry was archived.
+pub const ARCHIVED_AT_KEY: &str = "archived_at";
+
+/// Custom metadata key holding the IDs of the memories a summary replaced.
+pub const SUMMARIZED_FROM_KEY: &str = "summarized_from";
+
+/// Custom metadata key holding the ID of the summary that replaced an
+/// archived memory.
+pub const SUMMARIZED_INTO_KEY: &str = "summarized_into";
 
 /// The source of a memory entry.
 ///
@@ -58,6 +78,10 @@ pub struct MemoryEntry {
     pub id: String,
     /// Identifier of the agent this memory belongs to.
     pub agent_id: String,
+    /// Namespace isolating this memory from those of other agents or
+    /// tenants sharing the store; empty for the default namespace.
+    #[serde(default)]
+    pub namespace: String,
     /// The actual content of the memory.
     pub content: String,
     /// Vector embedding representing the semantic meaning of the content.
@@ -77,6 +101,7 @@ impl MemoryEntry {
         Self {
             id,
             agent_id,
+            namespace: String::new(),
             content,
             embedding,
             metadata: MemoryMetadata::default(),
@@ -84,6 +109,53 @@ impl MemoryEntry {
             updated_at: now,
         }
     }
+
+    /// Places the entry in `namespace`.
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = namespace.into();
+        self
+    }
+
+    /// Makes the entry expire `ttl` from now.
+    pub fn with_ttl(mut self, ttl: chrono::Duration) -> Self {
+        self.set_expires_at(Some(Utc::now() + ttl));
+        self
+    }
+
+    /// Returns when the entry expires, if it has a TTL.
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.metadata
+            .custom
+            .get(EXPIRES_AT_KEY)
+            .and_then(|value| value.as_str())
+            .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+            .map(|time| time.with_timezone(&Utc))
+    }
+
+    /// Sets or clears the time the entry expires.
+    pub fn set_expires_at(&mut self, expires_at: Option<DateTime<Utc>>) {
+        match expires_at {
+            Some(time) => {
+                self.metadata
+                    .custom
+                    .insert(EXPIRES_AT_KEY.to_string(), time.to_rfc3339().into());
+            }
+            None => {
+                self.metadata.custom.remove(EXPIRES_AT_KEY);
+            }
+        }
+    }
+
+    /// Returns true if the entry's TTL has passed at `now`.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at()
+            .is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Returns true if the entry was archived on expiry.
+    pub fn is_archived(&self) -> bool {
+        self.metadata.custom.contains_key(ARCHIVED_AT_KEY)
+    }
 }
 
 /// A match result from a memory query.
@@ -105,6 +177,8 @@ pub struct MemoryMatch {
 pub struct MemoryFilter {
     /// Filter by specific agent ID.
     pub agent_id: Option<String>,
+    /// Filter by namespace; `Some("")` selects the default namespace.
+    pub namespace: Option<String>,
     /// Filter by memories containing all of these tags.
     pub tags: Option<Vec<String>>,
     /// Filter by memory source.
@@ -140,6 +214,35 @@ impl Default for MemoryQueryOptions {
     }
 }
 
+/// Options for importing memories with
+/// [`MemoryStore::import_jsonl`](crate::MemoryStore::import_jsonl).
+#[derive(Clone, Default)]
+pub struct ImportOptions {
+    /// Assigns every imported memory to this agent, e.g. to seed a new agent
+    /// with curated knowledge. Memories get IDs derived from the agent and
+    /// their original ID, so one file can seed several agents.
+    pub agent_id: Option<String>,
+    /// Re-embeds the content with this provider, for stores using another
+    /// embedding model than the exported one. Memories exported without an
+    /// embedding are always embedded with it.
+    pub embedder: Option<Arc<dyn EmbeddingProvider>>,
+}
+
+/// Changes applied to a stored memory with
+/// [`MemoryStore::update`](crate::MemoryStore::update).
+#[derive(Clone, Default)]
+pub struct MemoryUpdate {
+    /// Replaces the content.
+    pub content: Option<String>,
+    /// Replaces the tags.
+    pub tags: Option<Vec<String>>,
+    /// Replaces the importance, clamped to 0.0..=1.0.
+    pub importance: Option<f32>,
+    /// Re-embeds changed content with this provider. Without one the memory
+    /// keeps its previous embedding.
+    pub embedder: Option<Arc<dyn EmbeddingProvider>>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -174,6 +277,25 @@ mod tests {
         assert_eq!(entry.embedding, vec![0.1, 0.2, 0.3]);
     }
 
+    #[test]
+    fn test_memory_entry_ttl() {
+        let entry = MemoryEntry::new(
+            "test-id".to_string(),
+            "agent-1".to_string(),
+            "test content".to_string(),
+            vec![0.1, 0.2, 0.3],
+        );
+        assert!(entry.expires_at().is_none());
+        assert!(!entry.is_expired(Utc::now()));
+
+        let mut entry = entry.with_ttl(chrono::Duration::hours(1));
+        assert!(!entry.is_expired(Utc::now()));
+        assert!(entry.is_expired(Utc::now() + chrono::Duration::hours(2)));
+
+        entry.set_expires_at(None);
+        assert!(entry.metadata.custom.is_empty());
+    }
+
     #[test]
     fn test_memory_match() {
         let entry = MemoryEntry::new(
@@ -5,13 +5,19 @@
 //! - Consolidation of similar memories
 //! - Quota enforcement by evicting lowest-importance entries
 //! - The maintain() function for running all operations
+//! - TTL expiry, archiving, background sweeps and decayed search
+//! - Deduplication of near-duplicate memories on store
+//! - Summarization of related memories through a chat model
 
 use aisopod_memory::sqlite::SqliteMemoryStore;
 use aisopod_memory::MockEmbeddingProvider;
 use aisopod_memory::{
-    EmbeddingProvider, MemoryEntry, MemoryFilter, MemoryManager, MemoryManagerConfig,
-    MemoryMetadata, MemorySource, MemoryStore,
+    EmbeddingProvider, ExpiryAction, MemoryEntry, MemoryFilter, MemoryManager, MemoryManagerConfig,
+    MemoryMetadata, MemoryQueryOptions, MemorySource, MemoryStore,
 };
+use aisopod_memory::{SUMMARIZED_FROM_KEY, SUMMARIZED_INTO_KEY};
+use aisopod_provider::helpers::MockProvider;
+use aisopod_provider::types::{ChatCompletionChunk, Message, MessageContent, MessageDelta, Role};
 use chrono::{Duration, Utc};
 use std::sync::Arc;
 
@@ -32,6 +38,7 @@ fn test_manager(
         expiration_days: 90,
         min_importance_threshold: 0.1,
         consolidation_similarity_threshold: 0.92,
+        ..Default::default()
     };
     let manager = MemoryManager::new(store.clone(), embedder, config);
     (manager, store)
@@ -760,3 +767,374 @@ async fn test_maintain_multiple_agents() {
     let entries2 = store.list(filter2).await.unwrap();
     assert_eq!(entries2.len(), 1);
 }
+
+/// Helper to create a manager with the given config over in-memory SQLite
+fn test_manager_with(config: MemoryManagerConfig) -> Arc<MemoryManager> {
+    let store =
+        Arc::new(SqliteMemoryStore::new(":memory:", 4).expect("Failed to create test store"));
+    let embedder = Arc::new(MockEmbeddingProvider::new(4));
+    Arc::new(MemoryManager::new(store, embedder, config))
+}
+
+/// Helper to create an entry whose embedding matches `content`
+async fn embedded_entry(id: &str, agent_id: &str, content: &str) -> MemoryEntry {
+    let embedding = MockEmbeddingProvider::new(4).embed(content).await.unwrap();
+    MemoryEntry {
+        metadata: MemoryMetadata {
+            importance: 0.8,
+            ..Default::default()
+        },
+        ..MemoryEntry::new(
+            id.to_string(),
+            agent_id.to_string(),
+            content.to_string(),
+            embedding,
+        )
+    }
+}
+
+#[tokio::test]
+async fn test_expire_deletes_entries_past_ttl() {
+    let manager = test_manager_with(MemoryManagerConfig::default());
+
+    let mut expired = embedded_entry("expired", "agent-1", "the build server is down").await;
+    expired.set_expires_at(Some(Utc::now() - Duration::hours(1)));
+    let live = embedded_entry("live", "agent-1", "the build server is up")
+        .await
+        .with_ttl(Duration::hours(1));
+    manager.store().store(expired).await.unwrap();
+    manager.store().store(live).await.unwrap();
+
+    assert_eq!(manager.expire("agent-1").await.unwrap(), 1);
+
+    let entries = manager.store().list(MemoryFilter::default()).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].id, "live");
+    assert!(entries[0].expires_at().is_some());
+}
+
+#[tokio::test]
+async fn test_expire_archives_when_configured() {
+    let manager = test_manager_with(MemoryManagerConfig {
+        expiry_action: ExpiryAction::Archive,
+        ..Default::default()
+    });
+
+    let mut entry = embedded_entry("stale", "agent-1", "deploys happen on fridays").await;
+    entry.set_expires_at(Some(Utc::now() - Duration::minutes(1)));
+    manager.store().store(entry).await.unwrap();
+
+    assert_eq!(manager.expire("agent-1").await.unwrap(), 1);
+    // Archived memories are not expired again
+    assert_eq!(manager.expire("agent-1").await.unwrap(), 0);
+
+    let entries = manager.store().list(MemoryFilter::default()).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert!(entries[0].is_archived());
+    assert!(entries[0].expires_at().is_none());
+
+    let matches = manager
+        .search("deploys happen on fridays", MemoryQueryOptions::default())
+        .await
+        .unwrap();
+    assert!(matches.is_empty());
+}
+
+#[tokio::test]
+async fn test_search_decays_old_memories() {
+    let manager = test_manager_with(MemoryManagerConfig::default());
+
+    let mut old = embedded_entry("old", "agent-1", "the api key rotates monthly").await;
+    old.updated_at = Utc::now() - Duration::days(60);
+    let recent = embedded_entry("recent", "agent-1", "the api key rotates monthly").await;
+    let mut expired = embedded_entry("expired", "agent-1", "the api key rotates monthly").await;
+    expired.set_expires_at(Some(Utc::now() - Duration::seconds(1)));
+    for entry in [old, recent, expired] {
+        manager.store().store(entry).await.unwrap();
+    }
+
+    let matches = manager
+        .search("the api key rotates monthly", MemoryQueryOptions::default())
+        .await
+        .unwrap();
+
+    let ids: Vec<&str> = matches.iter().map(|m| m.entry.id.as_str()).collect();
+    assert_eq!(ids, vec!["recent", "old"]);
+    // Two half-lives old: a quarter of the recent memory's score
+    assert!((matches[1].score / matches[0].score - 0.25).abs() < 0.01);
+}
+
+#[tokio::test]
+async fn test_sweep_covers_all_agents() {
+    let manager = test_manager_with(MemoryManagerConfig::default());
+
+    for agent in ["agent-1", "agent-2"] {
+        let mut entry = embedded_entry(&format!("{}-fact", agent), agent, "temporary fact").await;
+        entry.set_expires_at(Some(Utc::now() - Duration::minutes(5)));
+        manager.store().store(entry).await.unwrap();
+    }
+
+    assert_eq!(manager.sweep().await.unwrap(), 2);
+    assert!(manager
+        .store()
+        .list(MemoryFilter::default())
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_spawn_expiry_sweep() {
+    let manager = test_manager_with(MemoryManagerConfig {
+        sweep_interval: std::time::Duration::from_millis(10),
+        ..Default::default()
+    });
+
+    let entry = embedded_entry("short-lived", "agent-1", "the meeting moved to 3pm")
+        .await
+        .with_ttl(Duration::milliseconds(20));
+    manager.store().store(entry).await.unwrap();
+
+    let handle = manager.spawn_expiry_sweep();
+    let mut remaining = 1;
+    for _ in 0..100 {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        remaining = manager
+            .store()
+            .list(MemoryFilter::default())
+            .await
+            .unwrap()
+            .len();
+        if remaining == 0 {
+            break;
+        }
+    }
+    handle.abort();
+    assert_eq!(remaining, 0);
+}
+
+/// Helper to create an entry with an explicit embedding and metadata
+fn entry_with(id: &str, embedding: Vec<f32>, importance: f32, tags: &[&str]) -> MemoryEntry {
+    MemoryEntry {
+        metadata: MemoryMetadata {
+            importance,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Default::default()
+        },
+        ..MemoryEntry::new(
+            id.to_string(),
+            "agent-1".to_string(),
+            format!("content of {}", id),
+            embedding,
+        )
+    }
+}
+
+#[tokio::test]
+async fn test_store_deduplicated_merges_near_duplicate() {
+    let manager = test_manager_with(MemoryManagerConfig::default());
+
+    let mut original = entry_with("original", vec![1.0, 0.0, 0.0, 0.0], 0.4, &["food"]);
+    original.updated_at = Utc::now() - Duration::days(10);
+    manager.store().store(original.clone()).await.unwrap();
+
+    let repeat = entry_with(
+        "repeat",
+        vec![0.99, 0.05, 0.0, 0.0],
+        0.9,
+        &["food", "pizza"],
+    );
+    let stored = manager.store_deduplicated(repeat).await.unwrap();
+
+    assert_eq!(stored.id, "original");
+    assert_eq!(stored.content, "content of original");
+    assert_eq!(stored.metadata.importance, 0.9);
+    assert_eq!(stored.metadata.tags, vec!["food", "pizza"]);
+    assert_eq!(stored.created_at, original.created_at);
+    assert!(stored.updated_at > original.updated_at);
+
+    let entries = manager.store().list(MemoryFilter::default()).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].metadata.tags, vec!["food", "pizza"]);
+}
+
+#[tokio::test]
+async fn test_store_deduplicated_keeps_distinct_entries() {
+    let manager = test_manager_with(MemoryManagerConfig::default());
+
+    manager
+        .store_deduplicated(entry_with("a", vec![1.0, 0.0, 0.0, 0.0], 0.5, &[]))
+        .await
+        .unwrap();
+    let stored = manager
+        .store_deduplicated(entry_with("b", vec![0.0, 1.0, 0.0, 0.0], 0.5, &[]))
+        .await
+        .unwrap();
+
+    assert_eq!(stored.id, "b");
+    assert_eq!(
+        manager
+            .store()
+            .list(MemoryFilter::default())
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn test_store_deduplicated_disabled() {
+    let manager = test_manager_with(MemoryManagerConfig {
+        dedup_similarity_threshold: None,
+        ..Default::default()
+    });
+
+    for id in ["a", "b"] {
+        manager
+            .store_deduplicated(entry_with(id, vec![1.0, 0.0, 0.0, 0.0], 0.5, &[]))
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(
+        manager
+            .store()
+            .list(MemoryFilter::default())
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn test_extract_memories_merges_repeated_facts() {
+    let manager = test_manager_with(MemoryManagerConfig::default());
+    let conversation = vec![Message {
+        role: Role::User,
+        content: MessageContent::Text(
+            "Remember that the staging database is Postgres 16".to_string(),
+        ),
+        tool_calls: None,
+        tool_call_id: None,
+    }];
+
+    let first = manager
+        .extract_memories("agent-1", &conversation)
+        .await
+        .unwrap();
+    let second = manager
+        .extract_memories("agent-1", &conversation)
+        .await
+        .unwrap();
+
+    assert_eq!(first.len(), 1);
+    assert_eq!(second.len(), 1);
+    assert_eq!(second[0].id, first[0].id);
+    assert_eq!(
+        manager
+            .store()
+            .list(MemoryFilter::default())
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+/// Helper to create a summarizer that always replies with `summary`
+fn summarizer(summary: &str) -> Arc<MockProvider> {
+    Arc::new(
+        MockProvider::new("mock").with_chunks(vec![Ok(ChatCompletionChunk {
+            id: "summary".to_string(),
+            delta: MessageDelta {
+                role: None,
+                content: Some(summary.to_string()),
+                tool_calls: None,
+                reasoning: None,
+            },
+            finish_reason: None,
+            usage: None,
+        })]),
+    )
+}
+
+/// Helper to create an entry with an explicit embedding
+fn clustered_entry(id: &str, namespace: &str, embedding: Vec<f32>, tag: &str) -> MemoryEntry {
+    let mut entry = MemoryEntry::new(
+        id.to_string(),
+        "agent-1".to_string(),
+        format!("fact {}", id),
+        embedding,
+    )
+    .with_namespace(namespace);
+    entry.metadata.tags = vec![tag.to_string()];
+    entry
+}
+
+#[tokio::test]
+async fn test_summarize_clusters_replaces_related_memories() {
+    let store: Arc<dyn MemoryStore> =
+        Arc::new(SqliteMemoryStore::new(":memory:", 4).expect("Failed to create test store"));
+    let manager = MemoryManager::new(
+        store.clone(),
+        Arc::new(MockEmbeddingProvider::new(4)),
+        MemoryManagerConfig::default(),
+    )
+    .with_summarizer(
+        summarizer("The user runs Arch and uses Neovim"),
+        "mock-model",
+    );
+
+    let entries = vec![
+        clustered_entry("os-1", "", vec![1.0, 0.1, 0.0, 0.0], "setup"),
+        clustered_entry("os-2", "", vec![0.9, 0.2, 0.0, 0.0], "editor"),
+        clustered_entry("os-3", "", vec![1.0, 0.0, 0.1, 0.0], "setup"),
+        clustered_entry("pet", "", vec![0.0, 0.0, 0.0, 1.0], "pets"),
+        // Similar, but in another namespace and too few to summarize
+        clustered_entry("other-1", "tenant-b", vec![1.0, 0.1, 0.0, 0.0], "setup"),
+        clustered_entry("other-2", "tenant-b", vec![1.0, 0.0, 0.0, 0.0], "setup"),
+    ];
+    for entry in entries {
+        store.store(entry).await.unwrap();
+    }
+
+    assert_eq!(manager.summarize_clusters("agent-1").await.unwrap(), 1);
+
+    let entries = store.list(MemoryFilter::default()).await.unwrap();
+    let summary = entries
+        .iter()
+        .find(|e| e.metadata.custom.contains_key(SUMMARIZED_FROM_KEY))
+        .expect("summary stored");
+    assert_eq!(summary.content, "The user runs Arch and uses Neovim");
+    assert_eq!(summary.namespace, "");
+    assert_eq!(summary.metadata.tags, vec!["setup", "editor"]);
+    assert_eq!(
+        summary.metadata.custom[SUMMARIZED_FROM_KEY],
+        serde_json::json!(["os-1", "os-2", "os-3"])
+    );
+
+    let archived: Vec<&str> = entries
+        .iter()
+        .filter(|e| e.is_archived())
+        .map(|e| e.id.as_str())
+        .collect();
+    assert_eq!(archived.len(), 3);
+    assert!(["os-1", "os-2", "os-3"]
+        .iter()
+        .all(|id| archived.contains(id)));
+    assert!(entries
+        .iter()
+        .filter(|e| e.is_archived())
+        .all(|e| e.metadata.custom[SUMMARIZED_INTO_KEY] == summary.id.as_str()));
+
+    // Archived memories are not summarized again
+    assert_eq!(manager.summarize_clusters("agent-1").await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_summarize_clusters_requires_summarizer() {
+    let manager = test_manager_with(MemoryManagerConfig::default());
+    assert!(manager.summarize_clusters("agent-1").await.is_err());
+}
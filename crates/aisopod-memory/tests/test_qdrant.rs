@@ -0,0 +1,185 @@
+//! Qdrant backend tests for the aisopod-memory crate.
+//!
+//! These tests run the store against a mocked Qdrant REST API:
+//! - Collection and payload index creation
+//! - Batched upserts
+//! - Search with filters and score rescaling
+//! - Paginated listing
+
+#![cfg(feature = "qdrant")]
+
+use std::sync::Arc;
+
+use aisopod_memory::{
+    MemoryEntry, MemoryFilter, MemoryQueryOptions, MemoryStore, MockEmbeddingProvider,
+    QdrantConfig, QdrantMemoryStore,
+};
+use serde_json::{json, Value};
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn ok(result: Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({ "result": result, "status": "ok" }))
+}
+
+/// Mounts an existing 4-dimensional `memories` collection.
+async fn existing_collection(server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/collections/memories"))
+        .respond_with(ok(json!({ "config": { "params": { "vectors": { "size": 4, "distance": "Cosine" } } } })))
+        .mount(server)
+        .await;
+}
+
+async fn store(server: &MockServer, batch_size: usize) -> QdrantMemoryStore {
+    let mut config = QdrantConfig::new(server.uri());
+    config.batch_size = batch_size;
+    QdrantMemoryStore::new(config, 4, Arc::new(MockEmbeddingProvider::new(4)))
+        .await
+        .unwrap()
+}
+
+fn point(id: &str, score: Option<f32>) -> Value {
+    json!({
+        "id": "00000000-0000-0000-0000-000000000000",
+        "score": score,
+        "payload": {
+            "memory_id": id,
+            "agent_id": "agent-1",
+            "content": format!("content of {}", id),
+            "source": "User",
+            "session_key": null,
+            "tags": ["project"],
+            "importance": 0.8,
+            "metadata": {},
+            "created_at": 1_700_000_000_000i64,
+            "updated_at": 1_700_000_000_000i64
+        },
+        "vector": [0.1, 0.2, 0.3, 0.4]
+    })
+}
+
+#[tokio::test]
+async fn test_creates_missing_collection_with_indexes() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/collections/memories"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/collections/memories"))
+        .and(body_partial_json(json!({ "vectors": { "size": 4, "distance": "Cosine" } })))
+        .respond_with(ok(json!(true)))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/collections/memories/index"))
+        .respond_with(ok(json!({ "status": "completed" })))
+        .expect(8)
+        .mount(&server)
+        .await;
+
+    store(&server, 128).await;
+}
+
+#[tokio::test]
+async fn test_rejects_collection_with_other_dimension() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/collections/memories"))
+        .respond_with(ok(json!({ "config": { "params": { "vectors": { "size": 1536 } } } })))
+        .mount(&server)
+        .await;
+
+    let result = QdrantMemoryStore::new(
+        QdrantConfig::new(server.uri()),
+        4,
+        Arc::new(MockEmbeddingProvider::new(4)),
+    )
+    .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_store_batch_upserts_in_batches() {
+    let server = MockServer::start().await;
+    existing_collection(&server).await;
+    Mock::given(method("PUT"))
+        .and(path("/collections/memories/points"))
+        .respond_with(ok(json!({ "status": "completed" })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let store = store(&server, 2).await;
+    let entries = (0..3)
+        .map(|n| {
+            MemoryEntry::new(
+                format!("fact-{}", n),
+                "agent-1".to_string(),
+                "content".to_string(),
+                vec![0.1, 0.2, 0.3, 0.4],
+            )
+        })
+        .collect();
+
+    let ids = store.store_batch(entries).await.unwrap();
+    assert_eq!(ids, vec!["fact-0", "fact-1", "fact-2"]);
+}
+
+#[tokio::test]
+async fn test_query_maps_filter_and_rescales_score() {
+    let server = MockServer::start().await;
+    existing_collection(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/collections/memories/points/search"))
+        .and(body_partial_json(json!({
+            "limit": 5,
+            "filter": { "must": [{ "key": "agent_id", "match": { "value": "agent-1" } }] }
+        })))
+        .respond_with(ok(json!([point("fact-1", Some(0.8)), point("fact-2", Some(-0.5))])))
+        .mount(&server)
+        .await;
+
+    let store = store(&server, 128).await;
+    let opts = MemoryQueryOptions {
+        top_k: 5,
+        filter: MemoryFilter {
+            agent_id: Some("agent-1".to_string()),
+            ..Default::default()
+        },
+        min_score: Some(0.5),
+    };
+
+    let matches = store.query("project status", opts).await.unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].entry.id, "fact-1");
+    assert_eq!(matches[0].entry.metadata.tags, vec!["project"]);
+    assert!((matches[0].score - 0.9).abs() < 1e-6);
+}
+
+#[tokio::test]
+async fn test_list_follows_scroll_pages() {
+    let server = MockServer::start().await;
+    existing_collection(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/collections/memories/points/scroll"))
+        .and(body_partial_json(json!({ "offset": "next-page" })))
+        .respond_with(ok(json!({ "points": [point("fact-2", None)], "next_page_offset": null })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/collections/memories/points/scroll"))
+        .respond_with(ok(json!({ "points": [point("fact-1", None)], "next_page_offset": "next-page" })))
+        .mount(&server)
+        .await;
+
+    let store = store(&server, 128).await;
+    let entries = store.list(MemoryFilter::default()).await.unwrap();
+
+    let ids: Vec<&str> = entries.iter().map(|entry| entry.id.as_str()).collect();
+    assert_eq!(ids, vec!["fact-1", "fact-2"]);
+    assert_eq!(entries[0].embedding, vec![0.1, 0.2, 0.3, 0.4]);
+}
@@ -5,14 +5,19 @@
 //! - Top-K limiting
 //! - Min-score threshold filtering
 //! - Agent scoping
+//! - Keyword search and hybrid keyword + vector retrieval
+//! - Reranking and its latency budget
+//! - Combined vector and entity graph queries
 
 use aisopod_memory::sqlite::SqliteMemoryStore;
 use aisopod_memory::MockEmbeddingProvider;
 use aisopod_memory::{
-    EmbeddingProvider, MemoryEntry, MemoryFilter, MemoryMetadata, MemoryQueryOptions, MemorySource,
-    MemoryStore,
+    EmbeddingProvider, EntityExtractor, GraphFacts, GraphMemory, HybridSearchConfig, Relation,
+    SqliteGraphStore, MemoryEntry, MemoryQueryPipeline, MemoryFilter, MemoryMetadata, MemoryQueryOptions, MemorySource,
+    MemoryMatch, MemoryStore, RerankConfig, Reranker,
 };
 use std::sync::Arc;
+use std::time::Duration;
 
 // Import the test helpers
 mod helpers;
@@ -27,6 +32,7 @@ fn make_entry_with_embedding(
     MemoryEntry {
         id: id.to_string(),
         agent_id: agent_id.to_string(),
+        namespace: String::new(),
         content: content.to_string(),
         embedding,
         metadata: MemoryMetadata {
@@ -94,6 +100,7 @@ async fn test_similarity_search_top_k() {
         let entry = MemoryEntry {
             id: format!("entry-{}", i),
             agent_id: "agent-1".to_string(),
+            namespace: String::new(),
             content,
             embedding,
             metadata: MemoryMetadata {
@@ -136,6 +143,7 @@ async fn test_similarity_search_min_score() {
         let entry = MemoryEntry {
             id: format!("entry-{}", i),
             agent_id: "agent-1".to_string(),
+            namespace: String::new(),
             content,
             embedding,
             metadata: MemoryMetadata {
@@ -199,6 +207,7 @@ async fn test_similarity_search_agent_scoped() {
         let entry = MemoryEntry {
             id: format!("entry-{}", i),
             agent_id: agent_id.to_string(),
+            namespace: String::new(),
             content,
             embedding,
             metadata: MemoryMetadata {
@@ -377,3 +386,282 @@ async fn test_similarity_search_with_importance_filter() {
     // Both should be returned (re-ranked by combined score)
     assert_eq!(results.len(), 2);
 }
+
+#[tokio::test]
+async fn test_keyword_search_matches_identifiers() {
+    let store = helpers::test_store_with_mock_provider(4);
+
+    store
+        .store(make_entry_with_embedding(
+            "retry",
+            "agent-1",
+            "Rate limits are handled by parse_retry_after in retry.rs",
+            vec![0.1, 0.2, 0.3, 0.4],
+        ))
+        .await
+        .unwrap();
+    store
+        .store(make_entry_with_embedding(
+            "retry-other-agent",
+            "agent-2",
+            "parse_retry_after was rewritten",
+            vec![0.1, 0.2, 0.3, 0.4],
+        ))
+        .await
+        .unwrap();
+    store
+        .store(make_entry_with_embedding(
+            "theme",
+            "agent-1",
+            "The user prefers the dark theme",
+            vec![0.4, 0.3, 0.2, 0.1],
+        ))
+        .await
+        .unwrap();
+
+    let opts = MemoryQueryOptions {
+        top_k: 10,
+        filter: MemoryFilter {
+            agent_id: Some("agent-1".to_string()),
+            ..Default::default()
+        },
+        min_score: None,
+    };
+
+    // Underscores stay inside tokens, and FTS5 syntax in the query is literal
+    let results = store
+        .keyword_query("where is parse_retry_after? (NEAR", opts.clone())
+        .await
+        .unwrap();
+    let ids: Vec<&str> = results.iter().map(|m| m.entry.id.as_str()).collect();
+    assert_eq!(ids, vec!["retry"]);
+    assert!(results[0].score > 0.0);
+
+    // Updated and deleted entries are reflected in the index
+    store.delete("retry").await.unwrap();
+    let results = store
+        .keyword_query("parse_retry_after", opts)
+        .await
+        .unwrap();
+    assert!(results.is_empty());
+}
+
+#[tokio::test]
+async fn test_hybrid_search_recalls_exact_identifier() {
+    let embedder: Arc<dyn EmbeddingProvider> = Arc::new(MockEmbeddingProvider::new(8));
+    let store = Arc::new(helpers::test_store_with_embedder(8, embedder.clone()));
+
+    let contents = [
+        "The user prefers concise answers",
+        "Deployment happens every Friday",
+        "Incident INC_20931 was caused by an expired certificate",
+        "The user's favourite language is Rust",
+        "Meetings are scheduled in the morning",
+    ];
+    for (i, content) in contents.iter().enumerate() {
+        let embedding = embedder.embed(content).await.unwrap();
+        store
+            .store(make_entry_with_embedding(
+                &format!("m-{}", i),
+                "agent-1",
+                content,
+                embedding,
+            ))
+            .await
+            .unwrap();
+    }
+
+    let pipeline = MemoryQueryPipeline::new(store, embedder)
+        .with_hybrid_search(HybridSearchConfig::default());
+    let opts = MemoryQueryOptions {
+        top_k: 1,
+        filter: MemoryFilter::default(),
+        min_score: None,
+    };
+
+    let results = pipeline.query("what happened in INC_20931", opts).await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].entry.id, "m-2");
+}
+
+/// Reranker scoring memories that mention `word` as fully relevant.
+struct WordReranker {
+    word: &'static str,
+    delay: Duration,
+}
+
+#[async_trait::async_trait]
+impl Reranker for WordReranker {
+    async fn score(&self, _query: &str, candidates: &[MemoryMatch]) -> anyhow::Result<Vec<f32>> {
+        tokio::time::sleep(self.delay).await;
+        Ok(candidates
+            .iter()
+            .map(|m| f32::from(u8::from(m.entry.content.contains(self.word))))
+            .collect())
+    }
+}
+
+/// Stores three memories whose embeddings rank them m-0, m-1, m-2 for any
+/// query, with only m-2 mentioning "Miso".
+async fn rerank_pipeline() -> MemoryQueryPipeline {
+    let embedder: Arc<dyn EmbeddingProvider> = Arc::new(ConstantEmbedder);
+    let store = Arc::new(helpers::test_store_with_embedder(4, embedder.clone()));
+    let contents = [
+        (vec![1.0, 0.0, 0.0, 0.0], "The user has a cat"),
+        (vec![0.9, 0.1, 0.0, 0.0], "The cat likes the sofa"),
+        (vec![0.5, 0.5, 0.0, 0.0], "The cat is called Miso"),
+    ];
+    for (i, (embedding, content)) in contents.into_iter().enumerate() {
+        store
+            .store(make_entry_with_embedding(
+                &format!("m-{}", i),
+                "agent-1",
+                content,
+                embedding,
+            ))
+            .await
+            .unwrap();
+    }
+    MemoryQueryPipeline::new(store, embedder)
+}
+
+/// Embeds every text as the first axis, so retrieval order is fixed.
+struct ConstantEmbedder;
+
+#[async_trait::async_trait]
+impl EmbeddingProvider for ConstantEmbedder {
+    async fn embed(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+        Ok(vec![1.0, 0.0, 0.0, 0.0])
+    }
+
+    async fn embed_batch(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|_| vec![1.0, 0.0, 0.0, 0.0]).collect())
+    }
+
+    fn dimensions(&self) -> usize {
+        4
+    }
+}
+
+fn rerank_opts() -> MemoryQueryOptions {
+    MemoryQueryOptions {
+        top_k: 1,
+        filter: MemoryFilter::default(),
+        min_score: None,
+    }
+}
+
+#[tokio::test]
+async fn test_reranker_promotes_relevant_memory() {
+    let results = rerank_pipeline()
+        .await
+        .query("the cat's name", rerank_opts())
+        .await
+        .unwrap();
+    assert_eq!(results[0].entry.id, "m-0");
+
+    let reranker = Arc::new(WordReranker {
+        word: "Miso",
+        delay: Duration::ZERO,
+    });
+    let pipeline = rerank_pipeline()
+        .await
+        .with_reranker(reranker, RerankConfig::default());
+
+    let results = pipeline.query("the cat's name", rerank_opts()).await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].entry.id, "m-2");
+}
+
+#[tokio::test]
+async fn test_reranker_over_latency_budget_keeps_retrieval_order() {
+    let reranker = Arc::new(WordReranker {
+        word: "Miso",
+        delay: Duration::from_secs(5),
+    });
+    let config = RerankConfig {
+        latency_budget: Duration::from_millis(50),
+        ..Default::default()
+    };
+    let pipeline = rerank_pipeline().await.with_reranker(reranker, config);
+
+    let started = std::time::Instant::now();
+    let results = pipeline.query("the cat's name", rerank_opts()).await.unwrap();
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_eq!(results[0].entry.id, "m-0");
+}
+
+/// Extractor reading "<subject> | <predicate> | <object>" memories
+struct PipeExtractor;
+
+#[async_trait::async_trait]
+impl EntityExtractor for PipeExtractor {
+    async fn extract(&self, content: &str) -> anyhow::Result<GraphFacts> {
+        let parts: Vec<&str> = content.split(" | ").collect();
+        let [subject, predicate, object] = parts[..] else {
+            return Err(anyhow::anyhow!("not a relation"));
+        };
+        Ok(GraphFacts {
+            entities: Vec::new(),
+            relations: vec![Relation {
+                subject: subject.to_string(),
+                predicate: predicate.to_string(),
+                object: object.to_string(),
+                memory_id: String::new(),
+            }],
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_graph_query_relates_named_entities() {
+    let embedder: Arc<dyn EmbeddingProvider> = Arc::new(ConstantEmbedder);
+    let store = Arc::new(helpers::test_store_with_embedder(4, embedder));
+    let graph = Arc::new(SqliteGraphStore::new(":memory:").unwrap());
+    let memory = GraphMemory::new(store, graph, Arc::new(PipeExtractor));
+
+    let memories = [
+        "Carol | reports to | Bob",
+        "Bob | is the brother of | Alice",
+        "Dave | works with | Erin",
+        "The office closes at six",
+    ];
+    for (i, content) in memories.into_iter().enumerate() {
+        memory
+            .store_memory(make_entry_with_embedding(
+                &format!("g-{}", i),
+                "agent-1",
+                content,
+                vec![1.0, 0.0, 0.0, 0.0],
+            ))
+            .await
+            .unwrap();
+    }
+
+    let opts = MemoryQueryOptions {
+        min_score: Some(0.0),
+        ..Default::default()
+    };
+    let result = memory
+        .query("Who is Carol relative to alice?", opts)
+        .await
+        .unwrap();
+    assert_eq!(result.matches.len(), 4);
+    let names: Vec<&str> = result.entities.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, vec!["Alice", "Carol"]);
+    assert_eq!(result.paths.len(), 1);
+    assert_eq!(
+        result.paths[0]
+            .iter()
+            .map(|r| r.memory_id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["g-1", "g-0"]
+    );
+    assert!(result.to_context().contains(
+        "Bob -[is the brother of]-> Alice; Carol -[reports to]-> Bob"
+    ));
+
+    // Deleting a memory removes the relations it stated
+    memory.delete_memory("g-0").await.unwrap();
+    assert!(memory.relate("", "Carol", "Alice").await.unwrap().is_none());
+}
@@ -5,10 +5,16 @@
 //! - Retrieving memories
 //! - Deleting memories
 //! - Listing memories with various filters
+//! - JSON Lines export and import
+//! - Namespace isolation through `ScopedMemoryStore`
+//! - Updating stored memories
 
 use aisopod_memory::sqlite::SqliteMemoryStore;
 use aisopod_memory::{EmbeddingProvider, MockEmbeddingProvider};
-use aisopod_memory::{MemoryEntry, MemoryFilter, MemoryMetadata, MemorySource, MemoryStore};
+use aisopod_memory::{
+    agent_namespace, ImportOptions, MemoryEntry, MemoryFilter, MemoryMetadata, MemoryQueryOptions,
+    MemorySource, MemoryStore, MemoryUpdate, ScopedMemoryStore,
+};
 use std::sync::Arc;
 
 // Import the test helpers
@@ -19,6 +25,7 @@ fn make_entry(id: &str, agent_id: &str, content: &str, embedding: &[f32]) -> Mem
     MemoryEntry {
         id: id.to_string(),
         agent_id: agent_id.to_string(),
+        namespace: String::new(),
         content: content.to_string(),
         embedding: embedding.to_vec(),
         metadata: MemoryMetadata::default(),
@@ -35,6 +42,7 @@ async fn make_agent_entry(agent_id: &str, content: &str) -> MemoryEntry {
     MemoryEntry {
         id: uuid::Uuid::new_v4().to_string(),
         agent_id: agent_id.to_string(),
+        namespace: String::new(),
         content: content.to_string(),
         embedding,
         metadata: MemoryMetadata {
@@ -208,6 +216,7 @@ async fn test_list_with_tag_filter() {
     let entry_tags1 = MemoryEntry {
         id: "tags-1".to_string(),
         agent_id: "agent-1".to_string(),
+        namespace: String::new(),
         content: "Tagged entry".to_string(),
         embedding: vec![0.1, 0.2, 0.3, 0.4],
         metadata: MemoryMetadata {
@@ -225,6 +234,7 @@ async fn test_list_with_tag_filter() {
     let entry_tag2 = MemoryEntry {
         id: "tags-2".to_string(),
         agent_id: "agent-1".to_string(),
+        namespace: String::new(),
         content: "Tagged entry 2".to_string(),
         embedding: vec![0.3, 0.4, 0.5, 0.6],
         metadata: MemoryMetadata {
@@ -372,3 +382,171 @@ async fn test_store_overwrites_existing() {
     assert_eq!(entries[0].content, "Updated content");
     assert_eq!(entries[0].embedding, vec![0.5, 0.6, 0.7, 0.8]);
 }
+
+#[tokio::test]
+async fn test_export_import_round_trip() {
+    let source = helpers::test_store(4);
+    let mut entry = make_entry("fact-1", "agent-1", "The user likes tea", &[0.1, 0.2, 0.3, 0.4]);
+    entry.metadata.tags = vec!["preference".to_string()];
+    entry.metadata.importance = 0.9;
+    source.store(entry).await.unwrap();
+    source
+        .store(make_entry("fact-2", "agent-2", "Other agent", &[0.4, 0.3, 0.2, 0.1]))
+        .await
+        .unwrap();
+
+    let mut exported = Vec::new();
+    let filter = MemoryFilter {
+        agent_id: Some("agent-1".to_string()),
+        ..Default::default()
+    };
+    let count = source.export_jsonl(filter, &mut exported).await.unwrap();
+    assert_eq!(count, 1);
+    assert_eq!(exported.iter().filter(|b| **b == b'\n').count(), 1);
+
+    let target = helpers::test_store(4);
+    let count = target
+        .import_jsonl(&mut exported.as_slice(), ImportOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+
+    let entries = target.list(MemoryFilter::default()).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].id, "fact-1");
+    assert_eq!(entries[0].embedding, vec![0.1, 0.2, 0.3, 0.4]);
+    assert_eq!(entries[0].metadata.tags, vec!["preference"]);
+    assert!((entries[0].metadata.importance - 0.9).abs() < 1e-6);
+}
+
+#[tokio::test]
+async fn test_import_seeds_agents_and_reembeds() {
+    let line = serde_json::to_string(&make_entry("fact-1", "curated", "Rust is fun", &[]))
+        .unwrap();
+    let input = format!("{}\n\n", line);
+
+    let store = helpers::test_store(4);
+    let err = store
+        .import_jsonl(&mut input.as_bytes(), ImportOptions::default())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("line 1 has no embedding"));
+
+    let embedder: Arc<dyn EmbeddingProvider> = Arc::new(MockEmbeddingProvider::new(4));
+    for agent in ["agent-a", "agent-b"] {
+        let opts = ImportOptions {
+            agent_id: Some(agent.to_string()),
+            embedder: Some(embedder.clone()),
+        };
+        store.import_jsonl(&mut input.as_bytes(), opts).await.unwrap();
+    }
+
+    let entries = store.list(MemoryFilter::default()).await.unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_ne!(entries[0].id, entries[1].id);
+    let expected = embedder.embed("Rust is fun").await.unwrap();
+    assert!(entries.iter().all(|e| e.embedding == expected));
+
+    let err = store
+        .import_jsonl(&mut "{not json}\n".as_bytes(), ImportOptions::default())
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Invalid memory on line 1");
+}
+
+#[tokio::test]
+async fn test_scoped_stores_are_isolated() {
+    let shared: Arc<dyn MemoryStore> = Arc::new(helpers::test_store_with_mock_provider(4));
+    let alice = ScopedMemoryStore::new(shared.clone(), agent_namespace(Some("alice"), "assistant"));
+    let bob = ScopedMemoryStore::new(shared.clone(), agent_namespace(Some("bob"), "assistant"));
+
+    let embedder = MockEmbeddingProvider::new(4);
+    let embedding = embedder.embed("Alice's door code is 4521").await.unwrap();
+    alice
+        .store(make_entry(
+            "secret",
+            "assistant",
+            "Alice's door code is 4521",
+            &embedding,
+        ))
+        .await
+        .unwrap();
+
+    // Bob sees nothing, even when asking for Alice's namespace explicitly
+    let filter = MemoryFilter {
+        namespace: Some(alice.namespace().to_string()),
+        ..Default::default()
+    };
+    assert!(bob.list(filter.clone()).await.unwrap().is_empty());
+    let opts = MemoryQueryOptions {
+        filter,
+        min_score: Some(-1.0),
+        ..Default::default()
+    };
+    assert!(bob
+        .query("door code", opts.clone())
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(bob
+        .keyword_query("door code", opts)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(bob.get("secret").await.unwrap().is_none());
+
+    // Bob can neither overwrite nor delete Alice's memory by ID
+    let overwrite = make_entry("secret", "assistant", "Bob was here", &embedding);
+    assert!(bob.store(overwrite).await.is_err());
+    assert!(bob.delete("secret").await.is_err());
+
+    let entries = alice.list(MemoryFilter::default()).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].namespace, "alice/assistant");
+    assert_eq!(entries[0].content, "Alice's door code is 4521");
+
+    alice.delete("secret").await.unwrap();
+    assert!(shared
+        .list(MemoryFilter::default())
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_update_memory() {
+    let store = helpers::test_store(4);
+    store
+        .store(make_entry(
+            "fact-1",
+            "agent-1",
+            "The user lives in Oslo",
+            &[0.1, 0.2, 0.3, 0.4],
+        ))
+        .await
+        .unwrap();
+
+    let embedder: Arc<dyn EmbeddingProvider> = Arc::new(MockEmbeddingProvider::new(4));
+    let update = MemoryUpdate {
+        content: Some("The user lives in Bergen".to_string()),
+        importance: Some(1.5),
+        embedder: Some(embedder.clone()),
+        ..Default::default()
+    };
+    let updated = store.update("fact-1", update).await.unwrap();
+    assert_eq!(updated.content, "The user lives in Bergen");
+    assert_eq!(updated.metadata.importance, 1.0);
+
+    let stored = store.get("fact-1").await.unwrap().unwrap();
+    assert_eq!(stored.content, "The user lives in Bergen");
+    assert_eq!(
+        stored.embedding,
+        embedder.embed("The user lives in Bergen").await.unwrap()
+    );
+
+    let err = store
+        .update("missing", MemoryUpdate::default())
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Memory missing not found");
+}
@@ -10,6 +10,11 @@
 //! the Plugin trait or PluginApi changes in a breaking way, the ABI
 //! version should be incremented.
 //!
+//! Everything a plugin contributes, including channels through
+//! [`PluginApi::register_channel_factory()`](crate::PluginApi::register_channel_factory),
+//! crosses the ABI as Rust trait objects, so plugins must be built with the
+//! same compiler and aisopod crate versions as the host.
+//!
 //! # Example
 //!
 //! A dynamic plugin must export these symbols:
@@ -19,7 +24,7 @@
 //! use async_trait::async_trait;
 //! use std::sync::Arc;
 //!
-//! const ABI_VERSION: u32 = 1;
+//! const ABI_VERSION: u32 = 3;
 //!
 //! #[derive(Debug)]
 //! struct MyPlugin {
@@ -81,7 +86,7 @@ use crate::Plugin;
 
 /// ABI version for plugin compatibility checking.
 /// Bump this when the Plugin trait or PluginApi changes in a breaking way.
-pub const ABI_VERSION: u32 = 1;
+pub const ABI_VERSION: u32 = 3;
 
 /// Function signature that every dynamic plugin must export to create instances.
 ///
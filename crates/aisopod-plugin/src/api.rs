@@ -2,7 +2,12 @@
 //!
 //! This module defines the [`PluginApi`] struct that plugins use during
 //! registration to declare their capabilities. The API provides methods
-//! to register channels, tools, CLI commands, model providers, and lifecycle hooks.
+//! to register channels, channel factories, tools, CLI commands, model
+//! providers, and lifecycle hooks.
+//!
+//! An API created with [`PluginApi::with_permissions()`] only accepts
+//! registrations the plugin declared in its manifest and was granted; see
+//! the [`permissions`](crate::permissions) module.
 
 use std::sync::Arc;
 
@@ -10,8 +15,10 @@ use aisopod_channel::plugin::ChannelPlugin;
 use aisopod_provider::ModelProvider;
 use aisopod_tools::Tool;
 
+use crate::channel::ChannelFactory;
 use crate::command::PluginCommand;
 use crate::hook::{Hook, HookHandler, PluginHookHandler};
+use crate::permissions::{Capability, PermissionError, PluginPermissions};
 use crate::security::SecurityError;
 
 /// The API available to plugins during registration.
@@ -55,6 +62,8 @@ use crate::security::SecurityError;
 pub struct PluginApi {
     /// Registered channel implementations.
     pub(crate) channels: Vec<Arc<dyn ChannelPlugin>>,
+    /// Registered channel factories.
+    pub(crate) channel_factories: Vec<Arc<dyn ChannelFactory>>,
     /// Registered tool implementations.
     pub(crate) tools: Vec<Arc<dyn Tool>>,
     /// Registered CLI commands.
@@ -63,6 +72,10 @@ pub struct PluginApi {
     pub(crate) providers: Vec<Arc<dyn ModelProvider>>,
     /// Registered lifecycle hooks.
     pub(crate) hooks: Vec<PluginHookHandler>,
+    /// Permissions registrations are checked against, if restricted.
+    permissions: Option<PluginPermissions>,
+    /// Registrations refused by the permissions.
+    denied: Vec<PermissionError>,
 }
 
 impl PluginApi {
@@ -73,18 +86,52 @@ impl PluginApi {
     pub fn new() -> Self {
         Self {
             channels: Vec::new(),
+            channel_factories: Vec::new(),
             tools: Vec::new(),
             commands: Vec::new(),
             providers: Vec::new(),
             hooks: Vec::new(),
+            permissions: None,
+            denied: Vec::new(),
+        }
+    }
+
+    /// Creates an API that checks registrations against `permissions`.
+    ///
+    /// Registrations that are not permitted are dropped and recorded in
+    /// [`denied()`](Self::denied).
+    pub fn with_permissions(permissions: PluginPermissions) -> Self {
+        Self {
+            permissions: Some(permissions),
+            ..Self::new()
         }
     }
 
+    /// Returns the registrations refused by the permissions.
+    pub fn denied(&self) -> &[PermissionError] {
+        &self.denied
+    }
+
+    /// Checks a registration against the permissions, recording a denial.
+    fn permit(&mut self, capability: Capability, name: &str) -> Result<(), PermissionError> {
+        let Some(permissions) = &self.permissions else {
+            return Ok(());
+        };
+        permissions.check(capability, name).inspect_err(|e| {
+            self.denied.push(e.clone());
+        })
+    }
+
     /// Returns the number of registered channels.
     pub fn channel_count(&self) -> usize {
         self.channels.len()
     }
 
+    /// Returns the number of registered channel factories.
+    pub fn channel_factory_count(&self) -> usize {
+        self.channel_factories.len()
+    }
+
     /// Returns the number of registered tools.
     pub fn tool_count(&self) -> usize {
         self.tools.len()
@@ -110,6 +157,11 @@ impl PluginApi {
         &self.channels
     }
 
+    /// Returns a reference to the registered channel factories.
+    pub fn channel_factories(&self) -> &[Arc<dyn ChannelFactory>] {
+        &self.channel_factories
+    }
+
     /// Returns a reference to the registered tools.
     pub fn tools(&self) -> &[Arc<dyn Tool>] {
         &self.tools
@@ -135,13 +187,40 @@ impl PluginApi {
     /// This method allows plugins to contribute channel implementations
     /// that can be used for communication with external services.
     ///
+    /// A shared channel cannot be connected or disconnected, as those take
+    /// `&mut self`. Channels with a connection lifecycle are registered with
+    /// [`register_channel_factory()`](Self::register_channel_factory).
+    ///
     /// # Arguments
     ///
     /// * `channel` - An `Arc` wrapping the channel implementation
     pub fn register_channel(&mut self, channel: Arc<dyn ChannelPlugin>) {
+        if self.permit(Capability::Channels, channel.id()).is_err() {
+            return;
+        }
         self.channels.push(channel);
     }
 
+    /// Register a channel factory.
+    ///
+    /// The host creates a channel with the factory for each configured
+    /// account of its channel type, and drives the channel's `connect()`
+    /// and `disconnect()` lifecycle; see the [`channel`](crate::channel)
+    /// module.
+    ///
+    /// # Arguments
+    ///
+    /// * `factory` - An `Arc` wrapping the factory implementation
+    pub fn register_channel_factory(&mut self, factory: Arc<dyn ChannelFactory>) {
+        if self
+            .permit(Capability::Channels, factory.channel_type())
+            .is_err()
+        {
+            return;
+        }
+        self.channel_factories.push(factory);
+    }
+
     /// Register a tool implementation.
     ///
     /// This method allows plugins to contribute tool implementations
@@ -151,6 +230,9 @@ impl PluginApi {
     ///
     /// * `tool` - An `Arc` wrapping the tool implementation
     pub fn register_tool(&mut self, tool: Arc<dyn Tool>) {
+        if self.permit(Capability::Tools, tool.name()).is_err() {
+            return;
+        }
         self.tools.push(tool);
     }
 
@@ -175,9 +257,12 @@ impl PluginApi {
     /// matches a reserved built-in command.
     /// Returns `SecurityError::InvalidCommandName` if the command name
     /// fails validation (empty, too long, or contains invalid characters).
+    /// Returns `SecurityError::PermissionDenied` if the plugin's permissions
+    /// do not allow the command.
     pub fn register_command(&mut self, command: PluginCommand) -> Result<(), SecurityError> {
         // Validate the command name before registration
         crate::security::validate_command_name(&command.name)?;
+        self.permit(Capability::Commands, &command.name)?;
         self.commands.push(command);
         Ok(())
     }
@@ -191,6 +276,9 @@ impl PluginApi {
     ///
     /// * `provider` - An `Arc` wrapping the provider implementation
     pub fn register_provider(&mut self, provider: Arc<dyn ModelProvider>) {
+        if self.permit(Capability::Providers, provider.id()).is_err() {
+            return;
+        }
         self.providers.push(provider);
     }
 
@@ -205,6 +293,12 @@ impl PluginApi {
     /// * `plugin_id` - The ID of the plugin registering the handler
     /// * `handler` - An `Arc` wrapping the hook handler implementation
     pub fn register_hook(&mut self, hook: Hook, plugin_id: String, handler: Arc<dyn HookHandler>) {
+        if self
+            .permit(Capability::Hooks, &format!("{:?}", hook))
+            .is_err()
+        {
+            return;
+        }
         self.hooks.push(PluginHookHandler::new(hook, plugin_id, handler));
     }
 }
@@ -213,10 +307,12 @@ impl std::fmt::Debug for PluginApi {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("PluginApi")
             .field("channel_count", &self.channels.len())
+            .field("channel_factory_count", &self.channel_factories.len())
             .field("tool_count", &self.tools.len())
             .field("command_count", &self.commands.len())
             .field("provider_count", &self.providers.len())
             .field("hook_count", &self.hooks.len())
+            .field("denied_count", &self.denied.len())
             .finish()
     }
 }
@@ -256,6 +352,30 @@ mod tests {
         assert_eq!(api.command_count(), 1);
     }
 
+    #[test]
+    fn test_register_command_with_permissions() {
+        let declared = crate::PluginCapabilities {
+            commands: Some(vec!["myplugin".to_string()]),
+            ..Default::default()
+        };
+        let mut api =
+            PluginApi::with_permissions(PluginPermissions::new(declared, [Capability::Commands]));
+        let command = |name: &str| {
+            PluginCommand::new(name, "A test command", name, false, Arc::new(|_| Ok(())))
+        };
+
+        assert!(api.register_command(command("myplugin")).is_ok());
+        let result = api.register_command(command("undeclared"));
+        assert!(matches!(
+            result,
+            Err(SecurityError::PermissionDenied(
+                PermissionError::Undeclared { .. }
+            ))
+        ));
+        assert_eq!(api.command_count(), 1);
+        assert_eq!(api.denied().len(), 1);
+    }
+
     #[test]
     fn test_register_command_reserved_name() {
         let mut api = PluginApi::new();
@@ -0,0 +1,242 @@
+//! Channels contributed by plugins.
+//!
+//! [`ChannelPlugin::connect()`] and [`ChannelPlugin::disconnect()`] take
+//! `&mut self`, so a channel shared as an `Arc` through
+//! [`PluginApi::register_channel()`](crate::PluginApi::register_channel)
+//! can never be connected. Plugins shipping a channel, including dynamic
+//! plugins built as separate binaries, register a [`ChannelFactory`]
+//! instead.
+//!
+//! The host then runs channels through [`PluginChannels`]: it creates a
+//! channel for a configured account with the factory, connects it while it
+//! still owns it, and shares it through the [`ChannelRegistry`]. Stopping
+//! the channel unregisters and disconnects it.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use aisopod_plugin::channel::{ChannelFactory, PluginChannels};
+//!
+//! struct MatrixFactory;
+//!
+//! #[async_trait]
+//! impl ChannelFactory for MatrixFactory {
+//!     fn channel_type(&self) -> &str {
+//!         "matrix"
+//!     }
+//!
+//!     async fn create(
+//!         &self,
+//!         account_id: &str,
+//!         config: &serde_json::Value,
+//!     ) -> anyhow::Result<Box<dyn ChannelPlugin>> {
+//!         Ok(Box::new(MatrixChannel::new(account_id, serde_json::from_value(config.clone())?)))
+//!     }
+//! }
+//!
+//! // In the plugin's register()
+//! api.register_channel_factory(Arc::new(MatrixFactory));
+//!
+//! // In the host
+//! let mut running = PluginChannels::new();
+//! let id = running
+//!     .start(&plugins, "matrix", "work", &account_config, &mut channels)
+//!     .await?;
+//! // ...
+//! running.stop(&id, &mut channels).await?;
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aisopod_channel::{ChannelPlugin, ChannelRegistry};
+use async_trait::async_trait;
+use serde_json::Value;
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::PluginRegistry;
+
+/// Creates channel instances for a channel type provided by a plugin.
+///
+/// Dynamic plugins register factories across the plugin ABI, so they must
+/// be built against the same `aisopod-channel` version as the host.
+#[async_trait]
+pub trait ChannelFactory: Send + Sync {
+    /// Returns the channel type this factory creates (e.g., "matrix").
+    fn channel_type(&self) -> &str;
+
+    /// Creates an unconnected channel for an account.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_id` - Identifier of the configured account
+    /// * `config` - The account's configuration
+    async fn create(
+        &self,
+        account_id: &str,
+        config: &Value,
+    ) -> anyhow::Result<Box<dyn ChannelPlugin>>;
+}
+
+/// Error types for running plugin channels.
+#[derive(Debug, Error)]
+pub enum ChannelError {
+    /// No registered plugin provides the channel type.
+    #[error("No plugin provides channel type '{0}'")]
+    UnknownType(String),
+
+    /// The factory failed to create the channel.
+    #[error("Failed to create '{0}' channel: {1}")]
+    Create(String, String),
+
+    /// The channel failed to connect.
+    #[error("Failed to connect channel '{0}': {1}")]
+    Connect(String, String),
+
+    /// A channel with the same ID is already running.
+    #[error("Channel '{0}' is already running")]
+    AlreadyRunning(String),
+
+    /// No channel with the ID is running.
+    #[error("Channel '{0}' is not running")]
+    NotRunning(String),
+}
+
+struct RunningChannel {
+    plugin_id: String,
+    channel: Arc<dyn ChannelPlugin>,
+}
+
+/// The running channels created by plugin channel factories.
+#[derive(Default)]
+pub struct PluginChannels {
+    running: HashMap<String, RunningChannel>,
+}
+
+impl PluginChannels {
+    /// Creates an empty set of running channels.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates, connects, and registers a channel for an account.
+    ///
+    /// The factory for `channel_type` is looked up in `plugins`. The
+    /// connected channel is registered in `channels` under its ID, which
+    /// is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ChannelError::UnknownType` if no plugin provides the type,
+    /// `ChannelError::Create` or `ChannelError::Connect` if the channel
+    /// cannot be created or connected, and `ChannelError::AlreadyRunning`
+    /// if a channel with the same ID is running.
+    pub async fn start(
+        &mut self,
+        plugins: &PluginRegistry,
+        channel_type: &str,
+        account_id: &str,
+        config: &Value,
+        channels: &mut ChannelRegistry,
+    ) -> Result<String, ChannelError> {
+        let (plugin_id, factory) = plugins
+            .channel_factory(channel_type)
+            .ok_or_else(|| ChannelError::UnknownType(channel_type.to_string()))?;
+
+        let mut channel = factory
+            .create(account_id, config)
+            .await
+            .map_err(|e| ChannelError::Create(channel_type.to_string(), e.to_string()))?;
+        let id = channel.id().to_string();
+        if self.running.contains_key(&id) {
+            return Err(ChannelError::AlreadyRunning(id));
+        }
+        channel
+            .connect()
+            .await
+            .map_err(|e| ChannelError::Connect(id.clone(), e.to_string()))?;
+
+        info!(plugin_id = %plugin_id, channel_id = %id, "Started plugin channel");
+        let channel: Arc<dyn ChannelPlugin> = Arc::from(channel);
+        channels.register(channel.clone());
+        self.running.insert(
+            id.clone(),
+            RunningChannel {
+                plugin_id: plugin_id.to_string(),
+                channel,
+            },
+        );
+        Ok(id)
+    }
+
+    /// Unregisters and disconnects a running channel.
+    ///
+    /// The channel can only be disconnected once nothing else holds it;
+    /// if it is still in use, it is unregistered and dropped by its last
+    /// user without disconnecting.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ChannelError::NotRunning` if no channel with the ID runs.
+    pub async fn stop(
+        &mut self,
+        channel_id: &str,
+        channels: &mut ChannelRegistry,
+    ) -> Result<(), ChannelError> {
+        let running = self
+            .running
+            .remove(channel_id)
+            .ok_or_else(|| ChannelError::NotRunning(channel_id.to_string()))?;
+        channels.unregister(channel_id);
+        disconnect(running).await;
+        Ok(())
+    }
+
+    /// Stops all channels created by a plugin's factories.
+    ///
+    /// Returns the number of channels stopped.
+    pub async fn stop_plugin(&mut self, plugin_id: &str, channels: &mut ChannelRegistry) -> usize {
+        let ids: Vec<String> = self
+            .running
+            .iter()
+            .filter(|(_, running)| running.plugin_id == plugin_id)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &ids {
+            if let Some(running) = self.running.remove(id) {
+                channels.unregister(id);
+                disconnect(running).await;
+            }
+        }
+        ids.len()
+    }
+
+    /// Returns the IDs of the running channels.
+    pub fn running(&self) -> Vec<&str> {
+        self.running.keys().map(String::as_str).collect()
+    }
+}
+
+async fn disconnect(mut running: RunningChannel) {
+    let id = running.channel.id().to_string();
+    match Arc::get_mut(&mut running.channel) {
+        Some(channel) => {
+            if let Err(e) = channel.disconnect().await {
+                warn!(plugin_id = %running.plugin_id, channel_id = %id, error = %e, "Channel disconnect failed");
+            }
+        }
+        None => {
+            warn!(plugin_id = %running.plugin_id, channel_id = %id, "Channel still in use, not disconnected");
+        }
+    }
+    info!(plugin_id = %running.plugin_id, channel_id = %id, "Stopped plugin channel");
+}
+
+impl std::fmt::Debug for PluginChannels {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PluginChannels")
+            .field("running", &self.running.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
@@ -3,6 +3,8 @@ use std::sync::Arc;
 
 use serde_json::Value;
 
+use crate::events::EventBus;
+
 /// Runtime context provided to plugins during initialization.
 ///
 /// This struct contains runtime information and resources that plugins
@@ -20,12 +22,28 @@ pub struct PluginContext {
     /// caches, or other runtime files. The directory is guaranteed
     /// to exist and be writable by the plugin.
     pub data_dir: PathBuf,
+    /// The event bus shared by all plugins.
+    ///
+    /// Plugins publish events to and subscribe to events from other
+    /// plugins through it. See the [`events`](crate::events) module.
+    pub events: EventBus,
 }
 
 impl PluginContext {
-    /// Creates a new `PluginContext` instance.
+    /// Creates a new `PluginContext` instance with a new event bus.
     pub fn new(config: Arc<Value>, data_dir: PathBuf) -> Self {
-        Self { config, data_dir }
+        Self {
+            config,
+            data_dir,
+            events: EventBus::default(),
+        }
+    }
+
+    /// Sets the event bus, so plugins share events with the host and with
+    /// plugins initialized through other contexts.
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
     }
 }
 
@@ -33,6 +51,7 @@ impl std::fmt::Debug for PluginContext {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("PluginContext")
             .field("data_dir", &self.data_dir)
+            .field("events", &self.events)
             .finish()
     }
 }
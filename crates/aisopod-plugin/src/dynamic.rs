@@ -47,6 +47,7 @@ use std::sync::Arc;
 
 use crate::abi::{PluginAbiVersionFn, PluginCreateFn};
 use crate::manifest::PluginManifest;
+use crate::signing::SignaturePolicy;
 use crate::Plugin;
 use thiserror::Error;
 
@@ -88,6 +89,18 @@ pub enum LoadError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// Failed to load or initialize a WebAssembly plugin.
+    #[error("Failed to load WebAssembly plugin {0}: {1}")]
+    Wasm(PathBuf, String),
+
+    /// Failed to create the WebAssembly engine.
+    #[error("Failed to create WebAssembly engine: {0}")]
+    WasmEngine(String),
+
+    /// The plugin's signature was missing, untrusted, or did not match.
+    #[error("Signature check failed for plugin '{0}': {1}")]
+    Signature(String, #[source] crate::signing::SignatureError),
+
     /// Version compatibility check failed.
     #[error("Version compatibility check failed for plugin '{plugin_id}': {error}")]
     VersionCompatibility {
@@ -117,6 +130,7 @@ pub struct DiscoveredPlugin {
 /// - Library loading with platform-specific naming
 /// - ABI version checking
 /// - Version compatibility validation
+/// - Signature verification, see [`with_signature_policy()`](Self::with_signature_policy)
 ///
 /// # Platform Support
 ///
@@ -127,6 +141,8 @@ pub struct DiscoveredPlugin {
 pub struct DynamicPluginLoader {
     /// Directories to scan for plugins.
     plugin_dirs: Vec<PathBuf>,
+    /// Which plugin signatures are accepted.
+    signature_policy: SignaturePolicy,
 }
 
 impl DynamicPluginLoader {
@@ -149,7 +165,24 @@ impl DynamicPluginLoader {
     /// ]);
     /// ```
     pub fn new(plugin_dirs: Vec<PathBuf>) -> Self {
-        Self { plugin_dirs }
+        Self {
+            plugin_dirs,
+            signature_policy: SignaturePolicy::default(),
+        }
+    }
+
+    /// Sets which plugin signatures are accepted.
+    ///
+    /// By default unsigned plugins load, and signed plugins load as long
+    /// as their files match the signature.
+    pub fn with_signature_policy(mut self, policy: SignaturePolicy) -> Self {
+        self.signature_policy = policy;
+        self
+    }
+
+    /// Returns the directories scanned for plugins.
+    pub fn plugin_dirs(&self) -> &[PathBuf] {
+        &self.plugin_dirs
     }
 
     /// Scans plugin directories for discovered plugins.
@@ -256,6 +289,7 @@ impl DynamicPluginLoader {
     /// - `LoadError::Manifest` - Manifest error
     /// - `LoadError::Io` - I/O error
     /// - `LoadError::VersionCompatibility` - Version compatibility check failed
+    /// - `LoadError::Signature` - The signature policy refused the plugin
     ///
     /// # Example
     ///
@@ -276,6 +310,31 @@ impl DynamicPluginLoader {
         discovered: &DiscoveredPlugin,
     ) -> Result<Arc<dyn Plugin>, LoadError> {
         let lib_path = self.library_path(discovered);
+        self.load_library(discovered, &lib_path)
+    }
+
+    /// Loads a discovered plugin from the shared library at `lib_path`.
+    ///
+    /// Used by [`load_plugin()`](Self::load_plugin) and by hot reload, which
+    /// loads a copy of the library so that the platform loader does not hand
+    /// back the already loaded one.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`load_plugin()`](Self::load_plugin).
+    pub(crate) unsafe fn load_library(
+        &self,
+        discovered: &DiscoveredPlugin,
+        lib_path: &Path,
+    ) -> Result<Arc<dyn Plugin>, LoadError> {
+        let lib_path = lib_path.to_path_buf();
+
+        // Check the signature before running any of the library's code
+        self.verify_signature(
+            discovered,
+            &library_filename(&discovered.manifest.plugin.entry_point),
+            &lib_path,
+        )?;
 
         // Load the shared library
         let lib = libloading::Library::new(&lib_path).map_err(|e| {
@@ -312,11 +371,26 @@ impl DynamicPluginLoader {
         Ok(plugin)
     }
 
+    /// Checks the plugin's signature against the signature policy.
+    ///
+    /// `binary_name` is the entry point file name within the plugin
+    /// directory, and `binary_path` the file about to be loaded.
+    pub(crate) fn verify_signature(
+        &self,
+        discovered: &DiscoveredPlugin,
+        binary_name: &str,
+        binary_path: &Path,
+    ) -> Result<(), LoadError> {
+        self.signature_policy
+            .verify(&discovered.dir, binary_name, binary_path)
+            .map_err(|e| LoadError::Signature(discovered.manifest.plugin.id.clone(), e))
+    }
+
     /// Gets the path to the plugin's shared library.
     ///
     /// This method constructs the library path based on the plugin's
     /// entry point and the platform-specific naming convention.
-    fn library_path(&self, discovered: &DiscoveredPlugin) -> PathBuf {
+    pub(crate) fn library_path(&self, discovered: &DiscoveredPlugin) -> PathBuf {
         let entry_point = &discovered.manifest.plugin.entry_point;
         let lib_name = library_filename(entry_point);
         discovered.dir.join(lib_name)
@@ -336,7 +410,10 @@ impl DynamicPluginLoader {
     ///
     /// * `Ok(())` - Versions are compatible
     /// * `Err(LoadError::VersionCompatibility)` - Versions are not compatible
-    fn check_version_compatibility(&self, discovered: &DiscoveredPlugin) -> Result<(), LoadError> {
+    pub(crate) fn check_version_compatibility(
+        &self,
+        discovered: &DiscoveredPlugin,
+    ) -> Result<(), LoadError> {
         let plugin_version = &discovered.manifest.plugin.version;
         let compatibility = &discovered.manifest.compatibility;
         let plugin_id = &discovered.manifest.plugin.id;
@@ -442,6 +519,25 @@ impl DynamicPluginLoader {
     }
 }
 
+/// Reads the ABI version a plugin library was built against.
+///
+/// The library is unloaded again before returning.
+///
+/// # Safety
+///
+/// This loads the library and runs its initialization code, so the library
+/// must come from a trusted source.
+pub(crate) unsafe fn read_abi_version(lib_path: &Path) -> Result<u32, LoadError> {
+    let lib = libloading::Library::new(lib_path)
+        .map_err(|e| LoadError::LibraryLoad(lib_path.to_path_buf(), e.to_string()))?;
+    let abi_version_fn = lib
+        .get::<PluginAbiVersionFn>(b"aisopod_plugin_abi_version")
+        .map_err(|_| {
+            LoadError::MissingSymbol("aisopod_plugin_abi_version".into(), lib_path.to_path_buf())
+        })?;
+    Ok(abi_version_fn())
+}
+
 /// Returns the platform-specific shared library filename.
 ///
 /// This function constructs the library filename based on the platform:
@@ -461,17 +557,17 @@ impl DynamicPluginLoader {
 /// assert_eq!(library_filename("my_plugin"), "my_plugin.dll"); // Windows
 /// ```
 #[cfg(target_os = "linux")]
-fn library_filename(name: &str) -> String {
+pub(crate) fn library_filename(name: &str) -> String {
     format!("lib{}.so", name)
 }
 
 #[cfg(target_os = "macos")]
-fn library_filename(name: &str) -> String {
+pub(crate) fn library_filename(name: &str) -> String {
     format!("lib{}.dylib", name)
 }
 
 #[cfg(target_os = "windows")]
-fn library_filename(name: &str) -> String {
+pub(crate) fn library_filename(name: &str) -> String {
     format!("{}.dll", name)
 }
 
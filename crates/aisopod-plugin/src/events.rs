@@ -0,0 +1,333 @@
+//! Event bus for communication between plugins.
+//!
+//! Plugins publish and consume [`PluginEvent`]s through the [`EventBus`] in
+//! their [`PluginContext`](crate::PluginContext) without linking against
+//! each other. Every event has a topic; a subscription receives only the
+//! topics it asked for.
+//!
+//! Each subscription has a bounded queue. [`EventBus::publish()`] waits
+//! until every matching subscriber has room for the event, so a slow
+//! subscriber slows publishers down instead of losing events or growing
+//! without bound. [`EventBus::try_publish()`] never waits and skips
+//! subscribers whose queue is full. A plugin must not `publish()` to a
+//! topic it subscribes to without draining its own subscription, or it
+//! waits on itself.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use aisopod_plugin::events::{PluginEvent, topics};
+//!
+//! // In one plugin's init()
+//! let mut finished = ctx.events.subscribe([topics::AGENT_RUN_FINISHED]);
+//! tokio::spawn(async move {
+//!     while let Some(event) = finished.recv().await {
+//!         // ...
+//!     }
+//! });
+//!
+//! // In another plugin
+//! ctx.events
+//!     .publish(PluginEvent::custom("weather.updated", json!({"city": "Oslo"})))
+//!     .await;
+//! ```
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// Topics of the built-in events.
+pub mod topics {
+    /// Topic of [`PluginEvent::MessageReceived`](super::PluginEvent::MessageReceived).
+    pub const MESSAGE_RECEIVED: &str = "message.received";
+    /// Topic of [`PluginEvent::AgentRunFinished`](super::PluginEvent::AgentRunFinished).
+    pub const AGENT_RUN_FINISHED: &str = "agent.run_finished";
+    /// Topic of [`PluginEvent::ConfigReloaded`](super::PluginEvent::ConfigReloaded).
+    pub const CONFIG_RELOADED: &str = "config.reloaded";
+}
+
+/// An event published on the [`EventBus`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PluginEvent {
+    /// A message was received on a channel.
+    MessageReceived {
+        /// ID of the channel the message arrived on
+        channel_id: String,
+        /// ID of the sender
+        sender_id: String,
+        /// Text of the message
+        text: String,
+    },
+    /// An agent finished a run.
+    AgentRunFinished {
+        /// ID of the agent
+        agent_id: String,
+        /// Key of the session the run belonged to
+        session_key: String,
+        /// Whether the run completed without error
+        success: bool,
+    },
+    /// The configuration was reloaded.
+    ConfigReloaded,
+    /// An event defined by a plugin.
+    Custom {
+        /// Topic of the event, such as "weather.updated"
+        topic: String,
+        /// Event payload
+        payload: Value,
+    },
+}
+
+impl PluginEvent {
+    /// Creates an event defined by a plugin.
+    pub fn custom(topic: impl Into<String>, payload: Value) -> Self {
+        PluginEvent::Custom {
+            topic: topic.into(),
+            payload,
+        }
+    }
+
+    /// Returns the topic subscriptions are filtered by.
+    pub fn topic(&self) -> &str {
+        match self {
+            PluginEvent::MessageReceived { .. } => topics::MESSAGE_RECEIVED,
+            PluginEvent::AgentRunFinished { .. } => topics::AGENT_RUN_FINISHED,
+            PluginEvent::ConfigReloaded => topics::CONFIG_RELOADED,
+            PluginEvent::Custom { topic, .. } => topic,
+        }
+    }
+}
+
+struct Subscriber {
+    /// Topics the subscriber receives; `None` receives every topic.
+    topics: Option<HashSet<String>>,
+    sender: mpsc::Sender<PluginEvent>,
+}
+
+impl Subscriber {
+    fn wants(&self, topic: &str) -> bool {
+        !self.sender.is_closed()
+            && self
+                .topics
+                .as_ref()
+                .is_none_or(|topics| topics.contains(topic))
+    }
+}
+
+/// Publish-subscribe bus shared by all plugins.
+///
+/// Cloning the bus yields a handle to the same subscribers.
+#[derive(Clone)]
+pub struct EventBus {
+    capacity: usize,
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+}
+
+impl EventBus {
+    /// Creates a bus whose subscriptions each queue up to `capacity` events.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "event bus capacity must be positive");
+        Self {
+            capacity,
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Subscribes to the given topics.
+    pub fn subscribe<I, S>(&self, topics: I) -> EventSubscription
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.add_subscriber(Some(topics.into_iter().map(Into::into).collect()))
+    }
+
+    /// Subscribes to every topic.
+    pub fn subscribe_all(&self) -> EventSubscription {
+        self.add_subscriber(None)
+    }
+
+    fn add_subscriber(&self, topics: Option<HashSet<String>>) -> EventSubscription {
+        let (sender, receiver) = mpsc::channel(self.capacity);
+        self.lock().push(Subscriber { topics, sender });
+        EventSubscription { receiver }
+    }
+
+    /// Publishes an event, waiting for room in every matching subscription.
+    ///
+    /// Returns the number of subscribers the event was delivered to.
+    pub async fn publish(&self, event: PluginEvent) -> usize {
+        let senders = self.matching(event.topic());
+        let mut delivered = 0;
+        for sender in senders {
+            if sender.send(event.clone()).await.is_ok() {
+                delivered += 1;
+            }
+        }
+        delivered
+    }
+
+    /// Publishes an event without waiting.
+    ///
+    /// Subscribers whose queue is full miss the event. Returns the number
+    /// of subscribers the event was delivered to.
+    pub fn try_publish(&self, event: PluginEvent) -> usize {
+        let senders = self.matching(event.topic());
+        let mut delivered = 0;
+        for sender in senders {
+            match sender.try_send(event.clone()) {
+                Ok(()) => delivered += 1,
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    warn!(topic = %event.topic(), "Event subscriber is full, dropping event");
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => {}
+            }
+        }
+        delivered
+    }
+
+    /// Returns the number of open subscriptions.
+    pub fn subscriber_count(&self) -> usize {
+        let mut subscribers = self.lock();
+        subscribers.retain(|subscriber| !subscriber.sender.is_closed());
+        subscribers.len()
+    }
+
+    /// Returns the senders of open subscriptions matching `topic`, dropping
+    /// closed ones.
+    fn matching(&self, topic: &str) -> Vec<mpsc::Sender<PluginEvent>> {
+        let mut subscribers = self.lock();
+        subscribers.retain(|subscriber| !subscriber.sender.is_closed());
+        subscribers
+            .iter()
+            .filter(|subscriber| subscriber.wants(topic))
+            .map(|subscriber| subscriber.sender.clone())
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Subscriber>> {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(64)
+    }
+}
+
+impl std::fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventBus")
+            .field("capacity", &self.capacity)
+            .field("subscribers", &self.lock().len())
+            .finish()
+    }
+}
+
+/// A subscription to topics on the [`EventBus`].
+///
+/// Dropping the subscription unsubscribes.
+#[derive(Debug)]
+pub struct EventSubscription {
+    receiver: mpsc::Receiver<PluginEvent>,
+}
+
+impl EventSubscription {
+    /// Receives the next event, or `None` once the bus is gone.
+    pub async fn recv(&mut self) -> Option<PluginEvent> {
+        self.receiver.recv().await
+    }
+
+    /// Receives the next event if one is queued.
+    pub fn try_recv(&mut self) -> Option<PluginEvent> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message() -> PluginEvent {
+        PluginEvent::MessageReceived {
+            channel_id: "telegram".to_string(),
+            sender_id: "alice".to_string(),
+            text: "hi".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_filter_by_topic() {
+        let bus = EventBus::default();
+        let mut messages = bus.subscribe([topics::MESSAGE_RECEIVED]);
+        let mut weather = bus.subscribe(["weather.updated"]);
+        let mut all = bus.subscribe_all();
+
+        assert_eq!(bus.publish(message()).await, 2);
+        let custom = PluginEvent::custom("weather.updated", json!({"city": "Oslo"}));
+        assert_eq!(bus.publish(custom.clone()).await, 2);
+
+        assert_eq!(messages.try_recv(), Some(message()));
+        assert_eq!(messages.try_recv(), None);
+        assert_eq!(weather.try_recv(), Some(custom.clone()));
+        assert_eq!(all.try_recv(), Some(message()));
+        assert_eq!(all.try_recv(), Some(custom));
+    }
+
+    #[tokio::test]
+    async fn test_publish_waits_for_slow_subscriber() {
+        let bus = EventBus::new(1);
+        let mut subscription = bus.subscribe_all();
+        bus.publish(PluginEvent::ConfigReloaded).await;
+
+        // The queue is full: try_publish drops, publish waits for a receive
+        assert_eq!(bus.try_publish(PluginEvent::ConfigReloaded), 0);
+        let publisher = {
+            let bus = bus.clone();
+            tokio::spawn(async move { bus.publish(message()).await })
+        };
+        tokio::task::yield_now().await;
+        assert!(!publisher.is_finished());
+
+        assert_eq!(subscription.recv().await, Some(PluginEvent::ConfigReloaded));
+        assert_eq!(publisher.await.unwrap(), 1);
+        assert_eq!(subscription.recv().await, Some(message()));
+    }
+
+    #[tokio::test]
+    async fn test_dropped_subscriptions_are_removed() {
+        let bus = EventBus::default();
+        let subscription = bus.subscribe_all();
+        assert_eq!(bus.subscriber_count(), 1);
+        drop(subscription);
+        assert_eq!(bus.publish(PluginEvent::ConfigReloaded).await, 0);
+        assert_eq!(bus.subscriber_count(), 0);
+    }
+
+    #[test]
+    fn test_event_serialization() {
+        let event = PluginEvent::AgentRunFinished {
+            agent_id: "default".to_string(),
+            session_key: "s1".to_string(),
+            success: true,
+        };
+        assert_eq!(event.topic(), topics::AGENT_RUN_FINISHED);
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({"type": "agent_run_finished", "agent_id": "default", "session_key": "s1", "success": true})
+        );
+    }
+}
@@ -0,0 +1,308 @@
+//! Health monitoring for plugins.
+//!
+//! The [`PluginRegistry`](crate::PluginRegistry) records every hook call
+//! and initialization of a plugin in a [`HealthTracker`]: how long it took,
+//! whether it failed, and whether it panicked. Panics are caught at the
+//! plugin boundary, so a misbehaving plugin cannot take down the host.
+//!
+//! A [`HealthPolicy`] decides when a plugin is unhealthy and what
+//! [`PluginRegistry::check_health()`](crate::PluginRegistry::check_health)
+//! does about it: report it, disable the plugin, or restart it.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use aisopod_plugin::health::{HealthAction, HealthPolicy};
+//!
+//! registry.set_health_policy(HealthPolicy {
+//!     action: HealthAction::Restart,
+//!     ..Default::default()
+//! });
+//!
+//! // Periodically
+//! for (plugin_id, action) in registry.check_health(&ctx).await {
+//!     tracing::warn!(%plugin_id, ?action, "Unhealthy plugin");
+//! }
+//! ```
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_util::FutureExt;
+use serde::{Deserialize, Serialize};
+
+/// The health state of a plugin.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginState {
+    /// The plugin is within its health policy.
+    #[default]
+    Healthy,
+    /// The plugin violates its health policy but keeps running.
+    Unhealthy,
+    /// The plugin was shut down and its hooks removed.
+    Disabled,
+}
+
+/// Health statistics of a plugin.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PluginHealth {
+    /// Current health state
+    pub state: PluginState,
+    /// Number of calls into the plugin
+    pub calls: u64,
+    /// Number of calls that returned an error
+    pub errors: u64,
+    /// Number of calls that panicked
+    pub panics: u64,
+    /// Total time spent in the plugin, in microseconds
+    pub total_latency_us: u64,
+    /// Longest call, in microseconds
+    pub max_latency_us: u64,
+    /// Number of times the plugin was restarted
+    pub restarts: u32,
+    /// Message of the most recent error or panic
+    pub last_error: Option<String>,
+}
+
+impl PluginHealth {
+    /// Returns the fraction of calls that failed or panicked.
+    pub fn error_rate(&self) -> f64 {
+        if self.calls == 0 {
+            return 0.0;
+        }
+        (self.errors + self.panics) as f64 / self.calls as f64
+    }
+
+    /// Returns the average duration of a call.
+    pub fn average_latency(&self) -> Duration {
+        if self.calls == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_micros(self.total_latency_us / self.calls)
+    }
+}
+
+/// The outcome of a call into a plugin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallOutcome {
+    /// The call succeeded.
+    Ok,
+    /// The call returned an error.
+    Error(String),
+    /// The call panicked.
+    Panic(String),
+}
+
+/// Shared record of plugin health statistics.
+///
+/// Cloning the tracker yields a handle to the same statistics.
+#[derive(Debug, Clone, Default)]
+pub struct HealthTracker {
+    plugins: Arc<Mutex<BTreeMap<String, PluginHealth>>>,
+}
+
+impl HealthTracker {
+    /// Creates an empty tracker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a call into a plugin.
+    pub fn record(&self, plugin_id: &str, latency: Duration, outcome: CallOutcome) {
+        let mut plugins = self.lock();
+        let health = plugins.entry(plugin_id.to_string()).or_default();
+        let latency_us = latency.as_micros().min(u64::MAX as u128) as u64;
+        health.calls += 1;
+        health.total_latency_us = health.total_latency_us.saturating_add(latency_us);
+        health.max_latency_us = health.max_latency_us.max(latency_us);
+        match outcome {
+            CallOutcome::Ok => {}
+            CallOutcome::Error(message) => {
+                health.errors += 1;
+                health.last_error = Some(message);
+            }
+            CallOutcome::Panic(message) => {
+                health.panics += 1;
+                health.last_error = Some(message);
+            }
+        }
+    }
+
+    /// Returns the health of a plugin, if any call was recorded for it.
+    pub fn get(&self, plugin_id: &str) -> Option<PluginHealth> {
+        self.lock().get(plugin_id).cloned()
+    }
+
+    /// Returns the health of every tracked plugin, by plugin ID.
+    pub fn snapshot(&self) -> BTreeMap<String, PluginHealth> {
+        self.lock().clone()
+    }
+
+    /// Returns whether the plugin is disabled.
+    pub fn is_disabled(&self, plugin_id: &str) -> bool {
+        self.lock()
+            .get(plugin_id)
+            .is_some_and(|health| health.state == PluginState::Disabled)
+    }
+
+    /// Sets the state of a plugin.
+    pub(crate) fn set_state(&self, plugin_id: &str, state: PluginState) {
+        self.lock().entry(plugin_id.to_string()).or_default().state = state;
+    }
+
+    /// Clears a restarted plugin's statistics and counts the restart.
+    pub(crate) fn restarted(&self, plugin_id: &str) {
+        let mut plugins = self.lock();
+        let health = plugins.entry(plugin_id.to_string()).or_default();
+        *health = PluginHealth {
+            restarts: health.restarts + 1,
+            ..Default::default()
+        };
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, PluginHealth>> {
+        self.plugins
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// What [`PluginRegistry::check_health()`](crate::PluginRegistry::check_health)
+/// does with an unhealthy plugin.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthAction {
+    /// Mark the plugin unhealthy and keep it running.
+    #[default]
+    Report,
+    /// Shut the plugin down and remove its hooks and channel factories.
+    Disable,
+    /// Shut the plugin down and initialize it again with fresh statistics.
+    Restart,
+}
+
+/// Thresholds beyond which a plugin is unhealthy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthPolicy {
+    /// Highest tolerated fraction of failed calls
+    pub max_error_rate: f64,
+    /// Calls needed before the error rate and latency are judged
+    pub min_calls: u64,
+    /// Highest tolerated number of panics
+    pub max_panics: u64,
+    /// Highest tolerated average call duration
+    pub max_average_latency: Option<Duration>,
+    /// Action taken for an unhealthy plugin
+    pub action: HealthAction,
+    /// Restarts after which a plugin that stays unhealthy is disabled
+    pub max_restarts: u32,
+}
+
+impl Default for HealthPolicy {
+    fn default() -> Self {
+        Self {
+            max_error_rate: 0.5,
+            min_calls: 20,
+            max_panics: 3,
+            max_average_latency: None,
+            action: HealthAction::Report,
+            max_restarts: 3,
+        }
+    }
+}
+
+impl HealthPolicy {
+    /// Returns whether the statistics violate the policy.
+    pub fn is_unhealthy(&self, health: &PluginHealth) -> bool {
+        if health.panics > self.max_panics {
+            return true;
+        }
+        if health.calls < self.min_calls {
+            return false;
+        }
+        health.error_rate() > self.max_error_rate
+            || self
+                .max_average_latency
+                .is_some_and(|max| health.average_latency() > max)
+    }
+}
+
+/// Runs a call into a plugin, catching a panic as its message.
+pub(crate) async fn catch_panic<F: Future>(future: F) -> Result<F::Output, String> {
+    AssertUnwindSafe(future)
+        .catch_unwind()
+        .await
+        .map_err(|payload| panic_message(payload.as_ref()))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        format!("panicked: {}", message)
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        format!("panicked: {}", message)
+    } else {
+        "panicked".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_rates() {
+        let tracker = HealthTracker::new();
+        tracker.record("weather", Duration::from_millis(2), CallOutcome::Ok);
+        tracker.record(
+            "weather",
+            Duration::from_millis(4),
+            CallOutcome::Error("timeout".to_string()),
+        );
+
+        let health = tracker.get("weather").unwrap();
+        assert_eq!(health.calls, 2);
+        assert_eq!(health.errors, 1);
+        assert_eq!(health.error_rate(), 0.5);
+        assert_eq!(health.average_latency(), Duration::from_millis(3));
+        assert_eq!(health.max_latency_us, 4000);
+        assert_eq!(health.last_error.as_deref(), Some("timeout"));
+        assert!(tracker.get("other").is_none());
+    }
+
+    #[test]
+    fn test_policy() {
+        let policy = HealthPolicy {
+            min_calls: 4,
+            max_panics: 0,
+            ..Default::default()
+        };
+        let mut health = PluginHealth {
+            calls: 3,
+            errors: 3,
+            ..Default::default()
+        };
+        // Too few calls to judge the error rate
+        assert!(!policy.is_unhealthy(&health));
+        health.calls = 4;
+        assert!(policy.is_unhealthy(&health));
+
+        let panicked = PluginHealth {
+            calls: 1,
+            panics: 1,
+            ..Default::default()
+        };
+        assert!(policy.is_unhealthy(&panicked));
+    }
+
+    #[tokio::test]
+    async fn test_catch_panic() {
+        assert_eq!(catch_panic(async { 1 }).await, Ok(1));
+        let err = catch_panic(async { panic!("boom") }).await.unwrap_err();
+        assert_eq!(err, "panicked: boom");
+    }
+}
@@ -6,10 +6,12 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
-use tracing::debug;
+use tracing::{debug, warn};
 
 use crate::api::PluginApi;
+use crate::health::{catch_panic, CallOutcome, HealthTracker};
 
 /// Represents a lifecycle hook event that plugins can register for.
 ///
@@ -171,6 +173,8 @@ impl PluginHookHandler {
 pub struct HookRegistry {
     /// Map of hook type to list of (plugin_id, handler) tuples.
     handlers: HashMap<Hook, Vec<(String, Arc<dyn HookHandler>)>>,
+    /// Health statistics of the plugins whose handlers are called.
+    health: HealthTracker,
 }
 
 impl HookRegistry {
@@ -178,9 +182,15 @@ impl HookRegistry {
     pub fn new() -> Self {
         Self {
             handlers: HashMap::new(),
+            health: HealthTracker::new(),
         }
     }
 
+    /// Returns the tracker recording the health of handler calls.
+    pub fn health(&self) -> &HealthTracker {
+        &self.health
+    }
+
     /// Registers a hook handler for a specific lifecycle event.
     ///
     /// # Arguments
@@ -198,8 +208,10 @@ impl HookRegistry {
     /// Dispatches a hook event to all registered handlers.
     ///
     /// All handlers for the given hook are called asynchronously.
-    /// If a handler fails, the error is logged but other handlers
-    /// continue to execute.
+    /// If a handler fails or panics, the error is logged but other handlers
+    /// continue to execute. Each call's duration and outcome is recorded in
+    /// the [`health()`](HookRegistry::health) tracker, and handlers of
+    /// disabled plugins are skipped.
     ///
     /// # Arguments
     ///
@@ -207,23 +219,39 @@ impl HookRegistry {
     pub async fn dispatch(&self, ctx: &HookContext) {
         if let Some(handlers) = self.handlers.get(&ctx.hook) {
             for (plugin_id, handler) in handlers {
-                match handler.handle(ctx).await {
-                    Ok(()) => {
+                if self.health.is_disabled(plugin_id) {
+                    continue;
+                }
+                let start = Instant::now();
+                let outcome = match catch_panic(handler.handle(ctx)).await {
+                    Ok(Ok(())) => {
                         debug!(
                             plugin_id = %plugin_id,
                             hook = ?ctx.hook,
                             "Hook handler completed successfully"
                         );
+                        CallOutcome::Ok
                     }
-                    Err(e) => {
+                    Ok(Err(e)) => {
                         debug!(
                             plugin_id = %plugin_id,
                             hook = ?ctx.hook,
                             error = %e,
                             "Hook handler failed"
                         );
+                        CallOutcome::Error(e.to_string())
                     }
-                }
+                    Err(message) => {
+                        warn!(
+                            plugin_id = %plugin_id,
+                            hook = ?ctx.hook,
+                            error = %message,
+                            "Hook handler panicked"
+                        );
+                        CallOutcome::Panic(message)
+                    }
+                };
+                self.health.record(plugin_id, start.elapsed(), outcome);
             }
         }
     }
@@ -255,6 +283,24 @@ impl HookRegistry {
         }
     }
 
+    /// Removes all handlers registered by a plugin.
+    ///
+    /// Returns the number of handlers removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_id` - The ID of the plugin whose handlers are removed
+    pub fn remove_plugin(&mut self, plugin_id: &str) -> usize {
+        let mut removed = 0;
+        for handlers in self.handlers.values_mut() {
+            let before = handlers.len();
+            handlers.retain(|(id, _)| id != plugin_id);
+            removed += before - handlers.len();
+        }
+        self.handlers.retain(|_, handlers| !handlers.is_empty());
+        removed
+    }
+
     /// Returns the total number of registered hooks across all types.
     pub fn total_hook_count(&self) -> usize {
         self.handlers.values().map(|v| v.len()).sum()
@@ -457,4 +503,39 @@ mod tests {
         assert_eq!(registry.total_hook_count(), 3);
         assert_eq!(registry.hook_type_count(), 3);
     }
+
+    #[tokio::test]
+    async fn test_hook_registry_remove_plugin() {
+        let mut registry = HookRegistry::new();
+
+        #[derive(Clone)]
+        struct TestHandler;
+
+        #[async_trait::async_trait]
+        impl HookHandler for TestHandler {
+            async fn handle(&self, _ctx: &HookContext) -> Result<(), Box<dyn std::error::Error>> {
+                Ok(())
+            }
+        }
+
+        let handler = Arc::new(TestHandler);
+
+        registry.register(
+            Hook::BeforeAgentRun,
+            "plugin-1".to_string(),
+            handler.clone(),
+        );
+        registry.register(
+            Hook::BeforeAgentRun,
+            "plugin-2".to_string(),
+            handler.clone(),
+        );
+        registry.register(Hook::AfterAgentRun, "plugin-1".to_string(), handler.clone());
+
+        assert_eq!(registry.remove_plugin("plugin-1"), 2);
+        assert_eq!(registry.handler_count(&Hook::BeforeAgentRun), 1);
+        assert_eq!(registry.handler_count(&Hook::AfterAgentRun), 0);
+        assert_eq!(registry.hook_type_count(), 1);
+        assert_eq!(registry.remove_plugin("plugin-1"), 0);
+    }
 }
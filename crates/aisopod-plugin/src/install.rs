@@ -0,0 +1,508 @@
+//! Plugin installation from git repositories and archives.
+//!
+//! This module provides the [`PluginInstaller`] that fetches a plugin,
+//! verifies it, and places it in a plugin directory where the
+//! [`DynamicPluginLoader`] and the WebAssembly loader discover it.
+//!
+//! # Sources
+//!
+//! A [`PluginSource`] is parsed from the string given by the user:
+//!
+//! - `git+<url>[#<ref>]`, or any URL ending in `.git` - cloned with `git`,
+//!   optionally at a branch or tag
+//! - `http://` or `https://` URLs - downloaded as a `.tar.gz` archive, as
+//!   served by plugin registries and release pages
+//! - a local directory or `.tar.gz` file
+//!
+//! # Verification
+//!
+//! Before a plugin is moved into place, the installer checks that:
+//!
+//! - an `aisopod.plugin.toml` manifest exists at the top of the plugin or
+//!   of its single top-level directory, and is valid
+//! - the plugin ID is usable as a directory name
+//! - the manifest's compatibility constraints accept this host version
+//! - the entry point exists, either as `{entry_point}.wasm` or as a shared
+//!   library built for this host's plugin ABI
+//! - the plugin's signature is accepted by the installer's
+//!   [`SignaturePolicy`], checked before any plugin code is loaded
+//!
+//! # Example
+//!
+//! ```ignore
+//! use aisopod_plugin::install::{PluginInstaller, PluginSource};
+//!
+//! let installer = PluginInstaller::new("/home/me/.aisopod/plugins");
+//! let source = PluginSource::parse("git+https://github.com/example/weather-plugin#v1.2.0")?;
+//! let installed = unsafe { installer.install(&source, false).await? };
+//! println!("Installed {} v{}", installed.id, installed.version);
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use thiserror::Error;
+use tracing::info;
+
+use crate::dynamic::{
+    library_filename, read_abi_version, DiscoveredPlugin, DynamicPluginLoader, LoadError,
+};
+use crate::manifest::PluginManifest;
+use crate::signing::SignaturePolicy;
+
+/// Manifest file name expected at the root of a plugin.
+const MANIFEST_FILE: &str = "aisopod.plugin.toml";
+
+/// Largest archive accepted from a download, in bytes.
+const MAX_ARCHIVE_SIZE: usize = 256 * 1024 * 1024;
+
+/// Error types for plugin installation.
+#[derive(Debug, Error)]
+pub enum InstallError {
+    /// The source string could not be understood.
+    #[error("Invalid plugin source '{0}': {1}")]
+    InvalidSource(String, String),
+
+    /// Fetching the plugin failed.
+    #[error("Failed to fetch plugin: {0}")]
+    Fetch(String),
+
+    /// No manifest was found in the fetched plugin.
+    #[error("No {MANIFEST_FILE} found in the plugin source")]
+    MissingManifest,
+
+    /// The plugin ID cannot be used as a directory name.
+    #[error("Invalid plugin ID '{0}'")]
+    InvalidId(String),
+
+    /// The plugin's entry point is missing.
+    #[error("Plugin entry point not found: expected {0} or {1}")]
+    MissingEntryPoint(PathBuf, PathBuf),
+
+    /// A plugin with the same ID is already installed.
+    #[error("Plugin '{0}' is already installed")]
+    AlreadyInstalled(String),
+
+    /// Manifest, compatibility, or ABI verification failed.
+    #[error(transparent)]
+    Load(#[from] LoadError),
+
+    /// File I/O error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Where to fetch a plugin from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PluginSource {
+    /// A git repository, optionally at a branch or tag.
+    Git {
+        /// Repository URL.
+        url: String,
+        /// Branch or tag to check out.
+        reference: Option<String>,
+    },
+    /// A `.tar.gz` archive downloaded over HTTP(S).
+    Archive {
+        /// Archive URL.
+        url: String,
+    },
+    /// A local directory or `.tar.gz` file.
+    Local(PathBuf),
+}
+
+impl PluginSource {
+    /// Parses a source as given on the command line.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InstallError::InvalidSource` if the source is empty or looks
+    /// like a command-line option.
+    pub fn parse(source: &str) -> Result<Self, InstallError> {
+        let source = source.trim();
+        if source.is_empty() || source.starts_with('-') {
+            return Err(InstallError::InvalidSource(
+                source.to_string(),
+                "expected a git URL, archive URL, or local path".to_string(),
+            ));
+        }
+
+        let (location, reference) = match source.split_once('#') {
+            Some((location, reference)) if !reference.is_empty() => {
+                (location, Some(reference.to_string()))
+            }
+            _ => (source, None),
+        };
+        if let Some(url) = location.strip_prefix("git+") {
+            return Ok(Self::Git {
+                url: url.to_string(),
+                reference,
+            });
+        }
+        let is_remote = ["https://", "http://", "ssh://", "git://"]
+            .iter()
+            .any(|scheme| location.starts_with(scheme));
+        if location.ends_with(".git") && (is_remote || location.starts_with("git@")) {
+            return Ok(Self::Git {
+                url: location.to_string(),
+                reference,
+            });
+        }
+        if location.starts_with("https://") || location.starts_with("http://") {
+            return Ok(Self::Archive {
+                url: source.to_string(),
+            });
+        }
+        Ok(Self::Local(PathBuf::from(source)))
+    }
+}
+
+impl std::fmt::Display for PluginSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Git {
+                url,
+                reference: Some(reference),
+            } => write!(f, "{}#{}", url, reference),
+            Self::Git { url, .. } | Self::Archive { url } => write!(f, "{}", url),
+            Self::Local(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// A plugin placed in the plugin directory by [`PluginInstaller::install()`].
+#[derive(Debug, Clone)]
+pub struct InstalledPlugin {
+    /// The plugin's ID.
+    pub id: String,
+    /// The plugin's version.
+    pub version: String,
+    /// The directory the plugin was installed to.
+    pub dir: PathBuf,
+    /// The plugin's manifest.
+    pub manifest: PluginManifest,
+}
+
+/// Installs plugins into a plugin directory.
+#[derive(Debug)]
+pub struct PluginInstaller {
+    plugins_dir: PathBuf,
+    staging: AtomicU64,
+    signature_policy: SignaturePolicy,
+}
+
+impl PluginInstaller {
+    /// Creates an installer placing plugins in `plugins_dir`.
+    pub fn new(plugins_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            plugins_dir: plugins_dir.into(),
+            staging: AtomicU64::new(0),
+            signature_policy: SignaturePolicy::default(),
+        }
+    }
+
+    /// Sets which plugin signatures are accepted at install time.
+    pub fn with_signature_policy(mut self, policy: SignaturePolicy) -> Self {
+        self.signature_policy = policy;
+        self
+    }
+
+    /// Returns the directory plugins are installed to.
+    pub fn plugins_dir(&self) -> &Path {
+        &self.plugins_dir
+    }
+
+    /// Fetches, verifies, and installs a plugin.
+    ///
+    /// The plugin is fetched into a staging directory next to its final
+    /// location and only moved into place once verification passed. With
+    /// `force`, an installed plugin with the same ID is replaced.
+    ///
+    /// # Safety
+    ///
+    /// Verifying a native plugin's ABI version loads its shared library,
+    /// which runs the library's initialization code. Only install plugins
+    /// from sources you trust.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InstallError`] describing the failed step. Nothing is
+    /// left behind in the plugin directory on failure.
+    pub async unsafe fn install(
+        &self,
+        source: &PluginSource,
+        force: bool,
+    ) -> Result<InstalledPlugin, InstallError> {
+        std::fs::create_dir_all(&self.plugins_dir)?;
+        let staging = self.plugins_dir.join(format!(
+            ".staging-{}-{}",
+            std::process::id(),
+            self.staging.fetch_add(1, Ordering::SeqCst)
+        ));
+        let result = self.install_staged(source, &staging, force).await;
+        if staging.exists() {
+            let _ = std::fs::remove_dir_all(&staging);
+        }
+        result
+    }
+
+    async unsafe fn install_staged(
+        &self,
+        source: &PluginSource,
+        staging: &Path,
+        force: bool,
+    ) -> Result<InstalledPlugin, InstallError> {
+        let fetched = staging.join("source");
+        fetch(source, &fetched).await?;
+
+        let root = find_plugin_root(&fetched)?;
+        let manifest = PluginManifest::from_file(&root.join(MANIFEST_FILE))
+            .map_err(|e| LoadError::Manifest(root.to_string_lossy().to_string(), e))?;
+        let id = manifest.plugin.id.clone();
+        if !is_valid_id(&id) {
+            return Err(InstallError::InvalidId(id));
+        }
+
+        let discovered = DiscoveredPlugin {
+            manifest: manifest.clone(),
+            dir: root.clone(),
+        };
+        let loader =
+            DynamicPluginLoader::new(vec![]).with_signature_policy(self.signature_policy.clone());
+        loader.check_version_compatibility(&discovered)?;
+        verify_entry_point(&loader, &discovered)?;
+
+        let target = self.plugins_dir.join(&id);
+        if target.exists() {
+            if !force {
+                return Err(InstallError::AlreadyInstalled(id));
+            }
+            std::fs::remove_dir_all(&target)?;
+        }
+        std::fs::rename(&root, &target)?;
+        info!(plugin_id = %id, source = %source, "Installed plugin");
+
+        Ok(InstalledPlugin {
+            id,
+            version: manifest.plugin.version.clone(),
+            dir: target,
+            manifest,
+        })
+    }
+}
+
+/// Fetches a plugin source into `dest`.
+async fn fetch(source: &PluginSource, dest: &Path) -> Result<(), InstallError> {
+    match source {
+        PluginSource::Git { url, reference } => {
+            git_clone(url, reference.as_deref(), dest).await?;
+            // The history is not needed to run the plugin
+            let _ = std::fs::remove_dir_all(dest.join(".git"));
+            Ok(())
+        }
+        PluginSource::Archive { url } => unpack_archive(&download(url).await?[..], dest),
+        PluginSource::Local(path) if path.is_dir() => copy_dir(path, dest),
+        PluginSource::Local(path) if path.is_file() => {
+            unpack_archive(std::fs::File::open(path)?, dest)
+        }
+        PluginSource::Local(path) => Err(InstallError::Fetch(format!(
+            "{} does not exist",
+            path.display()
+        ))),
+    }
+}
+
+/// Shallowly clones a git repository into `dest`, optionally at a branch
+/// or tag.
+pub(crate) async fn git_clone(
+    url: &str,
+    reference: Option<&str>,
+    dest: &Path,
+) -> Result<(), InstallError> {
+    let mut command = tokio::process::Command::new("git");
+    command.args(["clone", "--quiet", "--depth", "1"]);
+    if let Some(reference) = reference {
+        command.args(["--branch", reference]);
+    }
+    let output = command
+        .arg("--")
+        .arg(url)
+        .arg(dest)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .await
+        .map_err(|e| InstallError::Fetch(format!("Failed to run git: {}", e)))?;
+    if !output.status.success() {
+        return Err(InstallError::Fetch(format!(
+            "git clone failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Downloads a file of at most [`MAX_ARCHIVE_SIZE`] bytes.
+pub(crate) async fn download(url: &str) -> Result<Vec<u8>, InstallError> {
+    let response = reqwest::get(url)
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| InstallError::Fetch(e.to_string()))?;
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_ARCHIVE_SIZE as u64)
+    {
+        return Err(InstallError::Fetch("Archive is too large".to_string()));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| InstallError::Fetch(e.to_string()))?;
+    if bytes.len() > MAX_ARCHIVE_SIZE {
+        return Err(InstallError::Fetch("Archive is too large".to_string()));
+    }
+    Ok(bytes.to_vec())
+}
+
+/// Unpacks a `.tar.gz` archive into `dest`.
+///
+/// Entries that would land outside `dest` are skipped by the unpacker.
+pub(crate) fn unpack_archive(reader: impl std::io::Read, dest: &Path) -> Result<(), InstallError> {
+    std::fs::create_dir_all(dest)?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(reader));
+    archive
+        .unpack(dest)
+        .map_err(|e| InstallError::Fetch(format!("Failed to unpack archive: {}", e)))
+}
+
+/// Copies a directory tree, skipping version control metadata.
+pub(crate) fn copy_dir(src: &Path, dest: &Path) -> Result<(), InstallError> {
+    for entry in walkdir::WalkDir::new(src)
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != ".git")
+    {
+        let entry = entry.map_err(|e| InstallError::Fetch(e.to_string()))?;
+        let relative = entry
+            .path()
+            .strip_prefix(src)
+            .map_err(|e| InstallError::Fetch(e.to_string()))?;
+        let target = dest.join(relative);
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(&target)?;
+        } else if entry.file_type().is_file() {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Finds the directory holding the manifest.
+///
+/// Archives commonly wrap their contents in a single top-level directory,
+/// so that directory is checked when the manifest is not at the top.
+fn find_plugin_root(fetched: &Path) -> Result<PathBuf, InstallError> {
+    if fetched.join(MANIFEST_FILE).is_file() {
+        return Ok(fetched.to_path_buf());
+    }
+    let mut entries = std::fs::read_dir(fetched)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path());
+    match (entries.next(), entries.next()) {
+        (Some(dir), None) if dir.join(MANIFEST_FILE).is_file() => Ok(dir),
+        _ => Err(InstallError::MissingManifest),
+    }
+}
+
+/// Returns whether a plugin ID is safe to use as a directory name.
+pub(crate) fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && !id.starts_with('.')
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Checks that the plugin's entry point exists and matches the host ABI.
+///
+/// # Safety
+///
+/// Loads native plugin libraries; see [`PluginInstaller::install()`].
+unsafe fn verify_entry_point(
+    loader: &DynamicPluginLoader,
+    discovered: &DiscoveredPlugin,
+) -> Result<(), InstallError> {
+    let component = discovered
+        .dir
+        .join(format!("{}.wasm", discovered.manifest.plugin.entry_point));
+    let entry_point = &discovered.manifest.plugin.entry_point;
+    if component.is_file() {
+        loader.verify_signature(discovered, &format!("{}.wasm", entry_point), &component)?;
+        return Ok(());
+    }
+
+    let library = loader.library_path(discovered);
+    if !library.is_file() {
+        return Err(InstallError::MissingEntryPoint(component, library));
+    }
+    loader.verify_signature(discovered, &library_filename(entry_point), &library)?;
+    let found = read_abi_version(&library)?;
+    if found != crate::abi::ABI_VERSION {
+        return Err(LoadError::AbiMismatch {
+            expected: crate::abi::ABI_VERSION,
+            found,
+            plugin_id: discovered.manifest.plugin.id.clone(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_source() {
+        assert_eq!(
+            PluginSource::parse("git+https://example.com/org/plugin#v1.0").unwrap(),
+            PluginSource::Git {
+                url: "https://example.com/org/plugin".to_string(),
+                reference: Some("v1.0".to_string()),
+            }
+        );
+        assert_eq!(
+            PluginSource::parse("https://github.com/org/plugin.git").unwrap(),
+            PluginSource::Git {
+                url: "https://github.com/org/plugin.git".to_string(),
+                reference: None,
+            }
+        );
+        assert_eq!(
+            PluginSource::parse("git@github.com:org/plugin.git#main").unwrap(),
+            PluginSource::Git {
+                url: "git@github.com:org/plugin.git".to_string(),
+                reference: Some("main".to_string()),
+            }
+        );
+        assert_eq!(
+            PluginSource::parse("https://plugins.example.com/weather/1.2.0").unwrap(),
+            PluginSource::Archive {
+                url: "https://plugins.example.com/weather/1.2.0".to_string(),
+            }
+        );
+        assert_eq!(
+            PluginSource::parse("./plugins/weather").unwrap(),
+            PluginSource::Local(PathBuf::from("./plugins/weather"))
+        );
+        assert!(PluginSource::parse("--upload-pack=evil").is_err());
+        assert!(PluginSource::parse("  ").is_err());
+    }
+
+    #[test]
+    fn test_is_valid_id() {
+        assert!(is_valid_id("weather-plugin_2.0"));
+        assert!(!is_valid_id(""));
+        assert!(!is_valid_id(".hidden"));
+        assert!(!is_valid_id("../escape"));
+        assert!(!is_valid_id("with space"));
+    }
+}
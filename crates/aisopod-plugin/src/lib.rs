@@ -18,6 +18,10 @@
 //! 4. **Initialization**: `Plugin::init()` is called with runtime context
 //! 5. **Shutdown**: `Plugin::shutdown()` is called during system shutdown
 //!
+//! Plugins communicate with each other through the [`EventBus`] in their
+//! [`PluginContext`], publishing and subscribing to [`PluginEvent`]s by
+//! topic. See the [`events`] module for details.
+//!
 //! ## Manifest Format
 //!
 //! Plugins can include a `aisopod.plugin.toml` manifest file that describes
@@ -31,6 +35,9 @@
 //! - **Registration**: Plugins are registered via [`PluginRegistry::register()`]
 //! - **Initialization**: All plugins initialized via [`PluginRegistry::init_all()`]
 //! - **Shutdown**: All plugins shut down via [`PluginRegistry::shutdown_all()`]
+//! - **Health**: Panics, latency, and errors of calls into plugins are
+//!   tracked, and misbehaving plugins disabled or restarted via
+//!   [`PluginRegistry::check_health()`]. See the [`health`] module.
 //!
 //! ## Dynamic Plugin Loading
 //!
@@ -51,6 +58,26 @@
 //! }
 //! ```
 //!
+//! Plugins are installed from git repositories, archives, or local
+//! directories with [`install::PluginInstaller`], which verifies the
+//! manifest and ABI version before placing the plugin in a plugin directory.
+//!
+//! Dynamic plugins can be hot reloaded during development with
+//! [`reload::PluginReloader`], which watches plugin directories for rebuilt
+//! libraries and swaps the new build in without restarting the process.
+//!
+//! Plugins, including dynamic ones, ship channels by registering a
+//! [`ChannelFactory`]. The host creates, connects, and disconnects the
+//! channels for configured accounts with [`PluginChannels`].
+//!
+//! ## WebAssembly Plugins
+//!
+//! With the `wasm` feature, plugins compiled to WebAssembly components can be
+//! loaded with [`wasm::WasmPluginLoader`]. They run sandboxed and only reach
+//! the host through a small API for logging, reading their configuration, and
+//! registering the tools declared in their manifest. See the `wasm` module for
+//! details.
+//!
 //! ## Configuration
 //!
 //! Plugins can define their own configuration schemas and receive configuration
@@ -72,6 +99,15 @@
 //!
 //! See the [`security`] and [`commands`] modules for details.
 //!
+//! Plugins request capabilities in their manifest, and the registry only
+//! lets a plugin with [`PluginPermissions`] register what it declared and
+//! was granted. See the [`permissions`] module for details.
+//!
+//! Plugin manifests and binaries can be signed with Ed25519 keys. Loaders
+//! and the installer given a [`SignaturePolicy`] refuse tampered plugins,
+//! and optionally any plugin not signed by a trusted key. See the
+//! [`signing`] module for details.
+//!
 //! ## Skills System
 //!
 //! The skills system provides a higher-level abstraction for reusable bundles
@@ -93,6 +129,13 @@
 //! - [`skills::ScaffoldOptions`] - Configuration options for scaffolding
 //! - [`skills::to_pascal_case`] - Helper to convert kebab-case names to PascalCase
 //!
+//! ### Remote Skills
+//!
+//! Curated skills can be installed from a git repository or an HTTP index:
+//!
+//! - [`skills::SkillInstaller`] - Install and update skills, honoring version pins
+//! - [`skills::SkillSource`] - Where remote skills are fetched from
+//!
 //! ## Example
 //!
 //! This example shows the basic structure of a plugin:
@@ -152,45 +195,71 @@
 //! - [`meta`]: Plugin metadata types
 //! - [`manifest`]: Plugin manifest format and parser
 //! - [`context`]: Runtime context for plugins
+//! - [`events`]: Event bus for communication between plugins
 //! - [`trait`]: Core plugin trait definitions
 //! - [`api`]: Plugin API for capability registration
+//! - [`channel`]: Channels contributed by plugins, with their connection lifecycle
 //! - [`command`]: Plugin command types for CLI integration
+//! - [`health`]: Health monitoring of plugins
 //! - [`hook`]: Lifecycle hook types
 //! - [`registry`]: Plugin registry for lifecycle management
 //! - [`config`]: Plugin configuration types
 //! - [`abi`]: ABI definitions for dynamic plugins
 //! - [`dynamic`]: Dynamic plugin loading from shared libraries
+//! - [`install`]: Plugin installation from git repositories and archives
+//! - [`reload`]: Hot reload of dynamic plugins
+//! - `wasm`: WebAssembly component plugins (requires the `wasm` feature)
+//! - [`permissions`]: Capability permissions declared by and granted to plugins
 //! - [`security`]: Security utilities for command registration
+//! - [`signing`]: Ed25519 signatures for plugin manifests and binaries
 //! - [`commands`]: Command registry with security hardening
 //! - [`skills`]: Core types for the skills system
 
 pub mod abi;
 pub mod api;
 pub mod builtin;
+pub mod channel;
 pub mod command;
 pub mod commands;
 pub mod config;
 pub mod context;
 pub mod dynamic;
+pub mod events;
+pub mod health;
 pub mod hook;
+pub mod install;
 pub mod manifest;
 pub mod meta;
+pub mod permissions;
 pub mod registry;
+pub mod reload;
 pub mod r#trait;
 pub mod security;
+pub mod signing;
 pub mod skills;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 pub use abi::{ABI_VERSION, PluginAbiVersionFn, PluginCreateFn, PluginDestroyFn};
 pub use api::PluginApi;
+pub use channel::{ChannelFactory, PluginChannels};
 pub use command::PluginCommand;
 pub use commands::CommandRegistry;
 pub use config::{ConfigError, ConfigReloadable, PluginConfig, PluginConfigSchema};
 pub use context::PluginContext;
 pub use dynamic::{DiscoveredPlugin, DynamicPluginLoader, LoadError};
+pub use events::{EventBus, EventSubscription, PluginEvent};
+pub use health::{HealthAction, HealthPolicy, HealthTracker, PluginHealth, PluginState};
 pub use hook::{Hook, HookContext, HookHandler, HookRegistry, PluginHookHandler};
+pub use install::{InstallError, InstalledPlugin, PluginInstaller, PluginSource};
 pub use manifest::{ManifestError, PluginCapabilities, PluginCompatibility, PluginManifest, PluginManifestInfo};
 pub use meta::PluginMeta;
+pub use permissions::{Capability, PermissionError, PluginPermissions};
 pub use registry::{PluginRegistry, RegistryError};
+pub use reload::{PluginReloader, PluginWatcher, ReloadError};
 pub use r#trait::Plugin;
+pub use signing::{PluginSignature, SignatureError, SignaturePolicy};
 pub use security::{SecurityError, MAX_ARG_SIZE, RESERVED_COMMANDS, sanitize_argument, validate_command_name};
 pub use skills::{Skill, SkillCategory, SkillContext, SkillMeta, scaffold_skill, ScaffoldOptions, to_pascal_case};
+#[cfg(feature = "wasm")]
+pub use wasm::{WasmPlugin, WasmPluginLoader, WasmTool};
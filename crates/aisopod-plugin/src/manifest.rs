@@ -23,6 +23,8 @@
 //! providers = []
 //! commands = ["my-command"]
 //! hooks = ["BeforeAgentRun", "AfterAgentRun"]
+//! network = true
+//! filesystem = false
 //!
 //! [compatibility]
 //! min_host_version = "0.1.0"
@@ -115,6 +117,11 @@ pub struct PluginManifestInfo {
 /// * `providers` - List of provider types this plugin supports
 /// * `commands` - List of CLI commands this plugin provides
 /// * `hooks` - List of lifecycle hooks this plugin handles
+/// * `network` - Whether this plugin needs network access
+/// * `filesystem` - Whether this plugin needs filesystem access
+///
+/// Registrations not listed here are denied when the registry enforces
+/// [`PluginPermissions`](crate::permissions::PluginPermissions).
 #[derive(Debug, Clone, Default, Deserialize, PartialEq)]
 pub struct PluginCapabilities {
     /// Channel types this plugin supports (e.g., "text", "voice", "dm").
@@ -132,6 +139,12 @@ pub struct PluginCapabilities {
     /// Lifecycle hooks this plugin handles.
     #[serde(default)]
     pub hooks: Option<Vec<String>>,
+    /// Whether this plugin needs network access.
+    #[serde(default)]
+    pub network: bool,
+    /// Whether this plugin needs filesystem access outside its data directory.
+    #[serde(default)]
+    pub filesystem: bool,
 }
 
 /// Host version compatibility constraints.
@@ -557,6 +570,8 @@ mod tests {
         assert_eq!(caps.providers, None);
         assert_eq!(caps.commands, None);
         assert_eq!(caps.hooks, None);
+        assert!(!caps.network);
+        assert!(!caps.filesystem);
     }
 
     #[test]
@@ -0,0 +1,261 @@
+//! Capability permissions for plugins.
+//!
+//! Plugins declare the capabilities they request in the `[capabilities]`
+//! section of their manifest, and operators grant capability kinds to each
+//! plugin in the configuration. A [`PluginPermissions`] combines the two:
+//! a registration through [`PluginApi`](crate::PluginApi) is only accepted
+//! when its capability kind is granted and the registered item is declared
+//! in the manifest.
+//!
+//! ```toml
+//! [capabilities]
+//! tools = ["weather"]
+//! hooks = ["BeforeAgentRun"]
+//! network = true
+//! ```
+//!
+//! Network and filesystem access are not registered through the API; hosts
+//! check them with [`PluginPermissions::allows()`] before handing a plugin
+//! those resources.
+//!
+//! Plugins the [`PluginRegistry`](crate::PluginRegistry) has no permissions
+//! for are unrestricted, which keeps compiled-in plugins working unchanged.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::manifest::PluginCapabilities;
+
+/// A kind of capability a plugin can request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// Registering channel implementations.
+    Channels,
+    /// Registering tools.
+    Tools,
+    /// Registering model providers.
+    Providers,
+    /// Registering CLI commands.
+    Commands,
+    /// Registering lifecycle hooks.
+    Hooks,
+    /// Network access.
+    Network,
+    /// Filesystem access outside the plugin's data directory.
+    Filesystem,
+}
+
+impl Capability {
+    /// All capability kinds.
+    pub const ALL: [Capability; 7] = [
+        Capability::Channels,
+        Capability::Tools,
+        Capability::Providers,
+        Capability::Commands,
+        Capability::Hooks,
+        Capability::Network,
+        Capability::Filesystem,
+    ];
+
+    /// Returns the name used in manifests and configuration.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Capability::Channels => "channels",
+            Capability::Tools => "tools",
+            Capability::Providers => "providers",
+            Capability::Commands => "commands",
+            Capability::Hooks => "hooks",
+            Capability::Network => "network",
+            Capability::Filesystem => "filesystem",
+        }
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Capability {
+    type Err = PermissionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Capability::ALL
+            .into_iter()
+            .find(|capability| capability.as_str() == s)
+            .ok_or_else(|| PermissionError::UnknownCapability(s.to_string()))
+    }
+}
+
+/// Error types for capability permission checks.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PermissionError {
+    /// A grant names a capability that does not exist.
+    #[error("Unknown capability '{0}'")]
+    UnknownCapability(String),
+
+    /// The capability kind is not granted to the plugin.
+    #[error("Capability '{0}' is not granted to the plugin")]
+    NotGranted(Capability),
+
+    /// The item is not declared in the plugin's manifest.
+    #[error("{capability} entry '{name}' is not declared in the plugin manifest")]
+    Undeclared {
+        /// The capability kind
+        capability: Capability,
+        /// The undeclared item's name
+        name: String,
+    },
+}
+
+/// The capabilities a plugin declared and was granted.
+#[derive(Debug, Clone, Default)]
+pub struct PluginPermissions {
+    declared: PluginCapabilities,
+    granted: HashSet<Capability>,
+}
+
+impl PluginPermissions {
+    /// Creates permissions from a manifest's declared capabilities and the
+    /// capability kinds granted to the plugin.
+    pub fn new(
+        declared: PluginCapabilities,
+        granted: impl IntoIterator<Item = Capability>,
+    ) -> Self {
+        Self {
+            declared,
+            granted: granted.into_iter().collect(),
+        }
+    }
+
+    /// Creates permissions from capability names, as listed in the
+    /// configuration's per-plugin grants.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PermissionError::UnknownCapability` for an unknown name.
+    pub fn from_grants<S: AsRef<str>>(
+        declared: PluginCapabilities,
+        grants: &[S],
+    ) -> Result<Self, PermissionError> {
+        let granted = grants
+            .iter()
+            .map(|grant| grant.as_ref().parse())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(declared, granted))
+    }
+
+    /// Returns whether the capability kind is both declared and granted.
+    pub fn allows(&self, capability: Capability) -> bool {
+        self.granted.contains(&capability) && self.declares(capability)
+    }
+
+    /// Checks that registering the item `name` of the capability kind is
+    /// permitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PermissionError::NotGranted` if the kind is not granted,
+    /// and `PermissionError::Undeclared` if the manifest does not list
+    /// `name`.
+    pub fn check(&self, capability: Capability, name: &str) -> Result<(), PermissionError> {
+        if !self.granted.contains(&capability) {
+            return Err(PermissionError::NotGranted(capability));
+        }
+        let declared = self
+            .declared_names(capability)
+            .is_some_and(|names| names.iter().any(|declared| declared == name));
+        if !declared {
+            return Err(PermissionError::Undeclared {
+                capability,
+                name: name.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the capability kinds the manifest requests.
+    pub fn requested(&self) -> Vec<Capability> {
+        Capability::ALL
+            .into_iter()
+            .filter(|capability| self.declares(*capability))
+            .collect()
+    }
+
+    fn declares(&self, capability: Capability) -> bool {
+        match capability {
+            Capability::Network => self.declared.network,
+            Capability::Filesystem => self.declared.filesystem,
+            _ => self
+                .declared_names(capability)
+                .is_some_and(|names| !names.is_empty()),
+        }
+    }
+
+    fn declared_names(&self, capability: Capability) -> Option<&[String]> {
+        let names = match capability {
+            Capability::Channels => &self.declared.channels,
+            Capability::Tools => &self.declared.tools,
+            Capability::Providers => &self.declared.providers,
+            Capability::Commands => &self.declared.commands,
+            Capability::Hooks => &self.declared.hooks,
+            Capability::Network | Capability::Filesystem => return None,
+        };
+        names.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn declared() -> PluginCapabilities {
+        PluginCapabilities {
+            tools: Some(vec!["weather".to_string()]),
+            hooks: Some(vec!["BeforeAgentRun".to_string()]),
+            network: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check_requires_grant_and_declaration() {
+        let permissions =
+            PluginPermissions::from_grants(declared(), &["tools", "network"]).unwrap();
+
+        assert!(permissions.check(Capability::Tools, "weather").is_ok());
+        assert_eq!(
+            permissions.check(Capability::Tools, "shell"),
+            Err(PermissionError::Undeclared {
+                capability: Capability::Tools,
+                name: "shell".to_string(),
+            })
+        );
+        // Declared in the manifest but not granted
+        assert_eq!(
+            permissions.check(Capability::Hooks, "BeforeAgentRun"),
+            Err(PermissionError::NotGranted(Capability::Hooks))
+        );
+        assert!(permissions.allows(Capability::Network));
+        assert!(!permissions.allows(Capability::Filesystem));
+    }
+
+    #[test]
+    fn test_requested_and_unknown_grants() {
+        let permissions = PluginPermissions::new(declared(), []);
+        assert_eq!(
+            permissions.requested(),
+            vec![Capability::Tools, Capability::Hooks, Capability::Network]
+        );
+        assert!(!permissions.allows(Capability::Network));
+
+        assert_eq!(
+            PluginPermissions::from_grants(declared(), &["sockets"]).unwrap_err(),
+            PermissionError::UnknownCapability("sockets".to_string())
+        );
+    }
+}
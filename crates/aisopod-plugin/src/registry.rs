@@ -3,13 +3,27 @@
 //! This module provides the [`PluginRegistry`] struct that manages
 //! the full lifecycle of plugins: registration, retrieval, listing,
 //! ordered initialization, and reverse-order shutdown.
+//!
+//! Plugins given [`PluginPermissions`] with
+//! [`set_permissions()`](PluginRegistry::set_permissions) may only register
+//! the capabilities they declared and were granted.
+//!
+//! Calls into plugins are timed and guarded against panics, and their
+//! outcomes recorded in a [`HealthTracker`]. A [`HealthPolicy`] set with
+//! [`set_health_policy()`](PluginRegistry::set_health_policy) lets
+//! [`check_health()`](PluginRegistry::check_health) disable or restart
+//! misbehaving plugins.
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 use tracing::{info, warn};
 
-use crate::{Plugin, PluginContext, HookRegistry};
+use crate::channel::ChannelFactory;
+use crate::health::{catch_panic, CallOutcome, HealthAction, HealthPolicy, HealthTracker, PluginState};
+use crate::permissions::PluginPermissions;
+use crate::{Plugin, PluginApi, PluginContext, HookRegistry};
 
 /// Registry error types for plugin lifecycle operations.
 ///
@@ -123,6 +137,12 @@ pub struct PluginRegistry {
     load_order: Vec<String>,
     /// Registry for hook handlers.
     hook_registry: HookRegistry,
+    /// Capability permissions of restricted plugins, by plugin ID.
+    permissions: HashMap<String, PluginPermissions>,
+    /// Channel factories by channel type, with the providing plugin's ID.
+    channel_factories: HashMap<String, (String, Arc<dyn ChannelFactory>)>,
+    /// Policy applied by `check_health()`.
+    health_policy: HealthPolicy,
 }
 
 impl PluginRegistry {
@@ -134,9 +154,54 @@ impl PluginRegistry {
             plugins: HashMap::new(),
             load_order: Vec::new(),
             hook_registry: HookRegistry::new(),
+            permissions: HashMap::new(),
+            channel_factories: HashMap::new(),
+            health_policy: HealthPolicy::default(),
         }
     }
 
+    /// Restricts what the plugin with the given ID may register.
+    ///
+    /// Applies to registrations made after the call, including those of
+    /// replacement instances during hot reload. Plugins without
+    /// permissions are unrestricted.
+    pub fn set_permissions(
+        &mut self,
+        plugin_id: impl Into<String>,
+        permissions: PluginPermissions,
+    ) {
+        self.permissions.insert(plugin_id.into(), permissions);
+    }
+
+    /// Returns the factory for a channel type, with the ID of the plugin
+    /// providing it.
+    pub fn channel_factory(&self, channel_type: &str) -> Option<(&str, Arc<dyn ChannelFactory>)> {
+        self.channel_factories
+            .get(channel_type)
+            .map(|(plugin_id, factory)| (plugin_id.as_str(), factory.clone()))
+    }
+
+    /// Returns the channel types provided by plugins.
+    pub fn channel_types(&self) -> Vec<&str> {
+        self.channel_factories.keys().map(String::as_str).collect()
+    }
+
+    /// Returns the permissions of the plugin with the given ID, if restricted.
+    pub fn permissions(&self, plugin_id: &str) -> Option<&PluginPermissions> {
+        self.permissions.get(plugin_id)
+    }
+
+    /// Returns the tracker recording the health of calls into plugins.
+    pub fn health(&self) -> &HealthTracker {
+        self.hook_registry.health()
+    }
+
+    /// Sets the policy [`check_health()`](PluginRegistry::check_health)
+    /// applies.
+    pub fn set_health_policy(&mut self, policy: HealthPolicy) {
+        self.health_policy = policy;
+    }
+
     /// Returns a reference to the [`HookRegistry`].
     pub fn hook_registry(&self) -> &HookRegistry {
         &self.hook_registry
@@ -188,6 +253,7 @@ impl PluginRegistry {
     /// This method first calls `register()` to register the plugin, then
     /// runs the plugin's `register()` method with a `PluginApi`, and finally
     /// transfers all hook registrations from the API to the internal `HookRegistry`.
+    /// Registrations the plugin's permissions do not allow are dropped.
     ///
     /// # Arguments
     ///
@@ -199,16 +265,252 @@ impl PluginRegistry {
     /// ID is already registered.
     pub async fn register_with_hooks(&mut self, plugin: Arc<dyn Plugin>) -> Result<(), RegistryError> {
         self.register(plugin.clone())?;
-        
-        // Create a PluginApi for this plugin to register hooks
-        let mut api = crate::PluginApi::new();
-        
-        // Call the plugin's register method
+        self.register_capabilities(&plugin);
+        Ok(())
+    }
+
+    /// Runs a plugin's `register()` against its permissions and transfers
+    /// its hooks to the hook registry.
+    fn register_capabilities(&mut self, plugin: &Arc<dyn Plugin>) {
+        let id = plugin.id();
+        let mut api = match self.permissions.get(id) {
+            Some(permissions) => PluginApi::with_permissions(permissions.clone()),
+            None => PluginApi::new(),
+        };
         plugin.register(&mut api).ok();
-        
-        // Transfer hook registrations to the hook registry
+        for denied in api.denied() {
+            warn!(plugin_id = %id, error = %denied, "Denied plugin capability");
+        }
         self.hook_registry.transfer_from_api(&api);
-        
+        for factory in api.channel_factories() {
+            let channel_type = factory.channel_type().to_string();
+            if let Some((owner, _)) = self.channel_factories.get(&channel_type) {
+                if owner != id {
+                    warn!(plugin_id = %id, channel_type = %channel_type, owner = %owner, "Channel type already provided by another plugin");
+                    continue;
+                }
+            }
+            self.channel_factories
+                .insert(channel_type, (id.to_string(), factory.clone()));
+        }
+    }
+
+    /// Replaces a registered plugin with a new instance of it.
+    ///
+    /// This is the hot reload sequence: the old instance's state is
+    /// collected with [`Plugin::export_state()`], the old instance is shut
+    /// down and its hooks and channel factories removed, and the replacement is registered,
+    /// initialized with `ctx`, and handed the state through
+    /// [`Plugin::import_state()`]. The replacement keeps the old instance's
+    /// position in the initialization and shutdown order.
+    ///
+    /// If the replacement fails to initialize, the old instance is
+    /// re-initialized and restored before the error is returned.
+    ///
+    /// Channels already created by the old instance's factories keep
+    /// running; restart them to pick up the replacement's code.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin` - The new plugin instance, with the same ID as the old one
+    /// * `ctx` - The [`PluginContext`] used to initialize plugins
+    ///
+    /// # Returns
+    ///
+    /// The replaced plugin instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RegistryError::NotFound` if no plugin with the same ID is
+    /// registered, and `RegistryError::InitFailed` if the replacement fails
+    /// to initialize.
+    pub async fn replace(
+        &mut self,
+        plugin: Arc<dyn Plugin>,
+        ctx: &PluginContext,
+    ) -> Result<Arc<dyn Plugin>, RegistryError> {
+        let id = plugin.id().to_string();
+        let old = self
+            .plugins
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| RegistryError::NotFound(id.clone()))?;
+
+        info!(plugin_id = %id, "Replacing plugin");
+        let state = old.export_state().await;
+        self.deactivate(&id, &old).await;
+
+        match self.activate(&plugin, ctx, state.clone()).await {
+            Ok(()) => {
+                self.plugins.insert(id, plugin);
+                Ok(old)
+            }
+            Err(e) => {
+                warn!(plugin_id = %id, error = %e, "Replacement failed, restoring previous plugin");
+                self.remove_capabilities(&id);
+                if let Err(restore_error) = self.activate(&old, ctx, state).await {
+                    warn!(plugin_id = %id, error = %restore_error, "Failed to restore previous plugin");
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Applies the health policy to every plugin.
+    ///
+    /// Plugins violating the policy are marked unhealthy, disabled, or
+    /// restarted with `ctx`, depending on the policy's action. A plugin
+    /// that fails to restart, or has been restarted `max_restarts` times,
+    /// is disabled instead. Plugins back within the policy are marked
+    /// healthy again.
+    ///
+    /// # Returns
+    ///
+    /// The unhealthy plugins' IDs with the action taken for each.
+    pub async fn check_health(&mut self, ctx: &PluginContext) -> Vec<(String, HealthAction)> {
+        let mut actions = Vec::new();
+        for id in self.load_order.clone() {
+            let Some(health) = self.health().get(&id) else {
+                continue;
+            };
+            if health.state == PluginState::Disabled {
+                continue;
+            }
+            if !self.health_policy.is_unhealthy(&health) {
+                if health.state == PluginState::Unhealthy {
+                    self.health().set_state(&id, PluginState::Healthy);
+                }
+                continue;
+            }
+
+            let mut action = self.health_policy.action;
+            if action == HealthAction::Restart && health.restarts >= self.health_policy.max_restarts
+            {
+                action = HealthAction::Disable;
+            }
+            warn!(plugin_id = %id, ?action, error_rate = health.error_rate(), panics = health.panics, "Plugin is unhealthy");
+            match action {
+                HealthAction::Report => self.health().set_state(&id, PluginState::Unhealthy),
+                HealthAction::Disable => {
+                    self.disable(&id).await.ok();
+                }
+                HealthAction::Restart => {
+                    if let Err(e) = self.restart(&id, ctx).await {
+                        warn!(plugin_id = %id, error = %e, "Plugin restart failed, disabling it");
+                        self.disable(&id).await.ok();
+                        action = HealthAction::Disable;
+                    }
+                }
+            }
+            actions.push((id, action));
+        }
+        actions
+    }
+
+    /// Disables a plugin: shuts it down and removes its hooks and channel
+    /// factories. The plugin stays registered, marked as disabled, and is
+    /// not shut down again by [`shutdown_all()`](PluginRegistry::shutdown_all).
+    ///
+    /// # Errors
+    ///
+    /// Returns `RegistryError::NotFound` if no plugin has the ID.
+    pub async fn disable(&mut self, id: &str) -> Result<(), RegistryError> {
+        let plugin = self
+            .plugins
+            .get(id)
+            .cloned()
+            .ok_or_else(|| RegistryError::NotFound(id.to_string()))?;
+        if self.health().is_disabled(id) {
+            return Ok(());
+        }
+        info!(plugin_id = %id, "Disabling plugin");
+        self.deactivate(id, &plugin).await;
+        self.health().set_state(id, PluginState::Disabled);
+        Ok(())
+    }
+
+    /// Restarts a plugin: shuts it down, then registers and initializes it
+    /// again with `ctx`, handing over its exported state. The plugin's
+    /// health statistics are cleared and the restart counted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RegistryError::NotFound` if no plugin has the ID, and
+    /// `RegistryError::InitFailed` if it fails to initialize again.
+    pub async fn restart(&mut self, id: &str, ctx: &PluginContext) -> Result<(), RegistryError> {
+        let plugin = self
+            .plugins
+            .get(id)
+            .cloned()
+            .ok_or_else(|| RegistryError::NotFound(id.to_string()))?;
+        info!(plugin_id = %id, "Restarting plugin");
+        let state = plugin.export_state().await;
+        self.deactivate(id, &plugin).await;
+        self.health().restarted(id);
+        self.activate(&plugin, ctx, state).await
+    }
+
+    /// Shuts a plugin down and removes its hooks and channel factories.
+    async fn deactivate(&mut self, id: &str, plugin: &Arc<dyn Plugin>) {
+        self.shutdown_plugin(id, plugin).await;
+        self.remove_capabilities(id);
+    }
+
+    /// Removes a plugin's hooks and channel factories.
+    fn remove_capabilities(&mut self, id: &str) {
+        self.hook_registry.remove_plugin(id);
+        self.channel_factories.retain(|_, (owner, _)| owner != id);
+    }
+
+    /// Initializes a plugin, recording the outcome in the health tracker.
+    async fn init_plugin(
+        &self,
+        id: &str,
+        plugin: &Arc<dyn Plugin>,
+        ctx: &PluginContext,
+    ) -> Result<(), RegistryError> {
+        let start = Instant::now();
+        let result = match catch_panic(plugin.init(ctx)).await {
+            Ok(result) => result.map_err(|e| e.to_string()),
+            Err(message) => {
+                self.health()
+                    .record(id, start.elapsed(), CallOutcome::Panic(message.clone()));
+                return Err(RegistryError::InitFailed(id.to_string(), message));
+            }
+        };
+        let outcome = match &result {
+            Ok(()) => CallOutcome::Ok,
+            Err(e) => CallOutcome::Error(e.clone()),
+        };
+        self.health().record(id, start.elapsed(), outcome);
+        result.map_err(|e| RegistryError::InitFailed(id.to_string(), e))
+    }
+
+    /// Shuts a plugin down, logging failures and panics.
+    async fn shutdown_plugin(&self, id: &str, plugin: &Arc<dyn Plugin>) {
+        match catch_panic(plugin.shutdown()).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => warn!(plugin_id = %id, error = %e, "Plugin shutdown failed"),
+            Err(message) => warn!(plugin_id = %id, error = %message, "Plugin shutdown panicked"),
+        }
+    }
+
+    /// Registers a plugin's hooks, initializes it, and imports handed-over state.
+    async fn activate(
+        &mut self,
+        plugin: &Arc<dyn Plugin>,
+        ctx: &PluginContext,
+        state: Option<serde_json::Value>,
+    ) -> Result<(), RegistryError> {
+        let id = plugin.id().to_string();
+        self.register_capabilities(plugin);
+
+        self.init_plugin(&id, plugin, ctx).await?;
+        if let Some(state) = state {
+            if let Err(e) = plugin.import_state(state).await {
+                warn!(plugin_id = %id, error = %e, "Plugin failed to import state");
+            }
+        }
         Ok(())
     }
 
@@ -266,8 +568,8 @@ impl PluginRegistry {
     /// Initializes all registered plugins in registration order.
     ///
     /// Plugins are initialized sequentially in the order they were registered.
-    /// If any plugin fails to initialize, the function returns an error and
-    /// does not attempt to initialize subsequent plugins.
+    /// If any plugin fails to initialize or panics, the function returns an
+    /// error and does not attempt to initialize subsequent plugins.
     ///
     /// # Arguments
     ///
@@ -276,7 +578,8 @@ impl PluginRegistry {
     /// # Errors
     ///
     /// Returns `RegistryError::InitFailed` if any plugin's `init()` method
-    /// returns an error. The error includes the plugin ID and the error message.
+    /// returns an error or panics. The error includes the plugin ID and the
+    /// error message.
     ///
     /// # Example
     ///
@@ -298,9 +601,7 @@ impl PluginRegistry {
         for id in &self.load_order {
             if let Some(plugin) = self.plugins.get(id) {
                 info!(plugin_id = %id, "Initializing plugin");
-                plugin.init(ctx).await.map_err(|e| {
-                    RegistryError::InitFailed(id.clone(), e.to_string())
-                })?;
+                self.init_plugin(id, plugin, ctx).await?;
             }
         }
         Ok(())
@@ -312,8 +613,9 @@ impl PluginRegistry {
     /// This ensures that plugins that depend on other plugins are shut down
     /// first, allowing for proper cleanup ordering.
     ///
-    /// If a plugin fails to shut down, an error is logged but the shutdown
-    /// continues for other plugins.
+    /// If a plugin fails to shut down or panics, an error is logged but the
+    /// shutdown continues for other plugins. Disabled plugins, which are
+    /// already shut down, are skipped.
     ///
     /// # Returns
     ///
@@ -333,11 +635,12 @@ impl PluginRegistry {
     /// ```
     pub async fn shutdown_all(&self) -> Result<(), RegistryError> {
         for id in self.load_order.iter().rev() {
+            if self.health().is_disabled(id) {
+                continue;
+            }
             if let Some(plugin) = self.plugins.get(id) {
                 info!(plugin_id = %id, "Shutting down plugin");
-                if let Err(e) = plugin.shutdown().await {
-                    warn!(plugin_id = %id, error = %e, "Plugin shutdown failed");
-                }
+                self.shutdown_plugin(id, plugin).await;
             }
         }
         Ok(())
@@ -518,4 +821,207 @@ mod tests {
         assert_eq!(plugins[1].id(), "alpha");
         assert_eq!(plugins[2].id(), "beta");
     }
+
+    /// A plugin counting its initializations and handing the count over.
+    #[derive(Debug)]
+    struct StatefulPlugin {
+        meta: PluginMeta,
+        fail_init: bool,
+        count: std::sync::Mutex<u64>,
+        shut_down: std::sync::atomic::AtomicBool,
+    }
+
+    impl StatefulPlugin {
+        fn new(fail_init: bool) -> Self {
+            Self {
+                meta: PluginMeta::new("stateful", "1.0.0", "Stateful", "Test", vec![], vec![]),
+                fail_init,
+                count: std::sync::Mutex::new(0),
+                shut_down: std::sync::atomic::AtomicBool::new(false),
+            }
+        }
+
+        fn count(&self) -> u64 {
+            *self.count.lock().unwrap()
+        }
+    }
+
+    #[async_trait]
+    impl Plugin for StatefulPlugin {
+        fn id(&self) -> &str {
+            "stateful"
+        }
+
+        fn meta(&self) -> &PluginMeta {
+            &self.meta
+        }
+
+        fn register(&self, _api: &mut crate::PluginApi) -> Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+
+        async fn init(&self, _ctx: &PluginContext) -> Result<(), Box<dyn std::error::Error>> {
+            if self.fail_init {
+                return Err("broken build".into());
+            }
+            *self.count.lock().unwrap() += 1;
+            Ok(())
+        }
+
+        async fn shutdown(&self) -> Result<(), Box<dyn std::error::Error>> {
+            self.shut_down
+                .store(true, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn export_state(&self) -> Option<serde_json::Value> {
+            Some(serde_json::json!({ "count": self.count() }))
+        }
+
+        async fn import_state(
+            &self,
+            state: serde_json::Value,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            *self.count.lock().unwrap() += state["count"].as_u64().unwrap_or(0);
+            Ok(())
+        }
+    }
+
+    fn test_context() -> PluginContext {
+        PluginContext::new(Arc::new(serde_json::json!({})), std::path::PathBuf::new())
+    }
+
+    #[tokio::test]
+    async fn test_replace_hands_over_state() {
+        let ctx = test_context();
+        let mut registry = PluginRegistry::new();
+        let old = Arc::new(StatefulPlugin::new(false));
+        registry
+            .register(Arc::new(TestPlugin::new("first")))
+            .unwrap();
+        registry.register(old.clone()).unwrap();
+        registry.init_all(&ctx).await.unwrap();
+
+        let new = Arc::new(StatefulPlugin::new(false));
+        let replaced = registry.replace(new.clone(), &ctx).await.unwrap();
+        assert_eq!(replaced.id(), "stateful");
+        assert!(old.shut_down.load(std::sync::atomic::Ordering::SeqCst));
+        // One initialization of its own plus the one handed over
+        assert_eq!(new.count(), 2);
+
+        let plugins = registry.list();
+        assert_eq!(plugins[0].id(), "first");
+        assert_eq!(plugins[1].id(), "stateful");
+        assert_eq!(plugins.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_replace_restores_old_plugin_on_failure() {
+        let ctx = test_context();
+        let mut registry = PluginRegistry::new();
+        let old = Arc::new(StatefulPlugin::new(false));
+        registry.register(old.clone()).unwrap();
+        registry.init_all(&ctx).await.unwrap();
+
+        let result = registry
+            .replace(Arc::new(StatefulPlugin::new(true)), &ctx)
+            .await;
+        assert!(matches!(result, Err(RegistryError::InitFailed(id, _)) if id == "stateful"));
+
+        // The old instance is back, with its state restored
+        let current = registry.get("stateful").unwrap();
+        assert!(Arc::ptr_eq(current, &(old.clone() as Arc<dyn Plugin>)));
+        assert_eq!(old.count(), 3);
+
+        let result = registry
+            .replace(Arc::new(TestPlugin::new("missing")), &ctx)
+            .await;
+        assert!(matches!(result, Err(RegistryError::NotFound(_))));
+    }
+
+    struct NoopHandler;
+
+    #[async_trait]
+    impl crate::HookHandler for NoopHandler {
+        async fn handle(
+            &self,
+            _ctx: &crate::HookContext,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct HookPlugin {
+        meta: PluginMeta,
+    }
+
+    #[async_trait]
+    impl Plugin for HookPlugin {
+        fn id(&self) -> &str {
+            "hooks"
+        }
+
+        fn meta(&self) -> &PluginMeta {
+            &self.meta
+        }
+
+        fn register(&self, api: &mut crate::PluginApi) -> Result<(), Box<dyn std::error::Error>> {
+            api.register_hook(
+                crate::Hook::BeforeAgentRun,
+                "hooks".to_string(),
+                Arc::new(NoopHandler),
+            );
+            api.register_hook(
+                crate::Hook::AfterAgentRun,
+                "hooks".to_string(),
+                Arc::new(NoopHandler),
+            );
+            Ok(())
+        }
+
+        async fn init(&self, _ctx: &PluginContext) -> Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+
+        async fn shutdown(&self) -> Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_permissions_deny_undeclared_hooks() {
+        use crate::permissions::Capability;
+
+        let plugin = || {
+            Arc::new(HookPlugin {
+                meta: PluginMeta::new("hooks", "1.0.0", "Hooks", "Test Author", vec![], vec![]),
+            })
+        };
+        let declared = crate::PluginCapabilities {
+            hooks: Some(vec!["BeforeAgentRun".to_string()]),
+            ..Default::default()
+        };
+
+        let mut registry = PluginRegistry::new();
+        registry.set_permissions(
+            "hooks",
+            PluginPermissions::new(declared.clone(), [Capability::Hooks]),
+        );
+        registry.register_with_hooks(plugin()).await.unwrap();
+        let hooks = registry.hook_registry();
+        assert_eq!(hooks.handler_count(&crate::Hook::BeforeAgentRun), 1);
+        assert_eq!(hooks.handler_count(&crate::Hook::AfterAgentRun), 0);
+
+        // Declared but not granted
+        let mut registry = PluginRegistry::new();
+        registry.set_permissions("hooks", PluginPermissions::new(declared, []));
+        registry.register_with_hooks(plugin()).await.unwrap();
+        assert_eq!(registry.hook_registry().total_hook_count(), 0);
+
+        // Unrestricted without permissions
+        let mut registry = PluginRegistry::new();
+        registry.register_with_hooks(plugin()).await.unwrap();
+        assert_eq!(registry.hook_registry().total_hook_count(), 2);
+    }
 }
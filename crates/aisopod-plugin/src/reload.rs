@@ -0,0 +1,365 @@
+//! Hot reload of dynamic plugins.
+//!
+//! This module lets plugin developers rebuild a dynamic plugin without
+//! restarting the process. [`PluginReloader`] loads the current build of a
+//! plugin's shared library and swaps it in through
+//! [`PluginRegistry::replace()`], which shuts the old instance down and hands
+//! its exported state to the new one. [`PluginReloader::watch()`] does this
+//! automatically whenever a plugin's library changes on disk.
+//!
+//! Platform loaders return the already loaded library when asked to load
+//! the same path twice, so each build is copied to a unique path in a
+//! shadow directory before it is loaded. Old builds stay mapped for the
+//! lifetime of the process.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use aisopod_plugin::dynamic::DynamicPluginLoader;
+//! use aisopod_plugin::reload::PluginReloader;
+//! use std::path::PathBuf;
+//! use std::sync::Arc;
+//!
+//! let loader = DynamicPluginLoader::new(vec![PathBuf::from("~/.aisopod/plugins")]);
+//! let reloader = Arc::new(PluginReloader::new(loader, registry.clone(), ctx.clone()));
+//!
+//! // Keep the watcher alive for as long as reloads should happen
+//! let _watcher = unsafe { reloader.watch()? };
+//! ```
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use thiserror::Error;
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::dynamic::{DiscoveredPlugin, DynamicPluginLoader, LoadError};
+use crate::registry::{PluginRegistry, RegistryError};
+use crate::PluginContext;
+
+/// Time to wait for a build to finish writing before reloading.
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(500);
+
+/// Error types for plugin hot reload.
+#[derive(Debug, Error)]
+pub enum ReloadError {
+    /// Loading the new build failed.
+    #[error(transparent)]
+    Load(#[from] LoadError),
+
+    /// Swapping the new build into the registry failed.
+    #[error(transparent)]
+    Registry(#[from] RegistryError),
+
+    /// Copying the library to the shadow directory failed.
+    #[error("Failed to copy plugin library {0}: {1}")]
+    Copy(PathBuf, String),
+
+    /// Watching the plugin directories failed.
+    #[error("Failed to watch plugin directories: {0}")]
+    Watch(String),
+}
+
+/// Reloads dynamic plugins into a running [`PluginRegistry`].
+pub struct PluginReloader {
+    loader: DynamicPluginLoader,
+    registry: Arc<RwLock<PluginRegistry>>,
+    ctx: Arc<PluginContext>,
+    shadow_dir: PathBuf,
+    generation: AtomicU64,
+}
+
+impl PluginReloader {
+    /// Creates a reloader for plugins found by `loader`.
+    ///
+    /// Reloaded plugins are initialized with `ctx`. Library copies are kept
+    /// in a per-process directory under the system temporary directory.
+    pub fn new(
+        loader: DynamicPluginLoader,
+        registry: Arc<RwLock<PluginRegistry>>,
+        ctx: Arc<PluginContext>,
+    ) -> Self {
+        Self {
+            loader,
+            registry,
+            ctx,
+            shadow_dir: std::env::temp_dir()
+                .join(format!("aisopod-plugins-{}", std::process::id())),
+            generation: AtomicU64::new(1),
+        }
+    }
+
+    /// Keeps library copies in the given directory.
+    pub fn with_shadow_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.shadow_dir = dir.into();
+        self
+    }
+
+    /// Returns the loader used to discover and load plugins.
+    pub fn loader(&self) -> &DynamicPluginLoader {
+        &self.loader
+    }
+
+    /// Loads the current build of a plugin and replaces the running instance.
+    ///
+    /// # Safety
+    ///
+    /// This loads and executes the plugin's shared library, with the same
+    /// requirements as [`DynamicPluginLoader::load_plugin()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReloadError::Registry` with `RegistryError::NotFound` if the
+    /// plugin is not registered, and `RegistryError::InitFailed` if the new
+    /// build fails to initialize, in which case the old instance stays active.
+    pub async unsafe fn reload(&self, discovered: &DiscoveredPlugin) -> Result<(), ReloadError> {
+        let lib_path = self.loader.library_path(discovered);
+        let shadow_path = self.shadow_copy(&lib_path)?;
+        let plugin = self.loader.load_library(discovered, &shadow_path)?;
+
+        let id = plugin.id().to_string();
+        self.registry
+            .write()
+            .await
+            .replace(plugin, &self.ctx)
+            .await?;
+        info!(plugin_id = %id, "Plugin reloaded");
+        Ok(())
+    }
+
+    /// Watches the plugin directories and reloads plugins whose library changes.
+    ///
+    /// Reloads stop when the returned [`PluginWatcher`] is dropped. Failed
+    /// reloads are logged and leave the running instance in place.
+    ///
+    /// # Safety
+    ///
+    /// Every library written to a plugin directory is loaded and executed,
+    /// so the directories must only be writable by trusted users.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReloadError::Watch` if the directories cannot be watched.
+    pub unsafe fn watch(self: Arc<Self>) -> Result<PluginWatcher, ReloadError> {
+        let (tx, mut rx) = mpsc::unbounded_channel::<PathBuf>();
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+                Ok(event) if is_library_change(&event.kind) => {
+                    for path in event.paths {
+                        let _ = tx.send(path);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Plugin watch error: {}", e),
+            })
+            .map_err(|e| ReloadError::Watch(e.to_string()))?;
+
+        for dir in self.loader.plugin_dirs() {
+            if dir.exists() {
+                watcher
+                    .watch(dir, RecursiveMode::Recursive)
+                    .map_err(|e| ReloadError::Watch(e.to_string()))?;
+            }
+        }
+
+        let task = tokio::spawn(async move {
+            while let Some(path) = rx.recv().await {
+                let mut changed = HashSet::from([path]);
+                loop {
+                    match tokio::time::timeout(DEBOUNCE_DELAY, rx.recv()).await {
+                        Ok(Some(path)) => {
+                            changed.insert(path);
+                        }
+                        Ok(None) => return,
+                        Err(_) => break,
+                    }
+                }
+                // SAFETY: the caller of `watch` vouched for the plugin directories
+                unsafe { self.reload_changed(&changed).await };
+            }
+        });
+
+        Ok(PluginWatcher {
+            _watcher: watcher,
+            task,
+        })
+    }
+
+    /// Reloads every plugin whose library is among the changed paths.
+    async unsafe fn reload_changed(&self, changed: &HashSet<PathBuf>) {
+        let discovered = match self.loader.discover() {
+            Ok(discovered) => discovered,
+            Err(e) => {
+                warn!(error = %e, "Plugin discovery failed during reload");
+                return;
+            }
+        };
+        for plugin in plugins_to_reload(&self.loader, discovered, changed) {
+            debug!(plugin_id = %plugin.manifest.plugin.id, "Plugin library changed");
+            if let Err(e) = self.reload(&plugin).await {
+                warn!(plugin_id = %plugin.manifest.plugin.id, error = %e, "Plugin reload failed");
+            }
+        }
+    }
+
+    /// Copies a library to a fresh path in the shadow directory.
+    fn shadow_copy(&self, lib_path: &Path) -> Result<PathBuf, ReloadError> {
+        let copy_error =
+            |e: std::io::Error| ReloadError::Copy(lib_path.to_path_buf(), e.to_string());
+        std::fs::create_dir_all(&self.shadow_dir).map_err(copy_error)?;
+        let file_name = lib_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst);
+        let shadow_path = self
+            .shadow_dir
+            .join(format!("{}-{}", generation, file_name));
+        std::fs::copy(lib_path, &shadow_path).map_err(copy_error)?;
+        Ok(shadow_path)
+    }
+}
+
+impl std::fmt::Debug for PluginReloader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PluginReloader")
+            .field("loader", &self.loader)
+            .field("shadow_dir", &self.shadow_dir)
+            .finish()
+    }
+}
+
+/// Handle keeping a plugin directory watch alive.
+///
+/// Dropping the handle stops watching and cancels pending reloads.
+pub struct PluginWatcher {
+    _watcher: RecommendedWatcher,
+    task: JoinHandle<()>,
+}
+
+impl Drop for PluginWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Returns whether a file event may have produced a new library build.
+fn is_library_change(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Create(_)
+            | EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Name(_) | ModifyKind::Any)
+            | EventKind::Any
+    )
+}
+
+/// Selects the discovered plugins whose library is among the changed paths.
+fn plugins_to_reload(
+    loader: &DynamicPluginLoader,
+    discovered: Vec<DiscoveredPlugin>,
+    changed: &HashSet<PathBuf>,
+) -> Vec<DiscoveredPlugin> {
+    discovered
+        .into_iter()
+        .filter(|plugin| changed.contains(&loader.library_path(plugin)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_manifest(dir: &Path, id: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(
+            dir.join("aisopod.plugin.toml"),
+            format!(
+                r#"
+                [plugin]
+                id = "{id}"
+                name = "{id}"
+                version = "0.1.0"
+                description = "Test plugin"
+                author = "Test"
+                entry_point = "{id}"
+                "#
+            ),
+        )
+        .unwrap();
+    }
+
+    fn reloader(plugin_dir: &Path, shadow_dir: &Path) -> PluginReloader {
+        PluginReloader::new(
+            DynamicPluginLoader::new(vec![plugin_dir.to_path_buf()]),
+            Arc::new(RwLock::new(PluginRegistry::new())),
+            Arc::new(PluginContext::new(
+                Arc::new(serde_json::json!({})),
+                PathBuf::new(),
+            )),
+        )
+        .with_shadow_dir(shadow_dir)
+    }
+
+    #[test]
+    fn test_plugins_to_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        write_manifest(&dir.path().join("alpha"), "alpha");
+        write_manifest(&dir.path().join("beta"), "beta");
+        let reloader = reloader(dir.path(), &dir.path().join("shadow"));
+        let loader = reloader.loader();
+
+        let discovered = loader.discover().unwrap();
+        let beta = discovered
+            .iter()
+            .find(|plugin| plugin.manifest.plugin.id == "beta")
+            .unwrap();
+        let changed = HashSet::from([
+            loader.library_path(beta),
+            dir.path().join("alpha/aisopod.plugin.toml"),
+        ]);
+
+        let selected = plugins_to_reload(loader, discovered, &changed);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].manifest.plugin.id, "beta");
+    }
+
+    #[test]
+    fn test_shadow_copies_are_unique() {
+        let dir = tempfile::tempdir().unwrap();
+        let lib = dir.path().join("libdemo.so");
+        std::fs::write(&lib, b"build").unwrap();
+        let reloader = reloader(dir.path(), &dir.path().join("shadow"));
+
+        let first = reloader.shadow_copy(&lib).unwrap();
+        let second = reloader.shadow_copy(&lib).unwrap();
+        assert_ne!(first, second);
+        assert!(first.starts_with(dir.path().join("shadow")));
+        assert_eq!(std::fs::read(&second).unwrap(), b"build");
+
+        let missing = reloader.shadow_copy(&dir.path().join("libmissing.so"));
+        assert!(matches!(missing, Err(ReloadError::Copy(..))));
+    }
+
+    #[test]
+    fn test_is_library_change() {
+        assert!(is_library_change(&EventKind::Create(
+            notify::event::CreateKind::File
+        )));
+        assert!(is_library_change(&EventKind::Modify(ModifyKind::Data(
+            notify::event::DataChange::Content
+        ))));
+        assert!(!is_library_change(&EventKind::Access(
+            notify::event::AccessKind::Any
+        )));
+        assert!(!is_library_change(&EventKind::Remove(
+            notify::event::RemoveKind::File
+        )));
+    }
+}
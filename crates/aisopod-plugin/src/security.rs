@@ -91,6 +91,14 @@ pub enum SecurityError {
     /// by the CLI system.
     #[error("Authorization required for command: '{0}'")]
     AuthorizationRequired(String),
+
+    /// Capability permission denied.
+    ///
+    /// The plugin registering the command was not granted the
+    /// `commands` capability or did not declare the command in its
+    /// manifest.
+    #[error("Permission denied: {0}")]
+    PermissionDenied(#[from] crate::permissions::PermissionError),
 }
 
 /// Validates a command name against security rules.
@@ -0,0 +1,358 @@
+//! Ed25519 signatures for plugin manifests and binaries.
+//!
+//! A signed plugin carries an `aisopod.plugin.sig` file next to its
+//! manifest. The file lists the SHA-256 hashes of the manifest and of the
+//! plugin's entry point binaries, and an Ed25519 signature over that list
+//! made with the publisher's key:
+//!
+//! ```toml
+//! public_key = "3Lv2k1...="
+//! signature = "q8Rk0w...=="
+//!
+//! [files]
+//! "aisopod.plugin.toml" = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+//! "libweather.so" = "60303ae22b998861bce3b28f33eec1be758a213c86c93c076dbe9f558c11c752"
+//! ```
+//!
+//! Keys are 32 bytes encoded as standard base64, for both the public keys
+//! operators trust and the secret keys publishers sign with.
+//!
+//! # Verification
+//!
+//! A [`SignaturePolicy`] decides what the loaders accept:
+//!
+//! - A plugin with a signature file must match it: a manifest or binary
+//!   changed after signing is always refused.
+//! - With [`SignaturePolicy::require_signatures()`], unsigned plugins and
+//!   plugins signed by a key outside the trusted keys are refused too.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use aisopod_plugin::signing::{sign_plugin, parse_signing_key, SignaturePolicy};
+//!
+//! // Publisher
+//! let key = parse_signing_key(&std::fs::read_to_string("publisher.key")?)?;
+//! sign_plugin(Path::new("weather-plugin"), &key)?;
+//!
+//! // Operator
+//! let policy = SignaturePolicy::new(["3Lv2k1...="])?.require_signatures(true);
+//! let loader = DynamicPluginLoader::new(dirs).with_signature_policy(policy);
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use ed25519_dalek::{Signature, Signer, Verifier};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tracing::warn;
+
+use crate::dynamic::library_filename;
+use crate::manifest::PluginManifest;
+
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+
+/// Signature file name expected next to the plugin manifest.
+pub const SIGNATURE_FILE: &str = "aisopod.plugin.sig";
+
+/// Manifest file name, always covered by the signature.
+const MANIFEST_FILE: &str = "aisopod.plugin.toml";
+
+/// First line of the signed payload, versioning its format.
+const PAYLOAD_HEADER: &str = "aisopod-plugin-signature-v1";
+
+/// Error types for plugin signing and signature verification.
+#[derive(Debug, Error)]
+pub enum SignatureError {
+    /// The plugin has no signature file but signatures are required.
+    #[error("Plugin is not signed")]
+    Unsigned,
+
+    /// The signature file could not be parsed.
+    #[error("Malformed signature file: {0}")]
+    Malformed(String),
+
+    /// A key could not be decoded.
+    #[error("Invalid key '{0}': {1}")]
+    InvalidKey(String, String),
+
+    /// The plugin was signed by a key that is not trusted.
+    #[error("Plugin is signed by untrusted key {0}")]
+    UntrustedKey(String),
+
+    /// The signature does not match the listed file hashes.
+    #[error("Signature verification failed")]
+    InvalidSignature,
+
+    /// A file the loader relies on is not covered by the signature.
+    #[error("File '{0}' is not covered by the signature")]
+    NotCovered(String),
+
+    /// A signed file was changed after signing.
+    #[error("File '{0}' does not match its signed hash")]
+    HashMismatch(String),
+
+    /// The plugin has no entry point binary to sign.
+    #[error("No entry point binary found to sign")]
+    MissingBinary,
+
+    /// File I/O error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Contents of an `aisopod.plugin.sig` file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginSignature {
+    /// Base64 Ed25519 public key of the signer.
+    pub public_key: String,
+    /// Base64 Ed25519 signature over the file hashes.
+    pub signature: String,
+    /// Hex SHA-256 hashes of the signed files, keyed by file name.
+    pub files: BTreeMap<String, String>,
+}
+
+impl PluginSignature {
+    /// Reads the signature file of the plugin in `dir`, if there is one.
+    pub fn from_dir(dir: &Path) -> Result<Option<Self>, SignatureError> {
+        let path = dir.join(SIGNATURE_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)?;
+        toml::from_str(&content)
+            .map(Some)
+            .map_err(|e| SignatureError::Malformed(e.to_string()))
+    }
+
+    /// Returns the bytes covered by the signature.
+    fn payload(files: &BTreeMap<String, String>) -> String {
+        let mut payload = format!("{}\n", PAYLOAD_HEADER);
+        for (file, hash) in files {
+            payload.push_str(&format!("{}  {}\n", hash, file));
+        }
+        payload
+    }
+}
+
+/// Which plugin signatures the loaders accept.
+///
+/// The default policy accepts unsigned plugins and only refuses signed
+/// plugins whose files do not match their signature.
+#[derive(Debug, Clone, Default)]
+pub struct SignaturePolicy {
+    trusted_keys: Vec<VerifyingKey>,
+    require_signatures: bool,
+}
+
+impl SignaturePolicy {
+    /// Creates a policy trusting the given base64 public keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SignatureError::InvalidKey` for a key that cannot be decoded.
+    pub fn new<I, K>(trusted_keys: I) -> Result<Self, SignatureError>
+    where
+        I: IntoIterator<Item = K>,
+        K: AsRef<str>,
+    {
+        let trusted_keys = trusted_keys
+            .into_iter()
+            .map(|key| parse_public_key(key.as_ref()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            trusted_keys,
+            require_signatures: false,
+        })
+    }
+
+    /// Refuses plugins that are not signed by a trusted key.
+    pub fn require_signatures(mut self, require: bool) -> Self {
+        self.require_signatures = require;
+        self
+    }
+
+    /// Verifies the plugin in `dir` before `binary_path` is loaded.
+    ///
+    /// `binary_name` is the name of the entry point binary within the
+    /// plugin directory. The file actually hashed is `binary_path`, which
+    /// may be a copy of it, so the bytes checked are the bytes loaded.
+    pub fn verify(
+        &self,
+        dir: &Path,
+        binary_name: &str,
+        binary_path: &Path,
+    ) -> Result<(), SignatureError> {
+        let Some(signature) = PluginSignature::from_dir(dir)? else {
+            if self.require_signatures {
+                return Err(SignatureError::Unsigned);
+            }
+            return Ok(());
+        };
+
+        let public_key = parse_public_key(&signature.public_key)?;
+        if !self.trusted_keys.contains(&public_key) {
+            if self.require_signatures {
+                return Err(SignatureError::UntrustedKey(signature.public_key));
+            }
+            warn!(
+                public_key = %signature.public_key,
+                dir = %dir.display(),
+                "Plugin is signed by a key that is not trusted"
+            );
+        }
+        let signature_bytes = BASE64
+            .decode(&signature.signature)
+            .map_err(|e| SignatureError::Malformed(e.to_string()))?;
+        let ed25519_signature = Signature::from_slice(&signature_bytes)
+            .map_err(|e| SignatureError::Malformed(e.to_string()))?;
+        public_key
+            .verify(
+                PluginSignature::payload(&signature.files).as_bytes(),
+                &ed25519_signature,
+            )
+            .map_err(|_| SignatureError::InvalidSignature)?;
+
+        for required in [MANIFEST_FILE, binary_name] {
+            if !signature.files.contains_key(required) {
+                return Err(SignatureError::NotCovered(required.to_string()));
+            }
+        }
+        for (file, hash) in &signature.files {
+            if file.contains(['/', '\\']) || file == ".." {
+                return Err(SignatureError::Malformed(format!(
+                    "invalid file name '{}'",
+                    file
+                )));
+            }
+            let path = if file == binary_name {
+                binary_path.to_path_buf()
+            } else {
+                dir.join(file)
+            };
+            if !path.is_file() || hash_file(&path)? != *hash {
+                return Err(SignatureError::HashMismatch(file.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Signs the plugin in `dir`, writing its `aisopod.plugin.sig` file.
+///
+/// The signature covers the manifest and whichever entry point binaries
+/// exist: the WebAssembly component and the shared library for this
+/// platform.
+///
+/// # Errors
+///
+/// Returns `SignatureError::MissingBinary` if the plugin has neither.
+pub fn sign_plugin(dir: &Path, key: &SigningKey) -> Result<PluginSignature, SignatureError> {
+    let manifest = PluginManifest::from_file(&dir.join(MANIFEST_FILE))
+        .map_err(|e| SignatureError::Malformed(e.to_string()))?;
+    let entry_point = &manifest.plugin.entry_point;
+
+    let mut files = BTreeMap::new();
+    files.insert(
+        MANIFEST_FILE.to_string(),
+        hash_file(&dir.join(MANIFEST_FILE))?,
+    );
+    for binary in [
+        format!("{}.wasm", entry_point),
+        library_filename(entry_point),
+    ] {
+        let path = dir.join(&binary);
+        if path.is_file() {
+            files.insert(binary, hash_file(&path)?);
+        }
+    }
+    if files.len() == 1 {
+        return Err(SignatureError::MissingBinary);
+    }
+
+    let signature = key.sign(PluginSignature::payload(&files).as_bytes());
+    let signature = PluginSignature {
+        public_key: encode_public_key(&key.verifying_key()),
+        signature: BASE64.encode(signature.to_bytes()),
+        files,
+    };
+    let content =
+        toml::to_string(&signature).map_err(|e| SignatureError::Malformed(e.to_string()))?;
+    std::fs::write(dir.join(SIGNATURE_FILE), content)?;
+    Ok(signature)
+}
+
+/// Generates a new random signing key.
+pub fn generate_signing_key() -> SigningKey {
+    SigningKey::from_bytes(&rand::random())
+}
+
+/// Decodes a base64 secret key, as stored in a key file.
+pub fn parse_signing_key(key: &str) -> Result<SigningKey, SignatureError> {
+    Ok(SigningKey::from_bytes(&decode_key(key)?))
+}
+
+/// Encodes a secret key for storing in a key file.
+pub fn encode_signing_key(key: &SigningKey) -> String {
+    BASE64.encode(key.to_bytes())
+}
+
+/// Decodes a base64 public key.
+pub fn parse_public_key(key: &str) -> Result<VerifyingKey, SignatureError> {
+    VerifyingKey::from_bytes(&decode_key(key)?)
+        .map_err(|e| SignatureError::InvalidKey(key.trim().to_string(), e.to_string()))
+}
+
+/// Encodes a public key for the trusted keys configuration.
+pub fn encode_public_key(key: &VerifyingKey) -> String {
+    BASE64.encode(key.to_bytes())
+}
+
+fn decode_key(key: &str) -> Result<[u8; 32], SignatureError> {
+    let key = key.trim();
+    let invalid = |reason: String| SignatureError::InvalidKey(key.to_string(), reason);
+    BASE64
+        .decode(key)
+        .map_err(|e| invalid(e.to_string()))?
+        .try_into()
+        .map_err(|bytes: Vec<u8>| invalid(format!("expected 32 bytes, found {}", bytes.len())))
+}
+
+fn hash_file(path: &Path) -> Result<String, SignatureError> {
+    let bytes = std::fs::read(path)?;
+    Ok(hex::encode(Sha256::digest(&bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_round_trip() {
+        let key = generate_signing_key();
+        let parsed = parse_signing_key(&format!("{}\n", encode_signing_key(&key))).unwrap();
+        assert_eq!(parsed.to_bytes(), key.to_bytes());
+
+        let public = encode_public_key(&key.verifying_key());
+        assert_eq!(parse_public_key(&public).unwrap(), key.verifying_key());
+        assert!(matches!(
+            parse_public_key("c2hvcnQ="),
+            Err(SignatureError::InvalidKey(..))
+        ));
+    }
+
+    #[test]
+    fn test_payload_lists_files_in_order() {
+        let mut files = BTreeMap::new();
+        files.insert("libweather.so".to_string(), "bb".to_string());
+        files.insert("aisopod.plugin.toml".to_string(), "aa".to_string());
+        assert_eq!(
+            PluginSignature::payload(&files),
+            "aisopod-plugin-signature-v1\naa  aisopod.plugin.toml\nbb  libweather.so\n"
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -89,13 +89,75 @@ pub fn discover_skill_dirs(base_dirs: &[PathBuf]) -> Vec<PathBuf> {
     skill_dirs
 }
 
+/// The outcome of checking a skill's runtime requirements.
+///
+/// Missing requirements make a skill unavailable; executables older than
+/// their minimum version only degrade it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequirementReport {
+    /// Unmet requirements: missing environment variables or executables,
+    /// or a platform mismatch.
+    pub missing: Vec<String>,
+    /// Executables older than their minimum version, or whose version
+    /// could not be determined.
+    pub outdated: Vec<String>,
+}
+
+impl RequirementReport {
+    /// Checks the requirements declared in a skill manifest.
+    pub fn for_manifest(manifest: &super::SkillManifest) -> Self {
+        check_requirements(
+            &manifest.required_env_vars,
+            &manifest.required_binaries,
+            &manifest.min_versions,
+            manifest.platform.as_deref(),
+        )
+    }
+
+    /// Checks the requirements declared in a skill's metadata.
+    pub fn for_meta(meta: &SkillMeta) -> Self {
+        check_requirements(
+            &meta.required_env_vars,
+            &meta.required_binaries,
+            &meta.min_versions,
+            meta.platform.as_deref(),
+        )
+    }
+
+    /// Returns `true` if every requirement is met.
+    pub fn is_satisfied(&self) -> bool {
+        self.missing.is_empty() && self.outdated.is_empty()
+    }
+
+    /// Returns the skill status the report implies: `Unavailable` if any
+    /// requirement is missing, `Degraded` if any executable is outdated,
+    /// and `Ready` otherwise.
+    pub fn status(&self) -> SkillStatus {
+        if !self.missing.is_empty() {
+            SkillStatus::Unavailable {
+                reason: self.missing.join("; "),
+            }
+        } else if !self.outdated.is_empty() {
+            SkillStatus::Degraded {
+                reason: self.outdated.join("; "),
+            }
+        } else {
+            SkillStatus::Ready
+        }
+    }
+}
+
 /// Validates that a skill's requirements are met.
 ///
 /// Checks that:
 /// - All required environment variables are set
 /// - All required binaries are available on PATH
+/// - Required binaries meet their minimum versions
 /// - The platform constraint (if any) matches the current OS
 ///
+/// Use [`RequirementReport::for_manifest()`] to tell missing requirements
+/// from outdated executables.
+///
 /// # Arguments
 ///
 /// * `manifest` - The skill manifest to validate
@@ -131,26 +193,65 @@ pub fn discover_skill_dirs(base_dirs: &[PathBuf]) -> Vec<PathBuf> {
 /// }
 /// ```
 pub fn validate_requirements(manifest: &super::SkillManifest) -> Result<(), Vec<String>> {
-    let mut errors = Vec::new();
+    let report = RequirementReport::for_manifest(manifest);
+    if report.is_satisfied() {
+        Ok(())
+    } else {
+        Err(report.missing.into_iter().chain(report.outdated).collect())
+    }
+}
+
+fn check_requirements(
+    env_vars: &[String],
+    binaries: &[String],
+    min_versions: &BTreeMap<String, String>,
+    platform: Option<&str>,
+) -> RequirementReport {
+    let mut report = RequirementReport::default();
 
     // Check environment variables
-    for var in &manifest.required_env_vars {
+    for var in env_vars {
         if env::var(var).is_err() {
-            errors.push(format!("Missing environment variable: {}", var));
+            report
+                .missing
+                .push(format!("Missing environment variable: {}", var));
         }
     }
 
-    // Check required binaries
-    for bin in &manifest.required_binaries {
-        if is_binary_available(bin) {
-            // Binary is available
-        } else {
-            errors.push(format!("Missing required binary: {}", bin));
+    // Check required binaries, including those with a minimum version
+    let versioned = min_versions.keys().filter(|bin| !binaries.contains(bin));
+    for bin in binaries.iter().chain(versioned) {
+        let Some(path) = find_binary(bin) else {
+            report
+                .missing
+                .push(format!("Missing required binary: {}", bin));
+            continue;
+        };
+        let Some(min_version) = min_versions.get(bin) else {
+            continue;
+        };
+        let Some(required) = parse_version(min_version) else {
+            report.outdated.push(format!(
+                "Invalid minimum version '{}' for {}",
+                min_version, bin
+            ));
+            continue;
+        };
+        match binary_version(&path) {
+            Some(version) if version >= required => {}
+            Some(version) => report.outdated.push(format!(
+                "{} {} is older than required {}",
+                bin, version, min_version
+            )),
+            None => report.outdated.push(format!(
+                "Could not determine version of {} (requires {})",
+                bin, min_version
+            )),
         }
     }
 
     // Check platform constraint
-    if let Some(ref platform) = manifest.platform {
+    if let Some(platform) = platform {
         let current_os = env::consts::OS;
         let platform_normalized = platform.to_lowercase();
 
@@ -159,7 +260,7 @@ pub fn validate_requirements(manifest: &super::SkillManifest) -> Result<(), Vec<
             "macos" if current_os == "macos" => {}
             "windows" if current_os == "windows" => {}
             _ => {
-                errors.push(format!(
+                report.missing.push(format!(
                     "Platform mismatch: requires '{}', running '{}'",
                     platform, current_os
                 ));
@@ -167,40 +268,82 @@ pub fn validate_requirements(manifest: &super::SkillManifest) -> Result<(), Vec<
         }
     }
 
-    if errors.is_empty() {
-        Ok(())
-    } else {
-        Err(errors)
-    }
+    report
 }
 
-/// Checks if a binary is available on the system PATH.
+/// Finds an executable by name in the directories of `PATH`.
 ///
-/// Uses platform-appropriate methods to check binary availability:
-/// - Unix: uses `which` command
-/// - Windows: uses `where` command
-///
-/// # Arguments
-///
-/// * `binary_name` - The name of the binary to check
-///
-/// # Returns
-///
-/// `true` if the binary is found, `false` otherwise.
-fn is_binary_available(binary_name: &str) -> bool {
-    let output = if cfg!(unix) {
-        Command::new("which").arg(binary_name).output()
-    } else if cfg!(windows) {
-        Command::new("where").arg(binary_name).output()
-    } else {
-        // For other platforms, try `which` first, then `where`
-        Command::new("which").arg(binary_name).output()
-    };
+/// Names containing a path separator are checked as paths. On Windows,
+/// the extensions in `PATHEXT` are tried as well.
+fn find_binary(binary_name: &str) -> Option<PathBuf> {
+    let candidate = Path::new(binary_name);
+    if candidate.components().count() > 1 {
+        return is_executable(candidate).then(|| candidate.to_path_buf());
+    }
+
+    let mut extensions = vec![String::new()];
+    if cfg!(windows) {
+        let pathext = env::var("PATHEXT").unwrap_or_else(|_| ".EXE;.CMD;.BAT".to_string());
+        extensions.extend(pathext.split(';').map(str::to_string));
+    }
+    let paths = env::var_os("PATH")?;
+    env::split_paths(&paths)
+        .flat_map(|dir| {
+            extensions
+                .iter()
+                .map(move |ext| dir.join(format!("{}{}", binary_name, ext)))
+        })
+        .find(|path| is_executable(path))
+}
 
-    match output {
-        Ok(output) => output.status.success(),
-        Err(_) => false,
+fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
     }
+    #[cfg(not(unix))]
+    {
+        metadata.is_file()
+    }
+}
+
+/// Reads an executable's version from its `--version` output.
+///
+/// The first word that looks like a dotted version number is used, so
+/// output such as `git version 2.39.2` yields `2.39.2`.
+fn binary_version(path: &Path) -> Option<semver::Version> {
+    let output = Command::new(path).arg("--version").output().ok()?;
+    let text = format!(
+        "{} {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    text.split(|c: char| c.is_whitespace() || matches!(c, ',' | '(' | ')'))
+        .filter(|word| word.trim_start_matches('v').contains('.'))
+        .find_map(parse_version)
+}
+
+/// Parses a version leniently: `2`, `2.30`, and `v2.30.1-rc1` are
+/// accepted, with missing components taken as zero.
+pub(crate) fn parse_version(version: &str) -> Option<semver::Version> {
+    let version = version.trim().trim_start_matches('v');
+    let core = version
+        .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .next()?;
+    let parts = core
+        .split('.')
+        .take(3)
+        .map(|part| part.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    Some(semver::Version::new(
+        parts[0],
+        parts.get(1).copied().unwrap_or(0),
+        parts.get(2).copied().unwrap_or(0),
+    ))
 }
 
 /// Loads and registers skills from discovered directories.
@@ -208,9 +351,9 @@ fn is_binary_available(binary_name: &str) -> bool {
 /// This is the main orchestration function that:
 /// 1. Discovers skill directories using `discover_skill_dirs`
 /// 2. Parses each manifest with `parse_manifest`
-/// 3. Validates requirements with `validate_requirements`
-/// 4. Registers skills into the registry, marking them as `Degraded`
-///    if requirements aren't met (instead of failing)
+/// 3. Registers skills into the registry, which checks their requirements
+///    and marks them as `Unavailable` if requirements are missing or
+///    `Degraded` if executables are outdated (instead of failing)
 ///
 /// # Arguments
 ///
@@ -282,42 +425,33 @@ pub async fn load_skills(
             }
         };
 
-        // Validate requirements
+        // Register the skill; the registry marks it by its requirements
         let skill_id_str = manifest.id.clone();
-        match validate_requirements(&manifest) {
-            Ok(()) => {
-                // Requirements met - register as Ready
-                match load_and_register_skill(registry, &manifest, skill_context).await {
-                    Ok(()) => {
-                        skill_statuses.insert(skill_id_str.clone(), SkillStatus::Ready);
-                        tracing::info!("Registered skill '{}' as Ready", skill_id_str);
-                    }
-                    Err(e) => {
-                        skill_statuses.insert(
-                            skill_id_str.clone(),
-                            SkillStatus::Failed {
-                                error: e.to_string(),
-                            },
-                        );
-                        tracing::warn!(
-                            "Failed to initialize skill '{}': {}",
-                            skill_id_str,
-                            e
-                        );
-                    }
-                }
+        let status = match load_and_register_skill(registry, &manifest, skill_context).await {
+            Ok(()) => registry
+                .status(&skill_id_str)
+                .cloned()
+                .unwrap_or(SkillStatus::Ready),
+            Err(e) => {
+                let status = SkillStatus::Failed {
+                    error: e.to_string(),
+                };
+                registry.set_status(&skill_id_str, status.clone());
+                status
             }
-            Err(reasons) => {
-                // Requirements not met - register as Degraded
-                let reason = reasons.join("; ");
-                skill_statuses.insert(skill_id_str.clone(), SkillStatus::Degraded { reason });
-                tracing::info!(
-                    "Registered skill '{}' as Degraded: {:?}",
-                    manifest.id,
-                    skill_statuses.get(&skill_id_str)
-                );
+        };
+        match &status {
+            SkillStatus::Ready => {
+                tracing::info!("Registered skill '{}' as Ready", skill_id_str);
+            }
+            SkillStatus::Failed { error } => {
+                tracing::warn!("Failed to initialize skill '{}': {}", skill_id_str, error);
+            }
+            _ => {
+                tracing::info!("Registered skill '{}' as {:?}", skill_id_str, status);
             }
         }
+        skill_statuses.insert(skill_id_str, status);
     }
 
     Ok(skill_statuses)
@@ -354,7 +488,8 @@ async fn load_and_register_skill(
         manifest.required_env_vars.clone(),
         manifest.required_binaries.clone(),
         manifest.platform.clone(),
-    );
+    )
+    .with_min_versions(manifest.min_versions.clone());
 
     // For now, we just register a minimal placeholder
     // The actual skill implementation would be loaded from a dynamic library
@@ -566,13 +701,13 @@ category = "Productivity"
     #[test]
     fn test_is_binary_available() {
         // echo should be available on all platforms
-        assert!(is_binary_available("echo"));
+        assert!(find_binary("echo").is_some());
     }
 
     #[test]
     fn test_is_binary_not_available() {
         // A truly nonexistent binary
-        assert!(!is_binary_available("this_binary_does_not_exist_12345_xyz"));
+        assert!(find_binary("this_binary_does_not_exist_12345_xyz").is_none());
     }
 
     #[test]
@@ -663,11 +798,80 @@ category = "Productivity"
     fn test_is_binary_available_with_path() {
         // Verify that binaries with full paths work
         // This test may behave differently depending on the system
-        let result = is_binary_available("ls");
+        let result = find_binary("ls").is_some();
         // ls should be available on Unix systems
         #[cfg(unix)]
         assert!(result);
         #[cfg(not(unix))]
         let _ = result; // Avoid unused variable warning
     }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("2.30"), Some(semver::Version::new(2, 30, 0)));
+        assert_eq!(
+            parse_version("v1.2.3-rc1"),
+            Some(semver::Version::new(1, 2, 3))
+        );
+        assert_eq!(parse_version("7"), Some(semver::Version::new(7, 0, 0)));
+        assert_eq!(parse_version("latest"), None);
+    }
+
+    #[test]
+    fn test_requirement_report_status() {
+        use crate::skills::SkillCategory;
+        use crate::skills::SkillManifest;
+
+        let mut manifest = SkillManifest::new(
+            "test-skill",
+            "Test Skill",
+            "A test skill",
+            "1.0.0",
+            SkillCategory::Utility,
+            vec![],
+            vec![],
+            None,
+        );
+        let report = RequirementReport::for_manifest(&manifest);
+        assert!(report.is_satisfied());
+        assert_eq!(report.status(), SkillStatus::Ready);
+
+        manifest
+            .min_versions
+            .insert("nonexistent_bin_xyz789".to_string(), "1.0".to_string());
+        let report = RequirementReport::for_manifest(&manifest);
+        assert_eq!(report.missing.len(), 1);
+        assert!(matches!(report.status(), SkillStatus::Unavailable { .. }));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_requirement_report_outdated_binary() {
+        let temp_dir = TempDir::new().unwrap();
+        let script = temp_dir.path().join("fake-tool");
+        fs::write(&script, "#!/bin/sh\necho 'fake-tool version 1.4.2'\n").unwrap();
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let bin = script.to_string_lossy().to_string();
+        let meta = SkillMeta::new(
+            "fake-skill",
+            "1.0.0",
+            "Fake skill",
+            crate::skills::SkillCategory::Utility,
+            vec![],
+            vec![bin.clone()],
+            None,
+        );
+
+        let meta = meta.with_min_versions(BTreeMap::from([(bin.clone(), "1.4".to_string())]));
+        assert!(RequirementReport::for_meta(&meta).is_satisfied());
+
+        let meta = meta.with_min_versions(BTreeMap::from([(bin, "2.0".to_string())]));
+        let report = RequirementReport::for_meta(&meta);
+        assert!(report.missing.is_empty());
+        assert_eq!(report.outdated.len(), 1);
+        assert!(matches!(report.status(), SkillStatus::Degraded { .. }));
+    }
 }
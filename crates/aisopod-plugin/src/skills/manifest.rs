@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use serde::Deserialize;
@@ -74,6 +75,19 @@ pub struct SkillManifest {
     /// When None, the skill is platform-agnostic.
     #[serde(default)]
     pub platform: Option<String>,
+
+    /// Minimum versions of required executables, by executable name.
+    ///
+    /// A version is read from the executable's `--version` output. A skill
+    /// whose executable is older than its minimum version is loaded as
+    /// degraded.
+    ///
+    /// ```toml
+    /// [min_versions]
+    /// git = "2.30"
+    /// ```
+    #[serde(default)]
+    pub min_versions: BTreeMap<String, String>,
 }
 
 impl SkillManifest {
@@ -111,6 +125,7 @@ impl SkillManifest {
             required_env_vars,
             required_binaries,
             platform,
+            min_versions: BTreeMap::new(),
         }
     }
 
@@ -143,6 +158,14 @@ impl SkillManifest {
             }
         }
 
+        for version in self.min_versions.values() {
+            if super::discovery::parse_version(version).is_none() {
+                return Err(ManifestError::InvalidField(
+                    "min_versions must map executables to versions like '2.30'",
+                ));
+            }
+        }
+
         Ok(())
     }
 }
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Category classification for skills.
@@ -69,6 +71,12 @@ pub struct SkillMeta {
     /// operating system. Valid values include "linux", "macos", and "windows".
     /// When None, the skill is platform-agnostic.
     pub platform: Option<String>,
+    /// Minimum versions of required executables, by executable name.
+    ///
+    /// The skill is degraded when an executable is older than its minimum
+    /// version.
+    #[serde(default)]
+    pub min_versions: BTreeMap<String, String>,
 }
 
 impl SkillMeta {
@@ -100,8 +108,15 @@ impl SkillMeta {
             required_env_vars,
             required_binaries,
             platform,
+            min_versions: BTreeMap::new(),
         }
     }
+
+    /// Sets the minimum versions of required executables.
+    pub fn with_min_versions(mut self, min_versions: BTreeMap<String, String>) -> Self {
+        self.min_versions = min_versions;
+        self
+    }
 }
 
 impl Default for SkillMeta {
@@ -118,6 +133,7 @@ impl Default for SkillMeta {
             required_env_vars: Vec::new(),
             required_binaries: Vec::new(),
             platform: None,
+            min_versions: BTreeMap::new(),
         }
     }
 }
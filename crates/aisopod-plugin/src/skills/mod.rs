@@ -21,13 +21,25 @@
 //!
 //! - [`discover_skill_dirs`]: Function to scan directories for skills.
 //! - [`validate_requirements`]: Function to check skill requirements.
+//! - [`RequirementReport`]: Missing and outdated requirements of a skill.
 //! - [`load_skills`]: Function to orchestrate the full discovery pipeline.
 //!
+//! # Remote Types
+//!
+//! - [`SkillSource`]: A git repository or HTTP index distributing skills.
+//! - [`SkillInstaller`]: Installs and updates remote skills, honoring version pins.
+//! - [`SkillLock`]: The `skills.lock` record of installed remote skills.
+//!
 //! # Registry Types
 //!
 //! - [`SkillRegistry`]: Central registry for skill discovery and lifecycle management.
 //! - [`SkillStatus`]: Status indicating a skill's health and availability.
 //!
+//! Skills whose required environment variables, executables, or platform
+//! are missing are registered as `Unavailable` and not offered to agents;
+//! skills with an executable older than its minimum version are `Degraded`
+//! but still offered.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -85,6 +97,7 @@ mod discovery;
 mod manifest;
 mod meta;
 mod registry;
+mod remote;
 mod r#trait;
 mod builtin;
 mod scaffold;
@@ -92,10 +105,17 @@ mod scaffold;
 use std::sync::Arc;
 
 pub use context::SkillContext;
-pub use discovery::{discover_skill_dirs, validate_requirements, load_skills, DiscoveryError, DiscoveryResult, DiscoveredSkill};
+pub use discovery::{
+    discover_skill_dirs, validate_requirements, load_skills, DiscoveryError, DiscoveryResult,
+    DiscoveredSkill, RequirementReport,
+};
 pub use manifest::{SkillManifest, ManifestError, parse_manifest};
 pub use meta::{SkillCategory, SkillMeta};
 pub use registry::{SkillRegistry, SkillStatus};
+pub use remote::{
+    IndexEntry, InstalledSkill, LockedSkill, RemoteError, SkillIndex, SkillInstaller, SkillLock,
+    SkillSource, SkillUpdate, LOCK_FILE,
+};
 pub use r#trait::Skill;
 pub use scaffold::{scaffold_skill, ScaffoldOptions, to_pascal_case};
 #[cfg(feature = "skill-healthcheck")]
@@ -2,27 +2,41 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::skills::discovery::RequirementReport;
 use crate::skills::Skill;
 
 /// Status of a skill indicating its health and availability.
 ///
 /// This enum tracks the lifecycle state of skills in the registry:
 /// - `Ready`: Skill is loaded and operational
-/// - `Degraded`: Skill is loaded but missing requirements
+/// - `Degraded`: Skill is loaded but an executable is older than required
+/// - `Unavailable`: Skill is loaded but missing requirements, so it is not
+///   offered to agents
 /// - `Failed`: Skill failed to initialize
 /// - `Unloaded`: Skill is not loaded
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SkillStatus {
     /// Skill is loaded and ready.
     Ready,
-    /// Skill is loaded but missing requirements (env vars, binaries).
+    /// Skill is loaded but an executable is older than required, or its
+    /// version could not be determined.
     Degraded { reason: String },
+    /// Skill is loaded but missing requirements (env vars, binaries, platform).
+    Unavailable { reason: String },
     /// Skill failed to initialize.
     Failed { error: String },
     /// Skill is not loaded.
     Unloaded,
 }
 
+impl SkillStatus {
+    /// Returns `true` if the skill's prompt fragment and tools may be
+    /// offered to agents, i.e. it is `Ready` or `Degraded`.
+    pub fn is_available(&self) -> bool {
+        matches!(self, SkillStatus::Ready | SkillStatus::Degraded { .. })
+    }
+}
+
 /// Central registry for managing skills.
 ///
 /// The `SkillRegistry` is the single access point for all skill lookup
@@ -35,6 +49,7 @@ pub enum SkillStatus {
 /// - **Skill Registration**: Register skills with `register()` and look them up by ID
 /// - **Agent Assignments**: Assign skills to agents and retrieve per-agent skill lists
 /// - **Status Management**: Track and update skill status for health monitoring
+/// - **Requirement Validation**: Check skill requirements at registration and on demand
 /// - **Discovery**: Enumerate all registered skills
 ///
 /// # Example
@@ -102,9 +117,11 @@ impl SkillRegistry {
 
     /// Registers a skill with the registry.
     ///
-    /// Inserts the skill into the registry and sets its initial status
-    /// to `SkillStatus::Ready`. If a skill with the same ID already exists,
-    /// it will be overwritten with a warning logged.
+    /// Inserts the skill into the registry and sets its initial status from
+    /// its requirements: `SkillStatus::Ready` if they are met,
+    /// `SkillStatus::Degraded` if an executable is outdated, and
+    /// `SkillStatus::Unavailable` if any is missing. If a skill with the
+    /// same ID already exists, it will be overwritten.
     ///
     /// # Arguments
     ///
@@ -119,7 +136,8 @@ impl SkillRegistry {
     /// ```
     pub fn register(&mut self, skill: Arc<dyn Skill>) {
         let id = skill.id().to_string();
-        self.statuses.insert(id.clone(), SkillStatus::Ready);
+        let status = RequirementReport::for_meta(skill.meta()).status();
+        self.statuses.insert(id.clone(), status);
         self.skills.insert(id, skill);
     }
 
@@ -184,7 +202,9 @@ impl SkillRegistry {
     ///
     /// Returns a `Vec<Arc<dyn Skill>>` containing all skills that have been
     /// assigned to the given agent. Skills that are not registered in the
-    /// registry are silently skipped.
+    /// registry, or whose status is not available (see
+    /// [`SkillStatus::is_available()`]), are silently skipped, so their
+    /// prompt fragments and tools are not offered to the agent.
     ///
     /// # Arguments
     ///
@@ -203,6 +223,7 @@ impl SkillRegistry {
             .get(agent_id)
             .map(|ids| {
                 ids.iter()
+                    .filter(|id| self.is_available(id))
                     .filter_map(|id| self.skills.get(id).cloned())
                     .collect()
             })
@@ -250,6 +271,46 @@ impl SkillRegistry {
     pub fn set_status(&mut self, id: &str, status: SkillStatus) {
         self.statuses.insert(id.to_string(), status);
     }
+
+    /// Returns `true` if the skill is registered and available to agents.
+    pub fn is_available(&self, id: &str) -> bool {
+        self.skills.contains_key(id) && self.statuses.get(id).is_some_and(SkillStatus::is_available)
+    }
+
+    /// Checks a registered skill's requirements again and updates its status.
+    ///
+    /// Use this after the environment changes, e.g. once a missing binary
+    /// has been installed. Skills that failed to initialize or are unloaded
+    /// keep their status.
+    ///
+    /// # Returns
+    ///
+    /// The skill's updated status, or `None` if the skill is not registered.
+    pub fn validate(&mut self, id: &str) -> Option<&SkillStatus> {
+        let skill = self.skills.get(id)?;
+        let keep = matches!(
+            self.statuses.get(id),
+            Some(SkillStatus::Failed { .. } | SkillStatus::Unloaded)
+        );
+        if !keep {
+            let status = RequirementReport::for_meta(skill.meta()).status();
+            if let SkillStatus::Unavailable { reason } = &status {
+                tracing::warn!(skill_id = %id, reason = %reason, "Skill is unavailable");
+            }
+            self.statuses.insert(id.to_string(), status);
+        }
+        self.statuses.get(id)
+    }
+
+    /// Checks the requirements of all registered skills again.
+    ///
+    /// See [`validate()`](SkillRegistry::validate).
+    pub fn validate_all(&mut self) {
+        let ids: Vec<String> = self.skills.keys().cloned().collect();
+        for id in ids {
+            self.validate(&id);
+        }
+    }
 }
 
 impl Default for SkillRegistry {
@@ -443,6 +504,56 @@ mod tests {
         assert!(agent_ids.contains(&"skill-2"));
     }
 
+    #[test]
+    fn test_skills_for_agent_skips_unavailable() {
+        let mut registry = SkillRegistry::new();
+
+        let mut missing = TestSkill::new("skill-2");
+        missing.meta.required_env_vars = vec!["NONEXISTENT_VAR_ABC123".to_string()];
+        registry.register(Arc::new(TestSkill::new("skill-1")));
+        registry.register(Arc::new(missing));
+        assert!(matches!(
+            registry.status("skill-2"),
+            Some(SkillStatus::Unavailable { .. })
+        ));
+
+        registry.assign_to_agent(
+            "agent-1",
+            vec!["skill-1".to_string(), "skill-2".to_string()],
+        );
+        let agent_skills = registry.skills_for_agent("agent-1");
+        assert_eq!(agent_skills.len(), 1);
+        assert_eq!(agent_skills[0].id(), "skill-1");
+    }
+
+    #[test]
+    fn test_validate_reevaluates_requirements() {
+        let mut registry = SkillRegistry::new();
+        registry.register(Arc::new(TestSkill::new("skill-1")));
+
+        registry.set_status(
+            "skill-1",
+            SkillStatus::Degraded {
+                reason: "stale".to_string(),
+            },
+        );
+        assert_eq!(registry.validate("skill-1"), Some(&SkillStatus::Ready));
+
+        // Failed skills keep their status
+        registry.set_status(
+            "skill-1",
+            SkillStatus::Failed {
+                error: "boom".to_string(),
+            },
+        );
+        registry.validate_all();
+        assert!(matches!(
+            registry.status("skill-1"),
+            Some(SkillStatus::Failed { .. })
+        ));
+        assert_eq!(registry.validate("missing"), None);
+    }
+
     #[test]
     fn test_skills_for_agent_empty() {
         let registry = SkillRegistry::new();
@@ -0,0 +1,794 @@
+//! Fetching skills from remote sources.
+//!
+//! Curated skills are distributed from a [`SkillSource`]: a git repository
+//! holding one or more skill directories, or an HTTP index listing the
+//! published versions of each skill as `.tar.gz` archives. The
+//! [`SkillInstaller`] fetches a skill into the skills directory, where
+//! [`load_skills`](super::load_skills) discovers it, and records where it
+//! came from in a `skills.lock` file next to the installed skills.
+//!
+//! # Sources
+//!
+//! - `git+<url>[#<ref>]`, or any URL ending in `.git` - cloned with `git`,
+//!   optionally at a branch or tag. The skill is the directory, at most
+//!   three levels deep, whose `skill.toml` declares the requested ID.
+//! - `http://` or `https://` URLs - a JSON index of the form
+//!   `{"skills": [{"id": "weather", "version": "1.2.0", "url": "weather-1.2.0.tar.gz", "sha256": "..."}]}`.
+//!   Archive URLs may be relative to the index, and archives whose
+//!   `sha256` is given are checked against it.
+//!
+//! # Version pinning
+//!
+//! A skill may be installed with a pin, a semver requirement such as
+//! `=1.2.0` or `^1.2`. [`SkillInstaller::update()`] only moves a skill to
+//! a newer version matching its pin, so pinned skills stay within the
+//! versions their user vetted.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use aisopod_plugin::skills::{SkillInstaller, SkillSource};
+//!
+//! let installer = SkillInstaller::new("/home/me/.aisopod/skills");
+//! let source = SkillSource::parse("https://skills.example.com/index.json")?;
+//! installer.install(&source, "weather", Some("^1.2"), false).await?;
+//!
+//! // Later
+//! for update in installer.update(&[], false).await? {
+//!     println!("{}: {} -> {:?}", update.id, update.current, update.available);
+//! }
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tracing::info;
+
+use super::discovery::parse_version;
+use super::manifest::{parse_manifest, ManifestError};
+use super::SkillManifest;
+use crate::install::{copy_dir, download, git_clone, is_valid_id, unpack_archive, InstallError};
+
+/// Manifest file name expected at the root of a skill.
+const MANIFEST_FILE: &str = "skill.toml";
+
+/// Name of the file recording installed remote skills.
+pub const LOCK_FILE: &str = "skills.lock";
+
+/// Error types for fetching remote skills.
+#[derive(Debug, Error)]
+pub enum RemoteError {
+    /// The source string could not be understood.
+    #[error("Invalid skill source '{0}': {1}")]
+    InvalidSource(String, String),
+
+    /// The version pin is not a valid semver requirement.
+    #[error("Invalid version pin '{0}': {1}")]
+    InvalidPin(String, String),
+
+    /// The skill index could not be read.
+    #[error("Invalid skill index: {0}")]
+    Index(String),
+
+    /// The skill ID cannot be used as a directory name.
+    #[error("Invalid skill ID '{0}'")]
+    InvalidId(String),
+
+    /// The source does not provide the skill.
+    #[error("Skill '{0}' not found in {1}")]
+    NotFound(String, String),
+
+    /// The source has no version of the skill matching its pin.
+    #[error("No version of skill '{0}' matches '{1}'")]
+    NoMatchingVersion(String, String),
+
+    /// A downloaded archive does not match its checksum.
+    #[error("Checksum mismatch for skill '{0}'")]
+    Checksum(String),
+
+    /// The skill is not installed from a remote source.
+    #[error("Skill '{0}' is not installed from a remote source")]
+    NotInstalled(String),
+
+    /// A skill with the same ID is already installed.
+    #[error("Skill '{0}' is already installed")]
+    AlreadyInstalled(String),
+
+    /// The lock file could not be read or written.
+    #[error("Invalid {LOCK_FILE}: {0}")]
+    Lock(String),
+
+    /// Fetching the skill failed.
+    #[error(transparent)]
+    Fetch(#[from] InstallError),
+
+    /// The skill's manifest is invalid.
+    #[error(transparent)]
+    Manifest(#[from] ManifestError),
+
+    /// File I/O error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Where to fetch skills from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkillSource {
+    /// A git repository, optionally at a branch or tag.
+    Git {
+        /// Repository URL.
+        url: String,
+        /// Branch or tag to check out.
+        reference: Option<String>,
+    },
+    /// A JSON index of skill archives served over HTTP(S).
+    Index {
+        /// Index URL.
+        url: String,
+    },
+}
+
+impl SkillSource {
+    /// Parses a source as given on the command line.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RemoteError::InvalidSource` if the source is neither a git
+    /// URL nor an HTTP(S) URL.
+    pub fn parse(source: &str) -> Result<Self, RemoteError> {
+        let source = source.trim();
+        let (location, reference) = match source.split_once('#') {
+            Some((location, reference)) if !reference.is_empty() => {
+                (location, Some(reference.to_string()))
+            }
+            _ => (source, None),
+        };
+        if let Some(url) = location.strip_prefix("git+") {
+            if !url.is_empty() && !url.starts_with('-') {
+                return Ok(Self::Git {
+                    url: url.to_string(),
+                    reference,
+                });
+            }
+        }
+        let is_http = location.starts_with("https://") || location.starts_with("http://");
+        let is_remote = is_http
+            || ["ssh://", "git://", "git@"]
+                .iter()
+                .any(|scheme| location.starts_with(scheme));
+        if location.ends_with(".git") && is_remote {
+            return Ok(Self::Git {
+                url: location.to_string(),
+                reference,
+            });
+        }
+        if is_http {
+            return Ok(Self::Index {
+                url: source.to_string(),
+            });
+        }
+        Err(RemoteError::InvalidSource(
+            source.to_string(),
+            "expected a git URL or a skill index URL".to_string(),
+        ))
+    }
+}
+
+impl std::fmt::Display for SkillSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Git {
+                url,
+                reference: Some(reference),
+            } => write!(f, "git+{}#{}", url, reference),
+            Self::Git { url, .. } => write!(f, "git+{}", url),
+            Self::Index { url } => write!(f, "{}", url),
+        }
+    }
+}
+
+/// A skill index served by a [`SkillSource::Index`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillIndex {
+    /// Published skill versions
+    #[serde(default)]
+    pub skills: Vec<IndexEntry>,
+}
+
+/// One published version of a skill in a [`SkillIndex`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    /// Skill ID
+    pub id: String,
+    /// Skill version
+    pub version: String,
+    /// URL of the `.tar.gz` archive, possibly relative to the index
+    pub url: String,
+    /// Hex-encoded SHA-256 of the archive
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+impl SkillIndex {
+    /// Returns the newest version of a skill matching `pin`.
+    ///
+    /// Entries whose version cannot be parsed are ignored.
+    pub fn select(&self, id: &str, pin: Option<&VersionReq>) -> Option<(Version, &IndexEntry)> {
+        self.skills
+            .iter()
+            .filter(|entry| entry.id == id)
+            .filter_map(|entry| Some((parse_version(&entry.version)?, entry)))
+            .filter(|(version, _)| pin.is_none_or(|pin| pin.matches(version)))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+    }
+}
+
+/// The remote skills installed in a skills directory.
+///
+/// Stored as `skills.lock` in the skills directory. Skills copied into the
+/// directory by hand are not listed and never updated.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillLock {
+    /// Installed skills
+    #[serde(default, rename = "skill")]
+    pub skills: Vec<LockedSkill>,
+}
+
+/// A remote skill recorded in a [`SkillLock`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedSkill {
+    /// Skill ID
+    pub id: String,
+    /// Installed version
+    pub version: String,
+    /// Source the skill was installed from
+    pub source: String,
+    /// Semver requirement updates must satisfy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pin: Option<String>,
+}
+
+impl SkillLock {
+    /// Reads the lock file of a skills directory, or an empty lock if there
+    /// is none.
+    pub fn load(skills_dir: &Path) -> Result<Self, RemoteError> {
+        let path = skills_dir.join(LOCK_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        toml::from_str(&content).map_err(|e| RemoteError::Lock(e.to_string()))
+    }
+
+    /// Writes the lock file of a skills directory.
+    pub fn save(&self, skills_dir: &Path) -> Result<(), RemoteError> {
+        let content = toml::to_string_pretty(self).map_err(|e| RemoteError::Lock(e.to_string()))?;
+        std::fs::write(skills_dir.join(LOCK_FILE), content)?;
+        Ok(())
+    }
+
+    /// Returns the entry of a skill.
+    pub fn get(&self, id: &str) -> Option<&LockedSkill> {
+        self.skills.iter().find(|skill| skill.id == id)
+    }
+
+    /// Adds or replaces the entry of a skill.
+    pub fn upsert(&mut self, skill: LockedSkill) {
+        match self.skills.iter_mut().find(|locked| locked.id == skill.id) {
+            Some(locked) => *locked = skill,
+            None => self.skills.push(skill),
+        }
+        self.skills.sort_by(|a, b| a.id.cmp(&b.id));
+    }
+}
+
+/// A skill placed in the skills directory by [`SkillInstaller::install()`].
+#[derive(Debug, Clone)]
+pub struct InstalledSkill {
+    /// The skill's ID.
+    pub id: String,
+    /// The skill's version.
+    pub version: String,
+    /// The directory the skill was installed to.
+    pub dir: PathBuf,
+    /// The skill's manifest.
+    pub manifest: SkillManifest,
+}
+
+/// The outcome of checking a skill for updates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkillUpdate {
+    /// The skill's ID.
+    pub id: String,
+    /// The version installed before the update.
+    pub current: String,
+    /// A newer version matching the pin, if the source has one.
+    pub available: Option<String>,
+    /// Whether the newer version was installed.
+    pub updated: bool,
+}
+
+/// A version of a skill found in a source, not yet installed.
+enum Candidate {
+    /// An archive listed in an index.
+    Archive {
+        version: Version,
+        url: String,
+        sha256: Option<String>,
+    },
+    /// A skill directory already fetched into staging.
+    Fetched { version: Version, root: PathBuf },
+}
+
+impl Candidate {
+    fn version(&self) -> &Version {
+        match self {
+            Candidate::Archive { version, .. } | Candidate::Fetched { version, .. } => version,
+        }
+    }
+}
+
+/// Installs and updates remote skills in a skills directory.
+#[derive(Debug)]
+pub struct SkillInstaller {
+    skills_dir: PathBuf,
+    staging: AtomicU64,
+}
+
+impl SkillInstaller {
+    /// Creates an installer placing skills in `skills_dir`.
+    pub fn new(skills_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            skills_dir: skills_dir.into(),
+            staging: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the directory skills are installed to.
+    pub fn skills_dir(&self) -> &Path {
+        &self.skills_dir
+    }
+
+    /// Returns the remote skills installed in the skills directory.
+    pub fn lock(&self) -> Result<SkillLock, RemoteError> {
+        SkillLock::load(&self.skills_dir)
+    }
+
+    /// Fetches and installs the newest version of a skill matching `pin`.
+    ///
+    /// The skill is fetched into a staging directory and only moved into
+    /// place once its manifest was validated. With `force`, an installed
+    /// skill with the same ID is replaced. The skill's source and pin are
+    /// recorded in the lock file for [`update()`](Self::update).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RemoteError`] describing the failed step. Nothing is left
+    /// behind in the skills directory on failure.
+    pub async fn install(
+        &self,
+        source: &SkillSource,
+        id: &str,
+        pin: Option<&str>,
+        force: bool,
+    ) -> Result<InstalledSkill, RemoteError> {
+        if !is_valid_id(id) {
+            return Err(RemoteError::InvalidId(id.to_string()));
+        }
+        let requirement = parse_pin(pin)?;
+        if !force && self.skills_dir.join(id).exists() {
+            return Err(RemoteError::AlreadyInstalled(id.to_string()));
+        }
+        self.staged(|staging| async move {
+            let candidate = self
+                .resolve(source, id, requirement.as_ref(), &staging)
+                .await?;
+            self.place(source, id, pin, candidate, &staging).await
+        })
+        .await
+    }
+
+    /// Checks remote skills for newer versions matching their pins, and
+    /// installs them unless `check_only` is set.
+    ///
+    /// With an empty `ids`, every skill in the lock file is checked. A
+    /// skill whose source only offers versions outside its pin is left
+    /// alone.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RemoteError::NotInstalled` if a requested skill is not in
+    /// the lock file, or the error of the first skill that fails to update.
+    pub async fn update(
+        &self,
+        ids: &[String],
+        check_only: bool,
+    ) -> Result<Vec<SkillUpdate>, RemoteError> {
+        let lock = self.lock()?;
+        let locked: Vec<LockedSkill> = if ids.is_empty() {
+            lock.skills.clone()
+        } else {
+            ids.iter()
+                .map(|id| {
+                    lock.get(id)
+                        .cloned()
+                        .ok_or_else(|| RemoteError::NotInstalled(id.clone()))
+                })
+                .collect::<Result<_, _>>()?
+        };
+
+        let mut updates = Vec::new();
+        for skill in locked {
+            let source = SkillSource::parse(&skill.source)?;
+            let requirement = parse_pin(skill.pin.as_deref())?;
+            let current = parse_version(&skill.version);
+            let update = self
+                .staged(|staging| async move {
+                    let candidate = match self
+                        .resolve(&source, &skill.id, requirement.as_ref(), &staging)
+                        .await
+                    {
+                        Ok(candidate) => candidate,
+                        Err(RemoteError::NoMatchingVersion(..)) => {
+                            return Ok(SkillUpdate {
+                                id: skill.id.clone(),
+                                current: skill.version.clone(),
+                                available: None,
+                                updated: false,
+                            })
+                        }
+                        Err(e) => return Err(e),
+                    };
+                    let newer = current
+                        .as_ref()
+                        .is_none_or(|current| candidate.version() > current);
+                    let mut update = SkillUpdate {
+                        id: skill.id.clone(),
+                        current: skill.version.clone(),
+                        available: newer.then(|| candidate.version().to_string()),
+                        updated: false,
+                    };
+                    if newer && !check_only {
+                        self.place(
+                            &source,
+                            &skill.id,
+                            skill.pin.as_deref(),
+                            candidate,
+                            &staging,
+                        )
+                        .await?;
+                        update.updated = true;
+                    }
+                    Ok(update)
+                })
+                .await?;
+            updates.push(update);
+        }
+        Ok(updates)
+    }
+
+    /// Runs `f` with a fresh staging directory, removing it afterwards.
+    async fn staged<F, Fut, T>(&self, f: F) -> Result<T, RemoteError>
+    where
+        F: FnOnce(PathBuf) -> Fut,
+        Fut: std::future::Future<Output = Result<T, RemoteError>>,
+    {
+        std::fs::create_dir_all(&self.skills_dir)?;
+        let staging = self.skills_dir.join(format!(
+            ".staging-{}-{}",
+            std::process::id(),
+            self.staging.fetch_add(1, Ordering::SeqCst)
+        ));
+        std::fs::create_dir_all(&staging)?;
+        let result = f(staging.clone()).await;
+        if staging.exists() {
+            let _ = std::fs::remove_dir_all(&staging);
+        }
+        result
+    }
+
+    /// Finds the newest version of a skill matching `requirement`.
+    async fn resolve(
+        &self,
+        source: &SkillSource,
+        id: &str,
+        requirement: Option<&VersionReq>,
+        staging: &Path,
+    ) -> Result<Candidate, RemoteError> {
+        match source {
+            SkillSource::Index { url } => {
+                let index: SkillIndex = serde_json::from_slice(&download(url).await?)
+                    .map_err(|e| RemoteError::Index(e.to_string()))?;
+                if !index.skills.iter().any(|entry| entry.id == id) {
+                    return Err(RemoteError::NotFound(id.to_string(), source.to_string()));
+                }
+                let (version, entry) = index
+                    .select(id, requirement)
+                    .ok_or_else(|| no_matching_version(id, requirement))?;
+                let archive_url = reqwest::Url::parse(url)
+                    .and_then(|base| base.join(&entry.url))
+                    .map_err(|e| RemoteError::Index(format!("{}: {}", entry.url, e)))?;
+                Ok(Candidate::Archive {
+                    version,
+                    url: archive_url.to_string(),
+                    sha256: entry.sha256.clone(),
+                })
+            }
+            SkillSource::Git { url, reference } => {
+                let fetched = staging.join("source");
+                git_clone(url, reference.as_deref(), &fetched).await?;
+                let root = find_skill_root(&fetched, id)?
+                    .ok_or_else(|| RemoteError::NotFound(id.to_string(), source.to_string()))?;
+                let manifest = parse_manifest(&root.join(MANIFEST_FILE))?;
+                let version = parse_version(&manifest.version)
+                    .ok_or(ManifestError::InvalidField("version must be like '1.2.0'"))?;
+                if requirement.is_some_and(|requirement| !requirement.matches(&version)) {
+                    return Err(no_matching_version(id, requirement));
+                }
+                Ok(Candidate::Fetched { version, root })
+            }
+        }
+    }
+
+    /// Fetches a candidate if needed, validates it, and moves it into place.
+    async fn place(
+        &self,
+        source: &SkillSource,
+        id: &str,
+        pin: Option<&str>,
+        candidate: Candidate,
+        staging: &Path,
+    ) -> Result<InstalledSkill, RemoteError> {
+        let root = match candidate {
+            Candidate::Fetched { root, .. } => root,
+            Candidate::Archive { url, sha256, .. } => {
+                let bytes = download(&url).await?;
+                if let Some(expected) = sha256 {
+                    if !hex::encode(Sha256::digest(&bytes)).eq_ignore_ascii_case(expected.trim()) {
+                        return Err(RemoteError::Checksum(id.to_string()));
+                    }
+                }
+                let fetched = staging.join("archive");
+                unpack_archive(&bytes[..], &fetched)?;
+                find_skill_root(&fetched, id)?
+                    .ok_or_else(|| RemoteError::NotFound(id.to_string(), url.clone()))?
+            }
+        };
+
+        let manifest = parse_manifest(&root.join(MANIFEST_FILE))?;
+        // Copy rather than rename: the skill may be a subdirectory of a
+        // fetched repository, and its history is not needed
+        let prepared = staging.join("skill");
+        copy_dir(&root, &prepared)?;
+
+        let target = self.skills_dir.join(id);
+        if target.exists() {
+            std::fs::remove_dir_all(&target)?;
+        }
+        std::fs::rename(&prepared, &target)?;
+
+        let mut lock = self.lock()?;
+        lock.upsert(LockedSkill {
+            id: id.to_string(),
+            version: manifest.version.clone(),
+            source: source.to_string(),
+            pin: pin.map(str::to_string),
+        });
+        lock.save(&self.skills_dir)?;
+        info!(skill_id = %id, version = %manifest.version, source = %source, "Installed skill");
+
+        Ok(InstalledSkill {
+            id: id.to_string(),
+            version: manifest.version.clone(),
+            dir: target,
+            manifest,
+        })
+    }
+}
+
+fn parse_pin(pin: Option<&str>) -> Result<Option<VersionReq>, RemoteError> {
+    pin.map(|pin| {
+        VersionReq::parse(pin).map_err(|e| RemoteError::InvalidPin(pin.to_string(), e.to_string()))
+    })
+    .transpose()
+}
+
+fn no_matching_version(id: &str, requirement: Option<&VersionReq>) -> RemoteError {
+    let pin = requirement.map_or_else(|| "*".to_string(), |requirement| requirement.to_string());
+    RemoteError::NoMatchingVersion(id.to_string(), pin)
+}
+
+/// Finds the directory, at most three levels below `fetched`, whose
+/// manifest declares the skill `id`.
+fn find_skill_root(fetched: &Path, id: &str) -> Result<Option<PathBuf>, RemoteError> {
+    for entry in walkdir::WalkDir::new(fetched)
+        .max_depth(4)
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .filter_map(|entry| entry.ok())
+    {
+        if entry.file_type().is_file() && entry.file_name() == MANIFEST_FILE {
+            let manifest = parse_manifest(entry.path())?;
+            if manifest.id == id {
+                return Ok(entry.path().parent().map(Path::to_path_buf));
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write_skill(dir: &Path, id: &str, version: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(
+            dir.join(MANIFEST_FILE),
+            format!(
+                "id = \"{id}\"\nname = \"{id}\"\ndescription = \"Test skill\"\nversion = \"{version}\"\ncategory = \"Utility\"\n"
+            ),
+        )
+        .unwrap();
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn test_parse_source() {
+        assert_eq!(
+            SkillSource::parse("git+https://example.com/skills#v1").unwrap(),
+            SkillSource::Git {
+                url: "https://example.com/skills".to_string(),
+                reference: Some("v1".to_string()),
+            }
+        );
+        assert_eq!(
+            SkillSource::parse("git@github.com:org/skills.git").unwrap(),
+            SkillSource::Git {
+                url: "git@github.com:org/skills.git".to_string(),
+                reference: None,
+            }
+        );
+        assert_eq!(
+            SkillSource::parse("https://skills.example.com/index.json").unwrap(),
+            SkillSource::Index {
+                url: "https://skills.example.com/index.json".to_string(),
+            }
+        );
+        assert!(SkillSource::parse("./skills").is_err());
+        assert!(SkillSource::parse("git+--upload-pack=evil").is_err());
+
+        let source = SkillSource::parse("https://example.com/skills.git#main").unwrap();
+        assert_eq!(SkillSource::parse(&source.to_string()).unwrap(), source);
+    }
+
+    #[test]
+    fn test_index_select_respects_pin() {
+        let entry = |version: &str| IndexEntry {
+            id: "weather".to_string(),
+            version: version.to_string(),
+            url: format!("weather-{}.tar.gz", version),
+            sha256: None,
+        };
+        let index = SkillIndex {
+            skills: vec![
+                entry("1.2.0"),
+                entry("2.0.0"),
+                entry("1.3.1"),
+                entry("bogus"),
+            ],
+        };
+
+        let (version, _) = index.select("weather", None).unwrap();
+        assert_eq!(version, Version::new(2, 0, 0));
+        let pin = VersionReq::parse("^1.2").unwrap();
+        let (version, entry) = index.select("weather", Some(&pin)).unwrap();
+        assert_eq!(version, Version::new(1, 3, 1));
+        assert_eq!(entry.url, "weather-1.3.1.tar.gz");
+        assert!(index.select("news", None).is_none());
+    }
+
+    #[test]
+    fn test_lock_round_trip() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(SkillLock::load(dir.path()).unwrap(), SkillLock::default());
+
+        let mut lock = SkillLock::default();
+        let locked = |version: &str| LockedSkill {
+            id: "weather".to_string(),
+            version: version.to_string(),
+            source: "https://skills.example.com/index.json".to_string(),
+            pin: Some("^1".to_string()),
+        };
+        lock.upsert(locked("1.0.0"));
+        lock.upsert(locked("1.1.0"));
+        lock.save(dir.path()).unwrap();
+
+        let loaded = SkillLock::load(dir.path()).unwrap();
+        assert_eq!(loaded.skills, vec![locked("1.1.0")]);
+    }
+
+    #[tokio::test]
+    async fn test_install_and_update_from_git() {
+        let repo = TempDir::new().unwrap();
+        git(
+            repo.path(),
+            &["init", "--quiet", "--initial-branch", "main"],
+        );
+        write_skill(&repo.path().join("skills/weather"), "weather", "1.0.0");
+        git(repo.path(), &["add", "."]);
+        git(repo.path(), &["commit", "--quiet", "-m", "weather 1.0.0"]);
+
+        let skills = TempDir::new().unwrap();
+        let installer = SkillInstaller::new(skills.path());
+        let source = SkillSource::Git {
+            url: format!("file://{}", repo.path().display()),
+            reference: Some("main".to_string()),
+        };
+        let installed = installer
+            .install(&source, "weather", Some("^1"), false)
+            .await
+            .unwrap();
+        assert_eq!(installed.version, "1.0.0");
+        assert!(skills.path().join("weather").join(MANIFEST_FILE).is_file());
+        assert!(matches!(
+            installer.install(&source, "weather", None, false).await,
+            Err(RemoteError::AlreadyInstalled(_))
+        ));
+        assert!(matches!(
+            installer.install(&source, "news", None, true).await,
+            Err(RemoteError::NotFound(..))
+        ));
+
+        write_skill(&repo.path().join("skills/weather"), "weather", "1.1.0");
+        git(repo.path(), &["commit", "--quiet", "-am", "weather 1.1.0"]);
+        let updates = installer.update(&[], true).await.unwrap();
+        assert_eq!(updates[0].available.as_deref(), Some("1.1.0"));
+        assert!(!updates[0].updated);
+
+        let updates = installer
+            .update(&["weather".to_string()], false)
+            .await
+            .unwrap();
+        assert!(updates[0].updated);
+        assert_eq!(
+            installer.lock().unwrap().get("weather").unwrap().version,
+            "1.1.0"
+        );
+
+        // Outside the pin
+        write_skill(&repo.path().join("skills/weather"), "weather", "2.0.0");
+        git(repo.path(), &["commit", "--quiet", "-am", "weather 2.0.0"]);
+        let updates = installer.update(&[], false).await.unwrap();
+        assert_eq!(updates[0].available, None);
+        assert_eq!(
+            installer.lock().unwrap().get("weather").unwrap().version,
+            "1.1.0"
+        );
+        assert!(matches!(
+            installer.update(&["news".to_string()], false).await,
+            Err(RemoteError::NotInstalled(_))
+        ));
+        let entries: Vec<_> = fs::read_dir(skills.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(entries.len(), 2, "staging left behind: {:?}", entries);
+    }
+}
@@ -94,6 +94,7 @@ impl {struct_name} {{
                 required_env_vars: vec![],
                 required_binaries: vec![],
                 platform: None,
+                min_versions: Default::default(),
             }},
         }}
     }}
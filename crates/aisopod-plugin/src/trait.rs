@@ -17,6 +17,10 @@ use crate::{PluginApi, PluginContext, PluginMeta};
 /// 4. **Shutdown**: When the system is shutting down, `shutdown()` is called
 ///    to allow graceful cleanup.
 ///
+/// When a dynamic plugin is hot reloaded, the old instance's state is
+/// collected with `export_state()` before it is shut down, and handed to the
+/// replacement through `import_state()` once it has been initialized.
+///
 /// # Lifetime and Ownership
 ///
 /// Plugins must implement `Send + Sync` to support both compiled-in plugins
@@ -134,4 +138,24 @@ pub trait Plugin: Send + Sync + std::fmt::Debug {
     /// Errors during shutdown are logged but do not affect the shutdown
     /// process itself.
     async fn shutdown(&self) -> Result<(), Box<dyn Error>>;
+
+    /// Returns state to hand over to a reloaded instance of this plugin.
+    ///
+    /// Called on the old instance before it is shut down during a hot
+    /// reload. The default implementation carries no state over.
+    async fn export_state(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Restores state exported by the instance this one replaces.
+    ///
+    /// Called after `init()` when the plugin replaces a hot-reloaded
+    /// instance that exported state. The default implementation ignores it.
+    ///
+    /// # Errors
+    ///
+    /// Errors are logged; the replacement stays active without the state.
+    async fn import_state(&self, _state: serde_json::Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
 }
@@ -0,0 +1,494 @@
+//! WebAssembly component plugins.
+//!
+//! This module loads plugins compiled to WebAssembly components and runs
+//! them in a [wasmtime] sandbox. Unlike the shared libraries loaded by
+//! [`DynamicPluginLoader`], a WebAssembly plugin cannot touch the host
+//! beyond the functions it is given, and the same `.wasm` file runs on
+//! every platform.
+//!
+//! # Host API
+//!
+//! Plugins target the `plugin` world defined in `wit/plugin.wit`. The host
+//! provides a deliberately small API:
+//!
+//! - `log` - writes to the host log, prefixed with the plugin ID
+//! - `get-config` - returns a top-level key of the plugin's configuration
+//!   as JSON
+//! - `register-tool` - registers a tool, but only while `init` runs and
+//!   only for tools listed under `capabilities.tools` in the manifest
+//!
+//! The plugin exports `init`, called once when it is loaded, and
+//! `call-tool`, called with the tool name and JSON parameters whenever
+//! one of its tools is executed. Each call runs with a fuel budget and the
+//! plugin's memory is capped; a plugin that traps or runs out of fuel is
+//! disabled until it is loaded again.
+//!
+//! # Plugin Directory Structure
+//!
+//! WebAssembly plugins use the same layout as native plugins, with the
+//! component stored as `{entry_point}.wasm` next to the manifest:
+//!
+//! ```toml
+//! [plugin]
+//! id = "weather"
+//! name = "Weather"
+//! version = "0.1.0"
+//! description = "Weather lookups"
+//! author = "Author Name"
+//! entry_point = "weather"
+//!
+//! [capabilities]
+//! tools = ["forecast"]
+//! ```
+//!
+//! # Example
+//!
+//! ```ignore
+//! use aisopod_plugin::wasm::WasmPluginLoader;
+//! use std::path::PathBuf;
+//! use std::sync::Arc;
+//!
+//! let loader = WasmPluginLoader::new(vec![PathBuf::from("~/.aisopod/plugins")])?;
+//! for discovered in loader.discover()? {
+//!     let plugin = loader.load_plugin(&discovered, Arc::new(serde_json::json!({})))?;
+//!     registry.register_with_hooks(plugin).await?;
+//! }
+//! ```
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use aisopod_tools::{Tool, ToolContext, ToolResult};
+use async_trait::async_trait;
+use serde_json::Value;
+use wasmtime::component::{Component, Linker};
+use wasmtime::{Config, Engine, Store, StoreLimits, StoreLimitsBuilder, Trap};
+
+use crate::dynamic::{DiscoveredPlugin, DynamicPluginLoader, LoadError};
+use crate::signing::SignaturePolicy;
+use crate::{Plugin, PluginApi, PluginContext, PluginMeta};
+
+mod bindings {
+    wasmtime::component::bindgen!({
+        path: "wit/plugin.wit",
+        world: "plugin",
+    });
+}
+
+use bindings::aisopod::plugin::host::{self, LogLevel};
+
+/// Default fuel available to a plugin for each call into it.
+pub const DEFAULT_FUEL: u64 = 1_000_000_000;
+
+/// Default limit on a plugin's linear memory, in bytes.
+pub const DEFAULT_MAX_MEMORY: usize = 64 * 1024 * 1024;
+
+/// Longest log message accepted from a plugin, in bytes.
+const MAX_LOG_MESSAGE: usize = 4096;
+
+/// A tool registered by a plugin during `init`.
+struct ToolSpec {
+    name: String,
+    description: String,
+    schema: Value,
+}
+
+/// Per-plugin state reachable from the host API.
+struct HostState {
+    plugin_id: String,
+    config: Arc<Value>,
+    allowed_tools: Vec<String>,
+    registering: bool,
+    tools: Vec<ToolSpec>,
+    limits: StoreLimits,
+}
+
+impl host::Host for HostState {
+    fn log(&mut self, level: LogLevel, message: String) {
+        let message = truncate(&message, MAX_LOG_MESSAGE);
+        let id = &self.plugin_id;
+        match level {
+            LogLevel::Trace => tracing::trace!("[plugin {}] {}", id, message),
+            LogLevel::Debug => tracing::debug!("[plugin {}] {}", id, message),
+            LogLevel::Info => tracing::info!("[plugin {}] {}", id, message),
+            LogLevel::Warn => tracing::warn!("[plugin {}] {}", id, message),
+            LogLevel::Error => tracing::error!("[plugin {}] {}", id, message),
+        }
+    }
+
+    fn get_config(&mut self, key: String) -> Option<String> {
+        self.config.get(&key).map(Value::to_string)
+    }
+
+    fn register_tool(
+        &mut self,
+        name: String,
+        description: String,
+        parameters_schema: String,
+    ) -> Result<(), String> {
+        if !self.registering {
+            return Err("Tools can only be registered during init".to_string());
+        }
+        if !self.allowed_tools.contains(&name) {
+            return Err(format!(
+                "Tool '{}' is not declared in the plugin manifest",
+                name
+            ));
+        }
+        if self.tools.iter().any(|tool| tool.name == name) {
+            return Err(format!("Tool '{}' is already registered", name));
+        }
+        let schema = serde_json::from_str(&parameters_schema)
+            .map_err(|e| format!("Invalid parameters schema for tool '{}': {}", name, e))?;
+        self.tools.push(ToolSpec {
+            name,
+            description,
+            schema,
+        });
+        Ok(())
+    }
+}
+
+/// An instantiated plugin shared by its tools.
+struct WasmInstance {
+    plugin_id: String,
+    store: Mutex<Store<HostState>>,
+    bindings: bindings::Plugin,
+    fuel: u64,
+    trapped: AtomicBool,
+}
+
+impl WasmInstance {
+    /// Calls one of the plugin's tools, blocking until it returns.
+    fn call_tool(&self, name: &str, params: &str) -> Result<String, String> {
+        if self.trapped.load(Ordering::SeqCst) {
+            return Err(format!(
+                "Plugin '{}' is disabled after an earlier trap",
+                self.plugin_id
+            ));
+        }
+        let mut store = self
+            .store
+            .lock()
+            .map_err(|_| format!("Plugin '{}' state lock poisoned", self.plugin_id))?;
+        store.set_fuel(self.fuel).map_err(|e| e.to_string())?;
+        match self
+            .bindings
+            .aisopod_plugin_guest()
+            .call_call_tool(&mut *store, name, params)
+        {
+            Ok(result) => result,
+            Err(e) => {
+                self.trapped.store(true, Ordering::SeqCst);
+                Err(describe_trap(&self.plugin_id, &e))
+            }
+        }
+    }
+}
+
+/// A tool implemented by a WebAssembly plugin.
+pub struct WasmTool {
+    instance: Arc<WasmInstance>,
+    name: String,
+    description: String,
+    schema: Value,
+}
+
+impl std::fmt::Debug for WasmTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmTool")
+            .field("plugin", &self.instance.plugin_id)
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Tool for WasmTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> Value {
+        self.schema.clone()
+    }
+
+    async fn execute(&self, params: Value, _ctx: &ToolContext) -> anyhow::Result<ToolResult> {
+        let instance = self.instance.clone();
+        let name = self.name.clone();
+        let params = params.to_string();
+        let result =
+            tokio::task::spawn_blocking(move || instance.call_tool(&name, &params)).await?;
+        Ok(match result {
+            Ok(output) => ToolResult::success(output),
+            Err(message) => ToolResult::error(message),
+        })
+    }
+}
+
+/// A plugin loaded from a WebAssembly component.
+///
+/// The plugin's `init` export has already run when the plugin is loaded,
+/// so [`Plugin::register`] only hands its tools to the [`PluginApi`].
+pub struct WasmPlugin {
+    id: String,
+    meta: PluginMeta,
+    tools: Vec<Arc<WasmTool>>,
+}
+
+impl WasmPlugin {
+    /// Returns the tools the plugin registered.
+    pub fn tools(&self) -> &[Arc<WasmTool>] {
+        &self.tools
+    }
+}
+
+impl std::fmt::Debug for WasmPlugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmPlugin")
+            .field("id", &self.id)
+            .field("tools", &self.tools)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Plugin for WasmPlugin {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn meta(&self) -> &PluginMeta {
+        &self.meta
+    }
+
+    fn register(&self, api: &mut PluginApi) -> Result<(), Box<dyn std::error::Error>> {
+        for tool in &self.tools {
+            api.register_tool(tool.clone());
+        }
+        Ok(())
+    }
+
+    async fn init(&self, _ctx: &PluginContext) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(())
+    }
+}
+
+/// Loader for WebAssembly component plugins.
+///
+/// Plugins are discovered like native plugins, but only directories
+/// containing an `{entry_point}.wasm` component are considered. The file
+/// may also hold the component in WebAssembly text format.
+pub struct WasmPluginLoader {
+    loader: DynamicPluginLoader,
+    engine: Engine,
+    fuel: u64,
+    max_memory: usize,
+}
+
+impl WasmPluginLoader {
+    /// Creates a loader scanning the given plugin directories.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LoadError::WasmEngine` if the WebAssembly engine cannot be
+    /// created on this platform.
+    pub fn new(plugin_dirs: Vec<PathBuf>) -> Result<Self, LoadError> {
+        let mut config = Config::new();
+        config.wasm_component_model(true).consume_fuel(true);
+        let engine = Engine::new(&config).map_err(|e| LoadError::WasmEngine(e.to_string()))?;
+        Ok(Self {
+            loader: DynamicPluginLoader::new(plugin_dirs),
+            engine,
+            fuel: DEFAULT_FUEL,
+            max_memory: DEFAULT_MAX_MEMORY,
+        })
+    }
+
+    /// Sets the fuel available to a plugin for each call into it.
+    pub fn with_fuel(mut self, fuel: u64) -> Self {
+        self.fuel = fuel;
+        self
+    }
+
+    /// Sets the limit on a plugin's linear memory, in bytes.
+    pub fn with_max_memory(mut self, max_memory: usize) -> Self {
+        self.max_memory = max_memory;
+        self
+    }
+
+    /// Sets which plugin signatures are accepted.
+    ///
+    /// See [`DynamicPluginLoader::with_signature_policy()`].
+    pub fn with_signature_policy(mut self, policy: SignaturePolicy) -> Self {
+        self.loader = self.loader.with_signature_policy(policy);
+        self
+    }
+
+    /// Scans the plugin directories for WebAssembly plugins.
+    pub fn discover(&self) -> Result<Vec<DiscoveredPlugin>, LoadError> {
+        Ok(self
+            .loader
+            .discover()?
+            .into_iter()
+            .filter(|discovered| component_path(discovered).is_file())
+            .collect())
+    }
+
+    /// Instantiates a discovered plugin and runs its `init` export.
+    ///
+    /// `config` is the plugin's configuration, readable through
+    /// `get-config`. Compiling the component can take a while, so async
+    /// callers should run this on a blocking thread.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LoadError::VersionCompatibility` if the manifest excludes
+    /// this host version, `LoadError::Signature` if the signature policy
+    /// refuses the plugin, and `LoadError::Wasm` if the component cannot be
+    /// compiled or instantiated, or its `init` fails.
+    pub fn load_plugin(
+        &self,
+        discovered: &DiscoveredPlugin,
+        config: Arc<Value>,
+    ) -> Result<Arc<WasmPlugin>, LoadError> {
+        self.loader.check_version_compatibility(discovered)?;
+
+        let manifest = &discovered.manifest;
+        let plugin_id = manifest.plugin.id.clone();
+        let path = component_path(discovered);
+        let wasm_error = |message: String| LoadError::Wasm(path.clone(), message);
+        self.loader.verify_signature(
+            discovered,
+            &format!("{}.wasm", manifest.plugin.entry_point),
+            &path,
+        )?;
+
+        let component =
+            Component::from_file(&self.engine, &path).map_err(|e| wasm_error(e.to_string()))?;
+        let mut linker = Linker::new(&self.engine);
+        bindings::Plugin::add_to_linker(&mut linker, |state: &mut HostState| state)
+            .map_err(|e| wasm_error(e.to_string()))?;
+
+        let state = HostState {
+            plugin_id: plugin_id.clone(),
+            config,
+            allowed_tools: manifest
+                .capabilities
+                .as_ref()
+                .and_then(|capabilities| capabilities.tools.clone())
+                .unwrap_or_default(),
+            registering: true,
+            tools: Vec::new(),
+            limits: StoreLimitsBuilder::new()
+                .memory_size(self.max_memory)
+                .build(),
+        };
+        let mut store = Store::new(&self.engine, state);
+        store.limiter(|state| &mut state.limits);
+        store
+            .set_fuel(self.fuel)
+            .map_err(|e| wasm_error(e.to_string()))?;
+
+        let instance = bindings::Plugin::instantiate(&mut store, &component, &linker)
+            .map_err(|e| wasm_error(e.to_string()))?;
+        match instance.aisopod_plugin_guest().call_init(&mut store) {
+            Ok(Ok(())) => {}
+            Ok(Err(message)) => return Err(wasm_error(format!("init failed: {}", message))),
+            Err(e) => return Err(wasm_error(describe_trap(&plugin_id, &e))),
+        }
+
+        let state = store.data_mut();
+        state.registering = false;
+        let specs = std::mem::take(&mut state.tools);
+        let instance = Arc::new(WasmInstance {
+            plugin_id: plugin_id.clone(),
+            store: Mutex::new(store),
+            bindings: instance,
+            fuel: self.fuel,
+            trapped: AtomicBool::new(false),
+        });
+        let tools = specs
+            .into_iter()
+            .map(|spec| {
+                Arc::new(WasmTool {
+                    instance: instance.clone(),
+                    name: spec.name,
+                    description: spec.description,
+                    schema: spec.schema,
+                })
+            })
+            .collect();
+
+        Ok(Arc::new(WasmPlugin {
+            id: plugin_id,
+            meta: PluginMeta::new(
+                &manifest.plugin.name,
+                &manifest.plugin.version,
+                &manifest.plugin.description,
+                &manifest.plugin.author,
+                vec![],
+                vec![],
+            ),
+            tools,
+        }))
+    }
+}
+
+impl std::fmt::Debug for WasmPluginLoader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmPluginLoader")
+            .field("loader", &self.loader)
+            .field("fuel", &self.fuel)
+            .field("max_memory", &self.max_memory)
+            .finish()
+    }
+}
+
+/// Returns the path of a plugin's component file.
+fn component_path(discovered: &DiscoveredPlugin) -> PathBuf {
+    discovered
+        .dir
+        .join(format!("{}.wasm", discovered.manifest.plugin.entry_point))
+}
+
+/// Describes an error raised while running plugin code.
+fn describe_trap(plugin_id: &str, error: &anyhow::Error) -> String {
+    match error.downcast_ref::<Trap>() {
+        Some(Trap::OutOfFuel) => format!("Plugin '{}' ran out of fuel", plugin_id),
+        _ => format!("Plugin '{}' trapped: {}", plugin_id, error),
+    }
+}
+
+/// Truncates a string to at most `max` bytes on a character boundary.
+fn truncate(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("hello", 10), "hello");
+        assert_eq!(truncate("hello", 3), "hel");
+        assert_eq!(truncate("héllo", 2), "h");
+    }
+}
@@ -0,0 +1,246 @@
+//! Tests for channels contributed by plugins.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use aisopod_channel::adapters::{AccountSnapshot, ChannelConfigAdapter, SecurityAdapter};
+use aisopod_channel::{ChannelCapabilities, ChannelMeta, ChannelPlugin, ChannelRegistry};
+use aisopod_plugin::channel::{ChannelError, ChannelFactory, PluginChannels};
+use aisopod_plugin::{Plugin, PluginApi, PluginContext, PluginMeta, PluginRegistry};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+/// Counts lifecycle calls across all channels of a test.
+#[derive(Debug, Default)]
+struct Lifecycle {
+    connects: AtomicUsize,
+    disconnects: AtomicUsize,
+}
+
+struct NoAccounts;
+
+impl ChannelConfigAdapter for NoAccounts {
+    fn list_accounts(&self) -> anyhow::Result<Vec<String>> {
+        Ok(vec![])
+    }
+
+    fn resolve_account(&self, id: &str) -> anyhow::Result<AccountSnapshot> {
+        Err(anyhow::anyhow!("Account not found: {}", id))
+    }
+
+    fn enable_account(&self, _id: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn disable_account(&self, _id: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn delete_account(&self, _id: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+struct EchoChannel {
+    id: String,
+    meta: ChannelMeta,
+    capabilities: ChannelCapabilities,
+    lifecycle: Arc<Lifecycle>,
+    connected: bool,
+}
+
+#[async_trait]
+impl ChannelPlugin for EchoChannel {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn meta(&self) -> &ChannelMeta {
+        &self.meta
+    }
+
+    fn capabilities(&self) -> &ChannelCapabilities {
+        &self.capabilities
+    }
+
+    fn config(&self) -> &dyn ChannelConfigAdapter {
+        &NoAccounts
+    }
+
+    fn security(&self) -> Option<&dyn SecurityAdapter> {
+        None
+    }
+
+    async fn connect(&mut self) -> anyhow::Result<()> {
+        self.connected = true;
+        self.lifecycle.connects.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> anyhow::Result<()> {
+        assert!(self.connected);
+        self.lifecycle.disconnects.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+struct EchoFactory {
+    lifecycle: Arc<Lifecycle>,
+}
+
+#[async_trait]
+impl ChannelFactory for EchoFactory {
+    fn channel_type(&self) -> &str {
+        "echo"
+    }
+
+    async fn create(
+        &self,
+        account_id: &str,
+        config: &Value,
+    ) -> anyhow::Result<Box<dyn ChannelPlugin>> {
+        let label = config["label"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("missing label"))?;
+        Ok(Box::new(EchoChannel {
+            id: format!("echo-{}", account_id),
+            meta: ChannelMeta {
+                label: label.to_string(),
+                docs_url: None,
+                ui_hints: json!({}),
+            },
+            capabilities: ChannelCapabilities::default(),
+            lifecycle: self.lifecycle.clone(),
+            connected: false,
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct EchoPlugin {
+    meta: PluginMeta,
+    lifecycle: Arc<Lifecycle>,
+}
+
+#[async_trait]
+impl Plugin for EchoPlugin {
+    fn id(&self) -> &str {
+        "echo-plugin"
+    }
+
+    fn meta(&self) -> &PluginMeta {
+        &self.meta
+    }
+
+    fn register(&self, api: &mut PluginApi) -> Result<(), Box<dyn std::error::Error>> {
+        api.register_channel_factory(Arc::new(EchoFactory {
+            lifecycle: self.lifecycle.clone(),
+        }));
+        Ok(())
+    }
+
+    async fn init(&self, _ctx: &PluginContext) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(())
+    }
+}
+
+async fn registry_with_plugin(lifecycle: Arc<Lifecycle>) -> PluginRegistry {
+    let mut registry = PluginRegistry::new();
+    registry
+        .register_with_hooks(Arc::new(EchoPlugin {
+            meta: PluginMeta::new("echo-plugin", "1.0.0", "Echo", "Test", vec![], vec![]),
+            lifecycle,
+        }))
+        .await
+        .unwrap();
+    registry
+}
+
+#[tokio::test]
+async fn test_channel_lifecycle() {
+    let lifecycle = Arc::new(Lifecycle::default());
+    let plugins = registry_with_plugin(lifecycle.clone()).await;
+    assert_eq!(plugins.channel_types(), vec!["echo"]);
+    let mut channels = ChannelRegistry::new();
+    let mut running = PluginChannels::new();
+
+    let id = running
+        .start(
+            &plugins,
+            "echo",
+            "work",
+            &json!({"label": "Work"}),
+            &mut channels,
+        )
+        .await
+        .unwrap();
+    assert_eq!(id, "echo-work");
+    assert_eq!(lifecycle.connects.load(Ordering::SeqCst), 1);
+    assert_eq!(channels.get("echo-work").unwrap().meta().label, "Work");
+
+    let err = running
+        .start(
+            &plugins,
+            "echo",
+            "work",
+            &json!({"label": "Work"}),
+            &mut channels,
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ChannelError::AlreadyRunning(id) if id == "echo-work"));
+
+    running.stop("echo-work", &mut channels).await.unwrap();
+    assert_eq!(lifecycle.disconnects.load(Ordering::SeqCst), 1);
+    assert!(!channels.contains("echo-work"));
+    assert!(running.running().is_empty());
+}
+
+#[tokio::test]
+async fn test_stop_plugin_channels() {
+    let lifecycle = Arc::new(Lifecycle::default());
+    let plugins = registry_with_plugin(lifecycle.clone()).await;
+    let mut channels = ChannelRegistry::new();
+    let mut running = PluginChannels::new();
+
+    for account in ["a", "b"] {
+        running
+            .start(
+                &plugins,
+                "echo",
+                account,
+                &json!({"label": account}),
+                &mut channels,
+            )
+            .await
+            .unwrap();
+    }
+    assert_eq!(running.stop_plugin("echo-plugin", &mut channels).await, 2);
+    assert_eq!(lifecycle.disconnects.load(Ordering::SeqCst), 2);
+    assert!(channels.list().is_empty());
+}
+
+#[tokio::test]
+async fn test_start_errors() {
+    let lifecycle = Arc::new(Lifecycle::default());
+    let plugins = registry_with_plugin(lifecycle.clone()).await;
+    let mut channels = ChannelRegistry::new();
+    let mut running = PluginChannels::new();
+
+    let err = running
+        .start(&plugins, "matrix", "work", &json!({}), &mut channels)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ChannelError::UnknownType(_)));
+
+    let err = running
+        .start(&plugins, "echo", "work", &json!({}), &mut channels)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ChannelError::Create(..)));
+    assert_eq!(lifecycle.connects.load(Ordering::SeqCst), 0);
+}
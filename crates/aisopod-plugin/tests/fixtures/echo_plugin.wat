@@ -0,0 +1,108 @@
+;; Test plugin for the WebAssembly runtime, written directly in the
+;; component text format.
+;;
+;; `init` logs a message, reads the "greeting" config key, registers the
+;; "echo" and "spin" tools and checks that registering the undeclared
+;; "forbidden" tool is refused. "echo" returns the greeting followed by the
+;; JSON parameters; "spin" loops forever.
+(component
+  (import "aisopod:plugin/host@0.1.0" (instance $host
+    (type $level (enum "trace" "debug" "info" "warn" "error"))
+    (export "log-level" (type $log-level (eq $level)))
+    (export "log" (func (param "level" $log-level) (param "message" string)))
+    (export "get-config" (func (param "key" string) (result (option string))))
+    (export "register-tool" (func (param "name" string) (param "description" string) (param "parameters-schema" string) (result (result (error string)))))
+  ))
+
+  (core module $Libc
+    (memory (export "memory") 1)
+    (global $heap (mut i32) (i32.const 1024))
+    (func (export "realloc") (param $old i32) (param $old_size i32) (param $align i32) (param $new_size i32) (result i32)
+      (local $ret i32)
+      (local.set $ret
+        (i32.and
+          (i32.add (global.get $heap) (i32.sub (local.get $align) (i32.const 1)))
+          (i32.sub (i32.const 0) (local.get $align))))
+      (global.set $heap (i32.add (local.get $ret) (local.get $new_size)))
+      (if (local.get $old_size)
+        (then (memory.copy (local.get $ret) (local.get $old) (local.get $old_size))))
+      (local.get $ret))
+  )
+
+  (core module $Main
+    (import "host" "log" (func $log (param i32 i32 i32)))
+    (import "host" "get-config" (func $get_config (param i32 i32 i32)))
+    (import "host" "register-tool" (func $register (param i32 i32 i32 i32 i32 i32 i32)))
+    (import "libc" "memory" (memory 1))
+    (import "libc" "realloc" (func $realloc (param i32 i32 i32 i32) (result i32)))
+    (global $greet_ptr (mut i32) (i32.const 0))
+    (global $greet_len (mut i32) (i32.const 0))
+    (data (i32.const 16) "starting")
+    (data (i32.const 32) "greeting")
+    (data (i32.const 48) "echo")
+    (data (i32.const 56) "spin")
+    (data (i32.const 64) "Echo text back")
+    (data (i32.const 96) "{\"type\":\"object\",\"properties\":{\"text\":{\"type\":\"string\"}},\"required\":[\"text\"]}")
+    (data (i32.const 256) "forbidden")
+    (data (i32.const 272) "capability check failed")
+
+    (func (export "init") (result i32)
+      (call $log (i32.const 2) (i32.const 16) (i32.const 8))
+      (call $get_config (i32.const 32) (i32.const 8) (i32.const 512))
+      (if (i32.load8_u (i32.const 512))
+        (then
+          (global.set $greet_ptr (i32.load (i32.const 516)))
+          (global.set $greet_len (i32.load (i32.const 520)))))
+      (call $register (i32.const 48) (i32.const 4) (i32.const 64) (i32.const 14) (i32.const 96) (i32.const 77) (i32.const 528))
+      (if (i32.load8_u (i32.const 528))
+        (then (return (i32.const 528))))
+      (call $register (i32.const 56) (i32.const 4) (i32.const 64) (i32.const 14) (i32.const 96) (i32.const 77) (i32.const 528))
+      (if (i32.load8_u (i32.const 528))
+        (then (return (i32.const 528))))
+      ;; Tools missing from the manifest must be refused
+      (call $register (i32.const 256) (i32.const 9) (i32.const 64) (i32.const 14) (i32.const 96) (i32.const 77) (i32.const 544))
+      (if (i32.eqz (i32.load8_u (i32.const 544)))
+        (then
+          (i32.store8 (i32.const 560) (i32.const 1))
+          (i32.store (i32.const 564) (i32.const 272))
+          (i32.store (i32.const 568) (i32.const 23))
+          (return (i32.const 560))))
+      (i32.store8 (i32.const 576) (i32.const 0))
+      (i32.const 576))
+
+    (func (export "call-tool") (param $np i32) (param $nl i32) (param $pp i32) (param $pl i32) (result i32)
+      (local $out i32)
+      (local $len i32)
+      ;; "spin" never returns
+      (if (i32.eq (i32.load8_u (local.get $np)) (i32.const 115))
+        (then (loop $spin (br $spin))))
+      (local.set $len (i32.add (global.get $greet_len) (local.get $pl)))
+      (local.set $out (call $realloc (i32.const 0) (i32.const 0) (i32.const 1) (local.get $len)))
+      (memory.copy (local.get $out) (global.get $greet_ptr) (global.get $greet_len))
+      (memory.copy (i32.add (local.get $out) (global.get $greet_len)) (local.get $pp) (local.get $pl))
+      (i32.store8 (i32.const 592) (i32.const 0))
+      (i32.store (i32.const 596) (local.get $out))
+      (i32.store (i32.const 600) (local.get $len))
+      (i32.const 592))
+  )
+
+  (core instance $libc (instantiate $Libc))
+  (core func $log (canon lower (func $host "log") (memory $libc "memory")))
+  (core func $get_config (canon lower (func $host "get-config") (memory $libc "memory") (realloc (func $libc "realloc"))))
+  (core func $register (canon lower (func $host "register-tool") (memory $libc "memory") (realloc (func $libc "realloc"))))
+  (core instance $main (instantiate $Main
+    (with "host" (instance
+      (export "log" (func $log))
+      (export "get-config" (func $get_config))
+      (export "register-tool" (func $register))))
+    (with "libc" (instance $libc))))
+
+  (func $init (result (result (error string)))
+    (canon lift (core func $main "init") (memory $libc "memory") (realloc (func $libc "realloc"))))
+  (func $call_tool (param "name" string) (param "params" string) (result (result string (error string)))
+    (canon lift (core func $main "call-tool") (memory $libc "memory") (realloc (func $libc "realloc"))))
+  (instance $guest
+    (export "init" (func $init))
+    (export "call-tool" (func $call_tool)))
+  (export "aisopod:plugin/guest@0.1.0" (instance $guest))
+)
@@ -0,0 +1,169 @@
+//! Tests for plugin health monitoring.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use aisopod_plugin::{
+    HealthAction, HealthPolicy, Hook, HookContext, HookHandler, Plugin, PluginApi, PluginContext,
+    PluginMeta, PluginRegistry, PluginState,
+};
+use async_trait::async_trait;
+
+/// Counts calls into the plugin.
+#[derive(Debug, Default)]
+struct Counters {
+    hook_calls: AtomicUsize,
+    inits: AtomicUsize,
+    shutdowns: AtomicUsize,
+}
+
+/// Hook handler that panics on every call.
+struct PanickingHandler {
+    counters: Arc<Counters>,
+}
+
+#[async_trait]
+impl HookHandler for PanickingHandler {
+    async fn handle(&self, _ctx: &HookContext) -> Result<(), Box<dyn std::error::Error>> {
+        self.counters.hook_calls.fetch_add(1, Ordering::SeqCst);
+        panic!("hook exploded");
+    }
+}
+
+#[derive(Debug)]
+struct FlakyPlugin {
+    meta: PluginMeta,
+    counters: Arc<Counters>,
+}
+
+#[async_trait]
+impl Plugin for FlakyPlugin {
+    fn id(&self) -> &str {
+        "flaky"
+    }
+
+    fn meta(&self) -> &PluginMeta {
+        &self.meta
+    }
+
+    fn register(&self, api: &mut PluginApi) -> Result<(), Box<dyn std::error::Error>> {
+        api.register_hook(
+            Hook::BeforeAgentRun,
+            "flaky".to_string(),
+            Arc::new(PanickingHandler {
+                counters: self.counters.clone(),
+            }),
+        );
+        Ok(())
+    }
+
+    async fn init(&self, _ctx: &PluginContext) -> Result<(), Box<dyn std::error::Error>> {
+        self.counters.inits.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.counters.shutdowns.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+async fn setup(action: HealthAction) -> (PluginRegistry, PluginContext, Arc<Counters>) {
+    let counters = Arc::new(Counters::default());
+    let mut registry = PluginRegistry::new();
+    registry
+        .register_with_hooks(Arc::new(FlakyPlugin {
+            meta: PluginMeta::new("flaky", "1.0.0", "Flaky", "Test", vec![], vec![]),
+            counters: counters.clone(),
+        }))
+        .await
+        .unwrap();
+    registry.set_health_policy(HealthPolicy {
+        max_panics: 1,
+        action,
+        max_restarts: 1,
+        ..Default::default()
+    });
+    let ctx = PluginContext::new(Arc::new(serde_json::json!({})), std::path::PathBuf::new());
+    registry.init_all(&ctx).await.unwrap();
+    (registry, ctx, counters)
+}
+
+async fn dispatch(registry: &PluginRegistry, times: usize) {
+    for _ in 0..times {
+        registry
+            .hook_registry()
+            .dispatch(&HookContext::new(Hook::BeforeAgentRun))
+            .await;
+    }
+}
+
+#[tokio::test]
+async fn test_panics_are_caught_and_reported() {
+    let (mut registry, ctx, _) = setup(HealthAction::Report).await;
+    dispatch(&registry, 1).await;
+
+    let health = registry.health().get("flaky").unwrap();
+    // One init and one hook call
+    assert_eq!(health.calls, 2);
+    assert_eq!(health.panics, 1);
+    assert_eq!(
+        health.last_error.as_deref(),
+        Some("panicked: hook exploded")
+    );
+    assert!(registry.check_health(&ctx).await.is_empty());
+
+    dispatch(&registry, 1).await;
+    assert_eq!(
+        registry.check_health(&ctx).await,
+        vec![("flaky".to_string(), HealthAction::Report)]
+    );
+    assert_eq!(
+        registry.health().get("flaky").unwrap().state,
+        PluginState::Unhealthy
+    );
+}
+
+#[tokio::test]
+async fn test_unhealthy_plugin_is_disabled() {
+    let (mut registry, ctx, counters) = setup(HealthAction::Disable).await;
+    dispatch(&registry, 2).await;
+
+    assert_eq!(
+        registry.check_health(&ctx).await,
+        vec![("flaky".to_string(), HealthAction::Disable)]
+    );
+    assert!(registry.health().is_disabled("flaky"));
+    assert_eq!(counters.shutdowns.load(Ordering::SeqCst), 1);
+    assert_eq!(registry.hook_registry().total_hook_count(), 0);
+
+    dispatch(&registry, 1).await;
+    assert_eq!(counters.hook_calls.load(Ordering::SeqCst), 2);
+    // Already shut down
+    registry.shutdown_all().await.unwrap();
+    assert_eq!(counters.shutdowns.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_unhealthy_plugin_is_restarted_then_disabled() {
+    let (mut registry, ctx, counters) = setup(HealthAction::Restart).await;
+    dispatch(&registry, 2).await;
+
+    assert_eq!(
+        registry.check_health(&ctx).await,
+        vec![("flaky".to_string(), HealthAction::Restart)]
+    );
+    assert_eq!(counters.inits.load(Ordering::SeqCst), 2);
+    let health = registry.health().get("flaky").unwrap();
+    assert_eq!(health.restarts, 1);
+    assert_eq!(health.panics, 0);
+    assert_eq!(registry.hook_registry().total_hook_count(), 1);
+
+    // Out of restarts
+    dispatch(&registry, 2).await;
+    assert_eq!(
+        registry.check_health(&ctx).await,
+        vec![("flaky".to_string(), HealthAction::Disable)]
+    );
+    assert!(registry.health().is_disabled("flaky"));
+}
@@ -0,0 +1,176 @@
+//! Tests for plugin installation.
+
+use std::path::Path;
+use std::process::Command;
+
+use aisopod_plugin::install::{InstallError, PluginInstaller, PluginSource};
+use aisopod_plugin::LoadError;
+
+/// Writes a WebAssembly plugin into `dir`.
+fn write_plugin(dir: &Path, id: &str, version: &str, compatibility: &str) {
+    std::fs::create_dir_all(dir).unwrap();
+    std::fs::write(
+        dir.join("aisopod.plugin.toml"),
+        format!(
+            r#"
+            [plugin]
+            id = "{id}"
+            name = "Weather"
+            version = "{version}"
+            description = "Weather lookups"
+            author = "Test"
+            entry_point = "weather"
+            {compatibility}
+            "#
+        ),
+    )
+    .unwrap();
+    std::fs::write(dir.join("weather.wasm"), "(component)").unwrap();
+}
+
+/// Packs `dir` into a `.tar.gz` file below a `name` top-level directory.
+fn write_archive(dir: &Path, name: &str, archive: &Path) {
+    let file = std::fs::File::create(archive).unwrap();
+    let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    builder.append_dir_all(name, dir).unwrap();
+    builder.into_inner().unwrap().finish().unwrap();
+}
+
+fn installed_entries(plugins_dir: &Path) -> Vec<String> {
+    let mut entries: Vec<String> = std::fs::read_dir(plugins_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    entries.sort();
+    entries
+}
+
+#[tokio::test]
+async fn test_install_from_local_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("weather-src");
+    write_plugin(&source, "weather", "0.1.0", "");
+    let installer = PluginInstaller::new(dir.path().join("plugins"));
+
+    let installed = unsafe {
+        installer
+            .install(&PluginSource::Local(source.clone()), false)
+            .await
+            .unwrap()
+    };
+    assert_eq!(installed.id, "weather");
+    assert_eq!(installed.version, "0.1.0");
+    assert_eq!(installed.dir, dir.path().join("plugins/weather"));
+    assert!(installed.dir.join("weather.wasm").is_file());
+    // The source is copied, not moved
+    assert!(source.join("aisopod.plugin.toml").is_file());
+
+    // Installing again requires force
+    let err = unsafe {
+        installer
+            .install(&PluginSource::Local(source.clone()), false)
+            .await
+            .unwrap_err()
+    };
+    assert!(matches!(err, InstallError::AlreadyInstalled(id) if id == "weather"));
+
+    write_plugin(&source, "weather", "0.2.0", "");
+    let installed = unsafe {
+        installer
+            .install(&PluginSource::Local(source), true)
+            .await
+            .unwrap()
+    };
+    assert_eq!(installed.version, "0.2.0");
+    assert_eq!(installed_entries(installer.plugins_dir()), vec!["weather"]);
+}
+
+#[tokio::test]
+async fn test_install_from_archive() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("weather-src");
+    write_plugin(&source, "weather", "1.0.0", "");
+    let archive = dir.path().join("weather-1.0.0.tar.gz");
+    write_archive(&source, "weather-1.0.0", &archive);
+    let installer = PluginInstaller::new(dir.path().join("plugins"));
+
+    let source = PluginSource::parse(archive.to_str().unwrap()).unwrap();
+    let installed = unsafe { installer.install(&source, false).await.unwrap() };
+    assert_eq!(installed.id, "weather");
+    assert!(installed.dir.join("aisopod.plugin.toml").is_file());
+}
+
+#[tokio::test]
+async fn test_install_from_git() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path().join("repo");
+    write_plugin(&repo, "weather", "0.3.0", "");
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(&repo)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    };
+    git(&["init", "--quiet", "--initial-branch=main"]);
+    git(&["add", "."]);
+    git(&["commit", "--quiet", "-m", "Release"]);
+    git(&["tag", "v0.3.0"]);
+    let installer = PluginInstaller::new(dir.path().join("plugins"));
+
+    let source = PluginSource::parse(&format!("git+file://{}#v0.3.0", repo.display())).unwrap();
+    let installed = unsafe { installer.install(&source, false).await.unwrap() };
+    assert_eq!(installed.version, "0.3.0");
+    assert!(!installed.dir.join(".git").exists());
+}
+
+#[tokio::test]
+async fn test_rejected_plugins_leave_nothing_behind() {
+    let dir = tempfile::tempdir().unwrap();
+    let installer = PluginInstaller::new(dir.path().join("plugins"));
+
+    let incompatible = dir.path().join("incompatible");
+    write_plugin(
+        &incompatible,
+        "weather",
+        "0.1.0",
+        "[compatibility]\nmin_host_version = \"99.0.0\"",
+    );
+    let err = unsafe {
+        installer
+            .install(&PluginSource::Local(incompatible), false)
+            .await
+            .unwrap_err()
+    };
+    assert!(matches!(
+        err,
+        InstallError::Load(LoadError::VersionCompatibility { .. })
+    ));
+
+    let missing_entry = dir.path().join("missing-entry");
+    write_plugin(&missing_entry, "weather", "0.1.0", "");
+    std::fs::remove_file(missing_entry.join("weather.wasm")).unwrap();
+    let err = unsafe {
+        installer
+            .install(&PluginSource::Local(missing_entry), false)
+            .await
+            .unwrap_err()
+    };
+    assert!(matches!(err, InstallError::MissingEntryPoint(..)));
+
+    let no_manifest = dir.path().join("no-manifest");
+    std::fs::create_dir_all(&no_manifest).unwrap();
+    let err = unsafe {
+        installer
+            .install(&PluginSource::Local(no_manifest), false)
+            .await
+            .unwrap_err()
+    };
+    assert!(matches!(err, InstallError::MissingManifest));
+
+    assert!(installed_entries(installer.plugins_dir()).is_empty());
+}
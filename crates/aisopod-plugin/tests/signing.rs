@@ -0,0 +1,158 @@
+//! Tests for plugin signature verification.
+
+use std::path::Path;
+
+use aisopod_plugin::install::{InstallError, PluginInstaller, PluginSource};
+use aisopod_plugin::signing::{
+    encode_public_key, generate_signing_key, sign_plugin, SignatureError, SignaturePolicy,
+    SIGNATURE_FILE,
+};
+use aisopod_plugin::{DynamicPluginLoader, LoadError};
+
+/// Writes a WebAssembly plugin into `dir`.
+fn write_plugin(dir: &Path) {
+    std::fs::create_dir_all(dir).unwrap();
+    std::fs::write(
+        dir.join("aisopod.plugin.toml"),
+        r#"
+        [plugin]
+        id = "weather"
+        name = "Weather"
+        version = "0.1.0"
+        description = "Weather lookups"
+        author = "Test"
+        entry_point = "weather"
+        "#,
+    )
+    .unwrap();
+    std::fs::write(dir.join("weather.wasm"), "(component)").unwrap();
+}
+
+async fn install(
+    policy: SignaturePolicy,
+    source: &Path,
+    plugins_dir: &Path,
+) -> Result<(), InstallError> {
+    let installer = PluginInstaller::new(plugins_dir).with_signature_policy(policy);
+    unsafe {
+        installer
+            .install(&PluginSource::Local(source.to_path_buf()), true)
+            .await
+            .map(|_| ())
+    }
+}
+
+fn signature_error(err: InstallError) -> SignatureError {
+    match err {
+        InstallError::Load(LoadError::Signature(id, e)) => {
+            assert_eq!(id, "weather");
+            e
+        }
+        other => panic!("Expected a signature error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_signed_plugin_is_accepted() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("weather");
+    write_plugin(&source);
+    let key = generate_signing_key();
+    let signature = sign_plugin(&source, &key).unwrap();
+    assert_eq!(
+        signature.files.keys().collect::<Vec<_>>(),
+        vec!["aisopod.plugin.toml", "weather.wasm"]
+    );
+    assert!(source.join(SIGNATURE_FILE).is_file());
+
+    let policy = SignaturePolicy::new([encode_public_key(&key.verifying_key())])
+        .unwrap()
+        .require_signatures(true);
+    install(policy, &source, &dir.path().join("plugins"))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_tampered_plugin_is_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("weather");
+    write_plugin(&source);
+    sign_plugin(&source, &generate_signing_key()).unwrap();
+    std::fs::write(source.join("weather.wasm"), "(component (core module))").unwrap();
+
+    // Even the default policy refuses a plugin that no longer matches its signature
+    let err = install(
+        SignaturePolicy::default(),
+        &source,
+        &dir.path().join("plugins"),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(
+        signature_error(err),
+        SignatureError::HashMismatch(file) if file == "weather.wasm"
+    ));
+}
+
+#[tokio::test]
+async fn test_required_signatures_refuse_unsigned_and_untrusted_plugins() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("weather");
+    write_plugin(&source);
+    let trusted = generate_signing_key();
+    let policy = SignaturePolicy::new([encode_public_key(&trusted.verifying_key())])
+        .unwrap()
+        .require_signatures(true);
+    let plugins_dir = dir.path().join("plugins");
+
+    // Unsigned plugins only load without required signatures
+    install(SignaturePolicy::default(), &source, &plugins_dir)
+        .await
+        .unwrap();
+    let err = install(policy.clone(), &source, &plugins_dir)
+        .await
+        .unwrap_err();
+    assert!(matches!(signature_error(err), SignatureError::Unsigned));
+
+    sign_plugin(&source, &generate_signing_key()).unwrap();
+    install(SignaturePolicy::default(), &source, &plugins_dir)
+        .await
+        .unwrap();
+    let err = install(policy, &source, &plugins_dir).await.unwrap_err();
+    assert!(matches!(
+        signature_error(err),
+        SignatureError::UntrustedKey(_)
+    ));
+}
+
+#[test]
+fn test_native_library_is_checked_before_loading() {
+    let dir = tempfile::tempdir().unwrap();
+    let plugin_dir = dir.path().join("weather");
+    write_plugin(&plugin_dir);
+    std::fs::remove_file(plugin_dir.join("weather.wasm")).unwrap();
+    let loader = DynamicPluginLoader::new(vec![dir.path().to_path_buf()]);
+    let discovered = loader.discover().unwrap();
+    // Not a real library; the signature check refuses it before it is opened
+    let library = loader_library(&plugin_dir);
+    std::fs::write(&library, "not a library").unwrap();
+    sign_plugin(&plugin_dir, &generate_signing_key()).unwrap();
+    std::fs::write(&library, "tampered").unwrap();
+
+    let err = unsafe { loader.load_plugin(&discovered[0]) }.unwrap_err();
+    assert!(matches!(
+        err,
+        LoadError::Signature(_, SignatureError::HashMismatch(_))
+    ));
+}
+
+fn loader_library(dir: &Path) -> std::path::PathBuf {
+    if cfg!(target_os = "windows") {
+        dir.join("weather.dll")
+    } else if cfg!(target_os = "macos") {
+        dir.join("libweather.dylib")
+    } else {
+        dir.join("libweather.so")
+    }
+}
@@ -0,0 +1,133 @@
+//! Tests for WebAssembly component plugins.
+
+#![cfg(feature = "wasm")]
+
+use std::path::Path;
+use std::sync::Arc;
+
+use aisopod_plugin::wasm::WasmPluginLoader;
+use aisopod_plugin::{LoadError, Plugin, PluginApi};
+use aisopod_tools::{Tool, ToolContext};
+use serde_json::json;
+
+const ECHO_PLUGIN: &str = include_str!("fixtures/echo_plugin.wat");
+
+/// Writes a plugin directory holding the echo component.
+fn write_plugin(root: &Path, id: &str, tools: &[&str]) {
+    let dir = root.join(id);
+    std::fs::create_dir_all(&dir).unwrap();
+    let tools = tools
+        .iter()
+        .map(|tool| format!("\"{}\"", tool))
+        .collect::<Vec<_>>()
+        .join(", ");
+    std::fs::write(
+        dir.join("aisopod.plugin.toml"),
+        format!(
+            r#"
+            [plugin]
+            id = "{id}"
+            name = "Echo"
+            version = "0.1.0"
+            description = "Echoes its parameters"
+            author = "Test"
+            entry_point = "echo"
+
+            [capabilities]
+            tools = [{tools}]
+            "#
+        ),
+    )
+    .unwrap();
+    // The component is stored in text format, which the loader accepts too
+    std::fs::write(dir.join("echo.wasm"), ECHO_PLUGIN).unwrap();
+}
+
+#[tokio::test]
+async fn test_load_plugin_and_call_tool() {
+    let dir = tempfile::tempdir().unwrap();
+    write_plugin(dir.path(), "echo", &["echo", "spin"]);
+    let loader = WasmPluginLoader::new(vec![dir.path().to_path_buf()]).unwrap();
+
+    let discovered = loader.discover().unwrap();
+    assert_eq!(discovered.len(), 1);
+    let plugin = loader
+        .load_plugin(&discovered[0], Arc::new(json!({"greeting": "hi"})))
+        .unwrap();
+    assert_eq!(plugin.id(), "echo");
+    assert_eq!(plugin.meta().version, "0.1.0");
+
+    let mut api = PluginApi::new();
+    plugin.register(&mut api).unwrap();
+    let names: Vec<&str> = api.tools().iter().map(|tool| tool.name()).collect();
+    assert_eq!(names, vec!["echo", "spin"]);
+
+    let echo = &api.tools()[0];
+    assert_eq!(echo.description(), "Echo text back");
+    assert_eq!(echo.parameters_schema()["required"], json!(["text"]));
+
+    // The greeting config value is returned as JSON ahead of the parameters
+    let result = echo
+        .execute(
+            json!({"text": "hello"}),
+            &ToolContext::new("agent-1", "session-1"),
+        )
+        .await
+        .unwrap();
+    assert!(!result.is_error, "{}", result.content);
+    assert_eq!(result.content, r#""hi"{"text":"hello"}"#);
+}
+
+#[tokio::test]
+async fn test_runaway_plugin_is_stopped_and_disabled() {
+    let dir = tempfile::tempdir().unwrap();
+    write_plugin(dir.path(), "echo", &["echo", "spin"]);
+    let loader = WasmPluginLoader::new(vec![dir.path().to_path_buf()])
+        .unwrap()
+        .with_fuel(100_000);
+    let discovered = loader.discover().unwrap();
+    let plugin = loader
+        .load_plugin(&discovered[0], Arc::new(json!({})))
+        .unwrap();
+    let ctx = ToolContext::new("agent-1", "session-1");
+
+    let spin = &plugin.tools()[1];
+    let result = spin.execute(json!({}), &ctx).await.unwrap();
+    assert!(result.is_error);
+    assert_eq!(result.content, "Plugin 'echo' ran out of fuel");
+
+    let echo = &plugin.tools()[0];
+    let result = echo.execute(json!({"text": "x"}), &ctx).await.unwrap();
+    assert!(result.is_error);
+    assert!(result.content.contains("disabled after an earlier trap"));
+}
+
+#[test]
+fn test_undeclared_tools_are_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    // "spin" is missing from the manifest, so registering it fails init
+    write_plugin(dir.path(), "echo", &["echo"]);
+    let loader = WasmPluginLoader::new(vec![dir.path().to_path_buf()]).unwrap();
+    let discovered = loader.discover().unwrap();
+
+    let err = loader
+        .load_plugin(&discovered[0], Arc::new(json!({})))
+        .unwrap_err();
+    assert!(matches!(err, LoadError::Wasm(..)));
+    assert!(err
+        .to_string()
+        .contains("init failed: Tool 'spin' is not declared in the plugin manifest"));
+}
+
+#[test]
+fn test_discover_skips_native_plugins() {
+    let dir = tempfile::tempdir().unwrap();
+    write_plugin(dir.path(), "echo", &["echo", "spin"]);
+    write_plugin(dir.path(), "native", &[]);
+    std::fs::remove_file(dir.path().join("native/echo.wasm")).unwrap();
+
+    let loader = WasmPluginLoader::new(vec![dir.path().to_path_buf()]).unwrap();
+    let discovered = loader.discover().unwrap();
+    assert_eq!(discovered.len(), 1);
+    assert_eq!(discovered[0].manifest.plugin.id, "echo");
+}
@@ -0,0 +1,74 @@
+//! Mistral AI provider implementation for aisopod.
+//!
+//! This crate provides the Mistral AI provider implementation,
+//! implementing the ModelProvider trait from aisopod-provider.
+
+use aisopod_provider::providers::mistral::MistralProvider;
+
+/// Mistral AI provider.
+///
+/// This struct wraps the MistralProvider and implements the ModelProvider trait
+/// for use with the aisopod system.
+pub struct MistralPlugin {
+    /// The underlying Mistral provider
+    provider: MistralProvider,
+}
+
+impl MistralPlugin {
+    /// Creates a new Mistral plugin with the given API key.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - The Mistral AI API key
+    /// * `base_url` - Optional base URL for the Mistral API
+    pub fn new(api_key: String, base_url: Option<String>) -> Self {
+        let provider = MistralProvider::new(api_key, base_url, None);
+
+        Self { provider }
+    }
+
+    /// Returns a reference to the underlying Mistral provider.
+    pub fn provider(&self) -> &MistralProvider {
+        &self.provider
+    }
+
+    /// Returns a mutable reference to the underlying Mistral provider.
+    pub fn provider_mut(&mut self) -> &mut MistralProvider {
+        &mut self.provider
+    }
+}
+
+impl Default for MistralPlugin {
+    fn default() -> Self {
+        Self::new("".to_string(), Some("https://api.mistral.ai".to_string()))
+    }
+}
+
+/// Creates a new Mistral plugin with the given API key.
+///
+/// This is a convenience function for creating Mistral plugins.
+pub fn create_plugin(api_key: String, base_url: Option<String>) -> MistralPlugin {
+    MistralPlugin::new(api_key, base_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mistral_plugin_creation() {
+        let plugin = MistralPlugin::new(
+            "test-key".to_string(),
+            Some("https://api.mistral.ai".to_string()),
+        );
+
+        assert_eq!(plugin.provider().api_key(), "test-key");
+    }
+
+    #[test]
+    fn test_mistral_plugin_default() {
+        let plugin = MistralPlugin::default();
+        assert!(plugin.provider().api_key().is_empty());
+        assert_eq!(plugin.provider().base_url, "https://api.mistral.ai");
+    }
+}
@@ -0,0 +1,227 @@
+//! Batch submission of chat completion requests.
+//!
+//! Non-interactive workloads such as memory consolidation or evals do not
+//! need answers within seconds. Providers with a batch API (OpenAI Batch,
+//! Anthropic Message Batches) process such requests asynchronously, within
+//! hours, at half the regular price. [`BatchProvider`] submits a set of
+//! requests as a job, polls the job, and retrieves the results once it has
+//! ended; [`wait_for_batch`] polls until then and [`batch_cost`] prices the
+//! results at the batch discount.
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::pricing::PricingTable;
+use crate::types::{ChatCompletionRequest, FinishReason, TokenUsage, ToolCall};
+
+/// Fraction of the regular price that providers charge for batched requests.
+pub const BATCH_DISCOUNT: f64 = 0.5;
+
+/// A request submitted as part of a batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchRequest {
+    /// Caller-chosen ID that identifies the result of this request. Must be
+    /// unique within the batch.
+    pub custom_id: String,
+    /// The request. Its `stream` flag is ignored.
+    pub request: ChatCompletionRequest,
+}
+
+/// Processing status of a batch job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    /// The batch is being validated or processed.
+    InProgress,
+    /// Processing ended and results are available.
+    Completed,
+    /// The batch was rejected as a whole, e.g. for invalid input.
+    Failed,
+    /// The batch was cancelled; results of finished requests are available.
+    Cancelled,
+    /// The batch did not finish within the provider's deadline; results of
+    /// finished requests are available.
+    Expired,
+}
+
+impl BatchStatus {
+    /// Returns true once the batch will no longer change.
+    pub fn is_terminal(self) -> bool {
+        self != BatchStatus::InProgress
+    }
+}
+
+/// Number of requests of a batch by outcome.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchCounts {
+    /// Requests in the batch.
+    pub total: u32,
+    /// Requests that completed successfully.
+    pub succeeded: u32,
+    /// Requests that failed, were cancelled or expired.
+    pub failed: u32,
+}
+
+/// A batch job at a provider.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchJob {
+    /// The provider's ID of the batch.
+    pub id: String,
+    /// The ID of the provider processing the batch.
+    pub provider: String,
+    /// Processing status.
+    pub status: BatchStatus,
+    /// Request counts reported by the provider.
+    pub counts: BatchCounts,
+}
+
+/// The response to a successful batched request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchResponse {
+    /// The model that produced the response.
+    pub model: String,
+    /// The text content of the response.
+    pub content: String,
+    /// Tool calls made by the model.
+    pub tool_calls: Vec<ToolCall>,
+    /// Why the model stopped generating.
+    pub finish_reason: Option<FinishReason>,
+    /// Token usage of the request.
+    pub usage: TokenUsage,
+}
+
+/// The outcome of a batched request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchResult {
+    /// The `custom_id` of the request.
+    pub custom_id: String,
+    /// The response, or why the request did not produce one.
+    pub response: std::result::Result<BatchResponse, String>,
+}
+
+/// Trait for providers that accept batches of requests.
+#[async_trait]
+pub trait BatchProvider: Send + Sync {
+    /// Submits requests as a new batch job.
+    async fn submit_batch(&self, requests: Vec<BatchRequest>) -> Result<BatchJob>;
+
+    /// Returns the current state of a batch job.
+    async fn get_batch(&self, batch_id: &str) -> Result<BatchJob>;
+
+    /// Requests cancellation of a batch job. Requests already being
+    /// processed still complete.
+    async fn cancel_batch(&self, batch_id: &str) -> Result<BatchJob>;
+
+    /// Retrieves the results of a batch job that has ended.
+    ///
+    /// Results are not necessarily in submission order; match them to
+    /// requests by `custom_id`.
+    async fn batch_results(&self, batch_id: &str) -> Result<Vec<BatchResult>>;
+}
+
+/// Polls a batch job every `poll_interval` until it ends, and returns it.
+///
+/// Fails if the job has not ended within `timeout`; the job keeps running at
+/// the provider and can be polled again later.
+pub async fn wait_for_batch(
+    provider: &dyn BatchProvider,
+    batch_id: &str,
+    poll_interval: Duration,
+    timeout: Duration,
+) -> Result<BatchJob> {
+    let started = Instant::now();
+    loop {
+        let job = provider.get_batch(batch_id).await?;
+        if job.status.is_terminal() {
+            return Ok(job);
+        }
+        if started.elapsed() + poll_interval > timeout {
+            return Err(anyhow::anyhow!(
+                "Batch '{}' still in progress after {:?} ({} of {} requests done)",
+                batch_id,
+                timeout,
+                job.counts.succeeded + job.counts.failed,
+                job.counts.total
+            ));
+        }
+        debug!(
+            "Batch '{}' in progress ({} of {} requests done)",
+            batch_id,
+            job.counts.succeeded + job.counts.failed,
+            job.counts.total
+        );
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+/// Returns the cost in USD of the successful results of a batch processed
+/// by `provider`, at the batch discount.
+///
+/// Results for models without known pricing cost nothing.
+pub fn batch_cost(pricing: &PricingTable, provider: &str, results: &[BatchResult]) -> f64 {
+    results
+        .iter()
+        .filter_map(|result| result.response.as_ref().ok())
+        .filter_map(|response| pricing.cost(provider, &response.model, &response.usage))
+        .sum::<f64>()
+        * BATCH_DISCOUNT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::ModelPricing;
+
+    fn result(custom_id: &str, model: &str, prompt_tokens: u32) -> BatchResult {
+        BatchResult {
+            custom_id: custom_id.to_string(),
+            response: Ok(BatchResponse {
+                model: model.to_string(),
+                content: "ok".to_string(),
+                tool_calls: Vec::new(),
+                finish_reason: Some(FinishReason::Stop),
+                usage: TokenUsage {
+                    prompt_tokens,
+                    completion_tokens: 0,
+                    total_tokens: prompt_tokens,
+                    cache_read_tokens: 0,
+                    cache_write_tokens: 0,
+                },
+            }),
+        }
+    }
+
+    #[test]
+    fn test_batch_cost_applies_discount_to_successful_results() {
+        let mut pricing = PricingTable::new();
+        pricing.set(
+            None,
+            "gpt-4o",
+            ModelPricing::per_million(2.0, 8.0, None, None),
+        );
+
+        let results = vec![
+            result("a", "gpt-4o", 1_000_000),
+            result("b", "gpt-4o-2024-08-06", 500_000),
+            result("c", "unpriced", 1_000_000),
+            BatchResult {
+                custom_id: "d".to_string(),
+                response: Err("invalid request".to_string()),
+            },
+        ];
+
+        let cost = batch_cost(&pricing, "openai", &results);
+        assert!((cost - 1.5).abs() < 1e-9, "{}", cost);
+    }
+
+    #[test]
+    fn test_batch_status_is_terminal() {
+        assert!(!BatchStatus::InProgress.is_terminal());
+        assert!(BatchStatus::Completed.is_terminal());
+        assert!(BatchStatus::Cancelled.is_terminal());
+    }
+}
@@ -3,16 +3,67 @@
 //! This module provides a `ModelCatalog` that aggregates models from all
 //! registered providers, caches the results with configurable TTL, and exposes
 //! rich capability metadata through a unified interface.
+//!
+//! The catalog can be persisted to disk so that it is available at startup
+//! even when providers cannot be reached, kept fresh by a background task,
+//! and observed through [`CatalogChange`] events as providers add or remove
+//! models.
 
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use anyhow::Result;
-use tracing::{error, info};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
 
 use crate::registry::ProviderRegistry;
 use crate::types::ModelInfo;
 
+/// Capacity of the change event channel.
+const CHANGE_CHANNEL_CAPACITY: usize = 16;
+
+/// Models added to and removed from the catalog by a refresh.
+///
+/// Models are identified by provider and ID.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogChange {
+    /// Models that were not in the catalog before the refresh.
+    pub added: Vec<ModelInfo>,
+    /// Models that are no longer offered by their provider.
+    pub removed: Vec<ModelInfo>,
+}
+
+impl CatalogChange {
+    /// Computes the change from `old` to `new`.
+    fn between(old: &[ModelInfo], new: &[ModelInfo]) -> Self {
+        let contains = |models: &[ModelInfo], model: &ModelInfo| {
+            models
+                .iter()
+                .any(|m| m.provider == model.provider && m.id == model.id)
+        };
+        Self {
+            added: new
+                .iter()
+                .filter(|model| !contains(old, model))
+                .cloned()
+                .collect(),
+            removed: old
+                .iter()
+                .filter(|model| !contains(new, model))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Returns true if no models were added or removed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
 /// A catalog of models aggregated from all registered providers.
 ///
 /// The catalog caches model listings with a configurable TTL to avoid
@@ -22,6 +73,8 @@ pub struct ModelCatalog {
     registry: Arc<RwLock<ProviderRegistry>>,
     cache: RwLock<ModelCache>,
     cache_ttl: Duration,
+    cache_path: Option<PathBuf>,
+    changes: broadcast::Sender<CatalogChange>,
 }
 
 struct ModelCache {
@@ -29,6 +82,14 @@ struct ModelCache {
     last_refresh: Option<Instant>,
 }
 
+/// The catalog as persisted to disk.
+#[derive(Serialize, Deserialize)]
+struct DiskCache {
+    /// Unix time of the refresh that produced the models, in seconds.
+    refreshed_at: u64,
+    models: Vec<ModelInfo>,
+}
+
 impl ModelCatalog {
     /// Creates a new `ModelCatalog` with an empty cache.
     ///
@@ -44,13 +105,59 @@ impl ModelCatalog {
                 last_refresh: None,
             }),
             cache_ttl,
+            cache_path: None,
+            changes: broadcast::channel(CHANGE_CHANNEL_CAPACITY).0,
+        }
+    }
+
+    /// Persists the catalog to the JSON file at `path` after every refresh.
+    ///
+    /// If the file exists, the catalog starts out with the models it holds,
+    /// aged by the time since they were written, so that models are listed
+    /// at startup even if no provider can be reached. An unreadable file is
+    /// logged and ignored.
+    pub fn with_disk_cache(self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        match Self::load_disk_cache(&path) {
+            Ok(Some(disk)) => {
+                let age = SystemTime::now()
+                    .duration_since(UNIX_EPOCH + Duration::from_secs(disk.refreshed_at))
+                    .unwrap_or_default();
+                debug!(
+                    "Loaded {} cached models from '{}'",
+                    disk.models.len(),
+                    path.display()
+                );
+                let mut cache = self.cache.write().unwrap();
+                cache.models = disk.models;
+                cache.last_refresh = Instant::now().checked_sub(age);
+            }
+            Ok(None) => {}
+            Err(e) => warn!(
+                "Ignoring unreadable model cache '{}': {}",
+                path.display(),
+                e
+            ),
         }
+        Self {
+            cache_path: Some(path),
+            ..self
+        }
+    }
+
+    /// Subscribes to the changes made by subsequent refreshes.
+    ///
+    /// A [`CatalogChange`] is sent after every refresh that added or removed
+    /// models.
+    pub fn subscribe(&self) -> broadcast::Receiver<CatalogChange> {
+        self.changes.subscribe()
     }
 
     /// Refreshes the model cache by querying all providers.
     ///
     /// If a provider fails during refresh, the error is logged but other
-    /// providers are still queried. Previously cached models are preserved.
+    /// providers are still queried, and the models previously cached for the
+    /// failing provider are preserved.
     pub async fn refresh(&self) -> Result<()> {
         let providers = {
             let registry = self.registry.read().unwrap();
@@ -77,9 +184,20 @@ impl ModelCatalog {
         }
 
         // Update cache with new models
-        let mut cache = self.cache.write().unwrap();
-        cache.models = new_models;
-        cache.last_refresh = Some(Instant::now());
+        let (change, models) = {
+            let mut cache = self.cache.write().unwrap();
+            new_models.extend(
+                cache
+                    .models
+                    .iter()
+                    .filter(|model| refresh_errors.iter().any(|(id, _)| *id == model.provider))
+                    .cloned(),
+            );
+            let change = CatalogChange::between(&cache.models, &new_models);
+            cache.models = new_models;
+            cache.last_refresh = Some(Instant::now());
+            (change, cache.models.clone())
+        };
 
         if !refresh_errors.is_empty() {
             info!(
@@ -89,6 +207,71 @@ impl ModelCatalog {
             );
         }
 
+        if let Some(path) = &self.cache_path {
+            if let Err(e) = Self::save_disk_cache(path, models) {
+                warn!("Failed to write model cache '{}': {}", path.display(), e);
+            }
+        }
+
+        if !change.is_empty() {
+            info!(
+                "Model catalog changed: {} added, {} removed",
+                change.added.len(),
+                change.removed.len()
+            );
+            // Nobody may be subscribed
+            let _ = self.changes.send(change);
+        }
+
+        Ok(())
+    }
+
+    /// Spawns a task that refreshes the catalog every `interval`, starting
+    /// immediately.
+    ///
+    /// The task holds only a weak reference to the catalog and ends once the
+    /// catalog is dropped, or when the returned handle is aborted.
+    pub fn spawn_refresh(self: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let catalog = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let Some(catalog) = catalog.upgrade() else {
+                    break;
+                };
+                if let Err(e) = catalog.refresh().await {
+                    warn!("Background model catalog refresh failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Reads the disk cache, returning `None` if it does not exist.
+    fn load_disk_cache(path: &Path) -> Result<Option<DiskCache>> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes the disk cache, replacing the previous file atomically.
+    fn save_disk_cache(path: &Path, models: Vec<ModelInfo>) -> Result<()> {
+        let disk = DiskCache {
+            refreshed_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            models,
+        };
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(&disk)?)?;
+        std::fs::rename(&tmp, path)?;
         Ok(())
     }
 
@@ -202,12 +385,15 @@ mod tests {
                     role: Some(Role::Assistant),
                     content: Some("test".to_string()),
                     tool_calls: None,
+                    reasoning: None,
                 },
                 finish_reason: Some(FinishReason::Stop),
                 usage: Some(TokenUsage {
                     prompt_tokens: 10,
                     completion_tokens: 5,
                     total_tokens: 15,
+                    cache_read_tokens: 0,
+                    cache_write_tokens: 0,
                 }),
             })]);
             Ok(Box::pin(stream) as ChatCompletionStream)
@@ -0,0 +1,58 @@
+//! Embedding providers that turn text into vectors.
+//!
+//! Embeddings are served by a different API than chat completions, and often
+//! by a different backend: a deployment may chat with Anthropic while
+//! embedding with a local Ollama model. [`EmbeddingProvider`] is therefore a
+//! separate trait, and embedding providers are registered in the
+//! [`ProviderRegistry`](crate::registry::ProviderRegistry) under a name of
+//! their own.
+//!
+//! Backends:
+//!
+//! - [`OpenAIEmbeddingProvider`] - OpenAI `/v1/embeddings`, and local
+//!   OpenAI-compatible servers such as llama.cpp or vLLM
+//! - [`GeminiEmbeddingProvider`] - Gemini `batchEmbedContents`
+//! - [`OllamaEmbeddingProvider`] - a local Ollama server's `/api/embed`
+//! - `LocalEmbeddingProvider` - an ONNX model run in-process, needing no
+//!   server or API key (`local-embeddings` feature)
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+pub mod gemini;
+#[cfg(feature = "local-embeddings")]
+pub mod local;
+pub mod ollama;
+pub mod openai;
+
+pub use gemini::GeminiEmbeddingProvider;
+#[cfg(feature = "local-embeddings")]
+pub use local::{LocalCrossEncoder, LocalEmbeddingModel, LocalEmbeddingProvider};
+pub use ollama::OllamaEmbeddingProvider;
+pub use openai::OpenAIEmbeddingProvider;
+
+/// Trait for embedding providers that generate vector embeddings from text.
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// Generate an embedding vector for the given text.
+    async fn embed(&self, text: &str) -> Result<Vec<f32>>;
+
+    /// Generate embeddings for multiple texts in a batch.
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>>;
+
+    /// Return the dimensionality of embeddings produced by this provider.
+    fn dimensions(&self) -> usize;
+}
+
+/// Checks that a backend returned one embedding per input text.
+fn check_count(backend: &str, expected: usize, embeddings: Vec<Vec<f32>>) -> Result<Vec<Vec<f32>>> {
+    if embeddings.len() != expected {
+        return Err(anyhow::anyhow!(
+            "{} returned {} embeddings for {} inputs",
+            backend,
+            embeddings.len(),
+            expected
+        ));
+    }
+    Ok(embeddings)
+}
@@ -0,0 +1,121 @@
+//! Gemini embedding provider implementation.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use tracing::instrument;
+
+use super::{check_count, EmbeddingProvider};
+
+/// Response body of `batchEmbedContents`.
+#[derive(Debug, Deserialize)]
+struct BatchEmbedResponse {
+    #[serde(default)]
+    embeddings: Vec<ContentEmbedding>,
+}
+
+/// A single embedding in the response.
+#[derive(Debug, Deserialize)]
+struct ContentEmbedding {
+    values: Vec<f32>,
+}
+
+/// Embedding provider for the Gemini API.
+pub struct GeminiEmbeddingProvider {
+    client: reqwest::Client,
+    api_key: String,
+    base_url: String,
+    model: String,
+    dimensions: usize,
+}
+
+impl GeminiEmbeddingProvider {
+    /// Creates a new Gemini embedding provider.
+    ///
+    /// # Arguments
+    /// * `api_key` - Gemini API key
+    /// * `model` - Optional model name, defaults to "text-embedding-004"
+    /// * `dimensions` - Optional dimensions, defaults to 768
+    pub fn new(api_key: String, model: Option<String>, dimensions: Option<usize>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key,
+            base_url: "https://generativelanguage.googleapis.com".to_string(),
+            model: model.unwrap_or_else(|| "text-embedding-004".to_string()),
+            dimensions: dimensions.unwrap_or(768),
+        }
+    }
+
+    /// Overrides the API base URL.
+    pub fn with_base_url(mut self, base_url: String) -> Self {
+        self.base_url = base_url;
+        self
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for GeminiEmbeddingProvider {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let mut embeddings = self.embed_batch(&[text]).await?;
+        Ok(embeddings.remove(0))
+    }
+
+    #[instrument(skip(self, texts), fields(model = self.model, count = texts.len()))]
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let model = format!("models/{}", self.model);
+        let requests: Vec<_> = texts
+            .iter()
+            .map(|text| {
+                serde_json::json!({
+                    "model": model,
+                    "content": {"parts": [{"text": text}]},
+                    "outputDimensionality": self.dimensions,
+                })
+            })
+            .collect();
+
+        let response = self
+            .client
+            .post(format!(
+                "{}/v1beta/{}:batchEmbedContents",
+                self.base_url, model
+            ))
+            .query(&[("key", &self.api_key)])
+            .json(&serde_json::json!({ "requests": requests }))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let message = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|json| json["error"]["message"].as_str().map(str::to_string))
+                .unwrap_or(body);
+            return Err(anyhow!(
+                "Gemini API error ({}): {}",
+                status.as_u16(),
+                message
+            ));
+        }
+
+        let response: BatchEmbedResponse = response.json().await?;
+        check_count(
+            "Gemini",
+            texts.len(),
+            response
+                .embeddings
+                .into_iter()
+                .map(|embedding| embedding.values)
+                .collect(),
+        )
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+}
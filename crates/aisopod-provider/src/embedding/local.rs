@@ -0,0 +1,521 @@
+//! Local embedding provider running a sentence-embedding model with ONNX
+//! Runtime, and a local cross-encoder for reranking.
+//!
+//! Models and their tokenizers are downloaded once from the Hugging Face Hub
+//! into a cache directory, so memory works without any external API key.
+//! ONNX Runtime itself is loaded at runtime from the shared library named by
+//! `ORT_DYLIB_PATH` (default `libonnxruntime.so` on the library path).
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use ort::session::Session;
+use ort::value::Tensor;
+use tokenizers::{Encoding, PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, info, instrument};
+
+use super::{check_count, EmbeddingProvider};
+
+/// Hugging Face Hub URL models are downloaded from.
+const DEFAULT_HUB_URL: &str = "https://huggingface.co";
+
+/// Number of texts run through the model at once.
+const DEFAULT_BATCH_SIZE: usize = 32;
+
+/// A sentence-embedding or cross-encoder model published on the Hugging Face
+/// Hub with an ONNX export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalEmbeddingModel {
+    /// Hub repository, e.g. "sentence-transformers/all-MiniLM-L6-v2"
+    pub repo: String,
+    /// Git revision of the repository
+    pub revision: String,
+    /// Path of the ONNX file within the repository
+    pub model_file: String,
+    /// Path of the tokenizer within the repository
+    pub tokenizer_file: String,
+    /// Dimensions of the model's output: the embedding size, or 1 for a
+    /// cross-encoder's relevance logit
+    pub dimensions: usize,
+    /// Maximum number of tokens per text; longer texts are truncated
+    pub max_tokens: usize,
+}
+
+impl LocalEmbeddingModel {
+    /// all-MiniLM-L6-v2: 384 dimensions, small and fast.
+    pub fn all_minilm_l6_v2() -> Self {
+        Self {
+            repo: "sentence-transformers/all-MiniLM-L6-v2".to_string(),
+            revision: "main".to_string(),
+            model_file: "onnx/model.onnx".to_string(),
+            tokenizer_file: "tokenizer.json".to_string(),
+            dimensions: 384,
+            max_tokens: 256,
+        }
+    }
+
+    /// gte-small: 384 dimensions, stronger retrieval quality than MiniLM.
+    pub fn gte_small() -> Self {
+        Self {
+            repo: "Xenova/gte-small".to_string(),
+            revision: "main".to_string(),
+            model_file: "onnx/model.onnx".to_string(),
+            tokenizer_file: "tokenizer.json".to_string(),
+            dimensions: 384,
+            max_tokens: 512,
+        }
+    }
+
+    /// ms-marco-MiniLM-L-6-v2: a small cross-encoder scoring query/passage
+    /// relevance, for reranking.
+    pub fn ms_marco_minilm_l6_v2() -> Self {
+        Self {
+            repo: "Xenova/ms-marco-MiniLM-L-6-v2".to_string(),
+            revision: "main".to_string(),
+            model_file: "onnx/model.onnx".to_string(),
+            tokenizer_file: "tokenizer.json".to_string(),
+            dimensions: 1,
+            max_tokens: 512,
+        }
+    }
+
+    /// Directory holding this model's files under `cache_dir`.
+    pub fn cache_path(&self, cache_dir: &Path) -> PathBuf {
+        cache_dir
+            .join(self.repo.replace('/', "--"))
+            .join(&self.revision)
+    }
+}
+
+impl Default for LocalEmbeddingModel {
+    fn default() -> Self {
+        Self::all_minilm_l6_v2()
+    }
+}
+
+/// Default model cache directory, `~/.aisopod/models`.
+pub fn default_cache_dir() -> PathBuf {
+    std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_default()
+        .join(".aisopod")
+        .join("models")
+}
+
+/// Downloads the model and tokenizer files into the cache, skipping files
+/// that are already present.
+///
+/// # Arguments
+/// * `model` - The model to download
+/// * `cache_dir` - Directory the model files are cached in
+/// * `hub_url` - Optional Hub URL, defaults to "https://huggingface.co"
+///
+/// # Returns
+/// Returns the paths of the ONNX model and the tokenizer.
+///
+/// # Errors
+/// Returns an error if a download fails or the cache cannot be written.
+pub async fn download_model(
+    model: &LocalEmbeddingModel,
+    cache_dir: &Path,
+    hub_url: Option<&str>,
+) -> Result<(PathBuf, PathBuf)> {
+    let hub_url = hub_url.unwrap_or(DEFAULT_HUB_URL).trim_end_matches('/');
+    let dir = model.cache_path(cache_dir);
+    let client = reqwest::Client::new();
+
+    let mut paths = Vec::with_capacity(2);
+    for file in [&model.model_file, &model.tokenizer_file] {
+        let path = dir.join(file);
+        if !path.exists() {
+            let url = format!(
+                "{}/{}/resolve/{}/{}",
+                hub_url, model.repo, model.revision, file
+            );
+            download_file(&client, &url, &path).await?;
+        }
+        paths.push(path);
+    }
+
+    let tokenizer = paths.pop().unwrap_or_default();
+    let onnx = paths.pop().unwrap_or_default();
+    Ok((onnx, tokenizer))
+}
+
+/// Streams `url` to `path` through a temporary file, so an interrupted
+/// download never leaves a truncated file in the cache.
+async fn download_file(client: &reqwest::Client, url: &str, path: &Path) -> Result<()> {
+    info!(url, "Downloading embedding model file");
+    let response = client.get(url).send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow!("Failed to download {} ({})", url, status.as_u16()));
+    }
+
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let partial = path.with_extension("part");
+    let mut file = tokio::fs::File::create(&partial)
+        .await
+        .with_context(|| format!("Failed to create {}", partial.display()))?;
+    let mut body = response.bytes_stream();
+    while let Some(chunk) = body.next().await {
+        file.write_all(&chunk?).await?;
+    }
+    file.flush().await?;
+    tokio::fs::rename(&partial, path).await?;
+    Ok(())
+}
+
+/// An ONNX session with the tokenizer for its inputs.
+struct OnnxModel {
+    session: Mutex<Session>,
+    tokenizer: Tokenizer,
+    uses_token_type_ids: bool,
+}
+
+impl OnnxModel {
+    /// Loads the session and tokenizer, padding batches to their longest
+    /// text and truncating texts to `model.max_tokens`.
+    fn load(model: &LocalEmbeddingModel, onnx: &Path, tokenizer: &Path) -> Result<Self> {
+        let session = Session::builder()?
+            .commit_from_file(onnx)
+            .with_context(|| format!("Failed to load ONNX model {}", onnx.display()))?;
+        let uses_token_type_ids = session
+            .inputs
+            .iter()
+            .any(|input| input.name == "token_type_ids");
+
+        let mut tokenizer = Tokenizer::from_file(tokenizer)
+            .map_err(|e| anyhow!("Failed to load tokenizer {}: {}", tokenizer.display(), e))?;
+        let padding = tokenizer
+            .get_padding()
+            .cloned()
+            .map(|padding| PaddingParams {
+                strategy: PaddingStrategy::BatchLongest,
+                ..padding
+            })
+            .unwrap_or_default();
+        tokenizer.with_padding(Some(padding));
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: model.max_tokens,
+                ..Default::default()
+            }))
+            .map_err(|e| anyhow!("Invalid truncation for {}: {}", model.repo, e))?;
+
+        debug!(repo = model.repo, uses_token_type_ids, "Loaded local model");
+        Ok(Self {
+            session: Mutex::new(session),
+            tokenizer,
+            uses_token_type_ids,
+        })
+    }
+
+    /// Runs a padded batch of encodings through the model.
+    ///
+    /// # Returns
+    /// Returns the shape and values of the model's first output.
+    fn run(&self, encodings: &[Encoding]) -> Result<(Vec<i64>, Vec<f32>)> {
+        let seq_len = encodings.first().map(|e| e.get_ids().len()).unwrap_or(0);
+        let shape = [encodings.len() as i64, seq_len as i64];
+
+        let collect = |field: fn(&Encoding) -> &[u32]| -> Vec<i64> {
+            encodings
+                .iter()
+                .flat_map(|e| field(e).iter().map(|&v| v as i64))
+                .collect()
+        };
+
+        let mut inputs = ort::inputs![
+            "input_ids" => Tensor::from_array((shape, collect(Encoding::get_ids)))?,
+            "attention_mask" => Tensor::from_array((shape, collect(Encoding::get_attention_mask)))?,
+        ];
+        if self.uses_token_type_ids {
+            let type_ids = collect(Encoding::get_type_ids);
+            inputs.push((
+                "token_type_ids".into(),
+                Tensor::from_array((shape, type_ids))?.into(),
+            ));
+        }
+
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|_| anyhow!("Local model session poisoned"))?;
+        let outputs = session.run(inputs)?;
+        let (output_shape, values) = outputs[0].try_extract_tensor::<f32>()?;
+        Ok((output_shape.to_vec(), values.to_vec()))
+    }
+}
+
+/// Embedding provider running a local ONNX model.
+///
+/// Texts are tokenized, run through the model in batches, mean-pooled over
+/// their tokens and L2-normalized, matching sentence-transformers output.
+/// Inference runs on the blocking thread pool.
+pub struct LocalEmbeddingProvider {
+    model: Arc<OnnxModel>,
+    info: LocalEmbeddingModel,
+    batch_size: usize,
+}
+
+impl LocalEmbeddingProvider {
+    /// Loads `model`, downloading it into the cache first if needed.
+    ///
+    /// # Arguments
+    /// * `model` - The model to load
+    /// * `cache_dir` - Optional cache directory, defaults to `~/.aisopod/models`
+    ///
+    /// # Errors
+    /// Returns an error if the download fails or the model cannot be loaded.
+    pub async fn load(model: LocalEmbeddingModel, cache_dir: Option<PathBuf>) -> Result<Self> {
+        let cache_dir = cache_dir.unwrap_or_else(default_cache_dir);
+        let (onnx, tokenizer) = download_model(&model, &cache_dir, None).await?;
+        tokio::task::spawn_blocking(move || Self::from_files(model, &onnx, &tokenizer)).await?
+    }
+
+    /// Loads a model from files already on disk.
+    ///
+    /// # Errors
+    /// Returns an error if ONNX Runtime cannot be loaded or either file is
+    /// invalid.
+    pub fn from_files(model: LocalEmbeddingModel, onnx: &Path, tokenizer: &Path) -> Result<Self> {
+        Ok(Self {
+            model: Arc::new(OnnxModel::load(&model, onnx, tokenizer)?),
+            info: model,
+            batch_size: DEFAULT_BATCH_SIZE,
+        })
+    }
+
+    /// Sets how many texts are run through the model at once.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Embeds one batch of texts. Runs synchronously.
+    fn run_batch(model: &OnnxModel, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let encodings = model
+            .tokenizer
+            .encode_batch(texts, true)
+            .map_err(|e| anyhow!("Tokenization failed: {}", e))?;
+        let batch = encodings.len();
+        let seq_len = encodings.first().map(|e| e.get_ids().len()).unwrap_or(0);
+        let mask: Vec<i64> = encodings
+            .iter()
+            .flat_map(|e| e.get_attention_mask().iter().map(|&v| v as i64))
+            .collect();
+
+        let (output_shape, values) = model.run(&encodings)?;
+        match output_shape[..] {
+            [rows, seq, dim] if rows as usize == batch && seq as usize == seq_len => {
+                Ok(mean_pool(&values, &mask, seq_len, dim as usize))
+            }
+            [rows, dim] if rows as usize == batch => Ok(values
+                .chunks(dim as usize)
+                .map(|row| normalize(row.to_vec()))
+                .collect()),
+            ref other => Err(anyhow!("Unexpected embedding output shape {:?}", other)),
+        }
+    }
+}
+
+/// Cross-encoder running a local ONNX model, scoring how relevant passages
+/// are to a query.
+///
+/// Unlike an embedding model, a cross-encoder reads the query and a passage
+/// together, which ranks more accurately but needs one model pass per pair.
+/// Inference runs on the blocking thread pool.
+pub struct LocalCrossEncoder {
+    model: Arc<OnnxModel>,
+    info: LocalEmbeddingModel,
+    batch_size: usize,
+}
+
+impl LocalCrossEncoder {
+    /// Loads `model`, downloading it into the cache first if needed.
+    ///
+    /// # Arguments
+    /// * `model` - The model to load, e.g. [`LocalEmbeddingModel::ms_marco_minilm_l6_v2`]
+    /// * `cache_dir` - Optional cache directory, defaults to `~/.aisopod/models`
+    ///
+    /// # Errors
+    /// Returns an error if the download fails or the model cannot be loaded.
+    pub async fn load(model: LocalEmbeddingModel, cache_dir: Option<PathBuf>) -> Result<Self> {
+        let cache_dir = cache_dir.unwrap_or_else(default_cache_dir);
+        let (onnx, tokenizer) = download_model(&model, &cache_dir, None).await?;
+        tokio::task::spawn_blocking(move || Self::from_files(model, &onnx, &tokenizer)).await?
+    }
+
+    /// Loads a model from files already on disk.
+    ///
+    /// # Errors
+    /// Returns an error if ONNX Runtime cannot be loaded or either file is
+    /// invalid.
+    pub fn from_files(model: LocalEmbeddingModel, onnx: &Path, tokenizer: &Path) -> Result<Self> {
+        Ok(Self {
+            model: Arc::new(OnnxModel::load(&model, onnx, tokenizer)?),
+            info: model,
+            batch_size: DEFAULT_BATCH_SIZE,
+        })
+    }
+
+    /// Sets how many pairs are run through the model at once.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Scores each passage's relevance to `query`.
+    ///
+    /// # Returns
+    /// Returns one score per passage, in order, between 0.0 and 1.0.
+    ///
+    /// # Errors
+    /// Returns an error if tokenization or inference fails.
+    #[instrument(skip(self, query, passages), fields(model = self.info.repo, count = passages.len()))]
+    pub async fn score(&self, query: &str, passages: &[&str]) -> Result<Vec<f32>> {
+        let mut scores = Vec::with_capacity(passages.len());
+        for chunk in passages.chunks(self.batch_size) {
+            let model = self.model.clone();
+            let pairs: Vec<(String, String)> = chunk
+                .iter()
+                .map(|passage| (query.to_string(), passage.to_string()))
+                .collect();
+            let batch =
+                tokio::task::spawn_blocking(move || Self::run_batch(&model, pairs)).await??;
+            scores.extend(batch);
+        }
+        Ok(scores)
+    }
+
+    /// Scores one batch of query/passage pairs. Runs synchronously.
+    fn run_batch(model: &OnnxModel, pairs: Vec<(String, String)>) -> Result<Vec<f32>> {
+        let batch = pairs.len();
+        let encodings = model
+            .tokenizer
+            .encode_batch(pairs, true)
+            .map_err(|e| anyhow!("Tokenization failed: {}", e))?;
+
+        let (output_shape, logits) = model.run(&encodings)?;
+        match output_shape[..] {
+            [rows] | [rows, 1] if rows as usize == batch => {
+                Ok(logits.into_iter().map(sigmoid).collect())
+            }
+            ref other => Err(anyhow!("Unexpected cross-encoder output shape {:?}", other)),
+        }
+    }
+}
+
+/// Maps a relevance logit to a score between 0.0 and 1.0.
+fn sigmoid(logit: f32) -> f32 {
+    1.0 / (1.0 + (-logit).exp())
+}
+
+/// Averages token vectors over the unmasked tokens of each text and
+/// L2-normalizes the result.
+///
+/// `hidden` holds `mask.len() / seq_len` texts of `seq_len` tokens of `dim`
+/// values each.
+fn mean_pool(hidden: &[f32], mask: &[i64], seq_len: usize, dim: usize) -> Vec<Vec<f32>> {
+    hidden
+        .chunks(seq_len * dim)
+        .zip(mask.chunks(seq_len))
+        .map(|(tokens, mask)| {
+            let mut sum = vec![0.0f32; dim];
+            let mut count = 0.0f32;
+            for (token, _) in tokens.chunks(dim).zip(mask).filter(|(_, &m)| m != 0) {
+                for (s, v) in sum.iter_mut().zip(token) {
+                    *s += v;
+                }
+                count += 1.0;
+            }
+            if count > 0.0 {
+                sum.iter_mut().for_each(|s| *s /= count);
+            }
+            normalize(sum)
+        })
+        .collect()
+}
+
+/// Scales `vector` to unit length, leaving zero vectors unchanged.
+fn normalize(mut vector: Vec<f32>) -> Vec<f32> {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+#[async_trait]
+impl EmbeddingProvider for LocalEmbeddingProvider {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let mut embeddings = self.embed_batch(&[text]).await?;
+        Ok(embeddings.remove(0))
+    }
+
+    #[instrument(skip(self, texts), fields(model = self.info.repo, count = texts.len()))]
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for chunk in texts.chunks(self.batch_size) {
+            let model = self.model.clone();
+            let chunk: Vec<String> = chunk.iter().map(|text| text.to_string()).collect();
+            let batch =
+                tokio::task::spawn_blocking(move || Self::run_batch(&model, chunk)).await??;
+            embeddings.extend(batch);
+        }
+        check_count("Local model", texts.len(), embeddings)
+    }
+
+    fn dimensions(&self) -> usize {
+        self.info.dimensions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mean_pool_ignores_padding() {
+        // Two texts of two tokens with 2-dimensional token vectors; the
+        // second text's last token is padding.
+        let hidden = [1.0, 0.0, 3.0, 0.0, 0.0, 2.0, 9.0, 9.0];
+        let mask = [1, 1, 1, 0];
+
+        let pooled = mean_pool(&hidden, &mask, 2, 2);
+        assert_eq!(pooled, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(vec![3.0, 4.0]), vec![0.6, 0.8]);
+        assert_eq!(normalize(vec![0.0, 0.0]), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_sigmoid() {
+        assert_eq!(sigmoid(0.0), 0.5);
+        assert!(sigmoid(8.0) > 0.99);
+        assert!(sigmoid(-8.0) < 0.01);
+    }
+
+    #[test]
+    fn test_cache_path() {
+        let model = LocalEmbeddingModel::default();
+        assert_eq!(
+            model.cache_path(Path::new("/cache")),
+            PathBuf::from("/cache/sentence-transformers--all-MiniLM-L6-v2/main")
+        );
+    }
+}
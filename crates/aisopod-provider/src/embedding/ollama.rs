@@ -0,0 +1,86 @@
+//! Ollama embedding provider implementation.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use tracing::instrument;
+
+use super::{check_count, EmbeddingProvider};
+
+/// Response body of `/api/embed`.
+#[derive(Debug, Deserialize)]
+struct EmbedResponse {
+    #[serde(default)]
+    embeddings: Vec<Vec<f32>>,
+}
+
+/// Embedding provider for a local Ollama server.
+pub struct OllamaEmbeddingProvider {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+    dimensions: usize,
+}
+
+impl OllamaEmbeddingProvider {
+    /// Creates a new Ollama embedding provider.
+    ///
+    /// # Arguments
+    /// * `base_url` - Optional server URL, defaults to "http://localhost:11434"
+    /// * `model` - Optional model name, defaults to "nomic-embed-text"
+    /// * `dimensions` - Optional dimensions of the model's embeddings, defaults to 768
+    pub fn new(base_url: Option<String>, model: Option<String>, dimensions: Option<usize>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.unwrap_or_else(|| "http://localhost:11434".to_string()),
+            model: model.unwrap_or_else(|| "nomic-embed-text".to_string()),
+            dimensions: dimensions.unwrap_or(768),
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbeddingProvider {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let mut embeddings = self.embed_batch(&[text]).await?;
+        Ok(embeddings.remove(0))
+    }
+
+    #[instrument(skip(self, texts), fields(model = self.model, count = texts.len()))]
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/api/embed", self.base_url))
+            .json(&serde_json::json!({
+                "model": self.model,
+                "input": texts,
+            }))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let message = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|json| json["error"].as_str().map(str::to_string))
+                .unwrap_or(body);
+            return Err(anyhow!(
+                "Ollama API error ({}): {}",
+                status.as_u16(),
+                message.trim()
+            ));
+        }
+
+        let response: EmbedResponse = response.json().await?;
+        check_count("Ollama", texts.len(), response.embeddings)
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+}
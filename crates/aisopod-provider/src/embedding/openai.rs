@@ -0,0 +1,144 @@
+//! OpenAI embedding provider implementation.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use tracing::instrument;
+
+use super::{check_count, EmbeddingProvider};
+
+/// Response body of `/v1/embeddings`.
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+/// A single embedding in the response.
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    #[serde(default)]
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+/// Embedding provider for the OpenAI embeddings API.
+///
+/// [`local`](Self::local) points it at an OpenAI-compatible server such as
+/// llama.cpp's `llama-server --embeddings` or vLLM instead.
+pub struct OpenAIEmbeddingProvider {
+    client: reqwest::Client,
+    api_key: Option<String>,
+    base_url: String,
+    model: String,
+    dimensions: usize,
+    /// Whether to ask the API to shorten embeddings to `dimensions`. Only
+    /// the `text-embedding-3` models support this.
+    request_dimensions: bool,
+}
+
+impl OpenAIEmbeddingProvider {
+    /// Creates a new OpenAI embedding provider.
+    ///
+    /// # Arguments
+    /// * `api_key` - OpenAI API key
+    /// * `model` - Optional model name, defaults to "text-embedding-3-small"
+    /// * `dimensions` - Optional dimensions, defaults to 1536
+    pub fn new(api_key: String, model: Option<String>, dimensions: Option<usize>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key: Some(api_key),
+            base_url: "https://api.openai.com".to_string(),
+            model: model.unwrap_or_else(|| "text-embedding-3-small".to_string()),
+            dimensions: dimensions.unwrap_or(1536),
+            request_dimensions: true,
+        }
+    }
+
+    /// Creates a provider for a local OpenAI-compatible embeddings server.
+    ///
+    /// No API key is sent, and `dimensions` must match what the model
+    /// produces since local servers cannot shorten embeddings.
+    pub fn local(base_url: String, model: String, dimensions: usize) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key: None,
+            base_url,
+            model,
+            dimensions,
+            request_dimensions: false,
+        }
+    }
+
+    /// Overrides the API base URL, e.g. for a proxy.
+    pub fn with_base_url(mut self, base_url: String) -> Self {
+        self.base_url = base_url;
+        self
+    }
+
+    /// Returns the embedding model name.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAIEmbeddingProvider {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let mut embeddings = self.embed_batch(&[text]).await?;
+        Ok(embeddings.remove(0))
+    }
+
+    #[instrument(skip(self, texts), fields(model = self.model, count = texts.len()))]
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut body = serde_json::json!({
+            "model": self.model,
+            "input": texts,
+        });
+        if self.request_dimensions {
+            body["dimensions"] = self.dimensions.into();
+        }
+
+        let mut req = self
+            .client
+            .post(format!("{}/v1/embeddings", self.base_url))
+            .json(&body);
+        if let Some(key) = &self.api_key {
+            req = req.bearer_auth(key);
+        }
+        let response = req.send().await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let message = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|json| json["error"]["message"].as_str().map(str::to_string))
+                .unwrap_or(body);
+            return Err(anyhow!(
+                "OpenAI API error ({}): {}",
+                status.as_u16(),
+                message
+            ));
+        }
+
+        let mut response: EmbeddingResponse = response.json().await?;
+        response.data.sort_by_key(|data| data.index);
+        check_count(
+            "OpenAI",
+            texts.len(),
+            response
+                .data
+                .into_iter()
+                .map(|data| data.embedding)
+                .collect(),
+        )
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+}
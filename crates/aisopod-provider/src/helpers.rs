@@ -48,6 +48,7 @@ impl MockProvider {
                         role: Some(Role::Assistant),
                         content: Some("Hello".to_string()),
                         tool_calls: None,
+                        reasoning: None,
                     },
                     finish_reason: None,
                     usage: None,
@@ -58,12 +59,15 @@ impl MockProvider {
                         role: None,
                         content: Some(" world!".to_string()),
                         tool_calls: None,
+                        reasoning: None,
                     },
                     finish_reason: Some(FinishReason::Stop),
                     usage: Some(TokenUsage {
                         prompt_tokens: 5,
                         completion_tokens: 3,
                         total_tokens: 8,
+                        cache_read_tokens: 0,
+                        cache_write_tokens: 0,
                     }),
                 }),
             ],
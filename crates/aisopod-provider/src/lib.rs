@@ -6,7 +6,8 @@
 //!
 //! This crate provides the [`ModelProvider`] trait, which is the primary
 //! abstraction for communicating with AI model providers. Every concrete
-//! provider (Anthropic, OpenAI, Gemini, Bedrock, Ollama) implements this trait.
+//! provider (Anthropic, OpenAI, Azure OpenAI, Gemini, Vertex AI, Mistral, Groq,
+//! OpenRouter, xAI, DeepSeek, Bedrock, Ollama, llama.cpp) implements this trait.
 //!
 //! ## Core Types
 //!
@@ -24,10 +25,47 @@
 //! - [`normalize::enforce_alternating_turns`] - Merge consecutive same-role messages
 //! - [`normalize::extract_system_prompt`] - Extract system prompt from messages
 //! - [`normalize::aggregate_usage`] - Aggregate token usage from streaming chunks
+//! - [`normalize::ToolCallAccumulator`] - Join tool calls streamed in fragments
+//!
+//! ## Retries
+//!
+//! - [`retry::send`] - Send a request, retrying it while the provider is rate
+//!   limiting or overloaded
+//! - [`RetryPolicy`] - Retry count, backoff and wait budget
+//!
+//! ## Batches
+//!
+//! - [`BatchProvider`] - Submit requests as a batch job processed
+//!   asynchronously at a discount (OpenAI Batch, Anthropic Message Batches),
+//!   poll it and retrieve its results
+//! - [`wait_for_batch`] - Poll a batch job until it ends
+//! - [`batch_cost`] - Cost of batch results at the batch discount
+//!
+//! ## Wire Logging
+//!
+//! - [`wire_log::install`] - Opt in to logging full provider requests and
+//!   responses, with credentials redacted, to rotating files
+//! - [`WireLogConfig`] - Log directory, rotation and redacted fields
+//!
+//! ## Embeddings
+//!
+//! - [`EmbeddingProvider`] - The trait for embedding backends (OpenAI and
+//!   OpenAI-compatible local servers, Gemini, Ollama), registered in the
+//!   [`ProviderRegistry`] by name
+//!
+//! ## Pricing
+//!
+//! - [`PricingTable`] - Model prices for converting [`TokenUsage`] into costs
+//!
+//! ## Media
+//!
+//! - [`media::fit_image`] - Downscale image input to a provider's limits
 //!
 //! ## Model Discovery
 //!
-//! - [`discovery::ModelCatalog`] - Aggregated model catalog with caching and capability filtering
+//! - [`discovery::ModelCatalog`] - Aggregated model catalog with caching and capability filtering,
+//!   a disk cache for offline startup and background refresh
+//! - [`discovery::CatalogChange`] - Models added or removed by a catalog refresh
 //!
 //! ## Registry
 //!
@@ -63,27 +101,41 @@
 #![deny(unused_must_use)]
 
 pub mod auth;
+pub mod batch;
 pub mod discovery;
+pub mod embedding;
 pub mod helpers;
+pub mod media;
 pub mod normalize;
+pub mod pricing;
 pub mod providers;
 pub mod registry;
+pub mod retry;
 pub mod trait_module;
 pub mod types;
+pub mod wire_log;
 
 // Re-export the main trait and all types for convenience
 pub use crate::auth::{AuthProfile, AuthProfileManager, ProfileStatus};
-pub use crate::discovery::ModelCatalog;
+pub use crate::batch::{
+    batch_cost, wait_for_batch, BatchJob, BatchProvider, BatchRequest, BatchResponse, BatchResult,
+    BatchStatus,
+};
+pub use crate::discovery::{CatalogChange, ModelCatalog};
+pub use crate::embedding::EmbeddingProvider;
 pub use crate::helpers::{
     create_test_model, create_test_request, create_test_tool, create_test_tool_call, MockProvider,
 };
 pub use crate::normalize::{
     aggregate_usage, enforce_alternating_turns, extract_system_prompt, map_http_error,
-    ProviderError,
+    ProviderError, ToolCallAccumulator, ToolCallDelta,
 };
+pub use crate::pricing::{ModelPricing, PricingTable};
 pub use crate::registry::{ModelAlias, ProviderRegistry};
+pub use crate::retry::RetryPolicy;
 pub use crate::trait_module::{ChatCompletionStream, ModelProvider};
 pub use crate::types::{
     ChatCompletionChunk, ChatCompletionRequest, ContentPart, FinishReason, Message, MessageContent,
     MessageDelta, ModelInfo, ProviderHealth, Role, TokenUsage, ToolCall, ToolDefinition,
 };
+pub use crate::wire_log::{WireLogConfig, WireLogger};
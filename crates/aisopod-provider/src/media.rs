@@ -0,0 +1,183 @@
+//! Image preparation for multi-modal requests.
+//!
+//! Providers reject, or silently downscale, images larger than they accept.
+//! [`fit_image`] scales base64 images down to a provider's [`ImageLimits`]
+//! before they are sent, so oversized photos from channels do not fail a
+//! request or waste upload bandwidth.
+
+use std::io::Cursor;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use image::{DynamicImage, GenericImageView, ImageFormat};
+use tracing::{debug, warn};
+
+/// The largest images a provider accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageLimits {
+    /// Maximum width and height in pixels.
+    pub max_dimension: u32,
+    /// Maximum size of the encoded image in bytes.
+    pub max_bytes: usize,
+}
+
+/// Scales a base64 image down to fit `limits`.
+///
+/// Returns the media type and base64 data to send. Images within the limits
+/// are returned unchanged; larger ones are resized preserving their aspect
+/// ratio and re-encoded, as JPEG unless they are PNGs that fit. Images that
+/// cannot be decoded are passed through for the provider to judge.
+pub fn fit_image(media_type: &str, data: &str, limits: &ImageLimits) -> (String, String) {
+    match try_fit_image(media_type, data, limits) {
+        Ok(Some(fitted)) => fitted,
+        Ok(None) => (media_type.to_string(), data.to_string()),
+        Err(e) => {
+            warn!("Sending image unchanged, failed to downscale: {}", e);
+            (media_type.to_string(), data.to_string())
+        }
+    }
+}
+
+fn try_fit_image(
+    media_type: &str,
+    data: &str,
+    limits: &ImageLimits,
+) -> anyhow::Result<Option<(String, String)>> {
+    let bytes = STANDARD.decode(data)?;
+    let img = image::load_from_memory(&bytes)?;
+    let (width, height) = img.dimensions();
+    if width <= limits.max_dimension
+        && height <= limits.max_dimension
+        && bytes.len() <= limits.max_bytes
+    {
+        return Ok(None);
+    }
+
+    let mut max_dimension = limits.max_dimension.min(width.max(height));
+    loop {
+        let resized = if width.max(height) > max_dimension {
+            img.resize(
+                max_dimension,
+                max_dimension,
+                image::imageops::FilterType::Lanczos3,
+            )
+        } else {
+            img.clone()
+        };
+        let (media_type, encoded) = encode(&resized, media_type == "image/png", limits.max_bytes)?;
+        if encoded.len() <= limits.max_bytes || max_dimension <= 64 {
+            debug!(
+                "Downscaled image from {}x{} ({} bytes) to {}x{} ({} bytes)",
+                width,
+                height,
+                bytes.len(),
+                resized.width(),
+                resized.height(),
+                encoded.len()
+            );
+            return Ok(Some((media_type.to_string(), STANDARD.encode(encoded))));
+        }
+        max_dimension = max_dimension * 3 / 4;
+    }
+}
+
+/// Encodes an image as PNG if requested and it fits in `max_bytes`, and as
+/// JPEG otherwise.
+fn encode(
+    img: &DynamicImage,
+    png: bool,
+    max_bytes: usize,
+) -> anyhow::Result<(&'static str, Vec<u8>)> {
+    if png {
+        let mut buf = Cursor::new(Vec::new());
+        img.write_to(&mut buf, ImageFormat::Png)?;
+        if buf.get_ref().len() <= max_bytes {
+            return Ok(("image/png", buf.into_inner()));
+        }
+    }
+    // JPEG has no alpha channel
+    let mut buf = Cursor::new(Vec::new());
+    DynamicImage::ImageRgb8(img.to_rgb8()).write_to(&mut buf, ImageFormat::Jpeg)?;
+    Ok(("image/jpeg", buf.into_inner()))
+}
+
+/// Guesses the media type of an image URL from its extension.
+pub fn guess_image_type(url: &str) -> &'static str {
+    let path = url.split(['?', '#']).next().unwrap_or(url).to_lowercase();
+    match path.rsplit('.').next() {
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        _ => "image/jpeg",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgba};
+
+    fn png(width: u32, height: u32) -> String {
+        let img = ImageBuffer::from_pixel(width, height, Rgba([200u8, 100, 50, 255]));
+        let mut buf = Cursor::new(Vec::new());
+        DynamicImage::ImageRgba8(img)
+            .write_to(&mut buf, ImageFormat::Png)
+            .unwrap();
+        STANDARD.encode(buf.into_inner())
+    }
+
+    fn dimensions(data: &str) -> (u32, u32) {
+        image::load_from_memory(&STANDARD.decode(data).unwrap())
+            .unwrap()
+            .dimensions()
+    }
+
+    #[test]
+    fn test_fit_image_within_limits_is_unchanged() {
+        let data = png(100, 50);
+        let limits = ImageLimits {
+            max_dimension: 200,
+            max_bytes: 1 << 20,
+        };
+        assert_eq!(
+            fit_image("image/png", &data, &limits),
+            ("image/png".to_string(), data)
+        );
+    }
+
+    #[test]
+    fn test_fit_image_downscales_preserving_aspect_ratio() {
+        let limits = ImageLimits {
+            max_dimension: 100,
+            max_bytes: 1 << 20,
+        };
+        let (media_type, data) = fit_image("image/png", &png(400, 200), &limits);
+        assert_eq!(media_type, "image/png");
+        assert_eq!(dimensions(&data), (100, 50));
+
+        let (media_type, data) = fit_image("image/webp", &png(400, 200), &limits);
+        assert_eq!(media_type, "image/jpeg");
+        assert_eq!(dimensions(&data), (100, 50));
+    }
+
+    #[test]
+    fn test_fit_image_passes_through_undecodable_data() {
+        let limits = ImageLimits {
+            max_dimension: 100,
+            max_bytes: 1,
+        };
+        assert_eq!(
+            fit_image("image/png", "bm90IGFuIGltYWdl", &limits),
+            ("image/png".to_string(), "bm90IGFuIGltYWdl".to_string())
+        );
+    }
+
+    #[test]
+    fn test_guess_image_type() {
+        assert_eq!(
+            guess_image_type("https://example.com/cat.PNG?size=large"),
+            "image/png"
+        );
+        assert_eq!(guess_image_type("https://example.com/photo"), "image/jpeg");
+    }
+}
@@ -348,7 +348,7 @@ pub fn extract_system_prompt(messages: &mut Vec<Message>) -> Option<String> {
                         .into_iter()
                         .filter_map(|p| match p {
                             ContentPart::Text { text } => Some(text),
-                            ContentPart::Image { .. } => None,
+                            _ => None,
                         })
                         .collect::<Vec<_>>()
                         .join(" ");
@@ -384,18 +384,20 @@ pub fn extract_system_prompt(messages: &mut Vec<Message>) -> Option<String> {
 /// let chunks = vec![
 ///     ChatCompletionChunk {
 ///         id: "chunk1".to_string(),
-///         delta: MessageDelta { role: Some(Role::Assistant), content: Some("Hello".to_string()), tool_calls: None },
+///         delta: MessageDelta { role: Some(Role::Assistant), content: Some("Hello".to_string()), tool_calls: None, reasoning: None },
 ///         finish_reason: None,
 ///         usage: None,
 ///     },
 ///     ChatCompletionChunk {
 ///         id: "chunk2".to_string(),
-///         delta: MessageDelta { role: None, content: Some(" world".to_string()), tool_calls: None },
+///         delta: MessageDelta { role: None, content: Some(" world".to_string()), tool_calls: None, reasoning: None },
 ///         finish_reason: Some(FinishReason::Stop),
 ///         usage: Some(TokenUsage {
 ///             prompt_tokens: 5,
 ///             completion_tokens: 2,
 ///             total_tokens: 7,
+///             cache_read_tokens: 0,
+///             cache_write_tokens: 0,
 ///         }),
 ///     },
 /// ];
@@ -409,12 +411,16 @@ pub fn aggregate_usage(chunks: &[ChatCompletionChunk]) -> TokenUsage {
     let mut total_prompt_tokens: u32 = 0;
     let mut total_completion_tokens: u32 = 0;
     let mut total_total_tokens: u32 = 0;
+    let mut total_cache_read_tokens: u32 = 0;
+    let mut total_cache_write_tokens: u32 = 0;
 
     for chunk in chunks {
         if let Some(usage) = &chunk.usage {
             total_prompt_tokens += usage.prompt_tokens;
             total_completion_tokens += usage.completion_tokens;
             total_total_tokens += usage.total_tokens;
+            total_cache_read_tokens += usage.cache_read_tokens;
+            total_cache_write_tokens += usage.cache_write_tokens;
         }
     }
 
@@ -422,6 +428,125 @@ pub fn aggregate_usage(chunks: &[ChatCompletionChunk]) -> TokenUsage {
         prompt_tokens: total_prompt_tokens,
         completion_tokens: total_completion_tokens,
         total_tokens: total_total_tokens,
+        cache_read_tokens: total_cache_read_tokens,
+        cache_write_tokens: total_cache_write_tokens,
+    }
+}
+
+/// A fragment of a streamed tool call.
+///
+/// OpenAI-compatible APIs stream a tool call in pieces: the first fragment of
+/// a call carries its ID and function name, and later fragments with the same
+/// `index` append to its JSON arguments.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolCallDelta {
+    /// Position of the call among the calls of the response.
+    pub index: usize,
+    /// The call ID, sent with the first fragment.
+    pub id: Option<String>,
+    /// The function name, sent with the first fragment.
+    pub name: Option<String>,
+    /// A piece of the JSON arguments.
+    pub arguments: Option<String>,
+}
+
+/// Joins streamed tool call fragments into complete [`ToolCall`]s.
+///
+/// Fragments are pushed as they arrive, either with their index via
+/// [`push`](Self::push) or as [`ToolCall`]s via
+/// [`push_call`](Self::push_call), and [`finish`](Self::finish) returns the
+/// assembled calls once the response is complete.
+///
+/// # Example
+///
+/// ```
+/// use aisopod_provider::normalize::{ToolCallAccumulator, ToolCallDelta};
+///
+/// let mut accumulator = ToolCallAccumulator::new();
+/// accumulator.push(ToolCallDelta {
+///     index: 0,
+///     id: Some("call_1".to_string()),
+///     name: Some("get_weather".to_string()),
+///     arguments: Some("{\"city\":".to_string()),
+/// });
+/// accumulator.push(ToolCallDelta {
+///     index: 0,
+///     arguments: Some("\"Paris\"}".to_string()),
+///     ..Default::default()
+/// });
+///
+/// let calls = accumulator.finish();
+/// assert_eq!(calls[0].name, "get_weather");
+/// assert_eq!(calls[0].arguments, "{\"city\":\"Paris\"}");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ToolCallAccumulator {
+    calls: Vec<ToolCall>,
+}
+
+impl ToolCallAccumulator {
+    /// Creates an empty accumulator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a fragment to the call at its index.
+    pub fn push(&mut self, delta: ToolCallDelta) {
+        if self.calls.len() <= delta.index {
+            self.calls.resize_with(delta.index + 1, || ToolCall {
+                id: String::new(),
+                name: String::new(),
+                arguments: String::new(),
+            });
+        }
+        let call = &mut self.calls[delta.index];
+        if let Some(id) = delta.id.filter(|id| !id.is_empty()) {
+            call.id = id;
+        }
+        if let Some(name) = delta.name {
+            call.name.push_str(&name);
+        }
+        if let Some(arguments) = delta.arguments {
+            call.arguments.push_str(&arguments);
+        }
+    }
+
+    /// Adds a tool call reported by a chunk, which may be a fragment.
+    ///
+    /// A call with a new ID starts a new call. A call without an ID, or with
+    /// the ID of the call being assembled, continues that call; this is how
+    /// providers that do not report indices send fragments.
+    pub fn push_call(&mut self, call: &ToolCall) {
+        let (index, name) = match self.calls.last() {
+            Some(last) if call.id.is_empty() || call.id == last.id => {
+                // Providers repeating the name with every fragment
+                let name = last.name.is_empty().then(|| call.name.clone());
+                (self.calls.len() - 1, name)
+            }
+            _ => (self.calls.len(), Some(call.name.clone())),
+        };
+        self.push(ToolCallDelta {
+            index,
+            id: Some(call.id.clone()),
+            name,
+            arguments: Some(call.arguments.clone()),
+        });
+    }
+
+    /// Returns true if no fragments were pushed since the last
+    /// [`finish`](Self::finish).
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Returns the assembled calls and resets the accumulator.
+    ///
+    /// Indices that never received a fragment are skipped.
+    pub fn finish(&mut self) -> Vec<ToolCall> {
+        std::mem::take(&mut self.calls)
+            .into_iter()
+            .filter(|call| !(call.id.is_empty() && call.name.is_empty()))
+            .collect()
     }
 }
 
@@ -547,6 +672,7 @@ mod tests {
                 role: Some(Role::Assistant),
                 content: Some("test".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: None,
             usage,
@@ -847,6 +973,8 @@ mod tests {
                 prompt_tokens: 5,
                 completion_tokens: 3,
                 total_tokens: 8,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             })),
         ];
 
@@ -864,6 +992,8 @@ mod tests {
                 prompt_tokens: 10,
                 completion_tokens: 5,
                 total_tokens: 15,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             })),
             chunk(None),
             chunk(None),
@@ -894,16 +1024,22 @@ mod tests {
                 prompt_tokens: 5,
                 completion_tokens: 2,
                 total_tokens: 7,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             })),
             chunk(Some(TokenUsage {
                 prompt_tokens: 10,
                 completion_tokens: 3,
                 total_tokens: 13,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             })),
             chunk(Some(TokenUsage {
                 prompt_tokens: 15,
                 completion_tokens: 5,
                 total_tokens: 20,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             })),
         ];
 
@@ -935,4 +1071,61 @@ mod tests {
         // Should implement std::error::Error
         let _: &dyn std::error::Error = &error;
     }
+
+    #[test]
+    fn test_tool_call_accumulator_joins_fragments_by_index() {
+        let mut accumulator = ToolCallAccumulator::new();
+        let fragment = |index, id: Option<&str>, name: Option<&str>, arguments: &str| {
+            ToolCallDelta {
+                index,
+                id: id.map(str::to_string),
+                name: name.map(str::to_string),
+                arguments: Some(arguments.to_string()),
+            }
+        };
+        accumulator.push(fragment(0, Some("call_0"), Some("get_weather"), ""));
+        accumulator.push(fragment(1, Some("call_1"), Some("get_time"), "{}"));
+        accumulator.push(fragment(0, None, None, "{\"city\":"));
+        accumulator.push(fragment(0, None, None, " \"Paris\"}"));
+        assert!(!accumulator.is_empty());
+
+        let calls = accumulator.finish();
+        assert_eq!(
+            calls,
+            vec![
+                ToolCall {
+                    id: "call_0".to_string(),
+                    name: "get_weather".to_string(),
+                    arguments: "{\"city\": \"Paris\"}".to_string(),
+                },
+                ToolCall {
+                    id: "call_1".to_string(),
+                    name: "get_time".to_string(),
+                    arguments: "{}".to_string(),
+                },
+            ]
+        );
+        assert!(accumulator.is_empty());
+    }
+
+    #[test]
+    fn test_tool_call_accumulator_continues_calls_without_id() {
+        let call = |id: &str, name: &str, arguments: &str| ToolCall {
+            id: id.to_string(),
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+        };
+        let mut accumulator = ToolCallAccumulator::new();
+        accumulator.push_call(&call("call_0", "search", "{\"q\":"));
+        accumulator.push_call(&call("", "", "\"rust\"}"));
+        accumulator.push_call(&call("call_1", "fetch", "{\"url\":\"x\"}"));
+
+        assert_eq!(
+            accumulator.finish(),
+            vec![
+                call("call_0", "search", "{\"q\":\"rust\"}"),
+                call("call_1", "fetch", "{\"url\":\"x\"}"),
+            ]
+        );
+    }
 }
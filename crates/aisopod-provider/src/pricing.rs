@@ -0,0 +1,229 @@
+//! Model pricing and cost calculation.
+//!
+//! [`PricingTable`] maps models to their [`ModelPricing`] and converts the
+//! [`TokenUsage`] of a request into its cost in USD. The table starts out
+//! with list prices for well-known hosted models; deployments override or
+//! extend it from configuration, e.g. for negotiated rates or models
+//! released after this table was written. Local providers have no entries,
+//! so their requests cost nothing.
+
+use std::collections::HashMap;
+
+use crate::types::TokenUsage;
+
+/// Prices of a model in USD.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ModelPricing {
+    /// Price per prompt token.
+    pub prompt: f64,
+    /// Price per completion token.
+    pub completion: f64,
+    /// Fixed price per request.
+    pub request: f64,
+    /// Price per input image.
+    pub image: f64,
+    /// Price per prompt token read from the prompt cache; `None` bills
+    /// them as regular prompt tokens.
+    pub cache_read: Option<f64>,
+    /// Price per prompt token written to the prompt cache; `None` bills
+    /// them as regular prompt tokens.
+    pub cache_write: Option<f64>,
+}
+
+impl ModelPricing {
+    /// Creates pricing from prices in USD per million tokens, the unit
+    /// providers publish prices in.
+    pub fn per_million(
+        input: f64,
+        output: f64,
+        cache_read: Option<f64>,
+        cache_write: Option<f64>,
+    ) -> Self {
+        Self {
+            prompt: input / 1e6,
+            completion: output / 1e6,
+            cache_read: cache_read.map(|price| price / 1e6),
+            cache_write: cache_write.map(|price| price / 1e6),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the price of a request with the given token usage.
+    ///
+    /// Cached prompt tokens are part of `usage.prompt_tokens` and are billed
+    /// at the cache prices instead of the prompt price.
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        let cache_read = u64::from(usage.cache_read_tokens);
+        let cache_write = u64::from(usage.cache_write_tokens);
+        let uncached = u64::from(usage.prompt_tokens).saturating_sub(cache_read + cache_write);
+        self.request
+            + self.prompt * uncached as f64
+            + self.cache_read.unwrap_or(self.prompt) * cache_read as f64
+            + self.cache_write.unwrap_or(self.prompt) * cache_write as f64
+            + self.completion * f64::from(usage.completion_tokens)
+    }
+}
+
+/// A list price in USD per million tokens: model, input, output, cache read,
+/// cache write.
+type ListPrice = (&'static str, f64, f64, Option<f64>, Option<f64>);
+
+/// List prices of well-known hosted models.
+const BUILTIN_PRICES: &[ListPrice] = &[
+    ("claude-opus-4", 15.0, 75.0, Some(1.5), Some(18.75)),
+    ("claude-sonnet-4", 3.0, 15.0, Some(0.3), Some(3.75)),
+    ("claude-3-7-sonnet", 3.0, 15.0, Some(0.3), Some(3.75)),
+    ("claude-3-5-sonnet", 3.0, 15.0, Some(0.3), Some(3.75)),
+    ("claude-3-5-haiku", 0.8, 4.0, Some(0.08), Some(1.0)),
+    ("claude-3-opus", 15.0, 75.0, Some(1.5), Some(18.75)),
+    ("claude-3-haiku", 0.25, 1.25, Some(0.03), Some(0.3)),
+    ("gpt-4.1", 2.0, 8.0, Some(0.5), None),
+    ("gpt-4.1-mini", 0.4, 1.6, Some(0.1), None),
+    ("gpt-4.1-nano", 0.1, 0.4, Some(0.025), None),
+    ("gpt-4o", 2.5, 10.0, Some(1.25), None),
+    ("gpt-4o-mini", 0.15, 0.6, Some(0.075), None),
+    ("gpt-4-turbo", 10.0, 30.0, None, None),
+    ("gpt-3.5-turbo", 0.5, 1.5, None, None),
+    ("o1", 15.0, 60.0, Some(7.5), None),
+    ("o1-mini", 1.1, 4.4, Some(0.55), None),
+    ("o3-mini", 1.1, 4.4, Some(0.55), None),
+    ("gemini-2.5-pro", 1.25, 10.0, Some(0.31), None),
+    ("gemini-2.5-flash", 0.3, 2.5, Some(0.075), None),
+    ("gemini-2.0-flash", 0.1, 0.4, Some(0.025), None),
+    ("gemini-1.5-pro", 1.25, 5.0, None, None),
+    ("gemini-1.5-flash", 0.075, 0.3, None, None),
+    ("mistral-large", 2.0, 6.0, None, None),
+    ("mistral-small", 0.1, 0.3, None, None),
+    ("deepseek-chat", 0.27, 1.1, Some(0.07), None),
+    ("deepseek-reasoner", 0.55, 2.19, Some(0.14), None),
+    ("grok-3", 3.0, 15.0, None, None),
+    ("grok-3-mini", 0.3, 0.5, None, None),
+];
+
+/// A table of model prices used to compute request costs.
+///
+/// Entries are keyed either by model (`"gpt-4o"`), applying to every
+/// provider serving the model, or by provider and model
+/// (`"azure-openai/gpt-4o"`), taking precedence for that provider. A model
+/// without an exact entry uses the longest model key it starts with, so
+/// dated snapshots such as `claude-3-5-sonnet-20241022` are priced like
+/// `claude-3-5-sonnet`.
+#[derive(Debug, Clone, Default)]
+pub struct PricingTable {
+    prices: HashMap<String, ModelPricing>,
+}
+
+impl PricingTable {
+    /// Creates an empty pricing table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a pricing table with list prices for well-known models.
+    pub fn builtin() -> Self {
+        let mut table = Self::new();
+        for (model, input, output, cache_read, cache_write) in BUILTIN_PRICES {
+            table.set(
+                None,
+                model,
+                ModelPricing::per_million(*input, *output, *cache_read, *cache_write),
+            );
+        }
+        table
+    }
+
+    /// Sets the pricing of a model, for all providers if `provider` is `None`.
+    pub fn set(&mut self, provider: Option<&str>, model: &str, pricing: ModelPricing) {
+        let key = match provider {
+            Some(provider) => format!("{}/{}", provider, model),
+            None => model.to_string(),
+        };
+        self.prices.insert(key, pricing);
+    }
+
+    /// Looks up the pricing of a model served by `provider`.
+    pub fn get(&self, provider: &str, model: &str) -> Option<&ModelPricing> {
+        if let Some(pricing) = self.prices.get(&format!("{}/{}", provider, model)) {
+            return Some(pricing);
+        }
+        // Aggregators name models "<author>/<model>"
+        let model = model.rsplit('/').next().unwrap_or(model);
+        if let Some(pricing) = self.prices.get(model) {
+            return Some(pricing);
+        }
+        self.prices
+            .iter()
+            .filter(|(key, _)| !key.contains('/') && model.starts_with(key.as_str()))
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, pricing)| pricing)
+    }
+
+    /// Returns the cost in USD of a request, or `None` if the model has no
+    /// known pricing.
+    pub fn cost(&self, provider: &str, model: &str, usage: &TokenUsage) -> Option<f64> {
+        self.get(provider, model).map(|pricing| pricing.cost(usage))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_cost(cost: Option<f64>, expected: f64) {
+        let cost = cost.expect("model should be priced");
+        assert!((cost - expected).abs() < 1e-9, "{} != {}", cost, expected);
+    }
+
+    fn usage(prompt: u32, completion: u32, cache_read: u32, cache_write: u32) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens: prompt,
+            completion_tokens: completion,
+            total_tokens: prompt + completion,
+            cache_read_tokens: cache_read,
+            cache_write_tokens: cache_write,
+        }
+    }
+
+    #[test]
+    fn test_cost_bills_cached_tokens_at_cache_prices() {
+        let pricing = ModelPricing::per_million(3.0, 15.0, Some(0.3), Some(3.75));
+        let cost = pricing.cost(&usage(1_000_000, 100_000, 500_000, 100_000));
+        // 400k uncached + 500k cache reads + 100k cache writes + 100k output
+        assert!((cost - (1.2 + 0.15 + 0.375 + 1.5)).abs() < 1e-9);
+
+        let uncached = ModelPricing::per_million(3.0, 15.0, None, None);
+        let cost = uncached.cost(&usage(1_000_000, 0, 500_000, 0));
+        assert!((cost - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_lookup_prefers_provider_specific_prices() {
+        let mut table = PricingTable::builtin();
+        table.set(
+            Some("azure-openai"),
+            "gpt-4o",
+            ModelPricing::per_million(2.0, 8.0, None, None),
+        );
+
+        let usage = usage(1_000_000, 0, 0, 0);
+        assert_cost(table.cost("azure-openai", "gpt-4o", &usage), 2.0);
+        assert_cost(table.cost("openai", "gpt-4o", &usage), 2.5);
+    }
+
+    #[test]
+    fn test_lookup_matches_snapshots_and_aggregator_ids() {
+        let table = PricingTable::builtin();
+        let usage = usage(1_000_000, 0, 0, 0);
+
+        assert_cost(
+            table.cost("anthropic", "claude-3-5-sonnet-20241022", &usage),
+            3.0,
+        );
+        assert_cost(table.cost("openai", "gpt-4o-mini-2024-07-18", &usage), 0.15);
+        assert_cost(
+            table.cost("openrouter", "anthropic/claude-sonnet-4", &usage),
+            3.0,
+        );
+        assert_eq!(table.cost("ollama", "llama3.2", &usage), None);
+    }
+}
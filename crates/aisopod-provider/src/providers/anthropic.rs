@@ -3,28 +3,43 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use futures_util::stream::{BoxStream, StreamExt};
+use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tracing::{debug, warn};
 
 use crate::auth::{AuthProfile, AuthProfileManager};
+use crate::batch::{
+    BatchCounts, BatchJob, BatchProvider, BatchRequest, BatchResponse, BatchResult, BatchStatus,
+};
+use crate::media::{fit_image, ImageLimits};
+use crate::retry::{self, RetryPolicy};
 use crate::trait_module::{ChatCompletionStream, ModelProvider};
 use crate::types::*;
 
 pub mod api_types;
 use api_types::*;
 
+/// The largest images sent to Anthropic; larger images are downscaled by
+/// the API to a 1568 pixel long edge anyway, adding latency.
+pub const IMAGE_LIMITS: ImageLimits = ImageLimits {
+    max_dimension: 1568,
+    max_bytes: 5 * 1024 * 1024,
+};
+
 /// Anthropic Claude provider implementation.
 ///
 /// This struct implements the [`ModelProvider`] trait for the Anthropic
 /// Messages API, supporting streaming SSE chat completions, tool use,
-/// system prompt handling, and vision (image) support.
+/// system prompt handling, vision (image) support, and prompt caching.
 pub struct AnthropicProvider {
     client: reqwest::Client,
     api_key: String,
     base_url: String,
     default_model: String,
     profile_manager: Arc<Mutex<AuthProfileManager>>,
+    prompt_caching: HashSet<String>,
+    retry_policy: RetryPolicy,
 }
 
 impl AnthropicProvider {
@@ -50,9 +65,32 @@ impl AnthropicProvider {
             profile_manager: Arc::new(Mutex::new(AuthProfileManager::new(Duration::from_secs(
                 cooldown_seconds.unwrap_or(60),
             )))),
+            prompt_caching: HashSet::new(),
+            retry_policy: RetryPolicy::default(),
         }
     }
 
+    /// Enables prompt caching for requests to `model`.
+    ///
+    /// The system prompt and tool definitions of these requests are marked
+    /// as cacheable, so repeated requests sharing them are billed at the
+    /// cheaper cache-read rate.
+    pub fn with_prompt_caching(mut self, model: impl Into<String>) -> Self {
+        self.prompt_caching.insert(model.into());
+        self
+    }
+
+    /// Sets how rate-limited and overloaded requests are retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Returns whether prompt caching is enabled for `model`.
+    pub fn prompt_caching_enabled(&self, model: &str) -> bool {
+        self.prompt_caching.contains(model)
+    }
+
     /// Adds an authentication profile for key rotation.
     pub fn add_profile(&mut self, profile: AuthProfile) {
         let mut manager = self.profile_manager.lock().unwrap();
@@ -96,11 +134,23 @@ impl AnthropicProvider {
                             content_blocks.push(AnthropicContentBlock::Text { text: text.clone() });
                         }
                         ContentPart::Image { media_type, data } => {
+                            let (media_type, data) = fit_image(media_type, data, &IMAGE_LIMITS);
                             content_blocks.push(AnthropicContentBlock::Image {
                                 source: AnthropicImageSource {
                                     r#type: "base64".to_string(),
-                                    media_type: media_type.clone(),
-                                    data: data.clone(),
+                                    media_type,
+                                    data,
+                                    url: None,
+                                },
+                            });
+                        }
+                        ContentPart::ImageUrl { url } => {
+                            content_blocks.push(AnthropicContentBlock::Image {
+                                source: AnthropicImageSource {
+                                    r#type: "url".to_string(),
+                                    media_type: String::new(),
+                                    data: String::new(),
+                                    url: Some(url.clone()),
                                 },
                             });
                         }
@@ -119,6 +169,7 @@ impl AnthropicProvider {
             name: tool.name.clone(),
             description: tool.description.clone(),
             input_schema: tool.parameters.clone(),
+            cache_control: None,
         }
     }
 
@@ -139,10 +190,50 @@ impl AnthropicProvider {
         prompt.map(|p| serde_json::Value::String(p))
     }
 
+    /// Converts Anthropic usage to token usage.
+    ///
+    /// Anthropic reports cached prompt tokens separately from
+    /// `input_tokens`; they are counted in the prompt tokens here.
+    fn convert_usage(usage: &AnthropicUsage) -> TokenUsage {
+        let prompt_tokens =
+            usage.input_tokens + usage.cache_creation_input_tokens + usage.cache_read_input_tokens;
+        TokenUsage {
+            prompt_tokens,
+            completion_tokens: usage.output_tokens,
+            total_tokens: prompt_tokens + usage.output_tokens,
+            cache_read_tokens: usage.cache_read_input_tokens,
+            cache_write_tokens: usage.cache_creation_input_tokens,
+        }
+    }
+
+    /// Maps an Anthropic stop reason to a [`FinishReason`].
+    fn convert_stop_reason(reason: &AnthropicStopReason) -> FinishReason {
+        match reason {
+            AnthropicStopReason::EndTurn => FinishReason::Stop,
+            AnthropicStopReason::MaxTokens => FinishReason::Length,
+            AnthropicStopReason::ToolUse => FinishReason::ToolCall,
+            AnthropicStopReason::StopSequence => FinishReason::Stop,
+        }
+    }
+
     /// Builds the Anthropic request from a core request.
+    ///
+    /// With prompt caching enabled for the model, cache breakpoints are set
+    /// after the system prompt and after the last tool definition.
     fn build_anthropic_request(&self, request: &ChatCompletionRequest) -> AnthropicRequest {
+        let prompt_caching = self.prompt_caching_enabled(&request.model);
         let system_prompt = self.extract_system_prompt(&request.messages);
-        let system_value = Self::system_prompt_to_value(system_prompt);
+        let system_value = if prompt_caching {
+            system_prompt.map(|text| {
+                serde_json::json!([{
+                    "type": "text",
+                    "text": text,
+                    "cache_control": AnthropicCacheControl::ephemeral(),
+                }])
+            })
+        } else {
+            Self::system_prompt_to_value(system_prompt)
+        };
 
         let mut anthropic_messages = Vec::new();
         for message in &request.messages {
@@ -154,10 +245,16 @@ impl AnthropicProvider {
             }
         }
 
-        let tools = request
-            .tools
-            .as_ref()
-            .map(|tools| tools.iter().map(|tool| self.convert_tool(tool)).collect());
+        let tools = request.tools.as_ref().map(|tools| {
+            let mut tools: Vec<AnthropicTool> =
+                tools.iter().map(|tool| self.convert_tool(tool)).collect();
+            if prompt_caching {
+                if let Some(last) = tools.last_mut() {
+                    last.cache_control = Some(AnthropicCacheControl::ephemeral());
+                }
+            }
+            tools
+        });
 
         AnthropicRequest {
             model: request.model.clone(),
@@ -194,6 +291,7 @@ impl AnthropicProvider {
                             role: Some(Role::Assistant),
                             content: Some(format!("{{\"tool\":\"{}\",\"id\":\"{}\"}}", name, id)),
                             tool_calls: None,
+                            reasoning: None,
                         },
                         finish_reason: None,
                         usage: None,
@@ -209,6 +307,7 @@ impl AnthropicProvider {
                         role: None,
                         content: Some(text),
                         tool_calls: None,
+                        reasoning: None,
                     },
                     finish_reason: None,
                     usage: None,
@@ -220,6 +319,7 @@ impl AnthropicProvider {
                             role: None,
                             content: Some(partial_json),
                             tool_calls: None,
+                            reasoning: None,
                         },
                         finish_reason: None,
                         usage: None,
@@ -228,12 +328,7 @@ impl AnthropicProvider {
             },
             AnthropicSseEvent::ContentBlockStop { .. } => None,
             AnthropicSseEvent::MessageDelta { delta, usage, .. } => {
-                let finish_reason = delta.stop_reason.as_ref().and_then(|s| match s {
-                    AnthropicStopReason::EndTurn => Some(FinishReason::Stop),
-                    AnthropicStopReason::MaxTokens => Some(FinishReason::Length),
-                    AnthropicStopReason::ToolUse => Some(FinishReason::ToolCall),
-                    AnthropicStopReason::StopSequence => Some(FinishReason::Stop),
-                });
+                let finish_reason = delta.stop_reason.as_ref().map(Self::convert_stop_reason);
 
                 Some(ChatCompletionChunk {
                     id: "delta".to_string(),
@@ -241,23 +336,16 @@ impl AnthropicProvider {
                         role: None,
                         content: None,
                         tool_calls: None,
+                        reasoning: None,
                     },
                     finish_reason: finish_reason.or(Some(FinishReason::Stop)),
-                    usage: Some(TokenUsage {
-                        prompt_tokens: usage.input_tokens,
-                        completion_tokens: usage.output_tokens,
-                        total_tokens: usage.input_tokens + usage.output_tokens,
-                    }),
+                    usage: Some(Self::convert_usage(&usage)),
                 })
             }
             AnthropicSseEvent::MessageStop { .. } => None,
             AnthropicSseEvent::MessageStart { message, .. } => {
                 let id = message.id.clone();
-                let usage = message.usage.clone().map(|u| TokenUsage {
-                    prompt_tokens: u.input_tokens,
-                    completion_tokens: u.output_tokens,
-                    total_tokens: u.input_tokens + u.output_tokens,
-                });
+                let usage = message.usage.as_ref().map(Self::convert_usage);
 
                 Some(ChatCompletionChunk {
                     id,
@@ -265,6 +353,7 @@ impl AnthropicProvider {
                         role: Some(Role::Assistant),
                         content: None,
                         tool_calls: None,
+                        reasoning: None,
                     },
                     finish_reason: None,
                     usage,
@@ -360,16 +449,15 @@ impl ModelProvider for AnthropicProvider {
 
         let url = format!("{}/v1/messages", self.base_url);
 
-        let response = self
+        let builder = self
             .client
             .request(reqwest::Method::POST, &url)
             .header("x-api-key", &api_key)
             .header("anthropic-version", "2023-06-01")
             .header("anthropic-beta", "messages-2023-12-15")
             .header("Content-Type", "application/json")
-            .json(&anthropic_request)
-            .send()
-            .await?;
+            .json(&anthropic_request);
+        let response = retry::send(builder, &self.retry_policy).await?;
 
         if !response.status().is_success() {
             let status = response.status().as_u16();
@@ -462,6 +550,165 @@ impl ModelProvider for AnthropicProvider {
     }
 }
 
+impl AnthropicProvider {
+    /// Builds an authenticated request to an Anthropic API path.
+    fn api_request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let api_key = self.get_api_key().unwrap_or(self.api_key.clone());
+
+        self.client
+            .request(method, format!("{}{}", self.base_url, path))
+            .header("x-api-key", &api_key)
+            .header("anthropic-version", "2023-06-01")
+            .header("Content-Type", "application/json")
+    }
+
+    /// Sends a request, converting error responses to errors.
+    async fn send_checked(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let response = retry::send(request, &self.retry_policy).await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            return Err(self.handle_api_error(status, &body));
+        }
+
+        Ok(response)
+    }
+
+    /// Converts an Anthropic message batch to a [`BatchJob`].
+    fn convert_batch(batch: AnthropicBatch) -> BatchJob {
+        let status = match batch.processing_status.as_str() {
+            "ended" if batch.cancel_initiated_at.is_some() => BatchStatus::Cancelled,
+            "ended" => BatchStatus::Completed,
+            // "in_progress" and "canceling"
+            _ => BatchStatus::InProgress,
+        };
+
+        let counts = &batch.request_counts;
+        let failed = counts.errored + counts.canceled + counts.expired;
+        BatchJob {
+            id: batch.id,
+            provider: "anthropic".to_string(),
+            status,
+            counts: BatchCounts {
+                total: counts.processing + counts.succeeded + failed,
+                succeeded: counts.succeeded,
+                failed,
+            },
+        }
+    }
+
+    /// Converts a message of a batch result to a [`BatchResponse`].
+    ///
+    /// Text blocks are joined into the content; tool use blocks become tool
+    /// calls with their input as JSON arguments.
+    fn convert_response(message: AnthropicResponse) -> BatchResponse {
+        let mut content = String::new();
+        let mut tool_calls = Vec::new();
+        for block in message.content {
+            match block {
+                AnthropicContentBlock::Text { text } => content.push_str(&text),
+                AnthropicContentBlock::ToolUse { id, name, input } => tool_calls.push(ToolCall {
+                    id,
+                    name,
+                    arguments: input.to_string(),
+                }),
+                _ => {}
+            }
+        }
+
+        BatchResponse {
+            model: message.model.unwrap_or_default(),
+            content,
+            tool_calls,
+            finish_reason: message.stop_reason.as_ref().map(Self::convert_stop_reason),
+            usage: message
+                .usage
+                .as_ref()
+                .map(Self::convert_usage)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[async_trait]
+impl BatchProvider for AnthropicProvider {
+    async fn submit_batch(&self, requests: Vec<BatchRequest>) -> Result<BatchJob> {
+        let requests = requests
+            .iter()
+            .map(|batch_request| {
+                let mut params = self.build_anthropic_request(&batch_request.request);
+                params.stream = false;
+                AnthropicBatchRequest {
+                    custom_id: batch_request.custom_id.clone(),
+                    params,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        debug!("Submitting Anthropic batch of {} requests", requests.len());
+
+        let response = self
+            .send_checked(
+                self.api_request(reqwest::Method::POST, "/v1/messages/batches")
+                    .json(&AnthropicBatchCreate { requests }),
+            )
+            .await?;
+
+        Ok(Self::convert_batch(response.json().await?))
+    }
+
+    async fn get_batch(&self, batch_id: &str) -> Result<BatchJob> {
+        let path = format!("/v1/messages/batches/{}", batch_id);
+        let response = self
+            .send_checked(self.api_request(reqwest::Method::GET, &path))
+            .await?;
+
+        Ok(Self::convert_batch(response.json().await?))
+    }
+
+    async fn cancel_batch(&self, batch_id: &str) -> Result<BatchJob> {
+        let path = format!("/v1/messages/batches/{}/cancel", batch_id);
+        let response = self
+            .send_checked(self.api_request(reqwest::Method::POST, &path))
+            .await?;
+
+        Ok(Self::convert_batch(response.json().await?))
+    }
+
+    async fn batch_results(&self, batch_id: &str) -> Result<Vec<BatchResult>> {
+        let path = format!("/v1/messages/batches/{}/results", batch_id);
+        let content = self
+            .send_checked(self.api_request(reqwest::Method::GET, &path))
+            .await?
+            .text()
+            .await?;
+
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let line: AnthropicBatchResultLine = serde_json::from_str(line)?;
+                let response = match line.result {
+                    AnthropicBatchResult::Succeeded { message } => {
+                        Ok(Self::convert_response(message))
+                    }
+                    AnthropicBatchResult::Errored { error } => Err(error
+                        .error
+                        .and_then(|error| error.message)
+                        .unwrap_or_else(|| "Unknown error".to_string())),
+                    AnthropicBatchResult::Canceled => Err("Request was canceled".to_string()),
+                    AnthropicBatchResult::Expired => Err("Request expired".to_string()),
+                };
+                Ok(BatchResult {
+                    custom_id: line.custom_id,
+                    response,
+                })
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -491,6 +738,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_convert_message_downscales_image() {
+        use base64::Engine;
+        use image::GenericImageView;
+
+        let provider = AnthropicProvider::new("test-key".to_string(), None, None, Some(60));
+        let mut png = std::io::Cursor::new(Vec::new());
+        image::DynamicImage::new_rgb8(3136, 100)
+            .write_to(&mut png, image::ImageFormat::Png)
+            .unwrap();
+        let data = base64::engine::general_purpose::STANDARD.encode(png.into_inner());
+
+        let message = Message {
+            role: Role::User,
+            content: MessageContent::Parts(vec![ContentPart::Image {
+                media_type: "image/png".to_string(),
+                data,
+            }]),
+            tool_calls: None,
+            tool_call_id: None,
+        };
+
+        let result = provider.convert_message(&message).unwrap();
+        let AnthropicContentBlock::Image { source } = &result.content[0] else {
+            panic!("Expected Image content block");
+        };
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&source.data)
+            .unwrap();
+        let img = image::load_from_memory(&bytes).unwrap();
+        assert_eq!(img.dimensions(), (1568, 50));
+    }
+
     #[test]
     fn test_extract_system_prompt() {
         let provider = AnthropicProvider::new("test-key".to_string(), None, None, Some(60));
@@ -553,4 +833,69 @@ mod tests {
         assert_eq!(anthropic_request.max_tokens, Some(1000));
         assert!(anthropic_request.stream);
     }
+
+    #[test]
+    fn test_build_anthropic_request_with_prompt_caching() {
+        let provider = AnthropicProvider::new("test-key".to_string(), None, None, Some(60))
+            .with_prompt_caching("claude-sonnet-4-5");
+
+        let tool = |name: &str| ToolDefinition::new(name, "", serde_json::json!({}));
+        let mut request = ChatCompletionRequest {
+            model: "claude-sonnet-4-5".to_string(),
+            messages: vec![Message {
+                role: Role::System,
+                content: MessageContent::Text("System prompt".to_string()),
+                tool_calls: None,
+                tool_call_id: None,
+            }],
+            tools: Some(vec![tool("search"), tool("fetch")]),
+            temperature: None,
+            max_tokens: None,
+            stop: None,
+            stream: true,
+        };
+
+        let anthropic_request = provider.build_anthropic_request(&request);
+        assert_eq!(
+            anthropic_request.system,
+            Some(serde_json::json!([{
+                "type": "text",
+                "text": "System prompt",
+                "cache_control": {"type": "ephemeral"}
+            }]))
+        );
+        let tools = anthropic_request.tools.unwrap();
+        assert_eq!(tools[0].cache_control, None);
+        assert_eq!(
+            tools[1].cache_control,
+            Some(AnthropicCacheControl::ephemeral())
+        );
+
+        // Other models are sent without cache breakpoints
+        request.model = "claude-haiku-4-5".to_string();
+        let anthropic_request = provider.build_anthropic_request(&request);
+        assert_eq!(
+            anthropic_request.system,
+            Some(serde_json::Value::String("System prompt".to_string()))
+        );
+        assert!(anthropic_request
+            .tools
+            .unwrap()
+            .iter()
+            .all(|tool| tool.cache_control.is_none()));
+    }
+
+    #[test]
+    fn test_parse_cache_usage() {
+        let chunk = AnthropicProvider::parse_sse_event(
+            r#"data: {"type":"message_start","message":{"id":"msg_1","type":"message","role":"assistant","content":[],"usage":{"input_tokens":12,"cache_creation_input_tokens":0,"cache_read_input_tokens":2048,"output_tokens":1}}}"#,
+        )
+        .unwrap();
+
+        let usage = chunk.usage.unwrap();
+        assert_eq!(usage.prompt_tokens, 2060);
+        assert_eq!(usage.cache_read_tokens, 2048);
+        assert_eq!(usage.cache_write_tokens, 0);
+        assert_eq!(usage.total_tokens, 2061);
+    }
 }
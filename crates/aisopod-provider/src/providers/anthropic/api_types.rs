@@ -67,12 +67,35 @@ pub enum AnthropicContentBlock {
 }
 
 /// Image source for Anthropic content blocks.
+///
+/// A "base64" source carries `media_type` and `data`; a "url" source
+/// carries `url`.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AnthropicImageSource {
     #[serde(rename = "type")]
     pub r#type: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub media_type: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub data: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// Marks the prompt prefix ending at a block as cacheable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnthropicCacheControl {
+    #[serde(rename = "type")]
+    pub r#type: String,
+}
+
+impl AnthropicCacheControl {
+    /// The default cache breakpoint, cached for five minutes.
+    pub fn ephemeral() -> Self {
+        Self {
+            r#type: "ephemeral".to_string(),
+        }
+    }
 }
 
 /// Tool definition for Anthropic API.
@@ -81,6 +104,8 @@ pub struct AnthropicTool {
     pub name: String,
     pub description: String,
     pub input_schema: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<AnthropicCacheControl>,
 }
 
 /// The main request body for Anthropic Messages API.
@@ -186,8 +211,16 @@ pub struct AnthropicMessageDelta {
 /// Usage statistics in Anthropic API response.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct AnthropicUsage {
+    #[serde(default)]
     pub input_tokens: u32,
+    #[serde(default)]
     pub output_tokens: u32,
+    /// Prompt tokens written to the cache; not included in `input_tokens`.
+    #[serde(default)]
+    pub cache_creation_input_tokens: u32,
+    /// Prompt tokens read from the cache; not included in `input_tokens`.
+    #[serde(default)]
+    pub cache_read_input_tokens: u32,
 }
 
 /// Error response from Anthropic API.
@@ -284,3 +317,64 @@ pub struct AnthropicResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub usage: Option<AnthropicUsage>,
 }
+
+/// Request body for creating a message batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnthropicBatchCreate {
+    pub requests: Vec<AnthropicBatchRequest>,
+}
+
+/// A request within a message batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnthropicBatchRequest {
+    pub custom_id: String,
+    pub params: AnthropicRequest,
+}
+
+/// A message batch from the Message Batches API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnthropicBatch {
+    pub id: String,
+    /// One of "in_progress", "canceling" or "ended".
+    pub processing_status: String,
+    #[serde(default)]
+    pub request_counts: AnthropicBatchRequestCounts,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancel_initiated_at: Option<String>,
+}
+
+/// Request counts of a message batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct AnthropicBatchRequestCounts {
+    #[serde(default)]
+    pub processing: u32,
+    #[serde(default)]
+    pub succeeded: u32,
+    #[serde(default)]
+    pub errored: u32,
+    #[serde(default)]
+    pub canceled: u32,
+    #[serde(default)]
+    pub expired: u32,
+}
+
+/// A line of the results of a message batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnthropicBatchResultLine {
+    pub custom_id: String,
+    pub result: AnthropicBatchResult,
+}
+
+/// The outcome of a request within a message batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum AnthropicBatchResult {
+    #[serde(rename = "succeeded")]
+    Succeeded { message: AnthropicResponse },
+    #[serde(rename = "errored")]
+    Errored { error: AnthropicErrorResponse },
+    #[serde(rename = "canceled")]
+    Canceled,
+    #[serde(rename = "expired")]
+    Expired,
+}
@@ -0,0 +1,294 @@
+//! Azure OpenAI Service provider implementation.
+//!
+//! Azure OpenAI serves the OpenAI Chat Completions API from per-resource
+//! endpoints. Requests are routed to a deployment rather than a model, the
+//! API version is passed as the `api-version` query parameter, and requests
+//! are authenticated with either a resource key or a Microsoft Entra ID
+//! (Azure AD) access token.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::{debug, warn};
+
+use crate::providers::openai::api_types::OpenAIErrorResponse;
+use crate::providers::openai::OpenAIProvider;
+use crate::retry::{self, RetryPolicy};
+use crate::trait_module::{ChatCompletionStream, ModelProvider};
+use crate::types::*;
+
+/// The Azure OpenAI API version used when none is configured.
+pub const DEFAULT_API_VERSION: &str = "2024-06-01";
+
+/// Credentials for an Azure OpenAI resource.
+#[derive(Clone)]
+pub enum AzureAuth {
+    /// A resource key, sent in the `api-key` header.
+    ApiKey(String),
+    /// A Microsoft Entra ID (Azure AD) access token, sent as a bearer token.
+    AzureAd(String),
+}
+
+impl std::fmt::Debug for AzureAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AzureAuth::ApiKey(_) => f.write_str("ApiKey(..)"),
+            AzureAuth::AzureAd(_) => f.write_str("AzureAd(..)"),
+        }
+    }
+}
+
+/// Azure OpenAI provider implementation.
+///
+/// This struct implements the [`ModelProvider`] trait for the Azure OpenAI
+/// Service. Request bodies and streamed responses use the OpenAI Chat
+/// Completions format; the model of a request selects the deployment it is
+/// sent to.
+///
+/// # Example
+///
+/// ```ignore
+/// use aisopod_provider::providers::azure_openai::{AzureAuth, AzureOpenAIProvider};
+///
+/// let provider = AzureOpenAIProvider::new(
+///     "https://my-resource.openai.azure.com".to_string(),
+///     AzureAuth::ApiKey(api_key),
+///     None,
+/// )
+/// .with_deployment("gpt-4o", "chat-prod");
+/// ```
+pub struct AzureOpenAIProvider {
+    client: reqwest::Client,
+    pub endpoint: String,
+    api_version: String,
+    deployments: HashMap<String, String>,
+    auth: RwLock<AzureAuth>,
+    retry_policy: RetryPolicy,
+}
+
+impl AzureOpenAIProvider {
+    /// Creates a new Azure OpenAI provider instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The resource endpoint (e.g., "https://my-resource.openai.azure.com").
+    /// * `auth` - The resource key or Azure AD token to authenticate with.
+    /// * `api_version` - The API version (defaults to [`DEFAULT_API_VERSION`]).
+    pub fn new(endpoint: String, auth: AzureAuth, api_version: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            api_version: api_version.unwrap_or_else(|| DEFAULT_API_VERSION.to_string()),
+            deployments: HashMap::new(),
+            auth: RwLock::new(auth),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Routes requests for `model` to the deployment named `deployment`.
+    ///
+    /// Models without a deployment are sent to a deployment of the same
+    /// name.
+    pub fn with_deployment(
+        mut self,
+        model: impl Into<String>,
+        deployment: impl Into<String>,
+    ) -> Self {
+        self.deployments.insert(model.into(), deployment.into());
+        self
+    }
+
+    /// Sets how rate-limited requests are retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Returns the API version sent with each request.
+    pub fn api_version(&self) -> &str {
+        &self.api_version
+    }
+
+    /// Returns the deployment requests for `model` are sent to.
+    pub fn deployment_for<'a>(&'a self, model: &'a str) -> &'a str {
+        self.deployments
+            .get(model)
+            .map(String::as_str)
+            .unwrap_or(model)
+    }
+
+    /// Replaces the credentials, e.g. with a refreshed Azure AD token.
+    pub fn set_auth(&self, auth: AzureAuth) {
+        *self.auth.write().unwrap() = auth;
+    }
+
+    /// Builds the URL of an API path with the `api-version` query parameter.
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}/openai/{}?api-version={}",
+            self.endpoint, path, self.api_version
+        )
+    }
+
+    /// Starts a request carrying the configured credentials.
+    fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        let builder = self.client.request(method, url);
+        match &*self.auth.read().unwrap() {
+            AzureAuth::ApiKey(key) => builder.header("api-key", key),
+            AzureAuth::AzureAd(token) => {
+                builder.header("Authorization", format!("Bearer {}", token))
+            }
+        }
+    }
+
+    /// Converts Azure OpenAI API error to anyhow error.
+    fn handle_api_error(&self, status: u16, body: &str) -> anyhow::Error {
+        match serde_json::from_str::<OpenAIErrorResponse>(body) {
+            Ok(OpenAIErrorResponse { error: Some(error) }) => anyhow::anyhow!(
+                "Azure OpenAI API error ({}): {}",
+                status,
+                error.message.unwrap_or_else(|| "Unknown error".to_string())
+            ),
+            _ => anyhow::anyhow!("Azure OpenAI API error ({}): {}", status, body.trim()),
+        }
+    }
+}
+
+#[async_trait]
+impl ModelProvider for AzureOpenAIProvider {
+    fn id(&self) -> &str {
+        "azure-openai"
+    }
+
+    /// Lists the models with a configured deployment.
+    ///
+    /// Deployments are managed in Azure rather than through the inference
+    /// API, so only the models routed with
+    /// [`with_deployment()`](AzureOpenAIProvider::with_deployment) are known.
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let mut models: Vec<ModelInfo> = self
+            .deployments
+            .iter()
+            .map(|(model, deployment)| ModelInfo {
+                id: model.clone(),
+                name: format!("{} ({})", model, deployment),
+                provider: "azure-openai".to_string(),
+                context_window: OpenAIProvider::estimate_context_window(model),
+                supports_vision: OpenAIProvider::supports_vision(model),
+                supports_tools: OpenAIProvider::supports_tools(model),
+            })
+            .collect();
+        models.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(models)
+    }
+
+    async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionStream> {
+        let deployment = self.deployment_for(&request.model);
+        let body = OpenAIProvider::build_openai_request(&request);
+
+        debug!(
+            "Sending request to Azure OpenAI: deployment={}, stream={}",
+            deployment, body.stream
+        );
+
+        let url = self.url(&format!("deployments/{}/chat/completions", deployment));
+        let builder = self
+            .request(reqwest::Method::POST, &url)
+            .header("Content-Type", "application/json")
+            .json(&body);
+        let response = retry::send(builder, &self.retry_policy).await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            return Err(self.handle_api_error(status, &body));
+        }
+
+        Ok(OpenAIProvider::sse_stream(response))
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth> {
+        let url = self.url("models");
+        let start = std::time::Instant::now();
+        let response = self.request(reqwest::Method::GET, &url).send().await;
+        let latency_ms = start.elapsed().as_millis() as u64;
+
+        match response {
+            Ok(resp) if resp.status().is_success() => Ok(ProviderHealth {
+                available: true,
+                latency_ms: Some(latency_ms),
+            }),
+            Ok(resp) => {
+                warn!(
+                    "Health check failed with status: {}",
+                    resp.status().as_u16()
+                );
+                Ok(ProviderHealth {
+                    available: false,
+                    latency_ms: Some(latency_ms),
+                })
+            }
+            Err(e) => {
+                warn!("Health check error: {}", e);
+                Ok(ProviderHealth {
+                    available: false,
+                    latency_ms: None,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider() -> AzureOpenAIProvider {
+        AzureOpenAIProvider::new(
+            "https://my-resource.openai.azure.com/".to_string(),
+            AzureAuth::ApiKey("test-key".to_string()),
+            None,
+        )
+        .with_deployment("gpt-4o", "chat-prod")
+    }
+
+    #[test]
+    fn test_deployment_routing() {
+        let provider = provider();
+        assert_eq!(provider.deployment_for("gpt-4o"), "chat-prod");
+        assert_eq!(provider.deployment_for("gpt-35-turbo"), "gpt-35-turbo");
+    }
+
+    #[test]
+    fn test_url_includes_api_version() {
+        let provider = provider();
+        assert_eq!(
+            provider.url("deployments/chat-prod/chat/completions"),
+            "https://my-resource.openai.azure.com/openai/deployments/chat-prod/chat/completions?api-version=2024-06-01"
+        );
+    }
+
+    #[test]
+    fn test_handle_api_error() {
+        let provider = provider();
+        let err = provider.handle_api_error(
+            404,
+            r#"{"error":{"code":"DeploymentNotFound","message":"The API deployment for this resource does not exist."}}"#,
+        );
+        assert_eq!(
+            err.to_string(),
+            "Azure OpenAI API error (404): The API deployment for this resource does not exist."
+        );
+    }
+
+    #[test]
+    fn test_auth_debug_is_redacted() {
+        let auth = AzureAuth::AzureAd("secret-token".to_string());
+        assert_eq!(format!("{:?}", auth), "AzureAd(..)");
+    }
+}
@@ -125,6 +125,12 @@ impl BedrockProvider {
                                 source: BedrockImageSource::Bytes { data: data.clone() },
                             });
                         }
+                        ContentPart::ImageUrl { url } => {
+                            return Err(anyhow!(
+                                "Bedrock does not accept image URLs, send the image data: {}",
+                                url
+                            ))
+                        }
                     }
                 }
                 content_blocks
@@ -233,6 +239,7 @@ impl BedrockProvider {
                                 name, tool_use_id
                             )),
                             tool_calls: None,
+                            reasoning: None,
                         },
                         finish_reason: None,
                         usage: None,
@@ -248,6 +255,7 @@ impl BedrockProvider {
                         role: None,
                         content: Some(text.clone()),
                         tool_calls: None,
+                        reasoning: None,
                     },
                     finish_reason: None,
                     usage: None,
@@ -259,6 +267,7 @@ impl BedrockProvider {
                             role: None,
                             content: Some(input.clone()),
                             tool_calls: None,
+                            reasoning: None,
                         },
                         finish_reason: None,
                         usage: None,
@@ -281,6 +290,7 @@ impl BedrockProvider {
                         role: None,
                         content: None,
                         tool_calls: None,
+                        reasoning: None,
                     },
                     finish_reason,
                     usage: None,
@@ -291,6 +301,8 @@ impl BedrockProvider {
                     prompt_tokens: usage.input_tokens,
                     completion_tokens: usage.output_tokens,
                     total_tokens: usage.total_tokens.unwrap_or(0),
+                    cache_read_tokens: 0,
+                    cache_write_tokens: 0,
                 });
 
                 Some(ChatCompletionChunk {
@@ -302,6 +314,7 @@ impl BedrockProvider {
                         }),
                         content: None,
                         tool_calls: None,
+                        reasoning: None,
                     },
                     finish_reason: None,
                     usage,
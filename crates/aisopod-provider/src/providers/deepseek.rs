@@ -0,0 +1,370 @@
+//! DeepSeek API provider implementation.
+//!
+//! DeepSeek serves an OpenAI-compatible chat completions API. Its reasoning
+//! model (`deepseek-reasoner`) streams its chain of thought in a separate
+//! `reasoning_content` field before the answer; these tokens are reported in
+//! [`MessageDelta::reasoning`] rather than in the content, so callers can log
+//! or suppress them.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures_util::stream::{self, StreamExt};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::auth::{AuthProfile, AuthProfileManager};
+use crate::normalize::{ToolCallAccumulator, ToolCallDelta};
+use crate::providers::openai::api_types::OpenAIErrorResponse;
+use crate::providers::openai::OpenAIProvider;
+use crate::retry::{self, RetryPolicy};
+use crate::trait_module::{ChatCompletionStream, ModelProvider};
+use crate::types::*;
+
+pub mod api_types;
+use api_types::*;
+
+/// DeepSeek provider implementation.
+///
+/// This struct implements the [`ModelProvider`] trait for the DeepSeek API,
+/// supporting streaming SSE responses, tool calls, and the reasoning content
+/// of reasoning models.
+pub struct DeepSeekProvider {
+    client: reqwest::Client,
+    api_key: String,
+    pub base_url: String,
+    profile_manager: Arc<Mutex<AuthProfileManager>>,
+    retry_policy: RetryPolicy,
+}
+
+/// State carried across the chunks of a streamed response.
+///
+/// Incomplete SSE lines are buffered until the rest arrives, and tool call
+/// fragments are joined so each call is reported once, complete, on the
+/// chunk that finishes the response.
+#[derive(Default)]
+struct StreamState {
+    buffer: String,
+    tool_calls: ToolCallAccumulator,
+}
+
+impl StreamState {
+    /// Parses the complete SSE lines received so far.
+    fn push(&mut self, bytes: &[u8]) -> Vec<ChatCompletionChunk> {
+        self.buffer.push_str(&String::from_utf8_lossy(bytes));
+        let mut chunks = Vec::new();
+        while let Some(end) = self.buffer.find('\n') {
+            let line: String = self.buffer.drain(..=end).collect();
+            let Some(data) = line.trim().strip_prefix("data:").map(str::trim) else {
+                // Blank lines and keep-alive comments
+                continue;
+            };
+            if data == "[DONE]" {
+                continue;
+            }
+            match serde_json::from_str::<DeepSeekChunk>(data) {
+                Ok(chunk) => chunks.extend(self.convert(chunk)),
+                Err(e) => warn!("Skipping malformed DeepSeek chunk: {}", e),
+            }
+        }
+        chunks
+    }
+
+    /// Converts a DeepSeek chunk, holding back tool call fragments.
+    fn convert(&mut self, chunk: DeepSeekChunk) -> Option<ChatCompletionChunk> {
+        let usage = chunk.usage.map(|u| TokenUsage {
+            prompt_tokens: u.prompt_tokens,
+            completion_tokens: u.completion_tokens,
+            total_tokens: u.total_tokens,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+        });
+        let (delta, finish_reason) = match chunk.choices.into_iter().next() {
+            Some(choice) => (choice.delta, choice.finish_reason),
+            None => (DeepSeekDelta::default(), None),
+        };
+
+        for fragment in delta.tool_calls.into_iter().flatten() {
+            let (name, arguments) = fragment
+                .function
+                .map_or((None, None), |function| (function.name, function.arguments));
+            self.tool_calls.push(ToolCallDelta {
+                index: fragment.index,
+                id: fragment.id,
+                name,
+                arguments,
+            });
+        }
+
+        let finish_reason = finish_reason.as_deref().and_then(|s| match s {
+            "stop" => Some(FinishReason::Stop),
+            "length" => Some(FinishReason::Length),
+            "tool_calls" => Some(FinishReason::ToolCall),
+            "content_filter" => Some(FinishReason::ContentFilter),
+            "insufficient_system_resource" => Some(FinishReason::Error),
+            _ => None,
+        });
+        let tool_calls = if finish_reason.is_some() && !self.tool_calls.is_empty() {
+            Some(self.tool_calls.finish())
+        } else {
+            None
+        };
+
+        let delta = MessageDelta {
+            role: (delta.role.as_deref() == Some("assistant")).then_some(Role::Assistant),
+            content: delta.content.filter(|content| !content.is_empty()),
+            tool_calls,
+            reasoning: delta.reasoning_content.filter(|text| !text.is_empty()),
+        };
+        let is_empty = delta.role.is_none()
+            && delta.content.is_none()
+            && delta.tool_calls.is_none()
+            && delta.reasoning.is_none();
+        if is_empty && finish_reason.is_none() && usage.is_none() {
+            return None;
+        }
+
+        Some(ChatCompletionChunk {
+            id: chunk.id,
+            delta,
+            finish_reason,
+            usage,
+        })
+    }
+}
+
+impl DeepSeekProvider {
+    /// Creates a new DeepSeek provider instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - The API key for authenticating with DeepSeek.
+    /// * `base_url` - The base URL for the DeepSeek API (defaults to "https://api.deepseek.com").
+    /// * `cooldown_seconds` - The cooldown period in seconds for failed profiles.
+    pub fn new(api_key: String, base_url: Option<String>, cooldown_seconds: Option<u64>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key,
+            base_url: base_url.unwrap_or_else(|| "https://api.deepseek.com".to_string()),
+            profile_manager: Arc::new(Mutex::new(AuthProfileManager::new(Duration::from_secs(
+                cooldown_seconds.unwrap_or(60),
+            )))),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Sets how rate-limited requests are retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Returns the API key used for authentication.
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    /// Adds an authentication profile for key rotation.
+    pub fn add_profile(&mut self, profile: AuthProfile) {
+        let mut manager = self.profile_manager.lock().unwrap();
+        manager.add_profile(profile);
+    }
+
+    /// Gets the next available API key for round-robin rotation.
+    fn get_api_key(&self) -> String {
+        let mut manager = self.profile_manager.lock().unwrap();
+        manager
+            .next_key("deepseek")
+            .map(|p| p.api_key.clone())
+            .unwrap_or_else(|| self.api_key.clone())
+    }
+
+    /// Converts a streaming chat completions response into chunks.
+    ///
+    /// Other OpenAI-compatible servers that report `reasoning_content`, such
+    /// as llama.cpp, stream the same format.
+    pub(crate) fn sse_stream(response: reqwest::Response) -> ChatCompletionStream {
+        let mut state = StreamState::default();
+        let stream = response.bytes_stream().flat_map(move |bytes| {
+            let items: Vec<Result<ChatCompletionChunk>> = match bytes {
+                Ok(bytes) => state.push(&bytes).into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(anyhow::anyhow!("Stream error: {}", e))],
+            };
+            stream::iter(items)
+        });
+        Box::pin(stream)
+    }
+
+    /// Converts DeepSeek API error to anyhow error.
+    fn handle_api_error(status: u16, body: &str) -> anyhow::Error {
+        match serde_json::from_str::<OpenAIErrorResponse>(body) {
+            Ok(OpenAIErrorResponse { error: Some(error) }) => anyhow::anyhow!(
+                "DeepSeek API error ({}): {}",
+                status,
+                error.message.unwrap_or_else(|| "Unknown error".to_string())
+            ),
+            _ => anyhow::anyhow!("DeepSeek API error ({}): {}", status, body.trim()),
+        }
+    }
+}
+
+#[async_trait]
+impl ModelProvider for DeepSeekProvider {
+    fn id(&self) -> &str {
+        "deepseek"
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        debug!("Listing DeepSeek models");
+
+        let url = format!("{}/models", self.base_url);
+        let builder = self.client.get(&url).bearer_auth(self.get_api_key());
+        let response = retry::send(builder, &self.retry_policy).await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            return Err(Self::handle_api_error(status, &body));
+        }
+
+        let response: DeepSeekModelList = response.json().await?;
+
+        let models = response
+            .data
+            .into_iter()
+            .map(|model| ModelInfo {
+                name: model.id.clone(),
+                id: model.id,
+                provider: "deepseek".to_string(),
+                context_window: 131072,
+                supports_vision: false,
+                supports_tools: true,
+            })
+            .collect();
+
+        Ok(models)
+    }
+
+    async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionStream> {
+        let body = OpenAIProvider::build_openai_request(&request);
+
+        debug!(
+            "Sending request to DeepSeek API: model={}, stream={}",
+            body.model, body.stream
+        );
+
+        let url = format!("{}/chat/completions", self.base_url);
+        let builder = self
+            .client
+            .post(&url)
+            .bearer_auth(self.get_api_key())
+            .json(&body);
+        let response = retry::send(builder, &self.retry_policy).await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            return Err(Self::handle_api_error(status, &body));
+        }
+
+        Ok(Self::sse_stream(response))
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth> {
+        let url = format!("{}/models", self.base_url);
+        let start = std::time::Instant::now();
+        let response = self
+            .client
+            .get(&url)
+            .bearer_auth(self.get_api_key())
+            .send()
+            .await;
+        let latency_ms = start.elapsed().as_millis() as u64;
+
+        match response {
+            Ok(resp) if resp.status().is_success() => Ok(ProviderHealth {
+                available: true,
+                latency_ms: Some(latency_ms),
+            }),
+            Ok(resp) => {
+                warn!(
+                    "Health check failed with status: {}",
+                    resp.status().as_u16()
+                );
+                Ok(ProviderHealth {
+                    available: false,
+                    latency_ms: Some(latency_ms),
+                })
+            }
+            Err(e) => {
+                warn!("Health check error: {}", e);
+                Ok(ProviderHealth {
+                    available: false,
+                    latency_ms: None,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reasoning_content_is_kept_apart() {
+        let mut state = StreamState::default();
+        let chunks = state.push(
+            concat!(
+                "data: {\"id\":\"1\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"content\":null,\"reasoning_content\":\"\"},\"finish_reason\":null}]}\n\n",
+                "data: {\"id\":\"1\",\"choices\":[{\"index\":0,\"delta\":{\"content\":null,\"reasoning_content\":\"9.11 < 9.9\"},\"finish_reason\":null}]}\n\n",
+                "data: {\"id\":\"1\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"9.9\",\"reasoning_content\":null},\"finish_reason\":null}]}\n\n",
+            )
+            .as_bytes(),
+        );
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].delta.role, Some(Role::Assistant));
+        assert_eq!(chunks[1].delta.reasoning.as_deref(), Some("9.11 < 9.9"));
+        assert_eq!(chunks[1].delta.content, None);
+        assert_eq!(chunks[2].delta.content.as_deref(), Some("9.9"));
+        assert_eq!(chunks[2].delta.reasoning, None);
+    }
+
+    #[test]
+    fn test_tool_call_fragments_are_joined() {
+        let mut state = StreamState::default();
+        let mut chunks = state.push(
+            concat!(
+                "data: {\"id\":\"1\",\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,\"id\":\"call_0\",\"type\":\"function\",\"function\":{\"name\":\"get_weather\",\"arguments\":\"\"}}]},\"finish_reason\":null}]}\n\n",
+                "data: {\"id\":\"1\",\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"{\\\"city\\\":\"}}]},\"finish_reason\":null}]}\n\n",
+                "data: {\"id\":\"1\",\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\" \\\"Paris\\\"}\"}}]},\"finish_reason\":null}]}\n\n",
+                "data: {\"id\":\"1\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"\"},\"fin",
+            )
+            .as_bytes(),
+        );
+        assert!(chunks.is_empty());
+
+        // The rest of the final line arrives in a later read
+        chunks.extend(state.push("ish_reason\":\"tool_calls\"}]}\n\ndata: [DONE]\n\n".as_bytes()));
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].finish_reason, Some(FinishReason::ToolCall));
+        assert_eq!(
+            chunks[0].delta.tool_calls,
+            Some(vec![ToolCall {
+                id: "call_0".to_string(),
+                name: "get_weather".to_string(),
+                arguments: "{\"city\": \"Paris\"}".to_string(),
+            }])
+        );
+    }
+
+    #[test]
+    fn test_keep_alive_comments_are_skipped() {
+        let mut state = StreamState::default();
+        assert!(state.push(b": keep-alive\n\n").is_empty());
+    }
+}
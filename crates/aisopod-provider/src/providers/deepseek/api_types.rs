@@ -0,0 +1,82 @@
+//! DeepSeek-specific response types.
+//!
+//! DeepSeek serves an OpenAI-compatible chat completions API, so requests
+//! reuse the OpenAI types. Streamed chunks are defined here because reasoning
+//! models add a `reasoning_content` field to the delta, and tool calls arrive
+//! as fragments that are joined by their `index`.
+
+use serde::Deserialize;
+
+/// A streamed chat completion chunk.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DeepSeekChunk {
+    pub id: String,
+    #[serde(default)]
+    pub choices: Vec<DeepSeekChoice>,
+    #[serde(default)]
+    pub usage: Option<DeepSeekUsage>,
+}
+
+/// A choice within a streamed chunk.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DeepSeekChoice {
+    pub delta: DeepSeekDelta,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+/// The incremental message of a streamed choice.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct DeepSeekDelta {
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Chain-of-thought tokens produced by reasoning models.
+    #[serde(default)]
+    pub reasoning_content: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Option<Vec<DeepSeekToolCallDelta>>,
+}
+
+/// A fragment of a tool call.
+///
+/// The first fragment of a call carries its ID and function name; later
+/// fragments with the same `index` append to the arguments.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DeepSeekToolCallDelta {
+    pub index: usize,
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub function: Option<DeepSeekFunctionDelta>,
+}
+
+/// A fragment of a function call.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DeepSeekFunctionDelta {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub arguments: Option<String>,
+}
+
+/// Token usage reported with the final chunk.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DeepSeekUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+}
+
+/// Response from the models endpoint.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DeepSeekModelList {
+    pub data: Vec<DeepSeekModel>,
+}
+
+/// A model returned by the models endpoint.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DeepSeekModel {
+    pub id: String,
+}
@@ -8,12 +8,21 @@ use std::time::Duration;
 use tracing::warn;
 
 use crate::auth::{AuthProfile, AuthProfileManager};
+use crate::media::{fit_image, guess_image_type, ImageLimits};
+use crate::retry::{self, RetryPolicy};
 use crate::trait_module::{ChatCompletionStream, ModelProvider};
 use crate::types::*;
 
 pub mod api_types;
 use api_types::*;
 
+/// The largest inline images sent to Gemini; larger images cost more tokens
+/// without improving results.
+pub const IMAGE_LIMITS: ImageLimits = ImageLimits {
+    max_dimension: 3072,
+    max_bytes: 20 * 1024 * 1024,
+};
+
 /// Gemini provider implementation.
 ///
 /// This struct implements the [`ModelProvider`] trait for the Google Gemini API,
@@ -26,6 +35,7 @@ pub struct GeminiProvider {
     oauth_token: Option<String>,
     base_url: String,
     profile_manager: Arc<Mutex<AuthProfileManager>>,
+    retry_policy: RetryPolicy,
 }
 
 impl GeminiProvider {
@@ -52,9 +62,16 @@ impl GeminiProvider {
             profile_manager: Arc::new(Mutex::new(AuthProfileManager::new(Duration::from_secs(
                 cooldown_seconds.unwrap_or(60),
             )))),
+            retry_policy: RetryPolicy::default(),
         }
     }
 
+    /// Sets how rate-limited requests are retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
     /// Adds an authentication profile for key rotation.
     pub fn add_profile(&mut self, profile: AuthProfile) {
         let mut manager = self.profile_manager.lock().unwrap();
@@ -83,7 +100,7 @@ impl GeminiProvider {
                                     .iter()
                                     .filter_map(|part| match part {
                                         ContentPart::Text { text } => Some(text.clone()),
-                                        ContentPart::Image { .. } => None,
+                                        _ => None,
                                     })
                                     .collect::<Vec<_>>()
                                     .join("\n")
@@ -103,8 +120,17 @@ impl GeminiProvider {
                 .iter()
                 .map(|part| match part {
                     ContentPart::Text { text } => GeminiPart::Text { text: text.clone() },
-                    ContentPart::Image { media_type, data } => GeminiPart::Text {
-                        text: format!("image: {} (base64: {})", media_type, data),
+                    ContentPart::Image { media_type, data } => {
+                        let (mime_type, data) = fit_image(media_type, data, &IMAGE_LIMITS);
+                        GeminiPart::InlineData {
+                            inline_data: GeminiInlineData { mime_type, data },
+                        }
+                    }
+                    ContentPart::ImageUrl { url } => GeminiPart::FileData {
+                        file_data: GeminiFileData {
+                            mime_type: guess_image_type(url).to_string(),
+                            file_uri: url.clone(),
+                        },
                     },
                 })
                 .collect(),
@@ -171,6 +197,7 @@ impl GeminiProvider {
             .iter()
             .filter_map(|part| match part {
                 GeminiPart::Text { text } => Some(text.clone()),
+                _ => None,
             })
             .collect::<Vec<_>>()
             .join("");
@@ -185,6 +212,7 @@ impl GeminiProvider {
                 role: Some(Role::Assistant),
                 content: Some(text),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: candidate
                 .finish_reason
@@ -198,6 +226,8 @@ impl GeminiProvider {
                 prompt_tokens: usage.prompt_token_count.unwrap_or(0),
                 completion_tokens: usage.candidates_token_count.unwrap_or(0),
                 total_tokens: usage.total_token_count.unwrap_or(0),
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             }),
         })
     }
@@ -243,7 +273,7 @@ impl ModelProvider for GeminiProvider {
             request = request.query(&[("key", key)]);
         }
 
-        let response = request.send().await?;
+        let response = retry::send(request, &self.retry_policy).await?;
         let status = response.status();
 
         if status.is_success() {
@@ -293,7 +323,7 @@ impl ModelProvider for GeminiProvider {
             req = req.query(&[("key", key)]);
         }
 
-        let response = req.send().await?;
+        let response = retry::send(req, &self.retry_policy).await?;
         let status = response.status();
 
         if !status.is_success() {
@@ -395,6 +425,35 @@ mod tests {
         assert!(json.contains("\"Hello\""));
     }
 
+    #[test]
+    fn test_convert_message_images() {
+        let provider = GeminiProvider::new(Some("test-key".to_string()), None, None, None);
+        let message = Message {
+            role: Role::User,
+            content: MessageContent::Parts(vec![
+                ContentPart::Image {
+                    media_type: "image/png".to_string(),
+                    data: "bm90IGFuIGltYWdl".to_string(),
+                },
+                ContentPart::ImageUrl {
+                    url: "gs://bucket/cat.webp".to_string(),
+                },
+            ]),
+            tool_calls: None,
+            tool_call_id: None,
+        };
+
+        let content = provider.convert_message(&message);
+        let json = serde_json::to_value(&content.parts).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"inlineData": {"mimeType": "image/png", "data": "bm90IGFuIGltYWdl"}},
+                {"fileData": {"mimeType": "image/webp", "fileUri": "gs://bucket/cat.webp"}}
+            ])
+        );
+    }
+
     #[test]
     fn test_gemini_content_serialization() {
         let content = GeminiContent {
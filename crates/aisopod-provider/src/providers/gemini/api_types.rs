@@ -16,18 +16,37 @@ pub enum GeminiRole {
 
 /// A single part of content in a Gemini message.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(tag = "role", content = "parts")]
+#[serde(untagged)]
 pub enum GeminiPart {
-    Text { text: String },
+    Text {
+        text: String,
+    },
+    InlineData {
+        #[serde(rename = "inlineData")]
+        inline_data: GeminiInlineData,
+    },
+    FileData {
+        #[serde(rename = "fileData")]
+        file_data: GeminiFileData,
+    },
 }
 
 /// A content part for images in Gemini.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GeminiInlineData {
     pub mime_type: String,
     pub data: String,
 }
 
+/// A content part referencing media by URI in Gemini.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiFileData {
+    pub mime_type: String,
+    pub file_uri: String,
+}
+
 /// Function declaration for tool calling.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GeminiFunctionDeclaration {
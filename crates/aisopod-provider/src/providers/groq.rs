@@ -0,0 +1,311 @@
+//! Groq API provider implementation.
+//!
+//! Groq serves the OpenAI Chat Completions API from
+//! `https://api.groq.com/openai`. Every response reports the remaining
+//! request and token budget in `x-ratelimit-*` headers, which the provider
+//! records so callers can back off before hitting the limit.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::auth::{AuthProfile, AuthProfileManager, ProfileStatus};
+use crate::normalize::ProviderError;
+use crate::providers::openai::api_types::OpenAIErrorResponse;
+use crate::providers::openai::OpenAIProvider;
+use crate::retry::{self, parse_duration, RetryPolicy};
+use crate::trait_module::{ChatCompletionStream, ModelProvider};
+use crate::types::*;
+
+pub mod api_types;
+use api_types::*;
+
+/// Rate-limit state reported by Groq in the headers of a response.
+///
+/// Request limits are per day and token limits are per minute.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GroqRateLimits {
+    /// Maximum number of requests per day.
+    pub limit_requests: Option<u64>,
+    /// Requests left before the daily limit is reached.
+    pub remaining_requests: Option<u64>,
+    /// Time until the request limit resets.
+    pub reset_requests: Option<Duration>,
+    /// Maximum number of tokens per minute.
+    pub limit_tokens: Option<u64>,
+    /// Tokens left before the per-minute limit is reached.
+    pub remaining_tokens: Option<u64>,
+    /// Time until the token limit resets.
+    pub reset_tokens: Option<Duration>,
+}
+
+impl GroqRateLimits {
+    /// Reads the `x-ratelimit-*` headers of a response.
+    ///
+    /// Returns `None` if the response carries none of them.
+    pub fn from_headers(headers: &reqwest::header::HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+        let number = |name: &str| header(name).and_then(|value| value.parse().ok());
+        let duration = |name: &str| header(name).and_then(parse_duration);
+
+        let limits = Self {
+            limit_requests: number("x-ratelimit-limit-requests"),
+            remaining_requests: number("x-ratelimit-remaining-requests"),
+            reset_requests: duration("x-ratelimit-reset-requests"),
+            limit_tokens: number("x-ratelimit-limit-tokens"),
+            remaining_tokens: number("x-ratelimit-remaining-tokens"),
+            reset_tokens: duration("x-ratelimit-reset-tokens"),
+        };
+        (limits != Self::default()).then_some(limits)
+    }
+}
+
+/// Groq provider implementation.
+///
+/// This struct implements the [`ModelProvider`] trait for Groq's
+/// OpenAI-compatible API, supporting streaming SSE responses, tool use, and
+/// the Groq model catalog. A rate-limited response (HTTP 429) is returned as
+/// a [`ProviderError::RateLimited`] carrying the `retry-after` delay, and
+/// puts the API key profile that hit it into cooldown.
+pub struct GroqProvider {
+    client: reqwest::Client,
+    api_key: String,
+    pub base_url: String,
+    profile_manager: Arc<Mutex<AuthProfileManager>>,
+    rate_limits: Mutex<Option<GroqRateLimits>>,
+    retry_policy: RetryPolicy,
+}
+
+impl GroqProvider {
+    /// Creates a new Groq provider instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - The API key for authenticating with Groq.
+    /// * `base_url` - The base URL for the Groq API (defaults to "https://api.groq.com/openai").
+    /// * `cooldown_seconds` - The cooldown period in seconds for failed profiles.
+    pub fn new(api_key: String, base_url: Option<String>, cooldown_seconds: Option<u64>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key,
+            base_url: base_url.unwrap_or_else(|| "https://api.groq.com/openai".to_string()),
+            profile_manager: Arc::new(Mutex::new(AuthProfileManager::new(Duration::from_secs(
+                cooldown_seconds.unwrap_or(60),
+            )))),
+            rate_limits: Mutex::new(None),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Sets how rate-limited requests are retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Returns the API key used for authentication.
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    /// Adds an authentication profile for key rotation.
+    pub fn add_profile(&mut self, profile: AuthProfile) {
+        let mut manager = self.profile_manager.lock().unwrap();
+        manager.add_profile(profile);
+    }
+
+    /// Returns the rate limits reported by the most recent response.
+    pub fn rate_limits(&self) -> Option<GroqRateLimits> {
+        self.rate_limits.lock().unwrap().clone()
+    }
+
+    /// Gets the next available profile ID and API key for round-robin rotation.
+    fn next_key(&self) -> (Option<String>, String) {
+        let mut manager = self.profile_manager.lock().unwrap();
+        match manager.next_key("groq") {
+            Some(profile) => (Some(profile.id.clone()), profile.api_key.clone()),
+            None => (None, self.api_key.clone()),
+        }
+    }
+
+    /// Sends a request, recording the rate limits reported in its response.
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<&impl serde::Serialize>,
+    ) -> Result<reqwest::Response> {
+        let (profile_id, api_key) = self.next_key();
+        let url = format!("{}/v1/{}", self.base_url, path);
+
+        let mut request_builder = self.client.request(method, &url).bearer_auth(api_key);
+        if let Some(body) = body {
+            request_builder = request_builder.json(body);
+        }
+        let response = retry::send(request_builder, &self.retry_policy).await?;
+
+        if let Some(limits) = GroqRateLimits::from_headers(response.headers()) {
+            *self.rate_limits.lock().unwrap() = Some(limits);
+        }
+
+        let status = response.status().as_u16();
+        if status == 429 {
+            if let Some(profile_id) = profile_id {
+                let mut manager = self.profile_manager.lock().unwrap();
+                manager.mark_failed("groq", &profile_id, ProfileStatus::RateLimited);
+            }
+            let retry_after = retry::retry_after(response.headers());
+            return Err(ProviderError::RateLimited {
+                provider: "groq".to_string(),
+                retry_after,
+            }
+            .into());
+        }
+        if !response.status().is_success() {
+            let body = response.text().await?;
+            return Err(Self::handle_api_error(status, &body));
+        }
+        Ok(response)
+    }
+
+    /// Converts Groq API error to anyhow error.
+    fn handle_api_error(status: u16, body: &str) -> anyhow::Error {
+        match serde_json::from_str::<OpenAIErrorResponse>(body) {
+            Ok(OpenAIErrorResponse { error: Some(error) }) => anyhow::anyhow!(
+                "Groq API error ({}): {}",
+                status,
+                error.message.unwrap_or_else(|| "Unknown error".to_string())
+            ),
+            _ => anyhow::anyhow!("Groq API error ({}): {}", status, body.trim()),
+        }
+    }
+
+    /// Determines if a model accepts chat completions based on its name.
+    fn is_chat_model(model_id: &str) -> bool {
+        // Speech-to-text, text-to-speech and moderation models
+        !(model_id.contains("whisper") || model_id.contains("tts") || model_id.contains("guard"))
+    }
+
+    /// Determines if a model supports vision based on its name.
+    fn supports_vision(model_id: &str) -> bool {
+        model_id.contains("vision") || model_id.contains("llama-4")
+    }
+}
+
+#[async_trait]
+impl ModelProvider for GroqProvider {
+    fn id(&self) -> &str {
+        "groq"
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        debug!("Listing Groq models");
+
+        let response = self
+            .send(reqwest::Method::GET, "models", None::<&()>)
+            .await?;
+        let response: GroqModelList = response.json().await?;
+
+        let mut models: Vec<ModelInfo> = response
+            .data
+            .into_iter()
+            .filter(|model| model.active && Self::is_chat_model(&model.id))
+            .map(|model| ModelInfo {
+                name: model.id.clone(),
+                provider: "groq".to_string(),
+                context_window: model.context_window.unwrap_or(8192),
+                supports_vision: Self::supports_vision(&model.id),
+                // All chat models on Groq support tool use
+                supports_tools: true,
+                id: model.id,
+            })
+            .collect();
+        models.sort_by(|a, b| a.id.cmp(&b.id));
+
+        Ok(models)
+    }
+
+    async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionStream> {
+        let body = OpenAIProvider::build_openai_request(&request);
+
+        debug!(
+            "Sending request to Groq API: model={}, stream={}",
+            body.model, body.stream
+        );
+
+        let response = self
+            .send(reqwest::Method::POST, "chat/completions", Some(&body))
+            .await?;
+
+        Ok(OpenAIProvider::sse_stream(response))
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth> {
+        let start = std::time::Instant::now();
+        let response = self.send(reqwest::Method::GET, "models", None::<&()>).await;
+        let latency_ms = start.elapsed().as_millis() as u64;
+
+        match response {
+            Ok(_) => Ok(ProviderHealth {
+                available: true,
+                latency_ms: Some(latency_ms),
+            }),
+            Err(e) => {
+                warn!("Health check error: {}", e);
+                Ok(ProviderHealth {
+                    available: false,
+                    latency_ms: None,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    #[test]
+    fn test_rate_limits_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-ratelimit-limit-requests",
+            HeaderValue::from_static("14400"),
+        );
+        headers.insert(
+            "x-ratelimit-remaining-requests",
+            HeaderValue::from_static("14370"),
+        );
+        headers.insert(
+            "x-ratelimit-reset-requests",
+            HeaderValue::from_static("2m59.56s"),
+        );
+        headers.insert(
+            "x-ratelimit-remaining-tokens",
+            HeaderValue::from_static("17997"),
+        );
+
+        let limits = GroqRateLimits::from_headers(&headers).unwrap();
+        assert_eq!(limits.limit_requests, Some(14400));
+        assert_eq!(limits.remaining_requests, Some(14370));
+        assert_eq!(limits.reset_requests, Some(Duration::from_millis(179560)));
+        assert_eq!(limits.limit_tokens, None);
+        assert_eq!(limits.remaining_tokens, Some(17997));
+
+        assert!(GroqRateLimits::from_headers(&HeaderMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_is_chat_model() {
+        assert!(GroqProvider::is_chat_model("llama-3.3-70b-versatile"));
+        assert!(GroqProvider::is_chat_model("mixtral-8x7b-32768"));
+        assert!(!GroqProvider::is_chat_model("whisper-large-v3"));
+        assert!(!GroqProvider::is_chat_model("playai-tts"));
+    }
+}
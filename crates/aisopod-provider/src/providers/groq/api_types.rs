@@ -0,0 +1,30 @@
+//! Groq-specific response types.
+//!
+//! Groq serves an OpenAI-compatible chat completions API, so requests and
+//! streamed responses reuse the OpenAI types. The models endpoint reports
+//! additional fields that are defined here.
+
+use serde::Deserialize;
+
+/// Response from the models endpoint.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct GroqModelList {
+    pub data: Vec<GroqModel>,
+}
+
+/// A model in the Groq catalog.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct GroqModel {
+    pub id: String,
+    #[serde(default)]
+    pub owned_by: Option<String>,
+    /// Whether the model can currently be used.
+    #[serde(default = "default_active")]
+    pub active: bool,
+    #[serde(default)]
+    pub context_window: Option<u32>,
+}
+
+fn default_active() -> bool {
+    true
+}
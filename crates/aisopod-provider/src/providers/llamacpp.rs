@@ -0,0 +1,459 @@
+//! llama.cpp server provider implementation for local LLM inference.
+//!
+//! `llama-server` serves a single loaded model through an OpenAI-compatible
+//! API and a native `/completion` endpoint. It runs without authentication
+//! by default; an API key is only needed if the server was started with
+//! `--api-key`.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures_util::stream::{self, StreamExt};
+use tracing::{debug, warn};
+
+use crate::providers::deepseek::DeepSeekProvider;
+use crate::providers::openai::OpenAIProvider;
+use crate::trait_module::{ChatCompletionStream, ModelProvider};
+use crate::types::*;
+
+pub mod api_types;
+use api_types::*;
+
+/// The endpoint completions are requested from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LlamaCppEndpoint {
+    /// The OpenAI-compatible `/v1/chat/completions` endpoint, which formats
+    /// the conversation with the model's chat template.
+    #[default]
+    Chat,
+    /// The native `/completion` endpoint, for base models without a chat
+    /// template. The conversation is rendered as a plain transcript and tool
+    /// use is not supported.
+    Completion,
+}
+
+/// llama.cpp server provider implementation.
+///
+/// This struct implements the [`ModelProvider`] trait for `llama-server`,
+/// supporting streaming chat completions, tool use and reasoning content
+/// where the model's chat template supports them, and detection of the
+/// configured context window from the server properties.
+///
+/// # Example
+///
+/// ```ignore
+/// use aisopod_provider::providers::llamacpp::LlamaCppProvider;
+///
+/// let provider = LlamaCppProvider::new(Some("http://localhost:8080".to_string()), None);
+/// ```
+pub struct LlamaCppProvider {
+    client: reqwest::Client,
+    pub base_url: String,
+    api_key: Option<String>,
+    endpoint: LlamaCppEndpoint,
+}
+
+impl LlamaCppProvider {
+    /// Creates a new llama.cpp provider instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - The base URL of the server (defaults to "http://localhost:8080").
+    /// * `api_key` - The API key, if the server was started with `--api-key`.
+    pub fn new(base_url: Option<String>, api_key: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.unwrap_or_else(|| "http://localhost:8080".to_string()),
+            api_key: api_key.filter(|key| !key.is_empty()),
+            endpoint: LlamaCppEndpoint::default(),
+        }
+    }
+
+    /// Sets the endpoint completions are requested from.
+    pub fn with_endpoint(mut self, endpoint: LlamaCppEndpoint) -> Self {
+        self.endpoint = endpoint;
+        self
+    }
+
+    /// Returns the endpoint completions are requested from.
+    pub fn endpoint(&self) -> LlamaCppEndpoint {
+        self.endpoint
+    }
+
+    /// Starts a request, with the API key if one is configured.
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let builder = self
+            .client
+            .request(method, format!("{}{}", self.base_url, path));
+        match &self.api_key {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        }
+    }
+
+    /// Fetches the server properties.
+    async fn props(&self) -> Result<LlamaCppProps> {
+        let response = self.request(reqwest::Method::GET, "/props").send().await?;
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            return Err(Self::handle_api_error(status, &body));
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Renders a conversation as a plain transcript for the completion
+    /// endpoint, ending with the assistant's turn.
+    fn render_prompt(messages: &[Message]) -> String {
+        let mut prompt = String::new();
+        for message in messages {
+            let speaker = match message.role {
+                Role::System => "System",
+                Role::User => "User",
+                Role::Assistant => "Assistant",
+                Role::Tool => "Tool",
+            };
+            let text = match &message.content {
+                MessageContent::Text(text) => text.clone(),
+                MessageContent::Parts(parts) => parts
+                    .iter()
+                    .filter_map(|part| match part {
+                        ContentPart::Text { text } => Some(text.as_str()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            };
+            prompt.push_str(&format!("{}: {}\n", speaker, text));
+        }
+        prompt.push_str("Assistant:");
+        prompt
+    }
+
+    /// Builds the completion endpoint request from a core request.
+    fn build_completion_request(request: &ChatCompletionRequest) -> LlamaCppCompletionRequest {
+        let mut stop = request.stop.clone().unwrap_or_default();
+        // Stop before the model writes the user's next turn
+        stop.push("\nUser:".to_string());
+
+        LlamaCppCompletionRequest {
+            prompt: Self::render_prompt(&request.messages),
+            n_predict: request.max_tokens.map_or(-1, |max| max as i32),
+            temperature: request.temperature,
+            stop,
+            stream: request.stream,
+        }
+    }
+
+    /// Parses a line from the completion endpoint into a chat completion chunk.
+    ///
+    /// Streamed lines carry a `data: ` prefix; a non-streamed response is a
+    /// single JSON object.
+    fn parse_completion_line(id: &str, line: &str) -> Option<ChatCompletionChunk> {
+        let trimmed = line.trim();
+        let data = trimmed.strip_prefix("data:").unwrap_or(trimmed).trim();
+        if data.is_empty() || data.starts_with(':') {
+            return None;
+        }
+
+        let chunk: LlamaCppCompletionChunk = serde_json::from_str(data).ok()?;
+        let (finish_reason, usage) = if chunk.stop {
+            let prompt_tokens = chunk.tokens_evaluated.unwrap_or(0);
+            let completion_tokens = chunk.tokens_predicted.unwrap_or(0);
+            (
+                Some(if chunk.stopped_limit {
+                    FinishReason::Length
+                } else {
+                    FinishReason::Stop
+                }),
+                Some(TokenUsage {
+                    prompt_tokens,
+                    completion_tokens,
+                    total_tokens: prompt_tokens + completion_tokens,
+                    cache_read_tokens: 0,
+                    cache_write_tokens: 0,
+                }),
+            )
+        } else {
+            (None, None)
+        };
+
+        let content = Some(chunk.content).filter(|content| !content.is_empty());
+        if content.is_none() && finish_reason.is_none() {
+            return None;
+        }
+
+        Some(ChatCompletionChunk {
+            id: id.to_string(),
+            delta: MessageDelta {
+                role: None,
+                content,
+                tool_calls: None,
+                reasoning: None,
+            },
+            finish_reason,
+            usage,
+        })
+    }
+
+    /// Requests a completion from the native completion endpoint.
+    async fn completion(&self, request: ChatCompletionRequest) -> Result<ChatCompletionStream> {
+        if request
+            .tools
+            .as_ref()
+            .is_some_and(|tools| !tools.is_empty())
+        {
+            return Err(anyhow::anyhow!(
+                "llama.cpp completion endpoint does not support tools"
+            ));
+        }
+        let body = Self::build_completion_request(&request);
+
+        debug!("Sending request to llama.cpp completion endpoint");
+
+        let response = self
+            .request(reqwest::Method::POST, "/completion")
+            .json(&body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            return Err(Self::handle_api_error(status, &body));
+        }
+
+        let id = format!(
+            "completion-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis()
+        );
+        let mut buffer = String::new();
+        let stream = response.bytes_stream().flat_map(move |bytes| {
+            let items: Vec<Result<ChatCompletionChunk>> = match bytes {
+                Ok(bytes) => {
+                    buffer.push_str(&String::from_utf8_lossy(&bytes));
+                    let mut chunks = Vec::new();
+                    while let Some(end) = buffer.find('\n') {
+                        let line: String = buffer.drain(..=end).collect();
+                        chunks.extend(Self::parse_completion_line(&id, &line).map(Ok));
+                    }
+                    // A non-streamed response may not end with a newline
+                    if !buffer.is_empty() {
+                        if let Some(chunk) = Self::parse_completion_line(&id, &buffer) {
+                            buffer.clear();
+                            chunks.push(Ok(chunk));
+                        }
+                    }
+                    chunks
+                }
+                Err(e) => vec![Err(anyhow::anyhow!("Stream error: {}", e))],
+            };
+            stream::iter(items)
+        });
+        Ok(Box::pin(stream))
+    }
+
+    /// Converts llama.cpp server error to anyhow error.
+    fn handle_api_error(status: u16, body: &str) -> anyhow::Error {
+        match serde_json::from_str::<LlamaCppErrorResponse>(body) {
+            Ok(response) => {
+                anyhow::anyhow!("llama.cpp error ({}): {}", status, response.error.message)
+            }
+            Err(_) => anyhow::anyhow!("llama.cpp error ({}): {}", status, body.trim()),
+        }
+    }
+}
+
+#[async_trait]
+impl ModelProvider for LlamaCppProvider {
+    fn id(&self) -> &str {
+        "llamacpp"
+    }
+
+    /// Lists the model loaded by the server.
+    ///
+    /// The context window is the per-slot context size reported by the
+    /// server properties, falling back to the model's training context.
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        debug!("Listing llama.cpp models");
+
+        let response = self
+            .request(reqwest::Method::GET, "/v1/models")
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            return Err(Self::handle_api_error(status, &body));
+        }
+        let response: LlamaCppModelList = response.json().await?;
+
+        let props = self.props().await.unwrap_or_else(|e| {
+            warn!("Failed to read llama.cpp server properties: {}", e);
+            LlamaCppProps::default()
+        });
+        let supports_tools = self.endpoint == LlamaCppEndpoint::Chat
+            && props
+                .chat_template_caps
+                .as_ref()
+                .is_some_and(|caps| caps.supports_tools);
+
+        let models = response
+            .data
+            .into_iter()
+            .map(|model| {
+                let context_window = props
+                    .default_generation_settings
+                    .n_ctx
+                    .or_else(|| model.meta.as_ref().and_then(|meta| meta.n_ctx_train))
+                    .unwrap_or(4096);
+                // Model IDs default to the path of the model file
+                let name = model
+                    .id
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or(&model.id)
+                    .trim_end_matches(".gguf")
+                    .to_string();
+                ModelInfo {
+                    id: model.id,
+                    name,
+                    provider: "llamacpp".to_string(),
+                    context_window,
+                    supports_vision: props.modalities.vision,
+                    supports_tools,
+                }
+            })
+            .collect();
+
+        Ok(models)
+    }
+
+    async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionStream> {
+        if self.endpoint == LlamaCppEndpoint::Completion {
+            return self.completion(request).await;
+        }
+
+        let body = OpenAIProvider::build_openai_request(&request);
+
+        debug!(
+            "Sending request to llama.cpp server: model={}, stream={}",
+            body.model, body.stream
+        );
+
+        let response = self
+            .request(reqwest::Method::POST, "/v1/chat/completions")
+            .json(&body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            return Err(Self::handle_api_error(status, &body));
+        }
+
+        Ok(DeepSeekProvider::sse_stream(response))
+    }
+
+    /// Checks the server health; the server is unavailable while it is
+    /// still loading the model.
+    async fn health_check(&self) -> Result<ProviderHealth> {
+        let start = std::time::Instant::now();
+        let response = self.request(reqwest::Method::GET, "/health").send().await;
+        let latency_ms = start.elapsed().as_millis() as u64;
+
+        match response {
+            Ok(resp) if resp.status().is_success() => Ok(ProviderHealth {
+                available: true,
+                latency_ms: Some(latency_ms),
+            }),
+            Ok(resp) => {
+                warn!(
+                    "Health check failed with status: {}",
+                    resp.status().as_u16()
+                );
+                Ok(ProviderHealth {
+                    available: false,
+                    latency_ms: Some(latency_ms),
+                })
+            }
+            Err(e) => {
+                warn!("Health check error: {}", e);
+                Ok(ProviderHealth {
+                    available: false,
+                    latency_ms: None,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: Role, text: &str) -> Message {
+        Message {
+            role,
+            content: MessageContent::Text(text.to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+        }
+    }
+
+    #[test]
+    fn test_render_prompt() {
+        let prompt = LlamaCppProvider::render_prompt(&[
+            message(Role::System, "Be brief."),
+            message(Role::User, "Hi"),
+            message(Role::Assistant, "Hello!"),
+            message(Role::User, "How are you?"),
+        ]);
+        assert_eq!(
+            prompt,
+            "System: Be brief.\nUser: Hi\nAssistant: Hello!\nUser: How are you?\nAssistant:"
+        );
+    }
+
+    #[test]
+    fn test_build_completion_request() {
+        let request = ChatCompletionRequest {
+            model: "model".to_string(),
+            messages: vec![message(Role::User, "Hi")],
+            tools: None,
+            temperature: Some(0.2),
+            max_tokens: None,
+            stop: Some(vec!["###".to_string()]),
+            stream: true,
+        };
+        let body = LlamaCppProvider::build_completion_request(&request);
+        assert_eq!(body.n_predict, -1);
+        assert_eq!(body.stop, ["###", "\nUser:"]);
+    }
+
+    #[test]
+    fn test_parse_completion_line() {
+        let chunk = LlamaCppProvider::parse_completion_line(
+            "c",
+            r#"data: {"content":" there","stop":false,"id_slot":0}"#,
+        )
+        .unwrap();
+        assert_eq!(chunk.delta.content.as_deref(), Some(" there"));
+        assert_eq!(chunk.finish_reason, None);
+
+        let last = LlamaCppProvider::parse_completion_line(
+            "c",
+            r#"data: {"content":"","stop":true,"stopped_eos":false,"stopped_limit":true,"tokens_predicted":16,"tokens_evaluated":9}"#,
+        )
+        .unwrap();
+        assert_eq!(last.delta.content, None);
+        assert_eq!(last.finish_reason, Some(FinishReason::Length));
+        assert_eq!(last.usage.unwrap().total_tokens, 25);
+    }
+}
@@ -0,0 +1,111 @@
+//! llama.cpp server-specific request/response types.
+//!
+//! The llama.cpp server (`llama-server`) serves an OpenAI-compatible chat
+//! completions API, so chat requests reuse the OpenAI types. The native
+//! `/completion` and `/props` endpoints have their own shapes, defined here.
+
+use serde::{Deserialize, Serialize};
+
+/// Response from the OpenAI-compatible models endpoint.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LlamaCppModelList {
+    pub data: Vec<LlamaCppModel>,
+}
+
+/// The model loaded by the server.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LlamaCppModel {
+    /// The model alias, or the path of the model file.
+    pub id: String,
+    #[serde(default)]
+    pub meta: Option<LlamaCppModelMeta>,
+}
+
+/// Metadata of the loaded model file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LlamaCppModelMeta {
+    /// The context length the model was trained with.
+    #[serde(default)]
+    pub n_ctx_train: Option<u32>,
+}
+
+/// Response from the server properties endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct LlamaCppProps {
+    #[serde(default)]
+    pub default_generation_settings: LlamaCppGenerationSettings,
+    #[serde(default)]
+    pub modalities: LlamaCppModalities,
+    /// Capabilities of the chat template, reported by newer servers.
+    #[serde(default)]
+    pub chat_template_caps: Option<LlamaCppTemplateCaps>,
+}
+
+/// Generation settings of the server slots.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct LlamaCppGenerationSettings {
+    /// The context size of each slot, as started with `--ctx-size`.
+    #[serde(default)]
+    pub n_ctx: Option<u32>,
+}
+
+/// Input modalities supported by the loaded model.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct LlamaCppModalities {
+    #[serde(default)]
+    pub vision: bool,
+}
+
+/// Capabilities of the chat template.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct LlamaCppTemplateCaps {
+    #[serde(default)]
+    pub supports_tools: bool,
+}
+
+/// The request body for the native completion endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LlamaCppCompletionRequest {
+    pub prompt: String,
+    /// Maximum number of tokens to predict; `-1` means no limit.
+    pub n_predict: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+    pub stream: bool,
+}
+
+/// A chunk from the native completion endpoint.
+///
+/// The final chunk has `stop` set and reports why generation stopped and how
+/// many tokens were processed.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct LlamaCppCompletionChunk {
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub stop: bool,
+    #[serde(default)]
+    pub stopped_limit: bool,
+    #[serde(default)]
+    pub tokens_predicted: Option<u32>,
+    #[serde(default)]
+    pub tokens_evaluated: Option<u32>,
+}
+
+/// Error response from the llama.cpp server.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LlamaCppErrorResponse {
+    pub error: LlamaCppError,
+}
+
+/// Error details from the llama.cpp server.
+///
+/// The `code` is the numeric HTTP status.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LlamaCppError {
+    #[serde(default)]
+    pub code: Option<u16>,
+    pub message: String,
+}
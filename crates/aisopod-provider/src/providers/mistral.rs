@@ -0,0 +1,471 @@
+//! Mistral AI chat completions API provider implementation.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures_util::stream::{self, StreamExt};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::auth::{AuthProfile, AuthProfileManager};
+use crate::retry::{self, RetryPolicy};
+use crate::trait_module::{ChatCompletionStream, ModelProvider};
+use crate::types::*;
+
+pub mod api_types;
+use api_types::*;
+
+/// Mistral AI provider implementation.
+///
+/// This struct implements the [`ModelProvider`] trait for the Mistral AI
+/// chat completions API, supporting streaming SSE responses, tool calling,
+/// vision (image content parts), and model listing with capabilities
+/// reported by the API.
+pub struct MistralProvider {
+    client: reqwest::Client,
+    api_key: String,
+    pub base_url: String,
+    profile_manager: Arc<Mutex<AuthProfileManager>>,
+    retry_policy: RetryPolicy,
+}
+
+impl MistralProvider {
+    /// Creates a new Mistral provider instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - The API key for authenticating with Mistral AI.
+    /// * `base_url` - The base URL for the Mistral API (defaults to "https://api.mistral.ai").
+    /// * `cooldown_seconds` - The cooldown period in seconds for failed profiles.
+    pub fn new(api_key: String, base_url: Option<String>, cooldown_seconds: Option<u64>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key,
+            base_url: base_url.unwrap_or_else(|| "https://api.mistral.ai".to_string()),
+            profile_manager: Arc::new(Mutex::new(AuthProfileManager::new(Duration::from_secs(
+                cooldown_seconds.unwrap_or(60),
+            )))),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Sets how rate-limited requests are retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Returns the API key used for authentication.
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    /// Adds an authentication profile for key rotation.
+    pub fn add_profile(&mut self, profile: AuthProfile) {
+        let mut manager = self.profile_manager.lock().unwrap();
+        manager.add_profile(profile);
+    }
+
+    /// Gets the next available API key for round-robin rotation.
+    fn get_api_key(&self) -> String {
+        let mut manager = self.profile_manager.lock().unwrap();
+        manager
+            .next_key("mistral")
+            .map(|p| p.api_key.clone())
+            .unwrap_or_else(|| self.api_key.clone())
+    }
+
+    /// Converts a core [`Message`] to a Mistral message.
+    fn convert_message(message: &Message) -> MistralMessage {
+        let role = match message.role {
+            Role::System => MistralRole::System,
+            Role::User => MistralRole::User,
+            Role::Assistant => MistralRole::Assistant,
+            Role::Tool => MistralRole::Tool,
+        };
+
+        let content = match &message.content {
+            MessageContent::Text(text) => MistralContent::Text(text.clone()),
+            MessageContent::Parts(parts) => MistralContent::Parts(
+                parts
+                    .iter()
+                    .map(|part| match part {
+                        ContentPart::Text { text } => {
+                            MistralContentPart::Text { text: text.clone() }
+                        }
+                        ContentPart::Image { media_type, data } => {
+                            // Mistral accepts image URLs and base64 data URLs
+                            let image_url = if media_type.starts_with("image/") {
+                                format!("data:{};base64,{}", media_type, data)
+                            } else {
+                                data.clone()
+                            };
+                            MistralContentPart::ImageUrl { image_url }
+                        }
+                        ContentPart::ImageUrl { url } => MistralContentPart::ImageUrl {
+                            image_url: url.clone(),
+                        },
+                    })
+                    .collect(),
+            ),
+        };
+
+        let tool_calls = message.tool_calls.as_ref().map(|tool_calls| {
+            tool_calls
+                .iter()
+                .map(|tool_call| MistralToolCall {
+                    id: tool_call.id.clone(),
+                    tool_type: "function".to_string(),
+                    function: MistralFunctionCall {
+                        name: tool_call.name.clone(),
+                        arguments: tool_call.arguments.clone(),
+                    },
+                })
+                .collect()
+        });
+
+        MistralMessage {
+            role,
+            content,
+            tool_calls,
+            tool_call_id: message.tool_call_id.clone(),
+        }
+    }
+
+    /// Converts a core [`ToolDefinition`] to a Mistral tool.
+    fn convert_tool(tool: &ToolDefinition) -> MistralTool {
+        MistralTool {
+            r#type: "function".to_string(),
+            function: MistralFunctionDefinition {
+                name: tool.name.clone(),
+                description: tool.description.clone(),
+                parameters: tool.parameters.clone(),
+            },
+        }
+    }
+
+    /// Builds the Mistral request from a core request.
+    fn build_mistral_request(request: &ChatCompletionRequest) -> MistralRequest {
+        let tools: Option<Vec<MistralTool>> = request
+            .tools
+            .as_ref()
+            .filter(|tools| !tools.is_empty())
+            .map(|tools| tools.iter().map(Self::convert_tool).collect());
+
+        MistralRequest {
+            model: request.model.clone(),
+            messages: request.messages.iter().map(Self::convert_message).collect(),
+            tool_choice: tools.as_ref().map(|_| "auto".to_string()),
+            tools,
+            temperature: request.temperature,
+            max_tokens: request.max_tokens,
+            stop: request.stop.clone(),
+            stream: request.stream,
+        }
+    }
+
+    /// Parses a Mistral SSE line into a chat completion chunk.
+    fn parse_sse_event(line: &str) -> Option<ChatCompletionChunk> {
+        let data = line.trim().strip_prefix("data:")?.trim();
+        if data == "[DONE]" {
+            return None;
+        }
+
+        let chunk: MistralChunk = serde_json::from_str(data).ok()?;
+        let choice = chunk.choices.into_iter().next();
+        let usage = chunk.usage.map(|u| TokenUsage {
+            prompt_tokens: u.prompt_tokens,
+            completion_tokens: u.completion_tokens,
+            total_tokens: u.total_tokens,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+        });
+
+        let Some(choice) = choice else {
+            // Usage may arrive on a chunk without choices
+            return usage.map(|usage| ChatCompletionChunk {
+                id: chunk.id,
+                delta: MessageDelta {
+                    role: None,
+                    content: None,
+                    tool_calls: None,
+                    reasoning: None,
+                },
+                finish_reason: None,
+                usage: Some(usage),
+            });
+        };
+
+        let role = choice.delta.role.map(|r| match r {
+            MistralRole::System => Role::System,
+            MistralRole::User => Role::User,
+            MistralRole::Assistant => Role::Assistant,
+            MistralRole::Tool => Role::Tool,
+        });
+
+        // Mistral sends each tool call complete in a single delta
+        let tool_calls = choice.delta.tool_calls.map(|tool_calls| {
+            tool_calls
+                .into_iter()
+                .map(|tool_call| ToolCall {
+                    id: tool_call.id,
+                    name: tool_call.function.name,
+                    arguments: tool_call.function.arguments,
+                })
+                .collect()
+        });
+
+        let finish_reason = choice.finish_reason.as_deref().and_then(|s| match s {
+            "stop" => Some(FinishReason::Stop),
+            "length" | "model_length" => Some(FinishReason::Length),
+            "tool_calls" => Some(FinishReason::ToolCall),
+            "error" => Some(FinishReason::Error),
+            _ => None,
+        });
+
+        Some(ChatCompletionChunk {
+            id: chunk.id,
+            delta: MessageDelta {
+                role,
+                content: choice.delta.content.filter(|content| !content.is_empty()),
+                tool_calls,
+                reasoning: None,
+            },
+            finish_reason,
+            usage,
+        })
+    }
+
+    /// Converts a streaming chat completions response into chunks.
+    ///
+    /// SSE lines may be split across network reads, so incomplete lines are
+    /// buffered until the rest arrives.
+    fn sse_stream(response: reqwest::Response) -> ChatCompletionStream {
+        let mut buffer = String::new();
+        let stream = response.bytes_stream().flat_map(move |bytes| {
+            let items: Vec<Result<ChatCompletionChunk>> = match bytes {
+                Ok(bytes) => {
+                    buffer.push_str(&String::from_utf8_lossy(&bytes));
+                    let mut chunks = Vec::new();
+                    while let Some(end) = buffer.find('\n') {
+                        let line: String = buffer.drain(..=end).collect();
+                        chunks.extend(Self::parse_sse_event(&line).map(Ok));
+                    }
+                    chunks
+                }
+                Err(e) => vec![Err(anyhow::anyhow!("Stream error: {}", e))],
+            };
+            stream::iter(items)
+        });
+        Box::pin(stream)
+    }
+
+    /// Converts Mistral API error to anyhow error.
+    fn handle_api_error(&self, status: u16, body: &str) -> anyhow::Error {
+        match serde_json::from_str::<MistralErrorResponse>(body) {
+            Ok(MistralErrorResponse {
+                message: Some(message),
+                ..
+            }) => anyhow::anyhow!("Mistral API error ({}): {}", status, message),
+            Ok(MistralErrorResponse {
+                detail: Some(detail),
+                ..
+            }) => anyhow::anyhow!("Mistral API error ({}): {}", status, detail),
+            _ => anyhow::anyhow!("Mistral API error ({}): {}", status, body.trim()),
+        }
+    }
+}
+
+#[async_trait]
+impl ModelProvider for MistralProvider {
+    fn id(&self) -> &str {
+        "mistral"
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        debug!("Listing Mistral models");
+
+        let url = format!("{}/v1/models", self.base_url);
+        let builder = self.client.get(&url).bearer_auth(self.get_api_key());
+        let response = retry::send(builder, &self.retry_policy).await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            return Err(self.handle_api_error(status, &body));
+        }
+
+        let response: MistralModelList = response.json().await?;
+
+        // Embedding and moderation models cannot serve chat completions
+        let models = response
+            .data
+            .into_iter()
+            .filter(|model| model.capabilities.completion_chat)
+            .map(|model| ModelInfo {
+                name: model.name.unwrap_or_else(|| model.id.clone()),
+                id: model.id,
+                provider: "mistral".to_string(),
+                context_window: model.max_context_length.unwrap_or(32768),
+                supports_vision: model.capabilities.vision,
+                supports_tools: model.capabilities.function_calling,
+            })
+            .collect();
+
+        Ok(models)
+    }
+
+    async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionStream> {
+        let mistral_request = Self::build_mistral_request(&request);
+
+        debug!(
+            "Sending request to Mistral API: model={}, stream={}",
+            mistral_request.model, mistral_request.stream
+        );
+
+        let url = format!("{}/v1/chat/completions", self.base_url);
+        let builder = self
+            .client
+            .post(&url)
+            .bearer_auth(self.get_api_key())
+            .json(&mistral_request);
+        let response = retry::send(builder, &self.retry_policy).await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            return Err(self.handle_api_error(status, &body));
+        }
+
+        Ok(Self::sse_stream(response))
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth> {
+        let url = format!("{}/v1/models", self.base_url);
+        let start = std::time::Instant::now();
+        let response = self
+            .client
+            .get(&url)
+            .bearer_auth(self.get_api_key())
+            .send()
+            .await;
+        let latency_ms = start.elapsed().as_millis() as u64;
+
+        match response {
+            Ok(resp) if resp.status().is_success() => Ok(ProviderHealth {
+                available: true,
+                latency_ms: Some(latency_ms),
+            }),
+            Ok(resp) => {
+                warn!(
+                    "Health check failed with status: {}",
+                    resp.status().as_u16()
+                );
+                Ok(ProviderHealth {
+                    available: false,
+                    latency_ms: Some(latency_ms),
+                })
+            }
+            Err(e) => {
+                warn!("Health check error: {}", e);
+                Ok(ProviderHealth {
+                    available: false,
+                    latency_ms: None,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_message_image_part() {
+        let message = Message {
+            role: Role::User,
+            content: MessageContent::Parts(vec![
+                ContentPart::Text {
+                    text: "What is this?".to_string(),
+                },
+                ContentPart::Image {
+                    media_type: "image/png".to_string(),
+                    data: "aGVsbG8=".to_string(),
+                },
+            ]),
+            tool_calls: None,
+            tool_call_id: None,
+        };
+
+        let converted = MistralProvider::convert_message(&message);
+        assert_eq!(
+            serde_json::to_value(&converted).unwrap(),
+            serde_json::json!({
+                "role": "user",
+                "content": [
+                    {"type": "text", "text": "What is this?"},
+                    {"type": "image_url", "image_url": "data:image/png;base64,aGVsbG8="}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_build_request_omits_unset_fields() {
+        let request = ChatCompletionRequest {
+            model: "mistral-small-latest".to_string(),
+            messages: vec![Message {
+                role: Role::User,
+                content: MessageContent::Text("Hello".to_string()),
+                tool_calls: None,
+                tool_call_id: None,
+            }],
+            tools: None,
+            temperature: None,
+            max_tokens: None,
+            stop: None,
+            stream: true,
+        };
+
+        let body = serde_json::to_value(MistralProvider::build_mistral_request(&request)).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "model": "mistral-small-latest",
+                "messages": [{"role": "user", "content": "Hello"}],
+                "stream": true
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_sse_event_tool_call() {
+        let line = r#"data: {"id":"cmpl-1","choices":[{"index":0,"delta":{"tool_calls":[{"id":"D681PevKs","function":{"name":"get_weather","arguments":"{\"city\": \"Paris\"}"}}]},"finish_reason":"tool_calls"}]}"#;
+
+        let chunk = MistralProvider::parse_sse_event(line).unwrap();
+        let tool_calls = chunk.delta.tool_calls.unwrap();
+        assert_eq!(tool_calls.len(), 1);
+        assert_eq!(tool_calls[0].id, "D681PevKs");
+        assert_eq!(tool_calls[0].name, "get_weather");
+        assert_eq!(tool_calls[0].arguments, r#"{"city": "Paris"}"#);
+        assert_eq!(chunk.finish_reason, Some(FinishReason::ToolCall));
+    }
+
+    #[test]
+    fn test_parse_sse_event_done() {
+        assert!(MistralProvider::parse_sse_event("data: [DONE]").is_none());
+        assert!(MistralProvider::parse_sse_event("").is_none());
+    }
+
+    #[test]
+    fn test_handle_api_error() {
+        let provider = MistralProvider::new("test-key".to_string(), None, None);
+        let err =
+            provider.handle_api_error(401, r#"{"message":"Unauthorized","request_id":"abc"}"#);
+        assert_eq!(err.to_string(), "Mistral API error (401): Unauthorized");
+    }
+}
@@ -0,0 +1,178 @@
+//! Mistral-specific request/response types.
+//!
+//! This module defines types used to serialize/deserialize requests and
+//! responses for the Mistral AI chat completions and models APIs. The API
+//! rejects unknown request fields, so only fields it accepts are sent.
+
+use serde::{Deserialize, Serialize};
+
+/// Role for a message in the Mistral API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MistralRole {
+    System,
+    User,
+    Assistant,
+    Tool,
+}
+
+/// A single message in a Mistral request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MistralMessage {
+    pub role: MistralRole,
+    pub content: MistralContent,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<MistralToolCall>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+/// Content for a Mistral message, either text or multi-modal parts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MistralContent {
+    /// Simple text content.
+    Text(String),
+    /// Multi-modal content with multiple parts.
+    Parts(Vec<MistralContentPart>),
+}
+
+/// A single part of multi-modal content in the Mistral API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MistralContentPart {
+    Text { text: String },
+    ImageUrl { image_url: String },
+}
+
+/// A tool definition for the Mistral API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MistralTool {
+    pub r#type: String,
+    pub function: MistralFunctionDefinition,
+}
+
+/// Function definition for a tool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MistralFunctionDefinition {
+    pub name: String,
+    pub description: String,
+    pub parameters: serde_json::Value,
+}
+
+/// A tool call made by the model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MistralToolCall {
+    #[serde(default)]
+    pub id: String,
+    #[serde(rename = "type", default = "function_type")]
+    pub tool_type: String,
+    pub function: MistralFunctionCall,
+}
+
+fn function_type() -> String {
+    "function".to_string()
+}
+
+/// Function call within a tool call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MistralFunctionCall {
+    pub name: String,
+    pub arguments: String,
+}
+
+/// The main request body for the Mistral chat completions API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MistralRequest {
+    pub model: String,
+    pub messages: Vec<MistralMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<MistralTool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    pub stream: bool,
+}
+
+/// A streamed chat completion chunk.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MistralChunk {
+    pub id: String,
+    #[serde(default)]
+    pub choices: Vec<MistralChoice>,
+    #[serde(default)]
+    pub usage: Option<MistralUsage>,
+}
+
+/// A choice within a streamed chunk.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MistralChoice {
+    pub delta: MistralDelta,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+/// The incremental message of a streamed choice.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MistralDelta {
+    #[serde(default)]
+    pub role: Option<MistralRole>,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Option<Vec<MistralToolCall>>,
+}
+
+/// Token usage reported with the final chunk.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MistralUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+}
+
+/// Response from the models endpoint.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MistralModelList {
+    pub data: Vec<MistralModel>,
+}
+
+/// A model returned by the models endpoint.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MistralModel {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub max_context_length: Option<u32>,
+    #[serde(default)]
+    pub capabilities: MistralCapabilities,
+}
+
+/// Capabilities of a Mistral model.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct MistralCapabilities {
+    #[serde(default)]
+    pub completion_chat: bool,
+    #[serde(default)]
+    pub function_calling: bool,
+    #[serde(default)]
+    pub vision: bool,
+}
+
+/// Error response from the Mistral API.
+///
+/// Most errors carry a `message`; request validation errors carry a
+/// `detail` list instead.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MistralErrorResponse {
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub detail: Option<serde_json::Value>,
+}
@@ -1,7 +1,15 @@
 //! Provider implementations for various LLM backends.
 
 pub mod anthropic;
+pub mod azure_openai;
 pub mod bedrock;
+pub mod deepseek;
 pub mod gemini;
+pub mod groq;
+pub mod llamacpp;
+pub mod mistral;
 pub mod ollama;
 pub mod openai;
+pub mod openrouter;
+pub mod vertex;
+pub mod xai;
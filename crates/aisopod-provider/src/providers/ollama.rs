@@ -58,7 +58,7 @@ impl OllamaProvider {
                     .iter()
                     .filter_map(|part| match part {
                         ContentPart::Text { text } => Some(text.clone()),
-                        ContentPart::Image { .. } => None, // Images not supported in chat API
+                        _ => None, // Images not supported in chat API
                     })
                     .collect::<Vec<_>>()
                     .join("\n")
@@ -421,6 +421,8 @@ impl OllamaProvider {
                     prompt_tokens,
                     completion_tokens,
                     total_tokens: prompt_tokens + completion_tokens,
+                    cache_read_tokens: 0,
+                    cache_write_tokens: 0,
                 }
             });
 
@@ -430,6 +432,7 @@ impl OllamaProvider {
                 role: Some(Self::convert_role(chunk.message.role)),
                 content: Some(chunk.message.content),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason,
             usage,
@@ -2,18 +2,30 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
-use futures_util::stream::{BoxStream, StreamExt};
+use futures_util::stream::StreamExt;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tracing::{debug, warn};
 
 use crate::auth::{AuthProfile, AuthProfileManager};
+use crate::batch::{
+    BatchCounts, BatchJob, BatchProvider, BatchRequest, BatchResponse, BatchResult, BatchStatus,
+};
+use crate::media::{fit_image, ImageLimits};
+use crate::retry::{self, RetryPolicy};
 use crate::trait_module::{ChatCompletionStream, ModelProvider};
 use crate::types::*;
 
 pub mod api_types;
 use api_types::*;
 
+/// The largest images sent to OpenAI; larger images are downscaled by the
+/// API to fit 2048x2048 anyway.
+pub const IMAGE_LIMITS: ImageLimits = ImageLimits {
+    max_dimension: 2048,
+    max_bytes: 20 * 1024 * 1024,
+};
+
 /// OpenAI provider implementation.
 ///
 /// This struct implements the [`ModelProvider`] trait for the OpenAI
@@ -25,6 +37,7 @@ pub struct OpenAIProvider {
     pub base_url: String,
     organization: Option<String>,
     profile_manager: Arc<Mutex<AuthProfileManager>>,
+    retry_policy: RetryPolicy,
 }
 
 impl OpenAIProvider {
@@ -50,6 +63,7 @@ impl OpenAIProvider {
             profile_manager: Arc::new(Mutex::new(AuthProfileManager::new(Duration::from_secs(
                 cooldown_seconds.unwrap_or(60),
             )))),
+            retry_policy: RetryPolicy::default(),
         }
     }
 
@@ -77,9 +91,16 @@ impl OpenAIProvider {
             profile_manager: Arc::new(Mutex::new(AuthProfileManager::new(Duration::from_secs(
                 cooldown_seconds.unwrap_or(60),
             )))),
+            retry_policy: RetryPolicy::default(),
         }
     }
 
+    /// Sets how rate-limited requests are retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
     /// Returns the API key used for authentication.
     pub fn api_key(&self) -> &str {
         &self.api_key
@@ -98,7 +119,7 @@ impl OpenAIProvider {
     }
 
     /// Converts a core [`Message`] to an OpenAI message.
-    fn convert_message(&self, message: &Message) -> OpenAIMessage {
+    pub(crate) fn convert_message(message: &Message) -> OpenAIMessage {
         let role = match message.role {
             Role::System => OpenAIRole::System,
             Role::User => OpenAIRole::User,
@@ -119,6 +140,7 @@ impl OpenAIProvider {
                             // Convert media type to URL format
                             // OpenAI supports base64 encoded images with data URLs
                             let url = if media_type.starts_with("image/") {
+                                let (media_type, data) = fit_image(media_type, data, &IMAGE_LIMITS);
                                 format!("data:{};base64,{}", media_type, data)
                             } else {
                                 data.clone()
@@ -127,6 +149,12 @@ impl OpenAIProvider {
                                 image_url: OpenAIImageUrl { url, detail: None },
                             }
                         }
+                        ContentPart::ImageUrl { url } => OpenAIContentPart::ImageUrl {
+                            image_url: OpenAIImageUrl {
+                                url: url.clone(),
+                                detail: None,
+                            },
+                        },
                     })
                     .collect();
                 Some(OpenAIContent::Parts(content_parts))
@@ -157,7 +185,7 @@ impl OpenAIProvider {
     }
 
     /// Converts a core [`ToolDefinition`] to an OpenAI tool.
-    fn convert_tool(&self, tool: &ToolDefinition) -> OpenAITool {
+    pub(crate) fn convert_tool(tool: &ToolDefinition) -> OpenAITool {
         OpenAITool {
             r#type: OpenAIToolType::Function,
             function: OpenAIFunctionDefinition {
@@ -169,17 +197,15 @@ impl OpenAIProvider {
     }
 
     /// Builds the OpenAI request from a core request.
-    fn build_openai_request(&self, request: &ChatCompletionRequest) -> OpenAIRequest {
-        let messages = request
-            .messages
-            .iter()
-            .map(|message| self.convert_message(message))
-            .collect();
+    ///
+    /// OpenAI-compatible APIs such as Azure OpenAI accept the same body.
+    pub(crate) fn build_openai_request(request: &ChatCompletionRequest) -> OpenAIRequest {
+        let messages = request.messages.iter().map(Self::convert_message).collect();
 
         let tools = request
             .tools
             .as_ref()
-            .map(|tools| tools.iter().map(|tool| self.convert_tool(tool)).collect());
+            .map(|tools| tools.iter().map(Self::convert_tool).collect());
 
         // Check if any message has parts with a hint for JSON mode
         // In a real implementation, this would be a more explicit flag
@@ -201,7 +227,7 @@ impl OpenAIProvider {
     }
 
     /// Parses an OpenAI SSE event into a chat completion chunk.
-    fn parse_sse_event(line: &str) -> Option<ChatCompletionChunk> {
+    pub(crate) fn parse_sse_event(line: &str) -> Option<ChatCompletionChunk> {
         // Skip comments and empty lines
         let trimmed = line.trim();
         if trimmed.is_empty() || trimmed.starts_with(':') {
@@ -246,32 +272,38 @@ impl OpenAIProvider {
                 // Extract content from delta
                 let content = choice.delta.content.clone();
 
-                // Extract tool calls from delta
+                // Extract tool calls from delta. Calls are streamed in
+                // fragments; those after the first carry no ID or name and
+                // are joined by a `ToolCallAccumulator`.
                 let tool_calls = choice.delta.tool_calls.as_ref().map(|tool_calls| {
                     tool_calls
                         .iter()
-                        .map(|tool_call| ToolCall {
-                            id: tool_call.id.clone(),
-                            name: tool_call.function.name.clone(),
-                            arguments: tool_call.function.arguments.clone(),
+                        .map(|tool_call| {
+                            let function = tool_call.function.as_ref();
+                            ToolCall {
+                                id: tool_call.id.clone().unwrap_or_default(),
+                                name: function.and_then(|f| f.name.clone()).unwrap_or_default(),
+                                arguments: function
+                                    .and_then(|f| f.arguments.clone())
+                                    .unwrap_or_default(),
+                            }
                         })
                         .collect()
                 });
 
                 // Map finish reason
-                let finish_reason = choice.finish_reason.as_deref().and_then(|s| match s {
-                    "stop" => Some(FinishReason::Stop),
-                    "length" => Some(FinishReason::Length),
-                    "tool_calls" | "tool_call" => Some(FinishReason::ToolCall),
-                    "content_filter" => Some(FinishReason::ContentFilter),
-                    _ => None,
-                });
+                let finish_reason = choice
+                    .finish_reason
+                    .as_deref()
+                    .and_then(Self::convert_finish_reason);
 
                 // Map usage if present
                 let usage = usage.map(|u| TokenUsage {
                     prompt_tokens: u.prompt_tokens,
                     completion_tokens: u.completion_tokens,
                     total_tokens: u.total_tokens,
+                    cache_read_tokens: 0,
+                    cache_write_tokens: 0,
                 });
 
                 Some(ChatCompletionChunk {
@@ -280,6 +312,7 @@ impl OpenAIProvider {
                         role,
                         content,
                         tool_calls,
+                        reasoning: None,
                     },
                     finish_reason,
                     usage,
@@ -289,6 +322,53 @@ impl OpenAIProvider {
         }
     }
 
+    /// Maps an OpenAI finish reason to a [`FinishReason`].
+    pub(crate) fn convert_finish_reason(reason: &str) -> Option<FinishReason> {
+        match reason {
+            "stop" => Some(FinishReason::Stop),
+            "length" => Some(FinishReason::Length),
+            "tool_calls" | "tool_call" => Some(FinishReason::ToolCall),
+            "content_filter" => Some(FinishReason::ContentFilter),
+            _ => None,
+        }
+    }
+
+    /// Converts a streaming Chat Completions response into chunks.
+    pub(crate) fn sse_stream(response: reqwest::Response) -> ChatCompletionStream {
+        // For streaming, parse SSE stream line by line
+        let stream = response.bytes_stream().map(move |chunk| {
+            let chunk = chunk.map_err(|e| anyhow::anyhow!("Stream error: {}", e))?;
+
+            // Decode the chunk as UTF-8
+            let text = String::from_utf8_lossy(&chunk);
+
+            // Process line by line
+            let mut chunks = Vec::new();
+            for line in text.lines() {
+                if let Some(parsed) = Self::parse_sse_event(line) {
+                    chunks.push(parsed);
+                }
+            }
+
+            if chunks.is_empty() {
+                return Ok(None);
+            }
+
+            // For simplicity, return the first chunk for now
+            // A more complete implementation would buffer and return all chunks
+            Ok(Some(chunks.remove(0)))
+        });
+
+        // Filter out None values and box the stream
+        Box::pin(stream.filter_map(|x| async move {
+            match x {
+                Ok(Some(chunk)) => Some(Ok(chunk)),
+                Ok(None) => None,
+                Err(e) => Some(Err(e)),
+            }
+        }))
+    }
+
     /// Converts OpenAI API error to anyhow error.
     fn handle_api_error(&self, status: u16, body: &str) -> anyhow::Error {
         match serde_json::from_str::<OpenAIErrorResponse>(body) {
@@ -308,7 +388,7 @@ impl OpenAIProvider {
     }
 
     /// Estimates context window based on model name.
-    fn estimate_context_window(model_id: &str) -> u32 {
+    pub(crate) fn estimate_context_window(model_id: &str) -> u32 {
         // Common context windows for popular OpenAI models
         if model_id.contains("gpt-4o") || model_id.contains("gpt-4-turbo") {
             128000
@@ -322,7 +402,7 @@ impl OpenAIProvider {
     }
 
     /// Determines if a model supports vision based on its name.
-    fn supports_vision(model_id: &str) -> bool {
+    pub(crate) fn supports_vision(model_id: &str) -> bool {
         // Vision-capable models
         model_id.contains("gpt-4o")
             || model_id.contains("gpt-4-turbo")
@@ -330,7 +410,7 @@ impl OpenAIProvider {
     }
 
     /// Determines if a model supports tool calling based on its name.
-    fn supports_tools(model_id: &str) -> bool {
+    pub(crate) fn supports_tools(model_id: &str) -> bool {
         // Tool-capable models:
         // - GPT-4o and GPT-4-turbo series
         // - GPT-4 models from June 2024 onward (gpt-4-0613 and later)
@@ -367,7 +447,7 @@ impl ModelProvider for OpenAIProvider {
             request_builder = request_builder.header("OpenAI-Organization", org);
         }
 
-        let response = request_builder.send().await?;
+        let response = retry::send(request_builder, &self.retry_policy).await?;
 
         if !response.status().is_success() {
             let status = response.status().as_u16();
@@ -402,7 +482,7 @@ impl ModelProvider for OpenAIProvider {
     ) -> Result<ChatCompletionStream> {
         let api_key = self.get_api_key().unwrap_or(self.api_key.clone());
 
-        let openai_request = self.build_openai_request(&request);
+        let openai_request = Self::build_openai_request(&request);
 
         debug!(
             "Sending request to OpenAI API: model={}, stream={}",
@@ -422,7 +502,8 @@ impl ModelProvider for OpenAIProvider {
             request_builder = request_builder.header("OpenAI-Organization", org);
         }
 
-        let response = request_builder.json(&openai_request).send().await?;
+        let response =
+            retry::send(request_builder.json(&openai_request), &self.retry_policy).await?;
 
         if !response.status().is_success() {
             let status = response.status().as_u16();
@@ -430,41 +511,7 @@ impl ModelProvider for OpenAIProvider {
             return Err(self.handle_api_error(status, &body));
         }
 
-        // For streaming, parse SSE stream line by line
-        let stream = response.bytes_stream().map(move |chunk| {
-            let chunk = chunk.map_err(|e| anyhow::anyhow!("Stream error: {}", e))?;
-
-            // Decode the chunk as UTF-8
-            let text = String::from_utf8_lossy(&chunk);
-
-            // Process line by line
-            let mut chunks = Vec::new();
-            for line in text.lines() {
-                if let Some(parsed) = Self::parse_sse_event(line) {
-                    chunks.push(parsed);
-                }
-            }
-
-            if chunks.is_empty() {
-                return Ok(None);
-            }
-
-            // For simplicity, return the first chunk for now
-            // A more complete implementation would buffer and return all chunks
-            Ok(Some(chunks.remove(0)))
-        });
-
-        // Filter out None values and box the stream
-        let boxed: BoxStream<'static, Result<ChatCompletionChunk>> =
-            Box::pin(stream.filter_map(|x| async move {
-                match x {
-                    Ok(Some(chunk)) => Some(Ok(chunk)),
-                    Ok(None) => None,
-                    Err(e) => Some(Err(e)),
-                }
-            }));
-
-        Ok(boxed)
+        Ok(Self::sse_stream(response))
     }
 
     async fn health_check(&self) -> Result<ProviderHealth> {
@@ -517,14 +564,235 @@ impl ModelProvider for OpenAIProvider {
     }
 }
 
+impl OpenAIProvider {
+    /// Builds an authenticated request to an OpenAI API path.
+    fn api_request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let api_key = self.get_api_key().unwrap_or(self.api_key.clone());
+
+        let mut request_builder = self
+            .client
+            .request(method, format!("{}{}", self.base_url, path))
+            .header("Authorization", format!("Bearer {}", api_key));
+
+        // Add organization header if configured
+        if let Some(ref org) = self.organization {
+            request_builder = request_builder.header("OpenAI-Organization", org);
+        }
+
+        request_builder
+    }
+
+    /// Sends a request, converting error responses to errors.
+    async fn send_checked(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let response = retry::send(request, &self.retry_policy).await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            return Err(self.handle_api_error(status, &body));
+        }
+
+        Ok(response)
+    }
+
+    /// Converts an OpenAI batch to a [`BatchJob`].
+    fn convert_batch(batch: OpenAIBatch) -> BatchJob {
+        let status = match batch.status.as_str() {
+            "completed" => BatchStatus::Completed,
+            "failed" => BatchStatus::Failed,
+            "expired" => BatchStatus::Expired,
+            "cancelled" => BatchStatus::Cancelled,
+            // "validating", "in_progress", "finalizing" and "cancelling"
+            _ => BatchStatus::InProgress,
+        };
+
+        BatchJob {
+            id: batch.id,
+            provider: "openai".to_string(),
+            status,
+            counts: BatchCounts {
+                total: batch.request_counts.total,
+                succeeded: batch.request_counts.completed,
+                failed: batch.request_counts.failed,
+            },
+        }
+    }
+
+    /// Converts a non-streaming Chat Completions response to a
+    /// [`BatchResponse`].
+    fn convert_completion(completion: OpenAIChatCompletion) -> BatchResponse {
+        let (content, tool_calls, finish_reason) = match completion.choices.into_iter().next() {
+            Some(choice) => (
+                choice.message.content.unwrap_or_default(),
+                choice
+                    .message
+                    .tool_calls
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|tool_call| ToolCall {
+                        id: tool_call.id,
+                        name: tool_call.function.name,
+                        arguments: tool_call.function.arguments,
+                    })
+                    .collect(),
+                choice
+                    .finish_reason
+                    .as_deref()
+                    .and_then(Self::convert_finish_reason),
+            ),
+            None => Default::default(),
+        };
+
+        let usage = completion
+            .usage
+            .map(|u| TokenUsage {
+                prompt_tokens: u.prompt_tokens,
+                completion_tokens: u.completion_tokens,
+                total_tokens: u.total_tokens,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
+            })
+            .unwrap_or_default();
+
+        BatchResponse {
+            model: completion.model,
+            content,
+            tool_calls,
+            finish_reason,
+            usage,
+        }
+    }
+
+    /// Converts a line of a batch output or error file to a [`BatchResult`].
+    fn convert_batch_output(&self, line: OpenAIBatchOutputLine) -> BatchResult {
+        let response = match (line.response, line.error) {
+            (Some(response), _) if response.status_code == 200 => {
+                serde_json::from_value::<OpenAIChatCompletion>(response.body)
+                    .map(Self::convert_completion)
+                    .map_err(|e| format!("Invalid OpenAI batch response: {}", e))
+            }
+            (Some(response), _) => Err(self
+                .handle_api_error(response.status_code, &response.body.to_string())
+                .to_string()),
+            (None, Some(error)) => {
+                Err(error.message.unwrap_or_else(|| "Unknown error".to_string()))
+            }
+            (None, None) => Err("Request produced no response".to_string()),
+        };
+
+        BatchResult {
+            custom_id: line.custom_id,
+            response,
+        }
+    }
+}
+
+#[async_trait]
+impl BatchProvider for OpenAIProvider {
+    async fn submit_batch(&self, requests: Vec<BatchRequest>) -> Result<BatchJob> {
+        let mut input = String::new();
+        for batch_request in &requests {
+            let mut body = Self::build_openai_request(&batch_request.request);
+            body.stream = false;
+            let line = OpenAIBatchInputLine {
+                custom_id: batch_request.custom_id.clone(),
+                method: "POST".to_string(),
+                url: "/v1/chat/completions".to_string(),
+                body,
+            };
+            input.push_str(&serde_json::to_string(&line)?);
+            input.push('\n');
+        }
+
+        debug!("Submitting OpenAI batch of {} requests", requests.len());
+
+        let file = reqwest::multipart::Part::text(input)
+            .file_name("batch.jsonl")
+            .mime_str("application/jsonl")?;
+        let form = reqwest::multipart::Form::new()
+            .text("purpose", "batch")
+            .part("file", file);
+        let upload = self
+            .send_checked(
+                self.api_request(reqwest::Method::POST, "/v1/files")
+                    .multipart(form),
+            )
+            .await?;
+        let file: OpenAIFile = upload.json().await?;
+
+        let create = OpenAIBatchCreate {
+            input_file_id: file.id,
+            endpoint: "/v1/chat/completions".to_string(),
+            completion_window: "24h".to_string(),
+        };
+        let response = self
+            .send_checked(
+                self.api_request(reqwest::Method::POST, "/v1/batches")
+                    .json(&create),
+            )
+            .await?;
+
+        Ok(Self::convert_batch(response.json().await?))
+    }
+
+    async fn get_batch(&self, batch_id: &str) -> Result<BatchJob> {
+        let path = format!("/v1/batches/{}", batch_id);
+        let response = self
+            .send_checked(self.api_request(reqwest::Method::GET, &path))
+            .await?;
+
+        Ok(Self::convert_batch(response.json().await?))
+    }
+
+    async fn cancel_batch(&self, batch_id: &str) -> Result<BatchJob> {
+        let path = format!("/v1/batches/{}/cancel", batch_id);
+        let response = self
+            .send_checked(self.api_request(reqwest::Method::POST, &path))
+            .await?;
+
+        Ok(Self::convert_batch(response.json().await?))
+    }
+
+    async fn batch_results(&self, batch_id: &str) -> Result<Vec<BatchResult>> {
+        let path = format!("/v1/batches/{}", batch_id);
+        let response = self
+            .send_checked(self.api_request(reqwest::Method::GET, &path))
+            .await?;
+        let batch: OpenAIBatch = response.json().await?;
+
+        if Self::convert_batch(batch.clone()).status == BatchStatus::InProgress {
+            return Err(anyhow::anyhow!("Batch '{}' has not ended yet", batch_id));
+        }
+
+        // Successful requests are in the output file, failed ones in the
+        // error file
+        let mut results = Vec::new();
+        for file_id in [batch.output_file_id, batch.error_file_id]
+            .into_iter()
+            .flatten()
+        {
+            let path = format!("/v1/files/{}/content", file_id);
+            let content = self
+                .send_checked(self.api_request(reqwest::Method::GET, &path))
+                .await?
+                .text()
+                .await?;
+            for line in content.lines().filter(|line| !line.trim().is_empty()) {
+                let line: OpenAIBatchOutputLine = serde_json::from_str(line)?;
+                results.push(self.convert_batch_output(line));
+            }
+        }
+
+        Ok(results)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_convert_message_text() {
-        let provider = OpenAIProvider::new("test-key".to_string(), None, None, Some(60));
-
         let message = Message {
             role: Role::User,
             content: MessageContent::Text("Hello".to_string()),
@@ -532,7 +800,7 @@ mod tests {
             tool_call_id: None,
         };
 
-        let result = provider.convert_message(&message);
+        let result = OpenAIProvider::convert_message(&message);
         assert_eq!(result.role, OpenAIRole::User);
         assert_eq!(
             result.content,
@@ -542,8 +810,6 @@ mod tests {
 
     #[test]
     fn test_convert_message_parts() {
-        let provider = OpenAIProvider::new("test-key".to_string(), None, None, Some(60));
-
         let message = Message {
             role: Role::User,
             content: MessageContent::Parts(vec![ContentPart::Text {
@@ -553,7 +819,7 @@ mod tests {
             tool_call_id: None,
         };
 
-        let result = provider.convert_message(&message);
+        let result = OpenAIProvider::convert_message(&message);
         assert_eq!(result.role, OpenAIRole::User);
 
         if let Some(OpenAIContent::Parts(parts)) = result.content {
@@ -567,9 +833,29 @@ mod tests {
     }
 
     #[test]
-    fn test_convert_tool() {
-        let provider = OpenAIProvider::new("test-key".to_string(), None, None, Some(60));
+    fn test_convert_message_image_url() {
+        let message = Message {
+            role: Role::User,
+            content: MessageContent::Parts(vec![ContentPart::ImageUrl {
+                url: "https://example.com/cat.png".to_string(),
+            }]),
+            tool_calls: None,
+            tool_call_id: None,
+        };
+
+        let result = OpenAIProvider::convert_message(&message);
+        let json = serde_json::to_value(&result.content).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([{
+                "type": "image_url",
+                "image_url": {"url": "https://example.com/cat.png"}
+            }])
+        );
+    }
 
+    #[test]
+    fn test_convert_tool() {
         let tool = ToolDefinition {
             name: "calculator".to_string(),
             description: "A calculator tool".to_string(),
@@ -582,15 +868,13 @@ mod tests {
             }),
         };
 
-        let result = provider.convert_tool(&tool);
+        let result = OpenAIProvider::convert_tool(&tool);
         assert_eq!(result.r#type, OpenAIToolType::Function);
         assert_eq!(result.function.name, "calculator");
     }
 
     #[test]
     fn test_build_openai_request() {
-        let provider = OpenAIProvider::new("test-key".to_string(), None, None, Some(60));
-
         let request = ChatCompletionRequest {
             model: "gpt-4".to_string(),
             messages: vec![
@@ -614,7 +898,7 @@ mod tests {
             stream: true,
         };
 
-        let openai_request = provider.build_openai_request(&request);
+        let openai_request = OpenAIProvider::build_openai_request(&request);
 
         assert_eq!(openai_request.model, "gpt-4");
         assert_eq!(openai_request.messages.len(), 2);
@@ -636,6 +920,26 @@ mod tests {
         assert_eq!(chunk.delta.content, Some("Hello".to_string()));
     }
 
+    #[test]
+    fn test_parse_sse_event_tool_call_fragments() {
+        let first = r#"data: {"id":"chatcmpl-abc","object":"chat.completion.chunk","created":1,"model":"gpt-4o","choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"id":"call_1","type":"function","function":{"name":"get_weather","arguments":""}}]},"finish_reason":null}]}"#;
+        let rest = r#"data: {"id":"chatcmpl-abc","object":"chat.completion.chunk","created":1,"model":"gpt-4o","choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{\"city\":\"Paris\"}"}}]},"finish_reason":null}]}"#;
+
+        let mut accumulator = crate::normalize::ToolCallAccumulator::new();
+        for line in [first, rest] {
+            let chunk = OpenAIProvider::parse_sse_event(line).unwrap();
+            for call in chunk.delta.tool_calls.iter().flatten() {
+                accumulator.push_call(call);
+            }
+        }
+
+        let calls = accumulator.finish();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].id, "call_1");
+        assert_eq!(calls[0].name, "get_weather");
+        assert_eq!(calls[0].arguments, r#"{"city":"Paris"}"#);
+    }
+
     #[test]
     fn test_parse_sse_done() {
         // [DONE] should return None
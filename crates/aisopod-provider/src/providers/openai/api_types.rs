@@ -98,6 +98,29 @@ pub struct OpenAIFunctionCall {
     pub arguments: String,
 }
 
+/// A fragment of a tool call in a streamed delta.
+///
+/// The first fragment of a call carries its ID and function name; later
+/// fragments with the same `index` append to the arguments.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenAIToolCallDelta {
+    #[serde(default)]
+    pub index: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<OpenAIFunctionDelta>,
+}
+
+/// A fragment of a function call in a streamed delta.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenAIFunctionDelta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<String>,
+}
+
 /// The main request body for OpenAI Chat Completions API.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OpenAIRequest {
@@ -189,7 +212,7 @@ pub struct OpenAIChoiceDelta {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub tool_calls: Option<Vec<OpenAIToolCall>>,
+    pub tool_calls: Option<Vec<OpenAIToolCallDelta>>,
 }
 
 /// Usage statistics in OpenAI API response.
@@ -234,3 +257,97 @@ pub struct OpenAIModel {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub owned_by: Option<String>,
 }
+
+/// A non-streaming Chat Completions response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenAIChatCompletion {
+    pub id: String,
+    pub model: String,
+    pub choices: Vec<OpenAICompletionChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<OpenAIUsage>,
+}
+
+/// A choice in a non-streaming Chat Completions response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenAICompletionChoice {
+    pub index: usize,
+    pub message: OpenAICompletionMessage,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finish_reason: Option<String>,
+}
+
+/// The message of a non-streaming choice.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenAICompletionMessage {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<OpenAIToolCall>>,
+}
+
+/// A file uploaded to the OpenAI Files API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenAIFile {
+    pub id: String,
+}
+
+/// A line of a batch input file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenAIBatchInputLine {
+    pub custom_id: String,
+    pub method: String,
+    pub url: String,
+    pub body: OpenAIRequest,
+}
+
+/// Request body for creating a batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenAIBatchCreate {
+    pub input_file_id: String,
+    pub endpoint: String,
+    pub completion_window: String,
+}
+
+/// A batch job from the OpenAI Batch API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenAIBatch {
+    pub id: String,
+    /// One of "validating", "failed", "in_progress", "finalizing",
+    /// "completed", "expired", "cancelling" or "cancelled".
+    pub status: String,
+    #[serde(default)]
+    pub request_counts: OpenAIBatchRequestCounts,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_file_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_file_id: Option<String>,
+}
+
+/// Request counts of an OpenAI batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct OpenAIBatchRequestCounts {
+    #[serde(default)]
+    pub total: u32,
+    #[serde(default)]
+    pub completed: u32,
+    #[serde(default)]
+    pub failed: u32,
+}
+
+/// A line of a batch output or error file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenAIBatchOutputLine {
+    pub custom_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<OpenAIBatchOutputResponse>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<OpenAIError>,
+}
+
+/// The HTTP response to a batched request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenAIBatchOutputResponse {
+    pub status_code: u16,
+    pub body: serde_json::Value,
+}
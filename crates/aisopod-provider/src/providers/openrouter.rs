@@ -0,0 +1,366 @@
+//! OpenRouter API provider implementation.
+//!
+//! OpenRouter aggregates models from many upstream providers behind an
+//! OpenAI-compatible API. Model IDs name the model's author (e.g.
+//! `anthropic/claude-3.5-sonnet`), the catalog reports per-model pricing, and
+//! each request can carry preferences for which upstream providers serve it.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::auth::{AuthProfile, AuthProfileManager};
+pub use crate::pricing::ModelPricing;
+use crate::providers::openai::OpenAIProvider;
+use crate::retry::{self, RetryPolicy};
+use crate::trait_module::{ChatCompletionStream, ModelProvider};
+use crate::types::*;
+
+pub mod api_types;
+use api_types::*;
+
+/// Upstream routing preferences sent with every request.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OpenRouterRouting {
+    /// Upstream providers to try first, in order (e.g. `"anthropic"`).
+    pub order: Vec<String>,
+    /// Whether upstream providers not listed in `order` may serve requests.
+    pub allow_fallbacks: Option<bool>,
+    /// Models to try, in order, when the requested model is unavailable.
+    pub fallbacks: Vec<String>,
+}
+
+impl From<&OpenRouterPricing> for ModelPricing {
+    fn from(pricing: &OpenRouterPricing) -> Self {
+        let price = |value: &Option<String>| {
+            value
+                .as_deref()
+                .and_then(|value| value.parse::<f64>().ok())
+                // Routers with variable pricing report negative prices
+                .filter(|price| *price >= 0.0)
+                .unwrap_or(0.0)
+        };
+        Self {
+            prompt: price(&pricing.prompt),
+            completion: price(&pricing.completion),
+            request: price(&pricing.request),
+            image: price(&pricing.image),
+            ..Self::default()
+        }
+    }
+}
+
+/// OpenRouter provider implementation.
+///
+/// This struct implements the [`ModelProvider`] trait for the OpenRouter
+/// API, supporting streaming SSE responses, tool use, model discovery from
+/// the catalog, and upstream provider routing. Pricing for the models found
+/// by [`list_models()`](ModelProvider::list_models) is available from
+/// [`pricing()`](OpenRouterProvider::pricing).
+pub struct OpenRouterProvider {
+    client: reqwest::Client,
+    api_key: String,
+    pub base_url: String,
+    routing: OpenRouterRouting,
+    pricing: RwLock<HashMap<String, ModelPricing>>,
+    profile_manager: Arc<Mutex<AuthProfileManager>>,
+    retry_policy: RetryPolicy,
+}
+
+impl OpenRouterProvider {
+    /// Creates a new OpenRouter provider instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - The API key for authenticating with OpenRouter.
+    /// * `base_url` - The base URL for the OpenRouter API (defaults to "https://openrouter.ai/api").
+    /// * `cooldown_seconds` - The cooldown period in seconds for failed profiles.
+    pub fn new(api_key: String, base_url: Option<String>, cooldown_seconds: Option<u64>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key,
+            base_url: base_url.unwrap_or_else(|| "https://openrouter.ai/api".to_string()),
+            routing: OpenRouterRouting::default(),
+            pricing: RwLock::new(HashMap::new()),
+            profile_manager: Arc::new(Mutex::new(AuthProfileManager::new(Duration::from_secs(
+                cooldown_seconds.unwrap_or(60),
+            )))),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Sets the upstream routing preferences sent with every request.
+    pub fn with_routing(mut self, routing: OpenRouterRouting) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    /// Sets how rate-limited requests are retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Returns the API key used for authentication.
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    /// Returns the upstream routing preferences.
+    pub fn routing(&self) -> &OpenRouterRouting {
+        &self.routing
+    }
+
+    /// Returns the pricing of a model listed by the last catalog fetch.
+    pub fn pricing(&self, model_id: &str) -> Option<ModelPricing> {
+        self.pricing.read().unwrap().get(model_id).copied()
+    }
+
+    /// Adds an authentication profile for key rotation.
+    pub fn add_profile(&mut self, profile: AuthProfile) {
+        let mut manager = self.profile_manager.lock().unwrap();
+        manager.add_profile(profile);
+    }
+
+    /// Gets the next available API key for round-robin rotation.
+    fn get_api_key(&self) -> String {
+        let mut manager = self.profile_manager.lock().unwrap();
+        manager
+            .next_key("openrouter")
+            .map(|p| p.api_key.clone())
+            .unwrap_or_else(|| self.api_key.clone())
+    }
+
+    /// Builds the OpenRouter request from a core request.
+    fn build_openrouter_request(&self, request: &ChatCompletionRequest) -> OpenRouterRequest {
+        let models = if self.routing.fallbacks.is_empty() {
+            Vec::new()
+        } else {
+            std::iter::once(request.model.clone())
+                .chain(self.routing.fallbacks.iter().cloned())
+                .collect()
+        };
+        let provider = (!self.routing.order.is_empty() || self.routing.allow_fallbacks.is_some())
+            .then(|| OpenRouterProviderPreferences {
+                order: self.routing.order.clone(),
+                allow_fallbacks: self.routing.allow_fallbacks,
+            });
+
+        OpenRouterRequest {
+            request: OpenAIProvider::build_openai_request(request),
+            models,
+            provider,
+        }
+    }
+
+    /// Converts OpenRouter API error to anyhow error.
+    fn handle_api_error(status: u16, body: &str) -> anyhow::Error {
+        match serde_json::from_str::<OpenRouterErrorResponse>(body) {
+            Ok(response) => {
+                anyhow::anyhow!(
+                    "OpenRouter API error ({}): {}",
+                    status,
+                    response.error.message
+                )
+            }
+            _ => anyhow::anyhow!("OpenRouter API error ({}): {}", status, body.trim()),
+        }
+    }
+
+    /// Converts a catalog entry to a [`ModelInfo`].
+    fn model_info(model: &OpenRouterModel) -> ModelInfo {
+        let supports_vision = model
+            .architecture
+            .input_modalities
+            .iter()
+            .any(|modality| modality == "image")
+            || model
+                .architecture
+                .modality
+                .as_deref()
+                .is_some_and(|modality| modality.contains("image->"));
+
+        ModelInfo {
+            id: model.id.clone(),
+            name: model.name.clone().unwrap_or_else(|| model.id.clone()),
+            provider: "openrouter".to_string(),
+            context_window: model.context_length.unwrap_or(4096),
+            supports_vision,
+            supports_tools: model.supported_parameters.iter().any(|p| p == "tools"),
+        }
+    }
+}
+
+#[async_trait]
+impl ModelProvider for OpenRouterProvider {
+    fn id(&self) -> &str {
+        "openrouter"
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        debug!("Listing OpenRouter models");
+
+        let url = format!("{}/v1/models", self.base_url);
+        let builder = self.client.get(&url).bearer_auth(self.get_api_key());
+        let response = retry::send(builder, &self.retry_policy).await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            return Err(Self::handle_api_error(status, &body));
+        }
+
+        let response: OpenRouterModelList = response.json().await?;
+
+        *self.pricing.write().unwrap() = response
+            .data
+            .iter()
+            .map(|model| (model.id.clone(), ModelPricing::from(&model.pricing)))
+            .collect();
+
+        Ok(response.data.iter().map(Self::model_info).collect())
+    }
+
+    async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionStream> {
+        let body = self.build_openrouter_request(&request);
+
+        debug!(
+            "Sending request to OpenRouter API: model={}, stream={}",
+            body.request.model, body.request.stream
+        );
+
+        let url = format!("{}/v1/chat/completions", self.base_url);
+        let builder = self
+            .client
+            .post(&url)
+            .bearer_auth(self.get_api_key())
+            .json(&body);
+        let response = retry::send(builder, &self.retry_policy).await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            return Err(Self::handle_api_error(status, &body));
+        }
+
+        Ok(OpenAIProvider::sse_stream(response))
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth> {
+        // The key endpoint checks the credentials without fetching the catalog
+        let url = format!("{}/v1/key", self.base_url);
+        let start = std::time::Instant::now();
+        let response = self
+            .client
+            .get(&url)
+            .bearer_auth(self.get_api_key())
+            .send()
+            .await;
+        let latency_ms = start.elapsed().as_millis() as u64;
+
+        match response {
+            Ok(resp) if resp.status().is_success() => Ok(ProviderHealth {
+                available: true,
+                latency_ms: Some(latency_ms),
+            }),
+            Ok(resp) => {
+                warn!(
+                    "Health check failed with status: {}",
+                    resp.status().as_u16()
+                );
+                Ok(ProviderHealth {
+                    available: false,
+                    latency_ms: Some(latency_ms),
+                })
+            }
+            Err(e) => {
+                warn!("Health check error: {}", e);
+                Ok(ProviderHealth {
+                    available: false,
+                    latency_ms: None,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: "anthropic/claude-3.5-sonnet".to_string(),
+            messages: vec![Message {
+                role: Role::User,
+                content: MessageContent::Text("Hello".to_string()),
+                tool_calls: None,
+                tool_call_id: None,
+            }],
+            tools: None,
+            temperature: None,
+            max_tokens: None,
+            stop: None,
+            stream: true,
+        }
+    }
+
+    #[test]
+    fn test_request_without_routing() {
+        let provider = OpenRouterProvider::new("test-key".to_string(), None, None);
+        let body = serde_json::to_value(provider.build_openrouter_request(&request())).unwrap();
+        assert!(body.get("models").is_none());
+        assert!(body.get("provider").is_none());
+        assert_eq!(body["model"], "anthropic/claude-3.5-sonnet");
+    }
+
+    #[test]
+    fn test_request_with_routing() {
+        let provider = OpenRouterProvider::new("test-key".to_string(), None, None).with_routing(
+            OpenRouterRouting {
+                order: vec!["anthropic".to_string(), "amazon-bedrock".to_string()],
+                allow_fallbacks: Some(false),
+                fallbacks: vec!["openai/gpt-4o".to_string()],
+            },
+        );
+        let body = serde_json::to_value(provider.build_openrouter_request(&request())).unwrap();
+        assert_eq!(
+            body["models"],
+            serde_json::json!(["anthropic/claude-3.5-sonnet", "openai/gpt-4o"])
+        );
+        assert_eq!(
+            body["provider"],
+            serde_json::json!({"order": ["anthropic", "amazon-bedrock"], "allow_fallbacks": false})
+        );
+    }
+
+    #[test]
+    fn test_model_pricing() {
+        let pricing = ModelPricing::from(&OpenRouterPricing {
+            prompt: Some("0.000003".to_string()),
+            completion: Some("0.000015".to_string()),
+            request: Some("0".to_string()),
+            image: None,
+        });
+        let cost = pricing.cost(&TokenUsage {
+            prompt_tokens: 1000,
+            completion_tokens: 100,
+            total_tokens: 1100,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+        });
+        assert!((cost - 0.0045).abs() < 1e-12);
+
+        let variable = ModelPricing::from(&OpenRouterPricing {
+            prompt: Some("-1".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(variable, ModelPricing::default());
+    }
+}
@@ -0,0 +1,92 @@
+//! OpenRouter-specific request/response types.
+//!
+//! OpenRouter serves an OpenAI-compatible chat completions API, so request
+//! bodies extend the OpenAI request and streamed responses reuse the OpenAI
+//! types. The models endpoint reports pricing and architecture details that
+//! are defined here.
+
+use serde::{Deserialize, Serialize};
+
+use crate::providers::openai::api_types::OpenAIRequest;
+
+/// The request body for the OpenRouter chat completions API.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OpenRouterRequest {
+    #[serde(flatten)]
+    pub request: OpenAIRequest,
+    /// Models to try in order, starting with the requested model.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<OpenRouterProviderPreferences>,
+}
+
+/// Upstream provider preferences for a request.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OpenRouterProviderPreferences {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub order: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_fallbacks: Option<bool>,
+}
+
+/// Response from the models endpoint.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OpenRouterModelList {
+    pub data: Vec<OpenRouterModel>,
+}
+
+/// A model in the OpenRouter catalog.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OpenRouterModel {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub context_length: Option<u32>,
+    #[serde(default)]
+    pub architecture: OpenRouterArchitecture,
+    #[serde(default)]
+    pub pricing: OpenRouterPricing,
+    #[serde(default)]
+    pub supported_parameters: Vec<String>,
+}
+
+/// Input and output modalities of a model.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct OpenRouterArchitecture {
+    #[serde(default)]
+    pub input_modalities: Vec<String>,
+    /// Summary such as `"text+image->text"`.
+    #[serde(default)]
+    pub modality: Option<String>,
+}
+
+/// Prices in USD, as decimal strings, per token, request or image.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct OpenRouterPricing {
+    #[serde(default)]
+    pub prompt: Option<String>,
+    #[serde(default)]
+    pub completion: Option<String>,
+    #[serde(default)]
+    pub request: Option<String>,
+    #[serde(default)]
+    pub image: Option<String>,
+}
+
+/// Error response from the OpenRouter API.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OpenRouterErrorResponse {
+    pub error: OpenRouterError,
+}
+
+/// Error details from the OpenRouter API.
+///
+/// Unlike OpenAI, the `code` is the numeric HTTP status.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OpenRouterError {
+    #[serde(default)]
+    pub code: Option<u16>,
+    pub message: String,
+}
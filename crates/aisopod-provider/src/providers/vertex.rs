@@ -0,0 +1,612 @@
+//! Google Vertex AI provider implementation.
+//!
+//! Vertex AI serves the Gemini models from regional Google Cloud endpoints
+//! for a project. Unlike the API-key Gemini provider, requests are
+//! authenticated with OAuth 2.0 access tokens obtained by signing a JWT with a
+//! service account key, so access is governed by the project's IAM policies.
+
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures_util::stream::{self, StreamExt};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+use crate::media::{fit_image, guess_image_type};
+use crate::providers::gemini::IMAGE_LIMITS;
+use crate::retry::{self, RetryPolicy};
+use crate::trait_module::{ChatCompletionStream, ModelProvider};
+use crate::types::*;
+
+pub mod api_types;
+use api_types::*;
+
+/// The location used when none is configured.
+pub const DEFAULT_LOCATION: &str = "us-central1";
+
+/// The OAuth 2.0 scope requested for access tokens.
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+
+/// Gemini models served by Vertex AI, with their context windows.
+const GEMINI_MODELS: &[(&str, &str, u32)] = &[
+    ("gemini-2.5-pro", "Gemini 2.5 Pro", 1_048_576),
+    ("gemini-2.5-flash", "Gemini 2.5 Flash", 1_048_576),
+    ("gemini-2.5-flash-lite", "Gemini 2.5 Flash-Lite", 1_048_576),
+    ("gemini-2.0-flash", "Gemini 2.0 Flash", 1_048_576),
+    ("gemini-2.0-flash-lite", "Gemini 2.0 Flash-Lite", 1_048_576),
+];
+
+/// An access token and the time it stops being valid.
+struct CachedToken {
+    access_token: String,
+    expires_at: Instant,
+}
+
+/// Vertex AI provider implementation.
+///
+/// This struct implements the [`ModelProvider`] trait for the Gemini models
+/// on Vertex AI, supporting streaming responses, function calling, and
+/// multi-modal input. Access tokens are cached and refreshed shortly before
+/// they expire.
+///
+/// # Example
+///
+/// ```ignore
+/// use aisopod_provider::providers::vertex::VertexAIProvider;
+///
+/// let provider = VertexAIProvider::from_key_file(
+///     "/etc/aisopod/service-account.json",
+///     Some("europe-west4".to_string()),
+/// )?;
+/// ```
+pub struct VertexAIProvider {
+    client: reqwest::Client,
+    key: ServiceAccountKey,
+    pub location: String,
+    pub base_url: String,
+    token: Mutex<Option<CachedToken>>,
+    retry_policy: RetryPolicy,
+}
+
+impl VertexAIProvider {
+    /// Creates a new Vertex AI provider instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The service account key to authenticate with; requests are
+    ///   billed to its project.
+    /// * `location` - The Google Cloud region or "global" (defaults to [`DEFAULT_LOCATION`]).
+    pub fn new(key: ServiceAccountKey, location: Option<String>) -> Self {
+        let location = location.unwrap_or_else(|| DEFAULT_LOCATION.to_string());
+        Self {
+            client: reqwest::Client::new(),
+            key,
+            base_url: Self::regional_endpoint(&location),
+            location,
+            token: Mutex::new(None),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Creates a provider from a service account key file.
+    pub fn from_key_file(path: impl AsRef<Path>, location: Option<String>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read key file '{}': {}", path.display(), e))?;
+        let key: ServiceAccountKey = serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Invalid service account key file: {}", e))?;
+        Ok(Self::new(key, location))
+    }
+
+    /// Sends requests to `base_url` instead of the regional endpoint, e.g. a
+    /// Private Service Connect endpoint.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets how rate-limited requests are retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Returns the project requests are billed to.
+    pub fn project_id(&self) -> &str {
+        &self.key.project_id
+    }
+
+    /// Returns the API endpoint serving a location.
+    pub fn regional_endpoint(location: &str) -> String {
+        if location == "global" {
+            "https://aiplatform.googleapis.com".to_string()
+        } else {
+            format!("https://{}-aiplatform.googleapis.com", location)
+        }
+    }
+
+    /// Builds the URL of a method on a Gemini model.
+    fn model_url(&self, model: &str, method: &str) -> String {
+        format!(
+            "{}/v1/projects/{}/locations/{}/publishers/google/models/{}:{}",
+            self.base_url, self.key.project_id, self.location, model, method
+        )
+    }
+
+    /// Signs the JWT assertion exchanged for an access token.
+    fn sign_assertion(&self) -> Result<String> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let claims = JwtClaims {
+            iss: self.key.client_email.clone(),
+            scope: CLOUD_PLATFORM_SCOPE.to_string(),
+            aud: self.key.token_uri.clone(),
+            iat: now,
+            exp: now + 3600,
+        };
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = self.key.private_key_id.clone();
+
+        let key = EncodingKey::from_rsa_pem(self.key.private_key.as_bytes())
+            .map_err(|e| anyhow::anyhow!("Invalid service account private key: {}", e))?;
+        jsonwebtoken::encode(&header, &claims, &key)
+            .map_err(|e| anyhow::anyhow!("Failed to sign service account JWT: {}", e))
+    }
+
+    /// Returns a valid access token, exchanging a new assertion for one if the
+    /// cached token expires within a minute.
+    async fn access_token(&self) -> Result<String> {
+        let mut cached = self.token.lock().await;
+        if let Some(token) = cached.as_ref() {
+            if token.expires_at > Instant::now() + Duration::from_secs(60) {
+                return Ok(token.access_token.clone());
+            }
+        }
+
+        debug!(
+            "Requesting Vertex AI access token for {}",
+            self.key.client_email
+        );
+        let assertion = self.sign_assertion()?;
+        let response = self
+            .client
+            .post(&self.key.token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", assertion.as_str()),
+            ])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            return Err(anyhow::anyhow!(
+                "Vertex AI token exchange failed ({}): {}",
+                status,
+                body.trim()
+            ));
+        }
+
+        let token: TokenResponse = response.json().await?;
+        *cached = Some(CachedToken {
+            access_token: token.access_token.clone(),
+            expires_at: Instant::now() + Duration::from_secs(token.expires_in),
+        });
+        Ok(token.access_token)
+    }
+
+    /// Converts core message content to Vertex AI parts.
+    fn convert_content(content: &MessageContent) -> Vec<VertexPart> {
+        match content {
+            MessageContent::Text(text) if text.is_empty() => Vec::new(),
+            MessageContent::Text(text) => vec![VertexPart::Text(text.clone())],
+            MessageContent::Parts(parts) => parts
+                .iter()
+                .map(|part| match part {
+                    ContentPart::Text { text } => VertexPart::Text(text.clone()),
+                    ContentPart::Image { media_type, data } => {
+                        let (mime_type, data) = fit_image(media_type, data, &IMAGE_LIMITS);
+                        VertexPart::InlineData(VertexInlineData { mime_type, data })
+                    }
+                    ContentPart::ImageUrl { url } => VertexPart::FileData(VertexFileData {
+                        mime_type: guess_image_type(url).to_string(),
+                        file_uri: url.clone(),
+                    }),
+                })
+                .collect(),
+        }
+    }
+
+    /// Builds the Vertex AI request from a core request.
+    ///
+    /// System messages become the system instruction. Gemini function calls
+    /// carry no ID, so tool results are matched to the name of the function
+    /// through the calls earlier in the conversation.
+    fn build_vertex_request(request: &ChatCompletionRequest) -> VertexRequest {
+        let mut system_parts = Vec::new();
+        let mut contents: Vec<VertexContent> = Vec::new();
+
+        for message in &request.messages {
+            match message.role {
+                Role::System => system_parts.extend(Self::convert_content(&message.content)),
+                Role::User => contents.push(VertexContent {
+                    role: Some("user".to_string()),
+                    parts: Self::convert_content(&message.content),
+                }),
+                Role::Assistant => {
+                    let mut parts = Self::convert_content(&message.content);
+                    for call in message.tool_calls.iter().flatten() {
+                        parts.push(VertexPart::FunctionCall(VertexFunctionCall {
+                            name: call.name.clone(),
+                            args: serde_json::from_str(&call.arguments)
+                                .unwrap_or_else(|_| serde_json::json!({})),
+                        }));
+                    }
+                    contents.push(VertexContent {
+                        role: Some("model".to_string()),
+                        parts,
+                    });
+                }
+                Role::Tool => {
+                    let call_id = message.tool_call_id.as_deref().unwrap_or_default();
+                    let name = request
+                        .messages
+                        .iter()
+                        .flat_map(|message| message.tool_calls.iter().flatten())
+                        .find(|call| call.id == call_id)
+                        .map_or_else(|| call_id.to_string(), |call| call.name.clone());
+                    let output = match &message.content {
+                        MessageContent::Text(text) => text.clone(),
+                        MessageContent::Parts(_) => String::new(),
+                    };
+                    let part = VertexPart::FunctionResponse(VertexFunctionResponse {
+                        name,
+                        response: serde_json::json!({ "content": output }),
+                    });
+
+                    // Results of parallel calls belong in the same turn
+                    match contents.last_mut() {
+                        Some(last)
+                            if last.role.as_deref() == Some("user")
+                                && last.parts.iter().all(|part| {
+                                    matches!(part, VertexPart::FunctionResponse(_))
+                                }) =>
+                        {
+                            last.parts.push(part)
+                        }
+                        _ => contents.push(VertexContent {
+                            role: Some("user".to_string()),
+                            parts: vec![part],
+                        }),
+                    }
+                }
+            }
+        }
+
+        let tools = match &request.tools {
+            Some(tools) if !tools.is_empty() => vec![VertexTool {
+                function_declarations: tools
+                    .iter()
+                    .map(|tool| VertexFunctionDeclaration {
+                        name: tool.name.clone(),
+                        description: tool.description.clone(),
+                        parameters: tool.parameters.clone(),
+                    })
+                    .collect(),
+            }],
+            _ => Vec::new(),
+        };
+
+        VertexRequest {
+            contents,
+            system_instruction: (!system_parts.is_empty()).then_some(VertexContent {
+                role: None,
+                parts: system_parts,
+            }),
+            tools,
+            generation_config: VertexGenerationConfig {
+                temperature: request.temperature,
+                max_output_tokens: request.max_tokens,
+                stop_sequences: request.stop.clone(),
+            },
+        }
+    }
+
+    /// Parses an SSE line into a chat completion chunk.
+    ///
+    /// `calls` counts the function calls seen so far, to give each an ID.
+    fn parse_sse_line(line: &str, calls: &mut usize) -> Option<ChatCompletionChunk> {
+        let data = line.trim().strip_prefix("data:")?.trim();
+        let response: VertexResponse = serde_json::from_str(data).ok()?;
+        let candidate = response.candidates.into_iter().next()?;
+
+        let mut text = String::new();
+        let mut tool_calls = Vec::new();
+        for part in candidate.content.map(|c| c.parts).unwrap_or_default() {
+            match part {
+                VertexPart::Text(part) => text.push_str(&part),
+                VertexPart::FunctionCall(call) => {
+                    tool_calls.push(ToolCall {
+                        id: format!("call_{}", *calls),
+                        name: call.name,
+                        arguments: call.args.to_string(),
+                    });
+                    *calls += 1;
+                }
+                VertexPart::InlineData(_)
+                | VertexPart::FileData(_)
+                | VertexPart::FunctionResponse(_) => {}
+            }
+        }
+
+        let finish_reason = candidate
+            .finish_reason
+            .as_deref()
+            .map(|reason| match reason {
+                "STOP" if *calls > 0 => FinishReason::ToolCall,
+                "STOP" => FinishReason::Stop,
+                "MAX_TOKENS" => FinishReason::Length,
+                "SAFETY" | "RECITATION" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" => {
+                    FinishReason::ContentFilter
+                }
+                _ => FinishReason::Error,
+            });
+        let usage = finish_reason
+            .as_ref()
+            .and(response.usage_metadata)
+            .map(|usage| TokenUsage {
+                prompt_tokens: usage.prompt_token_count,
+                completion_tokens: usage.candidates_token_count,
+                total_tokens: usage.total_token_count,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
+            });
+
+        if text.is_empty() && tool_calls.is_empty() && finish_reason.is_none() {
+            return None;
+        }
+
+        Some(ChatCompletionChunk {
+            id: "vertex_chunk".to_string(),
+            delta: MessageDelta {
+                role: Some(Role::Assistant),
+                content: Some(text).filter(|text| !text.is_empty()),
+                tool_calls: Some(tool_calls).filter(|calls| !calls.is_empty()),
+                reasoning: None,
+            },
+            finish_reason,
+            usage,
+        })
+    }
+
+    /// Converts Vertex AI API error to anyhow error.
+    fn handle_api_error(status: u16, body: &str) -> anyhow::Error {
+        match serde_json::from_str::<VertexErrorResponse>(body) {
+            Ok(response) => {
+                anyhow::anyhow!(
+                    "Vertex AI API error ({}): {}",
+                    status,
+                    response.error.message
+                )
+            }
+            Err(_) => anyhow::anyhow!("Vertex AI API error ({}): {}", status, body.trim()),
+        }
+    }
+}
+
+#[async_trait]
+impl ModelProvider for VertexAIProvider {
+    fn id(&self) -> &str {
+        "vertex-ai"
+    }
+
+    /// Lists the Gemini models served by Vertex AI.
+    ///
+    /// Publisher models are not listed by the inference API, so this returns
+    /// the known Gemini models; availability varies by location.
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        Ok(GEMINI_MODELS
+            .iter()
+            .map(|(id, name, context_window)| ModelInfo {
+                id: id.to_string(),
+                name: name.to_string(),
+                provider: "vertex-ai".to_string(),
+                context_window: *context_window,
+                supports_vision: true,
+                supports_tools: true,
+            })
+            .collect())
+    }
+
+    async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionStream> {
+        let body = Self::build_vertex_request(&request);
+        let token = self.access_token().await?;
+
+        debug!(
+            "Sending request to Vertex AI: model={}, location={}",
+            request.model, self.location
+        );
+
+        let url = self.model_url(&request.model, "streamGenerateContent");
+        let builder = self
+            .client
+            .post(&url)
+            .query(&[("alt", "sse")])
+            .bearer_auth(token)
+            .json(&body);
+        let response = retry::send(builder, &self.retry_policy).await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            if status == 401 {
+                // Drop a token revoked before its expiry
+                *self.token.lock().await = None;
+            }
+            let body = response.text().await?;
+            return Err(Self::handle_api_error(status, &body));
+        }
+
+        let mut buffer = String::new();
+        let mut calls = 0;
+        let stream = response.bytes_stream().flat_map(move |bytes| {
+            let items: Vec<Result<ChatCompletionChunk>> = match bytes {
+                Ok(bytes) => {
+                    buffer.push_str(&String::from_utf8_lossy(&bytes));
+                    let mut chunks = Vec::new();
+                    while let Some(end) = buffer.find('\n') {
+                        let line: String = buffer.drain(..=end).collect();
+                        chunks.extend(Self::parse_sse_line(&line, &mut calls).map(Ok));
+                    }
+                    chunks
+                }
+                Err(e) => vec![Err(anyhow::anyhow!("Stream error: {}", e))],
+            };
+            stream::iter(items)
+        });
+        Ok(Box::pin(stream))
+    }
+
+    /// Checks that an access token can be obtained and the location is
+    /// reachable with it.
+    async fn health_check(&self) -> Result<ProviderHealth> {
+        let start = std::time::Instant::now();
+        let token = match self.access_token().await {
+            Ok(token) => token,
+            Err(e) => {
+                warn!("Health check error: {}", e);
+                return Ok(ProviderHealth {
+                    available: false,
+                    latency_ms: None,
+                });
+            }
+        };
+
+        let url = format!(
+            "{}/v1/projects/{}/locations/{}",
+            self.base_url, self.key.project_id, self.location
+        );
+        let response = self.client.get(&url).bearer_auth(token).send().await;
+        let latency_ms = start.elapsed().as_millis() as u64;
+
+        match response {
+            Ok(resp) if resp.status().is_success() => Ok(ProviderHealth {
+                available: true,
+                latency_ms: Some(latency_ms),
+            }),
+            Ok(resp) => {
+                warn!(
+                    "Health check failed with status: {}",
+                    resp.status().as_u16()
+                );
+                Ok(ProviderHealth {
+                    available: false,
+                    latency_ms: Some(latency_ms),
+                })
+            }
+            Err(e) => {
+                warn!("Health check error: {}", e);
+                Ok(ProviderHealth {
+                    available: false,
+                    latency_ms: None,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: Role, text: &str) -> Message {
+        Message {
+            role,
+            content: MessageContent::Text(text.to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+        }
+    }
+
+    #[test]
+    fn test_regional_endpoint() {
+        assert_eq!(
+            VertexAIProvider::regional_endpoint("europe-west4"),
+            "https://europe-west4-aiplatform.googleapis.com"
+        );
+        assert_eq!(
+            VertexAIProvider::regional_endpoint("global"),
+            "https://aiplatform.googleapis.com"
+        );
+    }
+
+    #[test]
+    fn test_build_request_matches_tool_results_to_calls() {
+        let mut assistant = message(Role::Assistant, "");
+        assistant.tool_calls = Some(vec![ToolCall {
+            id: "call_0".to_string(),
+            name: "get_weather".to_string(),
+            arguments: r#"{"city":"Paris"}"#.to_string(),
+        }]);
+        let mut result = message(Role::Tool, "18C, sunny");
+        result.tool_call_id = Some("call_0".to_string());
+
+        let request = ChatCompletionRequest {
+            model: "gemini-2.5-flash".to_string(),
+            messages: vec![
+                message(Role::System, "Be brief."),
+                message(Role::User, "Weather in Paris?"),
+                assistant,
+                result,
+            ],
+            tools: None,
+            temperature: None,
+            max_tokens: Some(256),
+            stop: None,
+            stream: true,
+        };
+        let body = serde_json::to_value(VertexAIProvider::build_vertex_request(&request)).unwrap();
+
+        assert_eq!(
+            body["systemInstruction"],
+            serde_json::json!({"parts": [{"text": "Be brief."}]})
+        );
+        assert_eq!(body["contents"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            body["contents"][1],
+            serde_json::json!({
+                "role": "model",
+                "parts": [{"functionCall": {"name": "get_weather", "args": {"city": "Paris"}}}]
+            })
+        );
+        assert_eq!(
+            body["contents"][2]["parts"][0]["functionResponse"]["name"],
+            "get_weather"
+        );
+        assert_eq!(body["generationConfig"]["maxOutputTokens"], 256);
+    }
+
+    #[test]
+    fn test_parse_sse_line_function_call() {
+        let mut calls = 0;
+        let chunk = VertexAIProvider::parse_sse_line(
+            r#"data: {"candidates":[{"content":{"role":"model","parts":[{"functionCall":{"name":"get_weather","args":{"city":"Paris"}}}]},"finishReason":"STOP"}],"usageMetadata":{"promptTokenCount":20,"candidatesTokenCount":5,"totalTokenCount":25}}"#,
+            &mut calls,
+        )
+        .unwrap();
+
+        let tool_calls = chunk.delta.tool_calls.unwrap();
+        assert_eq!(tool_calls[0].id, "call_0");
+        assert_eq!(tool_calls[0].arguments, r#"{"city":"Paris"}"#);
+        assert_eq!(chunk.finish_reason, Some(FinishReason::ToolCall));
+        assert_eq!(chunk.usage.unwrap().total_tokens, 25);
+    }
+}
@@ -0,0 +1,195 @@
+//! Vertex AI-specific request/response types.
+//!
+//! This module defines types used to serialize/deserialize requests and
+//! responses for the Gemini models on Vertex AI, along with the service
+//! account key file and the OAuth 2.0 token exchange used to authenticate.
+
+use serde::{Deserialize, Serialize};
+
+/// A Google Cloud service account key file, as downloaded from the console.
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct ServiceAccountKey {
+    pub project_id: String,
+    pub client_email: String,
+    /// The PEM-encoded RSA private key.
+    pub private_key: String,
+    #[serde(default)]
+    pub private_key_id: Option<String>,
+    /// The OAuth 2.0 token endpoint the signed assertion is exchanged at.
+    #[serde(default = "default_token_uri")]
+    pub token_uri: String,
+}
+
+impl std::fmt::Debug for ServiceAccountKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServiceAccountKey")
+            .field("project_id", &self.project_id)
+            .field("client_email", &self.client_email)
+            .field("private_key", &"..")
+            .field("token_uri", &self.token_uri)
+            .finish()
+    }
+}
+
+fn default_token_uri() -> String {
+    "https://oauth2.googleapis.com/token".to_string()
+}
+
+/// Claims of the JWT assertion exchanged for an access token.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JwtClaims {
+    pub iss: String,
+    pub scope: String,
+    pub aud: String,
+    pub iat: u64,
+    pub exp: u64,
+}
+
+/// Response from the OAuth 2.0 token endpoint.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    /// Lifetime of the token in seconds.
+    pub expires_in: u64,
+}
+
+/// The request body for the `generateContent` methods.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VertexRequest {
+    pub contents: Vec<VertexContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_instruction: Option<VertexContent>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<VertexTool>,
+    pub generation_config: VertexGenerationConfig,
+}
+
+/// A message in the conversation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VertexContent {
+    /// "user" or "model"; omitted for system instructions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub parts: Vec<VertexPart>,
+}
+
+/// A part of a message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VertexPart {
+    Text(String),
+    InlineData(VertexInlineData),
+    FileData(VertexFileData),
+    FunctionCall(VertexFunctionCall),
+    FunctionResponse(VertexFunctionResponse),
+}
+
+/// Inline media data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VertexInlineData {
+    pub mime_type: String,
+    /// Base64-encoded bytes.
+    pub data: String,
+}
+
+/// Media referenced by URI, e.g. a `gs://` or `https://` URL.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VertexFileData {
+    pub mime_type: String,
+    pub file_uri: String,
+}
+
+/// A function call made by the model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VertexFunctionCall {
+    pub name: String,
+    #[serde(default)]
+    pub args: serde_json::Value,
+}
+
+/// The result of a function call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VertexFunctionResponse {
+    pub name: String,
+    pub response: serde_json::Value,
+}
+
+/// Tools the model may call.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VertexTool {
+    pub function_declarations: Vec<VertexFunctionDeclaration>,
+}
+
+/// A function the model may call.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VertexFunctionDeclaration {
+    pub name: String,
+    pub description: String,
+    pub parameters: serde_json::Value,
+}
+
+/// Generation parameters.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VertexGenerationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_sequences: Option<Vec<String>>,
+}
+
+/// A streamed response from `streamGenerateContent`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VertexResponse {
+    #[serde(default)]
+    pub candidates: Vec<VertexCandidate>,
+    #[serde(default)]
+    pub usage_metadata: Option<VertexUsageMetadata>,
+}
+
+/// A response candidate.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VertexCandidate {
+    #[serde(default)]
+    pub content: Option<VertexContent>,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+/// Token usage of a request.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VertexUsageMetadata {
+    #[serde(default)]
+    pub prompt_token_count: u32,
+    #[serde(default)]
+    pub candidates_token_count: u32,
+    #[serde(default)]
+    pub total_token_count: u32,
+}
+
+/// Error response from Google Cloud APIs.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct VertexErrorResponse {
+    pub error: VertexError,
+}
+
+/// Error details from Google Cloud APIs.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct VertexError {
+    #[serde(default)]
+    pub code: Option<u16>,
+    pub message: String,
+    /// The canonical status, e.g. "PERMISSION_DENIED".
+    #[serde(default)]
+    pub status: Option<String>,
+}
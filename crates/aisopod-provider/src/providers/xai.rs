@@ -0,0 +1,234 @@
+//! xAI Grok API provider implementation.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::auth::{AuthProfile, AuthProfileManager};
+use crate::providers::openai::OpenAIProvider;
+use crate::retry::{self, RetryPolicy};
+use crate::trait_module::{ChatCompletionStream, ModelProvider};
+use crate::types::*;
+
+pub mod api_types;
+use api_types::*;
+
+/// xAI provider implementation.
+///
+/// This struct implements the [`ModelProvider`] trait for the Grok API,
+/// which follows the OpenAI Chat Completions format. It supports streaming
+/// SSE responses, tool calls, and vision for models that accept images.
+pub struct XaiProvider {
+    client: reqwest::Client,
+    api_key: String,
+    pub base_url: String,
+    profile_manager: Arc<Mutex<AuthProfileManager>>,
+    retry_policy: RetryPolicy,
+}
+
+impl XaiProvider {
+    /// Creates a new xAI provider instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - The API key for authenticating with xAI.
+    /// * `base_url` - The base URL for the Grok API (defaults to "https://api.x.ai").
+    /// * `cooldown_seconds` - The cooldown period in seconds for failed profiles.
+    pub fn new(api_key: String, base_url: Option<String>, cooldown_seconds: Option<u64>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key,
+            base_url: base_url.unwrap_or_else(|| "https://api.x.ai".to_string()),
+            profile_manager: Arc::new(Mutex::new(AuthProfileManager::new(Duration::from_secs(
+                cooldown_seconds.unwrap_or(60),
+            )))),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Sets how rate-limited requests are retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Returns the API key used for authentication.
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    /// Adds an authentication profile for key rotation.
+    pub fn add_profile(&mut self, profile: AuthProfile) {
+        let mut manager = self.profile_manager.lock().unwrap();
+        manager.add_profile(profile);
+    }
+
+    /// Gets the next available API key for round-robin rotation.
+    fn get_api_key(&self) -> String {
+        let mut manager = self.profile_manager.lock().unwrap();
+        manager
+            .next_key("xai")
+            .map(|p| p.api_key.clone())
+            .unwrap_or_else(|| self.api_key.clone())
+    }
+
+    /// Converts Grok API error to anyhow error.
+    fn handle_api_error(status: u16, body: &str) -> anyhow::Error {
+        match serde_json::from_str::<XaiErrorResponse>(body) {
+            Ok(response) => anyhow::anyhow!("xAI API error ({}): {}", status, response.error),
+            Err(_) => anyhow::anyhow!("xAI API error ({}): {}", status, body.trim()),
+        }
+    }
+
+    /// Estimates context window based on model name.
+    ///
+    /// The language models endpoint does not report context windows.
+    fn estimate_context_window(model_id: &str) -> u32 {
+        if model_id.starts_with("grok-4") {
+            256000
+        } else if model_id.contains("vision") {
+            32768
+        } else if model_id.starts_with("grok-2") || model_id.starts_with("grok-3") {
+            131072
+        } else {
+            8192 // Default fallback
+        }
+    }
+}
+
+#[async_trait]
+impl ModelProvider for XaiProvider {
+    fn id(&self) -> &str {
+        "xai"
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        debug!("Listing xAI models");
+
+        let url = format!("{}/v1/language-models", self.base_url);
+        let builder = self.client.get(&url).bearer_auth(self.get_api_key());
+        let response = retry::send(builder, &self.retry_policy).await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            return Err(Self::handle_api_error(status, &body));
+        }
+
+        let response: XaiModelList = response.json().await?;
+
+        // Keep only models that produce text
+        let models = response
+            .models
+            .into_iter()
+            .filter(|model| {
+                model.output_modalities.is_empty()
+                    || model.output_modalities.iter().any(|m| m == "text")
+            })
+            .map(|model| ModelInfo {
+                name: model.id.clone(),
+                provider: "xai".to_string(),
+                context_window: Self::estimate_context_window(&model.id),
+                supports_vision: model.input_modalities.iter().any(|m| m == "image"),
+                supports_tools: true,
+                id: model.id,
+            })
+            .collect();
+
+        Ok(models)
+    }
+
+    async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionStream> {
+        let body = OpenAIProvider::build_openai_request(&request);
+
+        debug!(
+            "Sending request to xAI API: model={}, stream={}",
+            body.model, body.stream
+        );
+
+        let url = format!("{}/v1/chat/completions", self.base_url);
+        let builder = self
+            .client
+            .post(&url)
+            .bearer_auth(self.get_api_key())
+            .json(&body);
+        let response = retry::send(builder, &self.retry_policy).await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            return Err(Self::handle_api_error(status, &body));
+        }
+
+        Ok(OpenAIProvider::sse_stream(response))
+    }
+
+    async fn health_check(&self) -> Result<ProviderHealth> {
+        let url = format!("{}/v1/api-key", self.base_url);
+        let start = std::time::Instant::now();
+        let response = self
+            .client
+            .get(&url)
+            .bearer_auth(self.get_api_key())
+            .send()
+            .await;
+        let latency_ms = start.elapsed().as_millis() as u64;
+
+        match response {
+            Ok(resp) if resp.status().is_success() => Ok(ProviderHealth {
+                available: true,
+                latency_ms: Some(latency_ms),
+            }),
+            Ok(resp) => {
+                warn!(
+                    "Health check failed with status: {}",
+                    resp.status().as_u16()
+                );
+                Ok(ProviderHealth {
+                    available: false,
+                    latency_ms: Some(latency_ms),
+                })
+            }
+            Err(e) => {
+                warn!("Health check error: {}", e);
+                Ok(ProviderHealth {
+                    available: false,
+                    latency_ms: None,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_context_window() {
+        assert_eq!(XaiProvider::estimate_context_window("grok-4-0709"), 256000);
+        assert_eq!(XaiProvider::estimate_context_window("grok-3-mini"), 131072);
+        assert_eq!(
+            XaiProvider::estimate_context_window("grok-2-vision-1212"),
+            32768
+        );
+        assert_eq!(XaiProvider::estimate_context_window("grok-beta"), 8192);
+    }
+
+    #[test]
+    fn test_handle_api_error() {
+        let err = XaiProvider::handle_api_error(
+            400,
+            r#"{"code":"Client specified an invalid argument","error":"Incorrect API key provided: xa***23."}"#,
+        );
+        assert_eq!(
+            err.to_string(),
+            "xAI API error (400): Incorrect API key provided: xa***23."
+        );
+    }
+}
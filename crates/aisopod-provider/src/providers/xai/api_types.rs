@@ -0,0 +1,34 @@
+//! xAI-specific response types.
+//!
+//! The Grok API serves an OpenAI-compatible chat completions API, so requests
+//! and streamed responses reuse the OpenAI types. The language models
+//! endpoint and error responses have their own shapes, defined here.
+
+use serde::Deserialize;
+
+/// Response from the language models endpoint.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct XaiModelList {
+    pub models: Vec<XaiModel>,
+}
+
+/// A language model served by the Grok API.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct XaiModel {
+    pub id: String,
+    #[serde(default)]
+    pub input_modalities: Vec<String>,
+    #[serde(default)]
+    pub output_modalities: Vec<String>,
+    /// Alternative names that resolve to this model (e.g. `grok-3-latest`).
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+/// Error response from the Grok API.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct XaiErrorResponse {
+    #[serde(default)]
+    pub code: Option<String>,
+    pub error: String,
+}
@@ -2,8 +2,10 @@
 //!
 //! This module provides the [`ProviderRegistry`] struct, which serves as a
 //! central registry for AI model providers. It allows registration, lookup,
-//! and listing of providers, as well as resolution of model aliases.
+//! and listing of providers, as well as resolution of model aliases. Embedding
+//! providers are registered alongside under names of their own.
 
+use crate::embedding::EmbeddingProvider;
 use crate::trait_module::ModelProvider;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -62,6 +64,7 @@ pub struct ModelAlias {
 pub struct ProviderRegistry {
     pub providers: HashMap<String, Arc<dyn ModelProvider>>,
     pub aliases: HashMap<String, ModelAlias>,
+    pub embedders: HashMap<String, Arc<dyn EmbeddingProvider>>,
 }
 
 impl ProviderRegistry {
@@ -70,6 +73,7 @@ impl ProviderRegistry {
         Self {
             providers: HashMap::new(),
             aliases: HashMap::new(),
+            embedders: HashMap::new(),
         }
     }
 
@@ -186,6 +190,31 @@ impl ProviderRegistry {
 
         None
     }
+
+    /// Registers an embedding provider under the given name.
+    ///
+    /// If an embedding provider with the same name is already registered,
+    /// it will be replaced.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name consumers look the provider up by (e.g., `"openai"`).
+    /// * `embedder` - An `Arc` wrapping the embedding provider instance.
+    pub fn register_embedder(&mut self, name: &str, embedder: Arc<dyn EmbeddingProvider>) {
+        self.embedders.insert(name.to_string(), embedder);
+    }
+
+    /// Looks up an embedding provider by name.
+    ///
+    /// Returns `Some` with an `Arc` to the embedding provider if found,
+    /// `None` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name the embedding provider was registered under.
+    pub fn get_embedder(&self, name: &str) -> Option<Arc<dyn EmbeddingProvider>> {
+        self.embedders.get(name).cloned()
+    }
 }
 
 impl Default for ProviderRegistry {
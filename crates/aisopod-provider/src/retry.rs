@@ -0,0 +1,332 @@
+//! Rate-limit-aware retry for provider HTTP requests.
+//!
+//! Providers send their requests through [`send`], which retries responses
+//! that signal a transient overload (HTTP 429 Too Many Requests and
+//! Anthropic's 529 Overloaded) as well as connection failures. The delay
+//! before each retry honors the wait requested by the server, read from
+//! `retry-after-ms`, `Retry-After` or the provider's rate-limit reset
+//! headers, and otherwise backs off exponentially with jitter.
+//!
+//! Only the initial request is retried: [`send`] returns as soon as a
+//! response's headers arrive, so a streamed body is never replayed once the
+//! caller starts consuming it. When the retries or the wait budget of the
+//! [`RetryPolicy`] are exhausted, the last response is returned unchanged
+//! for the provider to map to an error, e.g. a
+//! [`ProviderError::RateLimited`](crate::normalize::ProviderError::RateLimited)
+//! carrying [`retry_after`].
+//!
+//! Every attempt is recorded by the [`wire_log`] logger when one is
+//! installed.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use tracing::warn;
+
+use crate::wire_log;
+
+/// Status code Anthropic returns when its API is overloaded.
+const STATUS_OVERLOADED: u16 = 529;
+
+/// Headers carrying the time until a rate limit resets, as a duration
+/// (`"1m30s"`) for OpenAI-style APIs or an RFC 3339 timestamp for Anthropic.
+const RESET_HEADERS: &[&str] = &[
+    "x-ratelimit-reset-requests",
+    "x-ratelimit-reset-tokens",
+    "anthropic-ratelimit-requests-reset",
+    "anthropic-ratelimit-tokens-reset",
+    "anthropic-ratelimit-input-tokens-reset",
+    "anthropic-ratelimit-output-tokens-reset",
+];
+
+/// Controls how [`send`] retries rate-limited and overloaded requests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the initial attempt.
+    pub max_retries: u32,
+    /// Backoff before the first retry when the server requests no delay.
+    /// It doubles with every further retry.
+    pub initial_backoff: Duration,
+    /// Upper bound of the exponential backoff.
+    pub max_backoff: Duration,
+    /// Longest single wait before a retry. When the server asks for a
+    /// longer wait, the response is returned instead so the caller can fail
+    /// over rather than stall.
+    pub max_retry_after: Duration,
+    /// Total time that may be spent waiting across all retries.
+    pub budget: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+            max_retry_after: Duration::from_secs(10),
+            budget: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns a policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the jittered exponential backoff before retry `attempt`,
+    /// counting from zero.
+    ///
+    /// The delay is drawn from the upper half of the exponential step so
+    /// that concurrent clients spread out without retrying immediately.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let step = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff);
+        step / 2 + step.mul_f64(random_fraction() / 2.0)
+    }
+}
+
+/// Returns whether a response status signals a transient overload worth
+/// retrying.
+pub fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.as_u16() == STATUS_OVERLOADED
+}
+
+/// Reads the wait requested by the server from the headers of a response.
+///
+/// `retry-after-ms` (OpenAI, Azure) takes precedence over `Retry-After`,
+/// in seconds or as an HTTP date. Without either, the longest rate-limit
+/// reset reported in the `x-ratelimit-reset-*` or
+/// `anthropic-ratelimit-*-reset` headers is used.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+    let seconds = |value: &str| {
+        value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .and_then(|seconds| Duration::try_from_secs_f64(seconds.max(0.0)).ok())
+    };
+
+    if let Some(millis) = header("retry-after-ms").and_then(seconds) {
+        return Some(millis / 1000);
+    }
+    if let Some(value) = header("retry-after") {
+        if let Some(delay) = seconds(value) {
+            return Some(delay);
+        }
+        let value = value.trim();
+        if let Ok(date) = chrono::DateTime::parse_from_rfc2822(value) {
+            return Some(until(date.with_timezone(&chrono::Utc)));
+        }
+    }
+
+    RESET_HEADERS
+        .iter()
+        .filter_map(|name| header(name))
+        .filter_map(|value| {
+            parse_duration(value).or_else(|| {
+                chrono::DateTime::parse_from_rfc3339(value.trim())
+                    .ok()
+                    .map(|date| until(date.with_timezone(&chrono::Utc)))
+            })
+        })
+        .max()
+}
+
+/// Sends a request, retrying it according to `policy` while the server is
+/// rate limiting or overloaded.
+///
+/// Returns the first response that is not retried, successful or not, so
+/// callers handle errors exactly as for a plain `send`. Requests whose body
+/// cannot be cloned, such as streamed uploads, are sent once.
+pub async fn send(
+    request: reqwest::RequestBuilder,
+    policy: &RetryPolicy,
+) -> reqwest::Result<reqwest::Response> {
+    let mut waited = Duration::ZERO;
+    let mut attempt = 0;
+    loop {
+        let retry = (attempt < policy.max_retries)
+            .then(|| request.try_clone())
+            .flatten();
+        let Some(next) = retry else {
+            return send_once(request).await;
+        };
+
+        let result = send_once(next).await;
+        let delay = match &result {
+            Ok(response) if is_retryable_status(response.status()) => {
+                retry_after(response.headers()).unwrap_or_else(|| policy.backoff(attempt))
+            }
+            Err(err) if err.is_connect() => policy.backoff(attempt),
+            _ => return result,
+        };
+        if delay > policy.max_retry_after || waited + delay > policy.budget {
+            return result;
+        }
+
+        warn!(
+            "Provider request to {} failed transiently, retrying in {:?} (attempt {} of {})",
+            request_url(&request),
+            delay,
+            attempt + 1,
+            policy.max_retries
+        );
+        tokio::time::sleep(delay).await;
+        waited += delay;
+        attempt += 1;
+    }
+}
+
+/// Sends a request once, through the installed wire logger if any.
+async fn send_once(request: reqwest::RequestBuilder) -> reqwest::Result<reqwest::Response> {
+    match wire_log::installed() {
+        Some(logger) => logger.send(request).await,
+        None => request.send().await,
+    }
+}
+
+/// Parses a duration such as `"2m59.56s"`, `"7.66s"` or `"120ms"`, the
+/// format of the `x-ratelimit-reset-*` headers.
+pub(crate) fn parse_duration(value: &str) -> Option<Duration> {
+    let mut total = 0.0;
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let split = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .filter(|&i| i > 0)?;
+        let amount: f64 = rest[..split].parse().ok()?;
+        rest = &rest[split..];
+        let (scale, unit_len) = if rest.starts_with("ms") {
+            (0.001, 2)
+        } else if rest.starts_with('h') {
+            (3600.0, 1)
+        } else if rest.starts_with('m') {
+            (60.0, 1)
+        } else if rest.starts_with('s') {
+            (1.0, 1)
+        } else {
+            return None;
+        };
+        total += amount * scale;
+        rest = &rest[unit_len..];
+    }
+    Some(Duration::from_millis((total * 1000.0).round() as u64))
+}
+
+/// Returns the time left until `date`, or zero if it has passed.
+fn until(date: chrono::DateTime<chrono::Utc>) -> Duration {
+    (date - chrono::Utc::now()).to_std().unwrap_or_default()
+}
+
+/// Returns the URL of a request for logging.
+fn request_url(request: &reqwest::RequestBuilder) -> String {
+    request
+        .try_clone()
+        .and_then(|request| request.build().ok())
+        .map(|request| request.url().to_string())
+        .unwrap_or_default()
+}
+
+/// Returns a pseudo-random number in `[0, 1)` for jitter.
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("7.66s"), Some(Duration::from_millis(7660)));
+        assert_eq!(
+            parse_duration("2m59.56s"),
+            Some(Duration::from_millis(179560))
+        );
+        assert_eq!(parse_duration("1h0m0s"), Some(Duration::from_secs(3600)));
+        assert_eq!(parse_duration("120ms"), Some(Duration::from_millis(120)));
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("soon"), None);
+    }
+
+    #[test]
+    fn test_retry_after_header_precedence() {
+        assert_eq!(
+            retry_after(&headers(&[("retry-after-ms", "250"), ("retry-after", "3")])),
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(
+            retry_after(&headers(&[
+                ("retry-after", "3"),
+                ("x-ratelimit-reset-tokens", "20s")
+            ])),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(
+            retry_after(&headers(&[
+                ("x-ratelimit-reset-requests", "1.5s"),
+                ("x-ratelimit-reset-tokens", "6m0s")
+            ])),
+            Some(Duration::from_secs(360))
+        );
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_retry_after_dates() {
+        let past = "Wed, 21 Oct 2015 07:28:00 GMT";
+        assert_eq!(
+            retry_after(&headers(&[("retry-after", past)])),
+            Some(Duration::ZERO)
+        );
+
+        let reset = (chrono::Utc::now() + chrono::Duration::seconds(30)).to_rfc3339();
+        let delay = retry_after(&headers(&[("anthropic-ratelimit-tokens-reset", &reset)]))
+            .expect("reset timestamp should parse");
+        assert!(delay > Duration::from_secs(28) && delay <= Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_backoff_is_jittered_and_capped() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(4),
+            ..RetryPolicy::default()
+        };
+        for attempt in 0..6 {
+            let step = Duration::from_secs(1 << attempt.min(2));
+            let delay = policy.backoff(attempt);
+            assert!(delay >= step / 2 && delay <= step, "{:?}", delay);
+        }
+    }
+}
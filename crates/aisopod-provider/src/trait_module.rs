@@ -144,6 +144,7 @@ mod tests {
                         role: Some(Role::Assistant),
                         content: Some("Hello".to_string()),
                         tool_calls: None,
+                        reasoning: None,
                     },
                     finish_reason: None,
                     usage: None,
@@ -154,12 +155,15 @@ mod tests {
                         role: None,
                         content: Some(" world!".to_string()),
                         tool_calls: None,
+                        reasoning: None,
                     },
                     finish_reason: Some(FinishReason::Stop),
                     usage: Some(TokenUsage {
                         prompt_tokens: 5,
                         completion_tokens: 3,
                         total_tokens: 8,
+                        cache_read_tokens: 0,
+                        cache_write_tokens: 0,
                     }),
                 }),
             ];
@@ -41,6 +41,8 @@ pub enum ContentPart {
     Text { text: String },
     /// Image content within a multi-modal message.
     Image { media_type: String, data: String },
+    /// An image the provider fetches from a URL.
+    ImageUrl { url: String },
 }
 
 /// A message in a chat conversation.
@@ -141,7 +143,7 @@ pub enum FinishReason {
 }
 
 /// Usage statistics for token consumption.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct TokenUsage {
     /// The number of tokens in the prompt.
     pub prompt_tokens: u32,
@@ -149,6 +151,16 @@ pub struct TokenUsage {
     pub completion_tokens: u32,
     /// The total number of tokens used.
     pub total_tokens: u32,
+    /// The number of prompt tokens read from the provider's prompt cache.
+    ///
+    /// Cached tokens are included in `prompt_tokens`.
+    #[serde(default)]
+    pub cache_read_tokens: u32,
+    /// The number of prompt tokens written to the provider's prompt cache.
+    ///
+    /// Cached tokens are included in `prompt_tokens`.
+    #[serde(default)]
+    pub cache_write_tokens: u32,
 }
 
 /// A delta (incremental change) in a streaming chat completion.
@@ -163,6 +175,12 @@ pub struct MessageDelta {
     /// Optional tool calls in this delta.
     #[serde(default)]
     pub tool_calls: Option<Vec<ToolCall>>,
+    /// Reasoning (chain-of-thought) delta, kept apart from the content.
+    ///
+    /// Only reasoning models report it; it is not part of the response and
+    /// is never sent back to the model.
+    #[serde(default)]
+    pub reasoning: Option<String>,
 }
 
 /// A chunk of a streaming chat completion response.
@@ -302,6 +320,8 @@ mod tests {
             prompt_tokens: 10,
             completion_tokens: 5,
             total_tokens: 15,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
         };
         let json = serde_json::to_string(&usage).unwrap();
         let parsed: TokenUsage = serde_json::from_str(&json).unwrap();
@@ -314,6 +334,7 @@ mod tests {
             role: Some(Role::Assistant),
             content: Some("Hello!".to_string()),
             tool_calls: None,
+            reasoning: None,
         };
         let json = serde_json::to_string(&delta).unwrap();
         let parsed: MessageDelta = serde_json::from_str(&json).unwrap();
@@ -328,6 +349,7 @@ mod tests {
                 role: Some(Role::Assistant),
                 content: Some("Hello".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: None,
             usage: None,
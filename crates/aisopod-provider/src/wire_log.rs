@@ -0,0 +1,449 @@
+//! Opt-in wire-level logging of provider HTTP traffic.
+//!
+//! Once a [`WireLogger`] is installed with [`install`], every request sent
+//! through [`retry::send`](crate::retry::send) is written to a log file
+//! together with its response, as JSON lines carrying the URL, headers and
+//! full bodies. This is meant for debugging incompatibilities with a
+//! provider's API; streamed responses are passed through unchanged and
+//! written once the caller has consumed or dropped them.
+//!
+//! Credentials never reach the file. Headers, query parameters and JSON
+//! body fields named in the [`WireLogConfig`] are replaced with the
+//! [`Sensitive`] redaction marker, and the values of secrets registered with
+//! [`WireLogger::with_secret`], such as API keys, are masked wherever they
+//! appear. The log rotates to numbered files once it exceeds a size limit.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+
+use aisopod_config::Sensitive;
+use futures_util::StreamExt;
+use reqwest::header::HeaderMap;
+use serde_json::{json, Map, Value};
+use tracing::warn;
+
+/// Name of the active log file; rotated files get a numeric suffix.
+const FILE_NAME: &str = "provider-wire.log";
+
+/// Headers redacted by default.
+const DEFAULT_REDACT_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "x-api-key",
+    "api-key",
+    "x-goog-api-key",
+    "cookie",
+    "set-cookie",
+];
+
+/// Query parameters and JSON body fields redacted by default.
+const DEFAULT_REDACT_FIELDS: &[&str] = &[
+    "api_key",
+    "apiKey",
+    "key",
+    "access_token",
+    "refresh_token",
+    "client_secret",
+    "private_key",
+    "password",
+    "secret",
+    "token",
+];
+
+/// The installed logger, if any.
+static LOGGER: RwLock<Option<Arc<WireLogger>>> = RwLock::new(None);
+
+/// Settings of a [`WireLogger`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct WireLogConfig {
+    /// Directory the log files are written to.
+    pub dir: PathBuf,
+    /// Size in bytes at which the log file is rotated.
+    pub max_file_bytes: u64,
+    /// Number of rotated files kept besides the active one.
+    pub max_files: usize,
+    /// Headers whose values are redacted, matched case-insensitively.
+    pub redact_headers: Vec<String>,
+    /// Query parameters and JSON body fields, at any depth, whose values are
+    /// redacted, matched case-insensitively.
+    pub redact_fields: Vec<String>,
+}
+
+impl WireLogConfig {
+    /// Creates settings logging to `dir`, rotating at 10 MiB and keeping
+    /// five rotated files, with the default redactions.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_file_bytes: 10 * 1024 * 1024,
+            max_files: 5,
+            redact_headers: DEFAULT_REDACT_HEADERS
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            redact_fields: DEFAULT_REDACT_FIELDS
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// The active log file and its size.
+struct LogFile {
+    file: File,
+    size: u64,
+}
+
+/// Writes redacted provider requests and responses to rotating files.
+pub struct WireLogger {
+    config: WireLogConfig,
+    secrets: Vec<Sensitive<String>>,
+    file: Mutex<LogFile>,
+    next_id: AtomicU64,
+}
+
+impl WireLogger {
+    /// Creates a logger, creating the log directory if needed. Entries are
+    /// appended to an existing log file.
+    pub fn new(config: WireLogConfig) -> io::Result<Self> {
+        fs::create_dir_all(&config.dir)?;
+        let file = open_log(&config.dir.join(FILE_NAME))?;
+        Ok(Self {
+            config,
+            secrets: Vec::new(),
+            file: Mutex::new(file),
+            next_id: AtomicU64::new(1),
+        })
+    }
+
+    /// Masks every occurrence of `secret` in the log, wherever it appears.
+    pub fn with_secret(mut self, secret: Sensitive<String>) -> Self {
+        if !secret.expose().is_empty() {
+            self.secrets.push(secret);
+        }
+        self
+    }
+
+    /// Returns the path of the active log file.
+    pub fn path(&self) -> PathBuf {
+        self.config.dir.join(FILE_NAME)
+    }
+
+    /// Sends a request, logging it and its response.
+    pub(crate) async fn send(
+        self: &Arc<Self>,
+        request: reqwest::RequestBuilder,
+    ) -> reqwest::Result<reqwest::Response> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.log_request(id, &request);
+
+        let started = Instant::now();
+        match client.execute(request).await {
+            Ok(response) => Ok(self.record_response(id, started, response)),
+            Err(err) => {
+                self.write(json!({
+                    "id": id,
+                    "time": now(),
+                    "kind": "error",
+                    "error": err.to_string(),
+                }));
+                Err(err)
+            }
+        }
+    }
+
+    /// Logs a request about to be sent.
+    fn log_request(&self, id: u64, request: &reqwest::Request) {
+        let body = match request.body() {
+            None => Value::Null,
+            Some(body) => match body.as_bytes() {
+                Some(bytes) => self.redact_body(bytes),
+                None => Value::String("<streamed body>".to_string()),
+            },
+        };
+        self.write(json!({
+            "id": id,
+            "time": now(),
+            "kind": "request",
+            "method": request.method().as_str(),
+            "url": self.redact_url(request.url()),
+            "headers": self.redact_headers(request.headers()),
+            "body": body,
+        }));
+    }
+
+    /// Returns `response` with a body that records what passes through it,
+    /// logging the response once the body is consumed or dropped.
+    fn record_response(
+        self: &Arc<Self>,
+        id: u64,
+        started: Instant,
+        response: reqwest::Response,
+    ) -> reqwest::Response {
+        let status = response.status();
+        let version = response.version();
+        let headers = response.headers().clone();
+
+        let mut record = ResponseRecord {
+            logger: Arc::clone(self),
+            id,
+            started,
+            status: status.as_u16(),
+            headers: headers.clone(),
+            body: Vec::new(),
+        };
+        let body = response.bytes_stream().map(move |chunk| {
+            if let Ok(bytes) = &chunk {
+                record.body.extend_from_slice(bytes);
+            }
+            chunk
+        });
+
+        let mut replayed = http::Response::new(reqwest::Body::wrap_stream(body));
+        *replayed.status_mut() = status;
+        *replayed.version_mut() = version;
+        *replayed.headers_mut() = headers;
+        reqwest::Response::from(replayed)
+    }
+
+    /// Returns the headers as a JSON object with redacted values.
+    fn redact_headers(&self, headers: &HeaderMap) -> Value {
+        let mut redacted = Map::new();
+        for (name, value) in headers {
+            let value = if self.is_redacted(&self.config.redact_headers, name.as_str()) {
+                redaction_marker()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            redacted.insert(name.to_string(), Value::String(value));
+        }
+        Value::Object(redacted)
+    }
+
+    /// Returns the URL with redacted query parameters.
+    fn redact_url(&self, url: &reqwest::Url) -> String {
+        if url.query().is_none() {
+            return url.to_string();
+        }
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(name, value)| {
+                let value = if self.is_redacted(&self.config.redact_fields, &name) {
+                    redaction_marker()
+                } else {
+                    value.into_owned()
+                };
+                (name.into_owned(), value)
+            })
+            .collect();
+        let mut url = url.clone();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+        url.to_string()
+    }
+
+    /// Returns a body as JSON with redacted fields, or as text if it is not
+    /// JSON, such as a server-sent event stream.
+    fn redact_body(&self, body: &[u8]) -> Value {
+        match serde_json::from_slice::<Value>(body) {
+            Ok(mut value) => {
+                self.redact_fields(&mut value);
+                value
+            }
+            Err(_) => Value::String(String::from_utf8_lossy(body).into_owned()),
+        }
+    }
+
+    /// Redacts the configured fields of a JSON value in place.
+    fn redact_fields(&self, value: &mut Value) {
+        match value {
+            Value::Object(fields) => {
+                for (name, field) in fields.iter_mut() {
+                    if self.is_redacted(&self.config.redact_fields, name) {
+                        *field = Value::String(redaction_marker());
+                    } else {
+                        self.redact_fields(field);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_fields(item)),
+            _ => {}
+        }
+    }
+
+    fn is_redacted(&self, names: &[String], name: &str) -> bool {
+        names
+            .iter()
+            .any(|redacted| redacted.eq_ignore_ascii_case(name))
+    }
+
+    /// Appends an entry to the log, rotating the file if it grew too large.
+    fn write(&self, entry: Value) {
+        let mut line = entry.to_string();
+        for secret in &self.secrets {
+            line = line.replace(secret.expose().as_str(), &redaction_marker());
+        }
+        line.push('\n');
+
+        let mut file = self.file.lock().unwrap();
+        if file.size > 0 && file.size + line.len() as u64 > self.config.max_file_bytes {
+            match self.rotate() {
+                Ok(rotated) => *file = rotated,
+                Err(err) => warn!("Failed to rotate provider wire log: {}", err),
+            }
+        }
+        match file.file.write_all(line.as_bytes()) {
+            Ok(()) => file.size += line.len() as u64,
+            Err(err) => warn!("Failed to write provider wire log: {}", err),
+        }
+    }
+
+    /// Shifts the rotated files up by one, dropping the oldest, moves the
+    /// active file to `.1` and opens a new active file.
+    fn rotate(&self) -> io::Result<LogFile> {
+        let path = self.path();
+        let rotated = |n: usize| self.config.dir.join(format!("{}.{}", FILE_NAME, n));
+
+        if self.config.max_files == 0 {
+            fs::remove_file(&path)?;
+        } else {
+            let oldest = rotated(self.config.max_files);
+            if oldest.exists() {
+                fs::remove_file(&oldest)?;
+            }
+            for n in (1..self.config.max_files).rev() {
+                if rotated(n).exists() {
+                    fs::rename(rotated(n), rotated(n + 1))?;
+                }
+            }
+            fs::rename(&path, rotated(1))?;
+        }
+        open_log(&path)
+    }
+}
+
+/// The response side of a logged exchange, written when dropped.
+struct ResponseRecord {
+    logger: Arc<WireLogger>,
+    id: u64,
+    started: Instant,
+    status: u16,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl Drop for ResponseRecord {
+    fn drop(&mut self) {
+        self.logger.write(json!({
+            "id": self.id,
+            "time": now(),
+            "kind": "response",
+            "status": self.status,
+            "elapsed_ms": self.started.elapsed().as_millis() as u64,
+            "headers": self.logger.redact_headers(&self.headers),
+            "body": self.logger.redact_body(&self.body),
+        }));
+    }
+}
+
+/// Installs `logger` for all provider requests, replacing any installed one.
+pub fn install(logger: WireLogger) {
+    *LOGGER.write().unwrap() = Some(Arc::new(logger));
+}
+
+/// Stops wire logging.
+pub fn uninstall() {
+    *LOGGER.write().unwrap() = None;
+}
+
+/// Returns the installed logger, if any.
+pub fn installed() -> Option<Arc<WireLogger>> {
+    LOGGER.read().unwrap().clone()
+}
+
+fn open_log(path: &Path) -> io::Result<LogFile> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let size = file.metadata()?.len();
+    Ok(LogFile { file, size })
+}
+
+fn redaction_marker() -> String {
+    Sensitive::<String>::redacted_display().to_string()
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logger(dir: &Path) -> WireLogger {
+        WireLogger::new(WireLogConfig::new(dir))
+            .unwrap()
+            .with_secret(Sensitive::new("sk-secret".to_string()))
+    }
+
+    #[test]
+    fn test_redacts_fields_query_parameters_and_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        let logger = logger(dir.path());
+
+        let body =
+            logger.redact_body(br#"{"model":"m","auth":{"api_key":"x"},"items":[{"token":"y"}]}"#);
+        assert_eq!(
+            body,
+            json!({"model": "m", "auth": {"api_key": "***REDACTED***"}, "items": [{"token": "***REDACTED***"}]})
+        );
+
+        let url = reqwest::Url::parse("https://example.com/v1/models?key=abc&alt=sse").unwrap();
+        assert_eq!(
+            logger.redact_url(&url),
+            "https://example.com/v1/models?key=***REDACTED***&alt=sse"
+        );
+
+        logger.write(json!({"text": "Bearer sk-secret"}));
+        let log = fs::read_to_string(logger.path()).unwrap();
+        assert!(!log.contains("sk-secret"));
+        assert!(log.contains("Bearer ***REDACTED***"));
+    }
+
+    #[test]
+    fn test_rotates_and_keeps_configured_number_of_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = WireLogConfig {
+            max_file_bytes: 64,
+            max_files: 2,
+            ..WireLogConfig::new(dir.path())
+        };
+        let logger = WireLogger::new(config).unwrap();
+
+        for n in 0..10 {
+            logger
+                .write(json!({"entry": n, "padding": "0123456789012345678901234567890123456789"}));
+        }
+
+        let mut files: Vec<String> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                "provider-wire.log",
+                "provider-wire.log.1",
+                "provider-wire.log.2"
+            ]
+        );
+        let active = fs::read_to_string(logger.path()).unwrap();
+        assert!(active.contains(r#""entry":9"#));
+    }
+}
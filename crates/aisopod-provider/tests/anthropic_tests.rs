@@ -121,6 +121,7 @@ fn test_anthropic_tool_definition_serialization() {
             },
             "required": ["operation", "a", "b"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -286,6 +287,7 @@ fn test_build_anthropic_request_with_tools() {
         name: "calculator".to_string(),
         description: "A calculator".to_string(),
         input_schema: json!({"type": "object", "properties": {}}),
+        cache_control: None,
     }];
 
     let request = anthropic_api::AnthropicRequest {
@@ -473,6 +475,7 @@ fn test_anthropic_content_block_with_image() {
             r#type: "base64".to_string(),
             media_type: "image/png".to_string(),
             data: "base64data".to_string(),
+            url: None,
         },
     };
 
@@ -634,6 +637,7 @@ fn test_anthropic_multimodal_request() {
                             r#type: "base64".to_string(),
                             media_type: "image/png".to_string(),
                             data: "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==".to_string(),
+                            url: None,
                         },
                     },
                 ],
@@ -775,6 +779,8 @@ fn test_token_usage_serialization() {
         prompt_tokens: 100,
         completion_tokens: 50,
         total_tokens: 150,
+        cache_read_tokens: 0,
+        cache_write_tokens: 0,
     };
 
     let json = serde_json::to_string(&usage).unwrap();
@@ -795,6 +801,7 @@ fn test_message_delta_serialization() {
         role: Some(aisopod_provider::Role::Assistant),
         content: Some("Hello".to_string()),
         tool_calls: None,
+        reasoning: None,
     };
 
     let json = serde_json::to_string(&delta).unwrap();
@@ -816,12 +823,15 @@ fn test_chat_completion_chunk_serialization() {
             role: Some(aisopod_provider::Role::Assistant),
             content: Some("Hello world!".to_string()),
             tool_calls: None,
+            reasoning: None,
         },
         finish_reason: Some(aisopod_provider::FinishReason::Stop),
         usage: Some(aisopod_provider::TokenUsage {
             prompt_tokens: 10,
             completion_tokens: 5,
             total_tokens: 15,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
         }),
     };
 
@@ -1046,6 +1056,7 @@ fn test_full_anthropic_request_roundtrip() {
                 },
                 "required": ["operation", "a", "b"]
             }),
+            cache_control: None,
         }]),
         max_tokens: Some(1000),
         temperature: Some(0.7),
@@ -1102,6 +1113,7 @@ fn test_anthropic_image_source_roundtrip() {
         r#type: "base64".to_string(),
         media_type: "image/jpeg".to_string(),
         data: "/9j/4AAQSkZJRgABAQE=".to_string(),
+        url: None,
     };
 
     let json = serde_json::to_string(&original).unwrap();
@@ -1153,6 +1165,8 @@ fn test_anthropic_usage_stats_roundtrip() {
     let original = anthropic_api::AnthropicUsage {
         input_tokens: 100,
         output_tokens: 50,
+        cache_creation_input_tokens: 0,
+        cache_read_input_tokens: 0,
     };
 
     let json = serde_json::to_string(&original).unwrap();
@@ -1181,6 +1195,8 @@ fn test_anthropic_message_response_roundtrip() {
         usage: Some(anthropic_api::AnthropicUsage {
             input_tokens: 10,
             output_tokens: 5,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 0,
         }),
     };
 
@@ -1440,6 +1456,7 @@ fn test_anthropic_streaming_request_with_tools() {
                 "operation": {"type": "string"}
             }
         }),
+        cache_control: None,
     }];
 
     let request = anthropic_api::AnthropicRequest {
@@ -1491,6 +1508,7 @@ fn test_anthropic_complex_tool_use() {
             },
             "required": ["nested"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -1534,6 +1552,7 @@ fn test_anthropic_message_with_image_content() {
                     r#type: "base64".to_string(),
                     media_type: "image/jpeg".to_string(),
                     data: "/9j/4AAQSkZJRgABAQE=".to_string(),
+                    url: None,
                 },
             },
         ],
@@ -1652,6 +1671,7 @@ fn test_anthropic_tool_schema_with_required_fields() {
             },
             "required": ["operation", "a", "b"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -1703,6 +1723,7 @@ fn test_anthropic_tool_nested_input_schema() {
                 }
             }
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -1833,6 +1854,8 @@ fn test_anthropic_message_response_content() {
         usage: Some(anthropic_api::AnthropicUsage {
             input_tokens: 10,
             output_tokens: 5,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 0,
         }),
     };
 
@@ -1859,6 +1882,7 @@ fn test_anthropic_tool_definition_with_required() {
             },
             "required": ["operation", "a", "b"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -1954,16 +1978,19 @@ fn test_anthropic_message_with_multiple_tools() {
             name: "calculator".to_string(),
             description: "A calculator".to_string(),
             input_schema: json!({"type": "object", "properties": {}}),
+            cache_control: None,
         },
         anthropic_api::AnthropicTool {
             name: "weather".to_string(),
             description: "Get weather".to_string(),
             input_schema: json!({"type": "object", "properties": {}}),
+            cache_control: None,
         },
         anthropic_api::AnthropicTool {
             name: "news".to_string(),
             description: "Get news".to_string(),
             input_schema: json!({"type": "object", "properties": {}}),
+            cache_control: None,
         },
     ];
 
@@ -2078,6 +2105,7 @@ fn test_anthropic_tool_result_content_with_image() {
             r#type: "base64".to_string(),
             media_type: "image/png".to_string(),
             data: "data".to_string(),
+            url: None,
         },
     }];
 
@@ -2113,6 +2141,7 @@ fn test_anthropic_tool_definition_with_properties() {
             },
             "required": ["operation", "a", "b"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -2189,6 +2218,7 @@ fn test_anthropic_message_with_multiple_content_types() {
                     r#type: "base64".to_string(),
                     media_type: "image/png".to_string(),
                     data: "data".to_string(),
+                    url: None,
                 },
             },
             anthropic_api::AnthropicContentBlock::ToolResult {
@@ -2224,6 +2254,7 @@ fn test_anthropic_tool_definition_with_no_required() {
                 "b": {"type": "number"}
             }
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -2297,6 +2328,7 @@ fn test_anthropic_tool_definition_with_description() {
             "type": "object",
             "properties": {}
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -2413,6 +2445,7 @@ fn test_anthropic_tool_definition_with_all_fields() {
             },
             "required": ["operation"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -2443,6 +2476,7 @@ fn test_anthropic_request_with_system_prompt_and_tools() {
             name: "calculator".to_string(),
             description: "A calculator".to_string(),
             input_schema: json!({"type": "object", "properties": {}}),
+            cache_control: None,
         }]),
         max_tokens: Some(1000),
         temperature: None,
@@ -2542,6 +2576,7 @@ fn test_anthropic_tool_definition_with_required_array() {
             },
             "required": ["operation", "a", "b"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -2681,6 +2716,7 @@ fn test_anthropic_tool_definition_with_special_characters() {
             "type": "object",
             "properties": {}
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -2763,6 +2799,7 @@ fn test_anthropic_tool_definition_with_nested_schema() {
                 }
             }
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -2851,6 +2888,7 @@ fn test_anthropic_tool_definition_empty_description() {
             "type": "object",
             "properties": {}
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -2932,6 +2970,7 @@ fn test_anthropic_tool_definition_with_mixed_types() {
                 }
             }
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -3013,6 +3052,7 @@ fn test_anthropic_tool_definition_with_empty_properties() {
             "type": "object",
             "properties": {}
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -3070,6 +3110,7 @@ fn test_anthropic_tool_definition_with_empty_schema() {
         name: "calculator".to_string(),
         description: "A calculator".to_string(),
         input_schema: json!({}),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -3145,6 +3186,7 @@ fn test_anthropic_tool_definition_with_array_property() {
                 "numbers": {"type": "array", "items": {"type": "number"}}
             }
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -3213,6 +3255,7 @@ fn test_anthropic_tool_definition_with_default_values() {
                 "b": {"type": "number", "default": 0}
             }
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -3290,6 +3333,7 @@ fn test_anthropic_tool_definition_with_pattern_properties() {
                 "^custom_": {"type": "string"}
             }
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -3368,6 +3412,7 @@ fn test_anthropic_tool_definition_with_enum_properties() {
                 }
             }
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -3458,6 +3503,7 @@ fn test_anthropic_tool_definition_with_multiple_required_fields() {
             },
             "required": ["operation", "a", "b"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -3569,6 +3615,7 @@ fn test_anthropic_tool_definition_with_complex_nested_schema() {
             },
             "required": ["operation", "args"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -3640,6 +3687,7 @@ fn test_anthropic_tool_definition_with_mixed_type_arrays() {
                 }
             }
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -3763,6 +3811,7 @@ fn test_anthropic_tool_definition_with_allof_schema() {
                 }
             ]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -3861,6 +3910,7 @@ fn test_anthropic_tool_definition_with_schema_validation() {
             },
             "required": ["operation", "a", "b"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -4001,6 +4051,7 @@ fn test_anthropic_tool_definition_with_schema_validation_all_required_fields() {
             },
             "required": ["operation", "a", "b", "options"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -4133,6 +4184,7 @@ fn test_anthropic_tool_definition_with_schema_validation_all_required_fields_dee
             },
             "required": ["operation", "a", "b", "options"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -4252,6 +4304,7 @@ fn test_anthropic_tool_definition_with_schema_validation_all_required_fields_com
             },
             "required": ["operation", "a", "b", "options"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -4382,6 +4435,7 @@ fn test_anthropic_tool_definition_with_schema_validation_all_required_fields_com
             },
             "required": ["operation", "a", "b", "options"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -4519,6 +4573,7 @@ fn test_anthropic_tool_definition_with_schema_validation_all_required_fields_ver
             },
             "required": ["operation", "a", "b", "options"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -4662,6 +4717,7 @@ fn test_anthropic_tool_definition_with_schema_validation_all_required_fields_ext
             },
             "required": ["operation", "a", "b", "options"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -4811,6 +4867,7 @@ fn test_anthropic_tool_definition_with_schema_validation_all_required_fields_ins
             },
             "required": ["operation", "a", "b", "options"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -4965,6 +5022,7 @@ fn test_anthropic_tool_definition_with_schema_validation_all_required_fields_max
             },
             "required": ["operation", "a", "b", "options"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -5125,6 +5183,7 @@ fn test_anthropic_tool_definition_with_schema_validation_all_required_fields_ult
             },
             "required": ["operation", "a", "b", "options"]
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
@@ -0,0 +1,123 @@
+//! Tests for Azure OpenAI provider
+//!
+//! These tests run the provider against a local mock of the Azure OpenAI API.
+
+use aisopod_provider::providers::azure_openai::{AzureAuth, AzureOpenAIProvider};
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{ChatCompletionRequest, Message, MessageContent, Role};
+use futures_util::StreamExt;
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn request(model: &str) -> ChatCompletionRequest {
+    ChatCompletionRequest {
+        model: model.to_string(),
+        messages: vec![Message {
+            role: Role::User,
+            content: MessageContent::Text("Say hello!".to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+        }],
+        tools: None,
+        temperature: None,
+        max_tokens: None,
+        stop: None,
+        stream: true,
+    }
+}
+
+const SSE_BODY: &str = concat!(
+    "data: {\"id\":\"chatcmpl-1\",\"object\":\"chat.completion.chunk\",\"created\":1,\"model\":\"gpt-4o\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"content\":\"Hello\"},\"finish_reason\":null}]}\n\n",
+    "data: [DONE]\n\n",
+);
+
+#[tokio::test]
+async fn test_azure_chat_completion_routes_to_deployment() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/openai/deployments/chat-prod/chat/completions"))
+        .and(query_param("api-version", "2024-10-21"))
+        .and(header("api-key", "test-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(SSE_BODY, "text/event-stream"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = AzureOpenAIProvider::new(
+        server.uri(),
+        AzureAuth::ApiKey("test-key".to_string()),
+        Some("2024-10-21".to_string()),
+    )
+    .with_deployment("gpt-4o", "chat-prod");
+
+    let mut stream = provider.chat_completion(request("gpt-4o")).await.unwrap();
+    let chunk = stream.next().await.unwrap().unwrap();
+    assert_eq!(chunk.id, "chatcmpl-1");
+    assert_eq!(chunk.delta.content.as_deref(), Some("Hello"));
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_azure_ad_token_auth() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/openai/models"))
+        .and(query_param("api-version", "2024-06-01"))
+        .and(header("Authorization", "Bearer fresh-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{\"data\":[]}"))
+        .mount(&server)
+        .await;
+
+    let provider = AzureOpenAIProvider::new(
+        server.uri(),
+        AzureAuth::AzureAd("expired-token".to_string()),
+        None,
+    );
+    assert!(!provider.health_check().await.unwrap().available);
+
+    provider.set_auth(AzureAuth::AzureAd("fresh-token".to_string()));
+    assert!(provider.health_check().await.unwrap().available);
+}
+
+#[tokio::test]
+async fn test_azure_api_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/openai/deployments/gpt-35-turbo/chat/completions"))
+        .respond_with(ResponseTemplate::new(404).set_body_string(
+            r#"{"error":{"code":"DeploymentNotFound","message":"The API deployment for this resource does not exist."}}"#,
+        ))
+        .mount(&server)
+        .await;
+
+    let provider = AzureOpenAIProvider::new(
+        server.uri(),
+        AzureAuth::ApiKey("test-key".to_string()),
+        None,
+    );
+    let err = match provider.chat_completion(request("gpt-35-turbo")).await {
+        Ok(_) => panic!("Expected an error"),
+        Err(err) => err,
+    };
+    assert_eq!(
+        err.to_string(),
+        "Azure OpenAI API error (404): The API deployment for this resource does not exist."
+    );
+}
+
+#[tokio::test]
+async fn test_azure_list_models_returns_deployments() {
+    let provider = AzureOpenAIProvider::new(
+        "https://my-resource.openai.azure.com".to_string(),
+        AzureAuth::ApiKey("test-key".to_string()),
+        None,
+    )
+    .with_deployment("gpt-4o", "chat-prod")
+    .with_deployment("gpt-35-turbo", "chat-cheap");
+
+    let models = provider.list_models().await.unwrap();
+    let ids: Vec<&str> = models.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, vec!["gpt-35-turbo", "gpt-4o"]);
+    assert!(models.iter().all(|m| m.provider == "azure-openai"));
+    assert!(models[1].supports_vision);
+}
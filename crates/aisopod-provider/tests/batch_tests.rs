@@ -0,0 +1,293 @@
+//! Tests for batch submission
+//!
+//! These tests run the OpenAI Batch and Anthropic Message Batches flows
+//! against local mocks of the batch endpoints.
+
+use std::time::Duration;
+
+use aisopod_provider::batch::{
+    batch_cost, wait_for_batch, BatchProvider, BatchRequest, BatchStatus,
+};
+use aisopod_provider::helpers::create_test_request;
+use aisopod_provider::pricing::{ModelPricing, PricingTable};
+use aisopod_provider::providers::anthropic::AnthropicProvider;
+use aisopod_provider::providers::openai::OpenAIProvider;
+use aisopod_provider::types::FinishReason;
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn requests() -> Vec<BatchRequest> {
+    vec![
+        BatchRequest {
+            custom_id: "first".to_string(),
+            request: create_test_request("gpt-4o", "Summarize the notes"),
+        },
+        BatchRequest {
+            custom_id: "second".to_string(),
+            request: create_test_request("gpt-4o", "Rate the answer"),
+        },
+    ]
+}
+
+fn openai_batch(status: &str) -> serde_json::Value {
+    json!({
+        "id": "batch_1",
+        "object": "batch",
+        "endpoint": "/v1/chat/completions",
+        "status": status,
+        "output_file_id": "file-out",
+        "error_file_id": "file-err",
+        "request_counts": {"total": 2, "completed": 1, "failed": 1}
+    })
+}
+
+#[tokio::test]
+async fn test_openai_submit_uploads_jsonl_and_creates_batch() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/files"))
+        .and(body_string_contains(r#""custom_id":"second""#))
+        .and(body_string_contains(r#""url":"/v1/chat/completions""#))
+        .and(body_string_contains(r#""stream":false"#))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": "file-in"})))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/batches"))
+        .and(body_partial_json(json!({
+            "input_file_id": "file-in",
+            "endpoint": "/v1/chat/completions",
+            "completion_window": "24h"
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(openai_batch("validating")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = OpenAIProvider::new("test-key".to_string(), Some(server.uri()), None, None);
+    let job = provider.submit_batch(requests()).await.unwrap();
+
+    assert_eq!(job.id, "batch_1");
+    assert_eq!(job.provider, "openai");
+    assert_eq!(job.status, BatchStatus::InProgress);
+    assert_eq!(job.counts.total, 2);
+}
+
+#[tokio::test]
+async fn test_openai_results_combine_output_and_error_files() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/batches/batch_1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(openai_batch("completed")))
+        .mount(&server)
+        .await;
+    let output = json!({
+        "id": "batch_req_1",
+        "custom_id": "first",
+        "response": {
+            "status_code": 200,
+            "body": {
+                "id": "chatcmpl-1",
+                "object": "chat.completion",
+                "model": "gpt-4o-2024-08-06",
+                "choices": [{
+                    "index": 0,
+                    "message": {"role": "assistant", "content": "Three notes."},
+                    "finish_reason": "stop"
+                }],
+                "usage": {"prompt_tokens": 1000000, "completion_tokens": 0, "total_tokens": 1000000}
+            }
+        },
+        "error": null
+    });
+    let error = json!({
+        "id": "batch_req_2",
+        "custom_id": "second",
+        "response": {
+            "status_code": 400,
+            "body": {"error": {"message": "Invalid model", "type": "invalid_request_error"}}
+        },
+        "error": null
+    });
+    Mock::given(method("GET"))
+        .and(path("/v1/files/file-out/content"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!("{}\n", output)))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/files/file-err/content"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!("{}\n", error)))
+        .mount(&server)
+        .await;
+
+    let provider = OpenAIProvider::new("test-key".to_string(), Some(server.uri()), None, None);
+    let results = provider.batch_results("batch_1").await.unwrap();
+
+    assert_eq!(results.len(), 2);
+    let response = results[0].response.as_ref().unwrap();
+    assert_eq!(results[0].custom_id, "first");
+    assert_eq!(response.content, "Three notes.");
+    assert_eq!(response.finish_reason, Some(FinishReason::Stop));
+    let error = results[1].response.as_ref().unwrap_err();
+    assert!(error.contains("Invalid model"), "{}", error);
+
+    let mut pricing = PricingTable::new();
+    pricing.set(
+        None,
+        "gpt-4o",
+        ModelPricing::per_million(2.0, 8.0, None, None),
+    );
+    assert!((batch_cost(&pricing, "openai", &results) - 1.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_openai_results_of_running_batch_fail() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/batches/batch_1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(openai_batch("in_progress")))
+        .mount(&server)
+        .await;
+
+    let provider = OpenAIProvider::new("test-key".to_string(), Some(server.uri()), None, None);
+
+    assert!(provider.batch_results("batch_1").await.is_err());
+}
+
+fn anthropic_batch(processing_status: &str) -> serde_json::Value {
+    json!({
+        "id": "msgbatch_1",
+        "type": "message_batch",
+        "processing_status": processing_status,
+        "request_counts": {
+            "processing": if processing_status == "ended" { 0 } else { 2 },
+            "succeeded": if processing_status == "ended" { 1 } else { 0 },
+            "errored": if processing_status == "ended" { 1 } else { 0 },
+            "canceled": 0,
+            "expired": 0
+        },
+        "cancel_initiated_at": null
+    })
+}
+
+#[tokio::test]
+async fn test_anthropic_submit_sends_requests_as_params() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/messages/batches"))
+        .and(body_partial_json(json!({
+            "requests": [
+                {"custom_id": "first", "params": {"model": "gpt-4o", "stream": false}},
+                {"custom_id": "second", "params": {"model": "gpt-4o", "stream": false}}
+            ]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(anthropic_batch("in_progress")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = AnthropicProvider::new("test-key".to_string(), Some(server.uri()), None, None);
+    let job = provider.submit_batch(requests()).await.unwrap();
+
+    assert_eq!(job.id, "msgbatch_1");
+    assert_eq!(job.status, BatchStatus::InProgress);
+    assert_eq!(job.counts.total, 2);
+}
+
+#[tokio::test]
+async fn test_anthropic_wait_and_retrieve_results() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/messages/batches/msgbatch_1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(anthropic_batch("in_progress")))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/messages/batches/msgbatch_1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(anthropic_batch("ended")))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let succeeded = json!({
+        "custom_id": "first",
+        "result": {
+            "type": "succeeded",
+            "message": {
+                "id": "msg_1",
+                "type": "message",
+                "role": "assistant",
+                "model": "claude-3-5-haiku-20241022",
+                "content": [
+                    {"type": "text", "text": "Looking it up."},
+                    {"type": "tool_use", "id": "toolu_1", "name": "search", "input": {"q": "notes"}}
+                ],
+                "stop_reason": "tool_use",
+                "usage": {"input_tokens": 10, "output_tokens": 5}
+            }
+        }
+    });
+    let errored = json!({
+        "custom_id": "second",
+        "result": {
+            "type": "errored",
+            "error": {"type": "error", "error": {"type": "invalid_request_error", "message": "max_tokens: Field required"}}
+        }
+    });
+    Mock::given(method("GET"))
+        .and(path("/v1/messages/batches/msgbatch_1/results"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(format!("{}\n{}\n", succeeded, errored)),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = AnthropicProvider::new("test-key".to_string(), Some(server.uri()), None, None);
+    let job = wait_for_batch(
+        &provider,
+        "msgbatch_1",
+        Duration::from_millis(10),
+        Duration::from_secs(5),
+    )
+    .await
+    .unwrap();
+    assert_eq!(job.status, BatchStatus::Completed);
+    assert_eq!(job.counts.succeeded, 1);
+    assert_eq!(job.counts.failed, 1);
+
+    let results = provider.batch_results(&job.id).await.unwrap();
+    let response = results[0].response.as_ref().unwrap();
+    assert_eq!(response.content, "Looking it up.");
+    assert_eq!(response.tool_calls[0].name, "search");
+    assert_eq!(response.tool_calls[0].arguments, r#"{"q":"notes"}"#);
+    assert_eq!(response.finish_reason, Some(FinishReason::ToolCall));
+    assert_eq!(response.usage.total_tokens, 15);
+    assert_eq!(
+        results[1].response,
+        Err("max_tokens: Field required".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_wait_for_batch_times_out() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/messages/batches/msgbatch_1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(anthropic_batch("in_progress")))
+        .mount(&server)
+        .await;
+
+    let provider = AnthropicProvider::new("test-key".to_string(), Some(server.uri()), None, None);
+    let result = wait_for_batch(
+        &provider,
+        "msgbatch_1",
+        Duration::from_millis(10),
+        Duration::from_millis(25),
+    )
+    .await;
+
+    assert!(result.is_err());
+}
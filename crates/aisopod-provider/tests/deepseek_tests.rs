@@ -0,0 +1,112 @@
+//! Tests for DeepSeek provider
+//!
+//! These tests run the provider against a local mock of the DeepSeek API.
+
+use aisopod_provider::providers::deepseek::DeepSeekProvider;
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{ChatCompletionRequest, FinishReason, Message, MessageContent, Role};
+use futures_util::StreamExt;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn request() -> ChatCompletionRequest {
+    ChatCompletionRequest {
+        model: "deepseek-reasoner".to_string(),
+        messages: vec![Message {
+            role: Role::User,
+            content: MessageContent::Text("9.11 and 9.8, which is greater?".to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+        }],
+        tools: None,
+        temperature: None,
+        max_tokens: None,
+        stop: None,
+        stream: true,
+    }
+}
+
+#[tokio::test]
+async fn test_deepseek_streams_reasoning_separately() {
+    let body = concat!(
+        "data: {\"id\":\"r-1\",\"object\":\"chat.completion.chunk\",\"created\":1,\"model\":\"deepseek-reasoner\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"content\":null,\"reasoning_content\":\"Compare the decimals.\"},\"finish_reason\":null}]}\n\n",
+        ": keep-alive\n\n",
+        "data: {\"id\":\"r-1\",\"object\":\"chat.completion.chunk\",\"created\":1,\"model\":\"deepseek-reasoner\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"9.8 is greater.\",\"reasoning_content\":null},\"finish_reason\":null}]}\n\n",
+        "data: {\"id\":\"r-1\",\"object\":\"chat.completion.chunk\",\"created\":1,\"model\":\"deepseek-reasoner\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"\",\"reasoning_content\":null},\"finish_reason\":\"stop\"}],\"usage\":{\"prompt_tokens\":17,\"completion_tokens\":12,\"total_tokens\":29,\"completion_tokens_details\":{\"reasoning_tokens\":5}}}\n\n",
+        "data: [DONE]\n\n",
+    );
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .and(header("Authorization", "Bearer test-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = DeepSeekProvider::new("test-key".to_string(), Some(server.uri()), None);
+    let chunks: Vec<_> = provider
+        .chat_completion(request())
+        .await
+        .unwrap()
+        .map(|chunk| chunk.unwrap())
+        .collect()
+        .await;
+
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(
+        chunks[0].delta.reasoning.as_deref(),
+        Some("Compare the decimals.")
+    );
+    assert_eq!(chunks[0].delta.content, None);
+    assert_eq!(chunks[1].delta.content.as_deref(), Some("9.8 is greater."));
+    assert_eq!(chunks[1].delta.reasoning, None);
+    assert_eq!(chunks[2].finish_reason, Some(FinishReason::Stop));
+    assert_eq!(chunks[2].usage.as_ref().unwrap().total_tokens, 29);
+}
+
+#[tokio::test]
+async fn test_deepseek_list_models() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "object": "list",
+            "data": [
+                {"id": "deepseek-chat", "object": "model", "owned_by": "deepseek"},
+                {"id": "deepseek-reasoner", "object": "model", "owned_by": "deepseek"}
+            ]
+        })))
+        .mount(&server)
+        .await;
+
+    let provider = DeepSeekProvider::new("test-key".to_string(), Some(server.uri()), None);
+    let models = provider.list_models().await.unwrap();
+    let ids: Vec<&str> = models.iter().map(|model| model.id.as_str()).collect();
+    assert_eq!(ids, ["deepseek-chat", "deepseek-reasoner"]);
+    assert!(models.iter().all(|model| model.provider == "deepseek"));
+}
+
+#[tokio::test]
+async fn test_deepseek_api_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(402).set_body_json(serde_json::json!({
+            "error": {
+                "message": "Insufficient Balance",
+                "type": "unknown_error",
+                "param": null,
+                "code": "invalid_request_error"
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    let provider = DeepSeekProvider::new("test-key".to_string(), Some(server.uri()), None);
+    let err = provider.chat_completion(request()).await.err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "DeepSeek API error (402): Insufficient Balance"
+    );
+}
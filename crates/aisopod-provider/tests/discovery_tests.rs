@@ -597,3 +597,111 @@ async fn test_cache_persists_across_list_all_calls() {
         "Cache should be used for subsequent calls"
     );
 }
+
+// ============================================================================
+// Disk Cache and Change Event Tests
+// ============================================================================
+
+fn model(provider: &str, id: &str) -> ModelInfo {
+    ModelInfo {
+        id: id.to_string(),
+        name: id.to_string(),
+        provider: provider.to_string(),
+        context_window: 8192,
+        supports_vision: false,
+        supports_tools: false,
+    }
+}
+
+fn registry_with(provider: MockProvider) -> Arc<RwLock<ProviderRegistry>> {
+    let registry = Arc::new(RwLock::new(ProviderRegistry::new()));
+    registry.write().unwrap().register(Arc::new(provider));
+    registry
+}
+
+#[tokio::test]
+async fn test_disk_cache_serves_models_when_providers_are_offline() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("models.json");
+
+    let mut provider = MockProvider::new("test");
+    provider.models = vec![model("test", "model1")];
+    let catalog = ModelCatalog::new(registry_with(provider), Duration::from_secs(3600))
+        .with_disk_cache(&path);
+    catalog.refresh().await.unwrap();
+    assert!(path.exists());
+
+    // A restarted catalog lists the cached models without querying providers
+    let call_count = Arc::new(Mutex::new(0i32));
+    let mut offline = MockProvider::new("test").with_call_count(call_count.clone());
+    offline.should_fail = true;
+    let catalog =
+        ModelCatalog::new(registry_with(offline), Duration::from_secs(3600)).with_disk_cache(&path);
+    let models = catalog.list_all().await.unwrap();
+    assert_eq!(models, vec![model("test", "model1")]);
+    assert_eq!(*call_count.lock().unwrap(), 0);
+
+    // Refreshing while the provider is down keeps its models
+    catalog.refresh().await.unwrap();
+    assert_eq!(*call_count.lock().unwrap(), 1);
+    assert_eq!(catalog.list_all().await.unwrap(), models);
+}
+
+#[tokio::test]
+async fn test_unreadable_disk_cache_is_ignored() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("models.json");
+    std::fs::write(&path, "not json").unwrap();
+
+    let mut provider = MockProvider::new("test");
+    provider.models = vec![model("test", "model1")];
+    let catalog = ModelCatalog::new(registry_with(provider), Duration::from_secs(3600))
+        .with_disk_cache(&path);
+
+    assert_eq!(catalog.list_all().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_refresh_emits_added_and_removed_models() {
+    let mut provider = MockProvider::new("test");
+    provider.models = vec![model("test", "model1"), model("test", "model2")];
+    let registry = registry_with(provider);
+    let catalog = ModelCatalog::new(registry.clone(), Duration::from_secs(3600));
+    catalog.refresh().await.unwrap();
+
+    let mut changes = catalog.subscribe();
+
+    // An unchanged refresh sends nothing
+    catalog.refresh().await.unwrap();
+    assert!(changes.try_recv().is_err());
+
+    let mut provider = MockProvider::new("test");
+    provider.models = vec![model("test", "model2"), model("test", "model3")];
+    registry.write().unwrap().register(Arc::new(provider));
+    catalog.refresh().await.unwrap();
+
+    let change = changes.try_recv().unwrap();
+    assert_eq!(change.added, vec![model("test", "model3")]);
+    assert_eq!(change.removed, vec![model("test", "model1")]);
+}
+
+#[tokio::test]
+async fn test_background_refresh_populates_catalog() {
+    let mut provider = MockProvider::new("test");
+    provider.models = vec![model("test", "model1")];
+    let catalog = Arc::new(ModelCatalog::new(
+        registry_with(provider),
+        Duration::from_secs(3600),
+    ));
+    let mut changes = catalog.subscribe();
+
+    let handle = catalog.spawn_refresh(Duration::from_secs(3600));
+    let change = tokio::time::timeout(Duration::from_secs(5), changes.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    handle.abort();
+
+    assert_eq!(change.added, vec![model("test", "model1")]);
+    assert!(change.removed.is_empty());
+}
@@ -0,0 +1,201 @@
+//! Tests for embedding providers
+//!
+//! These tests run the embedding backends against local mocks of their APIs.
+
+use std::sync::Arc;
+
+use aisopod_provider::embedding::{
+    EmbeddingProvider, GeminiEmbeddingProvider, OllamaEmbeddingProvider, OpenAIEmbeddingProvider,
+};
+use aisopod_provider::ProviderRegistry;
+use wiremock::matchers::{body_json, header, method, path, query_param};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+#[tokio::test]
+async fn test_openai_embed_batch_orders_by_index() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/embeddings"))
+        .and(header("Authorization", "Bearer sk-test"))
+        .and(body_json(serde_json::json!({
+            "model": "text-embedding-3-small",
+            "input": ["first", "second"],
+            "dimensions": 2
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "object": "list",
+            "data": [
+                {"object": "embedding", "index": 1, "embedding": [0.0, 1.0]},
+                {"object": "embedding", "index": 0, "embedding": [1.0, 0.0]}
+            ],
+            "model": "text-embedding-3-small",
+            "usage": {"prompt_tokens": 2, "total_tokens": 2}
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = OpenAIEmbeddingProvider::new("sk-test".to_string(), None, Some(2))
+        .with_base_url(server.uri());
+    let embeddings = provider.embed_batch(&["first", "second"]).await.unwrap();
+
+    assert_eq!(embeddings, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+    assert_eq!(provider.dimensions(), 2);
+}
+
+#[tokio::test]
+async fn test_openai_local_server_sends_no_auth_or_dimensions() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/embeddings"))
+        .and(|request: &Request| !request.headers.contains_key("authorization"))
+        .and(body_json(serde_json::json!({
+            "model": "bge-small",
+            "input": ["hello"]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{"index": 0, "embedding": [0.5, 0.5, 0.5]}]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = OpenAIEmbeddingProvider::local(server.uri(), "bge-small".to_string(), 3);
+    assert_eq!(provider.embed("hello").await.unwrap(), vec![0.5, 0.5, 0.5]);
+}
+
+#[tokio::test]
+async fn test_openai_embedding_error_message() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/embeddings"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+            "error": {"message": "Incorrect API key provided", "type": "invalid_request_error"}
+        })))
+        .mount(&server)
+        .await;
+
+    let provider =
+        OpenAIEmbeddingProvider::new("bad".to_string(), None, None).with_base_url(server.uri());
+    let err = provider.embed("hello").await.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "OpenAI API error (401): Incorrect API key provided"
+    );
+}
+
+#[tokio::test]
+async fn test_gemini_embed_batch() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1beta/models/text-embedding-004:batchEmbedContents"))
+        .and(query_param("key", "gemini-key"))
+        .and(body_json(serde_json::json!({
+            "requests": [
+                {
+                    "model": "models/text-embedding-004",
+                    "content": {"parts": [{"text": "first"}]},
+                    "outputDimensionality": 768
+                },
+                {
+                    "model": "models/text-embedding-004",
+                    "content": {"parts": [{"text": "second"}]},
+                    "outputDimensionality": 768
+                }
+            ]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "embeddings": [{"values": [0.1, 0.2]}, {"values": [0.3, 0.4]}]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = GeminiEmbeddingProvider::new("gemini-key".to_string(), None, None)
+        .with_base_url(server.uri());
+    let embeddings = provider.embed_batch(&["first", "second"]).await.unwrap();
+
+    assert_eq!(embeddings, vec![vec![0.1, 0.2], vec![0.3, 0.4]]);
+}
+
+#[tokio::test]
+async fn test_ollama_embed() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/embed"))
+        .and(body_json(serde_json::json!({
+            "model": "nomic-embed-text",
+            "input": ["hello"]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "model": "nomic-embed-text",
+            "embeddings": [[0.25, 0.75]]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = OllamaEmbeddingProvider::new(Some(server.uri()), None, Some(2));
+    assert_eq!(provider.embed("hello").await.unwrap(), vec![0.25, 0.75]);
+}
+
+#[tokio::test]
+async fn test_ollama_embedding_count_mismatch() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/embed"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "embeddings": [[0.25, 0.75]]
+        })))
+        .mount(&server)
+        .await;
+
+    let provider = OllamaEmbeddingProvider::new(Some(server.uri()), None, Some(2));
+    let err = provider.embed_batch(&["a", "b"]).await.unwrap_err();
+    assert_eq!(err.to_string(), "Ollama returned 1 embeddings for 2 inputs");
+}
+
+#[test]
+fn test_registry_embedders() {
+    let mut registry = ProviderRegistry::new();
+    registry.register_embedder(
+        "local",
+        Arc::new(OllamaEmbeddingProvider::new(None, None, Some(384))),
+    );
+
+    assert_eq!(registry.get_embedder("local").unwrap().dimensions(), 384);
+    assert!(registry.get_embedder("openai").is_none());
+}
+
+#[cfg(feature = "local-embeddings")]
+#[tokio::test]
+async fn test_local_model_download_is_cached() {
+    use aisopod_provider::embedding::local::{download_model, LocalEmbeddingModel};
+
+    let server = MockServer::start().await;
+    for (file, body) in [("onnx/model.onnx", "onnx-bytes"), ("tokenizer.json", "{}")] {
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/sentence-transformers/all-MiniLM-L6-v2/resolve/main/{}",
+                file
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_string(body))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let cache = tempfile::tempdir().unwrap();
+    let model = LocalEmbeddingModel::all_minilm_l6_v2();
+    let (onnx, tokenizer) = download_model(&model, cache.path(), Some(&server.uri()))
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(&onnx).unwrap(), "onnx-bytes");
+    assert_eq!(std::fs::read_to_string(&tokenizer).unwrap(), "{}");
+    assert!(onnx.starts_with(model.cache_path(cache.path())));
+
+    // A second load is served from the cache.
+    download_model(&model, cache.path(), Some(&server.uri()))
+        .await
+        .unwrap();
+}
@@ -0,0 +1,162 @@
+//! Tests for Groq provider
+//!
+//! These tests run the provider against a local mock of the Groq API.
+
+use std::time::Duration;
+
+use aisopod_provider::auth::AuthProfile;
+use aisopod_provider::normalize::ProviderError;
+use aisopod_provider::providers::groq::GroqProvider;
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{ChatCompletionRequest, Message, MessageContent, Role};
+use futures_util::StreamExt;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn request() -> ChatCompletionRequest {
+    ChatCompletionRequest {
+        model: "llama-3.3-70b-versatile".to_string(),
+        messages: vec![Message {
+            role: Role::User,
+            content: MessageContent::Text("Say hello!".to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+        }],
+        tools: None,
+        temperature: None,
+        max_tokens: None,
+        stop: None,
+        stream: true,
+    }
+}
+
+const SSE_BODY: &str = concat!(
+    "data: {\"id\":\"chatcmpl-1\",\"object\":\"chat.completion.chunk\",\"created\":1,\"model\":\"llama-3.3-70b-versatile\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"content\":\"Hello\"},\"finish_reason\":null}]}\n\n",
+    "data: [DONE]\n\n",
+);
+
+#[tokio::test]
+async fn test_groq_chat_completion_records_rate_limits() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(header("Authorization", "Bearer test-key"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw(SSE_BODY, "text/event-stream")
+                .insert_header("x-ratelimit-limit-requests", "14400")
+                .insert_header("x-ratelimit-remaining-requests", "14399")
+                .insert_header("x-ratelimit-reset-requests", "6s")
+                .insert_header("x-ratelimit-limit-tokens", "6000")
+                .insert_header("x-ratelimit-remaining-tokens", "5990")
+                .insert_header("x-ratelimit-reset-tokens", "100ms"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = GroqProvider::new("test-key".to_string(), Some(server.uri()), None);
+    let mut stream = provider.chat_completion(request()).await.unwrap();
+    let chunk = stream.next().await.unwrap().unwrap();
+    assert_eq!(chunk.delta.content.as_deref(), Some("Hello"));
+
+    let limits = provider.rate_limits().unwrap();
+    assert_eq!(limits.remaining_requests, Some(14399));
+    assert_eq!(limits.reset_requests, Some(Duration::from_secs(6)));
+    assert_eq!(limits.remaining_tokens, Some(5990));
+    assert_eq!(limits.reset_tokens, Some(Duration::from_millis(100)));
+}
+
+#[tokio::test]
+async fn test_groq_rate_limited_rotates_key() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(header("Authorization", "Bearer key-1"))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("retry-after", "12")
+                .set_body_string(
+                    "{\"error\":{\"message\":\"Rate limit reached\",\"type\":\"tokens\"}}",
+                ),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(header("Authorization", "Bearer key-2"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(SSE_BODY, "text/event-stream"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut provider = GroqProvider::new("test-key".to_string(), Some(server.uri()), None);
+    provider.add_profile(AuthProfile::new(
+        "first".to_string(),
+        "groq".to_string(),
+        "key-1".to_string(),
+    ));
+    provider.add_profile(AuthProfile::new(
+        "second".to_string(),
+        "groq".to_string(),
+        "key-2".to_string(),
+    ));
+
+    let err = provider.chat_completion(request()).await.err().unwrap();
+    assert_eq!(
+        err.downcast_ref::<ProviderError>(),
+        Some(&ProviderError::RateLimited {
+            provider: "groq".to_string(),
+            retry_after: Some(Duration::from_secs(12)),
+        })
+    );
+
+    // The rate-limited key is skipped until its cooldown ends
+    assert!(provider.chat_completion(request()).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_groq_list_models_skips_audio_and_inactive_models() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "object": "list",
+            "data": [
+                {"id": "mixtral-8x7b-32768", "object": "model", "owned_by": "Mistral AI", "active": true, "context_window": 32768},
+                {"id": "llama-3.3-70b-versatile", "object": "model", "owned_by": "Meta", "active": true, "context_window": 131072},
+                {"id": "whisper-large-v3", "object": "model", "owned_by": "OpenAI", "active": true, "context_window": 448},
+                {"id": "llama2-70b-4096", "object": "model", "owned_by": "Meta", "active": false, "context_window": 4096}
+            ]
+        })))
+        .mount(&server)
+        .await;
+
+    let provider = GroqProvider::new("test-key".to_string(), Some(server.uri()), None);
+    let models = provider.list_models().await.unwrap();
+    let ids: Vec<&str> = models.iter().map(|model| model.id.as_str()).collect();
+    assert_eq!(ids, ["llama-3.3-70b-versatile", "mixtral-8x7b-32768"]);
+    assert_eq!(models[0].context_window, 131072);
+    assert_eq!(models[0].provider, "groq");
+    assert!(models[0].supports_tools);
+}
+
+#[tokio::test]
+async fn test_groq_api_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(404).set_body_string(
+            "{\"error\":{\"message\":\"The model `llama-9` does not exist\",\"type\":\"invalid_request_error\",\"code\":\"model_not_found\"}}",
+        ))
+        .mount(&server)
+        .await;
+
+    let provider = GroqProvider::new("test-key".to_string(), Some(server.uri()), None);
+    let err = provider.chat_completion(request()).await.err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "Groq API error (404): The model `llama-9` does not exist"
+    );
+}
@@ -57,6 +57,7 @@ impl MockProvider {
                         role: Some(Role::Assistant),
                         content: Some("Hello".to_string()),
                         tool_calls: None,
+                        reasoning: None,
                     },
                     finish_reason: None,
                     usage: None,
@@ -67,12 +68,15 @@ impl MockProvider {
                         role: None,
                         content: Some(" world!".to_string()),
                         tool_calls: None,
+                        reasoning: None,
                     },
                     finish_reason: Some(FinishReason::Stop),
                     usage: Some(TokenUsage {
                         prompt_tokens: 5,
                         completion_tokens: 3,
                         total_tokens: 8,
+                        cache_read_tokens: 0,
+                        cache_write_tokens: 0,
                     }),
                 }),
             ],
@@ -260,6 +264,7 @@ pub fn create_stream_with_chunks(chunks: Vec<&str>) -> Vec<Result<ChatCompletion
                     role: if i == 0 { Some(Role::Assistant) } else { None },
                     content: Some(content.to_string()),
                     tool_calls: None,
+                    reasoning: None,
                 },
                 finish_reason: if i == len - 1 {
                     Some(FinishReason::Stop)
@@ -271,6 +276,8 @@ pub fn create_stream_with_chunks(chunks: Vec<&str>) -> Vec<Result<ChatCompletion
                         prompt_tokens: 5,
                         completion_tokens: i as u32 + 1,
                         total_tokens: i as u32 + 6,
+                        cache_read_tokens: 0,
+                        cache_write_tokens: 0,
                     })
                 } else {
                     None
@@ -299,6 +306,7 @@ pub fn create_stream_with_failures(
                         role: if i == 0 { Some(Role::Assistant) } else { None },
                         content: Some(content.to_string()),
                         tool_calls: None,
+                        reasoning: None,
                     },
                     finish_reason: if i == len - 1 {
                         Some(FinishReason::Stop)
@@ -407,12 +415,15 @@ impl ModelProvider for ConfigurableMockProvider {
                     role: Some(Role::Assistant),
                     content: Some("Default response".to_string()),
                     tool_calls: None,
+                    reasoning: None,
                 },
                 finish_reason: Some(FinishReason::Stop),
                 usage: Some(TokenUsage {
                     prompt_tokens: 1,
                     completion_tokens: 2,
                     total_tokens: 3,
+                    cache_read_tokens: 0,
+                    cache_write_tokens: 0,
                 }),
             })]))
         }
@@ -521,6 +532,7 @@ pub fn create_tool_call_stream(
                     name: tool_name.to_string(),
                     arguments: tool_args.to_string(),
                 }]),
+                reasoning: None,
             },
             finish_reason: None,
             usage: None,
@@ -531,12 +543,15 @@ pub fn create_tool_call_stream(
                 role: None,
                 content: None,
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: Some(finish_reason),
             usage: Some(TokenUsage {
                 prompt_tokens: 10,
                 completion_tokens: 5,
                 total_tokens: 15,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             }),
         }),
     ]
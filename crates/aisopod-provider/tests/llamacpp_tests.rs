@@ -0,0 +1,177 @@
+//! Tests for llama.cpp server provider
+//!
+//! These tests run the provider against a local mock of `llama-server`.
+
+use aisopod_provider::providers::llamacpp::{LlamaCppEndpoint, LlamaCppProvider};
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{ChatCompletionRequest, FinishReason, Message, MessageContent, Role};
+use futures_util::StreamExt;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+fn request() -> ChatCompletionRequest {
+    ChatCompletionRequest {
+        model: "qwen2.5-7b-instruct".to_string(),
+        messages: vec![Message {
+            role: Role::User,
+            content: MessageContent::Text("Hello".to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+        }],
+        tools: None,
+        temperature: None,
+        max_tokens: Some(32),
+        stop: None,
+        stream: true,
+    }
+}
+
+#[tokio::test]
+async fn test_llamacpp_list_models_detects_context_window() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "object": "list",
+            "data": [{
+                "id": "/models/qwen2.5-7b-instruct-q4_k_m.gguf",
+                "object": "model",
+                "owned_by": "llamacpp",
+                "meta": {"n_ctx_train": 32768, "n_params": 7615616512u64}
+            }]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/props"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "default_generation_settings": {"n_ctx": 8192, "temperature": 0.8},
+            "total_slots": 1,
+            "modalities": {"vision": false},
+            "chat_template_caps": {"supports_tools": true}
+        })))
+        .mount(&server)
+        .await;
+
+    let provider = LlamaCppProvider::new(Some(server.uri()), None);
+    let models = provider.list_models().await.unwrap();
+
+    assert_eq!(models.len(), 1);
+    assert_eq!(models[0].id, "/models/qwen2.5-7b-instruct-q4_k_m.gguf");
+    assert_eq!(models[0].name, "qwen2.5-7b-instruct-q4_k_m");
+    assert_eq!(models[0].provider, "llamacpp");
+    assert_eq!(models[0].context_window, 8192);
+    assert!(models[0].supports_tools);
+    assert!(!models[0].supports_vision);
+}
+
+#[tokio::test]
+async fn test_llamacpp_list_models_falls_back_to_training_context() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{"id": "local", "meta": {"n_ctx_train": 32768}}]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/props"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let provider = LlamaCppProvider::new(Some(server.uri()), None);
+    let models = provider.list_models().await.unwrap();
+
+    assert_eq!(models[0].context_window, 32768);
+    assert!(!models[0].supports_tools);
+}
+
+#[tokio::test]
+async fn test_llamacpp_chat_sends_no_auth_by_default() {
+    let body = concat!(
+        "data: {\"id\":\"chatcmpl-1\",\"object\":\"chat.completion.chunk\",\"created\":1,\"model\":\"local\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"content\":\"Hi!\"},\"finish_reason\":null}]}\n\n",
+        "data: {\"id\":\"chatcmpl-1\",\"object\":\"chat.completion.chunk\",\"created\":1,\"model\":\"local\",\"choices\":[{\"index\":0,\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n",
+        "data: [DONE]\n\n",
+    );
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(|request: &Request| !request.headers.contains_key("authorization"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = LlamaCppProvider::new(Some(server.uri()), None);
+    let chunks: Vec<_> = provider
+        .chat_completion(request())
+        .await
+        .unwrap()
+        .map(|chunk| chunk.unwrap())
+        .collect()
+        .await;
+
+    assert_eq!(chunks[0].delta.content.as_deref(), Some("Hi!"));
+    assert_eq!(
+        chunks.last().unwrap().finish_reason,
+        Some(FinishReason::Stop)
+    );
+}
+
+#[tokio::test]
+async fn test_llamacpp_completion_endpoint() {
+    let body = concat!(
+        "data: {\"content\":\"Hi\",\"stop\":false,\"id_slot\":0}\n\n",
+        "data: {\"content\":\" there\",\"stop\":false,\"id_slot\":0}\n\n",
+        "data: {\"content\":\"\",\"stop\":true,\"stopped_eos\":false,\"stopped_word\":true,\"stopped_limit\":false,\"tokens_predicted\":3,\"tokens_evaluated\":6}\n\n",
+    );
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/completion"))
+        .and(body_partial_json(serde_json::json!({
+            "prompt": "User: Hello\nAssistant:",
+            "n_predict": 32,
+            "stop": ["\nUser:"]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider =
+        LlamaCppProvider::new(Some(server.uri()), None).with_endpoint(LlamaCppEndpoint::Completion);
+    let chunks: Vec<_> = provider
+        .chat_completion(request())
+        .await
+        .unwrap()
+        .map(|chunk| chunk.unwrap())
+        .collect()
+        .await;
+
+    let text: String = chunks
+        .iter()
+        .filter_map(|chunk| chunk.delta.content.clone())
+        .collect();
+    assert_eq!(text, "Hi there");
+    let last = chunks.last().unwrap();
+    assert_eq!(last.finish_reason, Some(FinishReason::Stop));
+    assert_eq!(last.usage.as_ref().unwrap().total_tokens, 9);
+}
+
+#[tokio::test]
+async fn test_llamacpp_error_message() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(503).set_body_json(serde_json::json!({
+            "error": {"code": 503, "message": "Loading model", "type": "unavailable_error"}
+        })))
+        .mount(&server)
+        .await;
+
+    let provider = LlamaCppProvider::new(Some(server.uri()), None);
+    let err = provider.chat_completion(request()).await.err().unwrap();
+    assert_eq!(err.to_string(), "llama.cpp error (503): Loading model");
+}
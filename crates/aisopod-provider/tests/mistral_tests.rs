@@ -0,0 +1,169 @@
+//! Tests for Mistral AI provider
+//!
+//! These tests run the provider against a local mock of the Mistral API.
+
+use aisopod_provider::providers::mistral::MistralProvider;
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{
+    ChatCompletionRequest, FinishReason, Message, MessageContent, Role, ToolDefinition,
+};
+use futures_util::StreamExt;
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn request(tools: Option<Vec<ToolDefinition>>) -> ChatCompletionRequest {
+    ChatCompletionRequest {
+        model: "mistral-large-latest".to_string(),
+        messages: vec![Message {
+            role: Role::User,
+            content: MessageContent::Text("What's the weather in Paris?".to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+        }],
+        tools,
+        temperature: None,
+        max_tokens: None,
+        stop: None,
+        stream: true,
+    }
+}
+
+fn provider(server: &MockServer) -> MistralProvider {
+    MistralProvider::new("test-key".to_string(), Some(server.uri()), None)
+}
+
+#[tokio::test]
+async fn test_mistral_streams_every_chunk() {
+    let body = concat!(
+        "data: {\"id\":\"cmpl-1\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"content\":\"\"},\"finish_reason\":null}]}\n\n",
+        "data: {\"id\":\"cmpl-1\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hello\"},\"finish_reason\":null}]}\n\n",
+        "data: {\"id\":\"cmpl-1\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\" there\"},\"finish_reason\":\"stop\"}],\"usage\":{\"prompt_tokens\":10,\"completion_tokens\":2,\"total_tokens\":12}}\n\n",
+        "data: [DONE]\n\n",
+    );
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(header("Authorization", "Bearer test-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let chunks: Vec<_> = provider(&server)
+        .chat_completion(request(None))
+        .await
+        .unwrap()
+        .map(|chunk| chunk.unwrap())
+        .collect()
+        .await;
+
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks[0].delta.role, Some(Role::Assistant));
+    let text: String = chunks
+        .iter()
+        .filter_map(|chunk| chunk.delta.content.as_deref())
+        .collect();
+    assert_eq!(text, "Hello there");
+    assert_eq!(chunks[2].finish_reason, Some(FinishReason::Stop));
+    assert_eq!(chunks[2].usage.as_ref().unwrap().total_tokens, 12);
+}
+
+#[tokio::test]
+async fn test_mistral_tool_calling() {
+    let body = concat!(
+        "data: {\"id\":\"cmpl-2\",\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"id\":\"D681PevKs\",\"function\":{\"name\":\"get_weather\",\"arguments\":\"{\\\"city\\\": \\\"Paris\\\"}\"}}]},\"finish_reason\":\"tool_calls\"}]}\n\n",
+        "data: [DONE]\n\n",
+    );
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(serde_json::json!({
+            "tool_choice": "auto",
+            "tools": [{"type": "function", "function": {"name": "get_weather"}}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let tools = vec![ToolDefinition {
+        name: "get_weather".to_string(),
+        description: "Get the current weather".to_string(),
+        parameters: serde_json::json!({
+            "type": "object",
+            "properties": {"city": {"type": "string"}},
+            "required": ["city"]
+        }),
+    }];
+    let mut stream = provider(&server)
+        .chat_completion(request(Some(tools)))
+        .await
+        .unwrap();
+
+    let chunk = stream.next().await.unwrap().unwrap();
+    let tool_calls = chunk.delta.tool_calls.unwrap();
+    assert_eq!(tool_calls[0].id, "D681PevKs");
+    assert_eq!(tool_calls[0].name, "get_weather");
+    assert_eq!(tool_calls[0].arguments, "{\"city\": \"Paris\"}");
+    assert_eq!(chunk.finish_reason, Some(FinishReason::ToolCall));
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_mistral_list_models_skips_non_chat_models() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .and(header("Authorization", "Bearer test-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "object": "list",
+            "data": [
+                {
+                    "id": "pixtral-large-latest",
+                    "name": "pixtral-large-2411",
+                    "max_context_length": 131072,
+                    "capabilities": {"completion_chat": true, "function_calling": true, "vision": true}
+                },
+                {
+                    "id": "mistral-embed",
+                    "max_context_length": 8192,
+                    "capabilities": {"completion_chat": false, "function_calling": false, "vision": false}
+                }
+            ]
+        })))
+        .mount(&server)
+        .await;
+
+    let models = provider(&server).list_models().await.unwrap();
+    assert_eq!(models.len(), 1);
+    assert_eq!(models[0].id, "pixtral-large-latest");
+    assert_eq!(models[0].name, "pixtral-large-2411");
+    assert_eq!(models[0].provider, "mistral");
+    assert_eq!(models[0].context_window, 131072);
+    assert!(models[0].supports_vision);
+    assert!(models[0].supports_tools);
+}
+
+#[tokio::test]
+async fn test_mistral_api_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "object": "error",
+            "message": "Invalid model: mistral-huge",
+            "type": "invalid_model"
+        })))
+        .mount(&server)
+        .await;
+
+    let err = provider(&server)
+        .chat_completion(request(None))
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.to_string(),
+        "Mistral API error (400): Invalid model: mistral-huge"
+    );
+}
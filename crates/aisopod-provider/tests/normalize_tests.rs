@@ -324,12 +324,15 @@ fn test_aggregate_usage_single() {
             role: Some(Role::Assistant),
             content: Some("test".to_string()),
             tool_calls: None,
+            reasoning: None,
         },
         finish_reason: Some(FinishReason::Stop),
         usage: Some(TokenUsage {
             prompt_tokens: 10,
             completion_tokens: 5,
             total_tokens: 15,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
         }),
     };
 
@@ -345,12 +348,15 @@ fn test_aggregate_usage_multiple() {
             role: Some(Role::Assistant),
             content: Some("test1".to_string()),
             tool_calls: None,
+            reasoning: None,
         },
         finish_reason: Some(FinishReason::Stop),
         usage: Some(TokenUsage {
             prompt_tokens: 10,
             completion_tokens: 5,
             total_tokens: 15,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
         }),
     };
 
@@ -360,12 +366,15 @@ fn test_aggregate_usage_multiple() {
             role: Some(Role::Assistant),
             content: Some("test2".to_string()),
             tool_calls: None,
+            reasoning: None,
         },
         finish_reason: Some(FinishReason::Stop),
         usage: Some(TokenUsage {
             prompt_tokens: 20,
             completion_tokens: 10,
             total_tokens: 30,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
         }),
     };
 
@@ -386,12 +395,15 @@ fn test_aggregate_usage_multiple_chunks() {
                 role: Some(Role::Assistant),
                 content: Some("A".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: None,
             usage: Some(TokenUsage {
                 prompt_tokens: 5,
                 completion_tokens: 1,
                 total_tokens: 6,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             }),
         },
         ChatCompletionChunk {
@@ -400,12 +412,15 @@ fn test_aggregate_usage_multiple_chunks() {
                 role: None,
                 content: Some("B".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: None,
             usage: Some(TokenUsage {
                 prompt_tokens: 5,
                 completion_tokens: 2,
                 total_tokens: 7,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             }),
         },
         ChatCompletionChunk {
@@ -414,12 +429,15 @@ fn test_aggregate_usage_multiple_chunks() {
                 role: None,
                 content: None,
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: Some(FinishReason::Stop),
             usage: Some(TokenUsage {
                 prompt_tokens: 5,
                 completion_tokens: 3,
                 total_tokens: 8,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             }),
         },
     ];
@@ -587,6 +605,7 @@ fn test_aggregate_usage_with_none() {
                 role: Some(Role::Assistant),
                 content: Some("A".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: None,
             usage: None, // No usage in this chunk
@@ -597,12 +616,15 @@ fn test_aggregate_usage_with_none() {
                 role: None,
                 content: Some("B".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: Some(FinishReason::Stop),
             usage: Some(TokenUsage {
                 prompt_tokens: 5,
                 completion_tokens: 3,
                 total_tokens: 8,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             }),
         },
     ];
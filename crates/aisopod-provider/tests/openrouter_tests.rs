@@ -0,0 +1,141 @@
+//! Tests for OpenRouter provider
+//!
+//! These tests run the provider against a local mock of the OpenRouter API.
+
+use aisopod_provider::providers::openrouter::{
+    ModelPricing, OpenRouterProvider, OpenRouterRouting,
+};
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{ChatCompletionRequest, Message, MessageContent, Role};
+use futures_util::StreamExt;
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn request() -> ChatCompletionRequest {
+    ChatCompletionRequest {
+        model: "anthropic/claude-3.5-sonnet".to_string(),
+        messages: vec![Message {
+            role: Role::User,
+            content: MessageContent::Text("Say hello!".to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+        }],
+        tools: None,
+        temperature: None,
+        max_tokens: None,
+        stop: None,
+        stream: true,
+    }
+}
+
+const SSE_BODY: &str = concat!(
+    ": OPENROUTER PROCESSING\n\n",
+    "data: {\"id\":\"gen-1\",\"object\":\"chat.completion.chunk\",\"created\":1,\"model\":\"anthropic/claude-3.5-sonnet\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"content\":\"Hello\"},\"finish_reason\":null}]}\n\n",
+    "data: [DONE]\n\n",
+);
+
+#[tokio::test]
+async fn test_openrouter_passes_routing_preferences() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(header("Authorization", "Bearer test-key"))
+        .and(body_partial_json(serde_json::json!({
+            "model": "anthropic/claude-3.5-sonnet",
+            "models": ["anthropic/claude-3.5-sonnet", "openai/gpt-4o"],
+            "provider": {"order": ["anthropic", "amazon-bedrock"], "allow_fallbacks": true}
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(SSE_BODY, "text/event-stream"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = OpenRouterProvider::new("test-key".to_string(), Some(server.uri()), None)
+        .with_routing(OpenRouterRouting {
+            order: vec!["anthropic".to_string(), "amazon-bedrock".to_string()],
+            allow_fallbacks: Some(true),
+            fallbacks: vec!["openai/gpt-4o".to_string()],
+        });
+
+    let mut stream = provider.chat_completion(request()).await.unwrap();
+    let chunk = stream.next().await.unwrap().unwrap();
+    assert_eq!(chunk.id, "gen-1");
+    assert_eq!(chunk.delta.content.as_deref(), Some("Hello"));
+}
+
+#[tokio::test]
+async fn test_openrouter_catalog_and_pricing() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [
+                {
+                    "id": "anthropic/claude-3.5-sonnet",
+                    "name": "Anthropic: Claude 3.5 Sonnet",
+                    "context_length": 200000,
+                    "architecture": {
+                        "modality": "text+image->text",
+                        "input_modalities": ["text", "image"],
+                        "output_modalities": ["text"]
+                    },
+                    "pricing": {"prompt": "0.000003", "completion": "0.000015", "request": "0", "image": "0.0048"},
+                    "supported_parameters": ["max_tokens", "temperature", "tools", "tool_choice"]
+                },
+                {
+                    "id": "meta-llama/llama-3-8b-instruct",
+                    "name": "Meta: Llama 3 8B Instruct",
+                    "context_length": 8192,
+                    "architecture": {"modality": "text->text"},
+                    "pricing": {"prompt": "0.00000003", "completion": "0.00000006"},
+                    "supported_parameters": ["max_tokens", "temperature"]
+                }
+            ]
+        })))
+        .mount(&server)
+        .await;
+
+    let provider = OpenRouterProvider::new("test-key".to_string(), Some(server.uri()), None);
+    let models = provider.list_models().await.unwrap();
+    assert_eq!(models.len(), 2);
+    assert_eq!(models[0].name, "Anthropic: Claude 3.5 Sonnet");
+    assert_eq!(models[0].provider, "openrouter");
+    assert_eq!(models[0].context_window, 200000);
+    assert!(models[0].supports_vision);
+    assert!(models[0].supports_tools);
+    assert!(!models[1].supports_vision);
+    assert!(!models[1].supports_tools);
+
+    assert_eq!(
+        provider.pricing("anthropic/claude-3.5-sonnet"),
+        Some(ModelPricing {
+            prompt: 0.000003,
+            completion: 0.000015,
+            request: 0.0,
+            image: 0.0048,
+            cache_read: None,
+            cache_write: None,
+        })
+    );
+    assert!(provider.pricing("openai/gpt-4o").is_none());
+}
+
+#[tokio::test]
+async fn test_openrouter_api_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(402)
+                .set_body_string("{\"error\":{\"code\":402,\"message\":\"Insufficient credits\"}}"),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = OpenRouterProvider::new("test-key".to_string(), Some(server.uri()), None);
+    let err = provider.chat_completion(request()).await.err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "OpenRouter API error (402): Insufficient credits"
+    );
+}
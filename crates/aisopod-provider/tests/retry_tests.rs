@@ -0,0 +1,156 @@
+//! Tests for rate-limit-aware retries
+//!
+//! These tests send requests through the shared retry layer against local
+//! mocks that rate limit or report overload.
+
+use std::time::Duration;
+
+use aisopod_provider::providers::anthropic::AnthropicProvider;
+use aisopod_provider::retry::{self, RetryPolicy};
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{ChatCompletionRequest, Message, MessageContent, Role};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn policy() -> RetryPolicy {
+    RetryPolicy {
+        initial_backoff: Duration::from_millis(10),
+        ..RetryPolicy::default()
+    }
+}
+
+fn rate_limited() -> ResponseTemplate {
+    ResponseTemplate::new(429).insert_header("retry-after-ms", "20")
+}
+
+#[tokio::test]
+async fn test_retries_after_requested_delay() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(rate_limited())
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let request = reqwest::Client::new().get(format!("{}/v1/models", server.uri()));
+    let response = retry::send(request, &policy()).await.unwrap();
+
+    assert_eq!(response.status(), 200);
+}
+
+#[tokio::test]
+async fn test_returns_last_response_when_retries_are_exhausted() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(rate_limited())
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let request = reqwest::Client::new().get(server.uri());
+    let response = retry::send(request, &policy()).await.unwrap();
+
+    assert_eq!(response.status(), 429);
+    assert_eq!(
+        retry::retry_after(response.headers()),
+        Some(Duration::from_millis(20))
+    );
+}
+
+#[tokio::test]
+async fn test_long_retry_after_is_returned_without_waiting() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "60"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let request = reqwest::Client::new().get(server.uri());
+    let response = retry::send(request, &policy()).await.unwrap();
+
+    assert_eq!(response.status(), 429);
+}
+
+#[tokio::test]
+async fn test_budget_caps_total_wait() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(rate_limited())
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let policy = RetryPolicy {
+        max_retries: 5,
+        budget: Duration::from_millis(30),
+        ..policy()
+    };
+    let request = reqwest::Client::new().get(server.uri());
+    let response = retry::send(request, &policy).await.unwrap();
+
+    assert_eq!(response.status(), 429);
+}
+
+#[tokio::test]
+async fn test_client_errors_are_not_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(400))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let request = reqwest::Client::new().get(server.uri());
+    let response = retry::send(request, &policy()).await.unwrap();
+
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_anthropic_retries_overloaded_request() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .respond_with(ResponseTemplate::new(529).set_body_json(serde_json::json!({
+            "type": "error",
+            "error": {"type": "overloaded_error", "message": "Overloaded"}
+        })))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw("", "text/event-stream"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = AnthropicProvider::new("test-key".to_string(), Some(server.uri()), None, None)
+        .with_retry_policy(policy());
+    let request = ChatCompletionRequest {
+        model: "claude-3-5-sonnet-latest".to_string(),
+        messages: vec![Message {
+            role: Role::User,
+            content: MessageContent::Text("Hello".to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+        }],
+        tools: None,
+        temperature: None,
+        max_tokens: None,
+        stop: None,
+        stream: true,
+    };
+
+    assert!(provider.chat_completion(request).await.is_ok());
+}
@@ -28,6 +28,7 @@ async fn test_stream_chunks_arrive_in_order() {
                 role: Some(Role::Assistant),
                 content: Some("Hello".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: None,
             usage: None,
@@ -38,6 +39,7 @@ async fn test_stream_chunks_arrive_in_order() {
                 role: None,
                 content: Some(" world".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: None,
             usage: None,
@@ -48,12 +50,15 @@ async fn test_stream_chunks_arrive_in_order() {
                 role: None,
                 content: Some("!".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: Some(FinishReason::Stop),
             usage: Some(TokenUsage {
                 prompt_tokens: 5,
                 completion_tokens: 3,
                 total_tokens: 8,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             }),
         }),
     ];
@@ -83,6 +88,7 @@ async fn test_stream_finish_reason_on_last_chunk() {
                 role: Some(Role::Assistant),
                 content: Some("Response".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: None,
             usage: None,
@@ -93,12 +99,15 @@ async fn test_stream_finish_reason_on_last_chunk() {
                 role: None,
                 content: None,
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: Some(FinishReason::Stop),
             usage: Some(TokenUsage {
                 prompt_tokens: 10,
                 completion_tokens: 5,
                 total_tokens: 15,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             }),
         }),
     ];
@@ -131,6 +140,7 @@ async fn test_stream_terminates_correctly() {
                 role: Some(Role::Assistant),
                 content: Some("A".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: None,
             usage: None,
@@ -141,12 +151,15 @@ async fn test_stream_terminates_correctly() {
                 role: None,
                 content: Some("B".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: Some(FinishReason::Length),
             usage: Some(TokenUsage {
                 prompt_tokens: 2,
                 completion_tokens: 2,
                 total_tokens: 4,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             }),
         }),
     ];
@@ -182,6 +195,7 @@ async fn test_stream_with_tool_call_finish_reason() {
                     name: "calculator".to_string(),
                     arguments: "{\"op\":\"add\"}".to_string(),
                 }]),
+                reasoning: None,
             },
             finish_reason: None,
             usage: None,
@@ -192,12 +206,15 @@ async fn test_stream_with_tool_call_finish_reason() {
                 role: None,
                 content: None,
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: Some(FinishReason::ToolCall),
             usage: Some(TokenUsage {
                 prompt_tokens: 15,
                 completion_tokens: 5,
                 total_tokens: 20,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             }),
         }),
     ];
@@ -248,12 +265,15 @@ async fn test_stream_with_single_chunk() {
             role: Some(Role::Assistant),
             content: Some("Only chunk".to_string()),
             tool_calls: None,
+            reasoning: None,
         },
         finish_reason: Some(FinishReason::Stop),
         usage: Some(TokenUsage {
             prompt_tokens: 3,
             completion_tokens: 3,
             total_tokens: 6,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
         }),
     })];
 
@@ -283,6 +303,7 @@ async fn test_stream_with_partial_failures() {
                 role: Some(Role::Assistant),
                 content: Some("Part1".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: None,
             usage: None,
@@ -341,6 +362,7 @@ async fn test_stream_with_delays() {
                 role: Some(Role::Assistant),
                 content: Some("A".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: None,
             usage: None,
@@ -351,6 +373,7 @@ async fn test_stream_with_delays() {
                 role: None,
                 content: Some("B".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: None,
             usage: None,
@@ -361,12 +384,15 @@ async fn test_stream_with_delays() {
                 role: None,
                 content: Some("C".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: Some(FinishReason::Stop),
             usage: Some(TokenUsage {
                 prompt_tokens: 3,
                 completion_tokens: 3,
                 total_tokens: 6,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             }),
         }),
     ];
@@ -405,12 +431,15 @@ async fn test_usage_accumulates_across_chunks() {
                 role: Some(Role::Assistant),
                 content: Some("A".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: None,
             usage: Some(TokenUsage {
                 prompt_tokens: 5,
                 completion_tokens: 1,
                 total_tokens: 6,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             }),
         }),
         Ok(ChatCompletionChunk {
@@ -419,12 +448,15 @@ async fn test_usage_accumulates_across_chunks() {
                 role: None,
                 content: Some("B".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: Some(FinishReason::Stop),
             usage: Some(TokenUsage {
                 prompt_tokens: 5,
                 completion_tokens: 2,
                 total_tokens: 7,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
             }),
         }),
     ];
@@ -457,6 +489,7 @@ async fn test_chunk_ids_are_unique() {
                 role: Some(Role::Assistant),
                 content: Some("A".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: None,
             usage: None,
@@ -467,6 +500,7 @@ async fn test_chunk_ids_are_unique() {
                 role: None,
                 content: Some("B".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: None,
             usage: None,
@@ -477,6 +511,7 @@ async fn test_chunk_ids_are_unique() {
                 role: None,
                 content: Some("C".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: Some(FinishReason::Stop),
             usage: None,
@@ -512,6 +547,7 @@ async fn test_role_delta_on_first_chunk() {
             role: Some(Role::Assistant),
             content: Some("Response".to_string()),
             tool_calls: None,
+            reasoning: None,
         },
         finish_reason: None,
         usage: None,
@@ -537,6 +573,7 @@ async fn test_role_delta_not_repeated() {
                 role: Some(Role::Assistant),
                 content: Some("Start".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: None,
             usage: None,
@@ -547,6 +584,7 @@ async fn test_role_delta_not_repeated() {
                 role: None,
                 content: Some(" more".to_string()),
                 tool_calls: None,
+                reasoning: None,
             },
             finish_reason: None,
             usage: None,
@@ -15,10 +15,18 @@ use aisopod_provider::types::*;
 
 // Re-export providers for testing
 use aisopod_provider::providers::anthropic::{api_types as anthropic_api, AnthropicProvider};
+use aisopod_provider::providers::azure_openai::{AzureAuth, AzureOpenAIProvider};
 use aisopod_provider::providers::bedrock::{api_types as bedrock_api, BedrockProvider};
+use aisopod_provider::providers::deepseek::DeepSeekProvider;
 use aisopod_provider::providers::gemini::{api_types as gemini_api, GeminiProvider};
+use aisopod_provider::providers::groq::GroqProvider;
+use aisopod_provider::providers::llamacpp::LlamaCppProvider;
+use aisopod_provider::providers::mistral::MistralProvider;
 use aisopod_provider::providers::ollama::{api_types as ollama_api, OllamaProvider};
 use aisopod_provider::providers::openai::{api_types as openai_api, OpenAIProvider};
+use aisopod_provider::providers::openrouter::OpenRouterProvider;
+use aisopod_provider::providers::vertex::{api_types as vertex_api, VertexAIProvider};
+use aisopod_provider::providers::xai::XaiProvider;
 
 // Mock helper
 use aisopod_provider::helpers::{create_test_model, create_test_request, MockProvider};
@@ -84,6 +92,196 @@ async fn test_openai_provider_list_models() {
     assert!(result.is_err()); // Expected to fail without real API
 }
 
+// ============================================================================
+// Azure OpenAI Provider Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_azure_openai_provider_id() {
+    let provider = AzureOpenAIProvider::new(
+        "https://my-resource.openai.azure.com".to_string(),
+        AzureAuth::ApiKey("test-key".to_string()),
+        None,
+    );
+    assert_eq!(provider.id(), "azure-openai");
+    assert_eq!(provider.api_version(), "2024-06-01");
+}
+
+#[tokio::test]
+async fn test_azure_openai_provider_health_check() {
+    let provider = AzureOpenAIProvider::new(
+        "http://127.0.0.1:1".to_string(),
+        AzureAuth::ApiKey("test-key".to_string()),
+        None,
+    );
+    let health = provider.health_check().await.unwrap();
+    assert!(!health.available);
+}
+
+// ============================================================================
+// Mistral Provider Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_mistral_provider_id() {
+    let provider = MistralProvider::new("test-key".to_string(), None, None);
+    assert_eq!(provider.id(), "mistral");
+    assert_eq!(provider.base_url, "https://api.mistral.ai");
+}
+
+#[tokio::test]
+async fn test_mistral_provider_health_check() {
+    let provider = MistralProvider::new(
+        "test-key".to_string(),
+        Some("http://127.0.0.1:1".to_string()),
+        None,
+    );
+    let health = provider.health_check().await.unwrap();
+    assert!(!health.available);
+}
+
+// ============================================================================
+// Groq Provider Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_groq_provider_id() {
+    let provider = GroqProvider::new("test-key".to_string(), None, None);
+    assert_eq!(provider.id(), "groq");
+    assert_eq!(provider.base_url, "https://api.groq.com/openai");
+    assert!(provider.rate_limits().is_none());
+}
+
+#[tokio::test]
+async fn test_groq_provider_health_check() {
+    let provider = GroqProvider::new(
+        "test-key".to_string(),
+        Some("http://127.0.0.1:1".to_string()),
+        None,
+    );
+    let health = provider.health_check().await.unwrap();
+    assert!(!health.available);
+}
+
+// ============================================================================
+// OpenRouter Provider Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_openrouter_provider_id() {
+    let provider = OpenRouterProvider::new("test-key".to_string(), None, None);
+    assert_eq!(provider.id(), "openrouter");
+    assert_eq!(provider.base_url, "https://openrouter.ai/api");
+    assert!(provider.pricing("openai/gpt-4o").is_none());
+}
+
+#[tokio::test]
+async fn test_openrouter_provider_health_check() {
+    let provider = OpenRouterProvider::new(
+        "test-key".to_string(),
+        Some("http://127.0.0.1:1".to_string()),
+        None,
+    );
+    let health = provider.health_check().await.unwrap();
+    assert!(!health.available);
+}
+
+// ============================================================================
+// xAI Provider Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_xai_provider_id() {
+    let provider = XaiProvider::new("test-key".to_string(), None, None);
+    assert_eq!(provider.id(), "xai");
+    assert_eq!(provider.base_url, "https://api.x.ai");
+}
+
+#[tokio::test]
+async fn test_xai_provider_health_check() {
+    let provider = XaiProvider::new(
+        "test-key".to_string(),
+        Some("http://127.0.0.1:1".to_string()),
+        None,
+    );
+    let health = provider.health_check().await.unwrap();
+    assert!(!health.available);
+}
+
+// ============================================================================
+// DeepSeek Provider Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_deepseek_provider_id() {
+    let provider = DeepSeekProvider::new("test-key".to_string(), None, None);
+    assert_eq!(provider.id(), "deepseek");
+    assert_eq!(provider.base_url, "https://api.deepseek.com");
+}
+
+#[tokio::test]
+async fn test_deepseek_provider_health_check() {
+    let provider = DeepSeekProvider::new(
+        "test-key".to_string(),
+        Some("http://127.0.0.1:1".to_string()),
+        None,
+    );
+    let health = provider.health_check().await.unwrap();
+    assert!(!health.available);
+}
+
+// ============================================================================
+// llama.cpp Provider Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_llamacpp_provider_id() {
+    let provider = LlamaCppProvider::new(None, None);
+    assert_eq!(provider.id(), "llamacpp");
+    assert_eq!(provider.base_url, "http://localhost:8080");
+}
+
+#[tokio::test]
+async fn test_llamacpp_provider_health_check() {
+    let provider = LlamaCppProvider::new(Some("http://127.0.0.1:1".to_string()), None);
+    let health = provider.health_check().await.unwrap();
+    assert!(!health.available);
+}
+
+// ============================================================================
+// Vertex AI Provider Tests
+// ============================================================================
+
+fn vertex_key() -> vertex_api::ServiceAccountKey {
+    serde_json::from_value(serde_json::json!({
+        "type": "service_account",
+        "project_id": "my-project",
+        "client_email": "aisopod@my-project.iam.gserviceaccount.com",
+        "private_key": "not a key"
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_vertex_provider_id() {
+    let provider = VertexAIProvider::new(vertex_key(), None);
+    assert_eq!(provider.id(), "vertex-ai");
+    assert_eq!(provider.location, "us-central1");
+    assert_eq!(
+        provider.base_url,
+        "https://us-central1-aiplatform.googleapis.com"
+    );
+    assert_eq!(provider.project_id(), "my-project");
+}
+
+#[tokio::test]
+async fn test_vertex_provider_health_check() {
+    // An invalid private key fails before any request is made
+    let provider = VertexAIProvider::new(vertex_key(), None);
+    let health = provider.health_check().await.unwrap();
+    assert!(!health.available);
+}
+
 // ============================================================================
 // Gemini Provider Tests
 // ============================================================================
@@ -278,6 +476,7 @@ fn test_anthropic_tool_call_roundtrip() {
             "type": "object",
             "properties": {}
         }),
+        cache_control: None,
     };
 
     let json = serde_json::to_string(&tool).unwrap();
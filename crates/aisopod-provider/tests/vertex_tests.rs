@@ -0,0 +1,167 @@
+//! Tests for Vertex AI provider
+//!
+//! These tests run the provider against a local mock of the Vertex AI API and
+//! the OAuth 2.0 token endpoint.
+
+use aisopod_provider::providers::vertex::{api_types::ServiceAccountKey, VertexAIProvider};
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{ChatCompletionRequest, FinishReason, Message, MessageContent, Role};
+use futures_util::StreamExt;
+use wiremock::matchers::{body_string_contains, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const MODEL_PATH: &str = "/v1/projects/my-project/locations/europe-west4/publishers/google/models/gemini-2.5-flash:streamGenerateContent";
+
+fn key(server: &MockServer) -> ServiceAccountKey {
+    serde_json::from_value(serde_json::json!({
+        "type": "service_account",
+        "project_id": "my-project",
+        "private_key_id": "key-1",
+        "private_key": include_str!("fixtures/vertex_test_key.pem"),
+        "client_email": "aisopod@my-project.iam.gserviceaccount.com",
+        "token_uri": format!("{}/token", server.uri()),
+    }))
+    .unwrap()
+}
+
+fn provider(server: &MockServer) -> VertexAIProvider {
+    VertexAIProvider::new(key(server), Some("europe-west4".to_string())).with_base_url(server.uri())
+}
+
+fn request() -> ChatCompletionRequest {
+    ChatCompletionRequest {
+        model: "gemini-2.5-flash".to_string(),
+        messages: vec![Message {
+            role: Role::User,
+            content: MessageContent::Text("Hello".to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+        }],
+        tools: None,
+        temperature: None,
+        max_tokens: None,
+        stop: None,
+        stream: true,
+    }
+}
+
+async fn mount_token(server: &MockServer, expected_exchanges: u64) {
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .and(body_string_contains(
+            "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Ajwt-bearer",
+        ))
+        .and(body_string_contains("assertion=ey"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "access_token": "ya29.test-token",
+            "expires_in": 3599,
+            "token_type": "Bearer"
+        })))
+        .expect(expected_exchanges)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_vertex_streams_with_service_account_token() {
+    let body = concat!(
+        "data: {\"candidates\": [{\"content\": {\"role\": \"model\",\"parts\": [{\"text\": \"Hello\"}]}}],\"usageMetadata\": {\"promptTokenCount\": 2},\"modelVersion\": \"gemini-2.5-flash\"}\r\n\r\n",
+        "data: {\"candidates\": [{\"content\": {\"role\": \"model\",\"parts\": [{\"text\": \" there!\"}]},\"finishReason\": \"STOP\"}],\"usageMetadata\": {\"promptTokenCount\": 2,\"candidatesTokenCount\": 3,\"totalTokenCount\": 5},\"modelVersion\": \"gemini-2.5-flash\"}\r\n\r\n",
+    );
+    let server = MockServer::start().await;
+    mount_token(&server, 1).await;
+    Mock::given(method("POST"))
+        .and(path(MODEL_PATH))
+        .and(query_param("alt", "sse"))
+        .and(header("Authorization", "Bearer ya29.test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let provider = provider(&server);
+    let chunks: Vec<_> = provider
+        .chat_completion(request())
+        .await
+        .unwrap()
+        .map(|chunk| chunk.unwrap())
+        .collect()
+        .await;
+
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].delta.content.as_deref(), Some("Hello"));
+    assert_eq!(chunks[0].usage, None);
+    assert_eq!(chunks[1].delta.content.as_deref(), Some(" there!"));
+    assert_eq!(chunks[1].finish_reason, Some(FinishReason::Stop));
+    assert_eq!(chunks[1].usage.as_ref().unwrap().total_tokens, 5);
+
+    // The cached token is reused
+    let _ = provider.chat_completion(request()).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_vertex_token_exchange_failure() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": "invalid_grant",
+            "error_description": "Invalid JWT Signature."
+        })))
+        .mount(&server)
+        .await;
+
+    let err = provider(&server)
+        .chat_completion(request())
+        .await
+        .err()
+        .unwrap();
+    assert!(err
+        .to_string()
+        .starts_with("Vertex AI token exchange failed (400)"));
+}
+
+#[tokio::test]
+async fn test_vertex_error_message() {
+    let server = MockServer::start().await;
+    mount_token(&server, 1).await;
+    Mock::given(method("POST"))
+        .and(path(MODEL_PATH))
+        .respond_with(ResponseTemplate::new(403).set_body_json(serde_json::json!({
+            "error": {
+                "code": 403,
+                "message": "Permission 'aiplatform.endpoints.predict' denied",
+                "status": "PERMISSION_DENIED"
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    let err = provider(&server)
+        .chat_completion(request())
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.to_string(),
+        "Vertex AI API error (403): Permission 'aiplatform.endpoints.predict' denied"
+    );
+}
+
+#[tokio::test]
+async fn test_vertex_health_check() {
+    let server = MockServer::start().await;
+    mount_token(&server, 1).await;
+    Mock::given(method("GET"))
+        .and(path("/v1/projects/my-project/locations/europe-west4"))
+        .and(header("Authorization", "Bearer ya29.test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "name": "projects/my-project/locations/europe-west4",
+            "locationId": "europe-west4"
+        })))
+        .mount(&server)
+        .await;
+
+    let health = provider(&server).health_check().await.unwrap();
+    assert!(health.available);
+}
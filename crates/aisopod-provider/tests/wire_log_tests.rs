@@ -0,0 +1,67 @@
+//! Tests for wire-level logging
+//!
+//! The logger is installed process-wide, so this binary holds a single test
+//! that sends a request through the retry layer against a local mock.
+
+use aisopod_config::Sensitive;
+use aisopod_provider::retry::{self, RetryPolicy};
+use aisopod_provider::wire_log::{self, WireLogConfig, WireLogger};
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_logs_redacted_request_and_response() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "chatcmpl-1",
+            "echo": "sk-test-key",
+            "choices": []
+        })))
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let logger = WireLogger::new(WireLogConfig::new(dir.path()))
+        .unwrap()
+        .with_secret(Sensitive::new("sk-test-key".to_string()));
+    let log_path = logger.path();
+    wire_log::install(logger);
+
+    let request = reqwest::Client::new()
+        .post(format!("{}/v1/chat/completions", server.uri()))
+        .header("Authorization", "Bearer sk-test-key")
+        .json(&json!({"model": "gpt-4o", "api_key": "inline"}));
+    let response = retry::send(request, &RetryPolicy::none()).await.unwrap();
+    let body: Value = response.json().await.unwrap();
+    wire_log::uninstall();
+
+    // The caller sees the unredacted response
+    assert_eq!(body["echo"], "sk-test-key");
+
+    let log = std::fs::read_to_string(log_path).unwrap();
+    assert!(!log.contains("sk-test-key"), "{}", log);
+    assert!(!log.contains("inline"), "{}", log);
+
+    let entries: Vec<Value> = log
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(entries.len(), 2);
+
+    let request = &entries[0];
+    assert_eq!(request["kind"], "request");
+    assert_eq!(request["method"], "POST");
+    assert_eq!(request["headers"]["authorization"], "***REDACTED***");
+    assert_eq!(request["body"]["model"], "gpt-4o");
+    assert_eq!(request["body"]["api_key"], "***REDACTED***");
+
+    let response = &entries[1];
+    assert_eq!(response["kind"], "response");
+    assert_eq!(response["id"], request["id"]);
+    assert_eq!(response["status"], 200);
+    assert_eq!(response["body"]["id"], "chatcmpl-1");
+    assert_eq!(response["body"]["echo"], "***REDACTED***");
+}
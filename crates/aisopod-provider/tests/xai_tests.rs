@@ -0,0 +1,144 @@
+//! Tests for xAI provider
+//!
+//! These tests run the provider against a local mock of the Grok API.
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use aisopod_provider::discovery::ModelCatalog;
+use aisopod_provider::providers::xai::XaiProvider;
+use aisopod_provider::registry::ProviderRegistry;
+use aisopod_provider::trait_module::ModelProvider;
+use aisopod_provider::types::{
+    ChatCompletionRequest, FinishReason, Message, MessageContent, Role, ToolDefinition,
+};
+use futures_util::StreamExt;
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn request() -> ChatCompletionRequest {
+    ChatCompletionRequest {
+        model: "grok-3".to_string(),
+        messages: vec![Message {
+            role: Role::User,
+            content: MessageContent::Text("What's the weather in Paris?".to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+        }],
+        tools: Some(vec![ToolDefinition {
+            name: "get_weather".to_string(),
+            description: "Get the current weather".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {"city": {"type": "string"}}
+            }),
+        }]),
+        temperature: None,
+        max_tokens: None,
+        stop: None,
+        stream: true,
+    }
+}
+
+fn mock_language_models() -> Mock {
+    Mock::given(method("GET"))
+        .and(path("/v1/language-models"))
+        .and(header("Authorization", "Bearer test-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "models": [
+                {
+                    "id": "grok-3",
+                    "input_modalities": ["text"],
+                    "output_modalities": ["text"],
+                    "aliases": ["grok-3-latest"]
+                },
+                {
+                    "id": "grok-2-vision-1212",
+                    "input_modalities": ["text", "image"],
+                    "output_modalities": ["text"],
+                    "aliases": ["grok-2-vision"]
+                }
+            ]
+        })))
+}
+
+#[tokio::test]
+async fn test_xai_streams_tool_call() {
+    let body = concat!(
+        "data: {\"id\":\"cmpl-1\",\"object\":\"chat.completion.chunk\",\"created\":1,\"model\":\"grok-3\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"tool_calls\":[{\"id\":\"call_1\",\"type\":\"function\",\"function\":{\"name\":\"get_weather\",\"arguments\":\"{\\\"city\\\":\\\"Paris\\\"}\"}}]},\"finish_reason\":\"tool_calls\"}]}\n\n",
+        "data: [DONE]\n\n",
+    );
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(header("Authorization", "Bearer test-key"))
+        .and(body_partial_json(serde_json::json!({
+            "model": "grok-3",
+            "stream": true,
+            "tools": [{"type": "function", "function": {"name": "get_weather"}}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = XaiProvider::new("test-key".to_string(), Some(server.uri()), None);
+    let mut stream = provider.chat_completion(request()).await.unwrap();
+
+    let chunk = stream.next().await.unwrap().unwrap();
+    let tool_calls = chunk.delta.tool_calls.unwrap();
+    assert_eq!(tool_calls[0].id, "call_1");
+    assert_eq!(tool_calls[0].name, "get_weather");
+    assert_eq!(tool_calls[0].arguments, "{\"city\":\"Paris\"}");
+    assert_eq!(chunk.finish_reason, Some(FinishReason::ToolCall));
+}
+
+#[tokio::test]
+async fn test_xai_models_in_catalog() {
+    let server = MockServer::start().await;
+    mock_language_models().mount(&server).await;
+
+    let registry = Arc::new(RwLock::new(ProviderRegistry::new()));
+    registry
+        .write()
+        .unwrap()
+        .register(Arc::new(XaiProvider::new(
+            "test-key".to_string(),
+            Some(server.uri()),
+            None,
+        )));
+    let catalog = ModelCatalog::new(registry, Duration::from_secs(60));
+
+    let grok = catalog.get_model("grok-3").await.unwrap().unwrap();
+    assert_eq!(grok.provider, "xai");
+    assert_eq!(grok.context_window, 131072);
+    assert!(grok.supports_tools);
+    assert!(!grok.supports_vision);
+
+    let vision = catalog
+        .find_by_capability(Some(true), None, None)
+        .await
+        .unwrap();
+    assert_eq!(vision.len(), 1);
+    assert_eq!(vision[0].id, "grok-2-vision-1212");
+}
+
+#[tokio::test]
+async fn test_xai_api_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "code": "Some requested entity was not found",
+            "error": "The model grok-9 does not exist or your team does not have access to it."
+        })))
+        .mount(&server)
+        .await;
+
+    let provider = XaiProvider::new("test-key".to_string(), Some(server.uri()), None);
+    let err = provider.chat_completion(request()).await.err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "xAI API error (404): The model grok-9 does not exist or your team does not have access to it."
+    );
+}
@@ -0,0 +1,183 @@
+//! Session storage backends.
+//!
+//! [`SessionBackend`] is the interface shared by the session stores: the
+//! SQLite [`SessionStore`] used by a single process, and, behind the
+//! `postgres` feature, the `PostgresSessionStore` that several gateway
+//! instances can share.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::time::Duration;
+
+use crate::lease::SessionLease;
+use crate::store::SessionStore;
+use crate::types::{
+    HistoryQuery, Session, SessionFilter, SessionKey, SessionPatch, SessionSummary, StoredMessage,
+};
+
+/// Persistent storage of sessions and their message history.
+///
+/// Operations taking an `agent_id` are agent-scoped: they fail when the
+/// calling agent does not own the session.
+#[async_trait]
+pub trait SessionBackend: Send + Sync {
+    /// Gets an existing session or creates a new one if it doesn't exist.
+    async fn get_or_create(&self, agent_id: &str, key: &SessionKey) -> Result<Session>;
+
+    /// Retrieves a session by its key.
+    async fn get(&self, key: &SessionKey) -> Result<Option<Session>>;
+
+    /// Lists the calling agent's sessions matching the filter, most recently
+    /// updated first and paged by the filter's limit and offset.
+    async fn list(&self, agent_id: &str, filter: &SessionFilter) -> Result<Vec<SessionSummary>>;
+
+    /// Lists sessions of all agents matching the filter. For trusted admin
+    /// and monitoring code only.
+    async fn list_all_sessions(&self, filter: &SessionFilter) -> Result<Vec<SessionSummary>>;
+
+    /// Partially updates a session and returns the updated session.
+    async fn patch(
+        &self,
+        agent_id: &str,
+        key: &SessionKey,
+        patch: &SessionPatch,
+    ) -> Result<Session>;
+
+    /// Deletes a session and its messages, returning whether it existed.
+    async fn delete(&self, agent_id: &str, key: &SessionKey) -> Result<bool>;
+
+    /// Appends messages to a session.
+    async fn append_messages(
+        &self,
+        agent_id: &str,
+        key: &SessionKey,
+        messages: &[StoredMessage],
+    ) -> Result<()>;
+
+    /// Retrieves message history for a session in chronological order.
+    async fn get_history(
+        &self,
+        agent_id: &str,
+        key: &SessionKey,
+        query: &HistoryQuery,
+    ) -> Result<Vec<StoredMessage>>;
+
+    /// Acquires the lease on a session for `owner`, taking over an expired
+    /// lease of another owner, or renews it if `owner` already holds it.
+    /// Returns `None` if another owner holds an unexpired lease.
+    async fn try_acquire_lease(
+        &self,
+        session_key: &str,
+        owner: &str,
+        ttl: Duration,
+    ) -> Result<Option<SessionLease>>;
+
+    /// Extends a lease held by `owner` by `ttl` from now, returning whether
+    /// `owner` still held it.
+    async fn renew_lease(&self, session_key: &str, owner: &str, ttl: Duration) -> Result<bool>;
+
+    /// Releases a lease held by `owner`, returning whether it held it.
+    async fn release_lease(&self, session_key: &str, owner: &str) -> Result<bool>;
+}
+
+#[async_trait]
+impl SessionBackend for SessionStore {
+    async fn get_or_create(&self, agent_id: &str, key: &SessionKey) -> Result<Session> {
+        SessionStore::get_or_create(self, agent_id, key)
+    }
+
+    async fn get(&self, key: &SessionKey) -> Result<Option<Session>> {
+        SessionStore::get(self, key)
+    }
+
+    async fn list(&self, agent_id: &str, filter: &SessionFilter) -> Result<Vec<SessionSummary>> {
+        SessionStore::list(self, agent_id, filter)
+    }
+
+    async fn list_all_sessions(&self, filter: &SessionFilter) -> Result<Vec<SessionSummary>> {
+        SessionStore::list_all_sessions(self, filter)
+    }
+
+    async fn patch(
+        &self,
+        agent_id: &str,
+        key: &SessionKey,
+        patch: &SessionPatch,
+    ) -> Result<Session> {
+        SessionStore::patch(self, agent_id, key, patch)
+    }
+
+    async fn delete(&self, agent_id: &str, key: &SessionKey) -> Result<bool> {
+        SessionStore::delete(self, agent_id, key)
+    }
+
+    async fn append_messages(
+        &self,
+        agent_id: &str,
+        key: &SessionKey,
+        messages: &[StoredMessage],
+    ) -> Result<()> {
+        SessionStore::append_messages(self, agent_id, key, messages)
+    }
+
+    async fn get_history(
+        &self,
+        agent_id: &str,
+        key: &SessionKey,
+        query: &HistoryQuery,
+    ) -> Result<Vec<StoredMessage>> {
+        SessionStore::get_history(self, agent_id, key, query)
+    }
+
+    async fn try_acquire_lease(
+        &self,
+        session_key: &str,
+        owner: &str,
+        ttl: Duration,
+    ) -> Result<Option<SessionLease>> {
+        SessionStore::try_acquire_lease(self, session_key, owner, ttl)
+    }
+
+    async fn renew_lease(&self, session_key: &str, owner: &str, ttl: Duration) -> Result<bool> {
+        SessionStore::renew_lease(self, session_key, owner, ttl)
+    }
+
+    async fn release_lease(&self, session_key: &str, owner: &str) -> Result<bool> {
+        SessionStore::release_lease(self, session_key, owner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_sqlite_store_as_backend() {
+        let backend: Arc<dyn SessionBackend> = Arc::new(SessionStore::new_in_memory().unwrap());
+        let key = SessionKey {
+            agent_id: "agent_001".to_string(),
+            channel: "discord".to_string(),
+            account_id: "bot_123".to_string(),
+            peer_kind: "dm".to_string(),
+            peer_id: "user_456".to_string(),
+        };
+
+        backend.get_or_create("agent_001", &key).await.unwrap();
+        backend
+            .append_messages("agent_001", &key, &[StoredMessage::user("Hello")])
+            .await
+            .unwrap();
+
+        let history = backend
+            .get_history("agent_001", &key, &HistoryQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        let session = backend.get(&key).await.unwrap().unwrap();
+        assert_eq!(session.message_count, 1);
+
+        assert!(backend.delete("agent_001", &key).await.unwrap());
+        assert!(backend.get(&key).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,597 @@
+//! Hot session cache.
+//!
+//! [`CachedSessionBackend`] is a write-through cache in front of a durable
+//! [`SessionBackend`]. Reads of active sessions (the session record and its
+//! message history) are served from a [`SessionCache`], such as Redis behind
+//! the `redis` feature, and every write goes to the durable store first and
+//! then refreshes the cached entries.
+//!
+//! The history of a session is cached as a whole while it holds at most
+//! `max_messages` messages; longer histories are read from the durable store.
+//! Writes that bypass the cached backend, such as compaction, are picked up
+//! once the entries expire or after [`CachedSessionBackend::invalidate`].
+//!
+//! When the durable store encrypts messages, give the cache the same
+//! [`MessageCipher`] so that cached entries are encrypted too.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::backend::SessionBackend;
+use crate::encryption::{self, MessageCipher};
+use crate::lease::SessionLease;
+use crate::types::{
+    HistoryQuery, Session, SessionFilter, SessionKey, SessionPatch, SessionSummary, StoredMessage,
+};
+
+/// Maximum number of messages returned by a history query without a limit,
+/// matching the durable stores.
+const DEFAULT_HISTORY_LIMIT: u32 = 1000;
+
+/// A key-value store with expiring entries holding cached sessions.
+#[async_trait]
+pub trait SessionCache: Send + Sync {
+    /// Returns the value stored under `key`, if present and not expired.
+    async fn get(&self, key: &str) -> Result<Option<String>>;
+
+    /// Stores `value` under `key` for `ttl`.
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<()>;
+
+    /// Removes the values stored under `keys`.
+    async fn delete(&self, keys: &[String]) -> Result<()>;
+}
+
+/// An in-process [`SessionCache`], for single-instance deployments and tests.
+#[derive(Debug, Default)]
+pub struct MemorySessionCache {
+    entries: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+impl MemorySessionCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SessionCache for MemorySessionCache {
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((value, expires_at)) if *expires_at > Instant::now() => Ok(Some(value.clone())),
+            Some(_) => {
+                entries.remove(key);
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<()> {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), (value.to_string(), Instant::now() + ttl));
+        Ok(())
+    }
+
+    async fn delete(&self, keys: &[String]) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        for key in keys {
+            entries.remove(key);
+        }
+        Ok(())
+    }
+}
+
+/// Configuration for a [`CachedSessionBackend`].
+#[derive(Debug, Clone)]
+pub struct SessionCacheConfig {
+    /// How long cached entries live after their last write.
+    pub ttl: Duration,
+    /// Largest history, in messages, kept in the cache.
+    pub max_messages: usize,
+    /// Prefix of all cache keys, to share one cache between deployments.
+    pub key_prefix: String,
+}
+
+impl Default for SessionCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(15 * 60),
+            max_messages: 200,
+            key_prefix: "aisopod:".to_string(),
+        }
+    }
+}
+
+/// A [`SessionBackend`] serving active-session reads from a cache in front
+/// of a durable backend.
+///
+/// Cache failures are logged and fall back to the durable backend, so a
+/// cache outage slows sessions down but does not fail them.
+pub struct CachedSessionBackend {
+    backend: Arc<dyn SessionBackend>,
+    cache: Arc<dyn SessionCache>,
+    config: SessionCacheConfig,
+    cipher: Option<MessageCipher>,
+}
+
+impl CachedSessionBackend {
+    /// Creates a cached backend with the default configuration.
+    pub fn new(backend: Arc<dyn SessionBackend>, cache: Arc<dyn SessionCache>) -> Self {
+        Self {
+            backend,
+            cache,
+            config: SessionCacheConfig::default(),
+            cipher: None,
+        }
+    }
+
+    /// Encrypts cached entries with the given cipher, each bound to its
+    /// cache key.
+    pub fn with_cipher(mut self, cipher: MessageCipher) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    /// Sets the cache configuration.
+    pub fn with_config(mut self, config: SessionCacheConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Removes the cached entries of a session, so the next reads go to the
+    /// durable backend. Call after changing the session outside this backend.
+    pub async fn invalidate(&self, key: &SessionKey) -> Result<()> {
+        self.cache
+            .delete(&[self.session_key(key), self.history_key(key)])
+            .await
+    }
+
+    /// Returns the cache key of a session record.
+    fn session_key(&self, key: &SessionKey) -> String {
+        format!("{}session:{}", self.config.key_prefix, encode_key(key))
+    }
+
+    /// Returns the cache key of a session's message history.
+    fn history_key(&self, key: &SessionKey) -> String {
+        format!("{}history:{}", self.config.key_prefix, encode_key(key))
+    }
+
+    /// Reads and decodes a cached value, treating failures as misses.
+    async fn read<T: serde::de::DeserializeOwned>(&self, cache_key: &str) -> Option<T> {
+        let value = self
+            .cache
+            .get(cache_key)
+            .await
+            .and_then(|value| match value {
+                Some(value) => Ok(Some(encryption::open(
+                    self.cipher.as_ref(),
+                    value,
+                    cache_key.as_bytes(),
+                )?)),
+                None => Ok(None),
+            });
+        match value {
+            Ok(Some(value)) => match serde_json::from_str(&value) {
+                Ok(value) => Some(value),
+                Err(e) => {
+                    tracing::warn!("Discarding undecodable cache entry '{}': {}", cache_key, e);
+                    None
+                }
+            },
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Session cache read of '{}' failed: {}", cache_key, e);
+                None
+            }
+        }
+    }
+
+    /// Encodes and stores a value, logging failures.
+    async fn write<T: serde::Serialize>(&self, cache_key: &str, value: &T) {
+        let value = serde_json::to_string(value).map_err(anyhow::Error::from);
+        let result = match value
+            .and_then(|value| encryption::seal(self.cipher.as_ref(), value, cache_key.as_bytes()))
+        {
+            Ok(value) => self.cache.set(cache_key, &value, self.config.ttl).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            tracing::warn!("Session cache write of '{}' failed: {}", cache_key, e);
+        }
+    }
+
+    /// Removes the cached entries of a session, logging failures.
+    async fn evict(&self, key: &SessionKey) {
+        if let Err(e) = self.invalidate(key).await {
+            tracing::warn!("Session cache eviction for {:?} failed: {}", key, e);
+        }
+    }
+
+    /// Loads the history of a session from the durable backend and caches
+    /// it if it is short enough, returning the cached history.
+    async fn load_history(
+        &self,
+        agent_id: &str,
+        key: &SessionKey,
+    ) -> Result<Option<Vec<StoredMessage>>> {
+        let window = HistoryQuery {
+            limit: Some(self.config.max_messages as u32 + 1),
+            ..Default::default()
+        };
+        let messages = self.backend.get_history(agent_id, key, &window).await?;
+        let history_key = self.history_key(key);
+        if messages.len() > self.config.max_messages {
+            if let Err(e) = self.cache.delete(&[history_key]).await {
+                tracing::warn!("Session cache eviction for {:?} failed: {}", key, e);
+            }
+            return Ok(None);
+        }
+        self.write(&history_key, &messages).await;
+        Ok(Some(messages))
+    }
+}
+
+#[async_trait]
+impl SessionBackend for CachedSessionBackend {
+    async fn get_or_create(&self, agent_id: &str, key: &SessionKey) -> Result<Session> {
+        if agent_id == key.agent_id {
+            if let Some(session) = self.read(&self.session_key(key)).await {
+                return Ok(session);
+            }
+        }
+        let session = self.backend.get_or_create(agent_id, key).await?;
+        self.write(&self.session_key(key), &session).await;
+        Ok(session)
+    }
+
+    async fn get(&self, key: &SessionKey) -> Result<Option<Session>> {
+        if let Some(session) = self.read(&self.session_key(key)).await {
+            return Ok(Some(session));
+        }
+        let session = self.backend.get(key).await?;
+        if let Some(session) = &session {
+            self.write(&self.session_key(key), session).await;
+        }
+        Ok(session)
+    }
+
+    async fn list(&self, agent_id: &str, filter: &SessionFilter) -> Result<Vec<SessionSummary>> {
+        self.backend.list(agent_id, filter).await
+    }
+
+    async fn list_all_sessions(&self, filter: &SessionFilter) -> Result<Vec<SessionSummary>> {
+        self.backend.list_all_sessions(filter).await
+    }
+
+    async fn patch(
+        &self,
+        agent_id: &str,
+        key: &SessionKey,
+        patch: &SessionPatch,
+    ) -> Result<Session> {
+        let session = self.backend.patch(agent_id, key, patch).await?;
+        self.write(&self.session_key(key), &session).await;
+        Ok(session)
+    }
+
+    async fn delete(&self, agent_id: &str, key: &SessionKey) -> Result<bool> {
+        let deleted = self.backend.delete(agent_id, key).await?;
+        self.evict(key).await;
+        Ok(deleted)
+    }
+
+    async fn append_messages(
+        &self,
+        agent_id: &str,
+        key: &SessionKey,
+        messages: &[StoredMessage],
+    ) -> Result<()> {
+        self.backend
+            .append_messages(agent_id, key, messages)
+            .await?;
+
+        // Refresh from the durable store, which assigned the message ids and
+        // holds appends made by other instances
+        match self.backend.get(key).await {
+            Ok(Some(session)) => self.write(&self.session_key(key), &session).await,
+            Ok(None) => self.evict(key).await,
+            Err(e) => {
+                tracing::warn!("Session cache refresh for {:?} failed: {}", key, e);
+                self.evict(key).await;
+                return Ok(());
+            }
+        }
+        if let Err(e) = self.load_history(agent_id, key).await {
+            tracing::warn!("Session cache refresh for {:?} failed: {}", key, e);
+            self.evict(key).await;
+        }
+        Ok(())
+    }
+
+    async fn get_history(
+        &self,
+        agent_id: &str,
+        key: &SessionKey,
+        query: &HistoryQuery,
+    ) -> Result<Vec<StoredMessage>> {
+        if agent_id != key.agent_id {
+            // Let the durable backend report the scope violation
+            return self.backend.get_history(agent_id, key, query).await;
+        }
+
+        let history = match self.read(&self.history_key(key)).await {
+            Some(history) => Some(history),
+            None => self.load_history(agent_id, key).await?,
+        };
+        match history {
+            Some(history) => Ok(apply_query(history, query)),
+            None => self.backend.get_history(agent_id, key, query).await,
+        }
+    }
+
+    // Leases coordinate instances, so they are never cached
+
+    async fn try_acquire_lease(
+        &self,
+        session_key: &str,
+        owner: &str,
+        ttl: Duration,
+    ) -> Result<Option<SessionLease>> {
+        self.backend
+            .try_acquire_lease(session_key, owner, ttl)
+            .await
+    }
+
+    async fn renew_lease(&self, session_key: &str, owner: &str, ttl: Duration) -> Result<bool> {
+        self.backend.renew_lease(session_key, owner, ttl).await
+    }
+
+    async fn release_lease(&self, session_key: &str, owner: &str) -> Result<bool> {
+        self.backend.release_lease(session_key, owner).await
+    }
+}
+
+/// Encodes a session key for use in cache keys, escaping the separator so
+/// that distinct session keys never collide.
+fn encode_key(key: &SessionKey) -> String {
+    [
+        &key.agent_id,
+        &key.channel,
+        &key.account_id,
+        &key.peer_kind,
+        &key.peer_id,
+    ]
+    .iter()
+    .map(|field| field.replace('%', "%25").replace(':', "%3A"))
+    .collect::<Vec<_>>()
+    .join(":")
+}
+
+/// Answers a history query from a session's complete, chronological history.
+fn apply_query(history: Vec<StoredMessage>, query: &HistoryQuery) -> Vec<StoredMessage> {
+    history
+        .into_iter()
+        .filter(|msg| query.before.is_none_or(|before| msg.created_at < before))
+        .filter(|msg| query.after.is_none_or(|after| msg.created_at > after))
+        .skip(query.offset.unwrap_or(0) as usize)
+        .take(query.limit.unwrap_or(DEFAULT_HISTORY_LIMIT) as usize)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::SessionStore;
+
+    fn test_key() -> SessionKey {
+        SessionKey {
+            agent_id: "agent_001".to_string(),
+            channel: "discord".to_string(),
+            account_id: "bot_123".to_string(),
+            peer_kind: "dm".to_string(),
+            peer_id: "user_456".to_string(),
+        }
+    }
+
+    fn cached(store: Arc<SessionStore>, cache: Arc<MemorySessionCache>) -> CachedSessionBackend {
+        CachedSessionBackend::new(store, cache).with_config(SessionCacheConfig {
+            max_messages: 3,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_reads_are_served_from_cache() {
+        let store = Arc::new(SessionStore::new_in_memory().unwrap());
+        let backend = cached(store.clone(), Arc::new(MemorySessionCache::new()));
+        let key = test_key();
+
+        backend.get_or_create("agent_001", &key).await.unwrap();
+        backend
+            .append_messages("agent_001", &key, &[StoredMessage::user("Hello")])
+            .await
+            .unwrap();
+
+        // Changes behind the cache's back are not seen until invalidated
+        store
+            .append_messages("agent_001", &key, &[StoredMessage::user("Hidden")])
+            .unwrap();
+        let history = backend
+            .get_history("agent_001", &key, &HistoryQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert!(history[0].id > 0);
+        let session = backend.get(&key).await.unwrap().unwrap();
+        assert_eq!(session.message_count, 1);
+
+        backend.invalidate(&key).await.unwrap();
+        let history = backend
+            .get_history("agent_001", &key, &HistoryQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_entries_are_encrypted_with_cipher() {
+        let cipher = MessageCipher::from_base64(&MessageCipher::generate_key()).unwrap();
+        let store = Arc::new(
+            SessionStore::new_in_memory()
+                .unwrap()
+                .with_cipher(cipher.clone()),
+        );
+        let cache = Arc::new(MemorySessionCache::new());
+        let backend = cached(store, cache.clone()).with_cipher(cipher);
+        let key = test_key();
+
+        backend.get_or_create("agent_001", &key).await.unwrap();
+        backend
+            .append_messages("agent_001", &key, &[StoredMessage::user("Top secret")])
+            .await
+            .unwrap();
+
+        let entry = cache
+            .get(&backend.history_key(&key))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(MessageCipher::is_encrypted(&entry));
+        assert!(!entry.contains("Top secret"));
+        let history = backend
+            .get_history("agent_001", &key, &HistoryQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(history[0].content, serde_json::json!("Top secret"));
+
+        // Entries cannot be moved to another key
+        cache
+            .set(&backend.session_key(&key), &entry, Duration::from_secs(60))
+            .await
+            .unwrap();
+        let moved: Option<Vec<StoredMessage>> = backend.read(&backend.session_key(&key)).await;
+        assert!(moved.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_writes_refresh_cache() {
+        let store = Arc::new(SessionStore::new_in_memory().unwrap());
+        let cache = Arc::new(MemorySessionCache::new());
+        let backend = cached(store, cache.clone());
+        let key = test_key();
+
+        backend.get_or_create("agent_001", &key).await.unwrap();
+        backend
+            .append_messages(
+                "agent_001",
+                &key,
+                &[StoredMessage::user("Hi"), StoredMessage::assistant("Hello")],
+            )
+            .await
+            .unwrap();
+        let session = backend.get(&key).await.unwrap().unwrap();
+        assert_eq!(session.message_count, 2);
+
+        let patched = backend
+            .patch("agent_001", &key, &SessionPatch::with_message_count(7))
+            .await
+            .unwrap();
+        assert_eq!(patched.message_count, 7);
+        assert_eq!(backend.get(&key).await.unwrap().unwrap().message_count, 7);
+
+        let query = HistoryQuery {
+            limit: Some(1),
+            offset: Some(1),
+            ..Default::default()
+        };
+        let page = backend
+            .get_history("agent_001", &key, &query)
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].role, "assistant");
+
+        assert!(backend.delete("agent_001", &key).await.unwrap());
+        assert!(backend.get(&key).await.unwrap().is_none());
+        assert!(cache
+            .get(&backend.history_key(&key))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_long_history_is_read_from_backend() {
+        let store = Arc::new(SessionStore::new_in_memory().unwrap());
+        let cache = Arc::new(MemorySessionCache::new());
+        let backend = cached(store, cache.clone());
+        let key = test_key();
+
+        backend.get_or_create("agent_001", &key).await.unwrap();
+        let messages: Vec<StoredMessage> = (0..4)
+            .map(|i| StoredMessage::user(format!("Message {}", i)))
+            .collect();
+        backend
+            .append_messages("agent_001", &key, &messages)
+            .await
+            .unwrap();
+
+        let history = backend
+            .get_history("agent_001", &key, &HistoryQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 4);
+        assert!(cache
+            .get(&backend.history_key(&key))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_scope_is_enforced_on_cached_reads() {
+        let store = Arc::new(SessionStore::new_in_memory().unwrap());
+        let backend = cached(store, Arc::new(MemorySessionCache::new()));
+        let key = test_key();
+
+        backend.get_or_create("agent_001", &key).await.unwrap();
+        backend
+            .get_history("agent_001", &key, &HistoryQuery::default())
+            .await
+            .unwrap();
+
+        assert!(backend.get_or_create("agent_002", &key).await.is_err());
+        assert!(backend
+            .get_history("agent_002", &key, &HistoryQuery::default())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_memory_cache_expires_entries() {
+        let cache = MemorySessionCache::new();
+        cache.set("key", "value", Duration::ZERO).await.unwrap();
+        assert!(cache.get("key").await.unwrap().is_none());
+
+        cache
+            .set("key", "value", Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(cache.get("key").await.unwrap().as_deref(), Some("value"));
+    }
+
+    #[test]
+    fn test_encoded_keys_do_not_collide() {
+        let mut other = test_key();
+        other.channel = "discord:bot_123".to_string();
+        other.account_id = String::new();
+        assert_ne!(encode_key(&test_key()), encode_key(&other));
+    }
+}
@@ -8,7 +8,7 @@ use rusqlite::{params, Connection, Result};
 use std::path::Path;
 
 /// The current schema version.
-const SCHEMA_VERSION: i64 = 2;
+const SCHEMA_VERSION: i64 = 4;
 
 /// Opens or creates a SQLite database at the given path.
 ///
@@ -77,24 +77,28 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         return Ok(());
     }
 
-    // Define migrations in order
-    let migrations = vec![
-        create_tables_migration(),
-        create_indexes_migration(),
-        add_compaction_columns_migration(),
+    // Define migrations in order, each with the version it brings the schema to
+    let migrations: Vec<(i64, Vec<&str>)> = vec![
+        (1, vec![create_tables_migration(), create_indexes_migration()]),
+        (2, vec![add_compaction_columns_migration()]),
+        (3, vec![create_subagent_runs_migration()]),
+        (4, vec![create_session_leases_migration()]),
     ];
 
-    // Apply each migration
-    for migration_sql in migrations {
-        conn.execute_batch(migration_sql)?;
+    // Apply each unapplied migration and record its version
+    for (version, statements) in migrations {
+        if version <= current_version {
+            continue;
+        }
+        for migration_sql in statements {
+            conn.execute_batch(migration_sql)?;
+        }
+        conn.execute(
+            "INSERT INTO schema_version (version) VALUES (?)",
+            params![version],
+        )?;
     }
 
-    // Record the new version
-    conn.execute(
-        "INSERT INTO schema_version (version) VALUES (?)",
-        params![SCHEMA_VERSION],
-    )?;
-
     Ok(())
 }
 
@@ -155,6 +159,46 @@ fn add_compaction_columns_migration() -> &'static str {
     "#
 }
 
+/// Returns the SQL statements to create the subagent runs table.
+fn create_subagent_runs_migration() -> &'static str {
+    r#"
+    -- Create subagent runs table
+    CREATE TABLE IF NOT EXISTS subagent_runs (
+        id TEXT PRIMARY KEY,
+        parent_session_key TEXT NOT NULL,
+        agent_name TEXT NOT NULL,
+        prompt TEXT NOT NULL,
+        model TEXT NOT NULL,
+        depth INTEGER NOT NULL DEFAULT 0,
+        status TEXT NOT NULL DEFAULT 'pending',
+        result TEXT,
+        error TEXT,
+        attempts INTEGER NOT NULL DEFAULT 0,
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_subagent_runs_parent ON subagent_runs(parent_session_key);
+    CREATE INDEX IF NOT EXISTS idx_subagent_runs_status ON subagent_runs(status);
+    "#
+}
+
+/// Returns the SQL statement to create the session leases table.
+///
+/// Lease times are Unix timestamps in milliseconds, so that they compare
+/// correctly in SQL.
+fn create_session_leases_migration() -> &'static str {
+    r#"
+    -- Create session leases table
+    CREATE TABLE IF NOT EXISTS session_leases (
+        session_key TEXT PRIMARY KEY,
+        owner TEXT NOT NULL,
+        acquired_at INTEGER NOT NULL,
+        expires_at INTEGER NOT NULL
+    );
+    "#
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -328,7 +372,7 @@ mod tests {
         run_migrations(&conn).unwrap();
         run_migrations(&conn).unwrap();
 
-        // Verify schema_version is 1
+        // Verify schema_version is the current version
         let version: i64 = conn
             .query_row(
                 "SELECT version FROM schema_version ORDER BY version DESC LIMIT 1",
@@ -339,6 +383,39 @@ mod tests {
         assert_eq!(version, SCHEMA_VERSION);
     }
 
+    #[test]
+    fn test_migrations_upgrade_from_version_2() {
+        let conn = create_test_database();
+
+        // Simulate a database created by the version 2 schema
+        conn.execute_batch(create_tables_migration()).unwrap();
+        conn.execute_batch(create_indexes_migration()).unwrap();
+        conn.execute_batch(add_compaction_columns_migration()).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE schema_version (version INTEGER PRIMARY KEY);
+             INSERT INTO schema_version (version) VALUES (2);",
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        let table_exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type='table' AND name='subagent_runs')",
+                params![],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(table_exists);
+
+        let version: i64 = conn
+            .query_row("SELECT MAX(version) FROM schema_version", params![], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+    }
+
     #[test]
     fn test_foreign_key_constraint() {
         let conn = create_test_database();
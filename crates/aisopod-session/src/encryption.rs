@@ -0,0 +1,229 @@
+//! Encryption at rest for stored messages.
+//!
+//! A [`MessageCipher`] encrypts the content and tool calls of each message
+//! with AES-256-GCM before the session store writes them, and decrypts them
+//! when they are read back, so transcripts on disk are unreadable without
+//! the key. Each record gets a fresh random nonce and is bound to its
+//! session, so records cannot be moved between sessions unnoticed.
+//!
+//! Encrypted values are stored as `enc:v1:` followed by the base64 encoded
+//! nonce and ciphertext. Values without that prefix are read as plaintext,
+//! so encryption can be enabled on an existing database.
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use std::fmt;
+
+/// Prefix marking an encrypted value.
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+/// Length of an AES-256 key in bytes.
+const KEY_LEN: usize = 32;
+
+/// Length of an AES-GCM nonce in bytes.
+const NONCE_LEN: usize = 12;
+
+/// Account under which the key is stored in the OS keyring.
+#[cfg(feature = "keyring")]
+pub const KEYRING_ACCOUNT: &str = "session-encryption";
+
+/// Error returned when a stored message cannot be decrypted.
+///
+/// Returned for a wrong or missing key and for tampered records, so that
+/// such messages are reported instead of silently skipped.
+#[derive(Debug, thiserror::Error)]
+#[error("Failed to decrypt message: {0}")]
+pub struct DecryptError(String);
+
+/// Cipher encrypting stored messages with AES-256-GCM.
+#[derive(Clone)]
+pub struct MessageCipher {
+    cipher: Aes256Gcm,
+}
+
+impl MessageCipher {
+    /// Creates a cipher from a raw 32-byte key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is not exactly 32 bytes long.
+    pub fn new(key: &[u8]) -> Result<Self> {
+        if key.len() != KEY_LEN {
+            return Err(anyhow!(
+                "Session encryption key must be {} bytes, got {}",
+                KEY_LEN,
+                key.len()
+            ));
+        }
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+        })
+    }
+
+    /// Creates a cipher from a base64 encoded 32-byte key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is not valid base64 or has the wrong
+    /// length.
+    pub fn from_base64(key: &str) -> Result<Self> {
+        let key = BASE64
+            .decode(key.trim())
+            .map_err(|e| anyhow!("Session encryption key is not valid base64: {}", e))?;
+        Self::new(&key)
+    }
+
+    /// Creates a cipher from the base64 encoded key stored in the OS
+    /// keyring under `service` and [`KEYRING_ACCOUNT`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the keyring has no such entry or the stored key
+    /// is invalid.
+    #[cfg(feature = "keyring")]
+    pub fn from_keyring(service: &str) -> Result<Self> {
+        let entry = keyring::Entry::new(service, KEYRING_ACCOUNT)
+            .map_err(|e| anyhow!("Failed to open keyring entry '{}': {}", service, e))?;
+        let key = entry
+            .get_password()
+            .map_err(|e| anyhow!("Failed to read key from keyring entry '{}': {}", service, e))?;
+        Self::from_base64(&key)
+    }
+
+    /// Generates a new random key, base64 encoded for use in configuration.
+    pub fn generate_key() -> String {
+        BASE64.encode(Aes256Gcm::generate_key(OsRng))
+    }
+
+    /// Returns `true` if a stored value is encrypted.
+    pub fn is_encrypted(stored: &str) -> bool {
+        stored.starts_with(ENCRYPTED_PREFIX)
+    }
+
+    /// Encrypts a value, binding it to `aad` (the owning record's context).
+    pub fn encrypt(&self, plaintext: &str, aad: &[u8]) -> Result<String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let payload = Payload {
+            msg: plaintext.as_bytes(),
+            aad,
+        };
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, payload)
+            .map_err(|_| anyhow!("Failed to encrypt message"))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(sealed)))
+    }
+
+    /// Decrypts a value encrypted with the same key and `aad`.
+    ///
+    /// Values that are not encrypted are returned unchanged.
+    pub fn decrypt(&self, stored: &str, aad: &[u8]) -> Result<String, DecryptError> {
+        let Some(encoded) = stored.strip_prefix(ENCRYPTED_PREFIX) else {
+            return Ok(stored.to_string());
+        };
+        let sealed = BASE64
+            .decode(encoded)
+            .map_err(|e| DecryptError(format!("invalid encoding: {}", e)))?;
+        if sealed.len() < NONCE_LEN {
+            return Err(DecryptError("record is truncated".to_string()));
+        }
+
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let payload = Payload {
+            msg: ciphertext,
+            aad,
+        };
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), payload)
+            .map_err(|_| DecryptError("wrong key or tampered record".to_string()))?;
+        String::from_utf8(plaintext).map_err(|e| DecryptError(e.to_string()))
+    }
+}
+
+impl fmt::Debug for MessageCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MessageCipher(***REDACTED***)")
+    }
+}
+
+/// Decrypts a stored value with an optional cipher.
+///
+/// Encrypted values fail to decrypt when no cipher is configured.
+pub(crate) fn open(
+    cipher: Option<&MessageCipher>,
+    stored: String,
+    aad: &[u8],
+) -> Result<String, DecryptError> {
+    match cipher {
+        Some(cipher) => cipher.decrypt(&stored, aad),
+        None if MessageCipher::is_encrypted(&stored) => Err(DecryptError(
+            "message is encrypted but no encryption key is configured".to_string(),
+        )),
+        None => Ok(stored),
+    }
+}
+
+/// Encrypts a value with an optional cipher, leaving it as is without one.
+pub(crate) fn seal(cipher: Option<&MessageCipher>, value: String, aad: &[u8]) -> Result<String> {
+    match cipher {
+        Some(cipher) => cipher.encrypt(&value, aad),
+        None => Ok(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cipher() -> MessageCipher {
+        MessageCipher::from_base64(&MessageCipher::generate_key()).unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let cipher = cipher();
+        let sealed = cipher.encrypt("\"hello\"", b"1").unwrap();
+
+        assert!(MessageCipher::is_encrypted(&sealed));
+        assert!(!sealed.contains("hello"));
+        assert_eq!(cipher.decrypt(&sealed, b"1").unwrap(), "\"hello\"");
+        // Each record gets its own nonce
+        assert_ne!(sealed, cipher.encrypt("\"hello\"", b"1").unwrap());
+    }
+
+    #[test]
+    fn test_decrypt_rejects_wrong_key_and_context() {
+        let sealed = cipher().encrypt("secret", b"1").unwrap();
+
+        assert!(cipher().decrypt(&sealed, b"1").is_err());
+        let cipher = cipher();
+        let sealed = cipher.encrypt("secret", b"1").unwrap();
+        assert!(cipher.decrypt(&sealed, b"2").is_err());
+    }
+
+    #[test]
+    fn test_plaintext_passes_through() {
+        assert_eq!(cipher().decrypt("\"plain\"", b"1").unwrap(), "\"plain\"");
+        assert_eq!(
+            open(None, "\"plain\"".to_string(), b"1").unwrap(),
+            "\"plain\""
+        );
+
+        let sealed = cipher().encrypt("secret", b"1").unwrap();
+        assert!(open(None, sealed, b"1").is_err());
+    }
+
+    #[test]
+    fn test_invalid_keys() {
+        assert!(MessageCipher::new(&[0u8; 16]).is_err());
+        assert!(MessageCipher::from_base64("not base64!").is_err());
+        assert!(MessageCipher::new(&[7u8; 32]).is_ok());
+    }
+}
@@ -0,0 +1,378 @@
+//! Session transcript export.
+//!
+//! This module renders a session and its stored messages either as readable
+//! Markdown, for sharing a conversation, or as JSONL, for offline analysis.
+//! Both formats include the tool calls of each message and the token usage
+//! of the session.
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::fmt::Write;
+use std::str::FromStr;
+
+use crate::store::SessionStore;
+use crate::types::{HistoryQuery, Session, SessionKey, SessionStatus, StoredMessage};
+
+/// The format a session is exported in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// A readable Markdown transcript.
+    Markdown,
+    /// One JSON object per line: the session, then each message.
+    Jsonl,
+}
+
+impl ExportFormat {
+    /// Returns the file extension conventionally used for the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Ok(ExportFormat::Markdown),
+            "jsonl" | "ndjson" => Ok(ExportFormat::Jsonl),
+            other => Err(anyhow!(
+                "Unknown export format '{}'; expected 'markdown' or 'jsonl'",
+                other
+            )),
+        }
+    }
+}
+
+/// Renders a session and its messages in the given format.
+pub fn export_session(
+    format: ExportFormat,
+    session: &Session,
+    messages: &[StoredMessage],
+) -> Result<String> {
+    match format {
+        ExportFormat::Markdown => Ok(export_markdown(session, messages)),
+        ExportFormat::Jsonl => export_jsonl(session, messages),
+    }
+}
+
+/// Loads a session and its complete history from `store` and renders them
+/// in the given format.
+///
+/// # Errors
+///
+/// Returns an error if the session does not exist or cannot be read.
+pub fn export_from_store(
+    store: &SessionStore,
+    key: &SessionKey,
+    format: ExportFormat,
+) -> Result<String> {
+    let session = store
+        .get(key)?
+        .ok_or_else(|| anyhow!("Session not found for key {:?}", key))?;
+    let query = HistoryQuery {
+        limit: Some(u32::MAX),
+        ..Default::default()
+    };
+    let messages = store.get_history(&key.agent_id, key, &query)?;
+    export_session(format, &session, &messages)
+}
+
+/// Renders a session and its messages as a Markdown transcript.
+///
+/// The transcript starts with the session's identity and token usage,
+/// followed by one section per message. Tool calls are listed with their
+/// arguments under the message that made them.
+pub fn export_markdown(session: &Session, messages: &[StoredMessage]) -> String {
+    let key = &session.key;
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# Session {}\n", key.peer_id);
+    let _ = writeln!(out, "- **Agent:** {}", key.agent_id);
+    let _ = writeln!(out, "- **Channel:** {} ({})", key.channel, key.account_id);
+    let _ = writeln!(out, "- **Peer:** {} {}", key.peer_kind, key.peer_id);
+    let _ = writeln!(out, "- **Status:** {}", status_name(&session.status));
+    let _ = writeln!(out, "- **Created:** {}", session.created_at.to_rfc3339());
+    let _ = writeln!(out, "- **Updated:** {}", session.updated_at.to_rfc3339());
+    let _ = writeln!(out, "- **Messages:** {}", messages.len());
+    let _ = writeln!(out, "- **Token usage:** {}", session.token_usage);
+    if let Some(summary) = &session.last_compaction_summary {
+        let _ = writeln!(
+            out,
+            "\n> **Compacted summary:** {}",
+            summary.replace('\n', "\n> ")
+        );
+    }
+
+    for msg in messages {
+        let _ = writeln!(
+            out,
+            "\n## {} · {}\n",
+            role_title(&msg.role),
+            msg.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+
+        let text = content_text(&msg.content);
+        if !text.is_empty() {
+            let _ = writeln!(out, "{}", text);
+        }
+
+        if let Some(tool_calls) = &msg.tool_calls {
+            let _ = writeln!(out, "\n**Tool calls:**\n");
+            for call in tool_call_list(tool_calls) {
+                write_tool_call(&mut out, call);
+            }
+        }
+    }
+
+    out
+}
+
+/// Renders a session and its messages as JSONL.
+///
+/// The first line is a `session` record with the session's key, status and
+/// token usage; each following line is a `message` record with the role,
+/// content and tool calls of one message, in chronological order.
+pub fn export_jsonl(session: &Session, messages: &[StoredMessage]) -> Result<String> {
+    let key = &session.key;
+    let mut lines = vec![serde_json::to_string(&json!({
+        "type": "session",
+        "agent_id": key.agent_id,
+        "channel": key.channel,
+        "account_id": key.account_id,
+        "peer_kind": key.peer_kind,
+        "peer_id": key.peer_id,
+        "status": status_name(&session.status),
+        "created_at": session.created_at,
+        "updated_at": session.updated_at,
+        "message_count": messages.len(),
+        "token_usage": session.token_usage,
+        "compaction_count": session.compaction_count,
+        "last_compaction_summary": session.last_compaction_summary,
+    }))?];
+
+    for msg in messages {
+        lines.push(serde_json::to_string(&json!({
+            "type": "message",
+            "id": msg.id,
+            "role": msg.role,
+            "content": msg.content,
+            "tool_calls": msg.tool_calls,
+            "created_at": msg.created_at,
+        }))?);
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    Ok(out)
+}
+
+fn status_name(status: &SessionStatus) -> &'static str {
+    match status {
+        SessionStatus::Active => "active",
+        SessionStatus::Idle => "idle",
+        SessionStatus::Compacted => "compacted",
+        SessionStatus::Archived => "archived",
+    }
+}
+
+/// Returns the heading used for a message role.
+fn role_title(role: &str) -> String {
+    let mut chars = role.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => "Message".to_string(),
+    }
+}
+
+/// Returns the readable text of a message's content.
+///
+/// Plain strings are returned as they are, the text parts of multi-part
+/// content are joined, and other content is shown as a JSON code block.
+fn content_text(content: &Value) -> String {
+    match content {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .map(|part| match part {
+                Value::String(text) => text.clone(),
+                Value::Object(fields) => match fields.get("text").and_then(Value::as_str) {
+                    Some(text) => text.to_string(),
+                    None => json_block(part),
+                },
+                other => json_block(other),
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        other => json_block(other),
+    }
+}
+
+/// Returns the individual tool calls of a message's tool call data.
+fn tool_call_list(tool_calls: &Value) -> Vec<&Value> {
+    match tool_calls {
+        Value::Array(calls) => calls.iter().collect(),
+        other => vec![other],
+    }
+}
+
+/// Writes one tool call as a list item with its arguments.
+fn write_tool_call(out: &mut String, call: &Value) {
+    let name = call
+        .get("name")
+        .or_else(|| call.pointer("/function/name"))
+        .and_then(Value::as_str);
+    let Some(name) = name else {
+        let _ = writeln!(out, "- {}", json_block(call).replace('\n', "\n  "));
+        return;
+    };
+
+    match call.get("id").and_then(Value::as_str) {
+        Some(id) => {
+            let _ = writeln!(out, "- `{}` ({})", name, id);
+        }
+        None => {
+            let _ = writeln!(out, "- `{}`", name);
+        }
+    }
+
+    let arguments = call
+        .get("arguments")
+        .or_else(|| call.pointer("/function/arguments"));
+    if let Some(arguments) = arguments {
+        // Arguments are often a JSON document encoded as a string
+        let arguments = match arguments {
+            Value::String(text) => serde_json::from_str(text).unwrap_or(arguments.clone()),
+            other => other.clone(),
+        };
+        let _ = writeln!(out, "  {}", json_block(&arguments).replace('\n', "\n  "));
+    }
+}
+
+/// Returns a value as a pretty-printed JSON code block.
+fn json_block(value: &Value) -> String {
+    let pretty = serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string());
+    format!("```json\n{}\n```", pretty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SessionKey;
+
+    fn test_session() -> Session {
+        let mut session = Session::new(SessionKey {
+            agent_id: "agent_001".to_string(),
+            channel: "discord".to_string(),
+            account_id: "bot_123".to_string(),
+            peer_kind: "dm".to_string(),
+            peer_id: "user_456".to_string(),
+        });
+        session.token_usage = 321;
+        session
+    }
+
+    fn test_messages() -> Vec<StoredMessage> {
+        vec![
+            StoredMessage::user("What is 10 * 10?"),
+            StoredMessage::with_tool_calls(
+                "assistant",
+                "",
+                json!([{
+                    "id": "call_1",
+                    "name": "calculator",
+                    "arguments": "{\"expression\": \"10 * 10\"}"
+                }]),
+            ),
+            StoredMessage::tool("100"),
+            StoredMessage::assistant("The answer is 100."),
+        ]
+    }
+
+    #[test]
+    fn test_markdown_export() {
+        let markdown = export_markdown(&test_session(), &test_messages());
+
+        assert!(markdown.starts_with("# Session user_456\n"));
+        assert!(markdown.contains("- **Agent:** agent_001"));
+        assert!(markdown.contains("- **Messages:** 4"));
+        assert!(markdown.contains("- **Token usage:** 321"));
+        assert!(markdown.contains("## User · "));
+        assert!(markdown.contains("What is 10 * 10?"));
+        assert!(markdown.contains("- `calculator` (call_1)"));
+        assert!(markdown.contains("\"expression\": \"10 * 10\""));
+        assert!(markdown.contains("## Tool · "));
+        assert!(markdown.contains("The answer is 100."));
+    }
+
+    #[test]
+    fn test_jsonl_export() {
+        let jsonl = export_jsonl(&test_session(), &test_messages()).unwrap();
+        let records: Vec<Value> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[0]["type"], "session");
+        assert_eq!(records[0]["token_usage"], 321);
+        assert_eq!(records[0]["message_count"], 4);
+        assert_eq!(records[1]["type"], "message");
+        assert_eq!(records[1]["role"], "user");
+        assert_eq!(records[2]["tool_calls"][0]["name"], "calculator");
+        assert!(records[1]["tool_calls"].is_null());
+    }
+
+    #[test]
+    fn test_markdown_content_parts() {
+        let message = StoredMessage::with_json_content(
+            "user",
+            json!([{"type": "text", "text": "Look at this"}, {"type": "image", "url": "x.png"}]),
+        );
+        let markdown = export_markdown(&test_session(), &[message]);
+
+        assert!(markdown.contains("Look at this"));
+        assert!(markdown.contains("\"url\": \"x.png\""));
+    }
+
+    #[test]
+    fn test_export_from_store() {
+        let store = SessionStore::new_in_memory().unwrap();
+        let key = test_session().key;
+        store.get_or_create("agent_001", &key).unwrap();
+        store
+            .append_messages("agent_001", &key, &test_messages())
+            .unwrap();
+
+        let jsonl = export_from_store(&store, &key, ExportFormat::Jsonl).unwrap();
+        assert_eq!(jsonl.lines().count(), 5);
+
+        let missing = SessionKey {
+            peer_id: "nobody".to_string(),
+            ..key
+        };
+        assert!(export_from_store(&store, &missing, ExportFormat::Markdown).is_err());
+    }
+
+    #[test]
+    fn test_export_format_parsing() {
+        assert_eq!(
+            "markdown".parse::<ExportFormat>().unwrap(),
+            ExportFormat::Markdown
+        );
+        assert_eq!(
+            "MD".parse::<ExportFormat>().unwrap(),
+            ExportFormat::Markdown
+        );
+        assert_eq!(
+            "jsonl".parse::<ExportFormat>().unwrap(),
+            ExportFormat::Jsonl
+        );
+        assert!("pdf".parse::<ExportFormat>().is_err());
+        assert_eq!(ExportFormat::Jsonl.extension(), "jsonl");
+    }
+}
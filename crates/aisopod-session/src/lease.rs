@@ -0,0 +1,346 @@
+//! Session ownership leases for multi-instance deployments.
+//!
+//! When several aisopod instances share a session database behind a load
+//! balancer, messages of one conversation may reach different instances.
+//! A [`SessionLease`] makes one instance, the lease owner, the only one
+//! processing a session until the lease expires. Owners renew their leases
+//! while they work; when an owner crashes its lease expires and another
+//! instance takes the session over on its next attempt.
+//!
+//! Leases are keyed by the canonical string of the session key, so a lease
+//! can be taken before the session record exists. [`acquire_lease`] returns
+//! a [`LeaseGuard`] that renews the lease in the background and releases it
+//! when dropped; [`acquire_lease_for`] does the same for a session known by
+//! its key string, as agent runs are.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+use crate::backend::SessionBackend;
+use crate::types::SessionKey;
+
+/// Exclusive ownership of a session by one instance until it expires.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionLease {
+    /// The canonical string of the leased session's key.
+    pub session_key: String,
+    /// The identifier of the instance holding the lease.
+    pub owner: String,
+    /// When the owner first acquired the lease.
+    pub acquired_at: DateTime<Utc>,
+    /// When the lease expires unless it is renewed.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl SessionLease {
+    /// Returns `true` if the lease has expired, so that another instance
+    /// may take it over.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+}
+
+/// A held session lease, renewed in the background until dropped.
+///
+/// Dropping the guard stops the renewals and releases the lease. If a
+/// renewal finds the lease taken over, for example after this instance
+/// stalled for longer than the lease duration, the guard stops renewing,
+/// [`LeaseGuard::is_held`] returns `false` and [`LeaseGuard::lost`]
+/// completes; the holder should then stop working on the session.
+pub struct LeaseGuard {
+    backend: Arc<dyn SessionBackend>,
+    lease: SessionLease,
+    held: Arc<AtomicBool>,
+    lost: Arc<Notify>,
+    renewal: JoinHandle<()>,
+}
+
+impl LeaseGuard {
+    /// Returns the lease as it was acquired.
+    pub fn lease(&self) -> &SessionLease {
+        &self.lease
+    }
+
+    /// Returns `true` while the lease has not been lost to another owner.
+    pub fn is_held(&self) -> bool {
+        self.held.load(Ordering::SeqCst)
+    }
+
+    /// Completes once the lease has been lost to another owner.
+    pub async fn lost(&self) {
+        let lost = self.lost.notified();
+        tokio::pin!(lost);
+        // Register before checking, so that a loss in between is not missed
+        lost.as_mut().enable();
+        if self.is_held() {
+            lost.await;
+        }
+    }
+}
+
+impl Drop for LeaseGuard {
+    fn drop(&mut self) {
+        self.renewal.abort();
+        if !self.is_held() {
+            return;
+        }
+        let backend = self.backend.clone();
+        let lease = self.lease.clone();
+        tokio::spawn(async move {
+            if let Err(e) = backend
+                .release_lease(&lease.session_key, &lease.owner)
+                .await
+            {
+                tracing::warn!(
+                    "Failed to release lease on session {}: {}",
+                    lease.session_key,
+                    e
+                );
+            }
+        });
+    }
+}
+
+impl std::fmt::Debug for LeaseGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LeaseGuard")
+            .field("lease", &self.lease)
+            .field("held", &self.is_held())
+            .finish()
+    }
+}
+
+/// Tries to acquire the lease on a session for `owner`.
+///
+/// The lease lasts `ttl` and is renewed every third of it while the
+/// returned guard is alive. An expired lease of another owner is taken
+/// over. Must be called within a Tokio runtime.
+///
+/// # Returns
+///
+/// Returns `Ok(Some(LeaseGuard))` if the lease was acquired, `Ok(None)` if
+/// another owner holds an unexpired lease on the session, or an error if
+/// the store operation fails.
+pub async fn acquire_lease(
+    backend: Arc<dyn SessionBackend>,
+    key: &SessionKey,
+    owner: &str,
+    ttl: Duration,
+) -> Result<Option<LeaseGuard>> {
+    acquire_lease_for(backend, &key.canonical_string(), owner, ttl).await
+}
+
+/// Tries to acquire the lease on the session with the key string
+/// `session_key` for `owner`, like [`acquire_lease`].
+pub async fn acquire_lease_for(
+    backend: Arc<dyn SessionBackend>,
+    session_key: &str,
+    owner: &str,
+    ttl: Duration,
+) -> Result<Option<LeaseGuard>> {
+    let Some(lease) = backend.try_acquire_lease(session_key, owner, ttl).await? else {
+        return Ok(None);
+    };
+
+    let held = Arc::new(AtomicBool::new(true));
+    let lost = Arc::new(Notify::new());
+    let renewal = tokio::spawn(renew_lease_task(
+        backend.clone(),
+        lease.clone(),
+        ttl,
+        held.clone(),
+        lost.clone(),
+    ));
+    Ok(Some(LeaseGuard {
+        backend,
+        lease,
+        held,
+        lost,
+        renewal,
+    }))
+}
+
+/// Renews a lease every third of its duration until it is lost.
+///
+/// Failed renewals are retried on the next tick, as long as the lease may
+/// still be ours.
+async fn renew_lease_task(
+    backend: Arc<dyn SessionBackend>,
+    lease: SessionLease,
+    ttl: Duration,
+    held: Arc<AtomicBool>,
+    lost: Arc<Notify>,
+) {
+    let interval = (ttl / 3).max(Duration::from_millis(1));
+    loop {
+        tokio::time::sleep(interval).await;
+        match backend
+            .renew_lease(&lease.session_key, &lease.owner, ttl)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                tracing::warn!(
+                    "Lease on session {} was taken over by another instance",
+                    lease.session_key
+                );
+                held.store(false, Ordering::SeqCst);
+                lost.notify_waiters();
+                return;
+            }
+            Err(e) => tracing::warn!(
+                "Failed to renew lease on session {}: {}",
+                lease.session_key,
+                e
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::SessionStore;
+
+    fn key() -> SessionKey {
+        SessionKey {
+            agent_id: "agent_001".to_string(),
+            channel: "discord".to_string(),
+            account_id: "bot_123".to_string(),
+            peer_kind: "dm".to_string(),
+            peer_id: "user_456".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_lease_is_exclusive_until_expired() {
+        let store = SessionStore::new_in_memory().unwrap();
+        let session_key = key().canonical_string();
+
+        let lease = store
+            .try_acquire_lease(&session_key, "node-a", Duration::from_millis(200))
+            .unwrap()
+            .unwrap();
+        assert_eq!(lease.owner, "node-a");
+        assert!(!lease.is_expired());
+        assert!(store
+            .try_acquire_lease(&session_key, "node-b", Duration::from_secs(60))
+            .unwrap()
+            .is_none());
+
+        // Acquiring again as the owner renews, keeping the acquisition time
+        let renewed = store
+            .try_acquire_lease(&session_key, "node-a", Duration::from_millis(200))
+            .unwrap()
+            .unwrap();
+        assert_eq!(renewed.acquired_at, lease.acquired_at);
+
+        // node-a stops renewing, as after a crash
+        std::thread::sleep(Duration::from_millis(250));
+        assert!(store.get_lease(&session_key).unwrap().unwrap().is_expired());
+        let takeover = store
+            .try_acquire_lease(&session_key, "node-b", Duration::from_secs(60))
+            .unwrap()
+            .unwrap();
+        assert_eq!(takeover.owner, "node-b");
+        assert!(!store
+            .renew_lease(&session_key, "node-a", Duration::from_secs(60))
+            .unwrap());
+        assert!(!store.release_lease(&session_key, "node-a").unwrap());
+    }
+
+    #[test]
+    fn test_renew_and_release_lease() {
+        let store = SessionStore::new_in_memory().unwrap();
+        let session_key = key().canonical_string();
+        store
+            .try_acquire_lease(&session_key, "node-a", Duration::from_millis(30))
+            .unwrap()
+            .unwrap();
+
+        assert!(store
+            .renew_lease(&session_key, "node-a", Duration::from_secs(60))
+            .unwrap());
+        std::thread::sleep(Duration::from_millis(40));
+        assert!(store
+            .try_acquire_lease(&session_key, "node-b", Duration::from_secs(60))
+            .unwrap()
+            .is_none());
+
+        assert!(store.release_lease(&session_key, "node-a").unwrap());
+        assert!(store.get_lease(&session_key).unwrap().is_none());
+        assert!(store
+            .try_acquire_lease(&session_key, "node-b", Duration::from_secs(60))
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_guard_renews_and_releases_lease() {
+        let store = Arc::new(SessionStore::new_in_memory().unwrap());
+        let backend: Arc<dyn SessionBackend> = store.clone();
+        let ttl = Duration::from_millis(300);
+
+        let guard = acquire_lease(backend.clone(), &key(), "node-a", ttl)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(guard.lease().owner, "node-a");
+
+        // Renewals keep the lease alive past its initial duration
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(guard.is_held());
+        assert!(acquire_lease(backend.clone(), &key(), "node-b", ttl)
+            .await
+            .unwrap()
+            .is_none());
+
+        drop(guard);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(store
+            .get_lease(&key().canonical_string())
+            .unwrap()
+            .is_none());
+        assert!(acquire_lease(backend, &key(), "node-b", ttl)
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_guard_notices_takeover() {
+        let store = Arc::new(SessionStore::new_in_memory().unwrap());
+        let backend: Arc<dyn SessionBackend> = store.clone();
+        let session_key = key().canonical_string();
+
+        let guard = acquire_lease(backend, &key(), "node-a", Duration::from_millis(60))
+            .await
+            .unwrap()
+            .unwrap();
+        // Another instance takes the session over, as after a long stall
+        store.release_lease(&session_key, "node-a").unwrap();
+        store
+            .try_acquire_lease(&session_key, "node-b", Duration::from_secs(60))
+            .unwrap()
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(1), guard.lost())
+            .await
+            .expect("the loss is noticed on the next renewal");
+        assert!(!guard.is_held());
+        // Completes at once when the lease is already lost
+        guard.lost().await;
+        drop(guard);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(
+            store.get_lease(&session_key).unwrap().unwrap().owner,
+            "node-b"
+        );
+    }
+}
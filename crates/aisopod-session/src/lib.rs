@@ -1,16 +1,50 @@
 //! # aisopod-session
 //!
 //! Session management, state tracking, and session lifecycle for conversations.
+//!
+//! Sessions are stored in SQLite by default; a Postgres backend shared by
+//! several gateway instances is available behind the `postgres` feature.
+//! Active sessions can be served from a write-through cache, kept in Redis
+//! behind the `redis` feature. Message bodies in the SQLite store can be
+//! encrypted at rest, with the key optionally read from the OS keyring
+//! behind the `keyring` feature.
+//!
+//! Instances sharing a session database coordinate through session leases,
+//! so that each session is processed by one instance at a time.
 
+pub mod backend;
+pub mod cache;
 pub mod compaction;
 pub mod db;
+pub mod encryption;
+pub mod export;
+pub mod lease;
+pub mod named;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(feature = "redis")]
+pub mod redis;
+pub mod retention;
 pub mod routing;
 pub mod store;
+pub mod subagent;
 pub mod types;
 
+pub use backend::SessionBackend;
+pub use cache::{CachedSessionBackend, MemorySessionCache, SessionCache, SessionCacheConfig};
 pub use compaction::{CompactionRecord, CompactionStrategy};
+pub use encryption::{DecryptError, MessageCipher};
+pub use export::{export_from_store, export_jsonl, export_markdown, export_session, ExportFormat};
+pub use lease::{acquire_lease, acquire_lease_for, LeaseGuard, SessionLease};
+pub use named::NamedSession;
+#[cfg(feature = "postgres")]
+pub use postgres::{PostgresSessionConfig, PostgresSessionStore};
+#[cfg(feature = "redis")]
+pub use redis::RedisSessionCache;
+pub use retention::{run_pruning_task, PruneReport, RetentionPolicy};
 pub use routing::{resolve_session_key, ChannelContext, PeerKind};
 pub use store::SessionStore;
+pub use subagent::{SubagentRun, SubagentStatus};
 pub use types::{
     HistoryQuery, Session, SessionFilter, SessionKey, SessionMetadata, SessionPatch, SessionStatus,
     SessionSummary, StoredMessage,
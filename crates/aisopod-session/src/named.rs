@@ -0,0 +1,420 @@
+//! Named sessions for parallel conversations with one peer.
+//!
+//! A peer normally has a single session per agent, keyed by its
+//! [`SessionKey`]. Named sessions let the peer keep several conversations
+//! side by side and switch between them. The peer's own key, the *base*
+//! key, holds the `default` conversation; every further conversation is a
+//! session whose peer ID is the base peer ID followed by `#` and a number,
+//! so it has its own history, compaction and retention like any other
+//! session.
+//!
+//! The display name of each session is kept in its metadata under
+//! [`NAME_METADATA_KEY`], so renaming does not change its key. The
+//! conversation new messages go to is recorded in the metadata of the base
+//! session under [`ACTIVE_METADATA_KEY`].
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::routing::normalize;
+use crate::store::SessionStore;
+use crate::types::{Session, SessionFilter, SessionKey, SessionPatch, SessionStatus};
+
+/// Metadata key holding the display name of a session.
+pub const NAME_METADATA_KEY: &str = "session_name";
+
+/// Metadata key of the base session holding the peer ID of the active
+/// session.
+pub const ACTIVE_METADATA_KEY: &str = "active_session";
+
+/// Name of the base session unless it was renamed.
+pub const DEFAULT_SESSION_NAME: &str = "default";
+
+/// Maximum length of a session name.
+const MAX_NAME_LEN: usize = 64;
+
+/// One of a peer's conversations.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NamedSession {
+    /// The display name of the session.
+    pub name: String,
+    /// The key the session is stored under.
+    pub key: SessionKey,
+    /// The lifecycle status of the session.
+    pub status: SessionStatus,
+    /// The number of messages in the session.
+    pub message_count: u64,
+    /// When the session was last updated.
+    pub updated_at: DateTime<Utc>,
+    /// Whether new messages from the peer go to this session.
+    pub active: bool,
+}
+
+impl SessionStore {
+    /// Lists the conversations of the peer with the `base` key.
+    ///
+    /// The base session comes first, followed by the named sessions in the
+    /// order they were created. Archived sessions are included.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The peer's own session key.
+    ///
+    /// # Returns
+    ///
+    /// Returns the peer's sessions, or an error if a database operation
+    /// fails. The list is empty if the peer has no sessions yet.
+    pub fn list_named_sessions(&self, base: &SessionKey) -> Result<Vec<NamedSession>> {
+        let filter = SessionFilter {
+            agent_id: Some(base.agent_id.clone()),
+            channel: Some(base.channel.clone()),
+            account_id: Some(base.account_id.clone()),
+            peer_kind: Some(base.peer_kind.clone()),
+            ..Default::default()
+        };
+        let mut sessions: Vec<(u64, Session)> = Vec::new();
+        for summary in self.list(&base.agent_id, &filter)? {
+            let Some(index) = session_index(base, &summary.key) else {
+                continue;
+            };
+            if let Some(session) = self.get(&summary.key)? {
+                sessions.push((index, session));
+            }
+        }
+        sessions.sort_by_key(|(index, _)| *index);
+
+        let active = self.active_peer_id(base)?;
+        Ok(sessions
+            .into_iter()
+            .map(|(_, session)| named_session(session, &active))
+            .collect())
+    }
+
+    /// Returns the key of the session new messages from the peer go to.
+    ///
+    /// This is the base key unless the peer switched to a named session
+    /// that still exists.
+    pub fn active_session_key(&self, base: &SessionKey) -> Result<SessionKey> {
+        let active = self.active_peer_id(base)?;
+        if active != base.peer_id {
+            let key = SessionKey {
+                peer_id: active,
+                ..base.clone()
+            };
+            if self.get(&key)?.is_some() {
+                return Ok(key);
+            }
+        }
+        Ok(base.clone())
+    }
+
+    /// Starts a new conversation for the peer and makes it active.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The peer's own session key.
+    /// * `name` - The name of the session; `session-N` when not given.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is invalid or already taken by another
+    /// of the peer's sessions.
+    pub fn create_named_session(
+        &self,
+        base: &SessionKey,
+        name: Option<&str>,
+    ) -> Result<NamedSession> {
+        self.get_or_create(&base.agent_id, base)?;
+        let existing = self.list_named_sessions(base)?;
+        let index = existing
+            .iter()
+            .filter_map(|s| session_index(base, &s.key))
+            .max()
+            .unwrap_or(0)
+            + 1;
+
+        let name = match name {
+            Some(name) => {
+                let name = validate_name(name)?;
+                if existing.iter().any(|s| s.name == name) {
+                    return Err(anyhow!("A session named '{}' already exists", name));
+                }
+                name
+            }
+            None => (index..)
+                .map(|n| format!("session-{}", n))
+                .find(|name| existing.iter().all(|s| s.name != *name))
+                .unwrap_or_default(),
+        };
+
+        let key = SessionKey {
+            peer_id: format!("{}#{}", base.peer_id, index),
+            ..base.clone()
+        };
+        let session = self.get_or_create(&base.agent_id, &key)?;
+        self.set_metadata(&session, NAME_METADATA_KEY, serde_json::json!(name))?;
+        self.set_active(base, &key)?;
+        self.find_named_session(base, &name)
+    }
+
+    /// Makes the peer's session called `name` active.
+    ///
+    /// Archived sessions are reactivated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the peer has no session with that name.
+    pub fn switch_named_session(&self, base: &SessionKey, name: &str) -> Result<NamedSession> {
+        let session = self.find_named_session(base, name)?;
+        if session.status == SessionStatus::Archived {
+            self.patch(
+                &base.agent_id,
+                &session.key,
+                &SessionPatch::with_status(SessionStatus::Active),
+            )?;
+        }
+        self.set_active(base, &session.key)?;
+        self.find_named_session(base, &session.name)
+    }
+
+    /// Renames the peer's session called `name` to `new_name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the peer has no session called `name`, or if
+    /// `new_name` is invalid or already taken.
+    pub fn rename_named_session(
+        &self,
+        base: &SessionKey,
+        name: &str,
+        new_name: &str,
+    ) -> Result<NamedSession> {
+        let session = self.find_named_session(base, name)?;
+        let new_name = validate_name(new_name)?;
+        if new_name != session.name && self.find_named_session(base, &new_name).is_ok() {
+            return Err(anyhow!("A session named '{}' already exists", new_name));
+        }
+
+        let stored = self
+            .get(&session.key)?
+            .ok_or_else(|| anyhow!("Session '{}' not found", session.name))?;
+        self.set_metadata(&stored, NAME_METADATA_KEY, serde_json::json!(new_name))?;
+        self.find_named_session(base, &new_name)
+    }
+
+    /// Archives the peer's session called `name`.
+    ///
+    /// The history is kept and the session can be resumed later. When the
+    /// archived session was active, the peer switches back to the base
+    /// session.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the peer has no session with that name.
+    pub fn archive_named_session(&self, base: &SessionKey, name: &str) -> Result<NamedSession> {
+        let session = self.find_named_session(base, name)?;
+        self.patch(
+            &base.agent_id,
+            &session.key,
+            &SessionPatch::with_status(SessionStatus::Archived),
+        )?;
+        if session.active && session.key != *base {
+            self.set_active(base, base)?;
+        }
+        self.find_named_session(base, &session.name)
+    }
+
+    /// Finds the peer's session called `name`.
+    fn find_named_session(&self, base: &SessionKey, name: &str) -> Result<NamedSession> {
+        let name = normalize(name);
+        self.list_named_sessions(base)?
+            .into_iter()
+            .find(|s| s.name == name)
+            .ok_or_else(|| anyhow!("No session named '{}'", name))
+    }
+
+    /// Returns the peer ID of the active session recorded for `base`.
+    fn active_peer_id(&self, base: &SessionKey) -> Result<String> {
+        Ok(self
+            .get(base)?
+            .and_then(|session| {
+                session
+                    .metadata
+                    .get(ACTIVE_METADATA_KEY)
+                    .and_then(|value| value.as_str())
+                    .map(str::to_string)
+            })
+            .unwrap_or_else(|| base.peer_id.clone()))
+    }
+
+    /// Records `key` as the active session of `base`.
+    fn set_active(&self, base: &SessionKey, key: &SessionKey) -> Result<()> {
+        let session = self.get_or_create(&base.agent_id, base)?;
+        self.set_metadata(
+            &session,
+            ACTIVE_METADATA_KEY,
+            serde_json::json!(key.peer_id),
+        )
+    }
+
+    /// Sets one metadata entry of a session, keeping the others.
+    fn set_metadata(&self, session: &Session, key: &str, value: serde_json::Value) -> Result<()> {
+        let mut metadata = session.metadata.clone();
+        metadata.set(key, value);
+        let patch = SessionPatch {
+            metadata: Some(serde_json::to_value(&metadata)?),
+            ..Default::default()
+        };
+        self.patch(&session.key.agent_id, &session.key, &patch)?;
+        Ok(())
+    }
+}
+
+/// Returns the position of `key` among the sessions of `base`: 0 for the
+/// base session and N for the named session `#N`, or `None` if the key
+/// belongs to another peer.
+fn session_index(base: &SessionKey, key: &SessionKey) -> Option<u64> {
+    if key.peer_id == base.peer_id {
+        return Some(0);
+    }
+    key.peer_id
+        .strip_prefix(&base.peer_id)?
+        .strip_prefix('#')?
+        .parse()
+        .ok()
+}
+
+fn named_session(session: Session, active: &str) -> NamedSession {
+    let name = session
+        .metadata
+        .get(NAME_METADATA_KEY)
+        .and_then(|value| value.as_str())
+        .unwrap_or(DEFAULT_SESSION_NAME)
+        .to_string();
+    NamedSession {
+        name,
+        active: session.key.peer_id == active,
+        key: session.key,
+        status: session.status,
+        message_count: session.message_count,
+        updated_at: session.updated_at,
+    }
+}
+
+/// Normalizes a session name, checking that it is a single short word.
+fn validate_name(name: &str) -> Result<String> {
+    let name = normalize(name);
+    if name.is_empty() {
+        return Err(anyhow!("Session name must not be empty"));
+    }
+    if name.len() > MAX_NAME_LEN {
+        return Err(anyhow!(
+            "Session name must be at most {} characters",
+            MAX_NAME_LEN
+        ));
+    }
+    if name.chars().any(char::is_whitespace) {
+        return Err(anyhow!("Session name must not contain whitespace"));
+    }
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::StoredMessage;
+
+    fn base() -> SessionKey {
+        SessionKey {
+            agent_id: "agent_001".to_string(),
+            channel: "telegram".to_string(),
+            account_id: "bot_123".to_string(),
+            peer_kind: "dm".to_string(),
+            peer_id: "user_1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_create_and_switch_sessions() {
+        let store = SessionStore::new_in_memory().unwrap();
+        let base = base();
+        assert_eq!(store.active_session_key(&base).unwrap(), base);
+
+        let travel = store.create_named_session(&base, Some("Travel")).unwrap();
+        assert_eq!(travel.name, "travel");
+        assert_eq!(travel.key.peer_id, "user_1#1");
+        assert!(travel.active);
+        assert_eq!(store.active_session_key(&base).unwrap(), travel.key);
+
+        let unnamed = store.create_named_session(&base, None).unwrap();
+        assert_eq!(unnamed.name, "session-2");
+        assert!(store.create_named_session(&base, Some("travel")).is_err());
+
+        store
+            .append_messages(&base.agent_id, &unnamed.key, &[StoredMessage::user("Hi")])
+            .unwrap();
+        let resumed = store.switch_named_session(&base, "default").unwrap();
+        assert_eq!(resumed.key, base);
+        assert_eq!(store.active_session_key(&base).unwrap(), base);
+
+        let sessions = store.list_named_sessions(&base).unwrap();
+        let names: Vec<&str> = sessions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["default", "travel", "session-2"]);
+        assert_eq!(sessions[2].message_count, 1);
+        assert!(sessions[0].active);
+        assert!(store.switch_named_session(&base, "unknown").is_err());
+    }
+
+    #[test]
+    fn test_rename_session() {
+        let store = SessionStore::new_in_memory().unwrap();
+        let base = base();
+        store.create_named_session(&base, Some("work")).unwrap();
+        store.create_named_session(&base, Some("home")).unwrap();
+
+        let renamed = store.rename_named_session(&base, "work", "office").unwrap();
+        assert_eq!(renamed.name, "office");
+        assert_eq!(renamed.key.peer_id, "user_1#1");
+        assert!(store.rename_named_session(&base, "office", "home").is_err());
+        assert!(store
+            .rename_named_session(&base, "home", "two words")
+            .is_err());
+
+        // The active session is kept across renames
+        store.rename_named_session(&base, "home", "house").unwrap();
+        assert_eq!(store.active_session_key(&base).unwrap().peer_id, "user_1#2");
+    }
+
+    #[test]
+    fn test_archive_and_resume_session() {
+        let store = SessionStore::new_in_memory().unwrap();
+        let base = base();
+        let work = store.create_named_session(&base, Some("work")).unwrap();
+
+        let archived = store.archive_named_session(&base, "work").unwrap();
+        assert_eq!(archived.status, SessionStatus::Archived);
+        assert!(!archived.active);
+        assert_eq!(store.active_session_key(&base).unwrap(), base);
+
+        let resumed = store.switch_named_session(&base, "work").unwrap();
+        assert_eq!(resumed.status, SessionStatus::Active);
+        assert_eq!(store.active_session_key(&base).unwrap(), work.key);
+    }
+
+    #[test]
+    fn test_sessions_of_other_peers_are_ignored() {
+        let store = SessionStore::new_in_memory().unwrap();
+        let base = base();
+        let other = SessionKey {
+            peer_id: "user_10".to_string(),
+            ..base.clone()
+        };
+        store.get_or_create(&base.agent_id, &base).unwrap();
+        store.create_named_session(&other, Some("work")).unwrap();
+
+        let sessions = store.list_named_sessions(&base).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].name, DEFAULT_SESSION_NAME);
+    }
+}
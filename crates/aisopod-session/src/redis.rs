@@ -0,0 +1,66 @@
+//! Redis session cache.
+//!
+//! This module provides a `RedisSessionCache`, a [`SessionCache`] keeping
+//! hot sessions in Redis so that several gateway instances share one cache
+//! in front of a shared durable store.
+
+use crate::cache::SessionCache;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use std::time::Duration;
+
+/// Session cache stored in Redis.
+///
+/// Uses a multiplexed connection that reconnects automatically, so one
+/// cache can be shared between tasks.
+#[derive(Clone)]
+pub struct RedisSessionCache {
+    connection: ConnectionManager,
+}
+
+impl RedisSessionCache {
+    /// Connects to Redis.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - Redis connection URL, e.g. `redis://127.0.0.1:6379/0`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new `RedisSessionCache` or an error if the URL is invalid
+    /// or the server cannot be reached.
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url).map_err(|e| anyhow!("Invalid Redis URL: {}", e))?;
+        let connection = ConnectionManager::new(client)
+            .await
+            .map_err(|e| anyhow!("Failed to connect to Redis: {}", e))?;
+        Ok(Self { connection })
+    }
+}
+
+#[async_trait]
+impl SessionCache for RedisSessionCache {
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        let mut connection = self.connection.clone();
+        Ok(connection.get(key).await?)
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<()> {
+        let mut connection = self.connection.clone();
+        // Redis rejects a zero expiry; keep entries for at least a millisecond
+        let ttl_ms = ttl.as_millis().clamp(1, u64::MAX as u128) as u64;
+        let () = connection.pset_ex(key, value, ttl_ms).await?;
+        Ok(())
+    }
+
+    async fn delete(&self, keys: &[String]) -> Result<()> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        let mut connection = self.connection.clone();
+        let _: usize = connection.del(keys).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,281 @@
+//! Session retention and automatic pruning.
+//!
+//! This module keeps the session database bounded in long-running
+//! deployments. A [`RetentionPolicy`] limits how old sessions may get, how
+//! many messages each session keeps, and how many sessions each peer keeps;
+//! [`SessionStore::prune`] enforces it and [`run_pruning_task`] enforces it
+//! periodically in the background.
+//!
+//! Trimmed message histories are recorded as compactions, so the session's
+//! [`CompactionRecord`](crate::compaction::CompactionRecord) shows when and
+//! how much was pruned.
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::compaction::CompactionStrategy;
+use crate::store::SessionStore;
+use crate::types::{SessionFilter, SessionSummary};
+
+/// Limits enforced when pruning sessions.
+///
+/// Limits left unset are not enforced.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Sessions not updated for longer than this are deleted.
+    pub max_age: Option<Duration>,
+    /// Sessions keep at most this many of their most recent messages.
+    pub max_messages: Option<u32>,
+    /// Each peer keeps at most this many of its most recently updated
+    /// sessions, across agents.
+    pub max_sessions_per_peer: Option<u32>,
+}
+
+impl RetentionPolicy {
+    /// Returns `true` if the policy sets no limit, so pruning does nothing.
+    pub fn is_unbounded(&self) -> bool {
+        self.max_age.is_none()
+            && self.max_messages.is_none()
+            && self.max_sessions_per_peer.is_none()
+    }
+}
+
+/// What a pruning pass removed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PruneReport {
+    /// Sessions deleted for exceeding the maximum age.
+    pub expired_sessions: usize,
+    /// Sessions deleted for exceeding the per-peer session limit.
+    pub excess_sessions: usize,
+    /// Sessions whose history was trimmed to the message limit.
+    pub trimmed_sessions: usize,
+    /// Messages removed from trimmed sessions.
+    pub pruned_messages: u64,
+}
+
+impl SessionStore {
+    /// Prunes sessions according to a retention policy.
+    ///
+    /// Expired sessions are deleted first, then the oldest sessions of peers
+    /// above the per-peer limit, and finally the histories of the remaining
+    /// sessions are trimmed to their most recent messages. Each trim is
+    /// recorded as a sliding-window compaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The retention limits to enforce.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `PruneReport` describing what was removed, or an error if a
+    /// database operation fails.
+    pub fn prune(&self, policy: &RetentionPolicy) -> Result<PruneReport> {
+        let mut report = PruneReport::default();
+        if policy.is_unbounded() {
+            return Ok(report);
+        }
+
+        if let Some(max_age) = policy.max_age {
+            let filter = SessionFilter {
+                updated_before: Some(Utc::now() - max_age),
+                ..Default::default()
+            };
+            for session in self.list_all_sessions(&filter)? {
+                if self.delete(&session.key.agent_id, &session.key)? {
+                    report.expired_sessions += 1;
+                }
+            }
+        }
+
+        // Sessions are listed most recently updated first
+        let mut sessions = self.list_all_sessions(&SessionFilter::default())?;
+
+        if let Some(max_sessions) = policy.max_sessions_per_peer {
+            let mut kept: HashMap<(String, String, String, String), u32> = HashMap::new();
+            let mut remaining = Vec::with_capacity(sessions.len());
+            for session in sessions {
+                let key = &session.key;
+                let peer = (
+                    key.channel.clone(),
+                    key.account_id.clone(),
+                    key.peer_kind.clone(),
+                    key.peer_id.clone(),
+                );
+                let count = kept.entry(peer).or_insert(0);
+                if *count < max_sessions {
+                    *count += 1;
+                    remaining.push(session);
+                } else if self.delete(&key.agent_id, key)? {
+                    report.excess_sessions += 1;
+                }
+            }
+            sessions = remaining;
+        }
+
+        if let Some(max_messages) = policy.max_messages {
+            for session in sessions
+                .iter()
+                .filter(|s| s.message_count > u64::from(max_messages))
+            {
+                report.pruned_messages += self.trim_history(session, max_messages)?;
+                report.trimmed_sessions += 1;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Trims a session's history to its most recent messages, returning the
+    /// number of messages removed.
+    fn trim_history(&self, session: &SessionSummary, max_messages: u32) -> Result<u64> {
+        let pruned = session.message_count - u64::from(max_messages);
+        let summary = format!(
+            "Retention policy removed {} messages, keeping the most recent {}.",
+            pruned, max_messages
+        );
+        self.compact(
+            &session.key.agent_id,
+            &session.key,
+            &CompactionStrategy::SlidingWindow { max_messages },
+            Some(&summary),
+        )?;
+        Ok(pruned)
+    }
+}
+
+/// Background task enforcing a retention policy.
+///
+/// This function should be spawned as a background task. It prunes the
+/// store once per `interval`, logging what was removed; a failed pass is
+/// logged and retried on the next tick.
+pub async fn run_pruning_task(
+    store: Arc<SessionStore>,
+    policy: RetentionPolicy,
+    interval: std::time::Duration,
+) {
+    loop {
+        tokio::time::sleep(interval).await;
+        match store.prune(&policy) {
+            Ok(report) if report != PruneReport::default() => {
+                tracing::info!(
+                    expired = report.expired_sessions,
+                    excess = report.excess_sessions,
+                    trimmed = report.trimmed_sessions,
+                    messages = report.pruned_messages,
+                    "Pruned sessions by retention policy"
+                );
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Session pruning failed: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{SessionKey, StoredMessage};
+
+    fn key(agent_id: &str, peer_id: &str) -> SessionKey {
+        SessionKey {
+            agent_id: agent_id.to_string(),
+            channel: "discord".to_string(),
+            account_id: "bot_123".to_string(),
+            peer_kind: "dm".to_string(),
+            peer_id: peer_id.to_string(),
+        }
+    }
+
+    fn add_session(store: &SessionStore, key: &SessionKey, messages: usize) {
+        store.get_or_create(&key.agent_id, key).unwrap();
+        let messages: Vec<StoredMessage> = (0..messages)
+            .map(|i| StoredMessage::user(format!("message {}", i)))
+            .collect();
+        store
+            .append_messages(&key.agent_id, key, &messages)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_unbounded_policy_prunes_nothing() {
+        let store = SessionStore::new_in_memory().unwrap();
+        add_session(&store, &key("agent_001", "user_1"), 5);
+
+        let report = store.prune(&RetentionPolicy::default()).unwrap();
+
+        assert_eq!(report, PruneReport::default());
+        assert_eq!(
+            store
+                .list_all_sessions(&SessionFilter::default())
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_prune_expired_sessions() {
+        let store = SessionStore::new_in_memory().unwrap();
+        add_session(&store, &key("agent_001", "user_1"), 1);
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        add_session(&store, &key("agent_001", "user_2"), 1);
+
+        let policy = RetentionPolicy {
+            max_age: Some(Duration::milliseconds(10)),
+            ..Default::default()
+        };
+        let report = store.prune(&policy).unwrap();
+
+        assert_eq!(report.expired_sessions, 1);
+        let remaining = store.list_all_sessions(&SessionFilter::default()).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].key.peer_id, "user_2");
+    }
+
+    #[test]
+    fn test_prune_sessions_per_peer() {
+        let store = SessionStore::new_in_memory().unwrap();
+        for agent_id in ["agent_001", "agent_002", "agent_003"] {
+            add_session(&store, &key(agent_id, "user_1"), 1);
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        add_session(&store, &key("agent_001", "user_2"), 1);
+
+        let policy = RetentionPolicy {
+            max_sessions_per_peer: Some(2),
+            ..Default::default()
+        };
+        let report = store.prune(&policy).unwrap();
+
+        assert_eq!(report.excess_sessions, 1);
+        assert!(store.get(&key("agent_001", "user_1")).unwrap().is_none());
+        assert!(store.get(&key("agent_003", "user_1")).unwrap().is_some());
+        assert!(store.get(&key("agent_001", "user_2")).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_prune_trims_history_with_compaction_record() {
+        let store = SessionStore::new_in_memory().unwrap();
+        let long = key("agent_001", "user_1");
+        let short = key("agent_001", "user_2");
+        add_session(&store, &long, 10);
+        add_session(&store, &short, 3);
+
+        let policy = RetentionPolicy {
+            max_messages: Some(4),
+            ..Default::default()
+        };
+        let report = store.prune(&policy).unwrap();
+
+        assert_eq!(report.trimmed_sessions, 1);
+        assert_eq!(report.pruned_messages, 6);
+        let session = store.get(&long).unwrap().unwrap();
+        assert_eq!(session.message_count, 4);
+        let record = store.get_compaction_record("agent_001", &long).unwrap();
+        assert_eq!(record.compaction_count, 1);
+        assert!(record.summary.unwrap().contains("removed 6 messages"));
+        assert_eq!(store.get(&short).unwrap().unwrap().compaction_count, 0);
+    }
+}
@@ -8,6 +8,9 @@ use std::sync::{Arc, Mutex};
 
 use crate::compaction::CompactionStrategy;
 use crate::db;
+use crate::encryption::{self, DecryptError, MessageCipher};
+use crate::lease::SessionLease;
+use crate::subagent::{SubagentRun, SubagentStatus};
 use crate::types::{
     HistoryQuery, Session, SessionFilter, SessionKey, SessionPatch, SessionStatus, SessionSummary,
     StoredMessage,
@@ -45,9 +48,13 @@ use crate::types::{
 /// // Delete a session (also deletes associated messages via CASCADE)
 /// store.delete("agent_001", &key)?;
 /// ```
+///
+/// Message bodies can be encrypted at rest by attaching a [`MessageCipher`]
+/// with [`SessionStore::with_cipher`].
 #[derive(Debug)]
 pub struct SessionStore {
     conn: Arc<Mutex<Connection>>,
+    cipher: Option<MessageCipher>,
 }
 
 impl SessionStore {
@@ -68,6 +75,7 @@ impl SessionStore {
         let conn = db::open_database(path)?;
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            cipher: None,
         })
     }
 
@@ -84,9 +92,21 @@ impl SessionStore {
         db::run_migrations(&conn)?;
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            cipher: None,
         })
     }
 
+    /// Encrypts message bodies at rest with the given cipher.
+    ///
+    /// The content and tool calls of messages written from now on are
+    /// encrypted; plaintext messages already in the database stay readable.
+    /// Session metadata, such as keys and compaction summaries, is not
+    /// encrypted.
+    pub fn with_cipher(mut self, cipher: MessageCipher) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
     /// Gets an existing session or creates a new one if it doesn't exist.
     ///
     /// Queries the database for a session matching all five key fields.
@@ -234,7 +254,8 @@ impl SessionStore {
     /// # Returns
     ///
     /// Returns `Ok(Vec<SessionSummary>)` with matching sessions ordered by updated_at DESC,
-    /// or an error if the database query fails or the agent_id doesn't match the filter.
+    /// paged by the filter's limit and offset, or an error if the database query fails
+    /// or the agent_id doesn't match the filter.
     pub fn list(&self, agent_id: &str, filter: &SessionFilter) -> Result<Vec<SessionSummary>> {
         // Verify scope: ensure the calling agent matches the filter's agent_id (if specified)
         // Also validate against the filter's agent_id if present
@@ -313,6 +334,7 @@ impl SessionStore {
         }
 
         query.push_str(" ORDER BY updated_at DESC");
+        Self::push_pagination(&mut query, filter);
 
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(&query)?;
@@ -414,6 +436,7 @@ impl SessionStore {
         }
 
         query.push_str(" ORDER BY updated_at DESC");
+        Self::push_pagination(&mut query, filter);
 
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(&query)?;
@@ -580,11 +603,14 @@ impl SessionStore {
         let mut conn = self.conn.lock().unwrap();
         let tx = conn.transaction()?;
 
-        // Insert each message
+        // Insert each message, bound to its session when encrypted
+        let aad = session_id.to_be_bytes();
+        let cipher = self.cipher.as_ref();
         for msg in messages {
-            let content_str = serde_json::to_string(&msg.content)?;
+            let content_str =
+                encryption::seal(cipher, serde_json::to_string(&msg.content)?, &aad)?;
             let tool_calls_str = match &msg.tool_calls {
-                Some(tc) => Some(serde_json::to_string(tc)?),
+                Some(tc) => Some(encryption::seal(cipher, serde_json::to_string(tc)?, &aad)?),
                 None => None,
             };
 
@@ -687,12 +713,23 @@ impl SessionStore {
 
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(&query_sql)?;
-        let messages: Vec<StoredMessage> = stmt
-            .query_map(rusqlite::params_from_iter(params.iter()), |row| {
-                self.row_to_stored_message(row)
-            })?
-            .filter_map(|r| r.ok())
-            .collect();
+        let rows = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
+            self.row_to_stored_message(row)
+        })?;
+
+        let mut messages = Vec::new();
+        for row in rows {
+            match row {
+                Ok(message) => messages.push(message),
+                // A wrong or missing key must not look like an empty history
+                Err(rusqlite::Error::FromSqlConversionFailure(_, _, e))
+                    if e.is::<DecryptError>() =>
+                {
+                    return Err(anyhow::anyhow!(e));
+                }
+                Err(_) => {}
+            }
+        }
 
         Ok(messages)
     }
@@ -706,6 +743,22 @@ impl SessionStore {
         let tool_calls_str: Option<String> = row.get(4)?;
         let created_at_str: String = row.get(5)?;
 
+        let aad = session_id.to_be_bytes();
+        let cipher = self.cipher.as_ref();
+        let content_str = encryption::open(cipher, content_str, &aad).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+        })?;
+        let tool_calls_str = match tool_calls_str {
+            Some(s) => Some(encryption::open(cipher, s, &aad).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    4,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            })?),
+            None => None,
+        };
+
         let content: serde_json::Value = serde_json::from_str(&content_str).map_err(|e| {
             rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
         })?;
@@ -951,6 +1004,43 @@ mod tests {
         assert_eq!(sessions.len(), 2); // Only sessions for agent_001
     }
 
+    #[test]
+    fn test_list_pagination() {
+        let store = create_test_store();
+        for i in 0..5 {
+            let key = SessionKey {
+                peer_id: format!("user_{}", i),
+                ..create_test_key()
+            };
+            store.get_or_create("agent_001", &key).unwrap();
+        }
+
+        let page = |limit, offset| SessionFilter {
+            limit,
+            offset,
+            ..Default::default()
+        };
+        let first = store.list("agent_001", &page(Some(2), None)).unwrap();
+        let second = store.list("agent_001", &page(Some(2), Some(2))).unwrap();
+        let rest = store.list("agent_001", &page(None, Some(4))).unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 2);
+        assert_eq!(rest.len(), 1);
+
+        let mut peers: Vec<String> = first
+            .iter()
+            .chain(&second)
+            .chain(&rest)
+            .map(|summary| summary.key.peer_id.clone())
+            .collect();
+        peers.sort();
+        peers.dedup();
+        assert_eq!(peers.len(), 5);
+
+        let all = store.list_all_sessions(&page(Some(3), None)).unwrap();
+        assert_eq!(all.len(), 3);
+    }
+
     #[test]
     fn test_list_filter_by_agent() {
         let store = create_test_store();
@@ -1425,6 +1515,59 @@ mod tests {
         assert!(history[1].tool_calls.is_some());
     }
 
+    #[test]
+    fn test_encrypted_messages() {
+        let key = MessageCipher::generate_key();
+        let store = create_test_store().with_cipher(MessageCipher::from_base64(&key).unwrap());
+        let session_key = create_test_key();
+        store.get_or_create("agent_001", &session_key).unwrap();
+        let messages = vec![
+            StoredMessage::user("my password is hunter2"),
+            StoredMessage::with_tool_calls(
+                "assistant",
+                "",
+                serde_json::json!([{"name": "vault", "arguments": "hunter2"}]),
+            ),
+        ];
+        store
+            .append_messages("agent_001", &session_key, &messages)
+            .unwrap();
+
+        // Nothing readable is written to the database
+        {
+            let conn = store.conn.lock().unwrap();
+            let mut stmt = conn
+                .prepare("SELECT content, COALESCE(tool_calls, '') FROM messages")
+                .unwrap();
+            let rows: Vec<(String, String)> = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .unwrap()
+                .map(|r| r.unwrap())
+                .collect();
+            assert_eq!(rows.len(), 2);
+            for (content, tool_calls) in rows {
+                assert!(MessageCipher::is_encrypted(&content));
+                assert!(!content.contains("hunter2") && !tool_calls.contains("hunter2"));
+            }
+        }
+
+        let history = store
+            .get_history("agent_001", &session_key, &HistoryQuery::default())
+            .unwrap();
+        assert_eq!(history[0].content, "my password is hunter2");
+        assert_eq!(history[1].tool_calls.as_ref().unwrap()[0]["arguments"], "hunter2");
+
+        // Reading with the wrong key fails instead of returning nothing
+        let conn = store.conn.clone();
+        let wrong_key = SessionStore {
+            conn,
+            cipher: Some(MessageCipher::from_base64(&MessageCipher::generate_key()).unwrap()),
+        };
+        assert!(wrong_key
+            .get_history("agent_001", &session_key, &HistoryQuery::default())
+            .is_err());
+    }
+
     #[test]
     fn test_get_compaction_record_initial() {
         let store = create_test_store();
@@ -1804,6 +1947,11 @@ impl SessionStore {
                 let summary_json = serde_json::Value::String(summary_text.to_string());
                 let summary_json_str =
                     serde_json::to_string(&summary_json).expect("Failed to serialize summary JSON");
+                let summary_json_str = encryption::seal(
+                    self.cipher.as_ref(),
+                    summary_json_str,
+                    &session_id.to_be_bytes(),
+                )?;
 
                 // Insert the summary message with role="system"
                 conn.execute(
@@ -1856,6 +2004,21 @@ impl SessionStore {
         }
     }
 
+    /// Appends LIMIT and OFFSET clauses for the filter's pagination, if any.
+    fn push_pagination(query: &mut String, filter: &SessionFilter) {
+        if filter.limit.is_none() && filter.offset.is_none() {
+            return;
+        }
+        // SQLite only accepts OFFSET after a LIMIT; -1 means no limit
+        match filter.limit {
+            Some(limit) => query.push_str(&format!(" LIMIT {}", limit)),
+            None => query.push_str(" LIMIT -1"),
+        }
+        if let Some(offset) = filter.offset {
+            query.push_str(&format!(" OFFSET {}", offset));
+        }
+    }
+
     /// Verifies that the calling agent_id matches the session's agent_id.
     ///
     /// This is a security check to ensure agents cannot access sessions
@@ -1882,6 +2045,251 @@ impl SessionStore {
     }
 }
 
+impl SessionStore {
+    /// Saves a subagent run, replacing any earlier record with the same ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `run` - The run to save.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` if the run was saved, or an error if the database
+    /// operation fails.
+    pub fn save_subagent_run(&self, run: &SubagentRun) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO subagent_runs (id, parent_session_key, agent_name, prompt, \
+             model, depth, status, result, error, attempts, created_at, updated_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                run.id,
+                run.parent_session_key,
+                run.agent_name,
+                run.prompt,
+                run.model,
+                run.depth,
+                run.status.as_str(),
+                run.result,
+                run.error,
+                run.attempts,
+                run.created_at.to_rfc3339(),
+                run.updated_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Gets a subagent run by its ID.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Some(SubagentRun))` if found, `Ok(None)` if no run has the
+    /// ID, or an error if the database operation fails.
+    pub fn get_subagent_run(&self, id: &str) -> Result<Option<SubagentRun>> {
+        let conn = self.conn.lock().unwrap();
+        let run = conn
+            .query_row(
+                &format!("{} WHERE id = ?", SUBAGENT_RUN_SELECT),
+                params![id],
+                Self::row_to_subagent_run,
+            )
+            .optional()?;
+        Ok(run)
+    }
+
+    /// Lists the subagent runs spawned by a session, oldest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_session_key` - The session key of the spawning session.
+    pub fn list_subagent_runs(&self, parent_session_key: &str) -> Result<Vec<SubagentRun>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE parent_session_key = ? ORDER BY created_at, rowid",
+            SUBAGENT_RUN_SELECT
+        ))?;
+        let runs = stmt
+            .query_map(params![parent_session_key], Self::row_to_subagent_run)?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(runs)
+    }
+
+    /// Lists the subagent runs of all sessions that are still pending or
+    /// running, oldest first.
+    ///
+    /// After a restart these are the runs whose work was interrupted and
+    /// should be resumed.
+    pub fn list_unfinished_subagent_runs(&self) -> Result<Vec<SubagentRun>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE status IN (?, ?) ORDER BY created_at, rowid",
+            SUBAGENT_RUN_SELECT
+        ))?;
+        let runs = stmt
+            .query_map(
+                params![
+                    SubagentStatus::Pending.as_str(),
+                    SubagentStatus::Running.as_str()
+                ],
+                Self::row_to_subagent_run,
+            )?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(runs)
+    }
+
+    /// Converts a database row to a SubagentRun struct.
+    fn row_to_subagent_run(row: &rusqlite::Row) -> SqliteResult<SubagentRun> {
+        let status: String = row.get(6)?;
+        let created_at: String = row.get(10)?;
+        let updated_at: String = row.get(11)?;
+
+        Ok(SubagentRun {
+            id: row.get(0)?,
+            parent_session_key: row.get(1)?,
+            agent_name: row.get(2)?,
+            prompt: row.get(3)?,
+            model: row.get(4)?,
+            depth: row.get(5)?,
+            status: SubagentStatus::parse(&status).unwrap_or(SubagentStatus::Failed),
+            result: row.get(7)?,
+            error: row.get(8)?,
+            attempts: row.get(9)?,
+            created_at: DateTime::parse_from_rfc3339(&created_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            updated_at: DateTime::parse_from_rfc3339(&updated_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        })
+    }
+}
+
+/// The query selecting the columns of a subagent run, in the order read by
+/// `row_to_subagent_run`.
+const SUBAGENT_RUN_SELECT: &str = "SELECT id, parent_session_key, agent_name, prompt, model, \
+     depth, status, result, error, attempts, created_at, updated_at FROM subagent_runs";
+
+impl SessionStore {
+    /// Acquires the lease on a session for `owner`, or renews it if
+    /// `owner` already holds it.
+    ///
+    /// An expired lease of another owner is taken over. Acquiring is a
+    /// single statement, so two instances sharing the database cannot both
+    /// succeed.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_key` - The canonical string of the session's key.
+    /// * `owner` - The identifier of the acquiring instance.
+    /// * `ttl` - How long the lease lasts unless renewed.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Some(SessionLease))` with the held lease, `Ok(None)` if
+    /// another owner holds an unexpired lease, or an error if the database
+    /// operation fails.
+    pub fn try_acquire_lease(
+        &self,
+        session_key: &str,
+        owner: &str,
+        ttl: std::time::Duration,
+    ) -> Result<Option<SessionLease>> {
+        let now = Utc::now().timestamp_millis();
+        let expires_at = now.saturating_add(ttl.as_millis() as i64);
+        let conn = self.conn.lock().unwrap();
+        let acquired = conn.execute(
+            "INSERT INTO session_leases (session_key, owner, acquired_at, expires_at) \
+             VALUES (?1, ?2, ?3, ?4) \
+             ON CONFLICT(session_key) DO UPDATE SET \
+                 acquired_at = CASE WHEN owner = excluded.owner \
+                     THEN acquired_at ELSE excluded.acquired_at END, \
+                 owner = excluded.owner, \
+                 expires_at = excluded.expires_at \
+             WHERE owner = excluded.owner OR expires_at <= ?3",
+            params![session_key, owner, now, expires_at],
+        )?;
+        if acquired == 0 {
+            return Ok(None);
+        }
+        let lease = conn
+            .query_row(
+                &format!("{} WHERE session_key = ?", SESSION_LEASE_SELECT),
+                params![session_key],
+                Self::row_to_session_lease,
+            )
+            .optional()?;
+        Ok(lease)
+    }
+
+    /// Extends a lease held by `owner` by `ttl` from now.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(true)` if the lease was renewed, `Ok(false)` if `owner`
+    /// no longer holds it, or an error if the database operation fails.
+    pub fn renew_lease(
+        &self,
+        session_key: &str,
+        owner: &str,
+        ttl: std::time::Duration,
+    ) -> Result<bool> {
+        let expires_at = Utc::now()
+            .timestamp_millis()
+            .saturating_add(ttl.as_millis() as i64);
+        let renewed = self.conn.lock().unwrap().execute(
+            "UPDATE session_leases SET expires_at = ? WHERE session_key = ? AND owner = ?",
+            params![expires_at, session_key, owner],
+        )?;
+        Ok(renewed > 0)
+    }
+
+    /// Releases a lease held by `owner`, so that any instance may acquire
+    /// the session right away.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(true)` if the lease was released, `Ok(false)` if `owner`
+    /// did not hold it, or an error if the database operation fails.
+    pub fn release_lease(&self, session_key: &str, owner: &str) -> Result<bool> {
+        let released = self.conn.lock().unwrap().execute(
+            "DELETE FROM session_leases WHERE session_key = ? AND owner = ?",
+            params![session_key, owner],
+        )?;
+        Ok(released > 0)
+    }
+
+    /// Gets the lease recorded for a session, which may have expired.
+    pub fn get_lease(&self, session_key: &str) -> Result<Option<SessionLease>> {
+        let conn = self.conn.lock().unwrap();
+        let lease = conn
+            .query_row(
+                &format!("{} WHERE session_key = ?", SESSION_LEASE_SELECT),
+                params![session_key],
+                Self::row_to_session_lease,
+            )
+            .optional()?;
+        Ok(lease)
+    }
+
+    /// Converts a database row to a SessionLease struct.
+    fn row_to_session_lease(row: &rusqlite::Row) -> SqliteResult<SessionLease> {
+        let acquired_at: i64 = row.get(2)?;
+        let expires_at: i64 = row.get(3)?;
+        Ok(SessionLease {
+            session_key: row.get(0)?,
+            owner: row.get(1)?,
+            acquired_at: DateTime::from_timestamp_millis(acquired_at).unwrap_or_default(),
+            expires_at: DateTime::from_timestamp_millis(expires_at).unwrap_or_default(),
+        })
+    }
+}
+
+/// The query selecting the columns of a session lease, in the order read by
+/// `row_to_session_lease`.
+const SESSION_LEASE_SELECT: &str =
+    "SELECT session_key, owner, acquired_at, expires_at FROM session_leases";
+
 #[cfg(test)]
 mod store_tests {
     use super::*;
@@ -2740,4 +3148,49 @@ mod store_tests {
         assert_eq!(history.len(), 1);
         assert_eq!(history[0].role, "tool");
     }
+
+    #[test]
+    fn test_subagent_run_round_trip() {
+        let store = create_test_store();
+        let mut run = SubagentRun::new("run_1", "agent:main", "researcher", "Find X", "gpt-4", 1);
+        store.save_subagent_run(&run).unwrap();
+
+        let loaded = store.get_subagent_run("run_1").unwrap().unwrap();
+        assert_eq!(loaded.status, SubagentStatus::Pending);
+        assert_eq!(loaded.prompt, "Find X");
+        assert_eq!(loaded.depth, 1);
+
+        run.start();
+        run.complete("X is 42");
+        store.save_subagent_run(&run).unwrap();
+
+        let loaded = store.get_subagent_run("run_1").unwrap().unwrap();
+        assert_eq!(loaded.status, SubagentStatus::Completed);
+        assert_eq!(loaded.result.as_deref(), Some("X is 42"));
+        assert_eq!(loaded.attempts, 1);
+        assert!(store.get_subagent_run("missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_list_subagent_runs_by_parent_and_unfinished() {
+        let store = create_test_store();
+        let mut done = SubagentRun::new("run_1", "parent_a", "worker", "one", "m", 1);
+        done.complete("ok");
+        let mut running = SubagentRun::new("run_2", "parent_a", "worker", "two", "m", 1);
+        running.start();
+        let pending = SubagentRun::new("run_3", "parent_b", "worker", "three", "m", 1);
+        for run in [&done, &running, &pending] {
+            store.save_subagent_run(run).unwrap();
+        }
+
+        let ids = |runs: Vec<SubagentRun>| runs.into_iter().map(|r| r.id).collect::<Vec<_>>();
+        assert_eq!(
+            ids(store.list_subagent_runs("parent_a").unwrap()),
+            vec!["run_1", "run_2"]
+        );
+        assert_eq!(
+            ids(store.list_unfinished_subagent_runs().unwrap()),
+            vec!["run_2", "run_3"]
+        );
+    }
 }
@@ -0,0 +1,132 @@
+//! Persisted subagent runs.
+//!
+//! This module provides the record of a subagent spawned by a parent
+//! session. Runs are kept in the session store so that their results can be
+//! queried across turns, and so that work left pending or running by a
+//! crashed or restarted process can be resumed.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The state of a subagent run in its lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubagentStatus {
+    /// The run has been recorded but not started yet.
+    Pending,
+    /// The subagent is running.
+    Running,
+    /// The subagent finished and its result is recorded.
+    Completed,
+    /// The subagent failed and its error is recorded.
+    Failed,
+}
+
+impl SubagentStatus {
+    /// Returns the name under which the status is stored.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubagentStatus::Pending => "pending",
+            SubagentStatus::Running => "running",
+            SubagentStatus::Completed => "completed",
+            SubagentStatus::Failed => "failed",
+        }
+    }
+
+    /// Parses a stored status name, returning `None` for unknown names.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "pending" => Some(SubagentStatus::Pending),
+            "running" => Some(SubagentStatus::Running),
+            "completed" => Some(SubagentStatus::Completed),
+            "failed" => Some(SubagentStatus::Failed),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the run still has work to do.
+    pub fn is_unfinished(&self) -> bool {
+        matches!(self, SubagentStatus::Pending | SubagentStatus::Running)
+    }
+}
+
+/// A subagent spawned by a parent session, with its spawn parameters and
+/// outcome.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubagentRun {
+    /// The unique identifier of the run.
+    pub id: String,
+    /// The session key of the session that spawned the subagent.
+    pub parent_session_key: String,
+    /// The name of the agent to run.
+    pub agent_name: String,
+    /// The prompt given to the subagent.
+    pub prompt: String,
+    /// The model the subagent runs with.
+    pub model: String,
+    /// The spawn depth of the subagent.
+    pub depth: u32,
+    /// The current state of the run.
+    pub status: SubagentStatus,
+    /// The response of the subagent, once completed.
+    pub result: Option<String>,
+    /// The error of the last attempt, if it failed.
+    pub error: Option<String>,
+    /// How many times the subagent has been started.
+    pub attempts: u32,
+    /// When the run was recorded.
+    pub created_at: DateTime<Utc>,
+    /// When the run was last updated.
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SubagentRun {
+    /// Creates a pending run with the given spawn parameters.
+    pub fn new(
+        id: impl Into<String>,
+        parent_session_key: impl Into<String>,
+        agent_name: impl Into<String>,
+        prompt: impl Into<String>,
+        model: impl Into<String>,
+        depth: u32,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: id.into(),
+            parent_session_key: parent_session_key.into(),
+            agent_name: agent_name.into(),
+            prompt: prompt.into(),
+            model: model.into(),
+            depth,
+            status: SubagentStatus::Pending,
+            result: None,
+            error: None,
+            attempts: 0,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Marks the run as started, counting the attempt.
+    pub fn start(&mut self) {
+        self.status = SubagentStatus::Running;
+        self.attempts += 1;
+        self.error = None;
+        self.updated_at = Utc::now();
+    }
+
+    /// Marks the run as completed with the subagent's response.
+    pub fn complete(&mut self, result: impl Into<String>) {
+        self.status = SubagentStatus::Completed;
+        self.result = Some(result.into());
+        self.error = None;
+        self.updated_at = Utc::now();
+    }
+
+    /// Marks the run as failed with the given error.
+    pub fn fail(&mut self, error: impl Into<String>) {
+        self.status = SubagentStatus::Failed;
+        self.error = Some(error.into());
+        self.updated_at = Utc::now();
+    }
+}
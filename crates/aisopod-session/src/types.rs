@@ -236,6 +236,8 @@ impl From<&Session> for SessionSummary {
 ///
 /// All fields are optional. A filter matches a session if all provided
 /// fields match the corresponding session fields. Unset fields are ignored.
+/// `limit` and `offset` page through the results of a store query and are
+/// not considered by [`SessionFilter::matches`].
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SessionFilter {
     /// Filter by agent ID (exact match).
@@ -258,6 +260,10 @@ pub struct SessionFilter {
     pub updated_after: Option<DateTime<Utc>>,
     /// Filter by sessions updated before this time.
     pub updated_before: Option<DateTime<Utc>>,
+    /// Maximum number of sessions to return.
+    pub limit: Option<u32>,
+    /// Number of matching sessions to skip.
+    pub offset: Option<u32>,
 }
 
 impl SessionFilter {
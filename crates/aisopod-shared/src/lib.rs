@@ -1,3 +1,5 @@
 //! # aisopod-shared
 //!
 //! Shared utilities, common types, and helper functions for the aisopod project.
+
+pub mod net;
@@ -0,0 +1,102 @@
+//! Network helpers for fetching untrusted URLs.
+//!
+//! Tools and previews fetch URLs chosen by models or chat peers. Left
+//! unchecked, such a URL can point the gateway at itself, at services on
+//! its private network, or at a cloud metadata endpoint. The
+//! [`PublicAddressResolver`] only hands out public addresses, so a host
+//! name that resolves to a private address cannot be reached, whichever
+//! redirect led to it.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+
+/// Returns true if an address is reachable on the public internet.
+///
+/// Loopback, private, link-local, unique-local, shared (carrier-grade NAT),
+/// unspecified, broadcast and multicast addresses are not public. IPv4
+/// addresses mapped into IPv6 are judged as IPv4.
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_v4(mapped),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let shared = ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64;
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || shared)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let unique_local = (ip.segments()[0] & 0xfe00) == 0xfc00;
+    let link_local = (ip.segments()[0] & 0xffc0) == 0xfe80;
+    !(ip.is_loopback() || ip.is_unspecified() || ip.is_multicast() || unique_local || link_local)
+}
+
+/// A DNS resolver for reqwest that drops non-public addresses.
+///
+/// A name resolving only to non-public addresses fails to resolve. IP
+/// literals in URLs are not resolved by reqwest, so callers check those
+/// with [`is_public_address`] themselves.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PublicAddressResolver;
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_address(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("host '{}' does not resolve to a public address", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_public_address() {
+        for private in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_address(private.parse().unwrap()), "{}", private);
+        }
+        for public in ["8.8.8.8", "1.1.1.1", "2606:4700::1111", "::ffff:8.8.8.8"] {
+            assert!(is_public_address(public.parse().unwrap()), "{}", public);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolver_rejects_loopback_names() {
+        let name: Name = "localhost".parse().unwrap();
+        let error = PublicAddressResolver.resolve(name).await.err().unwrap();
+        assert!(error.to_string().contains("public address"));
+    }
+}
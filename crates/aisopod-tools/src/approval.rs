@@ -397,6 +397,25 @@ impl ApprovalHandler for NoOpApprovalHandler {
     }
 }
 
+/// An approval handler that denies every request.
+///
+/// Used where no operator can be asked, so that operations needing
+/// approval are refused instead of running unchecked.
+#[derive(Clone, Default)]
+pub struct DenyApprovalHandler;
+
+#[async_trait]
+impl ApprovalHandler for DenyApprovalHandler {
+    async fn request_approval(
+        &self,
+        _request: ApprovalRequest,
+    ) -> Result<ApprovalResponse, ApprovalError> {
+        Ok(ApprovalResponse::Denied {
+            reason: "no operator is available to approve this operation".to_string(),
+        })
+    }
+}
+
 /// Error type for approval-related failures.
 #[derive(Debug, thiserror::Error)]
 pub enum ApprovalError {
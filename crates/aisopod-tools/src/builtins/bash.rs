@@ -1,16 +1,19 @@
 //! Built-in bash/shell tool for executing shell commands.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use std::time::Duration;
 
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::Command;
 
 use crate::approval::{is_auto_approved, ApprovalRequest, ApprovalResponse, RiskLevel};
-use crate::{Tool, ToolContext, ToolResult};
+use crate::sandbox::{SandboxConfig, SandboxExecutor};
+use crate::{OutputStream, Tool, ToolContext, ToolOutputSink, ToolResult};
 
 /// A built-in tool that executes shell commands.
 ///
@@ -32,6 +35,14 @@ use crate::{Tool, ToolContext, ToolResult};
 /// - `working_dir`: Optional working directory (overrides default).
 /// - `env`: Optional environment variables as key-value pairs.
 ///
+/// When the context carries an enabled [`SandboxConfig`], the command runs
+/// in a one-shot Docker/Podman container instead of on the host, with the
+/// workspace mounted at `/workspace`. There, `working_dir` must lie inside
+/// the workspace and `timeout` cannot exceed the sandbox's timeout.
+///
+/// Through [`Tool::execute_streaming`], host commands report their stdout
+/// and stderr line by line while they run.
+///
 /// # Example
 ///
 /// ```json
@@ -81,6 +92,7 @@ impl BashTool {
         command_str: &str,
         params: &Value,
         ctx: &ToolContext,
+        output: Option<&ToolOutputSink>,
     ) -> Result<ToolResult> {
         // Extract optional timeout (in seconds)
         let timeout = params
@@ -107,6 +119,20 @@ impl BashTool {
             })
             .unwrap_or_default();
 
+        // Run inside a container when the context enables sandboxing
+        if let Some(config) = ctx.sandbox_config.as_ref().filter(|c| c.enabled) {
+            return self
+                .execute_sandboxed(
+                    command_str,
+                    params,
+                    config,
+                    ctx.workspace_path.as_deref(),
+                    working_dir.as_deref(),
+                    &env_vars,
+                )
+                .await;
+        }
+
         // Build the command
         let mut cmd = Command::new("sh");
         cmd.arg("-c").arg(command_str);
@@ -128,6 +154,10 @@ impl BashTool {
             }
         }
 
+        if let Some(sink) = output {
+            return Self::execute_streamed(cmd, timeout, sink).await;
+        }
+
         // Execute the command
         let output = tokio::time::timeout(timeout, cmd.output())
             .await
@@ -141,9 +171,149 @@ impl BashTool {
         let stdout = String::from_utf8_lossy(&output.stdout);
         let stderr = String::from_utf8_lossy(&output.stderr);
 
-        // Check exit status
-        let exit_code = output.status.code();
+        Ok(Self::format_output(output.status.code(), &stdout, &stderr))
+    }
+
+    /// Validates, approves and executes a command.
+    async fn run(
+        &self,
+        params: Value,
+        ctx: &ToolContext,
+        output: Option<&ToolOutputSink>,
+    ) -> Result<ToolResult> {
+        // Extract command parameter (required)
+        let command_str = params
+            .get("command")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required parameter 'command'"))?;
+
+        // Check for empty command
+        if command_str.trim().is_empty() {
+            return Ok(ToolResult::error("Command cannot be empty"));
+        }
+
+        // Check if command is auto-approved (safe)
+        if is_auto_approved(command_str) {
+            // Command is safe, execute directly
+            return self
+                .execute_command(command_str, &params, ctx, output)
+                .await;
+        }
+
+        // Check if approval handler is available
+        if let Some(approval_handler) = &ctx.approval_handler {
+            // Create approval request
+            let request = ApprovalRequest::new(
+                &ctx.agent_id,
+                format!("Execute bash command: {}", command_str),
+                RiskLevel::Medium,
+            )
+            .with_timeout(Duration::from_secs(60));
+
+            // Request approval
+            let response = approval_handler.request_approval(request).await?;
+
+            // Only proceed if approved
+            match response {
+                ApprovalResponse::Approved => {
+                    self.execute_command(command_str, &params, ctx, output)
+                        .await
+                }
+                ApprovalResponse::Denied { reason } => Ok(ToolResult::error(format!(
+                    "Command execution denied: {}",
+                    reason
+                ))),
+                ApprovalResponse::TimedOut => Ok(ToolResult::error(
+                    "Command execution timed out (approval timeout)",
+                )),
+            }
+        } else {
+            // No approval handler available - execute without approval (backward compatible)
+            self.execute_command(command_str, &params, ctx, output)
+                .await
+        }
+    }
+
+    /// Executes a host command, forwarding its output to `sink` line by line.
+    async fn execute_streamed(
+        mut cmd: Command,
+        timeout: Duration,
+        sink: &ToolOutputSink,
+    ) -> Result<ToolResult> {
+        cmd.stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("Command execution failed: {}", e))?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let stderr = child.stderr.take().expect("stderr is piped");
+
+        let run = async {
+            let (stdout, stderr) = tokio::join!(
+                forward_lines(stdout, OutputStream::Stdout, sink),
+                forward_lines(stderr, OutputStream::Stderr, sink)
+            );
+            let status = child.wait().await?;
+            Ok::<_, std::io::Error>((status, stdout?, stderr?))
+        };
+
+        // On timeout the child is killed when dropped
+        let (status, stdout, stderr) = tokio::time::timeout(timeout, run)
+            .await
+            .map_err(|_| anyhow::anyhow!("Command timed out after {} seconds", timeout.as_secs()))?
+            .map_err(|e| anyhow::anyhow!("Command execution failed: {}", e))?;
+
+        Ok(Self::format_output(status.code(), &stdout, &stderr))
+    }
+
+    /// Executes a command in a one-shot sandbox container.
+    ///
+    /// The context's workspace is mounted at `/workspace` according to the
+    /// sandbox's workspace access level; `working_dir` must lie inside it
+    /// and selects the command's directory under `/workspace`. The `timeout`
+    /// parameter can shorten the sandbox's configured timeout, not extend it.
+    async fn execute_sandboxed(
+        &self,
+        command_str: &str,
+        params: &Value,
+        config: &SandboxConfig,
+        workspace: Option<&Path>,
+        working_dir: Option<&Path>,
+        env_vars: &HashMap<String, String>,
+    ) -> Result<ToolResult> {
+        let Some(workspace) = workspace else {
+            return Ok(ToolResult::error(
+                "Sandboxed execution requires a workspace",
+            ));
+        };
+
+        let mut config = config.clone();
+        if let Some(timeout) = params.get("timeout").and_then(|v| v.as_u64()) {
+            config.timeout = Duration::from_secs(timeout).min(config.timeout);
+        }
+
+        let executor = SandboxExecutor::new(config.runtime.clone());
+        let result = executor
+            .run_one_shot_in(&config, command_str, workspace, working_dir, env_vars)
+            .await?;
+
+        if result.timed_out {
+            return Err(anyhow::anyhow!(
+                "Command timed out after {} seconds",
+                config.timeout.as_secs()
+            ));
+        }
+
+        Ok(Self::format_output(
+            Some(result.exit_code),
+            &result.stdout,
+            &result.stderr,
+        ))
+    }
 
+    /// Converts an exit code and captured output into a tool result.
+    fn format_output(exit_code: Option<i32>, stdout: &str, stderr: &str) -> ToolResult {
         match exit_code {
             Some(0) => {
                 // Success - combine stdout and stderr for output
@@ -152,9 +322,9 @@ impl BashTool {
                     if !content.is_empty() {
                         content.push('\n');
                     }
-                    content.push_str(&stderr);
+                    content.push_str(stderr);
                 }
-                Ok(ToolResult::success(content))
+                ToolResult::success(content)
             }
             Some(code) => {
                 // Non-zero exit code - mark as error
@@ -169,18 +339,37 @@ impl BashTool {
                         error_msg.push_str(&format!("\nstdout:\n{}", stdout));
                     }
                 }
-                Ok(ToolResult::error(error_msg))
+                ToolResult::error(error_msg)
             }
             None => {
                 // Process was terminated by a signal
-                Ok(ToolResult::error(
-                    "Command was terminated by a signal".to_string(),
-                ))
+                ToolResult::error("Command was terminated by a signal".to_string())
             }
         }
     }
 }
 
+/// Reads `reader` to the end, emitting each line to `sink`, and returns all text read.
+async fn forward_lines<R: AsyncRead + Unpin>(
+    reader: R,
+    stream: OutputStream,
+    sink: &ToolOutputSink,
+) -> std::io::Result<String> {
+    let mut reader = BufReader::new(reader);
+    let mut collected = String::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).await? == 0 {
+            break;
+        }
+        let text = String::from_utf8_lossy(&line);
+        sink.emit(stream, text.as_ref());
+        collected.push_str(&text);
+    }
+    Ok(collected)
+}
+
 #[async_trait]
 impl Tool for BashTool {
     fn name(&self) -> &str {
@@ -219,57 +408,16 @@ impl Tool for BashTool {
     }
 
     async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
-        // Extract command parameter (required)
-        let command_str = params
-            .get("command")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow::anyhow!("Missing required parameter 'command'"))?;
-
-        // Check for empty command
-        if command_str.trim().is_empty() {
-            return Ok(ToolResult::error("Command cannot be empty"));
-        }
-
-        // Check if command is auto-approved (safe)
-        if is_auto_approved(command_str) {
-            // Command is safe, execute directly
-            return self.execute_command(command_str, &params, ctx).await;
-        }
-
-        // Check if approval handler is available
-        if let Some(approval_handler) = &ctx.approval_handler {
-            // Create approval request
-            let request = ApprovalRequest::new(
-                &ctx.agent_id,
-                format!("Execute bash command: {}", command_str),
-                RiskLevel::Medium,
-            )
-            .with_timeout(Duration::from_secs(60));
-
-            // Request approval
-            let response = approval_handler.request_approval(request).await?;
+        self.run(params, ctx, None).await
+    }
 
-            // Only proceed if approved
-            match response {
-                ApprovalResponse::Approved => {
-                    return self.execute_command(command_str, &params, ctx).await;
-                }
-                ApprovalResponse::Denied { reason } => {
-                    return Ok(ToolResult::error(format!(
-                        "Command execution denied: {}",
-                        reason
-                    )));
-                }
-                ApprovalResponse::TimedOut => {
-                    return Ok(ToolResult::error(
-                        "Command execution timed out (approval timeout)",
-                    ));
-                }
-            }
-        } else {
-            // No approval handler available - execute without approval (backward compatible)
-            return self.execute_command(command_str, &params, ctx).await;
-        }
+    async fn execute_streaming(
+        &self,
+        params: Value,
+        ctx: &ToolContext,
+        output: &ToolOutputSink,
+    ) -> Result<ToolResult> {
+        self.run(params, ctx, Some(output)).await
     }
 }
 
@@ -441,6 +589,102 @@ mod tests {
         assert!(output.content.contains("stderr"));
     }
 
+    #[tokio::test]
+    async fn test_bash_tool_streams_output() {
+        let tool = BashTool::default();
+        let ctx = ToolContext::new("test_agent", "test_session");
+        let (sink, mut chunks) = ToolOutputSink::channel();
+
+        let output = tool
+            .execute_streaming(
+                json!({"command": "echo one; echo two; echo oops >&2"}),
+                &ctx,
+                &sink,
+            )
+            .await
+            .unwrap();
+        drop(sink);
+
+        assert!(!output.is_error);
+        assert!(output.content.contains("one\ntwo"));
+        assert!(output.content.contains("oops"));
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        while let Some(chunk) = chunks.recv().await {
+            match chunk.stream {
+                OutputStream::Stdout => stdout.push(chunk.text),
+                OutputStream::Stderr => stderr.push(chunk.text),
+            }
+        }
+        assert_eq!(stdout, vec!["one\n", "two\n"]);
+        assert_eq!(stderr, vec!["oops\n"]);
+    }
+
+    #[tokio::test]
+    async fn test_bash_tool_streaming_timeout() {
+        let tool = BashTool::default();
+        let ctx = ToolContext::new("test_agent", "test_session");
+        let (sink, _chunks) = ToolOutputSink::channel();
+
+        let result = tool
+            .execute_streaming(json!({"command": "sleep 5", "timeout": 1}), &ctx, &sink)
+            .await;
+        assert!(result.unwrap_err().to_string().contains("timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_bash_tool_sandbox_requires_workspace() {
+        let tool = BashTool::default();
+        let ctx =
+            ToolContext::new("test_agent", "test_session").with_sandbox_config(SandboxConfig {
+                enabled: true,
+                ..Default::default()
+            });
+
+        let output = tool
+            .execute(json!({"command": "echo hello"}), &ctx)
+            .await
+            .unwrap();
+        assert!(output.is_error);
+        assert!(output.content.contains("workspace"));
+    }
+
+    #[tokio::test]
+    async fn test_bash_tool_sandbox_rejects_working_dir_outside_workspace() {
+        let tool = BashTool::default();
+        let workspace = tempfile::tempdir().unwrap();
+        let ctx = ToolContext::new("test_agent", "test_session")
+            .with_workspace_path(workspace.path())
+            .with_sandbox_config(SandboxConfig {
+                enabled: true,
+                ..Default::default()
+            });
+
+        let error = tool
+            .execute(
+                json!({"command": "cat /workspace/etc/passwd", "working_dir": "/"}),
+                &ctx,
+            )
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("escapes workspace"));
+    }
+
+    #[test]
+    fn test_format_output() {
+        let ok = BashTool::format_output(Some(0), "out", "err");
+        assert!(!ok.is_error);
+        assert_eq!(ok.content, "out\nerr");
+
+        let failed = BashTool::format_output(Some(2), "", "boom");
+        assert!(failed.is_error);
+        assert!(failed.content.contains("exit code 2"));
+        assert!(failed.content.contains("boom"));
+
+        assert!(BashTool::format_output(None, "", "").is_error);
+    }
+
     #[test]
     fn test_bash_tool_name() {
         let tool = BashTool::default();
@@ -0,0 +1,983 @@
+//! Built-in headless browser tool.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::{Component, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use aisopod_config::types::WorkspaceAccess;
+use aisopod_shared::net::is_public_address;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::Engine;
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+use url::{Host, Url};
+
+use crate::approval::{ApprovalRequest, ApprovalResponse, RiskLevel};
+use crate::sandbox::WorkspaceGuard;
+use crate::{Tool, ToolContext, ToolPolicyEngine, ToolResult};
+
+/// Script returning the visible text of the page without navigation chrome.
+const READABLE_TEXT_SCRIPT: &str = r#"
+const root = (document.querySelector('article') || document.querySelector('main') || document.body).cloneNode(true);
+root.querySelectorAll('script, style, noscript, nav, header, footer, aside, svg, iframe').forEach(e => e.remove());
+return [document.title, root.innerText];
+"#;
+
+/// Trait for browser automation backends.
+///
+/// Each method takes the aisopod session key so that implementations can
+/// keep one browser session per conversation.
+#[async_trait]
+pub trait BrowserDriver: Send + Sync {
+    /// Navigates to a URL and waits for the page to load.
+    async fn navigate(&self, session_key: &str, url: &str) -> Result<()>;
+
+    /// Returns the URL of the current page.
+    async fn current_url(&self, session_key: &str) -> Result<String>;
+
+    /// Returns the page title and readable text of the current page.
+    async fn readable_text(&self, session_key: &str) -> Result<(String, String)>;
+
+    /// Captures a PNG screenshot of the current page.
+    async fn screenshot(&self, session_key: &str) -> Result<Vec<u8>>;
+
+    /// Clicks the first element matching a CSS selector.
+    async fn click(&self, session_key: &str, selector: &str) -> Result<()>;
+
+    /// Types a value into the first element matching a CSS selector.
+    async fn fill(&self, session_key: &str, selector: &str, value: &str) -> Result<()>;
+
+    /// Closes the browser session, if any.
+    async fn close(&self, session_key: &str) -> Result<()>;
+}
+
+/// A no-op browser driver for when no browser is configured.
+#[derive(Debug, Clone, Default)]
+pub struct NoOpBrowserDriver;
+
+#[async_trait]
+impl BrowserDriver for NoOpBrowserDriver {
+    async fn navigate(&self, _session_key: &str, _url: &str) -> Result<()> {
+        Err(anyhow::anyhow!("No browser is configured"))
+    }
+
+    async fn current_url(&self, _session_key: &str) -> Result<String> {
+        Err(anyhow::anyhow!("No browser is configured"))
+    }
+
+    async fn readable_text(&self, _session_key: &str) -> Result<(String, String)> {
+        Err(anyhow::anyhow!("No browser is configured"))
+    }
+
+    async fn screenshot(&self, _session_key: &str) -> Result<Vec<u8>> {
+        Err(anyhow::anyhow!("No browser is configured"))
+    }
+
+    async fn click(&self, _session_key: &str, _selector: &str) -> Result<()> {
+        Err(anyhow::anyhow!("No browser is configured"))
+    }
+
+    async fn fill(&self, _session_key: &str, _selector: &str, _value: &str) -> Result<()> {
+        Err(anyhow::anyhow!("No browser is configured"))
+    }
+
+    async fn close(&self, _session_key: &str) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Browser driver speaking the W3C WebDriver protocol.
+///
+/// Works with any WebDriver server such as `chromedriver` or `geckodriver`.
+/// A browser session is created lazily for each aisopod session key.
+pub struct WebDriverBrowser {
+    client: reqwest::Client,
+    endpoint: String,
+    capabilities: Value,
+    sessions: Mutex<HashMap<String, String>>,
+}
+
+impl WebDriverBrowser {
+    /// Creates a driver for the WebDriver server at `endpoint` with the given capabilities.
+    pub fn new(endpoint: impl Into<String>, capabilities: Value) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: endpoint.into().trim_end_matches('/').to_string(),
+            capabilities,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Creates a driver that starts headless Chrome through `chromedriver`.
+    pub fn headless_chrome(endpoint: impl Into<String>) -> Self {
+        Self::new(
+            endpoint,
+            json!({
+                "browserName": "chrome",
+                "goog:chromeOptions": {
+                    "args": ["--headless=new", "--disable-gpu", "--no-sandbox"]
+                }
+            }),
+        )
+    }
+
+    /// Sends a WebDriver command and returns its `value` field.
+    async fn command(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<Value> {
+        let mut request = self
+            .client
+            .request(method, format!("{}{}", self.endpoint, path));
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let response: Value = request
+            .send()
+            .await
+            .with_context(|| format!("WebDriver request to {} failed", path))?
+            .json()
+            .await
+            .context("Invalid WebDriver response")?;
+
+        let value = response.get("value").cloned().unwrap_or(Value::Null);
+        if let Some(error) = value.get("error").and_then(|e| e.as_str()) {
+            let message = value.get("message").and_then(|m| m.as_str()).unwrap_or("");
+            return Err(anyhow::anyhow!("WebDriver error '{}': {}", error, message));
+        }
+        Ok(value)
+    }
+
+    /// Returns the WebDriver session for a session key, creating one if needed.
+    async fn session(&self, session_key: &str) -> Result<String> {
+        let mut sessions = self.sessions.lock().await;
+        if let Some(id) = sessions.get(session_key) {
+            return Ok(id.clone());
+        }
+        let value = self
+            .command(
+                reqwest::Method::POST,
+                "/session",
+                Some(json!({ "capabilities": { "alwaysMatch": self.capabilities } })),
+            )
+            .await?;
+        let id = value
+            .get("sessionId")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("WebDriver did not return a session id"))?
+            .to_string();
+        sessions.insert(session_key.to_string(), id.clone());
+        Ok(id)
+    }
+
+    /// Finds an element by CSS selector and returns its WebDriver reference.
+    async fn find_element(&self, session: &str, selector: &str) -> Result<String> {
+        let value = self
+            .command(
+                reqwest::Method::POST,
+                &format!("/session/{}/element", session),
+                Some(json!({ "using": "css selector", "value": selector })),
+            )
+            .await?;
+        value
+            .as_object()
+            .and_then(|obj| obj.values().next())
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .ok_or_else(|| anyhow::anyhow!("No element matches selector '{}'", selector))
+    }
+}
+
+#[async_trait]
+impl BrowserDriver for WebDriverBrowser {
+    async fn navigate(&self, session_key: &str, url: &str) -> Result<()> {
+        let session = self.session(session_key).await?;
+        self.command(
+            reqwest::Method::POST,
+            &format!("/session/{}/url", session),
+            Some(json!({ "url": url })),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn current_url(&self, session_key: &str) -> Result<String> {
+        let session = self.session(session_key).await?;
+        let value = self
+            .command(
+                reqwest::Method::GET,
+                &format!("/session/{}/url", session),
+                None,
+            )
+            .await?;
+        Ok(value.as_str().unwrap_or_default().to_string())
+    }
+
+    async fn readable_text(&self, session_key: &str) -> Result<(String, String)> {
+        let session = self.session(session_key).await?;
+        let value = self
+            .command(
+                reqwest::Method::POST,
+                &format!("/session/{}/execute/sync", session),
+                Some(json!({ "script": READABLE_TEXT_SCRIPT, "args": [] })),
+            )
+            .await?;
+        let title = value[0].as_str().unwrap_or_default().to_string();
+        let text = value[1].as_str().unwrap_or_default().to_string();
+        Ok((title, text))
+    }
+
+    async fn screenshot(&self, session_key: &str) -> Result<Vec<u8>> {
+        let session = self.session(session_key).await?;
+        let value = self
+            .command(
+                reqwest::Method::GET,
+                &format!("/session/{}/screenshot", session),
+                None,
+            )
+            .await?;
+        let encoded = value
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("WebDriver returned no screenshot data"))?;
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .context("Invalid screenshot encoding")
+    }
+
+    async fn click(&self, session_key: &str, selector: &str) -> Result<()> {
+        let session = self.session(session_key).await?;
+        let element = self.find_element(&session, selector).await?;
+        self.command(
+            reqwest::Method::POST,
+            &format!("/session/{}/element/{}/click", session, element),
+            Some(json!({})),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn fill(&self, session_key: &str, selector: &str, value: &str) -> Result<()> {
+        let session = self.session(session_key).await?;
+        let element = self.find_element(&session, selector).await?;
+        self.command(
+            reqwest::Method::POST,
+            &format!("/session/{}/element/{}/clear", session, element),
+            Some(json!({})),
+        )
+        .await?;
+        self.command(
+            reqwest::Method::POST,
+            &format!("/session/{}/element/{}/value", session, element),
+            Some(json!({ "text": value })),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn close(&self, session_key: &str) -> Result<()> {
+        let session = self.sessions.lock().await.remove(session_key);
+        if let Some(session) = session {
+            self.command(
+                reqwest::Method::DELETE,
+                &format!("/session/{}", session),
+                None,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Page the browser is sent to when it lands somewhere it may not be.
+const BLANK_PAGE: &str = "about:blank";
+
+/// Returns true if a URL's host is the local machine or on a private network.
+///
+/// IP literals are checked directly. Host names count as private when any
+/// address they resolve to is not public; names that do not resolve are
+/// left to the browser, which cannot reach them either.
+async fn is_private_destination(url: &Url) -> bool {
+    let ip = match url.host() {
+        Some(Host::Ipv4(ip)) => IpAddr::V4(ip),
+        Some(Host::Ipv6(ip)) => IpAddr::V6(ip),
+        Some(Host::Domain(name)) => {
+            let name = name.to_ascii_lowercase();
+            if name == "localhost"
+                || name.ends_with(".localhost")
+                || name.ends_with(".local")
+                || name.ends_with(".internal")
+            {
+                return true;
+            }
+            let port = url.port_or_known_default().unwrap_or(80);
+            return match tokio::net::lookup_host((name.as_str(), port)).await {
+                Ok(mut addrs) => addrs.any(|addr| !is_public_address(addr.ip())),
+                Err(_) => false,
+            };
+        }
+        None => return true,
+    };
+    !is_public_address(ip)
+}
+
+/// Collapses runs of blank lines and truncates to `max_chars`.
+fn tidy_text(text: &str, max_chars: usize) -> String {
+    let mut out = String::new();
+    let mut blank = false;
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() {
+            blank = !out.is_empty();
+            continue;
+        }
+        if blank {
+            out.push('\n');
+            blank = false;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    if out.chars().count() > max_chars {
+        out = out.chars().take(max_chars).collect();
+        out.push_str("\n[text truncated]");
+    }
+    out.trim_end().to_string()
+}
+
+/// A built-in tool that drives a headless browser.
+///
+/// # Actions
+///
+/// - `open`: Navigate to `url` and return the page's readable text.
+/// - `text`: Return the readable text of the current page.
+/// - `screenshot`: Save a PNG of the current page to `path` in the workspace.
+/// - `click`: Click the element matching `selector`.
+/// - `fill`: Type `value` into the element matching `selector`.
+/// - `close`: Close the browser session.
+///
+/// # Policy
+///
+/// Navigation honors the attached [`ToolPolicyEngine`] host policies and is
+/// refused when the context's sandbox disables network access. Navigating to
+/// local or private-network hosts requires approval, and is refused without
+/// an approval handler; `click`/`fill` interactions go through the approval
+/// handler when one is present. The page the browser lands on after `open`,
+/// `click` or `fill` is checked again, and left for a blank page if it may
+/// not be shown. Screenshots are only written inside the workspace, and not
+/// at all when the sandbox grants less than read-write workspace access.
+#[derive(Clone)]
+pub struct BrowserTool {
+    driver: Arc<dyn BrowserDriver>,
+    policy: Option<Arc<ToolPolicyEngine>>,
+    /// Maximum number of characters of page text returned.
+    pub max_text_chars: usize,
+}
+
+impl BrowserTool {
+    /// Creates a new BrowserTool with the given driver.
+    pub fn new(driver: Arc<dyn BrowserDriver>) -> Self {
+        Self {
+            driver,
+            policy: None,
+            max_text_chars: 20_000,
+        }
+    }
+
+    /// Creates a BrowserTool without a configured browser.
+    pub fn with_noop_driver() -> Self {
+        Self::new(Arc::new(NoOpBrowserDriver))
+    }
+
+    /// Attaches a policy engine whose host policies are enforced on navigation.
+    pub fn with_policy_engine(mut self, policy: Arc<ToolPolicyEngine>) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Requests approval for a risky action when a handler is available.
+    ///
+    /// Returns an error result if the action was not approved.
+    async fn approve(
+        &self,
+        ctx: &ToolContext,
+        operation: String,
+        risk: RiskLevel,
+    ) -> Result<Option<ToolResult>> {
+        if ctx.approval_handler.is_none() {
+            return Ok(None);
+        }
+        self.require_approval(ctx, operation, risk).await
+    }
+
+    /// Requests approval for an action that may not run unapproved.
+    ///
+    /// Returns an error result if the action was not approved, including
+    /// when no approval handler is available.
+    async fn require_approval(
+        &self,
+        ctx: &ToolContext,
+        operation: String,
+        risk: RiskLevel,
+    ) -> Result<Option<ToolResult>> {
+        let Some(handler) = &ctx.approval_handler else {
+            return Ok(Some(ToolResult::error(format!(
+                "Browser action denied: {} requires approval, but no approval handler is available",
+                operation
+            ))));
+        };
+        let request = ApprovalRequest::new(&ctx.agent_id, operation, risk)
+            .with_timeout(Duration::from_secs(60));
+        match handler.request_approval(request).await? {
+            ApprovalResponse::Approved => Ok(None),
+            ApprovalResponse::Denied { reason } => Ok(Some(ToolResult::error(format!(
+                "Browser action denied: {}",
+                reason
+            )))),
+            ApprovalResponse::TimedOut => Ok(Some(ToolResult::error(
+                "Browser action timed out (approval timeout)",
+            ))),
+        }
+    }
+
+    /// Returns the readable text of the current page as a tool result.
+    async fn page_text(&self, ctx: &ToolContext) -> Result<ToolResult> {
+        let (title, text) = self.driver.readable_text(&ctx.session_key).await?;
+        let url = self.driver.current_url(&ctx.session_key).await?;
+        let text = tidy_text(&text, self.max_text_chars);
+        Ok(
+            ToolResult::success(format!("# {}\n{}\n\n{}", title, url, text))
+                .with_metadata(json!({ "url": url, "title": title })),
+        )
+    }
+
+    /// Checks that the browser may show `url`.
+    ///
+    /// Returns an error result if it may not.
+    async fn check_destination(&self, url: &Url, ctx: &ToolContext) -> Result<Option<ToolResult>> {
+        if url.as_str() == BLANK_PAGE {
+            return Ok(None);
+        }
+        if !matches!(url.scheme(), "http" | "https") {
+            return Ok(Some(ToolResult::error(format!(
+                "Unsupported URL scheme '{}'",
+                url.scheme()
+            ))));
+        }
+        let Some(host) = url.host_str() else {
+            return Ok(Some(ToolResult::error(format!(
+                "URL '{}' has no host",
+                url
+            ))));
+        };
+
+        if let Some(policy) = &self.policy {
+            if let Err(reason) =
+                policy.is_host_allowed(&ctx.agent_id, host.trim_matches(['[', ']']))
+            {
+                return Ok(Some(ToolResult::error(format!(
+                    "Navigation blocked: {}",
+                    reason
+                ))));
+            }
+        }
+        if is_private_destination(url).await {
+            return self
+                .require_approval(
+                    ctx,
+                    format!("Browse to private address {}", url),
+                    RiskLevel::High,
+                )
+                .await;
+        }
+        Ok(None)
+    }
+
+    /// Returns the URL of the current page, if it is a valid URL.
+    async fn current_page(&self, ctx: &ToolContext) -> Result<Option<Url>> {
+        let current = self.driver.current_url(&ctx.session_key).await?;
+        Ok(Url::parse(&current).ok())
+    }
+
+    /// Checks the page the browser landed on, unless it is `approved`.
+    ///
+    /// A page that may not be shown is left for a blank page and an error
+    /// result is returned.
+    async fn check_landing(
+        &self,
+        approved: Option<&Url>,
+        ctx: &ToolContext,
+    ) -> Result<Option<ToolResult>> {
+        let current = self.driver.current_url(&ctx.session_key).await?;
+        let denied = match Url::parse(&current) {
+            Ok(url) if Some(&url) == approved => None,
+            Ok(url) => self.check_destination(&url, ctx).await?,
+            Err(e) => Some(ToolResult::error(format!(
+                "Invalid page URL '{}': {}",
+                current, e
+            ))),
+        };
+        if denied.is_some() {
+            self.driver.navigate(&ctx.session_key, BLANK_PAGE).await?;
+        }
+        Ok(denied)
+    }
+
+    async fn open(&self, url_str: &str, ctx: &ToolContext) -> Result<ToolResult> {
+        let url = match Url::parse(url_str) {
+            Ok(url) => url,
+            Err(e) => {
+                return Ok(ToolResult::error(format!(
+                    "Invalid URL '{}': {}",
+                    url_str, e
+                )))
+            }
+        };
+
+        if let Some(sandbox) = &ctx.sandbox_config {
+            if sandbox.enabled && !sandbox.network_access {
+                return Ok(ToolResult::error(
+                    "Navigation blocked: network access is disabled by the sandbox policy",
+                ));
+            }
+        }
+        if let Some(denied) = self.check_destination(&url, ctx).await? {
+            return Ok(denied);
+        }
+
+        self.driver.navigate(&ctx.session_key, url.as_str()).await?;
+        // Redirects may have led elsewhere
+        if let Some(denied) = self.check_landing(Some(&url), ctx).await? {
+            return Ok(denied);
+        }
+        self.page_text(ctx).await
+    }
+
+    async fn screenshot(&self, params: &Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let Some(workspace) = &ctx.workspace_path else {
+            return Ok(ToolResult::error("Screenshots require a workspace"));
+        };
+        if let Some(sandbox) = &ctx.sandbox_config {
+            if sandbox.enabled && sandbox.workspace_access != WorkspaceAccess::ReadWrite {
+                return Ok(ToolResult::error(
+                    "Screenshot blocked: the sandbox does not allow writing to the workspace",
+                ));
+            }
+        }
+
+        let relative = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                PathBuf::from(format!(
+                    "screenshots/{}.png",
+                    chrono::Utc::now().format("%Y%m%d-%H%M%S%.3f")
+                ))
+            });
+        if relative.is_absolute()
+            || relative
+                .components()
+                .any(|c| !matches!(c, Component::Normal(_)))
+        {
+            return Ok(ToolResult::error(format!(
+                "Screenshot path must be relative to the workspace: {}",
+                relative.display()
+            )));
+        }
+
+        let target = workspace.join(&relative);
+        let parent = target.parent().unwrap_or(workspace);
+        tokio::fs::create_dir_all(parent).await?;
+        let guard = WorkspaceGuard::new(workspace.clone(), WorkspaceAccess::ReadWrite)?;
+        let parent = guard.validate_path(parent)?;
+        let target = parent.join(target.file_name().unwrap_or_default());
+
+        let png = self.driver.screenshot(&ctx.session_key).await?;
+        tokio::fs::write(&target, &png).await?;
+
+        Ok(
+            ToolResult::success(format!("Saved screenshot to {}", relative.display()))
+                .with_metadata(json!({ "path": relative.to_string_lossy(), "bytes": png.len() })),
+        )
+    }
+}
+
+/// Extracts a required string parameter.
+fn required_str<'a>(params: &'a Value, key: &str) -> Result<&'a str> {
+    params
+        .get(key)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing required parameter '{}'", key))
+}
+
+#[async_trait]
+impl Tool for BrowserTool {
+    fn name(&self) -> &str {
+        "browser"
+    }
+
+    fn description(&self) -> &str {
+        "Control a headless browser to read pages, take screenshots, and interact with elements"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["open", "text", "screenshot", "click", "fill", "close"],
+                    "description": "The browser action to perform"
+                },
+                "url": {
+                    "type": "string",
+                    "description": "The URL to open (for open)"
+                },
+                "selector": {
+                    "type": "string",
+                    "description": "CSS selector of the target element (for click and fill)"
+                },
+                "value": {
+                    "type": "string",
+                    "description": "Text to type into the element (for fill)"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Workspace-relative PNG path (for screenshot)"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let action = required_str(&params, "action")?;
+
+        match action {
+            "open" => self.open(required_str(&params, "url")?, ctx).await,
+            "text" => self.page_text(ctx).await,
+            "screenshot" => self.screenshot(&params, ctx).await,
+            "click" => {
+                let selector = required_str(&params, "selector")?;
+                if let Some(denied) = self
+                    .approve(
+                        ctx,
+                        format!("Click element '{}'", selector),
+                        RiskLevel::Medium,
+                    )
+                    .await?
+                {
+                    return Ok(denied);
+                }
+                let before = self.current_page(ctx).await?;
+                self.driver.click(&ctx.session_key, selector).await?;
+                // The click may have navigated
+                if let Some(denied) = self.check_landing(before.as_ref(), ctx).await? {
+                    return Ok(denied);
+                }
+                Ok(ToolResult::success(format!("Clicked '{}'", selector)))
+            }
+            "fill" => {
+                let selector = required_str(&params, "selector")?;
+                let value = required_str(&params, "value")?;
+                if let Some(denied) = self
+                    .approve(
+                        ctx,
+                        format!("Fill element '{}'", selector),
+                        RiskLevel::Medium,
+                    )
+                    .await?
+                {
+                    return Ok(denied);
+                }
+                let before = self.current_page(ctx).await?;
+                self.driver.fill(&ctx.session_key, selector, value).await?;
+                // Filling may have submitted a form
+                if let Some(denied) = self.check_landing(before.as_ref(), ctx).await? {
+                    return Ok(denied);
+                }
+                Ok(ToolResult::success(format!("Filled '{}'", selector)))
+            }
+            "close" => {
+                self.driver.close(&ctx.session_key).await?;
+                Ok(ToolResult::success("Browser session closed"))
+            }
+            other => Ok(ToolResult::error(format!(
+                "Unknown browser action '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approval::{ApprovalError, ApprovalHandler};
+    use crate::policy::HostPolicy;
+    use aisopod_config::types::SandboxConfig;
+
+    #[derive(Default)]
+    struct MockDriver {
+        calls: std::sync::Mutex<Vec<String>>,
+        url: std::sync::Mutex<Option<String>>,
+        /// Where navigations and clicks end up, when not where they were sent
+        lands_on: Option<String>,
+    }
+
+    impl MockDriver {
+        fn landing_on(url: &str) -> Self {
+            Self {
+                lands_on: Some(url.to_string()),
+                ..Default::default()
+            }
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl BrowserDriver for MockDriver {
+        async fn navigate(&self, _session_key: &str, url: &str) -> Result<()> {
+            self.calls.lock().unwrap().push(format!("navigate {}", url));
+            let landed = match &self.lands_on {
+                Some(target) if url != BLANK_PAGE => target.clone(),
+                _ => url.to_string(),
+            };
+            *self.url.lock().unwrap() = Some(landed);
+            Ok(())
+        }
+
+        async fn current_url(&self, _session_key: &str) -> Result<String> {
+            let url = self.url.lock().unwrap().clone();
+            Ok(url.unwrap_or_else(|| BLANK_PAGE.to_string()))
+        }
+
+        async fn readable_text(&self, _session_key: &str) -> Result<(String, String)> {
+            Ok(("Example".to_string(), "Hello\n\n\n\nWorld".to_string()))
+        }
+
+        async fn screenshot(&self, _session_key: &str) -> Result<Vec<u8>> {
+            Ok(b"\x89PNG".to_vec())
+        }
+
+        async fn click(&self, _session_key: &str, selector: &str) -> Result<()> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("click {}", selector));
+            if let Some(target) = &self.lands_on {
+                *self.url.lock().unwrap() = Some(target.clone());
+            }
+            Ok(())
+        }
+
+        async fn fill(&self, _session_key: &str, selector: &str, value: &str) -> Result<()> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("fill {} {}", selector, value));
+            Ok(())
+        }
+
+        async fn close(&self, _session_key: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    struct DenyAll;
+
+    #[async_trait]
+    impl ApprovalHandler for DenyAll {
+        async fn request_approval(
+            &self,
+            _request: ApprovalRequest,
+        ) -> Result<ApprovalResponse, ApprovalError> {
+            Ok(ApprovalResponse::Denied {
+                reason: "not allowed".to_string(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_open_returns_readable_text() {
+        let driver = Arc::new(MockDriver::default());
+        let tool = BrowserTool::new(driver.clone());
+        let ctx = ToolContext::new("agent", "session");
+
+        let result = tool
+            .execute(
+                json!({"action": "open", "url": "https://example.com"}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(!result.is_error);
+        assert!(result.content.contains("# Example"));
+        assert!(result.content.contains("Hello\n\nWorld"));
+        assert_eq!(driver.calls(), vec!["navigate https://example.com/"]);
+    }
+
+    #[tokio::test]
+    async fn test_open_enforces_policies() {
+        let driver = Arc::new(MockDriver::default());
+        let mut engine = ToolPolicyEngine::new();
+        engine.set_global_host_policy(HostPolicy::deny_list(vec!["blocked.com".to_string()]));
+        let tool = BrowserTool::new(driver.clone()).with_policy_engine(Arc::new(engine));
+
+        let ctx = ToolContext::new("agent", "session");
+        let result = tool
+            .execute(
+                json!({"action": "open", "url": "https://blocked.com"}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(result.is_error);
+
+        let result = tool
+            .execute(json!({"action": "open", "url": "file:///etc/passwd"}), &ctx)
+            .await
+            .unwrap();
+        assert!(result.is_error);
+
+        let sandboxed = ToolContext::new("agent", "session").with_sandbox_config(SandboxConfig {
+            enabled: true,
+            network_access: false,
+            ..Default::default()
+        });
+        let result = tool
+            .execute(
+                json!({"action": "open", "url": "https://example.com"}),
+                &sandboxed,
+            )
+            .await
+            .unwrap();
+        assert!(result.is_error);
+        assert!(result.content.contains("sandbox"));
+
+        let guarded = ToolContext::new("agent", "session").with_approval_handler(Arc::new(DenyAll));
+        let result = tool
+            .execute(
+                json!({"action": "open", "url": "http://127.0.0.1:8080/"}),
+                &guarded,
+            )
+            .await
+            .unwrap();
+        assert!(result.is_error);
+        assert!(driver.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_interactions_require_approval() {
+        let driver = Arc::new(MockDriver::default());
+        let tool = BrowserTool::new(driver.clone());
+
+        let guarded = ToolContext::new("agent", "session").with_approval_handler(Arc::new(DenyAll));
+        let result = tool
+            .execute(json!({"action": "click", "selector": "#submit"}), &guarded)
+            .await
+            .unwrap();
+        assert!(result.is_error);
+        assert!(driver.calls().is_empty());
+
+        let ctx = ToolContext::new("agent", "session");
+        tool.execute(
+            json!({"action": "fill", "selector": "#q", "value": "rust"}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(driver.calls(), vec!["fill #q rust"]);
+    }
+
+    #[tokio::test]
+    async fn test_screenshot_written_to_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = BrowserTool::new(Arc::new(MockDriver::default()));
+        let ctx = ToolContext::new("agent", "session").with_workspace_path(dir.path());
+
+        let result = tool
+            .execute(
+                json!({"action": "screenshot", "path": "shots/page.png"}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(!result.is_error);
+        assert_eq!(
+            std::fs::read(dir.path().join("shots/page.png")).unwrap(),
+            b"\x89PNG"
+        );
+
+        let result = tool
+            .execute(
+                json!({"action": "screenshot", "path": "../escape.png"}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(result.is_error);
+    }
+
+    #[tokio::test]
+    async fn test_private_navigation_needs_an_approval_handler() {
+        let driver = Arc::new(MockDriver::default());
+        let tool = BrowserTool::new(driver.clone());
+        let ctx = ToolContext::new("agent", "session");
+
+        for url in [
+            "http://localhost:8080/",
+            "http://10.1.2.3/",
+            "http://100.64.0.1/",
+            "http://[::ffff:127.0.0.1]/",
+            "http://printer.local/",
+        ] {
+            let result = tool
+                .execute(json!({"action": "open", "url": url}), &ctx)
+                .await
+                .unwrap();
+            assert!(result.is_error, "{}", url);
+            assert!(result.content.contains("no approval handler"), "{}", url);
+        }
+        assert!(driver.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_redirect_to_private_address_is_left() {
+        let driver = Arc::new(MockDriver::landing_on("http://169.254.169.254/latest/"));
+        let tool = BrowserTool::new(driver.clone());
+        let ctx = ToolContext::new("agent", "session");
+
+        let result = tool
+            .execute(json!({"action": "open", "url": "http://8.8.8.8/"}), &ctx)
+            .await
+            .unwrap();
+        assert!(result.is_error);
+        assert_eq!(
+            driver.calls(),
+            vec!["navigate http://8.8.8.8/", "navigate about:blank"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_click_landing_is_checked() {
+        let driver = Arc::new(MockDriver::landing_on("file:///etc/passwd"));
+        let tool = BrowserTool::new(driver.clone());
+        let ctx = ToolContext::new("agent", "session");
+
+        let result = tool
+            .execute(json!({"action": "click", "selector": "a"}), &ctx)
+            .await
+            .unwrap();
+        assert!(result.is_error);
+        assert_eq!(driver.calls(), vec!["click a", "navigate about:blank"]);
+        assert_eq!(driver.current_url("session").await.unwrap(), BLANK_PAGE);
+    }
+}
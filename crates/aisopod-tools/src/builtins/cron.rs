@@ -12,6 +12,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tracing::debug;
 
+use super::scheduler::ONE_SHOT_EXPRESSION;
 use crate::{Tool, ToolContext, ToolResult};
 
 /// Represents a scheduled job in the cron system.
@@ -27,6 +28,32 @@ pub struct ScheduledJob {
     pub next_run: Option<DateTime<Utc>>,
     /// Last run time.
     pub last_run: Option<DateTime<Utc>>,
+    /// The agent and session that scheduled the job, if it was scheduled by
+    /// an agent.
+    #[serde(default)]
+    pub owner: Option<JobOwner>,
+}
+
+/// The agent and session a job was scheduled from.
+///
+/// Tool invocations of the job run with the tools, workspace and sandbox
+/// of this agent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobOwner {
+    /// The agent that scheduled the job.
+    pub agent_id: String,
+    /// The session the job was scheduled in.
+    pub session_key: String,
+}
+
+impl JobOwner {
+    /// Creates the owner of jobs scheduled by `agent_id` in `session_key`.
+    pub fn new(agent_id: impl Into<String>, session_key: impl Into<String>) -> Self {
+        Self {
+            agent_id: agent_id.into(),
+            session_key: session_key.into(),
+        }
+    }
 }
 
 /// Trait for managing scheduled jobs.
@@ -40,6 +67,45 @@ pub trait JobScheduler: Send + Sync {
         command: &str,
     ) -> Result<ScheduledJob>;
 
+    /// Schedule a job that runs once at the given time and is then removed.
+    async fn schedule_once(
+        &self,
+        id: &str,
+        run_at: DateTime<Utc>,
+        command: &str,
+    ) -> Result<ScheduledJob> {
+        let _ = (id, run_at, command);
+        Err(anyhow!("This scheduler does not support one-shot jobs"))
+    }
+
+    /// Schedule a new job on behalf of `owner`.
+    async fn schedule_for(
+        &self,
+        owner: &JobOwner,
+        id: &str,
+        cron_expression: &str,
+        command: &str,
+    ) -> Result<ScheduledJob> {
+        let _ = (owner, id, cron_expression, command);
+        Err(anyhow!(
+            "This scheduler does not support jobs scheduled by agents"
+        ))
+    }
+
+    /// Schedule a one-shot job on behalf of `owner`.
+    async fn schedule_once_for(
+        &self,
+        owner: &JobOwner,
+        id: &str,
+        run_at: DateTime<Utc>,
+        command: &str,
+    ) -> Result<ScheduledJob> {
+        let _ = (owner, id, run_at, command);
+        Err(anyhow!(
+            "This scheduler does not support jobs scheduled by agents"
+        ))
+    }
+
     /// List all scheduled jobs.
     async fn list(&self) -> Result<Vec<ScheduledJob>>;
 
@@ -63,6 +129,14 @@ impl NoOpJobScheduler {
             jobs: Arc::new(std::sync::Mutex::new(HashMap::new())),
         }
     }
+
+    fn insert(&self, job: ScheduledJob) -> ScheduledJob {
+        self.jobs
+            .lock()
+            .unwrap()
+            .insert(job.id.clone(), job.clone());
+        job
+    }
 }
 
 #[async_trait]
@@ -78,19 +152,58 @@ impl JobScheduler for NoOpJobScheduler {
 
         let next_run = schedule.upcoming(Utc).next();
 
-        let job = ScheduledJob {
+        Ok(self.insert(ScheduledJob {
             id: id.to_string(),
             cron_expression: cron_expression.to_string(),
             command: command.to_string(),
             next_run,
             last_run: None,
-        };
+            owner: None,
+        }))
+    }
 
-        self.jobs
-            .lock()
-            .unwrap()
-            .insert(id.to_string(), job.clone());
-        Ok(job)
+    async fn schedule_once(
+        &self,
+        id: &str,
+        run_at: DateTime<Utc>,
+        command: &str,
+    ) -> Result<ScheduledJob> {
+        Ok(self.insert(ScheduledJob {
+            id: id.to_string(),
+            cron_expression: ONE_SHOT_EXPRESSION.to_string(),
+            command: command.to_string(),
+            next_run: Some(run_at),
+            last_run: None,
+            owner: None,
+        }))
+    }
+
+    async fn schedule_for(
+        &self,
+        owner: &JobOwner,
+        id: &str,
+        cron_expression: &str,
+        command: &str,
+    ) -> Result<ScheduledJob> {
+        let job = self.schedule(id, cron_expression, command).await?;
+        Ok(self.insert(ScheduledJob {
+            owner: Some(owner.clone()),
+            ..job
+        }))
+    }
+
+    async fn schedule_once_for(
+        &self,
+        owner: &JobOwner,
+        id: &str,
+        run_at: DateTime<Utc>,
+        command: &str,
+    ) -> Result<ScheduledJob> {
+        let job = self.schedule_once(id, run_at, command).await?;
+        Ok(self.insert(ScheduledJob {
+            owner: Some(owner.clone()),
+            ..job
+        }))
     }
 
     async fn list(&self) -> Result<Vec<ScheduledJob>> {
@@ -171,7 +284,12 @@ impl Tool for CronTool {
                 },
                 "cron_expression": {
                     "type": "string",
-                    "description": "Cron expression defining when the job runs (required for 'schedule' operation)"
+                    "description": "Cron expression defining when the job runs (required for 'schedule' operation unless 'delay_seconds' is given)"
+                },
+                "delay_seconds": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Run the job once after this many seconds instead of on a cron schedule ('schedule' operation only)"
                 },
                 "command": {
                     "type": "string",
@@ -186,7 +304,7 @@ impl Tool for CronTool {
         })
     }
 
-    async fn execute(&self, params: Value, _ctx: &ToolContext) -> Result<ToolResult> {
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
         // Extract operation
         let operation = params
             .get("operation")
@@ -194,7 +312,7 @@ impl Tool for CronTool {
             .ok_or_else(|| anyhow!("Missing required parameter 'operation'"))?;
 
         match operation {
-            "schedule" => self.execute_schedule(params).await,
+            "schedule" => self.execute_schedule(params, ctx).await,
             "list" => self.execute_list().await,
             "run" => self.execute_run(params).await,
             "remove" => self.execute_remove(params).await,
@@ -207,13 +325,14 @@ impl Tool for CronTool {
 }
 
 impl CronTool {
-    async fn execute_schedule(&self, params: Value) -> Result<ToolResult> {
-        let cron_expression = params
-            .get("cron_expression")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                anyhow!("Missing required parameter 'cron_expression' for 'schedule' operation")
-            })?;
+    async fn execute_schedule(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let delay_seconds = params.get("delay_seconds").and_then(|v| v.as_u64());
+        let cron_expression = params.get("cron_expression").and_then(|v| v.as_str());
+        if cron_expression.is_none() && delay_seconds.is_none() {
+            return Err(anyhow!(
+                "Missing required parameter 'cron_expression' for 'schedule' operation"
+            ));
+        }
 
         let command = params
             .get("command")
@@ -222,8 +341,8 @@ impl CronTool {
                 anyhow!("Missing required parameter 'command' for 'schedule' operation")
             })?;
 
-        // Validate cron expression
-        self.validate_cron_expression(cron_expression)?;
+        // Jobs run their tool invocations as the agent scheduling them
+        let owner = JobOwner::new(&ctx.agent_id, &ctx.session_key);
 
         // Generate a job ID (could be improved with UUID)
         let job_id = format!(
@@ -231,6 +350,30 @@ impl CronTool {
             chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0)
         );
 
+        if let Some(delay) = delay_seconds {
+            let delay = chrono::Duration::seconds(i64::try_from(delay).unwrap_or(i64::MAX));
+            let run_at = Utc::now()
+                .checked_add_signed(delay)
+                .ok_or_else(|| anyhow!("'delay_seconds' is too large"))?;
+            debug!("Scheduling one-shot job '{}' at {}", job_id, run_at);
+
+            let job = self
+                .scheduler
+                .schedule_once_for(&owner, &job_id, run_at, command)
+                .await?;
+
+            return Ok(ToolResult::success(format!(
+                "Job '{}' scheduled successfully.\nRuns once at: {}\nCommand: {}",
+                job.id,
+                run_at.to_rfc3339(),
+                job.command
+            )));
+        }
+
+        // Validate cron expression
+        let cron_expression = cron_expression.unwrap_or_default();
+        self.validate_cron_expression(cron_expression)?;
+
         debug!(
             "Scheduling job '{}' with expression '{}'",
             job_id, cron_expression
@@ -238,7 +381,7 @@ impl CronTool {
 
         let job = self
             .scheduler
-            .schedule(&job_id, cron_expression, command)
+            .schedule_for(&owner, &job_id, cron_expression, command)
             .await?;
 
         Ok(ToolResult::success(format!(
@@ -390,6 +533,36 @@ mod tests {
         assert!(err.contains("Invalid cron expression"));
     }
 
+    #[tokio::test]
+    async fn test_schedule_one_shot_job() {
+        let scheduler = Arc::new(NoOpJobScheduler::new());
+        let tool = CronTool::new(scheduler.clone());
+        let ctx = ToolContext::new("test_agent", "test_session");
+
+        let output = tool
+            .execute(
+                json!({
+                    "operation": "schedule",
+                    "delay_seconds": 120,
+                    "command": "echo later"
+                }),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(!output.is_error);
+        assert!(output.content.contains("Runs once at"));
+
+        let jobs = scheduler.list().await.unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].cron_expression, ONE_SHOT_EXPRESSION);
+        assert!(jobs[0].next_run.unwrap() > Utc::now());
+        assert_eq!(
+            jobs[0].owner,
+            Some(JobOwner::new("test_agent", "test_session"))
+        );
+    }
+
     #[tokio::test]
     async fn test_list_jobs() {
         let tool = CronTool::with_noop_scheduler();
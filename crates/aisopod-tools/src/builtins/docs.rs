@@ -0,0 +1,531 @@
+//! Built-in documentation search tool.
+
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+use aisopod_config::types::DocsToolConfig;
+use aisopod_memory::EmbeddingProvider;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+use walkdir::WalkDir;
+
+use crate::{Tool, ToolContext, ToolResult};
+
+/// Number of chunks embedded per provider request.
+const EMBED_BATCH_SIZE: usize = 64;
+
+/// A section of an indexed document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocChunk {
+    /// File path or URL the chunk was read from.
+    pub source: String,
+    /// Nearest Markdown heading at the start of the chunk, if any.
+    pub heading: Option<String>,
+    /// First line of the chunk (1-based).
+    pub start_line: usize,
+    /// Last line of the chunk (inclusive).
+    pub end_line: usize,
+    /// Chunk text.
+    pub text: String,
+}
+
+/// Returns the title of a Markdown ATX heading line.
+fn heading_title(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    let title = trimmed.trim_start_matches('#');
+    let level = trimmed.len() - title.len();
+    if (1..=6).contains(&level) && (title.is_empty() || title.starts_with(' ')) {
+        Some(title.trim().trim_end_matches('#').trim())
+    } else {
+        None
+    }
+}
+
+/// Splits a document into chunks along line boundaries.
+///
+/// Chunks hold roughly `chunk_size` characters, start a new chunk at each
+/// Markdown heading, and repeat up to `overlap` characters of trailing lines
+/// from the previous chunk so passages spanning a boundary stay searchable.
+pub fn chunk_document(
+    source: &str,
+    text: &str,
+    chunk_size: usize,
+    overlap: usize,
+) -> Vec<DocChunk> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut headings = Vec::with_capacity(lines.len());
+    let mut current: Option<String> = None;
+    for line in &lines {
+        if let Some(title) = heading_title(line) {
+            current = Some(title.to_string());
+        }
+        headings.push(current.clone());
+    }
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < lines.len() {
+        while start < lines.len() && lines[start].trim().is_empty() {
+            start += 1;
+        }
+        if start == lines.len() {
+            break;
+        }
+
+        let mut end = start;
+        let mut size = 0;
+        while end < lines.len() {
+            let line = lines[end];
+            let at_heading = end > start && heading_title(line).is_some();
+            if end > start && (at_heading || size + line.len() + 1 > chunk_size) {
+                break;
+            }
+            size += line.len() + 1;
+            end += 1;
+        }
+
+        let text = lines[start..end].join("\n").trim_end().to_string();
+        let mut last = end;
+        while last > start && lines[last - 1].trim().is_empty() {
+            last -= 1;
+        }
+        chunks.push(DocChunk {
+            source: source.to_string(),
+            heading: headings[start].clone(),
+            start_line: start + 1,
+            end_line: last,
+            text,
+        });
+
+        if end == lines.len() || heading_title(lines[end]).is_some() {
+            start = end;
+            continue;
+        }
+        let mut next = end;
+        let mut carried = 0;
+        while next > start + 1 && carried + lines[next - 1].len() < overlap {
+            next -= 1;
+            carried += lines[next].len() + 1;
+        }
+        start = next;
+    }
+    chunks
+}
+
+/// Converts an HTML page into plain text, keeping headings in Markdown form.
+fn html_to_text(html: &str) -> String {
+    static SKIPPED: OnceLock<Regex> = OnceLock::new();
+    static HEADING: OnceLock<Regex> = OnceLock::new();
+    static BREAK: OnceLock<Regex> = OnceLock::new();
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    let skipped = SKIPPED.get_or_init(|| {
+        Regex::new(r"(?is)<script[^>]*>.*?</script>|<style[^>]*>.*?</style>|<head[^>]*>.*?</head>")
+            .unwrap()
+    });
+    let heading = HEADING.get_or_init(|| Regex::new(r"(?i)<h([1-6])[^>]*>").unwrap());
+    let line_break = BREAK.get_or_init(|| {
+        Regex::new(r"(?i)<(br|/p|/div|/li|/h[1-6]|/tr|/pre|/blockquote)[^>]*>").unwrap()
+    });
+    let tag = TAG.get_or_init(|| Regex::new(r"<[^>]+>").unwrap());
+
+    let text = skipped.replace_all(html, "");
+    let text = heading.replace_all(&text, |caps: &Captures| {
+        let level: usize = caps[1].parse().unwrap_or(1);
+        format!("\n{} ", "#".repeat(level))
+    });
+    let text = line_break.replace_all(&text, "\n");
+    let text = tag.replace_all(&text, "");
+    let text = text
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&");
+
+    let mut out = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() && out.last().is_none_or(|l: &&str| l.is_empty()) {
+            continue;
+        }
+        out.push(line);
+    }
+    out.join("\n").trim_end().to_string()
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Indexed chunks and their embeddings.
+#[derive(Default)]
+struct DocsIndex {
+    chunks: Vec<DocChunk>,
+    embeddings: Vec<Vec<f32>>,
+    indexed: bool,
+}
+
+/// A built-in tool that searches project documentation.
+///
+/// Directories, files and http(s) URLs listed as sources are split into
+/// chunks and embedded with the configured [`EmbeddingProvider`] the first
+/// time the tool is used. Queries return the most similar chunks.
+///
+/// # Parameters
+///
+/// - `query`: What to look for (required).
+/// - `max_results`: Optional number of excerpts, capped by the configured maximum.
+/// - `reindex`: Re-read all sources before searching.
+///
+/// The content lists each excerpt with its source and line range. The
+/// same locations are returned as `metadata.citations` so answers can cite
+/// the documentation they are based on.
+#[derive(Clone)]
+pub struct DocsTool {
+    embedder: Arc<dyn EmbeddingProvider>,
+    sources: Vec<String>,
+    extensions: Vec<String>,
+    chunk_size: usize,
+    chunk_overlap: usize,
+    max_results: usize,
+    client: reqwest::Client,
+    index: Arc<RwLock<DocsIndex>>,
+}
+
+impl DocsTool {
+    /// Creates a DocsTool indexing `sources` with the given embedder.
+    pub fn new(embedder: Arc<dyn EmbeddingProvider>, sources: Vec<String>) -> Self {
+        let defaults = DocsToolConfig::default();
+        Self {
+            embedder,
+            sources,
+            extensions: defaults.extensions,
+            chunk_size: defaults.chunk_size,
+            chunk_overlap: defaults.chunk_overlap,
+            max_results: defaults.max_results,
+            client: reqwest::Client::new(),
+            index: Arc::new(RwLock::new(DocsIndex::default())),
+        }
+    }
+
+    /// Creates a DocsTool from the tools configuration.
+    pub fn from_config(config: &DocsToolConfig, embedder: Arc<dyn EmbeddingProvider>) -> Self {
+        Self::new(embedder, config.sources.clone())
+            .with_extensions(config.extensions.clone())
+            .with_chunking(config.chunk_size, config.chunk_overlap)
+            .with_max_results(config.max_results)
+    }
+
+    /// Sets the file extensions indexed when walking directories.
+    pub fn with_extensions(mut self, extensions: Vec<String>) -> Self {
+        self.extensions = extensions;
+        self
+    }
+
+    /// Sets the target chunk size and overlap, in characters.
+    pub fn with_chunking(mut self, chunk_size: usize, chunk_overlap: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self.chunk_overlap = chunk_overlap.min(self.chunk_size / 2);
+        self
+    }
+
+    /// Sets the maximum number of results returned.
+    pub fn with_max_results(mut self, max_results: usize) -> Self {
+        self.max_results = max_results.max(1);
+        self
+    }
+
+    /// Re-reads and re-embeds all sources, returning the number of chunks.
+    pub async fn reindex(&self) -> Result<usize> {
+        let index = self.build_index().await?;
+        let count = index.chunks.len();
+        *self.index.write().await = index;
+        Ok(count)
+    }
+
+    /// Returns the chunks most similar to `query`, with their scores.
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<(DocChunk, f32)>> {
+        {
+            let mut index = self.index.write().await;
+            if !index.indexed {
+                *index = self.build_index().await?;
+            }
+        }
+
+        let query_embedding = self.embedder.embed(query).await?;
+        let index = self.index.read().await;
+        let mut scored: Vec<(usize, f32)> = index
+            .embeddings
+            .iter()
+            .enumerate()
+            .map(|(i, embedding)| (i, cosine_similarity(&query_embedding, embedding)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ok(scored
+            .into_iter()
+            .take(limit)
+            .map(|(i, score)| (index.chunks[i].clone(), score))
+            .collect())
+    }
+
+    async fn build_index(&self) -> Result<DocsIndex> {
+        let mut chunks = Vec::new();
+        for source in &self.sources {
+            match self.load_source(source).await {
+                Ok(documents) => {
+                    for (name, text) in documents {
+                        chunks.extend(chunk_document(
+                            &name,
+                            &text,
+                            self.chunk_size,
+                            self.chunk_overlap,
+                        ));
+                    }
+                }
+                Err(e) => warn!("Skipping documentation source '{}': {:#}", source, e),
+            }
+        }
+
+        let mut embeddings = Vec::with_capacity(chunks.len());
+        for batch in chunks.chunks(EMBED_BATCH_SIZE) {
+            let texts: Vec<String> = batch
+                .iter()
+                .map(|chunk| match &chunk.heading {
+                    Some(heading) => format!("{}\n{}", heading, chunk.text),
+                    None => chunk.text.clone(),
+                })
+                .collect();
+            let refs: Vec<&str> = texts.iter().map(String::as_str).collect();
+            embeddings.extend(
+                self.embedder
+                    .embed_batch(&refs)
+                    .await
+                    .context("Failed to embed documentation")?,
+            );
+        }
+        debug!(
+            "Indexed {} documentation chunks from {} sources",
+            chunks.len(),
+            self.sources.len()
+        );
+
+        Ok(DocsIndex {
+            chunks,
+            embeddings,
+            indexed: true,
+        })
+    }
+
+    /// Reads a source into `(name, text)` documents.
+    async fn load_source(&self, source: &str) -> Result<Vec<(String, String)>> {
+        if source.starts_with("http://") || source.starts_with("https://") {
+            let response = self.client.get(source).send().await?.error_for_status()?;
+            let is_html = response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| v.contains("html"));
+            let body = response.text().await?;
+            let text = if is_html { html_to_text(&body) } else { body };
+            return Ok(vec![(source.to_string(), text)]);
+        }
+
+        let path = Path::new(source);
+        if path.is_file() {
+            let text = tokio::fs::read_to_string(path).await?;
+            return Ok(vec![(source.to_string(), text)]);
+        }
+        if !path.is_dir() {
+            anyhow::bail!("not a file, directory or URL");
+        }
+
+        let mut documents = Vec::new();
+        for entry in WalkDir::new(path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let matches = entry
+                .path()
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| self.extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)));
+            if !matches {
+                continue;
+            }
+            match tokio::fs::read_to_string(entry.path()).await {
+                Ok(text) => documents.push((entry.path().display().to_string(), text)),
+                Err(e) => warn!("Skipping '{}': {}", entry.path().display(), e),
+            }
+        }
+        Ok(documents)
+    }
+}
+
+#[async_trait]
+impl Tool for DocsTool {
+    fn name(&self) -> &str {
+        "docs"
+    }
+
+    fn description(&self) -> &str {
+        "Search the project documentation and return relevant excerpts with their sources"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "What to look for in the documentation"
+                },
+                "max_results": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Optional number of excerpts to return"
+                },
+                "reindex": {
+                    "type": "boolean",
+                    "description": "Re-read the documentation sources before searching"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, params: Value, _ctx: &ToolContext) -> Result<ToolResult> {
+        let query = params
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required parameter 'query'"))?;
+
+        if query.trim().is_empty() {
+            return Ok(ToolResult::error("Query cannot be empty"));
+        }
+
+        let limit = params
+            .get("max_results")
+            .and_then(|v| v.as_u64())
+            .map(|n| (n as usize).clamp(1, self.max_results))
+            .unwrap_or(self.max_results);
+
+        if params.get("reindex").and_then(|v| v.as_bool()) == Some(true) {
+            if let Err(e) = self.reindex().await {
+                return Ok(ToolResult::error(format!(
+                    "Failed to index documentation: {:#}",
+                    e
+                )));
+            }
+        }
+
+        let matches = match self.search(query.trim(), limit).await {
+            Ok(matches) => matches,
+            Err(e) => {
+                return Ok(ToolResult::error(format!(
+                    "Documentation search failed: {:#}",
+                    e
+                )))
+            }
+        };
+        if matches.is_empty() {
+            return Ok(ToolResult::error(
+                "No documentation is indexed; check the configured sources",
+            ));
+        }
+
+        let content = matches
+            .iter()
+            .enumerate()
+            .map(|(i, (chunk, _))| {
+                let mut entry = format!(
+                    "[{}] {}:{}-{}",
+                    i + 1,
+                    chunk.source,
+                    chunk.start_line,
+                    chunk.end_line
+                );
+                if let Some(heading) = &chunk.heading {
+                    entry.push_str(&format!(" ({})", heading));
+                }
+                format!("{}\n{}", entry, chunk.text)
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let citations: Vec<Value> = matches
+            .iter()
+            .enumerate()
+            .map(|(i, (chunk, score))| {
+                json!({
+                    "rank": i + 1,
+                    "source": chunk.source,
+                    "heading": chunk.heading,
+                    "start_line": chunk.start_line,
+                    "end_line": chunk.end_line,
+                    "score": score,
+                })
+            })
+            .collect();
+
+        Ok(ToolResult::success(content).with_metadata(json!({
+            "query": query,
+            "citations": citations,
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_document_splits_at_headings() {
+        let text = "# Intro\nWelcome.\n\n# Install\nRun cargo install.\nThen configure.\n";
+        let chunks = chunk_document("guide.md", text, 1000, 100);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].heading.as_deref(), Some("Intro"));
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (1, 2));
+        assert_eq!(chunks[1].heading.as_deref(), Some("Install"));
+        assert_eq!((chunks[1].start_line, chunks[1].end_line), (4, 6));
+        assert_eq!(
+            chunks[1].text,
+            "# Install\nRun cargo install.\nThen configure."
+        );
+    }
+
+    #[test]
+    fn test_chunk_document_overlaps_long_sections() {
+        let text = (1..=10)
+            .map(|i| format!("line number {:02}", i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let chunks = chunk_document("notes.txt", &text, 50, 15);
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks[0].start_line, 1);
+        assert_eq!(chunks.last().unwrap().end_line, 10);
+        for pair in chunks.windows(2) {
+            // Each chunk repeats the last line of the previous one.
+            assert_eq!(pair[1].start_line, pair[0].end_line);
+        }
+    }
+
+    #[test]
+    fn test_html_to_text() {
+        let html = "<html><head><title>x</title></head><body><h2>Setup</h2>\
+                    <p>Use <code>a &amp; b</code>.</p><script>var x;</script></body></html>";
+        assert_eq!(html_to_text(html), "## Setup\nUse a & b.");
+    }
+}
@@ -0,0 +1,569 @@
+//! Built-in git repository tool.
+
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tokio::process::Command;
+
+use crate::approval::{ApprovalRequest, ApprovalResponse, RiskLevel};
+use crate::sandbox::{SandboxExecutor, WorkspaceAccess, WorkspaceGuard};
+use crate::{Tool, ToolContext, ToolResult};
+
+/// URL schemes accepted for `clone`.
+///
+/// Transports such as `ext::` or `file://` would let a repository URL run
+/// commands or read outside the workspace, so they are rejected.
+const ALLOWED_SCHEMES: &[&str] = &["https://", "http://", "ssh://", "git://"];
+
+/// A built-in tool that manages git repositories inside the workspace.
+///
+/// The tool exposes a fixed set of operations instead of a shell, so agents
+/// can work with repositories without raw command execution:
+///
+/// - `clone`: clones a remote repository into the workspace.
+/// - `status`: shows the working tree status.
+/// - `diff`: shows unstaged or staged changes.
+/// - `log`: shows recent commits.
+/// - `branch`: lists, creates or switches branches.
+/// - `commit`: stages and commits changes, after approval.
+///
+/// Every repository path is resolved against the context's workspace and
+/// may not escape it. When the context carries an enabled sandbox
+/// configuration, git runs in a one-shot container with the workspace
+/// mounted at `/workspace`; otherwise it runs on the host.
+#[derive(Debug, Clone)]
+pub struct GitTool {
+    /// Maximum duration of a single git operation.
+    pub timeout: Duration,
+    /// Maximum number of output characters returned to the model.
+    pub max_output_chars: usize,
+    /// Optional `(name, email)` identity used for commits.
+    pub identity: Option<(String, String)>,
+}
+
+impl Default for GitTool {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(120),
+            max_output_chars: 50_000,
+            identity: None,
+        }
+    }
+}
+
+impl GitTool {
+    /// Creates a new GitTool with the given operation timeout.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the identity recorded as author and committer of commits.
+    ///
+    /// Without an identity, the repository's own git configuration is used.
+    pub fn with_identity(mut self, name: impl Into<String>, email: impl Into<String>) -> Self {
+        self.identity = Some((name.into(), email.into()));
+        self
+    }
+
+    /// Builds the git invocations for an operation.
+    ///
+    /// Returns the commands to run in order, or a message describing the
+    /// invalid parameters.
+    fn build_commands(
+        &self,
+        operation: &str,
+        params: &Value,
+    ) -> std::result::Result<Vec<Vec<String>>, String> {
+        let str_param = |name: &str| params.get(name).and_then(|v| v.as_str());
+        let bool_param = |name: &str| params.get(name).and_then(|v| v.as_bool()) == Some(true);
+        let paths: Vec<String> = params
+            .get("paths")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        for path in &paths {
+            check_relative(path)?;
+        }
+
+        let git = |args: &[&str]| -> Vec<String> {
+            std::iter::once("git")
+                .chain(args.iter().copied())
+                .map(String::from)
+                .collect()
+        };
+        let with_paths = |mut cmd: Vec<String>| -> Vec<String> {
+            if !paths.is_empty() {
+                cmd.push("--".to_string());
+                cmd.extend(paths.iter().cloned());
+            }
+            cmd
+        };
+
+        match operation {
+            "clone" => {
+                let url = str_param("url").ok_or("The 'url' parameter is required for clone")?;
+                check_url(url)?;
+                let mut cmd = git(&["clone"]);
+                if let Some(branch) = str_param("branch") {
+                    check_ref_name(branch)?;
+                    cmd.extend(["--branch".to_string(), branch.to_string()]);
+                }
+                if let Some(depth) = params.get("depth").and_then(|v| v.as_u64()) {
+                    cmd.push(format!("--depth={}", depth));
+                }
+                cmd.extend(["--".to_string(), url.to_string()]);
+                if let Some(dest) = str_param("dest") {
+                    check_relative(dest)?;
+                    cmd.push(dest.to_string());
+                }
+                Ok(vec![cmd])
+            }
+            "status" => Ok(vec![with_paths(git(&["status", "--short", "--branch"]))]),
+            "diff" => {
+                let mut cmd = git(&["--no-pager", "diff", "--no-color"]);
+                if bool_param("staged") {
+                    cmd.push("--staged".to_string());
+                }
+                if let Some(rev) = str_param("revision") {
+                    check_ref_name(rev)?;
+                    cmd.push(rev.to_string());
+                }
+                Ok(vec![with_paths(cmd)])
+            }
+            "log" => {
+                let max_count = params
+                    .get("max_count")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(20);
+                let mut cmd = git(&[
+                    "--no-pager",
+                    "log",
+                    "--no-color",
+                    "--date=short",
+                    "--format=%h %ad %an: %s",
+                ]);
+                cmd.push(format!("--max-count={}", max_count));
+                if let Some(rev) = str_param("revision") {
+                    check_ref_name(rev)?;
+                    cmd.push(rev.to_string());
+                }
+                Ok(vec![with_paths(cmd)])
+            }
+            "branch" => {
+                let Some(name) = str_param("name") else {
+                    return Ok(vec![git(&["branch", "--list", "--all", "--no-color"])]);
+                };
+                check_ref_name(name)?;
+                let cmd = if bool_param("checkout") {
+                    if bool_param("create") {
+                        git(&["checkout", "-b", name])
+                    } else {
+                        git(&["checkout", name, "--"])
+                    }
+                } else {
+                    git(&["branch", "--", name])
+                };
+                Ok(vec![cmd])
+            }
+            "commit" => {
+                let message = str_param("message")
+                    .filter(|m| !m.trim().is_empty())
+                    .ok_or("The 'message' parameter is required for commit")?;
+                let mut commands = Vec::new();
+                if bool_param("all") {
+                    commands.push(git(&["add", "--all"]));
+                } else if !paths.is_empty() {
+                    commands.push(with_paths(git(&["add"])));
+                }
+                let mut cmd = vec!["git".to_string()];
+                if let Some((name, email)) = &self.identity {
+                    cmd.extend([
+                        "-c".to_string(),
+                        format!("user.name={}", name),
+                        "-c".to_string(),
+                        format!("user.email={}", email),
+                    ]);
+                }
+                cmd.extend([
+                    "commit".to_string(),
+                    "--message".to_string(),
+                    message.to_string(),
+                ]);
+                commands.push(cmd);
+                Ok(commands)
+            }
+            other => Err(format!("Unknown git operation '{}'", other)),
+        }
+    }
+
+    /// Runs the commands in order on the host, stopping at the first failure.
+    async fn run_on_host(
+        &self,
+        commands: &[Vec<String>],
+        dir: &Path,
+    ) -> Result<(i32, String, String)> {
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        for args in commands {
+            let mut cmd = Command::new(&args[0]);
+            cmd.args(&args[1..])
+                .current_dir(dir)
+                .env("GIT_TERMINAL_PROMPT", "0")
+                .stdin(Stdio::null())
+                .kill_on_drop(true);
+            let output = tokio::time::timeout(self.timeout, cmd.output())
+                .await
+                .map_err(|_| {
+                    anyhow::anyhow!("git timed out after {} seconds", self.timeout.as_secs())
+                })?
+                .map_err(|e| anyhow::anyhow!("Failed to run git: {}", e))?;
+            stdout.push_str(&String::from_utf8_lossy(&output.stdout));
+            stderr.push_str(&String::from_utf8_lossy(&output.stderr));
+            let code = output.status.code().unwrap_or(-1);
+            if code != 0 {
+                return Ok((code, stdout, stderr));
+            }
+        }
+        Ok((0, stdout, stderr))
+    }
+
+    /// Truncates captured output to the configured limit.
+    fn truncate(&self, text: &str) -> String {
+        if text.chars().count() <= self.max_output_chars {
+            return text.to_string();
+        }
+        let mut out: String = text.chars().take(self.max_output_chars).collect();
+        out.push_str("\n[output truncated]");
+        out
+    }
+}
+
+/// Rejects paths that are absolute or climb out of their base directory.
+fn check_relative(path: &str) -> std::result::Result<(), String> {
+    let escapes = Path::new(path)
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+    if escapes || path.starts_with('-') {
+        return Err(format!(
+            "Path '{}' must be relative to the repository",
+            path
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects refs that git could mistake for options.
+fn check_ref_name(name: &str) -> std::result::Result<(), String> {
+    if name.is_empty() || name.starts_with('-') || name.chars().any(char::is_whitespace) {
+        return Err(format!("Invalid revision or branch name '{}'", name));
+    }
+    Ok(())
+}
+
+/// Accepts remote URLs using an allowed scheme or the scp-like `user@host:path` form.
+fn check_url(url: &str) -> std::result::Result<(), String> {
+    let scp_like = !url.contains("://")
+        && url
+            .split_once(':')
+            .is_some_and(|(host, _)| host.contains('@') && !host.contains('/'));
+    if url.starts_with('-') || !(ALLOWED_SCHEMES.iter().any(|s| url.starts_with(s)) || scp_like) {
+        return Err(format!(
+            "Unsupported repository URL '{}'; use https, ssh or git",
+            url
+        ));
+    }
+    Ok(())
+}
+
+/// Quotes an argument for a POSIX shell.
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+#[async_trait]
+impl Tool for GitTool {
+    fn name(&self) -> &str {
+        "git"
+    }
+
+    fn description(&self) -> &str {
+        "Manage git repositories in the workspace (clone, status, diff, log, branch, commit)"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "enum": ["clone", "status", "diff", "log", "branch", "commit"],
+                    "description": "The git operation to perform"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Repository directory relative to the workspace (default: workspace root)"
+                },
+                "url": {
+                    "type": "string",
+                    "description": "Remote repository URL (clone)"
+                },
+                "dest": {
+                    "type": "string",
+                    "description": "Directory to clone into, relative to 'path' (clone)"
+                },
+                "depth": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Create a shallow clone with this many commits (clone)"
+                },
+                "branch": {
+                    "type": "string",
+                    "description": "Branch to check out after cloning (clone)"
+                },
+                "revision": {
+                    "type": "string",
+                    "description": "Revision or range to show (diff, log)"
+                },
+                "staged": {
+                    "type": "boolean",
+                    "description": "Show staged instead of unstaged changes (diff)"
+                },
+                "max_count": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Maximum number of commits to show (log, default: 20)"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Branch name; lists branches when omitted (branch)"
+                },
+                "create": {
+                    "type": "boolean",
+                    "description": "Create the branch before checking it out (branch)"
+                },
+                "checkout": {
+                    "type": "boolean",
+                    "description": "Switch to the branch (branch)"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "Commit message (commit)"
+                },
+                "all": {
+                    "type": "boolean",
+                    "description": "Stage all changes before committing (commit)"
+                },
+                "paths": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Limit the operation to these paths; for commit, the paths to stage"
+                }
+            },
+            "required": ["operation"]
+        })
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let operation = params
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required parameter 'operation'"))?;
+
+        let commands = match self.build_commands(operation, &params) {
+            Ok(commands) => commands,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        let Some(workspace) = ctx.workspace_path.clone() else {
+            return Ok(ToolResult::error("Git operations require a workspace"));
+        };
+
+        let sandbox = ctx.sandbox_config.clone().filter(|c| c.enabled);
+        let access = sandbox
+            .as_ref()
+            .map(|c| c.workspace_access.clone())
+            .unwrap_or(WorkspaceAccess::ReadWrite);
+        if access == WorkspaceAccess::None {
+            return Ok(ToolResult::error(
+                "Git operations require workspace access in the sandbox",
+            ));
+        }
+        if access == WorkspaceAccess::ReadOnly && matches!(operation, "clone" | "branch" | "commit")
+        {
+            return Ok(ToolResult::error(format!(
+                "git {} requires write access to the workspace",
+                operation
+            )));
+        }
+
+        // Resolve the repository directory without letting it leave the workspace
+        let guard = WorkspaceGuard::new(workspace, access)?;
+        let repo_path = params.get("path").and_then(|v| v.as_str()).unwrap_or(".");
+        let repo_dir = match guard.validate_path(Path::new(repo_path)) {
+            Ok(dir) if dir.is_dir() => dir,
+            Ok(dir) => {
+                return Ok(ToolResult::error(format!(
+                    "'{}' is not a directory",
+                    dir.display()
+                )))
+            }
+            Err(e) => {
+                return Ok(ToolResult::error(format!(
+                    "Invalid path '{}': {}",
+                    repo_path, e
+                )))
+            }
+        };
+
+        if operation == "commit" {
+            if let Some(approval_handler) = &ctx.approval_handler {
+                let message = params.get("message").and_then(|v| v.as_str()).unwrap_or("");
+                let request = ApprovalRequest::new(
+                    &ctx.agent_id,
+                    format!("Commit in repository '{}': {}", repo_path, message),
+                    RiskLevel::Medium,
+                )
+                .with_timeout(Duration::from_secs(60));
+
+                match approval_handler.request_approval(request).await? {
+                    ApprovalResponse::Approved => {}
+                    ApprovalResponse::Denied { reason } => {
+                        return Ok(ToolResult::error(format!("Commit denied: {}", reason)));
+                    }
+                    ApprovalResponse::TimedOut => {
+                        return Ok(ToolResult::error("Commit timed out (approval timeout)"));
+                    }
+                }
+            }
+        }
+
+        let (exit_code, stdout, stderr) = match sandbox {
+            Some(mut config) => {
+                if operation == "clone" && !config.network_access {
+                    return Ok(ToolResult::error(
+                        "Cloning requires network access in the sandbox",
+                    ));
+                }
+                config.timeout = self.timeout;
+                let relative: PathBuf = repo_dir
+                    .strip_prefix(guard.root())
+                    .map(Path::to_path_buf)
+                    .unwrap_or_default();
+                let script = commands
+                    .iter()
+                    .map(|args| {
+                        args.iter()
+                            .map(|a| shell_quote(a))
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" && ");
+                let command = format!(
+                    "cd {} && {}",
+                    shell_quote(&Path::new("/workspace").join(relative).to_string_lossy()),
+                    script
+                );
+
+                let executor = SandboxExecutor::new(config.runtime.clone());
+                let result = executor
+                    .run_one_shot(&config, &command, guard.root())
+                    .await?;
+                if result.timed_out {
+                    return Ok(ToolResult::error(format!(
+                        "git {} timed out after {} seconds",
+                        operation,
+                        self.timeout.as_secs()
+                    )));
+                }
+                (result.exit_code, result.stdout, result.stderr)
+            }
+            None => match self.run_on_host(&commands, &repo_dir).await {
+                Ok(output) => output,
+                Err(e) => return Ok(ToolResult::error(e.to_string())),
+            },
+        };
+
+        let metadata = json!({
+            "operation": operation,
+            "exit_code": exit_code,
+        });
+
+        if exit_code != 0 {
+            let mut message = format!("git {} failed with exit code {}", operation, exit_code);
+            if !stderr.is_empty() {
+                message.push_str(&format!("\n\nstderr:\n{}", self.truncate(&stderr)));
+            }
+            return Ok(ToolResult::error(message).with_metadata(metadata));
+        }
+
+        // Clone and checkout report progress on stderr only
+        let mut content = self.truncate(&stdout);
+        if content.trim().is_empty() {
+            content = match operation {
+                "diff" => "No changes".to_string(),
+                "log" => "No commits".to_string(),
+                _ if !stderr.trim().is_empty() => self.truncate(stderr.trim()),
+                _ => format!("git {} completed", operation),
+            };
+        }
+        Ok(ToolResult::success(content).with_metadata(metadata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_unsafe_urls() {
+        assert!(check_url("https://github.com/example/repo.git").is_ok());
+        assert!(check_url("git@github.com:example/repo.git").is_ok());
+        assert!(check_url("ext::sh -c touch% /tmp/pwned").is_err());
+        assert!(check_url("file:///etc").is_err());
+        assert!(check_url("--upload-pack=touch /tmp/x").is_err());
+        assert!(check_url("/srv/repo.git").is_err());
+    }
+
+    #[test]
+    fn test_rejects_escaping_paths_and_option_refs() {
+        assert!(check_relative("src/lib.rs").is_ok());
+        assert!(check_relative("../outside").is_err());
+        assert!(check_relative("/etc/passwd").is_err());
+        assert!(check_relative("--output=x").is_err());
+        assert!(check_ref_name("feature/login").is_ok());
+        assert!(check_ref_name("--orphan").is_err());
+        assert!(check_ref_name("two words").is_err());
+    }
+
+    #[test]
+    fn test_builds_commit_commands() {
+        let tool = GitTool::default().with_identity("Bot", "bot@example.com");
+        let commands = tool
+            .build_commands("commit", &json!({"message": "Fix typo", "all": true}))
+            .unwrap();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0], vec!["git", "add", "--all"]);
+        assert!(commands[1].contains(&"user.name=Bot".to_string()));
+        assert_eq!(commands[1].last().unwrap(), "Fix typo");
+
+        assert!(tool.build_commands("commit", &json!({})).is_err());
+        assert!(tool.build_commands("push", &json!({})).is_err());
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+}
@@ -0,0 +1,286 @@
+//! Built-in handoff tool for agents to transfer a session to another agent.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::{Tool, ToolContext, ToolResult};
+
+/// Trait for session handoff implementations.
+///
+/// This trait defines the interface for transferring a session from one
+/// agent to another, e.g. from a triage agent to a specialist.
+#[async_trait]
+pub trait SessionHandoff: Send + Sync {
+    /// Transfers a session to another agent.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_key` - The session being handed off
+    /// * `from_agent` - The agent currently handling the session
+    /// * `to_agent` - The agent that handles the session from now on
+    /// * `note` - A note for the receiving agent explaining the handoff
+    /// * `context` - Optional context relevant to the receiving agent
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if the session was handed off, or an error if the
+    /// target agent cannot take over the session.
+    async fn handoff(
+        &self,
+        session_key: &str,
+        from_agent: &str,
+        to_agent: &str,
+        note: &str,
+        context: Option<&str>,
+    ) -> Result<()>;
+}
+
+/// A built-in tool for handing the current session off to another agent.
+///
+/// After a handoff, subsequent messages of the session are handled by the
+/// receiving agent, which is given the handoff note and context.
+///
+/// # Parameters
+///
+/// The tool accepts the following parameters:
+///
+/// - `agent_id`: The ID of the agent to hand the session to (required)
+/// - `note`: A note for the receiving agent explaining the handoff (required)
+/// - `context`: Relevant context for the receiving agent (optional)
+///
+/// # Example
+///
+/// ```json
+/// {
+///   "agent_id": "billing",
+///   "note": "Customer was charged twice for the March invoice",
+///   "context": "Invoice INV-2031, customer since 2021"
+/// }
+/// ```
+#[derive(Clone)]
+pub struct HandoffTool {
+    /// The handoff implementation recording the transfer.
+    handoff: Arc<dyn SessionHandoff>,
+}
+
+impl HandoffTool {
+    /// Creates a new HandoffTool with the given handoff implementation.
+    pub fn new(handoff: Arc<dyn SessionHandoff>) -> Self {
+        Self { handoff }
+    }
+}
+
+#[async_trait]
+impl Tool for HandoffTool {
+    fn name(&self) -> &str {
+        "handoff"
+    }
+
+    fn description(&self) -> &str {
+        "Hand the conversation off to another agent, which handles all further messages"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "agent_id": {
+                    "type": "string",
+                    "description": "The ID of the agent to hand the conversation to"
+                },
+                "note": {
+                    "type": "string",
+                    "description": "A note for the receiving agent explaining why the conversation is handed off"
+                },
+                "context": {
+                    "type": "string",
+                    "description": "Relevant context gathered so far for the receiving agent"
+                }
+            },
+            "required": ["agent_id", "note"]
+        })
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        // Extract agent_id parameter (required)
+        let agent_id = params
+            .get("agent_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required parameter 'agent_id'"))?;
+
+        // Extract note parameter (required)
+        let note = params
+            .get("note")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required parameter 'note'"))?;
+
+        // Extract context parameter (optional)
+        let context = params.get("context").and_then(|v| v.as_str());
+
+        if agent_id == ctx.agent_id {
+            return Ok(ToolResult::error(format!(
+                "The conversation is already handled by agent '{}'",
+                agent_id
+            )));
+        }
+
+        if let Err(e) = self
+            .handoff
+            .handoff(&ctx.session_key, &ctx.agent_id, agent_id, note, context)
+            .await
+        {
+            return Ok(ToolResult::error(format!("Handoff failed: {}", e)));
+        }
+
+        Ok(ToolResult::success(format!(
+            "The conversation was handed off to agent '{}', which will handle the user's next messages. \
+             Let the user know who will help them next.",
+            agent_id
+        )))
+    }
+}
+
+/// A no-op SessionHandoff implementation for testing.
+///
+/// This implementation accepts every handoff without recording it.
+#[derive(Clone, Default)]
+pub struct NoOpSessionHandoff;
+
+#[async_trait]
+impl SessionHandoff for NoOpSessionHandoff {
+    async fn handoff(
+        &self,
+        _session_key: &str,
+        _from_agent: &str,
+        _to_agent: &str,
+        _note: &str,
+        _context: Option<&str>,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Session key, from agent, to agent, note, and context of a handoff.
+    type RecordedHandoff = (String, String, String, String, Option<String>);
+
+    /// Handoff recording the transfers it accepts.
+    #[derive(Default)]
+    struct RecordingHandoff {
+        handoffs: Mutex<Vec<RecordedHandoff>>,
+    }
+
+    #[async_trait]
+    impl SessionHandoff for RecordingHandoff {
+        async fn handoff(
+            &self,
+            session_key: &str,
+            from_agent: &str,
+            to_agent: &str,
+            note: &str,
+            context: Option<&str>,
+        ) -> Result<()> {
+            if to_agent == "unknown" {
+                anyhow::bail!("Agent not found: {}", to_agent);
+            }
+            self.handoffs.lock().unwrap().push((
+                session_key.to_string(),
+                from_agent.to_string(),
+                to_agent.to_string(),
+                note.to_string(),
+                context.map(str::to_string),
+            ));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_handoff_tool_schema() {
+        let tool = HandoffTool::new(Arc::new(NoOpSessionHandoff));
+        assert_eq!(tool.name(), "handoff");
+
+        let schema = tool.parameters_schema();
+        let required = schema["required"].as_array().unwrap();
+        assert!(required.contains(&json!("agent_id")));
+        assert!(required.contains(&json!("note")));
+        assert!(!required.contains(&json!("context")));
+    }
+
+    #[tokio::test]
+    async fn test_handoff_tool_execute_success() {
+        let handoff = Arc::new(RecordingHandoff::default());
+        let tool = HandoffTool::new(handoff.clone());
+        let ctx = ToolContext::new("triage", "session_1");
+
+        let result = tool
+            .execute(
+                json!({
+                    "agent_id": "billing",
+                    "note": "Double charge on the March invoice",
+                    "context": "Invoice INV-2031"
+                }),
+                &ctx,
+            )
+            .await
+            .unwrap();
+
+        assert!(!result.is_error);
+        assert!(result.content.contains("'billing'"));
+        assert_eq!(
+            handoff.handoffs.lock().unwrap()[0],
+            (
+                "session_1".to_string(),
+                "triage".to_string(),
+                "billing".to_string(),
+                "Double charge on the March invoice".to_string(),
+                Some("Invoice INV-2031".to_string()),
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handoff_tool_rejects_current_agent() {
+        let handoff = Arc::new(RecordingHandoff::default());
+        let tool = HandoffTool::new(handoff.clone());
+        let ctx = ToolContext::new("triage", "session_1");
+
+        let result = tool
+            .execute(json!({"agent_id": "triage", "note": "Loop"}), &ctx)
+            .await
+            .unwrap();
+
+        assert!(result.is_error);
+        assert!(handoff.handoffs.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_handoff_tool_reports_failed_handoff() {
+        let tool = HandoffTool::new(Arc::new(RecordingHandoff::default()));
+        let ctx = ToolContext::new("triage", "session_1");
+
+        let result = tool
+            .execute(json!({"agent_id": "unknown", "note": "Help"}), &ctx)
+            .await
+            .unwrap();
+
+        assert!(result.is_error);
+        assert!(result.content.contains("Agent not found: unknown"));
+    }
+
+    #[tokio::test]
+    async fn test_handoff_tool_missing_note() {
+        let tool = HandoffTool::new(Arc::new(NoOpSessionHandoff));
+        let ctx = ToolContext::new("triage", "session_1");
+
+        let result = tool.execute(json!({"agent_id": "billing"}), &ctx).await;
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("Missing required parameter 'note'"));
+    }
+}
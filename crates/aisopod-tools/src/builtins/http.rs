@@ -0,0 +1,504 @@
+//! Built-in HTTP request tool.
+
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use aisopod_config::types::HttpToolConfig;
+use aisopod_shared::net::{is_public_address, PublicAddressResolver};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, COOKIE, LOCATION, PROXY_AUTHORIZATION,
+};
+use reqwest::{Method, StatusCode};
+use serde_json::{json, Value};
+use url::{Host, Url};
+
+use crate::approval::{ApprovalRequest, ApprovalResponse, RiskLevel};
+use crate::{HostPolicy, Tool, ToolContext, ToolPolicyEngine, ToolResult};
+
+/// Maximum number of redirects followed for a single request.
+const MAX_REDIRECTS: usize = 5;
+
+/// A built-in tool that performs HTTP requests.
+///
+/// Supports GET, POST, PUT, and DELETE with custom headers and JSON or
+/// text bodies. Responses larger than the configured limit are truncated.
+/// When a [`ToolPolicyEngine`] is attached, every host contacted, including
+/// redirect targets, is checked against its host policies. Loopback,
+/// private and link-local addresses are refused on every hop, after DNS
+/// resolution, unless private networks are explicitly allowed.
+///
+/// Requests that modify state (POST, PUT, DELETE) go through the approval
+/// handler when one is present in the context.
+///
+/// # Parameters
+///
+/// - `url`: The URL to request (required, `http` or `https`).
+/// - `method`: One of `GET`, `POST`, `PUT`, `DELETE` (default `GET`).
+/// - `headers`: Optional headers as key-value pairs.
+/// - `body`: Optional body. Strings are sent as-is, other values as JSON.
+/// - `timeout`: Optional timeout in seconds, at most the default timeout.
+///
+/// # Example
+///
+/// ```json
+/// {
+///   "url": "https://api.example.com/items",
+///   "method": "POST",
+///   "headers": { "Authorization": "Bearer token" },
+///   "body": { "name": "widget" }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HttpTool {
+    /// The client, or why it could not be built
+    client: std::result::Result<reqwest::Client, String>,
+    policy: Option<Arc<ToolPolicyEngine>>,
+    allow_private_networks: bool,
+    /// Default and longest timeout for requests.
+    pub default_timeout: Duration,
+    /// Maximum number of response body bytes returned to the model.
+    pub max_response_bytes: usize,
+}
+
+impl Default for HttpTool {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(30), 1024 * 1024)
+    }
+}
+
+impl HttpTool {
+    /// Creates a new HttpTool with the given timeout and response size limit.
+    pub fn new(default_timeout: Duration, max_response_bytes: usize) -> Self {
+        Self {
+            client: Self::build_client(false),
+            policy: None,
+            allow_private_networks: false,
+            default_timeout,
+            max_response_bytes,
+        }
+    }
+
+    /// Creates an HttpTool from the `tools.http` configuration.
+    pub fn from_config(config: &HttpToolConfig) -> Self {
+        let mut engine = ToolPolicyEngine::new();
+        engine.set_global_host_policy(HostPolicy {
+            allow: (!config.allowed_hosts.is_empty()).then(|| config.allowed_hosts.clone()),
+            deny: (!config.denied_hosts.is_empty()).then(|| config.denied_hosts.clone()),
+        });
+        Self::new(Duration::from_secs(config.timeout), 1024 * 1024)
+            .with_private_networks(config.allow_private_networks)
+            .with_policy_engine(Arc::new(engine))
+    }
+
+    /// Sets whether loopback, private and link-local addresses may be contacted.
+    pub fn with_private_networks(mut self, allowed: bool) -> Self {
+        self.allow_private_networks = allowed;
+        self.client = Self::build_client(allowed);
+        self
+    }
+
+    /// Builds the client, resolving host names to public addresses only
+    /// unless private networks are allowed.
+    ///
+    /// Proxies from the environment are ignored: a proxy would resolve the
+    /// target host itself, bypassing the address check.
+    fn build_client(allow_private_networks: bool) -> std::result::Result<reqwest::Client, String> {
+        let builder = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .no_proxy();
+        let builder = if allow_private_networks {
+            builder
+        } else {
+            builder.dns_resolver(Arc::new(PublicAddressResolver))
+        };
+        builder.build().map_err(|e| e.to_string())
+    }
+
+    /// Attaches a policy engine whose host policies are enforced on every request.
+    pub fn with_policy_engine(mut self, policy: Arc<ToolPolicyEngine>) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Checks the URL scheme and host against the attached policy.
+    fn check_url(&self, url: &Url, agent_id: &str) -> std::result::Result<(), String> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("Unsupported URL scheme '{}'", url.scheme()));
+        }
+        let host = url
+            .host_str()
+            .ok_or_else(|| format!("URL '{}' has no host", url))?;
+        // Host names are checked by the resolver, IP literals here
+        let literal = match url.host() {
+            Some(Host::Ipv4(ip)) => Some(IpAddr::V4(ip)),
+            Some(Host::Ipv6(ip)) => Some(IpAddr::V6(ip)),
+            _ => None,
+        };
+        if let Some(ip) = literal {
+            if !self.allow_private_networks && !is_public_address(ip) {
+                return Err(format!("Address {} is not a public address", ip));
+            }
+        }
+        match &self.policy {
+            Some(policy) => policy.is_host_allowed(agent_id, host.trim_matches(['[', ']'])),
+            None => Ok(()),
+        }
+    }
+
+    /// Builds a header map from the `headers` parameter.
+    fn parse_headers(params: &Value) -> std::result::Result<HeaderMap, String> {
+        let mut headers = HeaderMap::new();
+        if let Some(obj) = params.get("headers").and_then(|v| v.as_object()) {
+            for (key, value) in obj {
+                let value = value
+                    .as_str()
+                    .ok_or_else(|| format!("Header '{}' must be a string", key))?;
+                let name = HeaderName::from_bytes(key.as_bytes())
+                    .map_err(|_| format!("Invalid header name '{}'", key))?;
+                let value = HeaderValue::from_str(value)
+                    .map_err(|_| format!("Invalid value for header '{}'", key))?;
+                headers.insert(name, value);
+            }
+        }
+        Ok(headers)
+    }
+
+    /// Reads the response body up to the size limit.
+    ///
+    /// Returns the body and whether it was truncated.
+    async fn read_body(&self, mut response: reqwest::Response) -> Result<(Vec<u8>, bool)> {
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            let remaining = self.max_response_bytes - body.len();
+            if chunk.len() > remaining {
+                body.extend_from_slice(&chunk[..remaining]);
+                return Ok((body, true));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok((body, false))
+    }
+
+    /// Performs the request, following redirects after re-checking policy
+    /// and, through the client's resolver, the addresses contacted.
+    ///
+    /// Like reqwest's own redirect policy, credentials are not sent on to
+    /// a different origin.
+    async fn send(
+        &self,
+        method: Method,
+        mut url: Url,
+        mut headers: HeaderMap,
+        body: Option<&Value>,
+        timeout: Duration,
+        ctx: &ToolContext,
+    ) -> Result<ToolResult> {
+        let client = self
+            .client
+            .as_ref()
+            .map_err(|e| anyhow::anyhow!("HTTP client could not be built: {}", e))?;
+        let mut method = method;
+        let mut body = body;
+
+        for _ in 0..=MAX_REDIRECTS {
+            if let Err(reason) = self.check_url(&url, &ctx.agent_id) {
+                return Ok(ToolResult::error(format!("Request blocked: {}", reason)));
+            }
+
+            let mut request = client
+                .request(method.clone(), url.clone())
+                .headers(headers.clone())
+                .timeout(timeout);
+            request = match body {
+                Some(Value::String(text)) => request.body(text.clone()),
+                Some(value) => request.json(value),
+                None => request,
+            };
+
+            let response = request.send().await.map_err(|e| {
+                anyhow::anyhow!(
+                    "HTTP request to {} failed: {:#}",
+                    url,
+                    anyhow::Error::new(e)
+                )
+            })?;
+            let status = response.status();
+
+            if status.is_redirection() {
+                if let Some(location) = response
+                    .headers()
+                    .get(LOCATION)
+                    .and_then(|v| v.to_str().ok())
+                {
+                    let next = url
+                        .join(location)
+                        .map_err(|e| anyhow::anyhow!("Invalid redirect location: {}", e))?;
+                    if next.origin() != url.origin() {
+                        headers.remove(AUTHORIZATION);
+                        headers.remove(PROXY_AUTHORIZATION);
+                        headers.remove(COOKIE);
+                    }
+                    url = next;
+                    // 303, and 301/302 after POST, switch to a bodiless GET
+                    if status == StatusCode::SEE_OTHER
+                        || (method == Method::POST
+                            && matches!(status, StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND))
+                    {
+                        method = Method::GET;
+                        body = None;
+                    }
+                    continue;
+                }
+            }
+
+            return self.build_result(url, response).await;
+        }
+
+        Ok(ToolResult::error(format!(
+            "Too many redirects (more than {})",
+            MAX_REDIRECTS
+        )))
+    }
+
+    /// Converts a response into a tool result.
+    async fn build_result(&self, url: Url, response: reqwest::Response) -> Result<ToolResult> {
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+        let response_headers: serde_json::Map<String, Value> = response
+            .headers()
+            .iter()
+            .filter_map(|(k, v)| v.to_str().ok().map(|v| (k.to_string(), json!(v))))
+            .collect();
+
+        let (body, truncated) = self.read_body(response).await?;
+        let mut text = String::from_utf8_lossy(&body).into_owned();
+        if truncated {
+            text.push_str(&format!(
+                "\n\n[response truncated at {} bytes]",
+                self.max_response_bytes
+            ));
+        }
+
+        let content = format!("HTTP {}\n\n{}", status, text);
+        let metadata = json!({
+            "url": url.as_str(),
+            "status": status.as_u16(),
+            "content_type": content_type,
+            "headers": response_headers,
+            "bytes": body.len(),
+            "truncated": truncated,
+        });
+
+        let result = if status.is_client_error() || status.is_server_error() {
+            ToolResult::error(content)
+        } else {
+            ToolResult::success(content)
+        };
+        Ok(result.with_metadata(metadata))
+    }
+}
+
+#[async_trait]
+impl Tool for HttpTool {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    fn description(&self) -> &str {
+        "Perform an HTTP request"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "The http or https URL to request"
+                },
+                "method": {
+                    "type": "string",
+                    "enum": ["GET", "POST", "PUT", "DELETE"],
+                    "description": "The HTTP method (default GET)"
+                },
+                "headers": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                    "description": "Optional request headers as key-value pairs"
+                },
+                "body": {
+                    "description": "Optional request body; strings are sent as-is, other values as JSON"
+                },
+                "timeout": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Optional timeout in seconds, at most the default timeout"
+                }
+            },
+            "required": ["url"]
+        })
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let url_str = params
+            .get("url")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required parameter 'url'"))?;
+
+        let url = match Url::parse(url_str) {
+            Ok(url) => url,
+            Err(e) => {
+                return Ok(ToolResult::error(format!(
+                    "Invalid URL '{}': {}",
+                    url_str, e
+                )))
+            }
+        };
+
+        let method = match params
+            .get("method")
+            .and_then(|v| v.as_str())
+            .unwrap_or("GET")
+            .to_ascii_uppercase()
+            .as_str()
+        {
+            "GET" => Method::GET,
+            "POST" => Method::POST,
+            "PUT" => Method::PUT,
+            "DELETE" => Method::DELETE,
+            other => {
+                return Ok(ToolResult::error(format!(
+                    "Unsupported HTTP method '{}'",
+                    other
+                )))
+            }
+        };
+
+        let headers = match Self::parse_headers(&params) {
+            Ok(headers) => headers,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        let timeout = params
+            .get("timeout")
+            .and_then(|v| v.as_u64())
+            .map(|secs| Duration::from_secs(secs).min(self.default_timeout))
+            .unwrap_or(self.default_timeout);
+
+        if let Err(reason) = self.check_url(&url, &ctx.agent_id) {
+            return Ok(ToolResult::error(format!("Request blocked: {}", reason)));
+        }
+
+        // Requests that modify remote state require approval when a handler is available
+        if method != Method::GET {
+            if let Some(approval_handler) = &ctx.approval_handler {
+                let request = ApprovalRequest::new(
+                    &ctx.agent_id,
+                    format!("HTTP {} {}", method, url),
+                    RiskLevel::Medium,
+                )
+                .with_timeout(Duration::from_secs(60));
+
+                match approval_handler.request_approval(request).await? {
+                    ApprovalResponse::Approved => {}
+                    ApprovalResponse::Denied { reason } => {
+                        return Ok(ToolResult::error(format!(
+                            "HTTP request denied: {}",
+                            reason
+                        )));
+                    }
+                    ApprovalResponse::TimedOut => {
+                        return Ok(ToolResult::error(
+                            "HTTP request timed out (approval timeout)",
+                        ));
+                    }
+                }
+            }
+        }
+
+        self.send(method, url, headers, params.get("body"), timeout, ctx)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::HostPolicy;
+
+    fn tool_with_hosts(policy: HostPolicy) -> HttpTool {
+        let mut engine = ToolPolicyEngine::new();
+        engine.set_global_host_policy(policy);
+        HttpTool::default().with_policy_engine(Arc::new(engine))
+    }
+
+    #[test]
+    fn test_http_tool_name_and_schema() {
+        let tool = HttpTool::default();
+        assert_eq!(tool.name(), "http");
+
+        let schema = tool.parameters_schema();
+        assert_eq!(schema["type"], "object");
+        assert!(schema["required"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("url")));
+    }
+
+    #[test]
+    fn test_parse_headers() {
+        let headers =
+            HttpTool::parse_headers(&json!({"headers": {"X-Test": "1", "Accept": "text/plain"}}))
+                .unwrap();
+        assert_eq!(headers.get("x-test").unwrap(), "1");
+
+        assert!(HttpTool::parse_headers(&json!({"headers": {"X-Test": 1}})).is_err());
+        assert!(HttpTool::parse_headers(&json!({"headers": {"bad header": "x"}})).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_http_tool_rejects_denied_host() {
+        let tool = tool_with_hosts(HostPolicy::deny_list(vec!["*.internal".to_string()]));
+        let ctx = ToolContext::new("test_agent", "test_session");
+
+        let result = tool
+            .execute(json!({"url": "http://db.internal/"}), &ctx)
+            .await
+            .unwrap();
+        assert!(result.is_error);
+        assert!(result.content.contains("blocked"));
+    }
+
+    #[tokio::test]
+    async fn test_http_tool_rejects_bad_input() {
+        let tool = HttpTool::default();
+        let ctx = ToolContext::new("test_agent", "test_session");
+
+        let result = tool
+            .execute(json!({"url": "file:///etc/passwd"}), &ctx)
+            .await
+            .unwrap();
+        assert!(result.is_error);
+        assert!(result.content.contains("scheme"));
+
+        let result = tool
+            .execute(
+                json!({"url": "http://example.com", "method": "PATCH"}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(result.is_error);
+
+        assert!(tool.execute(json!({}), &ctx).await.is_err());
+    }
+}
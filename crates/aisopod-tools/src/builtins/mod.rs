@@ -1,17 +1,45 @@
 //! Built-in tools provided by aisopod.
 
 pub mod bash;
+pub mod browser;
 pub mod canvas;
 pub mod cron;
+pub mod docs;
 pub mod file;
+pub mod git;
+pub mod handoff;
+pub mod http;
 pub mod message;
+pub mod python;
+pub mod scheduler;
 pub mod session;
+pub mod sql;
 pub mod subagent;
+pub mod web_search;
 
 pub use bash::BashTool;
+pub use browser::{BrowserDriver, BrowserTool, NoOpBrowserDriver, WebDriverBrowser};
 pub use canvas::{CanvasRenderer, CanvasTool, InMemoryCanvasRenderer};
-pub use cron::{CronTool, JobScheduler, NoOpJobScheduler, ScheduledJob};
+pub use cron::{CronTool, JobOwner, JobScheduler, NoOpJobScheduler, ScheduledJob};
+pub use docs::{chunk_document, DocChunk, DocsTool};
 pub use file::FileTool;
+pub use git::GitTool;
+pub use handoff::{HandoffTool, NoOpSessionHandoff, SessionHandoff};
+pub use http::HttpTool;
 pub use message::{MessageSender, MessageTool, NoOpMessageSender};
+pub use python::PythonTool;
+pub use scheduler::{
+    CatchUpPolicy, JobRun, JobRunner, NoOpJobRunner, SqliteJobScheduler, ToolContextResolver,
+    ToolJobRunner,
+};
 pub use session::{NoOpSessionManager, SessionManager, SessionTool};
-pub use subagent::{AgentSpawner, NoOpAgentSpawner, SubagentTool};
+pub use sql::{is_read_statement, QueryOutput, SqlBackend, SqlTool, SqliteBackend};
+#[cfg(feature = "mysql")]
+pub use sql::MySqlBackend;
+#[cfg(feature = "postgres")]
+pub use sql::PostgresBackend;
+pub use subagent::{AgentSpawner, NoOpAgentSpawner, SubagentStatusTool, SubagentTool};
+pub use web_search::{
+    BingBackend, BraveBackend, DuckDuckGoBackend, SearchBackend, SearchResult, SearxngBackend,
+    WebSearchTool,
+};
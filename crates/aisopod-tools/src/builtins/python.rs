@@ -0,0 +1,484 @@
+//! Built-in Python code execution tool.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use regex::Regex;
+use serde_json::{json, Value};
+use walkdir::WalkDir;
+
+use crate::sandbox::{SandboxConfig, SandboxExecutor, WorkspaceAccess};
+use crate::{Tool, ToolContext, ToolResult};
+
+/// Directory inside the workspace where scripts are staged.
+const SCRIPT_DIR: &str = ".aisopod/python";
+
+/// Directory inside the container where requested packages are installed.
+const PACKAGE_DIR: &str = "/tmp/aisopod-packages";
+
+fn package_spec_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^([A-Za-z0-9][A-Za-z0-9._-]*)(\[[A-Za-z0-9,._-]+\])?((==|>=|<=|~=|!=|<|>)[A-Za-z0-9.*+!_-]+)?$")
+            .unwrap()
+    })
+}
+
+/// Normalizes a package name per PEP 503 (lowercase, runs of `-_.` become `-`).
+fn normalize_package_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut last_sep = false;
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !last_sep {
+                out.push('-');
+            }
+            last_sep = true;
+        } else {
+            out.push(c.to_ascii_lowercase());
+            last_sep = false;
+        }
+    }
+    out
+}
+
+/// A file produced or modified by a script run.
+#[derive(Debug, Clone, PartialEq)]
+struct OutputFile {
+    path: String,
+    bytes: u64,
+}
+
+/// Records modification times of all files under `dir`.
+fn snapshot(dir: &Path) -> HashMap<PathBuf, SystemTime> {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let modified = e.metadata().ok()?.modified().ok()?;
+            Some((e.into_path(), modified))
+        })
+        .collect()
+}
+
+/// Lists files under `dir` that are new or changed since `before`.
+fn collect_outputs(
+    workspace: &Path,
+    dir: &Path,
+    before: &HashMap<PathBuf, SystemTime>,
+) -> Vec<OutputFile> {
+    let mut files: Vec<OutputFile> = snapshot(dir)
+        .into_iter()
+        .filter(|(path, modified)| before.get(path) != Some(modified))
+        .filter_map(|(path, _)| {
+            let bytes = std::fs::metadata(&path).ok()?.len();
+            let relative = path.strip_prefix(workspace).ok()?;
+            Some(OutputFile {
+                path: relative.to_string_lossy().into_owned(),
+                bytes,
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+/// A built-in tool that runs Python scripts in a sandbox container.
+///
+/// Scripts are staged in the workspace and executed in a one-shot
+/// container with the workspace mounted at `/workspace`. Requested pip
+/// packages must appear in the allow list and are installed into a
+/// throwaway directory before the script runs.
+///
+/// Files the script writes under the output directory are reported back in
+/// the result metadata. The script sees the output directory as
+/// `AISOPOD_OUTPUT_DIR`, and matplotlib is set to a non-interactive backend.
+///
+/// # Parameters
+///
+/// - `code`: The Python source to run (required).
+/// - `packages`: Optional pip packages to install, e.g. `["pandas", "numpy==2.0.0"]`.
+/// - `timeout`: Optional timeout in seconds, capped at the sandbox timeout.
+///
+/// # Example
+///
+/// ```json
+/// {
+///   "code": "import pandas as pd\nprint(pd.__version__)",
+///   "packages": ["pandas"]
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PythonTool {
+    /// Container image providing the Python interpreter.
+    pub image: String,
+    /// Packages that scripts may request (PEP 503 names).
+    pub allowed_packages: Vec<String>,
+    /// Workspace-relative directory whose new files are collected as results.
+    pub output_dir: String,
+    /// Maximum number of stdout/stderr characters returned to the model.
+    pub max_output_chars: usize,
+}
+
+impl Default for PythonTool {
+    fn default() -> Self {
+        Self {
+            image: "python:3.12-slim".to_string(),
+            allowed_packages: [
+                "numpy",
+                "pandas",
+                "matplotlib",
+                "scipy",
+                "requests",
+                "pyyaml",
+                "openpyxl",
+                "tabulate",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            output_dir: "outputs".to_string(),
+            max_output_chars: 20_000,
+        }
+    }
+}
+
+impl PythonTool {
+    /// Creates a new PythonTool using the given image and package allow list.
+    pub fn new(image: impl Into<String>, allowed_packages: Vec<String>) -> Self {
+        Self {
+            image: image.into(),
+            allowed_packages,
+            ..Self::default()
+        }
+    }
+
+    /// Validates requested packages against the allow list.
+    ///
+    /// Returns the specs to pass to pip, or a message naming the rejected package.
+    fn validate_packages(&self, requested: &[String]) -> std::result::Result<Vec<String>, String> {
+        let allowed: Vec<String> = self
+            .allowed_packages
+            .iter()
+            .map(|p| normalize_package_name(p))
+            .collect();
+
+        requested
+            .iter()
+            .map(|spec| {
+                let spec = spec.trim();
+                let captures = package_spec_regex()
+                    .captures(spec)
+                    .ok_or_else(|| format!("Invalid package specification '{}'", spec))?;
+                let name = normalize_package_name(&captures[1]);
+                if !allowed.contains(&name) {
+                    return Err(format!("Package '{}' is not in the allow list", name));
+                }
+                Ok(spec.to_string())
+            })
+            .collect()
+    }
+
+    /// Builds the shell command run inside the container.
+    fn build_command(script: &str, packages: &[String]) -> String {
+        let run = format!("python3 /workspace/{}", script);
+        if packages.is_empty() {
+            return run;
+        }
+        let specs: Vec<String> = packages.iter().map(|p| format!("'{}'", p)).collect();
+        format!(
+            "pip install --quiet --disable-pip-version-check --no-cache-dir --target {} {} && {}",
+            PACKAGE_DIR,
+            specs.join(" "),
+            run
+        )
+    }
+
+    /// Truncates captured output to the configured limit.
+    fn truncate(&self, text: &str) -> String {
+        if text.chars().count() <= self.max_output_chars {
+            return text.to_string();
+        }
+        let mut out: String = text.chars().take(self.max_output_chars).collect();
+        out.push_str("\n[output truncated]");
+        out
+    }
+}
+
+#[async_trait]
+impl Tool for PythonTool {
+    fn name(&self) -> &str {
+        "python"
+    }
+
+    fn description(&self) -> &str {
+        "Run a Python script in a sandbox container"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "code": {
+                    "type": "string",
+                    "description": "The Python source code to run"
+                },
+                "packages": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": format!(
+                        "Optional pip packages to install; allowed: {}",
+                        self.allowed_packages.join(", ")
+                    )
+                },
+                "timeout": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Optional timeout in seconds, capped at the sandbox timeout"
+                }
+            },
+            "required": ["code"]
+        })
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let code = params
+            .get("code")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required parameter 'code'"))?;
+
+        if code.trim().is_empty() {
+            return Ok(ToolResult::error("Code cannot be empty"));
+        }
+
+        let requested: Vec<String> = params
+            .get("packages")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let packages = match self.validate_packages(&requested) {
+            Ok(packages) => packages,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        let Some(workspace) = ctx.workspace_path.clone() else {
+            return Ok(ToolResult::error("Python execution requires a workspace"));
+        };
+
+        let mut config = ctx.sandbox_config.clone().unwrap_or_else(|| SandboxConfig {
+            workspace_access: WorkspaceAccess::ReadWrite,
+            network_access: false,
+            ..SandboxConfig::default()
+        });
+        config.enabled = true;
+        config.image = self.image.clone();
+        if let Some(timeout) = params.get("timeout").and_then(|v| v.as_u64()) {
+            config.timeout = Duration::from_secs(timeout).min(config.timeout);
+        }
+
+        if config.workspace_access == WorkspaceAccess::None {
+            return Ok(ToolResult::error(
+                "Python execution requires workspace access in the sandbox",
+            ));
+        }
+        if !packages.is_empty() && !config.network_access {
+            return Ok(ToolResult::error(
+                "Installing packages requires network access in the sandbox",
+            ));
+        }
+
+        // Stage the script inside the workspace so the container can read it
+        let script = format!(
+            "{}/{}.py",
+            SCRIPT_DIR,
+            chrono::Utc::now().format("%Y%m%d%H%M%S%f")
+        );
+        let script_path = workspace.join(&script);
+        if let Some(parent) = script_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&script_path, code).await?;
+
+        let output_dir = workspace.join(&self.output_dir);
+        let before = snapshot(&output_dir);
+
+        let env: HashMap<String, String> = [
+            ("PYTHONPATH", PACKAGE_DIR.to_string()),
+            ("PYTHONUNBUFFERED", "1".to_string()),
+            ("MPLBACKEND", "Agg".to_string()),
+            ("MPLCONFIGDIR", "/tmp/matplotlib".to_string()),
+            ("HOME", "/tmp".to_string()),
+            (
+                "AISOPOD_OUTPUT_DIR",
+                format!("/workspace/{}", self.output_dir),
+            ),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+
+        let executor = SandboxExecutor::new(config.runtime.clone());
+        let result = executor
+            .run_one_shot_with_env(
+                &config,
+                &Self::build_command(&script, &packages),
+                &workspace,
+                &env,
+            )
+            .await;
+        let _ = tokio::fs::remove_file(&script_path).await;
+        let result = result?;
+
+        if result.timed_out {
+            return Ok(ToolResult::error(format!(
+                "Python script timed out after {} seconds",
+                config.timeout.as_secs()
+            )));
+        }
+
+        let files = collect_outputs(&workspace, &output_dir, &before);
+        let mut content = self.truncate(&result.stdout);
+        if !result.stderr.is_empty() {
+            content.push_str(&format!("\n\nstderr:\n{}", self.truncate(&result.stderr)));
+        }
+        if !files.is_empty() {
+            content.push_str("\n\nOutput files:");
+            for file in &files {
+                content.push_str(&format!("\n- {} ({} bytes)", file.path, file.bytes));
+            }
+        }
+
+        let metadata = json!({
+            "exit_code": result.exit_code,
+            "files": files
+                .iter()
+                .map(|f| json!({ "path": f.path, "bytes": f.bytes }))
+                .collect::<Vec<_>>(),
+        });
+
+        let output = if result.exit_code == 0 {
+            ToolResult::success(content)
+        } else {
+            ToolResult::error(format!(
+                "Python exited with code {}\n\n{}",
+                result.exit_code, content
+            ))
+        };
+        Ok(output.with_metadata(metadata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_package_name() {
+        assert_eq!(normalize_package_name("PyYAML"), "pyyaml");
+        assert_eq!(
+            normalize_package_name("typing_extensions"),
+            "typing-extensions"
+        );
+        assert_eq!(normalize_package_name("zope..interface"), "zope-interface");
+    }
+
+    #[test]
+    fn test_validate_packages() {
+        let tool = PythonTool::default();
+
+        let ok = tool
+            .validate_packages(&["Pandas".to_string(), "numpy==2.0.0".to_string()])
+            .unwrap();
+        assert_eq!(ok, vec!["Pandas", "numpy==2.0.0"]);
+
+        let err = tool.validate_packages(&["torch".to_string()]).unwrap_err();
+        assert!(err.contains("torch"));
+
+        // Shell metacharacters are rejected before reaching the command line
+        assert!(tool
+            .validate_packages(&["numpy; rm -rf /".to_string()])
+            .is_err());
+        assert!(tool
+            .validate_packages(&["git+https://evil/numpy".to_string()])
+            .is_err());
+    }
+
+    #[test]
+    fn test_build_command() {
+        let cmd = PythonTool::build_command("s.py", &[]);
+        assert_eq!(cmd, "python3 /workspace/s.py");
+
+        let cmd = PythonTool::build_command("s.py", &["pandas".to_string()]);
+        assert!(cmd.starts_with("pip install"));
+        assert!(cmd.contains("'pandas'"));
+        assert!(cmd.ends_with("&& python3 /workspace/s.py"));
+    }
+
+    #[test]
+    fn test_collect_outputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let outputs = dir.path().join("outputs");
+        std::fs::create_dir_all(&outputs).unwrap();
+        std::fs::write(outputs.join("old.txt"), "old").unwrap();
+
+        let before = snapshot(&outputs);
+        std::fs::write(outputs.join("plot.png"), "png-data").unwrap();
+
+        let files = collect_outputs(dir.path(), &outputs, &before);
+        assert_eq!(
+            files,
+            vec![OutputFile {
+                path: "outputs/plot.png".to_string(),
+                bytes: 8
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_python_tool_rejects_before_running() {
+        let tool = PythonTool::default();
+        let ctx = ToolContext::new("agent", "session");
+
+        let result = tool
+            .execute(json!({"code": "print(1)"}), &ctx)
+            .await
+            .unwrap();
+        assert!(result.is_error);
+        assert!(result.content.contains("workspace"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = ctx.with_workspace_path(dir.path());
+        let result = tool
+            .execute(json!({"code": "print(1)", "packages": ["pandas"]}), &ctx)
+            .await
+            .unwrap();
+        assert!(result.is_error);
+        assert!(result.content.contains("network"));
+
+        let result = tool.execute(json!({"code": "  "}), &ctx).await.unwrap();
+        assert!(result.is_error);
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires Docker/Podman
+    async fn test_python_tool_collects_output_files() {
+        let tool = PythonTool::default();
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = ToolContext::new("agent", "session").with_workspace_path(dir.path());
+
+        let code = "import os\nos.makedirs(os.environ['AISOPOD_OUTPUT_DIR'], exist_ok=True)\nopen(os.path.join(os.environ['AISOPOD_OUTPUT_DIR'], 'r.txt'), 'w').write('hi')\nprint('done')";
+        let result = tool.execute(json!({"code": code}), &ctx).await.unwrap();
+        assert!(!result.is_error, "{}", result.content);
+        assert!(result.content.contains("done"));
+        assert!(result.content.contains("outputs/r.txt"));
+    }
+}
@@ -0,0 +1,614 @@
+//! Persistent job scheduler backing the cron tool.
+//!
+//! [`SqliteJobScheduler`] stores jobs in a SQLite database so they survive
+//! restarts, supports recurring cron jobs as well as one-shot delayed jobs,
+//! and hands due jobs to a [`JobRunner`] which performs the actual work
+//! (invoking a tool or triggering an agent run).
+
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use super::cron::{JobOwner, JobScheduler, ScheduledJob};
+use crate::{ToolContext, ToolRegistry};
+
+/// Pseudo cron expression stored for one-shot jobs.
+pub const ONE_SHOT_EXPRESSION: &str = "@once";
+
+/// Upper bound on the number of missed occurrences examined per job and tick.
+const MAX_MISSED_SCAN: usize = 1000;
+
+/// Due occurrences of a job together with its next run after them.
+type Occurrences = (Vec<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+/// Executes the command of a scheduled job when it becomes due.
+#[async_trait]
+pub trait JobRunner: Send + Sync {
+    /// Runs the job and returns a textual summary of the outcome.
+    async fn run(&self, job: &ScheduledJob) -> Result<String>;
+}
+
+/// A runner that only reports what would have been executed.
+#[derive(Debug, Default, Clone)]
+pub struct NoOpJobRunner;
+
+#[async_trait]
+impl JobRunner for NoOpJobRunner {
+    async fn run(&self, job: &ScheduledJob) -> Result<String> {
+        Ok(format!("Job '{}' would execute: {}", job.id, job.command))
+    }
+}
+
+/// Resolves the context the tools of an agent run in.
+///
+/// Implemented by the agent runner, which knows the agents' workspaces
+/// and sandboxes.
+pub trait ToolContextResolver: Send + Sync {
+    /// Returns the context `agent_id`'s tools run in within `session_key`.
+    fn tool_context(&self, agent_id: &str, session_key: &str) -> ToolContext;
+}
+
+/// A runner that invokes tools from a [`ToolRegistry`].
+///
+/// Job commands of the form `{"tool": "<name>", "params": {...}}` are
+/// dispatched to the named tool, in the tool context of the agent that
+/// scheduled the job. Any other command is passed to the fallback runner,
+/// which is typically used to trigger an agent run with the command as its
+/// prompt.
+#[derive(Clone)]
+pub struct ToolJobRunner {
+    registry: Arc<ToolRegistry>,
+    contexts: Arc<dyn ToolContextResolver>,
+    fallback: Option<Arc<dyn JobRunner>>,
+}
+
+impl std::fmt::Debug for ToolJobRunner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ToolJobRunner").finish()
+    }
+}
+
+impl ToolJobRunner {
+    /// Creates a runner executing tools in the contexts given by `contexts`.
+    pub fn new(registry: Arc<ToolRegistry>, contexts: Arc<dyn ToolContextResolver>) -> Self {
+        Self {
+            registry,
+            contexts,
+            fallback: None,
+        }
+    }
+
+    /// Sets the runner used for commands that are not tool invocations.
+    pub fn with_fallback(mut self, fallback: Arc<dyn JobRunner>) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+}
+
+#[async_trait]
+impl JobRunner for ToolJobRunner {
+    async fn run(&self, job: &ScheduledJob) -> Result<String> {
+        let invocation = serde_json::from_str::<Value>(&job.command)
+            .ok()
+            .filter(|v| v.get("tool").and_then(|t| t.as_str()).is_some());
+
+        let Some(invocation) = invocation else {
+            return match &self.fallback {
+                Some(fallback) => fallback.run(job).await,
+                None => Err(anyhow!(
+                    "Job '{}' command is not a tool invocation and no agent runner is configured",
+                    job.id
+                )),
+            };
+        };
+
+        let Some(owner) = &job.owner else {
+            return Err(anyhow!(
+                "Job '{}' invokes a tool but was not scheduled by an agent",
+                job.id
+            ));
+        };
+        let name = invocation["tool"].as_str().unwrap_or_default();
+        if self.registry.get(name).is_none() {
+            return Err(anyhow!(
+                "Job '{}' references unknown tool '{}'",
+                job.id,
+                name
+            ));
+        }
+        let params = invocation
+            .get("params")
+            .cloned()
+            .unwrap_or_else(|| Value::Object(Default::default()));
+
+        // Go through the registry, which applies the tool policy, parameter
+        // validation and quotas, as the agent that scheduled the job
+        let ctx = self
+            .contexts
+            .tool_context(&owner.agent_id, &owner.session_key);
+        let result = self.registry.execute(name, params, &ctx).await?;
+        if result.is_error {
+            Err(anyhow!("Tool '{}' failed: {}", name, result.content))
+        } else {
+            Ok(result.content)
+        }
+    }
+}
+
+/// What to do with occurrences that were missed while the scheduler was not running.
+///
+/// An occurrence counts as missed when it is older than the scheduler's
+/// grace period at the time it is examined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatchUpPolicy {
+    /// Drop missed occurrences and wait for the next one.
+    Skip,
+    /// Collapse all missed occurrences into a single run.
+    #[default]
+    RunOnce,
+    /// Run every missed occurrence, up to the given limit.
+    RunAll {
+        /// Maximum number of catch-up runs per job.
+        max: usize,
+    },
+}
+
+/// The outcome of a single job execution performed by the scheduler.
+#[derive(Debug)]
+pub struct JobRun {
+    /// The job that was run.
+    pub job_id: String,
+    /// The runner output, or the error it reported.
+    pub result: Result<String>,
+}
+
+/// A [`JobScheduler`] persisting jobs in SQLite.
+///
+/// Call [`SqliteJobScheduler::run_due`] periodically, or use
+/// [`SqliteJobScheduler::start`] to spawn a background loop doing so.
+pub struct SqliteJobScheduler {
+    conn: Arc<Mutex<Connection>>,
+    runner: Arc<dyn JobRunner>,
+    catch_up: CatchUpPolicy,
+    grace: chrono::Duration,
+}
+
+impl std::fmt::Debug for SqliteJobScheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqliteJobScheduler")
+            .field("catch_up", &self.catch_up)
+            .field("grace", &self.grace)
+            .finish()
+    }
+}
+
+impl SqliteJobScheduler {
+    /// Opens (or creates) a job store at the given path.
+    pub fn open(path: impl AsRef<Path>, runner: Arc<dyn JobRunner>) -> Result<Self> {
+        let conn = Connection::open(path.as_ref())?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        Self::from_connection(conn, runner)
+    }
+
+    /// Creates a scheduler backed by an in-memory database.
+    pub fn in_memory(runner: Arc<dyn JobRunner>) -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?, runner)
+    }
+
+    fn from_connection(conn: Connection, runner: Arc<dyn JobRunner>) -> Result<Self> {
+        run_migrations(&conn)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            runner,
+            catch_up: CatchUpPolicy::default(),
+            grace: chrono::Duration::seconds(60),
+        })
+    }
+
+    /// Returns a scheduler sharing this scheduler's jobs, running them with
+    /// `runner`.
+    ///
+    /// Lets a tool schedule jobs that are run by a runner built later, e.g.
+    /// one that needs the tool's registry.
+    pub fn with_runner(&self, runner: Arc<dyn JobRunner>) -> Self {
+        Self {
+            conn: self.conn.clone(),
+            runner,
+            catch_up: self.catch_up,
+            grace: self.grace,
+        }
+    }
+
+    /// Sets the policy applied to missed occurrences.
+    pub fn with_catch_up_policy(mut self, policy: CatchUpPolicy) -> Self {
+        self.catch_up = policy;
+        self
+    }
+
+    /// Sets how late an occurrence may run before it counts as missed.
+    pub fn with_grace_period(mut self, grace: Duration) -> Self {
+        self.grace = chrono::Duration::from_std(grace).unwrap_or(chrono::Duration::MAX);
+        self
+    }
+
+    /// Looks up a single job by ID.
+    pub fn get(&self, id: &str) -> Result<Option<ScheduledJob>> {
+        let conn = self.conn.lock().unwrap();
+        let job = conn
+            .query_row(
+                "SELECT id, cron_expression, command, next_run, last_run, owner_agent_id, owner_session_key FROM jobs WHERE id = ?1",
+                params![id],
+                row_to_job,
+            )
+            .optional()?;
+        Ok(job)
+    }
+
+    /// Runs every job that is due at the current time.
+    pub async fn run_due(&self) -> Result<Vec<JobRun>> {
+        self.run_due_at(Utc::now()).await
+    }
+
+    /// Runs every job that is due at `now`, applying the catch-up policy.
+    ///
+    /// Recurring jobs are rescheduled to their next occurrence after `now`;
+    /// one-shot jobs are removed once handled.
+    pub async fn run_due_at(&self, now: DateTime<Utc>) -> Result<Vec<JobRun>> {
+        let due = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(
+                "SELECT id, cron_expression, command, next_run, last_run, owner_agent_id, owner_session_key FROM jobs
+                 WHERE next_run IS NOT NULL AND next_run <= ?1 ORDER BY next_run",
+            )?;
+            let rows = stmt.query_map(params![now.to_rfc3339()], row_to_job)?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+
+        let mut runs = Vec::new();
+        for job in due {
+            let (occurrences, next_run) = self.occurrences(&job, now)?;
+            let count = self.runs_for(&occurrences, now);
+            debug!(
+                "Job '{}' has {} due occurrence(s), running {} time(s)",
+                job.id,
+                occurrences.len(),
+                count
+            );
+
+            for _ in 0..count {
+                let result = self.runner.run(&job).await;
+                if let Err(e) = &result {
+                    warn!("Scheduled job '{}' failed: {}", job.id, e);
+                }
+                runs.push(JobRun {
+                    job_id: job.id.clone(),
+                    result,
+                });
+            }
+
+            let conn = self.conn.lock().unwrap();
+            if job.cron_expression == ONE_SHOT_EXPRESSION {
+                conn.execute("DELETE FROM jobs WHERE id = ?1", params![job.id])?;
+            } else {
+                let last_run = if count > 0 {
+                    Some(now.to_rfc3339())
+                } else {
+                    job.last_run.map(|t| t.to_rfc3339())
+                };
+                conn.execute(
+                    "UPDATE jobs SET next_run = ?1, last_run = ?2 WHERE id = ?3",
+                    params![next_run.map(|t| t.to_rfc3339()), last_run, job.id],
+                )?;
+            }
+        }
+
+        Ok(runs)
+    }
+
+    /// Spawns a background task calling [`Self::run_due`] every `interval`.
+    pub fn start(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            info!("Job scheduler started (interval {:?})", interval);
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.run_due().await {
+                    warn!("Job scheduler tick failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Returns the occurrences of `job` due at `now` and its next run after `now`.
+    fn occurrences(&self, job: &ScheduledJob, now: DateTime<Utc>) -> Result<Occurrences> {
+        let first = match job.next_run {
+            Some(first) => first,
+            None => return Ok((Vec::new(), None)),
+        };
+        if job.cron_expression == ONE_SHOT_EXPRESSION {
+            return Ok((vec![first], None));
+        }
+
+        let schedule = parse_schedule(&job.cron_expression)?;
+        let mut occurrences = vec![first];
+        occurrences.extend(
+            schedule
+                .after(&first)
+                .take_while(|t| *t <= now)
+                .take(MAX_MISSED_SCAN),
+        );
+        Ok((occurrences, schedule.after(&now).next()))
+    }
+
+    /// Returns how many times a job should run for the given due occurrences.
+    fn runs_for(&self, occurrences: &[DateTime<Utc>], now: DateTime<Utc>) -> usize {
+        let on_time = occurrences.iter().any(|t| now - *t <= self.grace);
+        let missed = occurrences
+            .iter()
+            .filter(|t| now - **t > self.grace)
+            .count();
+        let catch_up = match self.catch_up {
+            CatchUpPolicy::Skip => 0,
+            CatchUpPolicy::RunOnce => usize::from(missed > 0 && !on_time),
+            CatchUpPolicy::RunAll { max } => missed.min(max),
+        };
+        usize::from(on_time) + catch_up
+    }
+
+    fn insert(&self, job: &ScheduledJob, one_shot: bool) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO jobs (id, cron_expression, command, next_run, last_run, one_shot, created_at, owner_agent_id, owner_session_key)
+             VALUES (?1, ?2, ?3, ?4, NULL, ?5, ?6, ?7, ?8)",
+            params![
+                job.id,
+                job.cron_expression,
+                job.command,
+                job.next_run.map(|t| t.to_rfc3339()),
+                one_shot,
+                Utc::now().to_rfc3339(),
+                job.owner.as_ref().map(|o| o.agent_id.as_str()),
+                job.owner.as_ref().map(|o| o.session_key.as_str()),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn recurring_job(
+        id: &str,
+        cron_expression: &str,
+        command: &str,
+        owner: Option<&JobOwner>,
+    ) -> Result<ScheduledJob> {
+        let schedule = parse_schedule(cron_expression)?;
+        Ok(ScheduledJob {
+            id: id.to_string(),
+            cron_expression: cron_expression.to_string(),
+            command: command.to_string(),
+            next_run: schedule.upcoming(Utc).next(),
+            last_run: None,
+            owner: owner.cloned(),
+        })
+    }
+
+    fn one_shot_job(
+        id: &str,
+        run_at: DateTime<Utc>,
+        command: &str,
+        owner: Option<&JobOwner>,
+    ) -> ScheduledJob {
+        ScheduledJob {
+            id: id.to_string(),
+            cron_expression: ONE_SHOT_EXPRESSION.to_string(),
+            command: command.to_string(),
+            next_run: Some(run_at),
+            last_run: None,
+            owner: owner.cloned(),
+        }
+    }
+}
+
+#[async_trait]
+impl JobScheduler for SqliteJobScheduler {
+    async fn schedule(
+        &self,
+        id: &str,
+        cron_expression: &str,
+        command: &str,
+    ) -> Result<ScheduledJob> {
+        let job = Self::recurring_job(id, cron_expression, command, None)?;
+        self.insert(&job, false)?;
+        Ok(job)
+    }
+
+    async fn schedule_once(
+        &self,
+        id: &str,
+        run_at: DateTime<Utc>,
+        command: &str,
+    ) -> Result<ScheduledJob> {
+        let job = Self::one_shot_job(id, run_at, command, None);
+        self.insert(&job, true)?;
+        Ok(job)
+    }
+
+    async fn schedule_for(
+        &self,
+        owner: &JobOwner,
+        id: &str,
+        cron_expression: &str,
+        command: &str,
+    ) -> Result<ScheduledJob> {
+        let job = Self::recurring_job(id, cron_expression, command, Some(owner))?;
+        self.insert(&job, false)?;
+        Ok(job)
+    }
+
+    async fn schedule_once_for(
+        &self,
+        owner: &JobOwner,
+        id: &str,
+        run_at: DateTime<Utc>,
+        command: &str,
+    ) -> Result<ScheduledJob> {
+        let job = Self::one_shot_job(id, run_at, command, Some(owner));
+        self.insert(&job, true)?;
+        Ok(job)
+    }
+
+    async fn list(&self) -> Result<Vec<ScheduledJob>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, cron_expression, command, next_run, last_run, owner_agent_id, owner_session_key FROM jobs ORDER BY created_at, id",
+        )?;
+        let rows = stmt.query_map([], row_to_job)?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    async fn run_now(&self, id: &str) -> Result<String> {
+        let job = self
+            .get(id)?
+            .ok_or_else(|| anyhow!("Job '{}' not found", id))?;
+        debug!("Running job {} immediately: {}", id, job.command);
+
+        let output = self.runner.run(&job).await?;
+        self.conn.lock().unwrap().execute(
+            "UPDATE jobs SET last_run = ?1 WHERE id = ?2",
+            params![Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(output)
+    }
+
+    async fn remove(&self, id: &str) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let removed = conn.execute("DELETE FROM jobs WHERE id = ?1", params![id])?;
+        Ok(removed > 0)
+    }
+}
+
+fn parse_schedule(expression: &str) -> Result<Schedule> {
+    Schedule::from_str(expression)
+        .map_err(|e| anyhow!("Invalid cron expression '{}': {}", expression, e))
+}
+
+fn parse_timestamp(value: Option<String>) -> Option<DateTime<Utc>> {
+    value
+        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+        .map(|t| t.with_timezone(&Utc))
+}
+
+fn row_to_job(row: &rusqlite::Row<'_>) -> rusqlite::Result<ScheduledJob> {
+    Ok(ScheduledJob {
+        id: row.get(0)?,
+        cron_expression: row.get(1)?,
+        command: row.get(2)?,
+        next_run: parse_timestamp(row.get(3)?),
+        last_run: parse_timestamp(row.get(4)?),
+        owner: match (row.get(5)?, row.get(6)?) {
+            (Some(agent_id), Some(session_key)) => Some(JobOwner {
+                agent_id,
+                session_key,
+            }),
+            _ => None,
+        },
+    })
+}
+
+fn run_migrations(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS scheduler_schema_version (version INTEGER NOT NULL)",
+    )?;
+    let version: i64 = conn
+        .query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM scheduler_schema_version",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+
+    if version < 1 {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS jobs (
+                id TEXT PRIMARY KEY,
+                cron_expression TEXT NOT NULL,
+                command TEXT NOT NULL,
+                next_run TEXT,
+                last_run TEXT,
+                one_shot INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_jobs_next_run ON jobs(next_run);
+            INSERT INTO scheduler_schema_version (version) VALUES (1);",
+        )?;
+    }
+    if version < 2 {
+        conn.execute_batch(
+            "ALTER TABLE jobs ADD COLUMN owner_agent_id TEXT;
+            ALTER TABLE jobs ADD COLUMN owner_session_key TEXT;
+            INSERT INTO scheduler_schema_version (version) VALUES (2);",
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheduler() -> SqliteJobScheduler {
+        SqliteJobScheduler::in_memory(Arc::new(NoOpJobRunner)).unwrap()
+    }
+
+    #[test]
+    fn test_runs_for_policies() {
+        let now = Utc::now();
+        let late = vec![
+            now - chrono::Duration::hours(3),
+            now - chrono::Duration::hours(2),
+        ];
+        let mut on_time = late.clone();
+        on_time.push(now - chrono::Duration::seconds(5));
+
+        let s = scheduler().with_catch_up_policy(CatchUpPolicy::Skip);
+        assert_eq!(s.runs_for(&late, now), 0);
+        assert_eq!(s.runs_for(&on_time, now), 1);
+
+        let s = scheduler();
+        assert_eq!(s.runs_for(&late, now), 1);
+        assert_eq!(s.runs_for(&on_time, now), 1);
+
+        let s = scheduler().with_catch_up_policy(CatchUpPolicy::RunAll { max: 10 });
+        assert_eq!(s.runs_for(&late, now), 2);
+        assert_eq!(s.runs_for(&on_time, now), 3);
+
+        let s = scheduler().with_catch_up_policy(CatchUpPolicy::RunAll { max: 1 });
+        assert_eq!(s.runs_for(&on_time, now), 2);
+    }
+
+    #[test]
+    fn test_migrations_are_idempotent() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+        let version: i64 = conn
+            .query_row(
+                "SELECT MAX(version) FROM scheduler_schema_version",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(version, 2);
+    }
+}
@@ -0,0 +1,715 @@
+//! Built-in SQL query tool.
+//!
+//! SQLite databases are always supported. PostgreSQL and MySQL backends are
+//! available with the `postgres` and `mysql` features.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use aisopod_config::types::{SqlDriver, SqlToolConfig};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags};
+use serde_json::{json, Value};
+
+use crate::{Tool, ToolContext, ToolResult};
+
+/// Longest cell value rendered in a result table, in characters.
+const MAX_CELL_CHARS: usize = 200;
+
+/// Statement keywords accepted by read-only databases.
+const READ_KEYWORDS: &[&str] = &[
+    "select", "with", "explain", "show", "describe", "desc", "pragma", "values", "table",
+];
+
+/// Returns `true` if the statement starts with a keyword that only reads data.
+///
+/// This is a first line of defense giving the model a clear error; backends
+/// additionally open read-only databases in a read-only mode.
+pub fn is_read_statement(sql: &str) -> bool {
+    let mut rest = sql.trim_start();
+    loop {
+        if let Some(comment) = rest.strip_prefix("--") {
+            rest = comment.split_once('\n').map_or("", |(_, r)| r).trim_start();
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.split_once("*/").map_or("", |(_, r)| r).trim_start();
+        } else if let Some(inner) = rest.strip_prefix('(') {
+            rest = inner.trim_start();
+        } else {
+            break;
+        }
+    }
+    let keyword: String = rest
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    READ_KEYWORDS.contains(&keyword.as_str())
+}
+
+/// The outcome of a query.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryOutput {
+    /// Column names of the returned rows.
+    pub columns: Vec<String>,
+    /// Row values; `None` is SQL `NULL`.
+    pub rows: Vec<Vec<Option<String>>>,
+    /// Whether more rows were available than returned.
+    pub truncated: bool,
+    /// Number of rows changed by a statement that returns no rows.
+    pub rows_affected: Option<u64>,
+}
+
+impl QueryOutput {
+    /// Renders the rows as a Markdown table.
+    pub fn to_markdown(&self) -> String {
+        if let Some(affected) = self.rows_affected {
+            return format!("Statement executed; {} rows affected", affected);
+        }
+        if self.rows.is_empty() {
+            return "Query returned no rows".to_string();
+        }
+
+        let escape = |value: &str| {
+            let mut cell: String = value
+                .replace('|', "\\|")
+                .replace(['\r', '\n'], " ")
+                .chars()
+                .take(MAX_CELL_CHARS)
+                .collect();
+            if value.chars().count() > MAX_CELL_CHARS {
+                cell.push('…');
+            }
+            cell
+        };
+        let mut table = format!(
+            "| {} |\n|{}\n",
+            self.columns
+                .iter()
+                .map(|c| escape(c))
+                .collect::<Vec<_>>()
+                .join(" | "),
+            " --- |".repeat(self.columns.len())
+        );
+        for row in &self.rows {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|value| value.as_deref().map_or("NULL".to_string(), escape))
+                .collect();
+            table.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+        if self.truncated {
+            table.push_str(&format!(
+                "\n(showing the first {} rows; more are available)",
+                self.rows.len()
+            ));
+        }
+        table.trim_end().to_string()
+    }
+}
+
+/// Trait for database backends used by [`SqlTool`].
+#[async_trait]
+pub trait SqlBackend: Send + Sync {
+    /// Returns the driver name used in result metadata.
+    fn driver(&self) -> &str;
+
+    /// Returns `true` if statements that modify data are rejected.
+    fn read_only(&self) -> bool;
+
+    /// Runs one statement, returning at most `max_rows` rows.
+    async fn query(&self, sql: &str, max_rows: usize, timeout: Duration) -> Result<QueryOutput>;
+}
+
+/// A SQLite database file.
+///
+/// Read-only databases are opened with `SQLITE_OPEN_READ_ONLY` and
+/// `PRAGMA query_only`. Timed-out queries are interrupted.
+#[derive(Debug, Clone)]
+pub struct SqliteBackend {
+    path: PathBuf,
+    read_only: bool,
+}
+
+impl SqliteBackend {
+    /// Creates a backend for the database at `path`, which must already exist.
+    pub fn new(path: impl Into<PathBuf>, read_only: bool) -> Self {
+        Self {
+            path: path.into(),
+            read_only,
+        }
+    }
+
+    fn run(conn: &Connection, sql: &str, max_rows: usize) -> Result<QueryOutput> {
+        let mut stmt = conn.prepare(sql)?;
+        if stmt.column_count() == 0 {
+            let affected = stmt.execute([])?;
+            return Ok(QueryOutput {
+                rows_affected: Some(affected as u64),
+                ..Default::default()
+            });
+        }
+
+        let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+        let mut output = QueryOutput {
+            columns,
+            ..Default::default()
+        };
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            if output.rows.len() == max_rows {
+                output.truncated = true;
+                break;
+            }
+            let values = (0..output.columns.len())
+                .map(|i| {
+                    Ok(match row.get_ref(i)? {
+                        ValueRef::Null => None,
+                        ValueRef::Integer(v) => Some(v.to_string()),
+                        ValueRef::Real(v) => Some(v.to_string()),
+                        ValueRef::Text(v) => Some(String::from_utf8_lossy(v).into_owned()),
+                        ValueRef::Blob(v) => Some(format!("<{} bytes>", v.len())),
+                    })
+                })
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            output.rows.push(values);
+        }
+        Ok(output)
+    }
+}
+
+#[async_trait]
+impl SqlBackend for SqliteBackend {
+    fn driver(&self) -> &str {
+        "sqlite"
+    }
+
+    fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    async fn query(&self, sql: &str, max_rows: usize, timeout: Duration) -> Result<QueryOutput> {
+        let access = if self.read_only {
+            OpenFlags::SQLITE_OPEN_READ_ONLY
+        } else {
+            OpenFlags::SQLITE_OPEN_READ_WRITE
+        };
+        let conn = Connection::open_with_flags(
+            &self.path,
+            access | OpenFlags::SQLITE_OPEN_URI | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        if self.read_only {
+            conn.pragma_update(None, "query_only", true)?;
+        }
+
+        let interrupt = conn.get_interrupt_handle();
+        let sql = sql.to_string();
+        let task = tokio::task::spawn_blocking(move || Self::run(&conn, &sql, max_rows));
+        match tokio::time::timeout(timeout, task).await {
+            Ok(joined) => joined?,
+            Err(_) => {
+                interrupt.interrupt();
+                Err(anyhow!(
+                    "Query timed out after {} seconds",
+                    timeout.as_secs()
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+mod remote {
+    //! Backends for database servers, connecting once per query.
+
+    use super::*;
+    use futures_util::TryStreamExt;
+    use sqlx::{Column, Database, Executor, IntoArguments, Row};
+
+    /// Statements wrapping a query in a transaction.
+    pub(super) struct TransactionSql {
+        pub begin_read_only: &'static str,
+        pub begin: &'static str,
+    }
+
+    /// Runs one statement in a transaction that is rolled back when read-only.
+    pub(super) async fn run<DB>(
+        conn: &mut DB::Connection,
+        sql: &str,
+        read_only: bool,
+        max_rows: usize,
+        transaction: &TransactionSql,
+        cell: fn(&DB::Row, usize) -> Option<String>,
+        affected: fn(&DB::QueryResult) -> u64,
+    ) -> Result<QueryOutput>
+    where
+        DB: Database,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+        for<'q> DB::Arguments<'q>: IntoArguments<'q, DB>,
+    {
+        let begin = if read_only {
+            transaction.begin_read_only
+        } else {
+            transaction.begin
+        };
+        conn.execute(begin).await?;
+
+        let mut output = QueryOutput::default();
+        if is_read_statement(sql) {
+            let mut rows = sqlx::query::<DB>(sql).fetch(&mut *conn);
+            while let Some(row) = rows.try_next().await? {
+                if output.columns.is_empty() {
+                    output.columns = row.columns().iter().map(|c| c.name().to_string()).collect();
+                }
+                if output.rows.len() == max_rows {
+                    output.truncated = true;
+                    break;
+                }
+                output
+                    .rows
+                    .push((0..row.len()).map(|i| cell(&row, i)).collect());
+            }
+        } else {
+            let result = sqlx::query::<DB>(sql).execute(&mut *conn).await?;
+            output.rows_affected = Some(affected(&result));
+        }
+
+        conn.execute(if read_only { "ROLLBACK" } else { "COMMIT" })
+            .await?;
+        Ok(output)
+    }
+
+    /// Tries to decode a cell as each listed type, rendering the first match.
+    macro_rules! decode_cell {
+        ($row:expr, $index:expr, $($ty:ty),+) => {
+            $(
+                if let Ok(value) = $row.try_get::<Option<$ty>, _>($index) {
+                    return value.map(|v| v.to_string());
+                }
+            )+
+            if let Ok(value) = $row.try_get::<Option<Vec<u8>>, _>($index) {
+                return value.map(|v| format!("<{} bytes>", v.len()));
+            }
+            return Some(format!(
+                "<unsupported {}>",
+                sqlx::TypeInfo::name($row.column($index).type_info())
+            ));
+        };
+    }
+
+    #[cfg(feature = "postgres")]
+    pub(super) fn postgres_cell(row: &sqlx::postgres::PgRow, index: usize) -> Option<String> {
+        use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+        decode_cell!(
+            row,
+            index,
+            String,
+            i64,
+            i32,
+            i16,
+            f64,
+            f32,
+            bool,
+            DateTime<Utc>,
+            NaiveDateTime,
+            NaiveDate,
+            NaiveTime,
+            Value
+        );
+    }
+
+    #[cfg(feature = "mysql")]
+    pub(super) fn mysql_cell(row: &sqlx::mysql::MySqlRow, index: usize) -> Option<String> {
+        use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+        decode_cell!(
+            row,
+            index,
+            String,
+            i64,
+            i32,
+            i16,
+            i8,
+            u64,
+            u32,
+            u16,
+            u8,
+            f64,
+            f32,
+            bool,
+            DateTime<Utc>,
+            NaiveDateTime,
+            NaiveDate,
+            NaiveTime,
+            Value
+        );
+    }
+
+    pub(super) async fn with_timeout<T>(
+        timeout: Duration,
+        future: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        tokio::time::timeout(timeout, future)
+            .await
+            .map_err(|_| anyhow!("Query timed out after {} seconds", timeout.as_secs()))?
+    }
+}
+
+/// A PostgreSQL database.
+///
+/// Read-only queries run in a `READ ONLY` transaction that is rolled back.
+#[cfg(feature = "postgres")]
+#[derive(Debug, Clone)]
+pub struct PostgresBackend {
+    url: String,
+    read_only: bool,
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresBackend {
+    /// Creates a backend connecting to `url` for each query.
+    pub fn new(url: impl Into<String>, read_only: bool) -> Self {
+        Self {
+            url: url.into(),
+            read_only,
+        }
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[async_trait]
+impl SqlBackend for PostgresBackend {
+    fn driver(&self) -> &str {
+        "postgres"
+    }
+
+    fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    async fn query(&self, sql: &str, max_rows: usize, timeout: Duration) -> Result<QueryOutput> {
+        use sqlx::Connection;
+        remote::with_timeout(timeout, async {
+            let mut conn = sqlx::PgConnection::connect(&self.url).await?;
+            remote::run::<sqlx::Postgres>(
+                &mut conn,
+                sql,
+                self.read_only,
+                max_rows,
+                &remote::TransactionSql {
+                    begin_read_only: "BEGIN READ ONLY",
+                    begin: "BEGIN",
+                },
+                remote::postgres_cell,
+                |result| result.rows_affected(),
+            )
+            .await
+        })
+        .await
+    }
+}
+
+/// A MySQL or MariaDB database.
+///
+/// Read-only queries run in a `READ ONLY` transaction that is rolled back.
+#[cfg(feature = "mysql")]
+#[derive(Debug, Clone)]
+pub struct MySqlBackend {
+    url: String,
+    read_only: bool,
+}
+
+#[cfg(feature = "mysql")]
+impl MySqlBackend {
+    /// Creates a backend connecting to `url` for each query.
+    pub fn new(url: impl Into<String>, read_only: bool) -> Self {
+        Self {
+            url: url.into(),
+            read_only,
+        }
+    }
+}
+
+#[cfg(feature = "mysql")]
+#[async_trait]
+impl SqlBackend for MySqlBackend {
+    fn driver(&self) -> &str {
+        "mysql"
+    }
+
+    fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    async fn query(&self, sql: &str, max_rows: usize, timeout: Duration) -> Result<QueryOutput> {
+        use sqlx::Connection;
+        remote::with_timeout(timeout, async {
+            let mut conn = sqlx::MySqlConnection::connect(&self.url).await?;
+            remote::run::<sqlx::MySql>(
+                &mut conn,
+                sql,
+                self.read_only,
+                max_rows,
+                &remote::TransactionSql {
+                    begin_read_only: "START TRANSACTION READ ONLY",
+                    begin: "START TRANSACTION",
+                },
+                remote::mysql_cell,
+                |result| result.rows_affected(),
+            )
+            .await
+        })
+        .await
+    }
+}
+
+/// A database registered with the tool.
+#[derive(Clone)]
+struct SqlDatabase {
+    backend: Arc<dyn SqlBackend>,
+    max_rows: usize,
+    timeout: Duration,
+}
+
+/// A built-in tool that queries configured SQL databases.
+///
+/// # Parameters
+///
+/// - `query`: The SQL statement to run (required).
+/// - `database`: Name of the database; optional when only one is configured.
+/// - `max_rows`: Optional number of rows, capped by the database's limit.
+///
+/// Databases are read-only unless configured otherwise. Rows are returned
+/// as a Markdown table, with the columns, row count and truncation flag in
+/// the result metadata.
+#[derive(Clone)]
+pub struct SqlTool {
+    databases: BTreeMap<String, SqlDatabase>,
+    max_rows: usize,
+    timeout: Duration,
+}
+
+impl Default for SqlTool {
+    fn default() -> Self {
+        Self::new(100, Duration::from_secs(30))
+    }
+}
+
+impl SqlTool {
+    /// Creates a tool without databases using the given default limits.
+    pub fn new(max_rows: usize, timeout: Duration) -> Self {
+        Self {
+            databases: BTreeMap::new(),
+            max_rows: max_rows.max(1),
+            timeout,
+        }
+    }
+
+    /// Adds a database using the tool's default limits.
+    pub fn with_database(mut self, name: impl Into<String>, backend: Arc<dyn SqlBackend>) -> Self {
+        let database = SqlDatabase {
+            backend,
+            max_rows: self.max_rows,
+            timeout: self.timeout,
+        };
+        self.databases.insert(name.into(), database);
+        self
+    }
+
+    /// Creates a SqlTool from the tools configuration.
+    ///
+    /// Fails if a database uses a driver this build does not support.
+    pub fn from_config(config: &SqlToolConfig) -> Result<Self> {
+        let mut tool = Self::new(config.max_rows, Duration::from_secs(config.timeout));
+        for db in &config.databases {
+            let url = db.url.expose();
+            let backend: Arc<dyn SqlBackend> = match db.driver {
+                SqlDriver::Sqlite => {
+                    let path = url.strip_prefix("sqlite://").unwrap_or(url);
+                    Arc::new(SqliteBackend::new(path, db.read_only))
+                }
+                #[cfg(feature = "postgres")]
+                SqlDriver::Postgres => Arc::new(PostgresBackend::new(url.clone(), db.read_only)),
+                #[cfg(feature = "mysql")]
+                SqlDriver::Mysql => Arc::new(MySqlBackend::new(url.clone(), db.read_only)),
+                #[allow(unreachable_patterns)]
+                driver => {
+                    return Err(anyhow!(
+                        "Database '{}' uses {:?}, which requires building aisopod-tools with the '{}' feature",
+                        db.name,
+                        driver,
+                        format!("{:?}", driver).to_lowercase()
+                    ))
+                }
+            };
+            tool.databases.insert(
+                db.name.clone(),
+                SqlDatabase {
+                    backend,
+                    max_rows: db.max_rows.unwrap_or(config.max_rows).max(1),
+                    timeout: Duration::from_secs(db.timeout.unwrap_or(config.timeout)),
+                },
+            );
+        }
+        Ok(tool)
+    }
+
+    /// Returns the names of the configured databases.
+    pub fn databases(&self) -> Vec<&str> {
+        self.databases.keys().map(String::as_str).collect()
+    }
+}
+
+#[async_trait]
+impl Tool for SqlTool {
+    fn name(&self) -> &str {
+        "sql"
+    }
+
+    fn description(&self) -> &str {
+        "Run SQL queries against configured databases and return the rows as a table"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "The SQL statement to run"
+                },
+                "database": {
+                    "type": "string",
+                    "enum": self.databases(),
+                    "description": "The database to query (optional when only one is configured)"
+                },
+                "max_rows": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Optional maximum number of rows to return"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, params: Value, _ctx: &ToolContext) -> Result<ToolResult> {
+        let query = params
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing required parameter 'query'"))?;
+
+        if query.trim().is_empty() {
+            return Ok(ToolResult::error("Query cannot be empty"));
+        }
+
+        let (name, database) = match params.get("database").and_then(|v| v.as_str()) {
+            Some(name) => match self.databases.get_key_value(name) {
+                Some(entry) => entry,
+                None => {
+                    return Ok(ToolResult::error(format!(
+                        "Unknown database '{}'. Available: {}",
+                        name,
+                        self.databases().join(", ")
+                    )))
+                }
+            },
+            None if self.databases.len() == 1 => self.databases.iter().next().unwrap(),
+            None if self.databases.is_empty() => {
+                return Ok(ToolResult::error("No databases are configured"))
+            }
+            None => {
+                return Ok(ToolResult::error(format!(
+                    "Multiple databases are configured; specify 'database' (one of: {})",
+                    self.databases().join(", ")
+                )))
+            }
+        };
+
+        let backend = &database.backend;
+        if backend.read_only() && !is_read_statement(query) {
+            return Ok(ToolResult::error(format!(
+                "Database '{}' is read-only; only queries that read data are allowed",
+                name
+            )));
+        }
+
+        let max_rows = params
+            .get("max_rows")
+            .and_then(|v| v.as_u64())
+            .map(|n| (n as usize).clamp(1, database.max_rows))
+            .unwrap_or(database.max_rows);
+
+        let output = match backend
+            .query(query.trim(), max_rows, database.timeout)
+            .await
+        {
+            Ok(output) => output,
+            Err(e) => {
+                return Ok(ToolResult::error(format!(
+                    "Query on '{}' failed: {}",
+                    name, e
+                )))
+            }
+        };
+
+        Ok(
+            ToolResult::success(output.to_markdown()).with_metadata(json!({
+                "database": name,
+                "driver": backend.driver(),
+                "columns": output.columns,
+                "row_count": output.rows.len(),
+                "truncated": output.truncated,
+                "rows_affected": output.rows_affected,
+            })),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_read_statement() {
+        assert!(is_read_statement("SELECT 1"));
+        assert!(is_read_statement("  -- list users\n  select * from users"));
+        assert!(is_read_statement(
+            "/* cte */ WITH t AS (SELECT 1) SELECT * FROM t"
+        ));
+        assert!(is_read_statement("(SELECT 1) UNION (SELECT 2)"));
+        assert!(is_read_statement("EXPLAIN SELECT 1"));
+        assert!(!is_read_statement("DELETE FROM users"));
+        assert!(!is_read_statement("insert into t values (1)"));
+        assert!(!is_read_statement("selector"));
+    }
+
+    #[test]
+    fn test_markdown_rendering() {
+        let output = QueryOutput {
+            columns: vec!["id".to_string(), "note".to_string()],
+            rows: vec![
+                vec![Some("1".to_string()), Some("a|b\nc".to_string())],
+                vec![Some("2".to_string()), None],
+            ],
+            truncated: true,
+            rows_affected: None,
+        };
+        assert_eq!(
+            output.to_markdown(),
+            "| id | note |\n| --- | --- |\n| 1 | a\\|b c |\n| 2 | NULL |\n\n\
+             (showing the first 2 rows; more are available)"
+        );
+
+        let affected = QueryOutput {
+            rows_affected: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(
+            affected.to_markdown(),
+            "Statement executed; 3 rows affected"
+        );
+    }
+}
@@ -1,9 +1,16 @@
 //! Built-in subagent spawning tool for agents to spawn child agents.
+//!
+//! When given a [`SessionStore`], the spawning tool persists every run with
+//! its spawn parameters and outcome, so that the parent agent can query the
+//! runs across turns with [`SubagentStatusTool`] and a restarted process can
+//! resume the runs it left unfinished with [`SubagentTool::resume_pending`].
 
+use aisopod_session::{SessionStore, SubagentRun};
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::{json, Value};
 use std::sync::Arc;
+use tracing::warn;
 
 use crate::{Tool, ToolContext, ToolResult};
 
@@ -58,6 +65,8 @@ pub struct SubagentTool {
     max_depth: u32,
     /// Optional allowlist of models that child agents can use.
     model_allowlist: Option<Vec<String>>,
+    /// Optional store in which spawned runs are persisted.
+    store: Option<Arc<SessionStore>>,
 }
 
 impl SubagentTool {
@@ -71,6 +80,59 @@ impl SubagentTool {
             spawner,
             max_depth,
             model_allowlist,
+            store: None,
+        }
+    }
+
+    /// Persists spawned runs in the given session store.
+    pub fn with_store(mut self, store: Arc<SessionStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Resumes the persisted runs left pending or running, e.g. by a crashed
+    /// or restarted process.
+    ///
+    /// Each run is spawned again with its recorded parameters and its
+    /// outcome is persisted. Returns the resumed runs in their final state,
+    /// or an empty list if the tool has no store.
+    pub async fn resume_pending(&self) -> Result<Vec<SubagentRun>> {
+        let Some(store) = &self.store else {
+            return Ok(Vec::new());
+        };
+
+        let mut resumed = Vec::new();
+        for mut run in store.list_unfinished_subagent_runs()? {
+            // The outcome is recorded in the run; a failure does not stop the others
+            if let Err(e) = self.execute_run(store, &mut run).await {
+                warn!("Resumed subagent run '{}' failed: {}", run.id, e);
+            }
+            resumed.push(run);
+        }
+        Ok(resumed)
+    }
+
+    /// Spawns the child agent of a persisted run, recording its progress
+    /// and outcome in the store.
+    async fn execute_run(&self, store: &SessionStore, run: &mut SubagentRun) -> Result<String> {
+        run.start();
+        store.save_subagent_run(run)?;
+
+        match self
+            .spawner
+            .spawn(&run.agent_name, &run.prompt, &run.model)
+            .await
+        {
+            Ok(result) => {
+                run.complete(result.clone());
+                store.save_subagent_run(run)?;
+                Ok(result)
+            }
+            Err(e) => {
+                run.fail(e.to_string());
+                store.save_subagent_run(run)?;
+                Err(e)
+            }
         }
     }
 }
@@ -149,10 +211,106 @@ impl Tool for SubagentTool {
             }
         }
 
-        // Spawn the child agent
-        let result = self.spawner.spawn(agent_name, prompt, model).await?;
+        // Spawn the child agent, persisting the run if a store is configured
+        let Some(store) = &self.store else {
+            let result = self.spawner.spawn(agent_name, prompt, model).await?;
+            return Ok(ToolResult::success(result));
+        };
+
+        let mut run = SubagentRun::new(
+            uuid::Uuid::new_v4().to_string(),
+            &ctx.session_key,
+            agent_name,
+            prompt,
+            model,
+            current_depth + 1,
+        );
+        store.save_subagent_run(&run)?;
+        let result = self.execute_run(store, &mut run).await?;
+
+        Ok(ToolResult::success(result).with_metadata(json!({ "run_id": run.id })))
+    }
+}
+
+/// A built-in tool for querying the subagent runs persisted by
+/// [`SubagentTool`].
+///
+/// Without parameters the tool lists the runs spawned by the current
+/// session; with `run_id` it reports a single run of the session, including
+/// its result or error.
+///
+/// # Example
+///
+/// ```json
+/// {
+///   "run_id": "0b6f7c1e-5d0a-4f3b-9a57-3c2f1d9e8b42"
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SubagentStatusTool {
+    /// The store in which subagent runs are persisted.
+    store: Arc<SessionStore>,
+}
+
+impl SubagentStatusTool {
+    /// Creates a new SubagentStatusTool reading runs from the given store.
+    pub fn new(store: Arc<SessionStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for SubagentStatusTool {
+    fn name(&self) -> &str {
+        "subagent_status"
+    }
+
+    fn description(&self) -> &str {
+        "Check the status and results of subagents spawned by this session"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "run_id": {
+                    "type": "string",
+                    "description": "The subagent run to report; all runs of the session if omitted"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let run_id = params.get("run_id").and_then(|v| v.as_str());
 
-        Ok(ToolResult::success(result))
+        match run_id {
+            Some(run_id) => match self.store.get_subagent_run(run_id)? {
+                Some(run) if run.parent_session_key == ctx.session_key => {
+                    Ok(ToolResult::success(serde_json::to_string_pretty(&run)?))
+                }
+                _ => Ok(ToolResult::error(format!(
+                    "Subagent run '{}' not found",
+                    run_id
+                ))),
+            },
+            None => {
+                let runs = self.store.list_subagent_runs(&ctx.session_key)?;
+                let summaries: Vec<Value> = runs
+                    .iter()
+                    .map(|run| {
+                        json!({
+                            "id": run.id,
+                            "agent_name": run.agent_name,
+                            "status": run.status,
+                            "attempts": run.attempts,
+                            "updated_at": run.updated_at,
+                        })
+                    })
+                    .collect();
+                Ok(ToolResult::success(serde_json::to_string_pretty(&summaries)?))
+            }
+        }
     }
 }
 
@@ -363,6 +521,51 @@ mod tests {
         assert!(output.content.contains("not in the allowlist"));
     }
 
+    #[tokio::test]
+    async fn test_subagent_tool_persists_run() {
+        let store = Arc::new(SessionStore::new_in_memory().unwrap());
+        let tool = SubagentTool::new(Arc::new(NoOpAgentSpawner), 3, None)
+            .with_store(store.clone());
+        let ctx = ToolContext::new("test_agent", "test_session");
+
+        let output = tool
+            .execute(
+                json!({
+                    "agent_name": "child_agent",
+                    "prompt": "Solve the math problem",
+                    "model": "gpt-4"
+                }),
+                &ctx,
+            )
+            .await
+            .unwrap();
+
+        let run_id = output.metadata.unwrap()["run_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let run = store.get_subagent_run(&run_id).unwrap().unwrap();
+        assert_eq!(run.status, aisopod_session::SubagentStatus::Completed);
+        assert_eq!(run.parent_session_key, "test_session");
+        assert_eq!(run.depth, 1);
+        assert_eq!(run.result.as_deref(), Some(output.content.as_str()));
+    }
+
+    #[tokio::test]
+    async fn test_subagent_status_tool_scoped_to_session() {
+        let store = Arc::new(SessionStore::new_in_memory().unwrap());
+        let run = SubagentRun::new("run_1", "other_session", "child", "prompt", "gpt-4", 1);
+        store.save_subagent_run(&run).unwrap();
+        let tool = SubagentStatusTool::new(store);
+        let ctx = ToolContext::new("test_agent", "test_session");
+
+        let output = tool.execute(json!({"run_id": "run_1"}), &ctx).await.unwrap();
+        assert!(output.is_error);
+
+        let output = tool.execute(json!({}), &ctx).await.unwrap();
+        assert_eq!(output.content, "[]");
+    }
+
     #[tokio::test]
     async fn test_noop_spawner() {
         let spawner = NoOpAgentSpawner::default();
@@ -0,0 +1,654 @@
+//! Built-in web search tool with pluggable backends.
+
+use std::collections::HashSet;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use aisopod_config::types::{WebSearchBackend, WebSearchToolConfig};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::{Tool, ToolContext, ToolResult};
+
+/// A single ranked search result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    /// 1-based rank after deduplication.
+    pub rank: usize,
+    /// Result title.
+    pub title: String,
+    /// Result URL.
+    pub url: String,
+    /// Plain-text snippet describing the result.
+    pub snippet: String,
+}
+
+/// Trait for web search backends.
+///
+/// Backends return results in their own relevance order; the tool
+/// deduplicates and assigns final ranks.
+#[async_trait]
+pub trait SearchBackend: Send + Sync {
+    /// Returns the backend name used in result metadata.
+    fn name(&self) -> &str;
+
+    /// Searches for `query`, returning at most `max_results` results.
+    async fn search(&self, query: &str, max_results: usize) -> Result<Vec<SearchResult>>;
+}
+
+fn html_tag_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"<[^>]+>").unwrap())
+}
+
+/// Strips HTML tags and decodes common entities in a snippet.
+fn clean_text(text: &str) -> String {
+    let text = html_tag_regex().replace_all(text, "");
+    let text = text
+        .replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#x27;", "'")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Builds results from a JSON array using the given field names.
+fn parse_json_results(
+    items: Option<&Vec<Value>>,
+    title_key: &str,
+    url_key: &str,
+    snippet_key: &str,
+) -> Vec<SearchResult> {
+    items
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let url = item.get(url_key)?.as_str()?;
+                    Some(SearchResult {
+                        rank: 0,
+                        title: clean_text(item.get(title_key)?.as_str().unwrap_or(url)),
+                        url: url.to_string(),
+                        snippet: clean_text(
+                            item.get(snippet_key).and_then(|v| v.as_str()).unwrap_or(""),
+                        ),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parses a SearXNG `format=json` response.
+pub fn parse_searxng_response(body: &Value) -> Vec<SearchResult> {
+    parse_json_results(
+        body.get("results").and_then(|v| v.as_array()),
+        "title",
+        "url",
+        "content",
+    )
+}
+
+/// Parses a Brave Search API response.
+pub fn parse_brave_response(body: &Value) -> Vec<SearchResult> {
+    parse_json_results(
+        body.pointer("/web/results").and_then(|v| v.as_array()),
+        "title",
+        "url",
+        "description",
+    )
+}
+
+/// Parses a Bing Web Search API response.
+pub fn parse_bing_response(body: &Value) -> Vec<SearchResult> {
+    parse_json_results(
+        body.pointer("/webPages/value").and_then(|v| v.as_array()),
+        "name",
+        "url",
+        "snippet",
+    )
+}
+
+fn ddg_link_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#"(?s)<a[^>]*class="result__a"[^>]*href="([^"]+)"[^>]*>(.*?)</a>"#).unwrap()
+    })
+}
+
+fn ddg_snippet_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"(?s)class="result__snippet"[^>]*>(.*?)</a>"#).unwrap())
+}
+
+/// Resolves DuckDuckGo redirect links (`//duckduckgo.com/l/?uddg=...`).
+fn resolve_ddg_href(href: &str) -> Option<String> {
+    let href = href.replace("&amp;", "&");
+    let absolute = if href.starts_with("//") {
+        format!("https:{}", href)
+    } else {
+        href
+    };
+    let url = Url::parse(&absolute).ok()?;
+    if url
+        .host_str()
+        .is_some_and(|h| h.ends_with("duckduckgo.com"))
+    {
+        return url
+            .query_pairs()
+            .find(|(k, _)| k == "uddg")
+            .map(|(_, v)| v.into_owned());
+    }
+    Some(url.to_string())
+}
+
+/// Parses the DuckDuckGo HTML results page.
+pub fn parse_duckduckgo_html(html: &str) -> Vec<SearchResult> {
+    let snippets: Vec<String> = ddg_snippet_regex()
+        .captures_iter(html)
+        .map(|c| clean_text(&c[1]))
+        .collect();
+
+    ddg_link_regex()
+        .captures_iter(html)
+        .enumerate()
+        .filter_map(|(i, c)| {
+            Some(SearchResult {
+                rank: 0,
+                title: clean_text(&c[2]),
+                url: resolve_ddg_href(&c[1])?,
+                snippet: snippets.get(i).cloned().unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// SearXNG backend using the JSON API of a self-hosted instance.
+#[derive(Debug, Clone)]
+pub struct SearxngBackend {
+    client: reqwest::Client,
+    endpoint: String,
+}
+
+impl SearxngBackend {
+    /// Creates a backend for the SearXNG instance at `endpoint`.
+    pub fn new(client: reqwest::Client, endpoint: impl Into<String>) -> Self {
+        Self {
+            client,
+            endpoint: endpoint.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl SearchBackend for SearxngBackend {
+    fn name(&self) -> &str {
+        "searxng"
+    }
+
+    async fn search(&self, query: &str, max_results: usize) -> Result<Vec<SearchResult>> {
+        let body: Value = self
+            .client
+            .get(format!("{}/search", self.endpoint))
+            .query(&[("q", query), ("format", "json")])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let mut results = parse_searxng_response(&body);
+        results.truncate(max_results);
+        Ok(results)
+    }
+}
+
+/// Brave Search API backend.
+#[derive(Debug, Clone)]
+pub struct BraveBackend {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: String,
+}
+
+impl BraveBackend {
+    /// Default Brave Search API endpoint.
+    pub const DEFAULT_ENDPOINT: &'static str = "https://api.search.brave.com/res/v1/web/search";
+
+    /// Creates a Brave backend with the given API key.
+    pub fn new(client: reqwest::Client, api_key: impl Into<String>) -> Self {
+        Self {
+            client,
+            endpoint: Self::DEFAULT_ENDPOINT.to_string(),
+            api_key: api_key.into(),
+        }
+    }
+
+    /// Overrides the API endpoint.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+}
+
+#[async_trait]
+impl SearchBackend for BraveBackend {
+    fn name(&self) -> &str {
+        "brave"
+    }
+
+    async fn search(&self, query: &str, max_results: usize) -> Result<Vec<SearchResult>> {
+        let body: Value = self
+            .client
+            .get(&self.endpoint)
+            .header("X-Subscription-Token", &self.api_key)
+            .header("Accept", "application/json")
+            .query(&[("q", query), ("count", &max_results.to_string())])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let mut results = parse_brave_response(&body);
+        results.truncate(max_results);
+        Ok(results)
+    }
+}
+
+/// Bing Web Search API backend.
+#[derive(Debug, Clone)]
+pub struct BingBackend {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: String,
+}
+
+impl BingBackend {
+    /// Default Bing Web Search API endpoint.
+    pub const DEFAULT_ENDPOINT: &'static str = "https://api.bing.microsoft.com/v7.0/search";
+
+    /// Creates a Bing backend with the given subscription key.
+    pub fn new(client: reqwest::Client, api_key: impl Into<String>) -> Self {
+        Self {
+            client,
+            endpoint: Self::DEFAULT_ENDPOINT.to_string(),
+            api_key: api_key.into(),
+        }
+    }
+
+    /// Overrides the API endpoint.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+}
+
+#[async_trait]
+impl SearchBackend for BingBackend {
+    fn name(&self) -> &str {
+        "bing"
+    }
+
+    async fn search(&self, query: &str, max_results: usize) -> Result<Vec<SearchResult>> {
+        let body: Value = self
+            .client
+            .get(&self.endpoint)
+            .header("Ocp-Apim-Subscription-Key", &self.api_key)
+            .query(&[("q", query), ("count", &max_results.to_string())])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let mut results = parse_bing_response(&body);
+        results.truncate(max_results);
+        Ok(results)
+    }
+}
+
+/// DuckDuckGo backend scraping the key-less HTML results page.
+#[derive(Debug, Clone)]
+pub struct DuckDuckGoBackend {
+    client: reqwest::Client,
+    endpoint: String,
+}
+
+impl DuckDuckGoBackend {
+    /// Default DuckDuckGo HTML endpoint.
+    pub const DEFAULT_ENDPOINT: &'static str = "https://html.duckduckgo.com/html/";
+
+    /// Creates a DuckDuckGo backend.
+    pub fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            endpoint: Self::DEFAULT_ENDPOINT.to_string(),
+        }
+    }
+
+    /// Overrides the endpoint.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+}
+
+#[async_trait]
+impl SearchBackend for DuckDuckGoBackend {
+    fn name(&self) -> &str {
+        "duckduckgo"
+    }
+
+    async fn search(&self, query: &str, max_results: usize) -> Result<Vec<SearchResult>> {
+        let html = self
+            .client
+            .get(&self.endpoint)
+            .header("User-Agent", "Mozilla/5.0 (compatible; aisopod)")
+            .query(&[("q", query)])
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let mut results = parse_duckduckgo_html(&html);
+        results.truncate(max_results);
+        Ok(results)
+    }
+}
+
+/// A built-in tool that searches the web and returns ranked snippets.
+///
+/// # Parameters
+///
+/// - `query`: The search query (required).
+/// - `max_results`: Optional number of results, capped by the configured maximum.
+///
+/// The result content lists each hit as a numbered title, URL, and snippet.
+/// The same results are returned as structured `metadata.results` for
+/// retrieval-augmented answers that need to cite sources.
+#[derive(Clone)]
+pub struct WebSearchTool {
+    backend: Arc<dyn SearchBackend>,
+    max_results: usize,
+}
+
+impl WebSearchTool {
+    /// Creates a new WebSearchTool using the given backend.
+    pub fn new(backend: Arc<dyn SearchBackend>) -> Self {
+        Self {
+            backend,
+            max_results: 5,
+        }
+    }
+
+    /// Sets the maximum number of results returned.
+    pub fn with_max_results(mut self, max_results: usize) -> Self {
+        self.max_results = max_results.max(1);
+        self
+    }
+
+    /// Creates a WebSearchTool from the tools configuration.
+    ///
+    /// Fails if the selected backend is missing its endpoint or API key.
+    pub fn from_config(config: &WebSearchToolConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout))
+            .build()
+            .context("Failed to build HTTP client for web search")?;
+        let api_key = || {
+            config
+                .api_key
+                .as_ref()
+                .map(|k| k.expose().clone())
+                .filter(|k| !k.is_empty())
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Web search backend {:?} requires an api_key",
+                        config.backend
+                    )
+                })
+        };
+
+        let backend: Arc<dyn SearchBackend> = match config.backend {
+            WebSearchBackend::Searxng => {
+                let endpoint = config.endpoint.clone().ok_or_else(|| {
+                    anyhow::anyhow!("Web search backend Searxng requires an endpoint")
+                })?;
+                Arc::new(SearxngBackend::new(client, endpoint))
+            }
+            WebSearchBackend::Brave => {
+                let mut backend = BraveBackend::new(client, api_key()?);
+                if let Some(endpoint) = &config.endpoint {
+                    backend = backend.with_endpoint(endpoint.clone());
+                }
+                Arc::new(backend)
+            }
+            WebSearchBackend::Bing => {
+                let mut backend = BingBackend::new(client, api_key()?);
+                if let Some(endpoint) = &config.endpoint {
+                    backend = backend.with_endpoint(endpoint.clone());
+                }
+                Arc::new(backend)
+            }
+            WebSearchBackend::Duckduckgo => {
+                let mut backend = DuckDuckGoBackend::new(client);
+                if let Some(endpoint) = &config.endpoint {
+                    backend = backend.with_endpoint(endpoint.clone());
+                }
+                Arc::new(backend)
+            }
+        };
+
+        Ok(Self::new(backend).with_max_results(config.max_results))
+    }
+
+    /// Removes duplicate URLs and assigns final ranks.
+    fn rank(results: Vec<SearchResult>, limit: usize) -> Vec<SearchResult> {
+        let mut seen = HashSet::new();
+        results
+            .into_iter()
+            .filter(|r| seen.insert(r.url.trim_end_matches('/').to_string()))
+            .take(limit)
+            .enumerate()
+            .map(|(i, r)| SearchResult { rank: i + 1, ..r })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Tool for WebSearchTool {
+    fn name(&self) -> &str {
+        "web_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search the web and return ranked results with URLs and snippets"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "The search query"
+                },
+                "max_results": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Optional number of results to return"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, params: Value, _ctx: &ToolContext) -> Result<ToolResult> {
+        let query = params
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required parameter 'query'"))?;
+
+        if query.trim().is_empty() {
+            return Ok(ToolResult::error("Query cannot be empty"));
+        }
+
+        let limit = params
+            .get("max_results")
+            .and_then(|v| v.as_u64())
+            .map(|n| (n as usize).clamp(1, self.max_results))
+            .unwrap_or(self.max_results);
+
+        let results = match self.backend.search(query.trim(), limit).await {
+            Ok(results) => Self::rank(results, limit),
+            Err(e) => {
+                return Ok(ToolResult::error(format!(
+                    "Web search via {} failed: {}",
+                    self.backend.name(),
+                    e
+                )))
+            }
+        };
+
+        let content = if results.is_empty() {
+            format!("No results found for '{}'", query)
+        } else {
+            results
+                .iter()
+                .map(|r| {
+                    let mut entry = format!("{}. {}\n   {}", r.rank, r.title, r.url);
+                    if !r.snippet.is_empty() {
+                        entry.push_str(&format!("\n   {}", r.snippet));
+                    }
+                    entry
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        };
+
+        Ok(ToolResult::success(content).with_metadata(json!({
+            "query": query,
+            "backend": self.backend.name(),
+            "results": results,
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aisopod_config::sensitive::Sensitive;
+
+    struct MockBackend(Vec<SearchResult>);
+
+    #[async_trait]
+    impl SearchBackend for MockBackend {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn search(&self, _query: &str, _max_results: usize) -> Result<Vec<SearchResult>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn result(url: &str) -> SearchResult {
+        SearchResult {
+            rank: 0,
+            title: format!("Title {}", url),
+            url: url.to_string(),
+            snippet: "snippet".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_json_backends() {
+        let searx = json!({"results": [{"title": "Rust", "url": "https://rust-lang.org", "content": "A <b>language</b>"}]});
+        let parsed = parse_searxng_response(&searx);
+        assert_eq!(parsed[0].url, "https://rust-lang.org");
+        assert_eq!(parsed[0].snippet, "A language");
+
+        let brave = json!({"web": {"results": [{"title": "Brave", "url": "https://brave.com", "description": "d"}]}});
+        assert_eq!(parse_brave_response(&brave)[0].title, "Brave");
+
+        let bing = json!({"webPages": {"value": [{"name": "Bing", "url": "https://bing.com", "snippet": "s"}]}});
+        assert_eq!(parse_bing_response(&bing)[0].title, "Bing");
+
+        assert!(parse_bing_response(&json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_parse_duckduckgo_html() {
+        let html = r#"
+            <div class="result">
+              <a rel="nofollow" class="result__a" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fexample.com%2Fpage&amp;rut=abc">Example &amp; Co</a>
+              <a class="result__snippet" href="x">An <b>example</b> page</a>
+            </div>
+        "#;
+        let results = parse_duckduckgo_html(html);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://example.com/page");
+        assert_eq!(results[0].title, "Example & Co");
+        assert_eq!(results[0].snippet, "An example page");
+    }
+
+    #[tokio::test]
+    async fn test_web_search_ranks_and_dedupes() {
+        let backend = MockBackend(vec![
+            result("https://a.com"),
+            result("https://a.com/"),
+            result("https://b.com"),
+            result("https://c.com"),
+        ]);
+        let tool = WebSearchTool::new(Arc::new(backend)).with_max_results(2);
+        let ctx = ToolContext::new("agent", "session");
+
+        let output = tool
+            .execute(json!({"query": "test", "max_results": 10}), &ctx)
+            .await
+            .unwrap();
+        assert!(!output.is_error);
+        assert!(output.content.starts_with("1. Title https://a.com"));
+
+        let metadata = output.metadata.unwrap();
+        let results = metadata["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1]["url"], "https://b.com");
+        assert_eq!(results[1]["rank"], 2);
+        assert_eq!(metadata["backend"], "mock");
+    }
+
+    #[tokio::test]
+    async fn test_web_search_empty_query() {
+        let tool = WebSearchTool::new(Arc::new(MockBackend(vec![])));
+        let ctx = ToolContext::new("agent", "session");
+
+        let output = tool.execute(json!({"query": "  "}), &ctx).await.unwrap();
+        assert!(output.is_error);
+        assert!(tool.execute(json!({}), &ctx).await.is_err());
+    }
+
+    #[test]
+    fn test_from_config_requires_credentials() {
+        let mut config = WebSearchToolConfig {
+            backend: WebSearchBackend::Brave,
+            ..Default::default()
+        };
+        assert!(WebSearchTool::from_config(&config).is_err());
+
+        config.api_key = Some(Sensitive::new("key".to_string()));
+        assert!(WebSearchTool::from_config(&config).is_ok());
+
+        config.backend = WebSearchBackend::Searxng;
+        assert!(WebSearchTool::from_config(&config).is_err());
+
+        config.endpoint = Some("http://localhost:8888".to_string());
+        let tool = WebSearchTool::from_config(&config).unwrap();
+        assert_eq!(tool.backend.name(), "searxng");
+    }
+}
@@ -10,6 +10,13 @@
 //! - [`ToolContext`]: Context information passed to tool execution.
 //! - [`ToolResult`]: The result returned by tool execution.
 //! - [`ToolRegistry`]: Central registry for managing registered tools.
+//! - [`ToolOutputSink`]: Receives incremental output from streaming tools.
+//!
+//! ## MCP Integration
+//!
+//! The [`mcp`] module connects to Model Context Protocol servers over stdio or
+//! SSE and registers their tools as [`McpTool`] instances. [`McpServer`] does
+//! the reverse, exporting allowlisted local tools to external MCP clients.
 //!
 //! ## Schema Normalization
 //!
@@ -63,17 +70,28 @@
 
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
+use aisopod_config::types::ToolsConfig;
 use anyhow::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
 pub mod policy;
-pub use policy::{ToolPolicy, ToolPolicyEngine};
+pub use policy::{HostPolicy, ToolPolicy, ToolPolicyEngine};
+
+pub mod quota;
+pub use quota::{QuotaPermit, QuotaTracker, QuotaViolation, ToolQuota};
 
 pub mod registry;
 pub use registry::ToolRegistry;
 
+pub mod validation;
+pub use validation::{InvalidParams, ParamError, ParamValidator};
+
+pub mod streaming;
+pub use streaming::{OutputStream, ToolOutputChunk, ToolOutputSink};
+
 pub mod schema;
 pub use schema::{
     to_anthropic_batch, to_anthropic_format, to_gemini_batch, to_gemini_format, to_openai_batch,
@@ -83,28 +101,85 @@ pub use schema::{
 pub mod approval;
 pub use approval::{
     is_auto_approved, ApprovalError, ApprovalHandler, ApprovalRequest, ApprovalResponse,
-    ApprovalState, ApprovalStateTracker, ApprovalSummary, NoOpApprovalHandler, RiskLevel,
+    ApprovalState, ApprovalStateTracker, ApprovalSummary, DenyApprovalHandler,
+    NoOpApprovalHandler, RiskLevel,
 };
 
 pub mod builtins;
 pub use builtins::{
-    BashTool, CanvasRenderer, CanvasTool, CronTool, FileTool, InMemoryCanvasRenderer, JobScheduler,
-    MessageSender, MessageTool, NoOpAgentSpawner, NoOpJobScheduler, NoOpMessageSender,
-    NoOpSessionManager, ScheduledJob, SessionManager, SessionTool, SubagentTool,
+    BashTool, BrowserDriver, BrowserTool, CanvasRenderer, CanvasTool, CatchUpPolicy, CronTool,
+    DocsTool, FileTool, GitTool, HandoffTool, HttpTool, InMemoryCanvasRenderer, JobOwner, JobRun,
+    JobRunner, JobScheduler, MessageSender, MessageTool, NoOpAgentSpawner, NoOpBrowserDriver,
+    NoOpJobRunner, NoOpJobScheduler, NoOpMessageSender, NoOpSessionHandoff, NoOpSessionManager,
+    PythonTool, ScheduledJob, SessionHandoff, SessionManager, SessionTool, SqlTool,
+    SqliteJobScheduler, SubagentStatusTool, SubagentTool, ToolContextResolver, ToolJobRunner,
+    WebSearchTool,
 };
 
+pub mod mcp;
+pub use mcp::{McpClient, McpServer, McpTool};
+
 pub mod sandbox;
 pub use sandbox::config;
 pub use aisopod_config::types::{SandboxConfig, SandboxRuntime, WorkspaceAccess};
 pub use sandbox::{ContainerId, ExecutionResult, SandboxExecutor, WorkspaceError, WorkspaceGuard};
 
-/// Registers all built-in tools with the given registry.
+/// Registers all built-in tools with the given registry, using the default
+/// tool settings.
 pub fn register_all_tools(registry: &mut ToolRegistry) {
+    register_configured_tools(registry, &ToolsConfig::default());
+}
+
+/// Registers all built-in tools with the given registry, configured from
+/// the `tools` section of the configuration.
+pub fn register_configured_tools(registry: &mut ToolRegistry, config: &ToolsConfig) {
+    register_configured_tools_with_scheduler(registry, config, Arc::new(NoOpJobScheduler::new()));
+}
+
+/// Like [`register_configured_tools`], with the cron tool scheduling its
+/// jobs with `scheduler`.
+///
+/// The quotas of `tools.quotas` are enforced through a policy engine
+/// attached to the registry.
+pub fn register_configured_tools_with_scheduler(
+    registry: &mut ToolRegistry,
+    config: &ToolsConfig,
+    scheduler: Arc<dyn JobScheduler>,
+) {
+    register_configured_tools_with_services(
+        registry,
+        config,
+        scheduler,
+        Arc::new(NoOpMessageSender),
+    );
+}
+
+/// Like [`register_configured_tools_with_scheduler`], with the message
+/// tool delivering its messages with `sender`.
+pub fn register_configured_tools_with_services(
+    registry: &mut ToolRegistry,
+    config: &ToolsConfig,
+    scheduler: Arc<dyn JobScheduler>,
+    sender: Arc<dyn MessageSender>,
+) {
+    if !config.quotas.is_empty() {
+        let mut engine = ToolPolicyEngine::new();
+        engine.set_quotas_from_config(&config.quotas);
+        registry.set_policy_engine(Arc::new(engine));
+    }
+    registry
+        .quota_tracker()
+        .set_idle_timeout(Duration::from_secs(config.quotas.session_idle_timeout));
+
     registry.register(Arc::new(BashTool::default()));
+    registry.register(Arc::new(BrowserTool::with_noop_driver()));
     registry.register(Arc::new(CanvasTool::with_in_memory()));
-    registry.register(Arc::new(CronTool::with_noop_scheduler()));
+    registry.register(Arc::new(CronTool::new(scheduler)));
     registry.register(Arc::new(FileTool::new()));
-    registry.register(Arc::new(MessageTool::new(Arc::new(NoOpMessageSender))));
+    registry.register(Arc::new(GitTool::default()));
+    registry.register(Arc::new(HttpTool::from_config(&config.http)));
+    registry.register(Arc::new(MessageTool::new(sender)));
+    registry.register(Arc::new(PythonTool::default()));
     registry.register(Arc::new(SubagentTool::new(
         Arc::new(NoOpAgentSpawner),
         3,
@@ -253,4 +328,25 @@ pub trait Tool: Send + Sync {
     /// A `Result` containing the `ToolResult` on success, or an error if
     /// the tool execution failed.
     async fn execute(&self, params: serde_json::Value, ctx: &ToolContext) -> Result<ToolResult>;
+
+    /// Executes the tool, reporting output to `output` as it is produced.
+    ///
+    /// Long-running tools override this to emit progress before the final
+    /// result, which must still contain the complete output. The default
+    /// implementation calls [`execute`](Tool::execute) and emits nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The parameters for the tool, validated against the schema.
+    /// * `ctx` - The execution context containing agent, session, and environment info.
+    /// * `output` - The sink receiving incremental output chunks.
+    async fn execute_streaming(
+        &self,
+        params: serde_json::Value,
+        ctx: &ToolContext,
+        output: &ToolOutputSink,
+    ) -> Result<ToolResult> {
+        let _ = output;
+        self.execute(params, ctx).await
+    }
 }
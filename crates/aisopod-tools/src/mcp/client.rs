@@ -0,0 +1,144 @@
+//! MCP client session handling.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use aisopod_config::types::{McpServerConfig, McpTransportKind};
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use tracing::debug;
+
+use super::protocol::{CallToolResult, McpToolInfo, PROTOCOL_VERSION};
+use super::transport::{McpTransport, SseTransport, StdioTransport};
+
+/// An initialized connection to a single MCP server.
+pub struct McpClient {
+    name: String,
+    transport: Arc<dyn McpTransport>,
+    server_info: Value,
+}
+
+impl std::fmt::Debug for McpClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("McpClient")
+            .field("name", &self.name)
+            .field("server_info", &self.server_info)
+            .finish()
+    }
+}
+
+impl McpClient {
+    /// Connects to the server described by `config` and performs the handshake.
+    pub async fn connect(config: &McpServerConfig) -> Result<Self> {
+        let timeout = Duration::from_secs(config.timeout);
+        let transport: Arc<dyn McpTransport> = match config.transport {
+            McpTransportKind::Stdio => {
+                if config.command.is_empty() {
+                    return Err(anyhow!(
+                        "MCP server '{}' uses the stdio transport but has no command",
+                        config.name
+                    ));
+                }
+                Arc::new(StdioTransport::spawn(
+                    &config.command,
+                    &config.args,
+                    &config.env,
+                    timeout,
+                )?)
+            }
+            McpTransportKind::Sse => {
+                if config.url.is_empty() {
+                    return Err(anyhow!(
+                        "MCP server '{}' uses the sse transport but has no url",
+                        config.name
+                    ));
+                }
+                let headers: HashMap<String, String> = config
+                    .headers
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.expose().clone()))
+                    .collect();
+                Arc::new(SseTransport::connect(&config.url, &headers, timeout).await?)
+            }
+        };
+
+        Self::with_transport(&config.name, transport).await
+    }
+
+    /// Performs the MCP handshake over an already established transport.
+    pub async fn with_transport(
+        name: impl Into<String>,
+        transport: Arc<dyn McpTransport>,
+    ) -> Result<Self> {
+        let name = name.into();
+        let result = transport
+            .request(
+                "initialize",
+                Some(json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": {
+                        "name": "aisopod",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    }
+                })),
+            )
+            .await
+            .map_err(|e| anyhow!("MCP server '{}' failed to initialize: {}", name, e))?;
+        transport.notify("notifications/initialized", None).await?;
+
+        debug!(
+            "Connected to MCP server '{}' (protocol {})",
+            name,
+            result["protocolVersion"].as_str().unwrap_or("unknown")
+        );
+
+        Ok(Self {
+            name,
+            transport,
+            server_info: result.get("serverInfo").cloned().unwrap_or(Value::Null),
+        })
+    }
+
+    /// Returns the configured server name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the `serverInfo` reported during initialization.
+    pub fn server_info(&self) -> &Value {
+        &self.server_info
+    }
+
+    /// Lists every tool exposed by the server, following pagination cursors.
+    pub async fn list_tools(&self) -> Result<Vec<McpToolInfo>> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let params = cursor.as_ref().map(|c| json!({ "cursor": c }));
+            let result = self.transport.request("tools/list", params).await?;
+            let page: Vec<McpToolInfo> =
+                serde_json::from_value(result.get("tools").cloned().unwrap_or(json!([])))?;
+            tools.extend(page);
+
+            match result.get("nextCursor").and_then(|c| c.as_str()) {
+                Some(next) if !next.is_empty() => cursor = Some(next.to_string()),
+                _ => break,
+            }
+        }
+        Ok(tools)
+    }
+
+    /// Invokes a tool on the server.
+    pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<CallToolResult> {
+        let result = self
+            .transport
+            .request(
+                "tools/call",
+                Some(json!({ "name": name, "arguments": arguments })),
+            )
+            .await?;
+        Ok(serde_json::from_value(result)?)
+    }
+}
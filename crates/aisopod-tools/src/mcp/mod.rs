@@ -0,0 +1,77 @@
+//! MCP (Model Context Protocol) integration
+//!
+//! As a client, connects to the MCP servers listed in [`McpConfig`],
+//! discovers the tools they expose and registers each one in a
+//! [`ToolRegistry`] as an [`McpTool`] named `mcp__<server>__<tool>`.
+//!
+//! As a server, [`McpServer`] exports the tools of a local registry that
+//! match the allowlist in [`McpExportConfig`].
+
+pub mod client;
+pub mod protocol;
+pub mod server;
+pub mod tool;
+pub mod transport;
+
+use std::sync::Arc;
+
+pub use aisopod_config::types::{McpConfig, McpExportConfig, McpServerConfig, McpTransportKind};
+use anyhow::Result;
+pub use client::McpClient;
+pub use protocol::{CallToolResult, McpToolInfo};
+pub use server::{sse_router, McpServer};
+pub use tool::{mcp_tool_name, McpTool};
+use tracing::{info, warn};
+pub use transport::{McpTransport, SseTransport, StdioTransport};
+
+use crate::ToolRegistry;
+
+/// Discovers the tools of a connected server and registers them.
+///
+/// Returns the number of tools registered.
+pub async fn register_mcp_tools(
+    registry: &mut ToolRegistry,
+    client: Arc<McpClient>,
+) -> Result<usize> {
+    let tools = client.list_tools().await?;
+    let count = tools.len();
+    for info in tools {
+        registry.register(Arc::new(McpTool::new(client.clone(), info)));
+    }
+    Ok(count)
+}
+
+/// Connects to every enabled server in `config` and registers its tools.
+///
+/// Servers that fail to connect are logged and skipped so one broken server
+/// does not prevent startup. The returned clients keep the connections open
+/// and should be held for as long as the tools are in use.
+pub async fn connect_mcp_servers(
+    registry: &mut ToolRegistry,
+    config: &McpConfig,
+) -> Vec<Arc<McpClient>> {
+    let mut clients = Vec::new();
+    for server in config.servers.iter().filter(|s| s.enabled) {
+        let client = match McpClient::connect(server).await {
+            Ok(client) => Arc::new(client),
+            Err(e) => {
+                warn!("Skipping MCP server '{}': {}", server.name, e);
+                continue;
+            }
+        };
+        match register_mcp_tools(registry, client.clone()).await {
+            Ok(count) => {
+                info!(
+                    "Registered {} tool(s) from MCP server '{}'",
+                    count, server.name
+                );
+                clients.push(client);
+            }
+            Err(e) => warn!(
+                "Failed to list tools of MCP server '{}': {}",
+                server.name, e
+            ),
+        }
+    }
+    clients
+}
@@ -0,0 +1,172 @@
+//! JSON-RPC and MCP message types.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The MCP protocol revision requested during initialization.
+pub const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// An outgoing JSON-RPC request or notification.
+///
+/// Notifications are requests without an `id`.
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonRpcRequest {
+    /// Always `"2.0"`.
+    pub jsonrpc: &'static str,
+    /// Request identifier, absent for notifications.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    /// The method being invoked.
+    pub method: String,
+    /// Method parameters.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+impl JsonRpcRequest {
+    /// Creates a request expecting a response.
+    pub fn new(id: u64, method: impl Into<String>, params: Option<Value>) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id: Some(id),
+            method: method.into(),
+            params,
+        }
+    }
+
+    /// Creates a notification, which receives no response.
+    pub fn notification(method: impl Into<String>, params: Option<Value>) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id: None,
+            method: method.into(),
+            params,
+        }
+    }
+}
+
+/// An incoming JSON-RPC message: a response, a notification or a server request.
+#[derive(Debug, Clone, Deserialize)]
+pub struct JsonRpcMessage {
+    /// Identifier of the request this message answers (or of a server request).
+    #[serde(default)]
+    pub id: Option<Value>,
+    /// Method name for notifications and server requests.
+    #[serde(default)]
+    pub method: Option<String>,
+    /// Successful result.
+    #[serde(default)]
+    pub result: Option<Value>,
+    /// Error result.
+    #[serde(default)]
+    pub error: Option<JsonRpcError>,
+}
+
+/// A JSON-RPC error object.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    /// Error code.
+    pub code: i64,
+    /// Human-readable message.
+    pub message: String,
+    /// Optional additional data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+/// A tool advertised by an MCP server in `tools/list`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpToolInfo {
+    /// Tool name as known to the server.
+    pub name: String,
+    /// Tool description.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// JSON Schema of the tool arguments.
+    #[serde(rename = "inputSchema", default = "empty_object_schema")]
+    pub input_schema: Value,
+}
+
+/// The result of a `tools/call` request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallToolResult {
+    /// Content items (text, image, resource, ...).
+    #[serde(default)]
+    pub content: Vec<Value>,
+    /// Whether the tool reported an error.
+    #[serde(rename = "isError", default)]
+    pub is_error: bool,
+}
+
+impl CallToolResult {
+    /// Renders the content items as text suitable for a model.
+    ///
+    /// Text items are included verbatim; binary items are summarized.
+    pub fn text(&self) -> String {
+        self.content
+            .iter()
+            .map(|item| match item.get("type").and_then(|t| t.as_str()) {
+                Some("text") => item["text"].as_str().unwrap_or_default().to_string(),
+                Some("image") | Some("audio") => format!(
+                    "[{}: {}, {} bytes base64]",
+                    item["type"].as_str().unwrap_or_default(),
+                    item["mimeType"].as_str().unwrap_or("unknown type"),
+                    item["data"].as_str().map(str::len).unwrap_or(0)
+                ),
+                Some("resource") => {
+                    let resource = &item["resource"];
+                    match resource.get("text").and_then(|t| t.as_str()) {
+                        Some(text) => text.to_string(),
+                        None => format!(
+                            "[resource: {}]",
+                            resource["uri"].as_str().unwrap_or("unknown")
+                        ),
+                    }
+                }
+                _ => item.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn empty_object_schema() -> Value {
+    serde_json::json!({"type": "object", "properties": {}})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_notification_has_no_id() {
+        let value = serde_json::to_value(JsonRpcRequest::notification("ping", None)).unwrap();
+        assert_eq!(value, json!({"jsonrpc": "2.0", "method": "ping"}));
+    }
+
+    #[test]
+    fn test_tool_info_defaults_schema() {
+        let info: McpToolInfo = serde_json::from_value(json!({"name": "echo"})).unwrap();
+        assert_eq!(info.input_schema["type"], "object");
+        assert!(info.description.is_none());
+    }
+
+    #[test]
+    fn test_call_result_text() {
+        let result: CallToolResult = serde_json::from_value(json!({
+            "content": [
+                {"type": "text", "text": "hello"},
+                {"type": "image", "mimeType": "image/png", "data": "aGk="},
+                {"type": "resource", "resource": {"uri": "file:///a.txt", "text": "file body"}},
+                {"type": "resource", "resource": {"uri": "file:///b.bin", "blob": "AA=="}}
+            ]
+        }))
+        .unwrap();
+        assert!(!result.is_error);
+        assert_eq!(
+            result.text(),
+            "hello\n[image: image/png, 4 bytes base64]\nfile body\n[resource: file:///b.bin]"
+        );
+    }
+}
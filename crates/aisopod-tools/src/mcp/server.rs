@@ -0,0 +1,485 @@
+//! MCP server mode exporting the local [`ToolRegistry`].
+//!
+//! [`McpServer`] answers `initialize`, `ping`, `tools/list` and `tools/call`
+//! for the tools matched by its export allowlist. It can be served over
+//! stdio ([`McpServer::serve_stdio`]) or HTTP with Server-Sent Events
+//! ([`sse_router`] / [`McpServer::serve_sse`]).
+//!
+//! Over HTTP, requests must carry one of the server's bearer tokens when
+//! any are set, and that token must grant tool execution (the
+//! `operator.admin` or `operator.write` scope). Browser requests are only
+//! accepted from the allowed origins. Tool calls go through the registry's policy and quota checks,
+//! and operations needing approval are denied unless an approval handler
+//! is attached, since no operator is asked otherwise. Tools run in the
+//! workspace and sandbox of the export agent when a context resolver is
+//! set.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use aisopod_config::types::{McpExportConfig, TokenCredential};
+use anyhow::Result;
+use axum::extract::{Query, Request, State};
+use axum::http::header::{AUTHORIZATION, ORIGIN};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use futures_util::stream::{self, Stream};
+use futures_util::StreamExt;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tracing::{debug, info};
+
+use super::protocol::PROTOCOL_VERSION;
+use crate::{
+    ApprovalHandler, DenyApprovalHandler, Tool, ToolContext, ToolContextResolver, ToolRegistry,
+};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Scopes allowing a token to call exported tools.
+const TOOL_SCOPES: &[&str] = &["operator.admin", "operator.write"];
+
+/// Serves tools from a [`ToolRegistry`] to MCP clients.
+pub struct McpServer {
+    registry: Arc<ToolRegistry>,
+    exported: Vec<String>,
+    agent_id: String,
+    auth_tokens: Vec<TokenCredential>,
+    allowed_origins: Vec<String>,
+    approval_handler: Arc<dyn ApprovalHandler>,
+    contexts: Option<Arc<dyn ToolContextResolver>>,
+}
+
+impl std::fmt::Debug for McpServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("McpServer")
+            .field("exported", &self.exported)
+            .field("agent_id", &self.agent_id)
+            .field("auth_tokens", &self.auth_tokens.len())
+            .field("allowed_origins", &self.allowed_origins)
+            .finish()
+    }
+}
+
+impl McpServer {
+    /// Creates a server for `registry` that exports no tools yet.
+    pub fn new(registry: Arc<ToolRegistry>) -> Self {
+        Self {
+            registry,
+            exported: Vec::new(),
+            agent_id: "mcp".to_string(),
+            auth_tokens: Vec::new(),
+            allowed_origins: Vec::new(),
+            approval_handler: Arc::new(DenyApprovalHandler),
+            contexts: None,
+        }
+    }
+
+    /// Creates a server using the export settings from configuration.
+    pub fn from_config(registry: Arc<ToolRegistry>, config: &McpExportConfig) -> Self {
+        Self::new(registry)
+            .with_exported_tools(config.tools.clone())
+            .with_agent_id(config.agent_id.clone())
+            .with_allowed_origins(config.allowed_origins.clone())
+    }
+
+    /// Sets the export allowlist.
+    ///
+    /// Entries are exact tool names, `*` for every tool, or a prefix ending in `*`.
+    pub fn with_exported_tools(mut self, patterns: Vec<String>) -> Self {
+        self.exported = patterns;
+        self
+    }
+
+    /// Sets the agent ID used in the context of exported tool calls.
+    pub fn with_agent_id(mut self, agent_id: impl Into<String>) -> Self {
+        self.agent_id = agent_id.into();
+        self
+    }
+
+    /// Sets the bearer tokens known to the HTTP transport.
+    ///
+    /// Only tokens with a scope granting tool execution are accepted; the
+    /// others are refused. Without tokens, HTTP clients are not
+    /// authenticated.
+    pub fn with_auth_tokens(mut self, tokens: Vec<TokenCredential>) -> Self {
+        self.auth_tokens = tokens;
+        self
+    }
+
+    /// Returns whether HTTP clients must present a bearer token.
+    pub fn requires_auth(&self) -> bool {
+        !self.auth_tokens.is_empty()
+    }
+
+    /// Finds the credential for a presented bearer token.
+    ///
+    /// Every credential is compared, in constant time, so that the timing
+    /// reveals nothing about the configured tokens.
+    fn find_token(&self, token: &str) -> Option<&TokenCredential> {
+        self.auth_tokens.iter().fold(None, |found, credential| {
+            let matches = constant_time_eq(token.as_bytes(), credential.token.as_bytes());
+            found.or(matches.then_some(credential))
+        })
+    }
+
+    /// Sets the browser origins allowed to call the HTTP transport.
+    ///
+    /// Requests carrying any other `Origin` header are rejected.
+    pub fn with_allowed_origins(mut self, origins: Vec<String>) -> Self {
+        self.allowed_origins = origins;
+        self
+    }
+
+    /// Sets the handler asked to approve risky tool operations.
+    pub fn with_approval_handler(mut self, handler: Arc<dyn ApprovalHandler>) -> Self {
+        self.approval_handler = handler;
+        self
+    }
+
+    /// Sets the resolver of the context exported tools run in.
+    ///
+    /// Calls run in the context it resolves for the export agent, e.g. in
+    /// the agent's workspace and sandbox. Without a resolver they run in a
+    /// bare context.
+    pub fn with_context_resolver(mut self, contexts: Arc<dyn ToolContextResolver>) -> Self {
+        self.contexts = Some(contexts);
+        self
+    }
+
+    /// Returns whether the named tool may be listed and called by clients.
+    pub fn is_exported(&self, name: &str) -> bool {
+        self.exported
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => pattern == name,
+            })
+    }
+
+    /// Returns the exported tools, sorted by name.
+    pub fn exported_tools(&self) -> Vec<Arc<dyn Tool>> {
+        let mut names = self.registry.list();
+        names.retain(|name| self.is_exported(name));
+        names.sort();
+        names
+            .iter()
+            .filter_map(|name| self.registry.get(name))
+            .collect()
+    }
+
+    /// Handles one JSON-RPC message and returns the response, if any.
+    ///
+    /// Notifications produce no response. `session_key` identifies the
+    /// client connection in the context passed to tools.
+    pub async fn handle(&self, message: Value, session_key: &str) -> Option<Value> {
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(|m| m.as_str()) else {
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "Missing method",
+            ));
+        };
+        let Some(id) = id else {
+            debug!("MCP notification '{}'", method);
+            return None;
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": { "tools": { "listChanged": false } },
+                "serverInfo": { "name": "aisopod", "version": env!("CARGO_PKG_VERSION") }
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(self.list_tools()),
+            "tools/call" => self.call_tool(&params, session_key).await,
+            _ => Err((METHOD_NOT_FOUND, format!("Method '{}' not found", method))),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, &message),
+        })
+    }
+
+    /// Handles one serialized message, answering malformed JSON with a parse error.
+    pub async fn handle_raw(&self, raw: &str, session_key: &str) -> Option<Value> {
+        match serde_json::from_str::<Value>(raw) {
+            Ok(message) => self.handle(message, session_key).await,
+            Err(e) => Some(error_response(
+                Value::Null,
+                PARSE_ERROR,
+                &format!("Parse error: {}", e),
+            )),
+        }
+    }
+
+    /// Serves newline-delimited JSON-RPC over the given streams until EOF.
+    pub async fn serve_stdio<R, W>(&self, reader: R, mut writer: W) -> Result<()>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut lines = BufReader::new(reader).lines();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_raw(&line, "mcp:stdio").await {
+                let mut out = serde_json::to_string(&response)?;
+                out.push('\n');
+                writer.write_all(out.as_bytes()).await?;
+                writer.flush().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Serves the HTTP with Server-Sent Events transport on `listener`.
+    pub async fn serve_sse(self: Arc<Self>, listener: TcpListener) -> Result<()> {
+        info!("MCP server listening on {}", listener.local_addr()?);
+        axum::serve(listener, sse_router(self)).await?;
+        Ok(())
+    }
+
+    fn list_tools(&self) -> Value {
+        let tools: Vec<Value> = self
+            .exported_tools()
+            .iter()
+            .map(|tool| {
+                json!({
+                    "name": tool.name(),
+                    "description": tool.description(),
+                    "inputSchema": tool.parameters_schema(),
+                })
+            })
+            .collect();
+        json!({ "tools": tools })
+    }
+
+    async fn call_tool(&self, params: &Value, session_key: &str) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(|n| n.as_str())
+            .ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+        if !self.is_exported(name) || self.registry.get(name).is_none() {
+            return Err((INVALID_PARAMS, format!("Unknown tool '{}'", name)));
+        }
+        let arguments = params
+            .get("arguments")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+
+        let ctx = match &self.contexts {
+            Some(contexts) => contexts.tool_context(&self.agent_id, session_key),
+            None => ToolContext::new(&self.agent_id, session_key),
+        }
+        .with_approval_handler(self.approval_handler.clone());
+        let (text, is_error) = match self.registry.execute(name, arguments, &ctx).await {
+            Ok(result) => (result.content, result.is_error),
+            Err(e) => (e.to_string(), true),
+        };
+        Ok(json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": is_error,
+        }))
+    }
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message }
+    })
+}
+
+/// Shared state of the SSE transport: one outbound channel per open stream.
+struct SseState {
+    server: Arc<McpServer>,
+    sessions: Mutex<HashMap<String, mpsc::UnboundedSender<Value>>>,
+    next_session: AtomicU64,
+}
+
+/// Removes a session once its event stream is dropped.
+struct SessionGuard {
+    state: Arc<SseState>,
+    id: String,
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        self.state.sessions.lock().unwrap().remove(&self.id);
+        debug!("MCP SSE session {} closed", self.id);
+    }
+}
+
+/// Builds the router for the SSE transport.
+///
+/// Clients open `GET /sse`, receive an `endpoint` event naming the URL to
+/// POST their messages to, and get responses back as `message` events.
+pub fn sse_router(server: Arc<McpServer>) -> Router {
+    let state = Arc::new(SseState {
+        server,
+        sessions: Mutex::new(HashMap::new()),
+        next_session: AtomicU64::new(0),
+    });
+    Router::new()
+        .route("/sse", get(open_stream))
+        .route("/messages", post(post_message))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            check_request,
+        ))
+        .with_state(state)
+}
+
+/// Constant-time byte comparison to prevent timing attacks.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+/// Rejects requests from disallowed origins, without a valid bearer token,
+/// or whose token does not allow tool execution.
+async fn check_request(
+    State(state): State<Arc<SseState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let server = &state.server;
+    if let Some(origin) = request.headers().get(ORIGIN) {
+        let origin = origin.to_str().unwrap_or_default().trim_end_matches('/');
+        if !server
+            .allowed_origins
+            .iter()
+            .any(|allowed| allowed.trim_end_matches('/') == origin)
+        {
+            debug!("MCP request from origin '{}' rejected", origin);
+            return (StatusCode::FORBIDDEN, "Origin not allowed").into_response();
+        }
+    }
+    if server.requires_auth() {
+        let token = request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        let Some(credential) = token.and_then(|token| server.find_token(token)) else {
+            return (StatusCode::UNAUTHORIZED, "Missing or invalid bearer token").into_response();
+        };
+        if !credential
+            .scopes
+            .iter()
+            .any(|scope| TOOL_SCOPES.contains(&scope.as_str()))
+        {
+            debug!("MCP request with a token lacking tool scopes rejected");
+            return (StatusCode::FORBIDDEN, "Token does not allow tool execution").into_response();
+        }
+    }
+    next.run(request).await
+}
+
+async fn open_stream(
+    State(state): State<Arc<SseState>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let id = format!(
+        "{:x}{:x}",
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0),
+        state.next_session.fetch_add(1, Ordering::Relaxed)
+    );
+    let (tx, rx) = mpsc::unbounded_channel();
+    state.sessions.lock().unwrap().insert(id.clone(), tx);
+    debug!("MCP SSE session {} opened", id);
+
+    let endpoint = Event::default()
+        .event("endpoint")
+        .data(format!("/messages?session_id={}", id));
+    let guard = SessionGuard { state, id };
+    let messages = stream::unfold((rx, guard), |(mut rx, guard)| async move {
+        let message = rx.recv().await?;
+        let event = Event::default().event("message").data(message.to_string());
+        Some((Ok(event), (rx, guard)))
+    });
+
+    Sse::new(stream::once(async move { Ok(endpoint) }).chain(messages))
+        .keep_alive(KeepAlive::default())
+}
+
+async fn post_message(
+    State(state): State<Arc<SseState>>,
+    Query(query): Query<HashMap<String, String>>,
+    body: String,
+) -> impl IntoResponse {
+    let Some(session_id) = query.get("session_id") else {
+        return (StatusCode::BAD_REQUEST, "Missing session_id");
+    };
+    let Some(sender) = state.sessions.lock().unwrap().get(session_id).cloned() else {
+        return (StatusCode::NOT_FOUND, "Unknown session");
+    };
+
+    let session_key = format!("mcp:{}", session_id);
+    if let Some(response) = state.server.handle_raw(&body, &session_key).await {
+        if sender.send(response).is_err() {
+            return (StatusCode::GONE, "Session closed");
+        }
+    }
+    (StatusCode::ACCEPTED, "Accepted")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_patterns() {
+        let server = McpServer::new(Arc::new(ToolRegistry::new()))
+            .with_exported_tools(vec!["file".to_string(), "web_*".to_string()]);
+        assert!(server.is_exported("file"));
+        assert!(server.is_exported("web_search"));
+        assert!(!server.is_exported("bash"));
+        assert!(!server.is_exported("files"));
+
+        let nothing = McpServer::new(Arc::new(ToolRegistry::new()));
+        assert!(!nothing.is_exported("file"));
+    }
+
+    #[tokio::test]
+    async fn test_protocol_errors() {
+        let server = McpServer::new(Arc::new(ToolRegistry::new()));
+
+        let response = server.handle_raw("{not json", "s").await.unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+
+        let response = server
+            .handle(
+                json!({"jsonrpc": "2.0", "id": 1, "method": "resources/list"}),
+                "s",
+            )
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let notification = json!({"jsonrpc": "2.0", "method": "notifications/initialized"});
+        assert!(server.handle(notification, "s").await.is_none());
+    }
+}
@@ -0,0 +1,130 @@
+//! Adapter exposing MCP server tools through the [`Tool`] trait.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::client::McpClient;
+use super::protocol::McpToolInfo;
+use crate::{Tool, ToolContext, ToolResult};
+
+/// Builds the registry name for a server tool: `mcp__<server>__<tool>`.
+///
+/// Characters other than ASCII alphanumerics, `_` and `-` are replaced with
+/// `_` so the name is accepted by every provider's function-calling API.
+pub fn mcp_tool_name(server: &str, tool: &str) -> String {
+    let sanitize = |s: &str| {
+        s.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>()
+    };
+    format!("mcp__{}__{}", sanitize(server), sanitize(tool))
+}
+
+/// A tool provided by a remote MCP server.
+///
+/// The server's input schema is passed through unchanged and calls are
+/// forwarded verbatim as `tools/call` requests.
+pub struct McpTool {
+    client: Arc<McpClient>,
+    info: McpToolInfo,
+    name: String,
+    description: String,
+}
+
+impl std::fmt::Debug for McpTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("McpTool").field("name", &self.name).finish()
+    }
+}
+
+impl McpTool {
+    /// Wraps a tool advertised by `client`.
+    pub fn new(client: Arc<McpClient>, info: McpToolInfo) -> Self {
+        let name = mcp_tool_name(client.name(), &info.name);
+        let description = info
+            .description
+            .clone()
+            .filter(|d| !d.is_empty())
+            .unwrap_or_else(|| format!("Tool '{}' from MCP server '{}'", info.name, client.name()));
+        Self {
+            client,
+            info,
+            name,
+            description,
+        }
+    }
+
+    /// Returns the tool name as known to the MCP server.
+    pub fn remote_name(&self) -> &str {
+        &self.info.name
+    }
+}
+
+#[async_trait]
+impl Tool for McpTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> Value {
+        self.info.input_schema.clone()
+    }
+
+    async fn execute(&self, params: Value, _ctx: &ToolContext) -> Result<ToolResult> {
+        let arguments = if params.is_null() { json!({}) } else { params };
+        let result = match self.client.call_tool(&self.info.name, arguments).await {
+            Ok(result) => result,
+            Err(e) => {
+                return Ok(ToolResult::error(format!(
+                    "MCP server '{}' failed to run '{}': {}",
+                    self.client.name(),
+                    self.info.name,
+                    e
+                )))
+            }
+        };
+
+        let content = result.text();
+        let metadata = json!({
+            "server": self.client.name(),
+            "tool": self.info.name,
+            "content": result.content,
+        });
+        let tool_result = if result.is_error {
+            ToolResult::error(content)
+        } else {
+            ToolResult::success(content)
+        };
+        Ok(tool_result.with_metadata(metadata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mcp_tool_name_sanitizes() {
+        assert_eq!(
+            mcp_tool_name("github", "create_issue"),
+            "mcp__github__create_issue"
+        );
+        assert_eq!(
+            mcp_tool_name("my server", "fs.read"),
+            "mcp__my_server__fs_read"
+        );
+    }
+}
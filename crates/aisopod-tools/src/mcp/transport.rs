@@ -0,0 +1,469 @@
+//! Transports carrying JSON-RPC messages to and from MCP servers.
+
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT};
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+use url::Url;
+
+use super::protocol::{JsonRpcMessage, JsonRpcRequest};
+
+/// A bidirectional channel to an MCP server.
+#[async_trait]
+pub trait McpTransport: Send + Sync {
+    /// Sends a request and waits for its result.
+    async fn request(&self, method: &str, params: Option<Value>) -> Result<Value>;
+
+    /// Sends a notification, which receives no response.
+    async fn notify(&self, method: &str, params: Option<Value>) -> Result<()>;
+}
+
+type Responder = oneshot::Sender<Result<Value>>;
+
+/// Tracks in-flight requests and routes responses back to their callers.
+#[derive(Default)]
+struct PendingRequests {
+    next_id: AtomicU64,
+    waiting: Mutex<HashMap<u64, Responder>>,
+}
+
+impl PendingRequests {
+    fn register(&self) -> (u64, oneshot::Receiver<Result<Value>>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let (tx, rx) = oneshot::channel();
+        self.waiting.lock().unwrap().insert(id, tx);
+        (id, rx)
+    }
+
+    fn forget(&self, id: u64) {
+        self.waiting.lock().unwrap().remove(&id);
+    }
+
+    /// Routes one incoming message to the request it answers.
+    fn dispatch(&self, raw: &str) {
+        let message: JsonRpcMessage = match serde_json::from_str(raw) {
+            Ok(message) => message,
+            Err(e) => {
+                debug!("Ignoring non JSON-RPC line from MCP server: {}", e);
+                return;
+            }
+        };
+
+        if let Some(method) = &message.method {
+            debug!("Ignoring MCP server message '{}'", method);
+            return;
+        }
+
+        let Some(id) = message.id.as_ref().and_then(|v| v.as_u64()) else {
+            debug!("Ignoring MCP response without a numeric id");
+            return;
+        };
+        let Some(responder) = self.waiting.lock().unwrap().remove(&id) else {
+            debug!("Ignoring MCP response for unknown request {}", id);
+            return;
+        };
+
+        let outcome = match message.error {
+            Some(error) => Err(anyhow!("MCP error {}: {}", error.code, error.message)),
+            None => Ok(message.result.unwrap_or(Value::Null)),
+        };
+        let _ = responder.send(outcome);
+    }
+
+    /// Fails every in-flight request, e.g. when the connection is lost.
+    fn fail_all(&self, reason: &str) {
+        for (_, responder) in self.waiting.lock().unwrap().drain() {
+            let _ = responder.send(Err(anyhow!("{}", reason)));
+        }
+    }
+
+    async fn wait(
+        &self,
+        id: u64,
+        rx: oneshot::Receiver<Result<Value>>,
+        method: &str,
+        timeout: Duration,
+    ) -> Result<Value> {
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(outcome)) => outcome,
+            Ok(Err(_)) => Err(anyhow!(
+                "MCP connection closed while waiting for '{}'",
+                method
+            )),
+            Err(_) => {
+                self.forget(id);
+                Err(anyhow!(
+                    "MCP request '{}' timed out after {:?}",
+                    method,
+                    timeout
+                ))
+            }
+        }
+    }
+}
+
+/// Talks to an MCP server spawned as a child process, one JSON message per line.
+pub struct StdioTransport {
+    stdin: tokio::sync::Mutex<ChildStdin>,
+    pending: Arc<PendingRequests>,
+    _child: Child,
+    tasks: Vec<JoinHandle<()>>,
+    timeout: Duration,
+}
+
+impl StdioTransport {
+    /// Spawns the server process and starts reading its output.
+    pub fn spawn(
+        command: &str,
+        args: &[String],
+        env: &HashMap<String, String>,
+        timeout: Duration,
+    ) -> Result<Self> {
+        let mut child = Command::new(command)
+            .args(args)
+            .envs(env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to start MCP server '{}'", command))?;
+
+        let stdin = child.stdin.take().context("MCP server stdin unavailable")?;
+        let stdout = child
+            .stdout
+            .take()
+            .context("MCP server stdout unavailable")?;
+        let stderr = child
+            .stderr
+            .take()
+            .context("MCP server stderr unavailable")?;
+        let pending = Arc::new(PendingRequests::default());
+
+        let reader_pending = pending.clone();
+        let reader = tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if !line.trim().is_empty() {
+                    reader_pending.dispatch(&line);
+                }
+            }
+            reader_pending.fail_all("MCP server closed its output");
+        });
+
+        let server = command.to_string();
+        let logger = tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                debug!("[mcp {}] {}", server, line);
+            }
+        });
+
+        Ok(Self {
+            stdin: tokio::sync::Mutex::new(stdin),
+            pending,
+            _child: child,
+            tasks: vec![reader, logger],
+            timeout,
+        })
+    }
+
+    async fn send(&self, message: &JsonRpcRequest) -> Result<()> {
+        let mut line = serde_json::to_string(message)?;
+        line.push('\n');
+        let mut stdin = self.stdin.lock().await;
+        stdin
+            .write_all(line.as_bytes())
+            .await
+            .context("Failed to write to MCP server")?;
+        stdin.flush().await?;
+        Ok(())
+    }
+}
+
+impl Drop for StdioTransport {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+#[async_trait]
+impl McpTransport for StdioTransport {
+    async fn request(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        let (id, rx) = self.pending.register();
+        if let Err(e) = self.send(&JsonRpcRequest::new(id, method, params)).await {
+            self.pending.forget(id);
+            return Err(e);
+        }
+        self.pending.wait(id, rx, method, self.timeout).await
+    }
+
+    async fn notify(&self, method: &str, params: Option<Value>) -> Result<()> {
+        self.send(&JsonRpcRequest::notification(method, params))
+            .await
+    }
+}
+
+/// A single Server-Sent Event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseEvent {
+    /// Event type, `"message"` when the server did not name one.
+    pub event: String,
+    /// Event payload, with multiple `data:` lines joined by newlines.
+    pub data: String,
+}
+
+/// Incremental parser for a `text/event-stream` body.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    /// Creates an empty parser.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds a chunk of the stream and returns the events it completed.
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+
+        while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+            let raw: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&raw);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(SseEvent {
+                        event: self.event.take().unwrap_or_else(|| "message".to_string()),
+                        data: self.data.join("\n"),
+                    });
+                }
+                self.event = None;
+                self.data.clear();
+                continue;
+            }
+            if line.starts_with(':') {
+                continue;
+            }
+
+            let (field, value) = match line.split_once(':') {
+                Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+                None => (line, ""),
+            };
+            match field {
+                "event" => self.event = Some(value.to_string()),
+                "data" => self.data.push(value.to_string()),
+                _ => {}
+            }
+        }
+
+        events
+    }
+}
+
+/// Talks to a remote MCP server using the HTTP with Server-Sent Events transport.
+///
+/// Responses arrive on a long-lived SSE stream; requests are POSTed to the
+/// endpoint announced by the server in its first `endpoint` event.
+pub struct SseTransport {
+    client: reqwest::Client,
+    post_url: Url,
+    headers: HeaderMap,
+    pending: Arc<PendingRequests>,
+    reader: JoinHandle<()>,
+    timeout: Duration,
+}
+
+impl SseTransport {
+    /// Opens the event stream and waits for the server to announce its endpoint.
+    pub async fn connect(
+        url: &str,
+        headers: &HashMap<String, String>,
+        timeout: Duration,
+    ) -> Result<Self> {
+        let base = Url::parse(url).with_context(|| format!("Invalid MCP server URL '{}'", url))?;
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers {
+            header_map.insert(
+                HeaderName::from_bytes(name.as_bytes())
+                    .with_context(|| format!("Invalid header name '{}'", name))?,
+                HeaderValue::from_str(value)
+                    .with_context(|| format!("Invalid value for header '{}'", name))?,
+            );
+        }
+
+        let client = reqwest::Client::new();
+        let response = client
+            .get(base.clone())
+            .headers(header_map.clone())
+            .header(ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .with_context(|| format!("Failed to connect to MCP server at {}", url))?
+            .error_for_status()?;
+
+        let pending = Arc::new(PendingRequests::default());
+        let reader_pending = pending.clone();
+        let (endpoint_tx, endpoint_rx) = oneshot::channel::<String>();
+        let reader = tokio::spawn(async move {
+            let mut endpoint_tx = Some(endpoint_tx);
+            let mut parser = SseParser::new();
+            let mut stream = response.bytes_stream();
+            while let Some(chunk) = stream.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        warn!("MCP event stream failed: {}", e);
+                        break;
+                    }
+                };
+                for event in parser.feed(&chunk) {
+                    match event.event.as_str() {
+                        "endpoint" => {
+                            if let Some(tx) = endpoint_tx.take() {
+                                let _ = tx.send(event.data);
+                            }
+                        }
+                        "message" => reader_pending.dispatch(&event.data),
+                        other => debug!("Ignoring MCP event '{}'", other),
+                    }
+                }
+            }
+            reader_pending.fail_all("MCP event stream closed");
+        });
+
+        let endpoint = match tokio::time::timeout(timeout, endpoint_rx).await {
+            Ok(Ok(endpoint)) => endpoint,
+            _ => {
+                reader.abort();
+                return Err(anyhow!(
+                    "MCP server at {} did not announce a message endpoint",
+                    url
+                ));
+            }
+        };
+        let post_url = base
+            .join(endpoint.trim())
+            .with_context(|| format!("Invalid MCP message endpoint '{}'", endpoint))?;
+
+        Ok(Self {
+            client,
+            post_url,
+            headers: header_map,
+            pending,
+            reader,
+            timeout,
+        })
+    }
+
+    async fn send(&self, message: &JsonRpcRequest) -> Result<()> {
+        self.client
+            .post(self.post_url.clone())
+            .headers(self.headers.clone())
+            .json(message)
+            .timeout(self.timeout)
+            .send()
+            .await
+            .context("Failed to send MCP message")?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+impl Drop for SseTransport {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+#[async_trait]
+impl McpTransport for SseTransport {
+    async fn request(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        let (id, rx) = self.pending.register();
+        if let Err(e) = self.send(&JsonRpcRequest::new(id, method, params)).await {
+            self.pending.forget(id);
+            return Err(e);
+        }
+        self.pending.wait(id, rx, method, self.timeout).await
+    }
+
+    async fn notify(&self, method: &str, params: Option<Value>) -> Result<()> {
+        self.send(&JsonRpcRequest::notification(method, params))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_sse_parser_handles_split_chunks() {
+        let mut parser = SseParser::new();
+        assert!(parser.feed(b"event: endpoint\r\nda").is_empty());
+        let events = parser.feed(b"ta: /messages?session=1\r\n\r\n: keep-alive\n\ndata: {\"a\":\n");
+        assert_eq!(
+            events,
+            vec![SseEvent {
+                event: "endpoint".to_string(),
+                data: "/messages?session=1".to_string(),
+            }]
+        );
+
+        let events = parser.feed(b"data: 1}\n\n");
+        assert_eq!(events[0].event, "message");
+        assert_eq!(events[0].data, "{\"a\":\n1}");
+    }
+
+    #[tokio::test]
+    async fn test_pending_requests_dispatch() {
+        let pending = PendingRequests::default();
+        let (id, rx) = pending.register();
+        let (error_id, error_rx) = pending.register();
+
+        pending.dispatch(r#"{"jsonrpc":"2.0","method":"notifications/progress"}"#);
+        pending.dispatch(&json!({"jsonrpc": "2.0", "id": id, "result": {"ok": true}}).to_string());
+        pending.dispatch(
+            &json!({"jsonrpc": "2.0", "id": error_id, "error": {"code": -32601, "message": "nope"}})
+                .to_string(),
+        );
+
+        assert_eq!(rx.await.unwrap().unwrap(), json!({"ok": true}));
+        let err = error_rx.await.unwrap().unwrap_err().to_string();
+        assert!(err.contains("-32601"));
+        assert!(err.contains("nope"));
+    }
+
+    #[tokio::test]
+    async fn test_pending_request_timeout() {
+        let pending = PendingRequests::default();
+        let (id, rx) = pending.register();
+        let err = pending
+            .wait(id, rx, "tools/list", Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+        assert!(pending.waiting.lock().unwrap().is_empty());
+    }
+}
@@ -10,6 +10,10 @@
 //! - **Deny list**: If present, tools in the deny list are blocked.
 //! - **Precedence**: Deny lists always take precedence over allow lists.
 //!
+//! The engine also holds [`HostPolicy`] lists that restrict which network
+//! hosts tools such as `http` may contact, and the [`ToolQuota`]s enforced
+//! when tools are executed through the [`ToolRegistry`](crate::ToolRegistry).
+//!
 //! # Example
 //!
 //! ```ignore
@@ -41,6 +45,10 @@
 
 use std::collections::HashMap;
 
+use aisopod_config::types::ToolQuotasConfig;
+
+use crate::quota::ToolQuota;
+
 /// A policy that controls which tools are allowed or denied.
 ///
 /// The policy supports both allow lists (whitelists) and deny lists (blacklists).
@@ -142,6 +150,88 @@ impl ToolPolicy {
     }
 }
 
+/// A policy that controls which network hosts tools may contact.
+///
+/// Entries match a host exactly (case-insensitive), or any subdomain when
+/// written as `*.example.com`. A single `*` matches every host.
+///
+/// # Example
+///
+/// ```ignore
+/// // Only allow the GitHub API and any subdomain of example.com
+/// let policy = HostPolicy::allow_list(vec![
+///     "api.github.com".to_string(),
+///     "*.example.com".to_string(),
+/// ]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HostPolicy {
+    /// Optional allow list - if present, only these hosts may be contacted.
+    pub allow: Option<Vec<String>>,
+    /// Optional deny list - if present, these hosts are blocked.
+    pub deny: Option<Vec<String>>,
+}
+
+impl HostPolicy {
+    /// Creates a new empty host policy with no restrictions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a host policy with only an allow list.
+    ///
+    /// # Arguments
+    ///
+    /// * `allowed_hosts` - Host patterns that may be contacted.
+    pub fn allow_list(allowed_hosts: Vec<String>) -> Self {
+        Self {
+            allow: Some(allowed_hosts),
+            deny: None,
+        }
+    }
+
+    /// Creates a host policy with only a deny list.
+    ///
+    /// # Arguments
+    ///
+    /// * `denied_hosts` - Host patterns that are blocked.
+    pub fn deny_list(denied_hosts: Vec<String>) -> Self {
+        Self {
+            allow: None,
+            deny: Some(denied_hosts),
+        }
+    }
+
+    /// Checks whether a host matches a pattern.
+    fn pattern_matches(pattern: &str, host: &str) -> bool {
+        let pattern = pattern.trim().to_ascii_lowercase();
+        if pattern == "*" {
+            return true;
+        }
+        match pattern.strip_prefix("*.") {
+            Some(suffix) => host.len() > suffix.len() && host.ends_with(&format!(".{}", suffix)),
+            None => pattern == host,
+        }
+    }
+
+    /// Checks if a host is in the deny list.
+    fn is_denied(&self, host: &str) -> bool {
+        match &self.deny {
+            Some(deny_list) => deny_list.iter().any(|p| Self::pattern_matches(p, host)),
+            None => false,
+        }
+    }
+
+    /// Checks if a host is in the allow list.
+    ///
+    /// Returns `None` if no allow list is set.
+    fn is_in_allow_list(&self, host: &str) -> Option<bool> {
+        self.allow
+            .as_ref()
+            .map(|allow_list| allow_list.iter().any(|p| Self::pattern_matches(p, host)))
+    }
+}
+
 /// Engine that evaluates tool access policies for agents.
 ///
 /// The engine holds a global policy that applies to all agents, and
@@ -175,6 +265,10 @@ impl ToolPolicy {
 pub struct ToolPolicyEngine {
     global_policy: ToolPolicy,
     agent_policies: HashMap<String, ToolPolicy>,
+    global_host_policy: HostPolicy,
+    agent_host_policies: HashMap<String, HostPolicy>,
+    global_quotas: HashMap<String, ToolQuota>,
+    agent_quotas: HashMap<String, HashMap<String, ToolQuota>>,
 }
 
 impl ToolPolicyEngine {
@@ -183,6 +277,10 @@ impl ToolPolicyEngine {
         Self {
             global_policy: ToolPolicy::new(),
             agent_policies: HashMap::new(),
+            global_host_policy: HostPolicy::new(),
+            agent_host_policies: HashMap::new(),
+            global_quotas: HashMap::new(),
+            agent_quotas: HashMap::new(),
         }
     }
 
@@ -195,6 +293,10 @@ impl ToolPolicyEngine {
         Self {
             global_policy,
             agent_policies: HashMap::new(),
+            global_host_policy: HostPolicy::new(),
+            agent_host_policies: HashMap::new(),
+            global_quotas: HashMap::new(),
+            agent_quotas: HashMap::new(),
         }
     }
 
@@ -316,6 +418,132 @@ impl ToolPolicyEngine {
         // Rule 5: Tool is allowed
         Ok(())
     }
+
+    /// Sets the global host policy that applies to all agents.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The host policy to apply globally.
+    pub fn set_global_host_policy(&mut self, policy: HostPolicy) {
+        self.global_host_policy = policy;
+    }
+
+    /// Sets a per-agent host policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `agent_id` - The unique identifier of the agent.
+    /// * `policy` - The host policy to apply to this agent.
+    pub fn set_agent_host_policy(&mut self, agent_id: String, policy: HostPolicy) {
+        self.agent_host_policies.insert(agent_id, policy);
+    }
+
+    /// Evaluates whether an agent may contact a network host.
+    ///
+    /// Unlike tool policies, a global host deny cannot be overridden by an
+    /// agent allow list: both deny lists always apply. The agent allow list,
+    /// if present, replaces the global allow list.
+    ///
+    /// # Arguments
+    ///
+    /// * `agent_id` - The unique identifier of the agent.
+    /// * `host` - The host name or IP address being contacted.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The host may be contacted.
+    /// * `Err(String)` - The host is blocked with a descriptive reason.
+    pub fn is_host_allowed(&self, agent_id: &str, host: &str) -> Result<(), String> {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        let agent_policy = self.agent_host_policies.get(agent_id);
+
+        if let Some(policy) = agent_policy {
+            if policy.is_denied(&host) {
+                return Err(format!(
+                    "Host '{}' is denied by host policy for agent '{}'",
+                    host, agent_id
+                ));
+            }
+        }
+
+        if self.global_host_policy.is_denied(&host) {
+            return Err(format!(
+                "Host '{}' is denied by the global host policy",
+                host
+            ));
+        }
+
+        if let Some(allowed) = agent_policy.and_then(|p| p.is_in_allow_list(&host)) {
+            if !allowed {
+                return Err(format!(
+                    "Host '{}' is not in the host allow list for agent '{}'",
+                    host, agent_id
+                ));
+            }
+            return Ok(());
+        }
+
+        if self.global_host_policy.is_in_allow_list(&host) == Some(false) {
+            return Err(format!(
+                "Host '{}' is not in the global host allow list",
+                host
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Sets the quota applied to a tool for all agents.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_name` - The tool the quota applies to, or `*` for every tool.
+    /// * `quota` - The limits to enforce.
+    pub fn set_global_quota(&mut self, tool_name: String, quota: ToolQuota) {
+        self.global_quotas.insert(tool_name, quota);
+    }
+
+    /// Sets a per-agent quota for a tool, replacing the global quota.
+    ///
+    /// # Arguments
+    ///
+    /// * `agent_id` - The unique identifier of the agent.
+    /// * `tool_name` - The tool the quota applies to, or `*` for every tool.
+    /// * `quota` - The limits to enforce.
+    pub fn set_agent_quota(&mut self, agent_id: String, tool_name: String, quota: ToolQuota) {
+        self.agent_quotas
+            .entry(agent_id)
+            .or_default()
+            .insert(tool_name, quota);
+    }
+
+    /// Sets the global and per-agent quotas of the `tools.quotas` section.
+    pub fn set_quotas_from_config(&mut self, config: &ToolQuotasConfig) {
+        for (tool_name, quota) in &config.global {
+            self.set_global_quota(tool_name.clone(), ToolQuota::from_config(quota));
+        }
+        for (agent_id, quotas) in &config.agents {
+            for (tool_name, quota) in quotas {
+                self.set_agent_quota(
+                    agent_id.clone(),
+                    tool_name.clone(),
+                    ToolQuota::from_config(quota),
+                );
+            }
+        }
+    }
+
+    /// Returns the quota that applies when an agent calls a tool.
+    ///
+    /// Lookup order is the agent's quota for the tool, the agent's `*`
+    /// quota, the global quota for the tool and the global `*` quota.
+    pub fn quota_for(&self, agent_id: &str, tool_name: &str) -> Option<&ToolQuota> {
+        let agent_quotas = self.agent_quotas.get(agent_id);
+        agent_quotas
+            .and_then(|quotas| quotas.get(tool_name).or_else(|| quotas.get("*")))
+            .or_else(|| self.global_quotas.get(tool_name))
+            .or_else(|| self.global_quotas.get("*"))
+    }
 }
 
 impl Default for ToolPolicyEngine {
@@ -485,4 +713,64 @@ mod tests {
         assert!(error_msg.contains("bash"));
         assert!(error_msg.contains("agent-1"));
     }
+
+    #[test]
+    fn test_host_policy_wildcards() {
+        assert!(HostPolicy::pattern_matches(
+            "*.example.com",
+            "api.example.com"
+        ));
+        assert!(!HostPolicy::pattern_matches("*.example.com", "example.com"));
+        assert!(!HostPolicy::pattern_matches(
+            "*.example.com",
+            "badexample.com"
+        ));
+        assert!(HostPolicy::pattern_matches("Example.com", "example.com"));
+        assert!(HostPolicy::pattern_matches("*", "anything.test"));
+    }
+
+    #[test]
+    fn test_host_policy_evaluation() {
+        let mut engine = ToolPolicyEngine::new();
+        assert!(engine.is_host_allowed("agent-1", "example.com").is_ok());
+
+        engine.set_global_host_policy(HostPolicy::deny_list(vec!["169.254.169.254".to_string()]));
+        assert!(engine
+            .is_host_allowed("agent-1", "169.254.169.254")
+            .is_err());
+
+        // Agent allow lists cannot lift a global host deny
+        engine.set_agent_host_policy(
+            "agent-1".to_string(),
+            HostPolicy::allow_list(vec!["*".to_string()]),
+        );
+        assert!(engine
+            .is_host_allowed("agent-1", "169.254.169.254")
+            .is_err());
+        assert!(engine.is_host_allowed("agent-1", "api.github.com").is_ok());
+
+        engine.set_global_host_policy(HostPolicy::allow_list(vec!["api.github.com".to_string()]));
+        assert!(engine.is_host_allowed("agent-2", "API.GitHub.com.").is_ok());
+        assert!(engine.is_host_allowed("agent-2", "example.com").is_err());
+        // The agent allow list replaces the global one
+        assert!(engine.is_host_allowed("agent-1", "example.com").is_ok());
+    }
+
+    #[test]
+    fn test_quota_lookup_precedence() {
+        let mut engine = ToolPolicyEngine::new();
+        assert!(engine.quota_for("agent-1", "bash").is_none());
+
+        let global = ToolQuota::new().with_max_calls_per_session(10);
+        let fallback = ToolQuota::new().with_max_concurrent(4);
+        let agent = ToolQuota::new().with_max_calls_per_session(2);
+        engine.set_global_quota("bash".to_string(), global.clone());
+        engine.set_global_quota("*".to_string(), fallback.clone());
+        engine.set_agent_quota("agent-1".to_string(), "bash".to_string(), agent.clone());
+
+        assert_eq!(engine.quota_for("agent-1", "bash"), Some(&agent));
+        assert_eq!(engine.quota_for("agent-1", "file"), Some(&fallback));
+        assert_eq!(engine.quota_for("agent-2", "bash"), Some(&global));
+        assert_eq!(engine.quota_for("agent-2", "file"), Some(&fallback));
+    }
 }
@@ -0,0 +1,520 @@
+//! Tool execution quotas and rate limits.
+//!
+//! A [`ToolQuota`] bounds how often an agent may use a tool: the number of
+//! calls per session, the number of executions running at once, and the
+//! minimum delay between consecutive calls in a session. Quotas are declared
+//! on the [`ToolPolicyEngine`](crate::ToolPolicyEngine) and enforced by the
+//! [`QuotaTracker`] owned by the [`ToolRegistry`](crate::ToolRegistry). The
+//! usage of sessions idle for longer than the tracker's idle timeout is
+//! forgotten, so long-running gateways do not accumulate it.
+//!
+//! Violations are reported to the model as error [`ToolResult`]s carrying
+//! structured metadata, so it can decide whether to wait, use another tool
+//! or give up:
+//!
+//! ```json
+//! {
+//!   "error": "quota_exceeded",
+//!   "kind": "cooldown",
+//!   "tool": "web_search",
+//!   "retry_after_secs": 4.5
+//! }
+//! ```
+//!
+//! # Example
+//!
+//! ```ignore
+//! use std::time::Duration;
+//! use aisopod_tools::{ToolPolicyEngine, ToolQuota};
+//!
+//! let mut engine = ToolPolicyEngine::new();
+//! engine.set_global_quota(
+//!     "web_search".to_string(),
+//!     ToolQuota::new()
+//!         .with_max_calls_per_session(20)
+//!         .with_cooldown(Duration::from_secs(5)),
+//! );
+//! engine.set_agent_quota(
+//!     "worker".to_string(),
+//!     "bash".to_string(),
+//!     ToolQuota::new().with_max_concurrent(1),
+//! );
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use aisopod_config::types::ToolQuotaConfig;
+use serde_json::json;
+
+use crate::ToolResult;
+
+/// Default time after which the usage of an idle session is forgotten.
+pub const DEFAULT_SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Limits applied to the executions of one tool.
+///
+/// Unset limits are not enforced. Call counts and cooldowns are tracked per
+/// agent and session; concurrency is tracked per agent across all sessions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolQuota {
+    /// Maximum number of calls per session.
+    pub max_calls_per_session: Option<u32>,
+    /// Maximum number of executions running at the same time.
+    pub max_concurrent: Option<u32>,
+    /// Minimum delay between the starts of two calls in a session.
+    pub cooldown: Option<Duration>,
+}
+
+impl ToolQuota {
+    /// Creates a quota without any limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of calls per session.
+    pub fn with_max_calls_per_session(mut self, max_calls: u32) -> Self {
+        self.max_calls_per_session = Some(max_calls);
+        self
+    }
+
+    /// Sets the maximum number of concurrent executions.
+    pub fn with_max_concurrent(mut self, max_concurrent: u32) -> Self {
+        self.max_concurrent = Some(max_concurrent);
+        self
+    }
+
+    /// Sets the minimum delay between calls in a session.
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = Some(cooldown);
+        self
+    }
+
+    /// Creates a quota from its configuration.
+    pub fn from_config(config: &ToolQuotaConfig) -> Self {
+        Self {
+            max_calls_per_session: config.max_calls_per_session,
+            max_concurrent: config.max_concurrent,
+            cooldown: config.cooldown.map(Duration::from_secs),
+        }
+    }
+
+    /// Returns `true` if no limit is set.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_calls_per_session.is_none()
+            && self.max_concurrent.is_none()
+            && self.cooldown.is_none()
+    }
+}
+
+/// A call rejected because it would exceed a [`ToolQuota`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuotaViolation {
+    /// The session already used all of its calls to the tool.
+    CallLimit { tool: String, limit: u32 },
+    /// Too many executions of the tool are already running.
+    Concurrency { tool: String, limit: u32 },
+    /// The tool was called too recently in this session.
+    Cooldown { tool: String, retry_after: Duration },
+}
+
+impl QuotaViolation {
+    /// Returns the machine-readable kind of the violation.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            QuotaViolation::CallLimit { .. } => "call_limit",
+            QuotaViolation::Concurrency { .. } => "concurrency",
+            QuotaViolation::Cooldown { .. } => "cooldown",
+        }
+    }
+
+    /// Returns the name of the tool the violation applies to.
+    pub fn tool(&self) -> &str {
+        match self {
+            QuotaViolation::CallLimit { tool, .. }
+            | QuotaViolation::Concurrency { tool, .. }
+            | QuotaViolation::Cooldown { tool, .. } => tool,
+        }
+    }
+
+    /// Converts the violation into an error result for the model.
+    pub fn to_tool_result(&self) -> ToolResult {
+        let mut metadata = json!({
+            "error": "quota_exceeded",
+            "kind": self.kind(),
+            "tool": self.tool(),
+        });
+        match self {
+            QuotaViolation::CallLimit { limit, .. } | QuotaViolation::Concurrency { limit, .. } => {
+                metadata["limit"] = json!(limit);
+            }
+            QuotaViolation::Cooldown { retry_after, .. } => {
+                metadata["retry_after_secs"] = json!(retry_after.as_secs_f64());
+            }
+        }
+        ToolResult::error(self.to_string()).with_metadata(metadata)
+    }
+}
+
+impl fmt::Display for QuotaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuotaViolation::CallLimit { tool, limit } => write!(
+                f,
+                "Quota exceeded: tool '{}' may be called at most {} times per session",
+                tool, limit
+            ),
+            QuotaViolation::Concurrency { tool, limit } => write!(
+                f,
+                "Quota exceeded: at most {} concurrent executions of tool '{}' are allowed; wait for a running call to finish",
+                limit, tool
+            ),
+            QuotaViolation::Cooldown { tool, retry_after } => write!(
+                f,
+                "Quota exceeded: tool '{}' is cooling down; retry in {:.1}s",
+                tool,
+                retry_after.as_secs_f64()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for QuotaViolation {}
+
+/// Usage of one tool by one agent in one session.
+#[derive(Debug)]
+struct SessionUsage {
+    calls: u32,
+    last_call: Instant,
+}
+
+#[derive(Debug)]
+struct QuotaState {
+    /// Keyed by (agent, session, tool).
+    sessions: HashMap<(String, String, String), SessionUsage>,
+    /// Keyed by (agent, tool).
+    in_flight: HashMap<(String, String), u32>,
+    idle_timeout: Duration,
+    last_pruned: Option<Instant>,
+}
+
+impl Default for QuotaState {
+    fn default() -> Self {
+        Self {
+            sessions: HashMap::new(),
+            in_flight: HashMap::new(),
+            idle_timeout: DEFAULT_SESSION_IDLE_TIMEOUT,
+            last_pruned: None,
+        }
+    }
+}
+
+impl QuotaState {
+    /// Forgets the usage of sessions idle for longer than the idle timeout.
+    ///
+    /// Sweeps at most once per timeout, so an entry lives for less than
+    /// twice the timeout after its last call.
+    fn prune_idle(&mut self, now: Instant) {
+        let idle_timeout = self.idle_timeout;
+        if let Some(last_pruned) = self.last_pruned {
+            if now.saturating_duration_since(last_pruned) < idle_timeout {
+                return;
+            }
+        }
+        self.sessions
+            .retain(|_, usage| now.saturating_duration_since(usage.last_call) < idle_timeout);
+        self.last_pruned = Some(now);
+    }
+}
+
+/// Tracks tool usage and enforces [`ToolQuota`]s.
+///
+/// Cloning the tracker shares its state.
+#[derive(Debug, Clone, Default)]
+pub struct QuotaTracker {
+    state: Arc<Mutex<QuotaState>>,
+}
+
+impl QuotaTracker {
+    /// Creates a tracker with no recorded usage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the time after which the usage of an idle session is forgotten.
+    pub fn set_idle_timeout(&self, idle_timeout: Duration) {
+        self.state.lock().unwrap().idle_timeout = idle_timeout;
+    }
+
+    /// Records the start of a call, or rejects it if it would exceed `quota`.
+    ///
+    /// The returned permit counts as a running execution until dropped.
+    pub fn acquire(
+        &self,
+        agent_id: &str,
+        session_key: &str,
+        tool_name: &str,
+        quota: &ToolQuota,
+    ) -> Result<QuotaPermit, QuotaViolation> {
+        self.acquire_at(agent_id, session_key, tool_name, quota, Instant::now())
+    }
+
+    fn acquire_at(
+        &self,
+        agent_id: &str,
+        session_key: &str,
+        tool_name: &str,
+        quota: &ToolQuota,
+        now: Instant,
+    ) -> Result<QuotaPermit, QuotaViolation> {
+        let mut state = self.state.lock().unwrap();
+        state.prune_idle(now);
+        let session_key_tuple = (
+            agent_id.to_string(),
+            session_key.to_string(),
+            tool_name.to_string(),
+        );
+        let flight_key = (agent_id.to_string(), tool_name.to_string());
+
+        if let Some(usage) = state.sessions.get(&session_key_tuple) {
+            if let Some(limit) = quota.max_calls_per_session {
+                if usage.calls >= limit {
+                    return Err(QuotaViolation::CallLimit {
+                        tool: tool_name.to_string(),
+                        limit,
+                    });
+                }
+            }
+            if let Some(cooldown) = quota.cooldown {
+                let elapsed = now.saturating_duration_since(usage.last_call);
+                if elapsed < cooldown {
+                    return Err(QuotaViolation::Cooldown {
+                        tool: tool_name.to_string(),
+                        retry_after: cooldown - elapsed,
+                    });
+                }
+            }
+        } else if quota.max_calls_per_session == Some(0) {
+            return Err(QuotaViolation::CallLimit {
+                tool: tool_name.to_string(),
+                limit: 0,
+            });
+        }
+
+        let running = state.in_flight.get(&flight_key).copied().unwrap_or(0);
+        if let Some(limit) = quota.max_concurrent {
+            if running >= limit {
+                return Err(QuotaViolation::Concurrency {
+                    tool: tool_name.to_string(),
+                    limit,
+                });
+            }
+        }
+
+        state.in_flight.insert(flight_key.clone(), running + 1);
+        let usage = state
+            .sessions
+            .entry(session_key_tuple)
+            .or_insert(SessionUsage {
+                calls: 0,
+                last_call: now,
+            });
+        usage.calls += 1;
+        usage.last_call = now;
+
+        Ok(QuotaPermit {
+            state: self.state.clone(),
+            key: flight_key,
+        })
+    }
+
+    /// Returns how many times the agent called the tool in the session.
+    pub fn calls(&self, agent_id: &str, session_key: &str, tool_name: &str) -> u32 {
+        let key = (
+            agent_id.to_string(),
+            session_key.to_string(),
+            tool_name.to_string(),
+        );
+        self.state
+            .lock()
+            .unwrap()
+            .sessions
+            .get(&key)
+            .map_or(0, |usage| usage.calls)
+    }
+
+    /// Returns how many executions of the tool by the agent are running.
+    pub fn running(&self, agent_id: &str, tool_name: &str) -> u32 {
+        let key = (agent_id.to_string(), tool_name.to_string());
+        self.state
+            .lock()
+            .unwrap()
+            .in_flight
+            .get(&key)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Forgets the usage recorded for a session, e.g. after it was reset.
+    pub fn reset_session(&self, session_key: &str) {
+        self.state
+            .lock()
+            .unwrap()
+            .sessions
+            .retain(|(_, session, _), _| session != session_key);
+    }
+}
+
+/// A running execution counted against a concurrency limit.
+///
+/// Dropping the permit marks the execution as finished.
+#[derive(Debug)]
+pub struct QuotaPermit {
+    state: Arc<Mutex<QuotaState>>,
+    key: (String, String),
+}
+
+impl Drop for QuotaPermit {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(running) = state.in_flight.get_mut(&self.key) {
+            *running = running.saturating_sub(1);
+            if *running == 0 {
+                state.in_flight.remove(&self.key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_call_limit_per_session() {
+        let tracker = QuotaTracker::new();
+        let quota = ToolQuota::new().with_max_calls_per_session(2);
+
+        for _ in 0..2 {
+            drop(tracker.acquire("a", "s1", "bash", &quota).unwrap());
+        }
+        let violation = tracker.acquire("a", "s1", "bash", &quota).unwrap_err();
+        assert_eq!(
+            violation,
+            QuotaViolation::CallLimit {
+                tool: "bash".to_string(),
+                limit: 2
+            }
+        );
+
+        // Other sessions and agents have their own budget.
+        assert!(tracker.acquire("a", "s2", "bash", &quota).is_ok());
+        assert!(tracker.acquire("b", "s1", "bash", &quota).is_ok());
+
+        tracker.reset_session("s1");
+        assert_eq!(tracker.calls("a", "s1", "bash"), 0);
+        assert!(tracker.acquire("a", "s1", "bash", &quota).is_ok());
+    }
+
+    #[test]
+    fn test_concurrency_released_on_drop() {
+        let tracker = QuotaTracker::new();
+        let quota = ToolQuota::new().with_max_concurrent(1);
+
+        let permit = tracker.acquire("a", "s1", "bash", &quota).unwrap();
+        assert_eq!(tracker.running("a", "bash"), 1);
+        let violation = tracker.acquire("a", "s2", "bash", &quota).unwrap_err();
+        assert_eq!(violation.kind(), "concurrency");
+
+        drop(permit);
+        assert_eq!(tracker.running("a", "bash"), 0);
+        assert!(tracker.acquire("a", "s2", "bash", &quota).is_ok());
+    }
+
+    #[test]
+    fn test_idle_sessions_are_forgotten() {
+        let tracker = QuotaTracker::new();
+        tracker.set_idle_timeout(Duration::from_secs(60));
+        let quota = ToolQuota::new().with_max_calls_per_session(1);
+        let start = Instant::now();
+
+        tracker
+            .acquire_at("a", "s1", "bash", &quota, start)
+            .unwrap();
+        tracker
+            .acquire_at("a", "s2", "bash", &quota, start + Duration::from_secs(50))
+            .unwrap();
+
+        // The next sweep forgets s1 but keeps s2, which was used since.
+        tracker
+            .acquire_at("a", "s3", "bash", &quota, start + Duration::from_secs(70))
+            .unwrap();
+        assert_eq!(tracker.calls("a", "s1", "bash"), 0);
+        assert_eq!(tracker.calls("a", "s2", "bash"), 1);
+        assert!(tracker
+            .acquire_at("a", "s1", "bash", &quota, start + Duration::from_secs(70))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_cooldown() {
+        let tracker = QuotaTracker::new();
+        let quota = ToolQuota::new().with_cooldown(Duration::from_secs(10));
+        let start = Instant::now();
+
+        tracker
+            .acquire_at("a", "s1", "web_search", &quota, start)
+            .unwrap();
+        let violation = tracker
+            .acquire_at(
+                "a",
+                "s1",
+                "web_search",
+                &quota,
+                start + Duration::from_secs(4),
+            )
+            .unwrap_err();
+        assert_eq!(
+            violation,
+            QuotaViolation::Cooldown {
+                tool: "web_search".to_string(),
+                retry_after: Duration::from_secs(6)
+            }
+        );
+        // A rejected call does not restart the cooldown.
+        assert!(tracker
+            .acquire_at(
+                "a",
+                "s1",
+                "web_search",
+                &quota,
+                start + Duration::from_secs(10)
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_violation_result_metadata() {
+        let result = QuotaViolation::Cooldown {
+            tool: "web_search".to_string(),
+            retry_after: Duration::from_millis(1500),
+        }
+        .to_tool_result();
+        assert!(result.is_error);
+        assert!(result.content.contains("retry in 1.5s"));
+        let metadata = result.metadata.unwrap();
+        assert_eq!(metadata["error"], "quota_exceeded");
+        assert_eq!(metadata["kind"], "cooldown");
+        assert_eq!(metadata["tool"], "web_search");
+        assert_eq!(metadata["retry_after_secs"], 1.5);
+
+        let result = QuotaViolation::CallLimit {
+            tool: "bash".to_string(),
+            limit: 3,
+        }
+        .to_tool_result();
+        assert_eq!(result.metadata.unwrap()["limit"], 3);
+    }
+}
@@ -30,7 +30,11 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::Tool;
+use crate::quota::QuotaTracker;
+use crate::validation::ParamValidator;
+use crate::{ApprovalHandler, Tool, ToolContext, ToolOutputSink, ToolPolicyEngine, ToolResult};
+use anyhow::{anyhow, Result};
+use serde_json::json;
 use tracing::warn;
 
 /// A registry that stores and manages tools by name.
@@ -45,6 +49,27 @@ use tracing::warn;
 /// logging a warning and overwriting the existing tool. This allows runtime
 /// reconfiguration while providing visibility into overwrites.
 ///
+/// # Policy and Quotas
+///
+/// When a [`ToolPolicyEngine`] is attached with
+/// [`set_policy_engine`](Self::set_policy_engine), [`execute`](Self::execute)
+/// rejects denied tools and enforces the engine's quotas. Rejections are
+/// returned as error [`ToolResult`]s with structured metadata rather than
+/// as `Err`, so the model can see why a call was refused.
+///
+/// # Parameter Validation
+///
+/// Each tool's parameter schema is compiled when the tool is registered,
+/// and [`execute`](Self::execute) rejects arguments that do not match it
+/// with an `invalid_parameters` error result. Tools whose schema cannot be
+/// compiled are executed without validation.
+///
+/// # Approvals
+///
+/// An [`ApprovalHandler`] attached with
+/// [`set_approval_handler`](Self::set_approval_handler) is passed to tools
+/// whose [`ToolContext`] does not carry one of its own.
+///
 /// # Thread Safety
 ///
 /// The `ToolRegistry` is designed to be `Send` and `Sync` when all contained
@@ -71,6 +96,10 @@ use tracing::warn;
 #[derive(Default)]
 pub struct ToolRegistry {
     tools: HashMap<String, Arc<dyn Tool>>,
+    validators: HashMap<String, ParamValidator>,
+    policy: Option<Arc<ToolPolicyEngine>>,
+    quotas: QuotaTracker,
+    approval_handler: Option<Arc<dyn ApprovalHandler>>,
 }
 
 impl ToolRegistry {
@@ -78,6 +107,10 @@ impl ToolRegistry {
     pub fn new() -> Self {
         Self {
             tools: HashMap::new(),
+            validators: HashMap::new(),
+            policy: None,
+            quotas: QuotaTracker::new(),
+            approval_handler: None,
         }
     }
 
@@ -108,6 +141,16 @@ impl ToolRegistry {
             );
         }
 
+        match ParamValidator::new(&tool.parameters_schema()) {
+            Ok(validator) => {
+                self.validators.insert(name.clone(), validator);
+            }
+            Err(e) => {
+                warn!("Parameters of tool '{}' will not be validated: {}", name, e);
+                self.validators.remove(&name);
+            }
+        }
+
         self.tools.insert(name, tool);
     }
 
@@ -202,6 +245,7 @@ impl ToolRegistry {
     /// }
     /// ```
     pub fn remove(&mut self, name: &str) -> bool {
+        self.validators.remove(name);
         self.tools.remove(name).is_some()
     }
 
@@ -214,6 +258,125 @@ impl ToolRegistry {
     pub fn is_empty(&self) -> bool {
         self.tools.is_empty()
     }
+
+    /// Attaches the policy engine checked by [`execute`](Self::execute).
+    pub fn set_policy_engine(&mut self, engine: Arc<ToolPolicyEngine>) {
+        self.policy = Some(engine);
+    }
+
+    /// Returns the attached policy engine, if any.
+    pub fn policy_engine(&self) -> Option<&Arc<ToolPolicyEngine>> {
+        self.policy.as_ref()
+    }
+
+    /// Attaches the approval handler used when a call's context has none.
+    pub fn set_approval_handler(&mut self, handler: Arc<dyn ApprovalHandler>) {
+        self.approval_handler = Some(handler);
+    }
+
+    /// Returns the attached approval handler, if any.
+    pub fn approval_handler(&self) -> Option<&Arc<dyn ApprovalHandler>> {
+        self.approval_handler.as_ref()
+    }
+
+    /// Returns the tracker recording tool usage against quotas.
+    pub fn quota_tracker(&self) -> &QuotaTracker {
+        &self.quotas
+    }
+
+    /// Executes a registered tool, validating its parameters and enforcing
+    /// the attached policy and quotas.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ToolResult)` - The tool's result, or an error result with
+    ///   `policy_denied`, `invalid_parameters` or `quota_exceeded` metadata
+    ///   if the call was refused.
+    /// * `Err` - The tool is not registered or failed to execute.
+    pub async fn execute(
+        &self,
+        name: &str,
+        params: serde_json::Value,
+        ctx: &ToolContext,
+    ) -> Result<ToolResult> {
+        self.run(name, params, ctx, None).await
+    }
+
+    /// Like [`execute`](Self::execute), but forwards the tool's incremental
+    /// output to `output` while it runs.
+    pub async fn execute_streaming(
+        &self,
+        name: &str,
+        params: serde_json::Value,
+        ctx: &ToolContext,
+        output: &ToolOutputSink,
+    ) -> Result<ToolResult> {
+        self.run(name, params, ctx, Some(output)).await
+    }
+
+    async fn run(
+        &self,
+        name: &str,
+        params: serde_json::Value,
+        ctx: &ToolContext,
+        output: Option<&ToolOutputSink>,
+    ) -> Result<ToolResult> {
+        let tool = self
+            .get(name)
+            .ok_or_else(|| anyhow!("Tool not found: {}", name))?;
+
+        if let Some(policy) = &self.policy {
+            if let Err(reason) = policy.is_allowed(&ctx.agent_id, name) {
+                return Ok(ToolResult::error(reason).with_metadata(json!({
+                    "error": "policy_denied",
+                    "tool": name,
+                })));
+            }
+        }
+
+        // Rejected calls do not count against the quota.
+        if let Some(validator) = self.validators.get(name) {
+            if let Err(invalid) = validator.validate(name, &params) {
+                return Ok(invalid.to_tool_result());
+            }
+        }
+
+        // Held until the tool finishes so it counts as a running execution.
+        let mut permit = None;
+        if let Some(policy) = &self.policy {
+            if let Some(quota) = policy.quota_for(&ctx.agent_id, name) {
+                let acquired = self
+                    .quotas
+                    .acquire(&ctx.agent_id, &ctx.session_key, name, quota);
+                match acquired {
+                    Ok(acquired) => permit = Some(acquired),
+                    Err(violation) => {
+                        warn!(
+                            "Tool call rejected for agent '{}': {}",
+                            ctx.agent_id, violation
+                        );
+                        return Ok(violation.to_tool_result());
+                    }
+                }
+            }
+        }
+
+        let with_handler;
+        let ctx = match &self.approval_handler {
+            Some(handler) if ctx.approval_handler.is_none() => {
+                with_handler = ctx.clone().with_approval_handler(handler.clone());
+                &with_handler
+            }
+            _ => ctx,
+        };
+
+        let result = match output {
+            Some(output) => tool.execute_streaming(params, ctx, output).await,
+            None => tool.execute(params, ctx).await,
+        };
+        drop(permit);
+        result
+    }
 }
 
 #[cfg(test)]
@@ -4,6 +4,7 @@
 //! for isolated tool execution. It creates containers from configured images,
 //! mounts workspaces, executes commands, and ensures cleanup.
 
+use std::collections::HashMap;
 use std::path::Path;
 use std::time::Duration;
 
@@ -11,7 +12,7 @@ use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use tokio::process::Command;
 
-use crate::sandbox::config::{SandboxConfig, SandboxRuntime, WorkspaceAccess};
+use crate::sandbox::config::{SandboxConfig, SandboxRuntime};
 use crate::sandbox::{WorkspaceError, WorkspaceGuard};
 
 /// A unique identifier for a container
@@ -52,67 +53,88 @@ impl SandboxExecutor {
         }
     }
 
-    /// Creates a container from the configured image
+    /// Returns true if the container runtime CLI is installed and its daemon responds
+    pub async fn is_available(&self) -> bool {
+        Command::new(self.runtime_command())
+            .args(["version", "--format", "{{.Server.Version}}"])
+            .output()
+            .await
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Builds the `create` arguments for a container
     ///
-    /// The container is created in a stopped state with resource limits
-    /// and workspace mounts applied. Use `start_container()` to start it
-    /// or `run_one_shot()` for automatic lifecycle management.
-    pub async fn create_container(
+    /// Applies resource limits, the non-root user, network isolation,
+    /// privilege hardening, and the workspace bind mount if a guard is given.
+    fn create_args(
         &self,
         config: &SandboxConfig,
-    ) -> Result<ContainerId> {
-        let mut cmd = Command::new(self.runtime_command());
-        cmd.args(["create", "--rm"]);
+        workspace: Option<&WorkspaceGuard>,
+    ) -> Vec<String> {
+        let mut args: Vec<String> = vec!["create".into(), "--rm".into()];
 
         // Resource limits
         if let Some(ref mem) = config.memory_limit {
-            cmd.args(["--memory", mem]);
+            args.extend(["--memory".into(), mem.clone()]);
+            // Disallow swap beyond the memory limit
+            args.extend(["--memory-swap".into(), mem.clone()]);
         }
         if let Some(cpu) = config.cpu_limit {
-            cmd.args(["--cpus", &cpu.to_string()]);
+            args.extend(["--cpus".into(), cpu.to_string()]);
         }
+        args.extend(["--pids-limit".into(), "256".into()]);
 
-        // Security: Non-root container execution
-        // By default, run containers as user 1000:1000 (non-root)
-        cmd.args(["--user", &config.user]);
+        // Security: Non-root container execution without privilege escalation
+        args.extend(["--user".into(), config.user.clone()]);
+        args.extend(["--cap-drop".into(), "ALL".into()]);
+        args.extend(["--security-opt".into(), "no-new-privileges".into()]);
 
         // Network access
         if !config.network_access {
-            cmd.args(["--network", "none"]);
+            args.extend(["--network".into(), "none".into()]);
         }
 
-        // Set working directory
-        cmd.arg("-w").arg("/workspace");
-
         // Mount workspace based on access level
-        match config.workspace_access {
-            WorkspaceAccess::ReadOnly => {
-                // We'll mount in execute() since we need the actual path
-            }
-            WorkspaceAccess::ReadWrite => {
-                // We'll mount in execute() since we need the actual path
-            }
-            WorkspaceAccess::None => {
-                // No workspace mount needed
-            }
+        if let Some(mount_args) = workspace.and_then(|guard| guard.mount_args()) {
+            args.extend(mount_args);
+            args.extend(["-w".into(), "/workspace".into()]);
         }
 
-        cmd.arg(&config.image);
-        cmd.arg("sleep").arg("infinity");
+        args.push(config.image.clone());
+        args.extend(["sleep".into(), "infinity".into()]);
+        args
+    }
+
+    /// Creates a container from the configured image
+    ///
+    /// The container is created in a stopped state with resource limits
+    /// applied and no workspace mount. Use `start_container()` to start it
+    /// or `run_one_shot()` for automatic lifecycle management.
+    pub async fn create_container(&self, config: &SandboxConfig) -> Result<ContainerId> {
+        self.create_container_with_workspace(config, None).await
+    }
+
+    /// Creates a container with the guarded workspace bind-mounted at `/workspace`
+    ///
+    /// The mount is read-only or read-write according to the guard's
+    /// access level, and omitted entirely for `WorkspaceAccess::None`.
+    pub async fn create_container_with_workspace(
+        &self,
+        config: &SandboxConfig,
+        workspace: Option<&WorkspaceGuard>,
+    ) -> Result<ContainerId> {
+        let output = Command::new(self.runtime_command())
+            .args(self.create_args(config, workspace))
+            .output()
+            .await?;
 
-        let output = cmd.output().await?;
-        
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow!(
-                "Failed to create container: {}",
-                stderr.trim()
-            ));
+            return Err(anyhow!("Failed to create container: {}", stderr.trim()));
         }
 
-        let container_id = String::from_utf8_lossy(&output.stdout)
-            .trim()
-            .to_string();
+        let container_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
 
         if container_id.is_empty() {
             return Err(anyhow!("Container creation returned empty ID"));
@@ -142,8 +164,8 @@ impl SandboxExecutor {
 
     /// Executes a command inside a running container
     ///
-    /// This method mounts the workspace directory with the appropriate
-    /// permissions and runs the specified command inside the container.
+    /// The workspace is mounted when the container is created, so
+    /// `working_dir` is only validated against the workspace guard here.
     pub async fn execute(
         &self,
         config: &SandboxConfig,
@@ -157,16 +179,43 @@ impl SandboxExecutor {
         // Validate the working directory path
         let _ = guard.validate_path(working_dir)?;
 
+        self.execute_with_env(config, container_id, command, &HashMap::new())
+            .await
+    }
+
+    /// Executes a command inside a running container with extra environment variables
+    ///
+    /// The container is killed if the command exceeds `config.timeout`.
+    pub async fn execute_with_env(
+        &self,
+        config: &SandboxConfig,
+        container_id: &ContainerId,
+        command: &str,
+        env: &HashMap<String, String>,
+    ) -> Result<ExecutionResult> {
+        self.exec(config, container_id, command, env, None).await
+    }
+
+    /// Runs `command` in a container, in `workdir` when given
+    async fn exec(
+        &self,
+        config: &SandboxConfig,
+        container_id: &ContainerId,
+        command: &str,
+        env: &HashMap<String, String>,
+        workdir: Option<&Path>,
+    ) -> Result<ExecutionResult> {
         let mut cmd = Command::new(self.runtime_command());
         cmd.args(["exec"]);
-
-        // Mount workspace based on guard settings
-        if let Some(mount_args) = guard.mount_args() {
-            cmd.args(&mount_args);
+        if let Some(workdir) = workdir {
+            cmd.arg("-w").arg(workdir);
+        }
+        for (key, value) in env {
+            cmd.arg("-e").arg(format!("{}={}", key, value));
         }
-
         cmd.arg(&container_id.0);
         cmd.args(["sh", "-c", command]);
+        cmd.kill_on_drop(true);
 
         let result = tokio::time::timeout(config.timeout, cmd.output()).await;
 
@@ -269,13 +318,56 @@ impl SandboxExecutor {
         config: &SandboxConfig,
         command: &str,
         working_dir: &Path,
+    ) -> Result<ExecutionResult> {
+        self.run_one_shot_with_env(config, command, working_dir, &HashMap::new())
+            .await
+    }
+
+    /// Runs a command in a fresh container with extra environment variables
+    ///
+    /// `working_dir` is bind-mounted at `/workspace` according to
+    /// `config.workspace_access` and used as the command's working directory.
+    pub async fn run_one_shot_with_env(
+        &self,
+        config: &SandboxConfig,
+        command: &str,
+        working_dir: &Path,
+        env: &HashMap<String, String>,
+    ) -> Result<ExecutionResult> {
+        self.run_one_shot_in(config, command, working_dir, None, env)
+            .await
+    }
+
+    /// Runs a command in a fresh container with `workspace` mounted at `/workspace`
+    ///
+    /// `subdir`, absolute or relative to `workspace`, must lie inside the
+    /// workspace; the command runs in its counterpart under `/workspace`.
+    /// Without it the command runs in `/workspace`.
+    pub async fn run_one_shot_in(
+        &self,
+        config: &SandboxConfig,
+        command: &str,
+        workspace: &Path,
+        subdir: Option<&Path>,
+        env: &HashMap<String, String>,
     ) -> Result<ExecutionResult> {
         // Validate workspace access before creating container
-        let guard =
-            WorkspaceGuard::new(working_dir.to_path_buf(), config.workspace_access.clone())?;
-        let _ = guard.validate_path(working_dir)?;
+        let guard = WorkspaceGuard::new(workspace.to_path_buf(), config.workspace_access.clone())?;
+        let _ = guard.validate_path(workspace)?;
+        let workdir = match subdir {
+            Some(subdir) if guard.mount_args().is_none() => {
+                return Err(anyhow!(
+                    "Working directory {} requires workspace access",
+                    subdir.display()
+                ));
+            }
+            Some(subdir) => Some(guard.container_path(subdir)?),
+            None => None,
+        };
 
-        let container_id = self.create_container(config).await?;
+        let container_id = self
+            .create_container_with_workspace(config, Some(&guard))
+            .await?;
 
         // Start the container
         if let Err(e) = self.start_container(&container_id).await {
@@ -284,7 +376,7 @@ impl SandboxExecutor {
         }
 
         let result = self
-            .execute(config, &container_id, command, working_dir)
+            .exec(config, &container_id, command, env, workdir.as_deref())
             .await;
 
         // Always clean up
@@ -303,6 +395,7 @@ impl Default for SandboxExecutor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sandbox::config::WorkspaceAccess;
     use std::env;
 
     #[tokio::test]
@@ -420,4 +513,45 @@ mod tests {
         };
         assert_eq!(config.user, "1001:1001");
     }
+
+    #[test]
+    fn test_create_args_apply_limits_and_isolation() {
+        let executor = SandboxExecutor::new(SandboxRuntime::Podman);
+        let config = SandboxConfig {
+            enabled: true,
+            image: "python:3.12-slim".to_string(),
+            network_access: false,
+            memory_limit: Some("512m".to_string()),
+            cpu_limit: Some(0.5),
+            ..Default::default()
+        };
+
+        let args = executor.create_args(&config, None).join(" ");
+        assert!(args.contains("--memory 512m"));
+        assert!(args.contains("--cpus 0.5"));
+        assert!(args.contains("--network none"));
+        assert!(args.contains("--user 1000:1000"));
+        assert!(args.contains("--cap-drop ALL"));
+        assert!(!args.contains("/workspace"));
+        assert!(args.ends_with("python:3.12-slim sleep infinity"));
+    }
+
+    #[test]
+    fn test_create_args_mount_workspace() {
+        let executor = SandboxExecutor::default();
+        let dir = tempfile::tempdir().unwrap();
+        let config = SandboxConfig::default();
+
+        let guard =
+            WorkspaceGuard::new(dir.path().to_path_buf(), WorkspaceAccess::ReadOnly).unwrap();
+        let args = executor.create_args(&config, Some(&guard));
+        let root = dir.path().canonicalize().unwrap();
+        assert!(args.contains(&format!("{}:/workspace:ro", root.display())));
+        assert!(args.windows(2).any(|w| w == ["-w", "/workspace"]));
+        assert!(!args.contains(&"--network".to_string()));
+
+        let guard = WorkspaceGuard::new(dir.path().to_path_buf(), WorkspaceAccess::None).unwrap();
+        let args = executor.create_args(&config, Some(&guard));
+        assert!(!args.iter().any(|a| a.contains("/workspace")));
+    }
 }
@@ -124,6 +124,18 @@ impl WorkspaceGuard {
         Ok(())
     }
 
+    /// Maps a path inside the workspace to its location in the container.
+    ///
+    /// The path is validated with [`validate_path`](Self::validate_path);
+    /// relative paths are taken relative to the workspace root.
+    pub fn container_path(&self, path: &Path) -> Result<PathBuf, WorkspaceError> {
+        let canonical = self.validate_path(path)?;
+        let relative = canonical
+            .strip_prefix(&self.root)
+            .map_err(|_| WorkspaceError::PathEscape(canonical.clone()))?;
+        Ok(Path::new("/workspace").join(relative))
+    }
+
     /// Returns the access level for this guard.
     pub fn access(&self) -> &WorkspaceAccess {
         &self.access
@@ -309,4 +321,25 @@ mod tests {
         // (may fail if the path doesn't exist, which is Ok for this test)
         assert!(result.is_ok() || matches!(result, Err(WorkspaceError::PathEscape(_))));
     }
+
+    #[test]
+    fn test_container_path_maps_into_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        let guard =
+            WorkspaceGuard::new(dir.path().to_path_buf(), WorkspaceAccess::ReadWrite).unwrap();
+
+        assert_eq!(
+            guard.container_path(Path::new("src")).unwrap(),
+            Path::new("/workspace/src")
+        );
+        assert_eq!(
+            guard.container_path(dir.path()).unwrap(),
+            Path::new("/workspace")
+        );
+        assert!(matches!(
+            guard.container_path(Path::new("/")),
+            Err(WorkspaceError::PathEscape(_))
+        ));
+    }
 }
@@ -0,0 +1,65 @@
+//! Incremental output from long-running tools.
+//!
+//! Tools that produce output gradually, such as `bash`, implement
+//! [`Tool::execute_streaming`](crate::Tool::execute_streaming) and report
+//! chunks to a [`ToolOutputSink`] while they run. The agent pipeline forwards
+//! the chunks to the channel as progress updates; the final
+//! [`ToolResult`](crate::ToolResult) still carries the complete output.
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+/// The stream an output chunk was written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputStream {
+    /// Regular output.
+    Stdout,
+    /// Diagnostic output.
+    Stderr,
+}
+
+/// A piece of output emitted by a tool before it completed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolOutputChunk {
+    /// The stream the text was written to.
+    pub stream: OutputStream,
+    /// The emitted text.
+    pub text: String,
+}
+
+/// Receives the incremental output of a running tool.
+///
+/// Emitting never blocks; chunks are dropped once the receiver is gone.
+#[derive(Debug, Clone)]
+pub struct ToolOutputSink {
+    tx: mpsc::UnboundedSender<ToolOutputChunk>,
+}
+
+impl ToolOutputSink {
+    /// Creates a sink and the receiver its chunks are delivered to.
+    pub fn channel() -> (Self, mpsc::UnboundedReceiver<ToolOutputChunk>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (Self { tx }, rx)
+    }
+
+    /// Emits a chunk, returning `false` if nobody is listening anymore.
+    pub fn emit(&self, stream: OutputStream, text: impl Into<String>) -> bool {
+        self.tx
+            .send(ToolOutputChunk {
+                stream,
+                text: text.into(),
+            })
+            .is_ok()
+    }
+
+    /// Emits a chunk of regular output.
+    pub fn stdout(&self, text: impl Into<String>) -> bool {
+        self.emit(OutputStream::Stdout, text)
+    }
+
+    /// Emits a chunk of diagnostic output.
+    pub fn stderr(&self, text: impl Into<String>) -> bool {
+        self.emit(OutputStream::Stderr, text)
+    }
+}
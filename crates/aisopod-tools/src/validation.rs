@@ -0,0 +1,174 @@
+//! Validation of tool parameters against their JSON Schema.
+//!
+//! The [`ToolRegistry`](crate::ToolRegistry) compiles each tool's
+//! [`parameters_schema`](crate::Tool::parameters_schema) when the tool is
+//! registered and checks the model's arguments before executing it. Invalid
+//! arguments are reported to the model as an error [`ToolResult`] listing
+//! every problem, so it can correct the call instead of the tool failing
+//! halfway through:
+//!
+//! ```json
+//! {
+//!   "error": "invalid_parameters",
+//!   "tool": "bash",
+//!   "errors": [
+//!     { "path": "/timeout", "message": "\"soon\" is not of type \"integer\"" }
+//!   ]
+//! }
+//! ```
+
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::ToolResult;
+
+/// A compiled parameter schema.
+pub struct ParamValidator {
+    validator: jsonschema::Validator,
+}
+
+impl ParamValidator {
+    /// Compiles a tool's parameter schema.
+    ///
+    /// Fails if the schema itself is not a valid JSON Schema.
+    pub fn new(schema: &Value) -> Result<Self> {
+        let validator =
+            jsonschema::validator_for(schema).map_err(|e| anyhow!("Invalid schema: {}", e))?;
+        Ok(Self { validator })
+    }
+
+    /// Checks parameters for `tool`, collecting every violation.
+    pub fn validate(&self, tool: &str, params: &Value) -> Result<(), InvalidParams> {
+        let errors: Vec<ParamError> = self
+            .validator
+            .iter_errors(params)
+            .map(|error| ParamError {
+                path: error.instance_path.as_str().to_string(),
+                message: error.to_string(),
+            })
+            .collect();
+        if errors.is_empty() {
+            return Ok(());
+        }
+        Err(InvalidParams {
+            tool: tool.to_string(),
+            errors,
+        })
+    }
+}
+
+impl fmt::Debug for ParamValidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParamValidator").finish_non_exhaustive()
+    }
+}
+
+/// A single schema violation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParamError {
+    /// JSON Pointer to the offending value; empty for the parameters object.
+    pub path: String,
+    /// Description of the violation.
+    pub message: String,
+}
+
+/// Parameters that do not match a tool's schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidParams {
+    /// The tool that was called.
+    pub tool: String,
+    /// The violations found.
+    pub errors: Vec<ParamError>,
+}
+
+impl InvalidParams {
+    /// Converts the violations into an error result for the model.
+    pub fn to_tool_result(&self) -> ToolResult {
+        ToolResult::error(self.to_string()).with_metadata(json!({
+            "error": "invalid_parameters",
+            "tool": self.tool,
+            "errors": self.errors,
+        }))
+    }
+}
+
+impl fmt::Display for InvalidParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Invalid parameters for tool '{}':", self.tool)?;
+        for error in &self.errors {
+            if error.path.is_empty() {
+                writeln!(f, "- {}", error.message)?;
+            } else {
+                writeln!(f, "- {}: {}", error.path, error.message)?;
+            }
+        }
+        write!(
+            f,
+            "Fix the arguments to match the tool's parameter schema and call it again."
+        )
+    }
+}
+
+impl std::error::Error for InvalidParams {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validator() -> ParamValidator {
+        ParamValidator::new(&json!({
+            "type": "object",
+            "properties": {
+                "command": { "type": "string" },
+                "timeout": { "type": "integer", "minimum": 1 }
+            },
+            "required": ["command"]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_valid_params() {
+        let validator = validator();
+        assert!(validator
+            .validate("bash", &json!({"command": "ls", "timeout": 5}))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_collects_all_errors() {
+        let err = validator()
+            .validate("bash", &json!({"timeout": "soon"}))
+            .unwrap_err();
+        assert_eq!(err.tool, "bash");
+        assert_eq!(err.errors.len(), 2);
+        assert!(
+            err.errors
+                .iter()
+                .any(|e| e.path.is_empty()
+                    && e.message.contains("\"command\" is a required property"))
+        );
+        assert!(err
+            .errors
+            .iter()
+            .any(|e| e.path == "/timeout" && e.message.contains("is not of type \"integer\"")));
+
+        let result = err.to_tool_result();
+        assert!(result.is_error);
+        assert!(result
+            .content
+            .starts_with("Invalid parameters for tool 'bash':"));
+        assert!(result.content.contains("- /timeout: "));
+        let metadata = result.metadata.unwrap();
+        assert_eq!(metadata["error"], "invalid_parameters");
+        assert_eq!(metadata["errors"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_rejects_invalid_schema() {
+        assert!(ParamValidator::new(&json!({"type": "no-such-type"})).is_err());
+    }
+}
@@ -3,7 +3,7 @@
 use std::sync::Arc;
 
 use aisopod_tools::{
-    CronTool, JobScheduler, NoOpJobScheduler, ScheduledJob, Tool, ToolContext, ToolResult,
+    CronTool, JobOwner, JobScheduler, NoOpJobScheduler, ScheduledJob, Tool, ToolContext, ToolResult,
 };
 use anyhow::Result;
 use async_trait::async_trait;
@@ -54,6 +54,7 @@ impl JobScheduler for MockJobScheduler {
             command: command.to_string(),
             next_run,
             last_run: None,
+            owner: None,
         };
 
         self.jobs
@@ -63,6 +64,24 @@ impl JobScheduler for MockJobScheduler {
         Ok(job)
     }
 
+    async fn schedule_for(
+        &self,
+        owner: &JobOwner,
+        id: &str,
+        cron_expression: &str,
+        command: &str,
+    ) -> Result<ScheduledJob> {
+        let job = ScheduledJob {
+            owner: Some(owner.clone()),
+            ..self.schedule(id, cron_expression, command).await?
+        };
+        self.jobs
+            .lock()
+            .unwrap()
+            .insert(id.to_string(), job.clone());
+        Ok(job)
+    }
+
     async fn list(&self) -> Result<Vec<ScheduledJob>> {
         Ok(self.jobs.lock().unwrap().values().cloned().collect())
     }
@@ -474,7 +493,12 @@ async fn test_schedule_with_mock_scheduler() {
         .await;
 
     assert!(result.is_ok());
-    assert_eq!(scheduler.get_job_ids().len(), 1);
+    let ids = scheduler.get_job_ids();
+    assert_eq!(ids.len(), 1);
+    assert_eq!(
+        scheduler.get_job(&ids[0]).unwrap().owner,
+        Some(JobOwner::new("test_agent", "test_session"))
+    );
 }
 
 #[tokio::test]
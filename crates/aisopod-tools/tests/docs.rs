@@ -0,0 +1,139 @@
+//! Documentation search tool tests
+
+use std::sync::Arc;
+
+use aisopod_memory::EmbeddingProvider;
+use aisopod_tools::{DocsTool, Tool, ToolContext};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::json;
+
+const VOCABULARY: [&str; 6] = ["install", "cargo", "config", "token", "channel", "slack"];
+
+/// Embeds text as counts of a fixed vocabulary so similarity follows keywords.
+struct KeywordEmbedder;
+
+#[async_trait]
+impl EmbeddingProvider for KeywordEmbedder {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let text = text.to_lowercase();
+        Ok(VOCABULARY
+            .iter()
+            .map(|word| text.matches(word).count() as f32)
+            .collect())
+    }
+
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let mut embeddings = Vec::new();
+        for text in texts {
+            embeddings.push(self.embed(text).await?);
+        }
+        Ok(embeddings)
+    }
+
+    fn dimensions(&self) -> usize {
+        VOCABULARY.len()
+    }
+}
+
+fn write_docs(dir: &std::path::Path) {
+    std::fs::write(
+        dir.join("install.md"),
+        "# Installation\n\nInstall with cargo:\n\n    cargo install aisopod\n",
+    )
+    .unwrap();
+    std::fs::create_dir(dir.join("channels")).unwrap();
+    std::fs::write(
+        dir.join("channels").join("slack.md"),
+        "# Slack\n\nCreate a Slack app.\n\n## Tokens\n\nPut the Slack bot token in the channel settings.\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("notes.bin"), "install install install").unwrap();
+}
+
+#[tokio::test]
+async fn test_docs_tool_returns_excerpts_with_citations() {
+    let dir = tempfile::tempdir().unwrap();
+    write_docs(dir.path());
+    let tool = DocsTool::new(
+        Arc::new(KeywordEmbedder),
+        vec![dir.path().display().to_string()],
+    );
+    let ctx = ToolContext::new("agent-1", "session-1");
+
+    let result = tool
+        .execute(
+            json!({"query": "where does the slack token go", "max_results": 1}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+    assert!(!result.is_error);
+    assert!(result.content.contains("Put the Slack bot token"));
+    assert!(result.content.contains("(Tokens)"));
+
+    let metadata = result.metadata.unwrap();
+    let citations = metadata["citations"].as_array().unwrap();
+    assert_eq!(citations.len(), 1);
+    assert!(citations[0]["source"]
+        .as_str()
+        .unwrap()
+        .ends_with("slack.md"));
+    assert_eq!(citations[0]["heading"], "Tokens");
+    assert_eq!(citations[0]["start_line"], 5);
+    assert_eq!(citations[0]["end_line"], 7);
+
+    let result = tool
+        .execute(json!({"query": "cargo install"}), &ctx)
+        .await
+        .unwrap();
+    let citations = result.metadata.unwrap()["citations"].clone();
+    assert!(citations[0]["source"]
+        .as_str()
+        .unwrap()
+        .ends_with("install.md"));
+    // Files with other extensions are not indexed.
+    assert!(!citations
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|c| c["source"].as_str().unwrap().ends_with("notes.bin")));
+}
+
+#[tokio::test]
+async fn test_docs_tool_reindex_picks_up_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("config.md");
+    std::fs::write(&file, "# Config\n\nThe config lives in aisopod.json5.\n").unwrap();
+    let tool = DocsTool::new(Arc::new(KeywordEmbedder), vec![file.display().to_string()]);
+    assert_eq!(tool.reindex().await.unwrap(), 1);
+
+    std::fs::write(&file, "# Channel\n\nNew channel docs.\n").unwrap();
+    let ctx = ToolContext::new("agent-1", "session-1");
+    let result = tool
+        .execute(json!({"query": "channel", "reindex": true}), &ctx)
+        .await
+        .unwrap();
+    assert!(result.content.contains("New channel docs"));
+}
+
+#[tokio::test]
+async fn test_docs_tool_input_errors() {
+    let tool = DocsTool::new(
+        Arc::new(KeywordEmbedder),
+        vec!["/nonexistent/docs".to_string()],
+    );
+    let ctx = ToolContext::new("agent-1", "session-1");
+
+    assert!(tool.execute(json!({}), &ctx).await.is_err());
+
+    let result = tool.execute(json!({"query": "  "}), &ctx).await.unwrap();
+    assert!(result.is_error);
+
+    let result = tool
+        .execute(json!({"query": "install"}), &ctx)
+        .await
+        .unwrap();
+    assert!(result.is_error);
+    assert!(result.content.contains("No documentation is indexed"));
+}
@@ -0,0 +1,196 @@
+//! Git tool tests
+
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+
+use aisopod_tools::{
+    ApprovalError, ApprovalHandler, ApprovalRequest, ApprovalResponse, GitTool, Tool, ToolContext,
+};
+use serde_json::json;
+
+struct DenyHandler;
+
+#[async_trait::async_trait]
+impl ApprovalHandler for DenyHandler {
+    async fn request_approval(
+        &self,
+        _request: ApprovalRequest,
+    ) -> Result<ApprovalResponse, ApprovalError> {
+        Ok(ApprovalResponse::Denied {
+            reason: "Test denial".to_string(),
+        })
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+        .status;
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+/// Creates a workspace containing a repository with a single commit.
+fn workspace() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path().join("repo");
+    std::fs::create_dir(&repo).unwrap();
+    git(&repo, &["init", "--quiet", "--initial-branch=main"]);
+    std::fs::write(repo.join("README.md"), "# Demo\n").unwrap();
+    git(&repo, &["add", "README.md"]);
+    git(&repo, &["commit", "--quiet", "-m", "Initial commit"]);
+    dir
+}
+
+fn tool() -> GitTool {
+    GitTool::default().with_identity("Agent", "agent@example.com")
+}
+
+#[tokio::test]
+async fn test_status_diff_and_log() {
+    let dir = workspace();
+    std::fs::write(dir.path().join("repo/README.md"), "# Demo\n\nMore\n").unwrap();
+    let ctx = ToolContext::new("agent-1", "session-1").with_workspace_path(dir.path());
+    let tool = tool();
+
+    let result = tool
+        .execute(json!({"operation": "status", "path": "repo"}), &ctx)
+        .await
+        .unwrap();
+    assert!(!result.is_error, "{}", result.content);
+    assert!(result.content.contains("## main"));
+    assert!(result.content.contains(" M README.md"));
+
+    let result = tool
+        .execute(json!({"operation": "diff", "path": "repo"}), &ctx)
+        .await
+        .unwrap();
+    assert!(result.content.contains("+More"));
+
+    let result = tool
+        .execute(
+            json!({"operation": "diff", "path": "repo", "staged": true}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.content, "No changes");
+
+    let result = tool
+        .execute(json!({"operation": "log", "path": "repo"}), &ctx)
+        .await
+        .unwrap();
+    assert!(result.content.contains("Test: Initial commit"));
+    assert_eq!(result.metadata.unwrap()["operation"], "log");
+}
+
+#[tokio::test]
+async fn test_branch_and_commit() {
+    let dir = workspace();
+    let ctx = ToolContext::new("agent-1", "session-1").with_workspace_path(dir.path());
+    let tool = tool();
+
+    let result = tool
+        .execute(
+            json!({"operation": "branch", "path": "repo", "name": "feature", "checkout": true, "create": true}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+    assert!(!result.is_error, "{}", result.content);
+
+    std::fs::write(dir.path().join("repo/notes.txt"), "todo\n").unwrap();
+    let result = tool
+        .execute(
+            json!({"operation": "commit", "path": "repo", "message": "Add notes", "paths": ["notes.txt"]}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+    assert!(!result.is_error, "{}", result.content);
+
+    let result = tool
+        .execute(json!({"operation": "log", "path": "repo"}), &ctx)
+        .await
+        .unwrap();
+    assert!(result.content.contains("Agent: Add notes"));
+
+    let result = tool
+        .execute(json!({"operation": "branch", "path": "repo"}), &ctx)
+        .await
+        .unwrap();
+    assert!(result.content.contains("* feature"));
+    assert!(result.content.contains("main"));
+}
+
+#[tokio::test]
+async fn test_commit_requires_approval() {
+    let dir = workspace();
+    std::fs::write(dir.path().join("repo/README.md"), "changed\n").unwrap();
+    let ctx = ToolContext::new("agent-1", "session-1")
+        .with_workspace_path(dir.path())
+        .with_approval_handler(Arc::new(DenyHandler));
+
+    let result = tool()
+        .execute(
+            json!({"operation": "commit", "path": "repo", "message": "Change", "all": true}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+    assert!(result.is_error);
+    assert!(result.content.contains("Commit denied: Test denial"));
+
+    // Nothing was staged or committed
+    let result = tool()
+        .execute(json!({"operation": "status", "path": "repo"}), &ctx)
+        .await
+        .unwrap();
+    assert!(result.content.contains(" M README.md"));
+}
+
+#[tokio::test]
+async fn test_clone_from_local_source_is_rejected() {
+    let dir = workspace();
+    let ctx = ToolContext::new("agent-1", "session-1").with_workspace_path(dir.path());
+
+    let result = tool()
+        .execute(
+            json!({"operation": "clone", "url": dir.path().join("repo").to_string_lossy()}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+    assert!(result.is_error);
+    assert!(result.content.contains("Unsupported repository URL"));
+}
+
+#[tokio::test]
+async fn test_rejects_paths_outside_workspace() {
+    let dir = workspace();
+    let ctx = ToolContext::new("agent-1", "session-1").with_workspace_path(dir.path().join("repo"));
+
+    let result = tool()
+        .execute(json!({"operation": "status", "path": ".."}), &ctx)
+        .await
+        .unwrap();
+    assert!(result.is_error);
+    assert!(result.content.contains("Invalid path"));
+
+    let result = tool()
+        .execute(json!({"operation": "status"}), &ToolContext::new("a", "s"))
+        .await
+        .unwrap();
+    assert!(result.is_error);
+    assert!(result.content.contains("require a workspace"));
+
+    let result = tool()
+        .execute(json!({"operation": "rebase"}), &ctx)
+        .await
+        .unwrap();
+    assert!(result.content.contains("Unknown git operation"));
+}
@@ -0,0 +1,285 @@
+//! HTTP tool tests
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use aisopod_config::types::HttpToolConfig;
+use aisopod_tools::{HostPolicy, HttpTool, Tool, ToolContext, ToolPolicyEngine};
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Starts a server that answers each connection with the next canned response.
+///
+/// Returns the base URL and a handle resolving to the raw requests received.
+async fn serve(responses: Vec<String>) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let handle = tokio::spawn(async move {
+        let mut requests = Vec::new();
+        for response in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 8192];
+            let n = socket.read(&mut buf).await.unwrap();
+            requests.push(String::from_utf8_lossy(&buf[..n]).into_owned());
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.shutdown().await.ok();
+        }
+        requests
+    });
+    (base, handle)
+}
+
+fn response(status: &str, headers: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+        status,
+        body.len(),
+        headers,
+        body
+    )
+}
+
+#[tokio::test]
+async fn test_http_tool_get() {
+    let (base, handle) = serve(vec![response(
+        "200 OK",
+        "Content-Type: text/plain\r\n",
+        "hello world",
+    )])
+    .await;
+
+    let tool = HttpTool::default().with_private_networks(true);
+    let ctx = ToolContext::new("agent-1", "session-1");
+    let result = tool
+        .execute(
+            json!({"url": format!("{}/greeting", base), "headers": {"X-Trace": "abc"}}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+
+    assert!(!result.is_error);
+    assert!(result.content.contains("200 OK"));
+    assert!(result.content.contains("hello world"));
+    let metadata = result.metadata.unwrap();
+    assert_eq!(metadata["status"], 200);
+    assert_eq!(metadata["truncated"], false);
+
+    let requests = handle.await.unwrap();
+    assert!(requests[0].starts_with("GET /greeting"));
+    assert!(requests[0].to_ascii_lowercase().contains("x-trace: abc"));
+}
+
+#[tokio::test]
+async fn test_http_tool_post_json() {
+    let (base, handle) = serve(vec![response("201 Created", "", "")]).await;
+
+    let tool = HttpTool::default().with_private_networks(true);
+    let ctx = ToolContext::new("agent-1", "session-1");
+    let result = tool
+        .execute(
+            json!({"url": base, "method": "post", "body": {"name": "widget"}}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+    assert!(!result.is_error);
+
+    let requests = handle.await.unwrap();
+    assert!(requests[0].starts_with("POST /"));
+    assert!(requests[0].contains(r#"{"name":"widget"}"#));
+}
+
+#[tokio::test]
+async fn test_http_tool_truncates_large_responses() {
+    let body = "x".repeat(1000);
+    let (base, _handle) = serve(vec![response("200 OK", "", &body)]).await;
+
+    let tool = HttpTool::new(std::time::Duration::from_secs(5), 100).with_private_networks(true);
+    let ctx = ToolContext::new("agent-1", "session-1");
+    let result = tool.execute(json!({"url": base}), &ctx).await.unwrap();
+
+    let metadata = result.metadata.unwrap();
+    assert_eq!(metadata["truncated"], true);
+    assert_eq!(metadata["bytes"], 100);
+    assert!(result.content.contains("truncated"));
+}
+
+#[tokio::test]
+async fn test_http_tool_error_status() {
+    let (base, _handle) = serve(vec![response("404 Not Found", "", "missing")]).await;
+
+    let tool = HttpTool::default().with_private_networks(true);
+    let ctx = ToolContext::new("agent-1", "session-1");
+    let result = tool.execute(json!({"url": base}), &ctx).await.unwrap();
+    assert!(result.is_error);
+    assert!(result.content.contains("404"));
+}
+
+#[tokio::test]
+async fn test_http_tool_redirect_checked_against_policy() {
+    let (base, _handle) = serve(vec![response(
+        "302 Found",
+        "Location: http://metadata.internal/secrets\r\n",
+        "",
+    )])
+    .await;
+
+    let mut engine = ToolPolicyEngine::new();
+    engine.set_global_host_policy(HostPolicy::deny_list(vec!["*.internal".to_string()]));
+    let tool = HttpTool::default()
+        .with_private_networks(true)
+        .with_policy_engine(Arc::new(engine));
+    let ctx = ToolContext::new("agent-1", "session-1");
+
+    let result = tool.execute(json!({"url": base}), &ctx).await.unwrap();
+    assert!(result.is_error);
+    assert!(result.content.contains("metadata.internal"));
+}
+
+#[tokio::test]
+async fn test_http_tool_redirect_drops_credentials_across_origins() {
+    let (other, other_handle) = serve(vec![response("200 OK", "", "elsewhere")]).await;
+    let (base, handle) = serve(vec![
+        response("302 Found", "Location: /moved\r\n", ""),
+        response("302 Found", &format!("Location: {}/landing\r\n", other), ""),
+    ])
+    .await;
+
+    let tool = HttpTool::default().with_private_networks(true);
+    let ctx = ToolContext::new("agent-1", "session-1");
+    let result = tool
+        .execute(
+            json!({
+                "url": base,
+                "headers": {
+                    "Authorization": "Bearer secret",
+                    "Cookie": "session=secret",
+                    "X-Trace": "abc",
+                },
+            }),
+            &ctx,
+        )
+        .await
+        .unwrap();
+    assert!(!result.is_error);
+
+    // Kept on the same origin
+    let requests = handle.await.unwrap();
+    assert!(requests[1]
+        .to_lowercase()
+        .contains("authorization: bearer secret"));
+    assert!(requests[1]
+        .to_lowercase()
+        .contains("cookie: session=secret"));
+
+    // Dropped on another origin, unlike other headers
+    let request = other_handle.await.unwrap().remove(0).to_lowercase();
+    assert!(!request.contains("authorization"));
+    assert!(!request.contains("cookie"));
+    assert!(request.contains("x-trace: abc"));
+}
+
+#[tokio::test]
+async fn test_http_tool_host_allow_list() {
+    let mut engine = ToolPolicyEngine::new();
+    engine.set_agent_host_policy(
+        "agent-1".to_string(),
+        HostPolicy::allow_list(vec!["api.example.com".to_string()]),
+    );
+    let tool = HttpTool::default().with_policy_engine(Arc::new(engine));
+
+    let ctx = ToolContext::new("agent-1", "session-1");
+    let result = tool
+        .execute(json!({"url": "http://other.example.com/"}), &ctx)
+        .await
+        .unwrap();
+    assert!(result.is_error);
+    assert!(result.content.contains("allow list"));
+}
+
+#[tokio::test]
+async fn test_http_tool_refuses_private_addresses() {
+    let (base, _handle) = serve(vec![response("200 OK", "", "internal")]).await;
+    let tool = HttpTool::default();
+    let ctx = ToolContext::new("agent-1", "session-1");
+
+    let result = tool.execute(json!({"url": base}), &ctx).await.unwrap();
+    assert!(result.is_error);
+    assert!(result.content.contains("not a public address"));
+
+    for url in [
+        "http://169.254.169.254/latest/meta-data/",
+        "http://[::1]/",
+        "http://[::ffff:10.0.0.1]/",
+    ] {
+        let result = tool.execute(json!({"url": url}), &ctx).await.unwrap();
+        assert!(result.is_error, "{}", url);
+    }
+
+    // Names are checked once resolved
+    let port = base.rsplit(':').next().unwrap();
+    let error = tool
+        .execute(json!({"url": format!("http://localhost:{}/", port)}), &ctx)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("public address"));
+}
+
+#[tokio::test]
+async fn test_http_tool_from_config_applies_host_lists() {
+    let tool = HttpTool::from_config(&HttpToolConfig {
+        denied_hosts: vec!["*.example.com".to_string()],
+        ..Default::default()
+    });
+    let ctx = ToolContext::new("agent-1", "session-1");
+
+    let result = tool
+        .execute(json!({"url": "https://api.example.com/"}), &ctx)
+        .await
+        .unwrap();
+    assert!(result.is_error);
+    assert!(result.content.contains("denied"));
+}
+
+#[tokio::test]
+async fn test_http_tool_timeout_is_capped_at_default() {
+    // Accepts the connection but never answers
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let _handle = tokio::spawn(async move {
+        let (_socket, _) = listener.accept().await.unwrap();
+        std::future::pending::<()>().await
+    });
+
+    let tool = HttpTool::new(Duration::from_millis(200), 1024).with_private_networks(true);
+    let ctx = ToolContext::new("agent-1", "session-1");
+    let started = Instant::now();
+    let result = tool
+        .execute(json!({"url": base, "timeout": 3600}), &ctx)
+        .await;
+    assert!(result.is_err());
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn test_http_tool_ignores_environment_proxy() {
+    let (proxy, _handle) = serve(vec![response("200 OK", "", "proxied")]).await;
+    std::env::set_var("HTTP_PROXY", &proxy);
+    std::env::set_var("http_proxy", &proxy);
+
+    // A proxy would resolve the host itself, past the address check
+    let tool = HttpTool::default();
+    let ctx = ToolContext::new("agent-1", "session-1");
+    let result = tool
+        .execute(json!({"url": "http://localhost/admin"}), &ctx)
+        .await;
+    std::env::remove_var("HTTP_PROXY");
+    std::env::remove_var("http_proxy");
+
+    match result {
+        Ok(result) => assert!(result.is_error && !result.content.contains("proxied")),
+        Err(e) => assert!(!e.to_string().contains("proxied")),
+    }
+}
@@ -0,0 +1,196 @@
+//! MCP client integration tests
+
+use std::sync::Arc;
+
+use aisopod_tools::mcp::{connect_mcp_servers, McpClient, McpConfig, McpServerConfig};
+use aisopod_tools::{McpTool, Tool, ToolContext, ToolRegistry};
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// A minimal line-based MCP server answering with canned responses.
+const STUB_SERVER: &str = r#"
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9][0-9]*\).*/\1/p')
+  case "$line" in
+    *'"method":"initialize"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"2024-11-05","capabilities":{"tools":{}},"serverInfo":{"name":"stub"}}}\n' "$id" ;;
+    *'"method":"tools/list"'*'"cursor"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[{"name":"fail","inputSchema":{"type":"object"}}]}}\n' "$id" ;;
+    *'"method":"tools/list"'*)
+      echo "stub server log line" >&2
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[{"name":"greet","description":"Greets someone","inputSchema":{"type":"object","properties":{"who":{"type":"string"}},"required":["who"]}}],"nextCursor":"page2"}}\n' "$id" ;;
+    *'"method":"tools/call"'*'"name":"fail"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"content":[{"type":"text","text":"it broke"}],"isError":true}}\n' "$id" ;;
+    *'"method":"tools/call"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"content":[{"type":"text","text":"hello from stub"}]}}\n' "$id" ;;
+  esac
+done
+"#;
+
+fn stdio_config(dir: &std::path::Path) -> McpServerConfig {
+    let script = dir.join("server.sh");
+    std::fs::write(&script, STUB_SERVER).unwrap();
+    serde_json::from_value(json!({
+        "name": "stub",
+        "command": "sh",
+        "args": [script.to_string_lossy()],
+        "timeout": 5
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_stdio_server_tools_registered() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = McpConfig {
+        servers: vec![stdio_config(dir.path())],
+        ..Default::default()
+    };
+
+    let mut registry = ToolRegistry::new();
+    let clients = connect_mcp_servers(&mut registry, &config).await;
+    assert_eq!(clients.len(), 1);
+    assert_eq!(clients[0].server_info()["name"], "stub");
+
+    let mut names = registry.list();
+    names.sort();
+    assert_eq!(names, vec!["mcp__stub__fail", "mcp__stub__greet"]);
+
+    let greet = registry.get("mcp__stub__greet").unwrap();
+    assert_eq!(greet.description(), "Greets someone");
+    assert_eq!(greet.parameters_schema()["required"], json!(["who"]));
+
+    let ctx = ToolContext::new("agent-1", "session-1");
+    let result = greet.execute(json!({"who": "world"}), &ctx).await.unwrap();
+    assert!(!result.is_error);
+    assert_eq!(result.content, "hello from stub");
+    assert_eq!(result.metadata.unwrap()["tool"], "greet");
+
+    let fail = registry.get("mcp__stub__fail").unwrap();
+    let result = fail.execute(json!({}), &ctx).await.unwrap();
+    assert!(result.is_error);
+    assert_eq!(result.content, "it broke");
+}
+
+#[tokio::test]
+async fn test_broken_servers_are_skipped() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing: McpServerConfig = serde_json::from_value(json!({
+        "name": "missing",
+        "command": "/nonexistent/mcp-server"
+    }))
+    .unwrap();
+    let disabled: McpServerConfig = serde_json::from_value(json!({
+        "name": "disabled",
+        "enabled": false,
+        "command": "sh"
+    }))
+    .unwrap();
+    let config = McpConfig {
+        servers: vec![missing, disabled, stdio_config(dir.path())],
+        ..Default::default()
+    };
+
+    let mut registry = ToolRegistry::new();
+    let clients = connect_mcp_servers(&mut registry, &config).await;
+    assert_eq!(clients.len(), 1);
+    assert_eq!(clients[0].name(), "stub");
+    assert_eq!(registry.len(), 2);
+}
+
+/// Reads one HTTP request and returns its body.
+async fn read_request(socket: &mut TcpStream) -> String {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let n = socket.read(&mut buf).await.unwrap();
+        data.extend_from_slice(&buf[..n]);
+        let text = String::from_utf8_lossy(&data).into_owned();
+        if let Some(end) = text.find("\r\n\r\n") {
+            let length = text[..end]
+                .lines()
+                .find_map(|l| {
+                    let (name, value) = l.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse::<usize>().unwrap())
+                })
+                .unwrap_or(0);
+            if data.len() >= end + 4 + length {
+                return text[end + 4..end + 4 + length].to_string();
+            }
+        }
+        if n == 0 {
+            return String::new();
+        }
+    }
+}
+
+fn sse_reply(request: &Value) -> Option<Value> {
+    let id = request.get("id")?.clone();
+    let result = match request["method"].as_str()? {
+        "initialize" => json!({"protocolVersion": "2024-11-05", "serverInfo": {"name": "remote"}}),
+        "tools/list" => json!({"tools": [{"name": "lookup", "inputSchema": {"type": "object"}}]}),
+        "tools/call" => {
+            json!({"content": [{"type": "text", "text": format!("looked up {}", request["params"]["arguments"]["q"])}]})
+        }
+        _ => return None,
+    };
+    Some(json!({"jsonrpc": "2.0", "id": id, "result": result}))
+}
+
+#[tokio::test]
+async fn test_sse_transport_round_trip() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/sse", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        read_request(&mut stream).await;
+        stream
+            .write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\nevent: endpoint\ndata: /messages?session=abc\n\n",
+            )
+            .await
+            .unwrap();
+
+        loop {
+            let (mut post, _) = listener.accept().await.unwrap();
+            let body = read_request(&mut post).await;
+            post.write_all(
+                b"HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            )
+            .await
+            .unwrap();
+            let request: Value = serde_json::from_str(&body).unwrap();
+            if let Some(reply) = sse_reply(&request) {
+                stream
+                    .write_all(format!("event: message\ndata: {}\n\n", reply).as_bytes())
+                    .await
+                    .unwrap();
+            }
+        }
+    });
+
+    let config: McpServerConfig = serde_json::from_value(json!({
+        "name": "remote",
+        "transport": "sse",
+        "url": url,
+        "headers": {"Authorization": "Bearer secret"},
+        "timeout": 5
+    }))
+    .unwrap();
+
+    let client = Arc::new(McpClient::connect(&config).await.unwrap());
+    assert_eq!(client.server_info()["name"], "remote");
+
+    let tools = client.list_tools().await.unwrap();
+    assert_eq!(tools.len(), 1);
+
+    let tool = McpTool::new(client, tools[0].clone());
+    assert_eq!(tool.name(), "mcp__remote__lookup");
+    let ctx = ToolContext::new("agent-1", "session-1");
+    let result = tool.execute(json!({"q": "rust"}), &ctx).await.unwrap();
+    assert!(!result.is_error);
+    assert_eq!(result.content, "looked up \"rust\"");
+}
@@ -0,0 +1,321 @@
+//! MCP server mode tests
+
+use std::sync::Arc;
+
+use aisopod_config::types::TokenCredential;
+use aisopod_tools::mcp::{McpClient, McpServerConfig};
+use aisopod_tools::{
+    ApprovalRequest, McpServer, RiskLevel, SandboxConfig, Tool, ToolContext, ToolContextResolver,
+    ToolRegistry, ToolResult,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// Tool echoing its `text` parameter, or failing when it is missing.
+struct EchoTool(&'static str);
+
+#[async_trait]
+impl Tool for EchoTool {
+    fn name(&self) -> &str {
+        self.0
+    }
+
+    fn description(&self) -> &str {
+        "Echoes its input"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({"type": "object", "properties": {"text": {"type": "string"}}, "required": ["text"]})
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        match params["text"].as_str() {
+            Some(text) => Ok(ToolResult::success(format!("{} [{}]", text, ctx.agent_id))),
+            None => Ok(ToolResult::error("text is required")),
+        }
+    }
+}
+
+fn token(token: &str, scopes: &[&str]) -> TokenCredential {
+    TokenCredential {
+        token: token.to_string(),
+        role: "operator".to_string(),
+        scopes: scopes.iter().map(|s| s.to_string()).collect(),
+    }
+}
+
+/// Tool asking for approval before answering.
+struct GuardedTool;
+
+#[async_trait]
+impl Tool for GuardedTool {
+    fn name(&self) -> &str {
+        "guarded"
+    }
+
+    fn description(&self) -> &str {
+        "Needs approval"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({"type": "object"})
+    }
+
+    async fn execute(&self, _params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        let Some(handler) = &ctx.approval_handler else {
+            return Ok(ToolResult::success("ran unchecked"));
+        };
+        let request = ApprovalRequest::new(&ctx.agent_id, "guarded", RiskLevel::High);
+        let response = handler.request_approval(request).await?;
+        Ok(match response.denial_reason() {
+            Some(reason) => ToolResult::error(format!("denied: {}", reason)),
+            None => ToolResult::success("approved"),
+        })
+    }
+}
+
+fn server() -> Arc<McpServer> {
+    let mut registry = ToolRegistry::new();
+    registry.register(Arc::new(EchoTool("echo")));
+    registry.register(Arc::new(EchoTool("secret")));
+    Arc::new(
+        McpServer::new(Arc::new(registry))
+            .with_exported_tools(vec!["echo".to_string()])
+            .with_agent_id("exporter"),
+    )
+}
+
+#[tokio::test]
+async fn test_stdio_server_lists_and_calls_exported_tools() {
+    let (client, server_io) = tokio::io::duplex(64 * 1024);
+    let (server_read, server_write) = tokio::io::split(server_io);
+    let server = server();
+    tokio::spawn(async move { server.serve_stdio(server_read, server_write).await });
+
+    let (client_read, mut client_write) = tokio::io::split(client);
+    let mut lines = BufReader::new(client_read).lines();
+    let requests = [
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+        json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+        json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}),
+        json!({"jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": {"name": "echo", "arguments": {"text": "hi"}}}),
+        json!({"jsonrpc": "2.0", "id": 4, "method": "tools/call", "params": {"name": "echo", "arguments": {}}}),
+        json!({"jsonrpc": "2.0", "id": 5, "method": "tools/call", "params": {"name": "secret", "arguments": {"text": "x"}}}),
+    ];
+    for request in &requests {
+        client_write
+            .write_all(format!("{}\n", request).as_bytes())
+            .await
+            .unwrap();
+    }
+
+    let mut responses = Vec::new();
+    for _ in 0..5 {
+        let line = lines.next_line().await.unwrap().unwrap();
+        responses.push(serde_json::from_str::<Value>(&line).unwrap());
+    }
+
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(responses[0]["result"]["serverInfo"]["name"], "aisopod");
+
+    let tools = responses[1]["result"]["tools"].as_array().unwrap();
+    assert_eq!(tools.len(), 1);
+    assert_eq!(tools[0]["name"], "echo");
+    assert_eq!(tools[0]["inputSchema"]["required"], json!(["text"]));
+
+    assert_eq!(
+        responses[2]["result"]["content"][0]["text"],
+        "hi [exporter]"
+    );
+    assert_eq!(responses[2]["result"]["isError"], false);
+
+    assert_eq!(responses[3]["result"]["isError"], true);
+
+    assert_eq!(responses[4]["id"], 5);
+    assert_eq!(responses[4]["error"]["code"], -32602);
+}
+
+#[tokio::test]
+async fn test_sse_server_with_mcp_client() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/sse", listener.local_addr().unwrap());
+    tokio::spawn(server().serve_sse(listener));
+
+    let config: McpServerConfig = serde_json::from_value(json!({
+        "name": "local",
+        "transport": "sse",
+        "url": url,
+        "timeout": 5
+    }))
+    .unwrap();
+    let client = McpClient::connect(&config).await.unwrap();
+    assert_eq!(client.server_info()["name"], "aisopod");
+
+    let tools = client.list_tools().await.unwrap();
+    let names: Vec<_> = tools.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, vec!["echo"]);
+
+    let result = client
+        .call_tool("echo", json!({"text": "over sse"}))
+        .await
+        .unwrap();
+    assert!(!result.is_error);
+    assert_eq!(result.text(), "over sse [exporter]");
+
+    assert!(client.call_tool("secret", json!({})).await.is_err());
+}
+
+#[tokio::test]
+async fn test_sse_server_checks_token_and_origin() {
+    let mut registry = ToolRegistry::new();
+    registry.register(Arc::new(EchoTool("echo")));
+    let server = McpServer::new(Arc::new(registry))
+        .with_exported_tools(vec!["echo".to_string()])
+        .with_auth_tokens(vec![
+            token("secret-token", &["operator.write"]),
+            token("read-token", &["operator.read", "operator.chat"]),
+        ])
+        .with_allowed_origins(vec!["https://app.example.com".to_string()]);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/sse", listener.local_addr().unwrap());
+    tokio::spawn(Arc::new(server).serve_sse(listener));
+
+    let http = reqwest::Client::new();
+    let status = |request: reqwest::RequestBuilder| async move {
+        request.send().await.unwrap().status().as_u16()
+    };
+    assert_eq!(status(http.get(&url)).await, 401);
+    assert_eq!(status(http.get(&url).bearer_auth("wrong")).await, 401);
+    assert_eq!(
+        status(
+            http.post(url.replace("/sse", "/messages?session_id=x"))
+                .body("{}")
+        )
+        .await,
+        401
+    );
+    // Known, but not allowed to run tools
+    assert_eq!(status(http.get(&url).bearer_auth("read-token")).await, 403);
+    assert_eq!(
+        status(
+            http.post(url.replace("/sse", "/messages?session_id=x"))
+                .bearer_auth("read-token")
+                .body("{}")
+        )
+        .await,
+        403
+    );
+    assert_eq!(
+        status(
+            http.get(&url)
+                .bearer_auth("secret-token")
+                .header("Origin", "https://evil.example.com")
+        )
+        .await,
+        403
+    );
+
+    let config: McpServerConfig = serde_json::from_value(json!({
+        "name": "local",
+        "transport": "sse",
+        "url": url,
+        "headers": {
+            "Authorization": "Bearer secret-token",
+            "Origin": "https://app.example.com"
+        },
+        "timeout": 5
+    }))
+    .unwrap();
+    let client = McpClient::connect(&config).await.unwrap();
+    let result = client
+        .call_tool("echo", json!({"text": "authorized"}))
+        .await
+        .unwrap();
+    assert_eq!(result.text(), "authorized [mcp]");
+}
+
+#[tokio::test]
+async fn test_server_denies_operations_needing_approval() {
+    let mut registry = ToolRegistry::new();
+    registry.register(Arc::new(GuardedTool));
+    let server = McpServer::new(Arc::new(registry)).with_exported_tools(vec!["*".to_string()]);
+    let response = server
+        .handle(
+            json!({"jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": {"name": "guarded", "arguments": {}}}),
+            "s",
+        )
+        .await
+        .unwrap();
+    assert_eq!(response["result"]["isError"], true);
+    assert!(response["result"]["content"][0]["text"]
+        .as_str()
+        .unwrap()
+        .starts_with("denied:"));
+}
+
+/// Tool reporting the workspace and sandbox it runs in.
+struct WhereTool;
+
+#[async_trait]
+impl Tool for WhereTool {
+    fn name(&self) -> &str {
+        "where"
+    }
+
+    fn description(&self) -> &str {
+        "Reports its context"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({"type": "object"})
+    }
+
+    async fn execute(&self, _params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        Ok(ToolResult::success(format!(
+            "{} {:?} sandboxed={} approval={}",
+            ctx.agent_id,
+            ctx.workspace_path,
+            ctx.sandbox_config.as_ref().is_some_and(|c| c.enabled),
+            ctx.approval_handler.is_some(),
+        )))
+    }
+}
+
+/// Resolves the context of a sandboxed agent working in `/srv/<agent>`.
+struct SandboxedAgents;
+
+impl ToolContextResolver for SandboxedAgents {
+    fn tool_context(&self, agent_id: &str, session_key: &str) -> ToolContext {
+        ToolContext::new(agent_id, session_key)
+            .with_workspace_path(format!("/srv/{}", agent_id))
+            .with_sandbox_config(SandboxConfig {
+                enabled: true,
+                ..Default::default()
+            })
+    }
+}
+
+#[tokio::test]
+async fn test_exported_tools_run_in_export_agent_context() {
+    let mut registry = ToolRegistry::new();
+    registry.register(Arc::new(WhereTool));
+    let server = McpServer::new(Arc::new(registry))
+        .with_exported_tools(vec!["where".to_string()])
+        .with_agent_id("exporter")
+        .with_context_resolver(Arc::new(SandboxedAgents));
+
+    let response = server
+        .handle(
+            json!({"jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": {"name": "where", "arguments": {}}}),
+            "s",
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        response["result"]["content"][0]["text"],
+        "exporter Some(\"/srv/exporter\") sandboxed=true approval=true"
+    );
+}
@@ -2,11 +2,30 @@
 
 use std::sync::Arc;
 
-use aisopod_tools::{Tool, ToolContext, ToolRegistry, ToolResult};
+use aisopod_config::types::{ToolQuotaConfig, ToolsConfig};
+use aisopod_tools::{
+    ApprovalError, ApprovalHandler, ApprovalRequest, ApprovalResponse, BashTool,
+    NoOpApprovalHandler, Tool, ToolContext, ToolPolicy, ToolPolicyEngine, ToolQuota, ToolRegistry,
+    ToolResult,
+};
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::json;
 
+struct DenyAll;
+
+#[async_trait]
+impl ApprovalHandler for DenyAll {
+    async fn request_approval(
+        &self,
+        _request: ApprovalRequest,
+    ) -> Result<ApprovalResponse, ApprovalError> {
+        Ok(ApprovalResponse::Denied {
+            reason: "not now".to_string(),
+        })
+    }
+}
+
 struct TestTool {
     name: String,
     description: String,
@@ -156,10 +175,14 @@ async fn test_register_all_builtins() {
 
     // Verify all expected tools are registered
     assert!(tools.contains(&"bash".to_string()));
+    assert!(tools.contains(&"browser".to_string()));
     assert!(tools.contains(&"canvas".to_string()));
     assert!(tools.contains(&"cron".to_string()));
     assert!(tools.contains(&"file".to_string()));
+    assert!(tools.contains(&"git".to_string()));
+    assert!(tools.contains(&"http".to_string()));
     assert!(tools.contains(&"message".to_string()));
+    assert!(tools.contains(&"python".to_string()));
     assert!(tools.contains(&"subagent".to_string()));
     assert!(tools.contains(&"session".to_string()));
 }
@@ -180,3 +203,153 @@ async fn test_register_and_remove_all() {
     assert!(registry.is_empty());
     assert_eq!(registry.len(), 0);
 }
+
+#[tokio::test]
+async fn test_execute_without_policy_runs_tool() {
+    let mut registry = ToolRegistry::new();
+    registry.register(Arc::new(TestTool::new("echo", "Echo")));
+    let ctx = ToolContext::new("agent-1", "session-1");
+
+    let result = registry.execute("echo", json!({}), &ctx).await.unwrap();
+    assert!(!result.is_error);
+    assert_eq!(result.content, "Executed echo");
+
+    assert!(registry.execute("missing", json!({}), &ctx).await.is_err());
+}
+
+#[tokio::test]
+async fn test_execute_enforces_policy_and_quota() {
+    let mut engine =
+        ToolPolicyEngine::with_global_policy(ToolPolicy::deny_list(vec!["bash".to_string()]));
+    engine.set_global_quota(
+        "echo".to_string(),
+        ToolQuota::new().with_max_calls_per_session(2),
+    );
+
+    let mut registry = ToolRegistry::new();
+    registry.register(Arc::new(TestTool::new("echo", "Echo")));
+    registry.register(Arc::new(TestTool::new("bash", "Bash")));
+    registry.set_policy_engine(Arc::new(engine));
+    let ctx = ToolContext::new("agent-1", "session-1");
+
+    let denied = registry.execute("bash", json!({}), &ctx).await.unwrap();
+    assert!(denied.is_error);
+    assert_eq!(denied.metadata.unwrap()["error"], "policy_denied");
+
+    for _ in 0..2 {
+        let result = registry.execute("echo", json!({}), &ctx).await.unwrap();
+        assert!(!result.is_error);
+    }
+    let limited = registry.execute("echo", json!({}), &ctx).await.unwrap();
+    assert!(limited.is_error);
+    let metadata = limited.metadata.unwrap();
+    assert_eq!(metadata["error"], "quota_exceeded");
+    assert_eq!(metadata["kind"], "call_limit");
+    assert_eq!(metadata["limit"], 2);
+    assert_eq!(
+        registry
+            .quota_tracker()
+            .calls("agent-1", "session-1", "echo"),
+        2
+    );
+
+    // A fresh session has its own budget.
+    let other = ToolContext::new("agent-1", "session-2");
+    assert!(
+        !registry
+            .execute("echo", json!({}), &other)
+            .await
+            .unwrap()
+            .is_error
+    );
+}
+
+#[tokio::test]
+async fn test_configured_quotas_are_enforced() {
+    let mut config = ToolsConfig::default();
+    config.quotas.global.insert(
+        "echo".to_string(),
+        ToolQuotaConfig {
+            max_calls_per_session: Some(1),
+            ..Default::default()
+        },
+    );
+    let mut registry = ToolRegistry::new();
+    aisopod_tools::register_configured_tools(&mut registry, &config);
+    registry.register(Arc::new(TestTool::new("echo", "Echo")));
+    let ctx = ToolContext::new("agent-1", "session-1");
+
+    let result = registry.execute("echo", json!({}), &ctx).await.unwrap();
+    assert!(!result.is_error);
+    let limited = registry.execute("echo", json!({}), &ctx).await.unwrap();
+    assert_eq!(limited.metadata.unwrap()["kind"], "call_limit");
+}
+
+#[tokio::test]
+async fn test_execute_passes_default_approval_handler() {
+    let mut registry = ToolRegistry::new();
+    registry.register(Arc::new(BashTool::default()));
+    registry.set_approval_handler(Arc::new(DenyAll));
+    let ctx = ToolContext::new("agent-1", "session-1");
+
+    let result = registry
+        .execute("bash", json!({"command": "touch /tmp/never"}), &ctx)
+        .await
+        .unwrap();
+    assert!(result.is_error);
+    assert!(result.content.contains("denied: not now"));
+
+    // A handler on the context takes precedence
+    let ctx = ctx.with_approval_handler(Arc::new(NoOpApprovalHandler));
+    let result = registry
+        .execute("bash", json!({"command": "echo hi | cat"}), &ctx)
+        .await
+        .unwrap();
+    assert!(!result.is_error);
+}
+
+#[tokio::test]
+async fn test_execute_validates_parameters() {
+    let mut engine = ToolPolicyEngine::new();
+    engine.set_global_quota(
+        "bash".to_string(),
+        ToolQuota::new().with_max_calls_per_session(1),
+    );
+    let mut registry = ToolRegistry::new();
+    registry.register(Arc::new(BashTool::default()));
+    registry.set_policy_engine(Arc::new(engine));
+    let ctx = ToolContext::new("agent-1", "session-1");
+
+    let result = registry
+        .execute("bash", json!({"command": 42, "timeout": "soon"}), &ctx)
+        .await
+        .unwrap();
+    assert!(result.is_error);
+    assert!(result
+        .content
+        .starts_with("Invalid parameters for tool 'bash':"));
+    let metadata = result.metadata.unwrap();
+    assert_eq!(metadata["error"], "invalid_parameters");
+    assert_eq!(metadata["tool"], "bash");
+    let paths: Vec<&str> = metadata["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["path"].as_str().unwrap())
+        .collect();
+    assert!(paths.contains(&"/command"));
+    assert!(paths.contains(&"/timeout"));
+
+    // The rejected call did not use up the quota
+    assert_eq!(
+        registry
+            .quota_tracker()
+            .calls("agent-1", "session-1", "bash"),
+        0
+    );
+    let result = registry
+        .execute("bash", json!({"command": "echo ok"}), &ctx)
+        .await
+        .unwrap();
+    assert!(!result.is_error);
+}
@@ -0,0 +1,284 @@
+//! Persistent job scheduler tests
+
+use std::sync::{Arc, Mutex};
+
+use aisopod_tools::{
+    CatchUpPolicy, JobOwner, JobRunner, JobScheduler, NoOpJobRunner, ScheduledJob,
+    SqliteJobScheduler, Tool, ToolContext, ToolContextResolver, ToolJobRunner, ToolPolicy,
+    ToolPolicyEngine, ToolRegistry, ToolResult,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+
+/// Runner recording the IDs of the jobs it was asked to run.
+#[derive(Default)]
+struct RecordingRunner {
+    runs: Mutex<Vec<String>>,
+}
+
+impl RecordingRunner {
+    fn runs(&self) -> Vec<String> {
+        self.runs.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl JobRunner for RecordingRunner {
+    async fn run(&self, job: &ScheduledJob) -> Result<String> {
+        self.runs.lock().unwrap().push(job.id.clone());
+        Ok(format!("ran {}", job.command))
+    }
+}
+
+/// Resolves plain contexts, tagging the session with the agent.
+struct AgentContexts;
+
+impl ToolContextResolver for AgentContexts {
+    fn tool_context(&self, agent_id: &str, session_key: &str) -> ToolContext {
+        ToolContext::new(agent_id, format!("{}@{}", session_key, agent_id))
+    }
+}
+
+/// Tool echoing its `text` parameter.
+struct EchoTool;
+
+#[async_trait]
+impl Tool for EchoTool {
+    fn name(&self) -> &str {
+        "echo"
+    }
+
+    fn description(&self) -> &str {
+        "Echoes its input"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({"type": "object", "properties": {"text": {"type": "string"}}})
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> Result<ToolResult> {
+        Ok(ToolResult::success(format!(
+            "{} ({})",
+            params["text"].as_str().unwrap_or_default(),
+            ctx.session_key
+        )))
+    }
+}
+
+#[tokio::test]
+async fn test_jobs_persist_across_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("jobs.db");
+    let runner = Arc::new(RecordingRunner::default());
+
+    {
+        let scheduler = SqliteJobScheduler::open(&path, runner.clone()).unwrap();
+        scheduler
+            .schedule("recurring", "0 * * * * *", "echo tick")
+            .await
+            .unwrap();
+        scheduler
+            .schedule_once_for(
+                &JobOwner::new("agent-1", "chat"),
+                "once",
+                Utc::now() + Duration::hours(1),
+                "echo once",
+            )
+            .await
+            .unwrap();
+    }
+
+    let scheduler = SqliteJobScheduler::open(&path, runner).unwrap();
+    let jobs = scheduler.list().await.unwrap();
+    let ids: Vec<_> = jobs.iter().map(|j| j.id.as_str()).collect();
+    assert_eq!(ids, vec!["recurring", "once"]);
+    assert!(jobs.iter().all(|j| j.next_run.is_some()));
+    assert_eq!(jobs[0].owner, None);
+    assert_eq!(jobs[1].owner, Some(JobOwner::new("agent-1", "chat")));
+}
+
+#[tokio::test]
+async fn test_invalid_cron_expression_rejected() {
+    let scheduler = SqliteJobScheduler::in_memory(Arc::new(RecordingRunner::default())).unwrap();
+    let err = scheduler
+        .schedule("bad", "not a cron", "echo")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Invalid cron expression"));
+}
+
+#[tokio::test]
+async fn test_one_shot_job_runs_once_and_is_removed() {
+    let runner = Arc::new(RecordingRunner::default());
+    let scheduler = SqliteJobScheduler::in_memory(runner.clone()).unwrap();
+    let run_at = Utc::now() + Duration::minutes(5);
+    scheduler
+        .schedule_once("once", run_at, "echo once")
+        .await
+        .unwrap();
+
+    let runs = scheduler
+        .run_due_at(run_at - Duration::minutes(1))
+        .await
+        .unwrap();
+    assert!(runs.is_empty());
+
+    let runs = scheduler.run_due_at(run_at).await.unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].result.as_ref().unwrap(), "ran echo once");
+    assert!(scheduler.get("once").unwrap().is_none());
+
+    scheduler
+        .run_due_at(run_at + Duration::hours(1))
+        .await
+        .unwrap();
+    assert_eq!(runner.runs(), vec!["once"]);
+}
+
+#[tokio::test]
+async fn test_recurring_job_is_rescheduled() {
+    let runner = Arc::new(RecordingRunner::default());
+    let scheduler = SqliteJobScheduler::in_memory(runner.clone()).unwrap();
+    let job = scheduler
+        .schedule("tick", "0 * * * * *", "echo tick")
+        .await
+        .unwrap();
+    let first = job.next_run.unwrap();
+
+    let runs = scheduler.run_due_at(first).await.unwrap();
+    assert_eq!(runs.len(), 1);
+
+    let job = scheduler.get("tick").unwrap().unwrap();
+    assert!(job.next_run.unwrap() > first);
+    assert!(job.last_run.is_some());
+}
+
+#[tokio::test]
+async fn test_missed_runs_catch_up_policies() {
+    let cases = [
+        (CatchUpPolicy::Skip, 0),
+        (CatchUpPolicy::RunOnce, 1),
+        (CatchUpPolicy::RunAll { max: 3 }, 3),
+    ];
+
+    for (policy, expected) in cases {
+        let runner = Arc::new(RecordingRunner::default());
+        let scheduler = SqliteJobScheduler::in_memory(runner.clone())
+            .unwrap()
+            .with_catch_up_policy(policy)
+            .with_grace_period(std::time::Duration::from_secs(1));
+        let job = scheduler
+            .schedule("tick", "0 * * * * *", "echo tick")
+            .await
+            .unwrap();
+
+        // Land between occurrences so none of them is within the grace period.
+        let now = job.next_run.unwrap() + Duration::minutes(10) + Duration::seconds(30);
+        scheduler.run_due_at(now).await.unwrap();
+
+        assert_eq!(runner.runs().len(), expected, "policy {:?}", policy);
+        let job = scheduler.get("tick").unwrap().unwrap();
+        assert!(job.next_run.unwrap() > now);
+    }
+}
+
+#[tokio::test]
+async fn test_run_now_and_remove() {
+    let runner = Arc::new(RecordingRunner::default());
+    let scheduler = SqliteJobScheduler::in_memory(runner.clone()).unwrap();
+    scheduler
+        .schedule("tick", "0 * * * * *", "echo tick")
+        .await
+        .unwrap();
+
+    assert_eq!(scheduler.run_now("tick").await.unwrap(), "ran echo tick");
+    assert!(scheduler.get("tick").unwrap().unwrap().last_run.is_some());
+    assert!(scheduler.run_now("missing").await.is_err());
+
+    assert!(scheduler.remove("tick").await.unwrap());
+    assert!(!scheduler.remove("tick").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_with_runner_shares_jobs() {
+    let jobs = SqliteJobScheduler::in_memory(Arc::new(NoOpJobRunner)).unwrap();
+    jobs.schedule("tick", "0 * * * * *", "echo tick")
+        .await
+        .unwrap();
+
+    let runner = Arc::new(RecordingRunner::default());
+    let scheduler = jobs.with_runner(runner.clone());
+    assert_eq!(scheduler.run_now("tick").await.unwrap(), "ran echo tick");
+    assert_eq!(runner.runs(), vec!["tick"]);
+    assert!(jobs.get("tick").unwrap().unwrap().last_run.is_some());
+}
+
+#[tokio::test]
+async fn test_tool_job_runner_invokes_tools() {
+    let mut registry = ToolRegistry::new();
+    registry.register(Arc::new(EchoTool));
+    let runner = ToolJobRunner::new(Arc::new(registry), Arc::new(AgentContexts));
+
+    let job = ScheduledJob {
+        id: "job-1".to_string(),
+        cron_expression: "0 * * * * *".to_string(),
+        command: json!({"tool": "echo", "params": {"text": "hi"}}).to_string(),
+        next_run: None,
+        last_run: None,
+        owner: Some(JobOwner::new("agent-1", "chat")),
+    };
+    assert_eq!(runner.run(&job).await.unwrap(), "hi (chat@agent-1)");
+
+    let unowned = ScheduledJob {
+        owner: None,
+        ..job.clone()
+    };
+    assert!(runner.run(&unowned).await.is_err());
+
+    let unknown = ScheduledJob {
+        command: json!({"tool": "missing"}).to_string(),
+        ..job.clone()
+    };
+    assert!(runner.run(&unknown).await.is_err());
+
+    let prompt = ScheduledJob {
+        command: "summarize the inbox".to_string(),
+        ..job
+    };
+    assert!(runner.run(&prompt).await.is_err());
+
+    let fallback = Arc::new(RecordingRunner::default());
+    let runner = runner.with_fallback(fallback.clone());
+    assert_eq!(
+        runner.run(&prompt).await.unwrap(),
+        "ran summarize the inbox"
+    );
+    assert_eq!(fallback.runs(), vec!["job-1"]);
+}
+
+#[tokio::test]
+async fn test_tool_job_runner_applies_tool_policy() {
+    let mut engine = ToolPolicyEngine::new();
+    engine.set_global_policy(ToolPolicy::deny_list(vec!["echo".to_string()]));
+    let mut registry = ToolRegistry::new();
+    registry.register(Arc::new(EchoTool));
+    registry.set_policy_engine(Arc::new(engine));
+    let runner = ToolJobRunner::new(Arc::new(registry), Arc::new(AgentContexts));
+
+    let job = ScheduledJob {
+        id: "job-1".to_string(),
+        cron_expression: "0 * * * * *".to_string(),
+        command: json!({"tool": "echo", "params": {"text": "hi"}}).to_string(),
+        next_run: None,
+        last_run: None,
+        owner: Some(JobOwner::new("agent-1", "chat")),
+    };
+    let error = runner.run(&job).await.unwrap_err();
+    assert!(
+        error.to_string().contains("Tool 'echo' failed"),
+        "{}",
+        error
+    );
+}
@@ -0,0 +1,163 @@
+//! SQL tool tests
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use aisopod_config::types::SqlToolConfig;
+use aisopod_tools::builtins::SqliteBackend;
+use aisopod_tools::{SqlTool, Tool, ToolContext};
+use serde_json::json;
+
+fn create_database(path: &std::path::Path) {
+    let conn = rusqlite::Connection::open(path).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, score REAL);
+         INSERT INTO users (name, score) VALUES ('alice', 9.5), ('bob', NULL), ('carol', 7.0);",
+    )
+    .unwrap();
+}
+
+fn config(databases: serde_json::Value) -> SqlToolConfig {
+    serde_json::from_value(json!({ "enabled": true, "databases": databases })).unwrap()
+}
+
+#[tokio::test]
+async fn test_select_renders_markdown_table() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.db");
+    create_database(&path);
+    let tool = SqlTool::default().with_database("app", Arc::new(SqliteBackend::new(&path, true)));
+    let ctx = ToolContext::new("agent-1", "session-1");
+
+    let result = tool
+        .execute(
+            json!({"query": "SELECT id, name, score FROM users ORDER BY id"}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+    assert!(!result.is_error);
+    assert_eq!(
+        result.content,
+        "| id | name | score |\n| --- | --- | --- |\n| 1 | alice | 9.5 |\n| 2 | bob | NULL |\n| 3 | carol | 7 |"
+    );
+    let metadata = result.metadata.unwrap();
+    assert_eq!(metadata["driver"], "sqlite");
+    assert_eq!(metadata["row_count"], 3);
+    assert_eq!(metadata["truncated"], false);
+
+    let result = tool
+        .execute(
+            json!({"query": "SELECT name FROM users ORDER BY id", "max_rows": 2}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+    assert!(result.content.contains("showing the first 2 rows"));
+    assert_eq!(result.metadata.unwrap()["truncated"], true);
+}
+
+#[tokio::test]
+async fn test_read_only_by_default() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.db");
+    create_database(&path);
+    let tool = SqlTool::from_config(&config(json!([
+        {"name": "app", "url": path.display().to_string()}
+    ])))
+    .unwrap();
+    let ctx = ToolContext::new("agent-1", "session-1");
+
+    let result = tool
+        .execute(json!({"query": "DELETE FROM users"}), &ctx)
+        .await
+        .unwrap();
+    assert!(result.is_error);
+    assert!(result.content.contains("read-only"));
+
+    // A write hidden behind a read keyword is stopped by the database.
+    let result = tool
+        .execute(
+            json!({"query": "WITH x AS (SELECT 1) DELETE FROM users"}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+    assert!(result.is_error);
+
+    let result = tool
+        .execute(json!({"query": "SELECT count(*) AS n FROM users"}), &ctx)
+        .await
+        .unwrap();
+    assert!(result.content.contains("| 3 |"));
+}
+
+#[tokio::test]
+async fn test_writable_database_and_selection() {
+    let dir = tempfile::tempdir().unwrap();
+    let main = dir.path().join("main.db");
+    let scratch = dir.path().join("scratch.db");
+    create_database(&main);
+    create_database(&scratch);
+    let tool = SqlTool::from_config(&config(json!([
+        {"name": "main", "url": main.display().to_string()},
+        {"name": "scratch", "url": format!("sqlite://{}", scratch.display()), "read_only": false}
+    ])))
+    .unwrap();
+    assert_eq!(tool.databases(), vec!["main", "scratch"]);
+    let ctx = ToolContext::new("agent-1", "session-1");
+
+    let result = tool
+        .execute(json!({"query": "SELECT 1"}), &ctx)
+        .await
+        .unwrap();
+    assert!(result.is_error);
+    assert!(result.content.contains("specify 'database'"));
+
+    let result = tool
+        .execute(
+            json!({"database": "scratch", "query": "UPDATE users SET score = 1"}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+    assert!(!result.is_error);
+    assert_eq!(result.content, "Statement executed; 3 rows affected");
+
+    let result = tool
+        .execute(json!({"database": "nope", "query": "SELECT 1"}), &ctx)
+        .await
+        .unwrap();
+    assert!(result.content.contains("Unknown database"));
+}
+
+#[tokio::test]
+async fn test_query_timeout_interrupts_sqlite() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.db");
+    create_database(&path);
+    let tool = SqlTool::new(10, Duration::from_millis(300))
+        .with_database("app", Arc::new(SqliteBackend::new(&path, true)));
+    let ctx = ToolContext::new("agent-1", "session-1");
+
+    let result = tool
+        .execute(
+            json!({"query": "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) SELECT count(*) FROM c"}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+    assert!(result.is_error);
+    assert!(result.content.contains("timed out"));
+}
+
+#[cfg(not(feature = "postgres"))]
+#[test]
+fn test_from_config_requires_driver_feature() {
+    let err = SqlTool::from_config(&config(json!([
+        {"name": "pg", "driver": "postgres", "url": "postgres://localhost/app"}
+    ])))
+    .err()
+    .unwrap();
+    assert!(err.to_string().contains("'postgres' feature"));
+}
@@ -2,8 +2,9 @@
 
 use std::sync::Arc;
 
+use aisopod_session::{SessionStore, SubagentRun, SubagentStatus};
 use aisopod_tools::builtins::AgentSpawner;
-use aisopod_tools::{NoOpAgentSpawner, SubagentTool, Tool, ToolContext, ToolResult};
+use aisopod_tools::{NoOpAgentSpawner, SubagentStatusTool, SubagentTool, Tool, ToolContext};
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::json;
@@ -547,3 +548,92 @@ async fn test_subagent_tool_with_multiline_prompt() {
     let output = result.unwrap();
     assert!(!output.is_error);
 }
+
+// Spawner failing for prompts containing "fail"
+struct FlakySpawner;
+
+#[async_trait]
+impl AgentSpawner for FlakySpawner {
+    async fn spawn(&self, agent_name: &str, prompt: &str, _model: &str) -> Result<String> {
+        if prompt.contains("fail") {
+            anyhow::bail!("agent '{}' crashed", agent_name);
+        }
+        Ok(format!("{} done: {}", agent_name, prompt))
+    }
+}
+
+#[tokio::test]
+async fn test_subagent_runs_resumed_after_restart() {
+    let store = Arc::new(SessionStore::new_in_memory().unwrap());
+
+    // Runs left behind by a process that died while they were in flight
+    let pending = SubagentRun::new("run_1", "parent", "researcher", "find", "gpt-4", 1);
+    let mut running = SubagentRun::new("run_2", "parent", "writer", "write", "gpt-4", 1);
+    running.start();
+    let mut completed = SubagentRun::new("run_3", "parent", "writer", "old", "gpt-4", 1);
+    completed.complete("old result");
+    for run in [&pending, &running, &completed] {
+        store.save_subagent_run(run).unwrap();
+    }
+
+    let spawner = MockSpawner::new();
+    let tool = SubagentTool::new(Arc::new(spawner.clone()), 3, None).with_store(store.clone());
+    let resumed = tool.resume_pending().await.unwrap();
+
+    assert_eq!(resumed.len(), 2);
+    assert_eq!(spawner.get_count(), 2);
+    let run = store.get_subagent_run("run_2").unwrap().unwrap();
+    assert_eq!(run.status, SubagentStatus::Completed);
+    assert_eq!(run.attempts, 2);
+    assert!(store.list_unfinished_subagent_runs().unwrap().is_empty());
+    let run = store.get_subagent_run("run_3").unwrap().unwrap();
+    assert_eq!(run.result.as_deref(), Some("old result"));
+}
+
+#[tokio::test]
+async fn test_subagent_status_queried_across_turns() {
+    let store = Arc::new(SessionStore::new_in_memory().unwrap());
+    let spawn = SubagentTool::new(Arc::new(FlakySpawner), 3, None).with_store(store.clone());
+    let status = SubagentStatusTool::new(store.clone());
+    let ctx = ToolContext::new("test_agent", "parent");
+
+    let output = spawn
+        .execute(
+            json!({"agent_name": "researcher", "prompt": "find", "model": "gpt-4"}),
+            &ctx,
+        )
+        .await
+        .unwrap();
+    let run_id = output.metadata.unwrap()["run_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let failed = spawn
+        .execute(
+            json!({"agent_name": "writer", "prompt": "fail", "model": "gpt-4"}),
+            &ctx,
+        )
+        .await;
+    assert!(failed.is_err());
+
+    let output = status.execute(json!({}), &ctx).await.unwrap();
+    let runs: serde_json::Value = serde_json::from_str(&output.content).unwrap();
+    assert_eq!(runs.as_array().unwrap().len(), 2);
+    assert_eq!(runs[0]["status"], "completed");
+    assert_eq!(runs[1]["status"], "failed");
+
+    let output = status
+        .execute(json!({"run_id": run_id}), &ctx)
+        .await
+        .unwrap();
+    assert!(!output.is_error);
+    assert!(output.content.contains("researcher done: find"));
+
+    // Runs of other sessions are not visible
+    let other = ToolContext::new("test_agent", "other");
+    let output = status
+        .execute(json!({"run_id": run_id}), &other)
+        .await
+        .unwrap();
+    assert!(output.is_error);
+}
@@ -16,6 +16,11 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub config: Option<String>,
 
+    /// Configuration profile overlaid on the configuration file, e.g. `prod`
+    /// for `aisopod.prod.toml` (overrides AISOPOD_PROFILE)
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
     /// Enable verbose output
     #[arg(long, global = true)]
     pub verbose: bool,
@@ -61,6 +66,8 @@ pub enum Commands {
     Reset,
     /// Generate shell completions
     Completions(crate::commands::completions::CompletionsArgs),
+    /// Guided setup writing a new configuration file
+    Init(crate::commands::init::InitArgs),
     /// Interactive onboarding wizard for first-time users
     Onboarding {
         /// Path to configuration file
@@ -69,12 +76,28 @@ pub enum Commands {
     },
     /// Migrate configuration from other formats
     Migrate(crate::commands::migrate::MigrateArgs),
+    /// Serve tools over the Model Context Protocol
+    Mcp(crate::commands::mcp::McpArgs),
+    /// Manage plugins
+    Plugin(crate::commands::plugin::PluginArgs),
+    /// Manage skills
+    Skill(crate::commands::skill::SkillArgs),
+    /// Export and import memories
+    Memory(crate::commands::memory::MemoryArgs),
+    /// Run agents against an evaluation suite
+    Eval(crate::commands::eval::EvalArgs),
 }
 
 /// Main entry point for CLI processing.
 pub fn run_cli() {
     let cli = Cli::parse();
 
+    // Configs are loaded by the commands, which pick the profile up from the
+    // environment
+    if let Some(profile) = &cli.profile {
+        std::env::set_var(aisopod_config::PROFILE_ENV_VAR, profile);
+    }
+
     // Dispatch to subcommand handlers
     match cli.command {
         Commands::Gateway(args) => {
@@ -141,11 +164,41 @@ pub fn run_cli() {
         Commands::Completions(args) => {
             crate::commands::completions::run(args);
         }
+        Commands::Init(args) => {
+            let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+            rt.block_on(crate::commands::init::run(args, cli.config))
+                .expect("Init command failed");
+        }
         Commands::Onboarding { config } => {
             crate::commands::onboarding::run_onboarding(config).expect("Onboarding command failed");
         }
         Commands::Migrate(args) => {
             crate::commands::migrate::run_migrate(args).expect("Migrate command failed");
         }
+        Commands::Mcp(args) => {
+            let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+            rt.block_on(crate::commands::mcp::run(args, cli.config))
+                .expect("MCP command failed");
+        }
+        Commands::Plugin(args) => {
+            let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+            rt.block_on(crate::commands::plugin::run(args, cli.config, cli.json))
+                .expect("Plugin command failed");
+        }
+        Commands::Skill(args) => {
+            let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+            rt.block_on(crate::commands::skill::run(args, cli.json))
+                .expect("Skill command failed");
+        }
+        Commands::Memory(args) => {
+            let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+            rt.block_on(crate::commands::memory::run(args, cli.config, cli.json))
+                .expect("Memory command failed");
+        }
+        Commands::Eval(args) => {
+            let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+            rt.block_on(crate::commands::eval::run(args, cli.config, cli.json))
+                .expect("Eval command failed");
+        }
     }
 }
@@ -145,6 +145,12 @@ pub fn run(args: AgentArgs, config_path: Option<String>) -> Result<()> {
                 max_subagent_depth,
                 subagent_allowed_models: None,
                 skills: Vec::new(),
+                show_reasoning: false,
+                output_schema: None,
+                max_output_repairs: 2,
+                draft_verify: None,
+                prompt_template: None,
+                prompt_sections: Default::default(),
             };
 
             config.agents.agents.push(agent.clone());
@@ -197,6 +203,8 @@ fn save_config(
     config: &aisopod_config::AisopodConfig,
     config_path: Option<String>,
 ) -> Result<()> {
+    aisopod_config::ensure_no_active_profile()?;
+
     let path = match config_path {
         Some(p) => p,
         None => {
@@ -119,6 +119,8 @@ fn load_config_or_default(config_path: Option<&str>) -> Result<AisopodConfig> {
 
 /// Save configuration to file
 fn save_config(config: &AisopodConfig, config_path: Option<String>) -> Result<()> {
+    aisopod_config::ensure_no_active_profile()?;
+
     let path = match config_path {
         Some(p) => p,
         None => {
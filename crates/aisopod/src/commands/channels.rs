@@ -204,6 +204,8 @@ fn load_config_or_default(config_path: Option<&str>) -> Result<AisopodConfig> {
 
 /// Save configuration to file
 fn save_config(config: &AisopodConfig, config_path: Option<String>) -> Result<()> {
+    aisopod_config::ensure_no_active_profile()?;
+
     let path = match config_path {
         Some(p) => p,
         None => {
@@ -226,7 +228,9 @@ fn check_channel_status(channel: &Channel) -> &'static str {
     // For now, just return "configured" as we don't have a live connection check
     // This can be enhanced with actual connection testing when the channel plugins
     // are fully integrated
-    if channel.connection.endpoint.is_empty() {
+    if !channel.enabled {
+        "disabled"
+    } else if channel.connection.endpoint.is_empty() {
         "configured"
     } else {
         "connected"
@@ -286,6 +290,7 @@ pub fn setup_channel(channel_type: &ChannelType, config_path: Option<String>) ->
                 id: format!("telegram-{}", name),
                 name,
                 channel_type: "telegram".to_string(),
+                enabled: true,
                 connection: ChannelConnection {
                     endpoint: if webhook.is_empty() {
                         "polling".to_string()
@@ -316,6 +321,7 @@ pub fn setup_channel(channel_type: &ChannelType, config_path: Option<String>) ->
                 id: format!("discord-{}", name),
                 name,
                 channel_type: "discord".to_string(),
+                enabled: true,
                 connection: ChannelConnection {
                     endpoint: format!("https://discord.com/api/v10/webhooks/{}", guild_id),
                     token: token.clone(),
@@ -339,6 +345,7 @@ pub fn setup_channel(channel_type: &ChannelType, config_path: Option<String>) ->
                 id: format!("whatsapp-{}", name),
                 name,
                 channel_type: "whatsapp".to_string(),
+                enabled: true,
                 connection: ChannelConnection {
                     endpoint: "https://whatsapp-business.cloud/api/v1".to_string(),
                     token: token.clone(),
@@ -362,6 +369,7 @@ pub fn setup_channel(channel_type: &ChannelType, config_path: Option<String>) ->
                 id: format!("slack-{}", name),
                 name,
                 channel_type: "slack".to_string(),
+                enabled: true,
                 connection: ChannelConnection {
                     endpoint: "https://slack.com/api".to_string(),
                     token: bot_token.clone(),
@@ -400,6 +408,7 @@ pub fn setup_channel(channel_type: &ChannelType, config_path: Option<String>) ->
                 id: format!("nextcloud-{}", name),
                 name,
                 channel_type: "nextcloud".to_string(),
+                enabled: true,
                 connection: ChannelConnection {
                     endpoint: server_url.clone(),
                     token: password.clone(),
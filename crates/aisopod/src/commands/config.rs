@@ -6,6 +6,9 @@
 //! - wizard: Run interactive setup wizard for first-time configuration
 //! - channels: Interactive channel configuration helper
 //! - init: Initialize a new configuration file from a template
+//! - schema: Emit the JSON Schema of the configuration
+//! - migrate: Upgrade the configuration file to the current config version
+//! - validate: Report unknown keys and invalid settings with their lines
 
 use anyhow::{anyhow, Context, Result};
     use clap::{Args, Subcommand};
@@ -50,6 +53,24 @@ pub enum ConfigCommands {
         #[arg(short, long)]
         output: Option<String>,
     },
+    /// Emit the JSON Schema of the configuration for editors and CI validation
+    Schema {
+        /// Output file path (defaults to stdout)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Upgrade the configuration file written for an older release
+    Migrate {
+        /// Show the changes without rewriting the file
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Check the configuration file for unknown keys and invalid settings
+    Validate {
+        /// Fail on warnings, such as unknown keys, as well as on errors
+        #[arg(long)]
+        strict: bool,
+    },
 }
 
 /// Prompt the user for input
@@ -142,6 +163,8 @@ fn load_config_or_default(config_path: Option<&str>) -> Result<AisopodConfig> {
 
 /// Save configuration to file
 fn save_config(config: &AisopodConfig, config_path: Option<String>) -> Result<()> {
+    aisopod_config::ensure_no_active_profile()?;
+
     let path = match config_path {
         Some(p) => p,
         None => {
@@ -308,11 +331,15 @@ fn run_wizard(config: &mut AisopodConfig) -> Result<()> {
     let api_key = prompt_password(&format!("{} API key: ", provider_name))?;
 
     // Add provider to providers list
-    config.models.providers.push(aisopod_config::types::ModelProvider {
-        name: provider_name,
-        endpoint: "".to_string(),
-        api_key,
-    });
+    config
+        .models
+        .providers
+        .push(aisopod_config::types::ModelProvider {
+            name: provider_name,
+            endpoint: "".to_string(),
+            api_key,
+            ..Default::default()
+        });
 
     // Step 3: Confirm and save
     println!("\n=== Configuration Summary ===");
@@ -459,8 +486,124 @@ fn init_config(template: Option<String>, output: Option<String>) -> Result<()> {
     Ok(())
 }
 
+/// Write the JSON Schema of the configuration to a file or stdout
+fn emit_schema(output: Option<String>) -> Result<()> {
+    let schema = aisopod_config::config_schema_json()?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(&path, format!("{}\n", schema))
+                .with_context(|| format!("Failed to write schema to '{}'", path))?;
+            println!("Configuration schema written to '{}'", path);
+        }
+        None => println!("{}", schema),
+    }
+
+    Ok(())
+}
+
+/// Upgrade the configuration file to the current config version
+fn migrate_config_file(config_path: Option<&str>, dry_run: bool) -> Result<()> {
+    let path = match config_path {
+        Some(path) => PathBuf::from(path),
+        None => aisopod_config::default_config_path(),
+    };
+    let report = aisopod_config::migrate_config_file(&path, dry_run)
+        .with_context(|| format!("Failed to migrate '{}'", path.display()))?;
+
+    if report.is_up_to_date() {
+        println!(
+            "Configuration '{}' is up to date (version {})",
+            path.display(),
+            report.to_version
+        );
+        return Ok(());
+    }
+
+    println!(
+        "Configuration '{}' {} from version {} to {}:",
+        path.display(),
+        if dry_run { "would be migrated" } else { "migrated" },
+        report.from_version,
+        report.to_version
+    );
+    for description in &report.applied {
+        println!("  * {}", description);
+    }
+    println!("{}", report.diff());
+    if !dry_run {
+        println!(
+            "The original file was kept as '{}.v{}.bak'",
+            path.display(),
+            report.from_version
+        );
+    }
+
+    Ok(())
+}
+
+/// Check the configuration file and print its diagnostics, one per line
+/// with the file and line they refer to
+fn validate_config_file(config_path: Option<&str>, strict: bool) -> Result<()> {
+    let path = match config_path {
+        Some(path) => PathBuf::from(path),
+        None => aisopod_config::default_config_path(),
+    };
+    let diagnostics = aisopod_config::diagnose_config_file(&path)
+        .with_context(|| format!("Failed to validate '{}'", path.display()))?;
+
+    for diagnostic in &diagnostics {
+        match diagnostic.line {
+            Some(line) => println!("{}:{}: {}", path.display(), line, diagnostic),
+            None => println!("{}: {}", path.display(), diagnostic),
+        }
+        if let Some(suggestion) = &diagnostic.suggestion {
+            println!("  help: {}", suggestion);
+        }
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == aisopod_config::Severity::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+    if errors > 0 || (strict && warnings > 0) {
+        return Err(anyhow!(
+            "Configuration '{}' is invalid: {} error(s), {} warning(s)",
+            path.display(),
+            errors,
+            warnings
+        ));
+    }
+    if warnings > 0 {
+        println!(
+            "Configuration '{}' is valid with {} warning(s)",
+            path.display(),
+            warnings
+        );
+    } else {
+        println!("Configuration '{}' is valid", path.display());
+    }
+
+    Ok(())
+}
+
 /// Run the configuration management command with the given arguments and config path
 pub fn run(args: ConfigArgs, config_path: Option<String>) -> Result<()> {
+    // The schema does not depend on the current configuration, which may
+    // not even load yet
+    if let ConfigCommands::Schema { output } = args.command {
+        return emit_schema(output);
+    }
+    // Migrating must not require the file to load as it is
+    if let ConfigCommands::Migrate { dry_run } = args.command {
+        return migrate_config_file(config_path.as_deref(), dry_run);
+    }
+    // Validating reports what keeps the file from loading
+    if let ConfigCommands::Validate { strict } = args.command {
+        return validate_config_file(config_path.as_deref(), strict);
+    }
+
     let config_path_ref = config_path.as_deref();
     let mut config = load_config_or_default(config_path_ref)?;
 
@@ -482,6 +625,11 @@ pub fn run(args: ConfigArgs, config_path: Option<String>) -> Result<()> {
         ConfigCommands::Init { template, output } => {
             init_config(template, output)?;
         }
+        ConfigCommands::Schema { .. }
+        | ConfigCommands::Migrate { .. }
+        | ConfigCommands::Validate { .. } => {
+            unreachable!("handled above")
+        }
     }
 
     Ok(())
@@ -542,6 +690,7 @@ mod tests {
             name: "openai".to_string(),
             endpoint: "".to_string(),
             api_key: "sk-test-key-12345".to_string(),
+            ..Default::default()
         }];
 
         // This should not panic and should redact the sensitive field
@@ -594,4 +743,58 @@ mod tests {
             _ => assert!(false),
         }
     }
+
+    #[test]
+    fn test_config_schema_to_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("aisopod.schema.json");
+
+        emit_schema(Some(output.to_string_lossy().to_string())).unwrap();
+
+        let schema: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(schema["title"], "AisopodConfig");
+        assert!(schema["properties"]["gateway"].is_object());
+    }
+
+    #[test]
+    fn test_config_migrate_command() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("aisopod-config.json5");
+        fs::write(&path, "{ gateway: { server: { port: 9000 } } }").unwrap();
+        let path_str = path.to_string_lossy().to_string();
+
+        migrate_config_file(Some(&path_str), true).unwrap();
+        assert!(!fs::read_to_string(&path).unwrap().contains("config_version"));
+
+        migrate_config_file(Some(&path_str), false).unwrap();
+        let migrated: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(migrated["config_version"], aisopod_config::CURRENT_CONFIG_VERSION);
+        assert_eq!(migrated["gateway"]["server"]["port"], 9000);
+    }
+
+    #[test]
+    fn test_config_validate_command() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("aisopod-config.json5");
+        fs::write(
+            &path,
+            r#"{ agents: { agents: [{ id: "a", name: "A", modle: "openai/gpt-4o" }] } }"#,
+        )
+        .unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        let path_str = path.to_string_lossy().to_string();
+
+        validate_config_file(Some(&path_str), false).unwrap();
+        let err = validate_config_file(Some(&path_str), true).unwrap_err();
+        assert!(err.to_string().contains("0 error(s), 1 warning(s)"));
+
+        fs::write(
+            &path,
+            r#"{ bindings: [{ agent_id: "missing", channels: [] }] }"#,
+        )
+        .unwrap();
+        assert!(validate_config_file(Some(&path_str), false).is_err());
+    }
 }
@@ -0,0 +1,114 @@
+//! Agent evaluation command implementation module
+//!
+//! This module provides the `aisopod eval` command, which runs agents against a
+//! YAML-defined suite of prompts with assertions and reports the passed and failed
+//! cases. The command exits with a non-zero status if any case fails, so suites
+//! can gate changes to prompts and models in CI.
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use std::path::Path;
+use std::sync::Arc;
+
+use aisopod_agent::{AgentRunner, EvalReport, EvalRunner, EvalSuite};
+use aisopod_config::load_config;
+
+use crate::commands::models::create_provider_registry;
+
+/// Eval command arguments
+#[derive(Args)]
+pub struct EvalArgs {
+    /// Path to the YAML eval suite
+    pub suite: String,
+
+    /// Run every case with this agent, overriding the suite
+    #[arg(long)]
+    pub agent: Option<String>,
+}
+
+/// Load configuration from the given path or the default path
+fn load_eval_config(config_path: Option<&str>) -> Result<aisopod_config::AisopodConfig> {
+    let path = match config_path {
+        Some(path) => Path::new(path).to_path_buf(),
+        None => aisopod_config::default_config_path(),
+    };
+    load_config(&path)
+        .map_err(|e| anyhow!("Failed to load configuration from '{}': {}", path.display(), e))
+}
+
+/// Print the report of a suite in human-readable form
+fn print_report(report: &EvalReport) {
+    println!("Suite: {}", report.suite);
+    for case in &report.cases {
+        let symbol = if case.passed { "✓" } else { "✗" };
+        println!("  {} {} ({} ms)", symbol, case.name, case.duration_ms);
+        if let Some(error) = &case.error {
+            println!("      error: {}", error);
+        }
+        for assertion in case.assertions.iter().filter(|a| !a.passed) {
+            print!("      failed: {}", assertion.assertion);
+            if let Some(reason) = &assertion.reason {
+                print!(" ({})", reason);
+            }
+            println!();
+        }
+    }
+    println!("\n{} passed, {} failed", report.passed(), report.failed());
+}
+
+/// Run an eval suite against the configured agents
+pub async fn run(args: EvalArgs, config_path: Option<String>, json: bool) -> Result<()> {
+    let suite = EvalSuite::load(Path::new(&args.suite))?;
+    let config = load_eval_config(config_path.as_deref())?;
+
+    let providers = create_provider_registry(&config).await?;
+    let mut tools = aisopod_tools::ToolRegistry::new();
+    aisopod_tools::register_configured_tools(&mut tools, &config.tools);
+    let sessions = aisopod_session::SessionStore::new_in_memory()?;
+    let runner = AgentRunner::new(
+        Arc::new(config),
+        Arc::new(providers),
+        Arc::new(tools),
+        Arc::new(sessions),
+    );
+
+    let mut evaluator = EvalRunner::new(Arc::new(runner));
+    if let Some(agent) = args.agent {
+        evaluator = evaluator.with_agent(agent);
+    }
+    let report = evaluator.run(&suite).await;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+
+    if !report.all_passed() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eval_args() {
+        let args = EvalArgs {
+            suite: "suites/smoke.yaml".to_string(),
+            agent: Some("support".to_string()),
+        };
+
+        assert_eq!(args.suite, "suites/smoke.yaml");
+        assert_eq!(args.agent, Some("support".to_string()));
+    }
+
+    #[test]
+    fn test_missing_config_is_an_error() {
+        let result = load_eval_config(Some("/nonexistent/aisopod-config.json5"));
+        assert!(result.is_err());
+    }
+}
@@ -8,7 +8,8 @@ use clap::Args;
 use std::path::Path;
 
 use aisopod_config::load_config;
-use aisopod_gateway::run_with_config;
+use aisopod_config::{ConfigWatcher, RemoteConfigSource};
+use aisopod_gateway::{run_with_config, run_with_updates};
 
 /// Gateway command arguments
 #[derive(Args)]
@@ -24,23 +25,46 @@ pub struct GatewayArgs {
     /// Allow requests to unconfigured agents
     #[arg(long)]
     pub allow_unconfigured: bool,
+
+    /// Base64 Ed25519 public key trusted to sign a remote configuration
+    /// (repeatable); signatures are required once a key is given
+    #[arg(long = "config-public-key")]
+    pub config_public_keys: Vec<String>,
+}
+
+/// Build the source of a remote configuration trusting `keys`
+///
+/// Unsigned configurations are only accepted over TLS, as anyone on the
+/// path of a plain HTTP request could otherwise rewrite them.
+fn remote_source(url: &str, keys: &[String]) -> Result<RemoteConfigSource> {
+    if keys.is_empty() && url.starts_with("http://") {
+        anyhow::bail!(
+            "Refusing to load an unsigned configuration over plain HTTP from '{}'; \
+             use https:// or pass --config-public-key",
+            url
+        );
+    }
+    let mut source = RemoteConfigSource::new(url)?;
+    for key in keys {
+        source = source.with_trusted_key(key)?;
+    }
+    Ok(source)
 }
 
 /// Run the gateway server with the given arguments and config path
 pub async fn run(args: GatewayArgs, config_path: Option<String>) -> Result<()> {
-    // Set up tracing subscriber to output to stdout with audit logging
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::INFO)
-        .with_target(true)
-        // Configure audit logging to use the "audit" target at INFO level
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "aisopod=info,audit=info".into()),
-        )
-        .init();
-
-    // Load configuration from file or use defaults
+    // A remote configuration keeps being polled while the gateway runs
+    let mut watcher = None;
+
+    // Load configuration from a file or URL, or use defaults
     let mut config = match config_path {
+        Some(url) if aisopod_config::is_remote_url(&url) => {
+            let source = remote_source(&url, &args.config_public_keys)?;
+            let remote = ConfigWatcher::watch_remote(source).await?;
+            let config = remote.receiver().borrow().clone();
+            watcher = Some(remote);
+            config
+        }
         Some(path) => {
             let config_path = Path::new(&path);
             load_config(config_path).map_err(|e| {
@@ -53,6 +77,11 @@ pub async fn run(args: GatewayArgs, config_path: Option<String>) -> Result<()> {
         }
     };
 
+    // Set up tracing to output to stdout with audit logging, and to export
+    // spans when telemetry is configured; the guard flushes them on exit
+    let _telemetry =
+        aisopod_gateway::telemetry::init_tracing(&config.gateway.telemetry, "aisopod=info,audit=info")?;
+
     // Override config with CLI flags for bind address and port
     let bind_addr = format!("{}:{}", args.bind, args.port);
     
@@ -69,8 +98,24 @@ pub async fn run(args: GatewayArgs, config_path: Option<String>) -> Result<()> {
         println!("Allowing requests to unconfigured agents");
     }
 
-    // Run the gateway server with the loaded config
-    run_with_config(&config).await?;
+    // Run the gateway server with the loaded config, applying the updates
+    // of a remote one
+    match &watcher {
+        Some(watcher) => run_with_updates(&config, watcher.receiver()).await?,
+        None => run_with_config(&config).await?,
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_source_refuses_unsigned_http() {
+        let err = remote_source("http://config.example.com/aisopod.json", &[]).unwrap_err();
+        assert!(err.to_string().contains("--config-public-key"));
+        assert!(remote_source("https://config.example.com/aisopod.json", &[]).is_ok());
+    }
+}
@@ -0,0 +1,660 @@
+//! Guided setup command for new installations.
+//!
+//! `aisopod init` walks through configuring model providers, the default
+//! agent and messaging channels, checks the entered credentials against the
+//! live services, and writes a working configuration file. Channels are set
+//! up through their [`OnboardingAdapter`] implementations.
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use clap::Args;
+use colored::Colorize;
+use serde_json::{json, Value};
+use std::io::{self, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use aisopod_channel::{AccountConfig, OnboardingAdapter, OnboardingContext};
+use aisopod_config::sensitive::Sensitive;
+use aisopod_config::types::{Agent, AisopodConfig, Channel, ChannelConnection, ModelProvider};
+use aisopod_config::{generate_config_with_format, ConfigFormat};
+
+use crate::commands::models::create_provider_registry;
+
+/// Guided setup command arguments
+#[derive(Args)]
+pub struct InitArgs {
+    /// Output file path; a `.toml` extension writes TOML, anything else JSON5
+    #[arg(short, long)]
+    pub output: Option<String>,
+    /// Overwrite an existing configuration file
+    #[arg(long)]
+    pub force: bool,
+    /// Don't check the credentials against the live services
+    #[arg(long)]
+    pub skip_validation: bool,
+}
+
+/// A model provider the wizard can set up
+struct ProviderPreset {
+    name: &'static str,
+    label: &'static str,
+    endpoint: &'static str,
+    needs_api_key: bool,
+    default_model: &'static str,
+}
+
+const PROVIDER_PRESETS: &[ProviderPreset] = &[
+    ProviderPreset {
+        name: "openai",
+        label: "OpenAI",
+        endpoint: "https://api.openai.com",
+        needs_api_key: true,
+        default_model: "gpt-4o",
+    },
+    ProviderPreset {
+        name: "anthropic",
+        label: "Anthropic",
+        endpoint: "https://api.anthropic.com",
+        needs_api_key: true,
+        default_model: "claude-3-5-sonnet-latest",
+    },
+    ProviderPreset {
+        name: "gemini",
+        label: "Google Gemini",
+        endpoint: "https://generativelanguage.googleapis.com",
+        needs_api_key: true,
+        default_model: "gemini-1.5-pro",
+    },
+    ProviderPreset {
+        name: "openrouter",
+        label: "OpenRouter",
+        endpoint: "https://openrouter.ai/api",
+        needs_api_key: true,
+        default_model: "openai/gpt-4o",
+    },
+    ProviderPreset {
+        name: "groq",
+        label: "Groq",
+        endpoint: "https://api.groq.com/openai",
+        needs_api_key: true,
+        default_model: "llama-3.3-70b-versatile",
+    },
+    ProviderPreset {
+        name: "mistral",
+        label: "Mistral",
+        endpoint: "https://api.mistral.ai",
+        needs_api_key: true,
+        default_model: "mistral-large-latest",
+    },
+    ProviderPreset {
+        name: "deepseek",
+        label: "DeepSeek",
+        endpoint: "https://api.deepseek.com",
+        needs_api_key: true,
+        default_model: "deepseek-chat",
+    },
+    ProviderPreset {
+        name: "xai",
+        label: "xAI",
+        endpoint: "https://api.x.ai",
+        needs_api_key: true,
+        default_model: "grok-2-latest",
+    },
+    ProviderPreset {
+        name: "ollama",
+        label: "Ollama (local)",
+        endpoint: "http://localhost:11434",
+        needs_api_key: false,
+        default_model: "llama3.2",
+    },
+];
+
+/// Channels the wizard can set up
+const CHANNEL_CHOICES: &[&str] = &["telegram", "discord", "slack"];
+
+/// Prompt the user for input
+fn prompt(prompt_text: &str) -> Result<String> {
+    print!("{}", prompt_text);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    Ok(input.trim().to_string())
+}
+
+/// Prompt the user for input with a default value
+fn prompt_with_default(prompt_text: &str, default: &str) -> Result<String> {
+    let input = prompt(&format!("{} [{}]: ", prompt_text, default))?;
+    if input.is_empty() {
+        Ok(default.to_string())
+    } else {
+        Ok(input)
+    }
+}
+
+/// Prompt the user for a yes/no answer
+fn prompt_yes_no(prompt_text: &str, default: bool) -> Result<bool> {
+    let hint = if default { "Y/n" } else { "y/N" };
+    let input = prompt(&format!("{} [{}]: ", prompt_text, hint))?;
+    Ok(match input.to_lowercase().as_str() {
+        "" => default,
+        answer => answer == "y" || answer == "yes",
+    })
+}
+
+/// Prompt the user to select one of the options, returning its index
+fn prompt_select(prompt_text: &str, options: &[&str]) -> Result<usize> {
+    println!("{}:", prompt_text);
+    for (i, option) in options.iter().enumerate() {
+        println!("  {}. {}", i + 1, option);
+    }
+
+    loop {
+        let input = prompt("Enter choice (number): ")?;
+        if let Ok(index) = input.parse::<usize>() {
+            if index >= 1 && index <= options.len() {
+                return Ok(index - 1);
+            }
+        }
+        println!(
+            "Invalid choice. Please enter a number between 1 and {}.",
+            options.len()
+        );
+    }
+}
+
+/// Prompt the user for a secret without echoing it
+fn prompt_password(prompt_text: &str) -> Result<String> {
+    #[cfg(unix)]
+    {
+        let password = rpassword::prompt_password(prompt_text)?;
+        Ok(password.trim().to_string())
+    }
+    #[cfg(not(unix))]
+    {
+        prompt(prompt_text)
+    }
+}
+
+/// Prompt for a secret until a non-empty one is entered
+fn prompt_required_secret(prompt_text: &str) -> Result<String> {
+    loop {
+        let secret = prompt_password(prompt_text)?;
+        if !secret.is_empty() {
+            return Ok(secret);
+        }
+        println!("A value is required.");
+    }
+}
+
+/// Pick the config format from the output file extension
+fn format_for_path(path: &Path) -> ConfigFormat {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => ConfigFormat::Toml,
+        _ => ConfigFormat::Json5,
+    }
+}
+
+/// Build the provider entry for a preset
+fn provider_config(preset: &ProviderPreset, endpoint: &str, api_key: &str) -> ModelProvider {
+    ModelProvider {
+        name: preset.name.to_string(),
+        endpoint: endpoint.to_string(),
+        api_key: api_key.to_string(),
+        ..Default::default()
+    }
+}
+
+/// Check a provider's credentials by querying the live service, returning
+/// the ids of the models it offers
+async fn validate_provider(provider: &ModelProvider) -> Result<Vec<String>> {
+    let mut config = AisopodConfig::default();
+    config.models.providers.push(provider.clone());
+    let registry = create_provider_registry(&config).await?;
+    let client = registry
+        .get(&provider.name)
+        .ok_or_else(|| anyhow!("Unknown provider '{}'", provider.name))?;
+
+    let health = client.health_check().await?;
+    if !health.available {
+        bail!("Provider '{}' is not reachable", provider.name);
+    }
+    let models = client.list_models().await?;
+    Ok(models.into_iter().map(|model| model.id).collect())
+}
+
+/// Read a string credential of an onboarded account
+fn credential<'a>(account: &'a AccountConfig, key: &str) -> Result<&'a str> {
+    account
+        .credentials
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| anyhow!("Account '{}' has no '{}'", account.id, key))
+}
+
+/// Add an onboarded channel account to the configuration
+fn apply_account(config: &mut AisopodConfig, account: &AccountConfig) -> Result<()> {
+    let token = credential(account, "token")?.to_string();
+    let endpoint = match account.channel.as_str() {
+        "telegram" => {
+            config.channels.telegram.token = Some(Sensitive::new(token.clone()));
+            account
+                .credentials
+                .get("webhook")
+                .and_then(Value::as_str)
+                .filter(|webhook| !webhook.is_empty())
+                .unwrap_or("polling")
+                .to_string()
+        }
+        "discord" => {
+            config.channels.discord.token = Some(Sensitive::new(token.clone()));
+            "https://discord.com/api/v10".to_string()
+        }
+        "slack" => {
+            let signing_secret = credential(account, "signing_secret")?;
+            config.channels.slack.token = Some(Sensitive::new(token.clone()));
+            config.channels.slack.signing_secret = Some(Sensitive::new(signing_secret.to_string()));
+            "https://slack.com/api".to_string()
+        }
+        other => bail!("Unsupported channel '{}'", other),
+    };
+
+    let id = format!("{}-{}", account.channel, account.id);
+    if config.channels.channels.iter().any(|c| c.id == id) {
+        bail!("Channel '{}' is already configured", id);
+    }
+    config.channels.channels.push(Channel {
+        id,
+        name: account.id.clone(),
+        channel_type: account.channel.clone(),
+        enabled: account.enabled,
+        connection: ChannelConnection { endpoint, token },
+    });
+    Ok(())
+}
+
+/// Fetch a JSON document from a platform API, failing on error statuses
+async fn fetch_json(request: reqwest::RequestBuilder) -> Result<Value> {
+    let response = request.send().await?;
+    let status = response.status();
+    let body: Value = response.json().await.unwrap_or(Value::Null);
+    if !status.is_success() {
+        bail!("request failed with HTTP {}", status);
+    }
+    Ok(body)
+}
+
+/// Onboarding for Telegram bots
+struct TelegramOnboarding {
+    validate: bool,
+}
+
+#[async_trait]
+impl OnboardingAdapter for TelegramOnboarding {
+    async fn setup_wizard(&self, _ctx: &OnboardingContext) -> Result<AccountConfig, anyhow::Error> {
+        println!("\n=== Telegram Bot Setup ===");
+        println!("1. Open @BotFather on Telegram");
+        println!("2. Send /newbot and follow the prompts");
+        println!("3. Copy the bot token\n");
+
+        let token = prompt_required_secret("Bot token: ")?;
+        if self.validate {
+            let url = format!("https://api.telegram.org/bot{}/getMe", token);
+            let body = fetch_json(reqwest::Client::new().get(url))
+                .await
+                .context("Telegram rejected the bot token")?;
+            let username = body["result"]["username"].as_str().unwrap_or("unknown");
+            println!("{} Authenticated as @{}", "✓".green(), username);
+        }
+        let webhook = prompt_with_default("Webhook URL (leave blank for polling)", "")?;
+        let name = prompt_with_default("Channel name", "telegram")?;
+
+        Ok(AccountConfig {
+            id: name,
+            channel: "telegram".to_string(),
+            credentials: json!({ "token": token, "webhook": webhook }),
+            enabled: true,
+        })
+    }
+}
+
+/// Onboarding for Discord bots
+struct DiscordOnboarding {
+    validate: bool,
+}
+
+#[async_trait]
+impl OnboardingAdapter for DiscordOnboarding {
+    async fn setup_wizard(&self, _ctx: &OnboardingContext) -> Result<AccountConfig, anyhow::Error> {
+        println!("\n=== Discord Bot Setup ===");
+        println!("1. Go to https://discord.com/developers/applications");
+        println!("2. Create a new application and add a bot");
+        println!("3. Copy the bot token\n");
+
+        let token = prompt_required_secret("Bot token: ")?;
+        if self.validate {
+            let request = reqwest::Client::new()
+                .get("https://discord.com/api/v10/users/@me")
+                .header("Authorization", format!("Bot {}", token));
+            let body = fetch_json(request)
+                .await
+                .context("Discord rejected the bot token")?;
+            let username = body["username"].as_str().unwrap_or("unknown");
+            println!("{} Authenticated as {}", "✓".green(), username);
+        }
+        let name = prompt_with_default("Channel name", "discord")?;
+
+        Ok(AccountConfig {
+            id: name,
+            channel: "discord".to_string(),
+            credentials: json!({ "token": token }),
+            enabled: true,
+        })
+    }
+}
+
+/// Onboarding for Slack apps
+struct SlackOnboarding {
+    validate: bool,
+}
+
+#[async_trait]
+impl OnboardingAdapter for SlackOnboarding {
+    async fn setup_wizard(&self, _ctx: &OnboardingContext) -> Result<AccountConfig, anyhow::Error> {
+        println!("\n=== Slack App Setup ===");
+        println!("1. Go to https://api.slack.com/apps and create an app");
+        println!("2. Install it to your workspace");
+        println!("3. Copy the bot token and the signing secret\n");
+
+        let token = prompt_required_secret("Bot token (xoxb-...): ")?;
+        if self.validate {
+            let request = reqwest::Client::new()
+                .post("https://slack.com/api/auth.test")
+                .bearer_auth(&token);
+            let body = fetch_json(request).await?;
+            // Slack reports failures in the body with a 200 status
+            if body["ok"] != Value::Bool(true) {
+                bail!(
+                    "Slack rejected the bot token: {}",
+                    body["error"].as_str().unwrap_or("unknown error")
+                );
+            }
+            let team = body["team"].as_str().unwrap_or("unknown");
+            println!("{} Authenticated in workspace {}", "✓".green(), team);
+        }
+        let signing_secret = prompt_required_secret("Signing secret: ")?;
+        let name = prompt_with_default("Channel name", "slack")?;
+
+        Ok(AccountConfig {
+            id: name,
+            channel: "slack".to_string(),
+            credentials: json!({ "token": token, "signing_secret": signing_secret }),
+            enabled: true,
+        })
+    }
+}
+
+/// Get the onboarding adapter of a channel
+fn onboarding_adapter(channel: &str, validate: bool) -> Result<Box<dyn OnboardingAdapter>> {
+    Ok(match channel {
+        "telegram" => Box::new(TelegramOnboarding { validate }),
+        "discord" => Box::new(DiscordOnboarding { validate }),
+        "slack" => Box::new(SlackOnboarding { validate }),
+        other => bail!("No onboarding available for channel '{}'", other),
+    })
+}
+
+/// Ask for a provider and its credentials, returning the provider entry and
+/// the models it offers
+async fn setup_provider(validate: bool) -> Result<Option<(ModelProvider, Vec<String>)>> {
+    let labels: Vec<&str> = PROVIDER_PRESETS.iter().map(|p| p.label).collect();
+    let preset =
+        &PROVIDER_PRESETS[prompt_select("Which provider would you like to use?", &labels)?];
+
+    let endpoint = prompt_with_default("Endpoint", preset.endpoint)?;
+    let api_key = if preset.needs_api_key {
+        prompt_required_secret(&format!("{} API key: ", preset.label))?
+    } else {
+        String::new()
+    };
+    let provider = provider_config(preset, &endpoint, &api_key);
+
+    if !validate {
+        return Ok(Some((provider, vec![preset.default_model.to_string()])));
+    }
+
+    print!("Checking credentials... ");
+    io::stdout().flush()?;
+    match validate_provider(&provider).await {
+        Ok(models) => {
+            println!("{} ({} models available)", "ok".green(), models.len());
+            Ok(Some((provider, models)))
+        }
+        Err(e) => {
+            println!("{}", "failed".red());
+            println!("  {}", e);
+            if prompt_yes_no("Keep this provider anyway?", false)? {
+                Ok(Some((provider, vec![preset.default_model.to_string()])))
+            } else {
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Run the guided setup
+pub async fn run(args: InitArgs, config_path: Option<String>) -> Result<()> {
+    let path = PathBuf::from(
+        args.output
+            .or(config_path)
+            .unwrap_or_else(|| aisopod_config::DEFAULT_CONFIG_FILE.to_string()),
+    );
+    if path.exists() && !args.force {
+        bail!(
+            "'{}' already exists, use --force to overwrite it",
+            path.display()
+        );
+    }
+    let validate = !args.skip_validation;
+    let mut config = AisopodConfig::default();
+
+    println!("{}", "Welcome to aisopod!".bold());
+    println!(
+        "This wizard writes a configuration to {}.\n",
+        path.display()
+    );
+
+    // Step 1: Model providers
+    println!("{}\n", "Step 1: Model providers".bold());
+    let mut suggestions = Vec::new();
+    loop {
+        if let Some((provider, models)) = setup_provider(validate).await? {
+            if config.models.default_provider.is_empty() {
+                config.models.default_provider = provider.name.clone();
+            }
+            suggestions.extend(models.iter().map(|m| format!("{}/{}", provider.name, m)));
+            config.models.providers.push(provider);
+        }
+        if config.models.providers.is_empty() {
+            println!("At least one provider is required.\n");
+            continue;
+        }
+        if !prompt_yes_no("Add another provider?", false)? {
+            break;
+        }
+    }
+
+    // Step 2: Default agent
+    println!("\n{}\n", "Step 2: Default agent".bold());
+    if suggestions.len() > 1 {
+        println!("Available models:");
+        for model in suggestions.iter().take(10) {
+            println!("  {}", model);
+        }
+        if suggestions.len() > 10 {
+            println!("  ... and {} more", suggestions.len() - 10);
+        }
+    }
+    let agent_id = prompt_with_default("Agent id", "default")?;
+    let model = prompt_with_default("Model", &suggestions[0])?;
+    let system_prompt = prompt_with_default("System prompt", "You are a helpful assistant.")?;
+    config.agents.default.model = model.clone();
+    config.agents.agents.push(Agent {
+        id: agent_id.clone(),
+        name: agent_id,
+        model,
+        system_prompt,
+        ..Default::default()
+    });
+
+    // Step 3: Channels
+    println!("\n{}\n", "Step 3: Messaging channels (optional)".bold());
+    let ctx = OnboardingContext {
+        config_dir: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+        channel_config_dir: None,
+    };
+    while prompt_yes_no(
+        "Set up a messaging channel?",
+        config.channels.channels.is_empty(),
+    )? {
+        let channel = CHANNEL_CHOICES[prompt_select("Which channel?", CHANNEL_CHOICES)?];
+        let adapter = onboarding_adapter(channel, validate)?;
+        match adapter.setup_wizard(&ctx).await {
+            Ok(account) => apply_account(&mut config, &account)?,
+            Err(e) => println!("{} {:#}", "Channel setup failed:".red(), e),
+        }
+    }
+
+    // Step 4: Write the configuration
+    if let Err(errors) = config.validate() {
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        bail!("The configuration is invalid:\n  {}", messages.join("\n  "));
+    }
+    let content = generate_config_with_format(&config, format_for_path(&path))?;
+    std::fs::write(&path, content)
+        .with_context(|| format!("Failed to write '{}'", path.display()))?;
+    // The config holds credentials, and the loader rejects world-readable files
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+
+    println!(
+        "\n{} {}",
+        "Configuration written to".green().bold(),
+        path.display()
+    );
+    println!("\nNext steps:");
+    println!("  aisopod gateway     - Start the gateway server");
+    println!("  aisopod message     - Send a message");
+    println!("  aisopod doctor      - Check your setup");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(channel: &str, credentials: Value) -> AccountConfig {
+        AccountConfig {
+            id: "main".to_string(),
+            channel: channel.to_string(),
+            credentials,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_format_for_path() {
+        assert_eq!(
+            format_for_path(Path::new("aisopod.toml")),
+            ConfigFormat::Toml
+        );
+        assert_eq!(
+            format_for_path(Path::new("aisopod-config.json5")),
+            ConfigFormat::Json5
+        );
+        assert_eq!(format_for_path(Path::new("aisopod")), ConfigFormat::Json5);
+    }
+
+    #[test]
+    fn test_provider_presets_are_buildable() {
+        for preset in PROVIDER_PRESETS {
+            let provider = provider_config(preset, preset.endpoint, "key");
+            assert_eq!(provider.name, preset.name);
+            assert!(!preset.default_model.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provider_presets_are_known_to_the_registry() {
+        let mut config = AisopodConfig::default();
+        for preset in PROVIDER_PRESETS {
+            config
+                .models
+                .providers
+                .push(provider_config(preset, preset.endpoint, "key"));
+        }
+        let registry = create_provider_registry(&config).await.unwrap();
+        for preset in PROVIDER_PRESETS {
+            assert!(registry.get(preset.name).is_some(), "{}", preset.name);
+        }
+    }
+
+    #[test]
+    fn test_apply_telegram_account() {
+        let mut config = AisopodConfig::default();
+        apply_account(
+            &mut config,
+            &account("telegram", json!({"token": "123:abc", "webhook": ""})),
+        )
+        .unwrap();
+
+        let channel = &config.channels.channels[0];
+        assert_eq!(channel.id, "telegram-main");
+        assert_eq!(channel.connection.endpoint, "polling");
+        assert_eq!(
+            config.channels.telegram.token.as_ref().unwrap().expose(),
+            "123:abc"
+        );
+
+        // The same account can't be added twice
+        assert!(apply_account(&mut config, &account("telegram", json!({"token": "x"}))).is_err());
+    }
+
+    #[test]
+    fn test_apply_slack_account_requires_signing_secret() {
+        let mut config = AisopodConfig::default();
+        assert!(apply_account(&mut config, &account("slack", json!({"token": "xoxb-1"}))).is_err());
+        apply_account(
+            &mut config,
+            &account("slack", json!({"token": "xoxb-1", "signing_secret": "s"})),
+        )
+        .unwrap();
+        assert_eq!(config.channels.channels[0].channel_type, "slack");
+        assert!(config.channels.slack.signing_secret.is_some());
+        assert!(apply_account(&mut config, &account("irc", json!({"token": "t"}))).is_err());
+    }
+
+    #[test]
+    fn test_generated_config_is_valid() {
+        let mut config = AisopodConfig::default();
+        config.models.providers.push(provider_config(
+            &PROVIDER_PRESETS[0],
+            "https://api.openai.com",
+            "sk-test",
+        ));
+        config.agents.agents.push(Agent {
+            id: "default".to_string(),
+            name: "default".to_string(),
+            model: "openai/gpt-4o".to_string(),
+            ..Default::default()
+        });
+        apply_account(&mut config, &account("discord", json!({"token": "abc"}))).unwrap();
+        config.validate().unwrap();
+
+        let content = generate_config_with_format(&config, ConfigFormat::Toml).unwrap();
+        assert!(content.contains("discord-main"));
+    }
+}
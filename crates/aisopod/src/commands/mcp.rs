@@ -0,0 +1,149 @@
+//! MCP command implementation
+//!
+//! This module provides the `aisopod mcp serve` command, which exposes the
+//! built-in tools to external MCP clients (e.g. desktop assistants or other
+//! agents) over stdio or HTTP with Server-Sent Events. Only the tools listed
+//! in `tools.mcp.export.tools` (or passed with `--tool`) are exported. They
+//! run in the workspace and sandbox of the export agent, as they would in
+//! that agent's runs.
+//!
+//! Over HTTP, clients authenticate with one of the gateway tokens from
+//! `auth.tokens`. Without tokens the server only binds to loopback.
+
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+
+use aisopod_agent::ConfigToolContexts;
+use aisopod_config::load_config;
+use aisopod_tools::{register_configured_tools, McpServer, ToolRegistry};
+
+/// MCP command arguments
+#[derive(Args)]
+pub struct McpArgs {
+    #[command(subcommand)]
+    pub command: McpCommands,
+}
+
+/// Available MCP subcommands
+#[derive(Subcommand)]
+pub enum McpCommands {
+    /// Serve aisopod tools to MCP clients
+    Serve {
+        /// Listen for HTTP/SSE clients on this address instead of using stdio
+        #[arg(long)]
+        bind: Option<String>,
+
+        /// Export this tool in addition to the configured allowlist (repeatable)
+        #[arg(long = "tool")]
+        tools: Vec<String>,
+    },
+}
+
+/// Load configuration from file or use defaults
+fn load_config_or_default(config_path: Option<&str>) -> Result<aisopod_config::AisopodConfig> {
+    match config_path {
+        Some(path) => load_config(Path::new(path))
+            .map_err(|e| anyhow!("Failed to load configuration from '{}': {}", path, e)),
+        None => {
+            let default_path = aisopod_config::default_config_path();
+            if default_path.exists() {
+                load_config(&default_path).map_err(|e| {
+                    anyhow!(
+                        "Failed to load configuration from '{}': {}",
+                        default_path.display(),
+                        e
+                    )
+                })
+            } else {
+                Ok(aisopod_config::AisopodConfig::default())
+            }
+        }
+    }
+}
+
+/// Refuse to serve unauthenticated clients beyond the local machine
+fn check_bind(addr: SocketAddr, server: &McpServer) -> Result<()> {
+    if !addr.ip().is_loopback() && !server.requires_auth() {
+        return Err(anyhow!(
+            "Refusing to serve MCP on non-loopback address {} without authentication; configure auth.tokens or bind to 127.0.0.1",
+            addr
+        ));
+    }
+    Ok(())
+}
+
+/// Run the MCP command with the given arguments and config path
+pub async fn run(args: McpArgs, config_path: Option<String>) -> Result<()> {
+    match args.command {
+        McpCommands::Serve { bind, tools } => serve(bind, tools, config_path).await,
+    }
+}
+
+async fn serve(
+    bind: Option<String>,
+    tools: Vec<String>,
+    config_path: Option<String>,
+) -> Result<()> {
+    // Stdout carries the protocol in stdio mode, so logs go to stderr.
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "aisopod=info".into()),
+        )
+        .init();
+
+    let config = Arc::new(load_config_or_default(config_path.as_deref())?);
+    let mut export = config.tools.mcp.export.clone();
+    export.tools.extend(tools);
+    if export.tools.is_empty() {
+        tracing::warn!("No tools are exported; set tools.mcp.export.tools or pass --tool");
+    }
+
+    let mut registry = ToolRegistry::new();
+    register_configured_tools(&mut registry, &config.tools);
+    let server = Arc::new(
+        McpServer::from_config(Arc::new(registry), &export)
+            .with_auth_tokens(config.auth.tokens.clone())
+            .with_context_resolver(Arc::new(ConfigToolContexts::new(config.clone()))),
+    );
+
+    match bind {
+        Some(addr) => {
+            let listener = tokio::net::TcpListener::bind(&addr)
+                .await
+                .map_err(|e| anyhow!("Failed to bind MCP server to '{}': {}", addr, e))?;
+            check_bind(listener.local_addr()?, &server)?;
+            server.serve_sse(listener).await
+        }
+        None => {
+            server
+                .serve_stdio(tokio::io::stdin(), tokio::io::stdout())
+                .await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aisopod_config::types::TokenCredential;
+
+    #[test]
+    fn test_unauthenticated_server_binds_only_to_loopback() {
+        let open = McpServer::new(Arc::new(ToolRegistry::new()));
+        assert!(check_bind("127.0.0.1:3000".parse().unwrap(), &open).is_ok());
+        assert!(check_bind("[::1]:3000".parse().unwrap(), &open).is_ok());
+        assert!(check_bind("0.0.0.0:3000".parse().unwrap(), &open).is_err());
+
+        let authenticated = open.with_auth_tokens(vec![TokenCredential {
+            token: "secret".to_string(),
+            role: "operator".to_string(),
+            scopes: vec!["operator.write".to_string()],
+        }]);
+        assert!(check_bind("0.0.0.0:3000".parse().unwrap(), &authenticated).is_ok());
+    }
+}
@@ -0,0 +1,613 @@
+//! Memory management commands for the aisopod application.
+//!
+//! This module provides commands for inspecting, correcting, and moving the
+//! memories in the store configured in the `memory.backend` section:
+//! - `list`: List memories, most recently updated first
+//! - `search`: Find memories containing the terms of a query
+//! - `show`: Show a memory in full
+//! - `delete`: Delete a memory
+//! - `update`: Edit the content, tags, or importance of a memory
+//! - `export`: Write memories, with their embeddings and metadata, as JSON Lines
+//! - `import`: Store memories from a JSON Lines export
+//!
+//! Exports can be used as backups, to migrate between backends, or to seed a
+//! new agent with curated knowledge.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use clap::{Args, Subcommand};
+use std::path::Path;
+use std::sync::Arc;
+
+use aisopod_config::load_config;
+use aisopod_config::types::AisopodConfig;
+use aisopod_memory::{
+    store_from_config, EmbeddingProvider, ImportOptions, MemoryEntry, MemoryFilter,
+    MemoryQueryOptions, MemoryStore, MemoryUpdate,
+};
+
+use crate::output::Output;
+
+/// Memory management command arguments
+#[derive(Args)]
+pub struct MemoryArgs {
+    #[command(subcommand)]
+    pub command: MemoryCommands,
+}
+
+/// Available memory management subcommands
+#[derive(Subcommand)]
+pub enum MemoryCommands {
+    /// List memories, most recently updated first
+    List {
+        /// Only list memories of this agent
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Only list memories of this namespace
+        #[arg(long)]
+        namespace: Option<String>,
+
+        /// Only list memories with this tag (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Maximum number of memories to list
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+
+        /// Embedding dimensions of the memory store
+        #[arg(long, default_value_t = 1536)]
+        dimensions: usize,
+    },
+    /// Find memories containing the terms of a query
+    Search {
+        /// Text to search for
+        query: String,
+
+        /// Only search memories of this agent
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Only search memories of this namespace
+        #[arg(long)]
+        namespace: Option<String>,
+
+        /// Only search memories with this tag (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Maximum number of memories to return
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+
+        /// Embedding dimensions of the memory store
+        #[arg(long, default_value_t = 1536)]
+        dimensions: usize,
+    },
+    /// Show a memory in full
+    Show {
+        /// ID of the memory
+        id: String,
+
+        /// Embedding dimensions of the memory store
+        #[arg(long, default_value_t = 1536)]
+        dimensions: usize,
+    },
+    /// Delete a memory
+    Delete {
+        /// ID of the memory
+        id: String,
+
+        /// Embedding dimensions of the memory store
+        #[arg(long, default_value_t = 1536)]
+        dimensions: usize,
+    },
+    /// Edit the content, tags, or importance of a memory
+    Update {
+        /// ID of the memory
+        id: String,
+
+        /// New content
+        #[arg(long)]
+        content: Option<String>,
+
+        /// New importance, between 0.0 and 1.0
+        #[arg(long)]
+        importance: Option<f32>,
+
+        /// Replace the tags with these (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Embedding dimensions of the memory store
+        #[arg(long, default_value_t = 1536)]
+        dimensions: usize,
+    },
+    /// Export memories as JSON Lines
+    Export {
+        /// Only export memories of this agent
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// File to write to (standard output if omitted)
+        #[arg(long, short)]
+        output: Option<String>,
+
+        /// Embedding dimensions of the memory store
+        #[arg(long, default_value_t = 1536)]
+        dimensions: usize,
+    },
+    /// Import memories from a JSON Lines export
+    Import {
+        /// File written by `memory export`
+        file: String,
+
+        /// Assign the imported memories to this agent
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Embedding dimensions of the memory store (defaults to those of the imported memories)
+        #[arg(long)]
+        dimensions: Option<usize>,
+    },
+}
+
+/// Stands in for an embedding provider when the store is only used to list
+/// and store memories that already carry embeddings.
+struct PrecomputedEmbeddings {
+    dimensions: usize,
+}
+
+#[async_trait]
+impl EmbeddingProvider for PrecomputedEmbeddings {
+    async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+        Err(anyhow!("Embedding is not available from the command line"))
+    }
+
+    async fn embed_batch(&self, _texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        Err(anyhow!("Embedding is not available from the command line"))
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+}
+
+/// Load configuration from file or use defaults
+fn load_config_or_default(config_path: Option<&str>) -> Result<AisopodConfig> {
+    match config_path {
+        Some(path) => load_config(Path::new(path))
+            .map_err(|e| anyhow!("Failed to load configuration from '{}': {}", path, e)),
+        None => Ok(AisopodConfig::default()),
+    }
+}
+
+/// Open the configured memory store for embeddings of `dimensions`
+async fn open_store(config: &AisopodConfig, dimensions: usize) -> Result<Arc<dyn MemoryStore>> {
+    store_from_config(
+        &config.memory,
+        Arc::new(PrecomputedEmbeddings { dimensions }),
+    )
+    .await
+}
+
+/// Embedding dimensions of the first memory in a JSON Lines export
+fn export_dimensions(data: &str) -> Result<usize> {
+    let line = data
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .ok_or_else(|| anyhow!("The export contains no memories"))?;
+    let entry: MemoryEntry = serde_json::from_str(line)?;
+    Ok(entry.embedding.len())
+}
+
+/// Build a listing filter from the command line options
+fn listing_filter(
+    agent: Option<String>,
+    namespace: Option<String>,
+    tags: Vec<String>,
+) -> MemoryFilter {
+    MemoryFilter {
+        agent_id: agent,
+        namespace,
+        tags: if tags.is_empty() { None } else { Some(tags) },
+        ..Default::default()
+    }
+}
+
+/// Shorten memory content to a single table cell
+fn preview(content: &str) -> String {
+    const MAX_CHARS: usize = 60;
+    let line = content.lines().next().unwrap_or_default();
+    if content.chars().count() > MAX_CHARS || line.len() < content.len() {
+        let shortened: String = line.chars().take(MAX_CHARS).collect();
+        format!("{}...", shortened.trim_end())
+    } else {
+        content.to_string()
+    }
+}
+
+/// Table row describing a memory
+fn memory_row(entry: &MemoryEntry) -> Vec<String> {
+    vec![
+        entry.id.clone(),
+        entry.agent_id.clone(),
+        format!("{:.2}", entry.metadata.importance),
+        entry.updated_at.format("%Y-%m-%d %H:%M").to_string(),
+        preview(&entry.content),
+    ]
+}
+
+/// Fetch a memory, failing if it does not exist
+async fn require_memory(store: &Arc<dyn MemoryStore>, id: &str) -> Result<MemoryEntry> {
+    store
+        .get(id)
+        .await?
+        .ok_or_else(|| anyhow!("Memory '{}' not found", id))
+}
+
+/// List memories, most recently updated first
+async fn list(
+    filter: MemoryFilter,
+    limit: usize,
+    dimensions: usize,
+    config_path: Option<String>,
+    output: &Output,
+) -> Result<()> {
+    let config = load_config_or_default(config_path.as_deref())?;
+    let store = open_store(&config, dimensions).await?;
+    let mut entries = store.list(filter).await?;
+    if entries.is_empty() {
+        output.info("No memories found");
+        return Ok(());
+    }
+
+    entries.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    entries.truncate(limit);
+    let rows = entries.iter().map(memory_row).collect();
+    output.print_table(&["ID", "Agent", "Importance", "Updated", "Content"], rows);
+    Ok(())
+}
+
+/// Find memories containing the terms of a query
+async fn search(
+    query: &str,
+    filter: MemoryFilter,
+    limit: usize,
+    dimensions: usize,
+    config_path: Option<String>,
+    output: &Output,
+) -> Result<()> {
+    let config = load_config_or_default(config_path.as_deref())?;
+    let store = open_store(&config, dimensions).await?;
+    // Queries cannot be embedded from the command line, so match terms instead
+    let opts = MemoryQueryOptions {
+        top_k: limit,
+        filter,
+        min_score: None,
+    };
+    let matches = store.keyword_query(query, opts).await?;
+    if matches.is_empty() {
+        output.info(&format!("No memories match '{}'", query));
+        return Ok(());
+    }
+
+    let rows = matches
+        .iter()
+        .map(|m| {
+            let mut row = memory_row(&m.entry);
+            row.insert(0, format!("{:.3}", m.score));
+            row
+        })
+        .collect();
+    output.print_table(
+        &["Score", "ID", "Agent", "Importance", "Updated", "Content"],
+        rows,
+    );
+    Ok(())
+}
+
+/// Show a memory in full
+async fn show(id: &str, dimensions: usize, config_path: Option<String>, json: bool) -> Result<()> {
+    let config = load_config_or_default(config_path.as_deref())?;
+    let store = open_store(&config, dimensions).await?;
+    let mut entry = require_memory(&store, id).await?;
+
+    if json {
+        entry.embedding.clear();
+        println!("{}", serde_json::to_string_pretty(&entry)?);
+        return Ok(());
+    }
+
+    println!("ID:         {}", entry.id);
+    println!("Agent:      {}", entry.agent_id);
+    if !entry.namespace.is_empty() {
+        println!("Namespace:  {}", entry.namespace);
+    }
+    println!("Source:     {:?}", entry.metadata.source);
+    println!("Importance: {:.2}", entry.metadata.importance);
+    println!("Tags:       {}", entry.metadata.tags.join(", "));
+    println!("Created:    {}", entry.created_at.to_rfc3339());
+    println!("Updated:    {}", entry.updated_at.to_rfc3339());
+    println!();
+    println!("{}", entry.content);
+    Ok(())
+}
+
+/// Delete a memory
+async fn delete(
+    id: &str,
+    dimensions: usize,
+    config_path: Option<String>,
+    output: &Output,
+) -> Result<()> {
+    let config = load_config_or_default(config_path.as_deref())?;
+    let store = open_store(&config, dimensions).await?;
+    delete_memory(&store, id, output).await
+}
+
+/// Delete a memory from an open store
+async fn delete_memory(store: &Arc<dyn MemoryStore>, id: &str, output: &Output) -> Result<()> {
+    require_memory(store, id).await?;
+    store.delete(id).await?;
+    output.success(&format!("Deleted memory {}", id));
+    Ok(())
+}
+
+/// Edit the content, tags, or importance of a memory
+async fn update(
+    id: &str,
+    update: MemoryUpdate,
+    dimensions: usize,
+    config_path: Option<String>,
+    output: &Output,
+) -> Result<()> {
+    if update.content.is_none() && update.tags.is_none() && update.importance.is_none() {
+        return Err(anyhow!(
+            "Nothing to update; pass --content, --importance, or --tag"
+        ));
+    }
+
+    let config = load_config_or_default(config_path.as_deref())?;
+    let store = open_store(&config, dimensions).await?;
+    update_memory(&store, id, update, output).await
+}
+
+/// Edit a memory of an open store
+async fn update_memory(
+    store: &Arc<dyn MemoryStore>,
+    id: &str,
+    update: MemoryUpdate,
+    output: &Output,
+) -> Result<()> {
+    require_memory(store, id).await?;
+    if update.content.is_some() {
+        output.warning(
+            "The memory keeps its previous embedding; semantic searches still match the old content",
+        );
+    }
+    store.update(id, update).await?;
+    output.success(&format!("Updated memory {}", id));
+    Ok(())
+}
+
+/// Export memories to a file or standard output
+async fn export(
+    agent: Option<String>,
+    output_path: Option<String>,
+    dimensions: usize,
+    config_path: Option<String>,
+    output: &Output,
+) -> Result<()> {
+    let config = load_config_or_default(config_path.as_deref())?;
+    let store = open_store(&config, dimensions).await?;
+    let filter = MemoryFilter {
+        agent_id: agent,
+        ..Default::default()
+    };
+
+    match output_path {
+        Some(path) => {
+            let mut file = tokio::fs::File::create(&path)
+                .await
+                .map_err(|e| anyhow!("Failed to create '{}': {}", path, e))?;
+            let count = store.export_jsonl(filter, &mut file).await?;
+            output.success(&format!("Exported {} memories to {}", count, path));
+        }
+        None => {
+            store.export_jsonl(filter, &mut tokio::io::stdout()).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Import memories from a JSON Lines file
+async fn import(
+    file: &str,
+    agent: Option<String>,
+    dimensions: Option<usize>,
+    config_path: Option<String>,
+    output: &Output,
+) -> Result<()> {
+    let config = load_config_or_default(config_path.as_deref())?;
+    let data = tokio::fs::read_to_string(file)
+        .await
+        .map_err(|e| anyhow!("Failed to read '{}': {}", file, e))?;
+    let dimensions = match dimensions {
+        Some(dimensions) => dimensions,
+        None => export_dimensions(&data)?,
+    };
+
+    let store = open_store(&config, dimensions).await?;
+    let opts = ImportOptions {
+        agent_id: agent,
+        ..Default::default()
+    };
+    let count = store.import_jsonl(&mut data.as_bytes(), opts).await?;
+    output.success(&format!("Imported {} memories from {}", count, file));
+    Ok(())
+}
+
+/// Run the memory command with the given arguments and config path
+pub async fn run(args: MemoryArgs, config_path: Option<String>, json: bool) -> Result<()> {
+    let output = Output::new(json);
+    match args.command {
+        MemoryCommands::List {
+            agent,
+            namespace,
+            tags,
+            limit,
+            dimensions,
+        } => {
+            let filter = listing_filter(agent, namespace, tags);
+            list(filter, limit, dimensions, config_path, &output).await
+        }
+        MemoryCommands::Search {
+            query,
+            agent,
+            namespace,
+            tags,
+            limit,
+            dimensions,
+        } => {
+            let filter = listing_filter(agent, namespace, tags);
+            search(&query, filter, limit, dimensions, config_path, &output).await
+        }
+        MemoryCommands::Show { id, dimensions } => show(&id, dimensions, config_path, json).await,
+        MemoryCommands::Delete { id, dimensions } => {
+            delete(&id, dimensions, config_path, &output).await
+        }
+        MemoryCommands::Update {
+            id,
+            content,
+            importance,
+            tags,
+            dimensions,
+        } => {
+            let changes = MemoryUpdate {
+                content,
+                tags: if tags.is_empty() { None } else { Some(tags) },
+                importance,
+                embedder: None,
+            };
+            update(&id, changes, dimensions, config_path, &output).await
+        }
+        MemoryCommands::Export {
+            agent,
+            output: path,
+            dimensions,
+        } => export(agent, path, dimensions, config_path, &output).await,
+        MemoryCommands::Import {
+            file,
+            agent,
+            dimensions,
+        } => import(&file, agent, dimensions, config_path, &output).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_dimensions_reads_first_memory() {
+        let entry = MemoryEntry::new(
+            "fact-1".to_string(),
+            "agent-1".to_string(),
+            "content".to_string(),
+            vec![0.1, 0.2, 0.3],
+        );
+        let data = format!("\n{}\n", serde_json::to_string(&entry).unwrap());
+        assert_eq!(export_dimensions(&data).unwrap(), 3);
+        assert!(export_dimensions("\n").is_err());
+    }
+
+    #[test]
+    fn test_preview_shortens_long_and_multiline_content() {
+        assert_eq!(preview("The user likes tea"), "The user likes tea");
+        assert_eq!(preview("First line\nSecond line"), "First line...");
+        let long = "word ".repeat(20);
+        assert_eq!(preview(&long), format!("{}...", long[..60].trim_end()));
+    }
+
+    #[tokio::test]
+    async fn test_update_and_delete_through_sqlite() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = AisopodConfig::default();
+        config.memory.backend.connection = dir.path().join("memory.db").display().to_string();
+        let store = open_store(&config, 3).await.unwrap();
+        store
+            .store(MemoryEntry::new(
+                "fact-1".to_string(),
+                "agent-1".to_string(),
+                "The user likes tea".to_string(),
+                vec![0.1, 0.2, 0.3],
+            ))
+            .await
+            .unwrap();
+
+        let output = Output::new(true);
+        let changes = MemoryUpdate {
+            content: Some("The user likes coffee".to_string()),
+            tags: Some(vec!["drinks".to_string()]),
+            ..Default::default()
+        };
+        update_memory(&store, "fact-1", changes, &output)
+            .await
+            .unwrap();
+        let updated = store.get("fact-1").await.unwrap().unwrap();
+        assert_eq!(updated.content, "The user likes coffee");
+        assert_eq!(updated.metadata.tags, vec!["drinks"]);
+        assert_eq!(updated.embedding, vec![0.1, 0.2, 0.3]);
+
+        delete_memory(&store, "fact-1", &output).await.unwrap();
+        assert!(store.get("fact-1").await.unwrap().is_none());
+        let err = delete_memory(&store, "fact-1", &output).await.unwrap_err();
+        assert!(err.to_string().contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn test_export_then_import_through_sqlite() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = AisopodConfig::default();
+        config.memory.backend.connection = dir.path().join("memory.db").display().to_string();
+
+        let store = open_store(&config, 3).await.unwrap();
+        store
+            .store(MemoryEntry::new(
+                "fact-1".to_string(),
+                "agent-1".to_string(),
+                "The user likes tea".to_string(),
+                vec![0.1, 0.2, 0.3],
+            ))
+            .await
+            .unwrap();
+
+        let mut exported = Vec::new();
+        store
+            .export_jsonl(MemoryFilter::default(), &mut exported)
+            .await
+            .unwrap();
+        let data = String::from_utf8(exported).unwrap();
+        assert_eq!(export_dimensions(&data).unwrap(), 3);
+
+        let opts = ImportOptions {
+            agent_id: Some("agent-2".to_string()),
+            ..Default::default()
+        };
+        store
+            .import_jsonl(&mut data.as_bytes(), opts)
+            .await
+            .unwrap();
+        let seeded = store
+            .list(MemoryFilter {
+                agent_id: Some("agent-2".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(seeded.len(), 1);
+        assert_eq!(seeded[0].content, "The user likes tea");
+    }
+}
@@ -7,10 +7,16 @@ pub mod completions;
 pub mod config;
 pub mod daemon;
 pub mod doctor;
+pub mod eval;
 pub mod gateway;
+pub mod init;
+pub mod mcp;
+pub mod memory;
 pub mod message;
 pub mod migrate;
 pub mod models;
 pub mod onboarding;
+pub mod plugin;
 pub mod sessions;
+pub mod skill;
 pub mod status;
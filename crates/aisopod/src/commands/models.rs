@@ -12,11 +12,13 @@ use std::time::Duration;
 
 use aisopod_config::load_config;
 use aisopod_config::AisopodConfig;
+use aisopod_config::Sensitive;
 use aisopod_provider::discovery::ModelCatalog;
 use aisopod_provider::providers;
 use aisopod_provider::registry::ProviderRegistry;
 use aisopod_provider::trait_module::ModelProvider;
 use aisopod_provider::types::ModelInfo;
+use aisopod_provider::wire_log::{self, WireLogConfig, WireLogger};
 use std::collections::HashMap;
 use crate::output::Output;
 
@@ -56,9 +58,17 @@ async fn load_config_and_registry(
     let config_path = config_path.unwrap_or("aisopod-config.json5");
     let config = load_config(Path::new(config_path))?;
 
-    // Create provider registry
-    let registry = ProviderRegistry::new();
-    let registry_arc = Arc::new(std::sync::RwLock::new(registry));
+    if config.models.wire_log.enabled {
+        install_wire_log(&config)?;
+    }
+
+    let registry = create_provider_registry(&config).await?;
+    Ok((config, Arc::new(std::sync::RwLock::new(registry))))
+}
+
+/// Create a provider registry with the providers of the configuration
+pub(crate) async fn create_provider_registry(config: &AisopodConfig) -> Result<ProviderRegistry> {
+    let mut registry = ProviderRegistry::new();
 
     // Load models config and create providers
     for provider_config in &config.models.providers {
@@ -72,13 +82,89 @@ async fn load_config_and_registry(
                 );
                 Arc::new(provider)
             }
+            "azure-openai" => {
+                let provider = providers::azure_openai::AzureOpenAIProvider::new(
+                    provider_config.endpoint.clone(),
+                    providers::azure_openai::AzureAuth::ApiKey(provider_config.api_key.clone()),
+                    None,
+                );
+                Arc::new(provider)
+            }
+            "groq" => {
+                let provider = providers::groq::GroqProvider::new(
+                    provider_config.api_key.clone(),
+                    Some(provider_config.endpoint.clone()),
+                    None,
+                );
+                Arc::new(provider)
+            }
+            "openrouter" => {
+                let routing = &provider_config.routing;
+                let provider = providers::openrouter::OpenRouterProvider::new(
+                    provider_config.api_key.clone(),
+                    Some(provider_config.endpoint.clone()),
+                    None,
+                )
+                .with_routing(providers::openrouter::OpenRouterRouting {
+                    order: routing.order.clone(),
+                    allow_fallbacks: routing.allow_fallbacks,
+                    fallbacks: routing.fallbacks.clone(),
+                });
+                Arc::new(provider)
+            }
+            "xai" => {
+                let provider = providers::xai::XaiProvider::new(
+                    provider_config.api_key.clone(),
+                    Some(provider_config.endpoint.clone()),
+                    None,
+                );
+                Arc::new(provider)
+            }
+            "deepseek" => {
+                let provider = providers::deepseek::DeepSeekProvider::new(
+                    provider_config.api_key.clone(),
+                    Some(provider_config.endpoint.clone()),
+                    None,
+                );
+                Arc::new(provider)
+            }
+            "llamacpp" => {
+                let provider = providers::llamacpp::LlamaCppProvider::new(
+                    Some(provider_config.endpoint.clone()),
+                    Some(provider_config.api_key.clone()),
+                );
+                Arc::new(provider)
+            }
+            "vertex-ai" => {
+                // The API key holds the path of the service account key file,
+                // and the endpoint the location
+                let location = Some(provider_config.endpoint.clone()).filter(|l| !l.is_empty());
+                let provider = providers::vertex::VertexAIProvider::from_key_file(
+                    &provider_config.api_key,
+                    location,
+                )?;
+                Arc::new(provider)
+            }
+            "mistral" => {
+                let provider = providers::mistral::MistralProvider::new(
+                    provider_config.api_key.clone(),
+                    Some(provider_config.endpoint.clone()),
+                    None,
+                );
+                Arc::new(provider)
+            }
             "anthropic" => {
-                let provider = providers::anthropic::AnthropicProvider::new(
+                let mut provider = providers::anthropic::AnthropicProvider::new(
                     provider_config.api_key.clone(),
                     Some(provider_config.endpoint.clone()),
                     None,
                     None,
                 );
+                for model in &config.models.models {
+                    if model.provider == provider_config.name && model.prompt_caching {
+                        provider = provider.with_prompt_caching(model.id.clone());
+                    }
+                }
                 Arc::new(provider)
             }
             "gemini" => {
@@ -107,11 +193,31 @@ async fn load_config_and_registry(
                 continue;
             }
         };
-        let mut reg = registry_arc.write().unwrap();
-        reg.register(provider);
+        registry.register(provider);
     }
 
-    Ok((config, registry_arc))
+    Ok(registry)
+}
+
+/// Install the provider wire logger, masking the configured API keys
+fn install_wire_log(config: &AisopodConfig) -> Result<()> {
+    let settings = &config.models.wire_log;
+    let mut log_config = WireLogConfig::new(&settings.dir);
+    log_config.max_file_bytes = settings.max_file_mb * 1024 * 1024;
+    log_config.max_files = settings.max_files;
+    log_config
+        .redact_headers
+        .extend(settings.redact.iter().cloned());
+    log_config
+        .redact_fields
+        .extend(settings.redact.iter().cloned());
+
+    let mut logger = WireLogger::new(log_config)?;
+    for provider_config in &config.models.providers {
+        logger = logger.with_secret(Sensitive::new(provider_config.api_key.clone()));
+    }
+    wire_log::install(logger);
+    Ok(())
 }
 
 /// List all available models from all configured providers
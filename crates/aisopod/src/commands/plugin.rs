@@ -0,0 +1,332 @@
+//! Plugin management commands for the aisopod application.
+//!
+//! This module provides commands for managing plugins:
+//! - `install`: Install a plugin from a git repository, archive URL, or local path
+//! - `list`: List installed plugins
+//! - `keygen`: Generate a key for signing plugins
+//! - `sign`: Sign a plugin's manifest and binaries
+//!
+//! Plugins are installed to `plugins.settings.plugin_dir`, or to
+//! `~/.aisopod/plugins` when it is not configured. Installing a plugin also
+//! enables it in the `plugins.registry` section of the configuration.
+//! Plugins may only use the capabilities listed in their registry entry's
+//! `grants`. Signatures are checked against `plugins.settings.trusted_keys`, and
+//! unsigned plugins are refused when `plugins.settings.require_signatures`
+//! is set.
+
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use aisopod_config::load_config;
+use aisopod_config::types::{AisopodConfig, PluginEntry};
+use aisopod_plugin::signing::{
+    encode_public_key, encode_signing_key, generate_signing_key, parse_signing_key, sign_plugin,
+};
+use aisopod_plugin::{
+    DynamicPluginLoader, InstalledPlugin, PluginInstaller, PluginPermissions, PluginSource,
+    SignaturePolicy,
+};
+
+use crate::output::Output;
+
+/// Plugin management command arguments
+#[derive(Args)]
+pub struct PluginArgs {
+    #[command(subcommand)]
+    pub command: PluginCommands,
+}
+
+/// Available plugin management subcommands
+#[derive(Subcommand)]
+pub enum PluginCommands {
+    /// Install a plugin from a git repository, archive URL, or local path
+    Install {
+        /// Plugin source: git+<url>[#<ref>], a URL ending in .git, a .tar.gz URL, or a local path
+        source: String,
+
+        /// Replace an installed plugin with the same ID
+        #[arg(long)]
+        force: bool,
+
+        /// Install without enabling the plugin in the configuration
+        #[arg(long)]
+        no_activate: bool,
+    },
+    /// List installed plugins
+    List,
+    /// Generate a key for signing plugins
+    Keygen {
+        /// File to write the secret key to
+        output: String,
+    },
+    /// Sign a plugin's manifest and binaries
+    Sign {
+        /// Plugin directory containing aisopod.plugin.toml
+        dir: String,
+
+        /// File holding the secret key, as written by `plugin keygen`
+        #[arg(long)]
+        key: String,
+    },
+}
+
+/// Resolve the configuration file path
+fn resolve_config_path(config_path: Option<&str>) -> PathBuf {
+    config_path
+        .map(PathBuf::from)
+        .unwrap_or_else(aisopod_config::default_config_path)
+}
+
+/// Load configuration from file or use defaults
+fn load_config_or_default(path: &Path) -> Result<AisopodConfig> {
+    if path.exists() {
+        load_config(path).map_err(|e| {
+            anyhow!(
+                "Failed to load configuration from '{}': {}",
+                path.display(),
+                e
+            )
+        })
+    } else {
+        Ok(AisopodConfig::default())
+    }
+}
+
+/// Directory plugins are installed to
+fn plugins_dir(config: &AisopodConfig) -> PathBuf {
+    let configured = &config.plugins.settings.plugin_dir;
+    if !configured.is_empty() {
+        return PathBuf::from(configured);
+    }
+    dirs::home_dir()
+        .unwrap_or_default()
+        .join(".aisopod")
+        .join("plugins")
+}
+
+/// Signature policy configured in the plugin settings
+fn signature_policy(config: &AisopodConfig) -> Result<SignaturePolicy> {
+    let settings = &config.plugins.settings;
+    Ok(SignaturePolicy::new(&settings.trusted_keys)?
+        .require_signatures(settings.require_signatures))
+}
+
+/// Enable an installed plugin in the plugin registry configuration
+fn activate(config: &mut AisopodConfig, installed: &InstalledPlugin) {
+    let registry = &mut config.plugins.registry;
+    let index = match registry.iter().position(|entry| entry.id == installed.id) {
+        Some(index) => index,
+        None => {
+            registry.push(PluginEntry {
+                id: installed.id.clone(),
+                ..Default::default()
+            });
+            registry.len() - 1
+        }
+    };
+    let entry = &mut registry[index];
+    entry.name = installed.manifest.plugin.name.clone();
+    entry.version = installed.version.clone();
+    entry.enabled = true;
+}
+
+/// Install a plugin and optionally enable it
+async fn install(
+    source: &str,
+    force: bool,
+    no_activate: bool,
+    config_path: Option<String>,
+    output: &Output,
+) -> Result<()> {
+    let path = resolve_config_path(config_path.as_deref());
+    let mut config = load_config_or_default(&path)?;
+    let source = PluginSource::parse(source)?;
+    let installer = PluginInstaller::new(plugins_dir(&config))
+        .with_signature_policy(signature_policy(&config)?);
+
+    output.info(&format!("Installing plugin from {}...", source));
+    // SAFETY: the user explicitly asked to install this plugin
+    let installed = unsafe { installer.install(&source, force).await? };
+    output.success(&format!(
+        "Installed plugin '{}' v{} to {}",
+        installed.id,
+        installed.version,
+        installed.dir.display()
+    ));
+    let declared = installed.manifest.capabilities.clone().unwrap_or_default();
+    let requested = PluginPermissions::new(declared, []).requested();
+    if !requested.is_empty() {
+        let requested: Vec<&str> = requested.iter().map(|c| c.as_str()).collect();
+        output.info(&format!(
+            "Plugin requests capabilities: {} (allow them with `grants` in its plugins.registry entry)",
+            requested.join(", ")
+        ));
+    }
+
+    if !no_activate {
+        activate(&mut config, &installed);
+        std::fs::write(&path, serde_json::to_string_pretty(&config)?)?;
+        output.success(&format!(
+            "Enabled plugin '{}' in {}",
+            installed.id,
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// List installed plugins
+fn list(config_path: Option<String>, output: &Output) -> Result<()> {
+    let config = load_config_or_default(&resolve_config_path(config_path.as_deref()))?;
+    let loader = DynamicPluginLoader::new(vec![plugins_dir(&config)]);
+    let mut plugins = loader.discover()?;
+    plugins.sort_by(|a, b| a.manifest.plugin.id.cmp(&b.manifest.plugin.id));
+
+    let rows = plugins
+        .iter()
+        .map(|plugin| {
+            let info = &plugin.manifest.plugin;
+            let enabled = config
+                .plugins
+                .registry
+                .iter()
+                .any(|entry| entry.id == info.id && entry.enabled);
+            let kind = if plugin
+                .dir
+                .join(format!("{}.wasm", info.entry_point))
+                .is_file()
+            {
+                "wasm"
+            } else {
+                "native"
+            };
+            vec![
+                info.id.clone(),
+                info.name.clone(),
+                info.version.clone(),
+                kind.to_string(),
+                if enabled { "yes" } else { "no" }.to_string(),
+            ]
+        })
+        .collect();
+    output.print_table(&["ID", "Name", "Version", "Type", "Enabled"], rows);
+    Ok(())
+}
+
+/// Generate a signing key and print its public key
+fn keygen(output_path: &str, output: &Output) -> Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(output_path)
+        .map_err(|e| anyhow!("Failed to create key file '{}': {}", output_path, e))?;
+
+    let key = generate_signing_key();
+    writeln!(file, "{}", encode_signing_key(&key))?;
+    output.success(&format!("Wrote secret key to {}", output_path));
+    output.info(&format!(
+        "Public key (add to plugins.settings.trusted_keys): {}",
+        encode_public_key(&key.verifying_key())
+    ));
+    Ok(())
+}
+
+/// Sign a plugin directory with the key in `key_path`
+fn sign(dir: &str, key_path: &str, output: &Output) -> Result<()> {
+    let key = std::fs::read_to_string(key_path)
+        .map_err(|e| anyhow!("Failed to read key file '{}': {}", key_path, e))?;
+    let signature = sign_plugin(Path::new(dir), &parse_signing_key(&key)?)?;
+    output.success(&format!(
+        "Signed {} with key {}",
+        signature
+            .files
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", "),
+        signature.public_key
+    ));
+    Ok(())
+}
+
+/// Run the plugin command with the given arguments and config path
+pub async fn run(args: PluginArgs, config_path: Option<String>, json: bool) -> Result<()> {
+    let output = Output::new(json);
+    match args.command {
+        PluginCommands::Install {
+            source,
+            force,
+            no_activate,
+        } => install(&source, force, no_activate, config_path, &output).await,
+        PluginCommands::List => list(config_path, &output),
+        PluginCommands::Keygen { output: path } => keygen(&path, &output),
+        PluginCommands::Sign { dir, key } => sign(&dir, &key, &output),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aisopod_plugin::PluginManifest;
+
+    fn installed(version: &str) -> InstalledPlugin {
+        let manifest = PluginManifest::from_str(&format!(
+            r#"
+            [plugin]
+            id = "weather"
+            name = "Weather"
+            version = "{version}"
+            description = "Weather lookups"
+            author = "Test"
+            entry_point = "weather"
+            "#
+        ))
+        .unwrap();
+        InstalledPlugin {
+            id: "weather".to_string(),
+            version: version.to_string(),
+            dir: PathBuf::from("/plugins/weather"),
+            manifest,
+        }
+    }
+
+    #[test]
+    fn test_activate_adds_and_updates_entry() {
+        let mut config = AisopodConfig::default();
+        activate(&mut config, &installed("0.1.0"));
+        assert_eq!(config.plugins.registry.len(), 1);
+        assert_eq!(config.plugins.registry[0].name, "Weather");
+        assert!(config.plugins.registry[0].enabled);
+
+        config.plugins.registry[0].enabled = false;
+        activate(&mut config, &installed("0.2.0"));
+        assert_eq!(config.plugins.registry.len(), 1);
+        assert_eq!(config.plugins.registry[0].version, "0.2.0");
+        assert!(config.plugins.registry[0].enabled);
+    }
+
+    #[test]
+    fn test_signature_policy_rejects_invalid_trusted_keys() {
+        let mut config = AisopodConfig::default();
+        assert!(signature_policy(&config).is_ok());
+
+        config.plugins.settings.trusted_keys = vec!["not-a-key".to_string()];
+        assert!(signature_policy(&config).is_err());
+    }
+
+    #[test]
+    fn test_plugins_dir_uses_configured_directory() {
+        let mut config = AisopodConfig::default();
+        assert!(plugins_dir(&config).ends_with(".aisopod/plugins"));
+
+        config.plugins.settings.plugin_dir = "/opt/aisopod/plugins".to_string();
+        assert_eq!(plugins_dir(&config), PathBuf::from("/opt/aisopod/plugins"));
+    }
+}
@@ -3,6 +3,8 @@
 //! This module provides commands for managing conversation sessions:
 //! - `list`: List active sessions with metadata (agent, channel, last activity)
 //! - `clear`: Clear session history for specific or all sessions
+//! - `export`: Export a session transcript as Markdown or JSONL
+//! - `replay`: Re-run a session's user turns and diff the outcomes
 //!
 //! Also provides a top-level `reset` command for resetting all sessions.
 
@@ -12,10 +14,16 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::io::{self, Write};
 use std::path::Path;
+use std::sync::Arc;
 
+use aisopod_agent::{AgentRunner, DiffLine, ReplayReport, SessionReplayer};
 use aisopod_config::load_config;
 use aisopod_config::types::AisopodConfig;
-use aisopod_session::{SessionFilter, SessionStore};
+use aisopod_gateway::session_store::session_cipher;
+use aisopod_session::{
+    export_from_store, ExportFormat, HistoryQuery, SessionFilter, SessionStore, SessionSummary,
+};
+use crate::commands::models::create_provider_registry;
 use crate::output::Output;
 
 /// Information about a session.
@@ -72,6 +80,44 @@ pub enum SessionsCommands {
         #[arg(long)]
         id: Option<String>,
     },
+    /// Export a session transcript
+    Export {
+        /// Session ID to export
+        id: String,
+
+        /// Agent owning the session, when several agents share the ID
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Output format: markdown or jsonl
+        #[arg(long, default_value = "markdown")]
+        format: String,
+
+        /// File to write the transcript to (prints to stdout if omitted)
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+    /// Replay a session's user turns and diff the outcomes
+    Replay {
+        /// Session ID to replay
+        id: String,
+
+        /// Agent owning the session, when several agents share the ID
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Replay with this agent instead of the session's
+        #[arg(long)]
+        with_agent: Option<String>,
+
+        /// Replay with this model instead of the agent's
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 /// Load configuration from file or use defaults
@@ -103,14 +149,26 @@ fn prompt(prompt_text: &str) -> Result<String> {
 
 /// Build session store path from config
 fn build_session_store_path(config: &AisopodConfig) -> String {
-    // Use the default path from config or create a default
-    // For now, use the current directory with a default filename
+    // Use the configured SQLite file, or a default file in the current directory
+    if !config.session.storage.path.is_empty() {
+        return config.session.storage.path.clone();
+    }
     let db_path = std::env::current_dir()
         .unwrap_or_default()
         .join("aisopod-sessions.db");
     db_path.to_string_lossy().to_string()
 }
 
+/// Open the session store, encrypting messages when a key is configured
+fn open_session_store(config: &AisopodConfig) -> Result<SessionStore> {
+    let store_path = build_session_store_path(config);
+    let store = SessionStore::new(Path::new(&store_path))?;
+    Ok(match session_cipher(&config.session.encryption)? {
+        Some(cipher) => store.with_cipher(cipher),
+        None => store,
+    })
+}
+
 /// List all active sessions with optional filters
 pub async fn list_sessions(
     agent: Option<String>,
@@ -128,11 +186,8 @@ pub async fn list_sessions(
         filter = SessionFilter::for_channel(channel_id);
     }
 
-    // Build session store path
-    let store_path = build_session_store_path(&config);
-
     // Open or create the session store
-    let store = SessionStore::new(Path::new(&store_path))?;
+    let store = open_session_store(&config)?;
 
     // List sessions
     let summaries = store.list("admin", &filter)?;
@@ -165,8 +220,7 @@ pub async fn clear_sessions(
 ) -> Result<()> {
     let config = load_config_or_default(config_path.as_deref())?;
 
-    let store_path = build_session_store_path(&config);
-    let store = SessionStore::new(Path::new(&store_path))?;
+    let store = open_session_store(&config)?;
     let output = Output::new(false);
 
     match session_id {
@@ -212,6 +266,154 @@ pub async fn clear_sessions(
     Ok(())
 }
 
+/// Find the single session with the given ID, optionally of one agent
+fn find_session(
+    store: &SessionStore,
+    session_id: &str,
+    agent: Option<String>,
+) -> Result<SessionSummary> {
+    let mut filter = SessionFilter::new();
+    filter.peer_id = Some(session_id.to_string());
+    filter.agent_id = agent;
+    let mut sessions = store.list("admin", &filter)?;
+
+    match sessions.len() {
+        0 => Err(anyhow!("Session '{}' not found.", session_id)),
+        1 => Ok(sessions.remove(0)),
+        count => Err(anyhow!(
+            "Session ID '{}' matches {} sessions; select one with --agent.",
+            session_id,
+            count
+        )),
+    }
+}
+
+/// Export a session transcript to a file or stdout
+pub async fn export_session(
+    session_id: String,
+    agent: Option<String>,
+    format: String,
+    output_path: Option<String>,
+    config_path: Option<String>,
+) -> Result<()> {
+    let config = load_config_or_default(config_path.as_deref())?;
+    let format: ExportFormat = format.parse()?;
+
+    let store = open_session_store(&config)?;
+    let output = Output::new(false);
+
+    let session = find_session(&store, &session_id, agent)?;
+    let content = export_from_store(&store, &session.key, format)?;
+
+    match output_path {
+        Some(path) => {
+            std::fs::write(&path, content)
+                .with_context(|| format!("Failed to write export to '{}'", path))?;
+            output.success(&format!("Exported session '{}' to {}.", session_id, path));
+        }
+        None => print!("{}", content),
+    }
+
+    Ok(())
+}
+
+/// Set the model of `agent_id` in the configuration
+fn override_agent_model(config: &mut AisopodConfig, agent_id: &str, model: &str) -> Result<()> {
+    let agent = config
+        .agents
+        .agents
+        .iter_mut()
+        .find(|agent| agent.id == agent_id)
+        .ok_or_else(|| anyhow!("Agent '{}' is not configured.", agent_id))?;
+    agent.model = model.to_string();
+    Ok(())
+}
+
+/// Print a replay report in human-readable form
+fn print_replay_report(report: &ReplayReport) {
+    println!("Agent: {}", report.agent);
+    for turn in &report.turns {
+        let symbol = if turn.changed { "✗" } else { "✓" };
+        println!("  {} turn {}: {}", symbol, turn.turn, turn.prompt.lines().next().unwrap_or(""));
+        if let Some(error) = &turn.error {
+            println!("      error: {}", error);
+        }
+        if turn.recorded_tools != turn.replayed_tools {
+            println!(
+                "      tools: [{}] -> [{}]",
+                turn.recorded_tools.join(", "),
+                turn.replayed_tools.join(", ")
+            );
+        }
+        for line in &turn.diff {
+            match line {
+                DiffLine::Same(text) => println!("        {}", text),
+                DiffLine::Removed(text) => println!("      - {}", text),
+                DiffLine::Added(text) => println!("      + {}", text),
+            }
+        }
+    }
+    println!(
+        "\n{} unchanged, {} changed",
+        report.turns.len() - report.changed(),
+        report.changed()
+    );
+}
+
+/// Replay a session's user turns and report the turns whose outcome changed
+///
+/// Exits with a non-zero status if any turn changed.
+pub async fn replay_session(
+    session_id: String,
+    agent: Option<String>,
+    with_agent: Option<String>,
+    model: Option<String>,
+    json: bool,
+    config_path: Option<String>,
+) -> Result<()> {
+    let mut config = load_config_or_default(config_path.as_deref())?;
+    let store = open_session_store(&config)?;
+    let session = find_session(&store, &session_id, agent)?;
+    let query = HistoryQuery {
+        limit: Some(u32::MAX),
+        ..Default::default()
+    };
+    let messages = store.get_history(&session.key.agent_id, &session.key, &query)?;
+
+    let replay_agent = with_agent.unwrap_or_else(|| session.key.agent_id.clone());
+    if let Some(model) = &model {
+        override_agent_model(&mut config, &replay_agent, model)?;
+    }
+
+    // Replayed runs go to a scratch store, leaving the recorded sessions as they are
+    let providers = create_provider_registry(&config).await?;
+    let mut tools = aisopod_tools::ToolRegistry::new();
+    aisopod_tools::register_configured_tools(&mut tools, &config.tools);
+    let runner = AgentRunner::new(
+        Arc::new(config),
+        Arc::new(providers),
+        Arc::new(tools),
+        Arc::new(SessionStore::new_in_memory()?),
+    );
+
+    let report = SessionReplayer::new(Arc::new(runner))
+        .with_agent(replay_agent)
+        .replay(&session.key, &messages)
+        .await;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_replay_report(&report);
+    }
+
+    if !report.unchanged() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
 /// Run the session management command
 pub async fn run(args: SessionsArgs, config_path: Option<String>) -> Result<()> {
     match args.command {
@@ -221,6 +423,23 @@ pub async fn run(args: SessionsArgs, config_path: Option<String>) -> Result<()>
         SessionsCommands::Clear { id } => {
             clear_sessions(id, config_path).await?;
         }
+        SessionsCommands::Export {
+            id,
+            agent,
+            format,
+            output,
+        } => {
+            export_session(id, agent, format, output, config_path).await?;
+        }
+        SessionsCommands::Replay {
+            id,
+            agent,
+            with_agent,
+            model,
+            json,
+        } => {
+            replay_session(id, agent, with_agent, model, json, config_path).await?;
+        }
     }
     Ok(())
 }
@@ -243,6 +462,8 @@ pub async fn run_reset(config_path: Option<String>) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aisopod_config::types::EncryptionConfig;
+    use aisopod_session::MessageCipher;
 
     #[test]
     fn test_sessions_args_default() {
@@ -295,4 +516,57 @@ mod tests {
             _ => assert!(false),
         }
     }
+
+    #[test]
+    fn test_session_cipher_from_config() {
+        let config = EncryptionConfig::default();
+        assert!(session_cipher(&config).unwrap().is_none());
+
+        let config = EncryptionConfig {
+            key: Some(aisopod_config::Sensitive::new(MessageCipher::generate_key())),
+            keyring: None,
+        };
+        assert!(session_cipher(&config).unwrap().is_some());
+
+        let config = EncryptionConfig {
+            key: Some(aisopod_config::Sensitive::new("too short".to_string())),
+            keyring: None,
+        };
+        assert!(session_cipher(&config).is_err());
+    }
+
+    #[test]
+    fn test_override_agent_model() {
+        let mut config = AisopodConfig::default();
+        config.agents.agents.push(aisopod_config::types::Agent {
+            id: "support".to_string(),
+            model: "openai/gpt-4o".to_string(),
+            ..Default::default()
+        });
+
+        override_agent_model(&mut config, "support", "anthropic/claude-sonnet").unwrap();
+        assert_eq!(config.agents.agents[0].model, "anthropic/claude-sonnet");
+        assert!(override_agent_model(&mut config, "missing", "openai/gpt-4o").is_err());
+    }
+
+    #[test]
+    fn test_sessions_export_command() {
+        let args = SessionsArgs {
+            command: SessionsCommands::Export {
+                id: "session-123".to_string(),
+                agent: None,
+                format: "jsonl".to_string(),
+                output: Some("session.jsonl".to_string()),
+            },
+        };
+
+        match args.command {
+            SessionsCommands::Export { id, format, output, .. } => {
+                assert_eq!(id, "session-123");
+                assert_eq!(format.parse::<ExportFormat>().unwrap(), ExportFormat::Jsonl);
+                assert_eq!(output.unwrap(), "session.jsonl");
+            }
+            _ => panic!("expected the export command"),
+        }
+    }
 }
@@ -0,0 +1,160 @@
+//! Skill management commands for the aisopod application.
+//!
+//! This module provides commands for managing remote skills:
+//! - `install`: Install a skill from a git repository or skill index
+//! - `update`: Update installed remote skills within their version pins
+//! - `list`: List installed skills
+//!
+//! Skills are installed to `~/.aisopod/skills`. Where each remote skill came
+//! from, and the version requirement it is pinned to, is recorded in the
+//! `skills.lock` file of that directory.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
+
+use aisopod_plugin::skills::{discover_skill_dirs, parse_manifest, SkillInstaller, SkillSource};
+
+use crate::output::Output;
+
+/// Skill management command arguments
+#[derive(Args)]
+pub struct SkillArgs {
+    #[command(subcommand)]
+    pub command: SkillCommands,
+}
+
+/// Available skill management subcommands
+#[derive(Subcommand)]
+pub enum SkillCommands {
+    /// Install a skill from a git repository or skill index
+    Install {
+        /// ID of the skill to install
+        id: String,
+
+        /// Skill source: git+<url>[#<ref>], a URL ending in .git, or a skill index URL
+        #[arg(long)]
+        from: String,
+
+        /// Version requirement to pin the skill to (e.g. "=1.2.0" or "^1.2")
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Replace an installed skill with the same ID
+        #[arg(long)]
+        force: bool,
+    },
+    /// Update installed remote skills within their version pins
+    Update {
+        /// Skills to update (default: all remote skills)
+        ids: Vec<String>,
+
+        /// Only report available updates
+        #[arg(long)]
+        check: bool,
+    },
+    /// List installed skills
+    List,
+}
+
+/// Directory skills are installed to
+fn skills_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_default()
+        .join(".aisopod")
+        .join("skills")
+}
+
+/// Install a skill and pin it to a version requirement
+async fn install(
+    id: &str,
+    from: &str,
+    version: Option<&str>,
+    force: bool,
+    output: &Output,
+) -> Result<()> {
+    let source = SkillSource::parse(from)?;
+    let installer = SkillInstaller::new(skills_dir());
+
+    output.info(&format!("Installing skill '{}' from {}...", id, source));
+    let installed = installer.install(&source, id, version, force).await?;
+    output.success(&format!(
+        "Installed skill '{}' v{} to {}",
+        installed.id,
+        installed.version,
+        installed.dir.display()
+    ));
+    Ok(())
+}
+
+/// Update remote skills, or report available updates with `check`
+async fn update(ids: &[String], check: bool, output: &Output) -> Result<()> {
+    let installer = SkillInstaller::new(skills_dir());
+    let updates = installer.update(ids, check).await?;
+    if updates.is_empty() {
+        output.info("No remote skills installed");
+        return Ok(());
+    }
+
+    let rows = updates
+        .iter()
+        .map(|update| {
+            let status = match (&update.available, update.updated) {
+                (Some(_), true) => "updated",
+                (Some(_), false) => "available",
+                (None, _) => "up to date",
+            };
+            vec![
+                update.id.clone(),
+                update.current.clone(),
+                update.available.clone().unwrap_or_default(),
+                status.to_string(),
+            ]
+        })
+        .collect();
+    output.print_table(&["ID", "Installed", "Available", "Status"], rows);
+    Ok(())
+}
+
+/// List installed skills with their source and pin
+fn list(output: &Output) -> Result<()> {
+    let dir = skills_dir();
+    let lock = SkillInstaller::new(&dir).lock()?;
+    let mut manifests: Vec<_> = discover_skill_dirs(&[dir])
+        .iter()
+        .filter_map(|path| parse_manifest(&path.join("skill.toml")).ok())
+        .collect();
+    manifests.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let rows = manifests
+        .into_iter()
+        .map(|manifest| {
+            let locked = lock.get(&manifest.id);
+            vec![
+                manifest.id.clone(),
+                manifest.version,
+                locked.map_or_else(|| "local".to_string(), |locked| locked.source.clone()),
+                locked
+                    .and_then(|locked| locked.pin.clone())
+                    .unwrap_or_default(),
+            ]
+        })
+        .collect();
+    output.print_table(&["ID", "Version", "Source", "Pin"], rows);
+    Ok(())
+}
+
+/// Run the skill command with the given arguments
+pub async fn run(args: SkillArgs, json: bool) -> Result<()> {
+    let output = Output::new(json);
+    match args.command {
+        SkillCommands::Install {
+            id,
+            from,
+            version,
+            force,
+        } => install(&id, &from, version.as_deref(), force, &output).await,
+        SkillCommands::Update { ids, check } => update(&ids, check, &output).await,
+        SkillCommands::List => list(&output),
+    }
+}
@@ -97,7 +97,8 @@ pub async fn run_status(args: StatusArgs, config_path: Option<String>, json: boo
                     "active_channels": status.active_channels,
                     "active_sessions": status.active_sessions,
                     "uptime": status.uptime,
-                    "uptime_formatted": format_duration(status.uptime)
+                    "uptime_formatted": format_duration(status.uptime),
+                    "plugins": status.plugins,
                 }
             });
             println!("{}", serde_json::to_string_pretty(&detailed_json)?);
@@ -119,6 +120,17 @@ pub async fn run_status(args: StatusArgs, config_path: Option<String>, json: boo
             println!("Channels: {} active", status.active_channels);
             println!("Sessions: {} active", status.active_sessions);
             println!("Uptime:   {}", format_duration(status.uptime));
+            for plugin in &status.plugins {
+                println!(
+                    "Plugin:   {} {} ({} calls, {} errors, {} panics, {:.1}ms avg)",
+                    plugin.id,
+                    plugin.state,
+                    plugin.calls,
+                    plugin.errors,
+                    plugin.panics,
+                    plugin.average_latency_ms
+                );
+            }
         }
     }
 
@@ -7,6 +7,9 @@ use clap::Parser;
 use aisopod::cli::{Cli, Commands};
 use aisopod::commands::agent::AgentCommands;
 use aisopod::commands::config::ConfigCommands;
+use aisopod::commands::memory::MemoryCommands;
+use aisopod::commands::plugin::PluginCommands;
+use aisopod::commands::skill::SkillCommands;
 
 // ============================================================================
 // Unit Tests: Argument Parsing
@@ -163,6 +166,154 @@ fn test_parse_onboarding_command() {
     assert!(matches!(cli.command, Commands::Onboarding { .. }));
 }
 
+#[test]
+fn test_parse_plugin_install_command() {
+    let cli = Cli::parse_from([
+        "aisopod",
+        "plugin",
+        "install",
+        "git+https://example.com/weather.git#v1.0.0",
+        "--force",
+    ]);
+    match cli.command {
+        Commands::Plugin(args) => match args.command {
+            PluginCommands::Install {
+                source,
+                force,
+                no_activate,
+            } => {
+                assert_eq!(source, "git+https://example.com/weather.git#v1.0.0");
+                assert!(force);
+                assert!(!no_activate);
+            }
+            _ => panic!("Expected Install command"),
+        },
+        _ => panic!("Expected Plugin command"),
+    }
+}
+
+#[test]
+fn test_parse_memory_commands() {
+    let cli = Cli::parse_from(["aisopod", "memory", "export", "--agent", "agent-1", "-o", "backup.jsonl"]);
+    match cli.command {
+        Commands::Memory(args) => match args.command {
+            MemoryCommands::Export { agent, output, dimensions } => {
+                assert_eq!(agent.as_deref(), Some("agent-1"));
+                assert_eq!(output.as_deref(), Some("backup.jsonl"));
+                assert_eq!(dimensions, 1536);
+            }
+            _ => panic!("Expected memory export"),
+        },
+        _ => panic!("Expected memory command"),
+    }
+
+    let cli = Cli::parse_from(["aisopod", "memory", "import", "backup.jsonl", "--agent", "new-agent"]);
+    match cli.command {
+        Commands::Memory(args) => match args.command {
+            MemoryCommands::Import { file, agent, dimensions } => {
+                assert_eq!(file, "backup.jsonl");
+                assert_eq!(agent.as_deref(), Some("new-agent"));
+                assert!(dimensions.is_none());
+            }
+            _ => panic!("Expected memory import"),
+        },
+        _ => panic!("Expected memory command"),
+    }
+
+    let cli = Cli::parse_from(["aisopod", "memory", "search", "dark mode", "--agent", "agent-1", "--tag", "prefs"]);
+    match cli.command {
+        Commands::Memory(args) => match args.command {
+            MemoryCommands::Search { query, agent, tags, limit, .. } => {
+                assert_eq!(query, "dark mode");
+                assert_eq!(agent.as_deref(), Some("agent-1"));
+                assert_eq!(tags, vec!["prefs"]);
+                assert_eq!(limit, 10);
+            }
+            _ => panic!("Expected memory search"),
+        },
+        _ => panic!("Expected memory command"),
+    }
+
+    let cli = Cli::parse_from(["aisopod", "memory", "update", "fact-1", "--importance", "0.9", "--tag", "a", "--tag", "b"]);
+    match cli.command {
+        Commands::Memory(args) => match args.command {
+            MemoryCommands::Update { id, content, importance, tags, .. } => {
+                assert_eq!(id, "fact-1");
+                assert!(content.is_none());
+                assert_eq!(importance, Some(0.9));
+                assert_eq!(tags, vec!["a", "b"]);
+            }
+            _ => panic!("Expected memory update"),
+        },
+        _ => panic!("Expected memory command"),
+    }
+
+    let cli = Cli::parse_from(["aisopod", "memory", "delete", "fact-1"]);
+    match cli.command {
+        Commands::Memory(args) => match args.command {
+            MemoryCommands::Delete { id, .. } => assert_eq!(id, "fact-1"),
+            _ => panic!("Expected memory delete"),
+        },
+        _ => panic!("Expected memory command"),
+    }
+}
+
+#[test]
+fn test_parse_eval_command() {
+    let cli = Cli::parse_from(["aisopod", "--json", "eval", "suites/smoke.yaml", "--agent", "support"]);
+    assert!(cli.json);
+    match cli.command {
+        Commands::Eval(args) => {
+            assert_eq!(args.suite, "suites/smoke.yaml");
+            assert_eq!(args.agent.as_deref(), Some("support"));
+        }
+        _ => panic!("Expected eval command"),
+    }
+}
+
+#[test]
+fn test_parse_skill_install_and_update_commands() {
+    let cli = Cli::parse_from([
+        "aisopod",
+        "skill",
+        "install",
+        "weather",
+        "--from",
+        "https://skills.example.com/index.json",
+        "--version",
+        "^1.2",
+    ]);
+    match cli.command {
+        Commands::Skill(args) => match args.command {
+            SkillCommands::Install {
+                id,
+                from,
+                version,
+                force,
+            } => {
+                assert_eq!(id, "weather");
+                assert_eq!(from, "https://skills.example.com/index.json");
+                assert_eq!(version.as_deref(), Some("^1.2"));
+                assert!(!force);
+            }
+            _ => panic!("Expected Install command"),
+        },
+        _ => panic!("Expected Skill command"),
+    }
+
+    let cli = Cli::parse_from(["aisopod", "skill", "update", "--check"]);
+    match cli.command {
+        Commands::Skill(args) => match args.command {
+            SkillCommands::Update { ids, check } => {
+                assert!(ids.is_empty());
+                assert!(check);
+            }
+            _ => panic!("Expected Update command"),
+        },
+        _ => panic!("Expected Skill command"),
+    }
+}
+
 // ============================================================================
 // Integration Tests
 // ============================================================================